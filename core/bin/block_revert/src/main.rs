@@ -17,106 +17,11 @@ async fn revert_blocks_in_storage(
     storage: &mut StorageProcessor<'_>,
     last_block: BlockNumber,
 ) -> anyhow::Result<()> {
-    let mut transaction = storage.start_transaction().await?;
-
-    transaction
-        .chain()
-        .mempool_schema()
-        .return_executed_txs_to_mempool(last_block)
-        .await?;
-    println!("`mempool_txs`, `executed_transactions` tables are updated");
-    transaction
-        .chain()
-        .state_schema()
-        .clear_current_nonce_table(last_block)
-        .await?;
-    println!("`committed_nonce` table is updated");
-    transaction
+    storage
         .chain()
         .block_schema()
-        .remove_blocks(last_block)
-        .await?;
-    println!("`block` table is cleaned");
-    transaction
-        .chain()
-        .block_schema()
-        .remove_pending_block()
-        .await?;
-    println!("`pending_block` table is cleaned");
-    transaction
-        .chain()
-        .tree_cache_schema_bincode()
-        .remove_new_account_tree_cache(last_block)
-        .await?;
-    println!("`account_tree_cache` table is cleaned");
-
-    transaction
-        .chain()
-        .state_schema()
-        .remove_account_balance_updates(last_block)
-        .await?;
-    println!("`account_balance_updates` table is cleaned");
-    transaction
-        .chain()
-        .state_schema()
-        .remove_account_creates(last_block)
-        .await?;
-    println!("`account_creates` table is cleaned");
-    transaction
-        .chain()
-        .state_schema()
-        .remove_account_pubkey_updates(last_block)
+        .revert_blocks(last_block)
         .await?;
-    println!("`account_pubkey_updates` table is cleaned");
-
-    transaction
-        .chain()
-        .state_schema()
-        .remove_mint_nft_updates(last_block)
-        .await?;
-    println!("`mint_nft_updates` table is cleaned");
-
-    transaction
-        .chain()
-        .operations_schema()
-        .remove_eth_unprocessed_aggregated_ops()
-        .await?;
-    println!("`eth_unprocessed_aggregated_ops` table is cleaned");
-    transaction
-        .chain()
-        .operations_schema()
-        .remove_aggregate_operations_and_bindings(last_block)
-        .await?;
-    println!("`aggregate_operations`, `eth_aggregated_ops_binding`, `eth_tx_hashes`, `eth_operations` tables are cleaned");
-
-    transaction
-        .prover_schema()
-        .remove_witnesses(last_block)
-        .await?;
-    println!("`block_witness` table is cleaned");
-    transaction
-        .prover_schema()
-        .remove_proofs(last_block)
-        .await?;
-    println!("`proofs` table is cleaned");
-    transaction
-        .prover_schema()
-        .remove_aggregated_proofs(last_block)
-        .await?;
-    println!("`aggregated_proofs` table is cleaned");
-    transaction
-        .prover_schema()
-        .remove_prover_jobs(last_block)
-        .await?;
-    println!("`prover_job_queue` table is cleaned");
-
-    transaction
-        .ethereum_schema()
-        .update_eth_parameters(last_block)
-        .await?;
-    println!("`eth_parameters` table is updated");
-
-    transaction.commit().await?;
 
     println!("Blocks were reverted in storage");
     Ok(())
@@ -0,0 +1,389 @@
+//! Repair commands for the common operational incidents.
+//!
+//! Every command prints the changes it's going to make and only applies them
+//! if the `--i-know-what-i-am-doing` flag is passed. The services affected by the command
+//! are expected to be stopped while it is applied. The `dump-mempool` command only reads
+//! the storage, its dump is restored by the `rebuild-mempool` one.
+
+use std::{collections::HashMap, fs::File, path::PathBuf};
+
+use anyhow::ensure;
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    mempool::SignedTxVariant,
+    tx::{EthSignData, TxEthSignature},
+    SignedZkSyncTx, ZkSyncTx,
+};
+
+/// Entry of the mempool dump: either a single transaction or the transactions batch.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum DumpEntry {
+    Batch {
+        txs: Vec<SignedZkSyncTx>,
+        eth_signatures: Vec<TxEthSignature>,
+    },
+    Tx(SignedZkSyncTx),
+}
+
+impl From<SignedTxVariant> for DumpEntry {
+    fn from(element: SignedTxVariant) -> Self {
+        match element {
+            SignedTxVariant::Tx(tx) => Self::Tx(tx),
+            SignedTxVariant::Batch(batch) => Self::Batch {
+                txs: batch.txs,
+                eth_signatures: batch.eth_signatures,
+            },
+        }
+    }
+}
+
+impl DumpEntry {
+    fn txs(&self) -> &[SignedZkSyncTx] {
+        match self {
+            Self::Batch { txs, .. } => txs,
+            Self::Tx(tx) => std::slice::from_ref(tx),
+        }
+    }
+}
+
+fn print_dry_run_notice() {
+    println!("Dry run, pass `--i-know-what-i-am-doing` to apply the changes");
+}
+
+/// Checks the transaction of the dump before it's restored: its fields and the zkSync
+/// signature, its nonce, and the ECDSA Ethereum signature of the sender.
+/// The EIP1271 signatures and the signatures of the batches are checked by the server
+/// when the transactions are accepted, the tool can't check them without the Ethereum node.
+async fn validate_tx(
+    storage: &mut StorageProcessor<'_>,
+    tx: &SignedZkSyncTx,
+) -> anyhow::Result<()> {
+    tx.tx.clone().check_correctness()?;
+
+    let account_id = tx.account_id()?;
+    let committed_nonce = storage
+        .chain()
+        .account_schema()
+        .estimate_nonce(account_id)
+        .await?
+        .unwrap_or_default();
+    ensure!(
+        tx.nonce() >= committed_nonce,
+        "nonce {} is already used by the account {}",
+        *tx.nonce(),
+        *account_id
+    );
+
+    // The forced exit is signed by the initiator rather than the target account.
+    if let (
+        Some(EthSignData {
+            signature: TxEthSignature::EthereumSignature(signature),
+            message,
+        }),
+        false,
+    ) = (&tx.eth_sign_data, matches!(tx.tx, ZkSyncTx::ForcedExit(_)))
+    {
+        let signer = signature.signature_recover_signer(message)?;
+        ensure!(
+            signer == tx.account(),
+            "Ethereum signature belongs to {:?} instead of the sender {:?}",
+            signer,
+            tx.account()
+        );
+    }
+    Ok(())
+}
+
+async fn dump_mempool(storage: &mut StorageProcessor<'_>, dump: PathBuf) -> anyhow::Result<()> {
+    let entries: Vec<DumpEntry> = storage
+        .chain()
+        .mempool_schema()
+        .load_txs(&[])
+        .await?
+        .into_iter()
+        .map(DumpEntry::from)
+        .collect();
+    serde_json::to_writer_pretty(File::create(&dump)?, &entries)?;
+    println!(
+        "Saved {} mempool entries to {}",
+        entries.len(),
+        dump.display()
+    );
+    Ok(())
+}
+
+async fn rebuild_mempool(
+    storage: &mut StorageProcessor<'_>,
+    dump: PathBuf,
+    apply: bool,
+) -> anyhow::Result<()> {
+    let entries: Vec<DumpEntry> = serde_json::from_reader(File::open(&dump)?)?;
+    println!(
+        "Loaded {} mempool entries from {}",
+        entries.len(),
+        dump.display()
+    );
+
+    let mut missing_entries = Vec::new();
+    for entry in entries {
+        let mut known_txs = 0;
+        for tx in entry.txs() {
+            let tx_hash = tx.hash();
+            let in_mempool = storage
+                .chain()
+                .mempool_schema()
+                .contains_tx(tx_hash)
+                .await?;
+            let executed = storage
+                .chain()
+                .operations_ext_schema()
+                .tx_receipt(tx_hash.as_ref())
+                .await?
+                .is_some();
+            if in_mempool || executed {
+                known_txs += 1;
+            }
+        }
+
+        if known_txs == 0 {
+            let mut invalid = false;
+            for tx in entry.txs() {
+                if let Err(err) = validate_tx(storage, tx).await {
+                    println!("Transaction {} is invalid: {}", tx.hash().to_string(), err);
+                    invalid = true;
+                }
+            }
+            if invalid {
+                // The batch can't be restored without any of its transactions.
+                println!(
+                    "Skipping the entry with the invalid transactions, first transaction {}",
+                    entry.txs()[0].hash().to_string()
+                );
+            } else {
+                missing_entries.push(entry);
+            }
+        } else if known_txs < entry.txs().len() {
+            // The batch must be either executed or stored as a whole, it can't be restored partially.
+            println!(
+                "Skipping the batch with {} of {} transactions known to the storage, first transaction {}",
+                known_txs,
+                entry.txs().len(),
+                entry.txs()[0].hash().to_string()
+            );
+        }
+    }
+
+    for entry in &missing_entries {
+        match entry {
+            DumpEntry::Batch { txs, .. } => println!(
+                "Batch with {} transactions, first transaction {} will be restored",
+                txs.len(),
+                txs[0].hash().to_string()
+            ),
+            DumpEntry::Tx(tx) => {
+                println!("Transaction {} will be restored", tx.hash().to_string())
+            }
+        }
+    }
+    if missing_entries.is_empty() {
+        println!("All the transactions of the dump are known to the storage");
+        return Ok(());
+    }
+    if !apply {
+        print_dry_run_notice();
+        return Ok(());
+    }
+
+    let mut transaction = storage.start_transaction().await?;
+    for entry in missing_entries {
+        match entry {
+            DumpEntry::Batch {
+                txs,
+                eth_signatures,
+            } => {
+                transaction
+                    .chain()
+                    .mempool_schema()
+                    .insert_batch(&txs, eth_signatures)
+                    .await?;
+            }
+            DumpEntry::Tx(tx) => {
+                transaction.chain().mempool_schema().insert_tx(&tx).await?;
+            }
+        }
+    }
+    transaction.commit().await?;
+    println!("`mempool_txs` table is restored");
+    Ok(())
+}
+
+async fn resync_eth_nonce(storage: &mut StorageProcessor<'_>, apply: bool) -> anyhow::Result<()> {
+    let contracts = ContractsConfig::from_env();
+    let eth_client_config = ETHClientConfig::from_env();
+    let eth_sender_config = ETHSenderConfig::from_env();
+    let client = EthereumGateway::from_config(
+        &eth_client_config,
+        &eth_sender_config,
+        contracts.contract_addr,
+    );
+
+    let stored_nonce = storage.ethereum_schema().load_nonce().await?;
+    let chain_nonce = client.pending_nonce().await?.as_u64() as i64;
+    println!(
+        "Stored nonce {}, pending nonce of the operator account {}",
+        stored_nonce, chain_nonce
+    );
+    if stored_nonce == chain_nonce {
+        println!("Stored nonce is in sync with the chain");
+        return Ok(());
+    }
+    if chain_nonce < stored_nonce {
+        // The nonces of the unconfirmed transactions would be reused by the new ones.
+        let unconfirmed_ops = storage
+            .ethereum_schema()
+            .load_unconfirmed_operations()
+            .await?;
+        ensure!(
+            unconfirmed_ops.is_empty(),
+            "There are {} unconfirmed Ethereum operations, the stored nonce can't be decreased",
+            unconfirmed_ops.len()
+        );
+    }
+
+    println!(
+        "Nonce in `eth_parameters` table will be set to {}",
+        chain_nonce
+    );
+    if !apply {
+        print_dry_run_notice();
+        return Ok(());
+    }
+    storage.ethereum_schema().set_nonce(chain_nonce).await?;
+    println!("`eth_parameters` table is updated");
+    Ok(())
+}
+
+async fn recompute_token_cache(
+    storage: &mut StorageProcessor<'_>,
+    apply: bool,
+) -> anyhow::Result<()> {
+    let tokens = storage.tokens_schema().load_tokens().await?;
+    println!("Loaded {} tokens", tokens.len());
+
+    // The tokens are cached by the lowercase symbol, so such tokens shadow each other.
+    let mut symbols: HashMap<String, Vec<_>> = HashMap::new();
+    for token in tokens.values() {
+        symbols
+            .entry(token.symbol.to_lowercase())
+            .or_default()
+            .push(token.id);
+    }
+    for (symbol, token_ids) in symbols {
+        if token_ids.len() > 1 {
+            println!(
+                "Tokens {:?} have the same symbol {}, only one of them can be resolved by the symbol",
+                token_ids, symbol
+            );
+        }
+    }
+
+    println!("Token caches of the running services will be invalidated");
+    if !apply {
+        print_dry_run_notice();
+        return Ok(());
+    }
+    let mut transaction = storage.start_transaction().await?;
+    for token_id in tokens.keys() {
+        transaction
+            .tokens_schema()
+            .notify_token_updated(*token_id)
+            .await?;
+    }
+    transaction.commit().await?;
+    println!("Token caches are invalidated");
+    Ok(())
+}
+
+async fn reconcile_priority_ops(
+    storage: &mut StorageProcessor<'_>,
+    apply: bool,
+) -> anyhow::Result<()> {
+    let serial_ids = storage
+        .chain()
+        .mempool_schema()
+        .get_executed_priority_ops_in_mempool()
+        .await?;
+    if serial_ids.is_empty() {
+        println!("There are no executed priority operations in the mempool");
+        return Ok(());
+    }
+
+    println!(
+        "Executed priority operations {:?} will be removed from the mempool",
+        serial_ids
+    );
+    if !apply {
+        print_dry_run_notice();
+        return Ok(());
+    }
+    storage
+        .chain()
+        .mempool_schema()
+        .remove_priority_ops_from_mempool(&serial_ids)
+        .await?;
+    println!("`mempool_priority_operations` table is cleaned");
+    Ok(())
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Saves the transactions of the mempool to the dump restored by `rebuild-mempool`
+    DumpMempool {
+        /// JSON file the list of the transactions and the transactions batches is written to
+        #[structopt(long)]
+        dump: PathBuf,
+    },
+    /// Restores the transactions of the mempool dump that are neither stored nor executed
+    RebuildMempool {
+        /// JSON file with the list of the transactions and the transactions batches
+        #[structopt(long)]
+        dump: PathBuf,
+    },
+    /// Sets the stored nonce of the next operator transaction to the pending nonce on the chain
+    ResyncEthNonce,
+    /// Makes the running services reload the tokens from the storage
+    RecomputeTokenCache,
+    /// Removes the executed priority operations stuck in the mempool
+    ReconcilePriorityOps,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync consistency repair tool", author = "Matter Labs")]
+#[structopt(about = "Tool to repair the storage after the common operational incidents")]
+struct Opt {
+    #[structopt(subcommand)]
+    command: Command,
+    /// Applies the changes, otherwise the tool only prints them.
+    #[structopt(long = "i-know-what-i-am-doing")]
+    apply: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+
+    let mut storage = StorageProcessor::establish_connection().await?;
+
+    match opt.command {
+        Command::DumpMempool { dump } => dump_mempool(&mut storage, dump).await,
+        Command::RebuildMempool { dump } => rebuild_mempool(&mut storage, dump, opt.apply).await,
+        Command::ResyncEthNonce => resync_eth_nonce(&mut storage, opt.apply).await,
+        Command::RecomputeTokenCache => recompute_token_cache(&mut storage, opt.apply).await,
+        Command::ReconcilePriorityOps => reconcile_priority_ops(&mut storage, opt.apply).await,
+    }
+}
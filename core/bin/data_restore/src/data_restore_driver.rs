@@ -455,11 +455,19 @@ impl<T: Transport> DataRestoreDriver<T> {
         let mut count = 0;
 
         for op_block in new_ops_blocks {
-            // Take the contract version into account when choosing block chunk sizes.
-            let available_block_chunk_sizes = op_block
-                .contract_version
-                .expect("contract version must be set")
-                .available_block_chunk_sizes();
+            // The protocol parameters recorded for the block take precedence, otherwise
+            // the contract version is taken into account when choosing block chunk sizes.
+            let recorded_block_chunk_sizes = interactor
+                .get_protocol_parameters(op_block.block_num)
+                .await
+                .map(|parameters| parameters.block_chunk_sizes);
+            let available_block_chunk_sizes = match &recorded_block_chunk_sizes {
+                Some(sizes) => sizes.as_slice(),
+                None => op_block
+                    .contract_version
+                    .expect("contract version must be set")
+                    .available_block_chunk_sizes(),
+            };
             let (block, acc_updates) = self
                 .tree_state
                 .update_tree_states_from_ops_block(
@@ -7,6 +7,7 @@ use zksync_storage::{
 };
 use zksync_types::{
     aggregated_operations::{BlocksCommitOperation, BlocksExecuteOperation},
+    protocol::ProtocolParameters,
     AccountId, BlockNumber, NewTokenEvent, PriorityOp, SerialId, Token, TokenId, TokenInfo,
     TokenKind,
     {block::Block, AccountUpdate, AccountUpdates},
@@ -102,6 +103,7 @@ impl<'a> DatabaseStorageInteractor<'a> {
 
         let execute_aggregated_operation = BlocksExecuteOperation {
             blocks: vec![block.clone()],
+            complete_withdrawals: false,
         };
 
         transaction
@@ -436,4 +438,15 @@ impl<'a> DatabaseStorageInteractor<'a> {
             .expect("Failed to retrieve maximum priority op serial id")
             .unwrap_or(0)
     }
+
+    pub async fn get_protocol_parameters(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> Option<ProtocolParameters> {
+        self.storage
+            .protocol_parameters_schema()
+            .load_parameters_for_block(block_number)
+            .await
+            .expect("Failed to load protocol parameters")
+    }
 }
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use web3::types::Address;
 
 use zksync_types::block::Block;
+use zksync_types::protocol::ProtocolParameters;
 use zksync_types::{
     Account, AccountId, AccountMap, AccountUpdate, AccountUpdates, Action, BlockNumber,
     NewTokenEvent, Operation, PriorityOp, SerialId, Token, TokenId, TokenInfo, TokenKind,
@@ -331,4 +332,12 @@ impl InMemoryStorageInteractor {
             .count();
         number_of_priority_ops as SerialId
     }
+
+    pub async fn get_protocol_parameters(
+        &mut self,
+        _block_number: BlockNumber,
+    ) -> Option<ProtocolParameters> {
+        // The history of the protocol parameters is only stored in the database.
+        None
+    }
 }
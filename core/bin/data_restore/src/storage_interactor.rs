@@ -6,8 +6,9 @@ use zksync_storage::data_restore::records::{
     NewBlockEvent, StoredBlockEvent, StoredRollupOpsBlock,
 };
 use zksync_types::{
-    block::Block, AccountId, AccountMap, AccountUpdate, AccountUpdates, BlockNumber, NewTokenEvent,
-    PriorityOp, SerialId, Token, TokenId, TokenInfo, NFT,
+    block::Block, protocol::ProtocolParameters, AccountId, AccountMap, AccountUpdate,
+    AccountUpdates, BlockNumber, NewTokenEvent, PriorityOp, SerialId, Token, TokenId, TokenInfo,
+    NFT,
 };
 
 use crate::{
@@ -211,6 +212,19 @@ impl StorageInteractor<'_> {
     pub async fn get_max_priority_op_serial_id(&mut self) -> SerialId {
         storage_interact!(self.get_max_priority_op_serial_id())
     }
+
+    /// Retrieves the protocol parameters recorded for the block, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - The corresponding block number
+    ///
+    pub async fn get_protocol_parameters(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> Option<ProtocolParameters> {
+        storage_interact!(self.get_protocol_parameters(block_number))
+    }
 }
 
 /// Returns Rollup contract event from its stored representation
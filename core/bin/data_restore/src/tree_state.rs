@@ -564,6 +564,7 @@ impl TreeState {
             success: true,
             op: Some(executed_op),
             fail_reason: None,
+            fail_code: None,
             block_index: Some(block_index),
             created_at: chrono::Utc::now(),
             batch_id: None, // Currently `data_restore` is unable to restore `transaction <--> batch` relation
@@ -0,0 +1,146 @@
+use anyhow::ensure;
+use structopt::StructOpt;
+use zksync_config::{ChainConfig, ETHSenderConfig};
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    protocol::{ProtocolParameters, ProtocolParametersUpdate},
+    BlockNumber,
+};
+
+/// Protocol parameters from the current config.
+fn current_parameters() -> ProtocolParameters {
+    let chain_config = ChainConfig::from_env();
+    let eth_sender_config = ETHSenderConfig::from_env();
+    ProtocolParameters {
+        block_chunk_sizes: chain_config.state_keeper.block_chunk_sizes,
+        max_withdrawals_per_execute_tx: eth_sender_config.sender.max_withdrawals_per_execute_tx,
+    }
+}
+
+async fn show(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    let history = storage
+        .protocol_parameters_schema()
+        .load_parameters_history()
+        .await?;
+    if history.is_empty() {
+        println!(
+            "No protocol parameters are recorded, the current config is used for all the blocks"
+        );
+    }
+    for update in history {
+        println!(
+            "From block {}: block chunk sizes {:?}, max withdrawals per execute tx {}",
+            update.from_block,
+            update.parameters.block_chunk_sizes,
+            update.parameters.max_withdrawals_per_execute_tx
+        );
+    }
+    Ok(())
+}
+
+async fn record(
+    storage: &mut StorageProcessor<'_>,
+    from_block: Option<u32>,
+    historical: bool,
+) -> anyhow::Result<()> {
+    let parameters = current_parameters();
+    let last_committed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_block()
+        .await?;
+    let from_block = from_block
+        .map(BlockNumber)
+        .unwrap_or(last_committed_block + 1);
+    println!(
+        "Last committed block {}, recording the parameters from block {}",
+        last_committed_block, from_block
+    );
+
+    ensure!(
+        historical || from_block > last_committed_block,
+        "Block {} is already created, pass `--historical` to record the parameters it was created with",
+        from_block
+    );
+    ensure!(
+        !parameters.block_chunk_sizes.is_empty(),
+        "Block chunk sizes can't be empty"
+    );
+
+    let mut transaction = storage.start_transaction().await?;
+    let history = transaction
+        .protocol_parameters_schema()
+        .load_parameters_history()
+        .await?;
+    if let Some(last_update) = history.last() {
+        ensure!(
+            historical || from_block >= last_update.from_block,
+            "Parameters are already recorded from block {}, the history can't be rewritten",
+            last_update.from_block
+        );
+        if last_update.parameters == parameters && from_block > last_update.from_block {
+            println!(
+                "Parameters recorded from block {} match the current config, nothing to record",
+                last_update.from_block
+            );
+            return Ok(());
+        }
+    }
+
+    transaction
+        .protocol_parameters_schema()
+        .store_parameters(&ProtocolParametersUpdate {
+            from_block,
+            parameters: parameters.clone(),
+        })
+        .await?;
+    transaction.commit().await?;
+
+    println!(
+        "Recorded from block {}: block chunk sizes {:?}, max withdrawals per execute tx {}",
+        from_block, parameters.block_chunk_sizes, parameters.max_withdrawals_per_execute_tx
+    );
+    Ok(())
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Prints the recorded changes of the protocol parameters
+    Show,
+    /// Records the protocol parameters from the current config
+    Record {
+        /// The first block created with the new parameters, the next block by default.
+        #[structopt(long)]
+        from_block: Option<u32>,
+        /// Allows recording the parameters for the already created blocks,
+        /// e.g. the parameters the network was started with.
+        #[structopt(long)]
+        historical: bool,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync protocol parameters tool", author = "Matter Labs")]
+#[structopt(
+    about = "Tool to record the changes of the protocol parameters, so the stored blocks are validated and restored with the parameters they were created with. The server records the parameters from its config on start, so the tool is needed to record the parameters the already created blocks were made with."
+)]
+struct Opt {
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+
+    let mut storage = StorageProcessor::establish_connection().await?;
+    match opt.command {
+        Command::Show => show(&mut storage).await?,
+        Command::Record {
+            from_block,
+            historical,
+        } => record(&mut storage, from_block, historical).await?,
+    }
+
+    Ok(())
+}
@@ -119,7 +119,7 @@ impl crate::ApiClient for ApiClient {
         self.with_retries(operation).await
     }
 
-    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()> {
+    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<bool> {
         let operation = || async {
             log::trace!(
                 "sending working_on job_id: {}, prover_name: {}",
@@ -142,8 +142,11 @@ impl crate::ApiClient for ApiClient {
             if response.status() == reqwest::StatusCode::UNAUTHORIZED {
                 return Err(Permanent(format_err!("authorization error")));
             }
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                return Ok(false);
+            }
 
-            Ok(())
+            Ok(true)
         };
 
         self.with_retries(operation).await
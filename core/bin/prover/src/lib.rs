@@ -89,7 +89,8 @@ pub trait ProverImpl {
 #[async_trait::async_trait]
 pub trait ApiClient: Debug {
     async fn get_job(&self, req: ProverInputRequest) -> anyhow::Result<ProverInputResponse>;
-    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()>;
+    /// Sends the heartbeat, returns `false` if the job is not leased to the prover anymore.
+    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<bool>;
     async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()>;
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()>;
 }
@@ -109,8 +110,8 @@ where
     result_receiver.await?
 }
 
-/// Endlessly sends requests to the server, in case of not receiving a response
-/// notifies about it in the logs, but does not quit.
+/// Sends requests to the server until the job is leased to another prover, in case of not
+/// receiving a response notifies about it in the logs, but does not quit.
 async fn heartbeat_future_handle<CLIENT>(
     client: CLIENT,
     prover_name: &str,
@@ -124,17 +125,17 @@ async fn heartbeat_future_handle<CLIENT>(
             let between = Range::new(0.8f64, 2.0);
             let mut rng = thread_rng();
             let random_multiplier = between.ind_sample(&mut rng);
-            Duration::from_secs((heartbeat_interval.as_secs_f64() * random_multiplier) as u64)
+            heartbeat_interval.mul_f64(random_multiplier)
         };
         tokio::time::sleep(timeout_value).await;
 
         vlog::debug!("Starting sending heartbeats for job with ID: {}", job_id);
 
-        client
-            .working_on(job_id, prover_name)
-            .await
-            .map_err(|e| vlog::warn!("Failed to send heartbeat: {}", e))
-            .unwrap_or_default();
+        match client.working_on(job_id, prover_name).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => vlog::warn!("Failed to send heartbeat: {}", e),
+        }
     }
 }
 
@@ -157,7 +158,12 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             break;
         }
 
-        let aux_data = prover.get_request_aux_data();
+        let mut aux_data = prover.get_request_aux_data();
+        if aux_data.capability_tags.is_empty() {
+            aux_data
+                .capability_tags
+                .clone_from(&prover_options.prover.capability_tags);
+        }
         let prover_input_response = match client
             .get_job(ProverInputRequest {
                 prover_name: prover_name.to_string(),
@@ -212,7 +218,15 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             comp_proof = compute_proof_future => {
                 comp_proof.expect("Failed to compute proof")
             },
-            _ = heartbeat_future_handle => unreachable!(),
+            _ = heartbeat_future_handle => {
+                // The proof is computed by the blocking thread, which can't be interrupted,
+                // so the work cycle ends and the prover is restarted, like with `die_after_proof`.
+                vlog::warn!(
+                    "Job {} is leased to another prover, stopping the computation",
+                    job_id
+                );
+                return;
+            },
         };
         prover = ret_prover;
 
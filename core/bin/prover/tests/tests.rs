@@ -1,5 +1,5 @@
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 // External deps
@@ -23,7 +23,7 @@ use zksync_prover::plonk_step_by_step_prover::{
 };
 use zksync_prover::{ProverImpl, ShutdownRequest};
 use zksync_prover_utils::api::{
-    JobRequestData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
+    JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
 };
 use zksync_types::{
     block::smallest_block_size_for_chunks, operations::DepositOp, Account, AccountId, Address,
@@ -57,15 +57,22 @@ impl Default for MockProverConfigs {
                 cycle_wait: 500,
                 request_timeout: 1,
                 die_after_proof: false,
+                capability_tags: Vec::new(),
             },
             core: zksync_config::configs::prover::Core {
                 gone_timeout: 2,
                 idle_provers: 1,
+                job_aging_interval: 0,
+                aged_priority_op_threshold: 0,
             },
             witness_generator: zksync_config::configs::prover::WitnessGenerator {
                 prepare_data_interval: 5000,
                 witness_generators: 2,
             },
+            exit_proof_generator: zksync_config::configs::prover::ExitProofGenerator {
+                job_check_interval: 1000,
+                job_timeout: 3600,
+            },
         };
 
         Self {
@@ -256,6 +263,57 @@ async fn test_publishing_proof() {
     };
 }
 
+/// Prover which takes too long to compute the proof.
+struct StalledProver;
+
+impl ProverImpl for StalledProver {
+    type Config = DummyProverConfig;
+
+    fn create_from_config(_config: Self::Config) -> Self {
+        Self
+    }
+
+    fn create_proof(&self, _data: JobRequestData) -> anyhow::Result<JobResultData> {
+        std::thread::sleep(Duration::from_secs(60));
+        anyhow::bail!("proof is not computed")
+    }
+}
+
+#[tokio::test]
+async fn test_lost_job_lease() {
+    let MockProverConfigs {
+        plonk_config: _,
+        dummy_config,
+        prover_options,
+        shutdown_request,
+        prover_name,
+    } = MockProverConfigs::default();
+
+    let prover = StalledProver::create_from_config(dummy_config);
+    let client = MockApiClient::default();
+    client.lost_leases.lock().await.insert(0);
+
+    let prover_work_cycle = zksync_prover::prover_work_cycle(
+        prover,
+        client.clone(),
+        shutdown_request,
+        prover_options,
+        &prover_name,
+    )
+    .fuse();
+    let timeout = tokio::time::sleep(Duration::from_secs(10)).fuse();
+
+    pin_mut!(prover_work_cycle, timeout);
+
+    // The computation is stopped once the heartbeat is rejected.
+    futures::select! {
+        _ = prover_work_cycle => {
+            assert!(client.published_prof.lock().await.is_empty());
+        },
+        _ = timeout => panic!("prover kept computing the job leased to another prover"),
+    };
+}
+
 #[derive(Debug, Clone, Default)]
 struct MockApiClient {
     /// All published proofs are saved by `job_id`.
@@ -264,6 +322,8 @@ struct MockApiClient {
     working_on: Arc<Mutex<HashMap<i32, String>>>,
     /// `gob_id` of the last work that has not yet been submitted.
     last_job_id: Arc<Mutex<i32>>,
+    /// Jobs leased to another prover, the heartbeats for them are rejected.
+    lost_leases: Arc<Mutex<HashSet<i32>>>,
 }
 
 #[async_trait::async_trait]
@@ -281,13 +341,13 @@ impl zksync_prover::ApiClient for MockApiClient {
         Ok(response)
     }
 
-    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()> {
+    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<bool> {
         self.working_on
             .lock()
             .await
             .insert(job_id, prover_name.to_string());
 
-        Ok(())
+        Ok(!self.lost_leases.lock().await.contains(&job_id))
     }
 
     async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()> {
@@ -6,24 +6,40 @@ use structopt::StructOpt;
 
 use serde::{Deserialize, Serialize};
 
-use zksync_api::fee_ticker::{run_updaters, FeeTicker, TickerInfo};
-use zksync_core::{genesis_init, run_core, wait_for_tasks};
+use zksync_api::{
+    api_server::rate_limit::RateLimiter,
+    fee_ticker::{price_cache::PriceCacheConfig, run_updaters, FeeTicker, TickerInfo},
+};
+use zksync_core::{
+    genesis_init,
+    leader_election::{FencingTerm, Leadership},
+    run_core, wait_for_tasks,
+};
 use zksync_eth_client::EthereumGateway;
 use zksync_forced_exit_requests::run_forced_exit_requests_actors;
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
-use zksync_witness_generator::run_prover_server;
+use zksync_witness_generator::{exit_proof_generator::run_exit_proof_generator, run_prover_server};
 
 use tokio::task::JoinHandle;
-use zksync_config::configs::api::{PrivateApiConfig, PrometheusConfig, TokenConfig};
+use zksync_config::configs::api::{
+    ArchiveConfig, PrivateApiConfig, PrometheusConfig, RateLimitConfig, SandboxApiConfig,
+    TokenConfig,
+};
 use zksync_config::{
     configs::api::{CommonApiConfig, JsonRpcConfig, ProverApiConfig, RestApiConfig, Web3Config},
     ChainConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, ProverConfig, TickerConfig, ZkSyncConfig,
+    ForcedExitRequestsConfig, GatewayWatcherConfig, LeaderElectionConfig, ProverConfig,
+    TickerConfig, ZkSyncConfig,
 };
 use zksync_core::rejected_tx_cleaner::run_rejected_tx_cleaner;
 use zksync_mempool::run_mempool_tx_handler;
-use zksync_prometheus_exporter::{run_operation_counter, run_prometheus_exporter};
+use zksync_prometheus_exporter::{
+    run_operation_counter, run_pool_utilization_reporter, run_prometheus_exporter,
+};
+use zksync_state::shared::SharedCommittedState;
 use zksync_storage::ConnectionPool;
+use zksync_token_db_cache::run_token_cache_invalidation_listener;
+use zksync_types::tx::PackedEthSignature;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
@@ -40,12 +56,16 @@ pub enum Component {
     Web3Api,
     RpcApi,
     RpcWebSocketApi,
+    /// REST API serving the synthetic data, replaces the `RestApi` for the SDK testing.
+    SandboxApi,
 
     // Core components
     Fetchers,
     EthSender,
     Core,
     WitnessGenerator,
+    /// Generates the exit proofs requested via API, requires the exit circuit keys.
+    ExitProofGenerator,
     ForcedExit,
 
     // Additional components
@@ -63,8 +83,10 @@ impl FromStr for Component {
             "web3-api" => Ok(Component::Web3Api),
             "rpc-api" => Ok(Component::RpcApi),
             "rpc-websocket-api" => Ok(Component::RpcWebSocketApi),
+            "sandbox-api" => Ok(Component::SandboxApi),
             "eth-sender" => Ok(Component::EthSender),
             "witness-generator" => Ok(Component::WitnessGenerator),
+            "exit-proof-generator" => Ok(Component::ExitProofGenerator),
             "forced-exit" => Ok(Component::ForcedExit),
             "prometheus" => Ok(Component::Prometheus),
             "fetchers" => Ok(Component::Fetchers),
@@ -153,10 +175,50 @@ async fn main() -> anyhow::Result<()> {
 async fn run_server(components: &ComponentsToRun) {
     let connection_pool = ConnectionPool::new(None);
     let read_only_connection_pool = ConnectionPool::new_readonly_pool(None);
+    // The API reads use their own pool, so the bursts of the API traffic don't starve the core.
+    let api_connection_pool = ConnectionPool::new_api_pool(None, None);
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
 
     let mut tasks = vec![];
 
+    // Tokens can be changed via the admin API served by any process,
+    // so the token caches of every process have to follow the changes.
+    tasks.push(run_token_cache_invalidation_listener());
+
+    // If the REST API runs in the same process with the state keeper, it takes
+    // the committed state of the recently updated accounts from its memory.
+    let committed_state =
+        if components.0.contains(&Component::Core) && components.0.contains(&Component::RestApi) {
+            Some(SharedCommittedState::default())
+        } else {
+            None
+        };
+
+    // The components talking to Ethereum share the gateway, so once the watcher switches
+    // to another provider, all of them follow it.
+    let eth_gateway = if components.0.iter().any(|c| {
+        matches!(
+            c,
+            Component::RpcWebSocketApi
+                | Component::RpcApi
+                | Component::RestApi
+                | Component::Core
+                | Component::EthSender
+                | Component::Fetchers
+        )
+    }) {
+        let eth_gateway = create_eth_gateway();
+        let gateway_watcher_config = GatewayWatcherConfig::from_env();
+        if let Some(task) =
+            run_gateway_watcher_if_multiplexed(eth_gateway.clone(), &gateway_watcher_config)
+        {
+            tasks.push(task);
+        }
+        Some(eth_gateway)
+    } else {
+        None
+    };
+
     if components.0.contains(&Component::Web3Api) {
         // Run web3 api
         tasks.push(zksync_api::api_server::web3::start_rpc_server(
@@ -166,9 +228,24 @@ async fn run_server(components: &ComponentsToRun) {
         ));
     }
 
+    if components.0.contains(&Component::SandboxApi) {
+        tasks.push(
+            zksync_api::api_server::rest::start_sandbox_server_thread_detached(
+                RestApiConfig::from_env().bind_addr(),
+                SandboxApiConfig::from_env(),
+                ChainConfig::from_env().eth.network,
+            ),
+        );
+    }
+
     if components.0.contains(&Component::Fetchers) {
         // Run price fetchers
-        let mut price_tasks = run_price_updaters(connection_pool.clone());
+        let mut price_tasks = run_price_updaters(
+            connection_pool.clone(),
+            eth_gateway
+                .clone()
+                .expect("Ethereum gateway is not created"),
+        );
         tasks.append(&mut price_tasks);
     }
 
@@ -178,23 +255,15 @@ async fn run_server(components: &ComponentsToRun) {
             Component::RpcWebSocketApi | Component::RpcApi | Component::RestApi
         )
     }) {
-        // Create gateway
-        let eth_gateway = create_eth_gateway();
-
+        let eth_gateway = eth_gateway
+            .clone()
+            .expect("Ethereum gateway is not created");
         let eth_watch_config = ETHWatchConfig::from_env();
-        let gateway_watcher_config = GatewayWatcherConfig::from_env();
-
-        // Run eth multiplexer
-        if let Some(task) =
-            run_gateway_watcher_if_multiplexed(eth_gateway.clone(), &gateway_watcher_config)
-        {
-            tasks.push(task);
-        }
 
         // Run signer
         let (sign_check_sender, sign_check_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
         tasks.push(zksync_api::signature_checker::start_sign_checker(
-            eth_gateway,
+            eth_gateway.clone(),
             sign_check_receiver,
         ));
 
@@ -203,13 +272,16 @@ async fn run_server(components: &ComponentsToRun) {
         let token_config = TokenConfig::from_env();
         let chain_config = ChainConfig::from_env();
         let fee_ticker_config = TickerConfig::from_env();
-        let ticker_info = Box::new(TickerInfo::new(read_only_connection_pool.clone()));
+        let ticker_info = TickerInfo::new(api_connection_pool.clone())
+            .with_price_cache(PriceCacheConfig::from(&fee_ticker_config));
+        tasks.push(ticker_info.run_price_cache_refresh());
+        let ticker_info = Box::new(ticker_info);
 
         let ticker = FeeTicker::new_with_default_validator(
             ticker_info,
             fee_ticker_config,
             chain_config.max_blocks_to_aggregate(),
-            read_only_connection_pool.clone(),
+            api_connection_pool.clone(),
         );
 
         if components.0.contains(&Component::RpcWebSocketApi) {
@@ -219,9 +291,10 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
+                &chain_config.mempool,
             ));
             tasks.push(zksync_api::api_server::rpc_subscriptions::start_ws_server(
-                read_only_connection_pool.clone(),
+                api_connection_pool.clone(),
                 sign_check_sender.clone(),
                 ticker.clone(),
                 &common_config,
@@ -230,6 +303,9 @@ async fn run_server(components: &ComponentsToRun) {
                 chain_config.state_keeper.miniblock_iteration_interval(),
                 mempool_tx_request_sender,
                 eth_watch_config.confirmations_for_eth_event,
+                RateLimiter::new(&RateLimitConfig::from_env())
+                    .await
+                    .expect("Unable to initialize the rate limiter"),
             ));
         }
 
@@ -240,9 +316,10 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
+                &chain_config.mempool,
             ));
             tasks.push(zksync_api::api_server::rpc_server::start_rpc_server(
-                read_only_connection_pool.clone(),
+                api_connection_pool.clone(),
                 sign_check_sender.clone(),
                 ticker.clone(),
                 &JsonRpcConfig::from_env(),
@@ -250,6 +327,9 @@ async fn run_server(components: &ComponentsToRun) {
                 &token_config,
                 mempool_tx_request_sender,
                 eth_watch_config.confirmations_for_eth_event,
+                RateLimiter::new(&RateLimitConfig::from_env())
+                    .await
+                    .expect("Unable to initialize the rate limiter"),
             ));
         }
 
@@ -260,27 +340,51 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes,
+                &chain_config.mempool,
             ));
             let private_config = PrivateApiConfig::from_env();
             tasks.push(zksync_api::api_server::rest::start_server_thread_detached(
-                read_only_connection_pool.clone(),
+                api_connection_pool.clone(),
                 connection_pool.clone(),
                 RestApiConfig::from_env().bind_addr(),
                 contracts_config.contract_addr,
                 ticker,
                 sign_check_sender,
                 mempool_tx_request_sender,
-                private_config.url,
+                private_config,
+                eth_gateway,
+                committed_state.clone(),
             ));
         }
     }
 
+    // Only the elected instance is allowed to produce blocks and send them to L1.
+    // The rest of the components are started right away, and the standby instances keep serving them.
+    let mut fencing_term = None;
+    let leader_election_config = LeaderElectionConfig::from_env();
+    if leader_election_config.enabled
+        && (components.0.contains(&Component::Core) || components.0.contains(&Component::EthSender))
+    {
+        let leadership = Leadership::acquire(&connection_pool, &leader_election_config).await;
+        fencing_term = Some(leadership.fencing_term());
+        tasks.push(leadership.watch(&leader_election_config));
+    }
+
     if components.0.contains(&Component::EthSender) {
-        tasks.push(run_eth_sender(connection_pool.clone()))
+        let eth_gateway = eth_gateway
+            .clone()
+            .expect("Ethereum gateway is not created");
+        tasks.push(run_eth_sender(
+            connection_pool.clone(),
+            eth_gateway,
+            fencing_term.as_ref().map(FencingTerm::term),
+        ))
     }
 
     if components.0.contains(&Component::Core) {
-        let eth_gateway = create_eth_gateway();
+        let eth_gateway = eth_gateway
+            .clone()
+            .expect("Ethereum gateway is not created");
 
         tasks.append(
             &mut run_core(
@@ -288,6 +392,9 @@ async fn run_server(components: &ComponentsToRun) {
                 read_only_connection_pool.clone(),
                 &ZkSyncConfig::from_env(),
                 eth_gateway.clone(),
+                committed_state,
+                fencing_term,
+                stop_signal_sender.clone(),
             )
             .await
             .unwrap(),
@@ -298,11 +405,23 @@ async fn run_server(components: &ComponentsToRun) {
         tasks.push(run_witness_generator(connection_pool.clone()))
     }
 
+    if components.0.contains(&Component::ExitProofGenerator) {
+        tasks.push(run_exit_proof_generator(
+            connection_pool.clone(),
+            ProverConfig::from_env().exit_proof_generator,
+        ))
+    }
+
     if components.0.contains(&Component::Prometheus) {
         // Run prometheus data exporter.
         let config = PrometheusConfig::from_env();
         let prometheus_task_handle = run_prometheus_exporter(config.port);
         tasks.push(prometheus_task_handle);
+        tasks.push(run_pool_utilization_reporter(vec![
+            connection_pool.clone(),
+            read_only_connection_pool.clone(),
+            api_connection_pool.clone(),
+        ]));
         // We can run them only with active prometheus
         if components.0.contains(&Component::PrometheusPeriodicMetrics) {
             let counter_task_handle = run_operation_counter(read_only_connection_pool.clone());
@@ -316,7 +435,12 @@ async fn run_server(components: &ComponentsToRun) {
 
     if components.0.contains(&Component::RejectedTaskCleaner) {
         let config = DBConfig::from_env();
-        tasks.push(run_rejected_tx_cleaner(&config, connection_pool));
+        let archive_config = ArchiveConfig::from_env();
+        tasks.push(run_rejected_tx_cleaner(
+            &config,
+            &archive_config,
+            connection_pool.clone(),
+        ));
     }
 
     {
@@ -336,6 +460,13 @@ async fn run_server(components: &ComponentsToRun) {
             vlog::warn!("Stop signal received, shutting down");
         }
     };
+
+    // The clients use the snapshot to find out whether the interrupted submissions have to be repeated.
+    if let Err(err) =
+        zksync_api::api_server::in_flight::store_shutdown_snapshot(&connection_pool).await
+    {
+        vlog::warn!("Can't store the in-flight transaction submissions: {}", err);
+    }
 }
 
 pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
@@ -352,6 +483,7 @@ pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
         connection_pool.clone(),
         mempool_tx_request_receiver,
         chain_config.state_keeper.block_chunk_sizes,
+        &chain_config.mempool,
     );
     let forced_exit_task = run_forced_exit_requests_actors(
         connection_pool,
@@ -372,23 +504,49 @@ pub fn run_witness_generator(connection_pool: ConnectionPool) -> JoinHandle<()>
     run_prover_server(database, prover_api_config, prover_config)
 }
 
-pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
+pub fn run_eth_sender(
+    connection_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    fencing_term: Option<i64>,
+) -> JoinHandle<()> {
     vlog::info!("Starting the Ethereum sender actors");
-    let eth_client_config = ETHClientConfig::from_env();
     let eth_sender_config = ETHSenderConfig::from_env();
+    let eth_client_config = ETHClientConfig::from_env();
     let contracts = ContractsConfig::from_env();
-    let eth_gateway = EthereumGateway::from_config(
-        &eth_client_config,
-        &eth_sender_config,
-        contracts.contract_addr,
-    );
 
-    zksync_eth_sender::run_eth_sender(connection_pool, eth_gateway, eth_sender_config)
+    let additional_keys = eth_sender_config
+        .operator_keys
+        .private_keys
+        .iter()
+        .map(|&private_key| {
+            let address = PackedEthSignature::address_from_private_key(&private_key)
+                .expect("Invalid private key of the additional operator account");
+            let gateway = EthereumGateway::with_operator_key(
+                &eth_client_config,
+                address,
+                private_key,
+                contracts.contract_addr,
+            );
+            (address, gateway)
+        })
+        .collect();
+
+    zksync_eth_sender::run_eth_sender(
+        connection_pool,
+        eth_gateway,
+        additional_keys,
+        eth_sender_config,
+        contracts.contract_addr,
+        fencing_term,
+    )
 }
 
-pub fn run_price_updaters(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
+pub fn run_price_updaters(
+    connection_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+) -> Vec<JoinHandle<()>> {
     let ticker_config = TickerConfig::from_env();
-    run_updaters(connection_pool, &ticker_config)
+    run_updaters(connection_pool, eth_gateway, &ticker_config)
 }
 
 pub fn create_eth_gateway() -> EthereumGateway {
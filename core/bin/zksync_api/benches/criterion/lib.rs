@@ -13,6 +13,7 @@ fn generate_transactions(number: usize) -> BatchFeeRequest {
         transactions.push(TxInBatchFeeRequest {
             tx_type: ApiTxFeeTypes::Withdraw,
             address: Address::random(),
+            token: None,
         });
     }
     BatchFeeRequest {
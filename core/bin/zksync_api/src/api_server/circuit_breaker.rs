@@ -0,0 +1,245 @@
+//! Circuit breaker of the downstream services of the API (the storage and the mempool).
+//!
+//! The breaker counts the outcomes of the requests to the service within the window. Once
+//! the share of the failures exceeds the threshold, the breaker opens and the API fails the
+//! requests immediately instead of piling them up while the service is struggling. After
+//! the open period, a single trial request is let through: the breaker closes if it succeeds
+//! and opens again otherwise.
+
+// Built-in uses
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+// Workspace uses
+use zksync_config::configs::api::CircuitBreakerConfig;
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        window_start: Instant,
+        successes: u32,
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// The trial request is in progress. Another trial is let through if the outcome
+    /// isn't reported for the open period, e.g. the request was cancelled.
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+impl State {
+    fn closed(now: Instant) -> Self {
+        Self::Closed {
+            window_start: now,
+            successes: 0,
+            failures: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    name: &'static str,
+    config: Option<CircuitBreakerConfig>,
+    state: Arc<Mutex<State>>,
+}
+
+impl CircuitBreaker {
+    /// Creates the breaker, which never opens if it's disabled in the config.
+    pub fn new(name: &'static str, config: &CircuitBreakerConfig) -> Self {
+        Self {
+            name,
+            config: Some(config.clone()).filter(|config| config.enabled),
+            state: Arc::new(Mutex::new(State::closed(Instant::now()))),
+        }
+    }
+
+    /// Breaker letting all the requests through.
+    pub fn disabled(name: &'static str) -> Self {
+        Self {
+            name,
+            config: None,
+            state: Arc::new(Mutex::new(State::closed(Instant::now()))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Checks whether the request to the service can be made.
+    /// The outcome of the allowed request must be reported via `record`.
+    pub fn allow(&self) -> bool {
+        self.allow_at(Instant::now())
+    }
+
+    /// Reports the outcome of the request to the service.
+    pub fn record(&self, success: bool) {
+        self.record_at(success, Instant::now())
+    }
+
+    fn allow_at(&self, now: Instant) -> bool {
+        let open_duration = match &self.config {
+            Some(config) => config.open_duration(),
+            None => return true,
+        };
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            State::Closed { .. } => true,
+            State::Open { until } if until <= now => {
+                *state = State::HalfOpen { since: now };
+                true
+            }
+            State::HalfOpen { since } if now.duration_since(since) >= open_duration => {
+                *state = State::HalfOpen { since: now };
+                true
+            }
+            State::Open { .. } | State::HalfOpen { .. } => {
+                metrics::increment_counter!("api.circuit_breaker.rejected", "service" => self.name);
+                false
+            }
+        }
+    }
+
+    fn record_at(&self, success: bool, now: Instant) {
+        let config = match &self.config {
+            Some(config) => config,
+            None => return,
+        };
+        let mut state = self.state.lock().unwrap();
+        match &mut *state {
+            State::Closed {
+                window_start,
+                successes,
+                failures,
+            } => {
+                if now.duration_since(*window_start) >= config.window() {
+                    *window_start = now;
+                    *successes = 0;
+                    *failures = 0;
+                }
+                if success {
+                    *successes += 1;
+                } else {
+                    *failures += 1;
+                }
+                let total = *successes + *failures;
+                if total >= config.min_requests
+                    && f64::from(*failures) >= f64::from(total) * config.error_rate_threshold
+                {
+                    vlog::warn!(
+                        "Opening the circuit breaker of {}: {} of {} requests failed",
+                        self.name,
+                        failures,
+                        total
+                    );
+                    metrics::increment_counter!("api.circuit_breaker.opened", "service" => self.name);
+                    *state = State::Open {
+                        until: now + config.open_duration(),
+                    };
+                }
+            }
+            State::HalfOpen { .. } if success => {
+                vlog::info!("Closing the circuit breaker of {}", self.name);
+                *state = State::closed(now);
+            }
+            State::HalfOpen { .. } => {
+                *state = State::Open {
+                    until: now + config.open_duration(),
+                };
+            }
+            // The requests started before the breaker has opened.
+            State::Open { .. } => {}
+        }
+    }
+
+    /// Time left until the trial request is let through, `None` if the breaker is not open.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match *self.state.lock().unwrap() {
+            State::Open { until } => Some(until.saturating_duration_since(Instant::now())),
+            State::HalfOpen { since } => self.config.as_ref().map(|config| {
+                (since + config.open_duration()).saturating_duration_since(Instant::now())
+            }),
+            State::Closed { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(
+            "test",
+            &CircuitBreakerConfig {
+                enabled: true,
+                window_secs: 10,
+                min_requests: 4,
+                error_rate_threshold: 0.5,
+                open_secs: 5,
+            },
+        )
+    }
+
+    #[test]
+    fn opens_on_error_rate() {
+        let breaker = breaker();
+        let now = Instant::now();
+
+        // Not enough requests to judge.
+        breaker.record_at(false, now);
+        breaker.record_at(false, now);
+        breaker.record_at(true, now);
+        assert!(breaker.allow_at(now));
+
+        breaker.record_at(false, now);
+        assert!(!breaker.allow_at(now));
+        assert!(!breaker.allow_at(now + Duration::from_secs(4)));
+
+        // The trial request fails, so the breaker opens again.
+        let trial = now + Duration::from_secs(5);
+        assert!(breaker.allow_at(trial));
+        assert!(!breaker.allow_at(trial));
+        breaker.record_at(false, trial);
+        assert!(!breaker.allow_at(trial + Duration::from_secs(1)));
+
+        // The trial request succeeds, so the breaker closes.
+        let trial = trial + Duration::from_secs(5);
+        assert!(breaker.allow_at(trial));
+        breaker.record_at(true, trial);
+        assert!(breaker.allow_at(trial));
+        assert!(breaker.retry_after().is_none());
+    }
+
+    #[test]
+    fn failures_expire_with_window() {
+        let breaker = breaker();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            breaker.record_at(false, now);
+        }
+        // The failures of the previous window are not counted.
+        let next_window = now + Duration::from_secs(10);
+        breaker.record_at(false, next_window);
+        assert!(breaker.allow_at(next_window));
+
+        for _ in 0..3 {
+            breaker.record_at(true, next_window);
+        }
+        assert!(breaker.allow_at(next_window));
+    }
+
+    #[test]
+    fn disabled_breaker_never_opens() {
+        let breaker = CircuitBreaker::disabled("test");
+        let now = Instant::now();
+        for _ in 0..100 {
+            breaker.record_at(false, now);
+        }
+        assert!(breaker.allow_at(now));
+    }
+}
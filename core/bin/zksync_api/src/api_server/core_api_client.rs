@@ -0,0 +1,354 @@
+//! Client of the private API of the core server.
+//!
+//! The core is reached either via JSON over HTTP or via gRPC, depending on the configured
+//! transport.
+//!
+//! The transient failures (connection errors, timeouts, `5xx` and `429` responses) are retried
+//! with the jittered exponential backoff until either the retries or the deadline of the call
+//! are exhausted, so a single hiccup of the core doesn't surface to the users. The permanent
+//! failures (other `4xx` responses, malformed responses) are returned right away. The forwarded
+//! transactions are never retried, as the transaction may have been added even if the response
+//! was lost.
+
+// Built-in uses
+use std::future::Future;
+use std::time::{Duration, Instant};
+// External uses
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+use tonic::{transport::Channel, Code};
+// Workspace uses
+use zksync_api_types::{CoreStatus, NewTxRequest};
+use zksync_config::configs::api::{PrivateApiConfig, PrivateApiTransport};
+use zksync_core_api_grpc::{
+    proto::{
+        core_api_client::CoreApiClient as GrpcClient, GetStatusRequest,
+        GetUnconfirmedDepositsRequest, GetUnconfirmedOpRequest, SubmitTxRequest,
+    },
+    DecodeError,
+};
+use zksync_crypto::rand::{thread_rng, Rng};
+use zksync_types::{tx::error::TxAddError, Address, BlockNumber, PriorityOp, SignedZkSyncTx, H256};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CoreApiError {
+    #[error("Transient failure of the core API: {0}")]
+    Transient(String),
+    #[error("Permanent failure of the core API: {0}")]
+    Permanent(String),
+}
+
+impl CoreApiError {
+    /// Whether the request may succeed if retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Transient(_))
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+impl From<reqwest::Error> for CoreApiError {
+    fn from(err: reqwest::Error) -> Self {
+        let transient = match err.status() {
+            Some(status) => is_transient_status(status),
+            None => err.is_timeout() || err.is_connect() || err.is_request() || err.is_body(),
+        };
+        if transient {
+            Self::Transient(err.to_string())
+        } else {
+            Self::Permanent(err.to_string())
+        }
+    }
+}
+
+impl From<DecodeError> for CoreApiError {
+    fn from(err: DecodeError) -> Self {
+        Self::Permanent(err.to_string())
+    }
+}
+
+impl From<tonic::Status> for CoreApiError {
+    fn from(status: tonic::Status) -> Self {
+        match status.code() {
+            Code::Unavailable
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+            | Code::Aborted => Self::Transient(status.to_string()),
+            _ => Self::Permanent(status.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Transport {
+    Http {
+        client: reqwest::Client,
+        url: String,
+    },
+    Grpc(GrpcClient<Channel>),
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreApiClient {
+    transport: Transport,
+    deadline: Duration,
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+impl CoreApiClient {
+    pub fn new(config: &PrivateApiConfig) -> Self {
+        let transport = match config.transport {
+            PrivateApiTransport::Http => {
+                let client = reqwest::Client::builder()
+                    .timeout(config.client_request_timeout())
+                    .pool_max_idle_per_host(config.client_pool_max_idle)
+                    .pool_idle_timeout(config.client_pool_idle_timeout())
+                    .tcp_keepalive(config.client_pool_idle_timeout())
+                    .build()
+                    .expect("Failed to create the core API HTTP client");
+                Transport::Http {
+                    client,
+                    url: config.url.trim_end_matches('/').to_owned(),
+                }
+            }
+            PrivateApiTransport::Grpc => {
+                // The requests are multiplexed over the single HTTP/2 connection.
+                let channel = Channel::from_shared(config.grpc_url.clone())
+                    .expect("Invalid URL of the core gRPC API")
+                    .timeout(config.client_request_timeout())
+                    .tcp_keepalive(Some(config.client_pool_idle_timeout()))
+                    .connect_lazy()
+                    .expect("Failed to create the core API gRPC channel");
+                Transport::Grpc(GrpcClient::new(channel))
+            }
+        };
+        Self {
+            transport,
+            deadline: config.client_deadline(),
+            max_retries: config.client_max_retries,
+            retry_backoff: config.client_retry_backoff(),
+        }
+    }
+
+    /// Gets the healthcheck status of the core server.
+    pub async fn get_status(&self) -> Result<CoreStatus, CoreApiError> {
+        self.with_retries(|| async {
+            match &self.transport {
+                Transport::Http { client, url } => http_get(client, url, "/status").await,
+                Transport::Grpc(client) => {
+                    let status = client.clone().get_status(GetStatusRequest {}).await?;
+                    Ok(status.into_inner().into())
+                }
+            }
+        })
+        .await
+    }
+
+    /// Gets the priority operations of the account which are not confirmed on Ethereum yet.
+    pub async fn get_unconfirmed_deposits(
+        &self,
+        address: Address,
+    ) -> Result<Vec<PriorityOp>, CoreApiError> {
+        self.with_retries(|| async {
+            match &self.transport {
+                Transport::Http { client, url } => {
+                    let path = format!("/unconfirmed_deposits/{:?}", address);
+                    http_get(client, url, &path).await
+                }
+                Transport::Grpc(client) => {
+                    let request = GetUnconfirmedDepositsRequest::new(address);
+                    let ops = client.clone().get_unconfirmed_deposits(request).await?;
+                    Ok(ops.get_ref().decode_ops()?)
+                }
+            }
+        })
+        .await
+    }
+
+    /// Gets the unconfirmed priority operation by its hash.
+    pub async fn get_unconfirmed_op(
+        &self,
+        tx_hash: H256,
+    ) -> Result<Option<PriorityOp>, CoreApiError> {
+        self.with_retries(|| async {
+            match &self.transport {
+                Transport::Http { client, url } => {
+                    let path = format!("/unconfirmed_op/{:?}", tx_hash);
+                    http_get(client, url, &path).await
+                }
+                Transport::Grpc(client) => {
+                    let request = GetUnconfirmedOpRequest::new(tx_hash);
+                    let ops = client.clone().get_unconfirmed_op(request).await?;
+                    Ok(ops.get_ref().decode_ops()?.into_iter().next())
+                }
+            }
+        })
+        .await
+    }
+
+    /// Forwards the checked transaction to the mempool of the core.
+    pub async fn send_tx(
+        &self,
+        tx: &SignedZkSyncTx,
+        not_before_block: Option<BlockNumber>,
+    ) -> Result<Result<(), TxAddError>, CoreApiError> {
+        self.with_retries_limit(0, || async {
+            match &self.transport {
+                Transport::Http { client, url } => {
+                    let request = NewTxRequest {
+                        tx: tx.clone(),
+                        not_before_block,
+                    };
+                    http_post(client, url, "/new_tx", &request).await
+                }
+                Transport::Grpc(client) => {
+                    let request = SubmitTxRequest::new(tx, not_before_block)?;
+                    let response = client.clone().submit_tx(request).await?;
+                    Ok(response.get_ref().decode_result()?)
+                }
+            }
+        })
+        .await
+    }
+
+    async fn with_retries<T, F, Fut>(&self, request: F) -> Result<T, CoreApiError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, CoreApiError>>,
+    {
+        self.with_retries_limit(self.max_retries, request).await
+    }
+
+    async fn with_retries_limit<T, F, Fut>(
+        &self,
+        max_retries: u32,
+        request: F,
+    ) -> Result<T, CoreApiError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, CoreApiError>>,
+    {
+        let start = Instant::now();
+        let mut attempt = 0;
+
+        let result = loop {
+            let time_left = self.deadline.saturating_sub(start.elapsed());
+            let result = match tokio::time::timeout(time_left, request()).await {
+                Ok(result) => result,
+                Err(_) => break Err(CoreApiError::Transient("Deadline exceeded".to_owned())),
+            };
+            match result {
+                Err(err) if err.is_transient() && attempt < max_retries => {
+                    let delay = self.backoff(attempt);
+                    if start.elapsed() + delay >= self.deadline {
+                        break Err(err);
+                    }
+                    vlog::debug!("Retrying the request to the core in {:?}: {}", delay, err);
+                    metrics::increment_counter!("api.core_client.retries");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => break result,
+            }
+        };
+
+        metrics::histogram!("api.core_client.request", start.elapsed());
+        if let Err(err) = &result {
+            let kind = if err.is_transient() {
+                "transient"
+            } else {
+                "permanent"
+            };
+            metrics::increment_counter!("api.core_client.errors", "kind" => kind);
+        }
+        result
+    }
+
+    /// Delay before the retry: a random value between the half and the whole
+    /// of the exponentially growing backoff, so the clients don't retry in lockstep.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let max_delay = self.retry_backoff.saturating_mul(1 << attempt.min(16));
+        let max_millis = max_delay.as_millis() as u64;
+        Duration::from_millis(thread_rng().gen_range(max_millis / 2, max_millis + 1))
+    }
+}
+
+async fn http_get<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+    path: &str,
+) -> Result<T, CoreApiError> {
+    let response = client.get(format!("{}{}", url, path)).send().await?;
+    Ok(response.error_for_status()?.json().await?)
+}
+
+async fn http_post<T: DeserializeOwned, B: Serialize>(
+    client: &reqwest::Client,
+    url: &str,
+    path: &str,
+    body: &B,
+) -> Result<T, CoreApiError> {
+    let response = client
+        .post(format!("{}{}", url, path))
+        .json(body)
+        .send()
+        .await?;
+    Ok(response.error_for_status()?.json().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PrivateApiConfig {
+        PrivateApiConfig {
+            port: 8090,
+            url: "http://127.0.0.1:8090/".into(),
+            transport: PrivateApiTransport::Http,
+            grpc_port: 8091,
+            grpc_url: "http://127.0.0.1:8091".into(),
+            client_request_timeout_ms: 100,
+            client_deadline_ms: 500,
+            client_max_retries: 3,
+            client_retry_backoff_ms: 100,
+            client_pool_max_idle: 8,
+            client_pool_idle_timeout_secs: 90,
+        }
+    }
+
+    #[test]
+    fn backoff_grows_with_attempts() {
+        let client = CoreApiClient::new(&config());
+        for attempt in 0..4 {
+            let max_delay = Duration::from_millis(100 << attempt);
+            let delay = client.backoff(attempt);
+            assert!(delay >= max_delay / 2 && delay <= max_delay, "{:?}", delay);
+        }
+    }
+
+    #[test]
+    fn transient_statuses() {
+        assert!(is_transient_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_transient_status(StatusCode::NOT_FOUND));
+        assert!(!is_transient_status(StatusCode::BAD_REQUEST));
+
+        assert!(CoreApiError::from(tonic::Status::unavailable("")).is_transient());
+        assert!(!CoreApiError::from(tonic::Status::unimplemented("")).is_transient());
+    }
+
+    #[tokio::test]
+    async fn invalid_url_is_not_retried() {
+        let client = CoreApiClient::new(&PrivateApiConfig {
+            url: "0.0.0.0".into(),
+            ..config()
+        });
+        let err = client.get_status().await.unwrap_err();
+        assert!(!err.is_transient(), "{}", err);
+    }
+}
@@ -0,0 +1,143 @@
+//! Latest Ethereum block and the recent average block time, used to tell the users
+//! how long their deposits have to wait for the confirmations.
+
+// Built-in uses
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+// External uses
+use tokio::sync::{Mutex, RwLock};
+use web3::types::{BlockId, BlockNumber, U64};
+// Workspace uses
+use zksync_eth_client::EthereumGateway;
+
+/// The head is reloaded from the Ethereum node at most once per this period.
+const HEAD_CACHE_PERIOD: Duration = Duration::from_secs(5);
+/// The failure to load the head is cached for this period, so the unavailable node
+/// isn't queried on every request.
+const HEAD_ERROR_CACHE_PERIOD: Duration = Duration::from_secs(1);
+/// Number of the recent blocks the average block time is calculated over.
+const BLOCK_TIME_WINDOW: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EthHead {
+    pub block_number: u64,
+    /// `None` if the timestamps of the blocks are not available.
+    pub avg_block_time: Option<Duration>,
+}
+
+impl EthHead {
+    /// Number of the confirmations left until the block is reached.
+    pub fn remaining_confirmations(&self, expected_block: u64) -> u64 {
+        expected_block.saturating_sub(self.block_number)
+    }
+
+    /// Estimated time until the block is reached.
+    pub fn eta(&self, expected_block: u64) -> Option<Duration> {
+        let remaining = self.remaining_confirmations(expected_block);
+        self.avg_block_time
+            .map(|block_time| block_time * remaining as u32)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EthHeadCache {
+    client: EthereumGateway,
+    /// The head is `None` if it failed to load.
+    cached: Arc<RwLock<Option<(Option<EthHead>, Instant)>>>,
+    /// Held by the caller reloading the head, the readers aren't blocked meanwhile.
+    refresh: Arc<Mutex<()>>,
+}
+
+impl EthHeadCache {
+    pub fn new(client: EthereumGateway) -> Self {
+        Self {
+            client,
+            cached: Default::default(),
+            refresh: Default::default(),
+        }
+    }
+
+    /// Returns `None` if the Ethereum node is not available.
+    pub async fn get(&self) -> Option<EthHead> {
+        if let Some(head) = self.fresh().await {
+            return head;
+        }
+
+        // Only one caller reloads the head, the rest wait for its result.
+        let _refresh = self.refresh.lock().await;
+        if let Some(head) = self.fresh().await {
+            return head;
+        }
+        let head = match self.load().await {
+            Ok(head) => Some(head),
+            Err(err) => {
+                vlog::warn!("Failed to load the latest Ethereum block: {}", err);
+                None
+            }
+        };
+        *self.cached.write().await = Some((head, Instant::now()));
+        head
+    }
+
+    /// Cached result of the last load, if it's not outdated yet.
+    async fn fresh(&self) -> Option<Option<EthHead>> {
+        let (head, loaded_at) = (*self.cached.read().await)?;
+        let period = if head.is_some() {
+            HEAD_CACHE_PERIOD
+        } else {
+            HEAD_ERROR_CACHE_PERIOD
+        };
+        (loaded_at.elapsed() < period).then(|| head)
+    }
+
+    async fn load(&self) -> anyhow::Result<EthHead> {
+        let block_number = self.client.block_number().await?.as_u64();
+        let avg_block_time = self.avg_block_time(block_number).await?;
+        Ok(EthHead {
+            block_number,
+            avg_block_time,
+        })
+    }
+
+    async fn avg_block_time(&self, block_number: u64) -> anyhow::Result<Option<Duration>> {
+        let window = BLOCK_TIME_WINDOW.min(block_number);
+        if window == 0 {
+            return Ok(None);
+        }
+        let latest = self.block_timestamp(block_number).await?;
+        let earliest = self.block_timestamp(block_number - window).await?;
+        Ok(latest.zip(earliest).map(|(latest, earliest)| {
+            Duration::from_secs(latest.saturating_sub(earliest)) / window as u32
+        }))
+    }
+
+    async fn block_timestamp(&self, number: u64) -> anyhow::Result<Option<u64>> {
+        let block_id = BlockId::Number(BlockNumber::Number(U64::from(number)));
+        let block = self.client.block(block_id).await?;
+        Ok(block.map(|block| block.timestamp.as_u64()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deposit_eta() {
+        let head = EthHead {
+            block_number: 100,
+            avg_block_time: Some(Duration::from_secs(12)),
+        };
+        assert_eq!(head.remaining_confirmations(110), 10);
+        assert_eq!(head.eta(110), Some(Duration::from_secs(120)));
+        // The block is already reached.
+        assert_eq!(head.remaining_confirmations(90), 0);
+        assert_eq!(head.eta(90), Some(Duration::from_secs(0)));
+
+        let head = EthHead {
+            avg_block_time: None,
+            ..head
+        };
+        assert_eq!(head.eta(110), None);
+    }
+}
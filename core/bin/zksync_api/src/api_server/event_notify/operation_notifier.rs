@@ -138,6 +138,7 @@ impl OperationNotifier {
                         executed: true,
                         success: Some(tx.success),
                         fail_reason: tx.fail_reason,
+                        fail_code: tx.fail_code,
                         block: Some(BlockInfo {
                             block_number: i64::from(*block_number),
                             committed: true,
@@ -253,6 +254,7 @@ impl OperationNotifier {
                 executed: true,
                 success: Some(receipt.success),
                 fail_reason: receipt.fail_reason,
+                fail_code: receipt.fail_code,
                 block: Some(BlockInfo {
                     block_number: receipt.block_number,
                     committed: receipt.success,
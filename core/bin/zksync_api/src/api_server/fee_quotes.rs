@@ -0,0 +1,89 @@
+//! Recorder of the fee quotes served by the API to the audit log.
+//!
+//! The fee endpoints are public and not authenticated, so the quotes are not stored on the
+//! request path: they are queued and written in batches by the background task. Once the
+//! queue is full, e.g. the database is slow, the new quotes are dropped, the audit log is
+//! best-effort and must not slow down or fail the fee requests.
+
+// Built-in uses
+use std::sync::{Arc, Mutex};
+// External uses
+use futures::{channel::mpsc, StreamExt};
+// Workspace uses
+use zksync_storage::{fee_quotes::records::FeeQuote, ConnectionPool};
+
+/// Maximum number of the quotes waiting to be stored.
+const QUEUE_SIZE: usize = 4096;
+/// Maximum number of the quotes stored within a single database transaction.
+const MAX_BATCH_SIZE: usize = 256;
+
+struct Inner {
+    sender: mpsc::Sender<FeeQuote>,
+    /// Taken by the first recorded quote, which spawns the writer, since the recorder
+    /// may be created outside of the runtime.
+    writer: Mutex<Option<(mpsc::Receiver<FeeQuote>, ConnectionPool)>>,
+}
+
+/// Recorder of the fee quotes, the clones share the queue and the writer.
+#[derive(Clone)]
+pub struct FeeQuotesRecorder(Option<Arc<Inner>>);
+
+impl FeeQuotesRecorder {
+    /// Creates the recorder, which ignores the quotes if it's disabled.
+    pub fn new(pool: ConnectionPool, enabled: bool) -> Self {
+        if !enabled {
+            return Self::disabled();
+        }
+        let (sender, receiver) = mpsc::channel(QUEUE_SIZE);
+        Self(Some(Arc::new(Inner {
+            sender,
+            writer: Mutex::new(Some((receiver, pool))),
+        })))
+    }
+
+    pub fn disabled() -> Self {
+        Self(None)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Queues the quote to be stored, it's dropped if the queue is full.
+    pub fn record(&self, quote: FeeQuote) {
+        let inner = match &self.0 {
+            Some(inner) => inner,
+            None => return,
+        };
+        if let Some((receiver, pool)) = inner.writer.lock().unwrap().take() {
+            tokio::spawn(write_fee_quotes(receiver, pool));
+        }
+        if inner.sender.clone().try_send(quote).is_err() {
+            metrics::increment_counter!("api.fee_quotes.dropped");
+        }
+    }
+}
+
+async fn write_fee_quotes(mut receiver: mpsc::Receiver<FeeQuote>, pool: ConnectionPool) {
+    while let Some(quote) = receiver.next().await {
+        let mut quotes = vec![quote];
+        while quotes.len() < MAX_BATCH_SIZE {
+            match receiver.try_next() {
+                Ok(Some(quote)) => quotes.push(quote),
+                _ => break,
+            }
+        }
+
+        let result = async {
+            pool.access_storage()
+                .await?
+                .fee_quotes_schema()
+                .store_fee_quotes(&quotes)
+                .await
+        }
+        .await;
+        if let Err(err) = result {
+            vlog::warn!("Failed to store {} fee quotes: {}", quotes.len(), err);
+        }
+    }
+}
@@ -1,19 +1,22 @@
 //! Helpers collection shared between the different API implementations.
 
 // Built-in uses
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 // External uses
 use num::BigUint;
 
 // Workspace uses
-use zksync_api_types::v02::account::{DepositingAccountBalances, DepositingFunds, OngoingDeposit};
+use zksync_api_types::v02::account::{
+    DepositProgress, DepositingAccountBalances, DepositingTokenFunds, OngoingDeposit,
+};
 use zksync_storage::StorageProcessor;
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{Address, H256};
+use zksync_types::{Address, PriorityOp, H256};
 use zksync_utils::remove_prefix;
 
 // Local uses
+use super::eth_head::EthHead;
 use super::rest::v02::error::Error;
 use crate::fee_ticker::PriceError;
 
@@ -31,12 +34,17 @@ pub fn try_parse_hash(query: &str) -> Result<H256, hex::FromHexError> {
 async fn depositing_from_pending_ops(
     storage: &mut StorageProcessor<'_>,
     tokens: &TokenDBCache,
-    pending_ops: Vec<OngoingDeposit>,
+    pending_ops: Vec<PriorityOp>,
     confirmations_for_eth_event: u64,
+    eth_head: Option<EthHead>,
+    include_token_ids: bool,
 ) -> Result<DepositingAccountBalances, Error> {
-    let mut balances = HashMap::new();
+    let mut balances: BTreeMap<_, DepositingTokenFunds> = BTreeMap::new();
+    let mut deposits = Vec::with_capacity(pending_ops.len());
 
-    for op in pending_ops {
+    for priority_op in pending_ops {
+        let eth_tx_hash = priority_op.eth_hash;
+        let op = OngoingDeposit::new(priority_op);
         let token_symbol = if *op.token_id == 0 {
             "ETH".to_string()
         } else {
@@ -51,10 +59,26 @@ async fn depositing_from_pending_ops(
         };
 
         let expected_accept_block = op.received_on_block + confirmations_for_eth_event;
+        deposits.push(DepositProgress {
+            eth_tx_hash,
+            token_id: op.token_id,
+            amount: BigUint::from(op.amount),
+            received_on_block: op.received_on_block,
+            expected_accept_block,
+            remaining_confirmations: eth_head
+                .map(|head| head.remaining_confirmations(expected_accept_block)),
+            eta_seconds: eth_head
+                .and_then(|head| head.eta(expected_accept_block))
+                .map(|eta| eta.as_secs()),
+        });
 
         let balance = balances
-            .entry(token_symbol)
-            .or_insert_with(DepositingFunds::default);
+            .entry(op.token_id)
+            .or_insert_with(|| DepositingTokenFunds {
+                symbol: token_symbol,
+                amount: BigUint::default(),
+                expected_accept_block,
+            });
 
         balance.amount += BigUint::from(op.amount);
 
@@ -65,7 +89,11 @@ async fn depositing_from_pending_ops(
         }
     }
 
-    Ok(DepositingAccountBalances { balances })
+    Ok(DepositingAccountBalances::new(
+        balances,
+        deposits,
+        include_token_ids,
+    ))
 }
 
 pub async fn get_depositing(
@@ -73,14 +101,21 @@ pub async fn get_depositing(
     tokens: &TokenDBCache,
     address: Address,
     confirmations_for_eth_event: u64,
+    eth_head: Option<EthHead>,
+    include_token_ids: bool,
 ) -> Result<DepositingAccountBalances, Error> {
     let pending_ops = storage
         .chain()
         .mempool_schema()
         .get_pending_deposits(address)
-        .await?
-        .into_iter()
-        .map(OngoingDeposit::new)
-        .collect();
-    depositing_from_pending_ops(storage, tokens, pending_ops, confirmations_for_eth_event).await
+        .await?;
+    depositing_from_pending_ops(
+        storage,
+        tokens,
+        pending_ops,
+        confirmations_for_eth_event,
+        eth_head,
+        include_token_ids,
+    )
+    .await
 }
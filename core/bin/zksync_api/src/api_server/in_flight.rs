@@ -0,0 +1,115 @@
+//! Registry of the transaction submissions in progress.
+//!
+//! The client can't tell whether the transaction submitted right before the restart of the API
+//! server was stored by the mempool, since the connection is closed without the response.
+//! The submissions are tracked by all the API servers of the process, and the snapshot of the ones
+//! that are still in progress is stored on shutdown, so the `/transactions/{txHash}/submission`
+//! endpoint can tell the client whether the transaction has to be resubmitted.
+
+// Built-in uses
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+// External uses
+use once_cell::sync::Lazy;
+// Workspace uses
+use zksync_api_types::v02::transaction::SubmissionStage;
+use zksync_storage::ConnectionPool;
+use zksync_types::tx::TxHash;
+
+static IN_FLIGHT_SUBMISSIONS: Lazy<Mutex<HashMap<TxHash, SubmissionStage>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn registry() -> MutexGuard<'static, HashMap<TxHash, SubmissionStage>> {
+    // The lock can only be poisoned by a panic while the registry is updated,
+    // the entries are still consistent in that case.
+    match IN_FLIGHT_SUBMISSIONS.lock() {
+        Ok(submissions) => submissions,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Submission of the transaction (or the batch of transactions) in progress.
+/// The transactions are removed from the registry once the guard is dropped.
+#[derive(Debug)]
+pub struct InFlightSubmission {
+    tx_hashes: Vec<TxHash>,
+}
+
+impl InFlightSubmission {
+    pub fn start(tx_hashes: Vec<TxHash>) -> Self {
+        let submission = Self { tx_hashes };
+        submission.set_stage(SubmissionStage::Verifying);
+        submission
+    }
+
+    pub fn set_stage(&self, stage: SubmissionStage) {
+        let mut submissions = registry();
+        for tx_hash in &self.tx_hashes {
+            submissions.insert(*tx_hash, stage);
+        }
+    }
+}
+
+impl Drop for InFlightSubmission {
+    fn drop(&mut self) {
+        let mut submissions = registry();
+        for tx_hash in &self.tx_hashes {
+            submissions.remove(tx_hash);
+        }
+    }
+}
+
+fn in_flight_submissions() -> Vec<(TxHash, SubmissionStage)> {
+    registry()
+        .iter()
+        .map(|(tx_hash, stage)| (*tx_hash, *stage))
+        .collect()
+}
+
+/// Stores the submissions that are still in progress, must be called once the process
+/// received the stop signal.
+pub async fn store_shutdown_snapshot(pool: &ConnectionPool) -> anyhow::Result<()> {
+    let submissions = in_flight_submissions();
+    if submissions.is_empty() {
+        return Ok(());
+    }
+    vlog::info!(
+        "Storing {} interrupted transaction submissions",
+        submissions.len()
+    );
+    pool.access_storage()
+        .await?
+        .chain()
+        .mempool_schema()
+        .store_interrupted_submissions(&submissions)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage(tx_hash: &TxHash) -> Option<SubmissionStage> {
+        in_flight_submissions()
+            .into_iter()
+            .find(|(hash, _)| hash == tx_hash)
+            .map(|(_, stage)| stage)
+    }
+
+    #[test]
+    fn in_flight_submissions_registry() {
+        let tx_hashes: Vec<_> = (0u8..2)
+            .map(|i| TxHash::from_slice(&[i + 100; 32]).unwrap())
+            .collect();
+
+        let submission = InFlightSubmission::start(tx_hashes.clone());
+        assert_eq!(stage(&tx_hashes[0]), Some(SubmissionStage::Verifying));
+        submission.set_stage(SubmissionStage::SentToMempool);
+        assert_eq!(stage(&tx_hashes[1]), Some(SubmissionStage::SentToMempool));
+
+        drop(submission);
+        assert_eq!(stage(&tx_hashes[0]), None);
+        assert_eq!(stage(&tx_hashes[1]), None);
+    }
+}
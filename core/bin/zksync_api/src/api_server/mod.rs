@@ -4,9 +4,15 @@
 //! `mod rpc_server` - JSON rpc via HTTP (for request reply functions)
 //! `mod rpc_subscriptions` - JSON rpc via WebSocket (for request reply functions and subscriptions)
 
+pub mod circuit_breaker;
+pub mod core_api_client;
+pub mod eth_head;
 mod event_notify;
+mod fee_quotes;
 pub mod forced_exit_checker;
 mod helpers;
+pub mod in_flight;
+pub mod rate_limit;
 pub mod rest;
 pub mod rpc_server;
 pub mod rpc_subscriptions;
@@ -0,0 +1,446 @@
+//! Per-IP rate limiting of the REST and JSON-RPC APIs.
+//!
+//! Every client IP has a token bucket per route group: the bucket holds up to `burst` tokens
+//! and is refilled with `requests_per_second` tokens per second, every request takes one token.
+//! Requests submitting the signed data (`POST` requests except the fee quotes, the submitting
+//! JSON-RPC methods) are limited separately and usually stricter than the read ones. Rejected
+//! REST requests get `429 Too Many Requests` with the `Retry-After` header.
+//!
+//! Buckets are kept either in memory of the API server or in Redis, so the limits can be shared
+//! by all the replicas. The client IP is the address of the peer, the `CF-Connecting-IP` header
+//! set by Cloudflare is only trusted if the peer is one of the configured proxies.
+
+// Built-in uses
+use std::{
+    net::IpAddr,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// External uses
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::Method,
+    HttpRequest, HttpResponse,
+};
+use async_trait::async_trait;
+use futures::future::{ready, LocalBoxFuture, Ready};
+use lru_cache::LruCache;
+
+// Workspace uses
+use zksync_config::configs::api::{RateLimitBackend, RateLimitConfig};
+
+// Local uses
+use self::redis_buckets::RedisBuckets;
+
+mod redis_buckets;
+pub mod rpc;
+
+const CLOUDFLARE_CONNECTING_IP_HEADER: &str = "CF-Connecting-IP";
+/// The maximum amount of the buckets kept in memory, the least recently used ones are dropped.
+const MEMORY_BUCKETS_CAPACITY: usize = 100_000;
+
+/// Group of the routes sharing the limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RouteGroup {
+    Read,
+    Submit,
+}
+
+impl RouteGroup {
+    fn of(method: &Method, path: &str) -> Self {
+        if *method == Method::POST && !path.contains("/fee") {
+            Self::Submit
+        } else {
+            Self::Read
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Submit => "submit",
+        }
+    }
+}
+
+/// Sustained rate and the capacity of the bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limit {
+    pub requests_per_second: u32,
+    pub burst: u32,
+}
+
+/// Token bucket, the time is measured in milliseconds since the Unix epoch.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: u64,
+}
+
+impl Bucket {
+    fn full(limit: Limit, now: u64) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            updated_at: now,
+        }
+    }
+
+    /// Takes a token from the bucket, returns the time to wait for the next one if it is empty.
+    fn try_acquire(&mut self, limit: Limit, now: u64) -> Option<Duration> {
+        let elapsed = now.saturating_sub(self.updated_at) as f64;
+        let refill = elapsed * limit.requests_per_second as f64 / 1000.0;
+        self.tokens = (self.tokens + refill).min(limit.burst as f64);
+        self.updated_at = now.max(self.updated_at);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            let wait_ms = (1.0 - self.tokens) * 1000.0 / limit.requests_per_second as f64;
+            Some(Duration::from_millis(wait_ms.ceil() as u64))
+        }
+    }
+}
+
+/// Storage of the token buckets.
+#[async_trait]
+pub trait Buckets: Send + Sync {
+    /// Takes a token from the bucket stored by `key`,
+    /// returns the time to wait for the next token if there is none.
+    async fn try_acquire(&self, key: &str, limit: Limit) -> anyhow::Result<Option<Duration>>;
+}
+
+/// Buckets kept in memory of the API server.
+#[derive(Debug)]
+pub struct MemoryBuckets(Mutex<LruCache<String, Bucket>>);
+
+impl MemoryBuckets {
+    pub fn new(capacity: usize) -> Self {
+        Self(Mutex::new(LruCache::new(capacity)))
+    }
+}
+
+#[async_trait]
+impl Buckets for MemoryBuckets {
+    async fn try_acquire(&self, key: &str, limit: Limit) -> anyhow::Result<Option<Duration>> {
+        let now = now_millis();
+        let mut buckets = self.0.lock().unwrap();
+        if let Some(bucket) = buckets.get_mut(key) {
+            return Ok(bucket.try_acquire(limit, now));
+        }
+        let mut bucket = Bucket::full(limit, now);
+        let retry_after = bucket.try_acquire(limit, now);
+        buckets.insert(key.to_owned(), bucket);
+        Ok(retry_after)
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Incorrect system time")
+        .as_millis() as u64
+}
+
+/// Rate limiting middleware, shared by all the server workers.
+#[derive(Clone)]
+pub struct RateLimiter {
+    /// `None` if the rate limiting is disabled.
+    buckets: Option<Arc<dyn Buckets>>,
+    read: Limit,
+    submit: Limit,
+    /// Peers which are allowed to set the `CF-Connecting-IP` header.
+    trusted_proxies: Arc<Vec<IpAddr>>,
+}
+
+impl RateLimiter {
+    pub async fn new(config: &RateLimitConfig) -> anyhow::Result<Self> {
+        let buckets: Option<Arc<dyn Buckets>> = match (config.enabled, config.backend) {
+            (false, _) => None,
+            (true, RateLimitBackend::Memory) => {
+                Some(Arc::new(MemoryBuckets::new(MEMORY_BUCKETS_CAPACITY)))
+            }
+            (true, RateLimitBackend::Redis) => {
+                Some(Arc::new(RedisBuckets::new(&config.redis_url).await?))
+            }
+        };
+        Ok(Self {
+            buckets,
+            read: Limit {
+                requests_per_second: config.read_requests_per_second,
+                burst: config.read_burst,
+            },
+            submit: Limit {
+                requests_per_second: config.submit_requests_per_second,
+                burst: config.submit_burst,
+            },
+            trusted_proxies: Arc::new(config.trusted_proxies()),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.buckets.is_some()
+    }
+
+    fn client_ip(&self, peer: Option<IpAddr>, forwarded_ip: Option<&str>) -> Option<IpAddr> {
+        client_ip(&self.trusted_proxies, peer, forwarded_ip)
+    }
+
+    /// Returns the IP of the client of the JSON-RPC servers, which do not expose the address
+    /// of the peer. The header is only trusted if the proxies are configured, the servers must
+    /// not be reachable by the clients directly then.
+    pub(crate) fn rpc_client_ip(&self, forwarded_ip: Option<&str>) -> Option<IpAddr> {
+        if self.trusted_proxies.is_empty() {
+            return None;
+        }
+        forwarded_ip.and_then(|ip| ip.trim().parse().ok())
+    }
+
+    fn limit(&self, group: RouteGroup) -> Limit {
+        match group {
+            RouteGroup::Read => self.read,
+            RouteGroup::Submit => self.submit,
+        }
+    }
+
+    /// Takes a token from the bucket of the client identified by `client`,
+    /// returns the time to wait for the next token if the request is rejected.
+    pub(crate) async fn acquire(&self, client: &str, group: RouteGroup) -> Result<(), Duration> {
+        let buckets = match &self.buckets {
+            Some(buckets) => buckets,
+            None => return Ok(()),
+        };
+
+        let key = format!("rate_limit:{}:{}", group.as_str(), client);
+        let retry_after = match buckets.try_acquire(&key, self.limit(group)).await {
+            Ok(retry_after) => retry_after,
+            Err(err) => {
+                // The API stays available if the storage of the buckets is down.
+                vlog::warn!("Unable to check the rate limit: {}", err);
+                metrics::increment_counter!("api.rate_limit.errors");
+                return Ok(());
+            }
+        };
+
+        match retry_after {
+            None => Ok(()),
+            Some(retry_after) => {
+                metrics::increment_counter!("api.rate_limit.rejected", "group" => group.as_str());
+                Err(retry_after)
+            }
+        }
+    }
+
+    async fn check(&self, ip: Option<IpAddr>, group: RouteGroup) -> actix_web::Result<()> {
+        // Requests without the known IP are not limited, it only happens
+        // for the requests made through the Unix socket.
+        let ip = match ip {
+            Some(ip) => ip,
+            None => return Ok(()),
+        };
+        self.acquire(&ip.to_string(), group)
+            .await
+            .map_err(too_many_requests)
+    }
+}
+
+/// Returns the IP of the client: the value of the `CF-Connecting-IP` header if the request
+/// is made by one of the trusted proxies, the address of the peer otherwise.
+fn client_ip(
+    trusted_proxies: &[IpAddr],
+    peer: Option<IpAddr>,
+    forwarded_ip: Option<&str>,
+) -> Option<IpAddr> {
+    match peer {
+        Some(peer) if trusted_proxies.contains(&peer) => {
+            forwarded_ip.and_then(|ip| ip.trim().parse().ok())
+        }
+        peer => peer,
+    }
+}
+
+/// Returns the IP of the client making the REST API request, see `client_ip`.
+pub(crate) fn request_client_ip(
+    trusted_proxies: &[IpAddr],
+    request: &HttpRequest,
+) -> Option<IpAddr> {
+    let forwarded_ip = request
+        .headers()
+        .get(CLOUDFLARE_CONNECTING_IP_HEADER)
+        .and_then(|value| value.to_str().ok());
+    client_ip(
+        trusted_proxies,
+        request.peer_addr().map(|addr| addr.ip()),
+        forwarded_ip,
+    )
+}
+
+/// `Retry-After` is measured in whole seconds.
+pub(crate) fn retry_after_secs(retry_after: Duration) -> u64 {
+    ((retry_after.as_millis() as u64 + 999) / 1000).max(1)
+}
+
+fn too_many_requests(retry_after: Duration) -> actix_web::Error {
+    let response = HttpResponse::TooManyRequests()
+        .insert_header(("Retry-After", retry_after_secs(retry_after).to_string()))
+        .body("Rate limit exceeded");
+    InternalError::from_response("Rate limit exceeded", response).into()
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimiter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = RateLimiterMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimiterMiddleware {
+            service: Rc::new(service),
+            rate_limiter: self.clone(),
+        }))
+    }
+}
+
+pub struct RateLimiterMiddleware<S> {
+    service: Rc<S>,
+    rate_limiter: RateLimiter,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimiterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let rate_limiter = self.rate_limiter.clone();
+        let group = RouteGroup::of(req.method(), req.path());
+        let ip = request_client_ip(&rate_limiter.trusted_proxies, req.request());
+
+        Box::pin(async move {
+            rate_limiter.check(ip, group).await?;
+            service.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: Limit = Limit {
+        requests_per_second: 2,
+        burst: 3,
+    };
+
+    #[test]
+    fn token_bucket() {
+        let now = 1_000_000;
+        let mut bucket = Bucket::full(LIMIT, now);
+
+        for _ in 0..3 {
+            assert_eq!(bucket.try_acquire(LIMIT, now), None);
+        }
+        // The bucket is empty, the next token is added in 500 ms.
+        assert_eq!(
+            bucket.try_acquire(LIMIT, now),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            bucket.try_acquire(LIMIT, now + 200),
+            Some(Duration::from_millis(300))
+        );
+        assert_eq!(bucket.try_acquire(LIMIT, now + 500), None);
+        // The bucket is never refilled above the burst.
+        for _ in 0..3 {
+            assert_eq!(bucket.try_acquire(LIMIT, now + 60_000), None);
+        }
+        assert!(bucket.try_acquire(LIMIT, now + 60_000).is_some());
+    }
+
+    #[tokio::test]
+    async fn memory_buckets() {
+        let buckets = MemoryBuckets::new(10);
+        for _ in 0..3 {
+            assert_eq!(buckets.try_acquire("a", LIMIT).await.unwrap(), None);
+        }
+        assert!(buckets.try_acquire("a", LIMIT).await.unwrap().is_some());
+        // Buckets of the other keys are independent.
+        assert_eq!(buckets.try_acquire("b", LIMIT).await.unwrap(), None);
+    }
+
+    #[test]
+    fn client_ip_is_forwarded_only_by_trusted_proxies() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let client: IpAddr = "100.100.100.100".parse().unwrap();
+        let other: IpAddr = "200.200.200.200".parse().unwrap();
+        let rate_limiter = RateLimiter {
+            buckets: None,
+            read: LIMIT,
+            submit: LIMIT,
+            trusted_proxies: Arc::new(vec![proxy]),
+        };
+
+        assert_eq!(
+            rate_limiter.client_ip(Some(proxy), Some("100.100.100.100")),
+            Some(client)
+        );
+        // The header set by the client itself is ignored.
+        assert_eq!(
+            rate_limiter.client_ip(Some(other), Some("100.100.100.100")),
+            Some(other)
+        );
+        assert_eq!(rate_limiter.client_ip(Some(other), None), Some(other));
+        assert_eq!(rate_limiter.client_ip(Some(proxy), Some("garbage")), None);
+        assert_eq!(
+            rate_limiter.rpc_client_ip(Some("100.100.100.100")),
+            Some(client)
+        );
+
+        let rate_limiter = RateLimiter {
+            trusted_proxies: Arc::new(Vec::new()),
+            ..rate_limiter
+        };
+        assert_eq!(rate_limiter.rpc_client_ip(Some("100.100.100.100")), None);
+    }
+
+    #[test]
+    fn route_groups() {
+        assert_eq!(
+            RouteGroup::of(&Method::POST, "/api/v0.2/transactions"),
+            RouteGroup::Submit
+        );
+        assert_eq!(
+            RouteGroup::of(&Method::POST, "/api/v0.2/transactions/batches"),
+            RouteGroup::Submit
+        );
+        assert_eq!(
+            RouteGroup::of(&Method::POST, "/api/v0.2/accounts/1/exit_proof/ETH"),
+            RouteGroup::Submit
+        );
+        assert_eq!(
+            RouteGroup::of(&Method::POST, "/api/v0.2/fee/batch"),
+            RouteGroup::Read
+        );
+        assert_eq!(
+            RouteGroup::of(&Method::GET, "/api/v0.2/transactions/0x00"),
+            RouteGroup::Read
+        );
+    }
+}
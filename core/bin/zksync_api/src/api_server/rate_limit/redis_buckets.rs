@@ -0,0 +1,70 @@
+//! Token buckets stored in Redis, shared by all the API server replicas.
+
+// Built-in uses
+use std::time::Duration;
+// External uses
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, Script};
+// Local uses
+use super::{now_millis, Buckets, Limit};
+
+/// Same algorithm as `Bucket::try_acquire`, executed atomically by Redis.
+/// Returns the time to wait for the next token in milliseconds, `0` if the token is taken.
+/// Buckets expire once they are refilled completely, so the idle clients do not occupy the memory.
+const TRY_ACQUIRE_SCRIPT: &str = r#"
+local rate = tonumber(ARGV[1])
+local burst = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'updated_at')
+local tokens = tonumber(bucket[1]) or burst
+local updated_at = tonumber(bucket[2]) or now
+if now > updated_at then
+    tokens = math.min(burst, tokens + (now - updated_at) * rate / 1000)
+    updated_at = now
+end
+local wait = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+else
+    wait = math.ceil((1 - tokens) * 1000 / rate)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated_at', updated_at)
+redis.call('PEXPIRE', KEYS[1], math.ceil(burst * 1000 / rate))
+return wait
+"#;
+
+pub struct RedisBuckets {
+    connection: ConnectionManager,
+    script: Script,
+}
+
+impl RedisBuckets {
+    pub async fn new(url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = ConnectionManager::new(client).await?;
+        Ok(Self {
+            connection,
+            script: Script::new(TRY_ACQUIRE_SCRIPT),
+        })
+    }
+}
+
+#[async_trait]
+impl Buckets for RedisBuckets {
+    async fn try_acquire(&self, key: &str, limit: Limit) -> anyhow::Result<Option<Duration>> {
+        // Time of the API server is used, so the clocks of the replicas should be synchronized.
+        let wait_ms: u64 = self
+            .script
+            .key(key)
+            .arg(limit.requests_per_second)
+            .arg(limit.burst)
+            .arg(now_millis())
+            .invoke_async(&mut self.connection.clone())
+            .await?;
+        if wait_ms == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(Duration::from_millis(wait_ms)))
+        }
+    }
+}
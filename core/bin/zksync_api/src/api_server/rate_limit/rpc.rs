@@ -0,0 +1,131 @@
+//! Rate limiting of the JSON-RPC calls, shared by the HTTP and WebSocket servers.
+//!
+//! Neither server exposes the address of the peer: the HTTP server identifies the client by
+//! the `CF-Connecting-IP` header (only if the trusted proxies are configured), and the
+//! WebSocket server limits every connection separately.
+
+// Built-in uses
+use std::{net::IpAddr, sync::Arc};
+// External uses
+use futures::future::{BoxFuture, Either};
+use jsonrpc_core::{middleware::Middleware, Call, Error, ErrorCode, Metadata, Output, Response};
+use jsonrpc_pubsub::Session;
+use serde_json::json;
+
+// Local uses
+use super::{retry_after_secs, RateLimiter, RouteGroup, CLOUDFLARE_CONNECTING_IP_HEADER};
+use crate::api_server::rpc_server::error::RpcErrorCodes;
+
+/// JSON-RPC methods submitting the signed data.
+const SUBMIT_METHODS: &[&str] = &["tx_submit", "submit_txs_batch", "toggle_2fa"];
+
+/// Metadata of the JSON-RPC request identifying the client.
+pub trait RateLimitedMeta: Metadata {
+    /// Key of the client buckets, the requests are not limited if it's unknown.
+    fn client_key(&self) -> Option<String>;
+}
+
+/// Metadata of the request to the JSON-RPC HTTP server.
+#[derive(Debug, Clone, Default)]
+pub struct RpcRequestMeta {
+    pub ip: Option<IpAddr>,
+}
+
+impl Metadata for RpcRequestMeta {}
+
+impl RpcRequestMeta {
+    pub fn extract(rate_limiter: &RateLimiter, request: &hyper::Request<hyper::Body>) -> Self {
+        let forwarded_ip = request
+            .headers()
+            .get(CLOUDFLARE_CONNECTING_IP_HEADER)
+            .and_then(|value| value.to_str().ok());
+        Self {
+            ip: rate_limiter.rpc_client_ip(forwarded_ip),
+        }
+    }
+}
+
+impl RateLimitedMeta for RpcRequestMeta {
+    fn client_key(&self) -> Option<String> {
+        self.ip.map(|ip| ip.to_string())
+    }
+}
+
+impl RateLimitedMeta for Arc<Session> {
+    fn client_key(&self) -> Option<String> {
+        // The session lives as long as the connection.
+        Some(format!("ws:{:p}", Arc::as_ptr(self)))
+    }
+}
+
+fn route_group(method: &str) -> RouteGroup {
+    if SUBMIT_METHODS.contains(&method) {
+        RouteGroup::Submit
+    } else {
+        RouteGroup::Read
+    }
+}
+
+fn rate_limit_error(retry_after_secs: u64) -> Error {
+    Error {
+        code: ErrorCode::ServerError(RpcErrorCodes::RateLimitExceeded as i64),
+        message: "Rate limit exceeded".to_string(),
+        data: Some(json!({ "retryAfterSecs": retry_after_secs })),
+    }
+}
+
+/// Middleware taking a token for every method call of the request, batches included.
+#[derive(Clone)]
+pub struct RpcRateLimiter(pub RateLimiter);
+
+impl<M: RateLimitedMeta> Middleware<M> for RpcRateLimiter {
+    type Future = BoxFuture<'static, Option<Response>>;
+    type CallFuture = BoxFuture<'static, Option<Output>>;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: std::future::Future<Output = Option<Output>> + Send + 'static,
+    {
+        let (group, id, jsonrpc) = match &call {
+            // Subscriptions are handled as soon as they are called, so they are not limited.
+            Call::MethodCall(call)
+                if self.0.is_enabled() && !call.method.ends_with("subscribe") =>
+            {
+                (route_group(&call.method), call.id.clone(), call.jsonrpc)
+            }
+            _ => return Either::Right(next(call, meta)),
+        };
+        let client = match meta.client_key() {
+            Some(client) => client,
+            None => return Either::Right(next(call, meta)),
+        };
+
+        // Handlers of the methods are lazy, nothing is done until the future is polled.
+        let response = next(call, meta);
+        let rate_limiter = self.0.clone();
+        Either::Left(Box::pin(async move {
+            match rate_limiter.acquire(&client, group).await {
+                Ok(()) => response.await,
+                Err(retry_after) => Some(Output::from(
+                    Err(rate_limit_error(retry_after_secs(retry_after))),
+                    id,
+                    jsonrpc,
+                )),
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rpc_route_groups() {
+        assert_eq!(route_group("tx_submit"), RouteGroup::Submit);
+        assert_eq!(route_group("submit_txs_batch"), RouteGroup::Submit);
+        assert_eq!(route_group("get_tx_fee"), RouteGroup::Read);
+        assert_eq!(route_group("account_info"), RouteGroup::Read);
+    }
+}
@@ -0,0 +1,180 @@
+//! Admin endpoints to manage the API keys.
+//!
+//! The endpoints are served on `AdminApiConfig::port` rather than by the public REST API server.
+//! Requests must be authorized with the JWT signed by `AdminApiConfig::secret_auth`.
+
+// Built-in uses
+use std::{convert::TryFrom, num::TryFromIntError, time::Instant};
+
+// External uses
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+    web, HttpResponse, Scope,
+};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_crypto::rand::{thread_rng, Rng};
+use zksync_storage::{api_keys::records::StoredApiKey, ConnectionPool};
+
+// Local uses
+use super::{api_key_hash, ApiKeys};
+use crate::api_server::rest::helpers::authorize_admin;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NewApiKey {
+    name: String,
+    requests_per_second: u32,
+    requests_per_day: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiKeyInfo {
+    id: i64,
+    name: String,
+    requests_per_second: u32,
+    requests_per_day: u64,
+    created_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+    /// The key itself, only returned once the key is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+}
+
+impl TryFrom<StoredApiKey> for ApiKeyInfo {
+    type Error = TryFromIntError;
+
+    fn try_from(api_key: StoredApiKey) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: api_key.id,
+            name: api_key.name,
+            requests_per_second: u32::try_from(api_key.requests_per_second)?,
+            requests_per_day: u64::try_from(api_key.requests_per_day)?,
+            created_at: api_key.created_at,
+            revoked_at: api_key.revoked_at,
+            key: None,
+        })
+    }
+}
+
+/// Shared data between `api/admin/api_keys` endpoints.
+#[derive(Debug, Clone)]
+struct ApiKeysAdminData {
+    pool: ConnectionPool,
+    api_keys: ApiKeys,
+    secret_auth: String,
+}
+
+impl ApiKeysAdminData {
+    fn authorize(&self, credentials: &BearerAuth) -> actix_web::Result<()> {
+        authorize_admin(&self.secret_auth, credentials)
+    }
+}
+
+async fn create_key(
+    data: web::Data<ApiKeysAdminData>,
+    credentials: BearerAuth,
+    new_key: web::Json<NewApiKey>,
+) -> actix_web::Result<HttpResponse> {
+    let start = Instant::now();
+    data.authorize(&credentials)?;
+    // Quotas are stored as the signed integers.
+    if i32::try_from(new_key.requests_per_second).is_err()
+        || i64::try_from(new_key.requests_per_day).is_err()
+    {
+        return Err(ErrorBadRequest("Quota of the API key is too big"));
+    }
+
+    let key = hex::encode(thread_rng().gen::<[u8; 32]>());
+    let stored = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ErrorInternalServerError)?
+        .api_keys_schema()
+        .store_api_key(
+            &api_key_hash(&key),
+            &new_key.name,
+            new_key.requests_per_second,
+            new_key.requests_per_day,
+        )
+        .await
+        .map_err(ErrorInternalServerError)?;
+    // Unknown keys are cached as well.
+    data.api_keys.forget(&stored.key_hash);
+    vlog::info!("API key {} '{}' is created", stored.id, stored.name);
+
+    let mut info = ApiKeyInfo::try_from(stored).map_err(ErrorInternalServerError)?;
+    info.key = Some(key);
+    metrics::histogram!("api.admin.api_keys", start.elapsed(), "endpoint_name" => "create_key");
+    Ok(HttpResponse::Ok().json(info))
+}
+
+async fn list_keys(
+    data: web::Data<ApiKeysAdminData>,
+    credentials: BearerAuth,
+) -> actix_web::Result<HttpResponse> {
+    let start = Instant::now();
+    data.authorize(&credentials)?;
+
+    let keys = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ErrorInternalServerError)?
+        .api_keys_schema()
+        .load_api_keys()
+        .await
+        .map_err(ErrorInternalServerError)?
+        .into_iter()
+        .map(ApiKeyInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ErrorInternalServerError)?;
+
+    metrics::histogram!("api.admin.api_keys", start.elapsed(), "endpoint_name" => "list_keys");
+    Ok(HttpResponse::Ok().json(keys))
+}
+
+async fn revoke_key(
+    data: web::Data<ApiKeysAdminData>,
+    credentials: BearerAuth,
+    id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    let start = Instant::now();
+    data.authorize(&credentials)?;
+    let id = id.into_inner();
+
+    let key_hash = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ErrorInternalServerError)?
+        .api_keys_schema()
+        .revoke_api_key(id)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or_else(|| ErrorNotFound("API key does not exist or is already revoked"))?;
+    data.api_keys.forget(&key_hash);
+    vlog::info!("API key {} is revoked", id);
+
+    metrics::histogram!("api.admin.api_keys", start.elapsed(), "endpoint_name" => "revoke_key");
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn api_scope(pool: ConnectionPool, api_keys: ApiKeys, secret_auth: String) -> Scope {
+    let data = ApiKeysAdminData {
+        pool,
+        api_keys,
+        secret_auth,
+    };
+
+    web::scope("/api/admin/api_keys")
+        .app_data(web::Data::new(data))
+        .route("", web::get().to(list_keys))
+        .route("", web::post().to(create_key))
+        .route("{id}/revoke", web::post().to(revoke_key))
+}
@@ -0,0 +1,355 @@
+//! API keys and the per-key request quotas.
+//!
+//! Requests with the `X-API-Key` header are checked against the keys stored in the database.
+//! Only the keccak256 hashes of the keys are stored, the key itself is shown once on creation.
+//! Validated keys are cached for `ApiKeysConfig::cache_ttl`, so the revoked key may remain
+//! valid on other API servers until the cached entry expires. Unknown keys are cached separately
+//! for `ApiKeysConfig::negative_cache_ttl`, so the random keys can't evict the valid ones, and
+//! the keys which can't be generated by the admin API are rejected without the database lookup.
+//!
+//! Request counters are kept in memory, so the quotas are enforced per API server instance.
+
+// Built-in uses
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    num::TryFromIntError,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+// External uses
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::{ErrorInternalServerError, ErrorTooManyRequests, ErrorUnauthorized},
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+
+// Workspace uses
+use zksync_config::configs::api::ApiKeysConfig;
+use zksync_storage::{api_keys::records::StoredApiKey, ConnectionPool};
+
+// Local uses
+use crate::utils::shared_lru_cache::SharedLruCache;
+
+pub mod admin;
+
+/// Header with the API key.
+pub const API_KEY_HEADER: &str = "X-API-Key";
+/// The maximum amount of the cached valid keys.
+const KEYS_CACHE_CAPACITY: usize = 10_000;
+/// The maximum amount of the cached unknown or revoked keys.
+const UNKNOWN_KEYS_CACHE_CAPACITY: usize = 100_000;
+/// Length of the hex encoded 32-byte key.
+const API_KEY_LENGTH: usize = 64;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Returns the hash the API key is stored by.
+pub fn api_key_hash(api_key: &str) -> Vec<u8> {
+    tiny_keccak::keccak256(api_key.as_bytes()).to_vec()
+}
+
+/// Returns `true` if the key has the format of the keys generated by the admin API.
+fn is_well_formed(api_key: &str) -> bool {
+    api_key.len() == API_KEY_LENGTH && api_key.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Quota {
+    requests_per_second: u32,
+    requests_per_day: u64,
+}
+
+impl TryFrom<&StoredApiKey> for Quota {
+    type Error = TryFromIntError;
+
+    fn try_from(api_key: &StoredApiKey) -> Result<Self, Self::Error> {
+        Ok(Self {
+            requests_per_second: u32::try_from(api_key.requests_per_second)?,
+            requests_per_day: u64::try_from(api_key.requests_per_day)?,
+        })
+    }
+}
+
+/// Requests made with the key in the current second and the current day.
+#[derive(Debug, Default)]
+struct KeyUsage {
+    second: u64,
+    second_requests: u32,
+    day: u64,
+    day_requests: u64,
+}
+
+impl KeyUsage {
+    /// Registers the request made at `now` (seconds since the Unix epoch),
+    /// returns `false` if the quota is exhausted.
+    fn try_acquire(&mut self, quota: Quota, now: u64) -> bool {
+        if self.second != now {
+            self.second = now;
+            self.second_requests = 0;
+        }
+        let day = now / SECONDS_PER_DAY;
+        if self.day != day {
+            self.day = day;
+            self.day_requests = 0;
+        }
+
+        if self.second_requests >= quota.requests_per_second
+            || self.day_requests >= quota.requests_per_day
+        {
+            return false;
+        }
+        self.second_requests += 1;
+        self.day_requests += 1;
+        true
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedKey {
+    id: i64,
+    quota: Quota,
+    loaded_at: Instant,
+}
+
+/// Middleware checking the API keys, shared by all the server workers.
+#[derive(Debug, Clone)]
+pub struct ApiKeys {
+    pool: ConnectionPool,
+    config: ApiKeysConfig,
+    keys: SharedLruCache<Vec<u8>, CachedKey>,
+    /// Hashes of the unknown or revoked keys and the time they're looked up at.
+    unknown_keys: SharedLruCache<Vec<u8>, Instant>,
+    usage: Arc<Mutex<HashMap<i64, KeyUsage>>>,
+}
+
+impl ApiKeys {
+    pub fn new(pool: ConnectionPool, config: ApiKeysConfig) -> Self {
+        Self {
+            pool,
+            config,
+            keys: SharedLruCache::new(KEYS_CACHE_CAPACITY),
+            unknown_keys: SharedLruCache::new(UNKNOWN_KEYS_CACHE_CAPACITY),
+            usage: Arc::default(),
+        }
+    }
+
+    /// Drops the cached key, so the changes made by this server take effect immediately.
+    pub fn forget(&self, key_hash: &[u8]) {
+        self.keys.remove(&key_hash.to_vec());
+        self.unknown_keys.remove(&key_hash.to_vec());
+    }
+
+    async fn load_key(&self, key_hash: Vec<u8>) -> actix_web::Result<Option<(i64, Quota)>> {
+        if let Some(cached) = self.keys.get(&key_hash) {
+            if cached.loaded_at.elapsed() < self.config.cache_ttl() {
+                return Ok(Some((cached.id, cached.quota)));
+            }
+        }
+        if let Some(looked_up_at) = self.unknown_keys.get(&key_hash) {
+            if looked_up_at.elapsed() < self.config.negative_cache_ttl() {
+                return Ok(None);
+            }
+        }
+
+        let key = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(ErrorInternalServerError)?
+            .api_keys_schema()
+            .get_active_api_key(&key_hash)
+            .await
+            .map_err(ErrorInternalServerError)?
+            .map(|api_key| Quota::try_from(&api_key).map(|quota| (api_key.id, quota)))
+            .transpose()
+            .map_err(ErrorInternalServerError)?;
+        match key {
+            Some((id, quota)) => {
+                self.unknown_keys.remove(&key_hash);
+                self.keys.insert(
+                    key_hash,
+                    CachedKey {
+                        id,
+                        quota,
+                        loaded_at: Instant::now(),
+                    },
+                );
+            }
+            None => {
+                // The key may have been revoked since it's cached.
+                self.keys.remove(&key_hash);
+                self.unknown_keys.insert(key_hash, Instant::now());
+            }
+        }
+        Ok(key)
+    }
+
+    async fn check(&self, api_key: Option<String>) -> actix_web::Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let api_key = match api_key {
+            Some(api_key) => api_key,
+            None if self.config.require_key => {
+                metrics::increment_counter!("api.keys.rejected", "reason" => "missing");
+                return Err(ErrorUnauthorized("API key is required"));
+            }
+            None => return Ok(()),
+        };
+        if !is_well_formed(&api_key) {
+            metrics::increment_counter!("api.keys.rejected", "reason" => "malformed");
+            return Err(ErrorUnauthorized("Invalid API key"));
+        }
+
+        let (id, quota) = match self.load_key(api_key_hash(&api_key)).await? {
+            Some(key) => key,
+            None => {
+                metrics::increment_counter!("api.keys.rejected", "reason" => "invalid");
+                return Err(ErrorUnauthorized("Invalid API key"));
+            }
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Incorrect system time")
+            .as_secs();
+        let acquired = self
+            .usage
+            .lock()
+            .unwrap()
+            .entry(id)
+            .or_default()
+            .try_acquire(quota, now);
+        if !acquired {
+            metrics::increment_counter!("api.keys.rejected", "reason" => "quota");
+            return Err(ErrorTooManyRequests("API key quota exceeded"));
+        }
+        Ok(())
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiKeys
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = ApiKeysMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiKeysMiddleware {
+            service: Rc::new(service),
+            api_keys: self.clone(),
+        }))
+    }
+}
+
+pub struct ApiKeysMiddleware<S> {
+    service: Rc<S>,
+    api_keys: ApiKeys,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiKeysMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let api_keys = self.api_keys.clone();
+        let api_key = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .map(|value| value.to_str().map(ToOwned::to_owned));
+
+        Box::pin(async move {
+            let api_key = api_key
+                .transpose()
+                .map_err(|_| ErrorUnauthorized("Invalid API key"))?;
+            api_keys.check(api_key).await?;
+            service.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_usage_quota() {
+        let quota = Quota {
+            requests_per_second: 2,
+            requests_per_day: 3,
+        };
+        let mut usage = KeyUsage::default();
+        let now = 10 * SECONDS_PER_DAY;
+
+        assert!(usage.try_acquire(quota, now));
+        assert!(usage.try_acquire(quota, now));
+        // Per second quota is exhausted.
+        assert!(!usage.try_acquire(quota, now));
+        assert!(usage.try_acquire(quota, now + 1));
+        // Per day quota is exhausted.
+        assert!(!usage.try_acquire(quota, now + 2));
+        // Both counters are reset on the next day.
+        assert!(usage.try_acquire(quota, now + SECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn quota_of_stored_key() {
+        let mut api_key = StoredApiKey {
+            id: 1,
+            key_hash: api_key_hash("key"),
+            name: "partner".to_owned(),
+            requests_per_second: 10,
+            requests_per_day: 1000,
+            created_at: chrono::Utc::now(),
+            revoked_at: None,
+        };
+        assert_eq!(
+            Quota::try_from(&api_key).unwrap(),
+            Quota {
+                requests_per_second: 10,
+                requests_per_day: 1000,
+            }
+        );
+
+        // Negative quotas are rejected rather than wrapped around.
+        api_key.requests_per_second = -1;
+        assert!(Quota::try_from(&api_key).is_err());
+        api_key.requests_per_second = 10;
+        api_key.requests_per_day = -1;
+        assert!(Quota::try_from(&api_key).is_err());
+    }
+
+    #[test]
+    fn well_formed_keys() {
+        assert!(is_well_formed(&hex::encode([0xab; 32])));
+        assert!(is_well_formed(&"AB".repeat(32)));
+        assert!(!is_well_formed(&hex::encode([0xab; 31])));
+        assert!(!is_well_formed(&hex::encode([0xab; 33])));
+        assert!(!is_well_formed(&"zz".repeat(32)));
+        assert!(!is_well_formed(""));
+    }
+
+    #[test]
+    fn api_key_hashing() {
+        let hash = api_key_hash("key");
+        assert_eq!(hash.len(), 32);
+        assert_eq!(hash, api_key_hash("key"));
+        assert_ne!(hash, api_key_hash("another_key"));
+    }
+}
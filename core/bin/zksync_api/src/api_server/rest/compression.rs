@@ -0,0 +1,152 @@
+//! Compression of the REST API responses.
+//!
+//! Responses are compressed with gzip or brotli (depending on the `Accept-Encoding` header
+//! of the request) by the actix `Compress` middleware. The filter below runs before it and
+//! marks the small responses, the server-sent event streams and the responses of the excluded
+//! routes with the `Content-Encoding: identity` header, so `Compress` leaves them as is:
+//! compressing the small payloads costs more CPU time than it saves traffic, and the encoder
+//! buffers the events of a stream, delaying them until the buffer is flushed.
+
+// Built-in uses
+use std::rc::Rc;
+
+// External uses
+use actix_web::{
+    body::{BodySize, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{self, HeaderValue},
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+
+// Workspace uses
+use zksync_config::configs::api::RestApiConfig;
+
+/// Middleware disabling the compression of the small responses, the event streams
+/// and the excluded routes.
+#[derive(Debug, Clone)]
+pub struct CompressionFilter {
+    min_size: u64,
+    excluded_routes: Rc<Vec<String>>,
+}
+
+impl CompressionFilter {
+    pub fn new(config: &RestApiConfig) -> Self {
+        Self {
+            min_size: config.compression_min_size,
+            excluded_routes: Rc::new(config.compression_excluded_routes.clone()),
+        }
+    }
+
+    fn is_excluded(&self, path: &str) -> bool {
+        self.excluded_routes
+            .iter()
+            .any(|route| path.starts_with(route.as_str()))
+    }
+
+    /// Streamed responses are compressed since their size is unknown in advance,
+    /// unless they are event streams (see `is_event_stream`).
+    fn is_too_small(&self, body_size: BodySize) -> bool {
+        match body_size {
+            BodySize::None => true,
+            BodySize::Sized(size) => size < self.min_size,
+            BodySize::Stream => false,
+        }
+    }
+
+    fn is_event_stream(content_type: Option<&HeaderValue>) -> bool {
+        content_type
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.starts_with("text/event-stream"))
+            .unwrap_or(false)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CompressionFilter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = CompressionFilterMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CompressionFilterMiddleware {
+            service: Rc::new(service),
+            filter: self.clone(),
+        }))
+    }
+}
+
+pub struct CompressionFilterMiddleware<S> {
+    service: Rc<S>,
+    filter: CompressionFilter,
+}
+
+impl<S, B> Service<ServiceRequest> for CompressionFilterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let filter = self.filter.clone();
+        let excluded = filter.is_excluded(req.path());
+
+        Box::pin(async move {
+            let mut response = service.call(req).await?;
+            if excluded
+                || filter.is_too_small(response.response().body().size())
+                || CompressionFilter::is_event_stream(response.headers().get(header::CONTENT_TYPE))
+            {
+                response.headers_mut().insert(
+                    header::CONTENT_ENCODING,
+                    HeaderValue::from_static("identity"),
+                );
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compression_filter() {
+        let filter = CompressionFilter {
+            min_size: 1024,
+            excluded_routes: Rc::new(vec!["/api/v0.2/networkStatus".to_owned()]),
+        };
+
+        assert!(filter.is_excluded("/api/v0.2/networkStatus"));
+        assert!(!filter.is_excluded("/api/v0.2/blocks/lastCommitted/transactions"));
+
+        assert!(filter.is_too_small(BodySize::None));
+        assert!(filter.is_too_small(BodySize::Sized(1023)));
+        assert!(!filter.is_too_small(BodySize::Sized(1024)));
+        assert!(!filter.is_too_small(BodySize::Stream));
+
+        assert!(CompressionFilter::is_event_stream(Some(
+            &HeaderValue::from_static("text/event-stream")
+        )));
+        assert!(CompressionFilter::is_event_stream(Some(
+            &HeaderValue::from_static("text/event-stream; charset=utf-8")
+        )));
+        assert!(!CompressionFilter::is_event_stream(Some(
+            &HeaderValue::from_static("application/json")
+        )));
+        assert!(!CompressionFilter::is_event_stream(None));
+    }
+}
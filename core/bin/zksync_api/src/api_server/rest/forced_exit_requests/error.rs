@@ -99,12 +99,17 @@ impl SumbitErrorCode {
             SubmitError::InvalidParams(_) => Self::InvalidParams,
             SubmitError::UnsupportedFastProcessing => Self::UnsupportedFastProcessing,
             SubmitError::IncorrectTx(_) => Self::IncorrectTx,
-            SubmitError::TxAdd(_) => Self::TxAdd,
+            SubmitError::TxAdd(_) | SubmitError::BatchSigners(_) => Self::TxAdd,
             SubmitError::InappropriateFeeToken => Self::InappropriateFeeToken,
+            SubmitError::TokenDisabled(_) => Self::Other,
             SubmitError::MempoolCommunication(_) => Self::CommunicationCoreServer,
+            SubmitError::Timeout | SubmitError::ServiceUnavailable(_) => {
+                Self::CommunicationCoreServer
+            }
             SubmitError::Internal(_) => Self::Internal,
             SubmitError::Other(_) => Self::Other,
             SubmitError::Toggle2FA(_) => Self::Other,
+            SubmitError::AccountRecovery(_) => Self::Other,
             SubmitError::PriceError(_) => Self::Other,
         }
     }
@@ -0,0 +1,239 @@
+//! Liveness and readiness probes of the REST API server.
+//!
+//! `/healthz` only reports that the process serves the requests. `/readyz` also checks the
+//! dependencies required to serve them: the database, the core server, the Ethereum node and the
+//! mempool requests handler. The replica which is not ready (e.g. has the exhausted connection pool)
+//! responds with `503 Service Unavailable`, so the load balancer stops routing traffic to it.
+//!
+//! The core server and the Ethereum node are shared by all the replicas, so their outage is only
+//! reported as degraded: taking every replica out of the rotation wouldn't help, and the requests
+//! not depending on them (e.g. the historical data) can still be served.
+
+// Built-in uses
+use std::time::Duration;
+
+// External uses
+use actix_web::{web, HttpResponse, Resource};
+use futures::{
+    channel::{mpsc, oneshot},
+    Future, SinkExt,
+};
+use serde::Serialize;
+
+// Workspace uses
+use zksync_eth_client::EthereumGateway;
+use zksync_mempool::MempoolTransactionRequest;
+use zksync_storage::ConnectionPool;
+
+// Local uses
+use super::network_status::SharedNetworkStatus;
+
+/// Every dependency has to respond within this time to consider the server ready.
+const READINESS_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize)]
+struct DependencyStatus {
+    name: &'static str,
+    ready: bool,
+    /// Whether the replica is not ready without this dependency.
+    critical: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReadinessReport {
+    ready: bool,
+    /// Whether some of the non-critical dependencies are not ready.
+    degraded: bool,
+    dependencies: Vec<DependencyStatus>,
+}
+
+impl ReadinessReport {
+    fn new(dependencies: Vec<DependencyStatus>) -> Self {
+        Self {
+            ready: dependencies
+                .iter()
+                .all(|dependency| dependency.ready || !dependency.critical),
+            degraded: dependencies
+                .iter()
+                .any(|dependency| !dependency.ready && !dependency.critical),
+            dependencies,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ReadinessChecker {
+    connection_pool: ConnectionPool,
+    network_status: SharedNetworkStatus,
+    eth_gateway: EthereumGateway,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+}
+
+impl ReadinessChecker {
+    pub fn new(
+        connection_pool: ConnectionPool,
+        network_status: SharedNetworkStatus,
+        eth_gateway: EthereumGateway,
+        mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    ) -> Self {
+        Self {
+            connection_pool,
+            network_status,
+            eth_gateway,
+            mempool_tx_sender,
+        }
+    }
+
+    async fn check_database(&self) -> anyhow::Result<()> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+        Ok(())
+    }
+
+    async fn check_core(&self) -> anyhow::Result<()> {
+        self.network_status.get_core_status().await?;
+        Ok(())
+    }
+
+    async fn check_eth_node(&self) -> anyhow::Result<()> {
+        self.eth_gateway.block_number().await?;
+        Ok(())
+    }
+
+    async fn check_mempool(&self) -> anyhow::Result<()> {
+        let (sender, receiver) = oneshot::channel();
+        self.mempool_tx_sender
+            .clone()
+            .send(MempoolTransactionRequest::Ping(sender))
+            .await?;
+        receiver.await?;
+        Ok(())
+    }
+
+    async fn report(&self) -> ReadinessReport {
+        let (database, core, eth_node, mempool) = futures::join!(
+            check_dependency("database", true, self.check_database()),
+            check_dependency("core", false, self.check_core()),
+            check_dependency("eth_node", false, self.check_eth_node()),
+            check_dependency("mempool", true, self.check_mempool()),
+        );
+
+        ReadinessReport::new(vec![database, core, eth_node, mempool])
+    }
+}
+
+async fn check_dependency(
+    name: &'static str,
+    critical: bool,
+    check: impl Future<Output = anyhow::Result<()>>,
+) -> DependencyStatus {
+    let result = match tokio::time::timeout(READINESS_CHECK_TIMEOUT, check).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow::anyhow!("Timeout")),
+    };
+
+    match result {
+        Ok(()) => DependencyStatus {
+            name,
+            ready: true,
+            critical,
+            error: None,
+        },
+        Err(err) => {
+            metrics::increment_counter!("api.readiness.failed_checks", "dependency" => name);
+            DependencyStatus {
+                name,
+                ready: false,
+                critical,
+                error: Some(err.to_string()),
+            }
+        }
+    }
+}
+
+async fn liveness() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+async fn readiness(checker: web::Data<ReadinessChecker>) -> HttpResponse {
+    let report = checker.report().await;
+    if report.ready {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
+
+pub fn liveness_resource() -> Resource {
+    web::resource("/healthz").route(web::get().to(liveness))
+}
+
+pub fn readiness_resource(checker: ReadinessChecker) -> Resource {
+    web::resource("/readyz")
+        .app_data(web::Data::new(checker))
+        .route(web::get().to(readiness))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn check(name: &'static str, critical: bool, ready: bool) -> DependencyStatus {
+        check_dependency(name, critical, async move {
+            if ready {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("Unavailable"))
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn dependency_status() {
+        let status = check("database", true, true).await;
+        assert!(status.ready);
+        assert!(status.error.is_none());
+
+        let status = check("database", true, false).await;
+        assert!(!status.ready);
+        assert_eq!(status.error.as_deref(), Some("Unavailable"));
+    }
+
+    #[tokio::test]
+    async fn shared_dependencies_are_not_critical() {
+        let report = ReadinessReport::new(vec![
+            check("database", true, true).await,
+            check("core", false, true).await,
+            check("eth_node", false, true).await,
+            check("mempool", true, true).await,
+        ]);
+        assert!(report.ready);
+        assert!(!report.degraded);
+
+        // The outage of the shared dependencies keeps the replica in the rotation.
+        let report = ReadinessReport::new(vec![
+            check("database", true, true).await,
+            check("core", false, false).await,
+            check("eth_node", false, false).await,
+            check("mempool", true, true).await,
+        ]);
+        assert!(report.ready);
+        assert!(report.degraded);
+
+        let report = ReadinessReport::new(vec![
+            check("database", true, false).await,
+            check("core", false, true).await,
+            check("eth_node", false, true).await,
+            check("mempool", true, true).await,
+        ]);
+        assert!(!report.ready);
+        assert!(!report.degraded);
+    }
+}
@@ -1,7 +1,10 @@
 //! Utilities for the REST API.
 
-use actix_web::error::InternalError;
+use actix_web::error::{ErrorUnauthorized, InternalError};
 use actix_web::{HttpResponse, Result as ActixResult};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use zksync_storage::chain::{
     block::records::StorageBlockDetails,
@@ -10,6 +13,26 @@ use zksync_storage::chain::{
 use zksync_storage::StorageProcessor;
 use zksync_types::{PriorityOp, Token, TokenId, ZkSyncPriorityOp};
 
+#[derive(Debug, Serialize, Deserialize)]
+struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    sub: String,
+    /// Expiration time (as UTC timestamp).
+    exp: usize,
+}
+
+/// Checks that the request to the admin endpoints is authorized
+/// with the JWT signed by `AdminApiConfig::secret_auth`.
+pub fn authorize_admin(secret_auth: &str, credentials: &BearerAuth) -> ActixResult<()> {
+    decode::<PayloadAuthToken>(
+        credentials.token(),
+        &DecodingKey::from_secret(secret_auth.as_ref()),
+        &Validation::default(),
+    )
+    .map_err(|_| ErrorUnauthorized("Invalid authorization token"))?;
+    Ok(())
+}
+
 /// Checks if block is finalized, meaning that
 /// both Verify operation is performed for it, and this
 /// operation is anchored on the Ethereum blockchain.
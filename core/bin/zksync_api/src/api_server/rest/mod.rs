@@ -1,26 +1,40 @@
 use actix_cors::Cors;
-use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web::{middleware::Compress, web, App, HttpResponse, HttpServer};
 use futures::channel::mpsc;
 use std::net::SocketAddr;
 use zksync_storage::ConnectionPool;
-use zksync_types::{SequentialTxId, H160};
+use zksync_types::{network::Network, SequentialTxId, H160};
 
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
 use self::v01::api_decl::ApiV01;
 use crate::signature_checker::VerifySignatureRequest;
 
-use super::tx_sender::TxSender;
+use super::{
+    circuit_breaker::CircuitBreaker, core_api_client::CoreApiClient, rate_limit::RateLimiter,
+    tx_sender::TxSender,
+};
 
 use crate::api_server::rest::network_status::SharedNetworkStatus;
 use crate::fee_ticker::FeeTicker;
 use tokio::task::JoinHandle;
-use zksync_config::ZkSyncConfig;
+use zksync_config::{
+    configs::api::{PrivateApiConfig, SandboxApiConfig},
+    ZkSyncConfig,
+};
+use zksync_eth_client::EthereumGateway;
 use zksync_mempool::MempoolTransactionRequest;
+use zksync_state::shared::SharedCommittedState;
 
+mod api_keys;
+mod compression;
 mod forced_exit_requests;
+mod health;
 mod helpers;
 pub mod network_status;
+mod prometheus;
+mod resilience;
+mod token_admin;
 mod v01;
 pub mod v02;
 
@@ -30,9 +44,75 @@ async fn start_server(
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
     bind_to: SocketAddr,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    eth_gateway: EthereumGateway,
+    committed_state: Option<SharedCommittedState>,
 ) {
+    // Transaction and block events are fetched once and shared between all the server workers.
+    let block_stream = v02::block_stream::BlockStream::new(
+        api_v01.connection_pool.clone(),
+        api_v01
+            .config
+            .chain
+            .state_keeper
+            .miniblock_iteration_interval(),
+    );
+    let ws_v02_data = v02::ws::ApiWsData::new(
+        api_v01.connection_pool.clone(),
+        api_v01
+            .config
+            .chain
+            .state_keeper
+            .miniblock_iteration_interval(),
+        api_v01.config.api.mempool_feed.clone(),
+        api_v01.config.api.rest.ws_private_topics_auth,
+    );
+    let api_keys = api_keys::ApiKeys::new(
+        api_v01.connection_pool.clone(),
+        api_v01.config.api.keys.clone(),
+    );
+    let readiness_checker = health::ReadinessChecker::new(
+        api_v01.connection_pool.clone(),
+        api_v01.network_status.clone(),
+        eth_gateway.clone(),
+        mempool_tx_sender.clone(),
+    );
+    if api_v01.config.api.encrypted_txs.enabled {
+        let tx_sender = TxSender::new(
+            api_v01.connection_pool.clone(),
+            sign_verifier.clone(),
+            fee_ticker.clone(),
+            &api_v01.config.api.common,
+            &api_v01.config.api.token_config,
+            mempool_tx_sender.clone(),
+        );
+        let revealer = v02::encrypted_tx::EncryptedTxRevealer::new(
+            tx_sender,
+            api_v01.config.api.encrypted_txs.clone(),
+        );
+        actix_rt::spawn(revealer.run());
+    }
+    start_admin_server(&api_v01, api_keys.clone());
+    let monitored_pools = prometheus::MonitoredPools::new(
+        api_v01.connection_pool.clone(),
+        api_v01.main_database_connection_pool.clone(),
+    );
+    let rate_limiter = RateLimiter::new(&api_v01.config.api.rate_limit)
+        .await
+        .expect("Unable to initialize the rate limiter");
+    // The breakers are shared by all the workers, so the failures are counted over all the requests.
+    let storage_breaker = CircuitBreaker::new("storage", &api_v01.config.api.circuit_breaker);
+    let mempool_breaker = CircuitBreaker::new("mempool", &api_v01.config.api.circuit_breaker);
+
     HttpServer::new(move || {
         let api_v01 = api_v01.clone();
+        let resilience = resilience::Resilience::new(
+            &api_v01.config.api.request_timeout,
+            storage_breaker.clone(),
+        );
+        let token_admin_scope = token_admin::api_scope(
+            api_v01.main_database_connection_pool.clone(),
+            api_v01.config.api.admin.secret_auth.clone(),
+        );
         // This api stores forced exit requests, it's necessary to use main database connection
         let forced_exit_requests_api_scope = forced_exit_requests::api_scope(
             api_v01.main_database_connection_pool.clone(),
@@ -53,8 +133,17 @@ async fn start_server(
                 &api_v01.config.api.common,
                 &api_v01.config.api.token_config,
                 mempool_tx_sender.clone(),
-            );
-            v02::api_scope(tx_sender, &api_v01.config, api_v01.network_status.clone())
+            )
+            .with_mempool_breaker(mempool_breaker.clone());
+            v02::api_scope(
+                tx_sender,
+                api_v01.main_database_connection_pool.clone(),
+                &api_v01.config,
+                api_v01.network_status.clone(),
+                block_stream.clone(),
+                committed_state.clone(),
+                eth_gateway.clone(),
+            )
         };
         App::new()
             .wrap(
@@ -65,9 +154,41 @@ async fn start_server(
                     .allow_any_header()
                     .allow_any_method(),
             )
-            .service(api_v01.into_scope())
-            .service(forced_exit_requests_api_scope)
-            .service(api_v02_scope)
+            // The filter has to be applied before the compression, so it's wrapped first.
+            .wrap(compression::CompressionFilter::new(
+                &api_v01.config.api.rest,
+            ))
+            .wrap(Compress::default())
+            .wrap(prometheus::RequestMetrics)
+            .service(
+                api_v01
+                    .into_scope()
+                    .wrap(resilience.clone())
+                    .wrap(api_keys.clone())
+                    .wrap(rate_limiter.clone()),
+            )
+            .service(
+                forced_exit_requests_api_scope
+                    .wrap(resilience.clone())
+                    .wrap(api_keys.clone())
+                    .wrap(rate_limiter.clone()),
+            )
+            .service(
+                api_v02_scope
+                    .wrap(resilience.clone())
+                    .wrap(api_keys.clone())
+                    .wrap(rate_limiter.clone()),
+            )
+            .service(
+                v02::ws::api_resource(ws_v02_data.clone())
+                    .wrap(api_keys.clone())
+                    .wrap(rate_limiter.clone()),
+            )
+            .service(token_admin_scope)
+            // Probes are neither rate limited nor require the API keys.
+            .service(health::liveness_resource())
+            .service(health::readiness_resource(readiness_checker.clone()))
+            .service(prometheus::metrics_resource(monitored_pools.clone()))
             // Endpoint needed for js isReachable
             .route(
                 "/favicon.ico",
@@ -85,6 +206,24 @@ async fn start_server(
     .expect("REST API server has crashed");
 }
 
+/// Starts the server of the admin endpoints on the admin API port, which isn't exposed publicly.
+fn start_admin_server(api_v01: &ApiV01, api_keys: api_keys::ApiKeys) {
+    let pool = api_v01.main_database_connection_pool.clone();
+    let secret_auth = api_v01.config.api.admin.secret_auth.clone();
+    let server = HttpServer::new(move || {
+        App::new().service(api_keys::admin::api_scope(
+            pool.clone(),
+            api_keys.clone(),
+            secret_auth.clone(),
+        ))
+    })
+    .workers(1)
+    .bind(api_v01.config.api.admin.bind_addr())
+    .expect("Unable to bind the admin API server")
+    .run();
+    actix_rt::spawn(server);
+}
+
 /// Start HTTP REST API
 #[allow(clippy::too_many_arguments)]
 #[must_use]
@@ -96,7 +235,9 @@ pub fn start_server_thread_detached(
     fee_ticker: FeeTicker,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
-    core_address: String,
+    private_api_config: PrivateApiConfig,
+    eth_gateway: EthereumGateway,
+    committed_state: Option<SharedCommittedState>,
 ) -> JoinHandle<()> {
     let (handler, panic_sender) = spawn_panic_handler();
 
@@ -108,7 +249,8 @@ pub fn start_server_thread_detached(
                 // TODO remove this config ZKS-815
                 let config = ZkSyncConfig::from_env();
 
-                let mut network_status = SharedNetworkStatus::new(core_address);
+                let mut network_status =
+                    SharedNetworkStatus::new(CoreApiClient::new(&private_api_config));
                 // We want to update the network status, as soon as possible, otherwise we can catch the situation,
                 // when the node is started and receiving the request, but the status is still `null` and
                 // monitoring tools spawn the notification that our node is down, though it's just a default status
@@ -133,6 +275,8 @@ pub fn start_server_thread_detached(
                     sign_verifier,
                     listen_addr,
                     mempool_tx_sender.clone(),
+                    eth_gateway,
+                    committed_state,
                 )
                 .await;
             });
@@ -140,3 +284,52 @@ pub fn start_server_thread_detached(
         .expect("Api server thread");
     handler
 }
+
+/// Start HTTP REST API in the sandbox mode, serving the synthetic data without the database.
+#[must_use]
+pub fn start_sandbox_server_thread_detached(
+    listen_addr: SocketAddr,
+    config: SandboxApiConfig,
+    network: Network,
+) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+
+    std::thread::Builder::new()
+        .name("actix-sandbox-api".to_string())
+        .spawn(move || {
+            actix_rt::System::new().block_on(async move {
+                let _panic_sentinel = ThreadPanicNotify(panic_sender.clone());
+                let store = v02::sandbox::SandboxStore::new(config);
+
+                HttpServer::new(move || {
+                    App::new()
+                        .wrap(
+                            Cors::default()
+                                .send_wildcard()
+                                .max_age(3600)
+                                .allow_any_origin()
+                                .allow_any_header()
+                                .allow_any_method(),
+                        )
+                        .wrap(prometheus::RequestMetrics)
+                        .wrap(v02::sandbox::SandboxSessions(store.clone()))
+                        .service(v02::sandbox::api_scope(network))
+                        .service(health::liveness_resource())
+                        // Endpoint needed for js isReachable
+                        .route(
+                            "/favicon.ico",
+                            web::get().to(|| HttpResponse::Ok().finish()),
+                        )
+                })
+                .workers(super::THREADS_PER_SERVER)
+                .bind(listen_addr)
+                .unwrap()
+                .shutdown_timeout(60)
+                .run()
+                .await
+                .expect("Sandbox REST API server has crashed");
+            });
+        })
+        .expect("Sandbox api server thread");
+    handler
+}
@@ -9,6 +9,8 @@ use zksync_storage::ConnectionPool;
 use zksync_types::{BlockNumber, SequentialTxId};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+use crate::api_server::core_api_client::{CoreApiClient, CoreApiError};
+
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
 pub struct NetworkStatus {
     pub next_block_at_max: Option<u64>,
@@ -23,17 +25,13 @@ pub struct NetworkStatus {
 #[derive(Debug, Clone)]
 pub struct SharedNetworkStatus {
     status: Arc<RwLock<NetworkStatus>>,
-    core_status_address: String,
-    core_client: reqwest::Client,
+    core_client: CoreApiClient,
 }
 
 impl SharedNetworkStatus {
-    pub fn new(core_address: String) -> Self {
-        let core_client = reqwest::Client::new();
-        let core_status_address = format!("{}/status", core_address);
+    pub fn new(core_client: CoreApiClient) -> Self {
         Self {
             status: Default::default(),
-            core_status_address,
             core_client,
         }
     }
@@ -43,14 +41,8 @@ impl SharedNetworkStatus {
     }
 
     /// Get healthcheck status from core server.
-    async fn get_core_status(&self) -> anyhow::Result<CoreStatus> {
-        Ok(self
-            .core_client
-            .get(&self.core_status_address)
-            .send()
-            .await?
-            .json()
-            .await?)
+    pub(crate) async fn get_core_status(&self) -> Result<CoreStatus, CoreApiError> {
+        self.core_client.get_status().await
     }
 
     /// Updates shared network status. We use last_tx_id as a checkpoint
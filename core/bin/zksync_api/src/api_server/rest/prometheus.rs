@@ -0,0 +1,119 @@
+//! Prometheus scrape endpoint of the REST API server.
+//!
+//! `/metrics` renders all the metrics recorded by the process in the Prometheus text format,
+//! so the API replicas can be scraped through the same port they serve the requests on.
+//! The metrics are collected by the recorder of the Prometheus exporter, hence the endpoint
+//! responds with `503 Service Unavailable` if the `prometheus` component isn't running.
+//!
+//! The `RequestMetrics` middleware records the latency of every request labeled with the matched
+//! route pattern, so the endpoints without their own histograms are covered as well.
+
+// Built-in uses
+use std::{rc::Rc, time::Instant};
+
+// External uses
+use actix_web::{
+    body::MessageBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, HttpResponse, Resource,
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+
+// Workspace uses
+use zksync_prometheus_exporter::render_metrics;
+use zksync_storage::ConnectionPool;
+
+/// Connection pools of the server, their utilization is reported on every scrape.
+#[derive(Clone)]
+pub struct MonitoredPools {
+    pools: Vec<ConnectionPool>,
+}
+
+impl MonitoredPools {
+    pub fn new(read_only_pool: ConnectionPool, main_pool: ConnectionPool) -> Self {
+        Self {
+            pools: vec![read_only_pool, main_pool],
+        }
+    }
+}
+
+async fn metrics(pools: web::Data<MonitoredPools>) -> HttpResponse {
+    for pool in &pools.pools {
+        pool.report_utilization();
+    }
+
+    match render_metrics() {
+        Some(metrics) => HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(metrics),
+        None => HttpResponse::ServiceUnavailable().body("Prometheus exporter is not running"),
+    }
+}
+
+pub fn metrics_resource(pools: MonitoredPools) -> Resource {
+    web::resource("/metrics")
+        .app_data(web::Data::new(pools))
+        .route(web::get().to(metrics))
+}
+
+/// Middleware recording the latency of the requests per route.
+#[derive(Debug, Clone, Default)]
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = RequestMetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let method = req.method().to_string();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let response = service.call(req).await?;
+            // The pattern is used instead of the path to keep the cardinality of the labels bounded.
+            let endpoint = response
+                .request()
+                .match_pattern()
+                .unwrap_or_else(|| "unmatched".to_string());
+            let labels = vec![
+                ("endpoint", endpoint),
+                ("method", method),
+                ("status", response.status().as_u16().to_string()),
+            ];
+            metrics::histogram!("api.rest.request", start.elapsed(), &labels);
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,161 @@
+//! Timeouts of the REST API requests and the circuit breaker of the storage.
+//!
+//! The request is cancelled once its route timeout expires, so a slow query doesn't keep
+//! the connection to the client and to the database open, and the client gets
+//! `504 Gateway Timeout`. Only the storage errors reported by the handlers are counted as
+//! the failures of the storage, since the timed out request may be waiting for anything else:
+//! once their share exceeds the threshold, the requests are failed with
+//! `503 Service Unavailable` without being processed until the breaker closes.
+
+// Built-in uses
+use std::{rc::Rc, time::Duration};
+
+// External uses
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    HttpResponse,
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+
+// Workspace uses
+use zksync_config::configs::api::RequestTimeoutConfig;
+
+// Local uses
+use super::v02::error::ErrorCode;
+use crate::api_server::circuit_breaker::CircuitBreaker;
+
+/// Middleware applying the route timeouts and the circuit breaker of the storage.
+#[derive(Debug, Clone)]
+pub struct Resilience {
+    default_timeout: Duration,
+    /// Path prefixes and their timeouts, sorted by the prefix length in descending order.
+    route_timeouts: Rc<Vec<(String, Duration)>>,
+    storage_breaker: CircuitBreaker,
+}
+
+impl Resilience {
+    pub fn new(config: &RequestTimeoutConfig, storage_breaker: CircuitBreaker) -> Self {
+        let mut route_timeouts = config.route_timeouts();
+        route_timeouts.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        Self {
+            default_timeout: config.default_timeout(),
+            route_timeouts: Rc::new(route_timeouts),
+            storage_breaker,
+        }
+    }
+
+    fn timeout(&self, path: &str) -> Duration {
+        self.route_timeouts
+            .iter()
+            .find(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .map(|(_, timeout)| *timeout)
+            .unwrap_or(self.default_timeout)
+    }
+}
+
+fn service_unavailable(retry_after: Option<Duration>) -> actix_web::Error {
+    let retry_after_secs = retry_after.map_or(1, |retry_after| retry_after.as_secs().max(1));
+    let response = HttpResponse::ServiceUnavailable()
+        .insert_header(("Retry-After", retry_after_secs.to_string()))
+        .body("Storage is temporarily unavailable");
+    InternalError::from_response("Storage is temporarily unavailable", response).into()
+}
+
+fn gateway_timeout() -> actix_web::Error {
+    let response =
+        HttpResponse::GatewayTimeout().body("Request processing has not finished in time");
+    InternalError::from_response("Request timeout", response).into()
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Resilience
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = ResilienceMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ResilienceMiddleware {
+            service: Rc::new(service),
+            resilience: self.clone(),
+        }))
+    }
+}
+
+pub struct ResilienceMiddleware<S> {
+    service: Rc<S>,
+    resilience: Resilience,
+}
+
+impl<S, B> Service<ServiceRequest> for ResilienceMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let storage_breaker = self.resilience.storage_breaker.clone();
+        let timeout = self.resilience.timeout(req.path());
+
+        Box::pin(async move {
+            if !storage_breaker.allow() {
+                return Err(service_unavailable(storage_breaker.retry_after()));
+            }
+            let response = match tokio::time::timeout(timeout, service.call(req)).await {
+                Ok(response) => response?,
+                Err(_) => {
+                    metrics::increment_counter!("api.rest.request_timeout");
+                    return Err(gateway_timeout());
+                }
+            };
+            // The v02 handlers report the errors in the body, the code is passed in the extensions.
+            let storage_failed = response.request().extensions().get::<ErrorCode>()
+                == Some(&ErrorCode::StorageError);
+            storage_breaker.record(!storage_failed);
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_prefix_timeout() {
+        let config = RequestTimeoutConfig {
+            default_ms: 30_000,
+            route_timeouts: vec![
+                "/api/v0.2/transactions=10000".to_owned(),
+                "/api/v0.2/transactions/batches=20000".to_owned(),
+            ],
+        };
+        let resilience = Resilience::new(&config, CircuitBreaker::disabled("storage"));
+
+        assert_eq!(
+            resilience.timeout("/api/v0.2/transactions/batches"),
+            Duration::from_secs(20)
+        );
+        assert_eq!(
+            resilience.timeout("/api/v0.2/transactions/0x00"),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            resilience.timeout("/api/v0.2/blocks"),
+            Duration::from_secs(30)
+        );
+    }
+}
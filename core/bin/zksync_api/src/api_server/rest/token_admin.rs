@@ -0,0 +1,247 @@
+//! Admin endpoints to manage the tokens.
+//!
+//! Requests must be authorized with the JWT signed by `AdminApiConfig::secret_auth`.
+//! Every change notifies the `TOKENS_UPDATED_CHANNEL`, so the token caches of all the server
+//! processes are invalidated without a restart.
+
+// Built-in uses
+use std::time::Instant;
+
+// External uses
+use actix_web::{
+    error::{ErrorConflict, ErrorInternalServerError, ErrorNotFound},
+    web, HttpResponse, Scope,
+};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_storage::{tokens::StoreTokenError, ConnectionPool, StorageProcessor};
+use zksync_token_db_cache::invalidate_token_caches;
+use zksync_types::{
+    tokens::{TokenAdminSettings, TokenMarketVolume},
+    Address, Token, TokenId, TokenKind,
+};
+
+// Local uses
+use super::helpers::authorize_admin;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NewToken {
+    /// ID assigned to the token by the governance contract.
+    id: TokenId,
+    address: Address,
+    symbol: String,
+    decimals: u8,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenInfoUpdate {
+    symbol: Option<String>,
+    decimals: Option<u8>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenAdminInfo {
+    #[serde(flatten)]
+    token: Token,
+    settings: TokenAdminSettings,
+    /// Market volume and the result of the liquidity check, overridden by `settings.fee_eligible`.
+    market: Option<TokenMarketVolume>,
+}
+
+/// Shared data between `api/admin/tokens` endpoints.
+#[derive(Debug, Clone)]
+struct TokenAdminData {
+    pool: ConnectionPool,
+    secret_auth: String,
+}
+
+impl TokenAdminData {
+    fn authorize(&self, credentials: &BearerAuth) -> actix_web::Result<()> {
+        authorize_admin(&self.secret_auth, credentials)
+    }
+
+    async fn access_storage(&self) -> actix_web::Result<StorageProcessor<'_>> {
+        self.pool
+            .access_storage()
+            .await
+            .map_err(ErrorInternalServerError)
+    }
+}
+
+async fn load_token_info(
+    storage: &mut StorageProcessor<'_>,
+    token_id: TokenId,
+) -> actix_web::Result<TokenAdminInfo> {
+    let mut tokens_schema = storage.tokens_schema();
+    let token = tokens_schema
+        .get_token(token_id.into())
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or_else(|| ErrorNotFound("Token does not exist"))?;
+    let settings = tokens_schema
+        .load_token_admin_settings()
+        .await
+        .map_err(ErrorInternalServerError)?
+        .remove(&token_id)
+        .unwrap_or_default();
+    let market = tokens_schema
+        .get_token_market_volume(token_id)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(TokenAdminInfo {
+        token,
+        settings,
+        market,
+    })
+}
+
+/// Notifies the other processes about the change once the transaction is committed.
+async fn commit_token_update(
+    mut transaction: StorageProcessor<'_>,
+    token_id: TokenId,
+) -> actix_web::Result<()> {
+    transaction
+        .tokens_schema()
+        .notify_token_updated(token_id)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    transaction
+        .commit()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    // The listener of this process will invalidate the caches as well,
+    // but the subsequent requests shouldn't wait for it.
+    invalidate_token_caches();
+    Ok(())
+}
+
+async fn token_info(
+    data: web::Data<TokenAdminData>,
+    credentials: BearerAuth,
+    token_id: web::Path<TokenId>,
+) -> actix_web::Result<HttpResponse> {
+    let start = Instant::now();
+    data.authorize(&credentials)?;
+
+    let info = load_token_info(&mut data.access_storage().await?, *token_id).await?;
+
+    metrics::histogram!("api.admin.tokens", start.elapsed(), "endpoint_name" => "token_info");
+    Ok(HttpResponse::Ok().json(info))
+}
+
+async fn add_token(
+    data: web::Data<TokenAdminData>,
+    credentials: BearerAuth,
+    new_token: web::Json<NewToken>,
+) -> actix_web::Result<HttpResponse> {
+    let start = Instant::now();
+    data.authorize(&credentials)?;
+    let new_token = new_token.into_inner();
+    let token = Token::new(
+        new_token.id,
+        new_token.address,
+        &new_token.symbol,
+        new_token.decimals,
+        TokenKind::ERC20,
+    );
+
+    let mut storage = data.access_storage().await?;
+    let mut transaction = storage
+        .start_transaction()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    transaction
+        .tokens_schema()
+        .store_token(token.clone())
+        .await
+        .map_err(|err| match err {
+            StoreTokenError::TokenAlreadyExistsError(msg) => ErrorConflict(msg),
+            StoreTokenError::Other(err) => ErrorInternalServerError(err),
+        })?;
+    commit_token_update(transaction, token.id).await?;
+    vlog::info!("Token {} '{}' is added", token.id, token.symbol);
+
+    metrics::histogram!("api.admin.tokens", start.elapsed(), "endpoint_name" => "add_token");
+    Ok(HttpResponse::Ok().json(TokenAdminInfo {
+        token,
+        settings: TokenAdminSettings::default(),
+        market: None,
+    }))
+}
+
+async fn update_token_info(
+    data: web::Data<TokenAdminData>,
+    credentials: BearerAuth,
+    token_id: web::Path<TokenId>,
+    update: web::Json<TokenInfoUpdate>,
+) -> actix_web::Result<HttpResponse> {
+    let start = Instant::now();
+    data.authorize(&credentials)?;
+    let token_id = token_id.into_inner();
+    let update = update.into_inner();
+
+    let mut storage = data.access_storage().await?;
+    let mut transaction = storage
+        .start_transaction()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    transaction
+        .tokens_schema()
+        .update_token_info(token_id, update.symbol.as_deref(), update.decimals)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or_else(|| ErrorNotFound("Token does not exist"))?;
+    let info = load_token_info(&mut transaction, token_id).await?;
+    commit_token_update(transaction, token_id).await?;
+    vlog::info!("Token {} info is updated: {:?}", token_id, update);
+
+    metrics::histogram!("api.admin.tokens", start.elapsed(), "endpoint_name" => "update_token_info");
+    Ok(HttpResponse::Ok().json(info))
+}
+
+async fn update_token_settings(
+    data: web::Data<TokenAdminData>,
+    credentials: BearerAuth,
+    token_id: web::Path<TokenId>,
+    settings: web::Json<TokenAdminSettings>,
+) -> actix_web::Result<HttpResponse> {
+    let start = Instant::now();
+    data.authorize(&credentials)?;
+    let token_id = token_id.into_inner();
+    let settings = settings.into_inner();
+
+    let mut storage = data.access_storage().await?;
+    let mut transaction = storage
+        .start_transaction()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    // Checks that the token exists.
+    load_token_info(&mut transaction, token_id).await?;
+    transaction
+        .tokens_schema()
+        .store_token_admin_settings(token_id, settings)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let info = load_token_info(&mut transaction, token_id).await?;
+    commit_token_update(transaction, token_id).await?;
+    vlog::info!("Token {} settings are updated: {:?}", token_id, settings);
+
+    metrics::histogram!("api.admin.tokens", start.elapsed(), "endpoint_name" => "update_token_settings");
+    Ok(HttpResponse::Ok().json(info))
+}
+
+pub fn api_scope(pool: ConnectionPool, secret_auth: String) -> Scope {
+    let data = TokenAdminData { pool, secret_auth };
+
+    web::scope("/api/admin/tokens")
+        .app_data(web::Data::new(data))
+        .route("", web::post().to(add_token))
+        .route("{id}", web::get().to(token_info))
+        .route("{id}", web::put().to(update_token_info))
+        .route("{id}/settings", web::put().to(update_token_settings))
+}
@@ -3,23 +3,36 @@
 // Built-in uses
 use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Instant;
 
 // External uses
-use actix_web::{web, Scope};
+use actix_web::{web, FromRequest, Scope};
+use async_trait::async_trait;
+use num::Zero;
 
 // Workspace uses
 use zksync_api_types::v02::{
-    account::{Account, AccountAddressOrId, AccountState, IncomingAccountTxsQuery},
+    account::{
+        Account, AccountAddressOrId, AccountBatchState, AccountFullExits, AccountNextNonce,
+        AccountState, AccountStateChange, AccountStateChanges, AccountStateQuery,
+        AccountsBatchRequest, DepositingBalancesVersion, EthAccountType, ExitProofInfo,
+        FullExitStatus, IncomingAccountStateChangesQuery, IncomingAccountTxsCursorQuery,
+        IncomingAccountTxsQuery, PubKeyAuthStatus,
+    },
     pagination::{
-        parse_query, AccountTxsRequest, ApiEither, Paginated, PaginationQuery, PendingOpsRequest,
+        parse_query, AccountTxsCursorRequest, AccountTxsRequest, ApiEither, Cursor,
+        CursorPaginated, Paginated, PaginationQuery, PendingOpsRequest, MAX_LIMIT,
     },
     transaction::{Transaction, TxHashSerializeWrapper},
 };
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
+use zksync_state::shared::{CommittedAccount, SharedCommittedState};
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, SerialId, TokenLike};
+use zksync_types::{
+    tx::TxHash, AccountId, Address, BlockNumber, Nonce, SerialId, TokenId, TokenLike, H256,
+};
 
 // Local uses
 use super::{
@@ -27,25 +40,112 @@ use super::{
     paginate_trait::Paginate,
     response::ApiResult,
 };
-use crate::{api_server::helpers::get_depositing, api_try, fee_ticker::PriceError};
+use crate::{
+    api_server::{eth_head::EthHeadCache, helpers::get_depositing},
+    api_try,
+    eth_checker::EthereumChecker,
+    fee_ticker::PriceError,
+    utils::shared_lru_cache::SharedLruCache,
+};
+
+/// Maximum number of blocks in the range of the account state changes request.
+const MAX_STATE_CHANGES_BLOCK_RANGE: u32 = 1000;
+/// Number of the latest Ethereum blocks searched for the `FactAuth` event of the authorization,
+/// the events of the older authorizations are not reported.
+const PUBKEY_AUTH_EVENT_BLOCK_RANGE: u64 = 10_000;
+/// Maximum number of the authorizations the searches of the events are remembered for.
+const PUBKEY_AUTH_EVENTS_CACHE_CAPACITY: usize = 10_000;
+
+/// Progress of the search of the `FactAuth` event, the later requests only search
+/// the new blocks until the event is found.
+#[derive(Debug, Clone, Copy, Default)]
+struct PubKeyAuthEventSearch {
+    event: Option<(u64, H256)>,
+    searched_to: Option<u64>,
+}
+
+pub(super) fn parse_account_id_or_address(
+    account_address_or_id: &str,
+) -> Result<AccountAddressOrId, Error> {
+    if let Ok(account_id) = u32::from_str(account_address_or_id) {
+        Ok(AccountAddressOrId::Id(AccountId(account_id)))
+    } else {
+        let address_str = if let Some(address_str) = account_address_or_id.strip_prefix("0x") {
+            address_str
+        } else {
+            account_address_or_id
+        };
+
+        if let Ok(address) = Address::from_str(address_str) {
+            Ok(AccountAddressOrId::Address(address))
+        } else {
+            Err(Error::from(InvalidDataError::InvalidAccountIdOrAddress))
+        }
+    }
+}
 
 /// Shared data between `api/v02/accounts` endpoints.
 #[derive(Clone)]
 struct ApiAccountData {
     pool: ConnectionPool,
+    /// The exit proofs are requested by the API, so the replica pool can't be used for them.
+    main_database_pool: ConnectionPool,
     tokens: TokenDBCache,
     confirmations_for_eth_event: u64,
+    confirmations_for_full_exit: u64,
+    max_accounts_per_batch_request: u64,
+    /// Committed state shared by the state keeper, only available if it runs in the same process.
+    committed_state: Option<SharedCommittedState>,
+    eth_checker: EthereumChecker,
+    eth_head: EthHeadCache,
+    contract_address: Address,
+    /// Searches of the `FactAuth` events by the account, the nonce and the authorized fact.
+    pubkey_auth_events: SharedLruCache<(Address, Nonce, H256), PubKeyAuthEventSearch>,
 }
 
 impl ApiAccountData {
-    fn new(pool: ConnectionPool, tokens: TokenDBCache, confirmations_for_eth_event: u64) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        pool: ConnectionPool,
+        main_database_pool: ConnectionPool,
+        tokens: TokenDBCache,
+        confirmations_for_eth_event: u64,
+        confirmations_for_full_exit: u64,
+        max_accounts_per_batch_request: u64,
+        committed_state: Option<SharedCommittedState>,
+        eth_checker: EthereumChecker,
+        eth_head: EthHeadCache,
+        contract_address: Address,
+    ) -> Self {
         Self {
             pool,
+            main_database_pool,
             tokens,
             confirmations_for_eth_event,
+            confirmations_for_full_exit,
+            max_accounts_per_batch_request,
+            committed_state,
+            eth_checker,
+            eth_head,
+            contract_address,
+            pubkey_auth_events: SharedLruCache::new(PUBKEY_AUTH_EVENTS_CACHE_CAPACITY),
         }
     }
 
+    /// Takes the committed state of the account from the memory of the state keeper, `Some(None)`
+    /// means that the account was removed. Returns `None` if it's not available, so the state
+    /// has to be loaded from the database.
+    fn shared_committed_account(
+        &self,
+        account_id: AccountId,
+    ) -> Option<Option<Arc<CommittedAccount>>> {
+        let committed_state = self.committed_state.as_ref()?;
+        let account = committed_state.snapshot().account(account_id);
+        let result = if account.is_some() { "hit" } else { "miss" };
+        metrics::increment_counter!("api.v02.shared_committed_state", "result" => result);
+        account
+    }
+
     async fn get_id_by_address_or_id(
         &self,
         account_address_or_id: AccountAddressOrId,
@@ -53,6 +153,14 @@ impl ApiAccountData {
         match account_address_or_id {
             AccountAddressOrId::Id(account_id) => Ok(Some(account_id)),
             AccountAddressOrId::Address(address) => {
+                let shared_account_id = self
+                    .committed_state
+                    .as_ref()
+                    .and_then(|state| state.snapshot().account_id_by_address(&address));
+                if let Some(account_id) = shared_account_id {
+                    return Ok(account_id);
+                }
+
                 let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
                 let account_id = storage
                     .chain()
@@ -88,21 +196,7 @@ impl ApiAccountData {
         &self,
         account_address_or_id: &str,
     ) -> Result<AccountAddressOrId, Error> {
-        if let Ok(account_id) = u32::from_str(account_address_or_id) {
-            Ok(AccountAddressOrId::Id(AccountId(account_id)))
-        } else {
-            let address_str = if let Some(address_str) = account_address_or_id.strip_prefix("0x") {
-                address_str
-            } else {
-                account_address_or_id
-            };
-
-            if let Ok(address) = Address::from_str(address_str) {
-                Ok(AccountAddressOrId::Address(address))
-            } else {
-                Err(Error::from(InvalidDataError::InvalidAccountIdOrAddress))
-            }
-        }
+        parse_account_id_or_address(account_address_or_id)
     }
 
     async fn api_account(
@@ -111,6 +205,31 @@ impl ApiAccountData {
         account_id: AccountId,
         last_update_in_block: BlockNumber,
         storage: &mut StorageProcessor<'_>,
+    ) -> Result<Account, Error> {
+        let account_type = storage
+            .chain()
+            .account_schema()
+            .account_type_by_id(account_id)
+            .await
+            .map_err(Error::storage)?
+            .map(|t| t.into());
+        self.api_account_with_type(
+            account,
+            account_id,
+            last_update_in_block,
+            account_type,
+            storage,
+        )
+        .await
+    }
+
+    async fn api_account_with_type(
+        &self,
+        account: zksync_types::Account,
+        account_id: AccountId,
+        last_update_in_block: BlockNumber,
+        account_type: Option<EthAccountType>,
+        storage: &mut StorageProcessor<'_>,
     ) -> Result<Account, Error> {
         let mut balances = BTreeMap::new();
         let mut nfts = BTreeMap::new();
@@ -149,13 +268,6 @@ impl ApiAccountData {
             .map(|(id, nft)| (*id, nft.clone().into()))
             .collect();
 
-        let account_type = storage
-            .chain()
-            .account_schema()
-            .account_type_by_id(account_id)
-            .await
-            .map_err(Error::storage)?
-            .map(|t| t.into());
         Ok(Account {
             account_id,
             address: account.address,
@@ -174,6 +286,22 @@ impl ApiAccountData {
         account_id: AccountId,
     ) -> Result<Option<Account>, Error> {
         let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        if let Some(shared) = self.shared_committed_account(account_id) {
+            let shared = match shared {
+                Some(shared) => shared,
+                None => return Ok(None),
+            };
+            let account = self
+                .api_account(
+                    shared.account.clone(),
+                    account_id,
+                    shared.last_update_block,
+                    &mut storage,
+                )
+                .await?;
+            return Ok(Some(account));
+        }
+
         let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
         let ((last_block, _), account) = transaction
             .chain()
@@ -208,34 +336,41 @@ impl ApiAccountData {
     ) -> Result<Option<Account>, Error> {
         let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
         let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
-        let (last_block, account) = transaction
+        let result = self
+            .load_finalized_account(account_id, &mut transaction)
+            .await;
+        transaction.commit().await.map_err(Error::storage)?;
+        result
+    }
+
+    async fn load_finalized_account(
+        &self,
+        account_id: AccountId,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<Option<Account>, Error> {
+        let (last_block, account) = storage
             .chain()
             .account_schema()
             .account_and_last_block(account_id)
             .await
             .map_err(Error::storage)?;
-        let result = if let Some(account) = account {
+        if let Some(account) = account {
             Ok(Some(
-                self.api_account(
-                    account,
-                    account_id,
-                    BlockNumber(last_block as u32),
-                    &mut transaction,
-                )
-                .await?,
+                self.api_account(account, account_id, BlockNumber(last_block as u32), storage)
+                    .await?,
             ))
         } else {
             Ok(None)
-        };
-        transaction.commit().await.map_err(Error::storage)?;
-        result
+        }
     }
 
     async fn account_full_info(
         &self,
         address: Address,
         account_id: Option<AccountId>,
+        depositing_version: DepositingBalancesVersion,
     ) -> Result<AccountState, Error> {
+        let eth_head = self.eth_head.get().await;
         let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
         let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
 
@@ -244,43 +379,67 @@ impl ApiAccountData {
             &self.tokens,
             address,
             self.confirmations_for_eth_event,
+            eth_head,
+            depositing_version == DepositingBalancesVersion::V2,
         )
         .await?;
 
-        let (committed, finalized) = if let Some(account_id) = account_id {
-            let (finalized_state, committed_state) = transaction
-                .chain()
-                .account_schema()
-                .last_committed_state_for_account(account_id)
-                .await
-                .map_err(Error::storage)?;
-
-            let finalized_block = BlockNumber(finalized_state.0 as u32);
-            let finalized = if let Some(account) = finalized_state.1 {
-                Some(
-                    self.api_account(account, account_id, finalized_block, &mut transaction)
-                        .await?,
-                )
-            } else {
-                None
-            };
-            let committed = if let Some(account) = committed_state {
-                let last_block = transaction
+        let shared_account = account_id.and_then(|id| self.shared_committed_account(id));
+        let (committed, finalized) = match (account_id, shared_account) {
+            (Some(account_id), Some(None)) => {
+                let finalized = self
+                    .load_finalized_account(account_id, &mut transaction)
+                    .await?;
+                (None, finalized)
+            }
+            (Some(account_id), Some(Some(shared))) => {
+                let committed = self
+                    .api_account(
+                        shared.account.clone(),
+                        account_id,
+                        shared.last_update_block,
+                        &mut transaction,
+                    )
+                    .await?;
+                let finalized = self
+                    .load_finalized_account(account_id, &mut transaction)
+                    .await?;
+                (Some(committed), finalized)
+            }
+            (Some(account_id), None) => {
+                let (finalized_state, committed_state) = transaction
                     .chain()
                     .account_schema()
-                    .last_committed_block_with_update_for_acc(account_id, finalized_block)
+                    .last_committed_state_for_account(account_id)
                     .await
                     .map_err(Error::storage)?;
-                Some(
-                    self.api_account(account, account_id, last_block, &mut transaction)
-                        .await?,
-                )
-            } else {
-                None
-            };
-            (committed, finalized)
-        } else {
-            (None, None)
+
+                let finalized_block = BlockNumber(finalized_state.0 as u32);
+                let finalized = if let Some(account) = finalized_state.1 {
+                    Some(
+                        self.api_account(account, account_id, finalized_block, &mut transaction)
+                            .await?,
+                    )
+                } else {
+                    None
+                };
+                let committed = if let Some(account) = committed_state {
+                    let last_block = transaction
+                        .chain()
+                        .account_schema()
+                        .last_committed_block_with_update_for_acc(account_id, finalized_block)
+                        .await
+                        .map_err(Error::storage)?;
+                    Some(
+                        self.api_account(account, account_id, last_block, &mut transaction)
+                            .await?,
+                    )
+                } else {
+                    None
+                };
+                (committed, finalized)
+            }
+            (None, _) => (None, None),
         };
         transaction.commit().await.map_err(Error::storage)?;
         Ok(AccountState {
@@ -290,6 +449,79 @@ impl ApiAccountData {
         })
     }
 
+    /// Loads the states of all the accounts at once, the order of addresses is preserved.
+    async fn accounts_batch_info(
+        &self,
+        addresses: Vec<Address>,
+    ) -> Result<Vec<AccountBatchState>, Error> {
+        if addresses.len() as u64 > self.max_accounts_per_batch_request {
+            return Err(Error::from(InvalidDataError::TooManyAccountsRequested(
+                self.max_accounts_per_batch_request,
+            )));
+        }
+
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
+        let account_ids = transaction
+            .chain()
+            .account_schema()
+            .account_ids_by_addresses(&addresses)
+            .await
+            .map_err(Error::storage)?;
+        let ids: Vec<AccountId> = account_ids.values().copied().collect();
+        let mut states = transaction
+            .chain()
+            .account_schema()
+            .account_states_by_ids(&ids)
+            .await
+            .map_err(Error::storage)?;
+
+        let mut result = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let account_id = account_ids.get(&address).copied();
+            let state = account_id.and_then(|id| states.remove(&id).map(|state| (id, state)));
+            let (committed, finalized) = if let Some((account_id, state)) = state {
+                let account_type: Option<EthAccountType> = state.account_type.map(|t| t.into());
+                let committed = match state.committed {
+                    Some((block, account)) => Some(
+                        self.api_account_with_type(
+                            account,
+                            account_id,
+                            block,
+                            account_type.clone(),
+                            &mut transaction,
+                        )
+                        .await?,
+                    ),
+                    None => None,
+                };
+                let finalized = match state.finalized {
+                    Some((block, account)) => Some(
+                        self.api_account_with_type(
+                            account,
+                            account_id,
+                            block,
+                            account_type,
+                            &mut transaction,
+                        )
+                        .await?,
+                    ),
+                    None => None,
+                };
+                (committed, finalized)
+            } else {
+                (None, None)
+            };
+            result.push(AccountBatchState {
+                address,
+                committed,
+                finalized,
+            });
+        }
+        transaction.commit().await.map_err(Error::storage)?;
+        Ok(result)
+    }
+
     async fn account_txs(
         &self,
         query: PaginationQuery<ApiEither<TxHash>>,
@@ -298,18 +530,7 @@ impl ApiAccountData {
         second_address: Option<Address>,
     ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
         let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-        let token = if let Some(token_like) = token_like {
-            Some(
-                self.tokens
-                    .get_token(&mut storage, token_like.clone())
-                    .await
-                    .map_err(Error::storage)?
-                    .ok_or_else(|| Error::from(PriceError::token_not_found(token_like)))?
-                    .id,
-            )
-        } else {
-            None
-        };
+        let token = self.token_id(&mut storage, token_like).await?;
         let new_query = PaginationQuery {
             from: AccountTxsRequest {
                 tx_hash: query.from,
@@ -323,6 +544,316 @@ impl ApiAccountData {
         storage.paginate_checked(&new_query).await
     }
 
+    async fn account_txs_by_cursor(
+        &self,
+        query: PaginationQuery<Option<Cursor>>,
+        address: Address,
+        token_like: Option<TokenLike>,
+        second_address: Option<Address>,
+    ) -> Result<CursorPaginated<Transaction>, Error> {
+        if query.limit > MAX_LIMIT {
+            return Err(Error::from(InvalidDataError::PaginationLimitTooBig));
+        }
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let token = self.token_id(&mut storage, token_like).await?;
+        let new_query = PaginationQuery {
+            from: AccountTxsCursorRequest {
+                address,
+                cursor: query.from,
+                token,
+                second_address,
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+        storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_by_cursor(&new_query)
+            .await
+            .map_err(Error::storage)
+    }
+
+    /// Loads the changes of the account state in the range of blocks. The range is cut to
+    /// `MAX_STATE_CHANGES_BLOCK_RANGE` blocks, the actual last block is returned in the response.
+    async fn account_state_changes(
+        &self,
+        account_id: Option<AccountId>,
+        query: IncomingAccountStateChangesQuery,
+    ) -> Result<AccountStateChanges, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let from_block = query.from_block.unwrap_or(0);
+        let to_block = match query.to_block {
+            Some(to_block) => to_block,
+            None => *storage
+                .chain()
+                .block_schema()
+                .get_last_committed_block()
+                .await
+                .map_err(Error::storage)?,
+        };
+        if from_block > to_block {
+            return Err(Error::from(InvalidDataError::InvalidBlockRange));
+        }
+        let to_block = to_block.min(from_block.saturating_add(MAX_STATE_CHANGES_BLOCK_RANGE - 1));
+
+        let changes = match account_id {
+            Some(account_id) => storage
+                .chain()
+                .state_schema()
+                .load_account_state_changes(
+                    account_id,
+                    BlockNumber(from_block),
+                    BlockNumber(to_block),
+                )
+                .await
+                .map_err(Error::storage)?
+                .into_iter()
+                .map(|(block_number, update)| AccountStateChange {
+                    block_number,
+                    change: update.into(),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(AccountStateChanges {
+            from_block: BlockNumber(from_block),
+            to_block: BlockNumber(to_block),
+            changes,
+        })
+    }
+
+    /// The committed nonce of the account may be behind the transactions it has already sent,
+    /// so the queued transactions with the contiguous nonces starting from the committed one
+    /// are taken into account. The queued transactions after a gap can't be executed yet.
+    async fn account_next_nonce(
+        &self,
+        account_id: Option<AccountId>,
+    ) -> Result<AccountNextNonce, Error> {
+        let account_id = match account_id {
+            Some(account_id) => account_id,
+            None => {
+                return Ok(AccountNextNonce {
+                    committed_nonce: Nonce(0),
+                    next_nonce: Nonce(0),
+                })
+            }
+        };
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let (_, account) = storage
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(account_id)
+            .await
+            .map_err(Error::storage)?;
+        let committed_nonce = account.map(|account| account.nonce).unwrap_or(Nonce(0));
+        let queued_nonces = storage
+            .chain()
+            .mempool_schema()
+            .get_queued_nonces(account_id)
+            .await
+            .map_err(Error::storage)?;
+
+        let mut next_nonce = committed_nonce;
+        for nonce in queued_nonces {
+            if nonce == next_nonce {
+                *next_nonce += 1;
+            } else if nonce > next_nonce {
+                break;
+            }
+        }
+        Ok(AccountNextNonce {
+            committed_nonce,
+            next_nonce,
+        })
+    }
+
+    async fn token_id(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_like: Option<TokenLike>,
+    ) -> Result<Option<TokenId>, Error> {
+        let token_like = match token_like {
+            Some(token_like) => token_like,
+            None => return Ok(None),
+        };
+        let token = self
+            .tokens
+            .resolve_token(storage, token_like)
+            .await
+            .map_err(Error::from)?;
+        Ok(Some(token.id))
+    }
+
+    async fn pubkey_auth_status(
+        &self,
+        address: Address,
+        nonce: Nonce,
+    ) -> Result<PubKeyAuthStatus, Error> {
+        let fact = self
+            .eth_checker
+            .pubkey_auth_fact(address, nonce)
+            .await
+            .map_err(Error::from)?;
+        let mut status = PubKeyAuthStatus {
+            address,
+            nonce,
+            fact: None,
+            eth_tx_hash: None,
+            eth_block: None,
+            confirmations: None,
+        };
+        // The contract returns an empty fact if there's no authorization.
+        if fact.is_empty() {
+            return Ok(status);
+        }
+        let fact = H256::from_slice(&fact);
+        status.fact = Some(fact);
+
+        let current_block = self
+            .eth_head
+            .get()
+            .await
+            .ok_or_else(|| Error::from(anyhow::anyhow!("Ethereum node is not available")))?
+            .block_number;
+        let key = (address, nonce, fact);
+        let mut search = self.pubkey_auth_events.get(&key).unwrap_or_default();
+        if search.event.is_none() {
+            let from_block = search
+                .searched_to
+                .map_or(0, |block| block + 1)
+                .max(current_block.saturating_sub(PUBKEY_AUTH_EVENT_BLOCK_RANGE - 1));
+            if from_block <= current_block {
+                search.event = self
+                    .eth_checker
+                    .pubkey_auth_event(
+                        self.contract_address,
+                        address,
+                        nonce,
+                        from_block,
+                        current_block,
+                    )
+                    .await
+                    .map_err(Error::from)?;
+                search.searched_to = Some(current_block);
+                self.pubkey_auth_events.insert(key, search);
+            }
+        }
+        if let Some((eth_block, eth_tx_hash)) = search.event {
+            status.eth_tx_hash = Some(eth_tx_hash);
+            status.eth_block = Some(eth_block);
+            status.confirmations = Some(current_block.saturating_sub(eth_block) + 1);
+        }
+        Ok(status)
+    }
+
+    /// Queues the generation of the exit proof of the account balance against the last finalized
+    /// state, unless the up to date proof is already generated or queued. Returns its state.
+    async fn request_exit_proof(
+        &self,
+        account_id: Option<AccountId>,
+        token_like: TokenLike,
+    ) -> Result<ExitProofInfo, Error> {
+        let account_id =
+            account_id.ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))?;
+        let mut storage = self
+            .main_database_pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?;
+        let token = self
+            .tokens
+            .resolve_token(&mut storage, token_like)
+            .await
+            .map_err(Error::from)?;
+        let account = storage
+            .chain()
+            .account_schema()
+            .last_verified_state_for_account(account_id)
+            .await
+            .map_err(Error::storage)?;
+        let account = account.ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))?;
+        // The proofs of the zero balances are useless and only take the generator time.
+        if account.get_balance(token.id).is_zero() {
+            return Err(Error::from(InvalidDataError::ZeroExitBalance));
+        }
+        let last_finalized_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(Error::storage)?;
+
+        storage
+            .exit_proofs_schema()
+            .request_exit_proof(account_id, token.id, last_finalized_block)
+            .await
+            .map_err(Error::storage)?
+            .into_info()
+            .map_err(Error::storage)
+    }
+
+    /// Returns the state of the requested exit proof, `None` if it's never been requested.
+    async fn exit_proof(
+        &self,
+        account_id: Option<AccountId>,
+        token_like: TokenLike,
+    ) -> Result<Option<ExitProofInfo>, Error> {
+        let account_id = match account_id {
+            Some(account_id) => account_id,
+            None => return Ok(None),
+        };
+        let mut storage = self
+            .main_database_pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?;
+        let token = self
+            .tokens
+            .resolve_token(&mut storage, token_like)
+            .await
+            .map_err(Error::from)?;
+        storage
+            .exit_proofs_schema()
+            .load_exit_proof(account_id, token.id)
+            .await
+            .map_err(Error::storage)?
+            .map(|exit_proof| exit_proof.into_info())
+            .transpose()
+            .map_err(Error::storage)
+    }
+
+    async fn account_full_exits(&self, address: Address) -> Result<AccountFullExits, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut full_exits = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_full_exits(address, MAX_LIMIT)
+            .await
+            .map_err(Error::storage)?;
+
+        let awaits_confirmations = |status| status == FullExitStatus::AwaitingConfirmations;
+        if full_exits
+            .iter()
+            .any(|full_exit| awaits_confirmations(full_exit.status))
+        {
+            let eth_head = self.eth_head.get().await;
+            for full_exit in &mut full_exits {
+                if awaits_confirmations(full_exit.status) {
+                    let expected_accept_block =
+                        full_exit.received_on_block + self.confirmations_for_full_exit;
+                    full_exit.remaining_confirmations =
+                        eth_head.map(|head| head.remaining_confirmations(expected_accept_block));
+                }
+            }
+        }
+
+        Ok(AccountFullExits {
+            address,
+            full_exits,
+        })
+    }
+
     /// Pending deposits can be matched only with addresses,
     /// while pending full exits can be matched only with account ids.
     /// If the account isn't created yet it doesn't have an id
@@ -347,54 +878,123 @@ impl ApiAccountData {
     }
 }
 
-async fn account_committed_info(
-    data: web::Data<ApiAccountData>,
+/// Source of the account states, so the handlers serve both the stored accounts
+/// and the synthetic ones of the sandbox.
+#[async_trait(?Send)]
+pub(super) trait AccountStateSource: FromRequest + 'static {
+    async fn committed_account(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<Option<Account>, Error>;
+
+    async fn finalized_account(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<Option<Account>, Error>;
+
+    async fn account_state(
+        &self,
+        address_or_id: AccountAddressOrId,
+        depositing_version: DepositingBalancesVersion,
+    ) -> Result<AccountState, Error>;
+
+    async fn next_nonce(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<AccountNextNonce, Error>;
+}
+
+#[async_trait(?Send)]
+impl AccountStateSource for web::Data<ApiAccountData> {
+    async fn committed_account(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<Option<Account>, Error> {
+        match self.get_id_by_address_or_id(address_or_id).await? {
+            Some(account_id) => self.account_committed_info(account_id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn finalized_account(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<Option<Account>, Error> {
+        match self.get_id_by_address_or_id(address_or_id).await? {
+            Some(account_id) => self.account_finalized_info(account_id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn account_state(
+        &self,
+        address_or_id: AccountAddressOrId,
+        depositing_version: DepositingBalancesVersion,
+    ) -> Result<AccountState, Error> {
+        let address = self
+            .get_address_by_address_or_id(address_or_id.clone())
+            .await?;
+        let account_id = self.get_id_by_address_or_id(address_or_id).await?;
+        self.account_full_info(address, account_id, depositing_version)
+            .await
+    }
+
+    async fn next_nonce(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<AccountNextNonce, Error> {
+        let account_id = self.get_id_by_address_or_id(address_or_id).await?;
+        self.account_next_nonce(account_id).await
+    }
+}
+
+pub(super) async fn account_committed_info<D: AccountStateSource>(
+    data: D,
     account_id_or_address: web::Path<String>,
 ) -> ApiResult<Option<Account>> {
     let start = Instant::now();
-    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
-    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
-    let res = if let Some(account_id) = account_id {
-        data.account_committed_info(account_id).await.into()
-    } else {
-        ApiResult::Ok(None)
-    };
+    let address_or_id = api_try!(parse_account_id_or_address(&account_id_or_address));
+    let res = data.committed_account(address_or_id).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_committed_info");
     res
 }
 
-async fn account_finalized_info(
-    data: web::Data<ApiAccountData>,
+pub(super) async fn account_finalized_info<D: AccountStateSource>(
+    data: D,
     account_id_or_address: web::Path<String>,
 ) -> ApiResult<Option<Account>> {
     let start = Instant::now();
-    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
-    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
-    let res = if let Some(account_id) = account_id {
-        data.account_finalized_info(account_id).await.into()
-    } else {
-        ApiResult::Ok(None)
-    };
+    let address_or_id = api_try!(parse_account_id_or_address(&account_id_or_address));
+    let res = data.finalized_account(address_or_id).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_finalized_info");
     res
 }
 
-async fn account_full_info(
-    data: web::Data<ApiAccountData>,
+pub(super) async fn account_full_info<D: AccountStateSource>(
+    data: D,
     account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<AccountStateQuery>,
 ) -> ApiResult<AccountState> {
     let start = Instant::now();
-    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
-    let address = api_try!(
-        data.get_address_by_address_or_id(address_or_id.clone())
-            .await
-    );
-    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
-    let res = data.account_full_info(address, account_id).await.into();
+    let address_or_id = api_try!(parse_account_id_or_address(&account_id_or_address));
+    let res = data
+        .account_state(address_or_id, query.depositing_version)
+        .await
+        .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_full_info");
     res
 }
 
+async fn accounts_batch_info(
+    data: web::Data<ApiAccountData>,
+    web::Json(request): web::Json<AccountsBatchRequest>,
+) -> ApiResult<Vec<AccountBatchState>> {
+    let start = Instant::now();
+    let res = data.accounts_batch_info(request.addresses).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "accounts_batch_info");
+    res
+}
+
 async fn account_txs(
     data: web::Data<ApiAccountData>,
     account_id_or_address: web::Path<String>,
@@ -430,6 +1030,44 @@ async fn account_txs(
     res
 }
 
+async fn account_txs_by_cursor(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<IncomingAccountTxsCursorQuery>,
+) -> ApiResult<CursorPaginated<Transaction>> {
+    let start = Instant::now();
+    let cursor = match query.from {
+        Some(from) => Some(api_try!(Cursor::from_str(&from).map_err(Error::from))),
+        None => None,
+    };
+    let pagination = PaginationQuery {
+        from: cursor,
+        limit: query.limit,
+        direction: query.direction,
+    };
+
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+
+    let second_address = if let Some(second_account) = query.second_account {
+        let address_or_id = api_try!(data.parse_account_id_or_address(&second_account));
+        Some(api_try!(
+            data.get_address_by_address_or_id(address_or_id).await
+        ))
+    } else {
+        None
+    };
+
+    let token_like = query.token.map(|token| TokenLike::parse(&token));
+
+    let res = data
+        .account_txs_by_cursor(pagination, address, token_like, second_address)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_txs_by_cursor");
+    res
+}
+
 async fn account_pending_txs(
     data: web::Data<ApiAccountData>,
     account_id_or_address: web::Path<String>,
@@ -451,38 +1089,171 @@ async fn account_pending_txs(
     res
 }
 
+async fn account_state_changes(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<IncomingAccountStateChangesQuery>,
+) -> ApiResult<AccountStateChanges> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = data.account_state_changes(account_id, query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_state_changes");
+    res
+}
+
+pub(super) async fn account_next_nonce<D: AccountStateSource>(
+    data: D,
+    account_id_or_address: web::Path<String>,
+) -> ApiResult<AccountNextNonce> {
+    let start = Instant::now();
+    let address_or_id = api_try!(parse_account_id_or_address(&account_id_or_address));
+    let res = data.next_nonce(address_or_id).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_next_nonce");
+    res
+}
+
+async fn account_pubkey_auth(
+    data: web::Data<ApiAccountData>,
+    path: web::Path<(String, u32)>,
+) -> ApiResult<PubKeyAuthStatus> {
+    let start = Instant::now();
+    let (account_id_or_address, nonce) = path.into_inner();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data.pubkey_auth_status(address, Nonce(nonce)).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_pubkey_auth");
+    res
+}
+
+async fn account_request_exit_proof(
+    data: web::Data<ApiAccountData>,
+    path: web::Path<(String, String)>,
+) -> ApiResult<ExitProofInfo> {
+    let start = Instant::now();
+    let (account_id_or_address, token_like) = path.into_inner();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = data
+        .request_exit_proof(account_id, TokenLike::parse(&token_like))
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_request_exit_proof");
+    res
+}
+
+async fn account_exit_proof(
+    data: web::Data<ApiAccountData>,
+    path: web::Path<(String, String)>,
+) -> ApiResult<Option<ExitProofInfo>> {
+    let start = Instant::now();
+    let (account_id_or_address, token_like) = path.into_inner();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = data
+        .exit_proof(account_id, TokenLike::parse(&token_like))
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_exit_proof");
+    res
+}
+
+async fn account_full_exits(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+) -> ApiResult<AccountFullExits> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data.account_full_exits(address).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_full_exits");
+    res
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn api_scope(
     pool: ConnectionPool,
+    main_database_pool: ConnectionPool,
     tokens: TokenDBCache,
     confirmations_for_eth_event: u64,
+    confirmations_for_full_exit: u64,
+    max_accounts_per_batch_request: u64,
+    committed_state: Option<SharedCommittedState>,
+    eth_checker: EthereumChecker,
+    eth_head: EthHeadCache,
+    contract_address: Address,
 ) -> Scope {
-    let data = ApiAccountData::new(pool, tokens, confirmations_for_eth_event);
+    let data = ApiAccountData::new(
+        pool,
+        main_database_pool,
+        tokens,
+        confirmations_for_eth_event,
+        confirmations_for_full_exit,
+        max_accounts_per_batch_request,
+        committed_state,
+        eth_checker,
+        eth_head,
+        contract_address,
+    );
 
     web::scope("accounts")
         .app_data(web::Data::new(data))
+        .route("batch", web::post().to(accounts_batch_info))
         .route(
             "{account_id_or_address}/committed",
-            web::get().to(account_committed_info),
+            web::get().to(account_committed_info::<web::Data<ApiAccountData>>),
         )
         .route(
             "{account_id_or_address}/finalized",
-            web::get().to(account_finalized_info),
+            web::get().to(account_finalized_info::<web::Data<ApiAccountData>>),
+        )
+        .route(
+            "{account_id_or_address}",
+            web::get().to(account_full_info::<web::Data<ApiAccountData>>),
         )
-        .route("{account_id_or_address}", web::get().to(account_full_info))
         .route(
             "{account_id_or_address}/transactions",
             web::get().to(account_txs),
         )
+        .route(
+            "{account_id_or_address}/transactions/history",
+            web::get().to(account_txs_by_cursor),
+        )
         .route(
             "{account_id_or_address}/transactions/pending",
             web::get().to(account_pending_txs),
         )
+        .route(
+            "{account_id_or_address}/events",
+            web::get().to(account_state_changes),
+        )
+        .route(
+            "{account_id_or_address}/nonce/next",
+            web::get().to(account_next_nonce::<web::Data<ApiAccountData>>),
+        )
+        .route(
+            "{account_id_or_address}/pubkey_auth/{nonce}",
+            web::get().to(account_pubkey_auth),
+        )
+        .route(
+            "{account_id_or_address}/exit_proof/{token}",
+            web::post().to(account_request_exit_proof),
+        )
+        .route(
+            "{account_id_or_address}/exit_proof/{token}",
+            web::get().to(account_exit_proof),
+        )
+        .route(
+            "{account_id_or_address}/full_exits",
+            web::get().to(account_full_exits),
+        )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
+        error::ErrorCode,
         test_utils::{deserialize_response_result, TestServerConfig},
         SharedData,
     };
@@ -490,13 +1261,19 @@ mod tests {
     use serde::Deserialize;
     use zksync_api_client::rest::client::Client;
     use zksync_api_types::v02::{
-        account::{DepositingAccountBalances, DepositingFunds},
+        account::{
+            DepositProgress, DepositingAccountBalances, DepositingFunds, DepositingTokenFunds,
+        },
         pagination::{PaginationDirection, PaginationQuery},
         transaction::{L1Transaction, TransactionData},
         ApiVersion,
     };
+    use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
     use zksync_storage::StorageProcessor;
-    use zksync_types::{AccountId, Address, Deposit, PriorityOp, TokenId, ZkSyncPriorityOp, H256};
+    use zksync_types::{
+        tx::Transfer, AccountId, Address, Deposit, PriorityOp, SignedZkSyncTx, TokenId,
+        ZkSyncPriorityOp, ZkSyncTx, H256,
+    };
 
     // While the values of the PendingOpsFlattenRequest's fields are never directly
     // used in the tests, we still need them to specify the JSON format of the `unconfirmed_ops` endpoint input in tests.
@@ -514,6 +1291,7 @@ mod tests {
         api_server: actix_test::TestServer,
         pool: ConnectionPool,
         confirmations_for_eth_event: u64,
+        max_accounts_per_batch_request: u64,
     }
 
     impl TestServer {
@@ -530,11 +1308,18 @@ mod tests {
             let (api_client, api_server) = cfg.start_server(
                 move |cfg: &TestServerConfig| {
                     api_scope(
+                        cfg.pool.clone(),
                         cfg.pool.clone(),
                         TokenDBCache::new(
                             cfg.config.api.token_config.invalidate_token_cache_period(),
                         ),
                         cfg.config.eth_watch.confirmations_for_eth_event,
+                        cfg.config.eth_watch.confirmations_for_full_exit,
+                        cfg.config.api.common.max_accounts_per_batch_request,
+                        None,
+                        EthereumChecker::new(EthereumGateway::Mock(MockEthereum::default())),
+                        EthHeadCache::new(EthereumGateway::Mock(MockEthereum::default())),
+                        cfg.config.contracts.contract_addr,
                     )
                 },
                 Some(shared_data),
@@ -546,6 +1331,11 @@ mod tests {
                     api_server,
                     pool,
                     confirmations_for_eth_event: cfg.config.eth_watch.confirmations_for_eth_event,
+                    max_accounts_per_batch_request: cfg
+                        .config
+                        .api
+                        .common
+                        .max_accounts_per_batch_request,
                 },
             ))
         }
@@ -671,9 +1461,26 @@ mod tests {
         )]
         .into_iter()
         .collect();
-        let expected_depositing = DepositingAccountBalances { balances };
+        // The mocked Ethereum node is at the block 1 and doesn't provide the block timestamps.
+        let expected_accept_block = 25 + server.confirmations_for_eth_event;
+        let deposit = DepositProgress {
+            eth_tx_hash: H256::zero(),
+            token_id: TokenId(0),
+            amount: BigUint::from(100500u32),
+            received_on_block: 25,
+            expected_accept_block,
+            remaining_confirmations: Some(expected_accept_block - 1),
+            eta_seconds: None,
+        };
+        let expected_depositing = DepositingAccountBalances {
+            balances,
+            balances_by_token_id: None,
+            deposits: vec![deposit; 3],
+        };
 
-        let response = client.account_full_info(&format!("{:?}", address)).await?;
+        let response = client
+            .account_full_info(&format!("{:?}", address), DepositingBalancesVersion::V1)
+            .await?;
         let account_full_info: AccountState = deserialize_response_result(response)?;
         assert_eq!(
             account_full_info.committed,
@@ -682,6 +1489,60 @@ mod tests {
         assert_eq!(account_full_info.finalized, account_finalized_info);
         assert_eq!(account_full_info.depositing, expected_depositing);
 
+        let response = client
+            .account_full_info(&format!("{:?}", address), DepositingBalancesVersion::V2)
+            .await?;
+        let account_full_info: AccountState = deserialize_response_result(response)?;
+        let expected_by_token_id = vec![(
+            TokenId(0),
+            DepositingTokenFunds {
+                symbol: String::from("ETH"),
+                amount: BigUint::from(301500u32),
+                expected_accept_block: 25 + server.confirmations_for_eth_event,
+            },
+        )]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            account_full_info.depositing.balances_by_token_id,
+            Some(expected_by_token_id)
+        );
+        assert_eq!(
+            account_full_info.depositing.balances,
+            expected_depositing.balances
+        );
+
+        let unknown_address = Address::random();
+        let response = client
+            .accounts_batch_info(vec![unknown_address, address])
+            .await?;
+        let batch_info: Vec<AccountBatchState> = deserialize_response_result(response)?;
+        assert_eq!(
+            batch_info,
+            vec![
+                AccountBatchState {
+                    address: unknown_address,
+                    committed: None,
+                    finalized: None,
+                },
+                AccountBatchState {
+                    address,
+                    committed: account_full_info.committed,
+                    finalized: account_full_info.finalized,
+                },
+            ]
+        );
+
+        let max_accounts = server.max_accounts_per_batch_request;
+        let response = client
+            .accounts_batch_info(vec![address; max_accounts as usize + 1])
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(InvalidDataError::TooManyAccountsRequested(max_accounts))
+        );
+
         let query = PaginationQuery {
             from: ApiEither::from(tx_hash),
             limit: 1,
@@ -691,6 +1552,18 @@ mod tests {
         let txs: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
         assert_eq!(txs.list[0].tx_hash, tx_hash);
 
+        let query = PaginationQuery {
+            from: None,
+            limit: 1,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_txs_by_cursor(&query, &account_id.to_string())
+            .await?;
+        let txs: CursorPaginated<Transaction> = deserialize_response_result(response)?;
+        assert_eq!(txs.list.len(), 1);
+        assert_eq!(txs.pagination.from, None);
+
         let query = PaginationQuery {
             from: ApiEither::from_str("latest").unwrap(),
             limit: 2,
@@ -711,6 +1584,122 @@ mod tests {
             _ => panic!("account_pending_txs returned L2 tx"),
         }
 
+        // Get account state changes.
+        let (updated_account_id, expected_changes) = {
+            let mut storage = server.pool.access_storage().await?;
+            let updated_account_id = storage
+                .chain()
+                .state_schema()
+                .load_state_diff_for_block(BlockNumber(1))
+                .await?[0]
+                .0;
+            let expected_changes = storage
+                .chain()
+                .state_schema()
+                .load_account_state_changes(updated_account_id, BlockNumber(1), BlockNumber(1))
+                .await?;
+            (updated_account_id, expected_changes)
+        };
+        let query = IncomingAccountStateChangesQuery {
+            from_block: Some(1),
+            to_block: Some(1),
+        };
+        let response = client
+            .account_state_changes(&query, &updated_account_id.to_string())
+            .await?;
+        let state_changes: AccountStateChanges = deserialize_response_result(response)?;
+        assert_eq!(state_changes.from_block, BlockNumber(1));
+        assert_eq!(state_changes.to_block, BlockNumber(1));
+        assert!(!state_changes.changes.is_empty());
+        assert_eq!(state_changes.changes.len(), expected_changes.len());
+        for (change, (block_number, update)) in
+            state_changes.changes.into_iter().zip(expected_changes)
+        {
+            assert_eq!(change.block_number, block_number);
+            assert_eq!(change.change, update.into());
+        }
+
+        let query = IncomingAccountStateChangesQuery {
+            from_block: Some(2),
+            to_block: Some(1),
+        };
+        let response = client
+            .account_state_changes(&query, &account_id.to_string())
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidBlockRange);
+
+        // Get the next nonce of the account, taking the queued transactions into account.
+        let response = client.account_next_nonce(&account_id.to_string()).await?;
+        let nonce: AccountNextNonce = deserialize_response_result(response)?;
+        assert!(nonce.next_nonce >= nonce.committed_nonce);
+
+        let queue_tx = |nonce| {
+            let transfer = Transfer::new(
+                account_id,
+                Address::random(),
+                Address::random(),
+                TokenId(0),
+                100u32.into(),
+                10u32.into(),
+                nonce,
+                Default::default(),
+                None,
+            );
+            SignedZkSyncTx {
+                tx: ZkSyncTx::Transfer(Box::new(transfer)),
+                eth_sign_data: None,
+                created_at: chrono::Utc::now(),
+            }
+        };
+        // The queued transaction after the gap doesn't change the next nonce.
+        let gapped_tx = queue_tx(Nonce(*nonce.next_nonce + 2));
+        let next_tx = queue_tx(nonce.next_nonce);
+        {
+            let mut storage = server.pool.access_storage().await?;
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_tx(&gapped_tx)
+                .await?;
+        }
+        let response = client.account_next_nonce(&account_id.to_string()).await?;
+        let gapped_nonce: AccountNextNonce = deserialize_response_result(response)?;
+        {
+            let mut storage = server.pool.access_storage().await?;
+            storage.chain().mempool_schema().insert_tx(&next_tx).await?;
+        }
+        let response = client.account_next_nonce(&account_id.to_string()).await?;
+        let queued_nonce: AccountNextNonce = deserialize_response_result(response)?;
+        {
+            let mut storage = server.pool.access_storage().await?;
+            for tx in &[gapped_tx, next_tx] {
+                storage
+                    .chain()
+                    .mempool_schema()
+                    .remove_tx(tx.hash().as_ref())
+                    .await?;
+            }
+        }
+        assert_eq!(gapped_nonce, nonce);
+        assert_eq!(queued_nonce.committed_nonce, nonce.committed_nonce);
+        assert_eq!(queued_nonce.next_nonce, Nonce(*nonce.next_nonce + 1));
+
+        let response = client
+            .account_next_nonce(&format!("{:?}", Address::random()))
+            .await?;
+        let nonce: AccountNextNonce = deserialize_response_result(response)?;
+        assert_eq!(nonce.next_nonce, Nonce(0));
+
+        // The unknown address doesn't have full exits.
+        let unknown_address = Address::random();
+        let response = client
+            .account_full_exits(&format!("{:?}", unknown_address))
+            .await?;
+        let full_exits: AccountFullExits = deserialize_response_result(response)?;
+        assert_eq!(full_exits.address, unknown_address);
+        assert!(full_exits.full_exits.is_empty());
+
         server.stop().await;
         Ok(())
     }
@@ -0,0 +1,239 @@
+//! Read path of the cold storage with the records pruned from the database.
+//!
+//! Rejected transactions are exported to the S3-compatible bucket by the cleaner of the core
+//! before their removal, as JSON blobs indexed by the transaction hash, see `ArchivedTx`.
+//! The blobs are authenticated with the key shared by the core and the API, and the ones
+//! not signed with it are treated as missing, so the writable bucket is not trusted.
+//! The archive is only queried once the record is not found in the database, thus it only
+//! affects the latency of the requests for the unknown or pruned transactions. The misses are
+//! cached for a while, so the repeated requests for the unknown transactions don't reach it.
+
+// Built-in uses
+use std::time::{Duration, Instant};
+
+// External uses
+use reqwest::StatusCode;
+
+// Workspace uses
+use zksync_api_types::v02::transaction::{ArchivedTx, SignedArchivedTx};
+use zksync_config::configs::api::ArchiveConfig;
+use zksync_types::{tx::TxHash, H256};
+
+// Local uses
+use crate::utils::shared_lru_cache::SharedLruCache;
+
+/// Maximum number of the transactions remembered as missing in the archive.
+const NEGATIVE_CACHE_CAPACITY: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct ArchiveClient {
+    client: reqwest::Client,
+    url: String,
+    /// Transactions missing in the archive along with the moment of the request.
+    misses: SharedLruCache<TxHash, Instant>,
+    negative_cache_period: Duration,
+    signing_key: H256,
+}
+
+impl ArchiveClient {
+    /// Returns `None` if the archive is disabled.
+    pub fn new(config: &ArchiveConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout())
+            .build()
+            .expect("Failed to create the archive HTTP client");
+        Some(Self {
+            client,
+            url: config.url.trim_end_matches('/').to_owned(),
+            misses: SharedLruCache::new(NEGATIVE_CACHE_CAPACITY),
+            negative_cache_period: config.negative_cache_period(),
+            signing_key: config.signing_key,
+        })
+    }
+
+    fn tx_url(&self, tx_hash: TxHash) -> String {
+        format!("{}/{}", self.url, ArchivedTx::key(tx_hash))
+    }
+
+    fn is_known_miss(&self, tx_hash: TxHash) -> bool {
+        match self.misses.get(&tx_hash) {
+            Some(requested_at) => requested_at.elapsed() < self.negative_cache_period,
+            None => false,
+        }
+    }
+
+    /// Loads the archived transaction, returns `None` if there is no such blob.
+    ///
+    /// The archive is only a fallback for the records missing in the database, so its failures
+    /// are reported as the missing transaction rather than the storage error.
+    pub async fn load_tx(&self, tx_hash: TxHash) -> Option<ArchivedTx> {
+        if self.is_known_miss(tx_hash) {
+            metrics::increment_counter!("api.v02.archive.cached_misses");
+            return None;
+        }
+
+        let start = Instant::now();
+        let tx = match self.request_tx(tx_hash).await {
+            Ok(tx) => tx,
+            Err(err) => {
+                vlog::warn!(
+                    "Unable to load the transaction {} from the archive: {}",
+                    tx_hash.to_string(),
+                    err
+                );
+                metrics::increment_counter!("api.v02.archive.errors");
+                return None;
+            }
+        };
+
+        metrics::histogram!("api.v02.archive.load_tx", start.elapsed());
+        if tx.is_some() {
+            metrics::increment_counter!("api.v02.archive.hits");
+        } else {
+            self.misses.insert(tx_hash, Instant::now());
+        }
+        tx
+    }
+
+    async fn request_tx(&self, tx_hash: TxHash) -> anyhow::Result<Option<ArchivedTx>> {
+        let response = self.client.get(self.tx_url(tx_hash)).send().await?;
+        let blob: SignedArchivedTx = match response.status() {
+            // Some of the S3-compatible storages respond with `403` to the missing keys
+            // if the listing of the bucket is not allowed.
+            StatusCode::NOT_FOUND | StatusCode::FORBIDDEN => return Ok(None),
+            _ => response.error_for_status()?.json().await?,
+        };
+        match blob.verify(tx_hash, &self.signing_key) {
+            Some(tx) => Ok(Some(tx)),
+            None => {
+                metrics::increment_counter!("api.v02.archive.invalid_blobs");
+                anyhow::bail!("Blob is not signed by the core")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_api_types::v02::transaction::{
+        L2Receipt, L2Transaction, Receipt, Transaction, TransactionData, TxData, TxInBlockStatus,
+    };
+    use zksync_types::{tx::Transfer, AccountId, Address, Nonce, TokenId};
+
+    use super::*;
+
+    fn archived_tx(tx_hash: TxHash) -> ArchivedTx {
+        let transfer = Transfer::new(
+            AccountId(1),
+            Address::random(),
+            Address::random(),
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        ArchivedTx {
+            receipt: Receipt::L2(L2Receipt {
+                tx_hash,
+                rollup_block: None,
+                status: TxInBlockStatus::Rejected,
+                fail_reason: Some("Not enough balance".into()),
+                fail_code: None,
+                block_index: None,
+            }),
+            data: TxData {
+                tx: Transaction {
+                    tx_hash,
+                    block_index: None,
+                    block_number: None,
+                    op: TransactionData::L2(L2Transaction::Transfer(Box::new(transfer))),
+                    status: TxInBlockStatus::Rejected,
+                    fail_reason: Some("Not enough balance".into()),
+                    created_at: None,
+                    batch_id: None,
+                },
+                eth_signature: None,
+            },
+        }
+    }
+
+    /// Checks that only the blobs signed with the key for the requested hash are accepted.
+    #[test]
+    fn archived_txs_are_authenticated() {
+        let key = H256::repeat_byte(0x22);
+        let tx_hash = TxHash::from_slice(&[0xab; 32]).unwrap();
+        let other_hash = TxHash::from_slice(&[0xcd; 32]).unwrap();
+        let tx = archived_tx(tx_hash);
+
+        let blob = tx.sign(tx_hash, &key).unwrap();
+        let verified = blob.verify(tx_hash, &key).unwrap();
+        assert_eq!(verified.receipt, tx.receipt);
+
+        // Signed with another key.
+        let blob = tx.sign(tx_hash, &H256::repeat_byte(0x33)).unwrap();
+        assert!(blob.verify(tx_hash, &key).is_none());
+        // Served for another transaction.
+        let blob = tx.sign(tx_hash, &key).unwrap();
+        assert!(blob.verify(other_hash, &key).is_none());
+        // Payload is altered.
+        let mut blob = tx.sign(tx_hash, &key).unwrap();
+        blob.payload = blob.payload.replace("Not enough balance", "Executed");
+        assert!(blob.verify(tx_hash, &key).is_none());
+    }
+
+    #[test]
+    fn archive_tx_url() {
+        let mut config = ArchiveConfig {
+            enabled: false,
+            export_enabled: false,
+            url: "http://127.0.0.1:9000/zksync-archive/".into(),
+            request_timeout_ms: 1000,
+            negative_cache_period_sec: 60,
+            signing_key: H256::repeat_byte(0x22),
+        };
+        assert!(ArchiveClient::new(&config).is_none());
+
+        config.enabled = true;
+        let client = ArchiveClient::new(&config).unwrap();
+        let tx_hash = TxHash::from_slice(&[0xab; 32]).unwrap();
+        assert_eq!(
+            client.tx_url(tx_hash),
+            format!(
+                "http://127.0.0.1:9000/zksync-archive/txs/{}.json",
+                "ab".repeat(32)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn archive_misses_are_cached() {
+        let config = ArchiveConfig {
+            enabled: true,
+            export_enabled: false,
+            // Nothing listens on this port, so every request fails.
+            url: "http://127.0.0.1:1/zksync-archive".into(),
+            request_timeout_ms: 1000,
+            negative_cache_period_sec: 60,
+            signing_key: H256::repeat_byte(0x22),
+        };
+        let client = ArchiveClient::new(&config).unwrap();
+        let tx_hash = TxHash::from_slice(&[0xab; 32]).unwrap();
+
+        // Failed requests are not cached, the archive may be available once again.
+        assert!(client.load_tx(tx_hash).await.is_none());
+        assert!(!client.is_known_miss(tx_hash));
+
+        client.misses.insert(tx_hash, Instant::now());
+        assert!(client.is_known_miss(tx_hash));
+        client
+            .misses
+            .insert(tx_hash, Instant::now() - config.negative_cache_period());
+        assert!(!client.is_known_miss(tx_hash));
+    }
+}
@@ -5,11 +5,12 @@ use std::str::FromStr;
 use std::time::Instant;
 
 // External uses
-use actix_web::{web, Scope};
+use actix_web::{web, FromRequest, Scope};
+use async_trait::async_trait;
 
 // Workspace uses
 use zksync_api_types::v02::{
-    block::{BlockInfo, BlockStatus},
+    block::{BlockInfo, BlockRangeQuery, BlockStatus, MAX_BLOCK_RANGE},
     pagination::{parse_query, ApiEither, BlockAndTxHash, Paginated, PaginationQuery},
     transaction::{Transaction, TxData, TxHashSerializeWrapper},
 };
@@ -19,6 +20,7 @@ use zksync_types::{tx::TxHash, BlockNumber, H256};
 
 // Local uses
 use super::{
+    block_stream::{blocks_stream, BlockStream},
     error::{Error, InvalidDataError},
     paginate_trait::Paginate,
     response::ApiResult,
@@ -108,6 +110,35 @@ impl ApiBlockData {
         storage.paginate_checked(&query).await
     }
 
+    /// Loads the committed blocks of the range with a single query.
+    async fn block_range(&self, query: BlockRangeQuery) -> Result<Vec<BlockInfo>, Error> {
+        if query.from > query.to {
+            return Err(Error::from(InvalidDataError::InvalidBlockRange));
+        }
+        // The bounds may be up to `u32::MAX`, so the size of the range is computed in `u64`.
+        let count = u64::from(query.to) - u64::from(query.from) + 1;
+        if count > u64::from(MAX_BLOCK_RANGE) {
+            return Err(Error::from(InvalidDataError::BlockRangeTooBig));
+        }
+        let count = count as u32;
+
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let blocks = storage
+            .chain()
+            .block_schema()
+            .load_block_range_asc(BlockNumber(query.from), count)
+            .await
+            .map_err(Error::storage)?
+            .into_iter()
+            .map(block_info_from_details)
+            .filter(|block| {
+                *block.block_number <= query.to
+                    && query.status.map_or(true, |status| block.status == status)
+            })
+            .collect();
+        Ok(blocks)
+    }
+
     async fn transaction_page(
         &self,
         block_number: BlockNumber,
@@ -173,15 +204,45 @@ async fn block_pagination(
     res
 }
 
+async fn block_range(
+    data: web::Data<ApiBlockData>,
+    web::Query(query): web::Query<BlockRangeQuery>,
+) -> ApiResult<Vec<BlockInfo>> {
+    let start = Instant::now();
+    let res = data.block_range(query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_range");
+    res
+}
+
+/// Source of the blocks, so the handlers serve both the stored blocks
+/// and the synthetic ones of the sandbox.
+#[async_trait(?Send)]
+pub(super) trait BlockSource: FromRequest + 'static {
+    async fn block_number(&self, block_position: &str) -> Result<BlockNumber, Error>;
+
+    async fn block(&self, block_number: BlockNumber) -> Result<Option<BlockInfo>, Error>;
+}
+
+#[async_trait(?Send)]
+impl BlockSource for web::Data<ApiBlockData> {
+    async fn block_number(&self, block_position: &str) -> Result<BlockNumber, Error> {
+        self.get_block_number_by_position(block_position).await
+    }
+
+    async fn block(&self, block_number: BlockNumber) -> Result<Option<BlockInfo>, Error> {
+        self.block_info(block_number).await
+    }
+}
+
 // TODO: take `block_position` as enum.
 // Currently actix path extractor doesn't work with enums: https://github.com/actix/actix-web/issues/318 (ZKS-628)
-async fn block_by_position(
-    data: web::Data<ApiBlockData>,
+pub(super) async fn block_by_position<D: BlockSource>(
+    data: D,
     block_position: web::Path<String>,
 ) -> ApiResult<Option<BlockInfo>> {
     let start = Instant::now();
-    let block_number = api_try!(data.get_block_number_by_position(&block_position).await);
-    let res = data.block_info(block_number).await.into();
+    let block_number = api_try!(data.block_number(&block_position).await);
+    let res = data.block(block_number).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_by_position");
     res
 }
@@ -210,13 +271,20 @@ async fn transaction_in_block(
     ApiResult::Ok(res)
 }
 
-pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache) -> Scope {
+pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache, stream: BlockStream) -> Scope {
     let data = ApiBlockData::new(pool, cache);
 
     web::scope("blocks")
         .app_data(web::Data::new(data))
+        .app_data(web::Data::new(stream))
         .route("", web::get().to(block_pagination))
-        .route("{block_position}", web::get().to(block_by_position))
+        // Should be registered before `{block_position}`, which would match them otherwise.
+        .route("stream", web::get().to(blocks_stream))
+        .route("range", web::get().to(block_range))
+        .route(
+            "{block_position}",
+            web::get().to(block_by_position::<web::Data<ApiBlockData>>),
+        )
         .route(
             "{block_position}/transactions",
             web::get().to(block_transactions),
@@ -231,9 +299,11 @@ pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache) -> Scope {
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
+        error::ErrorCode,
         test_utils::{deserialize_response_result, TestServerConfig},
         SharedData,
     };
+    use std::time::Duration;
     use zksync_api_types::v02::{
         pagination::PaginationDirection, transaction::TransactionData, ApiVersion,
     };
@@ -252,7 +322,13 @@ mod tests {
             api_version: ApiVersion::V02,
         };
         let (client, server) = cfg.start_server(
-            |cfg: &TestServerConfig| api_scope(cfg.pool.clone(), BlockDetailsCache::new(10)),
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    BlockDetailsCache::new(10),
+                    BlockStream::new(cfg.pool.clone(), Duration::from_millis(100)),
+                )
+            },
             Some(shared_data),
         );
 
@@ -277,6 +353,56 @@ mod tests {
         let paginated: Paginated<BlockInfo, BlockNumber> = deserialize_response_result(response)?;
         assert_eq!(paginated, expected_blocks);
 
+        let range_query = BlockRangeQuery {
+            from: 1,
+            to: 3,
+            status: None,
+        };
+        let response = client.block_range(&range_query).await?;
+        let blocks: Vec<BlockInfo> = deserialize_response_result(response)?;
+        assert_eq!(blocks, expected_blocks.list);
+
+        let range_query = BlockRangeQuery {
+            status: Some(BlockStatus::Finalized),
+            ..range_query
+        };
+        let response = client.block_range(&range_query).await?;
+        let blocks: Vec<BlockInfo> = deserialize_response_result(response)?;
+        let expected_finalized: Vec<_> = expected_blocks
+            .list
+            .iter()
+            .filter(|block| block.status == BlockStatus::Finalized)
+            .cloned()
+            .collect();
+        assert_eq!(blocks, expected_finalized);
+
+        let range_query = BlockRangeQuery {
+            from: 1,
+            to: MAX_BLOCK_RANGE + 1,
+            status: None,
+        };
+        let response = client.block_range(&range_query).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::BlockRangeTooBig);
+
+        let range_query = BlockRangeQuery {
+            from: 0,
+            to: u32::MAX,
+            status: None,
+        };
+        let response = client.block_range(&range_query).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::BlockRangeTooBig);
+
+        let range_query = BlockRangeQuery {
+            from: 3,
+            to: 1,
+            status: None,
+        };
+        let response = client.block_range(&range_query).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidBlockRange);
+
         let block_number = BlockNumber(3);
         let expected_txs = {
             let mut storage = cfg.pool.access_storage().await?;
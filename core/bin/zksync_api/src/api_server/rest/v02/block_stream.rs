@@ -0,0 +1,231 @@
+//! Server-Sent Events stream of the committed and finalized blocks.
+//!
+//! Block events are stored to the `events` table once the corresponding operation is
+//! confirmed on L1. Since the core and the API server may run in separate processes, the events
+//! are fetched from the database once per server and shared between all the connected clients
+//! through the broadcast channel.
+
+// Built-in uses
+use std::{time::Duration, time::Instant};
+
+// External uses
+use actix_web::{web, HttpResponse};
+use futures::stream;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    time,
+};
+
+// Workspace uses
+use zksync_api_types::v02::block::{BlockStatus, BlockStreamEvent};
+use zksync_crypto::{convert::FeConvert, Fr};
+use zksync_storage::{ConnectionPool, QueryResult};
+use zksync_types::event::{
+    block::{BlockEvent, BlockStatus as BlockEventStatus},
+    EventData,
+};
+
+// Local uses
+use super::event_cursor::EventCursor;
+
+/// Capacity of the channel between the fetcher and the clients, in block events.
+const BLOCK_EVENTS_CHANNEL_CAPACITY: usize = 1024;
+
+/// Handle to the stream of block events, shared by all the server workers.
+#[derive(Debug, Clone)]
+pub struct BlockStream {
+    sender: broadcast::Sender<BlockStreamEvent>,
+}
+
+impl BlockStream {
+    /// Spawns the block events fetcher.
+    pub fn new(db_pool: ConnectionPool, poll_interval: Duration) -> Self {
+        let (sender, _) = broadcast::channel(BLOCK_EVENTS_CHANNEL_CAPACITY);
+        let fetcher = BlockEventsFetcher {
+            db_pool,
+            poll_interval,
+            cursor: EventCursor::default(),
+            sender: sender.clone(),
+        };
+        tokio::spawn(fetcher.run());
+
+        Self { sender }
+    }
+}
+
+struct BlockEventsFetcher {
+    db_pool: ConnectionPool,
+    poll_interval: Duration,
+    cursor: EventCursor,
+    sender: broadcast::Sender<BlockStreamEvent>,
+}
+
+impl BlockEventsFetcher {
+    async fn fetch_new_events(&mut self) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut storage = self.db_pool.access_storage().await?;
+        let events = self.cursor.next_events(&mut storage).await?;
+        let last_event_id = match events.last() {
+            Some(event) => event.id,
+            None => return Ok(()),
+        };
+
+        for event in events {
+            let block_event = match event.data {
+                EventData::Block(block_event) => block_event,
+                _ => continue,
+            };
+            let tx_count = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions_count(block_event.block_details.block_number)
+                .await?;
+            let block_number = block_event.block_details.block_number;
+            match block_stream_event(block_event, tx_count) {
+                // The error only means that there are no connected clients at the moment.
+                Ok(Some(event)) => {
+                    self.sender.send(event).unwrap_or_default();
+                }
+                Ok(None) => {}
+                // The malformed event would stall the stream if retried, so it's skipped.
+                Err(err) => {
+                    vlog::error!(
+                        "Unable to stream the event of the block {}: {}",
+                        block_number,
+                        err
+                    );
+                }
+            }
+        }
+        self.cursor.advance(last_event_id);
+
+        metrics::histogram!("api.v02.blocks_stream.fetch_new_events", start.elapsed());
+        Ok(())
+    }
+
+    async fn run(mut self) {
+        let mut timer = time::interval(self.poll_interval);
+        loop {
+            timer.tick().await;
+            if let Err(err) = self.fetch_new_events().await {
+                vlog::warn!("Unable to fetch new block events: {}", err);
+            }
+        }
+    }
+}
+
+/// Returns `None` for the reverted blocks, since they are not streamed.
+fn block_stream_event(
+    event: BlockEvent,
+    tx_count: u32,
+) -> anyhow::Result<Option<BlockStreamEvent>> {
+    let details = event.block_details;
+    let (status, timestamp) = match event.status {
+        BlockEventStatus::Committed => (BlockStatus::Committed, details.committed_at),
+        BlockEventStatus::Finalized => (
+            BlockStatus::Finalized,
+            details.verified_at.unwrap_or(details.committed_at),
+        ),
+        BlockEventStatus::Reverted => return Ok(None),
+    };
+    let new_state_root = Fr::from_bytes(&details.new_state_root).map_err(|err| {
+        anyhow::anyhow!(
+            "Database provided an incorrect new_state_root field: {:?}, an error occurred {}",
+            details.new_state_root,
+            err
+        )
+    })?;
+
+    Ok(Some(BlockStreamEvent {
+        block_number: details.block_number,
+        new_state_root,
+        tx_count,
+        timestamp,
+        status,
+    }))
+}
+
+/// Formats the block event as the SSE message, the status is used as the event type.
+fn sse_message(event: &BlockStreamEvent) -> web::Bytes {
+    let event_type = match event.status {
+        BlockStatus::Committed => "committed",
+        BlockStatus::Finalized => "finalized",
+    };
+    let data = serde_json::to_string(event).unwrap();
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", event_type, data))
+}
+
+pub(super) async fn blocks_stream(stream: web::Data<BlockStream>) -> HttpResponse {
+    metrics::increment_counter!("api.v02.blocks_stream.connections");
+    // Lagging behind only means that some of the events were dropped for this client,
+    // there is no reason to close the connection.
+    let events = stream::unfold(stream.sender.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    return Some((Ok::<_, actix_web::Error>(sse_message(&event)), receiver))
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    vlog::warn!("Blocks stream client skipped {} events", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use zksync_types::{event::block::BlockDetails, BlockNumber};
+
+    fn block_event(status: BlockEventStatus) -> BlockEvent {
+        BlockEvent {
+            status,
+            block_details: BlockDetails {
+                block_number: BlockNumber(1),
+                new_state_root: Fr::default().to_bytes(),
+                block_size: 10,
+                commit_tx_hash: None,
+                verify_tx_hash: None,
+                committed_at: Utc::now(),
+                verified_at: Some(Utc::now()),
+            },
+        }
+    }
+
+    #[test]
+    fn block_events_conversion() {
+        let event = block_event(BlockEventStatus::Committed);
+        let committed_at = event.block_details.committed_at;
+        let stream_event = block_stream_event(event, 5).unwrap().unwrap();
+        assert_eq!(stream_event.block_number, BlockNumber(1));
+        assert_eq!(stream_event.tx_count, 5);
+        assert_eq!(stream_event.status, BlockStatus::Committed);
+        assert_eq!(stream_event.timestamp, committed_at);
+        assert!(sse_message(&stream_event).starts_with(b"event: committed\ndata: {"));
+
+        let event = block_event(BlockEventStatus::Finalized);
+        let verified_at = event.block_details.verified_at.unwrap();
+        let stream_event = block_stream_event(event, 5).unwrap().unwrap();
+        assert_eq!(stream_event.status, BlockStatus::Finalized);
+        assert_eq!(stream_event.timestamp, verified_at);
+        assert!(sse_message(&stream_event).ends_with(b"}\n\n"));
+
+        assert!(
+            block_stream_event(block_event(BlockEventStatus::Reverted), 5)
+                .unwrap()
+                .is_none()
+        );
+
+        let mut event = block_event(BlockEventStatus::Committed);
+        event.block_details.new_state_root = vec![0xff; 32];
+        assert!(block_stream_event(event, 5).is_err());
+    }
+}
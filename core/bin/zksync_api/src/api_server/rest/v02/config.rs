@@ -10,10 +10,15 @@ use serde::{Deserialize, Serialize};
 // Workspace uses
 use zksync_api_types::v02::ZksyncVersion;
 use zksync_config::ZkSyncConfig;
-use zksync_types::{network::Network, Address};
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    network::Network,
+    protocol::{ProtocolParameters, ProtocolParametersUpdate},
+    Address, BlockNumber,
+};
 
 // Local uses
-use super::response::ApiResult;
+use super::{error::Error, response::ApiResult};
 
 /// Shared data between `api/v0.2/config` endpoints.
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
@@ -39,6 +44,35 @@ impl ApiConfigData {
     }
 }
 
+/// Shared data between `api/v0.2/config/protocolParameters` endpoints.
+#[derive(Clone)]
+struct ApiProtocolParametersData {
+    pool: ConnectionPool,
+}
+
+impl ApiProtocolParametersData {
+    async fn parameters_history(&self) -> Result<Vec<ProtocolParametersUpdate>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        storage
+            .protocol_parameters_schema()
+            .load_parameters_history()
+            .await
+            .map_err(Error::storage)
+    }
+
+    async fn block_parameters(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Option<ProtocolParameters>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        storage
+            .protocol_parameters_schema()
+            .load_parameters_for_block(block_number)
+            .await
+            .map_err(Error::storage)
+    }
+}
+
 // Server implementation
 
 async fn config_endpoint(data: web::Data<ApiConfigData>) -> ApiResult<ApiConfigData> {
@@ -48,12 +82,41 @@ async fn config_endpoint(data: web::Data<ApiConfigData>) -> ApiResult<ApiConfigD
     res
 }
 
-pub fn api_scope(config: &ZkSyncConfig) -> Scope {
+async fn protocol_parameters_history(
+    data: web::Data<ApiProtocolParametersData>,
+) -> ApiResult<Vec<ProtocolParametersUpdate>> {
+    let start = Instant::now();
+    let res = data.parameters_history().await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "protocol_parameters_history");
+    res
+}
+
+async fn block_protocol_parameters(
+    data: web::Data<ApiProtocolParametersData>,
+    block_number: web::Path<BlockNumber>,
+) -> ApiResult<Option<ProtocolParameters>> {
+    let start = Instant::now();
+    let res = data.block_parameters(*block_number).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_protocol_parameters");
+    res
+}
+
+pub fn api_scope(config: &ZkSyncConfig, pool: ConnectionPool) -> Scope {
     let data = ApiConfigData::new(config);
+    let parameters_data = ApiProtocolParametersData { pool };
 
     web::scope("config")
         .app_data(web::Data::new(data))
+        .app_data(web::Data::new(parameters_data))
         .route("", web::get().to(config_endpoint))
+        .route(
+            "protocolParameters",
+            web::get().to(protocol_parameters_history),
+        )
+        .route(
+            "protocolParameters/{block_number}",
+            web::get().to(block_protocol_parameters),
+        )
 }
 
 #[cfg(test)]
@@ -78,13 +141,22 @@ mod tests {
             api_version: ApiVersion::V02,
         };
         let (client, server) = cfg.start_server(
-            |cfg: &TestServerConfig| api_scope(&cfg.config),
+            |cfg: &TestServerConfig| api_scope(&cfg.config, cfg.pool.clone()),
             Some(shared_data),
         );
         let response = client.config().await?;
         let api_config: ApiConfigData = deserialize_response_result(response)?;
         assert_eq!(api_config, ApiConfigData::new(&cfg.config));
 
+        let response = client.protocol_parameters_history().await?;
+        let history: Vec<ProtocolParametersUpdate> = deserialize_response_result(response)?;
+        let last_update = history.last().cloned();
+        let response = client
+            .block_protocol_parameters(BlockNumber(u32::MAX))
+            .await?;
+        let parameters: Option<ProtocolParameters> = deserialize_response_result(response)?;
+        assert_eq!(parameters, last_update.map(|update| update.parameters));
+
         server.stop().await;
         Ok(())
     }
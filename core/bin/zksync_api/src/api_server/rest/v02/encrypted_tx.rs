@@ -0,0 +1,354 @@
+//! Encrypted transactions part of API implementation.
+//!
+//! The client encrypts the transaction (or the batch) to the public key of the operator with ECIES
+//! and submits the envelope. The envelope is stored as is and only decrypted by the revealer once
+//! the visibility delay has passed: the transactions are submitted to the mempool at that moment,
+//! so nobody observing the mempool can front-run them in the meantime. The revealed envelopes
+//! are dropped, and their submission results are removed once the retention period has passed.
+//!
+//! The submission is free, so the number of the pending envelopes submitted from one IP is limited.
+
+// Built-in uses
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    HttpRequest, Scope,
+};
+use chrono::Utc;
+use parity_crypto::{
+    publickey::{ecies, KeyPair, Secret},
+    Keccak256,
+};
+
+// Workspace uses
+use zksync_api_types::v02::encrypted_tx::{
+    EncryptedTxPayload, EncryptedTxStatus, EncryptionKey, IncomingEncryptedTx,
+};
+use zksync_config::configs::api::EncryptedTxsConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::{tx::TxHash, H256};
+
+// Local uses
+use super::{
+    error::{Error, InvalidDataError},
+    response::ApiResult,
+};
+use crate::api_server::{rate_limit::request_client_ip, tx_sender::TxSender};
+
+/// Maximum size of the plaintext: it's the body of the regular submission request,
+/// which is limited by the JSON payload limit of the API.
+const MAX_PAYLOAD_SIZE: usize = 32 * 1024;
+/// ECIES adds the ephemeral public key (65 bytes), the IV (16 bytes) and the MAC (32 bytes).
+const ECIES_OVERHEAD: usize = 65 + 16 + 32;
+/// Maximum size of the envelope, enough for the biggest request accepted by the regular submission.
+const MAX_ENVELOPE_SIZE: usize = MAX_PAYLOAD_SIZE + ECIES_OVERHEAD;
+/// JSON payload limit of the submission: the envelope is hex-encoded in the request.
+const MAX_REQUEST_SIZE: usize = 2 * MAX_ENVELOPE_SIZE + 1024;
+/// Maximum number of envelopes revealed at once by one API server.
+const REVEAL_CHUNK_SIZE: u32 = 100;
+/// The claimed envelopes are revealed by another API server if their results are not stored
+/// in this time, it's long enough to submit the whole chunk.
+const CLAIM_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// How often the revealed envelopes older than the retention period are removed.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+fn operator_key_pair(config: &EncryptedTxsConfig) -> KeyPair {
+    KeyPair::from_secret(Secret::from(config.secret_key))
+        .expect("Invalid secret key of the encrypted transactions")
+}
+
+fn public_encryption_key(config: &EncryptedTxsConfig) -> EncryptionKey {
+    EncryptionKey {
+        public_key: operator_key_pair(config).public().as_bytes().to_vec(),
+    }
+}
+
+/// Shared data between `api/v0.2/encryptedTransactions` endpoints.
+#[derive(Clone)]
+struct ApiEncryptedTxData {
+    pool: ConnectionPool,
+    config: EncryptedTxsConfig,
+    encryption_key: EncryptionKey,
+    /// Proxies allowed to pass the client IP, the same as for the rate limiting.
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl ApiEncryptedTxData {
+    fn new(pool: ConnectionPool, config: EncryptedTxsConfig, trusted_proxies: Vec<IpAddr>) -> Self {
+        let encryption_key = public_encryption_key(&config);
+        Self {
+            pool,
+            config,
+            encryption_key,
+            trusted_proxies,
+        }
+    }
+
+    async fn submit(
+        &self,
+        envelope: Vec<u8>,
+        client: Option<IpAddr>,
+    ) -> Result<EncryptedTxStatus, Error> {
+        if envelope.len() > MAX_ENVELOPE_SIZE {
+            return Err(Error::from(InvalidDataError::EnvelopeTooLarge(
+                MAX_ENVELOPE_SIZE,
+            )));
+        }
+
+        let envelope_hash = H256::from(envelope.keccak256());
+        let reveal_at = Utc::now()
+            + chrono::Duration::from_std(self.config.reveal_delay())
+                .expect("Reveal delay is too big");
+        // Requests without the known IP are only made through the Unix socket.
+        let client = client.map(|ip| ip.to_string());
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        if let Some(client) = &client {
+            let pending = storage
+                .encrypted_txs_schema()
+                .count_pending_envelopes(client)
+                .await
+                .map_err(Error::storage)?;
+            if pending >= self.config.max_pending_per_client {
+                metrics::increment_counter!("api.encrypted_txs.rejected_pending_limit");
+                return Err(Error::from(InvalidDataError::TooManyPendingEnvelopes(
+                    self.config.max_pending_per_client,
+                )));
+            }
+        }
+        let stored = storage
+            .encrypted_txs_schema()
+            .store_envelope(envelope_hash, &envelope, reveal_at, client.as_deref())
+            .await
+            .map_err(Error::storage)?;
+        Ok(stored.into())
+    }
+
+    async fn status(&self, envelope_hash: H256) -> Result<Option<EncryptedTxStatus>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let stored = storage
+            .encrypted_txs_schema()
+            .get_envelope(envelope_hash)
+            .await
+            .map_err(Error::storage)?;
+        Ok(stored.map(EncryptedTxStatus::from))
+    }
+}
+
+/// Decrypts the envelopes which have passed the visibility delay and submits their transactions
+/// to the mempool.
+pub struct EncryptedTxRevealer {
+    tx_sender: TxSender,
+    key_pair: KeyPair,
+    config: EncryptedTxsConfig,
+}
+
+impl EncryptedTxRevealer {
+    pub fn new(tx_sender: TxSender, config: EncryptedTxsConfig) -> Self {
+        Self {
+            tx_sender,
+            key_pair: operator_key_pair(&config),
+            config,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut timer = tokio::time::interval(self.config.reveal_poll_interval());
+        let mut last_prune: Option<Instant> = None;
+        loop {
+            timer.tick().await;
+            if let Err(err) = self.reveal_ready_envelopes().await {
+                vlog::warn!("Failed to reveal the encrypted transactions: {}", err);
+            }
+
+            if last_prune.map_or(true, |last_prune| last_prune.elapsed() >= PRUNE_INTERVAL) {
+                last_prune = Some(Instant::now());
+                if let Err(err) = self.remove_revealed_envelopes().await {
+                    vlog::warn!(
+                        "Failed to remove the revealed encrypted transactions: {}",
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    async fn reveal_ready_envelopes(&self) -> anyhow::Result<()> {
+        // Envelopes are claimed before their transactions are submitted, and the claim is committed
+        // right away, so no database transaction is held during the submission. The claim expires
+        // if the results are not stored, so the envelopes being revealed at the server shutdown
+        // are revealed again instead of being lost. The transactions submitted before
+        // the shutdown are rejected by the mempool then.
+        let mut storage = self.tx_sender.pool.access_storage().await?;
+        let envelopes = storage
+            .encrypted_txs_schema()
+            .claim_envelopes_to_reveal(
+                REVEAL_CHUNK_SIZE,
+                chrono::Duration::from_std(CLAIM_TIMEOUT)?,
+            )
+            .await?;
+
+        for envelope in envelopes {
+            let envelope_hash = H256::from_slice(&envelope.envelope_hash);
+            let (tx_hashes, error) = match self.reveal(&envelope.envelope).await {
+                Ok(tx_hashes) => (tx_hashes, None),
+                Err(err) => (Vec::new(), Some(err)),
+            };
+            let result = if error.is_some() {
+                "rejected"
+            } else {
+                "revealed"
+            };
+            metrics::increment_counter!("api.encrypted_txs.revealed", "result" => result);
+
+            storage
+                .encrypted_txs_schema()
+                .store_reveal_result(envelope_hash, &tx_hashes, error)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_revealed_envelopes(&self) -> anyhow::Result<()> {
+        let before = Utc::now() - chrono::Duration::from_std(self.config.retention())?;
+        let removed = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await?
+            .encrypted_txs_schema()
+            .remove_revealed_envelopes(before)
+            .await?;
+        if removed > 0 {
+            vlog::info!("Removed {} revealed encrypted transactions", removed);
+        }
+        Ok(())
+    }
+
+    async fn reveal(&self, envelope: &[u8]) -> Result<Vec<TxHash>, String> {
+        match decrypt_payload(&self.key_pair, envelope)? {
+            EncryptedTxPayload::Tx(tx) => self
+                .tx_sender
+                .submit_tx(tx.tx, tx.signature, None)
+                .await
+                .map(|tx_hash| vec![tx_hash]),
+            EncryptedTxPayload::Batch(batch) => self
+                .tx_sender
+                .submit_txs_batch(batch.txs, batch.signature, None)
+                .await
+                .map(|response| {
+                    response
+                        .transaction_hashes
+                        .into_iter()
+                        .map(|tx_hash| tx_hash.0)
+                        .collect()
+                }),
+        }
+        .map_err(|err| err.to_string())
+    }
+}
+
+fn decrypt_payload(key_pair: &KeyPair, envelope: &[u8]) -> Result<EncryptedTxPayload, String> {
+    let payload = ecies::decrypt(key_pair.secret(), &[], envelope)
+        .map_err(|_| "Cannot decrypt the envelope".to_string())?;
+    serde_json::from_slice(&payload)
+        .map_err(|err| format!("Cannot parse the decrypted envelope: {}", err))
+}
+
+// Server implementation
+
+async fn encryption_key(data: web::Data<ApiEncryptedTxData>) -> ApiResult<EncryptionKey> {
+    let start = Instant::now();
+    let res = ApiResult::Ok(data.encryption_key.clone());
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "encryption_key");
+    res
+}
+
+async fn submit_encrypted_tx(
+    data: web::Data<ApiEncryptedTxData>,
+    req: HttpRequest,
+    Json(body): Json<IncomingEncryptedTx>,
+) -> ApiResult<EncryptedTxStatus> {
+    let start = Instant::now();
+    let client = request_client_ip(&data.trusted_proxies, &req);
+    let res = data.submit(body.envelope, client).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "submit_encrypted_tx");
+    res
+}
+
+async fn encrypted_tx_status(
+    data: web::Data<ApiEncryptedTxData>,
+    envelope_hash: web::Path<H256>,
+) -> ApiResult<Option<EncryptedTxStatus>> {
+    let start = Instant::now();
+    let res = data.status(*envelope_hash).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "encrypted_tx_status");
+    res
+}
+
+pub fn api_scope(
+    pool: ConnectionPool,
+    config: EncryptedTxsConfig,
+    trusted_proxies: Vec<IpAddr>,
+) -> Scope {
+    let data = ApiEncryptedTxData::new(pool, config, trusted_proxies);
+
+    web::scope("encryptedTransactions")
+        .app_data(web::Data::new(data))
+        .app_data(web::JsonConfig::default().limit(MAX_REQUEST_SIZE))
+        .route("", web::post().to(submit_encrypted_tx))
+        .route("key", web::get().to(encryption_key))
+        .route("{envelope_hash}", web::get().to(encrypted_tx_status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parity_crypto::publickey::Public;
+
+    fn test_config() -> EncryptedTxsConfig {
+        EncryptedTxsConfig {
+            enabled: true,
+            secret_key: H256::repeat_byte(0x11),
+            reveal_delay_ms: 1000,
+            reveal_poll_interval_ms: 200,
+            retention_hours: 168,
+            max_pending_per_client: 10,
+        }
+    }
+
+    /// Checks that the envelope encrypted to the served public key is decrypted by the revealer.
+    #[test]
+    fn envelope_roundtrip() {
+        let config = test_config();
+        let key_pair = operator_key_pair(&config);
+        let public_key = Public::from_slice(&public_encryption_key(&config).public_key);
+
+        let payload = serde_json::json!({
+            "txs": [],
+            "signature": null,
+        });
+        let envelope = ecies::encrypt(&public_key, &[], payload.to_string().as_bytes()).unwrap();
+        match decrypt_payload(&key_pair, &envelope).unwrap() {
+            EncryptedTxPayload::Batch(batch) => assert!(batch.txs.is_empty()),
+            EncryptedTxPayload::Tx(_) => panic!("Batch is expected"),
+        }
+
+        let other_key_pair = KeyPair::from_secret(Secret::from(H256::repeat_byte(0x22))).unwrap();
+        assert!(decrypt_payload(&other_key_pair, &envelope).is_err());
+    }
+
+    /// Checks that the envelope of the biggest payload is accepted by the submission.
+    #[test]
+    fn envelope_size_limits() {
+        let public_key = Public::from_slice(&public_encryption_key(&test_config()).public_key);
+
+        let envelope = ecies::encrypt(&public_key, &[], &[0u8; MAX_PAYLOAD_SIZE]).unwrap();
+        assert_eq!(envelope.len(), MAX_ENVELOPE_SIZE);
+
+        let request = serde_json::to_string(&IncomingEncryptedTx { envelope }).unwrap();
+        assert!(request.len() <= MAX_REQUEST_SIZE);
+    }
+}
@@ -7,13 +7,24 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use thiserror::Error;
 
 // Workspace uses
-use zksync_api_types::v02::pagination::{UnknownFromParameter, MAX_LIMIT};
+use zksync_api_types::v02::{
+    block::MAX_BLOCK_RANGE,
+    fee::MAX_FEE_QUOTES,
+    pagination::{UnknownFromParameter, MAX_LIMIT},
+    token::MAX_PRICE_HISTORY_POINTS,
+};
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_token_db_cache::TokenResolutionError;
+use zksync_types::tx::error::TxAddError;
 
 // Local uses
 use crate::{api_server::tx_sender::SubmitError, fee_ticker::PriceError};
 
-#[derive(Serialize_repr, Debug, Deserialize_repr, Clone, PartialEq)]
+/// Catalog of the error codes returned by the API.
+///
+/// The codes are stable: the new codes can be added, but the existing ones are never changed or reused,
+/// so the clients should match on the code instead of the error message.
+#[derive(Serialize_repr, Debug, Deserialize_repr, Clone, Copy, PartialEq, Eq)]
 #[repr(u16)]
 pub enum ErrorCode {
     UnreacheableError = 0,
@@ -27,21 +38,210 @@ pub enum ErrorCode {
     PaginationLimitTooBig = 206,
     QueryDeserializationError = 207,
     InvalidNFTTokenId = 208,
+    TooManyAccountsRequested = 209,
+    InvalidOperationType = 210,
+    InvalidBlockRange = 211,
+    NotWithdrawal = 212,
+    InvalidPriceHistoryRange = 213,
+    EnvelopeTooLarge = 214,
+    BlockRangeTooBig = 215,
+    InvalidFeeHistoryQuery = 216,
+    ZeroExitBalance = 217,
+    TooManyPendingEnvelopes = 218,
     StorageError = 300,
     TokenNotFound = 500,
     ExternalApiError = 501,
+    AmbiguousTokenSymbol = 502,
     InternalError = 600,
     AccountCloseDisabled = 601,
     InvalidParams = 602,
     UnsupportedFastProcessing = 603,
     IncorrectTx = 604,
+    /// The transaction is rejected, the reason is reported in the `detail` with the `7xx` codes.
     TxAddError = 605,
     InappropriateFeeToken = 606,
     CommunicationCoreServer = 607,
     Toggle2FAError = 608,
+    AccountRecoveryError = 609,
+    RequestTimeout = 610,
+    TokenDisabled = 611,
+    ServiceUnavailable = 612,
+    NonceMismatch = 700,
+    InvalidTransaction = 701,
+    TxFeeTooLow = 702,
+    TxBatchFeeTooLow = 703,
+    EIP1271SignatureVerificationFail = 704,
+    MissingEthSignature = 705,
+    IncorrectEthSignature = 706,
+    ChangePkNotAuthorized = 707,
+    TxAddInternalError = 708,
+    TxAddStorageError = 709,
+    EmptyBatch = 710,
+    BatchTooBig = 711,
+    BatchWithdrawalsOverload = 712,
+    EthSignaturesLimitExceeded = 713,
+    BatchSignersError = 714,
     Other = 60_000,
 }
 
+impl ErrorCode {
+    /// All the codes of the catalog in ascending order.
+    pub const ALL: &'static [ErrorCode] = &[
+        Self::UnreacheableError,
+        Self::CoreApiError,
+        Self::TokenZeroPriceError,
+        Self::InvalidCurrency,
+        Self::InvalidBlockPosition,
+        Self::InvalidAccountIdOrAddress,
+        Self::AccountNotFound,
+        Self::TransactionNotFound,
+        Self::PaginationLimitTooBig,
+        Self::QueryDeserializationError,
+        Self::InvalidNFTTokenId,
+        Self::TooManyAccountsRequested,
+        Self::InvalidOperationType,
+        Self::InvalidBlockRange,
+        Self::NotWithdrawal,
+        Self::InvalidPriceHistoryRange,
+        Self::EnvelopeTooLarge,
+        Self::BlockRangeTooBig,
+        Self::InvalidFeeHistoryQuery,
+        Self::ZeroExitBalance,
+        Self::TooManyPendingEnvelopes,
+        Self::StorageError,
+        Self::TokenNotFound,
+        Self::ExternalApiError,
+        Self::AmbiguousTokenSymbol,
+        Self::InternalError,
+        Self::AccountCloseDisabled,
+        Self::InvalidParams,
+        Self::UnsupportedFastProcessing,
+        Self::IncorrectTx,
+        Self::TxAddError,
+        Self::InappropriateFeeToken,
+        Self::CommunicationCoreServer,
+        Self::Toggle2FAError,
+        Self::AccountRecoveryError,
+        Self::RequestTimeout,
+        Self::TokenDisabled,
+        Self::ServiceUnavailable,
+        Self::NonceMismatch,
+        Self::InvalidTransaction,
+        Self::TxFeeTooLow,
+        Self::TxBatchFeeTooLow,
+        Self::EIP1271SignatureVerificationFail,
+        Self::MissingEthSignature,
+        Self::IncorrectEthSignature,
+        Self::ChangePkNotAuthorized,
+        Self::TxAddInternalError,
+        Self::TxAddStorageError,
+        Self::EmptyBatch,
+        Self::BatchTooBig,
+        Self::BatchWithdrawalsOverload,
+        Self::EthSignaturesLimitExceeded,
+        Self::BatchSignersError,
+        Self::Other,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::UnreacheableError => "Unreachable error",
+            Self::CoreApiError => "Request to the core API failed",
+            Self::TokenZeroPriceError => "Price can't be shown in the token with zero price",
+            Self::InvalidCurrency => "Unknown currency, only token id and `usd` are supported",
+            Self::InvalidBlockPosition => "Unknown block position",
+            Self::InvalidAccountIdOrAddress => "Account id or address can't be parsed",
+            Self::AccountNotFound => "Account is not found",
+            Self::TransactionNotFound => "Transaction is not found",
+            Self::PaginationLimitTooBig => "Pagination limit is too big",
+            Self::QueryDeserializationError => "Query parameters can't be parsed",
+            Self::InvalidNFTTokenId => "Token id is not an NFT id",
+            Self::TooManyAccountsRequested => "Too many accounts are requested at once",
+            Self::InvalidOperationType => "Unknown operation type",
+            Self::InvalidBlockRange => "Range of blocks is empty",
+            Self::NotWithdrawal => "Transaction is not a withdrawal",
+            Self::InvalidPriceHistoryRange => "Range of the price history is invalid",
+            Self::EnvelopeTooLarge => "Encrypted envelope is too large",
+            Self::BlockRangeTooBig => "Range of blocks is too big",
+            Self::InvalidFeeHistoryQuery => "Fee history query is invalid",
+            Self::ZeroExitBalance => "Nothing to exit, the finalized balance is zero",
+            Self::TooManyPendingEnvelopes => "Too many encrypted envelopes are pending",
+            Self::StorageError => "Database request failed",
+            Self::TokenNotFound => "Token is not found",
+            Self::ExternalApiError => "Request to the external price API failed",
+            Self::AmbiguousTokenSymbol => "Token symbol is shared by several tokens",
+            Self::InternalError => "Internal server error",
+            Self::AccountCloseDisabled => "Close transactions are disabled",
+            Self::InvalidParams => "Invalid request parameters",
+            Self::UnsupportedFastProcessing => "Fast processing is only supported for withdrawals",
+            Self::IncorrectTx => "Transaction can't be processed",
+            Self::TxAddError => "Transaction is rejected, see the `7xx` code in the `detail`",
+            Self::InappropriateFeeToken => "Token can't be used to pay fees",
+            Self::CommunicationCoreServer => "Mempool is unavailable",
+            Self::Toggle2FAError => "2FA can't be toggled",
+            Self::AccountRecoveryError => "Account recovery request is rejected",
+            Self::RequestTimeout => "Request processing has not finished in time",
+            Self::TokenDisabled => "Token is disabled and can only be withdrawn",
+            Self::ServiceUnavailable => "Storage or mempool is temporarily unavailable",
+            Self::NonceMismatch => "Transaction nonce is too low",
+            Self::InvalidTransaction => "Transaction fields are invalid",
+            Self::TxFeeTooLow => "Transaction fee is too low",
+            Self::TxBatchFeeTooLow => "Summary fee of the batch is too low",
+            Self::EIP1271SignatureVerificationFail => "EIP-1271 signature can't be verified",
+            Self::MissingEthSignature => "Ethereum signature is missing",
+            Self::IncorrectEthSignature => "Ethereum signature is incorrect",
+            Self::ChangePkNotAuthorized => "Change pubkey is not authorized onchain",
+            Self::TxAddInternalError => "Transaction can't be processed due to an internal error",
+            Self::TxAddStorageError => "Transaction can't be stored, the database is unavailable",
+            Self::EmptyBatch => "Batch of transactions is empty",
+            Self::BatchTooBig => "Batch doesn't fit in any of the supported block sizes",
+            Self::BatchWithdrawalsOverload => "Batch contains too many withdrawals",
+            Self::EthSignaturesLimitExceeded => "Too many Ethereum signatures are provided",
+            Self::BatchSignersError => "Signatures of some of the batch signers are invalid",
+            Self::Other => "Unclassified error",
+        }
+    }
+}
+
+impl From<TxAddError> for ErrorCode {
+    fn from(err: TxAddError) -> Self {
+        match err {
+            TxAddError::NonceMismatch => Self::NonceMismatch,
+            TxAddError::IncorrectTx(_) => Self::InvalidTransaction,
+            TxAddError::TxFeeTooLow => Self::TxFeeTooLow,
+            TxAddError::TxBatchFeeTooLow => Self::TxBatchFeeTooLow,
+            TxAddError::EIP1271SignatureVerificationFail => Self::EIP1271SignatureVerificationFail,
+            TxAddError::MissingEthSignature => Self::MissingEthSignature,
+            TxAddError::IncorrectEthSignature => Self::IncorrectEthSignature,
+            TxAddError::ChangePkNotAuthorized => Self::ChangePkNotAuthorized,
+            TxAddError::Other => Self::TxAddInternalError,
+            TxAddError::DbError => Self::TxAddStorageError,
+            TxAddError::EmptyBatch => Self::EmptyBatch,
+            TxAddError::BatchTooBig => Self::BatchTooBig,
+            TxAddError::BatchWithdrawalsOverload => Self::BatchWithdrawalsOverload,
+            TxAddError::EthSignaturesLimitExceeded => Self::EthSignaturesLimitExceeded,
+        }
+    }
+}
+
+/// Entry of the error codes catalog returned by the `/errors` endpoint.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ErrorCodeInfo {
+    pub code: ErrorCode,
+    pub name: String,
+    pub description: String,
+}
+
+impl From<ErrorCode> for ErrorCodeInfo {
+    fn from(code: ErrorCode) -> Self {
+        Self {
+            code,
+            name: format!("{:?}", code),
+            description: code.description().to_string(),
+        }
+    }
+}
+
 /// Error object in a response
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -49,6 +249,9 @@ pub struct Error {
     pub error_type: String,
     pub code: ErrorCode,
     pub message: String,
+    /// Specific code of the error reported with the general `code`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<ErrorCode>,
 }
 
 /// Trait that can be used to map custom errors to the object.
@@ -60,6 +263,10 @@ pub trait ApiError: std::fmt::Display {
     fn message(&self) -> String {
         self.to_string()
     }
+
+    fn detail(&self) -> Option<ErrorCode> {
+        None
+    }
 }
 
 impl<T> From<T> for Error
@@ -71,6 +278,7 @@ where
             error_type: t.error_type(),
             code: t.code(),
             message: t.message(),
+            detail: t.detail(),
         }
     }
 }
@@ -103,6 +311,32 @@ pub enum InvalidDataError {
     PaginationLimitTooBig,
     #[error("NFT token ID should be greater than or equal to {}", MIN_NFT_TOKEN_ID)]
     InvalidNFTTokenId,
+    #[error("Number of requested accounts should be less than or equal to {0}")]
+    TooManyAccountsRequested(u64),
+    #[error("Unknown operation type: {0}")]
+    InvalidOperationType(String),
+    #[error("Invalid range of blocks: fromBlock should be less than or equal to toBlock")]
+    InvalidBlockRange,
+    #[error("Transaction is not a withdrawal")]
+    NotWithdrawal,
+    #[error(
+        "Invalid price history range: from should be less than to, resolution should be positive and the range should contain at most {} intervals",
+        MAX_PRICE_HISTORY_POINTS
+    )]
+    InvalidPriceHistoryRange,
+    #[error("Encrypted envelope should be less than or equal to {0} bytes")]
+    EnvelopeTooLarge(usize),
+    #[error("Range of blocks should contain at most {} blocks", MAX_BLOCK_RANGE)]
+    BlockRangeTooBig,
+    #[error(
+        "Invalid fee history query: from should be less than or equal to to, limit should be positive and at most {}",
+        MAX_FEE_QUOTES
+    )]
+    InvalidFeeHistoryQuery,
+    #[error("Finalized balance of the token is zero, there is nothing to exit")]
+    ZeroExitBalance,
+    #[error("At most {0} encrypted envelopes submitted by the client may be pending")]
+    TooManyPendingEnvelopes(u64),
 }
 
 impl ApiError for InvalidDataError {
@@ -120,6 +354,16 @@ impl ApiError for InvalidDataError {
             Self::TransactionNotFound => ErrorCode::TransactionNotFound,
             Self::PaginationLimitTooBig => ErrorCode::PaginationLimitTooBig,
             Self::InvalidNFTTokenId => ErrorCode::InvalidNFTTokenId,
+            Self::TooManyAccountsRequested(_) => ErrorCode::TooManyAccountsRequested,
+            Self::InvalidOperationType(_) => ErrorCode::InvalidOperationType,
+            Self::InvalidBlockRange => ErrorCode::InvalidBlockRange,
+            Self::NotWithdrawal => ErrorCode::NotWithdrawal,
+            Self::InvalidPriceHistoryRange => ErrorCode::InvalidPriceHistoryRange,
+            Self::EnvelopeTooLarge(_) => ErrorCode::EnvelopeTooLarge,
+            Self::BlockRangeTooBig => ErrorCode::BlockRangeTooBig,
+            Self::InvalidFeeHistoryQuery => ErrorCode::InvalidFeeHistoryQuery,
+            Self::ZeroExitBalance => ErrorCode::ZeroExitBalance,
+            Self::TooManyPendingEnvelopes(_) => ErrorCode::TooManyPendingEnvelopes,
         }
     }
 }
@@ -195,15 +439,27 @@ impl ApiError for SubmitError {
             Self::InvalidParams(_) => ErrorCode::InvalidParams,
             Self::UnsupportedFastProcessing => ErrorCode::UnsupportedFastProcessing,
             Self::IncorrectTx(_) => ErrorCode::IncorrectTx,
-            Self::TxAdd(_) => ErrorCode::TxAddError,
+            Self::TxAdd(_) | Self::BatchSigners(_) => ErrorCode::TxAddError,
             Self::InappropriateFeeToken => ErrorCode::InappropriateFeeToken,
+            Self::TokenDisabled(_) => ErrorCode::TokenDisabled,
             Self::MempoolCommunication(_) => ErrorCode::CommunicationCoreServer,
             Self::Internal(_) => ErrorCode::InternalError,
             Self::Toggle2FA(_) => ErrorCode::Toggle2FAError,
+            Self::AccountRecovery(_) => ErrorCode::AccountRecoveryError,
+            Self::Timeout => ErrorCode::RequestTimeout,
+            Self::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
             Self::Other(_) => ErrorCode::Other,
             Self::PriceError(_) => ErrorCode::InternalError,
         }
     }
+
+    fn detail(&self) -> Option<ErrorCode> {
+        match self {
+            Self::TxAdd(err) => Some(ErrorCode::from(*err)),
+            Self::BatchSigners(_) => Some(ErrorCode::BatchSignersError),
+            _ => None,
+        }
+    }
 }
 
 impl ApiError for PriceError {
@@ -220,6 +476,20 @@ impl ApiError for PriceError {
     }
 }
 
+impl ApiError for TokenResolutionError {
+    fn error_type(&self) -> String {
+        String::from("tokenError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::NotFound(_) => ErrorCode::TokenNotFound,
+            Self::AmbiguousSymbol { .. } => ErrorCode::AmbiguousTokenSymbol,
+            Self::Storage(_) => ErrorCode::StorageError,
+        }
+    }
+}
+
 impl ApiError for UnknownFromParameter {
     fn error_type(&self) -> String {
         String::from("invalidDataError")
@@ -229,3 +499,34 @@ impl ApiError for UnknownFromParameter {
         ErrorCode::QueryDeserializationError
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn error_codes_catalog() {
+        let codes: Vec<_> = ErrorCode::ALL.iter().map(|code| *code as u16).collect();
+        let mut sorted_codes = codes.clone();
+        sorted_codes.sort_unstable();
+        sorted_codes.dedup();
+        assert_eq!(codes, sorted_codes);
+
+        let names: HashSet<_> = ErrorCode::ALL
+            .iter()
+            .map(|code| ErrorCodeInfo::from(*code).name)
+            .collect();
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+
+        let error = Error::from(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+        assert_eq!(error.code, ErrorCode::TxAddError);
+        assert_eq!(error.detail, Some(ErrorCode::TxFeeTooLow));
+        let serialized = serde_json::to_value(&error).unwrap();
+        assert_eq!(serialized["code"], 605);
+        assert_eq!(serialized["detail"], 702);
+
+        let serialized = serde_json::to_value(&Error::from(SubmitError::Timeout)).unwrap();
+        assert!(serialized.get("detail").is_none());
+    }
+}
@@ -0,0 +1,63 @@
+//! Error codes part of API implementation.
+
+// Built-in uses
+use std::time::Instant;
+// External uses
+use actix_web::{web, Scope};
+
+// Local uses
+use super::{
+    error::{ErrorCode, ErrorCodeInfo},
+    response::ApiResult,
+};
+
+// Server implementation
+
+async fn error_codes() -> ApiResult<Vec<ErrorCodeInfo>> {
+    let start = Instant::now();
+    let codes = ErrorCode::ALL
+        .iter()
+        .copied()
+        .map(ErrorCodeInfo::from)
+        .collect();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "error_codes");
+    Ok(codes).into()
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("errors").route("", web::get().to(error_codes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{deserialize_response_result, TestServerConfig},
+        SharedData,
+    };
+    use zksync_api_types::v02::ApiVersion;
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn error_codes_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(|_| api_scope(), Some(shared_data));
+
+        let response = client.error_codes().await?;
+        let codes: Vec<ErrorCodeInfo> = deserialize_response_result(response)?;
+        assert_eq!(codes.len(), ErrorCode::ALL.len());
+        assert!(codes
+            .iter()
+            .any(|info| info.code == ErrorCode::NonceMismatch && info.name == "NonceMismatch"));
+
+        server.stop().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+//! Events part of API implementation.
+//!
+//! The endpoint is modeled on `eth_getLogs`: every executed transaction or priority operation
+//! is an event, and the events are filtered by the affected address, token, type of the operation
+//! and the range of blocks. The events are paginated with the same opaque cursors as the account
+//! history, so indexers can follow the chain without loading the full blocks.
+
+// Built-in uses
+use std::str::FromStr;
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+
+// Workspace uses
+use zksync_api_types::v02::{
+    event::{EventsFilter, EventsRequest, IncomingEventsQuery, L2Event},
+    pagination::{Cursor, CursorPaginated, PaginationQuery, MAX_LIMIT},
+};
+use zksync_storage::ConnectionPool;
+use zksync_token_db_cache::TokenDBCache;
+use zksync_types::{event::transaction::TransactionType, BlockNumber, TokenLike};
+
+// Local uses
+use super::{
+    error::{Error, InvalidDataError},
+    response::ApiResult,
+};
+
+/// Shared data between `api/v0.2/events` endpoints.
+#[derive(Clone)]
+struct ApiEventData {
+    pool: ConnectionPool,
+    tokens: TokenDBCache,
+}
+
+impl ApiEventData {
+    fn new(pool: ConnectionPool, tokens: TokenDBCache) -> Self {
+        Self { pool, tokens }
+    }
+
+    async fn events(&self, query: IncomingEventsQuery) -> Result<CursorPaginated<L2Event>, Error> {
+        if query.limit > MAX_LIMIT {
+            return Err(Error::from(InvalidDataError::PaginationLimitTooBig));
+        }
+        let cursor = match query.from {
+            Some(from) => Some(Cursor::from_str(&from)?),
+            None => None,
+        };
+        if let Some(op_type) = &query.op_type {
+            serde_json::from_value::<TransactionType>(op_type.as_str().into())
+                .map_err(|_| InvalidDataError::InvalidOperationType(op_type.clone()))?;
+        }
+
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let token = match query.token {
+            Some(token) => {
+                let token = self
+                    .tokens
+                    .resolve_token(&mut storage, TokenLike::parse(&token))
+                    .await
+                    .map_err(Error::from)?;
+                Some(token.id)
+            }
+            None => None,
+        };
+
+        let query = PaginationQuery {
+            from: EventsRequest {
+                filter: EventsFilter {
+                    address: query.address,
+                    token,
+                    op_type: query.op_type,
+                    from_block: query.from_block.map(BlockNumber),
+                    to_block: query.to_block.map(BlockNumber),
+                },
+                cursor,
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+        storage
+            .chain()
+            .operations_ext_schema()
+            .get_events(&query)
+            .await
+            .map_err(Error::storage)
+    }
+}
+
+// Server implementation
+
+async fn events(
+    data: web::Data<ApiEventData>,
+    web::Query(query): web::Query<IncomingEventsQuery>,
+) -> ApiResult<CursorPaginated<L2Event>> {
+    let start = Instant::now();
+    let res = data.events(query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "events");
+    res
+}
+
+pub fn api_scope(pool: ConnectionPool, tokens: TokenDBCache) -> Scope {
+    let data = ApiEventData::new(pool, tokens);
+
+    web::scope("events")
+        .app_data(web::Data::new(data))
+        .route("", web::get().to(events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        error::ErrorCode,
+        test_utils::{deserialize_response_result, TestServerConfig},
+        SharedData,
+    };
+    use zksync_api_types::v02::{pagination::PaginationDirection, ApiVersion};
+
+    fn events_query(limit: u32) -> IncomingEventsQuery {
+        IncomingEventsQuery {
+            from: None,
+            limit,
+            direction: PaginationDirection::Newer,
+            address: None,
+            token: None,
+            op_type: None,
+            from_block: None,
+            to_block: None,
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn events_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    TokenDBCache::new(cfg.config.api.token_config.invalidate_token_cache_period()),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let response = client.events(&events_query(MAX_LIMIT)).await?;
+        let all_events: CursorPaginated<L2Event> = deserialize_response_result(response)?;
+        assert!(!all_events.list.is_empty());
+
+        // The same events are returned page by page.
+        let mut query = events_query(3);
+        let mut events = Vec::new();
+        loop {
+            let response = client.events(&query).await?;
+            let page: CursorPaginated<L2Event> = deserialize_response_result(response)?;
+            events.extend(page.list);
+            match page.pagination.next {
+                Some(next) => query.from = Some(next.to_string()),
+                None => break,
+            }
+        }
+        // There may be more than `MAX_LIMIT` events in the database.
+        events.truncate(all_events.list.len());
+        let cursors =
+            |events: &[L2Event]| events.iter().map(|event| event.cursor).collect::<Vec<_>>();
+        assert_eq!(cursors(&events), cursors(&all_events.list));
+
+        let response = client
+            .events(&IncomingEventsQuery {
+                op_type: Some("Transfer".into()),
+                from_block: Some(2),
+                to_block: Some(3),
+                ..events_query(MAX_LIMIT)
+            })
+            .await?;
+        let transfers: CursorPaginated<L2Event> = deserialize_response_result(response)?;
+        assert!(!transfers.list.is_empty());
+        for event in transfers.list {
+            assert_eq!(event.op_type, "Transfer");
+            let block_number = event.tx.block_number.unwrap();
+            assert!(BlockNumber(2) <= block_number && block_number <= BlockNumber(3));
+        }
+
+        let response = client
+            .events(&IncomingEventsQuery {
+                op_type: Some("Unknown".into()),
+                ..events_query(MAX_LIMIT)
+            })
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidOperationType);
+
+        server.stop().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! Cursor over the `events` table shared by the fetchers of the streaming endpoints.
+//!
+//! Events are written by the core, so the fetchers only have to follow the id of the last
+//! processed event. Events created before the server start are skipped.
+
+// Built-in uses
+use std::convert::TryFrom;
+
+// Workspace uses
+use zksync_storage::{QueryResult, StorageProcessor};
+use zksync_types::event::{EventId, ZkSyncEvent};
+
+#[derive(Debug, Default)]
+pub struct EventCursor {
+    last_processed_event_id: Option<EventId>,
+}
+
+impl EventCursor {
+    /// Loads the events stored after the last processed one. The first call only remembers
+    /// the id of the latest event and returns nothing.
+    ///
+    /// Loaded events are not considered processed until `advance` is called, so the events
+    /// of the failed poll are loaded once again by the next one.
+    pub async fn next_events(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> QueryResult<Vec<ZkSyncEvent>> {
+        let last_processed_event_id = match self.last_processed_event_id {
+            Some(id) => id,
+            None => {
+                let id = storage
+                    .event_schema()
+                    .get_last_event_id()
+                    .await?
+                    .unwrap_or(EventId(0));
+                self.last_processed_event_id = Some(id);
+                return Ok(Vec::new());
+            }
+        };
+
+        let events = storage
+            .event_schema()
+            .fetch_new_events(last_processed_event_id)
+            .await?
+            .into_iter()
+            .map(ZkSyncEvent::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(events)
+    }
+
+    /// Marks the events up to `last_event_id` as processed.
+    pub fn advance(&mut self, last_event_id: EventId) {
+        self.last_processed_event_id = Some(last_event_id);
+    }
+}
@@ -1,107 +1,297 @@
 //! Fee part of API implementation.
 
 // Built-in uses
-
+use std::collections::HashSet;
 use std::time::Instant;
 // External uses
 use actix_web::{
     web::{self, Json},
-    Scope,
+    FromRequest, Scope,
 };
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use async_trait::async_trait;
+use chrono::Utc;
 
 // Workspace uses
-use zksync_api_types::v02::fee::{ApiFee, BatchFeeRequest, TxFeeRequest};
+use zksync_api_types::v02::fee::{
+    ApiFee, ApiFeeQuote, BatchFeeRequest, IncomingFeeHistoryQuery, TxFeeRequest,
+    TxInBatchFeeRequest, MAX_FEE_QUOTES,
+};
+use zksync_storage::fee_quotes::records::{FeeQuotesFilter, StoredFeeQuote};
+use zksync_types::{Address, TokenId, TokenLike, TxFeeTypes};
+use zksync_utils::big_decimal_to_ratio;
 
 // Local uses
-use super::{error::Error, response::ApiResult};
-use crate::{
-    api_server::tx_sender::{SubmitError, TxSender},
-    api_try,
+use super::{
+    error::{Error, InvalidDataError},
+    response::ApiResult,
+};
+use crate::api_server::{
+    rest::helpers::authorize_admin,
+    tx_sender::{SubmitError, TxSender},
 };
 
 /// Shared data between `api/v0.2/fee` endpoints.
 #[derive(Clone)]
 struct ApiFeeData {
     tx_sender: TxSender,
+    /// The fee history reveals the addresses of the users, so it's served to the admins only.
+    secret_auth: String,
 }
 
 impl ApiFeeData {
-    fn new(tx_sender: TxSender) -> Self {
-        Self { tx_sender }
+    fn new(tx_sender: TxSender, secret_auth: String) -> Self {
+        Self {
+            tx_sender,
+            secret_auth,
+        }
+    }
+
+    /// Checks that the tokens of the transactions in the batch are known.
+    async fn check_batch_tokens(&self, txs: &[TxInBatchFeeRequest]) -> Result<(), Error> {
+        let tokens: HashSet<_> = txs.iter().filter_map(|tx| tx.token.clone()).collect();
+        if tokens.is_empty() {
+            return Ok(());
+        }
+
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?;
+        for token in tokens {
+            self.tx_sender
+                .tokens
+                .resolve_token(&mut storage, token)
+                .await
+                .map_err(Error::from)?;
+        }
+        Ok(())
+    }
+
+    async fn fee_history(&self, query: IncomingFeeHistoryQuery) -> Result<Vec<ApiFeeQuote>, Error> {
+        let to = query.to.unwrap_or_else(Utc::now);
+        let from = query.from.unwrap_or_else(|| to - chrono::Duration::days(1));
+        let limit = query.limit.unwrap_or(MAX_FEE_QUOTES);
+        if from > to || limit == 0 || limit > MAX_FEE_QUOTES {
+            return Err(Error::from(InvalidDataError::InvalidFeeHistoryQuery));
+        }
+
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?;
+        let token_id = match query.token {
+            Some(token) => Some(
+                self.tx_sender
+                    .tokens
+                    .resolve_token(&mut storage, TokenLike::parse(&token))
+                    .await
+                    .map_err(Error::from)?
+                    .id,
+            ),
+            None => None,
+        };
+        let filter = FeeQuotesFilter {
+            token_id,
+            address: query.address,
+            tx_type: query.tx_type,
+            from,
+            to,
+            limit,
+        };
+        let quotes = storage
+            .fee_quotes_schema()
+            .load_fee_quotes(&filter)
+            .await
+            .map_err(Error::storage)?;
+        quotes
+            .into_iter()
+            .map(api_fee_quote)
+            .collect::<anyhow::Result<_>>()
+            .map_err(Error::storage)
     }
 }
 
-async fn get_tx_fee(
-    data: web::Data<ApiFeeData>,
+fn api_fee_quote(quote: StoredFeeQuote) -> anyhow::Result<ApiFeeQuote> {
+    let to_biguint = |amount| big_decimal_to_ratio(&amount).map(|amount| amount.to_integer());
+    Ok(ApiFeeQuote {
+        tx_types: quote.tx_types,
+        addresses: quote
+            .addresses
+            .iter()
+            .map(|address| Address::from_slice(address))
+            .collect(),
+        token_id: TokenId(quote.token_id as u32),
+        gas_fee: to_biguint(quote.gas_fee)?,
+        zkp_fee: to_biguint(quote.zkp_fee)?,
+        total_fee: to_biguint(quote.total_fee)?,
+        subsidized: quote.subsidized,
+        gas_price_wei: to_biguint(quote.gas_price_wei)?,
+        token_usd_price: quote.token_usd_price,
+        created_at: quote.created_at,
+    })
+}
+
+/// Source of the fees, so the handlers serve both the fees of the ticker
+/// and the fixed ones of the sandbox.
+#[async_trait(?Send)]
+pub(super) trait FeeSource: FromRequest + 'static {
+    async fn tx_fee(&self, request: TxFeeRequest) -> Result<ApiFee, Error>;
+
+    async fn batch_fee(&self, request: BatchFeeRequest) -> Result<ApiFee, Error>;
+}
+
+#[async_trait(?Send)]
+impl FeeSource for web::Data<ApiFeeData> {
+    async fn tx_fee(&self, request: TxFeeRequest) -> Result<ApiFee, Error> {
+        let token_allowed = self
+            .tx_sender
+            .ticker
+            .token_allowed_for_fees(request.token_like.clone())
+            .await
+            .map_err(Error::from)?;
+        if !token_allowed {
+            return Err(Error::from(SubmitError::InappropriateFeeToken));
+        }
+        let tx_type: TxFeeTypes = request.tx_type.into();
+        let fee = self
+            .tx_sender
+            .ticker
+            .get_fee_from_ticker_in_wei(tx_type, request.token_like, request.address)
+            .await
+            .map_err(Error::from)?;
+        // The request metadata is not available here, so only the subsidy rules apply.
+        // TODO implement the IP based subsidies for v02 api ZKS-888
+        let should_subsidize = self
+            .tx_sender
+            .should_subsidize(
+                &fee.normal_fee.total_fee,
+                &fee.subsidized_fee.total_fee,
+                &fee.subsidy_size_usd,
+                fee.subsidy_rule.as_ref(),
+                None,
+            )
+            .await
+            .map_err(Error::from)?;
+        let api_fee: ApiFee = if should_subsidize {
+            fee.subsidized_fee.into()
+        } else {
+            fee.normal_fee.into()
+        };
+        self.tx_sender.store_fee_quote(
+            &[tx_type],
+            &[request.address],
+            api_fee.clone(),
+            should_subsidize,
+            fee.inputs,
+        );
+        Ok(api_fee)
+    }
+
+    async fn batch_fee(&self, request: BatchFeeRequest) -> Result<ApiFee, Error> {
+        let token_allowed = self
+            .tx_sender
+            .ticker
+            .token_allowed_for_fees(request.token_like.clone())
+            .await
+            .map_err(Error::from)?;
+        if !token_allowed {
+            return Err(Error::from(SubmitError::InappropriateFeeToken));
+        }
+        self.check_batch_tokens(&request.transactions).await?;
+        let txs: Vec<(TxFeeTypes, Address)> = request
+            .transactions
+            .into_iter()
+            .map(|tx| (tx.tx_type.into(), tx.address))
+            .collect();
+        let (tx_types, addresses): (Vec<_>, Vec<_>) = txs.iter().copied().unzip();
+        let fee = self
+            .tx_sender
+            .ticker
+            .get_batch_from_ticker_in_wei(request.token_like, txs)
+            .await
+            .map_err(Error::from)?;
+        let should_subsidize = self
+            .tx_sender
+            .should_subsidize(
+                &fee.normal_fee.total_fee,
+                &fee.subsidized_fee.total_fee,
+                &fee.subsidy_size_usd,
+                fee.subsidy_rule.as_ref(),
+                None,
+            )
+            .await
+            .map_err(Error::from)?;
+        let api_fee: ApiFee = if should_subsidize {
+            fee.subsidized_fee.into()
+        } else {
+            fee.normal_fee.into()
+        };
+        self.tx_sender.store_fee_quote(
+            &tx_types,
+            &addresses,
+            api_fee.clone(),
+            should_subsidize,
+            fee.inputs,
+        );
+        Ok(api_fee)
+    }
+}
+
+pub(super) async fn get_tx_fee<D: FeeSource>(
+    data: D,
     Json(body): Json<TxFeeRequest>,
 ) -> ApiResult<ApiFee> {
     let start = Instant::now();
-    let token_allowed = api_try!(data
-        .tx_sender
-        .ticker
-        .token_allowed_for_fees(body.token_like.clone())
-        .await
-        .map_err(Error::from));
-    if !token_allowed {
-        return Error::from(SubmitError::InappropriateFeeToken).into();
-    }
-    // TODO implement subsidies for v02 api ZKS-888
-    let res = data
-        .tx_sender
-        .ticker
-        .get_fee_from_ticker_in_wei(body.tx_type.into(), body.token_like, body.address)
-        .await
-        .map(|fee| fee.normal_fee.into())
-        .map_err(Error::from)
-        .into();
+    let res = data.tx_fee(body).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_tx_fee");
     res
 }
 
-async fn get_batch_fee(
-    data: web::Data<ApiFeeData>,
+pub(super) async fn get_batch_fee<D: FeeSource>(
+    data: D,
     Json(body): Json<BatchFeeRequest>,
 ) -> ApiResult<ApiFee> {
     let start = Instant::now();
-    let token_allowed = api_try!(data
-        .tx_sender
-        .ticker
-        .token_allowed_for_fees(body.token_like.clone())
-        .await
-        .map_err(Error::from));
-    if !token_allowed {
-        return Error::from(SubmitError::InappropriateFeeToken).into();
-    }
-    let txs = body
-        .transactions
-        .into_iter()
-        .map(|tx| (tx.tx_type.into(), tx.address))
-        .collect();
-    let res = data
-        .tx_sender
-        .ticker
-        .get_batch_from_ticker_in_wei(body.token_like, txs)
-        .await
-        .map(|fee| fee.normal_fee.into())
-        .map_err(Error::from)
-        .into();
+    let res = data.batch_fee(body).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_batch_fee");
     res
 }
 
-pub fn api_scope(tx_sender: TxSender) -> Scope {
-    let data = ApiFeeData::new(tx_sender);
+async fn fee_history(
+    data: web::Data<ApiFeeData>,
+    credentials: BearerAuth,
+    web::Query(query): web::Query<IncomingFeeHistoryQuery>,
+) -> actix_web::Result<ApiResult<Vec<ApiFeeQuote>>> {
+    authorize_admin(&data.secret_auth, &credentials)?;
+    let start = Instant::now();
+    let res = data.fee_history(query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "fee_history");
+    Ok(res)
+}
+
+pub fn api_scope(tx_sender: TxSender, secret_auth: String) -> Scope {
+    let data = ApiFeeData::new(tx_sender, secret_auth);
 
     web::scope("fee")
         .app_data(web::Data::new(data))
-        .route("", web::post().to(get_tx_fee))
-        .route("/batch", web::post().to(get_batch_fee))
+        .route("", web::post().to(get_tx_fee::<web::Data<ApiFeeData>>))
+        .route(
+            "/batch",
+            web::post().to(get_batch_fee::<web::Data<ApiFeeData>>),
+        )
+        .route("/history", web::get().to(fee_history))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
+        error::ErrorCode,
         test_utils::{
             deserialize_response_result, dummy_fee_ticker, dummy_sign_verifier, TestServerConfig,
         },
@@ -129,6 +319,7 @@ mod tests {
     )]
     async fn fee_scope() -> anyhow::Result<()> {
         let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
 
         let (mempool_tx_request_sender, _mempool_tx_request_receiver) = mpsc::channel(100);
 
@@ -152,6 +343,7 @@ mod tests {
             TokenMarketVolume {
                 market_volume: Ratio::from_integer(BigUint::from(400u32)),
                 last_updated: Utc::now(),
+                fee_allowed: None,
             },
         );
         let prices = vec![
@@ -165,14 +357,17 @@ mod tests {
             .with_market(market);
         let (client, server) = cfg.start_server(
             move |cfg: &TestServerConfig| {
-                api_scope(TxSender::new(
-                    cfg.pool.clone(),
-                    dummy_sign_verifier(),
-                    dummy_fee_ticker(&prices, Some(cache.clone())),
-                    &cfg.config.api.common,
-                    &cfg.config.api.token_config,
-                    mempool_tx_request_sender.clone(),
-                ))
+                api_scope(
+                    TxSender::new(
+                        cfg.pool.clone(),
+                        dummy_sign_verifier(),
+                        dummy_fee_ticker(&prices, Some(cache.clone())),
+                        &cfg.config.api.common,
+                        &cfg.config.api.token_config,
+                        mempool_tx_request_sender.clone(),
+                    ),
+                    cfg.config.api.admin.secret_auth.clone(),
+                )
             },
             Some(shared_data),
         );
@@ -201,15 +396,39 @@ mod tests {
         let tx = TxInBatchFeeRequest {
             tx_type: ApiTxFeeTypes::Withdraw,
             address: Address::default(),
+            token: None,
         };
-        let txs = vec![tx.clone(), tx.clone(), tx];
+        let txs = vec![tx.clone(), tx.clone(), tx.clone()];
 
-        let response = client.get_batch_fee(txs, allowed_token).await?;
+        let response = client.get_batch_fee(txs, allowed_token.clone()).await?;
         let api_batch_fee: ApiFee = deserialize_response_result(response)?;
         assert_eq!(api_batch_fee.gas_fee, BigUint::from(1u32));
         assert_eq!(api_batch_fee.zkp_fee, BigUint::from(1u32));
         assert_eq!(api_batch_fee.total_fee, BigUint::from(2u32));
 
+        // Transactions of different types and tokens are priced together.
+        let txs = vec![
+            TxInBatchFeeRequest {
+                token: Some(TokenLike::Id(TokenId(0))),
+                ..tx.clone()
+            },
+            TxInBatchFeeRequest {
+                tx_type: ApiTxFeeTypes::Transfer,
+                token: Some(TokenLike::Id(TokenId(0))),
+                ..tx.clone()
+            },
+        ];
+        let response = client.get_batch_fee(txs, allowed_token.clone()).await?;
+        let _: ApiFee = deserialize_response_result(response)?;
+
+        let txs = vec![TxInBatchFeeRequest {
+            token: Some(TokenLike::Symbol("UNKNOWN".into())),
+            ..tx
+        }];
+        let response = client.get_batch_fee(txs, allowed_token).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::TokenNotFound);
+
         server.stop().await;
         Ok(())
     }
@@ -0,0 +1,635 @@
+//! GraphQL part of API implementation.
+//!
+//! Read-only facade over the storage exposing the blocks, transactions, accounts and tokens
+//! together with the relations between them (block → transactions → accounts → balances → tokens),
+//! so the analytics consumers can fetch the data they need with a single tailored query instead of
+//! stitching many REST calls. The depth and the complexity of the queries are limited,
+//! see `GraphQlConfig`.
+
+// Built-in uses
+use std::str::FromStr;
+use std::time::Instant;
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    HttpResponse, Scope,
+};
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, Error as GqlError, Object,
+    Result as GqlResult, Schema,
+};
+use chrono::{DateTime, Utc};
+use num::BigUint;
+
+// Workspace uses
+use zksync_api_types::v02::{
+    account::AccountAddressOrId,
+    block::{BlockInfo, BlockStatus as ApiBlockStatus},
+    pagination::{
+        AccountTxsRequest, ApiEither, BlockAndTxHash, PaginationDirection, PaginationQuery,
+    },
+    transaction::{Transaction, TxInBlockStatus},
+};
+use zksync_config::configs::api::GraphQlConfig;
+use zksync_crypto::convert::FeConvert;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_token_db_cache::TokenDBCache;
+use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, Token, TokenId, TokenLike};
+
+// Local uses
+use super::{account::parse_account_id_or_address, block::block_info_from_details};
+use crate::utils::block_details_cache::BlockDetailsCache;
+
+type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Shared data between the resolvers.
+struct GraphQlData {
+    pool: ConnectionPool,
+    tokens: TokenDBCache,
+    verified_blocks_cache: BlockDetailsCache,
+    max_page_size: u32,
+}
+
+impl GraphQlData {
+    fn page_size(&self, limit: Option<u32>) -> u32 {
+        limit.unwrap_or(self.max_page_size).min(self.max_page_size)
+    }
+
+    async fn block(&self, block_number: BlockNumber) -> GqlResult<Option<BlockNode>> {
+        let details = self
+            .verified_blocks_cache
+            .get(&self.pool, block_number)
+            .await?;
+        Ok(details.map(|details| BlockNode(block_info_from_details(details))))
+    }
+
+    async fn token(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token: TokenLike,
+    ) -> GqlResult<Option<TokenNode>> {
+        Ok(self.tokens.get_token(storage, token).await?.map(TokenNode))
+    }
+
+    async fn account(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        account: AccountAddressOrId,
+    ) -> GqlResult<Option<AccountNode>> {
+        let account_id = match account {
+            AccountAddressOrId::Id(account_id) => Some(account_id),
+            AccountAddressOrId::Address(address) => {
+                storage
+                    .chain()
+                    .account_schema()
+                    .account_id_by_address(address)
+                    .await?
+            }
+        };
+        let account_id = match account_id {
+            Some(account_id) => account_id,
+            None => return Ok(None),
+        };
+        let (_, committed) = storage
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(account_id)
+            .await?;
+        Ok(committed.map(|account| AccountNode {
+            id: account_id,
+            account,
+        }))
+    }
+}
+
+fn data<'a>(ctx: &Context<'a>) -> GqlResult<&'a GraphQlData> {
+    ctx.data::<GraphQlData>()
+}
+
+fn format_tx_hash(tx_hash: &TxHash) -> String {
+    format!("0x{}", hex::encode(tx_hash.as_ref()))
+}
+
+fn parse_address(address: &str) -> Option<Address> {
+    Address::from_str(address.strip_prefix("0x").unwrap_or(address)).ok()
+}
+
+#[derive(Debug, Enum, Copy, Clone, PartialEq, Eq)]
+enum BlockStatus {
+    Committed,
+    Finalized,
+}
+
+impl From<ApiBlockStatus> for BlockStatus {
+    fn from(status: ApiBlockStatus) -> Self {
+        match status {
+            ApiBlockStatus::Committed => Self::Committed,
+            ApiBlockStatus::Finalized => Self::Finalized,
+        }
+    }
+}
+
+#[derive(Debug, Enum, Copy, Clone, PartialEq, Eq)]
+enum TxStatus {
+    Queued,
+    Pending,
+    Committed,
+    Finalized,
+    Rejected,
+}
+
+impl From<TxInBlockStatus> for TxStatus {
+    fn from(status: TxInBlockStatus) -> Self {
+        match status {
+            TxInBlockStatus::Queued => Self::Queued,
+            TxInBlockStatus::Pending => Self::Pending,
+            TxInBlockStatus::Committed => Self::Committed,
+            TxInBlockStatus::Finalized => Self::Finalized,
+            TxInBlockStatus::Rejected => Self::Rejected,
+        }
+    }
+}
+
+struct BlockNode(BlockInfo);
+
+#[Object(name = "Block")]
+impl BlockNode {
+    async fn number(&self) -> u32 {
+        *self.0.block_number
+    }
+
+    async fn new_state_root(&self) -> String {
+        format!("0x{}", self.0.new_state_root.to_hex())
+    }
+
+    async fn size(&self) -> u64 {
+        self.0.block_size
+    }
+
+    async fn status(&self) -> BlockStatus {
+        self.0.status.into()
+    }
+
+    async fn commit_tx_hash(&self) -> Option<String> {
+        self.0.commit_tx_hash.map(|hash| format!("{:?}", hash))
+    }
+
+    async fn verify_tx_hash(&self) -> Option<String> {
+        self.0.verify_tx_hash.map(|hash| format!("{:?}", hash))
+    }
+
+    async fn committed_at(&self) -> DateTime<Utc> {
+        self.0.committed_at
+    }
+
+    async fn finalized_at(&self) -> Option<DateTime<Utc>> {
+        self.0.finalized_at
+    }
+
+    /// Transactions of the block, starting from the last one.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u32>,
+    ) -> GqlResult<Vec<TransactionNode>> {
+        let data = data(ctx)?;
+        let query = PaginationQuery {
+            from: BlockAndTxHash {
+                block_number: self.0.block_number,
+                tx_hash: ApiEither::latest(),
+            },
+            limit: data.page_size(limit),
+            direction: PaginationDirection::Older,
+        };
+        let mut storage = data.pool.access_storage().await?;
+        let txs = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_page(&query)
+            .await?
+            .unwrap_or_default();
+        Ok(txs.into_iter().map(TransactionNode::from).collect())
+    }
+}
+
+struct TransactionNode {
+    tx_hash: TxHash,
+    block_number: Option<BlockNumber>,
+    block_index: Option<u32>,
+    status: TxInBlockStatus,
+    fail_reason: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+    op: serde_json::Value,
+}
+
+impl From<Transaction> for TransactionNode {
+    fn from(tx: Transaction) -> Self {
+        Self {
+            tx_hash: tx.tx_hash,
+            block_number: tx.block_number,
+            block_index: tx.block_index,
+            status: tx.status,
+            fail_reason: tx.fail_reason,
+            created_at: tx.created_at,
+            op: serde_json::to_value(&tx.op).unwrap_or_default(),
+        }
+    }
+}
+
+impl TransactionNode {
+    async fn account_by_field(
+        &self,
+        ctx: &Context<'_>,
+        field: &str,
+    ) -> GqlResult<Option<AccountNode>> {
+        let address = self
+            .op
+            .get(field)
+            .and_then(|value| value.as_str())
+            .and_then(parse_address);
+        let address = match address {
+            Some(address) => address,
+            None => return Ok(None),
+        };
+        let data = data(ctx)?;
+        let mut storage = data.pool.access_storage().await?;
+        data.account(&mut storage, AccountAddressOrId::Address(address))
+            .await
+    }
+}
+
+#[Object(name = "Transaction")]
+impl TransactionNode {
+    async fn hash(&self) -> String {
+        format_tx_hash(&self.tx_hash)
+    }
+
+    /// Type of the operation, e.g. `Transfer` or `Deposit`.
+    #[graphql(name = "type")]
+    async fn tx_type(&self) -> Option<String> {
+        self.op
+            .get("type")
+            .and_then(|value| value.as_str())
+            .map(String::from)
+    }
+
+    async fn status(&self) -> TxStatus {
+        self.status.into()
+    }
+
+    async fn fail_reason(&self) -> Option<&str> {
+        self.fail_reason.as_deref()
+    }
+
+    async fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    async fn block_index(&self) -> Option<u32> {
+        self.block_index
+    }
+
+    /// The operation in the same format as in the REST API.
+    async fn op(&self) -> async_graphql::Json<serde_json::Value> {
+        async_graphql::Json(self.op.clone())
+    }
+
+    async fn block(&self, ctx: &Context<'_>) -> GqlResult<Option<BlockNode>> {
+        match self.block_number {
+            Some(block_number) => data(ctx)?.block(block_number).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Account the operation is sent from, if the operation has the `from` field.
+    async fn from(&self, ctx: &Context<'_>) -> GqlResult<Option<AccountNode>> {
+        self.account_by_field(ctx, "from").await
+    }
+
+    /// Account the operation is sent to, if the operation has the `to` field.
+    async fn to(&self, ctx: &Context<'_>) -> GqlResult<Option<AccountNode>> {
+        self.account_by_field(ctx, "to").await
+    }
+}
+
+/// Committed state of the account.
+struct AccountNode {
+    id: AccountId,
+    account: zksync_types::Account,
+}
+
+#[Object(name = "Account")]
+impl AccountNode {
+    async fn id(&self) -> u32 {
+        *self.id
+    }
+
+    async fn address(&self) -> String {
+        format!("{:?}", self.account.address)
+    }
+
+    async fn nonce(&self) -> u32 {
+        *self.account.nonce
+    }
+
+    async fn pub_key_hash(&self) -> String {
+        self.account.pub_key_hash.as_hex()
+    }
+
+    /// Non-zero balances of the account.
+    async fn balances(&self) -> Vec<BalanceNode> {
+        let mut balances: Vec<_> = self
+            .account
+            .get_nonzero_balances()
+            .into_iter()
+            .map(|(token_id, balance)| BalanceNode {
+                token_id,
+                amount: balance.0,
+            })
+            .collect();
+        balances.sort_unstable_by_key(|balance| balance.token_id);
+        balances
+    }
+
+    /// Transactions of the account, starting from the last one.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u32>,
+    ) -> GqlResult<Vec<TransactionNode>> {
+        let data = data(ctx)?;
+        let query = PaginationQuery {
+            from: AccountTxsRequest {
+                address: self.account.address,
+                tx_hash: ApiEither::latest(),
+                token: None,
+                second_address: None,
+            },
+            limit: data.page_size(limit),
+            direction: PaginationDirection::Older,
+        };
+        let mut storage = data.pool.access_storage().await?;
+        let txs = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions(&query)
+            .await?
+            .unwrap_or_default();
+        Ok(txs.into_iter().map(TransactionNode::from).collect())
+    }
+}
+
+struct BalanceNode {
+    token_id: TokenId,
+    amount: BigUint,
+}
+
+#[Object(name = "Balance")]
+impl BalanceNode {
+    async fn amount(&self) -> String {
+        self.amount.to_string()
+    }
+
+    async fn token(&self, ctx: &Context<'_>) -> GqlResult<Option<TokenNode>> {
+        let data = data(ctx)?;
+        let mut storage = data.pool.access_storage().await?;
+        data.token(&mut storage, TokenLike::Id(self.token_id)).await
+    }
+}
+
+struct TokenNode(Token);
+
+#[Object(name = "Token")]
+impl TokenNode {
+    async fn id(&self) -> u32 {
+        *self.0.id
+    }
+
+    async fn address(&self) -> String {
+        format!("{:?}", self.0.address)
+    }
+
+    async fn symbol(&self) -> &str {
+        &self.0.symbol
+    }
+
+    async fn decimals(&self) -> u8 {
+        self.0.decimals
+    }
+
+    async fn is_nft(&self) -> bool {
+        self.0.is_nft
+    }
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn block(&self, ctx: &Context<'_>, number: u32) -> GqlResult<Option<BlockNode>> {
+        data(ctx)?.block(BlockNumber(number)).await
+    }
+
+    /// Committed blocks starting from the block `from`.
+    async fn blocks(
+        &self,
+        ctx: &Context<'_>,
+        from: u32,
+        limit: Option<u32>,
+    ) -> GqlResult<Vec<BlockNode>> {
+        let data = data(ctx)?;
+        let mut storage = data.pool.access_storage().await?;
+        let blocks = storage
+            .chain()
+            .block_schema()
+            .load_block_range_asc(BlockNumber(from), data.page_size(limit))
+            .await?;
+        Ok(blocks
+            .into_iter()
+            .map(|details| BlockNode(block_info_from_details(details)))
+            .collect())
+    }
+
+    async fn transaction(
+        &self,
+        ctx: &Context<'_>,
+        hash: String,
+    ) -> GqlResult<Option<TransactionNode>> {
+        let tx_hash = TxHash::from_str(&hash).map_err(GqlError::new)?;
+        let data = data(ctx)?;
+        let mut storage = data.pool.access_storage().await?;
+        let tx = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_data_api_v02(tx_hash.as_ref())
+            .await?;
+        Ok(tx.map(|tx| TransactionNode::from(tx.tx)))
+    }
+
+    /// Account with the given id or address.
+    async fn account(
+        &self,
+        ctx: &Context<'_>,
+        id_or_address: String,
+    ) -> GqlResult<Option<AccountNode>> {
+        let account = parse_account_id_or_address(&id_or_address)
+            .map_err(|err| GqlError::new(err.message))?;
+        let data = data(ctx)?;
+        let mut storage = data.pool.access_storage().await?;
+        data.account(&mut storage, account).await
+    }
+
+    /// Token with the given id, address or symbol.
+    async fn token(&self, ctx: &Context<'_>, token: String) -> GqlResult<Option<TokenNode>> {
+        let data = data(ctx)?;
+        let mut storage = data.pool.access_storage().await?;
+        data.token(&mut storage, TokenLike::parse(&token)).await
+    }
+
+    /// Tokens starting from the token with id `from`.
+    async fn tokens(
+        &self,
+        ctx: &Context<'_>,
+        from: Option<u32>,
+        limit: Option<u32>,
+    ) -> GqlResult<Vec<TokenNode>> {
+        let data = data(ctx)?;
+        let mut storage = data.pool.access_storage().await?;
+        let tokens = storage
+            .tokens_schema()
+            .load_tokens_asc(TokenId(from.unwrap_or(0)), Some(data.page_size(limit)))
+            .await?;
+        Ok(tokens.into_iter().map(TokenNode).collect())
+    }
+}
+
+fn build_schema(
+    pool: ConnectionPool,
+    tokens: TokenDBCache,
+    verified_blocks_cache: BlockDetailsCache,
+    config: &GraphQlConfig,
+) -> ApiSchema {
+    let data = GraphQlData {
+        pool,
+        tokens,
+        verified_blocks_cache,
+        max_page_size: config.max_page_size,
+    };
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(data)
+        .limit_depth(config.max_depth)
+        .limit_complexity(config.max_complexity)
+        .finish()
+}
+
+async fn execute_query(
+    schema: web::Data<ApiSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    let start = Instant::now();
+    let response = schema.execute(request).await;
+    if response.is_err() {
+        metrics::increment_counter!("api.graphql.failed_queries");
+    }
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "graphql");
+    Json(response)
+}
+
+/// Returns the schema in the SDL format, so the clients can generate the typed queries.
+async fn schema_sdl(schema: web::Data<ApiSchema>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(schema.sdl())
+}
+
+pub fn api_scope(
+    pool: ConnectionPool,
+    tokens: TokenDBCache,
+    verified_blocks_cache: BlockDetailsCache,
+    config: &GraphQlConfig,
+) -> Scope {
+    let schema = build_schema(pool, tokens, verified_blocks_cache, config);
+
+    web::scope("graphql")
+        .app_data(web::Data::new(schema))
+        .route("", web::post().to(execute_query))
+        .route("schema", web::get().to(schema_sdl))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::test_utils::TestServerConfig, *};
+    use serde_json::json;
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn graphql_queries() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let schema = build_schema(
+            cfg.pool.clone(),
+            TokenDBCache::new(cfg.config.api.token_config.invalidate_token_cache_period()),
+            BlockDetailsCache::new(10),
+            &cfg.config.api.graphql,
+        );
+        let (expected_block, block_txs) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let details = storage
+                .chain()
+                .block_schema()
+                .load_block_range_asc(BlockNumber(1), 1)
+                .await?
+                .remove(0);
+            let block_txs = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(1))
+                .await?;
+            (block_info_from_details(details), block_txs)
+        };
+
+        let query = r#"{
+            block(number: 1) {
+                number
+                status
+                transactions(limit: 2) {
+                    hash
+                    block { number }
+                }
+            }
+            token(token: "ETH") { id symbol }
+        }"#;
+        let response = schema.execute(query).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+        let data = response.data.into_json()?;
+
+        let expected_status = match expected_block.status {
+            ApiBlockStatus::Committed => "COMMITTED",
+            ApiBlockStatus::Finalized => "FINALIZED",
+        };
+        assert_eq!(data["block"]["number"], json!(1));
+        assert_eq!(data["block"]["status"], json!(expected_status));
+        let txs = data["block"]["transactions"].as_array().unwrap();
+        assert_eq!(txs.len(), block_txs.len().min(2));
+        for tx in txs {
+            assert_eq!(tx["block"]["number"], json!(1));
+        }
+        assert_eq!(data["token"], json!({ "id": 0, "symbol": "ETH" }));
+
+        // Too deep queries are rejected.
+        let mut selection = "number".to_string();
+        for _ in 0..cfg.config.api.graphql.max_depth {
+            selection = format!("transactions {{ block {{ {} }} }}", selection);
+        }
+        let query = format!("{{ block(number: 1) {{ {} }} }}", selection);
+        let response = schema.execute(query.as_str()).await;
+        assert!(response.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+//! Mempool part of API implementation.
+//!
+//! Public feed of the pending transactions. Transactions are exposed only after the
+//! configured delay and may be partially redacted, see `MempoolFeedConfig`.
+//!
+//! Every route exposing the pending transactions goes through `MempoolRedaction`, so that
+//! the fields hidden by the feed can't be recovered via another route.
+
+// Built-in uses
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+use chrono::Utc;
+use num::BigUint;
+
+// Workspace uses
+use zksync_api_types::v02::{
+    mempool::PendingTx,
+    transaction::{L2Transaction, TransactionData, TxData, TxInBlockStatus},
+};
+use zksync_config::configs::api::MempoolFeedConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::{AccountId, Address, SignedZkSyncTx, ZkSyncTx};
+
+// Local uses
+use super::{error::Error, response::ApiResult};
+use crate::api_try;
+
+/// Shared data between `api/v0.2/mempool` endpoints.
+#[derive(Clone)]
+struct ApiMempoolData {
+    pool: ConnectionPool,
+    config: MempoolFeedConfig,
+    redaction: MempoolRedaction,
+}
+
+impl ApiMempoolData {
+    fn new(pool: ConnectionPool, config: MempoolFeedConfig) -> Self {
+        let redaction = MempoolRedaction::new(&config);
+        Self {
+            pool,
+            config,
+            redaction,
+        }
+    }
+
+    async fn pending_txs(&self) -> Result<Vec<PendingTx>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let created_before = Utc::now()
+            - chrono::Duration::from_std(self.config.min_age())
+                .expect("Mempool feed delay is too big");
+        let txs = storage
+            .chain()
+            .mempool_schema()
+            .load_pending_txs(None, created_before, self.config.max_txs)
+            .await
+            .map_err(Error::storage)?;
+
+        Ok(txs.iter().map(|tx| self.redaction.pending_tx(tx)).collect())
+    }
+}
+
+/// Redaction rules of the public mempool feed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MempoolRedaction {
+    amounts: bool,
+    fees: bool,
+    addresses: bool,
+}
+
+impl MempoolRedaction {
+    /// Creates the redaction rules of the feed. Nothing is redacted if the feed is disabled,
+    /// since the pending transactions aren't exposed at all in this case.
+    pub fn new(config: &MempoolFeedConfig) -> Self {
+        if !config.enabled {
+            return Self::default();
+        }
+        Self {
+            amounts: config.redact_amounts,
+            fees: config.redact_fees,
+            addresses: config.redact_addresses,
+        }
+    }
+
+    fn redacts_anything(&self) -> bool {
+        self.amounts || self.fees || self.addresses
+    }
+
+    /// Redacts the data of the queued transaction returned by its hash, so that the fields
+    /// hidden by the feed can't be looked up by the announced hash.
+    ///
+    /// Only the fields named by the rules are zeroed, the rest of the data is kept as is.
+    /// The Ethereum signature spells out the amounts, the fee and the recipient in its
+    /// message, so it's dropped if anything is redacted. Executed transactions are public
+    /// and aren't redacted.
+    pub fn redact_queued_tx_data(&self, data: &mut TxData) {
+        if data.tx.status != TxInBlockStatus::Queued || !self.redacts_anything() {
+            return;
+        }
+        data.eth_signature = None;
+
+        let tx = match &mut data.tx.op {
+            TransactionData::L2(tx) => tx,
+            // Priority operations are announced by the contract anyway.
+            TransactionData::L1(_) => return,
+        };
+        match tx {
+            L2Transaction::Transfer(tx) => {
+                self.redact_amount(&mut tx.amount);
+                self.redact_fee(&mut tx.fee);
+                self.redact_account(&mut tx.account_id);
+                self.redact_address(&mut tx.from);
+                self.redact_address(&mut tx.to);
+            }
+            L2Transaction::Withdraw(tx) => {
+                self.redact_amount(&mut tx.tx.amount);
+                self.redact_fee(&mut tx.tx.fee);
+                self.redact_account(&mut tx.tx.account_id);
+                self.redact_address(&mut tx.tx.from);
+                self.redact_address(&mut tx.tx.to);
+            }
+            L2Transaction::Close(tx) => {
+                self.redact_address(&mut tx.account);
+            }
+            L2Transaction::ChangePubKey(tx) => {
+                self.redact_fee(&mut tx.fee);
+                self.redact_account(&mut tx.account_id);
+                self.redact_address(&mut tx.account);
+            }
+            L2Transaction::ForcedExit(tx) => {
+                self.redact_fee(&mut tx.tx.fee);
+                self.redact_account(&mut tx.tx.initiator_account_id);
+                self.redact_address(&mut tx.tx.target);
+            }
+            L2Transaction::MintNFT(tx) => {
+                self.redact_fee(&mut tx.fee);
+                self.redact_account(&mut tx.creator_id);
+                self.redact_address(&mut tx.creator_address);
+                self.redact_address(&mut tx.recipient);
+            }
+            L2Transaction::Swap(tx) => {
+                self.redact_amount(&mut tx.amounts.0);
+                self.redact_amount(&mut tx.amounts.1);
+                self.redact_fee(&mut tx.fee);
+                self.redact_account(&mut tx.submitter_id);
+                self.redact_address(&mut tx.submitter_address);
+                for order in [&mut tx.orders.0, &mut tx.orders.1] {
+                    self.redact_amount(&mut order.amount);
+                    self.redact_account(&mut order.account_id);
+                    self.redact_address(&mut order.recipient_address);
+                }
+            }
+            L2Transaction::WithdrawNFT(tx) => {
+                self.redact_fee(&mut tx.tx.fee);
+                self.redact_account(&mut tx.tx.account_id);
+                self.redact_address(&mut tx.tx.from);
+                self.redact_address(&mut tx.tx.to);
+            }
+        }
+    }
+
+    fn redact_amount(&self, amount: &mut BigUint) {
+        if self.amounts {
+            *amount = Default::default();
+        }
+    }
+
+    fn redact_fee(&self, fee: &mut BigUint) {
+        if self.fees {
+            *fee = Default::default();
+        }
+    }
+
+    fn redact_account(&self, account_id: &mut AccountId) {
+        if self.addresses {
+            *account_id = AccountId(0);
+        }
+    }
+
+    fn redact_address(&self, address: &mut Address) {
+        if self.addresses {
+            *address = Address::zero();
+        }
+    }
+
+    /// Converts the mempool transaction into its public representation.
+    pub fn pending_tx(&self, tx: &SignedZkSyncTx) -> PendingTx {
+        let amount = match &tx.tx {
+            ZkSyncTx::Transfer(tx) => Some(tx.amount.clone()),
+            ZkSyncTx::Withdraw(tx) => Some(tx.amount.clone()),
+            _ => None,
+        };
+        let fee = tx.get_fee_info().map(|(_, _, _, fee)| fee);
+        // Both the account id and the hash identify the sender as well as its address.
+        let (tx_hash, account_id, from, to) = if self.addresses {
+            (None, None, None, None)
+        } else {
+            (
+                Some(tx.hash()),
+                tx.account_id().ok(),
+                Some(tx.from_account()),
+                tx.to_account(),
+            )
+        };
+
+        PendingTx {
+            tx_hash,
+            tx_type: tx.variance_name(),
+            account_id,
+            from,
+            to,
+            token: tx.token_id(),
+            amount: amount.filter(|_| !self.amounts).map(Into::into),
+            fee: fee.filter(|_| !self.fees).map(Into::into),
+            created_at: tx.created_at,
+        }
+    }
+}
+
+// Server implementation
+
+async fn get_pending_txs(data: web::Data<ApiMempoolData>) -> ApiResult<Vec<PendingTx>> {
+    let start = Instant::now();
+    let txs = api_try!(data.pending_txs().await);
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_pending_txs");
+    ApiResult::Ok(txs)
+}
+
+pub fn api_scope(pool: ConnectionPool, config: MempoolFeedConfig) -> Scope {
+    let data = ApiMempoolData::new(pool, config);
+
+    web::scope("mempool")
+        .app_data(web::Data::new(data))
+        .route("txs", web::get().to(get_pending_txs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_api_types::v02::transaction::Transaction;
+    use zksync_types::{tx::Transfer, Nonce, TokenId};
+    use zksync_utils::BigUintSerdeWrapper;
+
+    fn transfer() -> SignedZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(1),
+            Address::random(),
+            Address::random(),
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn pending_tx_redaction() {
+        let tx = transfer();
+        let mut config = MempoolFeedConfig {
+            enabled: true,
+            min_age_secs: 0,
+            max_txs: 100,
+            redact_amounts: false,
+            redact_fees: false,
+            redact_addresses: false,
+        };
+
+        let redaction = MempoolRedaction::new(&config);
+        let pending = redaction.pending_tx(&tx);
+        assert_eq!(pending.tx_hash, Some(tx.hash()));
+        assert_eq!(pending.tx_type, "Transfer");
+        assert_eq!(pending.account_id, Some(AccountId(1)));
+        assert_eq!(pending.from, Some(tx.from_account()));
+        assert_eq!(pending.to, tx.to_account());
+        assert_eq!(pending.amount, Some(BigUintSerdeWrapper(100u32.into())));
+        assert_eq!(pending.fee, Some(BigUintSerdeWrapper(10u32.into())));
+
+        config.redact_amounts = true;
+        config.redact_fees = true;
+        config.redact_addresses = true;
+        let redaction = MempoolRedaction::new(&config);
+        let pending = redaction.pending_tx(&tx);
+        assert_eq!(pending.tx_hash, None);
+        assert_eq!(pending.account_id, None);
+        assert_eq!(pending.from, None);
+        assert_eq!(pending.to, None);
+        assert_eq!(pending.amount, None);
+        assert_eq!(pending.fee, None);
+
+        // Nothing is exposed by the disabled feed, so there is nothing to redact.
+        config.enabled = false;
+        assert_eq!(MempoolRedaction::new(&config), MempoolRedaction::default());
+    }
+
+    #[test]
+    fn queued_tx_data_redaction() {
+        let tx = transfer();
+        let transfer = match &tx.tx {
+            ZkSyncTx::Transfer(transfer) => transfer.clone(),
+            _ => unreachable!(),
+        };
+        let tx_data = |status| TxData {
+            tx: Transaction {
+                tx_hash: tx.hash(),
+                block_index: None,
+                block_number: None,
+                op: TransactionData::L2(L2Transaction::Transfer(transfer.clone())),
+                status,
+                fail_reason: None,
+                created_at: None,
+                batch_id: None,
+            },
+            eth_signature: Some("0x00".to_string()),
+        };
+        let transfer_of = |data: &TxData| match &data.tx.op {
+            TransactionData::L2(L2Transaction::Transfer(tx)) => tx.clone(),
+            _ => panic!("Transfer expected"),
+        };
+        let mut config = MempoolFeedConfig {
+            enabled: true,
+            min_age_secs: 0,
+            max_txs: 100,
+            redact_amounts: true,
+            redact_fees: false,
+            redact_addresses: false,
+        };
+
+        // Only the amount is redacted, the rest of the queued transaction is kept.
+        let redaction = MempoolRedaction::new(&config);
+        let mut data = tx_data(TxInBlockStatus::Queued);
+        redaction.redact_queued_tx_data(&mut data);
+        let redacted = transfer_of(&data);
+        assert_eq!(redacted.amount, 0u32.into());
+        assert_eq!(redacted.fee, 10u32.into());
+        assert_eq!(redacted.account_id, AccountId(1));
+        assert_eq!(redacted.from, tx.from_account());
+        assert_eq!(data.eth_signature, None);
+
+        // Executed transactions are public.
+        let mut data = tx_data(TxInBlockStatus::Committed);
+        redaction.redact_queued_tx_data(&mut data);
+        assert_eq!(transfer_of(&data).amount, 100u32.into());
+        assert!(data.eth_signature.is_some());
+
+        config.redact_amounts = false;
+        config.redact_addresses = true;
+        let redaction = MempoolRedaction::new(&config);
+        let mut data = tx_data(TxInBlockStatus::Queued);
+        redaction.redact_queued_tx_data(&mut data);
+        let redacted = transfer_of(&data);
+        assert_eq!(redacted.amount, 100u32.into());
+        assert_eq!(redacted.account_id, AccountId(0));
+        assert_eq!(redacted.from, Address::zero());
+        assert_eq!(redacted.to, Address::zero());
+
+        // Nothing is touched without the redaction.
+        config.redact_addresses = false;
+        let redaction = MempoolRedaction::new(&config);
+        let mut data = tx_data(TxInBlockStatus::Queued);
+        redaction.redact_queued_tx_data(&mut data);
+        assert_eq!(transfer_of(&data).from, tx.from_account());
+        assert!(data.eth_signature.is_some());
+    }
+}
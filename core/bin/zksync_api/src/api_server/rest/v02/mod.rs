@@ -7,24 +7,43 @@ use actix_web::{
 use crate::api_server::rest::network_status::SharedNetworkStatus;
 use zksync_api_types::v02::ApiVersion;
 use zksync_config::ZkSyncConfig;
+use zksync_eth_client::EthereumGateway;
+use zksync_state::shared::SharedCommittedState;
+use zksync_storage::ConnectionPool;
 use zksync_types::network::Network;
 
 // Local uses
+use crate::api_server::eth_head::EthHeadCache;
 use crate::api_server::tx_sender::TxSender;
+use crate::eth_checker::EthereumChecker;
 
 mod account;
+mod archive;
 mod block;
+pub mod block_stream;
 mod config;
+pub mod encrypted_tx;
 pub mod error;
+mod error_codes;
+mod event;
+mod event_cursor;
 mod fee;
+mod graphql;
+mod mempool;
+mod openapi;
 mod paginate_impl;
 mod paginate_trait;
+mod recovery;
 mod response;
+pub mod sandbox;
+mod search;
 mod status;
 #[cfg(test)]
 pub mod test_utils;
 mod token;
 mod transaction;
+mod withdrawal;
+pub mod ws;
 
 #[derive(Debug, Clone, Copy)]
 pub struct SharedData {
@@ -34,26 +53,54 @@ pub struct SharedData {
 
 pub(crate) fn api_scope(
     tx_sender: TxSender,
+    main_database_pool: ConnectionPool,
     zk_config: &ZkSyncConfig,
     network_status: SharedNetworkStatus,
+    block_stream: block_stream::BlockStream,
+    committed_state: Option<SharedCommittedState>,
+    eth_gateway: EthereumGateway,
 ) -> Scope {
     let data = SharedData {
         net: zk_config.chain.eth.network,
         api_version: ApiVersion::V02,
     };
-    web::scope("/api/v0.2")
+    let mut scope = web::scope("/api/v0.2")
         .app_data(web::Data::new(data))
         .service(account::api_scope(
             tx_sender.pool.clone(),
+            main_database_pool,
             tx_sender.tokens.clone(),
             zk_config.eth_watch.confirmations_for_eth_event,
+            zk_config.eth_watch.confirmations_for_full_exit,
+            zk_config.api.common.max_accounts_per_batch_request,
+            committed_state,
+            EthereumChecker::new(eth_gateway.clone()),
+            EthHeadCache::new(eth_gateway),
+            zk_config.contracts.contract_addr,
         ))
         .service(block::api_scope(
             tx_sender.pool.clone(),
             tx_sender.blocks.clone(),
+            block_stream,
+        ))
+        .service(config::api_scope(zk_config, tx_sender.pool.clone()))
+        .service(error_codes::api_scope())
+        .service(event::api_scope(
+            tx_sender.pool.clone(),
+            tx_sender.tokens.clone(),
+        ))
+        .service(fee::api_scope(
+            tx_sender.clone(),
+            zk_config.api.admin.secret_auth.clone(),
+        ))
+        .service(recovery::api_scope(
+            tx_sender.clone(),
+            zk_config.api.recovery.clone(),
+        ))
+        .service(search::api_scope(
+            tx_sender.pool.clone(),
+            tx_sender.tokens.clone(),
         ))
-        .service(config::api_scope(zk_config))
-        .service(fee::api_scope(tx_sender.clone()))
         .service(status::api_scope(network_status))
         .service(token::api_scope(
             zk_config,
@@ -61,5 +108,40 @@ pub(crate) fn api_scope(
             tx_sender.tokens.clone(),
             tx_sender.ticker.clone(),
         ))
-        .service(transaction::api_scope(tx_sender))
+        .service(transaction::api_scope(
+            tx_sender.clone(),
+            archive::ArchiveClient::new(&zk_config.api.archive),
+            mempool::MempoolRedaction::new(&zk_config.api.mempool_feed),
+        ))
+        .service(withdrawal::api_scope(tx_sender.pool.clone()))
+        .service(openapi::spec_resource(openapi::ApiFeatures::new(
+            &zk_config.api,
+        )));
+
+    if let Some(docs) = openapi::docs_scope(&zk_config.api.rest.swagger_ui_dir) {
+        scope = scope.service(docs);
+    }
+
+    if zk_config.api.encrypted_txs.enabled {
+        scope = scope.service(encrypted_tx::api_scope(
+            tx_sender.pool.clone(),
+            zk_config.api.encrypted_txs.clone(),
+            zk_config.api.rate_limit.trusted_proxies(),
+        ));
+    }
+    if zk_config.api.graphql.enabled {
+        scope = scope.service(graphql::api_scope(
+            tx_sender.pool.clone(),
+            tx_sender.tokens.clone(),
+            tx_sender.blocks.clone(),
+            &zk_config.api.graphql,
+        ));
+    }
+    if zk_config.api.mempool_feed.enabled {
+        scope = scope.service(mempool::api_scope(
+            tx_sender.pool,
+            zk_config.api.mempool_feed.clone(),
+        ));
+    }
+    scope
 }
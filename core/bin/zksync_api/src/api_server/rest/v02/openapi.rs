@@ -0,0 +1,1640 @@
+//! OpenAPI 3 specification of the REST v0.2 API.
+//!
+//! The document is built from the table of endpoints below, which mirrors the routes registered
+//! by the `api_scope` functions of the sibling modules. Every response is wrapped into the common
+//! `Response` envelope, so only the schema of the `result` field is described per endpoint.
+//! The specification is served at `/api/v0.2/openapi.json`, and the Swagger UI at `/api/v0.2/docs`.
+//! The Swagger UI assets are served from the local copy of the `swagger-ui-dist` package, so the
+//! page doesn't load any scripts from the third-party hosts.
+
+// Built-in uses
+use std::path::Path;
+
+// External uses
+use actix_web::{web, HttpResponse, Resource, Scope};
+use serde_json::{json, Map, Value};
+
+// Workspace uses
+use zksync_api_types::v02::pagination::MAX_LIMIT;
+use zksync_config::configs::api::ApiConfig;
+
+/// Optional parts of the API, the endpoints of which are documented only if they are enabled.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ApiFeatures {
+    pub mempool_feed: bool,
+    pub encrypted_txs: bool,
+    pub graphql: bool,
+}
+
+impl ApiFeatures {
+    pub fn new(config: &ApiConfig) -> Self {
+        Self {
+            mempool_feed: config.mempool_feed.enabled,
+            encrypted_txs: config.encrypted_txs.enabled,
+            graphql: config.graphql.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    Get,
+    Post,
+}
+
+impl Method {
+    fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "get",
+            Method::Post => "post",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct QueryParam {
+    name: &'static str,
+    required: bool,
+    schema: Value,
+    description: &'static str,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    method: Method,
+    /// Path relative to `/api/v0.2` in the actix format, e.g. `/blocks/{block_position}`.
+    path: &'static str,
+    operation_id: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+    query: Vec<QueryParam>,
+    /// Name of the request body schema.
+    body: Option<&'static str>,
+    /// Schema of the `result` field of the response.
+    result: Value,
+    /// Media type of the response.
+    content_type: &'static str,
+    /// Whether the result is wrapped into the `Response` envelope.
+    envelope: bool,
+}
+
+impl Endpoint {
+    fn get(path: &'static str, operation_id: &'static str, summary: &'static str) -> Self {
+        Self {
+            method: Method::Get,
+            path,
+            operation_id,
+            tag: tag_of(path),
+            summary,
+            query: Vec::new(),
+            body: None,
+            result: json!({}),
+            content_type: "application/json",
+            envelope: true,
+        }
+    }
+
+    fn post(
+        path: &'static str,
+        operation_id: &'static str,
+        summary: &'static str,
+        body: &'static str,
+    ) -> Self {
+        Self {
+            method: Method::Post,
+            body: Some(body),
+            ..Self::get(path, operation_id, summary)
+        }
+    }
+
+    fn result(mut self, result: Value) -> Self {
+        self.result = result;
+        self
+    }
+
+    fn query(mut self, params: Vec<QueryParam>) -> Self {
+        self.query.extend(params);
+        self
+    }
+
+    /// Sets the media type of the response, such responses are not wrapped into the envelope.
+    fn content_type(mut self, content_type: &'static str) -> Self {
+        self.content_type = content_type;
+        self.without_envelope()
+    }
+
+    fn without_envelope(mut self) -> Self {
+        self.envelope = false;
+        self
+    }
+
+    fn path_params(&self) -> Vec<&'static str> {
+        self.path
+            .split('/')
+            .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+            .collect()
+    }
+
+    fn operation(&self) -> Value {
+        let mut parameters: Vec<Value> = self
+            .path_params()
+            .into_iter()
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect();
+        parameters.extend(self.query.iter().map(|param| {
+            json!({
+                "name": param.name,
+                "in": "query",
+                "required": param.required,
+                "schema": param.schema,
+                "description": param.description,
+            })
+        }));
+
+        let response_schema = if self.envelope {
+            json!({
+                "allOf": [
+                    schema_ref("Response"),
+                    { "type": "object", "properties": { "result": self.result } },
+                ]
+            })
+        } else {
+            self.result.clone()
+        };
+        let mut operation = json!({
+            "operationId": self.operation_id,
+            "tags": [self.tag],
+            "summary": self.summary,
+            "parameters": parameters,
+            "responses": {
+                "200": {
+                    "description": "Successful response or the error wrapped into the response envelope",
+                    "content": { self.content_type: { "schema": response_schema } },
+                }
+            },
+        });
+        if let Some(body) = self.body {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": schema_ref(body) } },
+            });
+        }
+        operation
+    }
+}
+
+/// Tag is the first segment of the path, i.e. the name of the API module.
+fn tag_of(path: &'static str) -> &'static str {
+    path.trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default()
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({ "nullable": true, "allOf": [schema] })
+}
+
+fn array_of(schema: Value) -> Value {
+    json!({ "type": "array", "items": schema })
+}
+
+fn string(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+fn integer() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn object(properties: Value) -> Value {
+    json!({ "type": "object", "properties": properties })
+}
+
+fn paginated(item: Value, from: Value) -> Value {
+    object(json!({
+        "list": array_of(item),
+        "pagination": object(json!({
+            "from": from,
+            "limit": integer(),
+            "direction": schema_ref("PaginationDirection"),
+            "count": integer(),
+        })),
+    }))
+}
+
+fn cursor_paginated(item: Value) -> Value {
+    object(json!({
+        "list": array_of(item),
+        "pagination": object(json!({
+            "from": nullable(string("Opaque cursor")),
+            "limit": integer(),
+            "direction": schema_ref("PaginationDirection"),
+            "next": nullable(string("Cursor of the next page, absent on the last page")),
+        })),
+    }))
+}
+
+fn pagination_params(from_description: &'static str) -> Vec<QueryParam> {
+    vec![
+        QueryParam {
+            name: "from",
+            required: true,
+            schema: json!({ "type": "string" }),
+            description: from_description,
+        },
+        QueryParam {
+            name: "limit",
+            required: true,
+            schema: json!({ "type": "integer", "minimum": 0, "maximum": MAX_LIMIT }),
+            description: "Maximum number of items in the page",
+        },
+        QueryParam {
+            name: "direction",
+            required: true,
+            schema: schema_ref("PaginationDirection"),
+            description: "Direction of the pagination",
+        },
+    ]
+}
+
+fn cursor_pagination_params() -> Vec<QueryParam> {
+    vec![
+        QueryParam {
+            name: "from",
+            required: false,
+            schema: json!({ "type": "string" }),
+            description: "Cursor returned with the previous page, absent for the first page",
+        },
+        QueryParam {
+            name: "limit",
+            required: true,
+            schema: json!({ "type": "integer", "minimum": 0, "maximum": MAX_LIMIT }),
+            description: "Maximum number of items in the page",
+        },
+        QueryParam {
+            name: "direction",
+            required: true,
+            schema: schema_ref("PaginationDirection"),
+            description: "Direction of the pagination",
+        },
+    ]
+}
+
+fn account_txs_filters() -> Vec<QueryParam> {
+    vec![
+        QueryParam {
+            name: "token",
+            required: false,
+            schema: json!({ "type": "string" }),
+            description: "Only transactions with the token (id, address or symbol)",
+        },
+        QueryParam {
+            name: "secondAccount",
+            required: false,
+            schema: json!({ "type": "string" }),
+            description: "Only transactions between the two accounts (id or address)",
+        },
+    ]
+}
+
+fn endpoints(features: ApiFeatures) -> Vec<Endpoint> {
+    let mut endpoints = vec![
+        // Accounts
+        Endpoint::get(
+            "/accounts/{account_id_or_address}",
+            "getAccount",
+            "Depositing, committed and finalized state of the account",
+        )
+        .query(vec![QueryParam {
+            name: "depositingVersion",
+            required: false,
+            schema: json!({ "type": "string", "enum": ["v1", "v2"] }),
+            description: "`v2` additionally returns the depositing balances keyed by the token id",
+        }])
+        .result(schema_ref("AccountState")),
+        Endpoint::post(
+            "/accounts/batch",
+            "getAccountsBatch",
+            "Committed and finalized states of the accounts, in the order of the requested addresses",
+            "AccountsBatchRequest",
+        )
+        .result(array_of(schema_ref("AccountBatchState"))),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/committed",
+            "getAccountCommitted",
+            "Committed state of the account",
+        )
+        .result(nullable(schema_ref("Account"))),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/finalized",
+            "getAccountFinalized",
+            "Finalized state of the account",
+        )
+        .result(nullable(schema_ref("Account"))),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/transactions",
+            "getAccountTransactions",
+            "Page of the account transactions",
+        )
+        .query(pagination_params("Transaction hash or `latest`"))
+        .query(account_txs_filters())
+        .result(paginated(
+            schema_ref("Transaction"),
+            string("Transaction hash"),
+        )),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/transactions/history",
+            "getAccountTransactionsByCursor",
+            "Page of the account transactions, paginated with the opaque cursor",
+        )
+        .query(cursor_pagination_params())
+        .query(account_txs_filters())
+        .result(cursor_paginated(schema_ref("Transaction"))),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/transactions/pending",
+            "getAccountPendingTransactions",
+            "Page of the account priority operations that are not executed yet",
+        )
+        .query(pagination_params(
+            "Serial id of the priority operation or `latest`",
+        ))
+        .result(paginated(schema_ref("Transaction"), integer())),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/events",
+            "getAccountStateChanges",
+            "Changes of the account state in the range of blocks, in the order of applying",
+        )
+        .query(vec![
+            QueryParam {
+                name: "fromBlock",
+                required: false,
+                schema: integer(),
+                description: "First block of the range, inclusive",
+            },
+            QueryParam {
+                name: "toBlock",
+                required: false,
+                schema: integer(),
+                description: "Last block of the range, inclusive, the last committed block by default",
+            },
+        ])
+        .result(schema_ref("AccountStateChanges")),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/nonce/next",
+            "getAccountNextNonce",
+            "Nonce for the next transaction of the account, taking the queued transactions into account",
+        )
+        .result(schema_ref("AccountNextNonce")),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/pubkey_auth/{nonce}",
+            "getAccountPubKeyAuth",
+            "Onchain authorization of the `ChangePubKey` with the nonce and its confirmations",
+        )
+        .result(schema_ref("PubKeyAuthStatus")),
+        Endpoint {
+            method: Method::Post,
+            ..Endpoint::get(
+                "/accounts/{account_id_or_address}/exit_proof/{token}",
+                "requestAccountExitProof",
+                "Queues the exit proof of the nonzero finalized balance unless the up to date one exists",
+            )
+        }
+        .result(schema_ref("ExitProofInfo")),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/exit_proof/{token}",
+            "getAccountExitProof",
+            "State of the requested exit proof, `null` if it's never been requested",
+        )
+        .result(nullable(schema_ref("ExitProofInfo"))),
+        Endpoint::get(
+            "/accounts/{account_id_or_address}/full_exits",
+            "getAccountFullExits",
+            "Full exits requested by the account on L1, both in-flight and executed, the newest first",
+        )
+        .result(schema_ref("AccountFullExits")),
+        // Blocks
+        Endpoint::get("/blocks", "getBlocks", "Page of the blocks")
+            .query(pagination_params("Block number or `latest`"))
+            .result(paginated(schema_ref("BlockInfo"), integer())),
+        Endpoint::get(
+            "/blocks/stream",
+            "streamBlocks",
+            "Server-Sent Events stream of the committed and finalized blocks",
+        )
+        .content_type("text/event-stream")
+        .result(schema_ref("BlockStreamEvent")),
+        Endpoint::get(
+            "/blocks/range",
+            "getBlockRange",
+            "Committed blocks of the range in the ascending order, up to 100 blocks",
+        )
+        .query(vec![
+            QueryParam {
+                name: "from",
+                required: true,
+                schema: integer(),
+                description: "First block of the range, inclusive",
+            },
+            QueryParam {
+                name: "to",
+                required: true,
+                schema: integer(),
+                description: "Last block of the range, inclusive",
+            },
+            QueryParam {
+                name: "status",
+                required: false,
+                schema: schema_ref("BlockStatus"),
+                description: "Only the blocks with the status",
+            },
+        ])
+        .result(array_of(schema_ref("BlockInfo"))),
+        Endpoint::get(
+            "/blocks/{block_position}",
+            "getBlock",
+            "Block by its number, `lastCommitted` or `lastFinalized`",
+        )
+        .result(nullable(schema_ref("BlockInfo"))),
+        Endpoint::get(
+            "/blocks/{block_position}/transactions",
+            "getBlockTransactions",
+            "Page of the block transactions",
+        )
+        .query(pagination_params("Transaction hash or `latest`"))
+        .result(paginated(
+            schema_ref("Transaction"),
+            string("Transaction hash"),
+        )),
+        Endpoint::get(
+            "/blocks/{block_position}/transactions/{block_index}",
+            "getBlockTransaction",
+            "Transaction by its index in the block",
+        )
+        .result(nullable(schema_ref("TxData"))),
+        // Config
+        Endpoint::get("/config", "getConfig", "Network configuration")
+            .result(schema_ref("ApiConfig")),
+        Endpoint::get(
+            "/config/protocolParameters",
+            "getProtocolParametersHistory",
+            "Changes of the protocol parameters ordered by the first block they are applied to",
+        )
+        .result(array_of(schema_ref("ProtocolParametersUpdate"))),
+        Endpoint::get(
+            "/config/protocolParameters/{block_number}",
+            "getBlockProtocolParameters",
+            "Protocol parameters in force at the block",
+        )
+        .result(nullable(schema_ref("ProtocolParameters"))),
+        // Errors
+        Endpoint::get(
+            "/errors",
+            "getErrorCodes",
+            "Catalog of the error codes returned in the `code` field of the errors",
+        )
+        .result(array_of(schema_ref("ErrorCodeInfo"))),
+        // Events
+        Endpoint::get(
+            "/events",
+            "getEvents",
+            "Page of the executed operations matching the filters, similar to `eth_getLogs`",
+        )
+        .query(cursor_pagination_params())
+        .query(vec![
+            QueryParam {
+                name: "address",
+                required: false,
+                schema: json!({ "type": "string" }),
+                description: "Only operations affecting the address",
+            },
+            QueryParam {
+                name: "token",
+                required: false,
+                schema: json!({ "type": "string" }),
+                description: "Only operations with the token (id, address or symbol)",
+            },
+            QueryParam {
+                name: "opType",
+                required: false,
+                schema: json!({ "type": "string" }),
+                description: "Only operations of the type, e.g. `Transfer` or `Deposit`",
+            },
+            QueryParam {
+                name: "fromBlock",
+                required: false,
+                schema: integer(),
+                description: "First block of the range, inclusive",
+            },
+            QueryParam {
+                name: "toBlock",
+                required: false,
+                schema: integer(),
+                description: "Last block of the range, inclusive",
+            },
+        ])
+        .result(cursor_paginated(schema_ref("L2Event"))),
+        // Fee
+        Endpoint::post(
+            "/fee",
+            "getTxFee",
+            "Fee for the transaction",
+            "TxFeeRequest",
+        )
+        .result(schema_ref("ApiFee")),
+        Endpoint::post(
+            "/fee/batch",
+            "getBatchFee",
+            "Fee for the batch of transactions",
+            "BatchFeeRequest",
+        )
+        .result(schema_ref("ApiFee")),
+        Endpoint::get(
+            "/fee/history",
+            "getFeeHistory",
+            "Fee quotes served by the server, the latest first. Requires the admin authorization",
+        )
+        .query(vec![
+            QueryParam {
+                name: "token",
+                required: false,
+                schema: string("Fee token: id, address or symbol"),
+                description: "Only the quotes in the token",
+            },
+            QueryParam {
+                name: "address",
+                required: false,
+                schema: string("Ethereum address"),
+                description: "Only the quotes for the transactions of the address",
+            },
+            QueryParam {
+                name: "txType",
+                required: false,
+                schema: string("Transaction type, e.g. `Transfer`"),
+                description: "Only the quotes for the transactions of the type",
+            },
+            QueryParam {
+                name: "from",
+                required: false,
+                schema: json!({ "type": "string", "format": "date-time" }),
+                description: "Start of the time range, one day before its end by default",
+            },
+            QueryParam {
+                name: "to",
+                required: false,
+                schema: json!({ "type": "string", "format": "date-time" }),
+                description: "End of the time range, the current time by default",
+            },
+            QueryParam {
+                name: "limit",
+                required: false,
+                schema: integer(),
+                description: "Maximum number of the quotes, 100 at most",
+            },
+        ])
+        .result(array_of(schema_ref("ApiFeeQuote"))),
+        // Recovery
+        Endpoint::post(
+            "/recovery",
+            "initiateRecovery",
+            "Proposes the new signing key for the account on behalf of the guardian",
+            "InitiateRecovery",
+        )
+        .result(schema_ref("AccountRecovery")),
+        Endpoint::post(
+            "/recovery/cancel",
+            "cancelRecovery",
+            "Cancels the recovery of the account in progress",
+            "CancelRecovery",
+        )
+        .result(schema_ref("AccountRecovery")),
+        Endpoint::post(
+            "/recovery/guardians",
+            "setGuardians",
+            "Replaces the guardians of the account",
+            "SetGuardians",
+        )
+        .result(array_of(string("Ethereum address"))),
+        Endpoint::get(
+            "/recovery/{account_id}",
+            "getRecovery",
+            "Latest recovery of the account which was not cancelled",
+        )
+        .result(nullable(schema_ref("AccountRecovery"))),
+        Endpoint::get(
+            "/recovery/{account_id}/guardians",
+            "getGuardians",
+            "Guardians of the account",
+        )
+        .result(array_of(string("Ethereum address"))),
+        // Search
+        Endpoint::get(
+            "/search",
+            "search",
+            "Resolves the query to the block, account, transaction or token",
+        )
+        .query(vec![QueryParam {
+            name: "query",
+            required: true,
+            schema: json!({ "type": "string" }),
+            description: "Block number, address, transaction or Ethereum priority operation hash, or token symbol",
+        }])
+        .result(nullable(schema_ref("SearchResult"))),
+        // Network status
+        Endpoint::get(
+            "/networkStatus",
+            "getNetworkStatus",
+            "Status of the network",
+        )
+        .result(schema_ref("NetworkStatus")),
+        // Tokens
+        Endpoint::get("/tokens", "getTokens", "Page of the tokens")
+            .query(pagination_params("Token id or `latest`"))
+            .result(paginated(schema_ref("ApiToken"), integer())),
+        Endpoint::get(
+            "/tokens/{token_like}",
+            "getToken",
+            "Token by its id, address or symbol",
+        )
+        .result(schema_ref("ApiToken")),
+        Endpoint::get(
+            "/tokens/{token_like}/priceIn/{currency}",
+            "getTokenPrice",
+            "Price of the token in `usd` or in another token (by its id)",
+        )
+        .result(schema_ref("TokenPrice")),
+        Endpoint::get(
+            "/tokens/{token_like}/price/history",
+            "getTokenPriceHistory",
+            "USD prices of the token used by the server for the fee calculation",
+        )
+        .query(vec![
+            QueryParam {
+                name: "from",
+                required: false,
+                schema: json!({ "type": "string", "format": "date-time" }),
+                description: "Start of the time range, one day before its end by default",
+            },
+            QueryParam {
+                name: "to",
+                required: false,
+                schema: json!({ "type": "string", "format": "date-time" }),
+                description: "End of the time range, the current time by default",
+            },
+            QueryParam {
+                name: "resolution",
+                required: false,
+                schema: integer(),
+                description: "Length of the interval in seconds, one hour by default. Every interval is represented by its last price",
+            },
+        ])
+        .result(schema_ref("TokenPriceHistory")),
+        Endpoint::get("/tokens/nft/{id}", "getNft", "NFT by its id")
+            .result(nullable(schema_ref("ApiNFT"))),
+        Endpoint::get(
+            "/tokens/nft/{id}/owner",
+            "getNftOwner",
+            "Id of the account owning the NFT",
+        )
+        .result(nullable(integer())),
+        Endpoint::get(
+            "/tokens/nft_id_by_tx_hash/{tx_hash}",
+            "getNftIdByTxHash",
+            "Id of the NFT minted by the transaction",
+        )
+        .result(nullable(integer())),
+        // Transactions
+        Endpoint::post(
+            "/transactions",
+            "submitTransaction",
+            "Submits the signed transaction",
+            "TxWithSignature",
+        )
+        .query(vec![
+            QueryParam {
+                name: "mode",
+                required: false,
+                schema: json!({ "type": "string", "enum": ["sync", "async"] }),
+                description: "With `async`, the hash is returned before the transaction is verified, \
+                    the rejection is reported by the receipt",
+            },
+            QueryParam {
+                name: "notBeforeBlock",
+                required: false,
+                schema: integer(),
+                description: "The transaction isn't executed until the block with this number is committed, \
+                    at most 1000 blocks after the last committed one. It's replaced by any transaction \
+                    with the same nonce, and is rejected if the nonce is taken by the unscheduled one",
+            },
+        ])
+        .result(string("Transaction hash")),
+        Endpoint::get(
+            "/transactions/{tx_hash}",
+            "getTransactionReceipt",
+            "Receipt of the L2 transaction or the priority operation",
+        )
+        .result(nullable(schema_ref("Receipt"))),
+        Endpoint::get(
+            "/transactions/{tx_hash}/data",
+            "getTransactionData",
+            "Transaction with its Ethereum signature, hidden while queued if the feed redacts it",
+        )
+        .result(nullable(schema_ref("TxData"))),
+        Endpoint::get(
+            "/transactions/{tx_hash}/submission",
+            "getTransactionSubmission",
+            "Whether the transaction submitted before the restart of the server has to be resubmitted",
+        )
+        .result(schema_ref("SubmissionStatus")),
+        Endpoint::get(
+            "/transactions/eth/{eth_tx_hash}",
+            "getPriorityOpsByEthHash",
+            "Priority operations created by the Ethereum transaction",
+        )
+        .result(array_of(schema_ref("L1TxPriorityOp"))),
+        Endpoint::post(
+            "/transactions/batches",
+            "submitBatch",
+            "Submits the signed batch of transactions",
+            "IncomingTxBatch",
+        )
+        .result(schema_ref("SubmitBatchResponse")),
+        Endpoint::get(
+            "/transactions/batches/{batch_hash}",
+            "getBatch",
+            "Aggregate status of the batch of transactions with the receipts of its transactions",
+        )
+        .result(nullable(schema_ref("BatchInfo"))),
+        Endpoint::post(
+            "/transactions/toggle2FA",
+            "toggle2FA",
+            "Enables or disables the 2FA for the account",
+            "Toggle2FA",
+        )
+        .result(schema_ref("Toggle2FAResponse")),
+        // Withdrawals
+        Endpoint::get(
+            "/withdrawals/{tx_hash}",
+            "getWithdrawal",
+            "Lifecycle of the withdrawal, up to the release of the funds on L1",
+        )
+        .result(nullable(schema_ref("WithdrawalInfo"))),
+    ];
+
+    if features.mempool_feed {
+        endpoints.push(
+            Endpoint::get(
+                "/mempool/txs",
+                "getPendingTransactions",
+                "Public feed of the pending transactions",
+            )
+            .result(array_of(schema_ref("PendingTx"))),
+        );
+    }
+    if features.encrypted_txs {
+        endpoints.extend(vec![
+            Endpoint::post(
+                "/encryptedTransactions",
+                "submitEncryptedTransaction",
+                "Submits the transaction or the batch encrypted to the operator key, it is only revealed after the visibility delay",
+                "IncomingEncryptedTx",
+            )
+            .result(schema_ref("EncryptedTxStatus")),
+            Endpoint::get(
+                "/encryptedTransactions/key",
+                "getEncryptionKey",
+                "Public key to encrypt the transactions to",
+            )
+            .result(schema_ref("EncryptionKey")),
+            Endpoint::get(
+                "/encryptedTransactions/{envelope_hash}",
+                "getEncryptedTransaction",
+                "Status of the encrypted transaction",
+            )
+            .result(nullable(schema_ref("EncryptedTxStatus"))),
+        ]);
+    }
+    if features.graphql {
+        endpoints.extend(vec![
+            Endpoint::post(
+                "/graphql",
+                "executeGraphQlQuery",
+                "Executes the GraphQL query over the blocks, transactions, accounts and tokens",
+                "GraphQlRequest",
+            )
+            .without_envelope()
+            .result(schema_ref("GraphQlResponse")),
+            Endpoint::get(
+                "/graphql/schema",
+                "getGraphQlSchema",
+                "GraphQL schema in the SDL format",
+            )
+            .content_type("text/plain")
+            .result(json!({ "type": "string" })),
+        ]);
+    }
+    endpoints
+}
+
+fn components() -> Value {
+    let address = || string("Ethereum address");
+    let hash = || string("0x-prefixed hash");
+    let amount = || string("Decimal amount in the smallest units of the token");
+    let timestamp = || json!({ "type": "string", "format": "date-time" });
+
+    json!({
+        "schemas": {
+            "Request": object(json!({
+                "network": string("Network name"),
+                "apiVersion": string("Version of the API"),
+                "resource": string("Requested path"),
+                "args": { "type": "object", "additionalProperties": { "type": "string" } },
+                "timestamp": timestamp(),
+            })),
+            "Response": object(json!({
+                "request": schema_ref("Request"),
+                "status": { "type": "string", "enum": ["success", "error"] },
+                "result": { "description": "Endpoint specific result, absent on error" },
+                "error": nullable(schema_ref("Error")),
+            })),
+            "Error": object(json!({
+                "errorType": string("Kind of the error"),
+                "code": {
+                    "type": "integer",
+                    "description": "Stable error code, see the `/errors` endpoint",
+                },
+                "message": string("Human readable description"),
+                "detail": {
+                    "type": "integer",
+                    "description": "Specific error code, e.g. the `7xx` reason of the rejected transaction",
+                },
+            })),
+            "ErrorCodeInfo": object(json!({
+                "code": integer(),
+                "name": string("Name of the code"),
+                "description": string("Meaning of the code"),
+            })),
+            "PaginationDirection": { "type": "string", "enum": ["newer", "older"] },
+            "TxInBlockStatus": {
+                "type": "string",
+                "enum": ["queued", "pending", "committed", "finalized", "rejected"],
+                "description": "`pending` means the transaction is executed in the block that isn't sealed yet",
+            },
+            "TxFailureCode": {
+                "type": "string",
+                "enum": [
+                    "insufficientBalance",
+                    "nonceMismatch",
+                    "invalidSignature",
+                    "invalidPubKeyAuth",
+                    "accountNotFound",
+                    "accountLocked",
+                    "invalidAccount",
+                    "invalidToken",
+                    "invalidTimestamp",
+                    "batchTooExpensive",
+                    "invalidOperation",
+                ],
+                "description": "Machine-readable reason of the rejection in the block",
+            },
+            "BlockStatus": { "type": "string", "enum": ["committed", "finalized"] },
+            "Account": object(json!({
+                "accountId": integer(),
+                "address": address(),
+                "nonce": integer(),
+                "pubKeyHash": string("`sync:`-prefixed hash of the signing key"),
+                "lastUpdateInBlock": integer(),
+                "balances": { "type": "object", "additionalProperties": amount() },
+                "nfts": { "type": "object", "additionalProperties": { "type": "object" } },
+                "mintedNfts": { "type": "object", "additionalProperties": { "type": "object" } },
+                "accountType": nullable(string("Type of the account signing")),
+            })),
+            "AccountState": object(json!({
+                "depositing": object(json!({
+                    "balances": { "type": "object", "additionalProperties": { "type": "object" } },
+                    "balancesByTokenId": {
+                        "type": "object",
+                        "additionalProperties": object(json!({
+                            "symbol": string("Symbol of the token"),
+                            "amount": amount(),
+                            "expectedAcceptBlock": integer(),
+                        })),
+                    },
+                    "deposits": array_of(object(json!({
+                        "ethTxHash": string("Hash of the L1 transaction"),
+                        "tokenId": integer(),
+                        "amount": amount(),
+                        "receivedOnBlock": integer(),
+                        "expectedAcceptBlock": integer(),
+                        "remainingConfirmations": nullable(integer()),
+                        "etaSeconds": nullable(integer()),
+                    }))),
+                })),
+                "committed": nullable(schema_ref("Account")),
+                "finalized": nullable(schema_ref("Account")),
+            })),
+            "AccountsBatchRequest": object(json!({ "addresses": array_of(address()) })),
+            "AccountBatchState": object(json!({
+                "address": address(),
+                "committed": nullable(schema_ref("Account")),
+                "finalized": nullable(schema_ref("Account")),
+            })),
+            "AccountStateChanges": object(json!({
+                "fromBlock": integer(),
+                "toBlock": integer(),
+                "changes": array_of(object(json!({
+                    "blockNumber": integer(),
+                    "type": {
+                        "type": "string",
+                        "enum": [
+                            "create",
+                            "delete",
+                            "updateBalance",
+                            "changePubKeyHash",
+                            "mintNFT",
+                            "removeNFT",
+                        ],
+                    },
+                }))),
+            })),
+            "AccountNextNonce": object(json!({
+                "committedNonce": integer(),
+                "nextNonce": integer(),
+            })),
+            "ExitProofInfo": object(json!({
+                "accountId": integer(),
+                "tokenId": integer(),
+                "blockNumber": nullable(integer()),
+                "status": {
+                    "type": "string",
+                    "enum": ["queued", "inProgress", "ready", "failed"],
+                },
+                "error": nullable(string("Reason of the failure")),
+                "data": nullable(object(json!({
+                    "storedBlockInfo": { "type": "object" },
+                    "owner": address(),
+                    "accountId": integer(),
+                    "tokenId": integer(),
+                    "tokenAddress": address(),
+                    "amount": amount(),
+                    "nftCreatorId": integer(),
+                    "nftCreatorAddress": address(),
+                    "nftSerialId": integer(),
+                    "nftContentHash": hash(),
+                    "proof": { "type": "object" },
+                }))),
+            })),
+            "AccountFullExits": object(json!({
+                "address": address(),
+                "fullExits": array_of(object(json!({
+                    "serialId": integer(),
+                    "ethTxHash": hash(),
+                    "accountId": integer(),
+                    "tokenId": integer(),
+                    "receivedOnBlock": integer(),
+                    "status": {
+                        "type": "string",
+                        "enum": ["awaitingConfirmations", "queued", "committed", "finalized"],
+                    },
+                    "remainingConfirmations": nullable(integer()),
+                    "blockNumber": nullable(integer()),
+                    "withdrawAmount": nullable(amount()),
+                }))),
+            })),
+            "PubKeyAuthStatus": object(json!({
+                "address": address(),
+                "nonce": integer(),
+                "fact": nullable(hash()),
+                "ethTxHash": nullable(hash()),
+                "ethBlock": nullable(integer()),
+                "confirmations": nullable(integer()),
+            })),
+            "BlockInfo": object(json!({
+                "blockNumber": integer(),
+                "newStateRoot": hash(),
+                "blockSize": integer(),
+                "commitTxHash": nullable(hash()),
+                "verifyTxHash": nullable(hash()),
+                "committedAt": timestamp(),
+                "finalizedAt": nullable(timestamp()),
+                "status": schema_ref("BlockStatus"),
+            })),
+            "BlockStreamEvent": object(json!({
+                "blockNumber": integer(),
+                "newStateRoot": hash(),
+                "txCount": integer(),
+                "timestamp": timestamp(),
+                "status": schema_ref("BlockStatus"),
+            })),
+            "Transaction": object(json!({
+                "txHash": hash(),
+                "blockIndex": nullable(integer()),
+                "blockNumber": nullable(integer()),
+                "op": {
+                    "type": "object",
+                    "description": "L2 transaction or priority operation, tagged by the `type` field",
+                },
+                "status": schema_ref("TxInBlockStatus"),
+                "failReason": nullable(string("Reason of the rejection")),
+                "createdAt": nullable(timestamp()),
+                "batchId": nullable(integer()),
+            })),
+            "TxData": object(json!({
+                "tx": schema_ref("Transaction"),
+                "ethSignature": nullable(string("Ethereum signature of the transaction")),
+            })),
+            "L1TxPriorityOp": object(json!({
+                "txHash": hash(),
+                "status": schema_ref("TxInBlockStatus"),
+                "ethBlock": integer(),
+                "rollupBlock": nullable(integer()),
+                "id": integer(),
+                "blockIndex": integer(),
+            })),
+            "Receipt": {
+                "oneOf": [
+                    object(json!({
+                        "status": schema_ref("TxInBlockStatus"),
+                        "ethBlock": integer(),
+                        "rollupBlock": nullable(integer()),
+                        "id": integer(),
+                        "blockIndex": integer(),
+                    })),
+                    object(json!({
+                        "txHash": hash(),
+                        "rollupBlock": nullable(integer()),
+                        "status": schema_ref("TxInBlockStatus"),
+                        "failReason": nullable(string("Reason of the rejection")),
+                        "failCode": schema_ref("TxFailureCode"),
+                        "blockIndex": integer(),
+                    })),
+                ],
+            },
+            "SubmissionStatus": object(json!({
+                "txHash": hash(),
+                "interrupted": nullable(object(json!({
+                    "stage": {
+                        "type": "string",
+                        "enum": ["verifying", "sentToMempool", "accepted"],
+                    },
+                    "interruptedAt": timestamp(),
+                }))),
+                "receipt": nullable(schema_ref("Receipt")),
+                "shouldResubmit": { "type": "boolean" },
+            })),
+            "WithdrawalInfo": object(json!({
+                "txHash": hash(),
+                "stage": {
+                    "type": "string",
+                    "enum": ["queued", "included", "verified", "released", "rejected"],
+                },
+                "blockNumber": nullable(integer()),
+                "deferred": {
+                    "type": "boolean",
+                    "description": "Whether the funds are left on the contract as a pending balance",
+                },
+                "l1TxHash": nullable(hash()),
+                "failReason": nullable(string("Reason of the rejection")),
+            })),
+            "TxWithSignature": object(json!({
+                "tx": { "type": "object", "description": "Signed L2 transaction" },
+                "signature": { "description": "Ethereum signature(s) of the transaction" },
+            })),
+            "IncomingTxBatch": object(json!({
+                "txs": array_of(schema_ref("TxWithSignature")),
+                "signature": { "description": "Ethereum signature(s) of the batch" },
+            })),
+            "SubmitBatchResponse": object(json!({
+                "transactionHashes": array_of(hash()),
+                "batchHash": hash(),
+            })),
+            "BatchInfo": object(json!({
+                "batchHash": hash(),
+                "transactionHashes": array_of(hash()),
+                "createdAt": timestamp(),
+                "batchStatus": object(json!({
+                    "updatedAt": timestamp(),
+                    "lastState": schema_ref("TxInBlockStatus"),
+                })),
+                "receipts": array_of(object(json!({
+                    "txHash": hash(),
+                    "rollupBlock": nullable(integer()),
+                    "status": schema_ref("TxInBlockStatus"),
+                    "failReason": nullable(string("Reason of the rejection")),
+                    "failCode": schema_ref("TxFailureCode"),
+                    "blockIndex": integer(),
+                }))),
+            })),
+            "Toggle2FA": object(json!({
+                "enable": { "type": "boolean" },
+                "timestamp": integer(),
+                "accountId": integer(),
+                "signature": { "type": "object" },
+                "pubKeyHash": nullable(string("`sync:`-prefixed hash of the signing key")),
+            })),
+            "Toggle2FAResponse": object(json!({ "success": { "type": "boolean" } })),
+            "SetGuardians": object(json!({
+                "accountId": integer(),
+                "guardians": array_of(address()),
+                "timestamp": integer(),
+                "signature": { "type": "object" },
+            })),
+            "InitiateRecovery": object(json!({
+                "accountId": integer(),
+                "guardian": address(),
+                "newPubKeyHash": string("`sync:`-prefixed hash of the signing key"),
+                "timestamp": integer(),
+                "signature": { "type": "object" },
+            })),
+            "CancelRecovery": object(json!({
+                "accountId": integer(),
+                "timestamp": integer(),
+                "signature": { "type": "object" },
+            })),
+            "AccountRecovery": object(json!({
+                "id": integer(),
+                "accountId": integer(),
+                "newPubKeyHash": string("`sync:`-prefixed hash of the signing key"),
+                "initiatedBy": address(),
+                "createdAt": timestamp(),
+                "executableAt": timestamp(),
+                "cancelledAt": nullable(timestamp()),
+            })),
+            "TxFeeRequest": object(json!({
+                "txType": { "description": "Type of the transaction" },
+                "address": address(),
+                "tokenLike": string("Token id, address or symbol"),
+            })),
+            "BatchFeeRequest": object(json!({
+                "transactions": array_of(object(json!({
+                    "txType": { "description": "Type of the transaction" },
+                    "address": address(),
+                    "token": nullable(string("Token of the transaction: id, address or symbol")),
+                }))),
+                "tokenLike": string("Token id, address or symbol"),
+            })),
+            "ApiFee": object(json!({
+                "gasFee": amount(),
+                "zkpFee": amount(),
+                "totalFee": amount(),
+            })),
+            "ApiFeeQuote": object(json!({
+                "txTypes": array_of(string("Transaction type")),
+                "addresses": array_of(address()),
+                "tokenId": integer(),
+                "gasFee": amount(),
+                "zkpFee": amount(),
+                "totalFee": amount(),
+                "subsidized": { "type": "boolean" },
+                "gasPriceWei": string("Decimal gas price in wei"),
+                "tokenUsdPrice": string("Decimal USD price of the token"),
+                "createdAt": timestamp(),
+            })),
+            "ApiToken": object(json!({
+                "id": integer(),
+                "address": address(),
+                "symbol": string("Token symbol"),
+                "decimals": integer(),
+                "enabledForFees": { "type": "boolean" },
+            })),
+            "TokenPrice": object(json!({
+                "tokenId": integer(),
+                "tokenSymbol": string("Token symbol"),
+                "priceIn": string("Currency of the price"),
+                "decimals": integer(),
+                "price": string("Decimal price"),
+            })),
+            "TokenPriceHistory": object(json!({
+                "tokenId": integer(),
+                "tokenSymbol": string("Token symbol"),
+                "from": timestamp(),
+                "to": timestamp(),
+                "resolution": integer(),
+                "prices": array_of(object(json!({
+                    "timestamp": timestamp(),
+                    "usdPrice": string("Decimal price in USD"),
+                }))),
+            })),
+            "ApiNFT": object(json!({
+                "id": integer(),
+                "contentHash": hash(),
+                "creatorId": integer(),
+                "creatorAddress": address(),
+                "serialId": integer(),
+                "address": address(),
+                "symbol": string("NFT symbol"),
+                "currentFactory": address(),
+                "withdrawnFactory": nullable(address()),
+            })),
+            "L2Event": object(json!({
+                "cursor": string("Position of the event, can be used as `from` of the next query"),
+                "opType": string("Type of the operation"),
+                "tx": schema_ref("Transaction"),
+            })),
+            "SearchResult": object(json!({
+                "entityType": {
+                    "type": "string",
+                    "enum": ["account", "transaction", "priorityOperation", "block", "token"],
+                },
+                "link": string("Path of the API resource, e.g. `/api/v0.2/blocks/1`"),
+            })),
+            "NetworkStatus": object(json!({
+                "lastCommitted": integer(),
+                "finalized": integer(),
+                "totalTransactions": integer(),
+                "mempoolSize": integer(),
+                "coreStatus": nullable(object(json!({
+                    "mainDatabaseAvailable": { "type": "boolean" },
+                    "replicaDatabaseAvailable": { "type": "boolean" },
+                    "web3Available": { "type": "boolean" },
+                }))),
+            })),
+            "ApiConfig": object(json!({
+                "network": string("Network name"),
+                "contract": address(),
+                "govContract": address(),
+                "depositConfirmations": integer(),
+                "zksyncVersion": string("Version of the protocol"),
+            })),
+            "ProtocolParameters": object(json!({
+                "blockChunkSizes": array_of(integer()),
+                "maxWithdrawalsPerExecuteTx": integer(),
+            })),
+            "ProtocolParametersUpdate": object(json!({
+                "fromBlock": integer(),
+                "blockChunkSizes": array_of(integer()),
+                "maxWithdrawalsPerExecuteTx": integer(),
+            })),
+            "IncomingEncryptedTx": object(json!({
+                "envelope": string("0x-prefixed ECIES ciphertext of the JSON `TxWithSignature` or `IncomingTxBatch`"),
+            })),
+            "EncryptionKey": object(json!({
+                "publicKey": string("0x-prefixed uncompressed secp256k1 public key without the `04` prefix"),
+            })),
+            "EncryptedTxStatus": object(json!({
+                "envelopeHash": hash(),
+                "state": { "type": "string", "enum": ["pending", "revealed", "rejected"] },
+                "createdAt": timestamp(),
+                "revealAt": timestamp(),
+                "revealedAt": nullable(timestamp()),
+                "txHashes": array_of(hash()),
+                "error": nullable(string("Reason the envelope was rejected")),
+            })),
+            "PendingTx": object(json!({
+                "txHash": nullable(hash()),
+                "txType": string("Type of the transaction"),
+                "accountId": nullable(integer()),
+                "from": nullable(address()),
+                "to": nullable(address()),
+                "token": integer(),
+                "amount": nullable(amount()),
+                "fee": nullable(amount()),
+                "createdAt": timestamp(),
+            })),
+            "GraphQlRequest": object(json!({
+                "query": string("GraphQL query document"),
+                "operationName": nullable(string("Operation of the document to execute")),
+                "variables": nullable(json!({ "type": "object" })),
+            })),
+            "GraphQlResponse": object(json!({
+                "data": nullable(json!({ "type": "object" })),
+                "errors": array_of(json!({ "type": "object" })),
+            })),
+        }
+    })
+}
+
+/// Builds the OpenAPI document of the API.
+pub fn api_spec(features: ApiFeatures) -> Value {
+    let mut paths = Map::new();
+    for endpoint in endpoints(features) {
+        let item = paths
+            .entry(endpoint.path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[endpoint.method.as_str()] = endpoint.operation();
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "zkSync REST API",
+            "version": "0.2",
+        },
+        "servers": [{ "url": "/api/v0.2" }],
+        "paths": paths,
+        "components": components(),
+    })
+}
+
+const SWAGGER_UI_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8" />
+    <title>zkSync REST API v0.2</title>
+    <link rel="stylesheet" href="docs/swagger-ui.css" />
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="docs/swagger-ui-bundle.js"></script>
+    <script>
+        window.onload = () => {
+            window.ui = SwaggerUIBundle({ url: "openapi.json", dom_id: "#swagger-ui" });
+        };
+    </script>
+</body>
+</html>
+"##;
+
+/// Serialized OpenAPI document, built once per server worker.
+struct ApiSpec(String);
+
+/// Files of the `swagger-ui-dist` package referenced by the Swagger UI page.
+#[derive(Debug, Clone)]
+struct SwaggerUiAssets {
+    css: web::Bytes,
+    bundle: web::Bytes,
+}
+
+impl SwaggerUiAssets {
+    fn load(dir: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            css: std::fs::read(dir.join("swagger-ui.css"))?.into(),
+            bundle: std::fs::read(dir.join("swagger-ui-bundle.js"))?.into(),
+        })
+    }
+}
+
+async fn openapi_json(spec: web::Data<ApiSpec>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(spec.0.clone())
+}
+
+async fn swagger_ui() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(SWAGGER_UI_PAGE)
+}
+
+async fn swagger_ui_css(assets: web::Data<SwaggerUiAssets>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/css; charset=utf-8")
+        .body(assets.css.clone())
+}
+
+async fn swagger_ui_bundle(assets: web::Data<SwaggerUiAssets>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/javascript; charset=utf-8")
+        .body(assets.bundle.clone())
+}
+
+pub fn spec_resource(features: ApiFeatures) -> Resource {
+    let spec = ApiSpec(api_spec(features).to_string());
+
+    web::resource("/openapi.json")
+        .app_data(web::Data::new(spec))
+        .route(web::get().to(openapi_json))
+}
+
+/// Swagger UI over the local copy of the `swagger-ui-dist` package in `swagger_ui_dir`.
+/// Returns `None` if the package files are missing, so the UI is not served.
+pub fn docs_scope(swagger_ui_dir: &str) -> Option<Scope> {
+    let assets = match SwaggerUiAssets::load(Path::new(swagger_ui_dir)) {
+        Ok(assets) => assets,
+        Err(err) => {
+            vlog::warn!(
+                "Swagger UI is not served, failed to load its assets from {}: {}",
+                swagger_ui_dir,
+                err
+            );
+            return None;
+        }
+    };
+
+    let scope = web::scope("docs")
+        .app_data(web::Data::new(assets))
+        .route("", web::get().to(swagger_ui))
+        .route("swagger-ui.css", web::get().to(swagger_ui_css))
+        .route("swagger-ui-bundle.js", web::get().to(swagger_ui_bundle));
+    Some(scope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    use actix_web::{http::Method as HttpMethod, test, App};
+    use futures::channel::mpsc;
+
+    use zksync_config::ZkSyncConfig;
+    use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
+    use zksync_storage::ConnectionPool;
+
+    use super::super::{
+        block_stream::BlockStream,
+        test_utils::{dummy_fee_ticker, dummy_sign_verifier},
+    };
+    use crate::api_server::{
+        core_api_client::CoreApiClient, rest::network_status::SharedNetworkStatus,
+        tx_sender::TxSender,
+    };
+
+    const ALL_FEATURES: ApiFeatures = ApiFeatures {
+        mempool_feed: true,
+        encrypted_txs: true,
+        graphql: true,
+    };
+
+    fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    refs.push(reference.clone());
+                }
+                map.values().for_each(|value| collect_refs(value, refs));
+            }
+            Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+            _ => {}
+        }
+    }
+
+    /// Checks that the document is self-consistent: all the references are resolvable,
+    /// the operation ids are unique and all the path parameters are declared.
+    #[test]
+    fn spec_is_consistent() {
+        let spec = api_spec(ALL_FEATURES);
+
+        let mut refs = Vec::new();
+        collect_refs(&spec, &mut refs);
+        assert!(!refs.is_empty());
+        for reference in refs {
+            let name = reference
+                .strip_prefix("#/components/schemas/")
+                .unwrap_or_else(|| panic!("Unexpected reference: {}", reference));
+            assert!(
+                spec["components"]["schemas"].get(name).is_some(),
+                "Unresolved reference: {}",
+                reference
+            );
+        }
+
+        let mut operation_ids = HashSet::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            assert!(path.starts_with('/'), "Path must be absolute: {}", path);
+            for (method, operation) in item.as_object().unwrap() {
+                let operation_id = operation["operationId"].as_str().unwrap();
+                assert!(
+                    operation_ids.insert(operation_id.to_owned()),
+                    "Duplicate operation id: {}",
+                    operation_id
+                );
+
+                let declared: HashSet<_> = operation["parameters"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .filter(|param| param["in"] == "path")
+                    .map(|param| format!("{{{}}}", param["name"].as_str().unwrap()))
+                    .collect();
+                let expected: HashSet<_> = path
+                    .split('/')
+                    .filter(|segment| segment.starts_with('{'))
+                    .map(str::to_owned)
+                    .collect();
+                assert_eq!(declared, expected, "{} {}", method, path);
+            }
+        }
+    }
+
+    /// Returns the value of the path parameter accepted by the handlers.
+    fn path_param_placeholder(name: &str) -> String {
+        if name.contains("hash") {
+            format!("0x{}", "00".repeat(32))
+        } else {
+            "1".to_owned()
+        }
+    }
+
+    /// Checks that the document describes the routes registered by `v02::api_scope`: every
+    /// documented operation is routed to the resource with the documented path, and the
+    /// documented paths aren't routed for the undocumented methods.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn spec_matches_registered_routes() {
+        let mut config = ZkSyncConfig::from_env();
+        config.api.mempool_feed.enabled = true;
+        config.api.encrypted_txs.enabled = true;
+        config.api.graphql.enabled = true;
+        let pool = ConnectionPool::new(Some(1));
+        let (mempool_tx_sender, _mempool_tx_receiver) = mpsc::channel(1);
+        let tx_sender = TxSender::new(
+            pool.clone(),
+            dummy_sign_verifier(),
+            dummy_fee_ticker(&[], None),
+            &config.api.common,
+            &config.api.token_config,
+            mempool_tx_sender,
+        );
+        let scope = super::super::api_scope(
+            tx_sender,
+            pool.clone(),
+            &config,
+            SharedNetworkStatus::new(CoreApiClient::new(&config.api.private)),
+            BlockStream::new(pool, Duration::from_millis(100)),
+            None,
+            EthereumGateway::Mock(MockEthereum::default()),
+        );
+        let app = test::init_service(App::new().service(scope)).await;
+
+        let spec = api_spec(ApiFeatures::new(&config.api));
+        assert_eq!(spec, api_spec(ALL_FEATURES));
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            let uri: Vec<_> = path
+                .split('/')
+                .map(
+                    |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                        Some(name) => path_param_placeholder(name),
+                        None => segment.to_owned(),
+                    },
+                )
+                .collect();
+            let pattern = format!("/api/v0.2{}", path);
+
+            for method in &[HttpMethod::GET, HttpMethod::POST] {
+                let request = test::TestRequest::default()
+                    .method(method.clone())
+                    .uri(&format!("/api/v0.2{}", uri.join("/")))
+                    .to_request();
+                let response = test::call_service(&app, request).await;
+                // The handler may fail, only the resource the request is routed to matters.
+                let routed = response.request().match_pattern() == Some(pattern.clone());
+                let documented = item.get(method.as_str().to_lowercase()).is_some();
+                assert_eq!(
+                    routed, documented,
+                    "{} {} is routed: {}, documented: {}",
+                    method, path, routed, documented
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn optional_endpoints_are_documented_only_if_enabled() {
+        let cases = [
+            (
+                "/mempool/txs",
+                ApiFeatures {
+                    mempool_feed: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "/encryptedTransactions",
+                ApiFeatures {
+                    encrypted_txs: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "/graphql",
+                ApiFeatures {
+                    graphql: true,
+                    ..Default::default()
+                },
+            ),
+        ];
+        for (path, features) in cases.iter() {
+            assert!(api_spec(*features)["paths"].get(path).is_some(), "{}", path);
+            assert!(
+                api_spec(ApiFeatures::default())["paths"]
+                    .get(path)
+                    .is_none(),
+                "{}",
+                path
+            );
+        }
+    }
+
+    /// The Swagger UI page must not load anything from the third-party hosts.
+    #[test]
+    fn swagger_ui_page_uses_local_assets() {
+        assert!(!SWAGGER_UI_PAGE.contains("://"));
+        assert!(docs_scope("/nonexistent/swagger-ui").is_none());
+    }
+}
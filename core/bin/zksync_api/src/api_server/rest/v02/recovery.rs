@@ -0,0 +1,142 @@
+//! Guardian-based account recovery part of API implementation.
+//!
+//! The owner of the account registers the guardians. Any of them may propose a new public key
+//! hash for the account, which may only be set by `ChangePubKey` after the timelock,
+//! unless the owner cancels the recovery in the meantime.
+
+// Built-in uses
+use std::time::Instant;
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+
+// Workspace uses
+use zksync_api_types::v02::recovery::{
+    AccountRecovery, CancelRecovery, InitiateRecovery, SetGuardians,
+};
+use zksync_config::configs::api::RecoveryConfig;
+use zksync_types::{AccountId, Address};
+
+// Local uses
+use super::{error::Error, response::ApiResult};
+use crate::api_server::tx_sender::TxSender;
+
+/// Shared data between `api/v0.2/recovery` endpoints.
+#[derive(Clone)]
+struct ApiRecoveryData {
+    tx_sender: TxSender,
+    config: RecoveryConfig,
+}
+
+impl ApiRecoveryData {
+    fn new(tx_sender: TxSender, config: RecoveryConfig) -> Self {
+        Self { tx_sender, config }
+    }
+
+    async fn guardians(&self, account_id: AccountId) -> Result<Vec<Address>, Error> {
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?;
+        storage
+            .recovery_schema()
+            .load_guardians(account_id)
+            .await
+            .map_err(Error::storage)
+    }
+
+    async fn recovery(&self, account_id: AccountId) -> Result<Option<AccountRecovery>, Error> {
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?;
+        let recovery = storage
+            .recovery_schema()
+            .get_active_recovery(account_id)
+            .await
+            .map_err(Error::storage)?;
+        Ok(recovery.map(AccountRecovery::from))
+    }
+}
+
+// Server implementation
+
+async fn guardians(
+    data: web::Data<ApiRecoveryData>,
+    account_id: web::Path<AccountId>,
+) -> ApiResult<Vec<Address>> {
+    let start = Instant::now();
+    let res = data.guardians(*account_id).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "recovery_guardians");
+    res
+}
+
+async fn set_guardians(
+    data: web::Data<ApiRecoveryData>,
+    Json(request): Json<SetGuardians>,
+) -> ApiResult<Vec<Address>> {
+    let start = Instant::now();
+    let res = data
+        .tx_sender
+        .set_guardians(request, &data.config)
+        .await
+        .map_err(Error::from);
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "recovery_set_guardians");
+    res.into()
+}
+
+async fn recovery(
+    data: web::Data<ApiRecoveryData>,
+    account_id: web::Path<AccountId>,
+) -> ApiResult<Option<AccountRecovery>> {
+    let start = Instant::now();
+    let res = data.recovery(*account_id).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "recovery");
+    res
+}
+
+async fn initiate_recovery(
+    data: web::Data<ApiRecoveryData>,
+    Json(request): Json<InitiateRecovery>,
+) -> ApiResult<AccountRecovery> {
+    let start = Instant::now();
+    let res = data
+        .tx_sender
+        .initiate_recovery(request, &data.config)
+        .await
+        .map_err(Error::from);
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "recovery_initiate");
+    res.into()
+}
+
+async fn cancel_recovery(
+    data: web::Data<ApiRecoveryData>,
+    Json(request): Json<CancelRecovery>,
+) -> ApiResult<AccountRecovery> {
+    let start = Instant::now();
+    let res = data
+        .tx_sender
+        .cancel_recovery(request)
+        .await
+        .map_err(Error::from);
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "recovery_cancel");
+    res.into()
+}
+
+pub fn api_scope(tx_sender: TxSender, config: RecoveryConfig) -> Scope {
+    let data = ApiRecoveryData::new(tx_sender, config);
+
+    web::scope("recovery")
+        .app_data(web::Data::new(data))
+        .route("", web::post().to(initiate_recovery))
+        .route("/cancel", web::post().to(cancel_recovery))
+        .route("/guardians", web::post().to(set_guardians))
+        .route("{account_id}", web::get().to(recovery))
+        .route("{account_id}/guardians", web::get().to(guardians))
+}
@@ -12,7 +12,10 @@ use serde::{Deserialize, Serialize};
 use zksync_api_types::v02::{Request, Response, ResultStatus};
 
 // Local uses
-use super::{error::Error, SharedData};
+use super::{
+    error::{Error, ErrorCode},
+    SharedData,
+};
 
 // This struct is needed to wrap all api responses is `Response` struct by implementing `Responder` trait for it.
 // We can't use simple `Result`, because `actix-web` has already `Responder` implementation for it.
@@ -45,6 +48,7 @@ impl<R: Serialize> Responder for ApiResult<R> {
             timestamp: Utc::now(),
         };
 
+        let mut builder = HttpResponse::Ok();
         let response = match self {
             ApiResult::Ok(res) => Response {
                 request,
@@ -52,19 +56,24 @@ impl<R: Serialize> Responder for ApiResult<R> {
                 result: Some(serde_json::to_value(res).unwrap()),
                 error: None,
             },
-            ApiResult::Error(err) => Response {
-                request,
-                status: ResultStatus::Error,
-                result: None,
-                error: Some(serde_json::to_value(err).unwrap()),
-            },
+            ApiResult::Error(err) => {
+                // The code is used by the middlewares, e.g. to count the storage failures.
+                req.extensions_mut().insert(err.code);
+                if err.code == ErrorCode::ServiceUnavailable {
+                    builder = HttpResponse::ServiceUnavailable();
+                }
+                Response {
+                    request,
+                    status: ResultStatus::Error,
+                    result: None,
+                    error: Some(serde_json::to_value(err).unwrap()),
+                }
+            }
         };
 
         let body = serde_json::to_string(&response).expect("Should be correct serializable");
 
-        HttpResponse::Ok()
-            .content_type("application/json")
-            .body(body)
+        builder.content_type("application/json").body(body)
     }
 }
 
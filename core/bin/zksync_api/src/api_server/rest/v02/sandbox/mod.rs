@@ -0,0 +1,279 @@
+//! Sandbox mode of the API.
+//!
+//! Serves the main endpoints of the `api/v0.2` with the same handlers, but the data is synthetic
+//! and stored in memory, so the SDKs and wallets can be integrated without running the node.
+//! The handlers are generic over the source of the data, the sandbox one is the session
+//! attached to the request by the `SandboxSessions` middleware. See `store` for the details
+//! of the simulation.
+
+// Built-in uses
+use std::str::FromStr;
+use std::time::Instant;
+
+// External uses
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Scope,
+};
+use async_trait::async_trait;
+use futures::future::{ready, Ready};
+
+// Workspace uses
+use zksync_api_types::{
+    v02::{
+        account::{
+            Account, AccountAddressOrId, AccountNextNonce, AccountState, DepositingBalancesVersion,
+        },
+        block::BlockInfo,
+        fee::{ApiFee, BatchFeeRequest, TxFeeRequest},
+        status::NetworkStatus,
+        token::ApiToken,
+        transaction::{Receipt, SubmissionMode},
+        ApiVersion,
+    },
+    TxWithSignature,
+};
+use zksync_types::{network::Network, tx::TxHash, BlockNumber, TokenLike};
+
+// Local uses
+use super::{
+    account::{self, AccountStateSource},
+    block::{self, BlockSource},
+    error::{Error, InvalidDataError},
+    fee::{self, FeeSource},
+    status::{self, StatusSource},
+    token::{self, TokenSource},
+    transaction::{self, TransactionSource},
+    SharedData,
+};
+use crate::{api_server::tx_sender::SubmitError, fee_ticker::PriceError};
+
+pub use self::store::{SandboxSession, SandboxStore};
+
+mod store;
+
+/// Header with the id of the session, the requests without it are served the initial state.
+pub const SESSION_HEADER: &str = "X-Sandbox-Session";
+
+/// Session of the request, extracted by the handlers.
+type SandboxData = web::ReqData<SandboxSession>;
+
+/// Middleware attaching the session chosen by the `SESSION_HEADER` to the request.
+#[derive(Debug, Clone)]
+pub struct SandboxSessions(pub SandboxStore);
+
+impl<S, B> Transform<S, ServiceRequest> for SandboxSessions
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = SandboxSessionsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SandboxSessionsMiddleware {
+            service,
+            store: self.0.clone(),
+        }))
+    }
+}
+
+pub struct SandboxSessionsMiddleware<S> {
+    service: S,
+    store: SandboxStore,
+}
+
+impl<S, B> Service<ServiceRequest> for SandboxSessionsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = S::Future;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let session_id = req
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|value| value.to_str().ok());
+        let session = self.store.session(session_id, Instant::now());
+        req.request().extensions_mut().insert(session);
+        self.service.call(req)
+    }
+}
+
+fn find_token(session: &SandboxSession, token_like: TokenLike) -> Result<ApiToken, Error> {
+    session
+        .token(&token_like)
+        .ok_or_else(|| Error::from(PriceError::token_not_found(token_like)))
+}
+
+#[async_trait(?Send)]
+impl AccountStateSource for SandboxData {
+    async fn committed_account(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<Option<Account>, Error> {
+        Ok(SandboxSession::account_state(self, &address_or_id, Instant::now()).committed)
+    }
+
+    async fn finalized_account(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<Option<Account>, Error> {
+        Ok(SandboxSession::account_state(self, &address_or_id, Instant::now()).finalized)
+    }
+
+    async fn account_state(
+        &self,
+        address_or_id: AccountAddressOrId,
+        _depositing_version: DepositingBalancesVersion,
+    ) -> Result<AccountState, Error> {
+        Ok(SandboxSession::account_state(
+            self,
+            &address_or_id,
+            Instant::now(),
+        ))
+    }
+
+    async fn next_nonce(
+        &self,
+        address_or_id: AccountAddressOrId,
+    ) -> Result<AccountNextNonce, Error> {
+        SandboxSession::account_next_nonce(self, &address_or_id, Instant::now())
+            .ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))
+    }
+}
+
+#[async_trait(?Send)]
+impl BlockSource for SandboxData {
+    async fn block_number(&self, block_position: &str) -> Result<BlockNumber, Error> {
+        if let Ok(number) = u32::from_str(block_position) {
+            return Ok(BlockNumber(number));
+        }
+        match block_position {
+            "lastCommitted" => Ok(self.last_committed_block(Instant::now())),
+            "lastFinalized" => Ok(self.last_finalized_block(Instant::now())),
+            _ => Err(Error::from(InvalidDataError::InvalidBlockPosition)),
+        }
+    }
+
+    async fn block(&self, block_number: BlockNumber) -> Result<Option<BlockInfo>, Error> {
+        Ok(self.block_info(block_number, Instant::now()))
+    }
+}
+
+#[async_trait(?Send)]
+impl TokenSource for SandboxData {
+    async fn find_token(&self, token_like: TokenLike) -> Result<ApiToken, Error> {
+        find_token(self, token_like)
+    }
+}
+
+#[async_trait(?Send)]
+impl FeeSource for SandboxData {
+    async fn tx_fee(&self, request: TxFeeRequest) -> Result<ApiFee, Error> {
+        let token = find_token(self, request.token_like)?;
+        Ok(SandboxSession::tx_fee(self, request.tx_type.into(), &token))
+    }
+
+    async fn batch_fee(&self, request: BatchFeeRequest) -> Result<ApiFee, Error> {
+        let token = find_token(self, request.token_like)?;
+        let mut batch_fee = ApiFee {
+            gas_fee: 0u32.into(),
+            zkp_fee: 0u32.into(),
+            total_fee: 0u32.into(),
+        };
+        for tx in request.transactions {
+            if let Some(tx_token) = tx.token {
+                find_token(self, tx_token)?;
+            }
+            let fee = SandboxSession::tx_fee(self, tx.tx_type.into(), &token);
+            batch_fee.gas_fee += fee.gas_fee;
+            batch_fee.zkp_fee += fee.zkp_fee;
+            batch_fee.total_fee += fee.total_fee;
+        }
+        Ok(batch_fee)
+    }
+}
+
+#[async_trait(?Send)]
+impl TransactionSource for SandboxData {
+    async fn submit(
+        &self,
+        tx: TxWithSignature,
+        _mode: SubmissionMode,
+        _not_before_block: Option<BlockNumber>,
+    ) -> Result<TxHash, SubmitError> {
+        // The signer isn't matched with the account, since the keys of the sandbox accounts
+        // are unknown to the clients, and the Ethereum signature isn't checked at all.
+        self.submit_tx(tx.tx, Instant::now())
+    }
+
+    async fn receipt(&self, tx_hash: TxHash) -> Result<Option<Receipt>, Error> {
+        Ok(self.tx_receipt(tx_hash, Instant::now()))
+    }
+}
+
+#[async_trait(?Send)]
+impl StatusSource for SandboxData {
+    async fn network_status(&self) -> NetworkStatus {
+        SandboxSession::network_status(self, Instant::now())
+    }
+}
+
+/// The session of the request has to be attached by the `SandboxSessions` middleware.
+pub fn api_scope(net: Network) -> Scope {
+    let data = SharedData {
+        net,
+        api_version: ApiVersion::V02,
+    };
+
+    web::scope("/api/v0.2")
+        .app_data(web::Data::new(data))
+        .route(
+            "accounts/{account_id_or_address}",
+            web::get().to(account::account_full_info::<SandboxData>),
+        )
+        .route(
+            "accounts/{account_id_or_address}/committed",
+            web::get().to(account::account_committed_info::<SandboxData>),
+        )
+        .route(
+            "accounts/{account_id_or_address}/finalized",
+            web::get().to(account::account_finalized_info::<SandboxData>),
+        )
+        .route(
+            "accounts/{account_id_or_address}/nonce/next",
+            web::get().to(account::account_next_nonce::<SandboxData>),
+        )
+        .route(
+            "blocks/{block_position}",
+            web::get().to(block::block_by_position::<SandboxData>),
+        )
+        .route("fee", web::post().to(fee::get_tx_fee::<SandboxData>))
+        .route(
+            "fee/batch",
+            web::post().to(fee::get_batch_fee::<SandboxData>),
+        )
+        .route(
+            "tokens/{token_like}",
+            web::get().to(token::token_info::<SandboxData>),
+        )
+        .route(
+            "transactions",
+            web::post().to(transaction::submit_tx::<SandboxData>),
+        )
+        .route(
+            "transactions/{tx_hash}",
+            web::get().to(transaction::tx_status::<SandboxData>),
+        )
+        .route(
+            "networkStatus",
+            web::get().to(status::get_status::<SandboxData>),
+        )
+}
@@ -0,0 +1,793 @@
+//! In-memory store of the sandbox API.
+//!
+//! Accounts, tokens and blocks are derived from the configured seed, so every sandbox server
+//! started with the same config serves exactly the same data. The submitted transactions are
+//! accepted without any checks besides the correctness of the transaction and the nonce of
+//! the account; they're committed and finalized after the configured delays, each one
+//! in its own block. Balances are not affected by the submitted transactions.
+//!
+//! Every client works with its own copy of the state chosen by the session id, so the clients
+//! don't see the transactions of each other. Both the number of the sessions and the number of
+//! the transactions per session are bounded by the config.
+
+// Built-in uses
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+// External uses
+use chrono::{DateTime, TimeZone, Utc};
+use num::BigUint;
+
+// Workspace uses
+use zksync_api_types::v02::{
+    account::{Account, AccountAddressOrId, AccountNextNonce, AccountState},
+    block::{BlockInfo, BlockStatus},
+    fee::ApiFee,
+    status::NetworkStatus,
+    token::ApiToken,
+    transaction::{L2Receipt, Receipt, TxInBlockStatus},
+};
+use zksync_config::configs::api::SandboxApiConfig;
+use zksync_crypto::{ff::PrimeField, Fr};
+use zksync_types::{
+    tx::{error::TxAddError, TxHash},
+    AccountId, Address, BlockNumber, Nonce, PubKeyHash, TokenId, TokenLike, TxFeeTypes, ZkSyncTx,
+    H256,
+};
+
+// Local uses
+use super::SESSION_HEADER;
+use crate::api_server::tx_sender::SubmitError;
+
+/// Maximum length of the session id.
+const MAX_SESSION_ID_LEN: usize = 64;
+/// Timestamp of the first synthetic block.
+const GENESIS_TIMESTAMP: i64 = 1_600_000_000;
+/// Interval between the synthetic blocks, in seconds.
+const BLOCK_INTERVAL_SECS: i64 = 60;
+/// Number of the latest synthetic blocks that are committed, but not finalized yet.
+const UNFINALIZED_BLOCKS: u32 = 2;
+/// Possible sizes of the synthetic blocks, in chunks.
+const BLOCK_SIZES: [u64; 4] = [6, 30, 74, 150];
+/// Tokens of the sandbox: symbol, decimals and the price in USD.
+const TOKENS: [(&str, u8, u64); 4] = [
+    ("ETH", 18, 3000),
+    ("DAI", 18, 1),
+    ("USDC", 6, 1),
+    ("wBTC", 8, 40000),
+];
+
+/// Deterministic generator of the synthetic data (SplitMix64).
+struct SeededRng(u64);
+
+impl SeededRng {
+    fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Self(seed ^ stream.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        rng.next_u64();
+        rng
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(8) {
+            let value = self.next_u64().to_be_bytes();
+            chunk.copy_from_slice(&value[..chunk.len()]);
+        }
+    }
+
+    fn address(&mut self) -> Address {
+        let mut address = Address::zero();
+        self.fill(address.as_bytes_mut());
+        address
+    }
+
+    fn hash(&mut self) -> H256 {
+        let mut hash = H256::zero();
+        self.fill(hash.as_bytes_mut());
+        hash
+    }
+}
+
+/// Stream of the random generator per kind of the synthetic data,
+/// so adding the data of one kind doesn't change the rest.
+#[derive(Debug, Clone, Copy)]
+enum DataKind {
+    Token = 1,
+    Account = 2,
+    Block = 3,
+}
+
+fn rng(seed: u64, kind: DataKind, index: u64) -> SeededRng {
+    SeededRng::new(seed, ((kind as u64) << 32) | index)
+}
+
+#[derive(Debug, Clone)]
+struct SandboxAccount {
+    id: AccountId,
+    address: Address,
+    pub_key_hash: PubKeyHash,
+    /// Nonce of the account before the submitted transactions.
+    initial_nonce: Nonce,
+    balances: BTreeMap<String, BigUint>,
+    /// Positions of the account transactions in the `SandboxState::txs`.
+    txs: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct SubmittedTx {
+    submitted_at: Instant,
+    submitted_at_utc: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct SandboxState {
+    accounts: Vec<SandboxAccount>,
+    /// Submitted transactions in the order of submission.
+    txs: Vec<SubmittedTx>,
+    tx_positions: HashMap<TxHash, usize>,
+}
+
+#[derive(Debug)]
+struct StoredSession {
+    state: Arc<RwLock<SandboxState>>,
+    last_access: Instant,
+}
+
+/// Synthetic data shared by all the sessions, and the states of the sessions.
+#[derive(Debug, Clone)]
+pub struct SandboxStore {
+    config: SandboxApiConfig,
+    tokens: Arc<Vec<ApiToken>>,
+    /// State every session starts with.
+    initial_state: Arc<SandboxState>,
+    sessions: Arc<Mutex<HashMap<String, StoredSession>>>,
+}
+
+/// View of the sandbox for a single request.
+#[derive(Debug, Clone)]
+pub struct SandboxSession {
+    store: SandboxStore,
+    /// State of the session, `None` if the request has no valid session id: the initial state
+    /// is served then, and the submissions are rejected.
+    state: Option<Arc<RwLock<SandboxState>>>,
+}
+
+impl SandboxStore {
+    pub fn new(config: SandboxApiConfig) -> Self {
+        let tokens = TOKENS
+            .iter()
+            .enumerate()
+            .map(|(id, (symbol, decimals, _))| ApiToken {
+                id: TokenId(id as u32),
+                address: if id == 0 {
+                    Address::zero()
+                } else {
+                    rng(config.seed, DataKind::Token, id as u64).address()
+                },
+                symbol: symbol.to_string(),
+                decimals: *decimals,
+                enabled_for_fees: true,
+            })
+            .collect();
+        let accounts = (0..config.accounts_count)
+            .map(|index| {
+                let mut rng = rng(config.seed, DataKind::Account, index as u64);
+                let address = rng.address();
+                let mut pub_key_hash = PubKeyHash::default();
+                rng.fill(&mut pub_key_hash.data);
+                let initial_nonce = Nonce((rng.next_u64() % 10) as u32);
+                let balances = TOKENS
+                    .iter()
+                    .map(|(symbol, decimals, _)| {
+                        let amount = BigUint::from(rng.next_u64() % 1000)
+                            * BigUint::from(10u32).pow(*decimals as u32);
+                        (symbol.to_string(), amount)
+                    })
+                    .collect();
+                SandboxAccount {
+                    // The first account is the fee account of the operator.
+                    id: AccountId(index + 1),
+                    address,
+                    pub_key_hash,
+                    initial_nonce,
+                    balances,
+                    txs: Vec::new(),
+                }
+            })
+            .collect();
+
+        Self {
+            config,
+            tokens: Arc::new(tokens),
+            initial_state: Arc::new(SandboxState {
+                accounts,
+                txs: Vec::new(),
+                tx_positions: HashMap::new(),
+            }),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the session with the given id, starting a new one if there is no such session.
+    pub fn session(&self, session_id: Option<&str>, now: Instant) -> SandboxSession {
+        let state = match session_id {
+            Some(id) if !id.is_empty() && id.len() <= MAX_SESSION_ID_LEN => {
+                Some(self.stored_session(id, now))
+            }
+            _ => None,
+        };
+        SandboxSession {
+            store: self.clone(),
+            state,
+        }
+    }
+
+    fn stored_session(&self, session_id: &str, now: Instant) -> Arc<RwLock<SandboxState>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(session) = sessions.get_mut(session_id) {
+            session.last_access = now;
+            return session.state.clone();
+        }
+
+        if sessions.len() >= self.config.max_sessions {
+            let least_recent = sessions
+                .iter()
+                .min_by_key(|(_, session)| session.last_access)
+                .map(|(id, _)| id.clone());
+            if let Some(id) = least_recent {
+                sessions.remove(&id);
+            }
+        }
+        let state = Arc::new(RwLock::new(SandboxState::clone(&self.initial_state)));
+        sessions.insert(
+            session_id.to_string(),
+            StoredSession {
+                state: state.clone(),
+                last_access: now,
+            },
+        );
+        state
+    }
+}
+
+impl SandboxSession {
+    fn config(&self) -> &SandboxApiConfig {
+        &self.store.config
+    }
+
+    /// Runs `f` over the state of the session.
+    fn read<R>(&self, f: impl FnOnce(&SandboxState) -> R) -> R {
+        match &self.state {
+            Some(state) => f(&state.read().unwrap()),
+            None => f(&self.store.initial_state),
+        }
+    }
+
+    fn commit_delay(&self) -> Duration {
+        self.config().commit_delay()
+    }
+
+    fn finalize_delay(&self) -> Duration {
+        self.config().commit_delay() + self.config().finalize_delay()
+    }
+
+    /// Number of the submitted transactions committed (or finalized) by the given moment.
+    fn processed_txs_count(txs: &[SubmittedTx], delay: Duration, now: Instant) -> usize {
+        // Transactions are ordered by the time of submission, so they're processed in order.
+        txs.iter()
+            .take_while(|tx| now.saturating_duration_since(tx.submitted_at) >= delay)
+            .count()
+    }
+
+    fn submitted_tx_block(&self, position: usize) -> BlockNumber {
+        BlockNumber(self.config().blocks_count + 1 + position as u32)
+    }
+
+    pub fn last_committed_block(&self, now: Instant) -> BlockNumber {
+        let committed =
+            self.read(|state| Self::processed_txs_count(&state.txs, self.commit_delay(), now));
+        BlockNumber(self.config().blocks_count + committed as u32)
+    }
+
+    pub fn last_finalized_block(&self, now: Instant) -> BlockNumber {
+        let blocks_count = self.config().blocks_count;
+        match self.read(|state| Self::processed_txs_count(&state.txs, self.finalize_delay(), now)) {
+            0 => BlockNumber(blocks_count.saturating_sub(UNFINALIZED_BLOCKS)),
+            finalized => BlockNumber(blocks_count + finalized as u32),
+        }
+    }
+
+    pub fn block_info(&self, block_number: BlockNumber, now: Instant) -> Option<BlockInfo> {
+        if *block_number == 0 || block_number > self.last_committed_block(now) {
+            return None;
+        }
+        let mut rng = rng(self.config().seed, DataKind::Block, *block_number as u64);
+        let new_state_root = Fr::from_str(&rng.next_u64().to_string()).unwrap();
+        let block_size = BLOCK_SIZES[(rng.next_u64() % BLOCK_SIZES.len() as u64) as usize];
+        let commit_tx_hash = rng.hash();
+        let verify_tx_hash = rng.hash();
+
+        let (committed_at, finalized_at) = if *block_number <= self.config().blocks_count {
+            let committed_at = Utc.timestamp(
+                GENESIS_TIMESTAMP + *block_number as i64 * BLOCK_INTERVAL_SECS,
+                0,
+            );
+            (
+                committed_at,
+                committed_at + chrono::Duration::seconds(BLOCK_INTERVAL_SECS),
+            )
+        } else {
+            let position = (*block_number - self.config().blocks_count - 1) as usize;
+            let submitted_at = self.read(|state| state.txs[position].submitted_at_utc);
+            let to_chrono = |delay| chrono::Duration::from_std(delay).unwrap();
+            (
+                submitted_at + to_chrono(self.commit_delay()),
+                submitted_at + to_chrono(self.finalize_delay()),
+            )
+        };
+        let finalized = block_number <= self.last_finalized_block(now);
+
+        Some(BlockInfo {
+            block_number,
+            new_state_root,
+            block_size,
+            commit_tx_hash: Some(commit_tx_hash),
+            verify_tx_hash: finalized.then(|| verify_tx_hash),
+            committed_at,
+            finalized_at: finalized.then(|| finalized_at),
+            status: if finalized {
+                BlockStatus::Finalized
+            } else {
+                BlockStatus::Committed
+            },
+        })
+    }
+
+    pub fn token(&self, token_like: &TokenLike) -> Option<ApiToken> {
+        self.store
+            .tokens
+            .iter()
+            .find(|token| match token_like {
+                TokenLike::Id(id) => token.id == *id,
+                TokenLike::Address(address) => token.address == *address,
+                TokenLike::Symbol(symbol) => token.symbol.eq_ignore_ascii_case(symbol),
+            })
+            .cloned()
+    }
+
+    fn find_account<'a>(
+        state: &'a SandboxState,
+        account: &AccountAddressOrId,
+    ) -> Option<&'a SandboxAccount> {
+        state.accounts.iter().find(|sandbox_account| match account {
+            AccountAddressOrId::Id(id) => sandbox_account.id == *id,
+            AccountAddressOrId::Address(address) => sandbox_account.address == *address,
+        })
+    }
+
+    fn api_account(
+        &self,
+        state: &SandboxState,
+        account: &SandboxAccount,
+        delay: Duration,
+        now: Instant,
+    ) -> Account {
+        let processed_txs: Vec<_> = account
+            .txs
+            .iter()
+            .filter(|position| {
+                now.saturating_duration_since(state.txs[**position].submitted_at) >= delay
+            })
+            .collect();
+        let last_update_in_block = match processed_txs.last() {
+            Some(position) => self.submitted_tx_block(**position),
+            None => BlockNumber(1),
+        };
+        Account {
+            account_id: account.id,
+            address: account.address,
+            nonce: account.initial_nonce + processed_txs.len() as u32,
+            pub_key_hash: account.pub_key_hash,
+            last_update_in_block,
+            balances: account
+                .balances
+                .iter()
+                .map(|(symbol, balance)| (symbol.clone(), balance.clone().into()))
+                .collect(),
+            nfts: BTreeMap::new(),
+            minted_nfts: BTreeMap::new(),
+            account_type: None,
+        }
+    }
+
+    pub fn account_state(&self, account: &AccountAddressOrId, now: Instant) -> AccountState {
+        self.read(|state| {
+            let account = match Self::find_account(state, account) {
+                Some(account) => account,
+                None => return AccountState::default(),
+            };
+            AccountState {
+                depositing: Default::default(),
+                committed: Some(self.api_account(state, account, self.commit_delay(), now)),
+                finalized: Some(self.api_account(state, account, self.finalize_delay(), now)),
+            }
+        })
+    }
+
+    pub fn account_next_nonce(
+        &self,
+        account: &AccountAddressOrId,
+        now: Instant,
+    ) -> Option<AccountNextNonce> {
+        self.read(|state| {
+            let account = Self::find_account(state, account)?;
+            let committed = self.api_account(state, account, self.commit_delay(), now);
+            Some(AccountNextNonce {
+                committed_nonce: committed.nonce,
+                next_nonce: account.initial_nonce + account.txs.len() as u32,
+            })
+        })
+    }
+
+    /// Fee of the transaction in the given token: the fixed price of the operation in USD
+    /// converted with the fixed price of the token.
+    pub fn tx_fee(&self, tx_type: TxFeeTypes, token: &ApiToken) -> ApiFee {
+        let gas_fee_usd_cents: u64 = match tx_type {
+            TxFeeTypes::Transfer => 10,
+            TxFeeTypes::Swap => 20,
+            TxFeeTypes::MintNFT => 30,
+            TxFeeTypes::ChangePubKey(_) => 100,
+            TxFeeTypes::Withdraw => 200,
+            TxFeeTypes::WithdrawNFT => 250,
+            TxFeeTypes::FastWithdraw => 500,
+            TxFeeTypes::FastWithdrawNFT => 600,
+        };
+        let zkp_fee_usd_cents = 1;
+
+        let usd_price = TOKENS[*token.id as usize].2;
+        let to_token_amount = |usd_cents: u64| {
+            BigUint::from(usd_cents) * BigUint::from(10u32).pow(token.decimals as u32)
+                / BigUint::from(100 * usd_price)
+        };
+        let gas_fee = to_token_amount(gas_fee_usd_cents);
+        let zkp_fee = to_token_amount(zkp_fee_usd_cents);
+        ApiFee {
+            total_fee: &gas_fee + &zkp_fee,
+            gas_fee,
+            zkp_fee,
+        }
+    }
+
+    pub fn submit_tx(&self, mut tx: ZkSyncTx, now: Instant) -> Result<TxHash, SubmitError> {
+        let tx_hash = tx.hash();
+        let state = self.state.as_ref().ok_or_else(|| {
+            SubmitError::InvalidParams(format!(
+                "`{}` header of at most {} characters is required to submit transactions",
+                SESSION_HEADER, MAX_SESSION_ID_LEN
+            ))
+        })?;
+        let mut state = state.write().unwrap();
+        let state = &mut *state;
+        if state.tx_positions.contains_key(&tx_hash) {
+            return Ok(tx_hash);
+        }
+        if state.txs.len() >= self.config().max_txs_per_session {
+            return Err(SubmitError::InvalidParams(format!(
+                "sandbox session is limited to {} transactions, start a new one",
+                self.config().max_txs_per_session
+            )));
+        }
+
+        tx.check_correctness()
+            .map_err(|err| SubmitError::TxAdd(err.into()))?;
+        let account_id = tx
+            .account_id()
+            .map_err(|_| SubmitError::AccountCloseDisabled)?;
+        let position = state.txs.len();
+        let account = state
+            .accounts
+            .iter_mut()
+            .find(|account| account.id == account_id)
+            .ok_or_else(|| SubmitError::InvalidParams("account is not found".to_string()))?;
+        if account.address != tx.account() {
+            return Err(SubmitError::InvalidParams(
+                "account address doesn't match the account id".to_string(),
+            ));
+        }
+        if tx.nonce() != account.initial_nonce + account.txs.len() as u32 {
+            return Err(SubmitError::TxAdd(TxAddError::NonceMismatch));
+        }
+
+        account.txs.push(position);
+        state.txs.push(SubmittedTx {
+            submitted_at: now,
+            submitted_at_utc: Utc::now(),
+        });
+        state.tx_positions.insert(tx_hash, position);
+        Ok(tx_hash)
+    }
+
+    pub fn tx_receipt(&self, tx_hash: TxHash, now: Instant) -> Option<Receipt> {
+        let (position, submitted_at) = self.read(|state| {
+            let position = *state.tx_positions.get(&tx_hash)?;
+            Some((position, state.txs[position].submitted_at))
+        })?;
+        let elapsed = now.saturating_duration_since(submitted_at);
+        let (status, rollup_block) = if elapsed >= self.finalize_delay() {
+            (
+                TxInBlockStatus::Finalized,
+                Some(self.submitted_tx_block(position)),
+            )
+        } else if elapsed >= self.commit_delay() {
+            (
+                TxInBlockStatus::Committed,
+                Some(self.submitted_tx_block(position)),
+            )
+        } else {
+            (TxInBlockStatus::Queued, None)
+        };
+        Some(Receipt::L2(L2Receipt {
+            tx_hash,
+            rollup_block,
+            status,
+            fail_reason: None,
+            fail_code: None,
+            block_index: None,
+        }))
+    }
+
+    pub fn network_status(&self, now: Instant) -> NetworkStatus {
+        let last_committed = self.last_committed_block(now);
+        let finalized = self.last_finalized_block(now);
+        let (committed_txs, submitted_txs) = self.read(|state| {
+            let committed = Self::processed_txs_count(&state.txs, self.commit_delay(), now);
+            (committed, state.txs.len())
+        });
+        // Every synthetic block is assumed to contain a single transaction per chunk.
+        let synthetic_txs: u64 = (1..=self.config().blocks_count)
+            .map(|block_number| {
+                let mut rng = rng(self.config().seed, DataKind::Block, block_number as u64);
+                rng.next_u64();
+                BLOCK_SIZES[(rng.next_u64() % BLOCK_SIZES.len() as u64) as usize]
+            })
+            .sum();
+        NetworkStatus {
+            last_committed,
+            finalized,
+            total_transactions: synthetic_txs as u32 + committed_txs as u32,
+            mempool_size: (submitted_txs - committed_txs) as u32,
+            core_status: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+    use zksync_test_account::ZkSyncAccount;
+
+    use super::*;
+
+    fn config(commit_delay_ms: u64, finalize_delay_ms: u64) -> SandboxApiConfig {
+        SandboxApiConfig {
+            seed: 42,
+            accounts_count: 3,
+            blocks_count: 10,
+            commit_delay_ms,
+            finalize_delay_ms,
+            max_sessions: 2,
+            max_txs_per_session: 2,
+        }
+    }
+
+    fn signed_transfer(store: &SandboxStore, account_id: AccountId, nonce: Nonce) -> ZkSyncTx {
+        let account = store.initial_state.accounts[*account_id as usize - 1].clone();
+        let mut signer = ZkSyncAccount::rand();
+        signer.set_account_id(Some(account.id));
+        signer.address = account.address;
+        let (transfer, _) = signer.sign_transfer(
+            TokenId(0),
+            "ETH",
+            BigUint::from(100u32),
+            BigUint::from(10u32),
+            &Address::repeat_byte(0x11),
+            Some(nonce),
+            false,
+            Default::default(),
+        );
+        ZkSyncTx::Transfer(Box::new(transfer))
+    }
+
+    #[test]
+    fn synthetic_data_is_deterministic() {
+        let now = Instant::now();
+        let store = SandboxStore::new(config(0, 0)).session(None, now);
+        let same_store = SandboxStore::new(config(0, 0)).session(Some("session"), now);
+        let other_store = SandboxStore::new(SandboxApiConfig {
+            seed: 43,
+            ..config(0, 0)
+        })
+        .session(None, now);
+
+        let account = AccountAddressOrId::Id(AccountId(1));
+        let state = store.account_state(&account, now);
+        assert!(state.committed.is_some());
+        assert_eq!(state, same_store.account_state(&account, now));
+        assert_ne!(state, other_store.account_state(&account, now));
+        assert_eq!(
+            store.block_info(BlockNumber(5), now),
+            same_store.block_info(BlockNumber(5), now)
+        );
+        assert_eq!(store.network_status(now), same_store.network_status(now));
+
+        // Accounts can be found by address as well.
+        let address = state.committed.unwrap().address;
+        assert_eq!(
+            store.account_state(&AccountAddressOrId::Address(address), now),
+            store.account_state(&account, now)
+        );
+        assert_eq!(
+            store.account_state(&AccountAddressOrId::Id(AccountId(4)), now),
+            AccountState::default()
+        );
+
+        assert_eq!(store.last_committed_block(now), BlockNumber(10));
+        assert_eq!(store.last_finalized_block(now), BlockNumber(8));
+        assert!(store.block_info(BlockNumber(11), now).is_none());
+        assert_eq!(
+            store.block_info(BlockNumber(9), now).unwrap().status,
+            BlockStatus::Committed
+        );
+        assert_eq!(
+            store.block_info(BlockNumber(8), now).unwrap().status,
+            BlockStatus::Finalized
+        );
+    }
+
+    #[test]
+    fn fees() {
+        let store = SandboxStore::new(config(0, 0)).session(None, Instant::now());
+        let usdc = store.token(&TokenLike::Symbol("usdc".to_string())).unwrap();
+        let fee = store.tx_fee(TxFeeTypes::Transfer, &usdc);
+        assert_eq!(fee.gas_fee, BigUint::from(100_000u32));
+        assert_eq!(fee.zkp_fee, BigUint::from(10_000u32));
+        assert_eq!(fee.total_fee, BigUint::from(110_000u32));
+
+        let eth = store.token(&TokenLike::Id(TokenId(0))).unwrap();
+        assert!(
+            store.tx_fee(TxFeeTypes::Withdraw, &eth).total_fee
+                > store.tx_fee(TxFeeTypes::Transfer, &eth).total_fee
+        );
+    }
+
+    #[test]
+    fn status_progression() {
+        let sandbox = SandboxStore::new(config(1000, 5000));
+        let account = AccountAddressOrId::Id(AccountId(2));
+        let start = Instant::now();
+        let store = sandbox.session(Some("session"), start);
+        let initial_nonce = store
+            .account_next_nonce(&account, start)
+            .unwrap()
+            .next_nonce;
+
+        // Transactions with the wrong nonce are rejected.
+        let tx = signed_transfer(&sandbox, AccountId(2), initial_nonce + 1);
+        assert!(matches!(
+            store.submit_tx(tx, start),
+            Err(SubmitError::TxAdd(_))
+        ));
+
+        let tx = signed_transfer(&sandbox, AccountId(2), initial_nonce);
+        let tx_hash = store.submit_tx(tx, start).unwrap();
+        assert_eq!(
+            store.account_next_nonce(&account, start),
+            Some(AccountNextNonce {
+                committed_nonce: initial_nonce,
+                next_nonce: initial_nonce + 1,
+            })
+        );
+
+        let receipt = |elapsed_ms| match store
+            .tx_receipt(tx_hash, start + Duration::from_millis(elapsed_ms))
+            .unwrap()
+        {
+            Receipt::L2(receipt) => (receipt.status, receipt.rollup_block),
+            Receipt::L1(_) => unreachable!(),
+        };
+        assert_eq!(receipt(0), (TxInBlockStatus::Queued, None));
+        assert_eq!(
+            receipt(1000),
+            (TxInBlockStatus::Committed, Some(BlockNumber(11)))
+        );
+        assert_eq!(
+            receipt(6000),
+            (TxInBlockStatus::Finalized, Some(BlockNumber(11)))
+        );
+
+        let committed = start + Duration::from_millis(1000);
+        assert_eq!(store.last_committed_block(committed), BlockNumber(11));
+        assert_eq!(store.last_finalized_block(committed), BlockNumber(8));
+        assert_eq!(
+            store
+                .account_state(&account, committed)
+                .committed
+                .unwrap()
+                .nonce,
+            initial_nonce + 1
+        );
+        let block = store.block_info(BlockNumber(11), committed).unwrap();
+        assert_eq!(block.status, BlockStatus::Committed);
+
+        let finalized = start + Duration::from_millis(6000);
+        assert_eq!(store.last_finalized_block(finalized), BlockNumber(11));
+        assert_eq!(store.network_status(finalized).mempool_size, 0);
+    }
+
+    #[test]
+    fn sessions_are_isolated_and_bounded() {
+        let sandbox = SandboxStore::new(config(1000, 5000));
+        let account = AccountAddressOrId::Id(AccountId(1));
+        let now = Instant::now();
+        let next_nonce = |session: &SandboxSession| {
+            session
+                .account_next_nonce(&account, now)
+                .unwrap()
+                .next_nonce
+        };
+        let initial_nonce = next_nonce(&sandbox.session(None, now));
+
+        // Submissions require the session.
+        let tx = signed_transfer(&sandbox, AccountId(1), initial_nonce);
+        assert!(matches!(
+            sandbox.session(None, now).submit_tx(tx.clone(), now),
+            Err(SubmitError::InvalidParams(_))
+        ));
+        let long_id = "x".repeat(MAX_SESSION_ID_LEN + 1);
+        assert!(matches!(
+            sandbox
+                .session(Some(&long_id), now)
+                .submit_tx(tx.clone(), now),
+            Err(SubmitError::InvalidParams(_))
+        ));
+
+        let first = sandbox.session(Some("first"), now);
+        first.submit_tx(tx.clone(), now).unwrap();
+        assert_eq!(next_nonce(&first), initial_nonce + 1);
+        // The same id refers to the same session.
+        assert_eq!(
+            next_nonce(&sandbox.session(Some("first"), now)),
+            initial_nonce + 1
+        );
+        // Other sessions don't see the transaction.
+        let second = sandbox.session(Some("second"), now + Duration::from_millis(1));
+        assert_eq!(next_nonce(&second), initial_nonce);
+        assert!(second.tx_receipt(tx.hash(), now).is_none());
+        assert_eq!(next_nonce(&sandbox.session(None, now)), initial_nonce);
+
+        // The number of the transactions per session is limited.
+        let tx = signed_transfer(&sandbox, AccountId(1), initial_nonce + 1);
+        first.submit_tx(tx, now).unwrap();
+        let tx = signed_transfer(&sandbox, AccountId(1), initial_nonce + 2);
+        assert!(matches!(
+            first.submit_tx(tx, now),
+            Err(SubmitError::InvalidParams(_))
+        ));
+
+        // The least recently used session is dropped once the limit is reached.
+        sandbox.session(Some("third"), now + Duration::from_millis(2));
+        assert_eq!(
+            next_nonce(&sandbox.session(Some("first"), now)),
+            initial_nonce
+        );
+        assert_eq!(sandbox.sessions.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,271 @@
+//! Search part of API implementation.
+//!
+//! Resolves a free-form query of the explorer search box to the API resource of the entity.
+//! The query is checked in the following order:
+//!
+//! 1. Block number, if the query is a decimal number;
+//! 2. Account, if the query is an address. Any well-formed address is resolved,
+//!    since the account endpoints are valid for the accounts not yet in the tree;
+//! 3. Transaction or priority operation, if the query is a 32-byte hash
+//!    (either zkSync transaction hash or Ethereum hash of the priority operation);
+//! 4. Token, by its symbol.
+
+// Built-in uses
+use std::str::FromStr;
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+
+// Workspace uses
+use zksync_api_types::v02::search::{SearchEntityType, SearchQuery, SearchResult};
+use zksync_storage::ConnectionPool;
+use zksync_token_db_cache::TokenDBCache;
+use zksync_types::{tx::TxHash, Address, BlockNumber, TokenLike, H256};
+
+// Local uses
+use super::{error::Error, response::ApiResult};
+
+const API_V02_PATH: &str = "/api/v0.2";
+
+/// Shared data between `api/v0.2/search` endpoints.
+#[derive(Clone)]
+struct ApiSearchData {
+    pool: ConnectionPool,
+    tokens: TokenDBCache,
+}
+
+impl ApiSearchData {
+    fn new(pool: ConnectionPool, tokens: TokenDBCache) -> Self {
+        Self { pool, tokens }
+    }
+
+    async fn search(&self, query: &str) -> Result<Option<SearchResult>, Error> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(None);
+        }
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+
+        if let Ok(block_number) = u32::from_str(query) {
+            let last_committed = storage
+                .chain()
+                .block_schema()
+                .get_last_committed_block()
+                .await
+                .map_err(Error::storage)?;
+            if block_number == 0 || BlockNumber(block_number) > last_committed {
+                return Ok(None);
+            }
+            return Ok(Some(SearchResult {
+                entity_type: SearchEntityType::Block,
+                link: format!("{}/blocks/{}", API_V02_PATH, block_number),
+            }));
+        }
+
+        let hex_query = query.strip_prefix("0x").unwrap_or(query);
+        if hex_query.len() == 40 {
+            if let Ok(address) = Address::from_str(hex_query) {
+                return Ok(Some(SearchResult {
+                    entity_type: SearchEntityType::Account,
+                    link: format!("{}/accounts/{:#x}", API_V02_PATH, address),
+                }));
+            }
+        }
+
+        if let Ok(tx_hash) = TxHash::from_str(query) {
+            if let Some(data) = storage
+                .chain()
+                .operations_ext_schema()
+                .tx_data_api_v02(tx_hash.as_ref())
+                .await
+                .map_err(Error::storage)?
+            {
+                // Executed priority operations are also found by their Ethereum hash.
+                let entity_type = if data.tx.tx_hash == tx_hash {
+                    SearchEntityType::Transaction
+                } else {
+                    SearchEntityType::PriorityOperation
+                };
+                return Ok(Some(SearchResult {
+                    entity_type,
+                    link: tx_link(data.tx.tx_hash),
+                }));
+            }
+            if let Some(op) = storage
+                .chain()
+                .mempool_schema()
+                .get_pending_operation_by_hash(H256::from(tx_hash))
+                .await
+                .map_err(Error::storage)?
+            {
+                return Ok(Some(SearchResult {
+                    entity_type: SearchEntityType::PriorityOperation,
+                    link: tx_link(op.tx_hash()),
+                }));
+            }
+            return Ok(None);
+        }
+
+        let token = self
+            .tokens
+            .get_token(&mut storage, TokenLike::Symbol(query.to_owned()))
+            .await
+            .map_err(Error::storage)?;
+        Ok(token.map(|token| SearchResult {
+            entity_type: SearchEntityType::Token,
+            link: format!("{}/tokens/{}", API_V02_PATH, token.id),
+        }))
+    }
+}
+
+fn tx_link(tx_hash: TxHash) -> String {
+    format!(
+        "{}/transactions/0x{}",
+        API_V02_PATH,
+        hex::encode(tx_hash.as_ref())
+    )
+}
+
+// Server implementation
+
+async fn search(
+    data: web::Data<ApiSearchData>,
+    web::Query(query): web::Query<SearchQuery>,
+) -> ApiResult<Option<SearchResult>> {
+    let start = Instant::now();
+    let res = data.search(&query.query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "search");
+    res
+}
+
+pub fn api_scope(pool: ConnectionPool, tokens: TokenDBCache) -> Scope {
+    let data = ApiSearchData::new(pool, tokens);
+
+    web::scope("search")
+        .app_data(web::Data::new(data))
+        .route("", web::get().to(search))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{deserialize_response_result, dummy_deposit_op, TestServerConfig},
+        SharedData,
+    };
+    use zksync_api_types::v02::ApiVersion;
+    use zksync_types::AccountId;
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn search_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    TokenDBCache::new(cfg.config.api.token_config.invalidate_token_cache_period()),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let (tx_hash, priority_op) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let txs = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(1))
+                .await?;
+            let tx_hash = TxHash::from_str(&txs[0].tx_hash).unwrap();
+
+            let mut priority_op =
+                dummy_deposit_op(Address::default(), AccountId(3), 1000, 0).priority_op;
+            priority_op.eth_hash = H256::repeat_byte(0xcd);
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_priority_ops(&[priority_op.clone()], true)
+                .await?;
+            (tx_hash, priority_op)
+        };
+
+        let response = client.search("1").await?;
+        let result: Option<SearchResult> = deserialize_response_result(response)?;
+        assert_eq!(
+            result,
+            Some(SearchResult {
+                entity_type: SearchEntityType::Block,
+                link: "/api/v0.2/blocks/1".into(),
+            })
+        );
+
+        let response = client.search("4294967295").await?;
+        let result: Option<SearchResult> = deserialize_response_result(response)?;
+        assert_eq!(result, None);
+
+        let address = Address::repeat_byte(0xab);
+        let response = client.search(&format!("{:?}", address)).await?;
+        let result: Option<SearchResult> = deserialize_response_result(response)?;
+        assert_eq!(
+            result,
+            Some(SearchResult {
+                entity_type: SearchEntityType::Account,
+                link: format!("/api/v0.2/accounts/0x{}", "ab".repeat(20)),
+            })
+        );
+
+        let response = client.search(&tx_hash.to_string()).await?;
+        let result: Option<SearchResult> = deserialize_response_result(response)?;
+        assert_eq!(
+            result,
+            Some(SearchResult {
+                entity_type: SearchEntityType::Transaction,
+                link: tx_link(tx_hash),
+            })
+        );
+
+        let response = client
+            .search(&format!("{:?}", priority_op.eth_hash))
+            .await?;
+        let result: Option<SearchResult> = deserialize_response_result(response)?;
+        assert_eq!(
+            result,
+            Some(SearchResult {
+                entity_type: SearchEntityType::PriorityOperation,
+                link: tx_link(priority_op.tx_hash()),
+            })
+        );
+
+        let response = client.search(&format!("0x{}", "00".repeat(32))).await?;
+        let result: Option<SearchResult> = deserialize_response_result(response)?;
+        assert_eq!(result, None);
+
+        let response = client.search("ETH").await?;
+        let result: Option<SearchResult> = deserialize_response_result(response)?;
+        assert_eq!(
+            result,
+            Some(SearchResult {
+                entity_type: SearchEntityType::Token,
+                link: "/api/v0.2/tokens/0".into(),
+            })
+        );
+
+        let response = client.search("UNKNOWN").await?;
+        let result: Option<SearchResult> = deserialize_response_result(response)?;
+        assert_eq!(result, None);
+
+        server.stop().await;
+        Ok(())
+    }
+}
@@ -4,7 +4,8 @@
 
 use std::time::Instant;
 // External uses
-use actix_web::{web, Scope};
+use actix_web::{web, FromRequest, Scope};
+use async_trait::async_trait;
 
 // Workspace uses
 use zksync_api_types::v02::status::NetworkStatus;
@@ -27,17 +28,30 @@ impl ApiStatusData {
 
 // Server implementation
 
-async fn get_status(data: web::Data<ApiStatusData>) -> ApiResult<NetworkStatus> {
-    let start = Instant::now();
+/// Source of the network status, so the handler serves both the status of the network
+/// and the simulated one of the sandbox.
+#[async_trait(?Send)]
+pub(super) trait StatusSource: FromRequest + 'static {
+    async fn network_status(&self) -> NetworkStatus;
+}
 
-    let status = data.status.read().await;
-    let network_status = NetworkStatus {
-        last_committed: status.last_committed,
-        finalized: status.last_verified,
-        total_transactions: status.total_transactions,
-        mempool_size: status.mempool_size,
-        core_status: status.core_status,
-    };
+#[async_trait(?Send)]
+impl StatusSource for web::Data<ApiStatusData> {
+    async fn network_status(&self) -> NetworkStatus {
+        let status = self.status.read().await;
+        NetworkStatus {
+            last_committed: status.last_committed,
+            finalized: status.last_verified,
+            total_transactions: status.total_transactions,
+            mempool_size: status.mempool_size,
+            core_status: status.core_status,
+        }
+    }
+}
+
+pub(super) async fn get_status<D: StatusSource>(data: D) -> ApiResult<NetworkStatus> {
+    let start = Instant::now();
+    let network_status = data.network_status().await;
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_status");
     Ok(network_status).into()
 }
@@ -47,17 +61,21 @@ pub fn api_scope(shared_status: SharedNetworkStatus) -> Scope {
 
     web::scope("networkStatus")
         .app_data(web::Data::new(data))
-        .route("", web::get().to(get_status))
+        .route("", web::get().to(get_status::<web::Data<ApiStatusData>>))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api_server::rest::v02::{
-        test_utils::{deserialize_response_result, TestServerConfig},
-        SharedData,
+    use crate::api_server::{
+        core_api_client::CoreApiClient,
+        rest::v02::{
+            test_utils::{deserialize_response_result, TestServerConfig},
+            SharedData,
+        },
     };
     use zksync_api_types::v02::ApiVersion;
+    use zksync_config::configs::api::PrivateApiConfig;
     use zksync_types::SequentialTxId;
 
     #[actix_rt::test]
@@ -73,7 +91,11 @@ mod tests {
             net: cfg.config.chain.eth.network,
             api_version: ApiVersion::V02,
         };
-        let mut status = SharedNetworkStatus::new("0.0.0.0".to_string());
+        let core_client = CoreApiClient::new(&PrivateApiConfig {
+            url: "0.0.0.0".to_string(),
+            ..cfg.config.api.private.clone()
+        });
+        let mut status = SharedNetworkStatus::new(core_client);
         let (client, server) = cfg.start_server(
             {
                 let status = status.clone();
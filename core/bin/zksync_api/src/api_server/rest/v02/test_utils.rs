@@ -47,6 +47,9 @@ use zksync_utils::{big_decimal_to_ratio, scaled_u64_to_ratio, UnsignedRatioSeria
 
 // Local uses
 use crate::fee_ticker::{
+    batch_discount::BatchDiscountConfig,
+    congestion::CongestionConfig,
+    gas_price::GasPriceConfig,
     tests::TestToken,
     ticker_info::BlocksInFutureAggregatedOperations,
     validator::{cache::TokenInMemoryCache, FeeTokenValidator},
@@ -171,6 +174,7 @@ impl TestServerConfig {
                 success: true,
                 op: Some(zksync_op),
                 fail_reason: None,
+                fail_code: None,
                 block_index: Some(1),
                 created_at: chrono::Utc::now(),
                 batch_id: None,
@@ -207,6 +211,7 @@ impl TestServerConfig {
                 success: true,
                 op: Some(zksync_op),
                 fail_reason: None,
+                fail_code: None,
                 block_index: Some(2),
                 created_at: chrono::Utc::now(),
                 batch_id: None,
@@ -243,6 +248,7 @@ impl TestServerConfig {
                 success: false,
                 op: Some(zksync_op),
                 fail_reason: Some("Unknown token".to_string()),
+                fail_code: None,
                 block_index: None,
                 created_at: chrono::Utc::now(),
                 batch_id: None,
@@ -278,6 +284,7 @@ impl TestServerConfig {
                 success: true,
                 op: Some(zksync_op),
                 fail_reason: None,
+                fail_code: None,
                 block_index: Some(3),
                 created_at: chrono::Utc::now(),
                 batch_id: None,
@@ -314,6 +321,7 @@ impl TestServerConfig {
                 success: true,
                 op: Some(zksync_op),
                 fail_reason: None,
+                fail_code: None,
                 block_index: Some(4),
                 created_at: chrono::Utc::now(),
                 batch_id: None,
@@ -499,6 +507,8 @@ impl TestServerConfig {
                     Some((id, op)),
                     100,
                     100u32.into(),
+                    None,
+                    None,
                     Default::default(),
                 )
                 .await?;
@@ -536,12 +546,22 @@ impl TestServerConfig {
 
                 // Get job id.
                 let stored_job_id = ProverSchema(&mut storage)
-                    .get_idle_prover_job_from_job_queue()
+                    .get_idle_prover_job_from_job_queue(
+                        "test_prover",
+                        &Default::default(),
+                        std::time::Duration::from_secs(60),
+                        &Default::default(),
+                    )
                     .await?
                     .unwrap()
                     .job_id;
                 let stored_aggregated_job_id = ProverSchema(&mut storage)
-                    .get_idle_prover_job_from_job_queue()
+                    .get_idle_prover_job_from_job_queue(
+                        "test_prover",
+                        &Default::default(),
+                        std::time::Duration::from_secs(60),
+                        &Default::default(),
+                    )
                     .await?
                     .unwrap()
                     .job_id;
@@ -585,6 +605,8 @@ impl TestServerConfig {
                         Some((id, op)),
                         100,
                         100u32.into(),
+                        None,
+                        None,
                         Default::default(),
                     )
                     .await?;
@@ -626,6 +648,8 @@ impl TestServerConfig {
                         Some((id, op)),
                         100,
                         100u32.into(),
+                        None,
+                        None,
                         Default::default(),
                     )
                     .await?;
@@ -854,6 +878,10 @@ impl FeeTickerInfo for DummyFeeTickerInfo {
         Ok(None)
     }
 
+    async fn mempool_size(&self) -> anyhow::Result<u32> {
+        Ok(0)
+    }
+
     async fn get_last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError> {
         if let Some(price) = self.prices.get(&token) {
             Ok(TokenPrice {
@@ -912,6 +940,22 @@ pub fn get_test_ticker_config() -> TickerConfig {
         scale_fee_coefficient: Ratio::new(BigUint::from(150u32), BigUint::from(100u32)),
         max_blocks_to_aggregate: 5,
         subsidy_cpk_price_usd: scaled_u64_to_ratio(SUBSIDY_CPK_PRICE_USD_SCALED),
+        subsidy_rules: Vec::new(),
+        congestion: CongestionConfig {
+            mempool_threshold: 0,
+            max_multiplier_percent: 100,
+            decay_percent: 0,
+            update_interval: std::time::Duration::from_secs(0),
+        },
+        batch_discount: BatchDiscountConfig {
+            min_txs: 0,
+            overhead_discount_percent: 0,
+        },
+        gas_price: GasPriceConfig {
+            smoothing_percent: 100,
+            max_deviation_percent: 0,
+            update_interval: std::time::Duration::from_secs(0),
+        },
     }
 }
 pub fn dummy_fee_ticker(
@@ -7,21 +7,27 @@ use std::time::Instant;
 // External uses
 use actix_web::{
     web::{self},
-    Scope,
+    FromRequest, Scope,
 };
+use async_trait::async_trait;
 use bigdecimal::{BigDecimal, Zero};
+use chrono::Utc;
 use num::{rational::Ratio, BigUint, FromPrimitive};
 
 // Workspace uses
 use zksync_api_types::v02::{
     pagination::{parse_query, ApiEither, Paginated, PaginationQuery},
-    token::{ApiNFT, ApiToken, TokenPrice},
+    token::{
+        ApiNFT, ApiToken, IncomingTokenPriceHistoryQuery, TokenPrice, TokenPriceHistory,
+        TokenPricePoint, DEFAULT_PRICE_HISTORY_RESOLUTION_SECS, MAX_PRICE_HISTORY_POINTS,
+    },
 };
 use zksync_config::ZkSyncConfig;
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
-use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_storage::{tokens::STORED_USD_PRICE_PRECISION, ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{tx::TxHash, AccountId, Token, TokenId, TokenLike};
+use zksync_utils::ratio_to_big_decimal;
 
 // Local uses
 use super::{
@@ -31,7 +37,7 @@ use super::{
 };
 use crate::{
     api_try,
-    fee_ticker::{FeeTicker, PriceError, TokenPriceRequestType},
+    fee_ticker::{FeeTicker, TokenPriceRequestType},
 };
 
 /// Shared data between `api/v0.2/tokens` endpoints.
@@ -126,18 +132,10 @@ impl ApiTokenData {
         // from the db.
         let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
 
-        let token = self
-            .tokens
-            .get_token(&mut storage, token_like)
+        self.tokens
+            .resolve_token(&mut storage, token_like)
             .await
-            .map_err(Error::storage)?;
-        if let Some(token) = token {
-            Ok(token)
-        } else {
-            Err(Error::from(PriceError::token_not_found(
-                "Token not found in storage",
-            )))
-        }
+            .map_err(Error::from)
     }
 
     async fn api_token(&self, token_like: TokenLike) -> Result<ApiToken, Error> {
@@ -186,6 +184,48 @@ impl ApiTokenData {
             }
         }
     }
+
+    async fn token_price_history(
+        &self,
+        token_like: TokenLike,
+        query: IncomingTokenPriceHistoryQuery,
+    ) -> Result<TokenPriceHistory, Error> {
+        let to = query.to.unwrap_or_else(Utc::now);
+        let from = query.from.unwrap_or_else(|| to - chrono::Duration::days(1));
+        let resolution = query
+            .resolution
+            .unwrap_or(DEFAULT_PRICE_HISTORY_RESOLUTION_SECS);
+        let range_secs = (to - from).num_seconds();
+        if resolution == 0
+            || range_secs <= 0
+            || range_secs as u64 / resolution > MAX_PRICE_HISTORY_POINTS
+        {
+            return Err(Error::from(InvalidDataError::InvalidPriceHistoryRange));
+        }
+
+        let token = self.token(token_like).await?;
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let prices = storage
+            .tokens_schema()
+            .load_ticker_price_history(token.id, from, to, resolution)
+            .await
+            .map_err(Error::storage)?
+            .into_iter()
+            .map(|price| TokenPricePoint {
+                timestamp: price.last_updated,
+                usd_price: ratio_to_big_decimal(&price.usd_price, STORED_USD_PRICE_PRECISION),
+            })
+            .collect();
+
+        Ok(TokenPriceHistory {
+            token_id: token.id,
+            token_symbol: token.symbol,
+            from,
+            to,
+            resolution,
+            prices,
+        })
+    }
 }
 
 // Server implementation
@@ -201,13 +241,27 @@ async fn token_pagination(
     res
 }
 
-async fn token_info(
-    data: web::Data<ApiTokenData>,
+/// Source of the tokens, so the handlers serve both the stored tokens
+/// and the synthetic ones of the sandbox.
+#[async_trait(?Send)]
+pub(super) trait TokenSource: FromRequest + 'static {
+    async fn find_token(&self, token_like: TokenLike) -> Result<ApiToken, Error>;
+}
+
+#[async_trait(?Send)]
+impl TokenSource for web::Data<ApiTokenData> {
+    async fn find_token(&self, token_like: TokenLike) -> Result<ApiToken, Error> {
+        self.api_token(token_like).await
+    }
+}
+
+pub(super) async fn token_info<D: TokenSource>(
+    data: D,
     token_like_string: web::Path<String>,
 ) -> ApiResult<ApiToken> {
     let start = Instant::now();
     let token_like = TokenLike::parse(&token_like_string);
-    let res = data.api_token(token_like).await.into();
+    let res = data.find_token(token_like).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_info");
     res
 }
@@ -235,6 +289,18 @@ async fn token_price(
     })
 }
 
+async fn token_price_history(
+    data: web::Data<ApiTokenData>,
+    token_like_string: web::Path<String>,
+    web::Query(query): web::Query<IncomingTokenPriceHistoryQuery>,
+) -> ApiResult<TokenPriceHistory> {
+    let start = Instant::now();
+    let token_like = TokenLike::parse(&token_like_string);
+    let res = data.token_price_history(token_like, query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_price_history");
+    res
+}
+
 async fn get_nft(
     data: web::Data<ApiTokenData>,
     id: web::Path<TokenId>,
@@ -299,11 +365,18 @@ pub fn api_scope(
     web::scope("tokens")
         .app_data(web::Data::new(data))
         .route("", web::get().to(token_pagination))
-        .route("{token_like}", web::get().to(token_info))
+        .route(
+            "{token_like}",
+            web::get().to(token_info::<web::Data<ApiTokenData>>),
+        )
         .route(
             "{token_like}/priceIn/{currency}",
             web::get().to(token_price),
         )
+        .route(
+            "{token_like}/price/history",
+            web::get().to(token_price_history),
+        )
         .route("nft/{id}", web::get().to(get_nft))
         .route("nft/{id}/owner", web::get().to(get_nft_owner))
         .route(
@@ -316,6 +389,7 @@ pub fn api_scope(
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
+        error::ErrorCode,
         test_utils::{deserialize_response_result, dummy_fee_ticker, TestServerConfig},
         SharedData,
     };
@@ -456,6 +530,42 @@ mod tests {
         let response = client.token_price(&token_like, "333").await?;
         assert!(response.error.is_some());
 
+        let price_updated_at = Utc::now() - chrono::Duration::minutes(10);
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .tokens_schema()
+                .store_ticker_price_history(
+                    token.id,
+                    &zksync_types::TokenPrice {
+                        usd_price: Ratio::from_integer(10u32.into()),
+                        last_updated: price_updated_at,
+                    },
+                )
+                .await?;
+        }
+        let query = IncomingTokenPriceHistoryQuery {
+            from: Some(price_updated_at - chrono::Duration::minutes(1)),
+            to: Some(price_updated_at + chrono::Duration::minutes(1)),
+            resolution: Some(1),
+        };
+        let response = client.token_price_history(&token_like, &query).await?;
+        let history: TokenPriceHistory = deserialize_response_result(response)?;
+        assert_eq!(history.token_id, token.id);
+        assert_eq!(history.resolution, 1);
+        assert_eq!(
+            history.prices.last().map(|price| price.usd_price.clone()),
+            Some(BigDecimal::from_u32(10).unwrap())
+        );
+
+        let query = IncomingTokenPriceHistoryQuery {
+            resolution: Some(0),
+            ..Default::default()
+        };
+        let response = client.token_price_history(&token_like, &query).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidPriceHistoryRange);
+
         let nft_id = TokenId(65542);
         let response = client.nft_by_id(nft_id).await?;
         let nft: ApiNFT = deserialize_response_result(response)?;
@@ -5,33 +5,58 @@ use std::time::Instant;
 // External uses
 use actix_web::{
     web::{self, Json},
-    Scope,
+    FromRequest, Scope,
 };
+use async_trait::async_trait;
 
 // Workspace uses
 use zksync_api_types::{
     v02::transaction::{
-        ApiTxBatch, IncomingTxBatch, L1Receipt, L1Transaction, Receipt, SubmitBatchResponse,
+        ArchivedTx, BatchInfo, IncomingTxBatch, L1Receipt, L1Transaction, L1TxPriorityOp,
+        L2Receipt, Receipt, SubmissionMode, SubmissionStatus, SubmitBatchResponse, SubmitTxQuery,
         Toggle2FA, Toggle2FAResponse, Transaction, TransactionData, TxData, TxHashSerializeWrapper,
         TxInBlockStatus,
     },
     TxWithSignature,
 };
-use zksync_types::{tx::TxHash, EthBlockId};
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    tx::{error::TxAddError, TxHash},
+    BlockNumber, EthBlockId, H256,
+};
 
 // Local uses
-use super::{error::Error, response::ApiResult};
+use super::{archive::ArchiveClient, error::Error, mempool::MempoolRedaction, response::ApiResult};
 use crate::api_server::tx_sender::{SubmitError, TxSender};
 
 /// Shared data between `api/v0.2/transactions` endpoints.
 #[derive(Clone)]
 struct ApiTransactionData {
     tx_sender: TxSender,
+    /// Cold storage with the pruned transactions, `None` if disabled.
+    archive: Option<ArchiveClient>,
+    /// Redaction rules of the public mempool feed applied to the queued transactions.
+    redaction: MempoolRedaction,
 }
 
 impl ApiTransactionData {
-    fn new(tx_sender: TxSender) -> Self {
-        Self { tx_sender }
+    fn new(
+        tx_sender: TxSender,
+        archive: Option<ArchiveClient>,
+        redaction: MempoolRedaction,
+    ) -> Self {
+        Self {
+            tx_sender,
+            archive,
+            redaction,
+        }
+    }
+
+    async fn load_archived_tx(&self, tx_hash: TxHash) -> Option<ArchivedTx> {
+        match &self.archive {
+            Some(archive) => archive.load_tx(tx_hash).await,
+            None => None,
+        }
     }
 
     async fn tx_status(&self, tx_hash: TxHash) -> Result<Option<Receipt>, Error> {
@@ -66,11 +91,29 @@ impl ApiTransactionData {
                 eth_block: EthBlockId(op.eth_block),
                 rollup_block: None,
                 id: op.serial_id,
+                block_index: None,
+            })))
+        }
+        // 3. Check whether the transaction was evicted from the mempool.
+        else if let Some(reason) = storage
+            .chain()
+            .mempool_schema()
+            .get_eviction_reason(tx_hash)
+            .await
+            .map_err(Error::storage)?
+        {
+            Ok(Some(Receipt::L2(L2Receipt {
+                tx_hash,
+                rollup_block: None,
+                status: TxInBlockStatus::Rejected,
+                fail_reason: Some(reason),
+                fail_code: None,
+                block_index: None,
             })))
         }
-        // 3. No operation found, return nothing.
+        // 4. Try to find the transaction pruned from the DB in the archive.
         else {
-            Ok(None)
+            Ok(self.load_archived_tx(tx_hash).await.map(|tx| tx.receipt))
         }
     }
 
@@ -81,13 +124,14 @@ impl ApiTransactionData {
             .access_storage()
             .await
             .map_err(Error::storage)?;
-        if let Some(data) = storage
+        if let Some(mut data) = storage
             .chain()
             .operations_ext_schema()
             .tx_data_api_v02(tx_hash.as_ref())
             .await
             .map_err(Error::storage)?
         {
+            self.redaction.redact_queued_tx_data(&mut data);
             Ok(Some(data))
         } else if let Some(op) = storage
             .chain()
@@ -118,34 +162,206 @@ impl ApiTransactionData {
                 eth_signature: None,
             }))
         } else {
-            Ok(None)
+            Ok(self.load_archived_tx(tx_hash).await.map(|tx| tx.data))
         }
     }
 
-    async fn get_batch(&self, batch_hash: TxHash) -> Result<Option<ApiTxBatch>, Error> {
+    /// Loads the priority operations created by the L1 transaction, both executed and pending.
+    async fn priority_ops_by_eth_hash(&self, eth_hash: H256) -> Result<Vec<L1TxPriorityOp>, Error> {
         let mut storage = self
             .tx_sender
             .pool
             .access_storage()
             .await
             .map_err(Error::storage)?;
-        storage
+
+        let executed_ops = storage
+            .chain()
+            .operations_schema()
+            .get_executed_priority_operations_by_eth_hash(eth_hash.as_bytes())
+            .await
+            .map_err(Error::storage)?;
+        let mut ops = Vec::with_capacity(executed_ops.len());
+        for op in executed_ops {
+            let tx_hash = TxHash::from_slice(&op.tx_hash)
+                .ok_or_else(|| Error::storage("Incorrect tx hash stored in the database"))?;
+            // The receipt takes the status of the block into account.
+            let receipt = storage
+                .chain()
+                .operations_ext_schema()
+                .tx_receipt_api_v02(tx_hash.as_ref())
+                .await
+                .map_err(Error::storage)?;
+            if let Some(Receipt::L1(receipt)) = receipt {
+                ops.push(L1TxPriorityOp { tx_hash, receipt });
+            }
+        }
+
+        // Priority operations are removed from the mempool once they are executed.
+        let pending_ops = storage
+            .chain()
+            .mempool_schema()
+            .get_pending_operations_by_eth_hash(eth_hash)
+            .await
+            .map_err(Error::storage)?;
+        ops.extend(pending_ops.into_iter().map(|op| L1TxPriorityOp {
+            tx_hash: op.tx_hash(),
+            receipt: L1Receipt {
+                status: TxInBlockStatus::Queued,
+                eth_block: EthBlockId(op.eth_block),
+                rollup_block: None,
+                id: op.serial_id,
+                block_index: None,
+            },
+        }));
+        ops.sort_by_key(|op| op.receipt.id);
+        ops.dedup_by_key(|op| op.receipt.id);
+
+        Ok(ops)
+    }
+
+    async fn batch_tx_receipt(
+        storage: &mut StorageProcessor<'_>,
+        tx_hash: TxHash,
+    ) -> Result<L2Receipt, Error> {
+        if let Some(Receipt::L2(receipt)) = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt_api_v02(tx_hash.as_ref())
+            .await
+            .map_err(Error::storage)?
+        {
+            return Ok(receipt);
+        }
+
+        let fail_reason = storage
+            .chain()
+            .mempool_schema()
+            .get_eviction_reason(tx_hash)
+            .await
+            .map_err(Error::storage)?;
+        let status = if fail_reason.is_some() {
+            TxInBlockStatus::Rejected
+        } else {
+            TxInBlockStatus::Queued
+        };
+        Ok(L2Receipt {
+            tx_hash,
+            rollup_block: None,
+            status,
+            fail_reason,
+            fail_code: None,
+            block_index: None,
+        })
+    }
+
+    async fn get_batch(&self, batch_hash: TxHash) -> Result<Option<BatchInfo>, Error> {
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?;
+        let mut batch = match storage
             .chain()
             .operations_ext_schema()
             .get_batch_info(batch_hash)
             .await
-            .map_err(Error::storage)
+            .map_err(Error::storage)?
+        {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+
+        let mut receipts = Vec::with_capacity(batch.transaction_hashes.len());
+        for TxHashSerializeWrapper(tx_hash) in &batch.transaction_hashes {
+            receipts.push(Self::batch_tx_receipt(&mut storage, *tx_hash).await?);
+        }
+        // The batch fails as a whole, even if only one of its transactions is rejected.
+        if receipts
+            .iter()
+            .any(|receipt| receipt.status == TxInBlockStatus::Rejected)
+        {
+            batch.batch_status.last_state = TxInBlockStatus::Rejected;
+        }
+
+        Ok(Some(BatchInfo { batch, receipts }))
+    }
+
+    async fn submission_status(&self, tx_hash: TxHash) -> Result<SubmissionStatus, Error> {
+        let interrupted = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?
+            .chain()
+            .mempool_schema()
+            .get_interrupted_submission(tx_hash)
+            .await
+            .map_err(Error::storage)?;
+        // The transaction accepted by the mempool is stored in the DB,
+        // so it doesn't have a receipt only if it was lost or rejected before that.
+        let receipt = self.tx_status(tx_hash).await?;
+
+        Ok(SubmissionStatus {
+            tx_hash,
+            interrupted,
+            should_resubmit: receipt.is_none(),
+            receipt,
+        })
+    }
+}
+
+/// Destination of the submitted transactions, so the handlers serve both the mempool
+/// and the simulated processing of the sandbox.
+#[async_trait(?Send)]
+pub(super) trait TransactionSource: FromRequest + 'static {
+    async fn submit(
+        &self,
+        tx: TxWithSignature,
+        mode: SubmissionMode,
+        not_before_block: Option<BlockNumber>,
+    ) -> Result<TxHash, SubmitError>;
+
+    async fn receipt(&self, tx_hash: TxHash) -> Result<Option<Receipt>, Error>;
+}
+
+#[async_trait(?Send)]
+impl TransactionSource for web::Data<ApiTransactionData> {
+    async fn submit(
+        &self,
+        tx: TxWithSignature,
+        mode: SubmissionMode,
+        not_before_block: Option<BlockNumber>,
+    ) -> Result<TxHash, SubmitError> {
+        match mode {
+            SubmissionMode::Sync => {
+                self.tx_sender
+                    .submit_scheduled_tx(tx.tx, tx.signature, None, not_before_block)
+                    .await
+            }
+            SubmissionMode::Async => {
+                self.tx_sender
+                    .submit_tx_async(tx.tx, tx.signature, None, not_before_block)
+                    .await
+            }
+        }
+    }
+
+    async fn receipt(&self, tx_hash: TxHash) -> Result<Option<Receipt>, Error> {
+        self.tx_status(tx_hash).await
     }
 }
 
 // Server implementation
 
-async fn tx_status(
-    data: web::Data<ApiTransactionData>,
+pub(super) async fn tx_status<D: TransactionSource>(
+    data: D,
     tx_hash: web::Path<TxHash>,
 ) -> ApiResult<Option<Receipt>> {
     let start = Instant::now();
-    let res = data.tx_status(*tx_hash).await.into();
+    let res = data.receipt(*tx_hash).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_status");
     res
 }
@@ -160,15 +376,24 @@ async fn tx_data(
     res
 }
 
-async fn submit_tx(
+async fn priority_ops_by_eth_hash(
     data: web::Data<ApiTransactionData>,
+    eth_tx_hash: web::Path<H256>,
+) -> ApiResult<Vec<L1TxPriorityOp>> {
+    let start = Instant::now();
+    let res = data.priority_ops_by_eth_hash(*eth_tx_hash).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "priority_ops_by_eth_hash");
+    res
+}
+
+pub(super) async fn submit_tx<D: TransactionSource>(
+    data: D,
+    web::Query(query): web::Query<SubmitTxQuery>,
     Json(body): Json<TxWithSignature>,
 ) -> ApiResult<TxHashSerializeWrapper> {
     let start = Instant::now();
-    let tx_hash = data
-        .tx_sender
-        .submit_tx(body.tx, body.signature, None)
-        .await;
+    let not_before_block = query.not_before_block.map(BlockNumber);
+    let tx_hash = data.submit(body, query.mode, not_before_block).await;
 
     if let Err(err) = &tx_hash {
         let err_label = match err {
@@ -199,6 +424,7 @@ async fn submit_batch(
         let err_label = match err {
             SubmitError::IncorrectTx(err) => err.clone(),
             SubmitError::TxAdd(err) => err.to_string(),
+            SubmitError::BatchSigners(_) => TxAddError::IncorrectEthSignature.to_string(),
             _ => "other".to_string(),
         };
         let labels = vec![("stage", "api".to_string()), ("error", err_label)];
@@ -228,21 +454,46 @@ async fn toggle_2fa(
 async fn get_batch(
     data: web::Data<ApiTransactionData>,
     batch_hash: web::Path<TxHash>,
-) -> ApiResult<Option<ApiTxBatch>> {
+) -> ApiResult<Option<BatchInfo>> {
     let start = Instant::now();
     let res = data.get_batch(*batch_hash).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_batch");
     res
 }
 
-pub fn api_scope(tx_sender: TxSender) -> Scope {
-    let data = ApiTransactionData::new(tx_sender);
+async fn submission_status(
+    data: web::Data<ApiTransactionData>,
+    tx_hash: web::Path<TxHash>,
+) -> ApiResult<SubmissionStatus> {
+    let start = Instant::now();
+    let res = data.submission_status(*tx_hash).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "submission_status");
+    res
+}
+
+pub fn api_scope(
+    tx_sender: TxSender,
+    archive: Option<ArchiveClient>,
+    redaction: MempoolRedaction,
+) -> Scope {
+    let data = ApiTransactionData::new(tx_sender, archive, redaction);
 
     web::scope("transactions")
         .app_data(web::Data::new(data))
-        .route("", web::post().to(submit_tx))
-        .route("{tx_hash}", web::get().to(tx_status))
+        .route(
+            "",
+            web::post().to(submit_tx::<web::Data<ApiTransactionData>>),
+        )
+        .route(
+            "{tx_hash}",
+            web::get().to(tx_status::<web::Data<ApiTransactionData>>),
+        )
         .route("{tx_hash}/data", web::get().to(tx_data))
+        .route("{tx_hash}/submission", web::get().to(submission_status))
+        .route(
+            "/eth/{eth_tx_hash}",
+            web::get().to(priority_ops_by_eth_hash),
+        )
         .route("/batches", web::post().to(submit_batch))
         .route("/batches/{batch_hash}", web::get().to(get_batch))
         .route("/toggle2FA", web::post().to(toggle_2fa))
@@ -254,7 +505,7 @@ mod tests {
     use crate::api_server::rest::v02::{
         test_utils::{
             deserialize_response_result, dummy_fee_ticker, dummy_sign_verifier, TestServerConfig,
-            TestTransactions,
+            TestTransactions, VERIFIED_OP_SERIAL_ID,
         },
         SharedData,
     };
@@ -266,17 +517,18 @@ mod tests {
     use std::str::FromStr;
     use tokio::task::JoinHandle;
     use zksync_api_types::v02::{
-        transaction::{L2Receipt, TxHashSerializeWrapper},
+        transaction::{L2Receipt, SubmissionStage, TxHashSerializeWrapper},
         ApiVersion,
     };
     use zksync_mempool::MempoolTransactionRequest;
+    use zksync_storage::test_data::dummy_ethereum_tx_hash;
     use zksync_types::{
         tokens::{Token, TokenMarketVolume},
         tx::{
             EthBatchSignData, EthBatchSignatures, PackedEthSignature, TxEthSignature,
             TxEthSignatureVariant,
         },
-        Address, BlockNumber, SignedZkSyncTx, TokenId, TokenKind, TokenLike,
+        Address, BlockNumber, SignedZkSyncTx, TokenId, TokenKind, TokenLike, ZkSyncTx,
     };
 
     fn submit_txs_loopback() -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
@@ -285,7 +537,7 @@ mod tests {
         let task = tokio::spawn(async move {
             while let Some(tx) = mempool_tx_request_receiver.next().await {
                 match tx {
-                    MempoolTransactionRequest::NewTx(_, resp) => {
+                    MempoolTransactionRequest::NewTx(_, _, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
                     MempoolTransactionRequest::NewPriorityOps(_, _, resp) => {
@@ -294,6 +546,10 @@ mod tests {
                     MempoolTransactionRequest::NewTxsBatch(_, _, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
+                    MempoolTransactionRequest::RevertUnconfirmedPriorityOps(_, resp) => {
+                        resp.send(Ok(Vec::new())).unwrap_or_default()
+                    }
+                    MempoolTransactionRequest::Ping(resp) => resp.send(()).unwrap_or_default(),
                 }
             }
         });
@@ -328,6 +584,7 @@ mod tests {
             TokenMarketVolume {
                 market_volume: Ratio::from_integer(BigUint::from(400u32)),
                 last_updated: Utc::now(),
+                fee_allowed: None,
             },
         );
         let cache = TokenInMemoryCache::new()
@@ -346,14 +603,18 @@ mod tests {
 
         let (client, server) = cfg.start_server(
             move |cfg: &TestServerConfig| {
-                api_scope(TxSender::new(
-                    cfg.pool.clone(),
-                    dummy_sign_verifier(),
-                    dummy_fee_ticker(&prices, Some(cache.clone())),
-                    &cfg.config.api.common,
-                    &cfg.config.api.token_config,
-                    sender.clone(),
-                ))
+                api_scope(
+                    TxSender::new(
+                        cfg.pool.clone(),
+                        dummy_sign_verifier(),
+                        dummy_fee_ticker(&prices, Some(cache.clone())),
+                        &cfg.config.api.common,
+                        &cfg.config.api.token_config,
+                        sender.clone(),
+                    ),
+                    None,
+                    MempoolRedaction::default(),
+                )
             },
             Some(shared_data),
         );
@@ -365,6 +626,42 @@ mod tests {
         let tx_hash: TxHash = deserialize_response_result(response)?;
         assert_eq!(tx.hash(), tx_hash);
 
+        let response = client
+            .submit_tx_async(tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let tx_hash: TxHash = deserialize_response_result(response)?;
+        assert_eq!(tx.hash(), tx_hash);
+
+        // The transaction paying the fee in the unknown token is rejected in the background.
+        let mut rejected_tx = tx.clone();
+        if let ZkSyncTx::ChangePubKey(change_pubkey) = &mut rejected_tx {
+            change_pubkey.fee_token = TokenId(1000);
+        }
+        let response = client
+            .submit_tx_async(rejected_tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let rejected_tx_hash: TxHash = deserialize_response_result(response)?;
+        assert_eq!(rejected_tx.hash(), rejected_tx_hash);
+        let mut receipt = None;
+        for _ in 0..50 {
+            let response = client.tx_status(rejected_tx_hash).await?;
+            receipt = deserialize_response_result(response)?;
+            if receipt.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        match receipt {
+            Some(Receipt::L2(receipt)) => {
+                assert_eq!(receipt.status, TxInBlockStatus::Rejected);
+                assert!(receipt.fail_reason.is_some());
+            }
+            other => panic!(
+                "Unexpected receipt of the rejected transaction: {:?}",
+                other
+            ),
+        }
+
         let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
         let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
         let (good_batch, expected_tx_hashes): (Vec<_>, Vec<_>) = txs
@@ -429,15 +726,29 @@ mod tests {
         };
 
         let response = client.get_batch(submit_batch_response.batch_hash).await?;
-        let batch: ApiTxBatch = deserialize_response_result(response)?;
-        assert_eq!(batch.batch_hash, submit_batch_response.batch_hash);
+        let info: BatchInfo = deserialize_response_result(response)?;
+        assert_eq!(info.batch.batch_hash, submit_batch_response.batch_hash);
         assert_eq!(
-            batch.transaction_hashes,
+            info.batch.transaction_hashes,
             submit_batch_response.transaction_hashes
         );
-        assert_eq!(batch.batch_status.last_state, TxInBlockStatus::Queued);
+        assert_eq!(info.batch.batch_status.last_state, TxInBlockStatus::Queued);
+        let receipt_hashes: Vec<_> = info
+            .receipts
+            .iter()
+            .map(|receipt| TxHashSerializeWrapper(receipt.tx_hash))
+            .collect();
+        assert_eq!(receipt_hashes, submit_batch_response.transaction_hashes);
+        assert!(info
+            .receipts
+            .iter()
+            .all(|receipt| receipt.status == TxInBlockStatus::Queued));
+
+        let response = client.get_batch(TxHash::batch_hash(&[])).await?;
+        let info: Option<BatchInfo> = deserialize_response_result(response)?;
+        assert!(info.is_none());
 
-        let tx_hash = {
+        let (tx_hash, block_index) = {
             let mut storage = cfg.pool.access_storage().await?;
 
             let transactions = storage
@@ -446,7 +757,10 @@ mod tests {
                 .get_block_transactions(BlockNumber(1))
                 .await?;
 
-            TxHash::from_str(&transactions[0].tx_hash).unwrap()
+            (
+                TxHash::from_str(&transactions[0].tx_hash).unwrap(),
+                transactions[0].block_index.map(|index| index as u32),
+            )
         };
         let response = client.tx_status(tx_hash).await?;
         let tx_status: Receipt = deserialize_response_result(response)?;
@@ -455,6 +769,8 @@ mod tests {
             rollup_block: Some(BlockNumber(1)),
             status: TxInBlockStatus::Finalized,
             fail_reason: None,
+            fail_code: None,
+            block_index,
         });
         assert_eq!(tx_status, expected_tx_status);
 
@@ -486,6 +802,8 @@ mod tests {
             rollup_block: None,
             status: TxInBlockStatus::Queued,
             fail_reason: None,
+            fail_code: None,
+            block_index: None,
         });
         assert_eq!(tx_status, expected_tx_status);
 
@@ -498,6 +816,42 @@ mod tests {
         let tx_data: Option<TxData> = deserialize_response_result(response)?;
         assert!(tx_data.is_none());
 
+        let response = client.tx_submission_status(pending_tx_hash).await?;
+        let status: SubmissionStatus = deserialize_response_result(response)?;
+        assert!(!status.should_resubmit);
+        assert!(status.interrupted.is_none());
+
+        // The transaction submission was interrupted before it reached the mempool.
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .store_interrupted_submissions(&[(tx.hash(), SubmissionStage::Verifying)])
+            .await?;
+        let response = client.tx_submission_status(tx.hash()).await?;
+        let status: SubmissionStatus = deserialize_response_result(response)?;
+        assert!(status.should_resubmit);
+        assert!(status.receipt.is_none());
+        assert_eq!(
+            status.interrupted.map(|submission| submission.stage),
+            Some(SubmissionStage::Verifying)
+        );
+
+        let eth_tx_hash = dummy_ethereum_tx_hash(VERIFIED_OP_SERIAL_ID as i64);
+        let response = client.priority_ops_by_eth_hash(eth_tx_hash).await?;
+        let ops: Vec<L1TxPriorityOp> = deserialize_response_result(response)?;
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].receipt.id, VERIFIED_OP_SERIAL_ID);
+        assert_eq!(ops[0].receipt.rollup_block, Some(BlockNumber(2)));
+        assert_eq!(ops[0].receipt.status, TxInBlockStatus::Finalized);
+
+        let response = client
+            .priority_ops_by_eth_hash(H256::repeat_byte(0xff))
+            .await?;
+        let ops: Vec<L1TxPriorityOp> = deserialize_response_result(response)?;
+        assert!(ops.is_empty());
+
         server.stop().await;
         task.abort();
         Ok(())
@@ -0,0 +1,230 @@
+//! Withdrawals part of API implementation.
+//!
+//! Reports the lifecycle of the withdrawal: the transaction is queued in the mempool (or in the
+//! priority queue for the full exits), included in the block, the block is verified, and finally
+//! the funds are released by the `executeBlocks` transaction once it's confirmed on L1.
+
+// Built-in uses
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+
+// Workspace uses
+use zksync_api_types::v02::{
+    transaction::{L1Transaction, L2Transaction, TransactionData, TxInBlockStatus},
+    withdrawal::{WithdrawalInfo, WithdrawalStage},
+};
+use zksync_storage::ConnectionPool;
+use zksync_types::{ethereum::WithdrawalStatus, tx::TxHash, ZkSyncPriorityOp, H256};
+
+// Local uses
+use super::{
+    error::{Error, InvalidDataError},
+    response::ApiResult,
+};
+
+/// Shared data between `api/v0.2/withdrawals` endpoints.
+#[derive(Clone)]
+struct ApiWithdrawalData {
+    pool: ConnectionPool,
+}
+
+impl ApiWithdrawalData {
+    fn new(pool: ConnectionPool) -> Self {
+        Self { pool }
+    }
+
+    async fn withdrawal(&self, tx_hash: TxHash) -> Result<Option<WithdrawalInfo>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+
+        let tx = match storage
+            .chain()
+            .operations_ext_schema()
+            .tx_data_api_v02(tx_hash.as_ref())
+            .await
+            .map_err(Error::storage)?
+        {
+            Some(data) => data.tx,
+            None => {
+                // Full exit may still wait in the priority queue.
+                let op = storage
+                    .chain()
+                    .mempool_schema()
+                    .get_pending_operation_by_hash(H256::from(tx_hash))
+                    .await
+                    .map_err(Error::storage)?;
+                return match op {
+                    Some(op) if matches!(op.data, ZkSyncPriorityOp::FullExit(_)) => {
+                        Ok(Some(WithdrawalInfo {
+                            tx_hash: op.tx_hash(),
+                            stage: WithdrawalStage::Queued,
+                            block_number: None,
+                            deferred: false,
+                            l1_tx_hash: None,
+                            fail_reason: None,
+                        }))
+                    }
+                    Some(_) => Err(Error::from(InvalidDataError::NotWithdrawal)),
+                    None => Ok(None),
+                };
+            }
+        };
+        if !is_withdrawal(&tx.op) {
+            return Err(Error::from(InvalidDataError::NotWithdrawal));
+        }
+
+        let mut info = WithdrawalInfo {
+            tx_hash: tx.tx_hash,
+            stage: WithdrawalStage::Queued,
+            block_number: tx.block_number,
+            deferred: false,
+            l1_tx_hash: None,
+            fail_reason: tx.fail_reason,
+        };
+        info.stage = match tx.status {
+            TxInBlockStatus::Queued => WithdrawalStage::Queued,
+            TxInBlockStatus::Rejected => WithdrawalStage::Rejected,
+            TxInBlockStatus::Pending | TxInBlockStatus::Committed => WithdrawalStage::Included,
+            TxInBlockStatus::Finalized => {
+                // The status is stored by `eth_sender` once the `executeBlocks` transaction
+                // is sent and updated when it's confirmed.
+                let status = storage
+                    .chain()
+                    .withdrawals_schema()
+                    .get_withdrawal_status(tx.tx_hash)
+                    .await
+                    .map_err(Error::storage)?;
+                match status {
+                    Some(status) => {
+                        info.l1_tx_hash = status.eth_tx_hash.as_deref().map(H256::from_slice);
+                        match status.status() {
+                            WithdrawalStatus::Scheduled => WithdrawalStage::Verified,
+                            WithdrawalStatus::Completed => WithdrawalStage::Released,
+                            WithdrawalStatus::Deferred => {
+                                info.deferred = true;
+                                WithdrawalStage::Verified
+                            }
+                        }
+                    }
+                    None => WithdrawalStage::Verified,
+                }
+            }
+        };
+        Ok(Some(info))
+    }
+}
+
+fn is_withdrawal(op: &TransactionData) -> bool {
+    matches!(
+        op,
+        TransactionData::L2(L2Transaction::Withdraw(_))
+            | TransactionData::L2(L2Transaction::ForcedExit(_))
+            | TransactionData::L2(L2Transaction::WithdrawNFT(_))
+            | TransactionData::L1(L1Transaction::FullExit(_))
+    )
+}
+
+// Server implementation
+
+async fn withdrawal(
+    data: web::Data<ApiWithdrawalData>,
+    tx_hash: web::Path<TxHash>,
+) -> ApiResult<Option<WithdrawalInfo>> {
+    let start = Instant::now();
+    let res = data.withdrawal(*tx_hash).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "withdrawal");
+    res
+}
+
+pub fn api_scope(pool: ConnectionPool) -> Scope {
+    let data = ApiWithdrawalData::new(pool);
+
+    web::scope("withdrawals")
+        .app_data(web::Data::new(data))
+        .route("{tx_hash}", web::get().to(withdrawal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        error::ErrorCode,
+        test_utils::{
+            deserialize_response_result, dummy_full_exit_op, TestServerConfig,
+            COMMITTED_OP_SERIAL_ID, VERIFIED_OP_SERIAL_ID,
+        },
+        SharedData,
+    };
+    use zksync_api_types::v02::ApiVersion;
+    use zksync_storage::test_data::dummy_ethereum_tx_hash;
+    use zksync_types::{AccountId, Address};
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn withdrawals_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| api_scope(cfg.pool.clone()),
+            Some(shared_data),
+        );
+
+        let queued_op = {
+            let mut priority_op =
+                dummy_full_exit_op(AccountId(3), Address::default(), 1000, 0).priority_op;
+            priority_op.eth_hash = H256::repeat_byte(0xef);
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_priority_ops(&[priority_op.clone()], true)
+                .await?;
+            priority_op
+        };
+
+        let response = client.withdrawal(queued_op.tx_hash()).await?;
+        let info: Option<WithdrawalInfo> = deserialize_response_result(response)?;
+        let info = info.expect("queued full exit should be found");
+        assert_eq!(info.stage, WithdrawalStage::Queued);
+        assert_eq!(info.block_number, None);
+
+        // Full exit is stored with its Ethereum hash as the transaction hash.
+        let full_exit_hash =
+            TxHash::from_slice(dummy_ethereum_tx_hash(COMMITTED_OP_SERIAL_ID as i64).as_bytes())
+                .unwrap();
+        let response = client.withdrawal(full_exit_hash).await?;
+        let info: Option<WithdrawalInfo> = deserialize_response_result(response)?;
+        let info = info.expect("executed full exit should be found");
+        assert!(info.block_number.is_some());
+        assert!(matches!(
+            info.stage,
+            WithdrawalStage::Included | WithdrawalStage::Verified
+        ));
+        assert_eq!(info.l1_tx_hash, None);
+
+        let deposit_hash =
+            TxHash::from_slice(dummy_ethereum_tx_hash(VERIFIED_OP_SERIAL_ID as i64).as_bytes())
+                .unwrap();
+        let response = client.withdrawal(deposit_hash).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::NotWithdrawal);
+
+        let response = client
+            .withdrawal(TxHash::from_slice(&[0xab; 32]).unwrap())
+            .await?;
+        let info: Option<WithdrawalInfo> = deserialize_response_result(response)?;
+        assert_eq!(info, None);
+
+        server.stop().await;
+        Ok(())
+    }
+}
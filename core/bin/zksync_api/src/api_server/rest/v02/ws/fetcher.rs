@@ -0,0 +1,156 @@
+// Built-in uses
+use std::{sync::Arc, time::Duration, time::Instant};
+// External uses
+use chrono::{DateTime, Utc};
+use tokio::{sync::broadcast, time};
+// Workspace uses
+use zksync_config::configs::api::MempoolFeedConfig;
+use zksync_storage::{ConnectionPool, QueryResult};
+use zksync_types::event::EventData;
+// Local uses
+use super::{PendingTxs, TxEvents};
+use crate::api_server::rest::v02::{event_cursor::EventCursor, mempool::MempoolRedaction};
+
+/// Polls the `events` table and broadcasts new transaction events to every
+/// connected WebSocket session.
+pub(super) struct TxEventsFetcher {
+    db_pool: ConnectionPool,
+    poll_interval: Duration,
+    cursor: EventCursor,
+    sender: broadcast::Sender<TxEvents>,
+}
+
+impl TxEventsFetcher {
+    pub fn new(
+        db_pool: ConnectionPool,
+        poll_interval: Duration,
+        sender: broadcast::Sender<TxEvents>,
+    ) -> Self {
+        Self {
+            db_pool,
+            poll_interval,
+            cursor: EventCursor::default(),
+            sender,
+        }
+    }
+
+    async fn fetch_new_events(&mut self) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut storage = self.db_pool.access_storage().await?;
+        let events = self.cursor.next_events(&mut storage).await?;
+        let last_event_id = match events.last() {
+            Some(event) => event.id,
+            None => return Ok(()),
+        };
+
+        let tx_events: Vec<_> = events
+            .into_iter()
+            .filter_map(|event| match event.data {
+                EventData::Transaction(tx_event) => Some(tx_event),
+                _ => None,
+            })
+            .collect();
+        // The error only means that there are no connected sessions at the moment.
+        if !tx_events.is_empty() {
+            self.sender.send(Arc::new(tx_events)).unwrap_or_default();
+        }
+        self.cursor.advance(last_event_id);
+
+        metrics::histogram!("api.v02.ws.fetch_new_events", start.elapsed());
+        Ok(())
+    }
+
+    pub async fn run(mut self) {
+        let mut timer = time::interval(self.poll_interval);
+        loop {
+            timer.tick().await;
+            if let Err(err) = self.fetch_new_events().await {
+                vlog::warn!("Unable to fetch new transaction events: {}", err);
+            }
+        }
+    }
+}
+
+/// Polls the mempool and broadcasts the transactions that became old enough to be exposed
+/// by the public mempool feed.
+///
+/// Transactions are exposed in the order of their creation. Transactions that become visible
+/// within a single poll interval are loaded and broadcasted by pages of `max_txs`.
+pub(super) struct PendingTxsFetcher {
+    db_pool: ConnectionPool,
+    poll_interval: Duration,
+    config: MempoolFeedConfig,
+    redaction: MempoolRedaction,
+    /// Transactions created before this moment are already broadcasted.
+    last_exposed_at: Option<DateTime<Utc>>,
+    sender: broadcast::Sender<PendingTxs>,
+}
+
+impl PendingTxsFetcher {
+    pub fn new(
+        db_pool: ConnectionPool,
+        poll_interval: Duration,
+        config: MempoolFeedConfig,
+        sender: broadcast::Sender<PendingTxs>,
+    ) -> Self {
+        let redaction = MempoolRedaction::new(&config);
+        Self {
+            db_pool,
+            poll_interval,
+            config,
+            redaction,
+            last_exposed_at: None,
+            sender,
+        }
+    }
+
+    async fn fetch_pending_txs(&mut self) -> QueryResult<()> {
+        let start = Instant::now();
+        let exposed_at = Utc::now() - chrono::Duration::from_std(self.config.min_age())?;
+
+        let last_exposed_at = match self.last_exposed_at.replace(exposed_at) {
+            Some(last_exposed_at) => last_exposed_at,
+            // Only the transactions that become visible after the start are interesting.
+            None => return Ok(()),
+        };
+
+        let mut storage = self.db_pool.access_storage().await?;
+        let mut last_id = None;
+        loop {
+            let txs = storage
+                .chain()
+                .mempool_schema()
+                .load_pending_txs_page(last_exposed_at, exposed_at, last_id, self.config.max_txs)
+                .await?;
+            let (id, _) = match txs.last() {
+                Some(last) => last,
+                None => break,
+            };
+            last_id = Some(*id);
+
+            let is_last_page = txs.len() < self.config.max_txs as usize;
+            let pending_txs = txs
+                .iter()
+                .map(|(_, tx)| self.redaction.pending_tx(tx))
+                .collect();
+            // The error only means that there are no connected sessions at the moment.
+            self.sender.send(Arc::new(pending_txs)).unwrap_or_default();
+            if is_last_page {
+                break;
+            }
+        }
+
+        metrics::histogram!("api.v02.ws.fetch_pending_txs", start.elapsed());
+        Ok(())
+    }
+
+    pub async fn run(mut self) {
+        let mut timer = time::interval(self.poll_interval);
+        loop {
+            timer.tick().await;
+            if let Err(err) = self.fetch_pending_txs().await {
+                vlog::warn!("Unable to fetch pending transactions: {}", err);
+            }
+        }
+    }
+}
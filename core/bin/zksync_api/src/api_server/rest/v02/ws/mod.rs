@@ -0,0 +1,217 @@
+//! WebSocket part of API implementation.
+//!
+//! Clients subscribe for the status updates of transactions instead of polling
+//! the receipt endpoints. Message format is described in `zksync_api_types::v02::ws`.
+//! If enabled, the public mempool feed is available via the `pendingTxs` subscription.
+//! The private topics of the accounts may require the client to authenticate first.
+
+// Built-in uses
+use std::{sync::Arc, time::Duration};
+// External uses
+use actix_web::{web, Error, HttpRequest, HttpResponse, Resource};
+use actix_web_actors::ws;
+use tokio::sync::broadcast;
+// Workspace uses
+use zksync_api_types::v02::mempool::PendingTx;
+use zksync_config::configs::api::MempoolFeedConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::event::transaction::TransactionEvent;
+// Local uses
+use self::{
+    fetcher::{PendingTxsFetcher, TxEventsFetcher},
+    session::WsSession,
+};
+
+mod fetcher;
+mod session;
+
+/// Capacity of the channel between the fetcher and the sessions, in batches of events.
+const TX_EVENTS_CHANNEL_CAPACITY: usize = 1024;
+
+type TxEvents = Arc<Vec<TransactionEvent>>;
+type PendingTxs = Arc<Vec<PendingTx>>;
+
+/// Shared data of the `/ws/v02` endpoint.
+#[derive(Clone)]
+pub struct ApiWsData {
+    db_pool: ConnectionPool,
+    tx_events: broadcast::Sender<TxEvents>,
+    /// `None` if the public mempool feed is disabled.
+    pending_txs: Option<broadcast::Sender<PendingTxs>>,
+    /// Whether the private topics require the authentication of the address.
+    private_topics_auth: bool,
+}
+
+impl ApiWsData {
+    /// Spawns the fetchers shared by all the sessions of the server.
+    pub fn new(
+        db_pool: ConnectionPool,
+        poll_interval: Duration,
+        mempool_feed: MempoolFeedConfig,
+        private_topics_auth: bool,
+    ) -> Self {
+        let (tx_events, _) = broadcast::channel(TX_EVENTS_CHANNEL_CAPACITY);
+        let fetcher = TxEventsFetcher::new(db_pool.clone(), poll_interval, tx_events.clone());
+        tokio::spawn(fetcher.run());
+
+        let pending_txs = if mempool_feed.enabled {
+            let (pending_txs, _) = broadcast::channel(TX_EVENTS_CHANNEL_CAPACITY);
+            let fetcher = PendingTxsFetcher::new(
+                db_pool.clone(),
+                poll_interval,
+                mempool_feed,
+                pending_txs.clone(),
+            );
+            tokio::spawn(fetcher.run());
+            Some(pending_txs)
+        } else {
+            None
+        };
+
+        Self {
+            db_pool,
+            tx_events,
+            pending_txs,
+            private_topics_auth,
+        }
+    }
+}
+
+async fn ws_index(
+    data: web::Data<ApiWsData>,
+    req: HttpRequest,
+    stream: web::Payload,
+) -> Result<HttpResponse, Error> {
+    metrics::increment_counter!("api.v02.ws.connections");
+    let session = WsSession::new(
+        data.db_pool.clone(),
+        data.tx_events.clone(),
+        data.pending_txs.clone(),
+        data.private_topics_auth,
+    );
+    ws::start(session, &req, stream)
+}
+
+pub fn api_resource(data: ApiWsData) -> Resource {
+    web::resource("/ws/v02")
+        .app_data(web::Data::new(data))
+        .route(web::get().to(ws_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::App;
+    use futures::{Sink, SinkExt, Stream, StreamExt};
+
+    use zksync_api_types::v02::ws::{Subscription, WsAuthentication, WsMessage, WsRequest};
+    use zksync_types::{
+        tx::{PackedEthSignature, TxHash},
+        H256,
+    };
+
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::TestServerConfig;
+
+    async fn send_request<S>(framed: &mut S, request: &WsRequest) -> anyhow::Result<WsMessage>
+    where
+        S: Sink<ws::Message, Error = ws::ProtocolError>
+            + Stream<Item = Result<ws::Frame, ws::ProtocolError>>
+            + Unpin,
+    {
+        let text = serde_json::to_string(request)?;
+        framed.send(ws::Message::Text(text.into())).await?;
+        match framed.next().await {
+            Some(Ok(ws::Frame::Text(bytes))) => Ok(serde_json::from_slice(&bytes)?),
+            frame => anyhow::bail!("Unexpected frame: {:?}", frame),
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn ws_subscriptions() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let data = ApiWsData::new(
+            cfg.pool.clone(),
+            Duration::from_millis(100),
+            cfg.config.api.mempool_feed.clone(),
+            true,
+        );
+        let mut server = actix_test::start(move || App::new().service(api_resource(data.clone())));
+        let mut framed = server
+            .ws_at("/ws/v02")
+            .await
+            .map_err(|err| anyhow::format_err!("{:?}", err))?;
+
+        // Unknown transaction can be subscribed to, since it may be sent later.
+        let subscription = Subscription::TxStatus {
+            tx_hash: TxHash::default(),
+        };
+        let response = send_request(&mut framed, &WsRequest::Subscribe(subscription)).await?;
+        assert!(matches!(
+            response,
+            WsMessage::Subscribed { subscription: sub, receipt: None } if sub == subscription
+        ));
+
+        let response = send_request(&mut framed, &WsRequest::Unsubscribe(subscription)).await?;
+        assert!(matches!(
+            response,
+            WsMessage::Unsubscribed { subscription: sub } if sub == subscription
+        ));
+
+        // Private topics are not available until the address is authenticated.
+        let eth_private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&eth_private_key)?;
+        let subscription = Subscription::AccountTxs { address };
+        let response = send_request(&mut framed, &WsRequest::Subscribe(subscription)).await?;
+        assert!(
+            matches!(&response, WsMessage::Error { message } if message == "Address is not authenticated")
+        );
+
+        let challenge = match send_request(&mut framed, &WsRequest::Challenge).await? {
+            WsMessage::Challenge { message } => message,
+            response => anyhow::bail!("Unexpected response: {:?}", response),
+        };
+        // The challenge is signed by the other key.
+        let auth = WsAuthentication {
+            address,
+            signature: PackedEthSignature::sign(&H256::random(), challenge.as_bytes())?,
+        };
+        let response = send_request(&mut framed, &WsRequest::Authenticate(auth)).await?;
+        assert!(matches!(response, WsMessage::Error { .. }));
+
+        let auth = WsAuthentication {
+            address,
+            signature: PackedEthSignature::sign(&eth_private_key, challenge.as_bytes())?,
+        };
+        let response = send_request(&mut framed, &WsRequest::Authenticate(auth)).await?;
+        assert!(matches!(response, WsMessage::Authenticated { address: addr } if addr == address));
+
+        // Transactions of the unknown account can't be tracked.
+        let response = send_request(&mut framed, &WsRequest::Subscribe(subscription)).await?;
+        assert!(
+            matches!(&response, WsMessage::Error { message } if message == "Account does not exist")
+        );
+
+        let response =
+            send_request(&mut framed, &WsRequest::Subscribe(Subscription::PendingTxs)).await?;
+        if cfg.config.api.mempool_feed.enabled {
+            assert!(matches!(
+                response,
+                WsMessage::Subscribed {
+                    subscription: Subscription::PendingTxs,
+                    receipt: None
+                }
+            ));
+        } else {
+            assert!(matches!(response, WsMessage::Error { .. }));
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+}
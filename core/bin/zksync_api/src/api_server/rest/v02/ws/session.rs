@@ -0,0 +1,321 @@
+// Built-in uses
+use std::collections::{HashMap, HashSet};
+// External uses
+use actix::prelude::*;
+use actix_web_actors::ws;
+use futures::{stream, Stream};
+use tokio::sync::broadcast::{self, error::RecvError};
+// Workspace uses
+use zksync_api_types::v02::{
+    transaction::Receipt,
+    ws::{Subscription, WsAuthentication, WsMessage, WsRequest},
+};
+use zksync_storage::{ConnectionPool, QueryResult};
+use zksync_types::{event::transaction::TransactionEvent, tx::TxHash, AccountId, Address, H256};
+// Local uses
+use super::{PendingTxs, TxEvents};
+
+/// Maximum number of subscriptions a single connection may have.
+const MAX_SUBSCRIPTIONS_PER_SESSION: usize = 128;
+
+/// The WebSocket actor. Created for each connected client.
+pub struct WsSession {
+    db_pool: ConnectionPool,
+    /// Used to subscribe for the new transaction events once the session is started.
+    tx_events: broadcast::Sender<TxEvents>,
+    /// Source of the public mempool feed, `None` if the feed is disabled.
+    pending_txs: Option<broadcast::Sender<PendingTxs>>,
+    subscriptions: HashSet<Subscription>,
+    /// Ids of the accounts from the `accountTxs` subscriptions. Transaction events
+    /// only contain the account id, so the address is resolved during the subscription.
+    accounts: HashMap<AccountId, Address>,
+    /// Whether the private topics are available only for the authenticated addresses.
+    private_topics_auth: bool,
+    /// Message to be signed for the authentication, generated once per connection.
+    challenge: Option<String>,
+    /// Addresses whose private topics are available for the connection.
+    authenticated: HashSet<Address>,
+}
+
+impl WsSession {
+    pub fn new(
+        db_pool: ConnectionPool,
+        tx_events: broadcast::Sender<TxEvents>,
+        pending_txs: Option<broadcast::Sender<PendingTxs>>,
+        private_topics_auth: bool,
+    ) -> Self {
+        Self {
+            db_pool,
+            tx_events,
+            pending_txs,
+            subscriptions: HashSet::new(),
+            accounts: HashMap::new(),
+            private_topics_auth,
+            challenge: None,
+            authenticated: HashSet::new(),
+        }
+    }
+
+    fn send(ctx: &mut <Self as Actor>::Context, message: &WsMessage) {
+        ctx.text(serde_json::to_string(message).unwrap());
+    }
+
+    fn send_error(ctx: &mut <Self as Actor>::Context, message: impl ToString) {
+        Self::send(
+            ctx,
+            &WsMessage::Error {
+                message: message.to_string(),
+            },
+        );
+    }
+
+    /// Returns the subscriptions the event belongs to.
+    fn matching_subscriptions(&self, event: &TransactionEvent) -> Vec<Subscription> {
+        let tx_status = event
+            .tx_hash
+            .parse()
+            .ok()
+            .map(|tx_hash| Subscription::TxStatus { tx_hash })
+            .filter(|subscription| self.subscriptions.contains(subscription));
+        let account_txs = self
+            .accounts
+            .get(&event.account_id)
+            .map(|&address| Subscription::AccountTxs { address });
+
+        tx_status.into_iter().chain(account_txs).collect()
+    }
+
+    async fn load_receipt(
+        db_pool: ConnectionPool,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<Receipt>> {
+        db_pool
+            .access_storage()
+            .await?
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt_api_v02(tx_hash.as_ref())
+            .await
+    }
+
+    async fn load_account_id(
+        db_pool: ConnectionPool,
+        address: Address,
+    ) -> QueryResult<Option<AccountId>> {
+        db_pool
+            .access_storage()
+            .await?
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await
+    }
+
+    fn challenge(&mut self, ctx: &mut <Self as Actor>::Context) {
+        let message = self
+            .challenge
+            .get_or_insert_with(|| {
+                format!(
+                    "Sign this message to access the private zkSync WebSocket topics of the account.\n\
+                     Challenge: {:x}",
+                    H256::random()
+                )
+            })
+            .clone();
+        Self::send(ctx, &WsMessage::Challenge { message });
+    }
+
+    fn authenticate(&mut self, auth: WsAuthentication, ctx: &mut <Self as Actor>::Context) {
+        let challenge = match &self.challenge {
+            Some(challenge) => challenge,
+            None => {
+                Self::send_error(ctx, "Challenge was not requested");
+                return;
+            }
+        };
+        match auth
+            .signature
+            .signature_recover_signer(challenge.as_bytes())
+        {
+            Ok(signer) if signer == auth.address => {
+                self.authenticated.insert(auth.address);
+                Self::send(
+                    ctx,
+                    &WsMessage::Authenticated {
+                        address: auth.address,
+                    },
+                );
+            }
+            _ => {
+                metrics::increment_counter!("api.v02.ws.failed_authentications");
+                Self::send_error(ctx, "Invalid signature of the challenge");
+            }
+        }
+    }
+
+    fn subscribe(&mut self, subscription: Subscription, ctx: &mut <Self as Actor>::Context) {
+        if self.subscriptions.len() >= MAX_SUBSCRIPTIONS_PER_SESSION {
+            Self::send_error(ctx, "Too many subscriptions");
+            return;
+        }
+        if let Some(address) = subscription.private_address() {
+            if self.private_topics_auth && !self.authenticated.contains(&address) {
+                Self::send_error(ctx, "Address is not authenticated");
+                return;
+            }
+        }
+
+        let db_pool = self.db_pool.clone();
+        match subscription {
+            Subscription::TxStatus { tx_hash } => {
+                // Subscription is registered before the receipt is loaded, so no status
+                // update can be missed in between.
+                self.subscriptions.insert(subscription);
+                Self::load_receipt(db_pool, tx_hash)
+                    .into_actor(self)
+                    .map(move |result, _, ctx| match result {
+                        Ok(receipt) => Self::send(
+                            ctx,
+                            &WsMessage::Subscribed {
+                                subscription,
+                                receipt,
+                            },
+                        ),
+                        Err(err) => Self::send_error(ctx, err),
+                    })
+                    .wait(ctx);
+            }
+            Subscription::AccountTxs { address } => {
+                Self::load_account_id(db_pool, address)
+                    .into_actor(self)
+                    .map(move |result, act, ctx| match result {
+                        Ok(Some(account_id)) => {
+                            act.subscriptions.insert(subscription);
+                            act.accounts.insert(account_id, address);
+                            Self::send(
+                                ctx,
+                                &WsMessage::Subscribed {
+                                    subscription,
+                                    receipt: None,
+                                },
+                            );
+                        }
+                        Ok(None) => Self::send_error(ctx, "Account does not exist"),
+                        Err(err) => Self::send_error(ctx, err),
+                    })
+                    .wait(ctx);
+            }
+            Subscription::PendingTxs => {
+                if self.pending_txs.is_none() {
+                    Self::send_error(ctx, "Mempool feed is disabled");
+                    return;
+                }
+                self.subscriptions.insert(subscription);
+                Self::send(
+                    ctx,
+                    &WsMessage::Subscribed {
+                        subscription,
+                        receipt: None,
+                    },
+                );
+            }
+        }
+    }
+
+    fn unsubscribe(&mut self, subscription: Subscription, ctx: &mut <Self as Actor>::Context) {
+        self.subscriptions.remove(&subscription);
+        if let Subscription::AccountTxs { address } = subscription {
+            self.accounts.retain(|_, account| *account != address);
+        }
+        Self::send(ctx, &WsMessage::Unsubscribed { subscription });
+    }
+}
+
+impl Actor for WsSession {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.set_mailbox_capacity(1 << 10);
+        ctx.add_stream(broadcast_stream(self.tx_events.subscribe()));
+        if let Some(pending_txs) = &self.pending_txs {
+            ctx.add_stream(broadcast_stream(pending_txs.subscribe()));
+        }
+    }
+}
+
+/// Converts the broadcast receiver into the stream of its messages.
+fn broadcast_stream<T: Clone + Send + 'static>(
+    receiver: broadcast::Receiver<T>,
+) -> impl Stream<Item = T> {
+    // Lagging behind only means that some of the messages were dropped for this session,
+    // there is no reason to close the connection.
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(item) => return Some((item, receiver)),
+                Err(RecvError::Lagged(skipped)) => {
+                    vlog::warn!("WebSocket session skipped {} message batches", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsSession {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
+            Ok(ws::Message::Text(text)) => match serde_json::from_str(&text) {
+                Ok(WsRequest::Subscribe(subscription)) => self.subscribe(subscription, ctx),
+                Ok(WsRequest::Unsubscribe(subscription)) => self.unsubscribe(subscription, ctx),
+                Ok(WsRequest::Challenge) => self.challenge(ctx),
+                Ok(WsRequest::Authenticate(auth)) => self.authenticate(auth, ctx),
+                Err(err) => Self::send_error(ctx, err),
+            },
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            Err(err) => {
+                let reason = Some(ws::CloseReason {
+                    code: ws::CloseCode::Error,
+                    description: Some(err.to_string()),
+                });
+                ctx.close(reason);
+                ctx.stop();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl StreamHandler<TxEvents> for WsSession {
+    fn handle(&mut self, events: TxEvents, ctx: &mut Self::Context) {
+        if self.subscriptions.is_empty() {
+            return;
+        }
+        for event in events.iter() {
+            for subscription in self.matching_subscriptions(event) {
+                Self::send(
+                    ctx,
+                    &WsMessage::Event {
+                        subscription,
+                        event: event.clone(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+impl StreamHandler<PendingTxs> for WsSession {
+    fn handle(&mut self, txs: PendingTxs, ctx: &mut Self::Context) {
+        if !self.subscriptions.contains(&Subscription::PendingTxs) {
+            return;
+        }
+        for tx in txs.iter() {
+            Self::send(ctx, &WsMessage::PendingTx { tx: tx.clone() });
+        }
+    }
+}
@@ -1,7 +1,8 @@
 // External uses
 use jsonrpc_core::ErrorCode;
-use zksync_types::tx::error::TxAddError;
 // Workspace uses
+use zksync_token_db_cache::TokenResolutionError;
+use zksync_types::tx::error::TxAddError;
 // Local uses
 use crate::api_server::tx_sender::SubmitError;
 
@@ -22,6 +23,13 @@ pub enum RpcErrorCodes {
     OperationsLimitReached = 302,
     UnsupportedFastProcessing = 303,
     Toggle2FA = 304,
+    AccountRecovery = 305,
+    Timeout = 306,
+    TokenDisabled = 307,
+    ServiceUnavailable = 308,
+    TokenNotFound = 309,
+    AmbiguousTokenSymbol = 310,
+    RateLimitExceeded = 311,
 }
 
 impl From<TxAddError> for RpcErrorCodes {
@@ -77,16 +85,41 @@ impl From<SubmitError> for jsonrpc_core::Error {
                 message: inner.to_string(),
                 data: None,
             },
+            SubmitError::BatchSigners(inner) => Self {
+                code: RpcErrorCodes::IncorrectEthSignature.into(),
+                message: inner.to_string(),
+                data: serde_json::to_value(&inner.0).ok(),
+            },
             SubmitError::Toggle2FA(inner) => Self {
                 code: RpcErrorCodes::Toggle2FA.into(),
                 message: inner.to_string(),
                 data: None,
             },
+            SubmitError::AccountRecovery(inner) => Self {
+                code: RpcErrorCodes::AccountRecovery.into(),
+                message: inner.to_string(),
+                data: None,
+            },
             SubmitError::InappropriateFeeToken => Self {
                 code: RpcErrorCodes::InappropriateFeeToken.into(),
                 message: inner.to_string(),
                 data: None,
             },
+            SubmitError::Timeout => Self {
+                code: RpcErrorCodes::Timeout.into(),
+                message: inner.to_string(),
+                data: None,
+            },
+            SubmitError::ServiceUnavailable(_) => Self {
+                code: RpcErrorCodes::ServiceUnavailable.into(),
+                message: inner.to_string(),
+                data: None,
+            },
+            SubmitError::TokenDisabled(_) => Self {
+                code: RpcErrorCodes::TokenDisabled.into(),
+                message: inner.to_string(),
+                data: None,
+            },
             SubmitError::MempoolCommunication(reason) => Self {
                 code: RpcErrorCodes::Other.into(),
                 message: "Error communicating core server".to_string(),
@@ -110,3 +143,23 @@ impl From<SubmitError> for jsonrpc_core::Error {
         }
     }
 }
+
+impl From<TokenResolutionError> for jsonrpc_core::Error {
+    fn from(inner: TokenResolutionError) -> Self {
+        let code = match &inner {
+            TokenResolutionError::NotFound(_) => RpcErrorCodes::TokenNotFound.into(),
+            TokenResolutionError::AmbiguousSymbol { .. } => {
+                RpcErrorCodes::AmbiguousTokenSymbol.into()
+            }
+            TokenResolutionError::Storage(err) => {
+                vlog::warn!("Failed to resolve the token: {}", err);
+                return Self::internal_error();
+            }
+        };
+        Self {
+            code,
+            message: inner.to_string(),
+            data: None,
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::time::Instant;
 
 // External uses
 use futures::channel::mpsc;
-use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
+use jsonrpc_core::{Error, MetaIoHandler, Metadata, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
 use tokio::task::JoinHandle;
 
@@ -16,7 +16,7 @@ use zksync_storage::{
     },
     ConnectionPool, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, Address, BlockNumber};
+use zksync_types::{tx::TxHash, Address, BlockNumber, Token, TokenLike};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
 // Local uses
@@ -30,7 +30,13 @@ pub mod types;
 
 pub use self::rpc_trait::Rpc;
 use self::types::*;
-use super::tx_sender::TxSender;
+use super::{
+    rate_limit::{
+        rpc::{RpcRateLimiter, RpcRequestMeta},
+        RateLimiter,
+    },
+    tx_sender::TxSender,
+};
 use crate::fee_ticker::FeeTicker;
 use ip_insert_middleware::IpInsertMiddleWare;
 use zksync_mempool::MempoolTransactionRequest;
@@ -253,6 +259,24 @@ impl RpcApp {
         };
         Ok(res)
     }
+
+    /// Resolves the token by its id, L1 address or symbol, the same way as the REST API does.
+    async fn resolve_token(&self, token: TokenLike) -> Result<Token> {
+        if let Some(token) = self
+            .tx_sender
+            .tokens
+            .try_get_token_from_cache(token.clone())
+            .await
+        {
+            return Ok(token);
+        }
+        let mut storage = self.access_storage().await?;
+        self.tx_sender
+            .tokens
+            .resolve_token(&mut storage, token)
+            .await
+            .map_err(Error::from)
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -266,6 +290,7 @@ pub fn start_rpc_server(
     token_config: &TokenConfig,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     confirmations_for_eth_event: u64,
+    rate_limiter: RateLimiter,
 ) -> JoinHandle<()> {
     let addr = config.http_bind_addr();
     let rpc_app = RpcApp::new(
@@ -281,10 +306,13 @@ pub fn start_rpc_server(
     let (handler, panic_sender) = spawn_panic_handler();
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
-        let mut io = IoHandler::new();
+        let mut io = MetaIoHandler::with_middleware(RpcRateLimiter(rate_limiter.clone()));
         rpc_app.extend(&mut io);
 
-        let server = ServerBuilder::new(io)
+        let server =
+            ServerBuilder::with_meta_extractor(io, move |request: &hyper::Request<hyper::Body>| {
+                RpcRequestMeta::extract(&rate_limiter, request)
+            })
             .threads(super::THREADS_PER_SERVER)
             .request_middleware(IpInsertMiddleWare {})
             .start_http(&addr)
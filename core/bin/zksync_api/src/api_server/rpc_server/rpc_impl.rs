@@ -34,11 +34,14 @@ impl RpcApp {
         let account_state = self.get_account_state(address).await?;
 
         let mut storage = self.access_storage().await?;
+        // The JSON RPC API keeps the depositing balances keyed by the token symbol.
         let depositing = get_depositing(
             &mut storage,
             &self.tx_sender.tokens,
             address,
             self.confirmations_for_eth_event,
+            None,
+            false,
         )
         .await
         .map_err(|err| {
@@ -120,6 +123,7 @@ impl RpcApp {
                 executed: true,
                 success: Some(stored_receipt.success),
                 fail_reason: stored_receipt.fail_reason,
+                fail_code: stored_receipt.fail_code,
                 block: Some(BlockInfo {
                     block_number: stored_receipt.block_number,
                     committed: true,
@@ -131,6 +135,7 @@ impl RpcApp {
                 executed: false,
                 success: None,
                 fail_reason: None,
+                fail_code: None,
                 block: None,
             }
         })
@@ -280,6 +285,7 @@ impl RpcApp {
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<Fee> {
         let start = Instant::now();
+        let token = TokenLike::Id(self.resolve_token(token).await?.id);
         let token_allowed = self
             .tx_sender
             .ticker
@@ -290,28 +296,37 @@ impl RpcApp {
             return Err(SubmitError::InappropriateFeeToken.into());
         }
 
+        let tx_type: TxFeeTypes = tx_type.into();
         let result = self
             .tx_sender
             .ticker
-            .get_fee_from_ticker_in_wei(tx_type.into(), token.clone(), address)
+            .get_fee_from_ticker_in_wei(tx_type, token.clone(), address)
             .await
             .map_err(SubmitError::Internal)?;
 
-        let should_subsidize_cpk = self
+        let should_subsidize = self
             .tx_sender
-            .should_subsidize_cpk(
+            .should_subsidize(
                 &result.normal_fee.total_fee,
                 &result.subsidized_fee.total_fee,
                 &result.subsidy_size_usd,
+                result.subsidy_rule.as_ref(),
                 extracted_request_metadata,
             )
             .await?;
 
-        let fee = if should_subsidize_cpk {
+        let fee = if should_subsidize {
             result.subsidized_fee
         } else {
             result.normal_fee
         };
+        self.tx_sender.store_fee_quote(
+            &[tx_type],
+            &[address],
+            fee.clone().into(),
+            should_subsidize,
+            result.inputs,
+        );
 
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_tx_fee");
         Ok(fee)
@@ -333,6 +348,7 @@ impl RpcApp {
             });
         }
 
+        let token = TokenLike::Id(self.resolve_token(token).await?.id);
         let token_allowed = self
             .tx_sender
             .ticker
@@ -350,6 +366,7 @@ impl RpcApp {
             .zip(addresses.iter().cloned()))
         .collect();
 
+        let tx_types: Vec<_> = transactions.iter().map(|(tx_type, _)| *tx_type).collect();
         let result = self
             .tx_sender
             .ticker
@@ -357,21 +374,29 @@ impl RpcApp {
             .await
             .map_err(SubmitError::Internal)?;
 
-        let should_subsidize_cpk = self
+        let should_subsidize = self
             .tx_sender
-            .should_subsidize_cpk(
+            .should_subsidize(
                 &result.normal_fee.total_fee,
                 &result.subsidized_fee.total_fee,
                 &result.subsidy_size_usd,
+                result.subsidy_rule.as_ref(),
                 extracted_request_metadata,
             )
             .await?;
 
-        let fee = if should_subsidize_cpk {
+        let fee = if should_subsidize {
             result.subsidized_fee
         } else {
             result.normal_fee
         };
+        self.tx_sender.store_fee_quote(
+            &tx_types,
+            &addresses,
+            fee.clone().into(),
+            should_subsidize,
+            result.inputs,
+        );
 
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_txs_batch_fee_in_wei");
         Ok(TotalFee {
@@ -381,6 +406,7 @@ impl RpcApp {
 
     pub async fn _impl_get_token_price(self, token: TokenLike) -> Result<BigDecimal> {
         let start = Instant::now();
+        let token = TokenLike::Id(self.resolve_token(token).await?.id);
         let result = self
             .tx_sender
             .ticker
@@ -12,7 +12,7 @@ use zksync_api_types::v02::{
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
 use zksync_storage::StorageProcessor;
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{Account, AccountId, Address, Nonce, PubKeyHash, TokenId};
+use zksync_types::{tx::TxFailureCode, Account, AccountId, Address, Nonce, PubKeyHash, TokenId};
 use zksync_utils::BigUintSerdeWrapper;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -109,6 +109,8 @@ pub struct TransactionInfoResp {
     pub executed: bool,
     pub success: Option<bool>,
     pub fail_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_code: Option<TxFailureCode>,
     pub block: Option<BlockInfo>,
 }
 
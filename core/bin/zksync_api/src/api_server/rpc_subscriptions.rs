@@ -20,6 +20,7 @@ use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 use crate::fee_ticker::FeeTicker;
 use crate::{
     api_server::event_notify::{start_sub_notifier, EventNotifierRequest, EventSubscribeRequest},
+    api_server::rate_limit::{rpc::RpcRateLimiter, RateLimiter},
     api_server::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
     signature_checker::VerifySignatureRequest,
 };
@@ -188,6 +189,7 @@ pub fn start_ws_server(
     miniblock_iteration_interval: Duration,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     confirmations_for_eth_event: u64,
+    rate_limiter: RateLimiter,
 ) -> JoinHandle<()> {
     let addr = config.ws_bind_addr();
 
@@ -215,7 +217,8 @@ pub fn start_ws_server(
 
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
-        let mut io = PubSubHandler::new(MetaIoHandler::default());
+        let mut io =
+            PubSubHandler::new(MetaIoHandler::with_middleware(RpcRateLimiter(rate_limiter)));
 
         req_rpc_app.extend(&mut io);
 
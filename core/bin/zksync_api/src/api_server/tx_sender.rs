@@ -6,11 +6,12 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
     str::FromStr,
+    sync::Arc,
 };
 
 // External uses
 use bigdecimal::BigDecimal;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use futures::{
     channel::{mpsc, oneshot},
     prelude::*,
@@ -19,12 +20,21 @@ use itertools::izip;
 use num::rational::Ratio;
 use num::{bigint::ToBigInt, BigUint, Zero};
 use thiserror::Error;
+use tokio::sync::Semaphore;
 
 // Workspace uses
 use zksync_api_types::{
-    v02::transaction::{SubmitBatchResponse, Toggle2FA, Toggle2FAResponse, TxHashSerializeWrapper},
+    v02::{
+        fee::ApiFee,
+        recovery::{AccountRecovery, CancelRecovery, InitiateRecovery, SetGuardians},
+        transaction::{
+            SubmissionStage, SubmitBatchResponse, Toggle2FA, Toggle2FAResponse,
+            TxHashSerializeWrapper,
+        },
+    },
     TxWithSignature,
 };
+use zksync_storage::fee_quotes::records::FeeQuote;
 use zksync_storage::misc::records::Subsidy;
 use zksync_storage::{chain::account::records::EthAccountType, ConnectionPool};
 use zksync_token_db_cache::TokenDBCache;
@@ -33,31 +43,45 @@ use zksync_types::{
         EthBatchSignData, EthBatchSignatures, EthSignData, Order, SignedZkSyncTx, TxEthSignature,
         TxEthSignatureVariant, TxHash,
     },
-    AccountId, Address, PubKeyHash, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H160,
+    AccountId, Address, BlockNumber, PubKeyHash, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx,
+    H160,
 };
 use zksync_utils::{
-    big_decimal_to_ratio, biguint_to_big_decimal, ratio_to_scaled_u64, scaled_big_decimal_to_ratio,
+    big_decimal_to_ratio, biguint_to_big_decimal,
+    deadline::{deadline_channel, Deadline},
+    ratio_to_scaled_u64, scaled_big_decimal_to_ratio,
 };
 
 // Local uses
 use crate::{
-    api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
-    fee_ticker::{ResponseBatchFee, ResponseFee, TokenPriceRequestType},
+    api_server::{
+        fee_quotes::FeeQuotesRecorder,
+        forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
+    },
+    fee_ticker::{FeeInputs, ResponseBatchFee, ResponseFee, TokenPriceRequestType},
     signature_checker::{
-        BatchRequest, OrderRequest, RequestData, Toggle2FARequest, TxRequest, VerifiedTx,
-        VerifySignatureRequest,
+        AccountRecoveryRequest, BatchRequest, BatchSignersError, OrderRequest, RequestData,
+        SignatureCheckError, Toggle2FARequest, TxRequest, VerifiedTx, VerifySignatureRequest,
     },
-    tx_error::Toggle2FAError,
+    tx_error::{AccountRecoveryError, Toggle2FAError},
     utils::block_details_cache::BlockDetailsCache,
 };
-use zksync_config::configs::api::{CommonApiConfig, TokenConfig};
+use zksync_config::configs::{
+    api::{CommonApiConfig, RecoveryConfig, TokenConfig},
+    ticker::SubsidyRule,
+};
 use zksync_mempool::MempoolTransactionRequest;
 use zksync_types::tx::error::TxAddError;
 
-use super::rpc_server::types::RequestMetadata;
+use super::{
+    circuit_breaker::CircuitBreaker, in_flight::InFlightSubmission,
+    rpc_server::types::RequestMetadata,
+};
 use crate::fee_ticker::{FeeTicker, PriceError};
 
 const VALIDNESS_INTERVAL_MINUTES: i64 = 40;
+/// Maximum number of the blocks the transaction can be scheduled after the last committed one.
+const MAX_SCHEDULE_HORIZON_BLOCKS: u32 = 1000;
 
 #[derive(Clone)]
 pub struct TxSender {
@@ -80,6 +104,17 @@ pub struct TxSender {
     pub current_subsidy_type: String,
     pub max_subsidy_usd: Ratio<BigUint>,
     pub subsidized_ips: HashSet<String>,
+    /// Records the served fee quotes to the audit log.
+    pub fee_quotes: FeeQuotesRecorder,
+    /// Part of the required fee the provided fee may fall short of, in percent.
+    pub fee_tolerance_percent: u32,
+
+    /// Time to wait for the signature checker and the mempool to handle the request.
+    pub internal_request_timeout: std::time::Duration,
+    /// Limits the number of the async submissions processed in the background.
+    pub async_submissions: Arc<Semaphore>,
+    /// Fails the submissions fast while the mempool keeps failing.
+    pub mempool_breaker: CircuitBreaker,
 }
 
 #[derive(Debug, Error)]
@@ -94,13 +129,23 @@ pub enum SubmitError {
     IncorrectTx(String),
     #[error("Transaction adding error: {0}.")]
     TxAdd(#[from] TxAddError),
+    #[error("{0}.")]
+    BatchSigners(#[from] BatchSignersError),
     #[error("Chosen token is not suitable for paying fees.")]
     InappropriateFeeToken,
+    #[error("Token {0} is disabled, it can only be withdrawn.")]
+    TokenDisabled(TokenId),
     // Not all TxAddErrors would apply to Toggle2FA, but
     // it is helpful to re-use IncorrectEthSignature and DbError
     #[error("Failed to toggle 2FA: {0}.")]
     Toggle2FA(#[from] Toggle2FAError),
+    #[error("Account recovery error: {0}.")]
+    AccountRecovery(#[from] AccountRecoveryError),
 
+    #[error("Request processing has not finished in time.")]
+    Timeout,
+    #[error("The {0} is temporarily unavailable, try again later.")]
+    ServiceUnavailable(&'static str),
     #[error("Communication error with the mempool: {0}.")]
     MempoolCommunication(String),
     #[error("Price error {0}")]
@@ -111,6 +156,15 @@ pub enum SubmitError {
     Other(String),
 }
 
+impl From<SignatureCheckError> for SubmitError {
+    fn from(err: SignatureCheckError) -> Self {
+        match err {
+            SignatureCheckError::TxAdd(err) => Self::TxAdd(err),
+            SignatureCheckError::BatchSigners(err) => Self::BatchSigners(err),
+        }
+    }
+}
+
 impl SubmitError {
     pub fn internal(inner: impl Into<anyhow::Error>) -> Self {
         Self::Internal(inner.into())
@@ -153,13 +207,15 @@ impl TxSender {
         let max_number_of_transactions_per_batch =
             config.max_number_of_transactions_per_batch as usize;
         let max_number_of_authors_per_batch = config.max_number_of_authors_per_batch as usize;
+        let fee_quotes = FeeQuotesRecorder::new(connection_pool.clone(), config.store_fee_quotes);
 
         Self {
             mempool_tx_sender,
             pool: connection_pool,
             sign_verify_requests: sign_verify_request_sender,
             ticker,
-            tokens: TokenDBCache::new(token_config.invalidate_token_cache_period()),
+            tokens: TokenDBCache::new(token_config.invalidate_token_cache_period())
+                .with_negative_cache(token_config.negative_cache_period()),
             forced_exit_checker: ForcedExitChecker::new(
                 config.forced_exit_minimum_account_age_secs,
             ),
@@ -172,9 +228,53 @@ impl TxSender {
             current_subsidy_type: config.subsidy_name.clone(),
             max_subsidy_usd: config.max_subsidy_usd(),
             subsidized_ips: config.subsidized_ips.clone().into_iter().collect(),
+            fee_quotes,
+            fee_tolerance_percent: config.fee_tolerance_percent,
+            internal_request_timeout: config.internal_request_timeout(),
+            async_submissions: Arc::new(Semaphore::new(config.max_async_submissions)),
+            mempool_breaker: CircuitBreaker::disabled("mempool"),
         }
     }
 
+    /// Sets the circuit breaker of the mempool, it's shared by all the server workers.
+    pub fn with_mempool_breaker(mut self, mempool_breaker: CircuitBreaker) -> Self {
+        self.mempool_breaker = mempool_breaker;
+        self
+    }
+
+    /// Sends the request to the mempool and waits for its response until the deadline.
+    async fn send_to_mempool(
+        &self,
+        item: MempoolTransactionRequest,
+        receiver: oneshot::Receiver<Result<(), TxAddError>>,
+        deadline: Deadline,
+    ) -> Result<Result<(), TxAddError>, SubmitError> {
+        if !self.mempool_breaker.allow() {
+            return Err(SubmitError::ServiceUnavailable(self.mempool_breaker.name()));
+        }
+        let mut mempool_sender = self.mempool_tx_sender.clone();
+        let result = async {
+            mempool_sender
+                .send(item)
+                .await
+                .map_err(SubmitError::mempool_communication)?;
+            deadline
+                .wait(receiver)
+                .await
+                .ok_or(SubmitError::Timeout)?
+                .map_err(SubmitError::internal)
+        }
+        .await;
+        // The rejection of the transaction is a valid response of the mempool.
+        self.mempool_breaker.record(result.is_ok());
+        result
+    }
+
+    /// Deadline of the internal requests made while handling the API request.
+    fn request_deadline(&self) -> Deadline {
+        Deadline::after(self.internal_request_timeout)
+    }
+
     /// If `ForcedExit` has Ethereum siganture (e.g. it's a part of a batch), an actual signer
     /// is initiator, not the target, thus, this function will perform a database query to acquire
     /// the corresponding address.
@@ -259,18 +359,7 @@ impl TxSender {
         &self,
         toggle_2fa: Toggle2FA,
     ) -> Result<(), SubmitError> {
-        let current_time = Utc::now();
-        let request_time = toggle_2fa.timestamp;
-        let validness_interval = Duration::minutes(VALIDNESS_INTERVAL_MINUTES);
-
-        if current_time - validness_interval > request_time
-            || current_time + validness_interval < request_time
-        {
-            return Err(SubmitError::InvalidParams(format!(
-                "Timestamp differs by more than {} minutes",
-                VALIDNESS_INTERVAL_MINUTES
-            )));
-        }
+        check_request_timestamp(toggle_2fa.timestamp)?;
 
         let message = toggle_2fa.get_ethereum_sign_message().into_bytes();
 
@@ -281,7 +370,7 @@ impl TxSender {
             .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
 
         let eth_sign_data = EthSignData { signature, message };
-        let (sender, receiever) = oneshot::channel();
+        let (sender, receiever) = deadline_channel(self.request_deadline());
 
         let request = VerifySignatureRequest {
             data: RequestData::Toggle2FA(Toggle2FARequest {
@@ -295,6 +384,212 @@ impl TxSender {
         Ok(())
     }
 
+    /// Replaces the guardians of the account, the request must be signed by the account owner.
+    pub async fn set_guardians(
+        &self,
+        request: SetGuardians,
+        config: &RecoveryConfig,
+    ) -> Result<Vec<Address>, SubmitError> {
+        if request.guardians.len() > config.max_guardians {
+            return Err(AccountRecoveryError::TooManyGuardians(config.max_guardians).into());
+        }
+        check_request_timestamp(request.timestamp)?;
+
+        let owner = self.get_recovery_account_owner(request.account_id).await?;
+        let message = request.get_ethereum_sign_message().into_bytes();
+        self.verify_recovery_request_eth_signature(request.signature, message, owner)
+            .await?;
+
+        let mut guardians = request.guardians;
+        guardians.sort_unstable();
+        guardians.dedup();
+        self.pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?
+            .recovery_schema()
+            .set_guardians(request.account_id, &guardians)
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+
+        Ok(guardians)
+    }
+
+    /// Starts the recovery of the account on behalf of the guardian.
+    /// `ChangePubKey` to the proposed key is only accepted once the timelock has passed.
+    pub async fn initiate_recovery(
+        &self,
+        request: InitiateRecovery,
+        config: &RecoveryConfig,
+    ) -> Result<AccountRecovery, SubmitError> {
+        check_request_timestamp(request.timestamp)?;
+        // Only checks that the account exists.
+        self.get_recovery_account_owner(request.account_id).await?;
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+        let guardians = storage
+            .recovery_schema()
+            .load_guardians(request.account_id)
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+        if !guardians.contains(&request.guardian) {
+            return Err(AccountRecoveryError::NotGuardian.into());
+        }
+
+        let message = request.get_ethereum_sign_message().into_bytes();
+        self.verify_recovery_request_eth_signature(request.signature, message, request.guardian)
+            .await?;
+
+        let now = Utc::now();
+        let active_recovery = storage
+            .recovery_schema()
+            .get_active_recovery(request.account_id)
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+        if matches!(active_recovery, Some(recovery) if recovery.executable_at > now) {
+            return Err(AccountRecoveryError::InProgress.into());
+        }
+
+        let timelock = Duration::from_std(config.timelock()).map_err(SubmitError::internal)?;
+        let recovery = storage
+            .recovery_schema()
+            .store_recovery(
+                request.account_id,
+                &request.new_pub_key_hash,
+                request.guardian,
+                now + timelock,
+            )
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+
+        Ok(recovery.into())
+    }
+
+    /// Cancels the recovery in progress, the request must be signed by the account owner.
+    pub async fn cancel_recovery(
+        &self,
+        request: CancelRecovery,
+    ) -> Result<AccountRecovery, SubmitError> {
+        check_request_timestamp(request.timestamp)?;
+
+        let owner = self.get_recovery_account_owner(request.account_id).await?;
+        let message = request.get_ethereum_sign_message().into_bytes();
+        self.verify_recovery_request_eth_signature(request.signature, message, owner)
+            .await?;
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+        let recovery = transaction
+            .recovery_schema()
+            .cancel_recovery(request.account_id)
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?
+            .ok_or(AccountRecoveryError::NoRecovery)?;
+        // `ChangePubKey` to the proposed key may have been queued to be executed after the
+        // timelock, it must not be executed once the recovery is cancelled.
+        let new_pk_hash = PubKeyHash::from_bytes(&recovery.new_pub_key_hash)
+            .map_err(|_| AccountRecoveryError::DbError)?;
+        transaction
+            .chain()
+            .mempool_schema()
+            .evict_change_pubkey_txs(
+                request.account_id,
+                &new_pk_hash,
+                &AccountRecoveryError::Cancelled.to_string(),
+            )
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+        transaction
+            .commit()
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?;
+
+        Ok(recovery.into())
+    }
+
+    async fn get_recovery_account_owner(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Address, SubmitError> {
+        self.pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await
+            .map_err(|_| AccountRecoveryError::DbError)?
+            .ok_or_else(|| AccountRecoveryError::AccountNotFound.into())
+    }
+
+    async fn verify_recovery_request_eth_signature(
+        &self,
+        signature: TxEthSignature,
+        message: Vec<u8>,
+        signer: Address,
+    ) -> Result<(), SubmitError> {
+        let (sender, receiever) = deadline_channel(self.request_deadline());
+        let request = VerifySignatureRequest {
+            data: RequestData::AccountRecovery(AccountRecoveryRequest {
+                sign_data: EthSignData { signature, message },
+                sender: signer,
+            }),
+            response: sender,
+        };
+
+        send_verify_request_and_recv(request, self.sign_verify_requests.clone(), receiever).await?;
+        Ok(())
+    }
+
+    /// `ChangePubKey` to the key proposed by the guardian must not be executable before the
+    /// timelock of the recovery passes, and is rejected if the owner cancelled the recovery.
+    /// The state keeper does not include the transaction before its `valid_from` timestamp,
+    /// so it is enough to check the time range of the transaction. The transactions queued
+    /// before the cancellation are evicted from the mempool by `cancel_recovery`.
+    async fn check_recovery_timelock(&self, tx: &ZkSyncTx) -> Result<(), SubmitError> {
+        let tx = match tx {
+            ZkSyncTx::ChangePubKey(tx) => tx,
+            _ => return Ok(()),
+        };
+
+        let recovery = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?
+            .recovery_schema()
+            .get_recovery_by_pub_key_hash(tx.account_id, &tx.new_pk_hash)
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?;
+        let recovery = match recovery {
+            Some(recovery) => recovery,
+            None => return Ok(()),
+        };
+
+        if recovery.cancelled_at.is_some() {
+            return Err(AccountRecoveryError::Cancelled.into());
+        }
+        let valid_from = tx.time_range.map(|range| range.valid_from).unwrap_or(0);
+        if Utc::now() < recovery.executable_at
+            && (valid_from as i64) < recovery.executable_at.timestamp()
+        {
+            return Err(AccountRecoveryError::Timelocked(recovery.executable_at).into());
+        }
+        Ok(())
+    }
+
     async fn verify_order_eth_signature(
         &self,
         order: &Order,
@@ -339,7 +634,7 @@ impl TxSender {
             .get_ethereum_sign_message(&token_sell.symbol, &token_buy.symbol, token_sell.decimals)
             .into_bytes();
         let eth_sign_data = EthSignData { signature, message };
-        let (sender, receiever) = oneshot::channel();
+        let (sender, receiever) = deadline_channel(self.request_deadline());
 
         let request = VerifySignatureRequest {
             data: RequestData::Order(OrderRequest {
@@ -398,6 +693,8 @@ impl TxSender {
 
     pub async fn can_subsidize(
         &self,
+        subsidy_type: &str,
+        max_subsidy_usd: &Ratio<BigUint>,
         new_subsidy_usd: Ratio<BigUint>,
     ) -> Result<bool, anyhow::Error> {
         let subsidized_already = self
@@ -405,12 +702,12 @@ impl TxSender {
             .access_storage()
             .await?
             .misc_schema()
-            .get_total_used_subsidy_for_type(&self.current_subsidy_type)
+            .get_total_used_subsidy_for_type(subsidy_type)
             .await?;
         let subsidized_already_usd = scaled_big_decimal_to_ratio(subsidized_already)?;
 
-        let result = if self.max_subsidy_usd > subsidized_already_usd {
-            &self.max_subsidy_usd - &subsidized_already_usd >= new_subsidy_usd
+        let result = if max_subsidy_usd > &subsidized_already_usd {
+            max_subsidy_usd - &subsidized_already_usd >= new_subsidy_usd
         } else {
             false
         };
@@ -418,35 +715,51 @@ impl TxSender {
         Ok(result)
     }
 
-    pub async fn should_subsidize_cpk(
+    /// Whether the subsidized fee is required instead of the normal one. The subsidy of the
+    /// CREATE2 ChangePubKey is given to the configured IPs only, while the subsidy rules apply
+    /// to everyone. Both are given until their budgets are spent.
+    pub async fn should_subsidize(
         &self,
         normal_fee: &BigUint,
         subsidized_fee: &BigUint,
         subsidy_size_usd: &Ratio<BigUint>,
+        subsidy_rule: Option<&SubsidyRule>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<bool, SubmitError> {
-        let should_subsidize_ip = if let Some(meta) = extracted_request_metadata {
-            self.subsidized_ips.contains(&meta.ip)
-        } else {
-            false
+        let (is_eligible, subsidy_type, max_subsidy_usd) = match subsidy_rule {
+            Some(rule) => (true, rule.name.as_str(), rule.max_subsidy_usd()),
+            None => {
+                let should_subsidize_ip = if let Some(meta) = extracted_request_metadata {
+                    self.subsidized_ips.contains(&meta.ip)
+                } else {
+                    false
+                };
+                (
+                    should_subsidize_ip,
+                    self.current_subsidy_type.as_str(),
+                    self.max_subsidy_usd.clone(),
+                )
+            }
         };
 
-        let result = should_subsidize_ip
+        let result = is_eligible
             && subsidized_fee < normal_fee
             && self
-                .can_subsidize(subsidy_size_usd.clone())
+                .can_subsidize(subsidy_type, &max_subsidy_usd, subsidy_size_usd.clone())
                 .await
                 .map_err(SubmitError::Internal)?;
 
         Ok(result)
     }
 
+    /// Records the subsidy of the transaction, under the name of the subsidy rule if it's given.
     pub async fn store_subsidy_data(
         &self,
         hash: TxHash,
         normal_fee: BigUint,
         subsidized_fee: BigUint,
         token_id: TokenId,
+        subsidy_rule: Option<&SubsidyRule>,
     ) -> Result<(), anyhow::Error> {
         let token_price_in_usd = self
             .ticker
@@ -467,7 +780,10 @@ impl TxSender {
             token_id,
             token_amount: biguint_to_big_decimal(subsidized_fee),
             full_cost_token: biguint_to_big_decimal(normal_fee),
-            subsidy_type: self.current_subsidy_type.clone(),
+            subsidy_type: subsidy_rule.map_or_else(
+                || self.current_subsidy_type.clone(),
+                |rule| rule.name.clone(),
+            ),
             tx_hash: hash,
         };
 
@@ -481,12 +797,130 @@ impl TxSender {
         Ok(())
     }
 
+    /// Queues the fee quote served to the user to be recorded to the audit log, if it's enabled.
+    /// The quote is served regardless of whether it's recorded.
+    pub fn store_fee_quote(
+        &self,
+        tx_types: &[TxFeeTypes],
+        addresses: &[Address],
+        fee: ApiFee,
+        subsidized: bool,
+        inputs: FeeInputs,
+    ) {
+        if !self.fee_quotes.is_enabled() {
+            return;
+        }
+        let quote = FeeQuote {
+            tx_types: tx_types
+                .iter()
+                .map(|tx_type| fee_type_name(*tx_type).to_string())
+                .collect(),
+            addresses: addresses.to_vec(),
+            token_id: inputs.token_id,
+            zkp_fee: fee.zkp_fee,
+            gas_fee: fee.gas_fee,
+            total_fee: fee.total_fee,
+            subsidized,
+            gas_price_wei: inputs.gas_price_wei,
+            token_usd_price: inputs.token_usd_price,
+            created_at: Utc::now(),
+        };
+        self.fee_quotes.record(quote);
+    }
+
+    /// Returns the hash of the transaction right away, the transaction is verified and sent to
+    /// the mempool in the background.
+    ///
+    /// If the transaction is rejected, the reason is recorded the same way as for the evicted
+    /// transactions, so it's reported by the receipt endpoint, and the `Rejected` event is
+    /// emitted for the WebSocket subscribers. Once there are too many submissions in progress,
+    /// the transaction is processed synchronously.
+    pub async fn submit_tx_async(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        extracted_request_metadata: Option<RequestMetadata>,
+        not_before_block: Option<BlockNumber>,
+    ) -> Result<TxHash, SubmitError> {
+        let permit = match self.async_submissions.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                metrics::increment_counter!("api.async_submissions_overflow");
+                return self
+                    .submit_scheduled_tx(
+                        tx,
+                        signature,
+                        extracted_request_metadata,
+                        not_before_block,
+                    )
+                    .await;
+            }
+        };
+
+        let tx_hash = tx.hash();
+        let tx_sender = self.clone();
+        tokio::spawn(async move {
+            let result = tx_sender
+                .submit_scheduled_tx(
+                    tx.clone(),
+                    signature,
+                    extracted_request_metadata,
+                    not_before_block,
+                )
+                .await;
+            if let Err(err) = result {
+                let reason = err.to_string();
+                if let Err(err) = tx_sender.store_rejected_tx(&tx, &reason).await {
+                    vlog::warn!(
+                        "Failed to store the rejection of the transaction {}: {}",
+                        tx_hash,
+                        err
+                    );
+                }
+            }
+            drop(permit);
+        });
+        Ok(tx_hash)
+    }
+
+    async fn store_rejected_tx(&self, tx: &ZkSyncTx, reason: &str) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+        transaction
+            .chain()
+            .mempool_schema()
+            .store_rejected_tx(tx.hash(), reason)
+            .await?;
+        transaction
+            .event_schema()
+            .store_rejected_transaction_event(tx, reason)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
     pub async fn submit_tx(
         &self,
         tx: ZkSyncTx,
         signature: TxEthSignatureVariant,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<TxHash, SubmitError> {
+        self.submit_scheduled_tx(tx, signature, extracted_request_metadata, None)
+            .await
+    }
+
+    /// Submits the transaction that is stored in the mempool right away, but isn't executed
+    /// until the block `not_before_block` is committed.
+    pub async fn submit_scheduled_tx(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        extracted_request_metadata: Option<RequestMetadata>,
+        not_before_block: Option<BlockNumber>,
+    ) -> Result<TxHash, SubmitError> {
+        // The deadline is shared by the signature check and the mempool requests.
+        let deadline = self.request_deadline();
+        let submission = InFlightSubmission::start(vec![tx.hash()]);
         let labels = vec![
             ("stage", "api".to_string()),
             ("name", tx.variance_name()),
@@ -498,10 +932,16 @@ impl TxSender {
         if tx.is_close() {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        check_signature_scheme(&tx)?;
 
         if let ZkSyncTx::ForcedExit(forced_exit) = &tx {
             self.check_forced_exit(forced_exit).await?;
         }
+        self.check_recovery_timelock(&tx).await?;
+        self.check_disabled_tokens(&tx).await?;
+        if let Some(not_before_block) = not_before_block {
+            self.check_schedule(not_before_block).await?;
+        }
 
         // Resolve the token.
         let token = self.token_info_from_id(tx.token_id()).await?;
@@ -540,10 +980,11 @@ impl TxSender {
                 .await?;
 
             let required_fee_data = if self
-                .should_subsidize_cpk(
+                .should_subsidize(
                     &required_fee_data.normal_fee.total_fee,
                     &required_fee_data.subsidized_fee.total_fee,
                     &required_fee_data.subsidy_size_usd,
+                    required_fee_data.subsidy_rule.as_ref(),
                     extracted_request_metadata,
                 )
                 .await?
@@ -558,7 +999,7 @@ impl TxSender {
             let required_fee: BigDecimal = required_fee_data.total_fee.to_bigint().unwrap().into();
             let provided_fee: BigDecimal = provided_fee.to_bigint().unwrap().into();
             // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
-            let scaled_provided_fee = scale_user_fee_up(provided_fee);
+            let scaled_provided_fee = scale_user_fee_up(provided_fee, self.fee_tolerance_percent);
             if required_fee >= scaled_provided_fee && should_enforce_fee {
                 return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
             }
@@ -577,6 +1018,7 @@ impl TxSender {
             signature.tx_signature().clone(),
             msg_to_sign,
             sign_verify_channel,
+            deadline,
         )
         .await?
         .unwrap_tx();
@@ -592,15 +1034,14 @@ impl TxSender {
                 .await?;
         }
 
-        let (sender, receiver) = oneshot::channel();
-        let item = MempoolTransactionRequest::NewTx(Box::new(verified_tx), sender);
-        let mut mempool_sender = self.mempool_tx_sender.clone();
-        mempool_sender
-            .send(item)
-            .await
-            .map_err(SubmitError::internal)?;
-
-        receiver.await.map_err(SubmitError::internal)??;
+        let (sender, receiver) = deadline_channel(deadline);
+        let item =
+            MempoolTransactionRequest::NewTx(Box::new(verified_tx), not_before_block, sender);
+        submission.set_stage(SubmissionStage::SentToMempool);
+        let mempool_result = self.send_to_mempool(item, receiver, deadline).await?;
+        report_mempool_outcome("tx", &mempool_result);
+        mempool_result?;
+        submission.set_stage(SubmissionStage::Accepted);
 
         // fee_data_for_subsidy has Some value only if the batch of transactions is subsidised
         if let Some(fee_data_for_subsidy) = fee_data_for_subsidy {
@@ -615,6 +1056,7 @@ impl TxSender {
                 fee_data_for_subsidy.normal_fee.total_fee,
                 fee_data_for_subsidy.subsidized_fee.total_fee,
                 token.id,
+                fee_data_for_subsidy.subsidy_rule.as_ref(),
             )
             .await
             .map_err(|e| {
@@ -636,6 +1078,8 @@ impl TxSender {
         eth_signatures: Option<EthBatchSignatures>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<SubmitBatchResponse, SubmitError> {
+        // The deadline is shared by the signature check and the mempool requests.
+        let deadline = self.request_deadline();
         // Bring the received signatures into a vector for simplified work.
         let eth_signatures = EthBatchSignatures::api_arg_to_vec(eth_signatures);
 
@@ -648,6 +1092,7 @@ impl TxSender {
         if txs.len() > self.max_number_of_transactions_per_batch {
             return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
         }
+        let submission = InFlightSubmission::start(txs.iter().map(|tx| tx.tx.hash()).collect());
 
         for tx in &txs {
             let labels = vec![
@@ -666,6 +1111,11 @@ impl TxSender {
         if txs.iter().any(|tx| tx.tx.is_close()) {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        for tx in &txs {
+            check_signature_scheme(&tx.tx)?;
+            self.check_recovery_timelock(&tx.tx).await?;
+            self.check_disabled_tokens(&tx.tx).await?;
+        }
 
         // Checking fees data
         let mut provided_total_usd_fee = BigDecimal::from(0);
@@ -732,10 +1182,11 @@ impl TxSender {
                 .await?;
 
             let required_fee = if self
-                .should_subsidize_cpk(
+                .should_subsidize(
                     &batch_token_fee.normal_fee.total_fee,
                     &batch_token_fee.subsidized_fee.total_fee,
                     &batch_token_fee.subsidy_size_usd,
+                    batch_token_fee.subsidy_rule.as_ref(),
                     extracted_request_metadata,
                 )
                 .await?
@@ -746,8 +1197,10 @@ impl TxSender {
                 batch_token_fee.normal_fee.total_fee
             };
 
-            let user_provided_fee =
-                scale_user_fee_up(BigDecimal::from(fee_paid.to_bigint().unwrap()));
+            let user_provided_fee = scale_user_fee_up(
+                BigDecimal::from(fee_paid.to_bigint().unwrap()),
+                self.fee_tolerance_percent,
+            );
             let required_normal_fee = BigDecimal::from(required_fee.to_bigint().unwrap());
 
             // Not enough fee
@@ -767,10 +1220,11 @@ impl TxSender {
                 .await?;
 
             let required_fee = if self
-                .should_subsidize_cpk(
+                .should_subsidize(
                     &required_eth_fee.normal_fee.total_fee,
                     &required_eth_fee.subsidized_fee.total_fee,
                     &required_eth_fee.subsidy_size_usd,
+                    required_eth_fee.subsidy_rule.as_ref(),
                     extracted_request_metadata,
                 )
                 .await?
@@ -790,7 +1244,8 @@ impl TxSender {
                 BigDecimal::from(required_fee.to_bigint().unwrap()) * &eth_price_in_usd;
 
             // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
-            let scaled_provided_fee_in_usd = scale_user_fee_up(provided_total_usd_fee.clone());
+            let scaled_provided_fee_in_usd =
+                scale_user_fee_up(provided_total_usd_fee.clone(), self.fee_tolerance_percent);
             if required_total_usd_fee > scaled_provided_fee_in_usd {
                 vlog::error!(
                     "User provided batch fee is too low, required: {}, provided: {} (scaled: {}); difference {}",
@@ -867,6 +1322,7 @@ impl TxSender {
             batch_sign_data,
             messages_to_sign,
             self.sign_verify_requests.clone(),
+            deadline,
         )
         .await?
         .unwrap_batch();
@@ -877,16 +1333,14 @@ impl TxSender {
 
         let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
 
-        let (sender, receiver) = oneshot::channel();
+        let (sender, receiver) = deadline_channel(deadline);
         let item =
             MempoolTransactionRequest::NewTxsBatch(verified_txs, verified_signatures, sender);
-        let mut mempool_sender = self.mempool_tx_sender.clone();
-        mempool_sender
-            .send(item)
-            .await
-            .map_err(SubmitError::mempool_communication)?;
-
-        receiver.await.map_err(SubmitError::internal)??;
+        submission.set_stage(SubmissionStage::SentToMempool);
+        let mempool_result = self.send_to_mempool(item, receiver, deadline).await?;
+        report_mempool_outcome("batch", &mempool_result);
+        mempool_result?;
+        submission.set_stage(SubmissionStage::Accepted);
 
         let batch_hash = TxHash::batch_hash(&tx_hashes);
 
@@ -913,6 +1367,7 @@ impl TxSender {
                 fee_data.normal_fee.total_fee,
                 fee_data.subsidized_fee.total_fee,
                 subsidy_token_id,
+                fee_data.subsidy_rule.as_ref(),
             )
             .await
             .map_err(|e| {
@@ -951,6 +1406,29 @@ impl TxSender {
             .await
     }
 
+    /// Checks that the scheduled transaction isn't postponed beyond the horizon.
+    async fn check_schedule(&self, not_before_block: BlockNumber) -> Result<(), SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let last_committed_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(SubmitError::internal)?;
+
+        if *not_before_block > (*last_committed_block).saturating_add(MAX_SCHEDULE_HORIZON_BLOCKS) {
+            return Err(SubmitError::InvalidParams(format!(
+                "Transaction can't be scheduled more than {} blocks ahead",
+                MAX_SCHEDULE_HORIZON_BLOCKS
+            )));
+        }
+        Ok(())
+    }
+
     /// Returns a message that user has to sign to send the transaction.
     /// If the transaction doesn't need a message signature, returns `None`.
     /// If any error is encountered during the message generation, returns `jsonrpc_core::Error`.
@@ -987,6 +1465,38 @@ impl TxSender {
     }
 
     /// Resolves the token from the database.
+    /// Disabled tokens can only be withdrawn from zkSync.
+    async fn check_disabled_tokens(&self, tx: &ZkSyncTx) -> Result<(), SubmitError> {
+        if tx.is_withdraw() {
+            return Ok(());
+        }
+
+        for token_id in tx.tokens() {
+            let settings = match self
+                .tokens
+                .try_get_token_admin_settings_from_cache(token_id)
+                .await
+            {
+                Some(settings) => settings,
+                None => {
+                    let mut storage = self
+                        .pool
+                        .access_storage()
+                        .await
+                        .map_err(SubmitError::internal)?;
+                    self.tokens
+                        .get_token_admin_settings(&mut storage, token_id)
+                        .await
+                        .map_err(SubmitError::internal)?
+                }
+            };
+            if settings.disabled {
+                return Err(SubmitError::TokenDisabled(token_id));
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) async fn token_info_from_id(
         &self,
         token_id: impl Into<TokenLike>,
@@ -1014,25 +1524,99 @@ impl TxSender {
     }
 }
 
+/// Checks that the signed request is neither outdated nor postdated.
+/// Name of the transaction type in the fee quotes audit log.
+fn fee_type_name(tx_type: TxFeeTypes) -> &'static str {
+    match tx_type {
+        TxFeeTypes::WithdrawNFT => "WithdrawNFT",
+        TxFeeTypes::FastWithdrawNFT => "FastWithdrawNFT",
+        TxFeeTypes::Withdraw => "Withdraw",
+        TxFeeTypes::FastWithdraw => "FastWithdraw",
+        TxFeeTypes::Transfer => "Transfer",
+        TxFeeTypes::ChangePubKey(_) => "ChangePubKey",
+        TxFeeTypes::Swap => "Swap",
+        TxFeeTypes::MintNFT => "MintNFT",
+    }
+}
+
+fn check_request_timestamp(request_time: DateTime<Utc>) -> Result<(), SubmitError> {
+    let current_time = Utc::now();
+    let validness_interval = Duration::minutes(VALIDNESS_INTERVAL_MINUTES);
+
+    if current_time - validness_interval > request_time
+        || current_time + validness_interval < request_time
+    {
+        return Err(SubmitError::InvalidParams(format!(
+            "Timestamp differs by more than {} minutes",
+            VALIDNESS_INTERVAL_MINUTES
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that the transaction and the orders of the swap are signed with the schemes
+/// supported by the current version of the protocol.
+fn check_signature_scheme(tx: &ZkSyncTx) -> Result<(), SubmitError> {
+    let mut schemes = vec![tx.signature().scheme];
+    if let ZkSyncTx::Swap(swap) = tx {
+        schemes.push(swap.orders.0.signature.scheme);
+        schemes.push(swap.orders.1.signature.scheme);
+    }
+
+    match schemes.into_iter().find(|scheme| !scheme.is_supported()) {
+        Some(scheme) => Err(SubmitError::IncorrectTx(format!(
+            "Signature scheme {:?} is not supported",
+            scheme
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Counts the responses of the mempool to the submitted transactions by the `TxAddError` variant.
+fn report_mempool_outcome(kind: &'static str, result: &Result<(), TxAddError>) {
+    let outcome = match result {
+        Ok(()) => "accepted",
+        Err(TxAddError::NonceMismatch) => "nonce_mismatch",
+        Err(TxAddError::IncorrectTx(_)) => "incorrect_tx",
+        Err(TxAddError::TxFeeTooLow) => "tx_fee_too_low",
+        Err(TxAddError::TxBatchFeeTooLow) => "tx_batch_fee_too_low",
+        Err(TxAddError::EIP1271SignatureVerificationFail) => "eip1271_signature_verification_fail",
+        Err(TxAddError::MissingEthSignature) => "missing_eth_signature",
+        Err(TxAddError::IncorrectEthSignature) => "incorrect_eth_signature",
+        Err(TxAddError::ChangePkNotAuthorized) => "change_pk_not_authorized",
+        Err(TxAddError::Other) => "other",
+        Err(TxAddError::DbError) => "db_error",
+        Err(TxAddError::EmptyBatch) => "empty_batch",
+        Err(TxAddError::BatchTooBig) => "batch_too_big",
+        Err(TxAddError::BatchWithdrawalsOverload) => "batch_withdrawals_overload",
+        Err(TxAddError::EthSignaturesLimitExceeded) => "eth_signatures_limit_exceeded",
+    };
+    metrics::increment_counter!("api.mempool.submit_outcome", "kind" => kind, "outcome" => outcome);
+}
+
 async fn send_verify_request_and_recv(
     request: VerifySignatureRequest,
     mut req_channel: mpsc::Sender<VerifySignatureRequest>,
-    receiver: oneshot::Receiver<Result<VerifiedTx, TxAddError>>,
+    receiver: oneshot::Receiver<Result<VerifiedTx, SignatureCheckError>>,
 ) -> Result<VerifiedTx, SubmitError> {
+    let deadline = request.response.deadline();
     // Send the check request.
     req_channel
         .send(request)
         .await
         .map_err(SubmitError::internal)?;
     // Wait for the check result.
-    receiver
+    deadline
+        .wait(receiver)
         .await
+        .ok_or(SubmitError::Timeout)?
         .map_err(|err| internal_error!(err))?
-        .map_err(SubmitError::TxAdd)
+        .map_err(SubmitError::from)
 }
 
 /// Send a request for Ethereum signature verification and wait for the response.
 /// If `msg_to_sign` is not `None`, then the signature must be present.
+#[allow(clippy::too_many_arguments)]
 async fn verify_tx_info_message_signature(
     tx: &ZkSyncTx,
     tx_sender: Address,
@@ -1041,6 +1625,7 @@ async fn verify_tx_info_message_signature(
     signature: Option<TxEthSignature>,
     msg_to_sign: Option<Vec<u8>>,
     req_channel: mpsc::Sender<VerifySignatureRequest>,
+    deadline: Deadline,
 ) -> Result<VerifiedTx, SubmitError> {
     if matches!(
         (account_type, signature.clone(), msg_to_sign.clone()),
@@ -1075,7 +1660,7 @@ async fn verify_tx_info_message_signature(
         _ => None,
     };
 
-    let (sender, receiever) = oneshot::channel();
+    let (sender, receiever) = deadline_channel(deadline);
 
     let request = VerifySignatureRequest {
         data: RequestData::Tx(TxRequest {
@@ -1097,6 +1682,7 @@ async fn verify_tx_info_message_signature(
 /// Unlike in case of `verify_tx_info_message_signature`, we do not require
 /// every transaction from the batch to be signed. The signature must be obtained
 /// through signing a human-readable message with accordance to zkSync protocol.
+#[allow(clippy::too_many_arguments)]
 async fn verify_txs_batch_signature(
     batch: Vec<TxWithSignature>,
     senders: Vec<Address>,
@@ -1105,6 +1691,7 @@ async fn verify_txs_batch_signature(
     batch_sign_data: Option<EthBatchSignData>,
     msgs_to_sign: Vec<Option<Vec<u8>>>,
     req_channel: mpsc::Sender<VerifySignatureRequest>,
+    deadline: Deadline,
 ) -> Result<VerifiedTx, SubmitError> {
     // This hashset holds addresses that have performed a CREATE2 ChangePubKey
     // within this batch, so that we don't check ETH signatures on their transactions
@@ -1173,7 +1760,7 @@ async fn verify_txs_batch_signature(
         });
     }
 
-    let (sender, receiver) = oneshot::channel();
+    let (sender, receiver) = deadline_channel(deadline);
 
     let request = VerifySignatureRequest {
         data: RequestData::Batch(BatchRequest {
@@ -1191,9 +1778,10 @@ async fn verify_txs_batch_signature(
 /// Scales the fee provided by user up to check whether the provided fee is enough to cover our expenses for
 /// maintaining the protocol.
 ///
-/// We calculate both `provided_fee * 1.05` and `provided_fee + 1 cent` and choose the maximum.
-/// This is required since the price may change between signing the transaction and sending it to the server.
-fn scale_user_fee_up(provided_total_usd_fee: BigDecimal) -> BigDecimal {
+/// We calculate both `provided_fee` increased by the tolerance percent and `provided_fee + 1 cent`
+/// and choose the maximum. This is required since the price may change between signing the transaction
+/// and sending it to the server.
+fn scale_user_fee_up(provided_total_usd_fee: BigDecimal, tolerance_percent: u32) -> BigDecimal {
     let one_cent = BigDecimal::from_str("0.01").unwrap();
 
     // This formula is needed when the fee is really small.
@@ -1208,9 +1796,10 @@ fn scale_user_fee_up(provided_total_usd_fee: BigDecimal) -> BigDecimal {
         return scaled_by_two_provided_fee_in_usd;
     }
 
-    // Scale by 5%.
-    let scaled_percent_provided_fee_in_usd =
-        provided_total_usd_fee.clone() * BigDecimal::from(105u32) / BigDecimal::from(100u32);
+    // Scale by the tolerance percent.
+    let scaled_percent_provided_fee_in_usd = provided_total_usd_fee.clone()
+        * BigDecimal::from(100 + tolerance_percent)
+        / BigDecimal::from(100u32);
 
     // Scale by 1 cent.
     let scaled_one_cent_provided_fee_in_usd = provided_total_usd_fee + one_cent;
@@ -1231,7 +1820,7 @@ mod tests {
         let provided_fee = BigDecimal::from_str("0.005").unwrap();
         let provided_fee_scaled_by_two = BigDecimal::from_str("0.01").unwrap();
 
-        let scaled_fee = scale_user_fee_up(provided_fee);
+        let scaled_fee = scale_user_fee_up(provided_fee, 5);
 
         assert_eq!(provided_fee_scaled_by_two, scaled_fee);
     }
@@ -1241,7 +1830,7 @@ mod tests {
         let provided_fee = BigDecimal::from_str("0.015").unwrap();
         let provided_fee_scaled_by_cent = BigDecimal::from_str("0.025").unwrap();
 
-        let scaled_fee = scale_user_fee_up(provided_fee);
+        let scaled_fee = scale_user_fee_up(provided_fee, 5);
 
         assert_eq!(provided_fee_scaled_by_cent, scaled_fee);
     }
@@ -1251,8 +1840,14 @@ mod tests {
         let provided_fee = BigDecimal::from_str("0.30").unwrap();
         let provided_fee_scaled_by_five_percent = BigDecimal::from_str("0.315").unwrap();
 
-        let scaled_fee = scale_user_fee_up(provided_fee);
+        let scaled_fee = scale_user_fee_up(provided_fee.clone(), 5);
 
         assert_eq!(provided_fee_scaled_by_five_percent, scaled_fee);
+
+        // The tolerance is configurable.
+        let provided_fee_scaled_by_ten_percent = BigDecimal::from_str("0.33").unwrap();
+        let scaled_fee = scale_user_fee_up(provided_fee, 10);
+
+        assert_eq!(provided_fee_scaled_by_ten_percent, scaled_fee);
     }
 }
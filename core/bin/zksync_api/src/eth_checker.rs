@@ -2,26 +2,55 @@
 //! onchain `ChangePubKey` authorization or EIP1271 signature
 //! verification.
 
-use web3::{contract::Options, types::Address};
-use zksync_contracts::eip1271_contract;
+use std::time::{Duration, Instant};
+
+use ethabi::RawLog;
+use serde::Serialize;
+use web3::{
+    contract::Options,
+    types::{Address, BlockNumber, FilterBuilder, H256, U256},
+};
+use zksync_contracts::{eip1271_contract, zksync_contract};
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_types::{
     tx::EIP1271Signature,
     {Nonce, PubKeyHash},
 };
 
+use crate::utils::shared_lru_cache::SharedLruCache;
+
 /// isValidSignature return value according to EIP1271 standard
 /// bytes4(keccak256("isValidSignature(bytes32,bytes)")
 pub const EIP1271_SUCCESS_RETURN_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
 
+/// Maximum number of the addresses the types of the signers are remembered for.
+const SIGNER_TYPES_CACHE_CAPACITY: usize = 10_000;
+/// Period the type of the signer is cached for, the contract may be deployed to the address
+/// of the externally owned account later, e.g. the counterfactual smart wallet.
+const SIGNER_TYPE_CACHE_PERIOD: Duration = Duration::from_secs(600);
+
+/// Type of the Ethereum account, which determines how its signatures are verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EthSignerType {
+    /// Externally owned account, the signer is recovered from the ECDSA signature.
+    Eoa,
+    /// Smart contract wallet, the signature is checked by its EIP1271 `isValidSignature`.
+    Contract,
+}
+
 #[derive(Clone)]
 pub struct EthereumChecker {
     client: EthereumGateway,
+    signer_types: SharedLruCache<Address, (EthSignerType, Instant)>,
 }
 
 impl EthereumChecker {
     pub fn new(client: EthereumGateway) -> Self {
-        Self { client }
+        Self {
+            client,
+            signer_types: SharedLruCache::new(SIGNER_TYPES_CACHE_CAPACITY),
+        }
     }
 
     /// Transforms the message into an array expected by EIP-1271 standard.
@@ -34,6 +63,26 @@ impl EthereumChecker {
         tiny_keccak::keccak256(&bytes)
     }
 
+    /// Resolves the type of the account by the presence of the deployed code,
+    /// the type is cached for the `SIGNER_TYPE_CACHE_PERIOD`.
+    pub async fn signer_type(&self, address: Address) -> Result<EthSignerType, anyhow::Error> {
+        if let Some((signer_type, loaded_at)) = self.signer_types.get(&address) {
+            if loaded_at.elapsed() < SIGNER_TYPE_CACHE_PERIOD {
+                return Ok(signer_type);
+            }
+        }
+
+        let code = self.client.get_code(address).await?;
+        let signer_type = if code.0.is_empty() {
+            EthSignerType::Eoa
+        } else {
+            EthSignerType::Contract
+        };
+        self.signer_types
+            .insert(address, (signer_type, Instant::now()));
+        Ok(signer_type)
+    }
+
     pub async fn is_eip1271_signature_correct(
         &self,
         address: Address,
@@ -74,8 +123,18 @@ impl EthereumChecker {
         nonce: Nonce,
         pub_key_hash: &PubKeyHash,
     ) -> Result<bool, anyhow::Error> {
-        let auth_fact: Vec<u8> = self
-            .client
+        let auth_fact = self.pubkey_auth_fact(address, nonce).await?;
+        Ok(auth_fact.as_slice() == tiny_keccak::keccak256(&pub_key_hash.data[..]))
+    }
+
+    /// Loads the fact of the onchain `ChangePubKey` authorization set by `setAuthPubkeyHash`,
+    /// the fact is empty if the authorization is absent.
+    pub async fn pubkey_auth_fact(
+        &self,
+        address: Address,
+        nonce: Nonce,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        self.client
             .call_main_contract_function(
                 "authFacts",
                 (address, u64::from(*nonce)),
@@ -84,8 +143,54 @@ impl EthereumChecker {
                 None,
             )
             .await
-            .map_err(|e| anyhow::format_err!("Failed to query contract authFacts: {}", e))?;
-        Ok(auth_fact.as_slice() == tiny_keccak::keccak256(&pub_key_hash.data[..]))
+            .map_err(|e| anyhow::format_err!("Failed to query contract authFacts: {}", e))
+    }
+
+    /// Finds the latest `FactAuth` event of the account for the given nonce within the range
+    /// of the Ethereum blocks, returns the number of its block and the hash of its transaction.
+    pub async fn pubkey_auth_event(
+        &self,
+        contract_address: Address,
+        address: Address,
+        nonce: Nonce,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Option<(u64, H256)>, anyhow::Error> {
+        let contract = zksync_contract();
+        let event = contract.event("FactAuth").expect("main contract abi error");
+        let filter = FilterBuilder::default()
+            .address(vec![contract_address])
+            .from_block(BlockNumber::Number(from_block.into()))
+            .to_block(BlockNumber::Number(to_block.into()))
+            .topics(
+                Some(vec![event.signature()]),
+                Some(vec![H256::from(address)]),
+                None,
+                None,
+            )
+            .build();
+
+        let mut latest_event = None;
+        for log in self.client.logs(filter).await? {
+            let (block_number, tx_hash) = match (log.block_number, log.transaction_hash) {
+                (Some(block_number), Some(tx_hash)) => (block_number.as_u64(), tx_hash),
+                // The log is pending.
+                _ => continue,
+            };
+            let parsed_log = event.parse_log(RawLog {
+                topics: log.topics,
+                data: log.data.0,
+            })?;
+            let log_nonce = parsed_log
+                .params
+                .into_iter()
+                .find(|param| param.name == "nonce")
+                .and_then(|param| param.value.into_uint());
+            if log_nonce == Some(U256::from(*nonce)) {
+                latest_event = latest_event.max(Some((block_number, tx_hash)));
+            }
+        }
+        Ok(latest_event)
     }
 }
 
@@ -0,0 +1,105 @@
+//! Discount of the fees of the large batches.
+//!
+//! The gas cost of every operation includes its share of the constant cost of the commit,
+//! execute and prove transactions, amortized per chunk. The large batches are committed along
+//! with the other operations of the block anyway, so pricing them as the sum of the individual
+//! fees overcharges for this overhead. Once the batch has more transactions than the threshold,
+//! the overhead of every its transaction is discounted. The fast withdrawals are not discounted,
+//! since they pay for the whole block they seal.
+
+// External deps
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+// Workspace deps
+use zksync_types::OutputFeeType;
+// Local deps
+use crate::fee_ticker::constants::AMORTIZED_COST_PER_CHUNK;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct BatchDiscountConfig {
+    /// Batches with more transactions are discounted, the discount is disabled if zero.
+    pub min_txs: u32,
+    /// Part of the per-transaction overhead discounted, in percent.
+    pub overhead_discount_percent: u32,
+}
+
+impl From<&zksync_config::TickerConfig> for BatchDiscountConfig {
+    fn from(config: &zksync_config::TickerConfig) -> Self {
+        Self {
+            min_txs: config.batch_discount_min_txs,
+            overhead_discount_percent: config.batch_overhead_discount_percent,
+        }
+    }
+}
+
+impl BatchDiscountConfig {
+    /// Whether the batch of the given size is discounted.
+    pub fn applies(&self, batch_size: usize) -> bool {
+        self.min_txs != 0 && batch_size > self.min_txs as usize
+    }
+
+    /// Returns the gas cost of the operation in the discounted batch.
+    pub fn discounted_gas_amount(
+        &self,
+        fee_type: OutputFeeType,
+        gas_tx_amount: BigUint,
+        op_chunks: &BigUint,
+    ) -> BigUint {
+        if matches!(
+            fee_type,
+            OutputFeeType::FastWithdraw | OutputFeeType::FastWithdrawNFT
+        ) {
+            return gas_tx_amount;
+        }
+        let overhead = op_chunks * BigUint::from(AMORTIZED_COST_PER_CHUNK);
+        let discount = overhead * BigUint::from(self.overhead_discount_percent.min(100))
+            / BigUint::from(100u32);
+        if discount >= gas_tx_amount {
+            BigUint::from(0u32)
+        } else {
+            gas_tx_amount - discount
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fee_ticker::constants::BASE_TRANSFER_COST;
+    use zksync_types::TransferOp;
+
+    #[test]
+    fn overhead_discount() {
+        let config = BatchDiscountConfig {
+            min_txs: 10,
+            overhead_discount_percent: 50,
+        };
+        assert!(!config.applies(10));
+        assert!(config.applies(11));
+        assert!(!BatchDiscountConfig {
+            min_txs: 0,
+            ..config
+        }
+        .applies(100));
+
+        let chunks = BigUint::from(TransferOp::CHUNKS);
+        let overhead = AMORTIZED_COST_PER_CHUNK * TransferOp::CHUNKS as u64;
+        assert_eq!(
+            config.discounted_gas_amount(
+                OutputFeeType::Transfer,
+                BASE_TRANSFER_COST.into(),
+                &chunks
+            ),
+            BigUint::from(BASE_TRANSFER_COST - overhead / 2)
+        );
+        // The fast withdrawals are not discounted.
+        assert_eq!(
+            config.discounted_gas_amount(
+                OutputFeeType::FastWithdraw,
+                BASE_TRANSFER_COST.into(),
+                &chunks
+            ),
+            BigUint::from(BASE_TRANSFER_COST)
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Scaling of the fees with the mempool congestion.
+//!
+//! Once the mempool holds more transactions than the threshold, the fees are multiplied
+//! proportionally to the excess: twice as many transactions as the threshold double the fees,
+//! up to the maximum multiplier. The multiplier follows the growing congestion right away to
+//! discourage the spam during the load spikes, and decays back to the baseline gradually as
+//! the mempool drains, so the fees don't jump back and forth with the queue.
+//!
+//! The mempool size is checked by the fee requests themselves, at most once per the interval.
+
+// Built-in deps
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+// External deps
+use num::{rational::Ratio, BigUint};
+use serde::{Deserialize, Serialize};
+
+/// Multiplier of the fees without the congestion, in percent.
+const BASE_MULTIPLIER_PERCENT: u32 = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CongestionConfig {
+    /// Mempool size the fees start growing from, the scaling is disabled if zero.
+    pub mempool_threshold: u32,
+    /// Maximum multiplier of the fees, in percent.
+    pub max_multiplier_percent: u32,
+    /// Part of the excess multiplier dropped per update once the mempool drains, in percent.
+    pub decay_percent: u32,
+    pub update_interval: Duration,
+}
+
+impl From<&zksync_config::TickerConfig> for CongestionConfig {
+    fn from(config: &zksync_config::TickerConfig) -> Self {
+        Self {
+            mempool_threshold: config.congestion_mempool_threshold,
+            max_multiplier_percent: config.congestion_max_multiplier_percent,
+            decay_percent: config.congestion_decay_percent,
+            update_interval: config.congestion_update_interval(),
+        }
+    }
+}
+
+impl CongestionConfig {
+    /// Multiplier of the fees matching the mempool size, in percent.
+    fn target_multiplier_percent(&self, mempool_size: u32) -> u32 {
+        if mempool_size <= self.mempool_threshold {
+            return BASE_MULTIPLIER_PERCENT;
+        }
+        let excess = u64::from(mempool_size - self.mempool_threshold);
+        let multiplier = u64::from(BASE_MULTIPLIER_PERCENT)
+            + excess * u64::from(BASE_MULTIPLIER_PERCENT) / u64::from(self.mempool_threshold);
+        multiplier.min(u64::from(
+            self.max_multiplier_percent.max(BASE_MULTIPLIER_PERCENT),
+        )) as u32
+    }
+}
+
+#[derive(Debug)]
+struct CongestionState {
+    multiplier_percent: u32,
+    last_update: Option<Instant>,
+}
+
+/// Congestion multiplier of the fees, shared by the clones of the ticker.
+#[derive(Debug, Clone)]
+pub struct CongestionMultiplier {
+    config: CongestionConfig,
+    state: Arc<Mutex<CongestionState>>,
+}
+
+impl CongestionMultiplier {
+    pub fn new(config: CongestionConfig) -> Self {
+        Self {
+            config,
+            state: Arc::new(Mutex::new(CongestionState {
+                multiplier_percent: BASE_MULTIPLIER_PERCENT,
+                last_update: None,
+            })),
+        }
+    }
+
+    /// Whether the multiplier is due for the update. If so, the update is considered started,
+    /// so the concurrent requests don't check the mempool as well.
+    pub fn start_update(&self) -> bool {
+        if self.config.mempool_threshold == 0 {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap();
+        let is_due = state.last_update.map_or(true, |last_update| {
+            last_update.elapsed() >= self.config.update_interval
+        });
+        if is_due {
+            state.last_update = Some(Instant::now());
+        }
+        is_due
+    }
+
+    /// Updates the multiplier with the current mempool size, returns the new multiplier in percent.
+    pub fn update(&self, mempool_size: u32) -> u32 {
+        let target = self.config.target_multiplier_percent(mempool_size);
+        let mut state = self.state.lock().unwrap();
+        state.multiplier_percent = if target >= state.multiplier_percent {
+            target
+        } else {
+            let excess = state.multiplier_percent - target;
+            let decay = (excess * self.config.decay_percent.min(100) / 100).max(1);
+            state.multiplier_percent - decay
+        };
+        state.multiplier_percent
+    }
+
+    pub fn multiplier(&self) -> Ratio<BigUint> {
+        let multiplier_percent = self.state.lock().unwrap().multiplier_percent;
+        Ratio::new(
+            BigUint::from(multiplier_percent),
+            BigUint::from(BASE_MULTIPLIER_PERCENT),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multiplier(mempool_threshold: u32) -> CongestionMultiplier {
+        CongestionMultiplier::new(CongestionConfig {
+            mempool_threshold,
+            max_multiplier_percent: 300,
+            decay_percent: 50,
+            update_interval: Duration::from_secs(3600),
+        })
+    }
+
+    #[test]
+    fn congestion_feedback() {
+        let congestion = multiplier(1000);
+        assert_eq!(congestion.multiplier(), Ratio::from_integer(1u32.into()));
+
+        // The updates are throttled.
+        assert!(congestion.start_update());
+        assert!(!congestion.start_update());
+
+        assert_eq!(congestion.update(500), 100);
+        assert_eq!(congestion.update(1500), 150);
+        // The multiplier is capped.
+        assert_eq!(congestion.update(10000), 300);
+        assert_eq!(congestion.multiplier(), Ratio::from_integer(3u32.into()));
+
+        // Once the mempool drains, the multiplier decays back to the baseline.
+        assert_eq!(congestion.update(0), 200);
+        assert_eq!(congestion.update(0), 150);
+        assert_eq!(congestion.update(2000), 200);
+        assert_eq!(congestion.update(0), 150);
+        assert_eq!(congestion.update(0), 125);
+        for _ in 0..10 {
+            congestion.update(0);
+        }
+        assert_eq!(congestion.update(0), 100);
+    }
+
+    #[test]
+    fn disabled_congestion() {
+        let congestion = multiplier(0);
+        assert!(!congestion.start_update());
+        assert_eq!(congestion.multiplier(), Ratio::from_integer(1u32.into()));
+    }
+}
@@ -0,0 +1,152 @@
+//! Smoothing of the gas price the fees are calculated with.
+//!
+//! The fees follow the L1 gas price, so every gas spike changes the quotes, and the transactions
+//! signed against the previous quote may not pay enough by the time they are submitted. So the
+//! fees are calculated with the exponential moving average of the gas price. Besides, the new
+//! gas price is bounded from below by the part of the average before it's folded in, so a single
+//! dip can lower the fees only so far. The rises aren't bounded, so the fees keep up with the
+//! sustained gas spikes.
+//!
+//! The gas price is folded in by the fee requests themselves, at most once per the interval.
+//! The zero gas prices are ignored, since they mean the actual gas price isn't known yet.
+
+// Built-in deps
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+// External deps
+use num::{BigUint, Zero};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct GasPriceConfig {
+    /// Weight of the new gas price in the average, in percent. 100 disables the smoothing.
+    pub smoothing_percent: u32,
+    /// Maximum drop of the new gas price below the average, in percent.
+    /// The new gas prices are not clamped if zero.
+    pub max_deviation_percent: u32,
+    pub update_interval: Duration,
+}
+
+impl From<&zksync_config::TickerConfig> for GasPriceConfig {
+    fn from(config: &zksync_config::TickerConfig) -> Self {
+        Self {
+            smoothing_percent: config.gas_price_smoothing_percent,
+            max_deviation_percent: config.gas_price_max_deviation_percent,
+            update_interval: config.gas_price_update_interval(),
+        }
+    }
+}
+
+impl GasPriceConfig {
+    /// Bounds the new gas price from below by the part of the average.
+    fn clamp(&self, gas_price: BigUint, average: &BigUint) -> BigUint {
+        if self.max_deviation_percent == 0 || self.max_deviation_percent >= 100 {
+            return gas_price;
+        }
+        let min = average * BigUint::from(100 - self.max_deviation_percent) / BigUint::from(100u32);
+        gas_price.max(min)
+    }
+}
+
+#[derive(Debug)]
+struct GasPriceState {
+    average: BigUint,
+    last_update: Instant,
+}
+
+/// Smoothed gas price, shared by the clones of the ticker.
+#[derive(Debug, Clone)]
+pub struct GasPriceSmoother {
+    config: GasPriceConfig,
+    state: Arc<Mutex<Option<GasPriceState>>>,
+}
+
+impl GasPriceSmoother {
+    pub fn new(config: GasPriceConfig) -> Self {
+        Self {
+            config,
+            state: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Folds the current gas price into the average once the update is due,
+    /// returns the smoothed gas price.
+    pub fn update(&self, gas_price: BigUint) -> BigUint {
+        let mut guard = self.state.lock().unwrap();
+        let state = match guard.as_mut() {
+            Some(state) if gas_price.is_zero() => return state.average.clone(),
+            Some(state) => state,
+            None if gas_price.is_zero() => return gas_price,
+            None => {
+                *guard = Some(GasPriceState {
+                    average: gas_price.clone(),
+                    last_update: Instant::now(),
+                });
+                return gas_price;
+            }
+        };
+        if state.last_update.elapsed() < self.config.update_interval {
+            return state.average.clone();
+        }
+
+        let gas_price = self.config.clamp(gas_price, &state.average);
+        let weight = self.config.smoothing_percent.min(100);
+        state.average = (gas_price * BigUint::from(weight)
+            + &state.average * BigUint::from(100 - weight))
+            / BigUint::from(100u32);
+        state.last_update = Instant::now();
+        state.average.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn smoother(smoothing_percent: u32, max_deviation_percent: u32) -> GasPriceSmoother {
+        GasPriceSmoother::new(GasPriceConfig {
+            smoothing_percent,
+            max_deviation_percent,
+            update_interval: Duration::from_secs(0),
+        })
+    }
+
+    #[test]
+    fn gas_price_smoothing() {
+        let gas_price = smoother(20, 50);
+        // The first gas price is taken as is.
+        assert_eq!(gas_price.update(100u32.into()), BigUint::from(100u32));
+        assert_eq!(gas_price.update(150u32.into()), BigUint::from(110u32));
+        // The rises aren't clamped.
+        assert_eq!(gas_price.update(610u32.into()), BigUint::from(210u32));
+        // The dip is clamped to 50% below the average.
+        assert_eq!(gas_price.update(10u32.into()), BigUint::from(189u32));
+
+        // Without the smoothing and clamping, the gas price is used as is.
+        let gas_price = smoother(100, 0);
+        assert_eq!(gas_price.update(100u32.into()), BigUint::from(100u32));
+        assert_eq!(gas_price.update(10_000u32.into()), BigUint::from(10_000u32));
+        assert_eq!(gas_price.update(1u32.into()), BigUint::from(1u32));
+    }
+
+    #[test]
+    fn zero_gas_prices_ignored() {
+        let gas_price = smoother(20, 50);
+        // The actual gas price isn't known yet.
+        assert_eq!(gas_price.update(0u32.into()), BigUint::from(0u32));
+        assert_eq!(gas_price.update(100u32.into()), BigUint::from(100u32));
+        assert_eq!(gas_price.update(0u32.into()), BigUint::from(100u32));
+        assert_eq!(gas_price.update(150u32.into()), BigUint::from(110u32));
+    }
+
+    #[test]
+    fn throttled_updates() {
+        let gas_price = GasPriceSmoother::new(GasPriceConfig {
+            smoothing_percent: 100,
+            max_deviation_percent: 0,
+            update_interval: Duration::from_secs(3600),
+        });
+        assert_eq!(gas_price.update(100u32.into()), BigUint::from(100u32));
+        assert_eq!(gas_price.update(200u32.into()), BigUint::from(100u32));
+    }
+}
@@ -12,10 +12,11 @@ use std::time::Duration;
 
 // External deps
 use bigdecimal::BigDecimal;
+use chrono::Utc;
 use num::{
     rational::Ratio,
     traits::{Inv, Pow},
-    BigUint, CheckedDiv, Zero,
+    BigUint, CheckedDiv, ToPrimitive, Zero,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -24,7 +25,8 @@ use tokio::time::Instant;
 
 // Workspace deps
 
-use zksync_config::configs::ticker::TokenPriceSource;
+use zksync_config::configs::ticker::{SubsidyRule, TokenPriceSource};
+use zksync_eth_client::EthereumGateway;
 use zksync_storage::ConnectionPool;
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
@@ -35,18 +37,27 @@ use zksync_types::{
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 
 // Local deps
+use crate::fee_ticker::batch_discount::BatchDiscountConfig;
+use crate::fee_ticker::congestion::{CongestionConfig, CongestionMultiplier};
 use crate::fee_ticker::constants::AMORTIZED_COST_PER_CHUNK;
+use crate::fee_ticker::gas_price::{GasPriceConfig, GasPriceSmoother};
 pub use crate::fee_ticker::ticker_info::{FeeTickerInfo, TickerInfo};
 use crate::fee_ticker::validator::FeeTokenValidator;
 use crate::fee_ticker::{
     ticker_api::{
-        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, FeeTickerAPI, TickerApi,
-        CONNECTION_TIMEOUT,
+        aggregator::AggregatedPriceAPI, chainlink::ChainlinkPriceAPI, coingecko::CoinGeckoAPI,
+        coinmarkercap::CoinMarketCapAPI, price_guard::PriceGuardConfig, uniswap::UniswapPriceAPI,
+        FeeTickerAPI, TickerApi, TokenPriceAPI, CONNECTION_TIMEOUT,
     },
     validator::{watcher::UniswapTokenWatcher, MarketUpdater},
 };
 
+pub mod batch_discount;
+pub mod congestion;
 mod constants;
+pub mod gas_price;
+pub mod price_cache;
+mod subsidy;
 mod ticker_api;
 pub(crate) mod ticker_info;
 pub mod validator;
@@ -143,6 +154,10 @@ pub struct TickerConfig {
     pub scale_fee_coefficient: Ratio<BigUint>,
     pub max_blocks_to_aggregate: u32,
     pub subsidy_cpk_price_usd: Ratio<BigUint>,
+    pub subsidy_rules: Vec<SubsidyRule>,
+    pub congestion: CongestionConfig,
+    pub batch_discount: BatchDiscountConfig,
+    pub gas_price: GasPriceConfig,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -151,11 +166,23 @@ pub enum TokenPriceRequestType {
     USDForOneToken,
 }
 
+/// Market data the fee is calculated from, recorded along with the served fee quotes.
+#[derive(Debug, Clone)]
+pub struct FeeInputs {
+    pub token_id: TokenId,
+    pub gas_price_wei: BigUint,
+    /// Price of the whole fee token, not the smallest unit of it.
+    pub token_usd_price: Ratio<BigUint>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ResponseFee {
     pub normal_fee: Fee,
     pub subsidized_fee: Fee,
     pub subsidy_size_usd: Ratio<BigUint>,
+    /// Rule the fee is subsidized by, `None` for the subsidy of the CREATE2 ChangePubKey.
+    pub subsidy_rule: Option<SubsidyRule>,
+    pub inputs: FeeInputs,
 }
 
 #[derive(Debug, Clone)]
@@ -163,6 +190,9 @@ pub struct ResponseBatchFee {
     pub normal_fee: BatchFee,
     pub subsidized_fee: BatchFee,
     pub subsidy_size_usd: Ratio<BigUint>,
+    /// Rule the fee is subsidized by, `None` for the subsidy of the CREATE2 ChangePubKey.
+    pub subsidy_rule: Option<SubsidyRule>,
+    pub inputs: FeeInputs,
 }
 
 #[derive(Debug, Error)]
@@ -194,6 +224,8 @@ pub struct FeeTicker {
     info: Box<dyn FeeTickerInfo>,
     config: TickerConfig,
     validator: FeeTokenValidator,
+    congestion: CongestionMultiplier,
+    gas_price: GasPriceSmoother,
 }
 
 const CPK_CREATE2_FEE_TYPE: OutputFeeType = OutputFeeType::ChangePubKey(
@@ -206,13 +238,18 @@ const TOKEN_INVALIDATE_CACHE: Duration = Duration::from_secs(5 * 60);
 #[must_use]
 pub fn run_updaters(
     db_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
     config: &zksync_config::TickerConfig,
 ) -> Vec<JoinHandle<()>> {
     let cache = (db_pool.clone(), TokenDBCache::new(TOKEN_INVALIDATE_CACHE));
 
     let watcher = UniswapTokenWatcher::new(config.uniswap_url.clone());
 
-    let updater = MarketUpdater::new(cache, watcher);
+    let updater = MarketUpdater::new(
+        cache,
+        watcher,
+        BigDecimal::try_from(config.liquidity_volume).expect("Valid f64 for decimal"),
+    );
     let mut tasks = vec![tokio::spawn(
         updater.keep_updated(config.token_market_update_time),
     )];
@@ -221,26 +258,41 @@ pub fn run_updaters(
         .connect_timeout(CONNECTION_TIMEOUT)
         .build()
         .expect("Failed to build reqwest::Client");
-    let (price_source, base_url) = config.price_source();
-    let price_updater = match price_source {
-        TokenPriceSource::CoinMarketCap => {
-            let token_price_api =
-                CoinMarketCapAPI::new(client, base_url.parse().expect("Correct CoinMarketCap url"));
-
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
-            tokio::spawn(ticker_api.keep_price_updated())
-        }
-
-        TokenPriceSource::CoinGecko => tokio::spawn(async move {
-            let token_price_api =
-                CoinGeckoAPI::new(client, base_url.parse().expect("Correct CoinGecko url"))
+    let price_sources = config.price_sources();
+    let max_price_age = config.max_price_age();
+    let chainlink_feeds = config.chainlink_feeds();
+    let price_guard_config = PriceGuardConfig::from(config);
+    let price_updater = tokio::spawn(async move {
+        let mut token_price_apis = Vec::new();
+        for (price_source, base_url) in price_sources {
+            let token_price_api: Box<dyn TokenPriceAPI + Send + Sync> = match price_source {
+                TokenPriceSource::CoinMarketCap => Box::new(CoinMarketCapAPI::new(
+                    client.clone(),
+                    base_url.parse().expect("Correct CoinMarketCap url"),
+                )),
+                TokenPriceSource::CoinGecko => Box::new(
+                    CoinGeckoAPI::new(
+                        client.clone(),
+                        base_url.parse().expect("Correct CoinGecko url"),
+                    )
                     .await
-                    .expect("failed to init CoinGecko client");
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
+                    .expect("failed to init CoinGecko client"),
+                ),
+                TokenPriceSource::Uniswap => {
+                    Box::new(UniswapPriceAPI::new(client.clone(), base_url))
+                }
+                TokenPriceSource::Chainlink => Box::new(ChainlinkPriceAPI::new(
+                    eth_gateway.clone(),
+                    chainlink_feeds.clone(),
+                )),
+            };
+            token_price_apis.push((format!("{:?}", price_source), token_price_api));
+        }
+        let token_price_api = AggregatedPriceAPI::new(token_price_apis, max_price_age);
+        let ticker_api = TickerApi::new(db_pool, token_price_api, price_guard_config);
 
-            ticker_api.keep_price_updated().await;
-        }),
-    };
+        ticker_api.keep_price_updated().await;
+    });
     tasks.push(price_updater);
     tasks
 }
@@ -251,10 +303,14 @@ impl FeeTicker {
         config: TickerConfig,
         validator: FeeTokenValidator,
     ) -> Self {
+        let congestion = CongestionMultiplier::new(config.congestion);
+        let gas_price = GasPriceSmoother::new(config.gas_price);
         Self {
             info,
             config,
             validator,
+            congestion,
+            gas_price,
         }
     }
 
@@ -275,6 +331,10 @@ impl FeeTicker {
             ),
             max_blocks_to_aggregate,
             subsidy_cpk_price_usd: config.subsidy_cpk_price_usd(),
+            subsidy_rules: config.subsidy_rules(),
+            congestion: CongestionConfig::from(&config),
+            batch_discount: BatchDiscountConfig::from(&config),
+            gas_price: GasPriceConfig::from(&config),
         };
         let validator = FeeTokenValidator::new(
             cache,
@@ -294,6 +354,34 @@ impl FeeTicker {
         gas_price * BigUint::from(130u32) / BigUint::from(100u32)
     }
 
+    /// Returns the congestion multiplier of the fees, updating it once the update is due.
+    /// The mempool size can't be checked, the latest multiplier is used.
+    async fn congestion_multiplier(&self) -> Ratio<BigUint> {
+        if self.congestion.start_update() {
+            match self.info.mempool_size().await {
+                Ok(mempool_size) => {
+                    let multiplier_percent = self.congestion.update(mempool_size);
+                    metrics::gauge!(
+                        "ticker.congestion_multiplier",
+                        multiplier_percent as f64 / 100.0
+                    );
+                }
+                Err(err) => vlog::warn!("Can't check the mempool congestion: {}", err),
+            }
+        }
+        self.congestion.multiplier()
+    }
+
+    /// Returns the smoothed gas price the fees are calculated with.
+    async fn gas_price_wei(&self) -> anyhow::Result<BigUint> {
+        let gas_price_wei = self.gas_price.update(self.info.get_gas_price_wei().await?);
+        metrics::gauge!(
+            "ticker.smoothed_gas_price",
+            gas_price_wei.to_f64().unwrap_or_default()
+        );
+        Ok(gas_price_wei)
+    }
+
     pub async fn get_token_price(
         &self,
         token: TokenLike,
@@ -332,16 +420,25 @@ impl FeeTicker {
         let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
         let token = self.info.get_token(token).await?;
 
-        let gas_price_wei = self.info.get_gas_price_wei().await?;
+        let gas_price_wei = self.gas_price_wei().await?;
         let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
         let wei_price_usd = self.wei_price_usd().await?;
-        let token_usd_risk = self.token_usd_risk(&token).await?;
+        let token_usd_price = self.token_usd_price(&token).await?;
+        let token_usd_risk = self.token_usd_risk(&token, &token_usd_price)?;
+        let inputs = FeeInputs {
+            token_id: token.id,
+            gas_price_wei: gas_price_wei.clone(),
+            token_usd_price,
+        };
+
+        let congestion_multiplier = self.congestion_multiplier().await;
 
         let (fee_type, gas_tx_amount, op_chunks) = self.gas_tx_amount(tx_type, recipient).await?;
 
-        let zkp_fee = (zkp_cost_chunk * op_chunks) * &token_usd_risk;
-        let mut normal_gas_fee =
-            (&wei_price_usd * gas_tx_amount.clone() * scale_gas_price.clone()) * &token_usd_risk;
+        let zkp_fee = (zkp_cost_chunk * op_chunks) * &token_usd_risk * &congestion_multiplier;
+        let mut normal_gas_fee = (&wei_price_usd * gas_tx_amount.clone() * scale_gas_price.clone())
+            * &token_usd_risk
+            * &congestion_multiplier;
 
         // Increase fee only for L2 operations
         if matches!(
@@ -356,12 +453,51 @@ impl FeeTicker {
 
         let normal_fee = Fee::new(
             fee_type,
-            zkp_fee,
-            normal_gas_fee,
-            gas_tx_amount,
+            zkp_fee.clone(),
+            normal_gas_fee.clone(),
+            gas_tx_amount.clone(),
             gas_price_wei.clone(),
         );
 
+        let timestamp = Utc::now().timestamp() as u64;
+        let subsidy_rule = subsidy::find_rule(
+            &self.config.subsidy_rules,
+            fee_type,
+            &token,
+            recipient,
+            timestamp,
+        )
+        .cloned();
+
+        if let Some(rule) = subsidy_rule {
+            let token_price = self
+                .get_token_price(TokenLike::Id(token.id), TokenPriceRequestType::USDForOneWei)
+                .await?;
+            let token_price = big_decimal_to_ratio(&token_price).unwrap();
+
+            let subsidized_fee = Fee::new(
+                fee_type,
+                subsidy::discounted(zkp_fee, &rule),
+                subsidy::discounted(normal_gas_fee, &rule),
+                gas_tx_amount,
+                gas_price_wei,
+            );
+            let subsidy_size_usd = subsidy::subsidy_size_usd(
+                token_price,
+                &normal_fee.total_fee,
+                &subsidized_fee.total_fee,
+            );
+
+            metrics::histogram!("ticker.get_fee_from_ticker_in_wei", start.elapsed());
+            return Ok(ResponseFee {
+                normal_fee,
+                subsidized_fee,
+                subsidy_size_usd,
+                subsidy_rule: Some(rule),
+                inputs,
+            });
+        }
+
         if fee_type == CPK_CREATE2_FEE_TYPE {
             let token_price = self
                 .get_token_price(TokenLike::Id(token.id), TokenPriceRequestType::USDForOneWei)
@@ -384,16 +520,18 @@ impl FeeTicker {
                 BigUint::zero(),
             );
 
-            let subsidy_size_usd = if normal_fee.total_fee > subsidized_fee.total_fee {
-                token_price * (&normal_fee.total_fee - &subsidized_fee.total_fee)
-            } else {
-                Ratio::from(BigUint::from(0u32))
-            };
+            let subsidy_size_usd = subsidy::subsidy_size_usd(
+                token_price,
+                &normal_fee.total_fee,
+                &subsidized_fee.total_fee,
+            );
 
             return Ok(ResponseFee {
                 normal_fee,
                 subsidized_fee,
                 subsidy_size_usd,
+                subsidy_rule: None,
+                inputs,
             });
         }
 
@@ -402,6 +540,8 @@ impl FeeTicker {
             normal_fee: normal_fee.clone(),
             subsidized_fee: normal_fee,
             subsidy_size_usd: Ratio::from(BigUint::from(0u32)),
+            subsidy_rule: None,
+            inputs,
         })
     }
 
@@ -415,10 +555,17 @@ impl FeeTicker {
 
         let token = self.info.get_token(token).await?;
 
-        let gas_price_wei = self.info.get_gas_price_wei().await?;
+        let gas_price_wei = self.gas_price_wei().await?;
         let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
         let wei_price_usd = self.wei_price_usd().await?;
-        let token_usd_risk = self.token_usd_risk(&token).await?;
+        let token_usd_price = self.token_usd_price(&token).await?;
+        let token_usd_risk = self.token_usd_risk(&token, &token_usd_price)?;
+        let inputs = FeeInputs {
+            token_id: token.id,
+            gas_price_wei,
+            token_usd_price,
+        };
+        let congestion_multiplier = self.congestion_multiplier().await;
 
         let mut total_normal_gas_tx_amount = Ratio::from(BigUint::zero());
         let mut total_op_chunks = Ratio::from(BigUint::zero());
@@ -460,9 +607,39 @@ impl FeeTicker {
             &self.config.subsidy_cpk_price_usd / denom_part
         };
 
+        // The per-transaction overhead of the large batches is discounted.
+        let discount_overhead = self.config.batch_discount.applies(txs.len());
+        if discount_overhead {
+            metrics::increment_counter!("ticker.discounted_batches");
+        }
+        let mut batch_ops = Vec::with_capacity(txs.len());
         for (tx_type, recipient) in txs {
-            let (output_fee_type, gas_tx_amount, op_chunks) =
+            let (output_fee_type, mut gas_tx_amount, op_chunks) =
                 self.gas_tx_amount(tx_type, recipient).await?;
+            if discount_overhead {
+                gas_tx_amount = self.config.batch_discount.discounted_gas_amount(
+                    output_fee_type,
+                    gas_tx_amount,
+                    &op_chunks,
+                );
+            }
+            batch_ops.push((output_fee_type, gas_tx_amount, op_chunks, recipient));
+        }
+        let timestamp = Utc::now().timestamp() as u64;
+        let subsidy_rule = batch_ops
+            .iter()
+            .find_map(|&(output_fee_type, _, _, recipient)| {
+                subsidy::find_rule(
+                    &self.config.subsidy_rules,
+                    output_fee_type,
+                    &token,
+                    recipient,
+                    timestamp,
+                )
+            })
+            .cloned();
+
+        for (output_fee_type, gas_tx_amount, op_chunks, recipient) in batch_ops {
             // Increase fee only for L2 operations
             let gas_tx_amount: Ratio<BigUint> = if matches!(
                 output_fee_type,
@@ -475,11 +652,23 @@ impl FeeTicker {
             } else {
                 gas_tx_amount.into()
             };
+            // The fees grow with the congestion, except for the CREATE2 ChangePubKey subsidy
+            // which is fixed in USD.
+            let gas_tx_amount = gas_tx_amount * &congestion_multiplier;
+            let op_chunks = Ratio::from(op_chunks) * &congestion_multiplier;
 
             total_normal_gas_tx_amount += &gas_tx_amount;
             total_op_chunks += &op_chunks;
 
-            if output_fee_type == CPK_CREATE2_FEE_TYPE {
+            if let Some(rule) = &subsidy_rule {
+                if subsidy::rule_applies(rule, output_fee_type, &token, recipient, timestamp) {
+                    total_subsidized_gas_tx_amount += subsidy::discounted(gas_tx_amount, rule);
+                    total_subsidized_op_chunks += subsidy::discounted(op_chunks, rule);
+                } else {
+                    total_subsidized_gas_tx_amount += gas_tx_amount;
+                    total_subsidized_op_chunks += op_chunks;
+                }
+            } else if output_fee_type == CPK_CREATE2_FEE_TYPE {
                 // The subsidy cost contains only gas cost
                 total_subsidized_gas_tx_amount += &subsidized_gas_amount;
             } else {
@@ -504,17 +693,19 @@ impl FeeTicker {
             BatchFee::new(total_zkp_fee, total_gas_fee)
         };
 
-        let subsidy_size_usd = if normal_fee.total_fee > subsidized_fee.total_fee {
-            token_price * (&normal_fee.total_fee - &subsidized_fee.total_fee)
-        } else {
-            Ratio::from(BigUint::from(0u32))
-        };
+        let subsidy_size_usd = subsidy::subsidy_size_usd(
+            token_price,
+            &normal_fee.total_fee,
+            &subsidized_fee.total_fee,
+        );
         metrics::histogram!("ticker.get_batch_from_ticker_in_wei", start.elapsed());
 
         Ok(ResponseBatchFee {
             normal_fee,
             subsidized_fee,
             subsidy_size_usd,
+            subsidy_rule,
+            inputs,
         })
     }
 
@@ -530,7 +721,20 @@ impl FeeTicker {
         Ok(res)
     }
 
-    pub async fn token_usd_risk(&self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
+    /// Returns the price of the whole token, not the smallest unit of it.
+    async fn token_usd_price(&self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
+        Ok(self
+            .info
+            .get_last_token_price(TokenLike::Id(token.id))
+            .await?
+            .usd_price)
+    }
+
+    pub fn token_usd_risk(
+        &self,
+        token: &Token,
+        token_usd_price: &Ratio<BigUint>,
+    ) -> anyhow::Result<Ratio<BigUint>> {
         let start = Instant::now();
         let token_risk_factor = self
             .config
@@ -539,12 +743,7 @@ impl FeeTicker {
             .cloned()
             .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
 
-        let token_price_usd = self
-            .info
-            .get_last_token_price(TokenLike::Id(token.id))
-            .await?
-            .usd_price
-            / BigUint::from(10u32).pow(u32::from(token.decimals));
+        let token_price_usd = token_usd_price / BigUint::from(10u32).pow(u32::from(token.decimals));
         // TODO Check tokens fee allowance by non-zero price (ZKS-580)
         metrics::histogram!("ticker.token_usd_risk", start.elapsed());
         token_risk_factor
@@ -0,0 +1,220 @@
+//! In-memory cache of the token prices the fees are calculated with.
+//!
+//! Every fee request needs the prices of the fee token and ETH, so loading them from the database
+//! on every request makes the concurrent requests for the same token load its price at once.
+//! Instead, the prices are cached for a short time, and the concurrent requests missing the cache
+//! wait for the single load of the price. The prices of the tokens requested recently are kept warm
+//! by the background refresh, so the fee requests are served from the cache.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+// Workspace deps
+use zksync_types::{TokenId, TokenPrice};
+// Local deps
+use crate::fee_ticker::PriceError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceCacheConfig {
+    /// Time the cached price is served for, the cache is disabled if zero.
+    pub ttl: Duration,
+    /// Interval of the refresh of the prices of the active tokens.
+    pub refresh_interval: Duration,
+    /// Tokens requested within this time are considered active, their prices are kept warm.
+    pub active_token_period: Duration,
+}
+
+impl From<&zksync_config::TickerConfig> for PriceCacheConfig {
+    fn from(config: &zksync_config::TickerConfig) -> Self {
+        Self {
+            ttl: config.price_cache_ttl(),
+            refresh_interval: config.price_cache_refresh_interval(),
+            active_token_period: config.price_cache_active_token_period(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CachedPrice {
+    price: TokenPrice,
+    loaded_at: Instant,
+    last_requested: Instant,
+}
+
+/// Cache of the token prices, shared by the clones of the ticker info.
+#[derive(Debug, Clone)]
+pub struct TokenPriceCache {
+    config: PriceCacheConfig,
+    prices: Arc<Mutex<HashMap<TokenId, CachedPrice>>>,
+    /// Locks of the price loads, so the concurrent requests load the price of the token once.
+    loads: Arc<Mutex<HashMap<TokenId, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl TokenPriceCache {
+    pub fn new(config: PriceCacheConfig) -> Self {
+        Self {
+            config,
+            prices: Default::default(),
+            loads: Default::default(),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(PriceCacheConfig {
+            ttl: Duration::from_secs(0),
+            refresh_interval: Duration::from_secs(0),
+            active_token_period: Duration::from_secs(0),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.ttl > Duration::from_secs(0)
+    }
+
+    pub fn refresh_interval(&self) -> Duration {
+        self.config.refresh_interval
+    }
+
+    /// Returns the cached price of the token unless it's expired, marking the token as active.
+    fn cached(&self, token_id: TokenId) -> Option<TokenPrice> {
+        let mut prices = self.prices.lock().unwrap();
+        let cached = prices.get_mut(&token_id)?;
+        cached.last_requested = Instant::now();
+        if cached.loaded_at.elapsed() < self.config.ttl {
+            Some(cached.price.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Stores the loaded price of the token.
+    pub fn store(&self, token_id: TokenId, price: TokenPrice) {
+        let now = Instant::now();
+        let mut prices = self.prices.lock().unwrap();
+        let cached = prices.entry(token_id).or_insert_with(|| CachedPrice {
+            price: price.clone(),
+            loaded_at: now,
+            last_requested: now,
+        });
+        cached.price = price;
+        cached.loaded_at = now;
+    }
+
+    /// Returns the cached price of the token, or loads it once for all the concurrent requests.
+    pub async fn get_or_load<F, Fut>(
+        &self,
+        token_id: TokenId,
+        load: F,
+    ) -> Result<TokenPrice, PriceError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<TokenPrice, PriceError>>,
+    {
+        if !self.is_enabled() {
+            return load().await;
+        }
+        if let Some(price) = self.cached(token_id) {
+            metrics::increment_counter!("ticker_info.price_cache", "result" => "hit");
+            return Ok(price);
+        }
+
+        let load_lock = self
+            .loads
+            .lock()
+            .unwrap()
+            .entry(token_id)
+            .or_default()
+            .clone();
+        let _load_guard = load_lock.lock().await;
+        // The price could be loaded by the concurrent request while waiting for the lock.
+        if let Some(price) = self.cached(token_id) {
+            metrics::increment_counter!("ticker_info.price_cache", "result" => "coalesced");
+            return Ok(price);
+        }
+        metrics::increment_counter!("ticker_info.price_cache", "result" => "miss");
+        let price = load().await?;
+        self.store(token_id, price.clone());
+        Ok(price)
+    }
+
+    /// Returns the tokens requested within the active period, the rest are evicted.
+    pub fn active_tokens(&self) -> Vec<TokenId> {
+        let mut prices = self.prices.lock().unwrap();
+        let active_token_period = self.config.active_token_period;
+        prices.retain(|_, cached| cached.last_requested.elapsed() < active_token_period);
+        self.loads
+            .lock()
+            .unwrap()
+            .retain(|token_id, _| prices.contains_key(token_id));
+        prices.keys().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use futures::future::join_all;
+    use num::rational::Ratio;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn cache() -> TokenPriceCache {
+        TokenPriceCache::new(PriceCacheConfig {
+            ttl: Duration::from_secs(3600),
+            refresh_interval: Duration::from_secs(1),
+            active_token_period: Duration::from_secs(3600),
+        })
+    }
+
+    #[tokio::test]
+    async fn coalesced_loads() {
+        let cache = cache();
+        let loads = AtomicUsize::new(0);
+        let loads = &loads;
+        let load = move || async move {
+            loads.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(TokenPrice {
+                usd_price: Ratio::from_integer(2u32.into()),
+                last_updated: Utc::now(),
+            })
+        };
+
+        let prices = join_all((0..10).map(|_| cache.get_or_load(TokenId(1), load))).await;
+        assert!(prices.iter().all(|price| price.is_ok()));
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.active_tokens(), vec![TokenId(1)]);
+
+        // The failed loads are not cached.
+        let result = cache
+            .get_or_load(TokenId(2), || async {
+                Err(PriceError::db_error("No price stored in database"))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(cache.active_tokens(), vec![TokenId(1)]);
+    }
+
+    #[tokio::test]
+    async fn disabled_cache() {
+        let cache = TokenPriceCache::disabled();
+        let loads = AtomicUsize::new(0);
+        let loads = &loads;
+        for _ in 0..2 {
+            cache
+                .get_or_load(TokenId(1), move || async move {
+                    loads.fetch_add(1, Ordering::SeqCst);
+                    Ok(TokenPrice {
+                        usd_price: Ratio::from_integer(2u32.into()),
+                        last_updated: Utc::now(),
+                    })
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+        assert!(cache.active_tokens().is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+//! Fee subsidy rules.
+//!
+//! Besides the subsidy of the CREATE2 ChangePubKey for the configured IPs, the fees may be
+//! discounted by the rules, e.g. to make the ChangePubKey free during a promotion, or the
+//! transfers to the partner's address cheaper. The fee is discounted by the first active rule
+//! matching the transaction type, the fee token and the address. The rules apply to everyone
+//! until their budgets are spent: the subsidized amounts are recorded to the `subsidies` table
+//! under the names of the rules.
+//!
+//! The batch is subsidized by the rule matching its first transaction, the other transactions
+//! are discounted only if they match the same rule. While the rule applies to the batch,
+//! the CREATE2 ChangePubKey in it is not subsidized.
+
+// External deps
+use num::{rational::Ratio, BigUint};
+// Workspace deps
+use zksync_config::configs::ticker::{SubsidizedTxType, SubsidyRule};
+use zksync_types::{Address, OutputFeeType, Token};
+
+fn subsidized_tx_type(fee_type: OutputFeeType) -> SubsidizedTxType {
+    match fee_type {
+        OutputFeeType::Transfer | OutputFeeType::TransferToNew => SubsidizedTxType::Transfer,
+        OutputFeeType::Withdraw | OutputFeeType::FastWithdraw => SubsidizedTxType::Withdraw,
+        OutputFeeType::WithdrawNFT | OutputFeeType::FastWithdrawNFT => {
+            SubsidizedTxType::WithdrawNFT
+        }
+        OutputFeeType::ChangePubKey(_) => SubsidizedTxType::ChangePubKey,
+        OutputFeeType::MintNFT => SubsidizedTxType::MintNFT,
+        OutputFeeType::Swap => SubsidizedTxType::Swap,
+    }
+}
+
+/// Whether the rule discounts the fee of the transaction at the unix timestamp.
+pub(super) fn rule_applies(
+    rule: &SubsidyRule,
+    fee_type: OutputFeeType,
+    token: &Token,
+    address: Address,
+    timestamp: u64,
+) -> bool {
+    rule.is_active(timestamp)
+        && (rule.tx_types.is_empty() || rule.tx_types.contains(&subsidized_tx_type(fee_type)))
+        && (rule.tokens.is_empty() || rule.tokens.contains(&token.address))
+        && (rule.addresses.is_empty() || rule.addresses.contains(&address))
+}
+
+/// Returns the first rule discounting the fee of the transaction at the unix timestamp.
+pub(super) fn find_rule<'a>(
+    rules: &'a [SubsidyRule],
+    fee_type: OutputFeeType,
+    token: &Token,
+    address: Address,
+    timestamp: u64,
+) -> Option<&'a SubsidyRule> {
+    rules
+        .iter()
+        .find(|rule| rule_applies(rule, fee_type, token, address, timestamp))
+}
+
+/// Applies the discount of the rule to the part of the fee.
+pub(super) fn discounted(amount: Ratio<BigUint>, rule: &SubsidyRule) -> Ratio<BigUint> {
+    let discount_percent = rule.discount_percent.min(100);
+    amount * Ratio::new(BigUint::from(100 - discount_percent), BigUint::from(100u32))
+}
+
+/// Returns the USD size of the subsidy, given the price of the fee token per its smallest unit.
+pub(super) fn subsidy_size_usd(
+    token_price: Ratio<BigUint>,
+    normal_fee: &BigUint,
+    subsidized_fee: &BigUint,
+) -> Ratio<BigUint> {
+    if normal_fee > subsidized_fee {
+        token_price * (normal_fee - subsidized_fee)
+    } else {
+        Ratio::from(BigUint::from(0u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, TokenId, TokenKind};
+
+    fn rule() -> SubsidyRule {
+        SubsidyRule {
+            name: "Promo".to_string(),
+            discount_percent: 50,
+            max_subsidy_usd_scaled: 1_000_000,
+            tx_types: vec![SubsidizedTxType::Transfer, SubsidizedTxType::ChangePubKey],
+            tokens: vec![Address::repeat_byte(1)],
+            addresses: Vec::new(),
+            active_from: Some(100),
+            active_until: None,
+        }
+    }
+
+    #[test]
+    fn rules_matching() {
+        let token = Token::new(
+            TokenId(1),
+            Address::repeat_byte(1),
+            "DAI",
+            18,
+            TokenKind::ERC20,
+        );
+        let other_token = Token::new(
+            TokenId(2),
+            Address::repeat_byte(2),
+            "USDC",
+            6,
+            TokenKind::ERC20,
+        );
+        let create2 = OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+            ChangePubKeyType::CREATE2,
+        ));
+        let address = Address::repeat_byte(3);
+        let rules = vec![rule()];
+
+        assert!(find_rule(&rules, OutputFeeType::TransferToNew, &token, address, 100).is_some());
+        assert!(find_rule(&rules, create2, &token, address, 200).is_some());
+        // Not active yet.
+        assert!(find_rule(&rules, OutputFeeType::Transfer, &token, address, 99).is_none());
+        // Another transaction type.
+        assert!(find_rule(&rules, OutputFeeType::Withdraw, &token, address, 100).is_none());
+        // Another token.
+        assert!(find_rule(&rules, OutputFeeType::Transfer, &other_token, address, 100).is_none());
+
+        // The addresses are restricted by the allowlist.
+        let mut rules = rules;
+        rules[0].addresses = vec![Address::repeat_byte(4)];
+        assert!(find_rule(&rules, OutputFeeType::Transfer, &token, address, 100).is_none());
+        assert!(find_rule(
+            &rules,
+            OutputFeeType::Transfer,
+            &token,
+            Address::repeat_byte(4),
+            100
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn discount() {
+        let mut rule = rule();
+        let amount = Ratio::from_integer(BigUint::from(10u32));
+        assert_eq!(
+            discounted(amount.clone(), &rule),
+            Ratio::from_integer(BigUint::from(5u32))
+        );
+        rule.discount_percent = 150;
+        assert_eq!(discounted(amount, &rule), Ratio::from(BigUint::from(0u32)));
+    }
+}
@@ -114,6 +114,22 @@ pub fn get_test_ticker_config() -> TickerConfig {
         scale_fee_coefficient: Ratio::new(BigUint::from(150u32), BigUint::from(100u32)),
         max_blocks_to_aggregate: 5,
         subsidy_cpk_price_usd: scaled_u64_to_ratio(SUBSIDY_CPK_PRICE_USD_SCALED),
+        subsidy_rules: Vec::new(),
+        congestion: CongestionConfig {
+            mempool_threshold: 0,
+            max_multiplier_percent: 100,
+            decay_percent: 0,
+            update_interval: Duration::from_secs(0),
+        },
+        batch_discount: BatchDiscountConfig {
+            min_txs: 0,
+            overhead_discount_percent: 0,
+        },
+        gas_price: GasPriceConfig {
+            smoothing_percent: 100,
+            max_deviation_percent: 0,
+            update_interval: Duration::from_secs(0),
+        },
     }
 }
 
@@ -130,6 +146,7 @@ impl FeeTickerAPI for MockApiProvider {
 struct MockTickerInfo {
     pub future_blocks: BlocksInFutureAggregatedOperations,
     pub remaining_chunks: Option<usize>,
+    pub mempool_size: u32,
 }
 
 impl Default for MockTickerInfo {
@@ -141,6 +158,7 @@ impl Default for MockTickerInfo {
                 blocks_to_execute: 0,
             },
             remaining_chunks: None,
+            mempool_size: 0,
         }
     }
 }
@@ -162,6 +180,10 @@ impl FeeTickerInfo for MockTickerInfo {
         Ok(self.remaining_chunks)
     }
 
+    async fn mempool_size(&self) -> anyhow::Result<u32> {
+        Ok(self.mempool_size)
+    }
+
     async fn get_last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError> {
         for test_token in TestToken::all_tokens() {
             if TokenLike::Id(test_token.id) == token {
@@ -679,6 +701,184 @@ fn test_ticker_formula() {
     }
 }
 
+#[test]
+fn test_ticker_subsidy_rules() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+
+    let merchant = Address::repeat_byte(0x11);
+    let mut config = get_test_ticker_config();
+    config.subsidy_rules = vec![SubsidyRule {
+        name: "FreeMerchantTransfers".to_string(),
+        discount_percent: 100,
+        max_subsidy_usd_scaled: 1_000_000_000,
+        tx_types: vec![zksync_config::configs::ticker::SubsidizedTxType::Transfer],
+        tokens: Vec::new(),
+        addresses: vec![merchant],
+        active_from: None,
+        active_until: None,
+    }];
+    let mut ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+
+    // Transfers to the merchant are free.
+    let (normal_price, subsidy_price) = get_normal_and_subsidy_fee(
+        &mut ticker,
+        TxFeeTypes::Transfer,
+        TokenId(0).into(),
+        merchant,
+        None,
+        None,
+    );
+    assert!(!normal_price.is_zero());
+    assert!(subsidy_price.is_zero());
+    let fee = block_on(ticker.get_fee_from_ticker_in_wei(
+        TxFeeTypes::Transfer,
+        TokenId(0).into(),
+        merchant,
+    ))
+    .unwrap();
+    assert_eq!(fee.subsidy_rule.unwrap().name, "FreeMerchantTransfers");
+    assert!(!fee.subsidy_size_usd.is_zero());
+
+    // Other transactions are not subsidized.
+    let (normal_price, subsidy_price) = get_normal_and_subsidy_fee(
+        &mut ticker,
+        TxFeeTypes::Transfer,
+        TokenId(0).into(),
+        Address::default(),
+        None,
+        None,
+    );
+    assert_eq!(normal_price, subsidy_price);
+
+    // Only the matching transactions of the batch are discounted.
+    let batch_fee = block_on(ticker.get_batch_from_ticker_in_wei(
+        TokenId(0).into(),
+        vec![
+            (TxFeeTypes::Transfer, merchant),
+            (TxFeeTypes::Withdraw, merchant),
+        ],
+    ))
+    .unwrap();
+    let withdraw_fee =
+        block_on(ticker.get_batch_from_ticker_in_wei(
+            TokenId(0).into(),
+            vec![(TxFeeTypes::Withdraw, merchant)],
+        ))
+        .unwrap();
+    assert!(batch_fee.subsidy_rule.is_some());
+    assert_eq!(
+        batch_fee.subsidized_fee.total_fee,
+        withdraw_fee.normal_fee.total_fee
+    );
+}
+
+#[test]
+fn test_ticker_congestion() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let ticker = FeeTicker::new(
+        Box::new(MockTickerInfo::default()),
+        get_test_ticker_config(),
+        validator.clone(),
+    );
+    let normal_fee = block_on(ticker.get_fee_from_ticker_in_wei(
+        TxFeeTypes::Withdraw,
+        TokenId(0).into(),
+        Address::default(),
+    ))
+    .unwrap()
+    .normal_fee
+    .total_fee;
+
+    // Twice as many transactions in the mempool as the threshold double the fees.
+    let mut config = get_test_ticker_config();
+    config.congestion = CongestionConfig {
+        mempool_threshold: 100,
+        max_multiplier_percent: 300,
+        decay_percent: 50,
+        update_interval: Duration::from_secs(3600),
+    };
+    let info = MockTickerInfo {
+        mempool_size: 200,
+        ..Default::default()
+    };
+    let ticker = FeeTicker::new(Box::new(info), config, validator);
+    let congested_fee = block_on(ticker.get_fee_from_ticker_in_wei(
+        TxFeeTypes::Withdraw,
+        TokenId(0).into(),
+        Address::default(),
+    ))
+    .unwrap()
+    .normal_fee
+    .total_fee;
+    let congested_batch_fee = block_on(ticker.get_batch_from_ticker_in_wei(
+        TokenId(0).into(),
+        vec![(TxFeeTypes::Withdraw, Address::default())],
+    ))
+    .unwrap()
+    .normal_fee
+    .total_fee;
+
+    assert!(congested_fee > normal_fee);
+    // The fees are rounded, so they may differ by the precision of the rounding.
+    let expected_fee = normal_fee * 2u32;
+    let difference = if congested_fee > expected_fee {
+        &congested_fee - &expected_fee
+    } else {
+        &expected_fee - &congested_fee
+    };
+    assert!(difference * 100u32 <= expected_fee);
+    assert_eq!(congested_fee, congested_batch_fee);
+}
+
+#[test]
+fn test_ticker_batch_discount() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let mut config = get_test_ticker_config();
+    config.batch_discount = BatchDiscountConfig {
+        min_txs: 2,
+        overhead_discount_percent: 50,
+    };
+    let ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+    let batch_fee = |size: usize| {
+        block_on(ticker.get_batch_from_ticker_in_wei(
+            TokenId(0).into(),
+            vec![(TxFeeTypes::Transfer, Address::default()); size],
+        ))
+        .unwrap()
+        .normal_fee
+    };
+
+    // The batches up to the threshold are priced as the sum of the transactions.
+    // The fees are rounded, so they may differ by the precision of the rounding.
+    let single_fee = batch_fee(1).gas_fee;
+    let small_batch_fee = batch_fee(2).gas_fee;
+    let expected_fee = &single_fee * 2u32;
+    let difference = if small_batch_fee > expected_fee {
+        &small_batch_fee - &expected_fee
+    } else {
+        &expected_fee - &small_batch_fee
+    };
+    assert!(difference * 100u32 <= expected_fee);
+    // The overhead of the larger batches is discounted.
+    let large_batch_fee = batch_fee(3).gas_fee;
+    assert!(large_batch_fee * 10u32 < single_fee * 3u32 * 9u32);
+}
+
 // It's temporary solution while zero-price tokens marked as allowed for fee
 #[test]
 fn test_zero_price_token_fee() {
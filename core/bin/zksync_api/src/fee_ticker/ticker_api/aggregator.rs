@@ -0,0 +1,244 @@
+//! Aggregation of the token prices reported by several APIs.
+//!
+//! A single price API makes every fee depend on its availability and correctness. So the price
+//! is requested from all the configured APIs, the prices older than the maximum age are ignored,
+//! and the median of the rest is used. Thus an outage of the API or a bad price of one of them
+//! doesn't affect the fees as long as the majority of the APIs reports the correct prices.
+
+// Built-in deps
+use std::time::Duration;
+// External deps
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::future::join_all;
+use num::{rational::Ratio, BigUint};
+// Workspace deps
+use super::TokenPriceAPI;
+use crate::fee_ticker::PriceError;
+use zksync_types::{Token, TokenPrice};
+
+/// Price API requesting the price from several APIs and returning the median of their prices.
+pub struct AggregatedPriceAPI {
+    /// Price APIs along with their names used in the logs and metrics.
+    sources: Vec<(String, Box<dyn TokenPriceAPI + Send + Sync>)>,
+    /// Maximum age of the price, the older prices are ignored.
+    max_price_age: Duration,
+}
+
+impl AggregatedPriceAPI {
+    pub fn new(
+        sources: Vec<(String, Box<dyn TokenPriceAPI + Send + Sync>)>,
+        max_price_age: Duration,
+    ) -> Self {
+        assert!(!sources.is_empty(), "At least one price source is required");
+        Self {
+            sources,
+            max_price_age,
+        }
+    }
+
+    /// Checks the price reported by the source, returns `None` if it can't be used.
+    fn check_price(
+        &self,
+        source: &str,
+        token: &Token,
+        price: Result<TokenPrice, PriceError>,
+    ) -> Option<TokenPrice> {
+        let price = match price {
+            Ok(price) => price,
+            Err(PriceError::TokenNotFound(_)) => return None,
+            Err(err) => {
+                vlog::warn!(
+                    "Price source {} failed to report the price of token {}: {}",
+                    source,
+                    token.symbol,
+                    err
+                );
+                metrics::gauge!("ticker.price_source.healthy", 0.0, "source" => source.to_string());
+                metrics::increment_counter!("ticker.price_source.errors", "source" => source.to_string());
+                return None;
+            }
+        };
+        metrics::gauge!("ticker.price_source.healthy", 1.0, "source" => source.to_string());
+
+        let age = (Utc::now() - price.last_updated)
+            .to_std()
+            .unwrap_or_default();
+        if age > self.max_price_age {
+            vlog::warn!(
+                "Price source {} reported the stale price of token {}, updated {}s ago",
+                source,
+                token.symbol,
+                age.as_secs()
+            );
+            metrics::increment_counter!("ticker.price_source.stale_prices", "source" => source.to_string());
+            return None;
+        }
+        Some(price)
+    }
+}
+
+/// Returns the median of the prices, the mean of the two middle prices for the even count.
+/// The price is updated at the oldest of the update times of the prices it's calculated from.
+pub(super) fn median_price(mut prices: Vec<TokenPrice>) -> Option<TokenPrice> {
+    prices.sort_by(|a, b| a.usd_price.cmp(&b.usd_price));
+    let middle = prices.len() / 2;
+    let middle_prices = if prices.len() % 2 == 0 {
+        prices.get(middle.checked_sub(1)?..=middle)?
+    } else {
+        prices.get(middle..=middle)?
+    };
+
+    let usd_price = middle_prices
+        .iter()
+        .map(|price| price.usd_price.clone())
+        .sum::<Ratio<BigUint>>()
+        / BigUint::from(middle_prices.len());
+    let last_updated = prices.iter().map(|price| price.last_updated).min()?;
+    Some(TokenPrice {
+        usd_price,
+        last_updated,
+    })
+}
+
+#[async_trait]
+impl TokenPriceAPI for AggregatedPriceAPI {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let responses = join_all(
+            self.sources
+                .iter()
+                .map(|(_, price_api)| price_api.get_price(token)),
+        )
+        .await;
+        // Unlisted tokens are reported as such, so the ticker can price them accordingly.
+        if responses
+            .iter()
+            .all(|price| matches!(price, Err(PriceError::TokenNotFound(_))))
+        {
+            return Err(PriceError::token_not_found(format!(
+                "Token {} is not listed by any of the price sources",
+                token.symbol
+            )));
+        }
+
+        let prices: Vec<_> = self
+            .sources
+            .iter()
+            .zip(responses)
+            .filter_map(|((source, _), price)| self.check_price(source, token, price))
+            .collect();
+        metrics::gauge!(
+            "ticker.price_source.used_prices",
+            prices.len() as f64,
+            "token" => token.symbol.clone()
+        );
+
+        median_price(prices).ok_or_else(|| {
+            PriceError::api_error(format!(
+                "None of the price sources reported the actual price of token {}",
+                token.symbol
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use zksync_types::{TokenId, TokenKind};
+
+    struct FixedPriceAPI(Result<TokenPrice, PriceError>);
+
+    #[async_trait]
+    impl TokenPriceAPI for FixedPriceAPI {
+        async fn get_price(&self, _token: &Token) -> Result<TokenPrice, PriceError> {
+            match &self.0 {
+                Ok(price) => Ok(price.clone()),
+                Err(PriceError::TokenNotFound(msg)) => Err(PriceError::token_not_found(msg)),
+                Err(err) => Err(PriceError::api_error(err)),
+            }
+        }
+    }
+
+    fn price(usd_price: u64, last_updated: DateTime<Utc>) -> TokenPrice {
+        TokenPrice {
+            usd_price: Ratio::from_integer(usd_price.into()),
+            last_updated,
+        }
+    }
+
+    fn source(
+        price: Result<TokenPrice, PriceError>,
+    ) -> (String, Box<dyn TokenPriceAPI + Send + Sync>) {
+        ("fixed".to_string(), Box::new(FixedPriceAPI(price)))
+    }
+
+    /// Checks that the price matches the expected one, `TokenPrice` is not `PartialEq`.
+    fn assert_price(actual: TokenPrice, expected: TokenPrice) {
+        assert_eq!(actual.usd_price, expected.usd_price);
+        assert_eq!(actual.last_updated, expected.last_updated);
+    }
+
+    #[test]
+    fn median() {
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::minutes(5);
+
+        assert!(median_price(Vec::new()).is_none());
+        assert_price(
+            median_price(vec![price(5, now), price(1000, now), price(3, earlier)]).unwrap(),
+            price(5, earlier),
+        );
+        assert_price(
+            median_price(vec![
+                price(4, now),
+                price(2, now),
+                price(1000, now),
+                price(1, now),
+            ])
+            .unwrap(),
+            price(3, now),
+        );
+    }
+
+    #[tokio::test]
+    async fn aggregation() {
+        let token = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
+        let now = Utc::now();
+        let stale = now - chrono::Duration::hours(2);
+
+        // The failed, unlisting and stale sources are ignored.
+        let api = AggregatedPriceAPI::new(
+            vec![
+                source(Ok(price(10, now))),
+                source(Ok(price(12, now))),
+                source(Ok(price(1000, stale))),
+                source(Err(PriceError::api_error("rate limit"))),
+                source(Err(PriceError::token_not_found("unknown token"))),
+            ],
+            Duration::from_secs(3600),
+        );
+        assert_price(api.get_price(&token).await.unwrap(), price(11, now));
+
+        // No actual prices.
+        let api = AggregatedPriceAPI::new(
+            vec![source(Ok(price(1000, stale)))],
+            Duration::from_secs(3600),
+        );
+        assert!(matches!(
+            api.get_price(&token).await,
+            Err(PriceError::ApiError(_))
+        ));
+
+        // The token is not listed anywhere.
+        let api = AggregatedPriceAPI::new(
+            vec![source(Err(PriceError::token_not_found("unknown token")))],
+            Duration::from_secs(3600),
+        );
+        assert!(matches!(
+            api.get_price(&token).await,
+            Err(PriceError::TokenNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,201 @@
+// Built-in deps
+use std::collections::HashMap;
+use std::time::Instant;
+// External deps
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use num::{rational::Ratio, BigUint};
+use web3::contract::Options;
+use web3::types::U256;
+// Workspace deps
+use super::TokenPriceAPI;
+use crate::fee_ticker::PriceError;
+use zksync_config::configs::ticker::ChainlinkFeed;
+use zksync_eth_client::EthereumGateway;
+use zksync_types::{Address, Token, TokenPrice};
+
+/// ABI of the `latestRoundData` method of the Chainlink aggregator contract.
+const AGGREGATOR_ABI: &str = r#"[{
+    "inputs": [],
+    "name": "latestRoundData",
+    "outputs": [
+        { "internalType": "uint80", "name": "roundId", "type": "uint80" },
+        { "internalType": "int256", "name": "answer", "type": "int256" },
+        { "internalType": "uint256", "name": "startedAt", "type": "uint256" },
+        { "internalType": "uint256", "name": "updatedAt", "type": "uint256" },
+        { "internalType": "uint80", "name": "answeredInRound", "type": "uint80" }
+    ],
+    "stateMutability": "view",
+    "type": "function"
+}]"#;
+
+/// Latest answer of the Chainlink feed: `(roundId, answer, startedAt, updatedAt, answeredInRound)`.
+/// The answer is signed, so it's decoded as the raw token.
+type RoundData = (U256, ethabi::Token, U256, U256, U256);
+
+/// Price API reading the Chainlink price feeds via the Ethereum node, so the prices
+/// are available while the HTTP price APIs are rate-limited or down.
+#[derive(Debug, Clone)]
+pub struct ChainlinkPriceAPI {
+    ethereum: EthereumGateway,
+    aggregator_abi: ethabi::Contract,
+    /// Feeds of the tokens, by the token address.
+    feeds: HashMap<Address, ChainlinkFeed>,
+}
+
+impl ChainlinkPriceAPI {
+    pub fn new(ethereum: EthereumGateway, feeds: Vec<ChainlinkFeed>) -> Self {
+        Self {
+            ethereum,
+            aggregator_abi: ethabi::Contract::load(AGGREGATOR_ABI.as_bytes())
+                .expect("Correct Chainlink aggregator ABI"),
+            feeds: feeds.into_iter().map(|feed| (feed.token, feed)).collect(),
+        }
+    }
+}
+
+/// Converts the latest answer of the feed into the price, rejecting the invalid and stale answers.
+fn round_data_to_price(
+    feed: &ChainlinkFeed,
+    round_data: RoundData,
+    now: DateTime<Utc>,
+) -> Result<TokenPrice, PriceError> {
+    let (round_id, answer, _, updated_at, answered_in_round) = round_data;
+    let answer = match answer {
+        ethabi::Token::Int(answer) => answer,
+        token => {
+            return Err(PriceError::api_error(format!(
+                "Chainlink feed {:#x} returned unexpected answer {:?}",
+                feed.feed, token
+            )))
+        }
+    };
+    // The answer is a two's complement number, the prices can't be negative or zero.
+    if answer.is_zero() || answer.bit(255) {
+        return Err(PriceError::api_error(format!(
+            "Chainlink feed {:#x} returned non-positive answer",
+            feed.feed
+        )));
+    }
+    if answered_in_round < round_id {
+        return Err(PriceError::api_error(format!(
+            "Chainlink feed {:#x} answer is carried over from the round {}",
+            feed.feed, answered_in_round
+        )));
+    }
+
+    let last_updated = DateTime::<Utc>::from_utc(
+        NaiveDateTime::from_timestamp(updated_at.as_u64() as i64, 0),
+        Utc,
+    );
+    let age = (now - last_updated).to_std().unwrap_or_default();
+    if age > feed.heartbeat {
+        return Err(PriceError::api_error(format!(
+            "Chainlink feed {:#x} missed the heartbeat, updated {}s ago",
+            feed.feed,
+            age.as_secs()
+        )));
+    }
+
+    let mut answer_bytes = [0u8; 32];
+    answer.to_big_endian(&mut answer_bytes);
+    let usd_price = Ratio::new(
+        BigUint::from_bytes_be(&answer_bytes),
+        BigUint::from(10u32).pow(feed.decimals as u32),
+    );
+    Ok(TokenPrice {
+        usd_price,
+        last_updated,
+    })
+}
+
+#[async_trait]
+impl TokenPriceAPI for ChainlinkPriceAPI {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let start = Instant::now();
+        let feed = self.feeds.get(&token.address).ok_or_else(|| {
+            PriceError::token_not_found(format!(
+                "Token '{}, {:?}' has no Chainlink feed",
+                token.symbol, token.address
+            ))
+        })?;
+
+        let round_data: RoundData = self
+            .ethereum
+            .call_contract_function(
+                "latestRoundData",
+                (),
+                None,
+                Options::default(),
+                None,
+                feed.feed,
+                self.aggregator_abi.clone(),
+            )
+            .await
+            .map_err(|err| {
+                PriceError::api_error(format!("Chainlink feed request failed: {}", err))
+            })?;
+
+        let price = round_data_to_price(feed, round_data, Utc::now())?;
+        metrics::histogram!("ticker.chainlink.request", start.elapsed());
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn round_data_conversion() {
+        let feed = ChainlinkFeed {
+            token: Address::zero(),
+            feed: Address::repeat_byte(1),
+            heartbeat: Duration::from_secs(3600),
+            decimals: 8,
+        };
+        let now = Utc::now();
+        let updated_at = U256::from(now.timestamp() - 60);
+        let round_data = |answer: U256, updated_at: U256, answered_in_round: u64| {
+            (
+                U256::from(10),
+                ethabi::Token::Int(answer),
+                updated_at,
+                updated_at,
+                U256::from(answered_in_round),
+            )
+        };
+
+        // 1850.5 USD with 8 decimals.
+        let price = round_data_to_price(
+            &feed,
+            round_data(185_050_000_000u64.into(), updated_at, 10),
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            price.usd_price,
+            Ratio::new(BigUint::from(3701u32), BigUint::from(2u32))
+        );
+        assert_eq!(price.last_updated.timestamp(), updated_at.as_u64() as i64);
+
+        // Negative answer.
+        assert!(round_data_to_price(&feed, round_data(U256::MAX, updated_at, 10), now).is_err());
+        // Stale answer.
+        let stale_updated_at = U256::from(now.timestamp() - 7200);
+        assert!(round_data_to_price(
+            &feed,
+            round_data(185_050_000_000u64.into(), stale_updated_at, 10),
+            now
+        )
+        .is_err());
+        // Answer carried over from the previous round.
+        assert!(round_data_to_price(
+            &feed,
+            round_data(185_050_000_000u64.into(), updated_at, 9),
+            now
+        )
+        .is_err());
+    }
+}
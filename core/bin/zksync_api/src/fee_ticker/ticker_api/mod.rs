@@ -10,8 +10,14 @@ use std::time::{Duration, Instant};
 use zksync_storage::ConnectionPool;
 use zksync_types::{Token, TokenId, TokenPrice};
 
+use self::price_guard::{PriceCheck, PriceGuard, PriceGuardConfig};
+
+pub mod aggregator;
+pub mod chainlink;
 pub mod coingecko;
 pub mod coinmarkercap;
+pub mod price_guard;
+pub mod uniswap;
 
 const UPDATE_PRICE_INTERVAL_SECS: u64 = 10 * 60;
 /// The limit of time we are willing to wait for response.
@@ -30,18 +36,24 @@ pub trait FeeTickerAPI {
     async fn keep_price_updated(self);
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub(super) struct TickerApi<T: TokenPriceAPI> {
     db_pool: ConnectionPool,
 
     token_price_api: T,
+    price_guard: PriceGuard,
 }
 
 impl<T: TokenPriceAPI> TickerApi<T> {
-    pub fn new(db_pool: ConnectionPool, token_price_api: T) -> Self {
+    pub fn new(
+        db_pool: ConnectionPool,
+        token_price_api: T,
+        price_guard_config: PriceGuardConfig,
+    ) -> Self {
         Self {
             db_pool,
             token_price_api,
+            price_guard: PriceGuard::new(price_guard_config),
         }
     }
 
@@ -69,15 +81,108 @@ impl<T: TokenPriceAPI> TickerApi<T> {
             .await
             .map_err(|e| format_err!("Can't access storage: {}", e))?;
 
-        storage
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|e| format_err!("Can't start transaction: {}", e))?;
+        // The history keeps the prices used for the fee calculation, so it's updated
+        // together with the current price.
+        transaction
+            .tokens_schema()
+            .store_ticker_price_history(token_id, &price)
+            .await
+            .map_err(|e| format_err!("Can't store ticker price history: {}", e))?;
+        transaction
             .tokens_schema()
             .update_historical_ticker_price(token_id, price)
             .await
             .map_err(|e| format_err!("Can't update historical ticker price from storage: {}", e))?;
+        transaction
+            .commit()
+            .await
+            .map_err(|e| format_err!("Can't commit transaction: {}", e))?;
 
         Ok(())
     }
-    async fn update_price(&self, token: &Token) -> Result<(), PriceError> {
+    async fn load_stored_price(&self, token_id: TokenId) -> Result<Option<TokenPrice>, PriceError> {
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(PriceError::db_error)?;
+        storage
+            .tokens_schema()
+            .get_historical_ticker_price(token_id)
+            .await
+            .map_err(PriceError::db_error)
+    }
+
+    /// Checks the price reported by the oracle, returns `false` if the last good price
+    /// should be kept instead.
+    async fn check_price(&mut self, token: &Token, price: &TokenPrice) -> Result<bool, PriceError> {
+        if !self.price_guard.is_known(token.id) {
+            if let Some(stored) = self.load_stored_price(token.id).await? {
+                self.price_guard.set_last_good(token.id, &stored.usd_price);
+            }
+        }
+
+        let check = self.price_guard.check(token, &price.usd_price);
+        let circuit_open = if self.price_guard.is_circuit_open(token.id) {
+            1.0
+        } else {
+            0.0
+        };
+        metrics::gauge!("ticker.price_guard.circuit_open", circuit_open, "token" => token.symbol.clone());
+        match check {
+            PriceCheck::Accepted => Ok(true),
+            PriceCheck::CircuitClosed => {
+                vlog::info!(
+                    "Price circuit breaker for token {} is closed, new price is {}",
+                    token.symbol,
+                    price.usd_price
+                );
+                Ok(true)
+            }
+            PriceCheck::Rejected => {
+                vlog::warn!(
+                    "Price {} of token {} deviates too much, keeping the last good price",
+                    price.usd_price,
+                    token.symbol
+                );
+                metrics::increment_counter!("ticker.price_guard.rejected", "token" => token.symbol.clone());
+                Ok(false)
+            }
+            PriceCheck::CircuitOpened => {
+                vlog::error!(
+                    "Price circuit breaker for token {} is opened: oracle keeps reporting anomalous prices, the latest is {}",
+                    token.symbol,
+                    price.usd_price
+                );
+                metrics::increment_counter!("ticker.price_guard.rejected", "token" => token.symbol.clone());
+                Ok(false)
+            }
+            PriceCheck::OutOfBounds => {
+                vlog::warn!(
+                    "Price {} of token {} is out of its bounds, keeping the last good price",
+                    price.usd_price,
+                    token.symbol
+                );
+                metrics::increment_counter!("ticker.price_guard.out_of_bounds", "token" => token.symbol.clone());
+                Ok(false)
+            }
+        }
+    }
+
+    /// Reports the age of the price the fees are calculated with, which grows
+    /// while the reported prices are rejected and the last good one is kept.
+    fn report_price_age(token: &Token, price: &TokenPrice) {
+        let age = (Utc::now() - price.last_updated)
+            .to_std()
+            .unwrap_or_default();
+        metrics::gauge!("ticker.price_age", age.as_secs() as f64, "token" => token.symbol.clone());
+    }
+
+    async fn update_price(&mut self, token: &Token) -> Result<(), PriceError> {
         let start = Instant::now();
         let api_price = match self.token_price_api.get_price(token).await {
             Ok(api_price) => api_price,
@@ -89,6 +194,13 @@ impl<T: TokenPriceAPI> TickerApi<T> {
             },
             Err(e) => return Err(e),
         };
+        if !self.check_price(token, &api_price).await? {
+            if let Some(last_good) = self.load_stored_price(token.id).await? {
+                Self::report_price_age(token, &last_good);
+            }
+            return Ok(());
+        }
+        Self::report_price_age(token, &api_price);
 
         self.update_stored_value(token.id, api_price.clone())
             .await
@@ -100,7 +212,7 @@ impl<T: TokenPriceAPI> TickerApi<T> {
 
 #[async_trait]
 impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
-    async fn keep_price_updated(self) {
+    async fn keep_price_updated(mut self) {
         loop {
             if let Ok(tokens) = self.get_all_tokens().await {
                 for token in &tokens {
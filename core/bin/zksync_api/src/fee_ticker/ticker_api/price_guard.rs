@@ -0,0 +1,303 @@
+//! Sanity checks of the token prices reported by the oracle.
+//!
+//! The price may only move by `max_change_percent` relative to the last good price between
+//! the updates, otherwise it is considered an anomaly and the last good price is kept.
+//! After `anomalies_to_open` consecutive anomalies the circuit breaker of the token is opened.
+//! While it is open, the last good price is kept until either the oracle returns back to it,
+//! or reports `stable_to_close` consecutive prices consistent with each other, so the genuine
+//! market moves are eventually accepted.
+//!
+//! The tokens may have the individual bounds: the maximum change overriding the global one,
+//! and the absolute minimum and maximum prices. The prices outside of the latter are rejected
+//! regardless of the circuit breaker, so the breaker can never accept a glitched price.
+
+// Built-in deps
+use std::collections::HashMap;
+// External deps
+use num::{rational::Ratio, BigUint, Zero};
+// Workspace deps
+use zksync_config::{configs::ticker::TokenPriceBounds, TickerConfig};
+use zksync_types::{Address, Token, TokenId};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceGuardConfig {
+    pub max_change_percent: u32,
+    pub anomalies_to_open: u32,
+    pub stable_to_close: u32,
+    /// Individual bounds of the token prices, by the token address.
+    pub token_bounds: HashMap<Address, TokenPriceBounds>,
+}
+
+impl From<&TickerConfig> for PriceGuardConfig {
+    fn from(config: &TickerConfig) -> Self {
+        Self {
+            max_change_percent: config.max_price_change_percent,
+            anomalies_to_open: config.price_anomalies_to_open_breaker,
+            stable_to_close: config.stable_prices_to_close_breaker,
+            token_bounds: config
+                .price_bounds()
+                .into_iter()
+                .map(|bounds| (bounds.token, bounds))
+                .collect(),
+        }
+    }
+}
+
+/// Result of the price check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriceCheck {
+    /// Price is accepted.
+    Accepted,
+    /// Price is an anomaly, the last good price should be used.
+    Rejected,
+    /// Price is an anomaly, and there were too many of them in a row.
+    CircuitOpened,
+    /// Price is outside of the absolute bounds of the token, the last good price should be used.
+    OutOfBounds,
+    /// Circuit breaker is closed, the price is accepted.
+    CircuitClosed,
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    last_good: Option<Ratio<BigUint>>,
+    anomalies: u32,
+    circuit_open: bool,
+    /// The latest price reported while the circuit breaker is open.
+    last_reported: Option<Ratio<BigUint>>,
+    stable_prices: u32,
+}
+
+impl TokenState {
+    fn accept(&mut self, price: &Ratio<BigUint>) {
+        *self = Self {
+            last_good: Some(price.clone()),
+            ..Self::default()
+        };
+    }
+}
+
+#[derive(Debug)]
+pub struct PriceGuard {
+    config: PriceGuardConfig,
+    tokens: HashMap<TokenId, TokenState>,
+}
+
+impl PriceGuard {
+    pub fn new(config: PriceGuardConfig) -> Self {
+        Self {
+            config,
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// Whether the guard has seen any price of the token.
+    pub fn is_known(&self, token_id: TokenId) -> bool {
+        self.tokens.contains_key(&token_id)
+    }
+
+    /// Sets the last good price of the token, e.g. the one stored before the restart.
+    pub fn set_last_good(&mut self, token_id: TokenId, price: &Ratio<BigUint>) {
+        self.tokens.entry(token_id).or_default().accept(price);
+    }
+
+    pub fn is_circuit_open(&self, token_id: TokenId) -> bool {
+        self.tokens
+            .get(&token_id)
+            .map(|state| state.circuit_open)
+            .unwrap_or(false)
+    }
+
+    fn is_within_bounds(
+        base: &Ratio<BigUint>,
+        price: &Ratio<BigUint>,
+        max_change_percent: u32,
+    ) -> bool {
+        let change = if price > base {
+            price - base
+        } else {
+            base - price
+        };
+        change * BigUint::from(100u32) <= base.clone() * BigUint::from(max_change_percent)
+    }
+
+    pub fn check(&mut self, token: &Token, price: &Ratio<BigUint>) -> PriceCheck {
+        let max_change_percent = match self.config.token_bounds.get(&token.address) {
+            Some(bounds) => {
+                if price < &bounds.min_usd_price || price > &bounds.max_usd_price {
+                    return PriceCheck::OutOfBounds;
+                }
+                bounds.max_change_percent
+            }
+            None => self.config.max_change_percent,
+        };
+
+        let mut state = self.tokens.remove(&token.id).unwrap_or_default();
+        let result = self.check_state(&mut state, price, max_change_percent);
+        self.tokens.insert(token.id, state);
+        result
+    }
+
+    fn check_state(
+        &self,
+        state: &mut TokenState,
+        price: &Ratio<BigUint>,
+        max_change_percent: u32,
+    ) -> PriceCheck {
+        let last_good = match &state.last_good {
+            // There is nothing to compare the price with.
+            Some(last_good) if !last_good.is_zero() => last_good.clone(),
+            _ => {
+                state.accept(price);
+                return PriceCheck::Accepted;
+            }
+        };
+
+        if Self::is_within_bounds(&last_good, price, max_change_percent) {
+            let was_open = state.circuit_open;
+            state.accept(price);
+            return if was_open {
+                PriceCheck::CircuitClosed
+            } else {
+                PriceCheck::Accepted
+            };
+        }
+
+        if !state.circuit_open {
+            state.anomalies += 1;
+            if state.anomalies < self.config.anomalies_to_open {
+                return PriceCheck::Rejected;
+            }
+            state.circuit_open = true;
+            state.last_reported = Some(price.clone());
+            state.stable_prices = 1;
+            return PriceCheck::CircuitOpened;
+        }
+
+        let is_stable = match &state.last_reported {
+            Some(last_reported) => Self::is_within_bounds(last_reported, price, max_change_percent),
+            None => false,
+        };
+        state.stable_prices = if is_stable {
+            state.stable_prices + 1
+        } else {
+            1
+        };
+        state.last_reported = Some(price.clone());
+
+        if state.stable_prices >= self.config.stable_to_close {
+            state.accept(price);
+            PriceCheck::CircuitClosed
+        } else {
+            PriceCheck::Rejected
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::TokenKind;
+
+    const TOKEN: TokenId = TokenId(1);
+
+    fn guard() -> PriceGuard {
+        PriceGuard::new(PriceGuardConfig {
+            max_change_percent: 50,
+            anomalies_to_open: 2,
+            stable_to_close: 3,
+            token_bounds: HashMap::new(),
+        })
+    }
+
+    fn token() -> Token {
+        Token::new(TOKEN, Address::zero(), "ETH", 18, TokenKind::ERC20)
+    }
+
+    fn price(value: u32) -> Ratio<BigUint> {
+        Ratio::from_integer(BigUint::from(value))
+    }
+
+    #[test]
+    fn bounded_price_movement() {
+        let token = token();
+        let mut guard = guard();
+        assert_eq!(guard.check(&token, &price(100)), PriceCheck::Accepted);
+        assert_eq!(guard.check(&token, &price(150)), PriceCheck::Accepted);
+        // More than 50% from the last good price.
+        assert_eq!(guard.check(&token, &price(10)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(100)), PriceCheck::Accepted);
+        // Anomalies are counted in a row only.
+        assert_eq!(guard.check(&token, &price(1000)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(120)), PriceCheck::Accepted);
+        assert!(!guard.is_circuit_open(TOKEN));
+    }
+
+    #[test]
+    fn circuit_breaker() {
+        let token = token();
+        let mut guard = guard();
+        guard.set_last_good(TOKEN, &price(100));
+        assert_eq!(guard.check(&token, &price(1000)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(1)), PriceCheck::CircuitOpened);
+        assert!(guard.is_circuit_open(TOKEN));
+
+        // Oracle returns back to the last good price.
+        assert_eq!(guard.check(&token, &price(110)), PriceCheck::CircuitClosed);
+        assert!(!guard.is_circuit_open(TOKEN));
+
+        // The price moved for real, it is accepted once it is stable.
+        assert_eq!(guard.check(&token, &price(10)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(10)), PriceCheck::CircuitOpened);
+        assert_eq!(guard.check(&token, &price(11)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(1000)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(10)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(10)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(9)), PriceCheck::CircuitClosed);
+        assert_eq!(guard.check(&token, &price(10)), PriceCheck::Accepted);
+    }
+
+    #[test]
+    fn unknown_and_zero_prices() {
+        let token = token();
+        let mut guard = guard();
+        assert!(!guard.is_known(TOKEN));
+        // Tokens not listed by the oracle have zero price.
+        guard.set_last_good(TOKEN, &price(0));
+        assert!(guard.is_known(TOKEN));
+        assert_eq!(guard.check(&token, &price(100)), PriceCheck::Accepted);
+    }
+
+    #[test]
+    fn absolute_bounds() {
+        let mut guard = guard();
+        let token = token();
+        guard.config.token_bounds.insert(
+            token.address,
+            TokenPriceBounds {
+                token: token.address,
+                min_usd_price: price(50),
+                max_usd_price: price(500),
+                max_change_percent: 10,
+            },
+        );
+
+        assert_eq!(guard.check(&token, &price(10)), PriceCheck::OutOfBounds);
+        assert_eq!(guard.check(&token, &price(100)), PriceCheck::Accepted);
+        // The individual maximum change is used.
+        assert_eq!(guard.check(&token, &price(120)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(105)), PriceCheck::Accepted);
+
+        // Out of bounds prices don't count towards the circuit breaker.
+        for _ in 0..5 {
+            assert_eq!(guard.check(&token, &price(5000)), PriceCheck::OutOfBounds);
+        }
+        assert!(!guard.is_circuit_open(TOKEN));
+        // Nor are they accepted by it.
+        assert_eq!(guard.check(&token, &price(400)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(400)), PriceCheck::CircuitOpened);
+        assert_eq!(guard.check(&token, &price(0)), PriceCheck::OutOfBounds);
+        assert_eq!(guard.check(&token, &price(400)), PriceCheck::Rejected);
+        assert_eq!(guard.check(&token, &price(400)), PriceCheck::CircuitClosed);
+    }
+}
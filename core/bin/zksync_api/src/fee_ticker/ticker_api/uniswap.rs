@@ -0,0 +1,133 @@
+// Built-in deps
+use std::time::Instant;
+// External deps
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use num::{rational::Ratio, BigUint};
+use serde::{Deserialize, Serialize};
+// Workspace deps
+use super::{TokenPriceAPI, REQUEST_TIMEOUT};
+use crate::fee_ticker::PriceError;
+use zksync_types::{Token, TokenPrice};
+use zksync_utils::UnsignedRatioSerializeAsDecimal;
+
+/// Number of the hourly prices the time-weighted average price is calculated over.
+const TWAP_HOURS: usize = 6;
+
+/// Price API calculating the time-weighted average price of the token
+/// from the hourly prices of the Uniswap V3 subgraph.
+/// https://thegraph.com/hosted-service/subgraph/uniswap/uniswap-v3
+#[derive(Debug, Clone)]
+pub struct UniswapPriceAPI {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl UniswapPriceAPI {
+    pub fn new(client: reqwest::Client, url: String) -> Self {
+        Self { client, url }
+    }
+}
+
+#[async_trait]
+impl TokenPriceAPI for UniswapPriceAPI {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let start = Instant::now();
+        // ETH is not an ERC20 token, so its price is the one of WETH, which is not known here.
+        if token.id.0 == 0 {
+            return Err(PriceError::token_not_found(
+                "ETH is not listed on Uniswap, only WETH is",
+            ));
+        }
+
+        let query = format!(
+            "{{tokenHourDatas(first: {}, orderBy: periodStartUnix, orderDirection: desc, where: {{token: \"{:#x}\"}}){{periodStartUnix, priceUSD}}}}",
+            TWAP_HOURS, token.address
+        );
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&serde_json::json!({ "query": query }))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|err| PriceError::api_error(format!("Uniswap API request failed: {}", err)))?
+            .json::<UniswapResponse>()
+            .await
+            .map_err(PriceError::api_error)?;
+
+        let hour_prices = response.data.token_hour_datas;
+        let last_updated_timestamp = hour_prices
+            .first()
+            .ok_or_else(|| {
+                PriceError::token_not_found(format!(
+                    "Token '{}, {:?}' is not listed on Uniswap",
+                    token.symbol, token.address
+                ))
+            })?
+            .period_start_unix;
+
+        // Every price is the latest one of the hour, so the average of them is time-weighted.
+        let len = hour_prices.len();
+        let usd_price = hour_prices
+            .into_iter()
+            .map(|hour_price| hour_price.price_usd)
+            .sum::<Ratio<BigUint>>()
+            / BigUint::from(len);
+
+        let naive_last_updated = NaiveDateTime::from_timestamp(last_updated_timestamp, 0);
+        let last_updated = DateTime::<Utc>::from_utc(naive_last_updated, Utc);
+        metrics::histogram!("ticker.uniswap.request", start.elapsed());
+        Ok(TokenPrice {
+            usd_price,
+            last_updated,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniswapResponse {
+    pub(crate) data: UniswapTokenHourDatas,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniswapTokenHourDatas {
+    #[serde(rename = "tokenHourDatas")]
+    pub(crate) token_hour_datas: Vec<UniswapTokenHourData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniswapTokenHourData {
+    /// Start of the hour, as the unix timestamp.
+    #[serde(rename = "periodStartUnix")]
+    pub(crate) period_start_unix: i64,
+    /// The latest price of the token during the hour.
+    #[serde(rename = "priceUSD", with = "UnsignedRatioSerializeAsDecimal")]
+    pub(crate) price_usd: Ratio<BigUint>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_uniswap_response() {
+        let example = r#"{
+    "data": {
+        "tokenHourDatas": [
+            { "periodStartUnix": 1661176800, "priceUSD": "1.0012" },
+            { "periodStartUnix": 1661173200, "priceUSD": "0.9988" }
+        ]
+    }
+}"#;
+        let response =
+            serde_json::from_str::<UniswapResponse>(example).expect("serialization failed");
+        let hour_prices = response.data.token_hour_datas;
+        assert_eq!(hour_prices.len(), 2);
+        assert_eq!(hour_prices[0].period_start_unix, 1661176800);
+        assert_eq!(
+            hour_prices[1].price_usd,
+            UnsignedRatioSerializeAsDecimal::deserialize_from_str_with_dot("0.9988").unwrap()
+        );
+    }
+}
@@ -5,19 +5,21 @@
 #[cfg(test)]
 use std::any::Any;
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External deps
 use anyhow::format_err;
 use async_trait::async_trait;
 use chrono::Utc;
 use num::rational::Ratio;
 use num::BigUint;
+use tokio::task::JoinHandle;
 // Workspace deps
 use zksync_storage::ConnectionPool;
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::aggregated_operations::AggregatedActionType;
 use zksync_types::{Address, Token, TokenId, TokenLike, TokenPrice};
 // Local deps
+use crate::fee_ticker::price_cache::{PriceCacheConfig, TokenPriceCache};
 use crate::fee_ticker::PriceError;
 
 pub trait FeeTickerClone {
@@ -52,6 +54,9 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
 
     async fn remaining_chunks_in_pending_block(&self) -> anyhow::Result<Option<usize>>;
 
+    /// Number of the transactions waiting in the mempool.
+    async fn mempool_size(&self) -> anyhow::Result<u32>;
+
     /// Get last price for token from ticker info
     async fn get_last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError>;
 
@@ -69,6 +74,7 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
 pub struct TickerInfo {
     db: ConnectionPool,
     token_db_cache: TokenDBCache,
+    price_cache: TokenPriceCache,
 }
 
 impl TickerInfo {
@@ -76,8 +82,39 @@ impl TickerInfo {
         Self {
             db,
             token_db_cache: Default::default(),
+            price_cache: TokenPriceCache::disabled(),
         }
     }
+
+    /// Caches the token prices, the cache is shared by the clones of the ticker info.
+    pub fn with_price_cache(mut self, config: PriceCacheConfig) -> Self {
+        self.price_cache = TokenPriceCache::new(config);
+        self
+    }
+
+    /// Runs the task keeping the cached prices of the recently requested tokens warm.
+    /// The task never finishes, it just idles if the cache or its refresh is disabled.
+    pub fn run_price_cache_refresh(&self) -> JoinHandle<()> {
+        let info = self.clone();
+        tokio::spawn(async move {
+            let refresh_interval = info.price_cache.refresh_interval();
+            if !info.price_cache.is_enabled() || refresh_interval == Duration::from_secs(0) {
+                // The server treats the finished task as a failure.
+                return futures::future::pending().await;
+            }
+            let mut timer = tokio::time::interval(refresh_interval);
+            loop {
+                timer.tick().await;
+                let start = Instant::now();
+                for token_id in info.price_cache.active_tokens() {
+                    if let Ok(price) = info.load_ticker_price(token_id).await {
+                        info.price_cache.store(token_id, price);
+                    }
+                }
+                metrics::histogram!("ticker_info.price_cache_refresh", start.elapsed());
+            }
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -162,6 +199,14 @@ impl FeeTickerInfo for TickerInfo {
         Ok(remaining_chunks)
     }
 
+    async fn mempool_size(&self) -> anyhow::Result<u32> {
+        let start = Instant::now();
+        let mut storage = self.db.access_storage().await?;
+        let mempool_size = storage.chain().mempool_schema().get_mempool_size().await?;
+        metrics::histogram!("ticker_info.mempool_size", start.elapsed());
+        Ok(mempool_size)
+    }
+
     /// Get last price from ticker
     async fn get_last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError> {
         let start = Instant::now();
@@ -201,17 +246,14 @@ impl FeeTickerInfo for TickerInfo {
             });
         }
 
-        let historical_price = self
-            .get_ticker_price(token.id)
-            .await
-            .map_err(|e| vlog::warn!("Failed to get historical ticker price: {}", e));
-
-        if let Ok(Some(historical_price)) = historical_price {
-            return Ok(historical_price);
+        let price = self
+            .price_cache
+            .get_or_load(token.id, || self.load_ticker_price(token.id))
+            .await;
+        if price.is_err() {
+            metrics::histogram!("ticker_info.get_last_token_price", start.elapsed(), "type" => "error");
         }
-
-        metrics::histogram!("ticker_info.get_last_token_price", start.elapsed(), "type" => "error");
-        Err(PriceError::db_error("No price stored in database"))
+        price
     }
 
     /// Get current gas price in ETH
@@ -265,6 +307,18 @@ impl FeeTickerInfo for TickerInfo {
 }
 
 impl TickerInfo {
+    async fn load_ticker_price(&self, token_id: TokenId) -> Result<TokenPrice, PriceError> {
+        let historical_price = self
+            .get_ticker_price(token_id)
+            .await
+            .map_err(|e| vlog::warn!("Failed to get historical ticker price: {}", e));
+
+        if let Ok(Some(historical_price)) = historical_price {
+            return Ok(historical_price);
+        }
+        Err(PriceError::db_error("No price stored in database"))
+    }
+
     async fn get_ticker_price(
         &self,
         token_id: TokenId,
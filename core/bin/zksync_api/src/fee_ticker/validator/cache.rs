@@ -5,7 +5,10 @@ use tokio::sync::Mutex;
 use zksync_token_db_cache::TokenDBCache;
 
 use zksync_storage::ConnectionPool;
-use zksync_types::{tokens::TokenMarketVolume, Token, TokenId, TokenLike};
+use zksync_types::{
+    tokens::{TokenAdminSettings, TokenMarketVolume},
+    Token, TokenId, TokenLike,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) enum TokenCacheWrapper {
@@ -25,6 +28,7 @@ pub(crate) struct TokenInDBCache {
 pub struct TokenInMemoryCache {
     tokens: Arc<Mutex<HashMap<TokenLike, Token>>>,
     market: Arc<Mutex<HashMap<TokenId, TokenMarketVolume>>>,
+    admin_settings: Arc<Mutex<HashMap<TokenId, TokenAdminSettings>>>,
 }
 
 impl TokenInDBCache {
@@ -52,6 +56,13 @@ impl TokenInMemoryCache {
             ..self
         }
     }
+
+    pub fn with_admin_settings(self, admin_settings: HashMap<TokenId, TokenAdminSettings>) -> Self {
+        Self {
+            admin_settings: Arc::new(Mutex::new(admin_settings)),
+            ..self
+        }
+    }
 }
 
 #[cfg(test)]
@@ -109,6 +120,36 @@ impl TokenCacheWrapper {
         }
     }
 
+    pub async fn get_token_admin_settings(
+        &self,
+        token_id: TokenId,
+    ) -> anyhow::Result<TokenAdminSettings> {
+        match self {
+            Self::DB(cache) => {
+                if let Some(settings) = cache
+                    .inner
+                    .try_get_token_admin_settings_from_cache(token_id)
+                    .await
+                {
+                    return Ok(settings);
+                }
+
+                cache
+                    .inner
+                    .get_token_admin_settings(&mut cache.pool.access_storage().await?, token_id)
+                    .await
+            }
+            #[cfg(test)]
+            Self::Memory(cache) => Ok(cache
+                .admin_settings
+                .lock()
+                .await
+                .get(&token_id)
+                .copied()
+                .unwrap_or_default()),
+        }
+    }
+
     pub async fn update_token_market_volume(
         &mut self,
         token_id: TokenId,
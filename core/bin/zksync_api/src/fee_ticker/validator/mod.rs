@@ -28,25 +28,50 @@ const CRITICAL_NUMBER_OF_ERRORS: u32 = 500;
 
 /// We don't want to send requests to the Internet for every request from users.
 /// Market updater periodically updates the values of the token market in the cache  
+/// and marks the tokens as allowed or disallowed for paying fees by their liquidity.
 #[derive(Clone, Debug)]
 pub(crate) struct MarketUpdater<W> {
     tokens_cache: TokenCacheWrapper,
     watcher: W,
+    /// Market volume required to accept fees in the token.
+    liquidity_volume: BigDecimal,
 }
 
 impl<W: TokenWatcher> MarketUpdater<W> {
-    pub(crate) fn new(cache: impl Into<TokenCacheWrapper>, watcher: W) -> Self {
+    pub(crate) fn new(
+        cache: impl Into<TokenCacheWrapper>,
+        watcher: W,
+        liquidity_volume: BigDecimal,
+    ) -> Self {
         Self {
             tokens_cache: cache.into(),
             watcher,
+            liquidity_volume,
         }
     }
 
     async fn update_token(&mut self, token: &Token) -> anyhow::Result<TokenMarketVolume> {
         let amount = self.watcher.get_token_market_volume(token).await?;
+        let fee_allowed = amount >= self.liquidity_volume;
+        let previous = self.tokens_cache.get_token_market_volume(token.id).await?;
+        if previous.and_then(|market| market.fee_allowed) != Some(fee_allowed) {
+            vlog::info!(
+                "Token {} is {} for paying fees, market volume {}",
+                token.symbol,
+                if fee_allowed { "allowed" } else { "disallowed" },
+                amount
+            );
+        }
+        metrics::gauge!(
+            "ticker.validator.fee_allowed",
+            if fee_allowed { 1.0 } else { 0.0 },
+            "token" => token.symbol.clone()
+        );
+
         let market = TokenMarketVolume {
             market_volume: big_decimal_to_ratio(&amount).unwrap(),
             last_updated: Utc::now(),
+            fee_allowed: Some(fee_allowed),
         };
 
         if let Err(e) = self
@@ -121,6 +146,11 @@ impl FeeTokenValidator {
     pub(crate) async fn token_allowed(&self, token: TokenLike) -> anyhow::Result<bool> {
         let token = self.resolve_token(token).await?;
         if let Some(token) = token {
+            // The operator override takes precedence over the other rules.
+            let settings = self.tokens_cache.get_token_admin_settings(token.id).await?;
+            if let Some(fee_eligible) = settings.fee_eligible {
+                return Ok(fee_eligible);
+            }
             if self.unconditionally_valid.contains(&token.address) {
                 return Ok(true);
             }
@@ -145,7 +175,10 @@ impl FeeTokenValidator {
         if Utc::now() - volume.last_updated > self.available_time {
             vlog::warn!("Token market amount for {} is not relevant", &token.symbol)
         }
-        let allowed = ratio_to_big_decimal(&volume.market_volume, 2) >= self.liquidity_volume;
+        // The tokens not checked by the market updater yet are judged by their market volume.
+        let allowed = volume.fee_allowed.unwrap_or_else(|| {
+            ratio_to_big_decimal(&volume.market_volume, 2) >= self.liquidity_volume
+        });
         metrics::histogram!("ticker.validator.check_token", start.elapsed());
         Ok(allowed)
     }
@@ -168,7 +201,7 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
     use tokio::sync::Mutex;
-    use zksync_types::{TokenId, TokenKind};
+    use zksync_types::{tokens::TokenAdminSettings, TokenId, TokenKind};
 
     #[derive(Clone)]
     struct InMemoryTokenWatcher {
@@ -207,6 +240,7 @@ mod tests {
             TokenMarketVolume {
                 market_volume: Ratio::new(BigUint::from(10u32), BigUint::from(1u32)),
                 last_updated: Utc::now(),
+                fee_allowed: None,
             },
         );
         market.insert(
@@ -214,6 +248,7 @@ mod tests {
             TokenMarketVolume {
                 market_volume: Ratio::new(BigUint::from(200u32), BigUint::from(1u32)),
                 last_updated: Utc::now(),
+                fee_allowed: None,
             },
         );
 
@@ -242,7 +277,7 @@ mod tests {
             unconditionally_valid,
         );
 
-        let mut updater = MarketUpdater::new(cache, watcher);
+        let mut updater = MarketUpdater::new(cache, watcher, BigDecimal::from(100));
         updater.update_all_tokens(all_tokens).await.unwrap();
 
         let new_dai_token_market = validator
@@ -256,6 +291,7 @@ mod tests {
             new_dai_token_market.market_volume,
             big_decimal_to_ratio(&BigDecimal::from(200)).unwrap()
         );
+        assert_eq!(new_dai_token_market.fee_allowed, Some(true));
 
         let new_phnx_token_market = validator
             .tokens_cache
@@ -267,6 +303,7 @@ mod tests {
             new_phnx_token_market.market_volume,
             big_decimal_to_ratio(&BigDecimal::from(10)).unwrap()
         );
+        assert_eq!(new_phnx_token_market.fee_allowed, Some(false));
 
         let dai_allowed = validator
             .token_allowed(TokenLike::Address(dai_token_address))
@@ -284,4 +321,67 @@ mod tests {
         assert!(!phnx_allowed);
         assert!(eth_allowed);
     }
+
+    /// Checks that the operator overrides take precedence over the market volume.
+    #[tokio::test]
+    async fn admin_settings_override() {
+        let dai_token_address =
+            Address::from_str("6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let dai_token = Token::new(TokenId(1), dai_token_address, "DAI", 18, TokenKind::ERC20);
+        let phnx_token_address =
+            Address::from_str("38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7").unwrap();
+        let phnx_token = Token::new(TokenId(2), phnx_token_address, "PHNX", 18, TokenKind::ERC20);
+
+        let mut tokens = HashMap::new();
+        tokens.insert(TokenLike::Address(dai_token_address), dai_token.clone());
+        tokens.insert(TokenLike::Address(phnx_token_address), phnx_token.clone());
+        let mut market = HashMap::new();
+        for (token, volume) in &[(&dai_token, 200u32), (&phnx_token, 10u32)] {
+            market.insert(
+                token.id,
+                TokenMarketVolume {
+                    market_volume: Ratio::from_integer(BigUint::from(*volume)),
+                    last_updated: Utc::now(),
+                    fee_allowed: None,
+                },
+            );
+        }
+        let mut admin_settings = HashMap::new();
+        admin_settings.insert(
+            dai_token.id,
+            TokenAdminSettings {
+                fee_eligible: Some(false),
+                disabled: false,
+            },
+        );
+        admin_settings.insert(
+            phnx_token.id,
+            TokenAdminSettings {
+                fee_eligible: Some(true),
+                disabled: false,
+            },
+        );
+
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market)
+            .with_admin_settings(admin_settings);
+        let validator = FeeTokenValidator::new(
+            cache,
+            chrono::Duration::seconds(100),
+            BigDecimal::from(100),
+            HashSet::new(),
+        );
+
+        // Token explicitly marked as ineligible is not allowed despite the sufficient volume.
+        assert!(!validator
+            .token_allowed(TokenLike::Address(dai_token_address))
+            .await
+            .unwrap());
+        // Token explicitly marked as eligible is allowed despite the low volume.
+        assert!(validator
+            .token_allowed(TokenLike::Address(phnx_token_address))
+            .await
+            .unwrap());
+    }
 }
@@ -6,13 +6,13 @@
 
 // Built-in uses
 use std::collections::HashSet;
+use std::fmt;
 use std::time::Instant;
 
 // External uses
-use futures::{
-    channel::{mpsc, oneshot},
-    StreamExt,
-};
+use futures::{channel::mpsc, StreamExt};
+use serde::Serialize;
+use thiserror::Error;
 use tokio::task::JoinHandle;
 
 // Workspace uses
@@ -21,10 +21,73 @@ use zksync_types::{
     tx::{error::TxAddError, EthBatchSignData, EthSignData, TxEthSignature},
     Address, Order, SignedZkSyncTx, Token, ZkSyncTx,
 };
+use zksync_utils::deadline::DeadlineSender;
 // Local uses
-use crate::eth_checker::EthereumChecker;
+use crate::eth_checker::{EthSignerType, EthereumChecker};
 use zksync_types::tx::TransactionError;
 
+/// Reason why the batch signer is not matched by any of the batch signatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchSignerFailureReason {
+    /// There are no signatures of the kind required by the signer type, e.g. the smart
+    /// contract wallet is a signer, but the batch is signed with ECDSA signatures only.
+    MissingSignature,
+    /// None of the signatures of the required kind is valid for the signer.
+    InvalidSignature,
+}
+
+/// Batch signer that failed the Ethereum signature verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSignerFailure {
+    pub signer: Address,
+    pub signer_type: EthSignerType,
+    pub reason: BatchSignerFailureReason,
+}
+
+impl fmt::Display for BatchSignerFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let signature_kind = match self.signer_type {
+            EthSignerType::Eoa => "ECDSA",
+            EthSignerType::Contract => "EIP1271",
+        };
+        match self.reason {
+            BatchSignerFailureReason::MissingSignature => {
+                write!(f, "{:?}: no {} signature", self.signer, signature_kind)
+            }
+            BatchSignerFailureReason::InvalidSignature => {
+                write!(f, "{:?}: invalid {} signature", self.signer, signature_kind)
+            }
+        }
+    }
+}
+
+/// Signers of the batch not matched by the batch signatures.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct BatchSignersError(pub Vec<BatchSignerFailure>);
+
+impl fmt::Display for BatchSignersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Eth signature is incorrect for the batch signers: ")?;
+        for (i, failure) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", failure)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum SignatureCheckError {
+    #[error(transparent)]
+    TxAdd(#[from] TxAddError),
+    #[error(transparent)]
+    BatchSigners(#[from] BatchSignersError),
+}
+
 /// `TxVariant` is used to form a verify request. It is possible to wrap
 /// either a single transaction, or the transaction batch.
 #[derive(Debug, Clone)]
@@ -33,6 +96,7 @@ pub enum TxVariant {
     Batch(Vec<SignedZkSyncTx>, Option<EthBatchSignData>),
     Order(Box<Order>),
     Toggle2FA,
+    AccountRecovery,
 }
 
 /// Wrapper on a `TxVariant` which guarantees that (a batch of)
@@ -50,7 +114,7 @@ impl VerifiedTx {
     pub async fn verify(
         request_data: RequestData,
         eth_checker: &EthereumChecker,
-    ) -> Result<Self, TxAddError> {
+    ) -> Result<Self, SignatureCheckError> {
         verify_eth_signature(&request_data, eth_checker).await?;
         let mut tx_variant = request_data.get_tx_variant();
         verify_tx_correctness(&mut tx_variant)?;
@@ -71,6 +135,9 @@ impl VerifiedTx {
             TxVariant::Batch(_, _) => panic!("called `unwrap_tx` on a `Batch` value"),
             TxVariant::Order(_) => panic!("called `unwrap_tx` on an `Order` value"),
             TxVariant::Toggle2FA => panic!("called `unwrap_tx` on an `Toggle2FA` value"),
+            TxVariant::AccountRecovery => {
+                panic!("called `unwrap_tx` on an `AccountRecovery` value")
+            }
         }
     }
 
@@ -81,6 +148,9 @@ impl VerifiedTx {
             TxVariant::Tx(_) => panic!("called `unwrap_batch` on a `Tx` value"),
             TxVariant::Order(_) => panic!("called `unwrap_batch` on an `Order` value"),
             TxVariant::Toggle2FA => panic!("called `unwrap_batch` on an `Toggle2FA` value"),
+            TxVariant::AccountRecovery => {
+                panic!("called `unwrap_batch` on an `AccountRecovery` value")
+            }
         }
     }
 }
@@ -89,7 +159,7 @@ impl VerifiedTx {
 async fn verify_eth_signature(
     request_data: &RequestData,
     eth_checker: &EthereumChecker,
-) -> Result<(), TxAddError> {
+) -> Result<(), SignatureCheckError> {
     match request_data {
         RequestData::Tx(request) => {
             verify_eth_signature_single_tx(
@@ -106,7 +176,7 @@ async fn verify_eth_signature(
             let txs = &request.txs;
 
             if accounts.len() != request.txs.len() {
-                return Err(TxAddError::Other);
+                return Err(TxAddError::Other.into());
             }
             if let Some(batch_sign_data) = &request.batch_sign_data {
                 verify_eth_signature_txs_batch(txs, accounts, batch_sign_data, eth_checker).await?;
@@ -128,7 +198,19 @@ async fn verify_eth_signature(
             )
             .await;
             if !signature_correct {
-                return Err(TxAddError::IncorrectEthSignature);
+                return Err(TxAddError::IncorrectEthSignature.into());
+            }
+        }
+        RequestData::AccountRecovery(request) => {
+            let signature_correct = verify_ethereum_signature(
+                &request.sign_data.signature,
+                &request.sign_data.message,
+                request.sender,
+                eth_checker,
+            )
+            .await;
+            if !signature_correct {
+                return Err(TxAddError::IncorrectEthSignature.into());
             }
         }
         RequestData::Toggle2FA(request) => {
@@ -140,7 +222,7 @@ async fn verify_eth_signature(
             )
             .await;
             if !signature_correct {
-                return Err(TxAddError::IncorrectEthSignature);
+                return Err(TxAddError::IncorrectEthSignature.into());
             }
         }
     }
@@ -229,68 +311,116 @@ async fn verify_eth_signature_single_tx(
     Ok(())
 }
 
+/// Checks that every sender of the batch is matched by one of the batch signatures.
+///
+/// The kind of the signature depends on the sender type: the externally owned accounts
+/// are recovered from the ECDSA signatures, while the smart contract wallets are checked
+/// against the EIP1271 signatures. All the unmatched senders are reported.
 async fn verify_eth_signature_txs_batch(
     txs: &[SignedZkSyncTx],
     senders: &[Address],
     batch_sign_data: &EthBatchSignData,
     eth_checker: &EthereumChecker,
-) -> Result<(), TxAddError> {
+) -> Result<(), SignatureCheckError> {
     let start = Instant::now();
-    // Cache for verified senders.
-    let mut signers = HashSet::with_capacity(senders.len());
-    // For every sender check whether there exists at least one signature that matches it.
     let old_message = match txs.iter().all(|tx| tx.is_backwards_compatible()) {
         true => Some(EthBatchSignData::get_old_ethereum_batch_message(
             txs.iter().map(|tx| &tx.tx),
         )),
         false => None,
     };
+    let mut messages = vec![batch_sign_data.message.as_slice()];
+    messages.extend(old_message.as_deref());
+
+    // The ECDSA signers are recovered once for all the senders.
+    let mut recovered_signers = HashSet::new();
+    let mut has_ecdsa_signatures = false;
+    let mut eip1271_signatures = Vec::new();
+    for signature in &batch_sign_data.signatures {
+        match signature {
+            TxEthSignature::EthereumSignature(packed_signature) => {
+                has_ecdsa_signatures = true;
+                for message in &messages {
+                    if let Ok(signer) = packed_signature.signature_recover_signer(message) {
+                        recovered_signers.insert(signer);
+                    }
+                }
+            }
+            TxEthSignature::EIP1271Signature(signature) => eip1271_signatures.push(signature),
+        }
+    }
 
-    for sender in senders {
-        if signers.contains(sender) {
+    let mut checked_senders = HashSet::with_capacity(senders.len());
+    let mut failures = Vec::new();
+    for &sender in senders {
+        if !checked_senders.insert(sender) {
             continue;
         }
-        // All possible signers are cached already and this sender didn't match any of them.
-        if signers.len() == batch_sign_data.signatures.len() {
-            return Err(TxAddError::IncorrectEthSignature);
+        // The code of the sender is only loaded if no ECDSA signature is recovered to it.
+        if recovered_signers.contains(&sender) {
+            continue;
         }
-        // This block will set the `sender_correct` variable to `true` at the first match.
-        let mut sender_correct = false;
-        for signature in &batch_sign_data.signatures {
-            let mut signature_correct = verify_ethereum_signature(
-                signature,
-                &batch_sign_data.message,
-                *sender,
-                eth_checker,
-            )
-            .await;
-            if !signature_correct {
-                if let Some(old_message) = &old_message {
-                    signature_correct = verify_ethereum_signature(
-                        signature,
-                        old_message.as_slice(),
-                        *sender,
-                        eth_checker,
-                    )
-                    .await;
-                }
+        let signer_type = eth_checker.signer_type(sender).await.map_err(|err| {
+            vlog::warn!(
+                "Unable to resolve the type of the signer {:?}: {}",
+                sender,
+                err
+            );
+            TxAddError::Other
+        })?;
+        let reason = match signer_type {
+            EthSignerType::Eoa if has_ecdsa_signatures => {
+                Some(BatchSignerFailureReason::InvalidSignature)
             }
-            if signature_correct {
-                signers.insert(sender);
-                sender_correct = true;
-                break;
+            EthSignerType::Eoa => Some(BatchSignerFailureReason::MissingSignature),
+            EthSignerType::Contract if eip1271_signatures.is_empty() => {
+                Some(BatchSignerFailureReason::MissingSignature)
             }
-        }
-        // No signature for this transaction found, return error.
-        if !sender_correct {
-            return Err(TxAddError::IncorrectEthSignature);
+            EthSignerType::Contract => {
+                let mut signature_correct = false;
+                'signatures: for &signature in &eip1271_signatures {
+                    for message in &messages {
+                        signature_correct = eth_checker
+                            .is_eip1271_signature_correct(sender, message, signature.clone())
+                            .await
+                            .expect("Unable to check EIP1271 signature");
+                        if signature_correct {
+                            break 'signatures;
+                        }
+                    }
+                }
+                if signature_correct {
+                    None
+                } else {
+                    Some(BatchSignerFailureReason::InvalidSignature)
+                }
+            }
+        };
+        if let Some(reason) = reason {
+            failures.push(BatchSignerFailure {
+                signer: sender,
+                signer_type,
+                reason,
+            });
         }
     }
+
     metrics::histogram!(
         "signature_checker.verify_eth_signature_txs_batch",
         start.elapsed()
     );
-    Ok(())
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        for failure in &failures {
+            let signer_type = match failure.signer_type {
+                EthSignerType::Eoa => "eoa",
+                EthSignerType::Contract => "contract",
+            };
+            metrics::increment_counter!("signature_checker.batch_signer_failure", "signer_type" => signer_type);
+        }
+        Err(BatchSignersError(failures).into())
+    }
 }
 
 /// Verifies the correctness of the ZKSync transaction(s) (including the
@@ -308,7 +438,7 @@ fn verify_tx_correctness(tx: &mut TxVariant) -> Result<(), TxAddError> {
         TxVariant::Order(order) => order
             .check_correctness()
             .map_err(|err| TxAddError::IncorrectTx(TransactionError::OrderError(err)))?,
-        TxVariant::Toggle2FA => {} // There is no data to check correctness of
+        TxVariant::Toggle2FA | TxVariant::AccountRecovery => {} // There is no data to check correctness of
     }
     Ok(())
 }
@@ -346,12 +476,19 @@ pub struct Toggle2FARequest {
     pub sender: Address,
 }
 
+/// Request of the account owner or the guardian related to the account recovery.
+#[derive(Debug)]
+pub struct AccountRecoveryRequest {
+    pub sign_data: EthSignData,
+    pub sender: Address,
+}
+
 /// Request for the signature check.
 #[derive(Debug)]
 pub struct VerifySignatureRequest {
     pub data: RequestData,
-    /// Channel for sending the check response.
-    pub response: oneshot::Sender<Result<VerifiedTx, TxAddError>>,
+    /// Channel for sending the check response, the check is skipped if the request is abandoned.
+    pub response: DeadlineSender<Result<VerifiedTx, SignatureCheckError>>,
 }
 
 #[derive(Debug)]
@@ -360,6 +497,7 @@ pub enum RequestData {
     Batch(BatchRequest),
     Order(OrderRequest),
     Toggle2FA(Toggle2FARequest),
+    AccountRecovery(AccountRecoveryRequest),
 }
 
 impl RequestData {
@@ -371,6 +509,7 @@ impl RequestData {
             }
             RequestData::Order(request) => TxVariant::Order(request.order.clone()),
             RequestData::Toggle2FA(_) => TxVariant::Toggle2FA,
+            RequestData::AccountRecovery(_) => TxVariant::AccountRecovery,
         }
     }
 }
@@ -390,6 +529,10 @@ pub fn start_sign_checker(
         eth_checker: EthereumChecker,
     ) {
         while let Some(VerifySignatureRequest { data, response }) = input.next().await {
+            if response.is_abandoned() {
+                metrics::increment_counter!("signature_checker.abandoned_requests");
+                continue;
+            }
             let eth_checker = eth_checker.clone();
             tokio::spawn(async move {
                 let resp = VerifiedTx::verify(data, &eth_checker).await;
@@ -400,3 +543,78 @@ pub fn start_sign_checker(
     }
     tokio::spawn(checker_routine(input, eth_checker))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_eth_client::clients::mock::MockEthereum;
+    use zksync_types::{
+        tx::{EIP1271Signature, PackedEthSignature},
+        H256,
+    };
+
+    fn eth_signature(private_key: &H256, message: &[u8]) -> TxEthSignature {
+        TxEthSignature::EthereumSignature(PackedEthSignature::sign(private_key, message).unwrap())
+    }
+
+    #[tokio::test]
+    async fn batch_signer_failures() {
+        // The mock has no deployed contracts, so all the signers are externally owned.
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(MockEthereum::default()));
+        let keys: Vec<_> = (1..=3).map(H256::from_low_u64_be).collect();
+        let senders: Vec<_> = keys
+            .iter()
+            .map(|key| PackedEthSignature::address_from_private_key(key).unwrap())
+            .collect();
+        let message = b"batch message".to_vec();
+
+        let batch_sign_data = EthBatchSignData {
+            signatures: vec![
+                eth_signature(&keys[0], &message),
+                eth_signature(&keys[1], &message),
+            ],
+            message: message.clone(),
+        };
+        verify_eth_signature_txs_batch(&[], &senders[..2], &batch_sign_data, &eth_checker)
+            .await
+            .unwrap();
+
+        // Every unmatched signer is reported once.
+        let signers = [senders[0], senders[2], senders[2]];
+        let err = verify_eth_signature_txs_batch(&[], &signers, &batch_sign_data, &eth_checker)
+            .await
+            .unwrap_err();
+        let failures = match err {
+            SignatureCheckError::BatchSigners(BatchSignersError(failures)) => failures,
+            err => panic!("Unexpected error: {}", err),
+        };
+        assert_eq!(
+            failures,
+            vec![BatchSignerFailure {
+                signer: senders[2],
+                signer_type: EthSignerType::Eoa,
+                reason: BatchSignerFailureReason::InvalidSignature,
+            }]
+        );
+
+        // Contract signatures don't match the externally owned accounts.
+        let batch_sign_data = EthBatchSignData {
+            signatures: vec![TxEthSignature::EIP1271Signature(EIP1271Signature(vec![
+                0;
+                65
+            ]))],
+            message,
+        };
+        let err =
+            verify_eth_signature_txs_batch(&[], &senders[..1], &batch_sign_data, &eth_checker)
+                .await
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Eth signature is incorrect for the batch signers: {:?}: no ECDSA signature",
+                senders[0]
+            )
+        );
+    }
+}
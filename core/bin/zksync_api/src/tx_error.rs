@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -15,3 +16,30 @@ pub enum Toggle2FAError {
     #[error("Request to enable 2FA should not have PubKeyHash field set")]
     UnusedPubKeyHash,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Error)]
+pub enum AccountRecoveryError {
+    #[error("Database unavailable")]
+    DbError,
+
+    #[error("Account does not exist")]
+    AccountNotFound,
+
+    #[error("Too many guardians, the limit is {0}")]
+    TooManyGuardians(usize),
+
+    #[error("Signer is not a guardian of the account")]
+    NotGuardian,
+
+    #[error("Another recovery of the account is in progress")]
+    InProgress,
+
+    #[error("There is no recovery of the account in progress")]
+    NoRecovery,
+
+    #[error("Recovery to this public key hash was cancelled by the account owner")]
+    Cancelled,
+
+    #[error("Recovery is timelocked until {0}, ChangePubKey must not be valid before it")]
+    Timelocked(DateTime<Utc>),
+}
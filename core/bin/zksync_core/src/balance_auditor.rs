@@ -0,0 +1,347 @@
+//! Auditor of the stored balances.
+//!
+//! The finalized balances are stored in the `balances` table, which is updated by applying
+//! the account updates history once the block is executed on Ethereum. A bug in either of these
+//! would only be noticed once the proof of the block fails. The auditor periodically picks random
+//! accounts, recomputes their balances from the history and compares them with the stored ones,
+//! so the discrepancies are reported together with the range of blocks that introduced them.
+
+// Built-in uses
+use std::collections::HashMap;
+use std::fmt;
+// External uses
+use num::{BigUint, Zero};
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::BalanceAuditorConfig;
+use zksync_notifier::Notifier;
+use zksync_storage::{chain::account::records::BalanceHistoryEntry, ConnectionPool};
+use zksync_types::{AccountId, BlockNumber, TokenId};
+
+/// Balance of the account that doesn't match its updates history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BalanceDiscrepancy {
+    /// The update doesn't start with the balance the previous update of the token ended with.
+    BrokenHistory {
+        account_id: AccountId,
+        token: TokenId,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        expected: BigUint,
+        actual: BigUint,
+    },
+    /// The stored balance differs from the one recomputed from the history.
+    StoredBalance {
+        account_id: AccountId,
+        token: TokenId,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        expected: BigUint,
+        actual: BigUint,
+    },
+}
+
+impl BalanceDiscrepancy {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::BrokenHistory { .. } => "broken_history",
+            Self::StoredBalance { .. } => "stored_balance",
+        }
+    }
+}
+
+impl fmt::Display for BalanceDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (description, account_id, token, from_block, to_block, expected, actual) = match self {
+            Self::BrokenHistory {
+                account_id,
+                token,
+                from_block,
+                to_block,
+                expected,
+                actual,
+            } => (
+                "Broken balance history",
+                account_id,
+                token,
+                from_block,
+                to_block,
+                expected,
+                actual,
+            ),
+            Self::StoredBalance {
+                account_id,
+                token,
+                from_block,
+                to_block,
+                expected,
+                actual,
+            } => (
+                "Stored balance mismatch",
+                account_id,
+                token,
+                from_block,
+                to_block,
+                expected,
+                actual,
+            ),
+        };
+        write!(
+            f,
+            "{} of account {} token {} in blocks {}..={}: expected = {}, actual = {}",
+            description, account_id, token, from_block, to_block, expected, actual
+        )
+    }
+}
+
+/// Recomputes the balances of the account from its history up to `last_block` and compares them
+/// with the stored ones.
+///
+/// The history must be sorted in the order of applying.
+pub fn audit_account_balances(
+    account_id: AccountId,
+    stored_balances: &HashMap<TokenId, BigUint>,
+    history: &[BalanceHistoryEntry],
+    last_block: BlockNumber,
+) -> Vec<BalanceDiscrepancy> {
+    let mut discrepancies = Vec::new();
+    // The last balance of every token and the block it was set in.
+    let mut balances: HashMap<TokenId, (BigUint, BlockNumber)> = HashMap::new();
+
+    for entry in history {
+        // The account starts with the empty balances.
+        let (previous_balance, previous_block) = balances
+            .remove(&entry.token)
+            .unwrap_or_else(|| (BigUint::zero(), entry.block_number));
+        if previous_balance != entry.old_balance {
+            discrepancies.push(BalanceDiscrepancy::BrokenHistory {
+                account_id,
+                token: entry.token,
+                from_block: previous_block,
+                to_block: entry.block_number,
+                expected: previous_balance,
+                actual: entry.old_balance.clone(),
+            });
+        }
+        balances.insert(entry.token, (entry.new_balance.clone(), entry.block_number));
+    }
+
+    let mut tokens: Vec<_> = balances.keys().chain(stored_balances.keys()).collect();
+    tokens.sort_unstable();
+    tokens.dedup();
+    for token in tokens {
+        // Missing balances are considered to be zero.
+        let zero = BigUint::zero();
+        let (expected, from_block) = balances
+            .get(token)
+            .map(|(balance, block)| (balance, *block))
+            .unwrap_or((&zero, BlockNumber(0)));
+        let actual = stored_balances.get(token).unwrap_or(&zero);
+        if expected != actual {
+            discrepancies.push(BalanceDiscrepancy::StoredBalance {
+                account_id,
+                token: *token,
+                from_block,
+                to_block: last_block,
+                expected: expected.clone(),
+                actual: actual.clone(),
+            });
+        }
+    }
+    discrepancies
+}
+
+/// Audits the balances of the account.
+///
+/// Returns `None` if a block got finalized during the audit, since the loaded balances and
+/// the history can belong to the different blocks in that case.
+async fn audit_account(
+    db_pool: &ConnectionPool,
+    account_id: AccountId,
+) -> anyhow::Result<Option<Vec<BalanceDiscrepancy>>> {
+    let mut storage = db_pool.access_storage().await?;
+    let last_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+    let stored_balances = storage
+        .chain()
+        .account_schema()
+        .stored_account_balances(account_id)
+        .await?;
+    let history = storage
+        .chain()
+        .account_schema()
+        .account_balance_history(account_id, last_block)
+        .await?;
+    let last_block_after = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+    if last_block != last_block_after {
+        return Ok(None);
+    }
+
+    Ok(Some(audit_account_balances(
+        account_id,
+        &stored_balances,
+        &history,
+        last_block,
+    )))
+}
+
+async fn audit_balances(
+    db_pool: &ConnectionPool,
+    sample_size: u32,
+) -> anyhow::Result<Vec<BalanceDiscrepancy>> {
+    let account_ids = db_pool
+        .access_storage()
+        .await?
+        .chain()
+        .account_schema()
+        .sample_account_ids(sample_size)
+        .await?;
+
+    let mut discrepancies = Vec::new();
+    for account_id in account_ids {
+        match audit_account(db_pool, account_id).await? {
+            Some(account_discrepancies) => {
+                metrics::increment_counter!("balance_auditor.accounts_checked");
+                discrepancies.extend(account_discrepancies);
+            }
+            // The account will be checked by one of the next audits.
+            None => metrics::increment_counter!("balance_auditor.accounts_skipped"),
+        }
+    }
+    Ok(discrepancies)
+}
+
+/// Runs the periodic audit of the stored balances.
+#[must_use]
+pub fn run_balance_auditor(
+    db_pool: ConnectionPool,
+    config: BalanceAuditorConfig,
+) -> JoinHandle<()> {
+    let webhook_url = reqwest::Url::parse(&config.webhook_url).ok();
+    let notifier = webhook_url.map(Notifier::with_mattermost);
+    let mut timer = time::interval(config.audit_interval());
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            let discrepancies = match audit_balances(&db_pool, config.sample_size).await {
+                Ok(discrepancies) => discrepancies,
+                Err(err) => {
+                    vlog::warn!("Can't audit the stored balances: {}", err);
+                    continue;
+                }
+            };
+            for discrepancy in discrepancies {
+                vlog::error!("Balance discrepancy: {}", discrepancy);
+                metrics::increment_counter!("balance_auditor.discrepancy", "kind" => discrepancy.kind());
+                if let Some(notifier) = &notifier {
+                    if let Err(err) = notifier
+                        .send_balance_discrepancy_notify(&discrepancy.to_string())
+                        .await
+                    {
+                        vlog::warn!("Can't send the balance discrepancy alert: {}", err);
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(block: u32, token: u32, old_balance: u64, new_balance: u64) -> BalanceHistoryEntry {
+        BalanceHistoryEntry {
+            block_number: BlockNumber(block),
+            token: TokenId(token),
+            old_balance: old_balance.into(),
+            new_balance: new_balance.into(),
+        }
+    }
+
+    fn balances(balances: &[(u32, u64)]) -> HashMap<TokenId, BigUint> {
+        balances
+            .iter()
+            .map(|(token, balance)| (TokenId(*token), (*balance).into()))
+            .collect()
+    }
+
+    #[test]
+    fn consistent_balances() {
+        let history = vec![
+            entry(1, 0, 0, 100),
+            entry(1, 1, 0, 5),
+            entry(3, 0, 100, 40),
+            entry(4, 1, 5, 0),
+        ];
+        let stored = balances(&[(0, 40), (1, 0)]);
+        assert!(audit_account_balances(AccountId(1), &stored, &history, BlockNumber(5)).is_empty());
+
+        // The zero balances may be missing.
+        let stored = balances(&[(0, 40)]);
+        assert!(audit_account_balances(AccountId(1), &stored, &history, BlockNumber(5)).is_empty());
+    }
+
+    #[test]
+    fn broken_history() {
+        let history = vec![entry(1, 0, 0, 100), entry(3, 0, 90, 40), entry(4, 1, 5, 10)];
+        let stored = balances(&[(0, 40), (1, 10)]);
+        assert_eq!(
+            audit_account_balances(AccountId(1), &stored, &history, BlockNumber(5)),
+            vec![
+                BalanceDiscrepancy::BrokenHistory {
+                    account_id: AccountId(1),
+                    token: TokenId(0),
+                    from_block: BlockNumber(1),
+                    to_block: BlockNumber(3),
+                    expected: 100u32.into(),
+                    actual: 90u32.into(),
+                },
+                BalanceDiscrepancy::BrokenHistory {
+                    account_id: AccountId(1),
+                    token: TokenId(1),
+                    from_block: BlockNumber(4),
+                    to_block: BlockNumber(4),
+                    expected: 0u32.into(),
+                    actual: 5u32.into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn stored_balance_mismatch() {
+        let history = vec![entry(1, 0, 0, 100), entry(3, 0, 100, 40)];
+        let stored = balances(&[(0, 100), (2, 7)]);
+        assert_eq!(
+            audit_account_balances(AccountId(1), &stored, &history, BlockNumber(5)),
+            vec![
+                BalanceDiscrepancy::StoredBalance {
+                    account_id: AccountId(1),
+                    token: TokenId(0),
+                    from_block: BlockNumber(3),
+                    to_block: BlockNumber(5),
+                    expected: 40u32.into(),
+                    actual: 100u32.into(),
+                },
+                BalanceDiscrepancy::StoredBalance {
+                    account_id: AccountId(1),
+                    token: TokenId(2),
+                    from_block: BlockNumber(0),
+                    to_block: BlockNumber(5),
+                    expected: 0u32.into(),
+                    actual: 7u32.into(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,710 @@
+//! Controlled revert of the blocks which are not verified yet.
+//!
+//! The revert is requested by the administrator via the private core API. Before touching the
+//! storage, the block production is halted: the state keeper stops executing the transactions,
+//! and the committer stops storing the blocks and creating the aggregated operations.
+//!
+//! Then the revert is recorded to the storage, which pauses `eth_sender`: the new operations are
+//! not sent, while the ones in flight are waited for until they're confirmed, so the blocks
+//! committed on L1 are known and nothing is sent for the reverted blocks afterwards. Then the
+//! blocks committed on L1 are reverted on the contract, and the blocks are reverted in storage
+//! with their transactions returned to the mempool, which finishes the recorded revert and resumes
+//! `eth_sender`. If the revert fails before the contract is touched, it's cancelled.
+//!
+//! The revert transaction is sent with the fees chosen by the `eth_sender` strategy, and the
+//! stuck one is replaced with the one paying the escalated fees, the same way `eth_sender`
+//! replaces its own. Every sent transaction is recorded, so if the server is stopped meanwhile,
+//! the revert is resumed on start: the recorded transactions are waited for instead of sending
+//! a new one, and the storage is reverted once one of them succeeds.
+//!
+//! If the revert fails on the contract, it's cancelled, so `eth_sender` is resumed: the nonce
+//! of the revert transaction which isn't sent is returned, and the revert transaction not
+//! mined in time is replaced with the empty one, so the transactions of `eth_sender` don't
+//! wait for its nonce.
+//!
+//! The committed state snapshot shared with the API is reset once the block production is halted,
+//! so the API loads the accounts from the storage instead of serving the reverted state.
+//!
+//! The in-memory state of the state keeper and the root hash calculator is ahead of the reverted
+//! storage, so once the block production is halted, the block reverter sends the stop signal to
+//! the server. After the restart the state keeper is initialized from the reverted storage and
+//! executes the reverted blocks again before proposing the new ones.
+
+// Built-in uses
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+// External uses
+use anyhow::format_err;
+use ethabi::Token;
+use futures::{
+    channel::{mpsc, oneshot},
+    future, SinkExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{Mutex, OwnedRwLockReadGuard, RwLock},
+    task::JoinHandle,
+    time,
+};
+use web3::{
+    contract::Options,
+    types::{TransactionReceipt, U256, U64},
+};
+// Workspace uses
+use zksync_config::ETHSenderConfig;
+use zksync_eth_client::EthereumGateway;
+use zksync_eth_sender::{set_tx_fees, OperatorTxFees};
+use zksync_state::shared::SharedCommittedState;
+use zksync_storage::{chain::block::records::PendingRevertTx, ConnectionPool, StorageProcessor};
+use zksync_types::{aggregated_operations::stored_block_info, Address, BlockNumber, H256};
+
+/// Gas limit of the revert transaction is `REVERT_BASE_GAS + REVERT_GAS_PER_BLOCK * blocks`.
+const REVERT_BASE_GAS: usize = 200_000;
+const REVERT_GAS_PER_BLOCK: usize = 15_000;
+/// Gas limit of the empty transaction replacing the revert transaction not mined in time.
+const CANCEL_TX_GAS: usize = 21_000;
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(1000);
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Time for `eth_sender` to confirm the operations in flight once it's paused.
+const ETH_SENDER_DRAIN_TIMEOUT: Duration = Duration::from_secs(1000);
+/// Time given to the private API to deliver the response before the server is stopped.
+const SHUTDOWN_DELAY: Duration = Duration::from_secs(1);
+
+/// Halts the components writing the blocks to the storage, so the blocks can be reverted.
+/// The clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct BlockProductionHalt(Arc<RwLock<bool>>);
+
+impl BlockProductionHalt {
+    /// Returns the guard to hold while the unit of work is done,
+    /// or `None` if the block production is halted and the work must be skipped.
+    pub async fn enter(&self) -> Option<OwnedRwLockReadGuard<bool>> {
+        let guard = self.0.clone().read_owned().await;
+        if *guard {
+            None
+        } else {
+            Some(guard)
+        }
+    }
+
+    /// Halts the block production, waiting for the units of work in progress to be done.
+    pub async fn halt(&self) {
+        *self.0.write().await = true;
+    }
+
+    pub async fn is_halted(&self) -> bool {
+        *self.0.read().await
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertBlocksRequest {
+    /// The blocks with the greater numbers are reverted.
+    pub last_correct_block: BlockNumber,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertBlocksResponse {
+    pub last_correct_block: BlockNumber,
+    /// The last block stored before the revert.
+    pub last_reverted_block: BlockNumber,
+    /// Hash of the transaction reverting the blocks committed on L1, if there were any.
+    pub revert_tx_hash: Option<H256>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BlockRevertError {
+    #[error("Blocks up to {last_verified_block} are already verified")]
+    AlreadyVerified { last_verified_block: BlockNumber },
+    #[error("There are no blocks after block {0}")]
+    NothingToRevert(BlockNumber),
+    #[error("The revert of the blocks after block {0} is in progress")]
+    InProgress(BlockNumber),
+    #[error("{0} Ethereum operations are still in flight")]
+    OperationsInFlight(usize),
+    #[error("Failed to revert the blocks on the contract: {0}")]
+    Contract(anyhow::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] anyhow::Error),
+}
+
+impl BlockRevertError {
+    /// Checks whether the request is rejected without halting the block production.
+    pub fn is_rejected(&self) -> bool {
+        matches!(
+            self,
+            Self::AlreadyVerified { .. } | Self::NothingToRevert(_) | Self::InProgress(_)
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct BlockRevertRequest {
+    pub last_correct_block: BlockNumber,
+    pub response: oneshot::Sender<Result<RevertBlocksResponse, BlockRevertError>>,
+}
+
+/// Transaction reverting the blocks on the contract.
+struct RevertTx {
+    data: Vec<u8>,
+    gas_limit: U256,
+}
+
+struct BlockReverter {
+    pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    /// Fees of the revert transaction, escalated the same way `eth_sender` escalates its own.
+    tx_fees: Mutex<OperatorTxFees>,
+    /// The revert transaction not mined within this number of blocks is replaced.
+    expected_wait_time: u64,
+    /// Address the revert transaction is sent from.
+    operator_address: Address,
+    halt: BlockProductionHalt,
+    committed_state: Option<SharedCommittedState>,
+}
+
+impl BlockReverter {
+    /// Returns the last stored block if the blocks after `last_correct_block` can be reverted.
+    async fn check_request(
+        storage: &mut StorageProcessor<'_>,
+        last_correct_block: BlockNumber,
+    ) -> Result<BlockNumber, BlockRevertError> {
+        let last_verified_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        if last_verified_block > last_correct_block {
+            return Err(BlockRevertError::AlreadyVerified {
+                last_verified_block,
+            });
+        }
+
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        if last_block <= last_correct_block {
+            return Err(BlockRevertError::NothingToRevert(last_correct_block));
+        }
+
+        // Only the revert in progress can be resumed.
+        let pending_revert = storage.chain().block_schema().load_pending_revert().await?;
+        match pending_revert {
+            Some(pending) if pending.last_correct_block != last_correct_block => {
+                Err(BlockRevertError::InProgress(pending.last_correct_block))
+            }
+            _ => Ok(last_block),
+        }
+    }
+
+    /// Reverts the blocks with logging and metrics.
+    async fn handle_revert(
+        &self,
+        last_correct_block: BlockNumber,
+    ) -> Result<RevertBlocksResponse, BlockRevertError> {
+        let start = Instant::now();
+        let result = self.revert_blocks(last_correct_block).await;
+        match &result {
+            Ok(response) => vlog::warn!(
+                "Reverted the blocks {}..={}, revert transaction: {:?}",
+                *response.last_correct_block + 1,
+                response.last_reverted_block,
+                response.revert_tx_hash
+            ),
+            Err(err) => vlog::error!(
+                "Failed to revert the blocks after block {}: {}",
+                last_correct_block,
+                err
+            ),
+        }
+        metrics::histogram!("block_revert.revert_blocks", start.elapsed());
+        result
+    }
+
+    async fn pending_revert(&self) -> anyhow::Result<Option<BlockNumber>> {
+        let pending = self
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .load_pending_revert()
+            .await?;
+        Ok(pending.map(|pending| pending.last_correct_block))
+    }
+
+    async fn cancel_revert(&self) -> anyhow::Result<()> {
+        self.pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .clear_pending_revert()
+            .await
+    }
+
+    async fn revert_blocks(
+        &self,
+        last_correct_block: BlockNumber,
+    ) -> Result<RevertBlocksResponse, BlockRevertError> {
+        let mut storage = self.pool.access_storage().await?;
+        Self::check_request(&mut storage, last_correct_block).await?;
+
+        vlog::warn!(
+            "Reverting the blocks after block {}, halting the block production",
+            last_correct_block
+        );
+        self.halt.halt().await;
+        // Nothing is published after the halt, the snapshot may include the reverted blocks.
+        if let Some(committed_state) = &self.committed_state {
+            committed_state.reset();
+        }
+        // The blocks could be sealed or verified while the production was being halted.
+        Self::check_request(&mut storage, last_correct_block).await?;
+
+        storage
+            .chain()
+            .block_schema()
+            .start_revert(last_correct_block)
+            .await?;
+        // The operations confirmed meanwhile could verify the blocks.
+        let last_reverted_block = match self.wait_for_eth_sender(&mut storage).await {
+            Ok(()) => Self::check_request(&mut storage, last_correct_block).await,
+            Err(err) => Err(err),
+        };
+        let last_reverted_block = match last_reverted_block {
+            Ok(last_reverted_block) => last_reverted_block,
+            Err(err) => {
+                // Nothing is reverted yet, so `eth_sender` is resumed.
+                storage
+                    .chain()
+                    .block_schema()
+                    .clear_pending_revert()
+                    .await?;
+                return Err(err);
+            }
+        };
+
+        let revert_tx_hash = match self
+            .revert_blocks_on_contract(&mut storage, last_correct_block)
+            .await
+        {
+            Ok(revert_tx_hash) => revert_tx_hash,
+            Err(err) => {
+                // The blocks are not reverted on the contract, and the revert transaction is
+                // not going to be mined, so `eth_sender` is resumed.
+                storage
+                    .chain()
+                    .block_schema()
+                    .clear_pending_revert()
+                    .await?;
+                return Err(err);
+            }
+        };
+        // Finishes the recorded revert, so `eth_sender` is resumed with the reverted operations.
+        storage
+            .chain()
+            .block_schema()
+            .revert_blocks(last_correct_block)
+            .await?;
+
+        Ok(RevertBlocksResponse {
+            last_correct_block,
+            last_reverted_block,
+            revert_tx_hash,
+        })
+    }
+
+    /// Waits until `eth_sender` has no operations in flight, it doesn't send the new ones
+    /// once the revert is started.
+    async fn wait_for_eth_sender(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<(), BlockRevertError> {
+        let start = Instant::now();
+        let mut timer = time::interval(CONFIRMATION_POLL_INTERVAL);
+        loop {
+            timer.tick().await;
+
+            let in_flight = storage
+                .ethereum_schema()
+                .load_unconfirmed_operations()
+                .await?
+                .len();
+            if in_flight == 0 {
+                return Ok(());
+            }
+            if start.elapsed() > ETH_SENDER_DRAIN_TIMEOUT {
+                return Err(BlockRevertError::OperationsInFlight(in_flight));
+            }
+        }
+    }
+
+    /// Reverts the blocks committed on L1, returns the hash of the mined revert transaction.
+    /// The transactions recorded by the interrupted revert are waited for instead of sending
+    /// a new one.
+    async fn revert_blocks_on_contract(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        last_correct_block: BlockNumber,
+    ) -> Result<Option<H256>, BlockRevertError> {
+        let recorded_tx = storage
+            .chain()
+            .block_schema()
+            .load_pending_revert()
+            .await?
+            .and_then(|pending| pending.revert_tx);
+
+        // Nothing is confirmed on L1 while the revert is in progress, so the data of the
+        // replacement of the recorded transaction is the same.
+        let last_committed_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_confirmed_block()
+            .await?;
+        if last_committed_block <= last_correct_block && recorded_tx.is_none() {
+            return Ok(None);
+        }
+
+        let mut blocks = Vec::new();
+        for block_number in (*last_correct_block + 1..=*last_committed_block).rev() {
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_block(BlockNumber(block_number))
+                .await?
+                .ok_or_else(|| format_err!("No block {} in storage", block_number))?;
+            blocks.push(block);
+        }
+        let tx_arg = Token::Array(blocks.iter().map(stored_block_info).collect());
+        let revert_tx = RevertTx {
+            data: self.eth_gateway.encode_tx_data("revertBlocks", tx_arg),
+            gas_limit: U256::from(REVERT_BASE_GAS + REVERT_GAS_PER_BLOCK * blocks.len()),
+        };
+
+        let sent_tx = match recorded_tx {
+            Some(sent_tx) => {
+                vlog::info!(
+                    "Waiting for the recorded transactions {:?} reverting the blocks",
+                    sent_tx.tx_hashes
+                );
+                sent_tx
+            }
+            None => {
+                let sent_tx = self.send_revert_tx(storage, &revert_tx, None).await?;
+                vlog::info!(
+                    "Sent the transaction {:#x} reverting the blocks {}..={} on the contract",
+                    sent_tx.tx_hashes[0],
+                    *last_correct_block + 1,
+                    last_committed_block
+                );
+                sent_tx
+            }
+        };
+
+        let tx_hash = self.confirm_revert_tx(storage, &revert_tx, sent_tx).await?;
+        Ok(Some(tx_hash))
+    }
+
+    /// Sends the revert transaction, or the replacement of the stuck one paying the escalated
+    /// fees the same way `eth_sender` does. The sent transaction is recorded, so the revert is
+    /// resumed with it if the server is stopped meanwhile.
+    async fn send_revert_tx(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        revert_tx: &RevertTx,
+        stuck_tx: Option<&PendingRevertTx>,
+    ) -> Result<PendingRevertTx, BlockRevertError> {
+        let (nonce, (gas_price, priority_fee)) = match stuck_tx {
+            Some(stuck_tx) => {
+                let fees = self
+                    .tx_fees
+                    .lock()
+                    .await
+                    .replacement_tx_fees(
+                        &self.eth_gateway,
+                        stuck_tx.gas_price,
+                        stuck_tx.priority_fee,
+                    )
+                    .await
+                    .map_err(BlockRevertError::Contract)?;
+                (stuck_tx.nonce, fees)
+            }
+            None => {
+                let fees = self
+                    .tx_fees
+                    .lock()
+                    .await
+                    .new_tx_fees(&self.eth_gateway)
+                    .await
+                    .map_err(BlockRevertError::Contract)?;
+                // The nonce is taken the same way `eth_sender` takes it, so their transactions
+                // don't collide.
+                let nonce = storage.ethereum_schema().get_next_nonce().await?;
+                (nonce, fees)
+            }
+        };
+
+        let options = Options::with(|options| {
+            options.gas = Some(revert_tx.gas_limit);
+            options.nonce = Some(U256::from(nonce as u64));
+            set_tx_fees(options, gas_price, priority_fee);
+        });
+        let sent = match self
+            .eth_gateway
+            .sign_prepared_tx(revert_tx.data.clone(), options)
+            .await
+        {
+            Ok(signed_tx) => self.eth_gateway.send_raw_tx(signed_tx.raw_tx).await,
+            Err(err) => Err(err),
+        };
+        let tx_hash = match sent {
+            Ok(tx_hash) => tx_hash,
+            Err(err) => {
+                // The new nonce is returned, so `eth_sender` doesn't wait for the nonce nothing
+                // is sent with.
+                if stuck_tx.is_none() && !storage.ethereum_schema().release_nonce(nonce).await? {
+                    vlog::error!(
+                        "Failed to release the nonce {} of the revert transaction",
+                        nonce
+                    );
+                }
+                return Err(BlockRevertError::Contract(err));
+            }
+        };
+
+        storage
+            .chain()
+            .block_schema()
+            .add_revert_tx(nonce, tx_hash, gas_price, priority_fee)
+            .await?;
+        let mut tx_hashes = stuck_tx
+            .map(|stuck_tx| stuck_tx.tx_hashes.clone())
+            .unwrap_or_default();
+        tx_hashes.push(tx_hash);
+        Ok(PendingRevertTx {
+            nonce,
+            tx_hashes,
+            gas_price,
+            priority_fee,
+        })
+    }
+
+    /// Waits for any of the sent revert transactions to be mined, replacing the stuck one
+    /// each `expected_wait_time_block` blocks. Returns the hash of the mined transaction if it
+    /// succeeds.
+    ///
+    /// If it's not mined in time, it's replaced with the empty transaction, so the transactions
+    /// of `eth_sender` don't wait for its nonce once the revert is cancelled.
+    async fn confirm_revert_tx(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        revert_tx: &RevertTx,
+        mut sent_tx: PendingRevertTx,
+    ) -> Result<H256, BlockRevertError> {
+        let start = Instant::now();
+        let mut deadline_block = None;
+        let mut timer = time::interval(CONFIRMATION_POLL_INTERVAL);
+        loop {
+            timer.tick().await;
+
+            match self.load_revert_tx_receipt(&sent_tx).await {
+                Ok(Some(receipt)) if receipt.status == Some(U64::from(1)) => {
+                    return Ok(receipt.transaction_hash);
+                }
+                Ok(Some(receipt)) => {
+                    let tx_hash = receipt.transaction_hash;
+                    let reason = self
+                        .eth_gateway
+                        .failure_reason(tx_hash)
+                        .await
+                        .map_err(BlockRevertError::Contract)?;
+                    return Err(BlockRevertError::Contract(format_err!(
+                        "Transaction {:#x} failed: {:?}",
+                        tx_hash,
+                        reason
+                    )));
+                }
+                Ok(None) => {}
+                Err(err) => vlog::warn!("Failed to check the revert transaction: {}", err),
+            }
+
+            if start.elapsed() > CONFIRMATION_TIMEOUT {
+                if let Err(err) = self.cancel_revert_tx(&sent_tx).await {
+                    vlog::error!(
+                        "Failed to cancel the revert transaction with nonce {}, the following \
+                         transactions wait for it: {}",
+                        sent_tx.nonce,
+                        err
+                    );
+                }
+                return Err(BlockRevertError::Contract(format_err!(
+                    "Transactions {:?} are not mined in time",
+                    sent_tx.tx_hashes
+                )));
+            }
+
+            let current_block = match self.eth_gateway.block_number().await {
+                Ok(block) => block.as_u64(),
+                Err(err) => {
+                    vlog::warn!("Failed to load the current Ethereum block: {}", err);
+                    continue;
+                }
+            };
+            let deadline = *deadline_block.get_or_insert(current_block + self.expected_wait_time);
+            if current_block >= deadline {
+                match self
+                    .send_revert_tx(storage, revert_tx, Some(&sent_tx))
+                    .await
+                {
+                    Ok(replacement) => {
+                        vlog::info!(
+                            "Replaced the stuck revert transaction with {:#x}",
+                            replacement.tx_hashes.last().unwrap()
+                        );
+                        sent_tx = replacement;
+                    }
+                    Err(err) => vlog::warn!("Failed to replace the revert transaction: {}", err),
+                }
+                deadline_block = Some(current_block + self.expected_wait_time);
+            }
+        }
+    }
+
+    /// Returns the receipt of the mined transaction of the sent ones, if any.
+    async fn load_revert_tx_receipt(
+        &self,
+        sent_tx: &PendingRevertTx,
+    ) -> anyhow::Result<Option<TransactionReceipt>> {
+        for &tx_hash in &sent_tx.tx_hashes {
+            if let Some(receipt) = self.eth_gateway.tx_receipt(tx_hash).await? {
+                return Ok(Some(receipt));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replaces the stuck revert transaction with the empty transfer to the operator itself.
+    async fn cancel_revert_tx(&self, stuck_tx: &PendingRevertTx) -> anyhow::Result<()> {
+        let (gas_price, priority_fee) = self
+            .tx_fees
+            .lock()
+            .await
+            .replacement_tx_fees(&self.eth_gateway, stuck_tx.gas_price, stuck_tx.priority_fee)
+            .await?;
+        let options = Options::with(|options| {
+            options.gas = Some(U256::from(CANCEL_TX_GAS));
+            options.nonce = Some(U256::from(stuck_tx.nonce as u64));
+            set_tx_fees(options, gas_price, priority_fee);
+        });
+        let signed_tx = self
+            .eth_gateway
+            .sign_prepared_tx_for_addr(Vec::new(), self.operator_address, options)
+            .await?;
+        let tx_hash = self.eth_gateway.send_raw_tx(signed_tx.raw_tx).await?;
+        vlog::warn!(
+            "Replaced the revert transaction with nonce {} with the empty transaction {:#x}",
+            stuck_tx.nonce,
+            tx_hash
+        );
+        Ok(())
+    }
+}
+
+/// Runs the task reverting the blocks on request.
+/// The task finishes once the block production is halted, so the server must be restarted then.
+#[must_use]
+pub fn run_block_reverter(
+    pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    eth_sender_config: ETHSenderConfig,
+    halt: BlockProductionHalt,
+    committed_state: Option<SharedCommittedState>,
+    mut requests: mpsc::Receiver<BlockRevertRequest>,
+    mut stop_signal_sender: mpsc::Sender<bool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let reverter = BlockReverter {
+            tx_fees: Mutex::new(OperatorTxFees::new(&eth_sender_config, pool.clone()).await),
+            expected_wait_time: eth_sender_config.sender.expected_wait_time_block,
+            operator_address: eth_sender_config.sender.operator_commit_eth_addr,
+            pool,
+            eth_gateway,
+            halt,
+            committed_state,
+        };
+
+        // The revert interrupted by the restart is finished before serving the requests.
+        match reverter.pending_revert().await {
+            Ok(Some(last_correct_block)) => {
+                vlog::warn!(
+                    "Resuming the revert of the blocks after block {}",
+                    last_correct_block
+                );
+                // E.g. the blocks were verified meanwhile, so `eth_sender` is resumed.
+                match reverter.handle_revert(last_correct_block).await {
+                    Err(err) if err.is_rejected() => {
+                        if let Err(err) = reverter.cancel_revert().await {
+                            vlog::error!("Failed to cancel the revert in progress: {}", err);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(None) => {}
+            Err(err) => vlog::error!("Failed to load the revert in progress: {}", err),
+        }
+
+        loop {
+            if reverter.halt.is_halted().await {
+                vlog::warn!(
+                    "Block production is halted, shutting down to restart from the storage"
+                );
+                time::sleep(SHUTDOWN_DELAY).await;
+                stop_signal_sender.send(true).await.unwrap_or_default();
+                break;
+            }
+
+            let request = match requests.next().await {
+                Some(request) => request,
+                // The private API is stopped, which is handled on its own.
+                None => break,
+            };
+            let result = reverter.handle_revert(request.last_correct_block).await;
+            request.response.send(result).unwrap_or_default();
+        }
+        future::pending::<()>().await;
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn block_production_halt() {
+        let halt = BlockProductionHalt::default();
+        let guard = halt
+            .enter()
+            .await
+            .expect("Block production is not halted yet");
+
+        // The halt waits for the unit of work in progress.
+        let mut halt_task = tokio::spawn({
+            let halt = halt.clone();
+            async move { halt.halt().await }
+        });
+        assert!(time::timeout(Duration::from_millis(10), &mut halt_task)
+            .await
+            .is_err());
+
+        drop(guard);
+        halt_task.await.unwrap();
+        assert!(halt.is_halted().await);
+        assert!(halt.enter().await.is_none());
+    }
+}
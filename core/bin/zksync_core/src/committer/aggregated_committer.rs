@@ -197,6 +197,7 @@ fn create_execute_blocks_operation(
 
     Some(BlocksExecuteOperation {
         blocks: blocks_to_execute,
+        complete_withdrawals: false,
     })
 }
 
@@ -8,12 +8,15 @@ use tokio::{task::JoinHandle, time};
 // Workspace uses
 use zksync_config::ChainConfig;
 use zksync_crypto::Fr;
-use zksync_storage::ConnectionPool;
+use zksync_state::shared::{CommittedStateSnapshot, SharedCommittedState};
+use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
     block::{Block, BlockMetadata, ExecutedOperations, IncompleteBlock, PendingBlock},
     AccountUpdates, BlockNumber,
 };
+// Local uses
+use crate::{block_revert::BlockProductionHalt, leader_election::FencingTerm};
 
 mod aggregated_committer;
 
@@ -45,6 +48,8 @@ pub struct BlockFinishRequest {
 pub struct AppliedUpdatesRequest {
     pub account_updates: AccountUpdates,
     pub first_update_order_id: usize,
+    /// Snapshot of the committed state including the updates, published once they are stored.
+    pub committed_state: Option<CommittedStateSnapshot>,
 }
 
 pub struct ExecutedOpsNotify {
@@ -54,48 +59,100 @@ pub struct ExecutedOpsNotify {
 
 const PROOF_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
-async fn handle_new_commit_task(mut rx_for_ops: Receiver<CommitRequest>, pool: ConnectionPool) {
+async fn handle_new_commit_task(
+    mut rx_for_ops: Receiver<CommitRequest>,
+    pool: ConnectionPool,
+    halt: BlockProductionHalt,
+    committed_state: Option<SharedCommittedState>,
+    fencing_term: Option<FencingTerm>,
+) {
+    let fencing_term = fencing_term.as_ref();
     vlog::info!("Run committer");
     let mut token_db_cache = TokenDBCache::new(TOKEN_INVALIDATE_CACHE);
     token_db_cache
         .fill_token_cache(&mut pool.access_storage().await.unwrap())
         .await;
     while let Some(request) = rx_for_ops.next().await {
+        // The blocks produced after the halt are not stored, since they are going to be reverted.
+        let _halt_guard = match halt.enter().await {
+            Some(guard) => guard,
+            None => continue,
+        };
         match request {
-            CommitRequest::SealIncompleteBlock((block_commit_request, applied_updates_req)) => {
+            CommitRequest::SealIncompleteBlock((block_commit_request, mut applied_updates_req)) => {
+                let snapshot = applied_updates_req.committed_state.take();
                 seal_incomplete_block(
                     block_commit_request,
                     applied_updates_req,
                     &pool,
                     &mut token_db_cache,
+                    fencing_term,
                 )
                 .await;
+                publish_committed_state(committed_state.as_ref(), snapshot);
             }
-            CommitRequest::PendingBlock((pending_block, applied_updates_req)) => {
-                save_pending_block(pending_block, applied_updates_req, &pool).await;
+            CommitRequest::PendingBlock((pending_block, mut applied_updates_req)) => {
+                let snapshot = applied_updates_req.committed_state.take();
+                save_pending_block(pending_block, applied_updates_req, &pool, fencing_term).await;
+                publish_committed_state(committed_state.as_ref(), snapshot);
             }
             CommitRequest::FinishBlock(request) => {
-                finish_block(request, &pool).await;
+                finish_block(request, &pool, fencing_term).await;
             }
             CommitRequest::RemoveRevertedBlock(block_number) => {
-                remove_reverted_block(block_number, &pool).await;
+                remove_reverted_block(block_number, &pool, fencing_term).await;
             }
         }
     }
 }
 
-async fn remove_reverted_block(block_number: BlockNumber, pool: &ConnectionPool) {
+/// Publishes the committed state snapshot once the updates it includes are stored,
+/// so the API never observes the state which may be lost on restart.
+fn publish_committed_state(
+    committed_state: Option<&SharedCommittedState>,
+    snapshot: Option<CommittedStateSnapshot>,
+) {
+    if let (Some(committed_state), Some(snapshot)) = (committed_state, snapshot) {
+        committed_state.publish(snapshot);
+    }
+}
+
+/// Checks the leadership term within the transaction storing the results of the committer,
+/// so the instance which is not the leader anymore can't store anything.
+async fn check_fencing_term(
+    fencing_term: Option<&FencingTerm>,
+    transaction: &mut StorageProcessor<'_>,
+) {
+    if let Some(fencing_term) = fencing_term {
+        fencing_term.check(transaction, "committer").await;
+    }
+}
+
+async fn remove_reverted_block(
+    block_number: BlockNumber,
+    pool: &ConnectionPool,
+    fencing_term: Option<&FencingTerm>,
+) {
     let start = Instant::now();
     let mut storage = pool
         .access_storage()
         .await
         .expect("db connection fail for committer");
-    storage
+    let mut transaction = storage
+        .start_transaction()
+        .await
+        .expect("Failed initializing a DB transaction");
+    check_fencing_term(fencing_term, &mut transaction).await;
+    transaction
         .chain()
         .mempool_schema()
         .remove_reverted_block(block_number)
         .await
         .expect("Failed to remove reverted blocks");
+    transaction
+        .commit()
+        .await
+        .expect("Unable to commit DB transaction");
     metrics::histogram!("committer.remove_reverted_block", start.elapsed());
 }
 
@@ -103,6 +160,7 @@ async fn save_pending_block(
     pending_block: PendingBlock,
     applied_updates_request: AppliedUpdatesRequest,
     pool: &ConnectionPool,
+    fencing_term: Option<&FencingTerm>,
 ) {
     let start = Instant::now();
     let mut storage = pool
@@ -114,6 +172,7 @@ async fn save_pending_block(
         .start_transaction()
         .await
         .expect("Failed initializing a DB transaction");
+    check_fencing_term(fencing_term, &mut transaction).await;
 
     let block_number = pending_block.number;
 
@@ -150,6 +209,7 @@ async fn seal_incomplete_block(
     applied_updates_request: AppliedUpdatesRequest,
     pool: &ConnectionPool,
     token_db_cache: &mut TokenDBCache,
+    fencing_term: Option<&FencingTerm>,
 ) {
     let start = Instant::now();
     let BlockCommitRequest {
@@ -166,6 +226,7 @@ async fn seal_incomplete_block(
         .start_transaction()
         .await
         .expect("Failed initializing a DB transaction");
+    check_fencing_term(fencing_term, &mut transaction).await;
 
     // This is needed to keep track of how many priority ops are in each block
     // and trigger grafana alerts if there are suspiciously few
@@ -225,7 +286,11 @@ async fn seal_incomplete_block(
     metrics::histogram!("committer.seal_incomplete_block", start.elapsed());
 }
 
-async fn finish_block(request: BlockFinishRequest, pool: &ConnectionPool) {
+async fn finish_block(
+    request: BlockFinishRequest,
+    pool: &ConnectionPool,
+    fencing_term: Option<&FencingTerm>,
+) {
     let start = Instant::now();
     let BlockFinishRequest {
         block_number,
@@ -241,6 +306,7 @@ async fn finish_block(request: BlockFinishRequest, pool: &ConnectionPool) {
         .start_transaction()
         .await
         .expect("Failed initializing a DB transaction");
+    check_fencing_term(fencing_term, &mut transaction).await;
 
     vlog::info!("finish block #{}", block_number);
 
@@ -281,29 +347,62 @@ async fn finish_block(request: BlockFinishRequest, pool: &ConnectionPool) {
     metrics::histogram!("committer.finish_block", start.elapsed());
 }
 
-async fn poll_for_new_proofs_task(pool: ConnectionPool, config: ChainConfig) {
+async fn poll_for_new_proofs_task(
+    pool: ConnectionPool,
+    config: ChainConfig,
+    halt: BlockProductionHalt,
+    fencing_term: Option<FencingTerm>,
+) {
     let mut timer = time::interval(PROOF_POLL_INTERVAL);
     loop {
         timer.tick().await;
 
+        let _halt_guard = match halt.enter().await {
+            Some(guard) => guard,
+            None => continue,
+        };
+
         let mut storage = pool
             .access_storage()
             .await
             .expect("db connection failed for committer");
-
-        aggregated_committer::create_aggregated_operations_storage(&mut storage, &config)
+        let mut transaction = storage
+            .start_transaction()
             .await
-            .map_err(|e| vlog::error!("Failed to create aggregated operation: {}", e))
-            .unwrap_or_default();
+            .expect("Failed initializing a DB transaction");
+        check_fencing_term(fencing_term.as_ref(), &mut transaction).await;
+
+        let result =
+            aggregated_committer::create_aggregated_operations_storage(&mut transaction, &config)
+                .await;
+        match result {
+            Ok(()) => transaction
+                .commit()
+                .await
+                .expect("Unable to commit DB transaction"),
+            Err(e) => vlog::error!("Failed to create aggregated operation: {}", e),
+        }
     }
 }
 
 #[must_use]
+/// If `committed_state` is provided, the snapshots built by the state keeper
+/// are published to it once the blocks are stored.
+/// If `fencing_term` is provided, it's checked by every transaction of the committer.
 pub fn run_committer(
     rx_for_ops: Receiver<CommitRequest>,
     pool: ConnectionPool,
     config: ChainConfig,
+    halt: BlockProductionHalt,
+    committed_state: Option<SharedCommittedState>,
+    fencing_term: Option<FencingTerm>,
 ) -> JoinHandle<()> {
-    tokio::spawn(handle_new_commit_task(rx_for_ops, pool.clone()));
-    tokio::spawn(poll_for_new_proofs_task(pool, config))
+    tokio::spawn(handle_new_commit_task(
+        rx_for_ops,
+        pool.clone(),
+        halt.clone(),
+        committed_state,
+        fencing_term.clone(),
+    ));
+    tokio::spawn(poll_for_new_proofs_task(pool, config, halt, fencing_term))
 }
@@ -0,0 +1,348 @@
+//! Monitor of the deposit sizes.
+//!
+//! The Ethereum watcher forwards the confirmed priority operations to the monitor, which keeps
+//! the window of the latest deposits of every token and reports the ones that don't fit
+//! the distribution: the whale deposits way above the usual sizes and the floods of the dust
+//! deposits. The distributions are exported as metrics, and the anomalies are reported
+//! both as metrics and as alerts via the configured webhook.
+
+// Built-in uses
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::Instant;
+// External uses
+use futures::{channel::mpsc, StreamExt};
+use num::{BigUint, ToPrimitive};
+use tokio::task::JoinHandle;
+// Workspace uses
+use zksync_config::DepositMonitorConfig;
+use zksync_notifier::Notifier;
+use zksync_types::{PriorityOp, SerialId, TokenId, ZkSyncPriorityOp};
+
+/// Deposit (or a series of deposits) that doesn't fit the distribution of the token deposits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepositAnomaly {
+    Whale {
+        serial_id: SerialId,
+        token: TokenId,
+        amount: BigUint,
+        threshold: BigUint,
+    },
+    DustFlood {
+        token: TokenId,
+        count: usize,
+    },
+}
+
+impl DepositAnomaly {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Whale { .. } => "whale",
+            Self::DustFlood { .. } => "dust_flood",
+        }
+    }
+
+    fn token(&self) -> TokenId {
+        match self {
+            Self::Whale { token, .. } | Self::DustFlood { token, .. } => *token,
+        }
+    }
+}
+
+impl fmt::Display for DepositAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Whale {
+                serial_id,
+                token,
+                amount,
+                threshold,
+            } => write!(
+                f,
+                "Whale deposit of token {}: priority op #{}, amount = {}, threshold = {}",
+                token, serial_id, amount, threshold
+            ),
+            Self::DustFlood { token, count } => write!(
+                f,
+                "Dust deposits flood of token {}: {} deposits within the interval",
+                token, count
+            ),
+        }
+    }
+}
+
+/// Latest deposits of the token.
+#[derive(Debug, Default)]
+struct TokenDeposits {
+    window: VecDeque<BigUint>,
+    /// Times of the dust deposits within the flood detection interval.
+    dust_deposits: VecDeque<Instant>,
+    /// Whether the ongoing flood is already reported.
+    flood_reported: bool,
+}
+
+impl TokenDeposits {
+    /// Returns the median and the 99th percentile of the window.
+    fn quantiles(&self) -> (BigUint, BigUint) {
+        let mut sorted: Vec<_> = self.window.iter().collect();
+        sorted.sort_unstable();
+        let p99_index = (sorted.len() * 99 / 100).min(sorted.len() - 1);
+        (sorted[sorted.len() / 2].clone(), sorted[p99_index].clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct DepositMonitor {
+    config: DepositMonitorConfig,
+    tokens: HashMap<TokenId, TokenDeposits>,
+    /// The watcher may forward the same operations several times.
+    last_serial_id: Option<SerialId>,
+}
+
+impl DepositMonitor {
+    pub fn new(config: DepositMonitorConfig) -> Self {
+        Self {
+            config,
+            tokens: HashMap::new(),
+            last_serial_id: None,
+        }
+    }
+
+    /// Processes the deposits among the priority operations, returns the detected anomalies.
+    pub fn process_ops(&mut self, ops: &[PriorityOp], now: Instant) -> Vec<DepositAnomaly> {
+        let mut ops: Vec<_> = ops
+            .iter()
+            .filter(|op| Some(op.serial_id) > self.last_serial_id)
+            .collect();
+        ops.sort_unstable_by_key(|op| op.serial_id);
+
+        let mut anomalies = Vec::new();
+        for op in ops {
+            self.last_serial_id = Some(op.serial_id);
+            if let ZkSyncPriorityOp::Deposit(deposit) = &op.data {
+                anomalies.extend(self.process_deposit(
+                    op.serial_id,
+                    deposit.token,
+                    &deposit.amount,
+                    now,
+                ));
+            }
+        }
+        anomalies
+    }
+
+    fn process_deposit(
+        &mut self,
+        serial_id: SerialId,
+        token: TokenId,
+        amount: &BigUint,
+        now: Instant,
+    ) -> Vec<DepositAnomaly> {
+        let config = &self.config;
+        let deposits = self.tokens.entry(token).or_default();
+        let token_label = token.to_string();
+        metrics::histogram!(
+            "deposit_monitor.amount",
+            amount.to_f64().unwrap_or(f64::MAX),
+            "token" => token_label.clone()
+        );
+
+        let mut anomalies = Vec::new();
+        if deposits.window.len() >= config.min_samples.max(1) {
+            let (median, p99) = deposits.quantiles();
+            metrics::gauge!(
+                "deposit_monitor.median",
+                median.to_f64().unwrap_or(f64::MAX),
+                "token" => token_label.clone()
+            );
+            metrics::gauge!(
+                "deposit_monitor.p99",
+                p99.to_f64().unwrap_or(f64::MAX),
+                "token" => token_label
+            );
+
+            let whale_threshold = p99 * config.whale_multiplier;
+            if *amount > whale_threshold {
+                anomalies.push(DepositAnomaly::Whale {
+                    serial_id,
+                    token,
+                    amount: amount.clone(),
+                    threshold: whale_threshold,
+                });
+            }
+
+            let flood_interval = config.dust_flood_interval();
+            while let Some(time) = deposits.dust_deposits.front() {
+                if now.saturating_duration_since(*time) > flood_interval {
+                    deposits.dust_deposits.pop_front();
+                } else {
+                    break;
+                }
+            }
+            if *amount < median / config.dust_divisor.max(1) {
+                deposits.dust_deposits.push_back(now);
+            }
+            if deposits.dust_deposits.len() < config.dust_flood_count {
+                deposits.flood_reported = false;
+            } else if !deposits.flood_reported {
+                deposits.flood_reported = true;
+                anomalies.push(DepositAnomaly::DustFlood {
+                    token,
+                    count: deposits.dust_deposits.len(),
+                });
+            }
+        }
+
+        deposits.window.push_back(amount.clone());
+        while deposits.window.len() > config.window_size.max(1) {
+            deposits.window.pop_front();
+        }
+
+        for anomaly in &anomalies {
+            metrics::increment_counter!(
+                "deposit_monitor.anomaly",
+                "kind" => anomaly.kind(),
+                "token" => anomaly.token().to_string()
+            );
+        }
+        anomalies
+    }
+}
+
+/// Runs the monitor of the deposits forwarded by the Ethereum watcher.
+#[must_use]
+pub fn run_deposit_monitor(
+    config: DepositMonitorConfig,
+    mut priority_ops: mpsc::Receiver<Vec<PriorityOp>>,
+) -> JoinHandle<()> {
+    let webhook_url = reqwest::Url::parse(&config.webhook_url).ok();
+    let notifier = webhook_url.map(Notifier::with_mattermost);
+    let mut monitor = DepositMonitor::new(config);
+
+    tokio::spawn(async move {
+        while let Some(ops) = priority_ops.next().await {
+            for anomaly in monitor.process_ops(&ops, Instant::now()) {
+                vlog::warn!("Deposit anomaly: {}", anomaly);
+                if let Some(notifier) = &notifier {
+                    if let Err(err) = notifier
+                        .send_deposit_anomaly_notify(&anomaly.to_string())
+                        .await
+                    {
+                        vlog::warn!("Can't send the deposit anomaly alert: {}", err);
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use zksync_types::Deposit;
+
+    use super::*;
+
+    fn config() -> DepositMonitorConfig {
+        DepositMonitorConfig {
+            enabled: true,
+            window_size: 100,
+            min_samples: 10,
+            whale_multiplier: 10,
+            dust_divisor: 100,
+            dust_flood_count: 3,
+            dust_flood_interval: 60,
+            webhook_url: String::new(),
+        }
+    }
+
+    fn deposit(serial_id: SerialId, token: u32, amount: u64) -> PriorityOp {
+        PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: TokenId(token),
+                amount: amount.into(),
+                to: Default::default(),
+            }),
+            deadline_block: 0,
+            eth_hash: Default::default(),
+            eth_block: 0,
+            eth_block_index: None,
+        }
+    }
+
+    fn usual_deposits(from_serial_id: SerialId) -> Vec<PriorityOp> {
+        (0..10)
+            .map(|i| deposit(from_serial_id + i, 0, 1000 + i * 10))
+            .collect()
+    }
+
+    #[test]
+    fn whale_deposits() {
+        let mut monitor = DepositMonitor::new(config());
+        let now = Instant::now();
+
+        // The anomalies are not detected until there are enough samples.
+        let mut ops = usual_deposits(0);
+        ops.insert(5, deposit(100, 0, 1_000_000));
+        assert!(monitor.process_ops(&ops[..6], now).is_empty());
+
+        let mut monitor = DepositMonitor::new(config());
+        assert!(monitor.process_ops(&usual_deposits(0), now).is_empty());
+        let anomalies = monitor.process_ops(
+            &[
+                deposit(10, 0, 10_000),
+                deposit(11, 0, 1_000_000),
+                // Other tokens have their own distributions.
+                deposit(12, 1, 1_000_000),
+            ],
+            now,
+        );
+        assert_eq!(
+            anomalies,
+            vec![DepositAnomaly::Whale {
+                serial_id: 11,
+                token: TokenId(0),
+                amount: 1_000_000u64.into(),
+                threshold: 100_000u64.into(),
+            }]
+        );
+
+        // Already processed operations are skipped.
+        assert!(monitor
+            .process_ops(&[deposit(11, 0, 1_000_000)], now)
+            .is_empty());
+    }
+
+    #[test]
+    fn dust_flood() {
+        let mut monitor = DepositMonitor::new(config());
+        let now = Instant::now();
+        assert!(monitor.process_ops(&usual_deposits(0), now).is_empty());
+
+        let dust = |serial_id| deposit(serial_id, 0, 1);
+        assert!(monitor.process_ops(&[dust(10), dust(11)], now).is_empty());
+        assert_eq!(
+            monitor.process_ops(&[dust(12), dust(13)], now),
+            vec![DepositAnomaly::DustFlood {
+                token: TokenId(0),
+                count: 3,
+            }]
+        );
+        // The ongoing flood is reported once.
+        assert!(monitor.process_ops(&[dust(14)], now).is_empty());
+
+        // The new flood is reported after the previous one is over.
+        let later = now + Duration::from_secs(120);
+        assert!(monitor.process_ops(&[dust(15), dust(16)], later).is_empty());
+        assert_eq!(
+            monitor.process_ops(&[dust(17)], later),
+            vec![DepositAnomaly::DustFlood {
+                token: TokenId(0),
+                count: 3,
+            }]
+        );
+    }
+}
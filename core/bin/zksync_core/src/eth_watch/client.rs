@@ -6,14 +6,14 @@ use std::fmt::Debug;
 use web3::{
     contract::Options,
     transports::http,
-    types::{BlockNumber, FilterBuilder, Log},
+    types::{BlockId, BlockNumber, Filter, FilterBuilder, Log},
     Web3,
 };
 
 use zksync_contracts::{governance_contract, zksync_contract};
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_types::{
-    Address, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent, H160, U256,
+    Address, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent, H160, H256, U256,
 };
 
 struct ContractTopics {
@@ -41,6 +41,27 @@ impl ContractTopics {
     }
 }
 
+/// Filter of the contract logs processed by the Ethereum watcher.
+pub(super) fn contract_logs_filter(
+    zksync_contract_addr: H160,
+    governance_contract_addr: H160,
+) -> Filter {
+    let topics = ContractTopics::new(&zksync_contract(), &governance_contract());
+    FilterBuilder::default()
+        .address(vec![zksync_contract_addr, governance_contract_addr])
+        .topics(
+            Some(vec![
+                topics.new_priority_request,
+                topics.new_token,
+                topics.factory_registered,
+            ]),
+            None,
+            None,
+            None,
+        )
+        .build()
+}
+
 #[async_trait::async_trait]
 pub trait EthClient {
     async fn get_priority_op_events(
@@ -59,11 +80,27 @@ pub trait EthClient {
         to: BlockNumber,
     ) -> anyhow::Result<Vec<NewTokenEvent>>;
     async fn block_number(&self) -> anyhow::Result<u64>;
+    /// Hash of the block, `None` if the block is not known to the node.
+    async fn block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>>;
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>>;
     async fn get_auth_fact_reset_time(&self, address: Address, nonce: Nonce)
         -> anyhow::Result<u64>;
 }
 
+/// Checks whether the node refused to return the logs because of their amount or
+/// the size of the block range. The messages differ between the providers.
+fn is_too_many_logs_error(error: &anyhow::Error) -> bool {
+    const MESSAGES: &[&str] = &[
+        "query returned more than",
+        "response size exceeded",
+        "block range is too wide",
+        "exceed maximum block range",
+        "query timeout exceeded",
+    ];
+    let error = error.to_string();
+    MESSAGES.iter().any(|message| error.contains(message))
+}
+
 pub struct EthHttpClient {
     client: EthereumGateway,
     topics: ContractTopics,
@@ -86,6 +123,67 @@ impl EthHttpClient {
         }
     }
 
+    /// Loads the logs of the block range. If the node refuses to return that many logs at once,
+    /// the range is split in halves until the parts are small enough.
+    async fn get_logs(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        topics: Vec<Hash>,
+    ) -> anyhow::Result<Vec<Log>> {
+        let mut ranges = vec![(from, to)];
+        let mut logs = Vec::new();
+
+        while let Some((from, to)) = ranges.pop() {
+            let filter = FilterBuilder::default()
+                .address(vec![
+                    self.zksync_contract_addr,
+                    self.governance_contract_addr,
+                ])
+                .from_block(from)
+                .to_block(to)
+                .topics(Some(topics.clone()), None, None, None)
+                .build();
+
+            let error = match self.client.logs(filter).await {
+                Ok(range_logs) => {
+                    logs.extend(range_logs);
+                    continue;
+                }
+                Err(error) if is_too_many_logs_error(&error) => error,
+                Err(error) => return Err(error),
+            };
+
+            let from_number = match from {
+                BlockNumber::Number(number) => number.as_u64(),
+                _ => 0,
+            };
+            let to_number = match to {
+                BlockNumber::Number(number) => number.as_u64(),
+                _ => self.block_number().await?,
+            };
+            if from_number >= to_number {
+                // A single block can't be split any further.
+                return Err(error);
+            }
+
+            let middle = from_number + (to_number - from_number) / 2;
+            vlog::debug!(
+                "Too many logs in the blocks [{},{}], splitting the range",
+                from_number,
+                to_number
+            );
+            metrics::increment_counter!("eth_watcher.logs_range_split");
+            // The lower half is loaded first to keep the logs in the order of the blocks.
+            ranges.push((BlockNumber::Number((middle + 1).into()), to));
+            ranges.push((
+                BlockNumber::Number(from_number.into()),
+                BlockNumber::Number(middle.into()),
+            ));
+        }
+        Ok(logs)
+    }
+
     async fn get_events<T>(
         &self,
         from: BlockNumber,
@@ -96,17 +194,7 @@ impl EthHttpClient {
         T: TryFrom<Log>,
         T::Error: Debug,
     {
-        let filter = FilterBuilder::default()
-            .address(vec![
-                self.zksync_contract_addr,
-                self.governance_contract_addr,
-            ])
-            .from_block(from)
-            .to_block(to)
-            .topics(Some(topics), None, None, None)
-            .build();
-
-        let mut logs = self.client.logs(filter).await?;
+        let mut logs = self.get_logs(from, to, topics).await?;
         let is_possible_to_sort_logs = logs.iter().all(|log| log.log_index.is_some());
         if is_possible_to_sort_logs {
             logs.sort_by_key(|log| {
@@ -224,6 +312,12 @@ impl EthClient for EthHttpClient {
         Ok(self.client.block_number().await?.as_u64())
     }
 
+    async fn block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>> {
+        let block_id = BlockId::Number(BlockNumber::Number(block_number.into()));
+        let block = self.client.block(block_id).await?;
+        Ok(block.and_then(|block| block.hash))
+    }
+
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>> {
         self.client
             .call_main_contract_function(
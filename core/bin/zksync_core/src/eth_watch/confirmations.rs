@@ -0,0 +1,109 @@
+// Built-in deps
+use std::collections::HashMap;
+// External uses
+use num::BigUint;
+// Workspace deps
+use zksync_config::ETHWatchConfig;
+use zksync_types::{TokenId, ZkSyncPriorityOp};
+
+/// Amounts of the confirmations required for the priority operations to be processed,
+/// depending on the operation type and the deposited amount.
+#[derive(Debug, Clone)]
+pub struct ConfirmationsPolicy {
+    deposit: u64,
+    full_exit: u64,
+    large_deposit: u64,
+    large_deposit_thresholds: HashMap<TokenId, BigUint>,
+}
+
+impl ConfirmationsPolicy {
+    /// Policy requiring the same amount of confirmations for all the operations.
+    pub fn uniform(confirmations: u64) -> Self {
+        Self {
+            deposit: confirmations,
+            full_exit: confirmations,
+            large_deposit: confirmations,
+            large_deposit_thresholds: HashMap::new(),
+        }
+    }
+
+    pub fn from_config(config: &ETHWatchConfig) -> Self {
+        Self {
+            deposit: config.confirmations_for_eth_event,
+            full_exit: config.confirmations_for_full_exit,
+            large_deposit: config.confirmations_for_large_deposit,
+            large_deposit_thresholds: config.large_deposit_thresholds(),
+        }
+    }
+
+    pub fn confirmations(&self, op: &ZkSyncPriorityOp) -> u64 {
+        match op {
+            ZkSyncPriorityOp::Deposit(deposit) => {
+                let is_large = self
+                    .large_deposit_thresholds
+                    .get(&deposit.token)
+                    .map_or(false, |threshold| deposit.amount >= *threshold);
+                if is_large {
+                    // The large deposits never require less confirmations than the regular ones.
+                    self.large_deposit.max(self.deposit)
+                } else {
+                    self.deposit
+                }
+            }
+            ZkSyncPriorityOp::FullExit(_) => self.full_exit,
+        }
+    }
+
+    /// The greatest amount of confirmations, the operations of the older blocks
+    /// are confirmed regardless of their type.
+    pub fn max_confirmations(&self) -> u64 {
+        let large_deposit = if self.large_deposit_thresholds.is_empty() {
+            0
+        } else {
+            self.large_deposit
+        };
+        self.deposit.max(self.full_exit).max(large_deposit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{AccountId, Address, Deposit, FullExit};
+
+    fn deposit(token: TokenId, amount: u32) -> ZkSyncPriorityOp {
+        ZkSyncPriorityOp::Deposit(Deposit {
+            from: Address::zero(),
+            token,
+            amount: amount.into(),
+            to: Address::zero(),
+        })
+    }
+
+    #[test]
+    fn confirmations_by_op_type() {
+        let policy = ConfirmationsPolicy {
+            deposit: 5,
+            full_exit: 2,
+            large_deposit: 20,
+            large_deposit_thresholds: vec![(TokenId(0), BigUint::from(1000u32))]
+                .into_iter()
+                .collect(),
+        };
+        let full_exit = ZkSyncPriorityOp::FullExit(FullExit {
+            account_id: AccountId(1),
+            eth_address: Address::zero(),
+            token: TokenId(0),
+            is_legacy: false,
+        });
+
+        assert_eq!(policy.confirmations(&full_exit), 2);
+        assert_eq!(policy.confirmations(&deposit(TokenId(0), 999)), 5);
+        assert_eq!(policy.confirmations(&deposit(TokenId(0), 1000)), 20);
+        // There is no threshold for the token.
+        assert_eq!(policy.confirmations(&deposit(TokenId(1), 1000)), 5);
+        assert_eq!(policy.max_confirmations(), 20);
+
+        assert_eq!(ConfirmationsPolicy::uniform(3).max_confirmations(), 3);
+    }
+}
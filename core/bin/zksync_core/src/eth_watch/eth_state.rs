@@ -98,6 +98,24 @@ impl ETHState {
         self.last_ethereum_block = self.last_ethereum_block_backup;
     }
 
+    /// Makes sure the next expected priority operation is not earlier than the given one,
+    /// e.g. when the operations preceding it are outdated and not kept in the queue.
+    pub fn bump_next_priority_op_id(&mut self, serial_id: SerialId) {
+        self.next_priority_op_id = self.next_priority_op_id.max(serial_id);
+    }
+
+    /// Reverts the state to the last Ethereum block remaining in the chain after a reorg,
+    /// so the following blocks are polled again.
+    /// Only the unconfirmed operations are dropped, the confirmed ones are considered final.
+    pub fn rollback(&mut self, last_valid_block: u64) {
+        self.last_ethereum_block = self.last_ethereum_block.min(last_valid_block);
+        self.last_ethereum_block_backup = self
+            .last_ethereum_block_backup
+            .min(self.last_ethereum_block);
+        self.unconfirmed_queue
+            .retain(|op| op.eth_block <= last_valid_block);
+    }
+
     #[cfg(test)]
     pub(crate) fn last_ethereum_block_backup(&self) -> u64 {
         self.last_ethereum_block_backup
@@ -1,12 +1,20 @@
 //! Ethereum watcher polls the Ethereum node for new events
 //! such as PriorityQueue events or NewToken events.
 //! New events are accepted to the zkSync network once they have the sufficient amount of confirmations.
+//! If the blocks with the unconfirmed events are reorged, these events are removed from the mempool
+//! and the blocks are polled again.
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
+//! Optionally, the watcher subscribes to the contract logs and the new blocks via WebSocket and polls
+//! the node as soon as a new block is mined, see the `subscription` module.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
 
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 use std::time::{Duration, Instant};
 
 // External uses
@@ -22,17 +30,29 @@ use tokio::{task::JoinHandle, time};
 use web3::types::BlockNumber;
 
 use zksync_config::{ContractsConfig, ETHWatchConfig};
-use zksync_crypto::params::PRIORITY_EXPIRATION;
+use zksync_crypto::{
+    params::PRIORITY_EXPIRATION,
+    rand::{thread_rng, Rng},
+};
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_mempool::MempoolTransactionRequest;
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_storage::ConnectionPool;
+use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, H256};
 
 // Local deps
-use self::{client::EthClient, eth_state::ETHState, received_ops::sift_outdated_ops};
+use self::{
+    client::{contract_logs_filter, EthClient},
+    confirmations::ConfirmationsPolicy,
+    eth_state::ETHState,
+    received_ops::sift_outdated_ops,
+    subscription::LogSubscription,
+};
 
 mod client;
+mod confirmations;
 mod eth_state;
 mod received_ops;
+mod subscription;
 
 #[cfg(test)]
 mod tests;
@@ -40,6 +60,19 @@ mod tests;
 /// As `infura` may limit the requests, upon error we need to wait for a while
 /// before repeating the request.
 const RATE_LIMIT_DELAY: Duration = Duration::from_secs(30);
+/// The backoff delay doubles while the rate limit keeps being reached, up to this value.
+const MAX_RATE_LIMIT_DELAY: Duration = Duration::from_secs(300);
+/// Amount of the retries of the failed poll before giving up until the next poll.
+const POLL_RETRIES: u32 = 3;
+/// Delay before the first retry of the failed poll, doubled with every retry.
+const POLL_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Random delay between the half and the whole of the given one,
+/// so the retries of the different servers don't happen in lockstep.
+fn with_jitter(delay: Duration) -> Duration {
+    let max_millis = delay.as_millis() as u64;
+    Duration::from_millis(thread_rng().gen_range(max_millis / 2, max_millis + 1))
+}
 
 /// Ethereum Watcher operating mode.
 ///
@@ -80,10 +113,22 @@ fn is_missing_priority_op_error(error: &anyhow::Error) -> bool {
 pub struct EthWatch<W: EthClient> {
     client: W,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    /// Receiver of the confirmed priority operations, e.g. the deposit monitor.
+    priority_ops_sender: Option<mpsc::Sender<Vec<PriorityOp>>>,
     eth_state: ETHState,
     /// All ethereum events are accepted after sufficient confirmations to eliminate risk of block reorg.
+    /// This is the greatest amount of confirmations required by `confirmations`.
     number_of_confirmations_for_event: u64,
+    /// Confirmations required for the priority operations depending on their type.
+    confirmations: ConfirmationsPolicy,
+    /// Hashes of the processed chain heads within the confirmation window,
+    /// used to detect the reorgs of the blocks with the unconfirmed events.
+    block_hashes: BTreeMap<u64, H256>,
     mode: WatcherMode,
+    /// Amount of the backoffs in a row, the backoff delay grows with it.
+    consecutive_backoffs: u32,
+    /// Storage of the watcher progress, the progress is not persisted if it's not set.
+    db_pool: Option<ConnectionPool>,
 }
 
 impl<W: EthClient> EthWatch<W> {
@@ -95,12 +140,57 @@ impl<W: EthClient> EthWatch<W> {
         Self {
             client,
             mempool_tx_sender,
+            priority_ops_sender: None,
             eth_state: ETHState::default(),
             mode: WatcherMode::Working,
+            consecutive_backoffs: 0,
             number_of_confirmations_for_event,
+            confirmations: ConfirmationsPolicy::uniform(number_of_confirmations_for_event),
+            block_hashes: BTreeMap::new(),
+            db_pool: None,
         }
     }
 
+    /// Forwards the confirmed priority operations to the given channel as well.
+    pub fn with_priority_ops_sender(mut self, sender: mpsc::Sender<Vec<PriorityOp>>) -> Self {
+        self.priority_ops_sender = Some(sender);
+        self
+    }
+
+    /// Persists the progress of the watcher, so after the restart it continues
+    /// from the last processed block.
+    pub fn with_storage(mut self, db_pool: ConnectionPool) -> Self {
+        self.db_pool = Some(db_pool);
+        self
+    }
+
+    /// Sets the amounts of confirmations depending on the priority operation type.
+    pub fn with_confirmations_policy(mut self, confirmations: ConfirmationsPolicy) -> Self {
+        self.number_of_confirmations_for_event = confirmations.max_confirmations();
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Splits off the operations that already have enough confirmations for their type
+    /// from the operations of the blocks within the confirmation window.
+    /// The operations are accepted in the order of their serial ids, so the operation waiting
+    /// for more confirmations holds back the following ones.
+    fn split_confirmed_ops(
+        &self,
+        mut ops: Vec<PriorityOp>,
+        current_ethereum_block: u64,
+    ) -> (Vec<PriorityOp>, Vec<PriorityOp>) {
+        ops.sort_by_key(|op| op.serial_id);
+        let confirmed_count = ops
+            .iter()
+            .take_while(|op| {
+                op.eth_block + self.confirmations.confirmations(&op.data) <= current_ethereum_block
+            })
+            .count();
+        let unconfirmed_ops = ops.split_off(confirmed_count);
+        (ops, unconfirmed_ops)
+    }
+
     /// Atomically replaces the stored Ethereum state.
     fn set_new_state(&mut self, new_state: ETHState) {
         self.eth_state = new_state;
@@ -139,7 +229,7 @@ impl<W: EthClient> EthWatch<W> {
         let block_difference = last_ethereum_block.saturating_sub(previous_ethereum_block);
 
         let updated_state = self
-            .update_eth_state(last_ethereum_block, block_difference)
+            .update_eth_state(last_ethereum_block, block_difference, block_difference)
             .await?;
 
         // It's assumed that for the current state all priority operations have consecutive ids,
@@ -191,7 +281,7 @@ impl<W: EthClient> EthWatch<W> {
         register_nft_factory_events.sort_by_key(|factory_event| factory_event.creator_address);
         register_nft_factory_events.dedup_by_key(|factory_event| factory_event.creator_address);
 
-        let new_state = ETHState::new(
+        let mut new_state = ETHState::new(
             last_ethereum_block,
             previous_ethereum_block,
             updated_state.unconfirmed_queue().to_vec(),
@@ -199,16 +289,36 @@ impl<W: EthClient> EthWatch<W> {
             new_tokens,
             register_nft_factory_events,
         );
+        new_state.bump_next_priority_op_id(next_priority_op_id);
         self.set_new_state(new_state);
         Ok(())
     }
 
     async fn restore_state_from_eth(&mut self, last_ethereum_block: u64) -> anyhow::Result<()> {
-        let new_state = self
-            .update_eth_state(last_ethereum_block, PRIORITY_EXPIRATION)
+        // If the state saved by the previous run is loaded, only the blocks processed
+        // since then are rescanned.
+        let saved_ethereum_block = self.eth_state.last_ethereum_block();
+        let unprocessed_blocks = if saved_ethereum_block > 0 {
+            last_ethereum_block
+                .saturating_sub(saved_ethereum_block)
+                .min(PRIORITY_EXPIRATION)
+        } else {
+            PRIORITY_EXPIRATION
+        };
+        let next_priority_op_id = self.eth_state.next_priority_op_id();
+
+        let block_hash = self.client.block_hash(last_ethereum_block).await?;
+        // The token handlers may have not stored the token events seen by the previous run,
+        // so these events are always reloaded for the whole priority expiration period.
+        let mut new_state = self
+            .update_eth_state(last_ethereum_block, unprocessed_blocks, PRIORITY_EXPIRATION)
             .await?;
+        new_state.bump_next_priority_op_id(next_priority_op_id);
 
         self.set_new_state(new_state);
+        self.block_hashes.clear();
+        self.remember_block_hash(last_ethereum_block, block_hash);
+        self.save_state().await;
 
         vlog::debug!("ETH state: {:#?}", self.eth_state);
         Ok(())
@@ -218,20 +328,26 @@ impl<W: EthClient> EthWatch<W> {
         &mut self,
         current_ethereum_block: u64,
         unprocessed_blocks_amount: u64,
+        unprocessed_governance_blocks_amount: u64,
     ) -> anyhow::Result<ETHState> {
         let new_block_with_accepted_events =
             current_ethereum_block.saturating_sub(self.number_of_confirmations_for_event);
         let previous_block_with_accepted_events =
             new_block_with_accepted_events.saturating_sub(unprocessed_blocks_amount);
+        let previous_block_with_governance_events =
+            new_block_with_accepted_events.saturating_sub(unprocessed_governance_blocks_amount);
 
         let unconfirmed_queue = self.get_unconfirmed_ops(current_ethereum_block).await?;
-        let priority_queue = self
+        let mut priority_queue = self
             .client
             .get_priority_op_events(
                 BlockNumber::Number(previous_block_with_accepted_events.into()),
                 BlockNumber::Number(new_block_with_accepted_events.into()),
             )
             .await?;
+        let (confirmed_ops, unconfirmed_queue) =
+            self.split_confirmed_ops(unconfirmed_queue, current_ethereum_block);
+        priority_queue.extend(confirmed_ops);
         let priority_queue_map: HashMap<u64, _> = priority_queue
             .iter()
             .cloned()
@@ -241,7 +357,7 @@ impl<W: EthClient> EthWatch<W> {
         let new_tokens = self
             .client
             .get_new_tokens_events(
-                BlockNumber::Number(previous_block_with_accepted_events.into()),
+                BlockNumber::Number(previous_block_with_governance_events.into()),
                 BlockNumber::Number(new_block_with_accepted_events.into()),
             )
             .await?;
@@ -249,7 +365,7 @@ impl<W: EthClient> EthWatch<W> {
         let new_register_nft_factory_events = self
             .client
             .get_new_register_nft_factory_events(
-                BlockNumber::Number(previous_block_with_accepted_events.into()),
+                BlockNumber::Number(previous_block_with_governance_events.into()),
                 BlockNumber::Number(new_block_with_accepted_events.into()),
             )
             .await?;
@@ -279,7 +395,7 @@ impl<W: EthClient> EthWatch<W> {
         let (sender, receiver) = oneshot::channel();
         self.mempool_tx_sender
             .send(MempoolTransactionRequest::NewPriorityOps(
-                priority_queue,
+                priority_queue.clone(),
                 true,
                 sender,
             ))
@@ -287,6 +403,12 @@ impl<W: EthClient> EthWatch<W> {
 
         // TODO maybe retry? It can be the only problem is database
         receiver.await.expect("Mempool actor was dropped")?;
+        if let Some(priority_ops_sender) = &mut self.priority_ops_sender {
+            // The receivers are not critical, so the watcher doesn't wait for them.
+            if priority_ops_sender.try_send(priority_queue).is_err() {
+                metrics::increment_counter!("eth_watcher.priority_ops_dropped");
+            }
+        }
         // The backup block number is not used.
         let state = ETHState::new(
             current_ethereum_block,
@@ -329,13 +451,162 @@ impl<W: EthClient> EthWatch<W> {
         new_tokens
     }
 
+    /// Processes the blocks up to the new chain head and remembers the hash of the head.
+    async fn process_new_head(&mut self, last_ethereum_block: u64) -> anyhow::Result<()> {
+        // The hash is loaded before the events, so if the head is reorged in between,
+        // the reorg is detected on the next poll.
+        let block_hash = self.client.block_hash(last_ethereum_block).await?;
+        self.process_new_blocks(last_ethereum_block).await?;
+        self.remember_block_hash(last_ethereum_block, block_hash);
+        self.save_state().await;
+        Ok(())
+    }
+
+    /// Loads the state saved by the previous run along with the unconfirmed priority operations,
+    /// so the state is available before the Ethereum node is reached.
+    async fn load_saved_state(&mut self) -> anyhow::Result<()> {
+        let db_pool = match &self.db_pool {
+            Some(db_pool) => db_pool,
+            None => return Ok(()),
+        };
+        let mut storage = db_pool.access_storage().await?;
+        let saved_state = match storage.eth_watch_schema().load_state().await? {
+            Some(saved_state) => saved_state,
+            None => return Ok(()),
+        };
+        let unconfirmed_queue = storage
+            .chain()
+            .mempool_schema()
+            .get_unconfirmed_priority_ops()
+            .await?;
+
+        let mut state = ETHState::new(
+            saved_state.last_ethereum_block(),
+            saved_state.last_ethereum_block(),
+            unconfirmed_queue,
+            HashMap::new(),
+            Vec::new(),
+            Vec::new(),
+        );
+        state.bump_next_priority_op_id(saved_state.next_priority_op_id());
+        vlog::info!(
+            "Loaded the saved Ethereum watcher state: last block {}, next priority op {}",
+            state.last_ethereum_block(),
+            state.next_priority_op_id()
+        );
+        self.set_new_state(state);
+        Ok(())
+    }
+
+    /// Saves the progress of the watcher. The failure is not critical,
+    /// more blocks are rescanned after the restart in this case.
+    async fn save_state(&self) {
+        if let Err(error) = self.try_save_state().await {
+            vlog::warn!("Failed to save the Ethereum watcher state: {}", error);
+        }
+    }
+
+    async fn try_save_state(&self) -> anyhow::Result<()> {
+        let db_pool = match &self.db_pool {
+            Some(db_pool) => db_pool,
+            None => return Ok(()),
+        };
+        db_pool
+            .access_storage()
+            .await?
+            .eth_watch_schema()
+            .save_state(
+                self.eth_state.last_ethereum_block(),
+                self.eth_state.next_priority_op_id(),
+            )
+            .await
+    }
+
+    fn remember_block_hash(&mut self, block_number: u64, block_hash: Option<H256>) {
+        if let Some(block_hash) = block_hash {
+            self.block_hashes.insert(block_number, block_hash);
+        }
+        // The events of the older blocks are confirmed, their reorgs are not expected.
+        let oldest_block = block_number.saturating_sub(self.number_of_confirmations_for_event);
+        self.block_hashes
+            .retain(|&block_number, _| block_number >= oldest_block);
+    }
+
+    /// Compares the hashes of the processed chain heads with the ones known to the node.
+    /// Returns the first block removed from the chain by a reorg, if any.
+    async fn find_reorged_block(&self) -> anyhow::Result<Option<u64>> {
+        let (&last_block, &last_hash) = match self.block_hashes.iter().next_back() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        // The block may be unknown to the node lagging behind the previously used one,
+        // the reorg is detected once the node reaches it.
+        match self.client.block_hash(last_block).await? {
+            Some(block_hash) if block_hash != last_hash => {}
+            _ => return Ok(None),
+        }
+
+        // Find the latest processed head that is still in the chain,
+        // every block after it is considered reorged.
+        let mut first_reorged_block = last_block;
+        for (&block_number, &block_hash) in self.block_hashes.iter().rev().skip(1) {
+            if self.client.block_hash(block_number).await? == Some(block_hash) {
+                return Ok(Some(first_reorged_block));
+            }
+            first_reorged_block = block_number;
+        }
+        vlog::error!(
+            "Reorg of the Ethereum blocks starting from {} or earlier is deeper than \
+            the confirmation window, the confirmed priority operations may be affected",
+            first_reorged_block
+        );
+        Ok(Some(first_reorged_block))
+    }
+
+    /// Drops the unconfirmed priority operations of the reorged blocks from the state and
+    /// from the mempool, so the blocks are polled again from the fork point.
+    /// The state keeper is not affected, as it only receives the confirmed operations.
+    async fn rollback_reorged_blocks(&mut self, first_reorged_block: u64) -> anyhow::Result<()> {
+        vlog::warn!(
+            "Ethereum blocks starting from {} are reorged, rolling back the unconfirmed \
+            priority operations",
+            first_reorged_block
+        );
+        metrics::increment_counter!("eth_watcher.reorg_detected");
+
+        let (sender, receiver) = oneshot::channel();
+        self.mempool_tx_sender
+            .send(MempoolTransactionRequest::RevertUnconfirmedPriorityOps(
+                first_reorged_block,
+                sender,
+            ))
+            .await?;
+        receiver.await.expect("Mempool actor was dropped")?;
+
+        self.eth_state
+            .rollback(first_reorged_block.saturating_sub(1));
+        self.block_hashes
+            .retain(|&block_number, _| block_number < first_reorged_block);
+        Ok(())
+    }
+
     async fn poll_eth_node(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
+        if let Some(first_reorged_block) = self.find_reorged_block().await? {
+            self.rollback_reorged_blocks(first_reorged_block).await?;
+        }
         let last_block_number = self.client.block_number().await?;
 
-        if last_block_number > self.eth_state.last_ethereum_block() {
-            self.process_new_blocks(last_block_number).await?;
-        }
+        let result = if last_block_number > self.eth_state.last_ethereum_block() {
+            self.process_new_head(last_block_number).await
+        } else {
+            Ok(())
+        };
+        // Amount of the Ethereum blocks which are not processed yet, including the ones
+        // waiting for the confirmations.
+        let lag = last_block_number.saturating_sub(self.eth_state.last_ethereum_block());
+        metrics::gauge!("eth_watcher.lag_blocks", lag as f64);
+        result?;
 
         metrics::histogram!("eth_watcher.poll_eth_node", start.elapsed());
         Ok(())
@@ -347,11 +618,45 @@ impl<W: EthClient> EthWatch<W> {
     }
 
     fn enter_backoff_mode(&mut self) {
-        let backoff_until = Instant::now() + RATE_LIMIT_DELAY;
+        let delay = RATE_LIMIT_DELAY
+            .saturating_mul(1 << self.consecutive_backoffs.min(4))
+            .min(MAX_RATE_LIMIT_DELAY);
+        let delay = with_jitter(delay);
+        self.consecutive_backoffs += 1;
+
+        let backoff_until = Instant::now() + delay;
         self.mode = WatcherMode::Backoff(backoff_until);
         // This is needed to track how much time is spent in backoff mode
         // and trigger grafana alerts
-        metrics::histogram!("eth_watcher.enter_backoff_mode", RATE_LIMIT_DELAY);
+        metrics::histogram!("eth_watcher.enter_backoff_mode", delay);
+    }
+
+    /// Polls the Ethereum node, retrying the failures with the exponential backoff,
+    /// so a single failed request doesn't delay the new events until the next poll.
+    /// The rate limit and the missing priority operations are not retried,
+    /// these are handled by the backoff mode.
+    async fn poll_eth_node_with_retries(&mut self) -> anyhow::Result<()> {
+        let mut attempt = 0;
+        loop {
+            match self.poll_eth_node().await {
+                Err(error)
+                    if attempt < POLL_RETRIES
+                        && !self.is_backoff_requested(&error)
+                        && !is_missing_priority_op_error(&error) =>
+                {
+                    let delay = with_jitter(POLL_RETRY_DELAY * 2u32.pow(attempt));
+                    vlog::debug!("Retrying the poll in {:?}: {}", delay, error);
+                    metrics::increment_counter!("eth_watcher.poll_retries");
+                    time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Ok(()) => {
+                    self.consecutive_backoffs = 0;
+                    return Ok(());
+                }
+                Err(error) => return Err(error),
+            }
+        }
     }
 
     fn polling_allowed(&mut self) -> bool {
@@ -371,6 +676,10 @@ impl<W: EthClient> EthWatch<W> {
     }
 
     pub async fn restore_from_eth_using_latest_block_number(&mut self) {
+        if let Err(error) = self.load_saved_state().await {
+            vlog::warn!("Failed to load the saved Ethereum watcher state: {}", error);
+        }
+
         // As infura may be not responsive, we want to retry the query until we've actually got the
         // block number.
         // Normally, however, this loop is not expected to last more than one iteration.
@@ -411,7 +720,7 @@ impl<W: EthClient> EthWatch<W> {
                         continue;
                     }
 
-                    let poll_result = self.poll_eth_node().await;
+                    let poll_result = self.poll_eth_node_with_retries().await;
 
                     if let Err(error) = poll_result {
                         if self.is_backoff_requested(&error) {
@@ -456,6 +765,8 @@ pub async fn start_eth_watch(
     contract_config: &ContractsConfig,
     eth_watcher_config: &ETHWatchConfig,
     mempool_req_sender: mpsc::Sender<MempoolTransactionRequest>,
+    priority_ops_sender: Option<mpsc::Sender<Vec<PriorityOp>>>,
+    db_pool: ConnectionPool,
 ) -> JoinHandle<()> {
     let eth_client = EthHttpClient::new(
         eth_gateway,
@@ -467,18 +778,44 @@ pub async fn start_eth_watch(
         eth_client,
         mempool_req_sender,
         eth_watcher_config.confirmations_for_eth_event,
-    );
+    )
+    .with_confirmations_policy(ConfirmationsPolicy::from_config(eth_watcher_config))
+    .with_storage(db_pool);
+    if let Some(sender) = priority_ops_sender {
+        eth_watch = eth_watch.with_priority_ops_sender(sender);
+    }
 
     eth_watch.restore_from_eth_using_latest_block_number().await;
 
     tokio::spawn(eth_watch.run(eth_req_receiver));
 
+    let subscription_active = if eth_watcher_config.log_subscription_enabled {
+        let subscription = LogSubscription::new(
+            eth_watcher_config.web3_ws_url.clone(),
+            contract_logs_filter(
+                contract_config.contract_addr,
+                contract_config.governance_addr,
+            ),
+            eth_req_sender.clone(),
+        );
+        let active = subscription.active_flag();
+        tokio::spawn(subscription.run());
+        active
+    } else {
+        Arc::new(AtomicBool::new(false))
+    };
+
     let poll_interval = eth_watcher_config.poll_interval();
+    let subscribed_poll_interval = eth_watcher_config.subscribed_poll_interval();
     tokio::spawn(async move {
-        let mut timer = time::interval(poll_interval);
-
         loop {
-            timer.tick().await;
+            // While the subscription is active, the node is polled on every new block, the timer
+            // only covers the missed notifications.
+            if subscription_active.load(Ordering::SeqCst) {
+                time::sleep(subscribed_poll_interval).await;
+            } else {
+                time::sleep(poll_interval).await;
+            }
             eth_req_sender
                 .clone()
                 .send(EthWatchRequest::PollETHNode)
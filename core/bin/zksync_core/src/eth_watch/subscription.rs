@@ -0,0 +1,104 @@
+//! Subscription to the contract logs and the new blocks via WebSocket.
+//!
+//! Every new block or log received makes the Ethereum watcher poll the node right away, once
+//! per block, so the new events surface and get their confirmations as soon as the blocks are
+//! mined instead of waiting for the next poll. The notifications themselves are not processed
+//! here: the polling still takes care of the confirmations and reorgs. Once the subscription
+//! drops, the watcher falls back to the regular poll interval until the subscription is restored.
+
+// Built-in deps
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
+// External uses
+use futures::{channel::mpsc, stream, SinkExt, StreamExt};
+use tokio::time;
+use web3::{transports::WebSocket, types::Filter, Web3};
+// Local deps
+use super::EthWatchRequest;
+
+/// Delay before the dropped subscription is restored.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+pub struct LogSubscription {
+    ws_url: String,
+    filter: Filter,
+    eth_req_sender: mpsc::Sender<EthWatchRequest>,
+    /// Whether the subscription is currently active.
+    active: Arc<AtomicBool>,
+    /// Block of the last notification, the node is polled once per block.
+    last_block: Option<u64>,
+}
+
+impl LogSubscription {
+    pub fn new(
+        ws_url: String,
+        filter: Filter,
+        eth_req_sender: mpsc::Sender<EthWatchRequest>,
+    ) -> Self {
+        Self {
+            ws_url,
+            filter,
+            eth_req_sender,
+            active: Arc::new(AtomicBool::new(false)),
+            last_block: None,
+        }
+    }
+
+    /// Flag telling whether the subscription is currently active.
+    pub fn active_flag(&self) -> Arc<AtomicBool> {
+        self.active.clone()
+    }
+
+    async fn subscribe(&mut self) -> anyhow::Result<()> {
+        let transport = WebSocket::new(&self.ws_url).await?;
+        let web3 = Web3::new(transport);
+        let logs = web3
+            .eth_subscribe()
+            .subscribe_logs(self.filter.clone())
+            .await?
+            .map(|log| log.map(|log| log.block_number.map(|number| number.as_u64())));
+        // The confirmations of the received events grow with every block, so the new blocks
+        // have to be polled as well.
+        let heads = web3
+            .eth_subscribe()
+            .subscribe_new_heads()
+            .await?
+            .map(|head| head.map(|head| head.number.map(|number| number.as_u64())));
+        let mut blocks = stream::select(logs, heads);
+
+        vlog::info!("Subscribed to the contract logs and the new blocks");
+        self.active.store(true, Ordering::SeqCst);
+        metrics::gauge!("eth_watcher.log_subscription_active", 1.0);
+
+        while let Some(block_number) = blocks.next().await {
+            let block_number = block_number?;
+            if block_number.is_some() && block_number == self.last_block {
+                continue;
+            }
+            self.last_block = block_number;
+            self.eth_req_sender
+                .send(EthWatchRequest::PollETHNode)
+                .await?;
+        }
+        anyhow::bail!("Log subscription stream has ended")
+    }
+
+    pub async fn run(mut self) {
+        loop {
+            if let Err(error) = self.subscribe().await {
+                vlog::warn!(
+                    "Log subscription failed: {}. Falling back to polling the Ethereum node",
+                    error
+                );
+            }
+            self.active.store(false, Ordering::SeqCst);
+            metrics::gauge!("eth_watcher.log_subscription_active", 0.0);
+            metrics::increment_counter!("eth_watcher.log_subscription_dropped");
+
+            time::sleep(RESUBSCRIBE_DELAY).await;
+        }
+    }
+}
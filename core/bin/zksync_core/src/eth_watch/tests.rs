@@ -12,14 +12,17 @@ use zksync_types::{
 use futures::channel::mpsc;
 use futures::StreamExt;
 use tokio::sync::RwLock;
+use zksync_config::ETHWatchConfig;
 use zksync_mempool::MempoolTransactionRequest;
 
 use super::is_missing_priority_op_error;
-use crate::eth_watch::{client::EthClient, EthWatch};
+use crate::eth_watch::{client::EthClient, confirmations::ConfirmationsPolicy, EthWatch};
 
 struct FakeEthClientData {
     priority_ops: HashMap<u64, Vec<PriorityOp>>,
     last_block_number: u64,
+    /// Hashes of the blocks replaced by the reorgs.
+    block_hashes: HashMap<u64, H256>,
 }
 
 impl FakeEthClientData {
@@ -27,6 +30,28 @@ impl FakeEthClientData {
         Self {
             priority_ops: Default::default(),
             last_block_number: 0,
+            block_hashes: Default::default(),
+        }
+    }
+
+    fn block_hash(&self, block_number: u64) -> Option<H256> {
+        if block_number > self.last_block_number {
+            return None;
+        }
+        let block_hash = self
+            .block_hashes
+            .get(&block_number)
+            .copied()
+            .unwrap_or_else(|| H256::from_low_u64_be(block_number));
+        Some(block_hash)
+    }
+
+    /// Replaces the blocks starting from the given one, dropping their operations.
+    fn reorg(&mut self, from_block: u64) {
+        self.priority_ops
+            .retain(|&block_number, _| block_number < from_block);
+        for block_number in from_block..=self.last_block_number {
+            self.block_hashes.insert(block_number, H256::random());
         }
     }
 
@@ -70,6 +95,10 @@ impl FakeEthClient {
         let mut inner = self.inner.write().await;
         inner.last_block_number = block_number;
     }
+
+    async fn reorg(&mut self, from_block: u64) {
+        self.inner.write().await.reorg(from_block);
+    }
 }
 
 #[async_trait::async_trait]
@@ -111,6 +140,10 @@ impl EthClient for FakeEthClient {
         Ok(self.inner.read().await.last_block_number)
     }
 
+    async fn block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>> {
+        Ok(self.inner.read().await.block_hash(block_number))
+    }
+
     async fn get_auth_fact(
         &self,
         _address: Address,
@@ -141,7 +174,7 @@ async fn fake_mempool(
 ) {
     while let Some(a) = receiver.next().await {
         match a {
-            MempoolTransactionRequest::NewTx(_, _) => {
+            MempoolTransactionRequest::NewTx(_, _, _) => {
                 unreachable!()
             }
             MempoolTransactionRequest::NewPriorityOps(ops, conf, channel) => {
@@ -151,7 +184,20 @@ async fn fake_mempool(
                 }
                 channel.send(Ok(())).unwrap_or_default()
             }
+            MempoolTransactionRequest::RevertUnconfirmedPriorityOps(from_eth_block, channel) => {
+                let mut lock = data.write().await;
+                let serial_ids: Vec<_> = lock
+                    .iter()
+                    .filter(|(_, (op, conf))| !conf && op.eth_block >= from_eth_block)
+                    .map(|(serial_id, _)| *serial_id)
+                    .collect();
+                for serial_id in &serial_ids {
+                    lock.remove(serial_id);
+                }
+                channel.send(Ok(serial_ids)).unwrap_or_default()
+            }
             MempoolTransactionRequest::NewTxsBatch(_, _, _) => unreachable!(),
+            MempoolTransactionRequest::Ping(resp) => resp.send(()).unwrap_or_default(),
         }
     }
 }
@@ -546,3 +592,153 @@ async fn test_serial_id_gaps() {
     assert_eq!(watcher.eth_state.last_ethereum_block_backup(), 0);
     assert_eq!(watcher.eth_state.last_ethereum_block(), 3);
 }
+
+/// Checks that the unconfirmed operations of the reorged blocks are dropped
+/// and the blocks are polled again.
+#[tokio::test]
+async fn test_reorg_rollback() {
+    let (sender, receiver) = mpsc::channel(10);
+    let deposit = ZkSyncPriorityOp::Deposit(Deposit {
+        from: Default::default(),
+        token: TokenId(0),
+        amount: Default::default(),
+        to: [2u8; 20].into(),
+    });
+
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    let mut client = FakeEthClient::new();
+    client
+        .add_operations(&[PriorityOp {
+            serial_id: 0,
+            data: deposit.clone(),
+            deadline_block: 0,
+            eth_hash: [2; 32].into(),
+            eth_block: 1,
+            eth_block_index: Some(1),
+        }])
+        .await;
+    client.set_last_block_number(2).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.poll_eth_node().await.unwrap();
+    client
+        .add_operations(&[PriorityOp {
+            serial_id: 1,
+            data: deposit.clone(),
+            deadline_block: 0,
+            eth_hash: [3; 32].into(),
+            eth_block: 3,
+            eth_block_index: Some(1),
+        }])
+        .await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.unconfirmed_queue().len(), 1);
+    assert!(data.read().await.contains_key(&1));
+
+    // The block with the unconfirmed operation is replaced.
+    client.reorg(3).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 3);
+    assert!(watcher.eth_state.unconfirmed_queue().is_empty());
+    assert_eq!(watcher.eth_state.priority_queue().len(), 1);
+    assert!(!data.read().await.contains_key(&1));
+
+    // The operation is included into the new chain.
+    client
+        .add_operations(&[PriorityOp {
+            serial_id: 1,
+            data: deposit,
+            deadline_block: 0,
+            eth_hash: [4; 32].into(),
+            eth_block: 4,
+            eth_block_index: Some(1),
+        }])
+        .await;
+    watcher.poll_eth_node().await.unwrap();
+    let unconfirmed_queue = watcher.eth_state.unconfirmed_queue();
+    assert_eq!(unconfirmed_queue.len(), 1);
+    assert_eq!(unconfirmed_queue[0].eth_block, 4);
+    let reader = data.read().await;
+    let (op, confirmed) = reader.get(&1).unwrap();
+    assert_eq!(op.eth_hash, H256::from_slice(&[4u8; 32]));
+    assert!(!confirmed);
+}
+
+/// Checks that the operations requiring fewer confirmations are accepted earlier,
+/// but never ahead of the preceding operations.
+#[tokio::test]
+async fn test_confirmations_by_op_type() {
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    let full_exit = ZkSyncPriorityOp::FullExit(FullExit {
+        account_id: AccountId(0),
+        eth_address: Default::default(),
+        token: TokenId(0),
+        is_legacy: false,
+    });
+    let mut client = FakeEthClient::new();
+    client
+        .add_operations(&[
+            PriorityOp {
+                serial_id: 0,
+                data: full_exit.clone(),
+                deadline_block: 0,
+                eth_hash: [2; 32].into(),
+                eth_block: 3,
+                eth_block_index: Some(1),
+            },
+            PriorityOp {
+                serial_id: 1,
+                data: ZkSyncPriorityOp::Deposit(Deposit {
+                    from: Default::default(),
+                    token: TokenId(0),
+                    amount: Default::default(),
+                    to: [2u8; 20].into(),
+                }),
+                deadline_block: 0,
+                eth_hash: [3; 32].into(),
+                eth_block: 3,
+                eth_block_index: Some(2),
+            },
+            PriorityOp {
+                serial_id: 2,
+                data: full_exit,
+                deadline_block: 0,
+                eth_hash: [4; 32].into(),
+                eth_block: 4,
+                eth_block_index: Some(1),
+            },
+        ])
+        .await;
+
+    let config = ETHWatchConfig {
+        confirmations_for_eth_event: 2,
+        confirmations_for_full_exit: 0,
+        confirmations_for_large_deposit: 2,
+        large_deposit_thresholds: Vec::new(),
+        eth_node_poll_interval: 300,
+        log_subscription_enabled: false,
+        web3_ws_url: String::new(),
+        subscribed_poll_interval: 300,
+    };
+    let mut watcher = create_watcher(client.clone(), sender)
+        .with_confirmations_policy(ConfirmationsPolicy::from_config(&config));
+    watcher.poll_eth_node().await.unwrap();
+    // The second full exit waits for the deposit.
+    let priority_queue = watcher.eth_state.priority_queue();
+    assert_eq!(priority_queue.len(), 1);
+    priority_queue.get(&0).unwrap();
+    let unconfirmed_queue = watcher.eth_state.unconfirmed_queue();
+    assert_eq!(unconfirmed_queue.len(), 2);
+    assert_eq!(unconfirmed_queue[0].serial_id, 1);
+    assert!(data.read().await.get(&0).unwrap().1);
+    assert!(!data.read().await.get(&2).unwrap().1);
+
+    client.set_last_block_number(5).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.priority_queue().len(), 3);
+    assert!(watcher.eth_state.unconfirmed_queue().is_empty());
+    assert!(data.read().await.get(&2).unwrap().1);
+}
@@ -0,0 +1,338 @@
+//! Fixtures for the tests of the mempool and the state keeper.
+//!
+//! `FixtureAccount` owns both zkSync and Ethereum private keys, so the transactions created
+//! by it pass all the signature checks, and `TxBuilder` controls their nonces and fees.
+//! The module is available in the crate tests and, with the `testkit` feature enabled,
+//! in the integration tests of other crates.
+
+// External uses
+use chrono::Utc;
+use num::BigUint;
+// Workspace uses
+use zksync_crypto::{
+    priv_key_from_fs,
+    rand::{Rng, SeedableRng, XorShiftRng},
+    PrivateKey,
+};
+use zksync_types::{
+    mempool::SignedTxsBatch,
+    tx::{
+        ChangePubKey, ChangePubKeyECDSAData, ChangePubKeyEthAuthData, EthBatchSignData,
+        EthSignData, PackedEthSignature, TimeRange, TxEthSignature,
+    },
+    Account, AccountId, Address, Nonce, PubKeyHash, SignedZkSyncTx, Token, TokenId, TokenKind,
+    Transfer, Withdraw, ZkSyncTx, H256,
+};
+
+/// Symbol of the tokens in the Ethereum messages, the messages are not checked against
+/// the real token list in the tests.
+const FIXTURE_TOKEN_SYMBOL: &str = "ETH";
+const FIXTURE_TOKEN_DECIMALS: u8 = 18;
+
+fn fixture_token(token_id: TokenId) -> Token {
+    Token::new(
+        token_id,
+        Address::zero(),
+        FIXTURE_TOKEN_SYMBOL,
+        FIXTURE_TOKEN_DECIMALS,
+        TokenKind::ERC20,
+    )
+}
+
+/// Test account that signs transactions and keeps track of its nonce.
+pub struct FixtureAccount {
+    pub id: AccountId,
+    pub address: Address,
+    pub nonce: Nonce,
+    private_key: PrivateKey,
+    eth_private_key: H256,
+}
+
+impl FixtureAccount {
+    /// Creates the account with the keys generated from the seed, so the same seed
+    /// always gives the same account.
+    pub fn new(id: AccountId, seed: u32) -> Self {
+        let mut rng = XorShiftRng::from_seed([seed, 1, 2, 3]);
+        let private_key = priv_key_from_fs(rng.gen());
+        let (eth_private_key, address) = loop {
+            let eth_private_key = H256::from(rng.gen::<[u8; 32]>());
+            if let Ok(address) = PackedEthSignature::address_from_private_key(&eth_private_key) {
+                break (eth_private_key, address);
+            }
+        };
+
+        Self {
+            id,
+            address,
+            nonce: Nonce(0),
+            private_key,
+            eth_private_key,
+        }
+    }
+
+    pub fn pub_key_hash(&self) -> PubKeyHash {
+        PubKeyHash::from_privkey(&self.private_key)
+    }
+
+    /// State of the account with the signing key already set,
+    /// to be inserted into the state of the test.
+    pub fn account(&self) -> Account {
+        let mut account = Account::default_with_address(&self.address);
+        account.pub_key_hash = self.pub_key_hash();
+        account.nonce = self.nonce;
+        account
+    }
+
+    /// Starts building the transaction. By default the transaction pays zero fee
+    /// in the token with id 0 and uses the current nonce of the account.
+    pub fn tx(&mut self) -> TxBuilder<'_> {
+        TxBuilder {
+            account: self,
+            token: TokenId(0),
+            fee: BigUint::from(0u32),
+            nonce: None,
+            time_range: TimeRange::default(),
+        }
+    }
+
+    fn eth_sign(&self, message: &[u8]) -> PackedEthSignature {
+        PackedEthSignature::sign(&self.eth_private_key, message)
+            .expect("Failed to sign the Ethereum message")
+    }
+
+    /// Returns the nonce for the next transaction and moves the nonce of the account past it.
+    fn use_nonce(&mut self, nonce: Option<Nonce>) -> Nonce {
+        let nonce = nonce.unwrap_or(self.nonce);
+        self.nonce = Nonce(*nonce + 1);
+        nonce
+    }
+
+    /// Wraps the transaction, adding the Ethereum signature if it's required.
+    fn signed_tx(&self, tx: ZkSyncTx, token: TokenId) -> SignedZkSyncTx {
+        let eth_sign_data = tx
+            .get_ethereum_sign_message(fixture_token(token))
+            .map(|message| EthSignData {
+                signature: TxEthSignature::EthereumSignature(self.eth_sign(message.as_bytes())),
+                message: message.into_bytes(),
+            });
+        SignedZkSyncTx {
+            tx,
+            eth_sign_data,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Builder of the signed transaction of the `FixtureAccount`.
+pub struct TxBuilder<'a> {
+    account: &'a mut FixtureAccount,
+    token: TokenId,
+    fee: BigUint,
+    nonce: Option<Nonce>,
+    time_range: TimeRange,
+}
+
+impl<'a> TxBuilder<'a> {
+    /// Token of both the amount and the fee.
+    pub fn token(mut self, token: TokenId) -> Self {
+        self.token = token;
+        self
+    }
+
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        self.fee = fee.into();
+        self
+    }
+
+    /// Overrides the nonce of the account, e.g. to create the transaction with an incorrect nonce.
+    /// The next transactions will use the nonces following the provided one.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn time_range(mut self, time_range: TimeRange) -> Self {
+        self.time_range = time_range;
+        self
+    }
+
+    pub fn transfer(self, to: Address, amount: impl Into<BigUint>) -> SignedZkSyncTx {
+        let nonce = self.account.use_nonce(self.nonce);
+        let transfer = Transfer::new_signed(
+            self.account.id,
+            self.account.address,
+            to,
+            self.token,
+            amount.into(),
+            self.fee,
+            nonce,
+            self.time_range,
+            &self.account.private_key,
+        )
+        .expect("Failed to sign transfer");
+        self.account
+            .signed_tx(ZkSyncTx::Transfer(Box::new(transfer)), self.token)
+    }
+
+    pub fn withdraw(self, to: Address, amount: impl Into<BigUint>) -> SignedZkSyncTx {
+        self.withdraw_impl(to, amount.into(), false)
+    }
+
+    pub fn fast_withdraw(self, to: Address, amount: impl Into<BigUint>) -> SignedZkSyncTx {
+        self.withdraw_impl(to, amount.into(), true)
+    }
+
+    /// Sets the signing key of the account with the ECDSA authorization.
+    pub fn change_pubkey(self) -> SignedZkSyncTx {
+        let nonce = self.account.use_nonce(self.nonce);
+        let mut change_pubkey = ChangePubKey::new_signed(
+            self.account.id,
+            self.account.address,
+            self.account.pub_key_hash(),
+            self.token,
+            self.fee,
+            nonce,
+            self.time_range,
+            None,
+            &self.account.private_key,
+        )
+        .expect("Failed to sign ChangePubKey");
+        let eth_signed_data = change_pubkey
+            .get_eth_signed_data()
+            .expect("Failed to construct ChangePubKey signed message");
+        change_pubkey.eth_auth_data = Some(ChangePubKeyEthAuthData::ECDSA(ChangePubKeyECDSAData {
+            eth_signature: self.account.eth_sign(&eth_signed_data),
+            batch_hash: H256::zero(),
+        }));
+
+        SignedZkSyncTx {
+            tx: ZkSyncTx::ChangePubKey(Box::new(change_pubkey)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn withdraw_impl(self, to: Address, amount: BigUint, fast: bool) -> SignedZkSyncTx {
+        let nonce = self.account.use_nonce(self.nonce);
+        let mut withdraw = Withdraw::new_signed(
+            self.account.id,
+            self.account.address,
+            to,
+            self.token,
+            amount,
+            self.fee,
+            nonce,
+            self.time_range,
+            &self.account.private_key,
+        )
+        .expect("Failed to sign withdraw");
+        withdraw.fast = fast;
+        self.account
+            .signed_tx(ZkSyncTx::Withdraw(Box::new(withdraw)), self.token)
+    }
+}
+
+/// Creates the batch of transactions signed by all the `signers` that sent any of them.
+pub fn signed_batch(
+    batch_id: i64,
+    txs: Vec<SignedZkSyncTx>,
+    signers: &[&FixtureAccount],
+) -> SignedTxsBatch {
+    let message = EthBatchSignData::get_batch_sign_message(
+        txs.iter()
+            .map(|tx| {
+                (
+                    tx.tx.clone(),
+                    fixture_token(tx.tx.token_id()),
+                    tx.tx.account(),
+                )
+            })
+            .collect(),
+    );
+    let eth_signatures = signers
+        .iter()
+        .filter(|signer| txs.iter().any(|tx| tx.tx.account() == signer.address))
+        .map(|signer| TxEthSignature::EthereumSignature(signer.eth_sign(&message)))
+        .collect();
+
+    SignedTxsBatch {
+        txs,
+        batch_id,
+        eth_signatures,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth_signer(signature: &TxEthSignature, message: &[u8]) -> Address {
+        match signature {
+            TxEthSignature::EthereumSignature(signature) => {
+                signature.signature_recover_signer(message).unwrap()
+            }
+            TxEthSignature::EIP1271Signature(_) => panic!("Unexpected EIP1271 signature"),
+        }
+    }
+
+    #[test]
+    fn fixture_txs_are_signed() {
+        let mut account = FixtureAccount::new(AccountId(1), 1);
+        let mut other_account = FixtureAccount::new(AccountId(2), 2);
+        assert_ne!(account.address, other_account.address);
+
+        let mut change_pubkey = account.tx().change_pubkey();
+        let transfer = account
+            .tx()
+            .fee(10u32)
+            .transfer(other_account.address, 100u32);
+        let withdraw = account
+            .tx()
+            .token(TokenId(1))
+            .nonce(Nonce(5))
+            .withdraw(account.address, 100u32);
+        let mut other_transfer = other_account.tx().transfer(account.address, 1u32);
+
+        assert!(change_pubkey.tx.check_correctness().is_ok());
+        for mut tx in vec![transfer.clone(), withdraw.clone()] {
+            assert!(tx.tx.check_correctness().is_ok());
+            let eth_sign_data = tx.eth_sign_data.unwrap();
+            assert_eq!(
+                eth_signer(&eth_sign_data.signature, &eth_sign_data.message),
+                account.address
+            );
+        }
+        assert!(other_transfer.tx.check_correctness().is_ok());
+
+        assert_eq!(change_pubkey.tx.nonce(), Nonce(0));
+        assert_eq!(transfer.tx.nonce(), Nonce(1));
+        assert_eq!(withdraw.tx.nonce(), Nonce(5));
+        assert_eq!(account.nonce, Nonce(6));
+        assert_eq!(other_transfer.tx.nonce(), Nonce(0));
+
+        let batch = signed_batch(
+            1,
+            vec![transfer, other_transfer],
+            &[&account, &other_account],
+        );
+        let message = EthBatchSignData::get_batch_sign_message(
+            batch
+                .txs
+                .iter()
+                .map(|tx| {
+                    (
+                        tx.tx.clone(),
+                        fixture_token(tx.tx.token_id()),
+                        tx.tx.account(),
+                    )
+                })
+                .collect(),
+        );
+        let signers: Vec<_> = batch
+            .eth_signatures
+            .iter()
+            .map(|signature| eth_signer(signature, &message))
+            .collect();
+        assert_eq!(signers, vec![account.address, other_account.address]);
+    }
+}
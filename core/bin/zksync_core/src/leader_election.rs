@@ -0,0 +1,155 @@
+//! Leader election between the hot-standby core servers.
+//!
+//! Several instances of the server may run in different regions against the same database,
+//! but only one of them is allowed to produce blocks and send them to L1. The leader is the
+//! instance holding the Postgres advisory lock on its dedicated connection, so once the leader
+//! is stopped or loses the connection, the lock is released and one of the standby instances
+//! takes over within the poll interval.
+//!
+//! Every elected leader starts a new term, which serves as the fencing token: `eth_sender` checks
+//! the term before sending each transaction to L1, and the committer checks it in every
+//! transaction storing the blocks, so the instance which has been replaced by the next leader
+//! can neither store nor send anything. The state keeper stops producing the blocks once
+//! the term is known to be over.
+
+// Built-in uses
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::LeaderElectionConfig;
+use zksync_storage::{leader_election::lock::LeaderLock, ConnectionPool, StorageProcessor};
+
+/// Leadership of this instance, kept as long as the lock connection is alive.
+pub struct Leadership {
+    /// The term this instance was elected in.
+    pub term: i64,
+    lock: LeaderLock,
+    lost: Arc<AtomicBool>,
+}
+
+/// Fencing token of the leadership term, shared by the actors of the leader.
+#[derive(Debug, Clone)]
+pub struct FencingTerm {
+    term: i64,
+    lost: Arc<AtomicBool>,
+}
+
+impl FencingTerm {
+    pub fn term(&self) -> i64 {
+        self.term
+    }
+
+    /// Whether the term is known to be over, doesn't access the database.
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::SeqCst)
+    }
+
+    /// Checks that the term is still the current one and locks it until the end of the transaction,
+    /// so the next leader can't be elected until it's committed. Panics otherwise, since the next
+    /// leader is already producing the blocks and this instance must not interfere.
+    pub async fn check(&self, transaction: &mut StorageProcessor<'_>, actor: &str) {
+        let current_term = transaction
+            .leader_election_schema()
+            .lock_current_term()
+            .await
+            .expect("Unable to load the leadership term");
+        if current_term != self.term {
+            self.lost.store(true, Ordering::SeqCst);
+            panic!(
+                "Leadership term {} is over, the current term is {}. Stopping the {}",
+                self.term, current_term, actor
+            );
+        }
+    }
+}
+
+impl Leadership {
+    /// Waits until this instance becomes the leader and starts the next term.
+    pub async fn acquire(pool: &ConnectionPool, config: &LeaderElectionConfig) -> Self {
+        let mut timer = time::interval(config.poll_interval());
+        let mut lock = None;
+        vlog::info!(
+            "Instance '{}' is waiting for the leadership",
+            config.instance_name
+        );
+
+        loop {
+            timer.tick().await;
+
+            if lock.is_none() {
+                match LeaderLock::connect().await {
+                    Ok(new_lock) => lock = Some(new_lock),
+                    Err(err) => {
+                        vlog::warn!("Unable to connect to the database: {}", err);
+                        continue;
+                    }
+                }
+            }
+            match lock.as_mut().unwrap().try_acquire().await {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(err) => {
+                    vlog::warn!("Unable to acquire the leader lock: {}", err);
+                    // The connection is probably broken, the new one will be established.
+                    lock = None;
+                }
+            }
+        }
+
+        let term = pool
+            .access_storage()
+            .await
+            .expect("Unable to connect to DB")
+            .leader_election_schema()
+            .start_term(&config.instance_name)
+            .await
+            .expect("Unable to start the leadership term");
+        vlog::info!(
+            "Instance '{}' is elected as the leader, term {}",
+            config.instance_name,
+            term
+        );
+        metrics::gauge!("leader_election.term", term as f64);
+
+        Self {
+            term,
+            lock: lock.unwrap(),
+            lost: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns the fencing token of the term, it's marked as lost once the leader lock is lost.
+    pub fn fencing_term(&self) -> FencingTerm {
+        FencingTerm {
+            term: self.term,
+            lost: self.lost.clone(),
+        }
+    }
+
+    /// Runs the task checking that the leader lock is still held.
+    /// The task finishes once the lock connection is lost, and since the lock could
+    /// be acquired by another instance at that moment, the server must be stopped.
+    pub fn watch(mut self, config: &LeaderElectionConfig) -> JoinHandle<()> {
+        let poll_interval = config.poll_interval();
+        tokio::spawn(async move {
+            let mut timer = time::interval(poll_interval);
+            loop {
+                timer.tick().await;
+
+                if let Err(err) = self.lock.check().await {
+                    self.lost.store(true, Ordering::SeqCst);
+                    vlog::error!(
+                        "Leader lock of the term {} is lost: {}. Shutting down",
+                        self.term,
+                        err
+                    );
+                    return;
+                }
+            }
+        })
+    }
+}
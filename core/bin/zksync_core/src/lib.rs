@@ -1,28 +1,46 @@
 use crate::register_factory_handler::run_register_factory_handler;
 use crate::state_keeper::ZkSyncStateInitParams;
 use crate::{
+    balance_auditor::run_balance_auditor,
+    block_revert::{run_block_reverter, BlockProductionHalt},
     committer::run_committer,
+    deposit_monitor::run_deposit_monitor,
     eth_watch::start_eth_watch,
-    state_keeper::{start_root_hash_calculator, start_state_keeper, ZkSyncStateKeeper},
+    leader_election::FencingTerm,
+    state_keeper::{
+        start_root_hash_calculator, start_state_keeper, SealingCriteria, TreeSnapshotStore,
+        ZkSyncStateKeeper,
+    },
     token_handler::run_token_handler,
+    witness_backlog::{run_witness_backlog_monitor, WitnessBacklog},
 };
 use futures::{channel::mpsc, future};
 use tokio::task::JoinHandle;
-use zksync_config::{ChainConfig, ZkSyncConfig};
+use zksync_config::{BalanceAuditorConfig, ChainConfig, DepositMonitorConfig, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
-use zksync_mempool::{run_mempool_block_handler, run_mempool_tx_handler};
+use zksync_mempool::{run_mempool_block_handler, run_mempool_revalidator, run_mempool_tx_handler};
+use zksync_state::shared::SharedCommittedState;
 use zksync_storage::ConnectionPool;
-use zksync_types::{tokens::get_genesis_token_list, Token, TokenId, TokenKind};
+use zksync_types::{
+    protocol::ProtocolParameters, tokens::get_genesis_token_list, Token, TokenId, TokenKind,
+};
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
+pub mod balance_auditor;
+pub mod block_revert;
 pub mod committer;
+pub mod deposit_monitor;
 pub mod eth_watch;
+#[cfg(any(test, feature = "testkit"))]
+pub mod fixtures;
+pub mod leader_election;
 pub mod register_factory_handler;
 pub mod rejected_tx_cleaner;
 pub mod state_keeper;
 pub mod token_handler;
 pub mod tx_event_emitter;
+pub mod witness_backlog;
 
 mod genesis;
 mod private_api;
@@ -86,12 +104,25 @@ pub async fn genesis_init(config: &ChainConfig) {
 /// - mempool, module to organize incoming transactions.
 /// - block proposer, module to create block proposals for state keeper.
 /// - committer, module to store pending and completed blocks into the database.
+/// - block reverter, module to revert the blocks on the admin request.
 /// - private Core API server.
+///
+/// If `committed_state` is provided, the updated accounts are published to it once they are
+/// stored, so the API running in the same process doesn't have to load them from the database.
+///
+/// If `fencing_term` is provided, the state keeper and the committer stop once the leadership
+/// term of the instance is over.
+///
+/// Once the block production is halted to revert the blocks, the stop signal is sent
+/// to the server, so it's restarted from the reverted storage.
 pub async fn run_core(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     eth_gateway: EthereumGateway,
+    committed_state: Option<SharedCommittedState>,
+    fencing_term: Option<FencingTerm>,
+    stop_signal_sender: mpsc::Sender<bool>,
 ) -> anyhow::Result<Vec<JoinHandle<()>>> {
     let (proposed_blocks_sender, proposed_blocks_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
@@ -103,11 +134,13 @@ pub async fn run_core(
 
     let (processed_tx_events_sender, processed_tx_events_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+    let (block_revert_sender, block_revert_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
 
     let mempool_tx_handler_task = run_mempool_tx_handler(
         connection_pool.clone(),
         mempool_tx_request_receiver,
         config.chain.state_keeper.block_chunk_sizes.clone(),
+        &config.chain.mempool,
     );
 
     // Run health check api for core
@@ -115,9 +148,22 @@ pub async fn run_core(
         connection_pool.clone(),
         read_only_connection_pool,
         eth_gateway.clone(),
+        mempool_tx_request_sender.clone(),
         config.api.private.clone(),
+        config.api.admin.secret_auth.clone(),
+        block_revert_sender,
     );
 
+    // Start deposit monitor, it's fed by the Ethereum Watcher.
+    let deposit_monitor_config = DepositMonitorConfig::from_env();
+    let (deposit_monitor_sender, deposit_monitor_task) = if deposit_monitor_config.enabled {
+        let (sender, receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+        let task = run_deposit_monitor(deposit_monitor_config, receiver);
+        (Some(sender), Some(task))
+    } else {
+        (None, None)
+    };
+
     // Start Ethereum Watcher.
     let eth_watch_task = start_eth_watch(
         eth_watch_req_sender.clone(),
@@ -126,6 +172,8 @@ pub async fn run_core(
         &config.contracts,
         &config.eth_watch,
         mempool_tx_request_sender.clone(),
+        deposit_monitor_sender,
+        connection_pool.clone(),
     )
     .await;
 
@@ -133,14 +181,34 @@ pub async fn run_core(
     let mut storage_processor = connection_pool.access_storage().await?;
 
     // Start state keeper and root hash calculator.
+    let tree_snapshots = TreeSnapshotStore::from_config(&config.chain.state_keeper);
     let state_keeper_init = ZkSyncStateInitParams::restore_from_db(
         &mut storage_processor,
         config.chain.state_keeper.fee_account_addr,
         &config.chain.state_keeper.block_chunk_sizes,
+        tree_snapshots.clone(),
     )
     .await;
 
-    let (mut state_keeper, root_hash_calculator) = ZkSyncStateKeeper::new(
+    // The blocks created from now on use the parameters from the config.
+    let protocol_parameters = ProtocolParameters {
+        block_chunk_sizes: config.chain.state_keeper.block_chunk_sizes.clone(),
+        max_withdrawals_per_execute_tx: config.eth_sender.sender.max_withdrawals_per_execute_tx,
+    };
+    let next_block_number = state_keeper_init.next_block_number();
+    if storage_processor
+        .protocol_parameters_schema()
+        .record_parameters(next_block_number, &protocol_parameters)
+        .await?
+    {
+        vlog::info!(
+            "Recorded the protocol parameters from block {}: {:?}",
+            next_block_number,
+            protocol_parameters
+        );
+    }
+
+    let (mut state_keeper, mut root_hash_calculator) = ZkSyncStateKeeper::new(
         state_keeper_init,
         config.chain.state_keeper.fee_account_addr,
         proposed_blocks_sender,
@@ -151,9 +219,33 @@ pub async fn run_core(
         processed_tx_events_sender,
     );
 
+    state_keeper.set_sealing_criteria(SealingCriteria::from(&config.chain.state_keeper));
+    state_keeper.set_parallel_execution(config.chain.state_keeper.parallel_execution_threads);
+    let block_production_halt = BlockProductionHalt::default();
+    state_keeper.set_block_production_halt(block_production_halt.clone());
+    if let Some(tree_snapshots) = tree_snapshots {
+        root_hash_calculator.set_tree_snapshots(tree_snapshots);
+    }
+
+    // Throttle the block production if the witness generator lags behind.
+    let witness_backlog = WitnessBacklog::new(&config.chain.state_keeper);
+    state_keeper.set_witness_backlog(witness_backlog.clone());
+    let witness_backlog_task = run_witness_backlog_monitor(
+        connection_pool.clone(),
+        witness_backlog,
+        &config.chain.state_keeper,
+    );
+
     // Execute reverted blocks before start
     state_keeper.execute_reverted_blocks().await;
 
+    if let Some(committed_state) = &committed_state {
+        state_keeper.set_committed_state(committed_state);
+    }
+    if let Some(fencing_term) = fencing_term.clone() {
+        state_keeper.set_fencing_term(fencing_term);
+    }
+
     let state_keeper_task = start_state_keeper(
         state_keeper,
         config.chain.state_keeper.miniblock_iteration_interval(),
@@ -165,6 +257,18 @@ pub async fn run_core(
         proposed_blocks_receiver,
         connection_pool.clone(),
         config.chain.clone(),
+        block_production_halt.clone(),
+        committed_state.clone(),
+        fencing_term,
+    );
+    let block_reverter_task = run_block_reverter(
+        connection_pool.clone(),
+        eth_gateway.clone(),
+        config.eth_sender.clone(),
+        block_production_halt,
+        committed_state,
+        block_revert_receiver,
+        stop_signal_sender,
     );
 
     // Start mempool.
@@ -172,6 +276,11 @@ pub async fn run_core(
         connection_pool.clone(),
         mempool_block_request_receiver,
         config.chain.state_keeper.block_chunk_sizes.clone(),
+        &config.chain.mempool,
+    );
+    let mempool_revalidator_task = run_mempool_revalidator(
+        connection_pool.clone(),
+        config.chain.state_keeper.mempool_revalidation_interval(),
     );
 
     // Start token handler.
@@ -194,18 +303,31 @@ pub async fn run_core(
         processed_tx_events_receiver,
     );
 
-    let task_futures = vec![
+    let mut task_futures = vec![
         eth_watch_task,
         state_keeper_task,
         root_hash_calculator_task,
+        witness_backlog_task,
         committer_task,
+        block_reverter_task,
         token_handler_task,
         register_factory_task,
         tx_event_emitter_task,
         mempool_block_handler_task,
         mempool_tx_handler_task,
+        mempool_revalidator_task,
         private_api_task,
     ];
+    task_futures.extend(deposit_monitor_task);
+
+    // Start the auditor of the stored balances.
+    let balance_auditor_config = BalanceAuditorConfig::from_env();
+    if balance_auditor_config.enabled {
+        task_futures.push(run_balance_auditor(
+            connection_pool.clone(),
+            balance_auditor_config,
+        ));
+    }
 
     Ok(task_futures)
 }
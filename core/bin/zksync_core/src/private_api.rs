@@ -5,72 +5,352 @@
 //! available from outside of the cluster.
 //!
 //! All the incoming data is assumed to be correct and not double-checked
-//! for correctness.
+//! for correctness, except for the admin endpoints which additionally require
+//! the JWT signed by `AdminApiConfig::secret_auth`. In particular, the transactions
+//! forwarded to the mempool must be checked by the API server beforehand.
 
 use std::thread;
 use std::time::{Duration, Instant};
 
+use actix_web::error::{ErrorBadRequest, ErrorInternalServerError, ErrorUnauthorized};
 use actix_web::{web, App, HttpResponse, HttpServer};
-use futures::{channel::mpsc, StreamExt};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use futures::{
+    channel::{mpsc, oneshot},
+    future, SinkExt, StreamExt,
+};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-use zksync_api_types::CoreStatus;
+use zksync_api_types::{CoreStatus, NewTxRequest};
+use zksync_types::{tx::error::TxAddError, Address, BlockNumber, PriorityOp, SignedZkSyncTx, H256};
 
-use zksync_config::configs::api::PrivateApiConfig;
+use zksync_config::configs::api::{PrivateApiConfig, PrivateApiTransport};
+use zksync_core_api_grpc::proto::{
+    core_api_server::{CoreApi, CoreApiServer},
+    GetStatusRequest, GetUnconfirmedDepositsRequest, GetUnconfirmedOpRequest, PriorityOps,
+    SubmitTxRequest, SubmitTxResponse,
+};
 use zksync_eth_client::EthereumGateway;
-use zksync_storage::ConnectionPool;
-use zksync_utils::panic_notify::ThreadPanicNotify;
+use zksync_mempool::MempoolTransactionRequest;
+use zksync_storage::{ConnectionPool, QueryResult};
+use zksync_utils::{
+    deadline::{deadline_channel, Deadline},
+    panic_notify::ThreadPanicNotify,
+};
+
+use crate::block_revert::{BlockRevertRequest, RevertBlocksRequest};
 
 const STATUS_INVALIDATION_PERIOD: Duration = Duration::from_secs(60);
+/// Time for the mempool to handle the forwarded transaction.
+const NEW_TX_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug)]
 struct AppState {
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     status_cache: RwLock<Option<(CoreStatus, Instant)>>,
 }
 
+impl AppState {
+    fn new(
+        connection_pool: ConnectionPool,
+        read_only_connection_pool: ConnectionPool,
+        eth_client: EthereumGateway,
+        mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    ) -> Self {
+        Self {
+            connection_pool,
+            read_only_connection_pool,
+            eth_client,
+            mempool_tx_sender,
+            status_cache: Default::default(),
+        }
+    }
+
+    async fn unconfirmed_deposits(&self, address: Address) -> QueryResult<Vec<PriorityOp>> {
+        let mut storage = self.read_only_connection_pool.access_storage().await?;
+        storage
+            .chain()
+            .mempool_schema()
+            .get_pending_deposits(address)
+            .await
+    }
+
+    async fn unconfirmed_op(&self, tx_hash: H256) -> QueryResult<Option<PriorityOp>> {
+        let mut storage = self.read_only_connection_pool.access_storage().await?;
+        storage
+            .chain()
+            .mempool_schema()
+            .get_pending_operation_by_hash(tx_hash)
+            .await
+    }
+
+    /// Forwards the transaction to the mempool and waits for it to be added.
+    async fn new_tx(
+        &self,
+        tx: SignedZkSyncTx,
+        not_before_block: Option<BlockNumber>,
+    ) -> anyhow::Result<Result<(), TxAddError>> {
+        let (sender, receiver) = deadline_channel(Deadline::after(NEW_TX_TIMEOUT));
+        self.mempool_tx_sender
+            .clone()
+            .send(MempoolTransactionRequest::NewTx(
+                Box::new(tx),
+                not_before_block,
+                sender,
+            ))
+            .await?;
+        Ok(receiver.await?)
+    }
+
+    async fn status(&self) -> CoreStatus {
+        if let Some((status, data)) = self.status_cache.read().await.as_ref() {
+            if data.elapsed() < STATUS_INVALIDATION_PERIOD {
+                return status.clone();
+            }
+        }
+
+        // We need to get a lock here so we don't abuse the database and eth node connections
+        // with multiple requests from other API nodes when the cache has been invalidated.
+
+        let mut status = self.status_cache.write().await;
+        let main_database_status = self.connection_pool.access_storage().await.is_ok();
+        let replica_database_status = self
+            .read_only_connection_pool
+            .access_storage()
+            .await
+            .is_ok();
+        let eth_status = self.eth_client.block_number().await.is_ok();
+
+        let response = CoreStatus {
+            main_database_available: main_database_status,
+            replica_database_available: replica_database_status,
+            web3_available: eth_status,
+        };
+        *status = Some((response.clone(), Instant::now()));
+        response
+    }
+}
+
 /// Health check.
 /// The core actor is expected have connection to web3 and both main/replica databases
 #[actix_web::get("/status")]
 async fn status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
-    if let Some((status, data)) = data.status_cache.read().await.as_ref() {
-        if data.elapsed() < STATUS_INVALIDATION_PERIOD {
-            return Ok(HttpResponse::Ok().json(status.clone()));
-        }
+    Ok(HttpResponse::Ok().json(data.status().await))
+}
+
+/// Priority operations of the account which are not confirmed on Ethereum yet.
+#[actix_web::get("/unconfirmed_deposits/{address}")]
+async fn unconfirmed_deposits(
+    data: web::Data<AppState>,
+    address: web::Path<Address>,
+) -> actix_web::Result<HttpResponse> {
+    let deposits = data
+        .unconfirmed_deposits(address.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(deposits))
+}
+
+/// Unconfirmed priority operation with the given hash.
+#[actix_web::get("/unconfirmed_op/{tx_hash}")]
+async fn unconfirmed_op(
+    data: web::Data<AppState>,
+    tx_hash: web::Path<H256>,
+) -> actix_web::Result<HttpResponse> {
+    let op = data
+        .unconfirmed_op(tx_hash.into_inner())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(op))
+}
+
+/// Adds the transaction to the mempool, responds with the result of the addition.
+#[actix_web::post("/new_tx")]
+async fn new_tx(
+    data: web::Data<AppState>,
+    request: web::Json<NewTxRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let request = request.into_inner();
+    let result = data
+        .new_tx(request.tx, request.not_before_block)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    sub: String,
+    /// Expiration time (as UTC timestamp).
+    exp: usize,
+}
+
+/// Shared data of the admin endpoints.
+#[derive(Debug, Clone)]
+struct AdminState {
+    secret_auth: String,
+    block_revert_requests: mpsc::Sender<BlockRevertRequest>,
+}
+
+impl AdminState {
+    fn authorize(&self, credentials: &BearerAuth) -> actix_web::Result<()> {
+        decode::<PayloadAuthToken>(
+            credentials.token(),
+            &DecodingKey::from_secret(self.secret_auth.as_ref()),
+            &Validation::default(),
+        )
+        .map_err(|_| ErrorUnauthorized("Invalid authorization token"))?;
+        Ok(())
     }
+}
 
-    // We need to get a lock here so we don't abuse the database and eth node connections
-    // with multiple requests from other API nodes when the cache has been invalidated.
+/// Reverts the blocks after the given one and stops the server, so it's restarted
+/// from the reverted state. See the `block_revert` module for details.
+#[actix_web::post("/admin/revert_blocks")]
+async fn revert_blocks(
+    data: web::Data<AdminState>,
+    credentials: BearerAuth,
+    request: web::Json<RevertBlocksRequest>,
+) -> actix_web::Result<HttpResponse> {
+    data.authorize(&credentials)?;
 
-    let mut status = data.status_cache.write().await;
-    let main_database_status = data.connection_pool.access_storage().await.is_ok();
-    let replica_database_status = data
-        .read_only_connection_pool
+    let (response_sender, response) = oneshot::channel();
+    data.block_revert_requests
+        .clone()
+        .send(BlockRevertRequest {
+            last_correct_block: request.last_correct_block,
+            response: response_sender,
+        })
+        .await
+        .map_err(ErrorInternalServerError)?;
+
+    match response.await.map_err(ErrorInternalServerError)? {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(err) if err.is_rejected() => Err(ErrorBadRequest(err.to_string())),
+        Err(err) => Err(ErrorInternalServerError(err.to_string())),
+    }
+}
+
+/// Lists the unfinished prover jobs with the provers they're leased to.
+#[actix_web::get("/admin/prover/jobs")]
+async fn prover_jobs(
+    data: web::Data<AdminState>,
+    app_state: web::Data<AppState>,
+    credentials: BearerAuth,
+) -> actix_web::Result<HttpResponse> {
+    data.authorize(&credentials)?;
+
+    let mut storage = app_state
+        .connection_pool
         .access_storage()
         .await
-        .is_ok();
-    let eth_status = data.eth_client.block_number().await.is_ok();
+        .map_err(ErrorInternalServerError)?;
+    let jobs = storage
+        .prover_schema()
+        .load_prover_job_assignments()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(jobs))
+}
 
-    let response = CoreStatus {
-        main_database_available: main_database_status,
-        replica_database_available: replica_database_status,
-        web3_available: eth_status,
-    };
-    *status = Some((response.clone(), Instant::now()));
+/// gRPC counterpart of the HTTP endpoints.
+#[derive(Debug)]
+struct GrpcCoreApi {
+    state: AppState,
+}
+
+#[tonic::async_trait]
+impl CoreApi for GrpcCoreApi {
+    async fn get_status(
+        &self,
+        _request: tonic::Request<GetStatusRequest>,
+    ) -> Result<tonic::Response<zksync_core_api_grpc::proto::CoreStatus>, tonic::Status> {
+        Ok(tonic::Response::new(self.state.status().await.into()))
+    }
+
+    async fn get_unconfirmed_deposits(
+        &self,
+        request: tonic::Request<GetUnconfirmedDepositsRequest>,
+    ) -> Result<tonic::Response<PriorityOps>, tonic::Status> {
+        let address = request.get_ref().address().map_err(invalid_argument)?;
+        let deposits = self
+            .state
+            .unconfirmed_deposits(address)
+            .await
+            .map_err(internal)?;
+        let deposits = PriorityOps::new(&deposits).map_err(internal)?;
+        Ok(tonic::Response::new(deposits))
+    }
+
+    async fn get_unconfirmed_op(
+        &self,
+        request: tonic::Request<GetUnconfirmedOpRequest>,
+    ) -> Result<tonic::Response<PriorityOps>, tonic::Status> {
+        let tx_hash = request.get_ref().tx_hash().map_err(invalid_argument)?;
+        let op = self.state.unconfirmed_op(tx_hash).await.map_err(internal)?;
+        let op = PriorityOps::new(&op).map_err(internal)?;
+        Ok(tonic::Response::new(op))
+    }
+
+    async fn submit_tx(
+        &self,
+        request: tonic::Request<SubmitTxRequest>,
+    ) -> Result<tonic::Response<SubmitTxResponse>, tonic::Status> {
+        let (tx, not_before_block) = request.get_ref().decode_tx().map_err(invalid_argument)?;
+        let result = self
+            .state
+            .new_tx(tx, not_before_block)
+            .await
+            .map_err(internal)?;
+        let response = SubmitTxResponse::new(result).map_err(internal)?;
+        Ok(tonic::Response::new(response))
+    }
+}
+
+fn invalid_argument(err: impl std::fmt::Display) -> tonic::Status {
+    tonic::Status::invalid_argument(err.to_string())
+}
 
-    Ok(HttpResponse::Ok().json(response))
+fn internal(err: impl std::fmt::Display) -> tonic::Status {
+    tonic::Status::internal(err.to_string())
 }
 
 pub fn start_private_core_api(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     config: PrivateApiConfig,
+    admin_secret_auth: String,
+    block_revert_requests: mpsc::Sender<BlockRevertRequest>,
 ) -> JoinHandle<()> {
+    let admin_state = AdminState {
+        secret_auth: admin_secret_auth,
+        block_revert_requests,
+    };
+
     let (panic_sender, mut panic_receiver) = mpsc::channel(1);
 
+    let grpc_server = (config.transport == PrivateApiTransport::Grpc).then(|| {
+        let service = GrpcCoreApi {
+            state: AppState::new(
+                connection_pool.clone(),
+                read_only_connection_pool.clone(),
+                eth_client.clone(),
+                mempool_tx_sender.clone(),
+            ),
+        };
+        tonic::transport::Server::builder()
+            .add_service(CoreApiServer::new(service))
+            .serve(config.grpc_bind_addr())
+    });
+
     thread::Builder::new()
         .name("core-private-api".to_string())
         .spawn(move || {
@@ -80,20 +360,26 @@ pub fn start_private_core_api(
             actix_runtime.block_on(async move {
                 // Start HTTP server.
                 HttpServer::new(move || {
-                    let app_state = AppState {
-                        connection_pool: connection_pool.clone(),
-                        read_only_connection_pool: read_only_connection_pool.clone(),
-                        eth_client: eth_client.clone(),
-                        status_cache: Default::default(),
-                    };
+                    let app_state = AppState::new(
+                        connection_pool.clone(),
+                        read_only_connection_pool.clone(),
+                        eth_client.clone(),
+                        mempool_tx_sender.clone(),
+                    );
 
                     // By calling `register_data` instead of `data` we're avoiding double
                     // `Arc` wrapping of the object.
                     App::new()
                         .wrap(actix_web::middleware::Logger::default())
                         .app_data(web::Data::new(app_state))
+                        .app_data(web::Data::new(admin_state.clone()))
                         .app_data(web::JsonConfig::default().limit(2usize.pow(32)))
                         .service(status)
+                        .service(unconfirmed_deposits)
+                        .service(unconfirmed_op)
+                        .service(new_tx)
+                        .service(revert_blocks)
+                        .service(prover_jobs)
                 })
                 .bind(&config.bind_addr())
                 .expect("failed to bind")
@@ -103,6 +389,90 @@ pub fn start_private_core_api(
         })
         .expect("failed to start prover server");
     tokio::spawn(async move {
-        panic_receiver.next().await.unwrap();
+        let grpc_server = async move {
+            match grpc_server {
+                Some(grpc_server) => grpc_server.await,
+                None => future::pending().await,
+            }
+        };
+        tokio::select! {
+            _ = panic_receiver.next() => {}
+            result = grpc_server => {
+                if let Err(err) = result {
+                    vlog::error!("gRPC server of the private core API failed: {}", err);
+                }
+            }
+        }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use actix_web::{http::StatusCode, test};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    use super::*;
+
+    const SECRET_AUTH: &str = "sample";
+
+    fn auth_token(secret: &str) -> String {
+        let exp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap() + Duration::from_secs(60);
+        let payload = PayloadAuthToken {
+            sub: "Authorization".to_string(),
+            exp: exp.as_secs() as usize,
+        };
+        encode(
+            &Header::default(),
+            &payload,
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+        .unwrap()
+    }
+
+    fn revert_request(token: Option<String>) -> test::TestRequest {
+        let request = test::TestRequest::post()
+            .uri("/admin/revert_blocks")
+            .set_json(&RevertBlocksRequest {
+                last_correct_block: BlockNumber(1),
+            });
+        match token {
+            Some(token) => request.insert_header(("Authorization", format!("Bearer {}", token))),
+            None => request,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn revert_blocks_requires_admin_token() {
+        let (block_revert_requests, mut requests) = mpsc::channel(1);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(AdminState {
+                    secret_auth: SECRET_AUTH.to_string(),
+                    block_revert_requests,
+                }))
+                .service(revert_blocks),
+        )
+        .await;
+
+        let request = revert_request(None).to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let request = revert_request(Some(auth_token("wrong"))).to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(requests.try_next().is_err());
+
+        // The authorized request is forwarded to the block reverter.
+        let request = revert_request(Some(auth_token(SECRET_AUTH))).to_request();
+        let response = test::call_service(&app, request);
+        let reverter = async {
+            let request = requests.next().await.unwrap();
+            assert_eq!(request.last_correct_block, BlockNumber(1));
+            // The response is dropped, so the revert is reported as failed.
+        };
+        let (response, _) = future::join(response, reverter).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
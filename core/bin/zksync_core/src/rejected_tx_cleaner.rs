@@ -1,21 +1,181 @@
-//! The cleaner is responsible for removing rejected transactions from the database
-//! that were stored 2 or more weeks ago (this value is configurable as well as the actor's sleep time).
+//! The cleaner is responsible for removing rejected (and evicted from the mempool) transactions
+//! from the database that were stored 2 or more weeks ago (this value is configurable as well as the actor's sleep time).
 //!
 //! The purpose is not to store the information about the failed transaction execution
 //! which is useful only for a short period of time. Since such transactions are not actually
 //! included in the block and don't affect the state hash, there is no much sense to keep
 //! them forever.
+//!
+//! The fee quotes served by the API are removed as well once they are older than their own
+//! maximum age, as the audit log is only needed for resolving the recent disputes. The same
+//! applies to the price history of the fee ticker.
+//!
+//! If the export to the cold storage is enabled, the rejected transactions are uploaded to the
+//! archive before the removal, signed with the key shared with the API, and only the uploaded
+//! ones are removed. The transactions failed to upload are skipped and retried with a backoff,
+//! so they don't block the removal of the others.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 // External uses
+use chrono::{DateTime, Utc};
 use tokio::{task::JoinHandle, time};
 
 // Workspace deps
-use zksync_config::DBConfig;
-use zksync_storage::ConnectionPool;
+use zksync_api_types::v02::transaction::ArchivedTx;
+use zksync_config::{configs::api::ArchiveConfig, DBConfig};
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{tx::TxHash, H256};
+
+/// Maximum number of the rejected transactions removed by a single iteration of the cleaner.
+const REMOVAL_BATCH_SIZE: u32 = 1000;
+/// Delay before the first retry of the failed export, doubled on every subsequent failure.
+const EXPORT_RETRY_DELAY: Duration = Duration::from_secs(60);
+/// Maximum delay between the retries of the failed export.
+const MAX_EXPORT_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// Returns the delay before the next export attempt after the given number of the failed ones.
+fn export_retry_delay(failed_attempts: u32) -> Duration {
+    let factor = 1u32 << failed_attempts.saturating_sub(1).min(16);
+    (EXPORT_RETRY_DELAY * factor).min(MAX_EXPORT_RETRY_DELAY)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ExportRetry {
+    failed_attempts: u32,
+    next_attempt: Instant,
+}
+
+/// Uploads the rejected transactions to the cold storage read by the API.
+struct ArchiveExporter {
+    client: reqwest::Client,
+    url: String,
+    signing_key: H256,
+    /// The last transaction of the previously loaded batch, the next one starts after it.
+    cursor: Option<(DateTime<Utc>, TxHash)>,
+    /// Transactions failed to upload, they are skipped until their next attempt.
+    retries: HashMap<TxHash, ExportRetry>,
+}
+
+impl ArchiveExporter {
+    /// Returns `None` if the export is disabled.
+    fn new(config: &ArchiveConfig) -> Option<Self> {
+        if !config.export_enabled {
+            return None;
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout())
+            .build()
+            .expect("Failed to create the archive HTTP client");
+        Some(Self {
+            client,
+            url: config.url.trim_end_matches('/').to_owned(),
+            signing_key: config.signing_key,
+            cursor: None,
+            retries: HashMap::new(),
+        })
+    }
+
+    async fn export_tx(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx_hash: TxHash,
+    ) -> anyhow::Result<()> {
+        let mut schema = storage.chain().operations_ext_schema();
+        let receipt = schema.tx_receipt_api_v02(tx_hash.as_ref()).await?;
+        let data = schema.tx_data_api_v02(tx_hash.as_ref()).await?;
+        let tx = match (receipt, data) {
+            (Some(receipt), Some(data)) => ArchivedTx { receipt, data },
+            _ => anyhow::bail!("Transaction is not found in the database"),
+        };
+        let blob = tx.sign(tx_hash, &self.signing_key)?;
+
+        self.client
+            .put(format!("{}/{}", self.url, ArchivedTx::key(tx_hash)))
+            .json(&blob)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Uploads the next batch of the rejected transactions older than `max_age` to the archive
+    /// and removes the uploaded ones from the database.
+    async fn archive_rejected_transactions(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        max_age: chrono::Duration,
+    ) -> anyhow::Result<()> {
+        let txs = storage
+            .chain()
+            .operations_schema()
+            .load_rejected_transactions(max_age, self.cursor, REMOVAL_BATCH_SIZE)
+            .await?;
+        // Once the end is reached, start over to retry the skipped transactions.
+        self.cursor = if txs.len() < REMOVAL_BATCH_SIZE as usize {
+            None
+        } else {
+            txs.last().copied()
+        };
+
+        let mut exported = Vec::with_capacity(txs.len());
+        for (_, tx_hash) in txs {
+            if let Some(retry) = self.retries.get(&tx_hash) {
+                if retry.next_attempt > Instant::now() {
+                    continue;
+                }
+            }
+            match self.export_tx(storage, tx_hash).await {
+                Ok(()) => {
+                    self.retries.remove(&tx_hash);
+                    exported.push(tx_hash);
+                }
+                Err(err) => {
+                    let retry = self.retries.entry(tx_hash).or_insert(ExportRetry {
+                        failed_attempts: 0,
+                        next_attempt: Instant::now(),
+                    });
+                    retry.failed_attempts += 1;
+                    retry.next_attempt = Instant::now() + export_retry_delay(retry.failed_attempts);
+                    metrics::increment_counter!("rejected_tx_cleaner.archive_errors");
+                    vlog::warn!(
+                        "Can't export the transaction {} to the archive (attempt {}): {}",
+                        tx_hash.to_string(),
+                        retry.failed_attempts,
+                        err
+                    );
+                }
+            }
+        }
+        metrics::gauge!(
+            "rejected_tx_cleaner.archive_retries",
+            self.retries.len() as f64
+        );
+
+        storage
+            .chain()
+            .operations_schema()
+            .remove_rejected_transactions_by_hash(&exported)
+            .await?;
+        metrics::counter!("rejected_tx_cleaner.archived_txs", exported.len() as u64);
+        Ok(())
+    }
+}
 
 #[must_use]
-pub fn run_rejected_tx_cleaner(config: &DBConfig, db_pool: ConnectionPool) -> JoinHandle<()> {
+pub fn run_rejected_tx_cleaner(
+    config: &DBConfig,
+    archive: &ArchiveConfig,
+    db_pool: ConnectionPool,
+) -> JoinHandle<()> {
+    let mut exporter = ArchiveExporter::new(archive);
     let max_age = chrono::Duration::from_std(config.rejected_transactions_max_age()).unwrap();
+    let fee_quotes_max_age = chrono::Duration::from_std(config.fee_quotes_max_age()).unwrap();
+    let price_history_max_age =
+        chrono::Duration::from_std(config.ticker_price_history_max_age()).unwrap();
     let interval = config.rejected_transactions_cleaner_interval();
     let mut timer = time::interval(interval);
 
@@ -25,15 +185,68 @@ pub fn run_rejected_tx_cleaner(config: &DBConfig, db_pool: ConnectionPool) -> Jo
                 .access_storage()
                 .await
                 .expect("transactions cleaner couldn't access the database");
+            let removal = match &mut exporter {
+                Some(exporter) => {
+                    exporter
+                        .archive_rejected_transactions(&mut storage, max_age)
+                        .await
+                }
+                None => {
+                    storage
+                        .chain()
+                        .operations_schema()
+                        .remove_rejected_transactions(max_age)
+                        .await
+                }
+            };
+            if let Err(e) = removal {
+                vlog::error!("Can't delete rejected transactions {:?}", e);
+            }
             if let Err(e) = storage
                 .chain()
-                .operations_schema()
-                .remove_rejected_transactions(max_age)
+                .mempool_schema()
+                .remove_evicted_txs(max_age)
                 .await
             {
-                vlog::error!("Can't delete rejected transactions {:?}", e);
+                vlog::error!("Can't delete evicted transactions {:?}", e);
+            }
+            if let Err(e) = storage
+                .chain()
+                .mempool_schema()
+                .remove_interrupted_submissions(max_age)
+                .await
+            {
+                vlog::error!("Can't delete interrupted submissions {:?}", e);
+            }
+            if let Err(e) = storage
+                .fee_quotes_schema()
+                .remove_old_fee_quotes(chrono::Utc::now() - fee_quotes_max_age)
+                .await
+            {
+                vlog::error!("Can't delete old fee quotes {:?}", e);
+            }
+            if let Err(e) = storage
+                .tokens_schema()
+                .remove_old_ticker_price_history(chrono::Utc::now() - price_history_max_age)
+                .await
+            {
+                vlog::error!("Can't delete old ticker price history {:?}", e);
             }
             timer.tick().await;
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_retry_delay_is_bounded() {
+        assert_eq!(export_retry_delay(1), EXPORT_RETRY_DELAY);
+        assert_eq!(export_retry_delay(2), EXPORT_RETRY_DELAY * 2);
+        assert_eq!(export_retry_delay(3), EXPORT_RETRY_DELAY * 4);
+        assert_eq!(export_retry_delay(10), MAX_EXPORT_RETRY_DELAY);
+        assert_eq!(export_retry_delay(u32::MAX), MAX_EXPORT_RETRY_DELAY);
+    }
+}
@@ -9,7 +9,7 @@ use zksync_types::{
 
 use super::{
     root_hash_calculator::BlockRootHashJob,
-    state_restore::{db::StateRestoreStorage, RestoredTree},
+    state_restore::{db::StateRestoreStorage, RestoredTree, TreeSnapshotStore},
 };
 
 #[derive(Debug, Clone)]
@@ -59,12 +59,19 @@ impl ZkSyncStateInitParams {
         }
     }
 
+    /// Number of the first block the state keeper is going to create.
+    pub fn next_block_number(&self) -> BlockNumber {
+        self.last_block_number + self.root_hash_jobs.len() as u32 + 1
+    }
+
     pub async fn restore_from_db(
         storage: &mut zksync_storage::StorageProcessor<'_>,
         fee_account_addr: Address,
         available_chunk_sizes: &[usize],
+        tree_snapshots: Option<TreeSnapshotStore>,
     ) -> Self {
-        let (last_block_number, tree, acc_id_by_addr) = Self::load_account_tree(storage).await;
+        let (last_block_number, tree, acc_id_by_addr) =
+            Self::load_account_tree(storage, tree_snapshots).await;
 
         let unprocessed_priority_op = Self::unprocessed_priority_op_id(storage).await;
         let nfts = Self::load_nft_tokens(storage, last_block_number).await;
@@ -100,8 +107,10 @@ impl ZkSyncStateInitParams {
 
     async fn load_account_tree(
         storage: &mut zksync_storage::StorageProcessor<'_>,
+        tree_snapshots: Option<TreeSnapshotStore>,
     ) -> (BlockNumber, AccountTree, HashMap<Address, AccountId>) {
-        let mut restored_tree = RestoredTree::new(StateRestoreStorage::new(storage));
+        let mut restored_tree =
+            RestoredTree::new(StateRestoreStorage::new(storage)).with_snapshots(tree_snapshots);
         let last_block_number = restored_tree.restore().await;
         (
             last_block_number,
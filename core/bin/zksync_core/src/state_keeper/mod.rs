@@ -1,4 +1,4 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::time::{Duration, Instant};
 
 // External uses
@@ -11,7 +11,12 @@ use tokio::task::JoinHandle;
 use tokio::time;
 // Workspace uses
 use zksync_mempool::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
-use zksync_state::state::{OpSuccess, ZkSyncState};
+use zksync_state::{
+    error::OpError,
+    parallel::PreparedOp,
+    shared::{CommittedStateSnapshot, SharedCommittedState},
+    state::{OpSuccess, ZkSyncState},
+};
 use zksync_types::{
     block::{
         BlockMetadata, ExecutedOperations, ExecutedPriorityOp, ExecutedTx, IncompleteBlock,
@@ -19,7 +24,7 @@ use zksync_types::{
     },
     gas_counter::GasCounter,
     mempool::SignedTxVariant,
-    tx::ZkSyncTx,
+    tx::{TxFailureCode, ZkSyncTx},
     Address, PriorityOp, SignedZkSyncTx,
 };
 // Local uses
@@ -30,19 +35,25 @@ use self::{
     utils::system_time_timestamp,
 };
 use crate::{
-    committer::{BlockCommitRequest, CommitRequest},
+    block_revert::BlockProductionHalt,
+    committer::{AppliedUpdatesRequest, BlockCommitRequest, CommitRequest},
+    leader_election::FencingTerm,
     tx_event_emitter::ProcessedOperations,
+    witness_backlog::{BlockProductionThrottle, WitnessBacklog},
 };
 
 pub use self::{
     init_params::ZkSyncStateInitParams,
     root_hash_calculator::{start_root_hash_calculator, BlockRootHashJobQueue},
+    sealing::{SealReason, SealingCriteria},
+    state_restore::TreeSnapshotStore,
     types::StateKeeperTestkitRequest,
 };
 
 mod init_params;
 mod pending_block;
 mod root_hash_calculator;
+mod sealing;
 mod state_restore;
 mod types;
 mod utils;
@@ -70,6 +81,25 @@ pub struct ZkSyncStateKeeper {
     /// Queue of reverted blocks
     /// They will be executed before the start of the StateKeeper
     reverted_blocks: VecDeque<IncompleteBlock>,
+
+    /// Backlog of the witness generator, the block production is throttled if it's too deep.
+    witness_backlog: Option<WitnessBacklog>,
+
+    /// Latest snapshot of the committed state shared with the API, if it runs in the same process.
+    /// It's published by the committer once the updates are stored in the database.
+    committed_state: Option<CommittedStateSnapshot>,
+
+    /// Criteria of sealing the block before the miniblock iterations limit is reached.
+    sealing_criteria: SealingCriteria,
+
+    /// Thread pool the operations of the independent transactions are created on, if enabled.
+    execution_pool: Option<rayon::ThreadPool>,
+
+    /// The miniblocks are not executed once the block production is halted to revert the blocks.
+    block_production_halt: BlockProductionHalt,
+
+    /// Leadership term of the instance, the miniblocks are not executed once it's over.
+    fencing_term: Option<FencingTerm>,
 }
 
 impl ZkSyncStateKeeper {
@@ -158,12 +188,102 @@ impl ZkSyncStateKeeper {
 
             root_hash_queue,
             reverted_blocks: initial_state.reverted_blocks.clone(),
+            witness_backlog: None,
+            committed_state: None,
+            sealing_criteria: SealingCriteria::default(),
+            execution_pool: None,
+            block_production_halt: BlockProductionHalt::default(),
+            fencing_term: None,
         };
         keeper.initialize(initial_state.pending_block);
 
         (keeper, root_hash_calculator)
     }
 
+    /// Makes the state keeper throttle the block production when the witness generator lags behind.
+    pub fn set_witness_backlog(&mut self, witness_backlog: WitnessBacklog) {
+        self.witness_backlog = Some(witness_backlog);
+    }
+
+    /// Makes the state keeper seal the blocks once any of the criteria is met.
+    pub fn set_sealing_criteria(&mut self, sealing_criteria: SealingCriteria) {
+        self.sealing_criteria = sealing_criteria;
+    }
+
+    /// Makes the state keeper create the operations of the independent transactions in parallel.
+    /// The transactions are still applied in the order they're proposed in.
+    pub fn set_parallel_execution(&mut self, threads: usize) {
+        self.execution_pool = if threads > 1 {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|index| format!("state-keeper-execution-{}", index))
+                .build()
+                .expect("Failed to create the state keeper execution thread pool");
+            Some(pool)
+        } else {
+            None
+        };
+    }
+
+    /// Makes the state keeper stop executing the miniblocks once the block production is halted.
+    pub fn set_block_production_halt(&mut self, block_production_halt: BlockProductionHalt) {
+        self.block_production_halt = block_production_halt;
+    }
+
+    /// Makes the state keeper stop executing the miniblocks once the leadership term is over.
+    pub fn set_fencing_term(&mut self, fencing_term: FencingTerm) {
+        self.fencing_term = Some(fencing_term);
+    }
+
+    /// Makes the state keeper build the snapshots of the updated accounts on top of the shared
+    /// committed state, the committer publishes them (see `run_committer`).
+    pub fn set_committed_state(&mut self, committed_state: &SharedCommittedState) {
+        self.committed_state = Some(committed_state.snapshot().as_ref().clone());
+    }
+
+    /// Adds the accounts updated by the request to the committed state snapshot, so the committer
+    /// publishes it once the request is stored.
+    fn attach_committed_state(&mut self, applied_updates_request: &mut AppliedUpdatesRequest) {
+        let committed_state = match &mut self.committed_state {
+            Some(committed_state) => committed_state,
+            None => return,
+        };
+
+        let updated_accounts = applied_updates_request
+            .account_updates
+            .iter()
+            .map(|(account_id, _)| *account_id)
+            .collect::<HashSet<_>>();
+        if updated_accounts.is_empty() {
+            return;
+        }
+
+        *committed_state = committed_state.with_updates(
+            self.pending_block.number,
+            updated_accounts
+                .into_iter()
+                .map(|account_id| (account_id, self.state.get_account(account_id))),
+        );
+        applied_updates_request.committed_state = Some(committed_state.clone());
+    }
+
+    fn block_production_throttle(&self) -> BlockProductionThrottle {
+        self.witness_backlog
+            .as_ref()
+            .map(WitnessBacklog::throttle)
+            .unwrap_or(BlockProductionThrottle::None)
+    }
+
+    /// Size of the next block: the smallest one if the witness generator lags behind too much.
+    fn next_block_size(&self) -> usize {
+        match self.block_production_throttle() {
+            BlockProductionThrottle::SmallBlocks => self.config.available_block_chunk_sizes[0],
+            BlockProductionThrottle::None | BlockProductionThrottle::SlowSealing => {
+                self.config.max_block_size()
+            }
+        }
+    }
+
     // TODO (ZKS-821): We should get rid of this function and create state keeper in a ready-to-go state.
     // Currently we partially initialize state keeper, and then finalize initialization when it's actually started
     // which is not a good practice.
@@ -262,7 +382,7 @@ impl ZkSyncStateKeeper {
                     sender.send(account).unwrap_or_default();
                 }
                 StateKeeperTestkitRequest::SealBlock => {
-                    self.seal_pending_block().await;
+                    self.seal_pending_block(SealReason::Manual).await;
                 }
                 StateKeeperTestkitRequest::GetCurrentState(sender) => {
                     sender.send(self.get_current_state()).unwrap_or_default();
@@ -291,6 +411,19 @@ impl ZkSyncStateKeeper {
             self.root_hash_queue.throttle().await;
             metrics::histogram!("state_keeper.throttle", start.elapsed());
 
+            let _halt_guard = match self.block_production_halt.enter().await {
+                Some(guard) => guard,
+                None => continue,
+            };
+            // The next leader is producing the blocks already, the server is going to be stopped.
+            if self
+                .fencing_term
+                .as_ref()
+                .map_or(false, FencingTerm::is_lost)
+            {
+                continue;
+            }
+
             let block_timestamp = self.pending_block.timestamp;
             let proposed_block = self.propose_new_block(block_timestamp).await;
             metrics::histogram!("miniblock_size", proposed_block.size() as f64);
@@ -367,7 +500,7 @@ impl ZkSyncStateKeeper {
             self.pending_block.unprocessed_priority_op_current, after_priority_op,
             "Unexpected executed priority operations amount after applying reverted blocks."
         );
-        self.seal_pending_block().await;
+        self.seal_pending_block(SealReason::Reverted).await;
         self.tx_for_commitments
             .send(CommitRequest::RemoveRevertedBlock(block.block_number))
             .await
@@ -396,7 +529,7 @@ impl ZkSyncStateKeeper {
                     executed_ops.push(exec_op);
                 }
                 ApplyOutcome::NotIncluded => {
-                    self.seal_pending_block().await;
+                    self.seal_pending_block(SealReason::NoSpace).await;
 
                     priority_op_queue.push_front(priority_op);
                 }
@@ -404,10 +537,18 @@ impl ZkSyncStateKeeper {
         }
 
         let mut tx_queue = proposed_block.txs.into_iter().collect::<VecDeque<_>>();
+        // Operations of the independent transactions following in the queue, created in advance.
+        let mut prepared_txs = VecDeque::new();
         while let Some(variant) = tx_queue.pop_front() {
             match &variant {
                 SignedTxVariant::Tx(tx) => {
-                    match self.apply_tx(tx) {
+                    if prepared_txs.is_empty() {
+                        prepared_txs = self.prepare_txs_wave(tx, &tx_queue);
+                    }
+                    let prepared = prepared_txs
+                        .pop_front()
+                        .expect("The wave contains the operation of the current transaction");
+                    match self.apply_prepared_tx(tx, prepared) {
                         ApplyOutcome::Included(exec_op) => {
                             executed_ops.push(exec_op);
                         }
@@ -415,7 +556,10 @@ impl ZkSyncStateKeeper {
                             // We could not execute the tx due to either of block size limit
                             // or the withdraw operations limit, so we seal this block and
                             // the last transaction will go to the next block instead.
-                            self.seal_pending_block().await;
+                            self.seal_pending_block(SealReason::NoSpace).await;
+                            // Sealing the block collects the fees, so the operations
+                            // created in advance may be outdated.
+                            prepared_txs.clear();
 
                             tx_queue.push_front(variant);
                         }
@@ -430,7 +574,7 @@ impl ZkSyncStateKeeper {
                             // We could not execute the batch tx due to either of block size limit
                             // or the withdraw operations limit, so we seal this block and
                             // the last transaction will go to the next block instead.
-                            self.seal_pending_block().await;
+                            self.seal_pending_block(SealReason::NoSpace).await;
 
                             tx_queue.push_front(variant);
                         }
@@ -454,17 +598,39 @@ impl ZkSyncStateKeeper {
         // Iteration is complete, increment it in the pending block.
         self.pending_block.increment_iteration();
 
-        // If pending block contains withdrawals we seal it faster
+        // If pending block contains withdrawals we seal it faster, and if the witness generator
+        // lags behind we seal it slower.
+        let throttle = self.block_production_throttle();
         let max_miniblock_iterations = if self.pending_block.fast_processing_required {
             self.config.fast_miniblock_iterations
+        } else if throttle != BlockProductionThrottle::None {
+            let throttled_iterations = self
+                .witness_backlog
+                .as_ref()
+                .map(WitnessBacklog::throttled_miniblock_iterations)
+                .unwrap_or_default();
+            self.config
+                .max_miniblock_iterations
+                .max(throttled_iterations)
         } else {
             self.config.max_miniblock_iterations
         };
+        metrics::gauge!(
+            "state_keeper.block_production_throttled",
+            if throttle == BlockProductionThrottle::None {
+                0.0
+            } else {
+                1.0
+            }
+        );
 
         // Check whether we should seal this block and start processing the next one, or we just need
         // to persist the pending block.
-        if self.pending_block.should_seal(max_miniblock_iterations) {
-            self.seal_pending_block().await;
+        if let Some(reason) = self
+            .pending_block
+            .should_seal(max_miniblock_iterations, &self.sealing_criteria)
+        {
+            self.seal_pending_block(reason).await;
         } else {
             // State keeper may process empty blocks (or blocks containing rejected transactions only), and it's an
             // important part of its logic: timeout for sealing the block is expressed in the amount of processing
@@ -562,6 +728,7 @@ impl ZkSyncStateKeeper {
                     success: false,
                     op: None,
                     fail_reason: Some(fail_reason.clone()),
+                    fail_code: Some(TxFailureCode::BatchTooExpensive),
                     block_index: None,
                     created_at: chrono::Utc::now(),
                     batch_id: Some(batch_id),
@@ -603,6 +770,7 @@ impl ZkSyncStateKeeper {
                         success: true,
                         op: Some(executed_op),
                         fail_reason: None,
+                        fail_code: None,
                         block_index: Some(block_index),
                         created_at: chrono::Utc::now(),
                         batch_id: Some(batch_id),
@@ -631,6 +799,7 @@ impl ZkSyncStateKeeper {
                         success: false,
                         op: None,
                         fail_reason: Some(e.to_string()),
+                        fail_code: Some(e.reason.failure_code()),
                         block_index: None,
                         created_at: chrono::Utc::now(),
                         batch_id: Some(batch_id),
@@ -646,7 +815,49 @@ impl ZkSyncStateKeeper {
         ApplyOutcome::Included(executed_operations)
     }
 
+    /// Creates the operations of the transaction and of the following transactions independent of it.
+    /// The operations are created in parallel if the parallel execution is enabled,
+    /// otherwise only the operation of the transaction itself is created.
+    fn prepare_txs_wave(
+        &self,
+        tx: &SignedZkSyncTx,
+        tx_queue: &VecDeque<SignedTxVariant>,
+    ) -> VecDeque<Result<PreparedOp, OpError>> {
+        let pool = match &self.execution_pool {
+            Some(pool) => pool,
+            None => return std::iter::once(self.state.prepare_tx(tx.tx.clone())).collect(),
+        };
+
+        // Only the transactions of the wave are borrowed from the queue, not the whole queue.
+        let txs = std::iter::once(&tx.tx).chain(
+            tx_queue
+                .iter()
+                // Batches are executed on their own, so the wave ends at the first one.
+                .take_while(|variant| matches!(variant, SignedTxVariant::Tx(_)))
+                .filter_map(|variant| match variant {
+                    SignedTxVariant::Tx(tx) => Some(&tx.tx),
+                    SignedTxVariant::Batch(_) => None,
+                }),
+        );
+        let wave_len = self.state.independent_wave_len(txs.clone());
+        let wave: Vec<&ZkSyncTx> = txs.take(wave_len).collect();
+        metrics::histogram!("state_keeper.prepared_txs_wave", wave.len() as f64);
+
+        let state = &self.state;
+        pool.install(|| state.prepare_txs_wave(&wave)).into()
+    }
+
     fn apply_tx(&mut self, tx: &SignedZkSyncTx) -> ApplyOutcome<ExecutedOperations> {
+        let prepared = self.state.prepare_tx(tx.tx.clone());
+        self.apply_prepared_tx(tx, prepared)
+    }
+
+    /// Applies the transaction with the operation created against the current state.
+    fn apply_prepared_tx(
+        &mut self,
+        tx: &SignedZkSyncTx,
+        prepared: Result<PreparedOp, OpError>,
+    ) -> ApplyOutcome<ExecutedOperations> {
         let start = Instant::now();
         let chunks_needed = self.state.chunks_for_tx(tx);
 
@@ -658,14 +869,13 @@ impl ZkSyncStateKeeper {
 
         // Check if adding this transaction to the block won't make the contract operations
         // too expensive.
-        let non_executed_op = self.state.zksync_tx_to_zksync_op(tx.tx.clone());
-        if let Ok(non_executed_op) = non_executed_op {
+        if let Ok(prepared) = &prepared {
             // We only care about successful conversions, since if conversion failed,
             // then transaction will fail as well (as it shares the same code base).
             if !self
                 .pending_block
                 .gas_counter
-                .can_include(&[non_executed_op])
+                .can_include(&[prepared.to_zksync_op()])
             {
                 // We've reached the gas limit, seal the block.
                 // This transaction will go into the next one.
@@ -680,9 +890,9 @@ impl ZkSyncStateKeeper {
             }
         }
 
-        let tx_updates = self
-            .state
-            .execute_tx(tx.tx.clone(), self.pending_block.timestamp);
+        let tx_updates =
+            self.state
+                .execute_prepared_tx(&tx.tx, prepared, self.pending_block.timestamp);
 
         let exec_result = match tx_updates {
             Ok(OpSuccess {
@@ -701,6 +911,7 @@ impl ZkSyncStateKeeper {
                     success: true,
                     op: Some(executed_op),
                     fail_reason: None,
+                    fail_code: None,
                     block_index: Some(block_index),
                     created_at: chrono::Utc::now(),
                     batch_id: None,
@@ -722,6 +933,7 @@ impl ZkSyncStateKeeper {
                     success: false,
                     op: None,
                     fail_reason: Some(e.to_string()),
+                    fail_code: Some(e.failure_code()),
                     block_index: None,
                     created_at: chrono::Utc::now(),
                     batch_id: None,
@@ -738,7 +950,7 @@ impl ZkSyncStateKeeper {
     }
 
     /// Finalizes the pending block, transforming it into a full block.
-    async fn seal_pending_block(&mut self) {
+    async fn seal_pending_block(&mut self, reason: SealReason) {
         let start = Instant::now();
 
         // Before sealing the block, we need to store the pending block in order to
@@ -767,7 +979,7 @@ impl ZkSyncStateKeeper {
         let new_pending_block = PendingBlock::new(
             self.pending_block.number + 1,
             next_unprocessed_priority_op,
-            self.config.max_block_size(),
+            self.next_block_size(),
             system_time_timestamp(),
         );
 
@@ -820,19 +1032,22 @@ impl ZkSyncStateKeeper {
             block,
             block_metadata,
         };
-        let applied_updates_request = self.pending_block.prepare_applied_updates_request();
+        let mut applied_updates_request = self.pending_block.prepare_applied_updates_request();
+        self.attach_committed_state(&mut applied_updates_request);
         let root_hash_job = BlockRootHashJob {
             block: current_block,
             updates: self.pending_block.account_updates.clone(),
         };
 
         vlog::info!(
-            "Creating full block: {}, operations: {}, chunks_left: {}, miniblock iterations: {}",
+            "Creating full block: {}, operations: {}, chunks_left: {}, miniblock iterations: {}, sealed by: {}",
             *block_commit_request.block.block_number,
             block_commit_request.block.block_transactions.len(),
             self.pending_block.chunks_left,
-            self.pending_block.pending_block_iteration
+            self.pending_block.pending_block_iteration,
+            reason.as_str()
         );
+        metrics::increment_counter!("state_keeper.block_sealed", "reason" => reason.as_str());
         metrics::gauge!(
             "last_processed_block",
             block_commit_request.block.block_number.0 as f64,
@@ -856,7 +1071,8 @@ impl ZkSyncStateKeeper {
         let start = Instant::now();
 
         let pending_block = self.pending_block.prepare_for_storing();
-        let applied_updates_request = self.pending_block.prepare_applied_updates_request();
+        let mut applied_updates_request = self.pending_block.prepare_applied_updates_request();
+        self.attach_committed_state(&mut applied_updates_request);
 
         vlog::debug!(
             "Persisting mini block: {}, operations: {}, failed_txs: {}, chunks_left: {}, miniblock iterations: {}",
@@ -1,3 +1,5 @@
+// Built-in uses
+use std::time::Instant;
 // External uses
 // Workspace uses
 use zksync_state::state::CollectedFee;
@@ -7,6 +9,7 @@ use zksync_types::{
     AccountUpdates, BlockNumber,
 };
 
+use super::sealing::{SealReason, SealingCriteria};
 use crate::committer::AppliedUpdatesRequest;
 // Local uses
 
@@ -18,6 +21,8 @@ pub(super) struct PendingBlock {
     pub(super) failed_txs: Vec<ExecutedTx>,
     pub(super) account_updates: AccountUpdates,
     pub(super) chunks_left: usize,
+    /// Number of the chunks the block started with.
+    pub(super) block_size: usize,
     pub(super) pending_op_block_index: u32,
     pub(super) unprocessed_priority_op_before: u64,
     pub(super) unprocessed_priority_op_current: u64,
@@ -30,6 +35,10 @@ pub(super) struct PendingBlock {
     /// Number of stored account updates in the db (from `account_updates` field)
     pub(super) stored_account_updates: usize,
     pub(super) timestamp: u64,
+    /// Time the first successful operation was added to the block.
+    pub(super) first_op_at: Option<Instant>,
+    /// Time the first priority operation was added to the block.
+    pub(super) first_priority_op_at: Option<Instant>,
 
     // Two fields below are for optimization: we don't want to overwrite all the block contents over and over.
     // With these fields we'll be able save the diff between two pending block states only.
@@ -52,6 +61,7 @@ impl PendingBlock {
             failed_txs: Vec::new(),
             account_updates: Vec::new(),
             chunks_left: max_block_size,
+            block_size: max_block_size,
             pending_op_block_index: 0,
             unprocessed_priority_op_before,
             unprocessed_priority_op_current: unprocessed_priority_op_before,
@@ -61,6 +71,8 @@ impl PendingBlock {
             collected_fees: Vec::new(),
             stored_account_updates: 0,
             timestamp,
+            first_op_at: None,
+            first_priority_op_at: None,
 
             success_txs_pending_len: 0,
             failed_txs_pending_len: 0,
@@ -77,9 +89,23 @@ impl PendingBlock {
         self.failed_txs.is_empty() && self.success_operations.is_empty()
     }
 
-    pub(super) fn should_seal(&self, miniblock_iterations: usize) -> bool {
+    /// Returns the reason to seal the block, if it should be sealed.
+    pub(super) fn should_seal(
+        &self,
+        miniblock_iterations: usize,
+        criteria: &SealingCriteria,
+    ) -> Option<SealReason> {
+        if self.chunks_left == 0 {
+            return Some(SealReason::BlockFull);
+        }
         // `>=` in condition since iterations start with 0.
-        self.chunks_left == 0 || self.pending_block_iteration >= miniblock_iterations
+        if self.pending_block_iteration >= miniblock_iterations {
+            return Some(SealReason::Iterations);
+        }
+        if self.success_operations.is_empty() {
+            return None;
+        }
+        criteria.check(self)
     }
 
     pub(super) fn add_successful_execution(
@@ -107,8 +133,11 @@ impl PendingBlock {
         }
         self.pending_op_block_index += 1;
 
+        let now = Instant::now();
+        self.first_op_at.get_or_insert(now);
         if exec_result.is_priority() {
             self.unprocessed_priority_op_current += 1;
+            self.first_priority_op_at.get_or_insert(now);
         }
 
         self.success_operations.push(exec_result);
@@ -151,6 +180,7 @@ impl PendingBlock {
         let applied_updates_request = AppliedUpdatesRequest {
             account_updates,
             first_update_order_id,
+            committed_state: None,
         };
         self.stored_account_updates = self.account_updates.len();
 
@@ -161,8 +191,9 @@ impl PendingBlock {
 #[cfg(test)]
 mod tests {
     use chrono::prelude::*;
+    use std::time::Duration;
     use zksync_types::{
-        AccountId, AccountUpdate, Address, Nonce, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx,
+        AccountId, AccountUpdate, Address, Nonce, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx, U256,
     };
 
     use super::*;
@@ -209,6 +240,7 @@ mod tests {
             success: false,
             op: None,
             fail_reason: Some("Mock".to_string()),
+            fail_code: None,
             block_index: None,
             created_at: Utc.ymd(2021, 12, 9).and_hms(12, 26, 11),
             batch_id: None,
@@ -255,7 +287,9 @@ mod tests {
         // Methods testing on the empty block.
         assert!(pending_block.is_empty(), "Block should be empty");
         assert!(
-            !pending_block.should_seal(MAX_ITERATIONS),
+            pending_block
+                .should_seal(MAX_ITERATIONS, &SealingCriteria::default())
+                .is_none(),
             "Should no seal empty block with no enough iterations"
         );
 
@@ -284,7 +318,9 @@ mod tests {
         );
 
         assert!(
-            !pending_block.should_seal(MAX_ITERATIONS),
+            pending_block
+                .should_seal(MAX_ITERATIONS, &SealingCriteria::default())
+                .is_none(),
             "Block should not be sealed after 1 iteration"
         );
 
@@ -294,8 +330,9 @@ mod tests {
             "Iteration should be incremented"
         );
 
-        assert!(
-            pending_block.should_seal(MAX_ITERATIONS),
+        assert_eq!(
+            pending_block.should_seal(MAX_ITERATIONS, &SealingCriteria::default()),
+            Some(SealReason::Iterations),
             "Block should be sealed after 2 iteration"
         );
 
@@ -382,4 +419,66 @@ mod tests {
             pending_block.account_updates.len(),
         )
     }
+
+    #[test]
+    fn sealing_criteria() {
+        let mut pending_block = pending_block();
+        let criteria = SealingCriteria {
+            chunks_utilization_percent: 50,
+            max_first_tx_age: Duration::from_secs(60),
+            max_priority_op_age: Duration::from_secs(60),
+            max_commit_gas: U256::zero(),
+        };
+        assert!(pending_block
+            .should_seal(MAX_ITERATIONS, &criteria)
+            .is_none());
+
+        let (chunks, updates, fee, exec_result) = prepare_successful_execution();
+        pending_block.add_successful_execution(chunks, updates, fee, exec_result);
+        assert!(pending_block.first_op_at.is_some());
+        assert!(pending_block.first_priority_op_at.is_none());
+        assert!(pending_block
+            .should_seal(MAX_ITERATIONS, &criteria)
+            .is_none());
+
+        // The block waits for too long.
+        pending_block.first_priority_op_at = Some(Instant::now() - Duration::from_secs(60));
+        assert_eq!(
+            pending_block.should_seal(MAX_ITERATIONS, &criteria),
+            Some(SealReason::PriorityOpAge)
+        );
+        pending_block.first_op_at = Some(Instant::now() - Duration::from_secs(60));
+        assert_eq!(
+            pending_block.should_seal(MAX_ITERATIONS, &criteria),
+            Some(SealReason::FirstTxAge)
+        );
+
+        // The block is full enough.
+        pending_block.chunks_left = CHUNKS_PER_BLOCK / 2;
+        assert_eq!(
+            pending_block.should_seal(MAX_ITERATIONS, &criteria),
+            Some(SealReason::ChunksUtilization)
+        );
+        pending_block.chunks_left = 0;
+        assert_eq!(
+            pending_block.should_seal(MAX_ITERATIONS, &criteria),
+            Some(SealReason::BlockFull)
+        );
+
+        // The commit of the block is too expensive.
+        let mut pending_block = pending_block();
+        let (chunks, updates, fee, exec_result) = prepare_successful_execution();
+        pending_block.add_successful_execution(chunks, updates, fee, exec_result);
+        let criteria = SealingCriteria {
+            max_commit_gas: pending_block.gas_counter.commit_gas_limit(),
+            ..Default::default()
+        };
+        assert_eq!(
+            pending_block.should_seal(MAX_ITERATIONS, &criteria),
+            Some(SealReason::CommitGas)
+        );
+        assert!(pending_block
+            .should_seal(MAX_ITERATIONS, &SealingCriteria::default())
+            .is_none());
+    }
 }
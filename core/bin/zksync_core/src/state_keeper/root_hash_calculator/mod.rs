@@ -1,11 +1,12 @@
 use std::time::Instant;
 
-use futures::{channel::mpsc, SinkExt};
+use futures::{channel::mpsc, FutureExt, SinkExt};
 use tokio::task::JoinHandle;
 
 use zksync_state::state::ZkSyncState;
-use zksync_types::BlockNumber;
+use zksync_types::{AccountId, BlockNumber};
 
+use super::state_restore::{TreeSnapshot, TreeSnapshotStore};
 use crate::committer::{BlockFinishRequest, CommitRequest};
 
 mod queue;
@@ -35,6 +36,12 @@ pub struct RootHashCalculator {
     // While we don't really need the number for calculations, it's useful for safety
     // to ensure that every block is processed in order.
     last_block_number: BlockNumber,
+
+    /// Storage of the account tree snapshots, if enabled.
+    tree_snapshots: Option<TreeSnapshotStore>,
+    /// Task storing the last snapshot, so the snapshots are never stored concurrently.
+    /// The snapshot due while the previous one is being stored is skipped.
+    snapshot_task: Option<JoinHandle<()>>,
 }
 
 impl RootHashCalculator {
@@ -52,9 +59,16 @@ impl RootHashCalculator {
             job_queue,
             tx_for_commitments,
             last_block_number,
+            tree_snapshots: None,
+            snapshot_task: None,
         }
     }
 
+    /// Makes the root hash calculator periodically store the snapshots of the account tree.
+    pub fn set_tree_snapshots(&mut self, tree_snapshots: TreeSnapshotStore) {
+        self.tree_snapshots = Some(tree_snapshots);
+    }
+
     pub async fn run(mut self) {
         loop {
             let job = self.job_queue.pop().await;
@@ -92,6 +106,8 @@ impl RootHashCalculator {
         // Increment block number to expect the next one.
         self.last_block_number = self.last_block_number + 1;
 
+        self.store_tree_snapshot(job.block).await;
+
         metrics::histogram!("root_hash_calculator.process_job", start.elapsed());
         metrics::gauge!(
             "last_processed_block",
@@ -101,6 +117,65 @@ impl RootHashCalculator {
         self.report_memory_stats();
     }
 
+    /// Stores the snapshot of the account tree in the background, if it's due for the block.
+    async fn store_tree_snapshot(&mut self, block: BlockNumber) {
+        let tree_snapshots = match &self.tree_snapshots {
+            Some(tree_snapshots) if tree_snapshots.is_due(block) => tree_snapshots.clone(),
+            _ => return,
+        };
+        if let Some(snapshot_task) = self.snapshot_task.as_mut() {
+            match snapshot_task.now_or_never() {
+                Some(result) => result.expect("Tree snapshot task panicked"),
+                None => {
+                    vlog::warn!(
+                        "The previous account tree snapshot is still being stored, \
+                         skipping the snapshot of block #{}",
+                        block
+                    );
+                    return;
+                }
+            }
+        }
+
+        // The accounts are copied on the blocking thread, so the large state doesn't block
+        // the runtime. The state is moved there, since it can't change until it's copied.
+        let start = Instant::now();
+        let state = std::mem::replace(&mut self.state, ZkSyncState::empty());
+        let (state, accounts) = tokio::task::spawn_blocking(move || {
+            let accounts = state
+                .get_accounts()
+                .into_iter()
+                .map(|(id, account)| (AccountId(id), account))
+                .collect();
+            (state, accounts)
+        })
+        .await
+        .expect("Tree snapshot copy panicked");
+        self.state = state;
+        let snapshot = TreeSnapshot::new(block, accounts);
+        metrics::histogram!("root_hash_calculator.create_tree_snapshot", start.elapsed());
+
+        self.snapshot_task = Some(tokio::task::spawn_blocking(move || {
+            let start = Instant::now();
+            match tree_snapshots.store(&snapshot) {
+                Ok(()) => {
+                    vlog::info!("Stored the account tree snapshot of block #{}", block);
+                    metrics::histogram!(
+                        "root_hash_calculator.store_tree_snapshot",
+                        start.elapsed()
+                    );
+                }
+                Err(err) => {
+                    vlog::warn!(
+                        "Failed to store the account tree snapshot of block #{}: {}",
+                        block,
+                        err
+                    );
+                }
+            }
+        }));
+    }
+
     fn report_memory_stats(&self) {
         let memory_stats = self.state.tree_memory_stats();
         metrics::histogram!("tree_memory_usage", memory_stats.allocated_total as f64, "type" => "total");
@@ -0,0 +1,105 @@
+//! Criteria of sealing the pending block.
+//!
+//! Besides running out of chunks and reaching the limit of the miniblock iterations, the block
+//! is sealed once it's full enough to not wait for more transactions, once its operations
+//! wait for too long to be committed, or once its commit becomes too expensive.
+//! Every criterion is disabled if zero.
+
+// Built-in uses
+use std::time::{Duration, Instant};
+// Workspace uses
+use zksync_config::configs::chain::StateKeeper as StateKeeperConfig;
+use zksync_types::U256;
+// Local uses
+use super::pending_block::PendingBlock;
+
+/// The criterion the pending block was sealed by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SealReason {
+    /// The next operation doesn't fit into the block.
+    NoSpace,
+    /// All the chunks of the block are used.
+    BlockFull,
+    /// The limit of the miniblock iterations is reached.
+    Iterations,
+    /// The share of the used chunks is reached.
+    ChunksUtilization,
+    /// The first operation of the block waits for too long.
+    FirstTxAge,
+    /// The first priority operation of the block waits for too long.
+    PriorityOpAge,
+    /// The gas cost of the block commit is reached.
+    CommitGas,
+    /// The reverted block is executed again.
+    Reverted,
+    /// The block is sealed on request.
+    Manual,
+}
+
+impl SealReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NoSpace => "no_space",
+            Self::BlockFull => "block_full",
+            Self::Iterations => "iterations",
+            Self::ChunksUtilization => "chunks_utilization",
+            Self::FirstTxAge => "first_tx_age",
+            Self::PriorityOpAge => "priority_op_age",
+            Self::CommitGas => "commit_gas",
+            Self::Reverted => "reverted",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SealingCriteria {
+    /// Share of the used block chunks, in percent.
+    pub chunks_utilization_percent: u32,
+    pub max_first_tx_age: Duration,
+    pub max_priority_op_age: Duration,
+    /// Estimated gas cost of the block commit.
+    pub max_commit_gas: U256,
+}
+
+impl From<&StateKeeperConfig> for SealingCriteria {
+    fn from(config: &StateKeeperConfig) -> Self {
+        Self {
+            chunks_utilization_percent: config.seal_chunks_utilization_percent,
+            max_first_tx_age: config.seal_first_tx_age(),
+            max_priority_op_age: config.seal_priority_op_age(),
+            max_commit_gas: config.seal_commit_gas_limit.into(),
+        }
+    }
+}
+
+impl SealingCriteria {
+    /// Returns the first criterion met by the pending block, if any.
+    pub(super) fn check(&self, block: &PendingBlock) -> Option<SealReason> {
+        if self.chunks_utilization_percent != 0 && block.block_size != 0 {
+            let chunks_used = block.block_size - block.chunks_left;
+            if chunks_used * 100 >= block.block_size * self.chunks_utilization_percent as usize {
+                return Some(SealReason::ChunksUtilization);
+            }
+        }
+        if Self::is_expired(block.first_op_at, self.max_first_tx_age) {
+            return Some(SealReason::FirstTxAge);
+        }
+        if Self::is_expired(block.first_priority_op_at, self.max_priority_op_age) {
+            return Some(SealReason::PriorityOpAge);
+        }
+        if !self.max_commit_gas.is_zero()
+            && block.gas_counter.commit_gas_limit() >= self.max_commit_gas
+        {
+            return Some(SealReason::CommitGas);
+        }
+        None
+    }
+
+    fn is_expired(since: Option<Instant>, max_age: Duration) -> bool {
+        match since {
+            Some(since) => max_age != Duration::from_secs(0) && since.elapsed() >= max_age,
+            None => false,
+        }
+    }
+}
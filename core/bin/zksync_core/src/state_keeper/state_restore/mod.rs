@@ -1,9 +1,11 @@
 //! This module contains the logic to restore state keeper state from the database.
 
 pub(crate) mod db;
+pub(crate) mod snapshot;
 pub(crate) mod tree_restore;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::snapshot::{TreeSnapshot, TreeSnapshotStore};
 pub(crate) use self::tree_restore::RestoredTree;
@@ -0,0 +1,160 @@
+//! Snapshots of the account tree stored on disk.
+//!
+//! Restoring the tree from the database requires loading the whole committed state, which takes
+//! a long time for large states. Instead, the root hash calculator periodically stores the accounts
+//! of the processed blocks, so on restart the tree is rebuilt from the accounts of the latest
+//! snapshot and only the state diff of the subsequent blocks is loaded from the database.
+//!
+//! The snapshot is not trusted: the tree is rebuilt from its accounts rather than from a stored
+//! tree cache, so every hash is recalculated, and the snapshot is used only if the recalculated
+//! root hash matches the one stored in the database.
+//!
+//! The snapshot directory can be a mount of an object storage, so the snapshots are written into
+//! temporary files first and then renamed, and the broken snapshots are skipped on restore.
+
+// Built-in deps
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+// External uses
+use serde::{Deserialize, Serialize};
+// Workspace uses
+use zksync_config::configs::chain::StateKeeper as StateKeeperConfig;
+use zksync_types::{Account, AccountId, BlockNumber};
+
+const SNAPSHOT_PREFIX: &str = "tree_snapshot_";
+const SNAPSHOT_EXTENSION: &str = "json";
+
+/// Accounts at the end of the block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeSnapshot {
+    pub block: BlockNumber,
+    pub accounts: Vec<(AccountId, Account)>,
+}
+
+impl TreeSnapshot {
+    pub fn new(block: BlockNumber, accounts: Vec<(AccountId, Account)>) -> Self {
+        Self { block, accounts }
+    }
+}
+
+/// Directory with the snapshots of the account tree.
+#[derive(Debug, Clone)]
+pub struct TreeSnapshotStore {
+    dir: PathBuf,
+    /// Number of blocks between two snapshots.
+    interval: u32,
+    snapshots_to_keep: usize,
+}
+
+impl TreeSnapshotStore {
+    pub fn new(dir: impl Into<PathBuf>, interval: u32, snapshots_to_keep: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            interval,
+            snapshots_to_keep: snapshots_to_keep.max(1),
+        }
+    }
+
+    /// Returns `None` if the snapshots are disabled.
+    pub fn from_config(config: &StateKeeperConfig) -> Option<Self> {
+        if config.tree_snapshot_interval == 0 {
+            return None;
+        }
+        // Relative paths are resolved against `$ZKSYNC_HOME`, the absolute ones are used as is.
+        let home = std::env::var("ZKSYNC_HOME").unwrap_or_default();
+        Some(Self::new(
+            Path::new(&home).join(&config.tree_snapshot_dir),
+            config.tree_snapshot_interval,
+            config.tree_snapshots_to_keep,
+        ))
+    }
+
+    /// Checks whether the snapshot of the block should be stored.
+    pub fn is_due(&self, block: BlockNumber) -> bool {
+        *block % self.interval == 0
+    }
+
+    fn snapshot_path(&self, block: BlockNumber) -> PathBuf {
+        self.dir.join(format!(
+            "{}{:010}.{}",
+            SNAPSHOT_PREFIX, *block, SNAPSHOT_EXTENSION
+        ))
+    }
+
+    /// Returns the blocks of the stored snapshots, the latest first.
+    fn stored_blocks(&self) -> io::Result<Vec<BlockNumber>> {
+        let mut blocks = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(SNAPSHOT_EXTENSION) {
+                continue;
+            }
+            let block = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.strip_prefix(SNAPSHOT_PREFIX))
+                .and_then(|block| block.parse().ok());
+            if let Some(block) = block {
+                blocks.push(BlockNumber(block));
+            }
+        }
+        blocks.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(blocks)
+    }
+
+    /// Loads the latest readable snapshot not newer than `max_block`.
+    pub fn load_latest(&self, max_block: BlockNumber) -> Option<TreeSnapshot> {
+        let blocks = match self.stored_blocks() {
+            Ok(blocks) => blocks,
+            Err(err) => {
+                vlog::warn!(
+                    "Failed to list the tree snapshots in {}: {}",
+                    self.dir.display(),
+                    err
+                );
+                return None;
+            }
+        };
+
+        for block in blocks.into_iter().filter(|block| *block <= max_block) {
+            let path = self.snapshot_path(block);
+            let snapshot = fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|data| Ok(serde_json::from_slice::<TreeSnapshot>(&data)?));
+            match snapshot {
+                Ok(snapshot) if snapshot.block == block => return Some(snapshot),
+                Ok(snapshot) => vlog::warn!(
+                    "Tree snapshot {} contains block {}, skipping it",
+                    path.display(),
+                    snapshot.block
+                ),
+                Err(err) => vlog::warn!(
+                    "Failed to read the tree snapshot {}: {}",
+                    path.display(),
+                    err
+                ),
+            }
+        }
+        None
+    }
+
+    /// Stores the snapshot, removing the oldest ones beyond the limit.
+    pub fn store(&self, snapshot: &TreeSnapshot) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.snapshot_path(snapshot.block);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(snapshot)?)?;
+        fs::rename(&tmp_path, &path)?;
+
+        for block in self
+            .stored_blocks()?
+            .into_iter()
+            .skip(self.snapshots_to_keep)
+        {
+            fs::remove_file(self.snapshot_path(block))?;
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
-use zksync_types::{BlockNumber, TokenId};
+use zksync_types::{AccountId, AccountTree, BlockNumber, TokenId};
 
 use super::state_generator::StateGenerator;
-use crate::state_keeper::state_restore::{db::StateRestoreDb, RestoredTree};
+use crate::state_keeper::state_restore::{
+    db::StateRestoreDb, RestoredTree, TreeSnapshot, TreeSnapshotStore,
+};
 
 fn generate_blocks(generator: &mut StateGenerator, blocks: usize, cache_on: Option<BlockNumber>) {
     let accounts: Vec<_> = (0..20).map(|_| generator.create_account()).collect();
@@ -212,3 +214,75 @@ async fn verified_state_wrong_root() {
     let mut restorer = RestoredTree::new(db);
     restorer.restore().await;
 }
+
+fn tree_snapshot(tree: &AccountTree, block: BlockNumber) -> TreeSnapshot {
+    let accounts = tree
+        .items
+        .iter()
+        .map(|(id, account)| (AccountId(*id as u32), account.clone()))
+        .collect();
+    TreeSnapshot::new(block, accounts)
+}
+
+/// Checks that the tree is restored from the latest snapshot matching the database,
+/// and that the unreadable and outdated snapshots are skipped.
+#[tokio::test]
+async fn snapshot_restore() {
+    const N_BLOCKS: usize = 4;
+    const LAST_BLOCK: BlockNumber = BlockNumber(N_BLOCKS as u32);
+
+    let snapshots_dir =
+        std::env::temp_dir().join(format!("zksync_tree_snapshots_{}", std::process::id()));
+    let snapshots = TreeSnapshotStore::new(&snapshots_dir, 1, 3);
+
+    let mut state_generator = StateGenerator::new();
+    let accounts: Vec<_> = (0..20).map(|_| state_generator.create_account()).collect();
+    let mut first_block_tree = None;
+    for block in 1..=N_BLOCKS {
+        for account in &accounts {
+            state_generator.change_account_balance(*account, TokenId(account.0), 100u64);
+        }
+        let block = BlockNumber(block as u32);
+        match *block {
+            1 => first_block_tree = Some(state_generator.tree.clone()),
+            2 => {
+                let snapshot = tree_snapshot(&state_generator.tree, block);
+                snapshots.store(&snapshot).unwrap();
+            }
+            _ => {}
+        }
+        state_generator.seal_block();
+    }
+    // The snapshot of block 3 is broken, so the snapshot of block 2 must be used.
+    std::fs::write(
+        snapshots_dir.join("tree_snapshot_0000000003.json"),
+        "broken snapshot",
+    )
+    .unwrap();
+
+    let mut restorer =
+        RestoredTree::new(state_generator.create_db()).with_snapshots(Some(snapshots.clone()));
+    assert_eq!(restorer.restore().await, LAST_BLOCK);
+    assert_eq!(restorer.tree.root_hash(), state_generator.tree.root_hash());
+
+    // The snapshot of block 4 doesn't match the database, so the tree must be restored from the database.
+    let snapshot = tree_snapshot(&first_block_tree.unwrap(), LAST_BLOCK);
+    snapshots.store(&snapshot).unwrap();
+    let mut restorer =
+        RestoredTree::new(state_generator.create_db()).with_snapshots(Some(snapshots.clone()));
+    assert_eq!(restorer.restore().await, LAST_BLOCK);
+    assert_eq!(restorer.tree.root_hash(), state_generator.tree.root_hash());
+
+    // The corrupted account of the snapshot must not become the restored state.
+    let mut snapshot = tree_snapshot(&state_generator.tree, LAST_BLOCK);
+    *snapshot.accounts[0].1.nonce += 1;
+    snapshots.store(&snapshot).unwrap();
+    let mut restorer =
+        RestoredTree::new(state_generator.create_db()).with_snapshots(Some(snapshots));
+    assert_eq!(restorer.restore().await, LAST_BLOCK);
+    assert_eq!(restorer.tree.root_hash(), state_generator.tree.root_hash());
+    let (id, account) = &snapshot.accounts[0];
+    assert_eq!(restorer.tree.get(**id).unwrap().nonce + 1, account.nonce);
+
+    std::fs::remove_dir_all(snapshots_dir).unwrap();
+}
@@ -3,12 +3,14 @@ use std::collections::HashMap;
 // Workspace uses
 use zksync_types::{Account, AccountId, AccountTree, AccountUpdates, Address, BlockNumber};
 // Local uses
-use super::db::StateRestoreDb;
+use super::{db::StateRestoreDb, snapshot::TreeSnapshotStore};
 
 /// `RestoredTree` is an entity capable of restoring the account tree to the latest observed state
 /// using the database.
 ///
-/// By default, it will try to load the last tree cache and update from there by loading the state difference.
+/// If the snapshots are enabled, it will try to load the latest snapshot of the tree and apply the state difference
+/// of the subsequent blocks to it.
+/// Otherwise, it will try to load the last tree cache and update from there by loading the state difference.
 /// If there is no cache, tree will be recalculated from scratch.
 ///
 /// If the tree root hash will not match the hash from the database, `RestoredTree` will find the block
@@ -16,6 +18,7 @@ use super::db::StateRestoreDb;
 #[derive(Debug)]
 pub(crate) struct RestoredTree<S: StateRestoreDb> {
     pub(crate) storage: S,
+    snapshots: Option<TreeSnapshotStore>,
 
     pub(crate) tree: AccountTree,
     pub(crate) acc_id_by_addr: HashMap<Address, AccountId>,
@@ -28,34 +31,28 @@ where
     pub(crate) fn new(storage: S) -> Self {
         Self {
             storage,
+            snapshots: None,
 
             tree: AccountTree::new(zksync_crypto::params::account_tree_depth()),
             acc_id_by_addr: HashMap::default(),
         }
     }
 
+    /// Makes the tree restore from the latest snapshot, if there is one.
+    pub(crate) fn with_snapshots(mut self, snapshots: Option<TreeSnapshotStore>) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
     /// Restores the tree state.
     /// Returns the block number to which the state was initialized.
     /// This block number is guaranteed to be the last committed block.
     pub(crate) async fn restore(&mut self) -> BlockNumber {
         let last_block = self.storage.load_last_committed_block().await;
 
-        if let Some(cached_block) = self.storage.load_last_cached_block().await {
-            self.init_tree_with_cache(cached_block).await;
-            self.assert_calculated_root(
-                "Root hash from the cached tree doesn't match the root hash from the database",
-                cached_block,
-            )
-            .await;
-
-            // We may not be at the latest point in time.
-            // If so, we need to load the state diff and apply it to the tree.
-            if let Some(diff) = self.storage.load_state_diff(cached_block, last_block).await {
-                self.apply_state_diff(last_block, diff).await;
-            }
-        } else {
-            self.init_tree_without_cache(last_block).await;
-        };
+        if !self.init_tree_with_snapshot(last_block).await {
+            self.init_tree_from_db(last_block).await;
+        }
 
         // Now we *must* have the newest tree state. At this point we should check the root hash
         // and ensure that it corresponds to the previously calculated root hash that is already stored in
@@ -80,6 +77,25 @@ where
         last_block
     }
 
+    async fn init_tree_from_db(&mut self, last_block: BlockNumber) {
+        if let Some(cached_block) = self.storage.load_last_cached_block().await {
+            self.init_tree_with_cache(cached_block).await;
+            self.assert_calculated_root(
+                "Root hash from the cached tree doesn't match the root hash from the database",
+                cached_block,
+            )
+            .await;
+
+            // We may not be at the latest point in time.
+            // If so, we need to load the state diff and apply it to the tree.
+            if let Some(diff) = self.storage.load_state_diff(cached_block, last_block).await {
+                self.apply_state_diff(last_block, diff).await;
+            }
+        } else {
+            self.init_tree_without_cache(last_block).await;
+        }
+    }
+
     async fn init_tree_with_cache(&mut self, cache_block: BlockNumber) {
         let committed_state = self.storage.load_committed_state(cache_block).await;
         let cache = self.storage.load_account_tree_cache(cache_block).await;
@@ -90,6 +106,51 @@ where
         self.tree.set_internals(cache);
     }
 
+    /// Initializes the tree with the latest snapshot and applies the state diff of the subsequent blocks.
+    /// Returns `false` if there is no snapshot matching the database.
+    ///
+    /// The tree is rebuilt from the accounts of the snapshot, so the root hash compared with
+    /// the database is recalculated from every account rather than taken from the snapshot.
+    async fn init_tree_with_snapshot(&mut self, last_block: BlockNumber) -> bool {
+        let snapshot = match self.snapshots.as_ref() {
+            Some(snapshots) => snapshots.load_latest(last_block),
+            None => None,
+        };
+        let snapshot = match snapshot {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+
+        for (id, account) in snapshot.accounts {
+            self.insert_account(id, account);
+        }
+
+        let root_hash_from_db = self.storage.load_block_hash_from_db(snapshot.block).await;
+        if self.tree.root_hash() != root_hash_from_db {
+            vlog::warn!(
+                "Root hash from the tree snapshot of block {} doesn't match the root hash from the database, \
+                 restoring the tree from the database",
+                snapshot.block
+            );
+            self.tree = AccountTree::new(zksync_crypto::params::account_tree_depth());
+            self.acc_id_by_addr = HashMap::new();
+            return false;
+        }
+        vlog::info!(
+            "Account tree is initialized with the snapshot of block {}",
+            snapshot.block
+        );
+
+        if let Some(diff) = self
+            .storage
+            .load_state_diff(snapshot.block, last_block)
+            .await
+        {
+            self.apply_account_updates(diff);
+        }
+        true
+    }
+
     async fn init_tree_without_cache(&mut self, last_block_number: BlockNumber) {
         // If we don't have cache we have no other choice rather than load the latest state and recalculate the tree
         // from scratch.
@@ -169,6 +230,20 @@ where
         }
     }
 
+    /// Applies the account updates to the tree one by one.
+    /// Unlike `apply_state_diff`, it doesn't require loading the committed state.
+    fn apply_account_updates(&mut self, updates: AccountUpdates) {
+        for (id, update) in updates {
+            let account = self.tree.get(*id).cloned();
+            match Account::apply_update(account, update) {
+                Some(account) => self.insert_account(id, account),
+                None => {
+                    self.remove_account(id);
+                }
+            }
+        }
+    }
+
     /// Checks that current root hash matches the hash from the database.
     /// Panics with provided message otherwise.
     async fn assert_calculated_root(&mut self, message: &str, current_block: BlockNumber) {
@@ -6,6 +6,7 @@ use zksync_types::{
 };
 
 use super::utils::*;
+use crate::witness_backlog::WitnessBacklog;
 
 /// Checks if executing a proposed_block with just enough chunks is done correctly
 /// and checks if number of chunks left is correct after each operation.
@@ -236,6 +237,46 @@ async fn fast_withdrawal() {
     tester.assert_sealed().await;
 }
 
+/// Checks that the block is sealed slower when the witness generator lags behind.
+#[tokio::test]
+async fn witness_backlog_throttling() {
+    const THROTTLED_ITERATIONS: usize = 5;
+
+    let mut tester = StateKeeperTester::new(20, 0, 0);
+    let witness_backlog = WitnessBacklog::with_limits(1, 10, THROTTLED_ITERATIONS);
+    witness_backlog.set_depth(1);
+    tester
+        .state_keeper
+        .set_witness_backlog(witness_backlog.clone());
+
+    let withdraw = create_account_and_withdrawal(
+        &mut tester,
+        TokenId(0),
+        AccountId(1),
+        200u32,
+        145u32,
+        Default::default(),
+    );
+    let proposed_block = ProposedBlock {
+        priority_ops: Vec::new(),
+        txs: vec![withdraw.into()],
+    };
+    tester
+        .state_keeper
+        .execute_proposed_block(proposed_block)
+        .await;
+
+    // The block would be sealed right away if the backlog didn't exceed the soft limit.
+    tester.assert_pending().await;
+
+    witness_backlog.set_depth(0);
+    tester
+        .state_keeper
+        .execute_proposed_block(ProposedBlock::new())
+        .await;
+    tester.assert_sealed().await;
+}
+
 /// Checks the following things:
 /// 1. if proposed block is empty, no pending block is yielded from the state keeper.
 /// 2. if there were no successful operations in the block, pending block iteration is not incremented after empty or rejected-only updates.
@@ -565,3 +606,43 @@ async fn transfers_with_different_timestamps() {
         })
         .await;
 }
+
+/// Checks that the independent transactions executed in parallel are included in the proposed order,
+/// and that the transactions not fitting into the sealed block are executed in the next one.
+#[tokio::test]
+async fn parallel_execution() {
+    let mut tester = StateKeeperTester::new(6, 100, 100);
+    tester.state_keeper.set_parallel_execution(4);
+
+    let transfers: Vec<_> = (1..=4)
+        .map(|id| {
+            create_account_and_transfer(&mut tester, TokenId(0), AccountId(id), 200u32, 100u32)
+        })
+        .collect();
+    let proposed_block = ProposedBlock {
+        txs: transfers.iter().cloned().map(SignedTxVariant::Tx).collect(),
+        priority_ops: Vec::new(),
+    };
+    tester
+        .state_keeper
+        .execute_proposed_block(proposed_block)
+        .await;
+
+    tester
+        .assert_sealed_with(|block| {
+            let hashes: Vec<_> = block
+                .block_transactions
+                .iter()
+                .map(|op| op.get_executed_tx().unwrap().signed_tx.hash())
+                .collect();
+            let expected: Vec<_> = transfers[..3].iter().map(SignedZkSyncTx::hash).collect();
+            assert_eq!(hashes, expected);
+        })
+        .await;
+    tester
+        .assert_pending_with(|block| {
+            assert_eq!(block.success_operations.len(), 1);
+            assert_eq!(block.chunks_left, 4);
+        })
+        .await;
+}
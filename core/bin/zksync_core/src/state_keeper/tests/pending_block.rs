@@ -4,7 +4,7 @@ use zksync_types::tx::TimeRange;
 use zksync_types::{AccountId, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx};
 
 use super::utils::*;
-use crate::state_keeper::utils::system_time_timestamp;
+use crate::state_keeper::{sealing::SealReason, utils::system_time_timestamp};
 
 /// Checks if block sealing is done correctly by sealing a block
 /// with 1 priority_op, 1 succeeded tx, 1 failed tx.
@@ -37,7 +37,10 @@ async fn seal_pending_block() {
         .is_included());
 
     let old_updates_len = tester.state_keeper.pending_block.account_updates.len();
-    tester.state_keeper.seal_pending_block().await;
+    tester
+        .state_keeper
+        .seal_pending_block(SealReason::Manual)
+        .await;
 
     assert!(tester.state_keeper.pending_block.failed_txs.is_empty());
     assert!(tester
@@ -1,6 +1,6 @@
 use crate::committer::{AppliedUpdatesRequest, BlockCommitRequest};
+use crate::fixtures::{signed_batch, FixtureAccount};
 use crate::state_keeper::{CommitRequest, ZkSyncStateInitParams, ZkSyncStateKeeper};
-use chrono::Utc;
 use futures::{channel::mpsc, stream::StreamExt};
 use num::BigUint;
 use zksync_crypto::{
@@ -11,9 +11,7 @@ use zksync_crypto::{
 use zksync_mempool::ProposedBlock;
 use zksync_types::block::{IncompleteBlock, PendingBlock};
 use zksync_types::tx::TimeRange;
-use zksync_types::{
-    mempool::SignedTxVariant, mempool::SignedTxsBatch, tx::PackedEthSignature, AccountId, H160, *,
-};
+use zksync_types::{mempool::SignedTxVariant, tx::PackedEthSignature, AccountId, H160, *};
 
 pub struct StateKeeperTester {
     pub state_keeper: ZkSyncStateKeeper,
@@ -85,6 +83,15 @@ impl StateKeeperTester {
         (account, sk)
     }
 
+    /// Adds the account with the signing key set to the state.
+    pub fn add_fixture_account(&mut self, account_id: AccountId) -> FixtureAccount {
+        let account = FixtureAccount::new(account_id, *account_id);
+        self.state_keeper
+            .state
+            .insert_account(account_id, account.account());
+        account
+    }
+
     /// Ensures that `PendingBlock` is sent to the channel.
     pub async fn assert_pending(&mut self) {
         assert!(
@@ -194,26 +201,15 @@ pub fn create_account_and_transfer<B: Into<BigUint>>(
     balance: B,
     transfer_amount: B,
 ) -> SignedZkSyncTx {
-    let (account, sk) = tester.add_account(account_id);
+    let mut account = tester.add_fixture_account(account_id);
     tester.set_balance(account_id, token_id, balance);
 
-    let transfer = Transfer::new_signed(
-        account_id,
-        account.address,
-        account.address,
-        token_id,
-        transfer_amount.into(),
-        BigUint::from(1u32),
-        account.nonce,
-        Default::default(),
-        &sk,
-    )
-    .unwrap();
-    SignedZkSyncTx {
-        tx: ZkSyncTx::Transfer(Box::new(transfer)),
-        eth_sign_data: None,
-        created_at: Utc::now(),
-    }
+    let to = account.address;
+    account
+        .tx()
+        .token(token_id)
+        .fee(1u32)
+        .transfer(to, transfer_amount)
 }
 
 pub fn create_account_and_withdrawal<B: Into<BigUint>>(
@@ -264,28 +260,19 @@ pub fn create_account_and_withdrawal_impl<B: Into<BigUint>>(
     fast: bool,
     time_range: TimeRange,
 ) -> SignedZkSyncTx {
-    let (account, sk) = tester.add_account(account_id);
+    let mut account = tester.add_fixture_account(account_id);
     tester.set_balance(account_id, token_id, balance);
 
-    let mut withdraw = Withdraw::new_signed(
-        account_id,
-        account.address,
-        account.address,
-        token_id,
-        withdraw_amount.into(),
-        BigUint::from(1u32),
-        account.nonce,
-        time_range,
-        &sk,
-    )
-    .unwrap();
-
-    withdraw.fast = fast;
-
-    SignedZkSyncTx {
-        tx: ZkSyncTx::Withdraw(Box::new(withdraw)),
-        eth_sign_data: None,
-        created_at: Utc::now(),
+    let to = account.address;
+    let builder = account
+        .tx()
+        .token(token_id)
+        .fee(1u32)
+        .time_range(time_range);
+    if fast {
+        builder.fast_withdraw(to, withdraw_amount)
+    } else {
+        builder.withdraw(to, withdraw_amount)
     }
 }
 
@@ -320,16 +307,25 @@ pub async fn apply_single_transfer(tester: &mut StateKeeperTester) {
 }
 
 pub async fn apply_batch_with_two_transfers(tester: &mut StateKeeperTester) {
-    let first_transfer =
-        create_account_and_transfer(tester, TokenId(0), AccountId(1), 200u32, 100u32);
-    let second_transfer =
-        create_account_and_transfer(tester, TokenId(0), AccountId(2), 200u32, 100u32);
+    let mut first_account = tester.add_fixture_account(AccountId(1));
+    tester.set_balance(AccountId(1), TokenId(0), 200u32);
+    let mut second_account = tester.add_fixture_account(AccountId(2));
+    tester.set_balance(AccountId(2), TokenId(0), 200u32);
+
+    let first_transfer = first_account
+        .tx()
+        .fee(1u32)
+        .transfer(second_account.address, 100u32);
+    let second_transfer = second_account
+        .tx()
+        .fee(1u32)
+        .transfer(first_account.address, 100u32);
     let proposed_block = ProposedBlock {
-        txs: vec![SignedTxVariant::Batch(SignedTxsBatch {
-            txs: vec![first_transfer, second_transfer],
-            batch_id: 1,
-            eth_signatures: Vec::new(),
-        })],
+        txs: vec![SignedTxVariant::Batch(signed_batch(
+            1,
+            vec![first_transfer, second_transfer],
+            &[&first_account, &second_account],
+        ))],
         priority_ops: Vec::new(),
     };
     tester
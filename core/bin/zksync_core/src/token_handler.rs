@@ -3,7 +3,14 @@
 //!
 //! To set the name and the decimals parameter for the token, a match is searched for with the
 //! token list (which is taken from the environment). If the token address is not found in the
-//! trusted token list, then the default values are used (name = "ERC20-{id}", decimals = 18).
+//! trusted token list, then the `symbol` and `decimals` methods of the token contract are called.
+//! If the contract doesn't provide them, then the default values are used
+//! (name = "ERC20-{id}", decimals = 18).
+//!
+//! The symbols provided by the contracts are not trusted: anyone can deploy a token named after
+//! an existing one. Since the tokens are resolved by the symbol case-insensitively, such a symbol
+//! is replaced with the default one if it matches the symbol of another token regardless of
+//! the case, or if it's not a plain ASCII one.
 
 // Built-in deps
 use std::collections::HashMap;
@@ -23,10 +30,28 @@ use zksync_types::{
 };
 // Local uses
 use crate::eth_watch::EthWatchRequest;
-use web3::contract::Options;
-use zksync_contracts::erc20_contract;
+use web3::contract::{tokens::Detokenize, Options};
+use zksync_contracts::{erc20_contract, erc20_metadata_contract};
 use zksync_eth_client::EthereumGateway;
 
+/// Symbols of the greater length are considered bogus and replaced with the default one.
+const MAX_TOKEN_SYMBOL_LEN: usize = 32;
+/// Prefix of the default symbols, it's reserved for them.
+const DEFAULT_SYMBOL_PREFIX: &str = "ERC20-";
+
+/// Whether the symbol provided by the token contract can be used as is.
+/// The look-alike characters and the default symbols of other tokens are rejected.
+fn is_valid_untrusted_symbol(symbol: &str) -> bool {
+    !symbol.is_empty()
+        && symbol.len() <= MAX_TOKEN_SYMBOL_LEN
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        && !symbol
+            .to_ascii_uppercase()
+            .starts_with(DEFAULT_SYMBOL_PREFIX)
+}
+
 struct TokenHandler {
     connection_pool: ConnectionPool,
     poll_interval: std::time::Duration,
@@ -94,6 +119,44 @@ impl TokenHandler {
             .is_ok()
     }
 
+    /// Loads the symbol and the decimals from the token contract.
+    /// Returns `None` if the contract doesn't implement the optional ERC20 metadata methods.
+    async fn load_token_metadata(&self, address: Address) -> Option<(String, u8)> {
+        let symbol: String = self.call_metadata_method("symbol", address).await?;
+        let decimals: U256 = self.call_metadata_method("decimals", address).await?;
+        if decimals > U256::from(u8::MAX) {
+            return None;
+        }
+
+        let symbol = symbol.trim();
+        if !is_valid_untrusted_symbol(symbol) {
+            vlog::warn!("Token {:?} has an invalid symbol {:?}", address, symbol);
+            return None;
+        }
+        Some((symbol.to_owned(), decimals.as_u32() as u8))
+    }
+
+    async fn call_metadata_method<R>(&self, func: &str, address: Address) -> Option<R>
+    where
+        R: Detokenize + Unpin,
+    {
+        self.eth_client
+            .call_contract_function(
+                func,
+                (),
+                None,
+                Options::default(),
+                None,
+                address,
+                erc20_metadata_contract(),
+            )
+            .await
+            .map_err(|err| {
+                vlog::debug!("Token {:?} doesn't provide `{}`: {}", address, func, err);
+            })
+            .ok()
+    }
+
     async fn save_new_tokens(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -110,9 +173,9 @@ impl TokenHandler {
                 continue;
             }
 
-            // Find a token in the list of trusted tokens
+            // Find a token in the list of trusted tokens, load its metadata from the contract
             // or use default values (name = "ERC20-{id}", decimals = 18).
-            let default_symbol = format!("ERC20-{}", token_event.id);
+            let default_symbol = format!("{}{}", DEFAULT_SYMBOL_PREFIX, token_event.id);
             let default_decimals = 18;
 
             let is_erc20 = self.is_contract_erc20(token_event.address).await;
@@ -122,36 +185,51 @@ impl TokenHandler {
                 TokenKind::None
             };
 
-            let token_from_list = {
-                let token_info = self.token_list.get(&token_event.address).cloned();
-
-                if let Some(token_info) = token_info {
-                    Some(Token::new(
-                        token_event.id,
-                        token_info.address,
-                        &token_info.symbol,
-                        token_info.decimals,
-                        token_kind,
-                    ))
-                } else {
-                    None
+            let token_info = match self.token_list.get(&token_event.address) {
+                Some(token_info) => Some((token_info.symbol.clone(), token_info.decimals)),
+                None if is_erc20 => self.load_token_metadata(token_event.address).await,
+                None => None,
+            };
+            let token_info = match token_info {
+                Some((symbol, decimals)) if !self.token_list.contains_key(&token_event.address) => {
+                    let taken = !token_schema.get_tokens_by_symbol(&symbol).await?.is_empty();
+                    if taken {
+                        vlog::warn!(
+                            "Symbol {:?} of the token {:?} is already taken, using the default one",
+                            symbol,
+                            token_event.address
+                        );
+                        Some((default_symbol.clone(), decimals))
+                    } else {
+                        Some((symbol, decimals))
+                    }
                 }
+                token_info => token_info,
             };
+            let known_token = token_info.map(|(symbol, decimals)| {
+                Token::new(
+                    token_event.id,
+                    token_event.address,
+                    &symbol,
+                    decimals,
+                    token_kind,
+                )
+            });
 
-            let token = match token_from_list {
-                Some(token_from_list) => {
-                    let try_insert_token = token_schema.store_token(token_from_list.clone()).await;
+            let token = match known_token {
+                Some(known_token) => {
+                    let try_insert_token = token_schema.store_token(known_token.clone()).await;
 
                     match try_insert_token {
-                        Ok(..) => token_from_list,
+                        Ok(..) => known_token,
                         Err(StoreTokenError::TokenAlreadyExistsError(..)) => {
                             // If a token with such parameters already exists in the database
                             // then try insert token with other symbol.
                             let token = Token::new(
-                                token_from_list.id,
-                                token_from_list.address,
+                                known_token.id,
+                                known_token.address,
                                 &default_symbol,
-                                token_from_list.decimals,
+                                known_token.decimals,
                                 token_kind,
                             );
                             let try_insert_token = token_schema.store_token(token.clone()).await;
@@ -190,6 +268,9 @@ impl TokenHandler {
                 }
             };
 
+            // The API servers may have cached the token as unknown. The notification
+            // is delivered once the transaction is committed.
+            token_schema.notify_token_updated(token.id).await?;
             new_tokens.push(token);
         }
 
@@ -252,3 +333,23 @@ pub fn run_token_handler(
         token_handler.run().await
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untrusted_symbols() {
+        assert!(is_valid_untrusted_symbol("USDC"));
+        assert!(is_valid_untrusted_symbol("yv.DAI-2_x"));
+        assert!(!is_valid_untrusted_symbol(""));
+        assert!(!is_valid_untrusted_symbol(
+            &"A".repeat(MAX_TOKEN_SYMBOL_LEN + 1)
+        ));
+        // Cyrillic "С" looks the same as the Latin one.
+        assert!(!is_valid_untrusted_symbol("USDС"));
+        assert!(!is_valid_untrusted_symbol("US DC"));
+        assert!(!is_valid_untrusted_symbol("ERC20-5"));
+        assert!(!is_valid_untrusted_symbol("erc20-5"));
+    }
+}
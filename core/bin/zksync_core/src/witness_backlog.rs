@@ -0,0 +1,139 @@
+//! Backpressure from the witness generator to the block production.
+//!
+//! Witness generator runs as a separate application, so the only way to observe its progress
+//! is the database: the monitor periodically loads the number of the saved blocks without a witness
+//! and shares it with the state keeper, which throttles the block production once the backlog
+//! exceeds the configured limits. Otherwise the blocks would keep piling up in the database
+//! together with the data required to generate their witnesses.
+
+// Built-in uses
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::configs::chain::StateKeeper as StateKeeperConfig;
+use zksync_storage::ConnectionPool;
+
+/// The way the block production is throttled depending on the witness backlog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockProductionThrottle {
+    /// The witness generator keeps up with the block production.
+    None,
+    /// The blocks are sealed after more miniblock iterations.
+    SlowSealing,
+    /// The blocks are sealed after more miniblock iterations and limited to the smallest size.
+    SmallBlocks,
+}
+
+/// Depth of the witness generator backlog shared between the monitor and the state keeper.
+#[derive(Debug, Clone)]
+pub struct WitnessBacklog {
+    depth: Arc<AtomicU64>,
+    soft_limit: u64,
+    hard_limit: u64,
+    throttled_miniblock_iterations: usize,
+}
+
+impl WitnessBacklog {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self::with_limits(
+            config.witness_backlog_soft_limit,
+            config.witness_backlog_hard_limit,
+            config.throttled_miniblock_iterations as usize,
+        )
+    }
+
+    pub fn with_limits(
+        soft_limit: u64,
+        hard_limit: u64,
+        throttled_miniblock_iterations: usize,
+    ) -> Self {
+        Self {
+            depth: Arc::new(AtomicU64::new(0)),
+            soft_limit,
+            hard_limit,
+            throttled_miniblock_iterations,
+        }
+    }
+
+    /// Returns the number of the saved blocks without a witness reported by the last check.
+    pub fn depth(&self) -> u64 {
+        // `Relaxed` is enough as the value is only used as a hint for the block production.
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    pub fn set_depth(&self, depth: u64) {
+        self.depth.store(depth, Ordering::Relaxed);
+    }
+
+    pub fn throttle(&self) -> BlockProductionThrottle {
+        let depth = self.depth();
+        if depth >= self.hard_limit {
+            BlockProductionThrottle::SmallBlocks
+        } else if depth >= self.soft_limit {
+            BlockProductionThrottle::SlowSealing
+        } else {
+            BlockProductionThrottle::None
+        }
+    }
+
+    /// Maximum amount of miniblock iterations before sealing the block while the block production is throttled.
+    pub fn throttled_miniblock_iterations(&self) -> usize {
+        self.throttled_miniblock_iterations
+    }
+}
+
+async fn load_witness_backlog(db_pool: &ConnectionPool) -> anyhow::Result<u32> {
+    let mut storage = db_pool.access_storage().await?;
+    storage.prover_schema().witness_backlog().await
+}
+
+#[must_use]
+pub fn run_witness_backlog_monitor(
+    db_pool: ConnectionPool,
+    witness_backlog: WitnessBacklog,
+    config: &StateKeeperConfig,
+) -> JoinHandle<()> {
+    let mut timer = time::interval(config.witness_backlog_check_interval());
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            match load_witness_backlog(&db_pool).await {
+                Ok(depth) => {
+                    witness_backlog.set_depth(depth as u64);
+                    metrics::gauge!("witness_backlog.depth", depth as f64);
+                }
+                Err(err) => {
+                    // The last known depth is kept, the state keeper doesn't depend on the database.
+                    vlog::warn!("Can't load the witness backlog: {}", err);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_thresholds() {
+        let backlog = WitnessBacklog::with_limits(10, 20, 40);
+        let shared = backlog.clone();
+        assert_eq!(backlog.throttle(), BlockProductionThrottle::None);
+
+        shared.set_depth(9);
+        assert_eq!(backlog.throttle(), BlockProductionThrottle::None);
+        shared.set_depth(10);
+        assert_eq!(backlog.throttle(), BlockProductionThrottle::SlowSealing);
+        shared.set_depth(20);
+        assert_eq!(backlog.throttle(), BlockProductionThrottle::SmallBlocks);
+        shared.set_depth(0);
+        assert_eq!(backlog.throttle(), BlockProductionThrottle::None);
+    }
+}
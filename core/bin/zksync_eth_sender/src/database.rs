@@ -2,19 +2,22 @@
 use std::collections::VecDeque;
 use std::str::FromStr;
 // External uses
+use anyhow::format_err;
 use num::BigUint;
-use zksync_basic_types::{H256, U256};
+use zksync_basic_types::{Address, BlockNumber, H256, U256};
 // Workspace uses
 use zksync_storage::{ConnectionPool, StorageProcessor};
-use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
+use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse, OperatorKeyUsage};
 // Local uses
 use super::transactions::ETHStats;
-use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
+use zksync_types::aggregated_operations::{
+    AggregatedActionType, AggregatedOperation, BlocksExecuteOperation,
+};
 use zksync_types::block::Block;
 
 /// Abstract database access trait, optimized for the needs of `ETHSender`.
 #[async_trait::async_trait]
-pub(super) trait DatabaseInterface {
+pub(super) trait DatabaseInterface: Send + Sync {
     /// Returns connection to the database.
     async fn acquire_connection(&self) -> anyhow::Result<StorageProcessor<'_>>;
 
@@ -48,6 +51,8 @@ pub(super) trait DatabaseInterface {
     ) -> anyhow::Result<()>;
 
     /// Saves a new unconfirmed operation to the database.
+    /// The nonce is assigned from the ones of the `sender` key, `None` for the main operator key.
+    #[allow(clippy::too_many_arguments)]
     async fn save_new_eth_tx(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -55,9 +60,36 @@ pub(super) trait DatabaseInterface {
         op: Option<(i64, AggregatedOperation)>,
         deadline_block: i64,
         used_gas_price: U256,
+        used_priority_fee: Option<U256>,
+        sender: Option<Address>,
         raw_tx: Vec<u8>,
     ) -> anyhow::Result<InsertedOperationResponse>;
 
+    /// Initializes the nonce of the additional operator key, unless it's already stored.
+    async fn initialize_operator_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        address: Address,
+        nonce: i64,
+    ) -> anyhow::Result<()>;
+
+    /// Loads the nonce to be used by the next transaction of the `sender` key,
+    /// `None` for the main operator key.
+    async fn load_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+    ) -> anyhow::Result<i64>;
+
+    /// Overrides the nonce to be used by the next transaction of the `sender` key,
+    /// `None` for the main operator key.
+    async fn set_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+        nonce: i64,
+    ) -> anyhow::Result<()>;
+
     /// Adds a tx hash entry associated with some Ethereum operation to the database.
     async fn add_hash_entry(
         &self,
@@ -73,6 +105,7 @@ pub(super) trait DatabaseInterface {
         eth_op_id: EthOpId,
         new_deadline_block: i64,
         new_gas_value: U256,
+        new_priority_fee: Option<U256>,
     ) -> anyhow::Result<()>;
 
     /// Marks an operation as completed in the database.
@@ -105,6 +138,38 @@ pub(super) trait DatabaseInterface {
         connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
     ) -> anyhow::Result<bool>;
+
+    /// Stores the status of the withdrawals processed by the execute operation.
+    async fn schedule_withdrawals(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op_id: i64,
+        op: &BlocksExecuteOperation,
+    ) -> anyhow::Result<()>;
+
+    /// Checks whether the withdrawals of the execute operation are completed by its transaction.
+    async fn completes_withdrawals(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op_id: i64,
+    ) -> anyhow::Result<bool>;
+
+    /// Appends the entry to the operator key audit log.
+    async fn record_operator_key_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        usage: &OperatorKeyUsage,
+    ) -> anyhow::Result<()>;
+
+    /// Loads the current leadership term and locks it until the end of the transaction.
+    async fn lock_leader_term(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<i64>;
+
+    /// Loads the last correct block of the block revert in progress, if any,
+    /// and locks it until the end of the transaction.
+    async fn lock_pending_block_revert(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<BlockNumber>>;
 }
 
 /// The actual database wrapper.
@@ -184,6 +249,8 @@ impl DatabaseInterface for Database {
         op: Option<(i64, AggregatedOperation)>,
         deadline_block: i64,
         used_gas_price: U256,
+        used_priority_fee: Option<U256>,
+        sender: Option<Address>,
         raw_tx: Vec<u8>,
     ) -> anyhow::Result<InsertedOperationResponse> {
         let result = connection
@@ -193,6 +260,8 @@ impl DatabaseInterface for Database {
                 op,
                 deadline_block,
                 BigUint::from_str(&used_gas_price.to_string()).unwrap(),
+                used_priority_fee.map(|fee| BigUint::from_str(&fee.to_string()).unwrap()),
+                sender,
                 raw_tx,
             )
             .await?;
@@ -200,6 +269,58 @@ impl DatabaseInterface for Database {
         Ok(result)
     }
 
+    async fn initialize_operator_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        address: Address,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        connection
+            .ethereum_schema()
+            .initialize_operator_nonce(address, nonce)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+    ) -> anyhow::Result<i64> {
+        let nonce = match sender {
+            Some(address) => connection
+                .ethereum_schema()
+                .load_operator_nonce(address)
+                .await?
+                .ok_or_else(|| {
+                    format_err!("Nonce of the operator key {:?} is not initialized", address)
+                })?,
+            None => connection.ethereum_schema().load_nonce().await?,
+        };
+
+        Ok(nonce)
+    }
+
+    async fn set_nonce(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        match sender {
+            Some(address) => {
+                connection
+                    .ethereum_schema()
+                    .set_operator_nonce(address, nonce)
+                    .await?
+            }
+            None => connection.ethereum_schema().set_nonce(nonce).await?,
+        }
+
+        Ok(())
+    }
+
     async fn add_hash_entry(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -218,6 +339,7 @@ impl DatabaseInterface for Database {
         eth_op_id: EthOpId,
         new_deadline_block: i64,
         new_gas_value: U256,
+        new_priority_fee: Option<U256>,
     ) -> anyhow::Result<()> {
         Ok(connection
             .ethereum_schema()
@@ -225,6 +347,7 @@ impl DatabaseInterface for Database {
                 eth_op_id,
                 new_deadline_block,
                 BigUint::from_str(&new_gas_value.to_string()).unwrap(),
+                new_priority_fee.map(|fee| BigUint::from_str(&fee.to_string()).unwrap()),
             )
             .await?)
     }
@@ -251,6 +374,70 @@ impl DatabaseInterface for Database {
         Ok(confirmed)
     }
 
+    async fn schedule_withdrawals(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op_id: i64,
+        op: &BlocksExecuteOperation,
+    ) -> anyhow::Result<()> {
+        connection
+            .chain()
+            .withdrawals_schema()
+            .schedule_withdrawals(op_id, op)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn completes_withdrawals(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op_id: i64,
+    ) -> anyhow::Result<bool> {
+        let completes = connection
+            .chain()
+            .withdrawals_schema()
+            .completes_withdrawals(op_id)
+            .await?;
+
+        Ok(completes)
+    }
+
+    async fn record_operator_key_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        usage: &OperatorKeyUsage,
+    ) -> anyhow::Result<()> {
+        connection
+            .ethereum_schema()
+            .record_operator_key_usage(usage)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn lock_leader_term(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<i64> {
+        let term = connection
+            .leader_election_schema()
+            .lock_current_term()
+            .await?;
+
+        Ok(term)
+    }
+
+    async fn lock_pending_block_revert(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<BlockNumber>> {
+        let last_correct_block = connection
+            .chain()
+            .block_schema()
+            .lock_pending_revert()
+            .await?;
+
+        Ok(last_correct_block)
+    }
+
     async fn confirm_operation(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -288,7 +475,7 @@ impl DatabaseInterface for Database {
                     )
                     .await?;
             }
-            Some((_, AggregatedOperation::ExecuteBlocks(op))) => {
+            Some((op_id, AggregatedOperation::ExecuteBlocks(op))) => {
                 let (first_block, last_block) = op.block_range();
                 self.set_metrics(&op.blocks, "L1_execute".to_string()).await;
                 for block in &op.blocks {
@@ -308,6 +495,11 @@ impl DatabaseInterface for Database {
                         AggregatedActionType::ExecuteBlocks,
                     )
                     .await?;
+                transaction
+                    .chain()
+                    .withdrawals_schema()
+                    .confirm_withdrawals(*op_id, *hash)
+                    .await?;
             }
             _ => {}
         }
@@ -0,0 +1,217 @@
+//! Fees of the EIP-1559 transactions.
+//!
+//! The priority fee is the median over the recent blocks of the configured percentile of
+//! the priority fees paid in them. The max fee per gas covers the base fee of the next block
+//! scaled by the configured multiplier on top of the priority fee, so the transaction stays
+//! includable while the base fee grows. Both fees never exceed the configured cap.
+//!
+//! Nodes accept the replacement of the stuck transaction only if it raises both fees, so
+//! the replacement raises them by the escalation percentage, or up to the current market fees
+//! if those are higher.
+
+// External uses
+use anyhow::format_err;
+use web3::types::{BlockNumber, FeeHistory, U256};
+// Workspace uses
+use zksync_config::configs::eth_sender::FeeMarket as FeeMarketConfig;
+use zksync_eth_client::EthereumGateway;
+
+/// Fees of the EIP-1559 transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Eip1559Fees {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct FeeMarket {
+    config: FeeMarketConfig,
+}
+
+impl FeeMarket {
+    pub fn new(config: &FeeMarketConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// Whether the EIP-1559 transactions are sent instead of the legacy ones.
+    pub fn is_enabled(&self) -> bool {
+        self.config.eip1559_enabled
+    }
+
+    /// Returns the max fee per gas the fees are capped by.
+    pub fn max_fee_per_gas_cap(&self) -> U256 {
+        self.config.max_fee_per_gas_cap.into()
+    }
+
+    /// Estimates the fees of the new transaction from the fee history of the recent blocks.
+    pub async fn estimate_fees(&self, ethereum: &EthereumGateway) -> anyhow::Result<Eip1559Fees> {
+        let history = self.load_history(ethereum).await?;
+        self.fees_from_history(&history)
+    }
+
+    /// Estimates the gas price of the legacy transaction from the fee history of the recent
+    /// blocks: the base fee of the next block plus the priority fee, capped as the max fee.
+    pub async fn estimate_gas_price(&self, ethereum: &EthereumGateway) -> anyhow::Result<U256> {
+        let history = self.load_history(ethereum).await?;
+        let (base_fee, priority_fee) = self.market_fees(&history)?;
+        Ok((base_fee + priority_fee).min(self.max_fee_per_gas_cap()))
+    }
+
+    async fn load_history(&self, ethereum: &EthereumGateway) -> anyhow::Result<FeeHistory> {
+        ethereum
+            .fee_history(
+                self.config.fee_history_blocks,
+                BlockNumber::Latest,
+                vec![self.config.priority_fee_percentile],
+            )
+            .await
+    }
+
+    /// Calculates the fees of the transaction replacing the stuck one.
+    pub fn escalate(&self, old_fees: Eip1559Fees, current_fees: Eip1559Fees) -> Eip1559Fees {
+        let escalation_percent = U256::from(self.config.escalation_percent);
+        let raise = |fee: U256| fee + fee * escalation_percent / U256::from(100);
+        self.capped(Eip1559Fees {
+            max_fee_per_gas: raise(old_fees.max_fee_per_gas).max(current_fees.max_fee_per_gas),
+            max_priority_fee_per_gas: raise(old_fees.max_priority_fee_per_gas)
+                .max(current_fees.max_priority_fee_per_gas),
+        })
+    }
+
+    fn fees_from_history(&self, history: &FeeHistory) -> anyhow::Result<Eip1559Fees> {
+        let (base_fee, priority_fee) = self.market_fees(history)?;
+
+        // Since `U256` cannot be multiplied by `f64`, the multiplier is applied as
+        // a percentage, which is precise enough for the values like `1.5` or `2.0`.
+        let multiplier = U256::from((self.config.base_fee_multiplier * 100.0).round() as u64);
+        let max_fee_per_gas = base_fee * multiplier / U256::from(100) + priority_fee;
+
+        Ok(self.capped(Eip1559Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas: priority_fee,
+        }))
+    }
+
+    /// Returns the base fee of the next block and the priority fee.
+    fn market_fees(&self, history: &FeeHistory) -> anyhow::Result<(U256, U256)> {
+        // The history contains one more base fee than the blocks, it's the base fee
+        // of the next block.
+        let base_fee = *history
+            .base_fee_per_gas
+            .last()
+            .ok_or_else(|| format_err!("Fee history contains no base fees"))?;
+
+        let mut priority_fees: Vec<U256> = history
+            .reward
+            .iter()
+            .flatten()
+            .filter_map(|rewards| rewards.first().copied())
+            .collect();
+        priority_fees.sort();
+        let priority_fee = priority_fees
+            .get(priority_fees.len() / 2)
+            .copied()
+            .unwrap_or_default()
+            .max(self.config.min_priority_fee.into());
+
+        Ok((base_fee, priority_fee))
+    }
+
+    /// Cuts the max fee per gas by the cap. The priority fee is a part of the max fee,
+    /// so it never exceeds the latter.
+    fn capped(&self, fees: Eip1559Fees) -> Eip1559Fees {
+        let max_fee_per_gas = fees.max_fee_per_gas.min(self.max_fee_per_gas_cap());
+        Eip1559Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas.min(max_fee_per_gas),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_market() -> FeeMarket {
+        FeeMarket::new(&FeeMarketConfig {
+            eip1559_enabled: true,
+            fee_history_blocks: 3,
+            priority_fee_percentile: 50.0,
+            base_fee_multiplier: 2.0,
+            min_priority_fee: 2,
+            max_fee_per_gas_cap: 1000,
+            escalation_percent: 15,
+        })
+    }
+
+    fn history(base_fees: Vec<u64>, priority_fees: Vec<u64>) -> FeeHistory {
+        FeeHistory {
+            oldest_block: BlockNumber::Number(1.into()),
+            gas_used_ratio: vec![0.5; priority_fees.len()],
+            base_fee_per_gas: base_fees.into_iter().map(U256::from).collect(),
+            reward: Some(
+                priority_fees
+                    .into_iter()
+                    .map(|fee| vec![U256::from(fee)])
+                    .collect(),
+            ),
+        }
+    }
+
+    fn fees(max_fee_per_gas: u64, max_priority_fee_per_gas: u64) -> Eip1559Fees {
+        Eip1559Fees {
+            max_fee_per_gas: max_fee_per_gas.into(),
+            max_priority_fee_per_gas: max_priority_fee_per_gas.into(),
+        }
+    }
+
+    #[test]
+    fn fees_from_history() {
+        let fee_market = fee_market();
+
+        // The latest base fee is doubled, the median priority fee is added.
+        let estimated = fee_market
+            .fees_from_history(&history(vec![90, 100, 110, 120], vec![30, 10, 20]))
+            .unwrap();
+        assert_eq!(estimated, fees(260, 20));
+
+        // The priority fee is raised to the minimum.
+        let estimated = fee_market
+            .fees_from_history(&history(vec![100, 100], vec![0]))
+            .unwrap();
+        assert_eq!(estimated, fees(202, 2));
+
+        // Both fees are capped.
+        let estimated = fee_market
+            .fees_from_history(&history(vec![100, 600], vec![2000]))
+            .unwrap();
+        assert_eq!(estimated, fees(1000, 1000));
+
+        assert!(fee_market
+            .fees_from_history(&history(vec![], vec![]))
+            .is_err());
+    }
+
+    #[test]
+    fn escalation() {
+        let fee_market = fee_market();
+
+        // Both fees are raised by 15%.
+        assert_eq!(
+            fee_market.escalate(fees(200, 20), fees(100, 10)),
+            fees(230, 23)
+        );
+        // The market fees are used once they are higher.
+        assert_eq!(
+            fee_market.escalate(fees(200, 20), fees(300, 10)),
+            fees(300, 23)
+        );
+        // The escalated fees are capped.
+        assert_eq!(
+            fee_market.escalate(fees(900, 20), fees(100, 10)),
+            fees(1000, 23)
+        );
+    }
+}
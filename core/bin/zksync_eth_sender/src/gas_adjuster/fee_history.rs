@@ -0,0 +1,48 @@
+// External deps
+use zksync_basic_types::U256;
+use zksync_config::configs::eth_sender::GasPriceStrategy;
+use zksync_eth_client::EthereumGateway;
+// Local deps
+use super::{scale_up, GasAdjuster};
+use crate::{database::DatabaseInterface, fee_market::FeeMarket};
+
+/// Gas adjuster pricing the transactions from the fee history of the recent blocks:
+/// the base fee of the next block plus the percentile of the recent priority fees.
+///
+/// Unlike the price suggested by the node, it follows the base fee without a delay,
+/// and doesn't depend on the previously sent transactions.
+#[derive(Debug)]
+pub(super) struct FeeHistoryGasAdjuster {
+    fee_market: FeeMarket,
+}
+
+impl FeeHistoryGasAdjuster {
+    pub fn new(fee_market: FeeMarket) -> Self {
+        Self { fee_market }
+    }
+}
+
+#[async_trait::async_trait]
+impl<DB: DatabaseInterface> GasAdjuster<DB> for FeeHistoryGasAdjuster {
+    fn strategy(&self) -> GasPriceStrategy {
+        GasPriceStrategy::FeeHistory
+    }
+
+    async fn get_gas_price(
+        &mut self,
+        ethereum: &EthereumGateway,
+        old_tx_gas_price: Option<U256>,
+    ) -> anyhow::Result<U256> {
+        let market_price = self.fee_market.estimate_gas_price(ethereum).await?;
+        let price = match old_tx_gas_price {
+            Some(old_price) => scale_up(old_price, market_price),
+            None => market_price,
+        };
+
+        let cap = self.fee_market.max_fee_per_gas_cap();
+        if price >= cap {
+            vlog::warn!("Maximum possible gas price will be used: <{}>", cap);
+        }
+        Ok(price.min(cap))
+    }
+}
@@ -0,0 +1,46 @@
+// External deps
+use zksync_basic_types::U256;
+use zksync_config::configs::eth_sender::GasPriceStrategy;
+use zksync_eth_client::EthereumGateway;
+// Local deps
+use super::{scale_up, GasAdjuster};
+use crate::database::DatabaseInterface;
+
+/// Gas adjuster using the price suggested by the Ethereum node, limited by the fixed cap.
+///
+/// Unlike the node oracle strategy, the limit doesn't follow the recently used prices,
+/// which suits the networks with the predictable gas prices, e.g. the testnets.
+#[derive(Debug)]
+pub(super) struct FixedCapGasAdjuster {
+    cap: U256,
+}
+
+impl FixedCapGasAdjuster {
+    pub fn new(cap: U256) -> Self {
+        Self { cap }
+    }
+}
+
+#[async_trait::async_trait]
+impl<DB: DatabaseInterface> GasAdjuster<DB> for FixedCapGasAdjuster {
+    fn strategy(&self) -> GasPriceStrategy {
+        GasPriceStrategy::FixedCap
+    }
+
+    async fn get_gas_price(
+        &mut self,
+        ethereum: &EthereumGateway,
+        old_tx_gas_price: Option<U256>,
+    ) -> anyhow::Result<U256> {
+        let network_price = ethereum.get_gas_price().await?;
+        let price = match old_tx_gas_price {
+            Some(old_price) => scale_up(old_price, network_price),
+            None => network_price,
+        };
+
+        if price >= self.cap {
+            vlog::warn!("Maximum possible gas price will be used: <{}>", self.cap);
+        }
+        Ok(price.min(self.cap))
+    }
+}
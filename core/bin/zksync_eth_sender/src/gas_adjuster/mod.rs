@@ -1,11 +1,15 @@
 // Built-in deps
-use std::{collections::VecDeque, marker::PhantomData, time::Instant};
+use std::{collections::VecDeque, time::Instant};
 // External deps
 use zksync_basic_types::U256;
+use zksync_config::configs::eth_sender::{ETHSenderConfig, GasPriceStrategy};
 use zksync_eth_client::EthereumGateway;
 // Local deps
-use crate::database::DatabaseInterface;
+use self::{fee_history::FeeHistoryGasAdjuster, fixed_cap::FixedCapGasAdjuster};
+use crate::{database::DatabaseInterface, fee_market::FeeMarket};
 
+mod fee_history;
+mod fixed_cap;
 mod parameters;
 
 #[cfg(test)]
@@ -14,6 +18,53 @@ mod tests;
 /// Gas adjuster is an entity capable of scaling the gas price for
 /// all the Ethereum transactions.
 ///
+/// Every implementation is a strategy of choosing the gas price, the one to use is
+/// selected in the config. The new transactions are sent with the price estimated by the
+/// strategy, and the stuck ones are replaced with the price raised by at least 15%, since
+/// the nodes don't accept the replacements raising it by less than 10%.
+#[async_trait::async_trait]
+pub(super) trait GasAdjuster<DB: DatabaseInterface>: Send + Sync {
+    /// Strategy implemented by the gas adjuster.
+    fn strategy(&self) -> GasPriceStrategy;
+
+    /// Returns the gas price for the new transaction, or for the replacement
+    /// of the stuck one if `old_tx_gas_price` is provided.
+    async fn get_gas_price(
+        &mut self,
+        ethereum: &EthereumGateway,
+        old_tx_gas_price: Option<U256>,
+    ) -> anyhow::Result<U256>;
+
+    /// Performs an actualization routine of the gas adjuster state.
+    /// This method is intended to be invoked periodically.
+    async fn keep_updated(&mut self, _ethereum: &EthereumGateway, _db: &DB) {}
+}
+
+/// Creates the gas adjuster implementing the configured strategy.
+pub(super) async fn new_gas_adjuster<DB: DatabaseInterface>(
+    config: &ETHSenderConfig,
+    db: &DB,
+) -> Box<dyn GasAdjuster<DB>> {
+    match config.gas_price_limit.strategy {
+        GasPriceStrategy::NodeOracle => Box::new(NodeOracleGasAdjuster::new(db).await),
+        GasPriceStrategy::FeeHistory => Box::new(FeeHistoryGasAdjuster::new(FeeMarket::new(
+            &config.fee_market,
+        ))),
+        GasPriceStrategy::FixedCap => Box::new(FixedCapGasAdjuster::new(
+            config.gas_price_limit.default.into(),
+        )),
+    }
+}
+
+/// Calculates the price for the replacement of the stuck tx: the maximum of the old price
+/// increased by 15% and the current price.
+fn scale_up(price_to_scale: U256, current_network_price: U256) -> U256 {
+    let replacement_price = (price_to_scale * U256::from(115)) / U256::from(100);
+    std::cmp::max(current_network_price, replacement_price)
+}
+
+/// Gas adjuster using the price suggested by the Ethereum node.
+///
 /// Gas price is adjusted with an upper limit, which is configured
 /// dynamically based on the average gas price observed within past
 /// sent transactions, and with a lower limit (for managing "stuck"
@@ -21,18 +72,17 @@ mod tests;
 /// gas price for transactions that were not mined by the network
 /// within a reasonable time.
 #[derive(Debug)]
-pub(super) struct GasAdjuster<DB: DatabaseInterface> {
+pub(super) struct NodeOracleGasAdjuster {
     /// Collected statistics about recently used gas prices.
     statistics: GasStatistics,
     /// Timestamp of the last maximum gas price update.
     last_price_renewal: Instant,
     /// Timestamp of the last sample added to the `statistics`.
     last_sample_added: Instant,
-    _db: PhantomData<DB>,
 }
 
-impl<DB: DatabaseInterface> GasAdjuster<DB> {
-    pub async fn new(db: &DB) -> Self {
+impl NodeOracleGasAdjuster {
+    pub async fn new<DB: DatabaseInterface>(db: &DB) -> Self {
         let mut connection = db
             .acquire_connection()
             .await
@@ -45,8 +95,6 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             statistics: GasStatistics::new(gas_price_limit),
             last_price_renewal: Instant::now(),
             last_sample_added: Instant::now(),
-
-            _db: PhantomData,
         }
     }
 
@@ -62,7 +110,7 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
         let network_price = ethereum.get_gas_price().await?;
         let scaled_price = if let Some(old_price) = old_tx_gas_price {
             // Stuck transaction, scale it up.
-            self.scale_up(old_price, network_price)
+            scale_up(old_price, network_price)
         } else {
             // New transaction, use the network price as the base.
             network_price
@@ -70,9 +118,27 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
         Ok(scaled_price)
     }
 
+    fn limit_max(&self, price: U256) -> U256 {
+        let limit = self.get_current_max_price();
+
+        std::cmp::min(price, limit)
+    }
+
+    /// Returns current max gas price that can be used to send transactions.
+    pub fn get_current_max_price(&self) -> U256 {
+        self.statistics.get_limit()
+    }
+}
+
+#[async_trait::async_trait]
+impl<DB: DatabaseInterface> GasAdjuster<DB> for NodeOracleGasAdjuster {
+    fn strategy(&self) -> GasPriceStrategy {
+        GasPriceStrategy::NodeOracle
+    }
+
     /// Calculates a new gas amount for the replacement of the stuck tx.
     /// Replacement price is usually suggested to be at least 10% higher, we make it 15% higher.
-    pub async fn get_gas_price(
+    async fn get_gas_price(
         &mut self,
         ethereum: &EthereumGateway,
         old_tx_gas_price: Option<U256>,
@@ -96,7 +162,7 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
     /// Performs an actualization routine for `GasAdjuster`:
     /// This method is intended to be invoked periodically, and it updates the
     /// current max gas price limit according to the configurable update interval.
-    pub async fn keep_updated(&mut self, ethereum: &EthereumGateway, db: &DB) {
+    async fn keep_updated(&mut self, ethereum: &EthereumGateway, db: &DB) {
         if self.last_sample_added.elapsed() >= parameters::sample_adding_interval() {
             // Report the current price to be gathered by the statistics module.
             match ethereum.get_gas_price().await {
@@ -149,22 +215,6 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             }
         }
     }
-
-    fn scale_up(&self, price_to_scale: U256, current_network_price: U256) -> U256 {
-        let replacement_price = (price_to_scale * U256::from(115)) / U256::from(100);
-        std::cmp::max(current_network_price, replacement_price)
-    }
-
-    fn limit_max(&self, price: U256) -> U256 {
-        let limit = self.get_current_max_price();
-
-        std::cmp::min(price, limit)
-    }
-
-    /// Returns current max gas price that can be used to send transactions.
-    pub fn get_current_max_price(&self) -> U256 {
-        self.statistics.get_limit()
-    }
 }
 
 /// Helper structure responsible for collecting the data about recent transactions,
@@ -3,8 +3,12 @@
 use zksync_basic_types::U256;
 // Local uses
 use crate::{
-    gas_adjuster::{parameters::limit_scale_factor, GasStatistics},
-    tests::mock::{default_eth_sender, MockDatabase},
+    fee_market::FeeMarket,
+    gas_adjuster::{
+        parameters::limit_scale_factor, FeeHistoryGasAdjuster, FixedCapGasAdjuster, GasStatistics,
+        NodeOracleGasAdjuster,
+    },
+    tests::mock::{default_eth_sender, default_fee_market, MockDatabase},
     DatabaseInterface, GasAdjuster,
 };
 
@@ -17,6 +21,11 @@ async fn eth_and_db_clients() -> (EthereumGateway, MockDatabase) {
     (eth_sender.ethereum, eth_sender.db)
 }
 
+/// Creates the gas adjuster of the node oracle strategy.
+async fn node_oracle_gas_adjuster(db: &MockDatabase) -> Box<dyn GasAdjuster<MockDatabase>> {
+    Box::new(NodeOracleGasAdjuster::new(db).await)
+}
+
 /// Scales the gas limit according to the scale factor for GasAdjuster.
 fn scale_gas_limit(value: u64) -> u64 {
     let scale = (limit_scale_factor() * 100.0).round() as u64;
@@ -30,7 +39,7 @@ fn scale_gas_limit(value: u64) -> u64 {
 async fn initial_price() {
     let (mut ethereum, db) = eth_and_db_clients().await;
     let mut connection = db.acquire_connection().await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster = node_oracle_gas_adjuster(&db).await;
 
     // Vector of ethereum client prices.
     let test_vector = vec![
@@ -62,7 +71,7 @@ async fn initial_price() {
 async fn lower_gas_limit() {
     let (mut ethereum, db) = eth_and_db_clients().await;
 
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster = node_oracle_gas_adjuster(&db).await;
 
     // Test vector of pairs (ethereum client price, price of the last tx, expected price).
     let test_vector = vec![
@@ -99,7 +108,7 @@ async fn gas_price_limit_restore() {
 
     let (_, db) = eth_and_db_clients().await;
     db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
-    let gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let gas_adjuster = NodeOracleGasAdjuster::new(&db).await;
 
     assert_eq!(gas_adjuster.get_current_max_price(), PRICE_LIMIT.into());
 }
@@ -115,7 +124,7 @@ async fn initial_upper_gas_limit() {
     let (mut ethereum, db) = eth_and_db_clients().await;
 
     db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster = node_oracle_gas_adjuster(&db).await;
 
     // Set the gas price in Ethereum, which is greater than the current limit.
     ethereum
@@ -155,7 +164,7 @@ async fn average_gas_price_stored_correctly() {
     let (mut ethereum, db) = eth_and_db_clients().await;
 
     db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster = node_oracle_gas_adjuster(&db).await;
 
     let initial_db_price = db.average_gas_price().await;
     assert_eq!(initial_db_price, 0u64.into()); // Check just in case.
@@ -204,7 +213,7 @@ async fn gas_price_limit_scaling() {
 
     db.update_gas_price_limit(PRICE_LIMIT as i64).await.unwrap();
 
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster = node_oracle_gas_adjuster(&db).await;
 
     // Set the client price way beyond the limit.
     ethereum
@@ -287,7 +296,7 @@ async fn gas_price_limit_average_basis() {
     let (mut ethereum, db) = eth_and_db_clients().await;
     let mut connection = db.acquire_connection().await.unwrap();
     db.update_gas_price_limit(PRICE_LIMIT as i64).await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster = node_oracle_gas_adjuster(&db).await;
 
     // Set the client price way beyond the limit.
     ethereum
@@ -352,7 +361,7 @@ async fn gas_price_limit_preservation() {
     let (mut ethereum, db) = eth_and_db_clients().await;
     let mut connection = db.acquire_connection().await.unwrap();
     db.update_gas_price_limit(price_limit as i64).await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster = node_oracle_gas_adjuster(&db).await;
 
     // Set the client price way beyond the limit.
     ethereum
@@ -378,3 +387,73 @@ async fn gas_price_limit_preservation() {
         assert_eq!(new_limit, price_limit.into());
     }
 }
+
+/// Checks that the fixed cap strategy uses the network price, scales it up for the stuck
+/// transactions, and never exceeds the cap.
+#[tokio::test]
+async fn fixed_cap_strategy() {
+    const PRICE_CAP: u64 = 1000;
+
+    let (mut ethereum, _) = eth_and_db_clients().await;
+    let mut gas_adjuster: Box<dyn GasAdjuster<MockDatabase>> =
+        Box::new(FixedCapGasAdjuster::new(PRICE_CAP.into()));
+
+    // Test vector of (ethereum client price, price of the last tx, expected price).
+    let test_vector = vec![
+        (100, None, 100),
+        (100, Some(100), 115),
+        (PRICE_CAP + 1, None, PRICE_CAP),
+        (100, Some(PRICE_CAP), PRICE_CAP),
+    ];
+
+    for (eth_client_price, previous_price, expected_price) in test_vector {
+        ethereum
+            .get_mut_mock()
+            .unwrap()
+            .set_gas_price(eth_client_price.into())
+            .await
+            .unwrap();
+
+        let scaled_gas = gas_adjuster
+            .get_gas_price(&ethereum, previous_price.map(U256::from))
+            .await
+            .unwrap();
+        assert_eq!(scaled_gas, expected_price.into());
+    }
+}
+
+/// Checks that the fee history strategy uses the base fee plus the priority fee,
+/// and scales it up for the stuck transactions.
+#[tokio::test]
+async fn fee_history_strategy() {
+    let (mut ethereum, _) = eth_and_db_clients().await;
+    let mut gas_adjuster: Box<dyn GasAdjuster<MockDatabase>> = Box::new(
+        FeeHistoryGasAdjuster::new(FeeMarket::new(&default_fee_market(false))),
+    );
+
+    // The mock client reports the gas price as the base fee, and the tenth
+    // of it as the priority fee.
+    // Test vector of (ethereum client price, price of the last tx, expected price).
+    let test_vector = vec![
+        (100, None, 110),
+        (100, Some(200), 230),
+        (100, Some(50), 110),
+        // The priority fee is raised to the minimum one.
+        (5, None, 6),
+    ];
+
+    for (eth_client_price, previous_price, expected_price) in test_vector {
+        ethereum
+            .get_mut_mock()
+            .unwrap()
+            .set_gas_price(eth_client_price.into())
+            .await
+            .unwrap();
+
+        let scaled_gas = gas_adjuster
+            .get_gas_price(&ethereum, previous_price.map(U256::from))
+            .await
+            .unwrap();
+        assert_eq!(scaled_gas, expected_price.into());
+    }
+}
@@ -11,33 +11,51 @@ use anyhow::format_err;
 use tokio::{task::JoinHandle, time};
 use web3::{
     contract::Options,
+    signing::keccak256,
     types::{TransactionReceipt, H256, U256},
 };
 // Workspace uses
 use zksync_config::ETHSenderConfig;
 use zksync_eth_client::{EthereumGateway, SignedCallResult};
-use zksync_storage::ConnectionPool;
-use zksync_types::ethereum::ETHOperation;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::ethereum::{ETHOperation, OperatorKeyUsage, OperatorKeyUsageOutcome};
 // Local uses
 use self::{
     database::{Database, DatabaseInterface},
-    gas_adjuster::GasAdjuster,
+    fee_market::FeeMarket,
+    gas_adjuster::{new_gas_adjuster, GasAdjuster},
+    nonce_reconciliation::reconcile_nonces,
+    operator_keys::{must_follow, OperatorKeys},
+    signing_policy::SigningPolicy,
+    stuck_tx_alert::StuckTxAlert,
     transactions::*,
+    tx_fees::{new_tx_fees, replacement_tx_fees},
     tx_queue::{TxData, TxQueue, TxQueueBuilder},
+    tx_simulation::TxSimulation,
 };
 use zksync_types::{
-    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    aggregated_operations::{AggregatedActionType, AggregatedOperation, BlocksExecuteOperation},
     gas_counter::GasCounter,
+    Address, BlockNumber,
 };
 
 mod database;
+mod fee_market;
 mod gas_adjuster;
+mod nonce_reconciliation;
+mod operator_keys;
+mod signing_policy;
+mod stuck_tx_alert;
 mod transactions;
+mod tx_fees;
 mod tx_queue;
+mod tx_simulation;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::tx_fees::{set_tx_fees, OperatorTxFees};
+
 /// Wait this amount of time if we hit rate limit on infura https://infura.io/docs/ethereum/json-rpc/ratelimits
 const RATE_LIMIT_BACKOFF_PERIOD: Duration = Duration::from_secs(30);
 /// Rate limit error will contain this response code
@@ -85,6 +103,9 @@ enum TxCheckMode {
 /// keep the list of all sent transaction hashes for one particular operations, since we can't be
 /// sure which one will be committed; thus we have to track all of them.
 ///
+/// If the EIP-1559 transactions are enabled, their fees are estimated from the fee history
+/// of the recent blocks instead, and both fees are raised for the stuck transaction (see `FeeMarket`).
+///
 /// Note: make sure to save signed tx to db before sending it to ETH, this way we can be sure
 /// that state is always recoverable.
 ///
@@ -102,12 +123,68 @@ enum TxCheckMode {
 /// 2. Withdraw operations (only if both commit/verify for the same block operations were sent).
 /// 3. Commit operations.
 ///
+/// # Aggregation
+///
+/// Every operation covers several blocks: the blocks are aggregated into the commit, proof and
+/// execute operations by the aggregated committer of the core, limited by the
+/// `max_aggregated_blocks_to_*` and `max_aggregated_tx_gas` options of the state keeper.
+/// Operations of different types are never packed into one transaction: the contract accepts
+/// them only from the validator account itself, so they can't be batched by a multicall contract.
+///
 /// # Failure policy
 ///
 /// By default, `ETHSender` expects no transactions to fail, and thus upon a failure it will
 /// report the incident to the log and then panic to prevent continue working in a probably
 /// erroneous conditions. Failure handling policy is determined by a corresponding callback,
 /// which can be changed if needed.
+///
+/// # Signing policy
+///
+/// Every transaction is checked against the `SigningPolicy` before it's signed, and every
+/// attempt to sign a transaction is recorded to the operator key audit log. Transactions
+/// violating the policy are not signed, and the operation is retried later.
+///
+/// # Operator keys
+///
+/// Besides the main operator key, the transactions may be signed with the additional keys,
+/// each having its own nonce. The operation is assigned to the key when it's sent, and all the
+/// replacements of its transaction are signed with the same key. See `OperatorKeys` for details.
+///
+/// Once the keys signing the commits are critically low on funds, `ETHSender` enters the safety
+/// mode: the new blocks are not committed, while the committed ones are still proven and executed.
+///
+/// # Stuck transactions
+///
+/// The transaction not included within `expected_wait_time_block` blocks is replaced with
+/// the one paying the escalated fees, up to the configured caps. If the operation still isn't
+/// confirmed within the hard deadline since its first transaction was sent, it's reported so
+/// on-call can intervene. See `StuckTxAlert` for details.
+///
+/// # Nonce reconciliation
+///
+/// On startup, the stored nonces of the operator keys are reconciled with the chain, since
+/// a crash may leave the transactions sent but not stored, or stored but never sent.
+/// See `nonce_reconciliation` for details.
+///
+/// # Simulation
+///
+/// The transaction of the new operation is simulated with `eth_call` before it's sent. If it
+/// would revert, it's not sent, the decoded revert reason is logged, and the operations of the
+/// same type are paused for a while. See `TxSimulation` for details.
+///
+/// # Fencing
+///
+/// If the server instances elect the leader, `ETHSender` is given the leadership term of its
+/// instance. The term is checked in the same database transaction the sent transaction is stored
+/// in, so the instance which lost the leadership stops before sending anything to L1.
+///
+/// # Block revert
+///
+/// While the blocks are being reverted by the core, the new operations are not sent, while the
+/// ones in flight are still confirmed, so the revert can wait for them. The pending revert is
+/// checked in the same database transaction the sent transaction is stored in, so the revert
+/// also waits for the operations being sent. Once the revert is finished, the queue of the
+/// operations is reloaded from the reverted storage.
 struct ETHSender<DB: DatabaseInterface> {
     /// Ongoing operations queue.
     ongoing_ops: VecDeque<ETHOperation>,
@@ -117,14 +194,46 @@ struct ETHSender<DB: DatabaseInterface> {
     ethereum: EthereumGateway,
     /// Queue for ordered transaction processing.
     tx_queue: TxQueue,
-    /// Utility for managing the gas price for transactions.
-    gas_adjuster: GasAdjuster<DB>,
+    /// Utility for managing the gas price for transactions, implements the configured strategy.
+    gas_adjuster: Box<dyn GasAdjuster<DB>>,
+    /// Fees of the EIP-1559 transactions.
+    fee_market: FeeMarket,
+    /// Operator keys the transactions are signed with.
+    operator_keys: OperatorKeys,
     /// Settings for the `ETHSender`.
     options: ETHSenderConfig,
+    /// Restrictions on the transactions signed with the operator key.
+    signing_policy: SigningPolicy,
+    /// Alerts on the operations not confirmed within the hard deadline.
+    stuck_tx_alert: StuckTxAlert,
+    /// Simulation of the transactions of the new operations.
+    tx_simulation: TxSimulation,
+    /// Address of the zkSync contract, the recipient of all the sent transactions.
+    main_contract: Address,
+    /// Leadership term of the instance, `None` if the leader election is disabled.
+    fencing_term: Option<i64>,
+    /// Last correct block of the block revert in progress, if any.
+    block_revert: Option<BlockNumber>,
+}
+
+/// Creates the queue following the operations sent and confirmed so far.
+fn build_tx_queue(options: &ETHSenderConfig, sent_pending_txs: usize, stats: &ETHStats) -> TxQueue {
+    TxQueueBuilder::new(options.sender.max_txs_in_flight as usize)
+        .with_sent_pending_txs(sent_pending_txs)
+        .with_commit_operations_count(stats.last_committed_block)
+        .with_verify_operations_count(stats.last_verified_block)
+        .with_execute_operations_count(stats.last_executed_block)
+        .build()
 }
 
 impl<DB: DatabaseInterface> ETHSender<DB> {
-    pub async fn new(options: ETHSenderConfig, db: DB, ethereum: EthereumGateway) -> Self {
+    pub async fn new(
+        options: ETHSenderConfig,
+        db: DB,
+        ethereum: EthereumGateway,
+        additional_keys: Vec<(Address, EthereumGateway)>,
+        main_contract: Address,
+    ) -> Self {
         let mut connection = db
             .acquire_connection()
             .await
@@ -138,11 +247,22 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .await
             .expect("Can't restore unprocessed operations");
 
-        let ongoing_ops = db
+        let mut ongoing_ops = db
             .load_unconfirmed_operations(&mut transaction)
             .await
             .expect("Can't restore state");
 
+        // Whether the execute operation completes withdrawals is decided when it's loaded,
+        // so it's not a part of the stored aggregated operation and has to be restored.
+        for eth_op in ongoing_ops.iter_mut() {
+            if let Some((id, AggregatedOperation::ExecuteBlocks(op))) = eth_op.op.as_mut() {
+                op.complete_withdrawals = db
+                    .completes_withdrawals(&mut transaction, *id)
+                    .await
+                    .expect("Can't restore withdrawals status");
+            }
+        }
+
         let operations_id = ongoing_ops
             .iter()
             .filter_map(|eth_op| eth_op.op.as_ref())
@@ -157,14 +277,41 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .await
             .expect("Failed loading ETH operations stats");
 
-        let tx_queue = TxQueueBuilder::new(options.sender.max_txs_in_flight as usize)
-            .with_sent_pending_txs(ongoing_ops.len())
-            .with_commit_operations_count(stats.last_committed_block)
-            .with_verify_operations_count(stats.last_verified_block)
-            .with_execute_operations_count(stats.last_executed_block)
-            .build();
+        let tx_queue = build_tx_queue(&options, ongoing_ops.len(), &stats);
+
+        let gas_adjuster = new_gas_adjuster(&options, &db).await;
 
-        let gas_adjuster = GasAdjuster::new(&db).await;
+        let operator_keys = OperatorKeys::new(
+            options.sender.operator_commit_eth_addr,
+            additional_keys,
+            &options.operator_keys,
+        );
+        for (address, key_ethereum) in operator_keys.additional_keys() {
+            let nonce = key_ethereum
+                .pending_nonce()
+                .await
+                .expect("Can't load the nonce of the operator key");
+            db.initialize_operator_nonce(&mut transaction, address, nonce.as_u64() as i64)
+                .await
+                .expect("Can't initialize the nonce of the operator key");
+        }
+
+        // The stored nonces may diverge from the chain after a crash.
+        let current_block = ethereum
+            .block_number()
+            .await
+            .expect("Can't load the current block number")
+            .as_u64();
+        reconcile_nonces(
+            &db,
+            &mut transaction,
+            &ethereum,
+            &operator_keys,
+            &mut ongoing_ops,
+            current_block,
+        )
+        .await
+        .expect("Can't reconcile the operator nonces with the chain");
 
         transaction
             .commit()
@@ -172,16 +319,54 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .expect("Failed commit database transaction");
         drop(connection);
 
+        let signing_policy = SigningPolicy::new(&options.signing_policy, main_contract);
+        let fee_market = FeeMarket::new(&options.fee_market);
+        let stuck_tx_alert = StuckTxAlert::new(&options);
+        let tx_simulation = TxSimulation::new(&options.tx_simulation);
+
         Self {
             ongoing_ops,
             db,
             ethereum,
             tx_queue,
             gas_adjuster,
+            fee_market,
+            operator_keys,
             options,
+            signing_policy,
+            stuck_tx_alert,
+            tx_simulation,
+            main_contract,
+            fencing_term: None,
+            block_revert: None,
         }
     }
 
+    /// Sets the leadership term the sent transactions are fenced with.
+    pub fn with_fencing_term(mut self, fencing_term: Option<i64>) -> Self {
+        self.fencing_term = fencing_term;
+        self
+    }
+
+    /// Checks that the instance is still the leader and locks the term until the end of
+    /// the transaction. Panics otherwise, since the new leader is already sending
+    /// the transactions and this instance must not interfere.
+    async fn check_fencing_term(
+        &self,
+        transaction: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        if let Some(term) = self.fencing_term {
+            let current_term = self.db.lock_leader_term(transaction).await?;
+            if current_term != term {
+                panic!(
+                    "Leadership term {} is over, the current term is {}. Stopping the Ethereum sender",
+                    term, current_term
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Main routine of `ETHSender`.
     pub async fn run(mut self) {
         // `eth_sender` must perform some of the activities only once per block change.
@@ -197,14 +382,49 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             }
 
             if self.options.sender.is_enabled {
+                if let Err(error) = self.check_block_revert().await {
+                    vlog::error!("Unable to check the block revert: {}", error);
+                    continue;
+                }
                 // ...and proceed them.
                 last_used_block = self.proceed_next_operations(last_used_block).await;
                 // Update the gas adjuster to maintain the up-to-date max gas price limit.
                 self.gas_adjuster
                     .keep_updated(&self.ethereum, &self.db)
                     .await;
+                // Report the balances of the operator accounts.
+                self.operator_keys.check_balances(&self.ethereum).await;
+            }
+        }
+    }
+
+    /// Follows the revert of the blocks: the new operations are not sent while it's in progress,
+    /// and the queue is reloaded from the reverted storage once it's finished.
+    async fn check_block_revert(&mut self) -> anyhow::Result<()> {
+        let mut connection = self.db.acquire_connection().await?;
+        let block_revert = self.db.lock_pending_block_revert(&mut connection).await?;
+        match (self.block_revert, block_revert) {
+            (None, Some(last_correct_block)) => {
+                vlog::warn!(
+                    "The blocks after block {} are being reverted, \
+                     the new operations are not sent until the revert is finished",
+                    last_correct_block
+                );
             }
+            (Some(_), None) => {
+                vlog::warn!("The blocks are reverted, reloading the operations to send");
+                let mut transaction = connection.start_transaction().await?;
+                self.db
+                    .restore_unprocessed_operations(&mut transaction)
+                    .await?;
+                let stats = self.db.load_stats(&mut transaction).await?;
+                transaction.commit().await?;
+                self.tx_queue = build_tx_queue(&self.options, self.ongoing_ops.len(), &stats);
+            }
+            _ => {}
         }
+        self.block_revert = block_revert;
+        Ok(())
     }
 
     /// Gets the incoming operations from the database and adds them to the
@@ -214,7 +434,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         let mut connection = self.db.acquire_connection().await?;
         let mut transaction = connection.start_transaction().await?;
 
-        let new_operations = self.db.load_new_operations(&mut transaction).await?;
+        let mut new_operations = self.db.load_new_operations(&mut transaction).await?;
 
         if !new_operations.is_empty() {
             vlog::info!("Loaded {} new operations", new_operations.len());
@@ -229,6 +449,13 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .remove_unprocessed_operations(&mut transaction, operations_id)
             .await?;
 
+        for (id, operation) in new_operations.iter_mut() {
+            if let AggregatedOperation::ExecuteBlocks(operation) = operation {
+                self.schedule_withdrawals(&mut transaction, *id, operation)
+                    .await?;
+            }
+        }
+
         transaction.commit().await?;
         drop(connection);
 
@@ -240,6 +467,38 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         Ok(())
     }
 
+    /// Decides whether the withdrawals of the execute operation should be completed
+    /// by the `executeBlocks` transaction and stores their status.
+    async fn schedule_withdrawals(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        id: i64,
+        operation: &mut BlocksExecuteOperation,
+    ) -> anyhow::Result<()> {
+        let withdrawals_count = operation.withdrawals_count();
+        if withdrawals_count == 0 {
+            return Ok(());
+        }
+
+        operation.complete_withdrawals = should_complete_withdrawals(
+            self.options.sender.complete_withdrawals_on_execute,
+            self.options.sender.max_withdrawals_per_execute_tx,
+            withdrawals_count,
+        );
+        if operation.complete_withdrawals {
+            metrics::counter!(
+                "eth_sender.withdrawals_completed_in_execute",
+                withdrawals_count as u64
+            );
+        } else {
+            metrics::counter!("eth_sender.withdrawals_deferred", withdrawals_count as u64);
+        }
+
+        self.db
+            .schedule_withdrawals(connection, id, operation)
+            .await
+    }
+
     /// This method does two main things:
     ///
     /// 1. Pops all the available transactions from the `TxQueue` and sends them.
@@ -258,8 +517,60 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             }
         };
 
+        // The commits are held while the operator funds are critically low, and the operations
+        // of any type are held while the blocks are being reverted or for a while after their
+        // simulated transaction has reverted.
+        for &op_type in &[
+            AggregatedActionType::CommitBlocks,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ] {
+            let safety_mode = op_type == AggregatedActionType::CommitBlocks
+                && self.operator_keys.is_safety_mode();
+            let paused = safety_mode
+                || self.block_revert.is_some()
+                || self.tx_simulation.is_paused(op_type, current_block);
+            self.tx_queue.set_paused(op_type, paused);
+        }
+
         while let Some(tx) = self.tx_queue.pop_front() {
-            if let Err(e) = self.initialize_operation(tx.clone(), current_block).await {
+            // Keys of the operations in flight the new one has to go after.
+            let preceding_senders: Vec<_> = self
+                .ongoing_ops
+                .iter()
+                .filter(|op| match &op.op {
+                    Some((_, op)) => must_follow(&tx.operation.1, op),
+                    None => true,
+                })
+                .map(|op| op.sender)
+                .collect();
+            let sender = self
+                .operator_keys
+                .assign(tx.op_type, preceding_senders.last().copied());
+            if preceding_senders
+                .iter()
+                .any(|&preceding| preceding != sender)
+            {
+                // Transactions signed by different keys may be included in any order, so the
+                // operation waits until the ones it has to go after are confirmed, while
+                // the transactions of the same key are ordered by the nonces.
+                if let Err(err_message) = self.tx_queue.return_popped(tx) {
+                    panic!(
+                        "Failed return previous sent operation to the queue: {}",
+                        err_message
+                    );
+                }
+                break;
+            }
+
+            // The transaction is simulated against the pending state, so it reverts if the
+            // operations it has to go after are not included yet, e.g. on the block hash check.
+            // The simulation is inconclusive then and is skipped.
+            let simulate = preceding_senders.is_empty();
+            if let Err(e) = self
+                .initialize_operation(tx.clone(), sender, simulate, current_block)
+                .await
+            {
                 Self::process_error(e).await;
                 // Return the unperformed operation to the queue, since failing the
                 // operation initialization means that it was not stored in the database.
@@ -269,6 +580,9 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                         err_message
                     );
                 }
+                // The operation is retried on the next iteration, otherwise it would be
+                // popped again right away (e.g. if it's rejected by the signing policy).
+                break;
             }
         }
 
@@ -302,8 +616,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     OperationCommitment::Committed => {
                         // Free a slot for the next tx in the queue.
                         self.tx_queue.report_commitment();
+                        self.stuck_tx_alert.report_confirmed(&current_op);
                     }
                     OperationCommitment::Pending => {
+                        // Report the operation if it's not confirmed for too long.
+                        self.stuck_tx_alert.check(&current_op, current_block).await;
                         // Poll this operation on the next iteration.
                         new_ongoing_ops.push_back(current_op);
                     }
@@ -335,17 +652,42 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         }
     }
 
-    /// Stores the new operation in the database and sends the corresponding transaction.
-    async fn initialize_operation(&mut self, tx: TxData, current_block: u64) -> anyhow::Result<()> {
+    /// Stores the new operation in the database and sends the corresponding transaction
+    /// signed with the `sender` key, `None` for the main operator key. The transaction is
+    /// simulated first if `simulate` is set.
+    async fn initialize_operation(
+        &mut self,
+        tx: TxData,
+        sender: Option<Address>,
+        simulate: bool,
+        current_block: u64,
+    ) -> anyhow::Result<()> {
+        // The reverting transaction isn't stored, so its nonce is not taken.
+        let ethereum = self.operator_keys.ethereum(&self.ethereum, sender)?;
+        if simulate {
+            self.tx_simulation
+                .check(ethereum, &tx, current_block)
+                .await?;
+        }
+
         let deadline_block = self.get_deadline_block(current_block);
-        let gas_price = self
-            .gas_adjuster
-            .get_gas_price(&self.ethereum, None)
-            .await?;
+        let (gas_price, priority_fee) =
+            new_tx_fees(&self.fee_market, self.gas_adjuster.as_mut(), &self.ethereum).await?;
 
         let mut connection = self.db.acquire_connection().await?;
         let mut transaction = connection.start_transaction().await?;
 
+        // The revert of the blocks started meanwhile waits for the transaction to be committed.
+        if let Some(last_correct_block) =
+            self.db.lock_pending_block_revert(&mut transaction).await?
+        {
+            self.block_revert = Some(last_correct_block);
+            anyhow::bail!(
+                "The blocks after block {} are being reverted",
+                last_correct_block
+            );
+        }
+
         // let (new_op, signed_tx) = self.db.transaction(|| {
         let (new_op, signed_tx) = {
             // First, we should store the operation in the database and obtain the assigned
@@ -358,6 +700,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     Some(tx.operation.clone()),
                     deadline_block as i64,
                     gas_price,
+                    priority_fee,
+                    sender,
                     tx.raw.clone(),
                 )
                 .await?;
@@ -368,7 +712,10 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 op: Some(tx.operation),
                 nonce: assigned_data.nonce,
                 last_deadline_block: deadline_block,
+                first_deadline_block: deadline_block,
                 last_used_gas_price: gas_price,
+                last_used_priority_fee: priority_fee,
+                sender,
                 used_tx_hashes: vec![], // No hash yet, will be added below.
                 encoded_tx_data: tx.raw,
                 confirmed: false,
@@ -376,7 +723,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             };
 
             // Sign the transaction.
-            let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op).await?;
+            let signed_tx = self.sign_new_tx(&new_op).await?;
 
             // With signed tx, update the hash in the operation entry and in the db.
             new_op.used_tx_hashes.push(signed_tx.hash);
@@ -396,6 +743,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             "Sending new tx: [ETH Operation <id: {}, type: {:?}>. ETH tx: {}. ZKSync operation: {}]",
             new_op.id, new_op.op_type, self.eth_tx_description(&signed_tx), self.zksync_operation_description(&new_op),
         );
+        self.check_fencing_term(&mut transaction).await?;
         if let Err(e) = self.ethereum.send_raw_tx(signed_tx.raw_tx).await {
             // Sending tx error is not critical: this will result in transaction being considered stuck,
             // and resent. We can't do anything about this failure either, since it's most probably is not
@@ -414,10 +762,19 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     fn eth_tx_description(&self, tx: &SignedCallResult) -> String {
         // Gas price in gwei (wei / 10^9).
         let gas_price = tx.gas_price / (1_000_000_000);
-        format!(
-            "<hash: {:#x}; gas price: {} gwei; nonce: {}>",
-            tx.hash, gas_price, tx.nonce
-        )
+        match tx.max_priority_fee_per_gas {
+            Some(priority_fee) => format!(
+                "<hash: {:#x}; max fee: {} gwei; priority fee: {} gwei; nonce: {}>",
+                tx.hash,
+                gas_price,
+                priority_fee / (1_000_000_000),
+                tx.nonce
+            ),
+            None => format!(
+                "<hash: {:#x}; gas price: {} gwei; nonce: {}>",
+                tx.hash, gas_price, tx.nonce
+            ),
+        }
     }
 
     /// Helper method to obtain the string representation of the zkSync operation.
@@ -472,7 +829,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     // Transaction is pending, nothing to do yet.
                     return Ok(OperationCommitment::Pending);
                 }
-                TxCheckOutcome::Committed => {
+                TxCheckOutcome::Committed { inclusion_block } => {
                     let mut connection = self.db.acquire_connection().await?;
                     let mut transaction = connection.start_transaction().await?;
 
@@ -509,6 +866,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                         .confirm_operation(&mut transaction, tx_hash, op)
                         .await?;
                     transaction.commit().await?;
+                    self.report_inclusion_time(op, inclusion_block);
                     return Ok(OperationCommitment::Committed);
                 }
                 TxCheckOutcome::Stuck => {
@@ -545,6 +903,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 op.id,
                 deadline_block as i64,
                 new_tx.gas_price,
+                new_tx.max_priority_fee_per_gas,
             )
             .await?;
         self.db
@@ -556,6 +915,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             op.id,
             self.eth_tx_description(&new_tx),
         );
+        self.check_fencing_term(&mut transaction).await?;
         self.ethereum.send_raw_tx(new_tx.raw_tx).await?;
         transaction.commit().await?;
 
@@ -578,6 +938,27 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         panic!("Cannot operate after unexpected TX failure");
     }
 
+    /// Reports the amount of blocks the latest transaction of the confirmed operation waited
+    /// for the inclusion against the one predicted by the gas price strategy, so the
+    /// strategies can be compared.
+    fn report_inclusion_time(&self, op: &ETHOperation, inclusion_block: u64) {
+        let strategy = if self.fee_market.is_enabled() {
+            "eip1559"
+        } else {
+            self.gas_adjuster.strategy().as_str()
+        };
+        let predicted_blocks = self.options.sender.expected_wait_time_block;
+        let sent_block = op.last_deadline_block.saturating_sub(predicted_blocks);
+        let actual_blocks = inclusion_block.saturating_sub(sent_block);
+
+        metrics::histogram!("eth_sender.gas_price.predicted_inclusion_blocks", predicted_blocks as f64, "strategy" => strategy);
+        metrics::histogram!("eth_sender.gas_price.actual_inclusion_blocks", actual_blocks as f64, "strategy" => strategy);
+        metrics::histogram!("eth_sender.gas_price.replacements", (op.used_tx_hashes.len() - 1) as f64, "strategy" => strategy);
+        if actual_blocks > predicted_blocks {
+            metrics::increment_counter!("eth_sender.gas_price.missed_predictions", "strategy" => strategy);
+        }
+    }
+
     /// Helper method encapsulating the logic of determining the next deadline block.
     fn get_deadline_block(&self, current_block: u64) -> u64 {
         current_block + self.options.sender.expected_wait_time_block
@@ -602,7 +983,9 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             Some(status) if status.success => {
                 // Check if transaction has enough confirmations.
                 if status.confirmations >= self.options.sender.wait_confirmations {
-                    TxCheckOutcome::Committed
+                    TxCheckOutcome::Committed {
+                        inclusion_block: current_block.saturating_sub(status.confirmations),
+                    }
                 } else {
                     TxCheckOutcome::Pending
                 }
@@ -635,10 +1018,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     }
 
     /// Creates a new Ethereum operation.
-    async fn sign_new_tx(
-        ethereum: &EthereumGateway,
-        op: &ETHOperation,
-    ) -> anyhow::Result<SignedCallResult> {
+    async fn sign_new_tx(&self, op: &ETHOperation) -> anyhow::Result<SignedCallResult> {
         let tx_options = {
             // We set the gas limit for commit / verify operations as pre-calculated estimation.
             // This estimation is a higher bound based on a pre-calculated cost of every operation in the block.
@@ -656,21 +1036,87 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 gas_limit
             );
 
-            Options {
+            let mut options = Options {
                 nonce: Some(op.nonce),
-                gas_price: Some(op.last_used_gas_price),
                 gas: Some(gas_limit),
-                max_fee_per_gas: None,
-                max_priority_fee_per_gas: None,
                 ..Default::default()
+            };
+            set_tx_fees(
+                &mut options,
+                op.last_used_gas_price,
+                op.last_used_priority_fee,
+            );
+            options
+        };
+
+        self.sign_tx(op, tx_options).await
+    }
+
+    /// Checks the transaction of the operation against the signing policy and signs it.
+    /// The attempt is recorded to the operator key audit log regardless of the outcome.
+    async fn sign_tx(
+        &self,
+        op: &ETHOperation,
+        options: Options,
+    ) -> anyhow::Result<SignedCallResult> {
+        let mut usage = OperatorKeyUsage {
+            eth_op_id: op.id,
+            op_type: op.op_type,
+            contract_addr: self.main_contract,
+            payload_hash: H256::from(keccak256(&op.encoded_tx_data)),
+            nonce: options.nonce.unwrap_or_default(),
+            gas_limit: options.gas.unwrap_or_default(),
+            gas_price: options
+                .max_fee_per_gas
+                .or(options.gas_price)
+                .unwrap_or_default(),
+            value: options.value.unwrap_or_default(),
+            tx_hash: None,
+            outcome: OperatorKeyUsageOutcome::Signed,
+            reason: None,
+        };
+
+        let result = match self.signing_policy.check(self.main_contract, &options) {
+            Ok(()) => {
+                // The transaction is signed with the key the operation is assigned to.
+                let result = match self.operator_keys.ethereum(&self.ethereum, op.sender) {
+                    Ok(ethereum) => {
+                        ethereum
+                            .sign_prepared_tx(op.encoded_tx_data.clone(), options)
+                            .await
+                    }
+                    Err(err) => Err(err),
+                };
+                match &result {
+                    Ok(signed_tx) => usage.tx_hash = Some(signed_tx.hash),
+                    Err(err) => {
+                        usage.outcome = OperatorKeyUsageOutcome::Failed;
+                        usage.reason = Some(err.to_string());
+                    }
+                }
+                result
+            }
+            Err(violation) => {
+                vlog::error!(
+                    "Signing policy rejected the transaction for <ETH Operation id: {}>: {}",
+                    op.id,
+                    violation
+                );
+                usage.outcome = OperatorKeyUsageOutcome::Rejected;
+                usage.reason = Some(violation.to_string());
+                Err(format_err!("Signing policy violation: {}", violation))
             }
         };
 
-        let signed_tx = ethereum
-            .sign_prepared_tx(op.encoded_tx_data.clone(), tx_options)
+        // The entry is stored using a separate connection, so it's kept even if the
+        // database transaction storing the operation is rolled back.
+        let mut connection = self.db.acquire_connection().await?;
+        self.db
+            .record_operator_key_usage(&mut connection, &usage)
             .await?;
+        metrics::increment_counter!("eth_sender.operator_key_usage", "outcome" => usage.outcome.as_str());
 
-        Ok(signed_tx)
+        result
     }
 
     /// Calculates the gas limit for transaction to be send, depending on the type of operation.
@@ -683,6 +1129,12 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             AggregatedOperation::CommitBlocks(commit) => {
                 GasCounter::commit_gas_limit_aggregated(&commit.blocks)
             }
+            AggregatedOperation::ExecuteBlocks(execute) if execute.complete_withdrawals => {
+                GasCounter::execute_gas_limit_with_withdrawals(
+                    &execute.blocks,
+                    execute.withdrawals_count(),
+                )
+            }
             AggregatedOperation::ExecuteBlocks(execute) => {
                 GasCounter::execute_gas_limit_aggregated(&execute.blocks)
             }
@@ -704,11 +1156,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     ) -> anyhow::Result<SignedCallResult> {
         let tx_options = self.tx_options_from_stuck_tx(stuck_tx).await?;
 
-        let raw_tx = stuck_tx.encoded_tx_data.clone();
-        let signed_tx = self.ethereum.sign_prepared_tx(raw_tx, tx_options).await?;
+        let signed_tx = self.sign_tx(stuck_tx, tx_options).await?;
 
         stuck_tx.last_deadline_block = deadline_block;
         stuck_tx.last_used_gas_price = signed_tx.gas_price;
+        stuck_tx.last_used_priority_fee = signed_tx.max_priority_fee_per_gas;
         stuck_tx.used_tx_hashes.push(signed_tx.hash);
 
         Ok(signed_tx)
@@ -722,10 +1174,14 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     ) -> anyhow::Result<Options> {
         let old_tx_gas_price = stuck_tx.last_used_gas_price;
 
-        let new_gas_price = self
-            .gas_adjuster
-            .get_gas_price(&self.ethereum, Some(old_tx_gas_price))
-            .await?;
+        let (new_gas_price, new_priority_fee) = replacement_tx_fees(
+            &self.fee_market,
+            self.gas_adjuster.as_mut(),
+            &self.ethereum,
+            old_tx_gas_price,
+            stuck_tx.last_used_priority_fee,
+        )
+        .await?;
         let nonce = stuck_tx.nonce;
         let gas_limit = Self::gas_limit_for_op(stuck_tx);
 
@@ -736,16 +1192,18 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         );
 
         vlog::info!(
-            "Replacing tx: hash: {:#x}, old_gas: {}, new_gas: {}, used nonce: {}, gas limit: {}",
+            "Replacing tx: hash: {:#x}, old_gas: {}, new_gas: {}, old_priority_fee: {:?}, new_priority_fee: {:?}, used nonce: {}, gas limit: {}",
             stuck_tx.used_tx_hashes.last().unwrap(),
             old_tx_gas_price,
             new_gas_price,
+            stuck_tx.last_used_priority_fee,
+            new_priority_fee,
             nonce,
             gas_limit,
         );
 
         Ok(Options::with(move |opt| {
-            opt.gas_price = Some(new_gas_price);
+            set_tx_fees(opt, new_gas_price, new_priority_fee);
             opt.nonce = Some(nonce);
             opt.gas = Some(gas_limit);
         }))
@@ -796,16 +1254,32 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     }
 }
 
+/// Withdrawals are completed by the execute transaction only if it's enabled and the
+/// transaction doesn't exceed the withdrawals limit. Otherwise they are left pending
+/// on the contract and can be completed by the users themselves.
+fn should_complete_withdrawals(
+    enabled: bool,
+    max_withdrawals: u64,
+    withdrawals_count: usize,
+) -> bool {
+    enabled && withdrawals_count > 0 && withdrawals_count as u64 <= max_withdrawals
+}
+
 #[must_use]
 pub fn run_eth_sender(
     pool: ConnectionPool,
     eth_gateway: EthereumGateway,
+    additional_keys: Vec<(Address, EthereumGateway)>,
     options: ETHSenderConfig,
+    main_contract: Address,
+    fencing_term: Option<i64>,
 ) -> JoinHandle<()> {
     let db = Database::new(pool);
 
     tokio::spawn(async move {
-        let eth_sender = ETHSender::new(options, db, eth_gateway).await;
+        let eth_sender = ETHSender::new(options, db, eth_gateway, additional_keys, main_contract)
+            .await
+            .with_fencing_term(fencing_term);
 
         eth_sender.run().await
     })
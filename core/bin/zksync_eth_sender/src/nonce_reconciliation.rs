@@ -0,0 +1,222 @@
+//! Reconciliation of the stored operator nonces with the chain on startup.
+//!
+//! The transaction is sent before the database transaction storing it is committed, and the
+//! sending errors are ignored, so after a crash the stored nonce may diverge from the chain:
+//!
+//! - If the transactions were sent but not stored, the pending nonce of the account is ahead
+//!   of the stored one, and the next transactions would reuse the taken nonces.
+//! - If the stored transactions never reached the node (or were dropped from its mempool),
+//!   they are orphaned: nothing is going to include them, and every following transaction
+//!   waits for them.
+//!
+//! On startup, the stored nonce of every operator key is moved to the pending nonce of the
+//! account, or right after the nonces of the unconfirmed operations if they are ahead of it.
+//! The orphaned transactions are considered stuck right away, so they're resent on the first
+//! iteration instead of waiting for their deadline.
+
+// Built-in deps
+use std::collections::VecDeque;
+// Workspace uses
+use zksync_basic_types::Address;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::StorageProcessor;
+use zksync_types::ethereum::ETHOperation;
+// Local uses
+use crate::{database::DatabaseInterface, operator_keys::OperatorKeys};
+
+/// Result of comparing the stored nonce of the operator key with the chain.
+#[derive(Debug, PartialEq)]
+pub(super) struct NonceReconciliation {
+    /// Nonce the next transaction of the key has to be sent with.
+    pub next_nonce: i64,
+    /// Nonces of the unconfirmed operations the node doesn't know the transactions of.
+    pub orphaned_nonces: Vec<u64>,
+    /// Nonces the node waits for, but no operation is going to send a transaction with.
+    /// Such gaps can't be repaired automatically.
+    pub missing_nonces: Vec<u64>,
+}
+
+/// Determines the next nonce of the key from the pending nonce of its account
+/// and the nonces of the unconfirmed operations of the key.
+pub(super) fn reconcile_nonce(
+    pending_nonce: u64,
+    unconfirmed_nonces: &[u64],
+) -> NonceReconciliation {
+    let orphaned_nonces: Vec<u64> = unconfirmed_nonces
+        .iter()
+        .copied()
+        .filter(|&nonce| nonce >= pending_nonce)
+        .collect();
+    let next_nonce = orphaned_nonces
+        .iter()
+        .max()
+        .map_or(pending_nonce, |&nonce| nonce + 1);
+    let missing_nonces = (pending_nonce..next_nonce)
+        .filter(|nonce| !orphaned_nonces.contains(nonce))
+        .collect();
+
+    NonceReconciliation {
+        next_nonce: next_nonce as i64,
+        orphaned_nonces,
+        missing_nonces,
+    }
+}
+
+/// Reconciles the stored nonces of all the operator keys with the chain and marks
+/// the orphaned operations as stuck at the `current_block`.
+pub(super) async fn reconcile_nonces<DB: DatabaseInterface>(
+    db: &DB,
+    connection: &mut StorageProcessor<'_>,
+    main_ethereum: &EthereumGateway,
+    operator_keys: &OperatorKeys,
+    ongoing_ops: &mut VecDeque<ETHOperation>,
+    current_block: u64,
+) -> anyhow::Result<()> {
+    for sender in operator_keys.senders() {
+        let ethereum = operator_keys.ethereum(main_ethereum, sender)?;
+        let latest_nonce = ethereum.current_nonce().await?.as_u64();
+        let pending_nonce = ethereum.pending_nonce().await?.as_u64();
+        let stored_nonce = db.load_nonce(connection, sender).await?;
+        let unconfirmed_nonces: Vec<u64> = ongoing_ops
+            .iter()
+            .filter(|op| op.sender == sender)
+            .map(|op| op.nonce.as_u64())
+            .collect();
+
+        vlog::info!(
+            "Operator key {}: stored nonce {}, latest nonce {}, pending nonce {}, {} unconfirmed operations",
+            key_description(sender),
+            stored_nonce,
+            latest_nonce,
+            pending_nonce,
+            unconfirmed_nonces.len()
+        );
+        let reconciliation = reconcile_nonce(pending_nonce, &unconfirmed_nonces);
+
+        if reconciliation.next_nonce != stored_nonce {
+            vlog::warn!(
+                "Stored nonce {} of the operator key {} diverged from the chain, the next nonce is {}",
+                stored_nonce,
+                key_description(sender),
+                reconciliation.next_nonce
+            );
+            db.set_nonce(connection, sender, reconciliation.next_nonce)
+                .await?;
+            metrics::increment_counter!("eth_sender.nonce_reconciliation.nonce_reset");
+        }
+        if !reconciliation.orphaned_nonces.is_empty() {
+            vlog::warn!(
+                "Transactions of the operator key {} with nonces {:?} are unknown to the node, they will be resent",
+                key_description(sender),
+                reconciliation.orphaned_nonces
+            );
+            metrics::counter!(
+                "eth_sender.nonce_reconciliation.orphaned_txs",
+                reconciliation.orphaned_nonces.len() as u64
+            );
+            for op in ongoing_ops.iter_mut() {
+                if op.sender == sender
+                    && reconciliation.orphaned_nonces.contains(&op.nonce.as_u64())
+                {
+                    op.last_deadline_block = current_block;
+                }
+            }
+        }
+        if !reconciliation.missing_nonces.is_empty() {
+            vlog::error!(
+                "No transactions of the operator key {} are going to use nonces {:?}, the following transactions are stalled",
+                key_description(sender),
+                reconciliation.missing_nonces
+            );
+            metrics::counter!(
+                "eth_sender.nonce_reconciliation.missing_nonces",
+                reconciliation.missing_nonces.len() as u64
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn key_description(sender: Option<Address>) -> String {
+    sender.map_or_else(|| "main".to_owned(), |address| format!("{:#x}", address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_sync() {
+        // No operations in flight.
+        assert_eq!(
+            reconcile_nonce(5, &[]),
+            NonceReconciliation {
+                next_nonce: 5,
+                orphaned_nonces: vec![],
+                missing_nonces: vec![],
+            }
+        );
+        // The transactions of the operations are in the mempool.
+        assert_eq!(
+            reconcile_nonce(5, &[3, 4]),
+            NonceReconciliation {
+                next_nonce: 5,
+                orphaned_nonces: vec![],
+                missing_nonces: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn chain_ahead() {
+        // The transactions were sent, but not stored.
+        assert_eq!(
+            reconcile_nonce(7, &[4]),
+            NonceReconciliation {
+                next_nonce: 7,
+                orphaned_nonces: vec![],
+                missing_nonces: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn orphaned_transactions() {
+        // The stored transactions never reached the node.
+        assert_eq!(
+            reconcile_nonce(3, &[3, 4]),
+            NonceReconciliation {
+                next_nonce: 5,
+                orphaned_nonces: vec![3, 4],
+                missing_nonces: vec![],
+            }
+        );
+        // The nonces without operations are released.
+        assert_eq!(
+            reconcile_nonce(3, &[2, 3, 4]),
+            NonceReconciliation {
+                next_nonce: 5,
+                orphaned_nonces: vec![3, 4],
+                missing_nonces: vec![],
+            }
+        );
+        assert_eq!(
+            reconcile_nonce(3, &[]),
+            NonceReconciliation {
+                next_nonce: 3,
+                orphaned_nonces: vec![],
+                missing_nonces: vec![],
+            }
+        );
+        // The gap before the orphaned transaction is reported.
+        assert_eq!(
+            reconcile_nonce(3, &[5]),
+            NonceReconciliation {
+                next_nonce: 6,
+                orphaned_nonces: vec![5],
+                missing_nonces: vec![3, 4],
+            }
+        );
+    }
+}
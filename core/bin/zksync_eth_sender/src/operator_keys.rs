@@ -0,0 +1,366 @@
+//! Pool of the operator keys the Ethereum transactions are signed with.
+//!
+//! Besides the main operator key, the transactions may be signed with the additional keys,
+//! each having its own nonce tracked in the database. The operations are assigned to the keys
+//! either in turns or by their type.
+//!
+//! Transactions of the different accounts may be included in any order, while the contract
+//! expects some operations to come in order: the operations of the same type follow each other,
+//! and the blocks are proven once committed and executed once proven. Thus the operation is
+//! never sent while the operations it has to go after are in flight with another key, see
+//! `must_follow`. The round-robin assignment keeps the key of such operations, so it switches
+//! to the next key once the operation doesn't have to wait for anything, and the role-based one
+//! makes the operation wait for them. The operations independent of each other, e.g. the commit
+//! of the new blocks and the execution of the old ones, are in flight with different keys at once.
+//!
+//! The balances of the accounts are checked periodically. The accounts with the low balance are
+//! reported to the Mattermost webhook. Once the keys signing the commits run critically low on
+//! funds, the pool enters the safety mode: the new blocks are not committed, so the funds left
+//! are spent on proving and executing the committed ones instead of failing mid-sequence.
+
+// Built-in deps
+use std::time::Instant;
+// External uses
+use anyhow::format_err;
+// Workspace uses
+use zksync_basic_types::{Address, U256};
+use zksync_config::configs::eth_sender::{KeyAssignment, OperatorKeys as OperatorKeysConfig};
+use zksync_eth_client::EthereumGateway;
+use zksync_notifier::Notifier;
+use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
+
+/// Position of the operations of the type in the lifecycle of the blocks.
+fn block_stage(op_type: AggregatedActionType) -> u8 {
+    match op_type {
+        AggregatedActionType::CommitBlocks => 0,
+        AggregatedActionType::CreateProofBlocks
+        | AggregatedActionType::PublishProofBlocksOnchain => 1,
+        AggregatedActionType::ExecuteBlocks => 2,
+    }
+}
+
+/// Whether the contract accepts the operation only after the one in flight is included:
+/// the operations of the same type go in order, and the blocks are proven once committed
+/// and executed once proven.
+pub(super) fn must_follow(op: &AggregatedOperation, in_flight: &AggregatedOperation) -> bool {
+    let stage = block_stage(op.get_action_type());
+    let in_flight_stage = block_stage(in_flight.get_action_type());
+    let (_, last_block) = op.get_block_range();
+    let (in_flight_first_block, _) = in_flight.get_block_range();
+    stage == in_flight_stage || (in_flight_stage < stage && in_flight_first_block <= last_block)
+}
+
+#[derive(Debug)]
+struct OperatorKey {
+    /// Address of the operator account.
+    address: Address,
+    /// Client signing the transactions with the key, `None` for the main key:
+    /// the transactions are signed with it by the main client of `ETHSender`.
+    ethereum: Option<EthereumGateway>,
+    /// Whether the balance of the account was below the minimum at the last check.
+    low_balance: bool,
+    /// Whether the balance of the account was below the critical one at the last check.
+    critical_balance: bool,
+}
+
+impl OperatorKey {
+    /// Key as it's stored with the operation: `None` stands for the main key.
+    fn sender(&self) -> Option<Address> {
+        self.ethereum.as_ref().map(|_| self.address)
+    }
+}
+
+pub(super) struct OperatorKeys {
+    /// Keys of the pool, the main operator key goes first.
+    keys: Vec<OperatorKey>,
+    config: OperatorKeysConfig,
+    /// Index of the key the round-robin assignment takes next.
+    next_key: usize,
+    /// Timestamp of the last check of the accounts balances.
+    last_balance_check: Option<Instant>,
+    /// Whether the keys signing the commits are critically low on funds.
+    safety_mode: bool,
+    /// Notifier sending the low balance alerts, `None` if the webhook isn't configured.
+    notifier: Option<Notifier>,
+}
+
+impl OperatorKeys {
+    pub fn new(
+        main_address: Address,
+        additional_keys: Vec<(Address, EthereumGateway)>,
+        config: &OperatorKeysConfig,
+    ) -> Self {
+        let main_key = OperatorKey {
+            address: main_address,
+            ethereum: None,
+            low_balance: false,
+            critical_balance: false,
+        };
+        let keys: Vec<_> = std::iter::once(main_key)
+            .chain(
+                additional_keys
+                    .into_iter()
+                    .map(|(address, ethereum)| OperatorKey {
+                        address,
+                        ethereum: Some(ethereum),
+                        low_balance: false,
+                        critical_balance: false,
+                    }),
+            )
+            .collect();
+
+        if config.assignment == KeyAssignment::RoleBased {
+            for &index in &[config.commit_key, config.prove_key, config.execute_key] {
+                assert!(
+                    index < keys.len(),
+                    "Operator key index {} is out of the pool of {} keys",
+                    index,
+                    keys.len()
+                );
+            }
+        }
+
+        let webhook_url = reqwest::Url::parse(&config.webhook_url).ok();
+        Self {
+            keys,
+            config: config.clone(),
+            next_key: 0,
+            last_balance_check: None,
+            safety_mode: false,
+            notifier: webhook_url.map(Notifier::with_mattermost),
+        }
+    }
+
+    /// Additional keys of the pool with their clients.
+    pub fn additional_keys(&self) -> impl Iterator<Item = (Address, &EthereumGateway)> {
+        self.keys
+            .iter()
+            .filter_map(|key| Some((key.address, key.ethereum.as_ref()?)))
+    }
+
+    /// Keys of the pool as they're stored with the operations, `None` for the main key.
+    pub fn senders(&self) -> impl Iterator<Item = Option<Address>> + '_ {
+        self.keys.iter().map(OperatorKey::sender)
+    }
+
+    /// Returns the client signing the transactions with the key the operation is assigned to.
+    pub fn ethereum<'a>(
+        &'a self,
+        main_ethereum: &'a EthereumGateway,
+        sender: Option<Address>,
+    ) -> anyhow::Result<&'a EthereumGateway> {
+        self.keys
+            .iter()
+            .find(|key| key.sender() == sender)
+            .map(|key| key.ethereum.as_ref().unwrap_or(main_ethereum))
+            .ok_or_else(|| format_err!("Operator key {:?} is not configured", sender))
+    }
+
+    /// Chooses the key for the new operation. `preceding_sender` is the key of the last
+    /// operation in flight the new one has to go after, if there is any.
+    ///
+    /// Returns `None` for the main operator key.
+    pub fn assign(
+        &mut self,
+        op_type: AggregatedActionType,
+        preceding_sender: Option<Option<Address>>,
+    ) -> Option<Address> {
+        match self.config.assignment {
+            KeyAssignment::RoundRobin => {
+                // The operation would wait for the preceding one signed by another key,
+                // while the same key orders them by the nonces.
+                if let Some(sender) = preceding_sender {
+                    return sender;
+                }
+
+                let keys_count = self.keys.len();
+                let index = (0..keys_count)
+                    .map(|offset| (self.next_key + offset) % keys_count)
+                    .find(|&index| !self.keys[index].low_balance)
+                    .unwrap_or(self.next_key % keys_count);
+                self.next_key = (index + 1) % keys_count;
+                self.keys[index].sender()
+            }
+            KeyAssignment::RoleBased => {
+                let index = match op_type {
+                    AggregatedActionType::CommitBlocks => self.config.commit_key,
+                    AggregatedActionType::PublishProofBlocksOnchain => self.config.prove_key,
+                    AggregatedActionType::ExecuteBlocks => self.config.execute_key,
+                    AggregatedActionType::CreateProofBlocks => {
+                        panic!("Proof creation should never be sent to Ethereum")
+                    }
+                };
+                self.keys[index].sender()
+            }
+        }
+    }
+
+    /// Whether the new blocks must not be committed, since the keys signing
+    /// the commits are critically low on funds.
+    pub fn is_safety_mode(&self) -> bool {
+        self.safety_mode
+    }
+
+    /// Reports the balances of the operator accounts and marks the ones below the thresholds.
+    /// This method is intended to be invoked periodically, the balances are checked
+    /// once per the configured interval.
+    pub async fn check_balances(&mut self, main_ethereum: &EthereumGateway) {
+        if let Some(last_check) = self.last_balance_check {
+            if last_check.elapsed() < self.config.balance_check_interval() {
+                return;
+            }
+        }
+        self.last_balance_check = Some(Instant::now());
+
+        let min_balance = U256::from(self.config.min_balance);
+        let critical_balance = U256::from(self.config.critical_balance);
+        let mut alerts = Vec::new();
+        for key in &mut self.keys {
+            let ethereum = key.ethereum.as_ref().unwrap_or(main_ethereum);
+            match ethereum.sender_eth_balance().await {
+                Ok(balance) => {
+                    metrics::gauge!(
+                        "eth_sender.operator_balance",
+                        balance.low_u128() as f64,
+                        "address" => format!("{:#x}", key.address)
+                    );
+                    let was_low = key.low_balance;
+                    key.low_balance = balance < min_balance;
+                    key.critical_balance = balance < critical_balance;
+                    if key.low_balance {
+                        vlog::warn!(
+                            "Balance of the operator account {:#x} is low: {} wei",
+                            key.address,
+                            balance
+                        );
+                    }
+                    if key.low_balance && !was_low {
+                        alerts.push(format!(
+                            "Balance of the operator account {:#x} is low: {} wei",
+                            key.address, balance
+                        ));
+                    }
+                }
+                Err(err) => {
+                    vlog::warn!(
+                        "Cannot check the balance of the operator account {:#x}: {}",
+                        key.address,
+                        err
+                    );
+                }
+            }
+        }
+
+        let safety_mode = self.commit_keys_critical();
+        if safety_mode != self.safety_mode {
+            self.safety_mode = safety_mode;
+            let message = if safety_mode {
+                "Operator accounts signing the commits are critically low on funds, \
+                the new blocks are not committed until they are topped up"
+            } else {
+                "Operator accounts signing the commits are topped up, the new blocks are committed again"
+            };
+            vlog::warn!("{}", message);
+            alerts.push(message.to_owned());
+        }
+        metrics::gauge!("eth_sender.safety_mode", self.safety_mode as u8 as f64);
+
+        if let Some(notifier) = &self.notifier {
+            for alert in alerts {
+                if let Err(err) = notifier.send_operator_balance_notify(&alert).await {
+                    vlog::warn!("Can't send the operator balance alert: {}", err);
+                }
+            }
+        }
+    }
+
+    /// Whether all the keys that may sign the commits have the critical balance.
+    fn commit_keys_critical(&self) -> bool {
+        match self.config.assignment {
+            KeyAssignment::RoundRobin => self.keys.iter().all(|key| key.critical_balance),
+            KeyAssignment::RoleBased => self.keys[self.config.commit_key].critical_balance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_eth_client::clients::mock::MockEthereum;
+
+    fn operator_keys(assignment: KeyAssignment) -> OperatorKeys {
+        let additional_keys = (1..=2)
+            .map(|address| {
+                (
+                    Address::from_low_u64_be(address),
+                    EthereumGateway::Mock(MockEthereum::default()),
+                )
+            })
+            .collect();
+        OperatorKeys::new(
+            Address::zero(),
+            additional_keys,
+            &OperatorKeysConfig {
+                assignment,
+                private_keys: Vec::new(),
+                commit_key: 0,
+                prove_key: 1,
+                execute_key: 2,
+                min_balance: 0,
+                critical_balance: 0,
+                balance_check_interval: 60,
+                webhook_url: String::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn round_robin_assignment() {
+        let mut keys = operator_keys(KeyAssignment::RoundRobin);
+        let commit = AggregatedActionType::CommitBlocks;
+        let first_key = Some(Address::from_low_u64_be(1));
+        let second_key = Some(Address::from_low_u64_be(2));
+
+        assert_eq!(keys.assign(commit, None), None);
+        assert_eq!(keys.assign(commit, None), first_key);
+        // The key of the operations in flight is kept.
+        assert_eq!(keys.assign(commit, Some(first_key)), first_key);
+        assert_eq!(keys.assign(commit, None), second_key);
+        assert_eq!(keys.assign(commit, None), None);
+
+        // The keys with the low balance are skipped.
+        keys.keys[1].low_balance = true;
+        assert_eq!(keys.assign(commit, None), second_key);
+        assert_eq!(keys.assign(commit, None), None);
+
+        // Unless all of them have the low balance.
+        for key in &mut keys.keys {
+            key.low_balance = true;
+        }
+        assert_eq!(keys.assign(commit, None), first_key);
+    }
+
+    #[test]
+    fn role_based_assignment() {
+        let mut keys = operator_keys(KeyAssignment::RoleBased);
+
+        assert_eq!(keys.assign(AggregatedActionType::CommitBlocks, None), None);
+        assert_eq!(
+            keys.assign(AggregatedActionType::PublishProofBlocksOnchain, Some(None)),
+            Some(Address::from_low_u64_be(1))
+        );
+        assert_eq!(
+            keys.assign(AggregatedActionType::ExecuteBlocks, None),
+            Some(Address::from_low_u64_be(2))
+        );
+
+        let main_ethereum = EthereumGateway::Mock(MockEthereum::default());
+        assert!(keys
+            .ethereum(&main_ethereum, Some(Address::from_low_u64_be(2)))
+            .is_ok());
+        assert!(keys
+            .ethereum(&main_ethereum, Some(Address::from_low_u64_be(3)))
+            .is_err());
+        assert_eq!(keys.additional_keys().count(), 2);
+    }
+}
@@ -0,0 +1,165 @@
+//! Restrictions on the transactions signed with the operator key.
+//!
+//! The policy is checked by `ETHSender` before the transaction is passed to the signer,
+//! so a transaction violating it is never signed. Every check, successful or not, is
+//! recorded to the operator key audit log.
+
+// Built-in deps
+use std::collections::HashSet;
+use std::fmt;
+// External uses
+use web3::{contract::Options, types::U256};
+// Workspace uses
+use zksync_config::configs::eth_sender::SigningPolicy as SigningPolicyConfig;
+use zksync_types::Address;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum PolicyViolation {
+    ContractNotAllowed(Address),
+    GasPriceNotSet,
+    GasPriceTooHigh {
+        gas_price: U256,
+        max_gas_price: U256,
+    },
+    ValueTooHigh {
+        value: U256,
+        max_value: U256,
+    },
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContractNotAllowed(address) => {
+                write!(f, "contract {:#x} is not allowed", address)
+            }
+            Self::GasPriceNotSet => write!(f, "gas price is not set"),
+            Self::GasPriceTooHigh {
+                gas_price,
+                max_gas_price,
+            } => write!(
+                f,
+                "gas price {} exceeds the limit of {}",
+                gas_price, max_gas_price
+            ),
+            Self::ValueTooHigh { value, max_value } => {
+                write!(f, "value {} exceeds the limit of {}", value, max_value)
+            }
+        }
+    }
+}
+
+/// Signing policy of the operator key.
+#[derive(Debug, Clone)]
+pub(super) struct SigningPolicy {
+    allowed_contracts: HashSet<Address>,
+    max_gas_price: U256,
+    max_value: U256,
+}
+
+impl SigningPolicy {
+    /// Creates the policy, the zkSync contract is always allowed.
+    pub fn new(config: &SigningPolicyConfig, main_contract: Address) -> Self {
+        let mut allowed_contracts: HashSet<_> = config.allowed_contracts.iter().copied().collect();
+        allowed_contracts.insert(main_contract);
+        Self {
+            allowed_contracts,
+            max_gas_price: config.max_gas_price.into(),
+            max_value: config.max_value.into(),
+        }
+    }
+
+    /// Checks the transaction to `contract_addr` with the given options.
+    /// The gas price must be set explicitly, since otherwise it's chosen by the client.
+    /// The max fee per gas of the EIP-1559 transaction is checked as its gas price.
+    pub fn check(&self, contract_addr: Address, options: &Options) -> Result<(), PolicyViolation> {
+        if !self.allowed_contracts.contains(&contract_addr) {
+            return Err(PolicyViolation::ContractNotAllowed(contract_addr));
+        }
+        let gas_price = options
+            .max_fee_per_gas
+            .or(options.gas_price)
+            .ok_or(PolicyViolation::GasPriceNotSet)?;
+        if gas_price > self.max_gas_price {
+            return Err(PolicyViolation::GasPriceTooHigh {
+                gas_price,
+                max_gas_price: self.max_gas_price,
+            });
+        }
+        let value = options.value.unwrap_or_default();
+        if value > self.max_value {
+            return Err(PolicyViolation::ValueTooHigh {
+                value,
+                max_value: self.max_value,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_policy() {
+        let main_contract = Address::repeat_byte(1);
+        let other_contract = Address::repeat_byte(2);
+        let config = SigningPolicyConfig {
+            allowed_contracts: vec![other_contract],
+            max_gas_price: 100,
+            max_value: 0,
+        };
+        let policy = SigningPolicy::new(&config, main_contract);
+
+        let options = Options::with(|opt| opt.gas_price = Some(100.into()));
+        assert_eq!(policy.check(main_contract, &options), Ok(()));
+        assert_eq!(policy.check(other_contract, &options), Ok(()));
+
+        let unknown_contract = Address::repeat_byte(3);
+        assert_eq!(
+            policy.check(unknown_contract, &options),
+            Err(PolicyViolation::ContractNotAllowed(unknown_contract))
+        );
+        assert_eq!(
+            policy.check(main_contract, &Options::default()),
+            Err(PolicyViolation::GasPriceNotSet)
+        );
+        assert_eq!(
+            policy.check(
+                main_contract,
+                &Options::with(|opt| opt.gas_price = Some(101.into()))
+            ),
+            Err(PolicyViolation::GasPriceTooHigh {
+                gas_price: 101.into(),
+                max_gas_price: 100.into(),
+            })
+        );
+        assert_eq!(
+            policy.check(
+                main_contract,
+                &Options::with(|opt| {
+                    opt.max_fee_per_gas = Some(101.into());
+                    opt.max_priority_fee_per_gas = Some(1.into());
+                })
+            ),
+            Err(PolicyViolation::GasPriceTooHigh {
+                gas_price: 101.into(),
+                max_gas_price: 100.into(),
+            })
+        );
+        assert_eq!(
+            policy.check(
+                main_contract,
+                &Options::with(|opt| {
+                    opt.gas_price = Some(1.into());
+                    opt.value = Some(1.into());
+                })
+            ),
+            Err(PolicyViolation::ValueTooHigh {
+                value: 1.into(),
+                max_value: 0.into(),
+            })
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Alerts on the operations not confirmed within the hard deadline.
+//!
+//! The stuck transactions are replaced with the escalated fees every `expected_wait_time_block`
+//! blocks, but the fees never exceed the configured caps, so the operation may stay unconfirmed
+//! regardless of the replacements (e.g. during the gas price spikes). Once the operation isn't
+//! confirmed within the hard deadline since its first transaction was sent, it's reported to
+//! the log, the metrics and the Mattermost webhook, so on-call can intervene.
+
+// Built-in deps
+use std::collections::HashSet;
+// Workspace uses
+use zksync_config::ETHSenderConfig;
+use zksync_notifier::Notifier;
+use zksync_types::ethereum::ETHOperation;
+
+pub(super) struct StuckTxAlert {
+    /// Amount of blocks since the first transaction was sent after which the operation is reported.
+    hard_deadline_blocks: u64,
+    /// Amount of blocks between sending the transaction and its deadline.
+    expected_wait_time_block: u64,
+    /// Notifier sending the alerts, `None` if the webhook isn't configured.
+    notifier: Option<Notifier>,
+    /// Identifiers of the reported operations which are not confirmed yet.
+    reported_ops: HashSet<i64>,
+}
+
+impl StuckTxAlert {
+    pub fn new(options: &ETHSenderConfig) -> Self {
+        let webhook_url = reqwest::Url::parse(&options.stuck_tx_alert.webhook_url).ok();
+        Self {
+            hard_deadline_blocks: options.stuck_tx_alert.hard_deadline_blocks,
+            expected_wait_time_block: options.sender.expected_wait_time_block,
+            notifier: webhook_url.map(Notifier::with_mattermost),
+            reported_ops: HashSet::new(),
+        }
+    }
+
+    /// Amount of blocks passed since the first transaction of the operation was sent.
+    fn waited_blocks(&self, op: &ETHOperation, current_block: u64) -> u64 {
+        let sent_block = op
+            .first_deadline_block
+            .saturating_sub(self.expected_wait_time_block);
+        current_block.saturating_sub(sent_block)
+    }
+
+    /// Reports the unconfirmed operation if it has missed the hard deadline.
+    /// Every operation is reported once.
+    pub async fn check(&mut self, op: &ETHOperation, current_block: u64) {
+        let waited_blocks = self.waited_blocks(op, current_block);
+        if waited_blocks < self.hard_deadline_blocks || !self.reported_ops.insert(op.id) {
+            return;
+        }
+
+        let message = format!(
+            "ETH Operation <id: {}, type: {:?}> is not confirmed for {} blocks, {} transactions were sent. Last tx hash: <{:#x}>",
+            op.id,
+            op.op_type,
+            waited_blocks,
+            op.used_tx_hashes.len(),
+            op.used_tx_hashes.last().copied().unwrap_or_default(),
+        );
+        vlog::error!("{}", message);
+        metrics::increment_counter!("eth_sender.stuck_tx_alerts", "op_type" => op.op_type.to_string());
+        metrics::gauge!(
+            "eth_sender.stuck_operations",
+            self.reported_ops.len() as f64
+        );
+
+        if let Some(notifier) = &self.notifier {
+            if let Err(err) = notifier.send_stuck_tx_notify(&message).await {
+                vlog::warn!("Can't send the stuck operation alert: {}", err);
+            }
+        }
+    }
+
+    /// Clears the alert of the confirmed operation.
+    pub fn report_confirmed(&mut self, op: &ETHOperation) {
+        if self.reported_ops.remove(&op.id) {
+            vlog::info!("Stuck ETH Operation <id: {}> is finally confirmed", op.id);
+            metrics::gauge!(
+                "eth_sender.stuck_operations",
+                self.reported_ops.len() as f64
+            );
+        }
+    }
+
+    /// Whether the operation was reported and isn't confirmed yet.
+    #[cfg(test)]
+    pub fn is_reported(&self, op_id: i64) -> bool {
+        self.reported_ops.contains(&op_id)
+    }
+}
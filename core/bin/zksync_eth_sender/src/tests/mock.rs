@@ -1,18 +1,24 @@
 //! Mocking utilities for tests.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
 // External uses
 use tokio::sync::RwLock;
 use web3::contract::Options;
 use zksync_basic_types::{BlockNumber, H256, U256};
 // Workspace uses
-use zksync_config::configs::eth_sender::{ETHSenderConfig, GasLimit, Sender};
+use zksync_config::configs::eth_sender::{
+    ETHSenderConfig, FeeMarket, GasLimit, GasPriceStrategy, KeyAssignment, OperatorKeys, Sender,
+    SigningPolicy, StuckTxAlert, TxSimulation,
+};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::{ethereum::records::ETHParams, StorageProcessor};
-use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
-use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
+use zksync_types::aggregated_operations::{
+    AggregatedActionType, AggregatedOperation, BlocksExecuteOperation,
+};
+use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse, OperatorKeyUsage};
+use zksync_types::Address;
 // Local uses
 use super::ETHSender;
 use crate::database::DatabaseInterface;
@@ -26,6 +32,13 @@ pub(crate) struct MockDatabase {
     aggregated_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     unprocessed_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     eth_parameters: RwLock<ETHParams>,
+    /// Whether the execute operation completes its withdrawals, by the operation id.
+    scheduled_withdrawals: RwLock<HashMap<i64, bool>>,
+    operator_key_usages: RwLock<Vec<OperatorKeyUsage>>,
+    /// Next nonces of the operator keys, `None` for the main key.
+    operator_nonces: RwLock<HashMap<Option<Address>, i64>>,
+    /// Last correct block of the block revert in progress.
+    pending_block_revert: RwLock<Option<BlockNumber>>,
 }
 
 impl MockDatabase {
@@ -36,14 +49,35 @@ impl MockDatabase {
         unprocessed_operations: Vec<(i64, AggregatedOperation)>,
         eth_parameters: ETHParams,
     ) -> Self {
+        // The nonce of the main key follows the restored operations.
+        let main_nonce = eth_operations
+            .iter()
+            .filter(|op| op.sender.is_none())
+            .count() as i64;
+        let operator_nonces = std::iter::once((None, main_nonce)).collect();
+
         Self {
             eth_operations: RwLock::new(eth_operations),
             aggregated_operations: RwLock::new(aggregated_operations),
             unprocessed_operations: RwLock::new(unprocessed_operations),
             eth_parameters: RwLock::new(eth_parameters),
+            scheduled_withdrawals: RwLock::new(HashMap::new()),
+            operator_key_usages: RwLock::new(Vec::new()),
+            operator_nonces: RwLock::new(operator_nonces),
+            pending_block_revert: RwLock::new(None),
         }
     }
 
+    /// Returns the recorded operator key audit log.
+    pub async fn operator_key_usages(&self) -> Vec<OperatorKeyUsage> {
+        self.operator_key_usages.read().await.clone()
+    }
+
+    /// Simulates the start and the end of the block revert by the core.
+    pub async fn set_pending_block_revert(&self, last_correct_block: Option<BlockNumber>) {
+        *self.pending_block_revert.write().await = last_correct_block;
+    }
+
     pub async fn update_gas_price_limit(&self, value: i64) -> anyhow::Result<()> {
         let mut eth_parameters = self.eth_parameters.write().await;
         eth_parameters.gas_price_limit = value;
@@ -204,11 +238,20 @@ impl DatabaseInterface for MockDatabase {
         op: Option<(i64, AggregatedOperation)>,
         deadline_block: i64,
         used_gas_price: U256,
+        used_priority_fee: Option<U256>,
+        sender: Option<Address>,
         encoded_tx_data: Vec<u8>,
     ) -> anyhow::Result<InsertedOperationResponse> {
         let mut eth_operations = self.eth_operations.write().await;
         let id = eth_operations.len() as i64;
-        let nonce = eth_operations.len();
+        let nonce = {
+            let mut operator_nonces = self.operator_nonces.write().await;
+            let nonce = operator_nonces.get_mut(&sender).ok_or_else(|| {
+                anyhow::format_err!("Nonce of the operator key {:?} is not initialized", sender)
+            })?;
+            *nonce += 1;
+            *nonce as usize - 1
+        };
 
         // Store with the assigned ID.
         let eth_operation = ETHOperation {
@@ -217,7 +260,10 @@ impl DatabaseInterface for MockDatabase {
             op,
             nonce: nonce.into(),
             last_deadline_block: deadline_block as u64,
+            first_deadline_block: deadline_block as u64,
             last_used_gas_price: used_gas_price,
+            last_used_priority_fee: used_priority_fee,
+            sender,
             used_tx_hashes: vec![],
             encoded_tx_data,
             confirmed: false,
@@ -234,6 +280,47 @@ impl DatabaseInterface for MockDatabase {
         Ok(response)
     }
 
+    async fn initialize_operator_nonce(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        address: Address,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        self.operator_nonces
+            .write()
+            .await
+            .entry(Some(address))
+            .or_insert(nonce);
+
+        Ok(())
+    }
+
+    async fn load_nonce(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+    ) -> anyhow::Result<i64> {
+        self.operator_nonces
+            .read()
+            .await
+            .get(&sender)
+            .copied()
+            .ok_or_else(|| {
+                anyhow::format_err!("Nonce of the operator key {:?} is not initialized", sender)
+            })
+    }
+
+    async fn set_nonce(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        sender: Option<Address>,
+        nonce: i64,
+    ) -> anyhow::Result<()> {
+        self.operator_nonces.write().await.insert(sender, nonce);
+
+        Ok(())
+    }
+
     /// Adds a tx hash entry associated with some Ethereum operation to the database.
     async fn add_hash_entry(
         &self,
@@ -261,6 +348,7 @@ impl DatabaseInterface for MockDatabase {
         eth_op_id: EthOpId,
         new_deadline_block: i64,
         new_gas_value: U256,
+        new_priority_fee: Option<U256>,
     ) -> anyhow::Result<()> {
         let mut eth_operations = self.eth_operations.write().await;
         let eth_op = eth_operations
@@ -270,6 +358,7 @@ impl DatabaseInterface for MockDatabase {
         if let Some(eth_op) = eth_op {
             eth_op.last_deadline_block = new_deadline_block as u64;
             eth_op.last_used_gas_price = new_gas_value;
+            eth_op.last_used_priority_fee = new_priority_fee;
         } else {
             panic!("Attempt to update tx that is not unconfirmed");
         }
@@ -323,6 +412,30 @@ impl DatabaseInterface for MockDatabase {
         Ok(eth_stats)
     }
 
+    async fn schedule_withdrawals(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        op_id: i64,
+        op: &BlocksExecuteOperation,
+    ) -> anyhow::Result<()> {
+        self.scheduled_withdrawals
+            .write()
+            .await
+            .insert(op_id, op.complete_withdrawals);
+
+        Ok(())
+    }
+
+    async fn completes_withdrawals(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        op_id: i64,
+    ) -> anyhow::Result<bool> {
+        let scheduled_withdrawals = self.scheduled_withdrawals.read().await;
+
+        Ok(scheduled_withdrawals.get(&op_id).copied().unwrap_or(false))
+    }
+
     async fn is_previous_operation_confirmed(
         &self,
         _connection: &mut StorageProcessor<'_>,
@@ -355,6 +468,30 @@ impl DatabaseInterface for MockDatabase {
 
         Ok(confirmed)
     }
+
+    async fn record_operator_key_usage(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        usage: &OperatorKeyUsage,
+    ) -> anyhow::Result<()> {
+        self.operator_key_usages.write().await.push(usage.clone());
+
+        Ok(())
+    }
+
+    async fn lock_leader_term(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<i64> {
+        Ok(0)
+    }
+
+    async fn lock_pending_block_revert(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<BlockNumber>> {
+        Ok(*self.pending_block_revert.read().await)
+    }
 }
 
 /// Creates a default `ETHParams` for use by mock `ETHSender` .
@@ -379,6 +516,8 @@ pub(crate) async fn default_eth_sender() -> ETHSender<MockDatabase> {
         Vec::new(),
         Vec::new(),
         default_eth_parameters(),
+        default_operator_keys(),
+        0,
     )
     .await
 }
@@ -393,17 +532,66 @@ pub(crate) async fn concurrent_eth_sender(max_txs_in_flight: u64) -> ETHSender<M
         Vec::new(),
         Vec::new(),
         default_eth_parameters(),
+        default_operator_keys(),
+        0,
+    )
+    .await
+}
+
+/// Creates an `ETHSender` with mock Ethereum connection/database, no operations in DB,
+/// and the additional operator keys with addresses `1..=additional_keys`.
+pub(crate) async fn multi_key_eth_sender(
+    max_txs_in_flight: u64,
+    assignment: KeyAssignment,
+    additional_keys: u64,
+) -> ETHSender<MockDatabase> {
+    let operator_keys = OperatorKeys {
+        assignment,
+        private_keys: (1..=additional_keys).map(H256::from_low_u64_be).collect(),
+        prove_key: additional_keys.min(1) as usize,
+        execute_key: additional_keys.min(2) as usize,
+        ..default_operator_keys()
+    };
+    build_eth_sender(
+        max_txs_in_flight,
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        default_eth_parameters(),
+        operator_keys,
+        0,
+    )
+    .await
+}
+
+/// Creates an `ETHSender` with mock Ethereum connection/database, no operations in DB,
+/// and the operator account considered critically low on funds below `critical_balance`.
+pub(crate) async fn low_funds_eth_sender(critical_balance: u64) -> ETHSender<MockDatabase> {
+    let operator_keys = OperatorKeys {
+        critical_balance,
+        ..default_operator_keys()
+    };
+    build_eth_sender(
+        1,
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        default_eth_parameters(),
+        operator_keys,
+        0,
     )
     .await
 }
 
 /// Creates an `ETHSender` with mock Ethereum connection/database and restores its state "from DB".
+/// The operator account has sent the transactions with nonces below `chain_nonce`.
 /// Returns the `ETHSender` itself along with communication channels to interact with it.
 pub(crate) async fn restored_eth_sender(
     eth_operations: Vec<ETHOperation>,
     aggregated_operations: Vec<(i64, AggregatedOperation)>,
     unprocessed_operations: Vec<(i64, AggregatedOperation)>,
     eth_parameters: ETHParams,
+    chain_nonce: u64,
 ) -> ETHSender<MockDatabase> {
     const MAX_TXS_IN_FLIGHT: u64 = 1;
 
@@ -413,6 +601,8 @@ pub(crate) async fn restored_eth_sender(
         aggregated_operations,
         unprocessed_operations,
         eth_parameters,
+        default_operator_keys(),
+        chain_nonce,
     )
     .await
 }
@@ -424,8 +614,12 @@ async fn build_eth_sender(
     aggregated_operations: Vec<(i64, AggregatedOperation)>,
     unprocessed_operations: Vec<(i64, AggregatedOperation)>,
     eth_parameters: ETHParams,
+    operator_keys: OperatorKeys,
+    chain_nonce: u64,
 ) -> ETHSender<MockDatabase> {
-    let ethereum = EthereumGateway::Mock(MockEthereum::default());
+    let mut ethereum = MockEthereum::default();
+    ethereum.set_nonce(chain_nonce);
+    let ethereum = EthereumGateway::Mock(ethereum);
     let db = MockDatabase::with_restorable_state(
         eth_operations,
         aggregated_operations,
@@ -440,18 +634,73 @@ async fn build_eth_sender(
             wait_confirmations: super::WAIT_CONFIRMATIONS,
             tx_poll_period: 0,
             is_enabled: true,
+            complete_withdrawals_on_execute: true,
+            max_withdrawals_per_execute_tx: 100,
             operator_commit_eth_addr: Default::default(),
             operator_private_key: Default::default(),
         },
         gas_price_limit: GasLimit {
+            strategy: GasPriceStrategy::NodeOracle,
             default: 1000,
             sample_interval: 15,
             update_interval: 15,
             scale_factor: 1.0f64,
         },
+        signing_policy: SigningPolicy {
+            allowed_contracts: Vec::new(),
+            max_gas_price: u64::MAX,
+            max_value: 0,
+        },
+        fee_market: default_fee_market(false),
+        operator_keys,
+        stuck_tx_alert: StuckTxAlert {
+            hard_deadline_blocks: super::HARD_DEADLINE_BLOCKS,
+            webhook_url: String::new(),
+        },
+        tx_simulation: TxSimulation {
+            enabled: true,
+            pause_blocks: super::SIMULATION_PAUSE_BLOCKS,
+        },
     };
+    // The mock clients don't sign anything, so the addresses of the keys are arbitrary.
+    let additional_keys = (1..=options.operator_keys.private_keys.len() as u64)
+        .map(|address| {
+            (
+                Address::from_low_u64_be(address),
+                EthereumGateway::Mock(MockEthereum::default()),
+            )
+        })
+        .collect();
+
+    ETHSender::new(options, db, ethereum, additional_keys, Address::zero()).await
+}
 
-    ETHSender::new(options, db, ethereum).await
+/// Creates the operator keys config with no additional keys.
+fn default_operator_keys() -> OperatorKeys {
+    OperatorKeys {
+        assignment: KeyAssignment::RoundRobin,
+        private_keys: Vec::new(),
+        commit_key: 0,
+        prove_key: 0,
+        execute_key: 0,
+        min_balance: 0,
+        critical_balance: 0,
+        balance_check_interval: 60,
+        webhook_url: String::new(),
+    }
+}
+
+/// Creates the fee market config for the mock `ETHSender`.
+pub(crate) fn default_fee_market(eip1559_enabled: bool) -> FeeMarket {
+    FeeMarket {
+        eip1559_enabled,
+        fee_history_blocks: 10,
+        priority_fee_percentile: 50.0,
+        base_fee_multiplier: 2.0,
+        min_priority_fee: 1,
+        max_fee_per_gas_cap: u64::MAX,
+        escalation_percent: 15,
+    }
 }
 
 /// Behaves the same as `ETHSender::sign_new_tx`, but does not affect nonce.
@@ -484,7 +733,10 @@ pub(crate) async fn create_signed_tx(
         op: Some(aggregated_operation.clone()),
         nonce: signed_tx.nonce,
         last_deadline_block: deadline_block,
+        first_deadline_block: deadline_block,
         last_used_gas_price: signed_tx.gas_price,
+        last_used_priority_fee: signed_tx.max_priority_fee_per_gas,
+        sender: None,
         used_tx_hashes: vec![signed_tx.hash],
         encoded_tx_data: raw_tx,
         confirmed: false,
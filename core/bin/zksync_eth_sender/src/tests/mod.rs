@@ -1,14 +1,18 @@
 // Local uses
 use self::mock::{
     concurrent_eth_sender, create_signed_tx, default_eth_parameters, default_eth_sender,
-    restored_eth_sender,
+    low_funds_eth_sender, multi_key_eth_sender, restored_eth_sender,
 };
 use super::{transactions::TxCheckOutcome, ETHSender, TxCheckMode};
 use web3::types::U64;
+use zksync_config::configs::eth_sender::KeyAssignment;
 use zksync_eth_client::ethereum_gateway::ExecutedTxStatus;
+use zksync_types::BlockNumber;
 
 const EXPECTED_WAIT_TIME_BLOCKS: u64 = 30;
 const WAIT_CONFIRMATIONS: u64 = 3;
+const HARD_DEADLINE_BLOCKS: u64 = 90;
+const SIMULATION_PAUSE_BLOCKS: u64 = 10;
 
 pub mod mock;
 mod test_data;
@@ -139,7 +143,9 @@ async fn transaction_state() {
             )
             .await
             .unwrap(),
-        TxCheckOutcome::Committed
+        TxCheckOutcome::Committed {
+            inclusion_block: current_block
+        }
     );
 
     // Pending operation (no enough confirmations).
@@ -666,11 +672,13 @@ async fn restore_state() {
     eth_parameters.last_verified_block = 1;
     eth_parameters.last_executed_block = 1;
 
+    // The transactions of the restored operations are known to the node.
     let mut eth_sender = restored_eth_sender(
         stored_eth_operations,
         aggregated_operations.clone(),
         unprocessed_operations,
         eth_parameters,
+        3,
     )
     .await;
 
@@ -972,3 +980,501 @@ async fn concurrent_operations_order() {
         }
     }
 }
+
+/// Checks that the operations are signed with the keys of their roles, every key having its
+/// own nonce, and that the operation waits while the ones of the other keys are in flight.
+#[tokio::test]
+async fn role_based_operator_keys() {
+    let mut eth_sender = multi_key_eth_sender(3, KeyAssignment::RoleBased, 1).await;
+    let prove_key = Some(zksync_types::Address::from_low_u64_be(1));
+
+    let commit_op = test_data::COMMIT_BLOCKS_OPERATIONS[0].clone();
+    let verify_op = test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[0].clone();
+    eth_sender
+        .db
+        .send_aggregated_operation(commit_op)
+        .await
+        .unwrap();
+    eth_sender
+        .db
+        .send_aggregated_operation(verify_op)
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+
+    // Only the commit operation is sent, since its key differs from the prove one.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let commit_tx = eth_sender.ongoing_ops[0].clone();
+    assert_eq!(commit_tx.sender, None);
+    assert_eq!(commit_tx.nonce, 0.into());
+
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(commit_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+        .await;
+    // The commit operation is confirmed, and the prove one is sent on the next round.
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    eth_sender.proceed_next_operations(0).await;
+
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let verify_tx = &eth_sender.ongoing_ops[0];
+    assert_eq!(verify_tx.sender, prove_key);
+    // The prove key has its own nonce.
+    assert_eq!(verify_tx.nonce, 0.into());
+    eth_sender
+        .ethereum
+        .get_mock()
+        .unwrap()
+        .assert_sent(&verify_tx.used_tx_hashes[0].as_bytes().to_vec())
+        .await;
+}
+
+/// Checks that the operations independent of each other are in flight with different keys
+/// at once, without waiting for each other.
+#[tokio::test]
+async fn independent_operations_with_different_keys() {
+    let mut eth_sender = multi_key_eth_sender(3, KeyAssignment::RoleBased, 2).await;
+    let prove_key = Some(zksync_types::Address::from_low_u64_be(1));
+
+    let operations = vec![
+        test_data::COMMIT_BLOCKS_OPERATIONS[0].clone(),
+        test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[0].clone(),
+        test_data::COMMIT_BLOCKS_OPERATIONS[1].clone(),
+    ];
+    for operation in operations {
+        eth_sender
+            .db
+            .send_aggregated_operation(operation)
+            .await
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
+
+    // The proof of the block 1 waits for its commit.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let commit_tx = eth_sender.ongoing_ops[0].clone();
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(commit_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+
+    // The block 1 is proven while the block 2 is committed.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    let mut senders: Vec<_> = eth_sender.ongoing_ops.iter().map(|op| op.sender).collect();
+    senders.sort();
+    assert_eq!(senders, vec![None, prove_key]);
+}
+
+/// Checks that the new blocks are not committed while the operator account is critically
+/// low on funds, but the already committed ones are still proven.
+#[tokio::test]
+async fn operator_balance_safety_mode() {
+    // The balance of the mock account is zero.
+    let mut eth_sender = low_funds_eth_sender(1_000).await;
+
+    let operations = vec![
+        test_data::COMMIT_BLOCKS_OPERATIONS[0].clone(),
+        test_data::COMMIT_BLOCKS_OPERATIONS[1].clone(),
+        test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[0].clone(),
+    ];
+    for operation in operations {
+        eth_sender
+            .db
+            .send_aggregated_operation(operation)
+            .await
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
+
+    // The first block is committed before the balance is checked.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let commit_tx = eth_sender.ongoing_ops[0].clone();
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(commit_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+
+    eth_sender
+        .operator_keys
+        .check_balances(&eth_sender.ethereum)
+        .await;
+    assert!(eth_sender.operator_keys.is_safety_mode());
+
+    // The committed block is proven.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let verify_tx = eth_sender.ongoing_ops[0].clone();
+    assert_eq!(
+        verify_tx.op_type,
+        zksync_types::aggregated_operations::AggregatedActionType::PublishProofBlocksOnchain
+    );
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(verify_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+
+    // The next block isn't committed.
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+}
+
+/// Checks that the new operations are not sent while the blocks are being reverted,
+/// and that the operations to send are reloaded once the revert is finished.
+#[tokio::test]
+async fn block_revert_pause() {
+    let mut eth_sender = default_eth_sender().await;
+    eth_sender
+        .db
+        .send_aggregated_operation(test_data::commit_blocks_operation(0))
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+
+    // The revert is started while the operation is being sent.
+    eth_sender
+        .db
+        .set_pending_block_revert(Some(BlockNumber(0)))
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert_eq!(eth_sender.block_revert, Some(BlockNumber(0)));
+
+    // Nothing is sent until the revert is finished.
+    eth_sender.check_block_revert().await.unwrap();
+    eth_sender.proceed_next_operations(1).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+
+    eth_sender.db.set_pending_block_revert(None).await;
+    eth_sender.check_block_revert().await.unwrap();
+    assert_eq!(eth_sender.block_revert, None);
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(2).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+}
+
+/// Checks that the transaction violating the signing policy is not signed
+/// and that every signing attempt is recorded to the audit log.
+#[tokio::test]
+async fn signing_policy_rejection() {
+    use super::signing_policy::SigningPolicy;
+    use zksync_config::configs::eth_sender::SigningPolicy as SigningPolicyConfig;
+    use zksync_types::ethereum::OperatorKeyUsageOutcome;
+
+    let mut eth_sender = default_eth_sender().await;
+    let main_contract = eth_sender.main_contract;
+    let policy_config = |max_gas_price| SigningPolicyConfig {
+        allowed_contracts: Vec::new(),
+        max_gas_price,
+        max_value: 0,
+    };
+    eth_sender.signing_policy = SigningPolicy::new(&policy_config(0), main_contract);
+
+    let aggregated_operation = test_data::commit_blocks_operation(0);
+    eth_sender
+        .db
+        .send_aggregated_operation(aggregated_operation)
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // Gas price exceeds the limit, so the operation stays in the queue.
+    assert!(eth_sender.ongoing_ops.is_empty());
+    let usages = eth_sender.db.operator_key_usages().await;
+    assert_eq!(usages.len(), 1);
+    assert_eq!(usages[0].outcome, OperatorKeyUsageOutcome::Rejected);
+    assert!(usages[0].tx_hash.is_none());
+    assert!(usages[0].reason.is_some());
+
+    // Once the limit is raised, the operation is signed and sent.
+    eth_sender.signing_policy = SigningPolicy::new(&policy_config(u64::MAX), main_contract);
+    eth_sender.proceed_next_operations(1).await;
+
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let usages = eth_sender.db.operator_key_usages().await;
+    assert_eq!(usages.len(), 2);
+    assert_eq!(usages[1].outcome, OperatorKeyUsageOutcome::Signed);
+    assert_eq!(
+        usages[1].tx_hash,
+        eth_sender.ongoing_ops[0].used_tx_hashes.last().copied()
+    );
+}
+
+/// Checks that the EIP-1559 transaction is priced from the fee history
+/// and that both of its fees are raised once it's stuck.
+#[tokio::test]
+async fn eip1559_stuck_transaction() {
+    use self::mock::default_fee_market;
+    use super::fee_market::FeeMarket;
+
+    let mut eth_sender = default_eth_sender().await;
+    eth_sender.fee_market = FeeMarket::new(&default_fee_market(true));
+
+    eth_sender
+        .db
+        .send_aggregated_operation(test_data::commit_blocks_operation(0))
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // The mock base fee is the gas price of 100 and the priority fee is 10.
+    // The max fee per gas is the doubled base fee plus the priority fee.
+    let op = eth_sender.ongoing_ops[0].clone();
+    assert_eq!(op.last_used_gas_price, 210.into());
+    assert_eq!(op.last_used_priority_fee, Some(10.into()));
+
+    // Skip some blocks, so the transaction is considered stuck.
+    let mock = eth_sender.ethereum.get_mut_mock().unwrap();
+    let block_number = mock.block_number().await.unwrap().as_u64() + EXPECTED_WAIT_TIME_BLOCKS;
+    mock.set_block_number(block_number.into()).await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // Both fees are raised by 15%.
+    let op = eth_sender.ongoing_ops[0].clone();
+    assert_eq!(op.used_tx_hashes.len(), 2);
+    assert_eq!(op.last_used_gas_price, 241.into());
+    assert_eq!(op.last_used_priority_fee, Some(11.into()));
+}
+
+/// Checks that the operation not confirmed within the hard deadline is reported once,
+/// and the alert is cleared after its confirmation.
+#[tokio::test]
+async fn stuck_operation_alert() {
+    let mut eth_sender = default_eth_sender().await;
+
+    eth_sender
+        .db
+        .send_aggregated_operation(test_data::commit_blocks_operation(0))
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    let sent_block = eth_sender
+        .ethereum
+        .get_mock()
+        .unwrap()
+        .block_number()
+        .await
+        .unwrap()
+        .as_u64();
+
+    // The stuck transaction is replaced, but the operation is not reported yet.
+    let mock = eth_sender.ethereum.get_mut_mock().unwrap();
+    mock.set_block_number((sent_block + EXPECTED_WAIT_TIME_BLOCKS).into())
+        .await
+        .unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops[0].used_tx_hashes.len(), 2);
+    assert!(!eth_sender.stuck_tx_alert.is_reported(0));
+
+    // The hard deadline is counted from the first transaction.
+    let mock = eth_sender.ethereum.get_mut_mock().unwrap();
+    mock.set_block_number((sent_block + HARD_DEADLINE_BLOCKS).into())
+        .await
+        .unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.stuck_tx_alert.is_reported(0));
+
+    // The alert is cleared once the operation is confirmed.
+    let last_tx_hash = *eth_sender.ongoing_ops[0].used_tx_hashes.last().unwrap();
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(last_tx_hash, WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert!(!eth_sender.stuck_tx_alert.is_reported(0));
+}
+
+/// Checks that the transaction reverting in the simulation is not sent, and the operations
+/// of its type are paused until the pause is over.
+#[tokio::test]
+async fn simulation_revert() {
+    use zksync_eth_client::RevertReason;
+
+    let mut eth_sender = default_eth_sender().await;
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_revert_reason(Some(RevertReason {
+            data: Vec::new(),
+            reason: "i".to_string(),
+        }));
+
+    eth_sender
+        .db
+        .send_aggregated_operation(test_data::commit_blocks_operation(0))
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // The operation is not stored, and the transaction is not signed.
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert!(eth_sender.db.operator_key_usages().await.is_empty());
+    let mock = eth_sender.ethereum.get_mut_mock().unwrap();
+    let paused_block = mock.block_number().await.unwrap().as_u64();
+
+    // The commits are paused even though the transaction would succeed now.
+    mock.set_revert_reason(None);
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+
+    // The transaction is simulated again and sent once the pause is over.
+    let mock = eth_sender.ethereum.get_mut_mock().unwrap();
+    mock.set_block_number((paused_block + SIMULATION_PAUSE_BLOCKS).into())
+        .await
+        .unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    // The reverted transaction hasn't taken the nonce.
+    assert_eq!(eth_sender.ongoing_ops[0].nonce, 0.into());
+}
+
+/// Checks that the transaction is not simulated while the operations it has to go after
+/// are in flight, since it would revert until they're included.
+#[tokio::test]
+async fn simulation_skipped_with_preceding_operations() {
+    use zksync_eth_client::RevertReason;
+
+    let mut eth_sender = concurrent_eth_sender(2).await;
+    eth_sender
+        .db
+        .send_aggregated_operation(test_data::commit_blocks_operation(0))
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_revert_reason(Some(RevertReason {
+            data: Vec::new(),
+            reason: "i".to_string(),
+        }));
+    eth_sender
+        .db
+        .send_aggregated_operation(test_data::commit_blocks_operation(1))
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // The second commit is sent after the first one, and the commits are not paused.
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    let current_block = eth_sender.ethereum.block_number().await.unwrap().as_u64();
+    assert!(!eth_sender.tx_simulation.is_paused(
+        zksync_types::aggregated_operations::AggregatedActionType::CommitBlocks,
+        current_block
+    ));
+}
+
+/// Checks that the stored nonce is reconciled with the chain on startup: the orphaned
+/// transactions are resent right away, and the nonces taken by the unknown transactions
+/// are skipped.
+#[tokio::test]
+async fn nonce_reconciliation() {
+    let (stored_eth_operations, aggregated_operations) = {
+        let eth_sender = default_eth_sender().await;
+        let mut stored_eth_operations = Vec::new();
+        let mut aggregated_operations = Vec::new();
+        for (id, operation) in vec![
+            test_data::commit_blocks_operation(0),
+            test_data::publish_proof_blocks_onchain_operations(0),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let deadline_block = eth_sender.get_deadline_block(1);
+            let eth_op = create_signed_tx(
+                id as i64,
+                &eth_sender,
+                operation.clone(),
+                deadline_block,
+                id as i64,
+            )
+            .await;
+            stored_eth_operations.push(eth_op);
+            aggregated_operations.push(operation);
+        }
+        (stored_eth_operations, aggregated_operations)
+    };
+
+    // Only the first transaction has reached the node.
+    let mut eth_parameters = default_eth_parameters();
+    eth_parameters.last_committed_block = 1;
+    eth_parameters.last_verified_block = 1;
+    let mut eth_sender = restored_eth_sender(
+        stored_eth_operations,
+        aggregated_operations,
+        Vec::new(),
+        eth_parameters,
+        1,
+    )
+    .await;
+
+    // The orphaned transaction is resent before its deadline.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops[0].used_tx_hashes.len(), 1);
+    assert_eq!(eth_sender.ongoing_ops[1].used_tx_hashes.len(), 2);
+
+    // The transactions unknown to the database have taken the nonces.
+    let mut eth_sender = restored_eth_sender(
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        default_eth_parameters(),
+        5,
+    )
+    .await;
+    eth_sender
+        .db
+        .send_aggregated_operation(test_data::commit_blocks_operation(0))
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops[0].nonce, 5.into());
+}
+
+/// Checks that withdrawals are completed within the execute transaction
+/// only when it's enabled and the withdrawals limit is not exceeded.
+#[test]
+fn complete_withdrawals_policy() {
+    use super::should_complete_withdrawals;
+
+    assert!(should_complete_withdrawals(true, 10, 1));
+    assert!(should_complete_withdrawals(true, 10, 10));
+    // Nothing to complete.
+    assert!(!should_complete_withdrawals(true, 10, 0));
+    // Limit is exceeded.
+    assert!(!should_complete_withdrawals(true, 10, 11));
+    // Disabled by the config.
+    assert!(!should_complete_withdrawals(false, 10, 1));
+}
@@ -83,6 +83,7 @@ fn gen_aggregated_operation(
         AggregatedActionType::ExecuteBlocks => {
             AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation {
                 blocks: vec![block],
+                complete_withdrawals: false,
             })
         }
     };
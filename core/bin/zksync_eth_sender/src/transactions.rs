@@ -37,7 +37,10 @@ impl From<StorageETHStats> for ETHStats {
 #[derive(Debug, PartialEq)]
 pub enum TxCheckOutcome {
     /// Transaction was committed and confirmed.
-    Committed,
+    Committed {
+        /// Number of the Ethereum block the transaction was included into.
+        inclusion_block: u64,
+    },
     /// Transaction is pending yet.
     Pending,
     /// Transaction is considered stuck, a replacement should be made.
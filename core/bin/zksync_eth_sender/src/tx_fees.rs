@@ -0,0 +1,121 @@
+//! Fees of the new and the replacement transactions.
+//!
+//! The fees are the gas price (the max fee per gas for the EIP-1559 transaction) and
+//! the priority fee, which is `None` for the legacy transaction. The EIP-1559 fees are
+//! estimated by the `FeeMarket`, the legacy gas price is chosen by the configured `GasAdjuster`.
+//!
+//! Besides `ETHSender`, the fees are used by the other components sending the transactions from
+//! the operator account (e.g. the block reverter), see `OperatorTxFees`, so their stuck
+//! transactions are escalated the same way.
+
+// External uses
+use web3::{contract::Options, types::U256};
+// Workspace uses
+use zksync_config::ETHSenderConfig;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::ConnectionPool;
+// Local uses
+use crate::{
+    database::{Database, DatabaseInterface},
+    fee_market::{Eip1559Fees, FeeMarket},
+    gas_adjuster::{new_gas_adjuster, GasAdjuster},
+};
+
+/// Returns the fees of the new transaction.
+pub(super) async fn new_tx_fees<DB: DatabaseInterface>(
+    fee_market: &FeeMarket,
+    gas_adjuster: &mut dyn GasAdjuster<DB>,
+    ethereum: &EthereumGateway,
+) -> anyhow::Result<(U256, Option<U256>)> {
+    if fee_market.is_enabled() {
+        let fees = fee_market.estimate_fees(ethereum).await?;
+        Ok((fees.max_fee_per_gas, Some(fees.max_priority_fee_per_gas)))
+    } else {
+        let gas_price = gas_adjuster.get_gas_price(ethereum, None).await?;
+        Ok((gas_price, None))
+    }
+}
+
+/// Returns the fees of the transaction replacing the stuck one sent with the given fees.
+pub(super) async fn replacement_tx_fees<DB: DatabaseInterface>(
+    fee_market: &FeeMarket,
+    gas_adjuster: &mut dyn GasAdjuster<DB>,
+    ethereum: &EthereumGateway,
+    old_gas_price: U256,
+    old_priority_fee: Option<U256>,
+) -> anyhow::Result<(U256, Option<U256>)> {
+    if fee_market.is_enabled() {
+        // The legacy transaction is replaced as if both of its fees were the gas price.
+        let old_fees = Eip1559Fees {
+            max_fee_per_gas: old_gas_price,
+            max_priority_fee_per_gas: old_priority_fee.unwrap_or(old_gas_price),
+        };
+        let current_fees = fee_market.estimate_fees(ethereum).await?;
+        let fees = fee_market.escalate(old_fees, current_fees);
+        if fees.max_fee_per_gas == fee_market.max_fee_per_gas_cap() {
+            vlog::warn!(
+                "Maximum possible max fee per gas will be used: <{}>",
+                fees.max_fee_per_gas
+            );
+        }
+        Ok((fees.max_fee_per_gas, Some(fees.max_priority_fee_per_gas)))
+    } else {
+        let gas_price = gas_adjuster
+            .get_gas_price(ethereum, Some(old_gas_price))
+            .await?;
+        Ok((gas_price, None))
+    }
+}
+
+/// Sets the fees of the transaction: the EIP-1559 ones if the priority fee is provided
+/// (the gas price is the max fee per gas then), the legacy gas price otherwise.
+pub fn set_tx_fees(options: &mut Options, gas_price: U256, priority_fee: Option<U256>) {
+    match priority_fee {
+        Some(priority_fee) => {
+            options.max_fee_per_gas = Some(gas_price);
+            options.max_priority_fee_per_gas = Some(priority_fee);
+        }
+        None => options.gas_price = Some(gas_price),
+    }
+}
+
+/// Fees of the transactions sent from the operator account outside of `ETHSender`,
+/// chosen with the same strategy and escalated the same way as its own.
+pub struct OperatorTxFees {
+    fee_market: FeeMarket,
+    gas_adjuster: Box<dyn GasAdjuster<Database>>,
+}
+
+impl OperatorTxFees {
+    pub async fn new(options: &ETHSenderConfig, pool: ConnectionPool) -> Self {
+        Self {
+            fee_market: FeeMarket::new(&options.fee_market),
+            gas_adjuster: new_gas_adjuster(options, &Database::new(pool)).await,
+        }
+    }
+
+    /// Returns the gas price and the priority fee of the new transaction.
+    pub async fn new_tx_fees(
+        &mut self,
+        ethereum: &EthereumGateway,
+    ) -> anyhow::Result<(U256, Option<U256>)> {
+        new_tx_fees(&self.fee_market, self.gas_adjuster.as_mut(), ethereum).await
+    }
+
+    /// Returns the gas price and the priority fee of the transaction replacing the stuck one.
+    pub async fn replacement_tx_fees(
+        &mut self,
+        ethereum: &EthereumGateway,
+        old_gas_price: U256,
+        old_priority_fee: Option<U256>,
+    ) -> anyhow::Result<(U256, Option<U256>)> {
+        replacement_tx_fees(
+            &self.fee_market,
+            self.gas_adjuster.as_mut(),
+            ethereum,
+            old_gas_price,
+            old_priority_fee,
+        )
+        .await
+    }
+}
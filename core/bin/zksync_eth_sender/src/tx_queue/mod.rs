@@ -1,3 +1,5 @@
+// Built-in deps
+use std::collections::HashSet;
 // Workspace imports
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
@@ -119,6 +121,7 @@ impl TxQueueBuilder {
             execute_operations: OperationQueue::new(BlockNumber(
                 self.execute_operations_count as u32,
             )),
+            paused: HashSet::new(),
         }
     }
 }
@@ -135,6 +138,8 @@ impl TxQueueBuilder {
 ///   - If `verify` queue contains elements for some blocks, and `commit` operations
 ///     for corresponding blocks is committed, the `verify` operation is yielded.
 ///   - Otherwise, if `commit` queue is not empty, a `commit` operation is yielded.
+///
+///   The operations of the paused types are skipped.
 /// 3. If all the queues are empty, no operation is returned.
 #[derive(Debug)]
 pub struct TxQueue {
@@ -144,9 +149,22 @@ pub struct TxQueue {
     commit_operations: OperationQueue,
     verify_operations: OperationQueue,
     execute_operations: OperationQueue,
+
+    /// Types of the operations held back, e.g. the commits while the operator funds are low.
+    paused: HashSet<AggregatedActionType>,
 }
 
 impl TxQueue {
+    /// Pauses or resumes yielding the operations of the given type.
+    /// The operations of the other types are yielded as usual.
+    pub fn set_paused(&mut self, op_type: AggregatedActionType, paused: bool) {
+        if paused {
+            self.paused.insert(op_type);
+        } else {
+            self.paused.remove(&op_type);
+        }
+    }
+
     /// Adds the `commit` operation to the queue.
     pub fn add_commit_operation(&mut self, commit_operation: TxData) -> anyhow::Result<()> {
         self.commit_operations.push_back(commit_operation)?;
@@ -246,22 +264,32 @@ impl TxQueue {
     /// This method does not use/affect `sent_pending_tx` counter.
     fn get_next_operation(&mut self) -> Option<TxData> {
         // 1. Highest priority: execute operations.
-        if let Some(next_execute_block) = self.execute_operations.get_next_last_block_number() {
-            let current_verify_block = self.verify_operations.get_last_block_number();
-            if *next_execute_block <= *current_verify_block {
-                return Some(self.execute_operations.pop_front().unwrap());
+        if !self.paused.contains(&AggregatedActionType::ExecuteBlocks) {
+            if let Some(next_execute_block) = self.execute_operations.get_next_last_block_number() {
+                let current_verify_block = self.verify_operations.get_last_block_number();
+                if *next_execute_block <= *current_verify_block {
+                    return Some(self.execute_operations.pop_front().unwrap());
+                }
             }
         }
 
         // 2. After execute operations we should process verify operation.
-        if let Some(next_verify_block) = self.verify_operations.get_next_last_block_number() {
-            let current_commit_block = self.commit_operations.get_last_block_number();
-            if *next_verify_block <= *current_commit_block {
-                return Some(self.verify_operations.pop_front().unwrap());
+        if !self
+            .paused
+            .contains(&AggregatedActionType::PublishProofBlocksOnchain)
+        {
+            if let Some(next_verify_block) = self.verify_operations.get_next_last_block_number() {
+                let current_commit_block = self.commit_operations.get_last_block_number();
+                if *next_verify_block <= *current_commit_block {
+                    return Some(self.verify_operations.pop_front().unwrap());
+                }
             }
         }
 
         // 3. Finally, check the commit queue.
+        if self.paused.contains(&AggregatedActionType::CommitBlocks) {
+            return None;
+        }
         self.commit_operations.pop_front()
     }
 
@@ -408,6 +436,45 @@ mod tests {
         assert_eq!(queue.sent_pending_txs, pending_count);
     }
 
+    /// Checks that the operations of the paused type are held back, while the other ones are yielded.
+    #[test]
+    fn paused_operations() {
+        const MAX_IN_FLY: usize = 3;
+        const COMMIT_MARK: u8 = 0;
+        const VERIFY_MARK: u8 = 1;
+
+        let mut queue = TxQueueBuilder::new(MAX_IN_FLY)
+            .with_commit_operations_count(1)
+            .build();
+        queue
+            .add_commit_operation(get_tx_data(
+                AggregatedActionType::CommitBlocks,
+                BlockNumber(2),
+                vec![COMMIT_MARK, 1],
+            ))
+            .unwrap();
+        queue
+            .add_verify_operation(get_tx_data(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                BlockNumber(1),
+                vec![VERIFY_MARK, 0],
+            ))
+            .unwrap();
+
+        queue.set_paused(AggregatedActionType::PublishProofBlocksOnchain, true);
+        queue.set_paused(AggregatedActionType::CommitBlocks, true);
+        assert_eq!(queue.pop_front(), None);
+
+        queue.set_paused(AggregatedActionType::PublishProofBlocksOnchain, false);
+        let op_1 = queue.pop_front().unwrap();
+        assert_eq!(op_1.raw, vec![VERIFY_MARK, 0]);
+        assert_eq!(queue.pop_front(), None);
+
+        queue.set_paused(AggregatedActionType::CommitBlocks, false);
+        let op_2 = queue.pop_front().unwrap();
+        assert_eq!(op_2.raw, vec![COMMIT_MARK, 1]);
+    }
+
     #[test]
     #[should_panic(expected = "No transactions are expected to be returned")]
     fn return_popped_empty() {
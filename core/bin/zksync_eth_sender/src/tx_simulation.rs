@@ -0,0 +1,89 @@
+//! Simulation of the transactions before they're sent.
+//!
+//! The reverted transaction still burns the gas, and its failure reason is only known once it's
+//! included. So the transaction of the new operation is simulated with `eth_call` first: if it
+//! would revert, it's not sent, the decoded revert reason is reported, and the operations of the
+//! same type are paused for a while, since the following ones would most likely revert as well.
+//! Once the pause is over, the transaction is simulated again.
+//!
+//! The simulation runs against the pending state, so the transaction is simulated only if none
+//! of the operations it has to go after are in flight: otherwise it would revert since they're
+//! not included yet, and the operations would be paused for nothing.
+
+// Built-in deps
+use std::collections::HashMap;
+// External uses
+use anyhow::format_err;
+// Workspace uses
+use zksync_config::configs::eth_sender::TxSimulation as TxSimulationConfig;
+use zksync_eth_client::EthereumGateway;
+use zksync_types::aggregated_operations::AggregatedActionType;
+// Local uses
+use crate::tx_queue::TxData;
+
+#[derive(Debug)]
+pub(super) struct TxSimulation {
+    config: TxSimulationConfig,
+    /// Block until which the operations of the type are paused, for the types
+    /// the last simulated transaction of which has reverted.
+    paused_until: HashMap<AggregatedActionType, u64>,
+}
+
+impl TxSimulation {
+    pub fn new(config: &TxSimulationConfig) -> Self {
+        Self {
+            config: config.clone(),
+            paused_until: HashMap::new(),
+        }
+    }
+
+    /// Whether the operations of the type are not sent at the `current_block`.
+    pub fn is_paused(&self, op_type: AggregatedActionType, current_block: u64) -> bool {
+        self.paused_until
+            .get(&op_type)
+            .map_or(false, |&until_block| current_block < until_block)
+    }
+
+    /// Simulates the transaction of the operation. Returns an error with the revert reason
+    /// and pauses the operations of the same type if the transaction would revert.
+    pub async fn check(
+        &mut self,
+        ethereum: &EthereumGateway,
+        tx: &TxData,
+        current_block: u64,
+    ) -> anyhow::Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let revert_reason = match ethereum.simulate_tx(tx.raw.clone()).await? {
+            Some(revert_reason) => revert_reason,
+            None => {
+                if self.paused_until.remove(&tx.op_type).is_some() {
+                    vlog::info!(
+                        "Simulated {:?} transaction succeeded, the operations are resumed",
+                        tx.op_type
+                    );
+                }
+                return Ok(());
+            }
+        };
+
+        let until_block = current_block + self.config.pause_blocks;
+        self.paused_until.insert(tx.op_type, until_block);
+        vlog::error!(
+            "Simulated transaction of the operation <id: {}, type: {:?}> reverts: {}. \
+            The operations of the type are paused until block {}",
+            tx.operation.0,
+            tx.op_type,
+            revert_reason.reason,
+            until_block
+        );
+        metrics::increment_counter!("eth_sender.simulation_reverts", "op_type" => tx.op_type.to_string());
+
+        Err(format_err!(
+            "Transaction would revert: {}",
+            revert_reason.reason
+        ))
+    }
+}
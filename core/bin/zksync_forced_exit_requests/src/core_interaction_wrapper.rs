@@ -1,8 +1,5 @@
 use chrono::Utc;
-use futures::{
-    channel::{mpsc, oneshot},
-    SinkExt,
-};
+use futures::{channel::mpsc, SinkExt};
 use num::Zero;
 
 use zksync_storage::{chain::operations_ext::records::TxReceiptResponse, ConnectionPool};
@@ -15,6 +12,7 @@ use zksync_types::{
 use zksync_api::api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker};
 use zksync_mempool::MempoolTransactionRequest;
 use zksync_types::SignedZkSyncTx;
+use zksync_utils::deadline::{deadline_channel, Deadline};
 
 // We could use `db reset` and test the db the same way as in rust_api
 // but it seemed to be an overkill here, so it was decided to use
@@ -145,7 +143,7 @@ impl CoreInteractionWrapper for MempoolCoreInteractionWrapper {
 
         let hashes: Vec<TxHash> = txs.iter().map(|tx| tx.hash()).collect();
 
-        let (sender, receiver) = oneshot::channel();
+        let (sender, receiver) = deadline_channel(Deadline::never());
         let item = MempoolTransactionRequest::NewTxsBatch(txs, vec![], sender);
         self.mempool_tx_sender.send(item).await?;
         receiver.await??;
@@ -14,12 +14,13 @@ use zksync_types::{Nonce, TokenId};
 
 use zksync_crypto::franklin_crypto::eddsa::PrivateKey;
 
-use futures::channel::{mpsc, oneshot};
+use futures::channel::mpsc;
 use futures::SinkExt;
 use tokio::time;
 
 use zksync_mempool::MempoolTransactionRequest;
 use zksync_test_account::{ZkSyncAccount, ZkSyncETHAccountData};
+use zksync_utils::deadline::{deadline_channel, Deadline};
 
 use super::utils::{read_signing_key, Engine};
 
@@ -202,8 +203,8 @@ pub async fn register_signing_key(
     let tx = ZkSyncTx::ChangePubKey(Box::new(cpk));
     let tx_hash = tx.hash();
 
-    let (sender, receiver) = oneshot::channel();
-    let item = MempoolTransactionRequest::NewTx(Box::new(tx.into()), sender);
+    let (sender, receiver) = deadline_channel(Deadline::never());
+    let item = MempoolTransactionRequest::NewTx(Box::new(tx.into()), None, sender);
 
     mempool_tx_sender
         .send(item)
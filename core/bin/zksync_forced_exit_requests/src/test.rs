@@ -32,6 +32,7 @@ impl Default for MockCoreInteractionWrapper {
                 success: true,
                 verified: false,
                 fail_reason: None,
+                fail_code: None,
                 prover_run: None,
             }),
             sent_txs: Mutex::new(vec![]),
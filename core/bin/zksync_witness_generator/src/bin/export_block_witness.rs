@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use structopt::StructOpt;
+use zksync_storage::ConnectionPool;
+use zksync_types::BlockNumber;
+use zksync_witness_generator::{database::Database, witness_export::export_block_witness};
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "export_block_witness",
+    about = "Tool to export the data required to regenerate the witness of a block."
+)]
+struct Opt {
+    /// Number of the block to export.
+    #[structopt(long)]
+    block: u32,
+    /// File to write the export to, the standard output is used if not specified.
+    #[structopt(long)]
+    output: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+
+    let database = Database::new(ConnectionPool::new(Some(1)));
+    let export = export_block_witness(&database, BlockNumber(opt.block))
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Block {} does not exist", opt.block))?;
+    export.verify_pre_state()?;
+
+    let export = serde_json::to_string_pretty(&export)?;
+    match opt.output {
+        Some(path) => std::fs::write(path, export)?,
+        None => println!("{}", export),
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Monitor of the idle prover jobs none of the active provers is capable of.
+//!
+//! The jobs are given out only to the provers with the matching capability tags, so the job
+//! matching none of them waits in the queue until a capable prover is started. The capabilities
+//! of the provers requesting the jobs are remembered until the prover is gone, and the idle jobs
+//! none of them can take are reported by the `prover_server.unservable_jobs` gauge and the log.
+
+// Built-in
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+// Workspace deps
+use zksync_types::prover::ProverCapabilities;
+// Local deps
+use crate::database_interface::DatabaseInterface;
+
+const UNSERVABLE_JOBS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Capabilities of the provers which requested the jobs or sent the heartbeats recently.
+#[derive(Debug, Clone)]
+pub struct ActiveProvers {
+    provers: Arc<Mutex<HashMap<String, (ProverCapabilities, Instant)>>>,
+    /// The prover is considered gone after this duration without the requests.
+    gone_timeout: Duration,
+}
+
+impl ActiveProvers {
+    pub fn new(gone_timeout: Duration) -> Self {
+        Self {
+            provers: Default::default(),
+            gone_timeout,
+        }
+    }
+
+    /// Records the capabilities of the prover requesting the job.
+    pub fn record_job_request(&self, prover_name: &str, capabilities: ProverCapabilities) {
+        self.provers
+            .lock()
+            .unwrap()
+            .insert(prover_name.to_string(), (capabilities, Instant::now()));
+    }
+
+    /// Keeps the prover computing the proof active.
+    pub fn record_heartbeat(&self, prover_name: &str) {
+        if let Some((_, last_seen)) = self.provers.lock().unwrap().get_mut(prover_name) {
+            *last_seen = Instant::now();
+        }
+    }
+
+    pub fn record_stop(&self, prover_name: &str) {
+        self.provers.lock().unwrap().remove(prover_name);
+    }
+
+    /// Returns the capabilities of the active provers, forgetting the gone ones.
+    fn capabilities(&self) -> Vec<ProverCapabilities> {
+        let mut provers = self.provers.lock().unwrap();
+        provers.retain(|_, (_, last_seen)| last_seen.elapsed() < self.gone_timeout);
+        provers
+            .values()
+            .map(|(capabilities, _)| capabilities.clone())
+            .collect()
+    }
+
+    /// Returns the number of the idle jobs none of the active provers can take.
+    /// Without the active provers every job waits, which is up to the scaler,
+    /// so none of the jobs is counted.
+    async fn count_unservable_jobs<DB: DatabaseInterface>(
+        &self,
+        database: &DB,
+    ) -> anyhow::Result<usize> {
+        let capabilities = self.capabilities();
+        if capabilities.is_empty() {
+            return Ok(0);
+        }
+
+        let mut connection = database.acquire_connection().await?;
+        let idle_jobs = database.load_idle_prover_jobs(&mut connection).await?;
+        let unservable_jobs: Vec<_> = idle_jobs
+            .iter()
+            .filter(|job| !capabilities.iter().any(|caps| caps.can_prove(job)))
+            .collect();
+        if let Some(job) = unservable_jobs.first() {
+            vlog::warn!(
+                "{} idle prover jobs match none of the {} active provers, e.g. {} job {} \
+                 for blocks [{}, {}] with block size {:?}",
+                unservable_jobs.len(),
+                capabilities.len(),
+                job.job_type.to_string(),
+                job.job_id,
+                job.first_block,
+                job.last_block,
+                job.block_size
+            );
+        }
+        Ok(unservable_jobs.len())
+    }
+}
+
+pub async fn run_unservable_jobs_monitor<DB: DatabaseInterface>(
+    database: DB,
+    active_provers: ActiveProvers,
+) {
+    let mut interval = tokio::time::interval(UNSERVABLE_JOBS_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        match active_provers.count_unservable_jobs(&database).await {
+            Ok(count) => metrics::gauge!("prover_server.unservable_jobs", count as f64),
+            Err(e) => vlog::warn!("Failed to check the idle prover jobs: {}", e),
+        }
+    }
+}
@@ -5,13 +5,14 @@
 
 // Built-in
 use std::clone::Clone;
+use std::time::Duration;
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{IdleProverJob, ProverCapabilities, ProverJob, ProverJobAging, ProverJobType},
     AccountMap, AccountUpdates, BlockNumber,
 };
 // Local uses
@@ -64,6 +65,19 @@ impl DatabaseInterface for Database {
         Ok(witness)
     }
 
+    async fn load_witness_hash(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let witness_hash = connection
+            .prover_schema()
+            .get_witness_hash(block_number)
+            .await?;
+
+        Ok(witness_hash)
+    }
+
     async fn add_prover_job_to_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -154,27 +168,41 @@ impl DatabaseInterface for Database {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
+        prover_name: &str,
+        capabilities: &ProverCapabilities,
+        lease_duration: Duration,
+        aging: &ProverJobAging,
     ) -> anyhow::Result<Option<ProverJob>> {
         let proof = connection
             .prover_schema()
-            .get_idle_prover_job_from_job_queue()
+            .get_idle_prover_job_from_job_queue(prover_name, capabilities, lease_duration, aging)
             .await?;
 
         Ok(proof)
     }
 
+    async fn load_idle_prover_jobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<IdleProverJob>> {
+        let jobs = connection.prover_schema().load_idle_jobs().await?;
+
+        Ok(jobs)
+    }
+
     async fn record_prover_is_working(
         &self,
         connection: &mut StorageProcessor<'_>,
         job_id: i32,
         prover_name: &str,
-    ) -> anyhow::Result<()> {
-        connection
+        lease_duration: Duration,
+    ) -> anyhow::Result<bool> {
+        let renewed = connection
             .prover_schema()
-            .record_prover_is_working(job_id, prover_name)
+            .record_prover_is_working(job_id, prover_name, lease_duration)
             .await?;
 
-        Ok(())
+        Ok(renewed)
     }
 
     async fn store_proof(
@@ -276,15 +304,26 @@ impl DatabaseInterface for Database {
         connection: &mut StorageProcessor<'_>,
         block: BlockNumber,
         witness: serde_json::Value,
+        witness_hash: Vec<u8>,
     ) -> anyhow::Result<()> {
         connection
             .prover_schema()
-            .store_witness(block, witness)
+            .store_witness(block, witness, witness_hash)
             .await?;
 
         Ok(())
     }
 
+    async fn remove_witness(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        connection.prover_schema().remove_witness(block).await?;
+
+        Ok(())
+    }
+
     async fn pending_jobs_count(
         &self,
         connection: &mut StorageProcessor<'_>,
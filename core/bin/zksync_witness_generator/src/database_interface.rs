@@ -6,13 +6,14 @@
 // Built-in
 use std::clone::Clone;
 use std::marker::{Send, Sync};
+use std::time::Duration;
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_storage::StorageProcessor;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::{
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{IdleProverJob, ProverCapabilities, ProverJob, ProverJobAging, ProverJobType},
     AccountMap, AccountUpdates, BlockNumber,
 };
 
@@ -36,6 +37,14 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         block_number: BlockNumber,
     ) -> anyhow::Result<Option<serde_json::Value>>;
 
+    /// Returns the hash of the witness computed by the witness generator at the moment
+    /// of the witness creation.
+    async fn load_witness_hash(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Option<Vec<u8>>>;
+
     async fn add_prover_job_to_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -83,14 +92,26 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
+        prover_name: &str,
+        capabilities: &ProverCapabilities,
+        lease_duration: Duration,
+        aging: &ProverJobAging,
     ) -> anyhow::Result<Option<ProverJob>>;
 
+    /// Returns the idle jobs to match them against the capabilities of the provers.
+    async fn load_idle_prover_jobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<IdleProverJob>>;
+
+    /// Renews the lease of the job, returns `false` if the job isn't leased to the prover.
     async fn record_prover_is_working(
         &self,
         connection: &mut StorageProcessor<'_>,
         job_id: i32,
         prover_name: &str,
-    ) -> anyhow::Result<()>;
+        lease_duration: Duration,
+    ) -> anyhow::Result<bool>;
 
     async fn store_proof(
         &self,
@@ -140,6 +161,14 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         connection: &mut StorageProcessor<'_>,
         block: BlockNumber,
         witness: serde_json::Value,
+        witness_hash: Vec<u8>,
+    ) -> anyhow::Result<()>;
+
+    /// Removes the witness of the block, so it's generated again.
+    async fn remove_witness(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: BlockNumber,
     ) -> anyhow::Result<()>;
 
     async fn pending_jobs_count(
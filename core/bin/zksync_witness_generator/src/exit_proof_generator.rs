@@ -0,0 +1,185 @@
+//! Generator of the exit proofs requested via API.
+//!
+//! The API only queues the requests, since the proof generation takes minutes and requires
+//! the exit circuit keys. The generator takes the queued requests one by one, proves the balance
+//! against the state of the block which is the last finalized one at the time and stores the inputs
+//! of the exit transaction, so they're returned by the API once ready. The proof is requested again
+//! via API once a newer block is finalized.
+
+// Built-in deps
+use std::time::Duration;
+// External deps
+use anyhow::format_err;
+use chrono::Utc;
+use tokio::{task::JoinHandle, time};
+// Workspace deps
+use zksync_api_types::v02::account::{ExitProofData, StoredBlockInfo};
+use zksync_config::configs::prover::ExitProofGenerator as ExitProofGeneratorConfig;
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_prover_utils::exit_proof::{create_exit_proof_fungible, create_exit_proof_nft};
+use zksync_storage::ConnectionPool;
+use zksync_types::{AccountId, TokenId, TokenLike};
+
+async fn generate_exit_proof(
+    db_pool: &ConnectionPool,
+    account_id: AccountId,
+    token_id: TokenId,
+) -> anyhow::Result<ExitProofData> {
+    // The connection isn't held while the proof is generated.
+    let (accounts, stored_block_info, token, nft, zero_account_address) = {
+        let mut storage = db_pool.access_storage().await?;
+        let (last_finalized_block, accounts) =
+            storage.chain().state_schema().load_verified_state().await?;
+        let block = storage
+            .chain()
+            .block_schema()
+            .get_block(last_finalized_block)
+            .await?
+            .ok_or_else(|| format_err!("Block {} is not stored", last_finalized_block))?;
+        let token = storage
+            .tokens_schema()
+            .get_token(TokenLike::Id(token_id))
+            .await?
+            .ok_or_else(|| format_err!("Token {} not found", token_id))?;
+        let nft = if token_id.0 >= MIN_NFT_TOKEN_ID {
+            let nft = storage
+                .tokens_schema()
+                .get_nft(token_id)
+                .await?
+                .ok_or_else(|| format_err!("NFT {} not found", token_id))?;
+            Some(nft)
+        } else {
+            None
+        };
+        // The placeholder creator of the fungible tokens is the account with id 0.
+        let zero_account_address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(AccountId(0))
+            .await?
+            .ok_or_else(|| format_err!("Account with id 0 does not exist"))?;
+
+        (
+            accounts,
+            StoredBlockInfo::from_block(&block),
+            token,
+            nft,
+            zero_account_address,
+        )
+    };
+    let owner = accounts
+        .get(&account_id)
+        .map(|account| account.address)
+        .ok_or_else(|| format_err!("Account {} not found", account_id))?;
+
+    let nft_info = nft.clone();
+    let (proof, amount) = tokio::task::spawn_blocking(move || match nft_info {
+        Some(nft) => create_exit_proof_nft(
+            accounts,
+            account_id,
+            owner,
+            token_id,
+            nft.creator_id,
+            nft.serial_id,
+            nft.content_hash,
+        ),
+        None => create_exit_proof_fungible(accounts, account_id, owner, token_id),
+    })
+    .await??;
+
+    let (nft_creator_id, nft_creator_address, nft_serial_id, nft_content_hash) = match nft {
+        Some(nft) => (
+            nft.creator_id,
+            nft.creator_address,
+            nft.serial_id,
+            nft.content_hash,
+        ),
+        None => (AccountId(0), zero_account_address, 0, Default::default()),
+    };
+    Ok(ExitProofData {
+        stored_block_info,
+        owner,
+        account_id,
+        token_id,
+        token_address: token.address,
+        amount: amount.into(),
+        nft_creator_id,
+        nft_creator_address,
+        nft_serial_id,
+        nft_content_hash,
+        proof,
+    })
+}
+
+/// Generates the oldest queued exit proof, if any.
+async fn process_exit_proof_job(
+    db_pool: &ConnectionPool,
+    job_timeout: Duration,
+) -> anyhow::Result<()> {
+    let abandoned_before = Utc::now() - chrono::Duration::from_std(job_timeout)?;
+    let job = db_pool
+        .access_storage()
+        .await?
+        .exit_proofs_schema()
+        .start_exit_proof_job(abandoned_before)
+        .await?;
+    let job = match job {
+        Some(job) => job,
+        None => return Ok(()),
+    };
+    let account_id = AccountId(job.account_id as u32);
+    let token_id = TokenId(job.token_id as u32);
+    vlog::info!(
+        "Generating exit proof for account {} token {}",
+        account_id,
+        token_id
+    );
+
+    let result = generate_exit_proof(db_pool, account_id, token_id).await;
+    let mut storage = db_pool.access_storage().await?;
+    match result {
+        Ok(data) => {
+            vlog::info!(
+                "Exit proof for account {} token {} is generated at block {}",
+                account_id,
+                token_id,
+                data.stored_block_info.block_number
+            );
+            storage.exit_proofs_schema().store_exit_proof(&data).await?;
+            metrics::increment_counter!("exit_proof_generator.proofs", "result" => "ready");
+        }
+        Err(err) => {
+            vlog::warn!(
+                "Failed to generate exit proof for account {} token {}: {}",
+                account_id,
+                token_id,
+                err
+            );
+            storage
+                .exit_proofs_schema()
+                .store_exit_proof_error(account_id, token_id, &err.to_string())
+                .await?;
+            metrics::increment_counter!("exit_proof_generator.proofs", "result" => "failed");
+        }
+    }
+    Ok(())
+}
+
+#[must_use]
+pub fn run_exit_proof_generator(
+    db_pool: ConnectionPool,
+    config: ExitProofGeneratorConfig,
+) -> JoinHandle<()> {
+    let mut timer = time::interval(config.job_check_interval());
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = process_exit_proof_job(&db_pool, config.job_timeout()).await {
+                // The job will be restarted once it's considered abandoned.
+                vlog::warn!("Failed to process exit proof job: {}", err);
+            }
+        }
+    })
+}
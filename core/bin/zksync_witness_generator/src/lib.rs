@@ -18,6 +18,7 @@ use tokio::sync::RwLock;
 // Workspace deps
 use zksync_config::ProverConfig;
 // Local deps
+use self::capable_provers::{run_unservable_jobs_monitor, ActiveProvers};
 use self::database_interface::DatabaseInterface;
 use self::scaler::ScalerOracle;
 use tokio::task::JoinHandle;
@@ -31,7 +32,8 @@ use zksync_types::aggregated_operations::{
     AggregatedActionType, AggregatedOperation, BlocksCreateProofOperation,
 };
 use zksync_types::prover::{
-    ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    ProverCapabilities, ProverJobAging, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY,
+    SINGLE_PROOF_JOB_PRIORITY,
 };
 use zksync_types::BlockNumber;
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
@@ -39,11 +41,25 @@ use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 #[cfg(test)]
 mod tests;
 
+mod capable_provers;
 pub mod database;
 mod database_interface;
+pub mod exit_proof_generator;
 mod scaler;
+pub mod witness_export;
 mod witness_generator;
 
+/// Computes the hash of the block witness.
+///
+/// The hash is calculated by the witness generator when the witness is created, and
+/// recalculated by the prover server before the prover job is created, so the witness
+/// corrupted in the storage (or produced by an incompatible server version) won't be
+/// sent to the provers.
+pub fn witness_hash(witness: &serde_json::Value) -> Vec<u8> {
+    let witness_bytes = serde_json::to_vec(witness).expect("Failed to serialize witness");
+    tiny_keccak::keccak256(&witness_bytes).to_vec()
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct PayloadAuthToken {
     /// Subject (whom auth token refers to).
@@ -57,10 +73,21 @@ struct AppState<DB: DatabaseInterface> {
     secret_auth: String,
     database: DB,
     scaler_oracle: Arc<RwLock<ScalerOracle<DB>>>,
+    /// The job is returned to the queue unless the prover sends a heartbeat within this duration.
+    lease_duration: Duration,
+    job_aging: ProverJobAging,
+    active_provers: ActiveProvers,
 }
 
 impl<DB: DatabaseInterface> AppState<DB> {
-    pub fn new(secret_auth: String, database: DB, idle_provers: u32) -> Self {
+    pub fn new(
+        secret_auth: String,
+        database: DB,
+        idle_provers: u32,
+        lease_duration: Duration,
+        job_aging: ProverJobAging,
+        active_provers: ActiveProvers,
+    ) -> Self {
         let scaler_oracle = Arc::new(RwLock::new(ScalerOracle::new(
             database.clone(),
             idle_provers,
@@ -70,6 +97,9 @@ impl<DB: DatabaseInterface> AppState<DB> {
             secret_auth,
             database,
             scaler_oracle,
+            lease_duration,
+            job_aging,
+            active_provers,
         }
     }
 
@@ -126,10 +156,20 @@ async fn get_job<DB: DatabaseInterface>(
     if r.prover_name.is_empty() {
         return Err(actix_web::error::ErrorBadRequest("empty name"));
     }
+    let capabilities = ProverCapabilities::from_tags(&r.aux_data.capability_tags)
+        .map_err(actix_web::error::ErrorBadRequest)?;
+    data.active_provers
+        .record_job_request(&r.prover_name, capabilities.clone());
     let mut storage = data.access_storage().await?;
     let ret = data
         .database
-        .load_idle_prover_job_from_job_queue(&mut storage)
+        .load_idle_prover_job_from_job_queue(
+            &mut storage,
+            &r.prover_name,
+            &capabilities,
+            data.lease_duration,
+            &data.job_aging,
+        )
         .await
         .map_err(|e| {
             vlog::warn!("could not get next unverified commit operation: {}", e);
@@ -160,20 +200,31 @@ async fn working_on<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
     r: web::Json<WorkingOn>,
 ) -> actix_web::Result<HttpResponse> {
-    // These heartbeats aren't really important, as they're sent
-    // continuously while prover is performing computations.
+    // The heartbeats are sent continuously while prover is performing computations,
+    // each of them renews the lease of the job.
     vlog::trace!("Received heartbeat for prover_run with id: {}", r.job_id);
+    data.active_provers.record_heartbeat(&r.prover_name);
     let mut storage = data
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
-    data.database
-        .record_prover_is_working(&mut storage, r.job_id, &r.prover_name)
+    let renewed = data
+        .database
+        .record_prover_is_working(&mut storage, r.job_id, &r.prover_name, data.lease_duration)
         .await
         .map_err(|e| {
             vlog::warn!("failed to record prover work in progress request: {}", e);
             actix_web::error::ErrorInternalServerError("storage layer error")
         })?;
+    if !renewed {
+        vlog::warn!(
+            "Prover '{}' sent a heartbeat for job {} which isn't leased to it",
+            r.prover_name,
+            r.job_id
+        );
+        metrics::increment_counter!("prover_server.lost_job_leases");
+        return Ok(HttpResponse::Conflict().body("job is not leased to the prover"));
+    }
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -241,6 +292,7 @@ async fn stopped<DB: DatabaseInterface>(
         "Prover instance '{}' send a stopping notification",
         &prover_name
     );
+    data.active_provers.record_stop(&prover_name);
 
     data.database
         .record_prover_stop(&mut storage, &prover_name)
@@ -295,7 +347,7 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     loop {
         interval.tick().await;
 
-        update_prover_job_queue(database.clone())
+        update_prover_job_queue(database.clone(), prepare_data_interval)
             .await
             .unwrap_or_else(|e| {
                 vlog::warn!("Failed to update prover job queue: {}", e);
@@ -303,7 +355,10 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     }
 }
 
-async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow::Result<()> {
+async fn update_prover_job_queue<DB: DatabaseInterface>(
+    database: DB,
+    prepare_data_interval: Duration,
+) -> anyhow::Result<()> {
     let mut connection = database.acquire_connection().await?;
     {
         let next_single_block_to_add = database
@@ -313,7 +368,33 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
         let witness_for_next_single_block = database
             .load_witness(&mut connection, next_single_block_to_add)
             .await?;
-        if let Some(witness) = witness_for_next_single_block {
+        // Witnesses created before the hash was introduced have no hash stored.
+        let expected_hash = database
+            .load_witness_hash(&mut connection, next_single_block_to_add)
+            .await?;
+        let witness_is_corrupted = match (&witness_for_next_single_block, expected_hash) {
+            (Some(witness), Some(expected_hash)) => witness_hash(witness) != expected_hash,
+            _ => false,
+        };
+        if witness_is_corrupted {
+            metrics::increment_counter!("witness_generator.witness_hash_mismatch");
+            vlog::error!(
+                "Witness for block {} does not match the hash computed at its creation, \
+                 generating it again",
+                next_single_block_to_add
+            );
+            // The prover job is created once the new witness is stored.
+            database
+                .remove_witness(&mut connection, next_single_block_to_add)
+                .await?;
+            witness_generator::WitnessGenerator::new(
+                database.clone(),
+                prepare_data_interval,
+                next_single_block_to_add,
+                BlockNumber(1),
+            )
+            .start_regeneration();
+        } else if let Some(witness) = witness_for_next_single_block {
             let prover_data: ProverData =
                 serde_json::from_value(witness).expect("incorrect single block witness");
             let block_size = prover_data.operations.len();
@@ -437,12 +518,35 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                     );
                     pool_maintainer.start(panic_sender.clone());
                 }
+                // The witness exports are served without the prover token.
+                let witness_export_server = witness_export::witness_export_server(
+                    database.clone(),
+                    prover_api_opts.witness_export_bind_addr(),
+                )
+                .expect("failed to bind witness export server");
+                actix_rt::spawn(witness_export_server);
                 // Start HTTP server.
                 let secret_auth = prover_api_opts.secret_auth.clone();
                 let idle_provers = core_opts.idle_provers;
+                let lease_duration = core_opts.gone_timeout();
+                let job_aging = ProverJobAging {
+                    interval: core_opts.job_aging_interval(),
+                    priority_op_threshold: core_opts.aged_priority_op_threshold(),
+                };
+                let active_provers = ActiveProvers::new(lease_duration);
+                tokio::spawn(run_unservable_jobs_monitor(
+                    database.clone(),
+                    active_provers.clone(),
+                ));
                 HttpServer::new(move || {
-                    let app_state =
-                        AppState::new(secret_auth.clone(), database.clone(), idle_provers);
+                    let app_state = AppState::new(
+                        secret_auth.clone(),
+                        database.clone(),
+                        idle_provers,
+                        lease_duration,
+                        job_aging,
+                        active_provers.clone(),
+                    );
 
                     let auth = HttpAuthentication::bearer(move |req, credentials| async {
                         let secret_auth = req
@@ -16,7 +16,10 @@ use zksync_storage::StorageProcessor;
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobStatus, ProverJobType},
+    prover::{
+        IdleProverJob, ProverCapabilities, ProverJob, ProverJobAging, ProverJobStatus,
+        ProverJobType,
+    },
     AccountId, AccountMap, AccountTree, AccountUpdates, Address, BlockNumber,
 };
 // Local uses
@@ -109,6 +112,10 @@ impl DatabaseInterface for MockDatabase {
             id,
             job_priority,
             job_data,
+            oldest_priority_op_at: None,
+            assigned_prover: None,
+            lease_expires_at: None,
+            reassignments: 0,
         };
 
         prover_job_queue.1.push(new_job);
@@ -176,10 +183,16 @@ impl DatabaseInterface for MockDatabase {
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
 
         for job in prover_job_queue.iter_mut() {
-            if now - job.updated_at > chrono::Duration::seconds(10) {
+            let lease_expired = job
+                .lease_expires_at
+                .map_or(false, |lease_expires_at| lease_expires_at <= now);
+            if job.job_status == ProverJobStatus::InProgress.to_number() && lease_expired {
                 job.job_status = ProverJobStatus::Idle.to_number();
                 job.updated_at = now;
                 job.updated_by = "server_clean_idle".to_string();
+                job.assigned_prover = None;
+                job.lease_expires_at = None;
+                job.reassignments += 1;
             }
         }
 
@@ -224,6 +237,10 @@ impl DatabaseInterface for MockDatabase {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         _: &mut StorageProcessor<'_>,
+        prover_name: &str,
+        _: &ProverCapabilities,
+        lease_duration: Duration,
+        _: &ProverJobAging,
     ) -> anyhow::Result<Option<ProverJob>> {
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
         let idle_prover_job = prover_job_queue
@@ -235,6 +252,9 @@ impl DatabaseInterface for MockDatabase {
             job.job_status = ProverJobStatus::InProgress.to_number();
             job.updated_at = Utc::now();
             job.updated_by = "server_give_job".to_string();
+            job.assigned_prover = Some(prover_name.to_string());
+            job.lease_expires_at =
+                Some(Utc::now() + chrono::Duration::from_std(lease_duration).unwrap());
 
             Some(ProverJob::new(
                 job.id,
@@ -249,21 +269,63 @@ impl DatabaseInterface for MockDatabase {
         Ok(prover_job)
     }
 
+    async fn load_idle_prover_jobs(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<IdleProverJob>> {
+        let prover_job_queue = &self.prover_job_queue.read().await.1;
+        let blocks = self.blocks.read().await;
+        let single_proof = ProverJobType::SingleProof.to_string();
+
+        let jobs = prover_job_queue
+            .iter()
+            .filter(|job| job.job_status == ProverJobStatus::Idle.to_number())
+            .map(|job| {
+                let first_block = BlockNumber(job.first_block as u32);
+                let (job_type, block_size) = if job.job_type == single_proof {
+                    let block_size = blocks
+                        .iter()
+                        .find(|block| block.block_number == first_block)
+                        .map(|block| block.block_chunks_size);
+                    (ProverJobType::SingleProof, block_size)
+                } else {
+                    (ProverJobType::AggregatedProof, None)
+                };
+                IdleProverJob {
+                    job_id: job.id,
+                    job_type,
+                    first_block,
+                    last_block: BlockNumber(job.last_block as u32),
+                    block_size,
+                }
+            })
+            .collect();
+        Ok(jobs)
+    }
+
     async fn record_prover_is_working(
         &self,
         _: &mut StorageProcessor<'_>,
         job_id: i32,
         prover_name: &str,
-    ) -> anyhow::Result<()> {
+        lease_duration: Duration,
+    ) -> anyhow::Result<bool> {
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
-        let prover_job = prover_job_queue.iter_mut().find(|job| job.id == job_id);
+        let prover_job = prover_job_queue.iter_mut().find(|job| {
+            job.id == job_id
+                && job.job_status == ProverJobStatus::InProgress.to_number()
+                && job.assigned_prover.as_deref() == Some(prover_name)
+        });
 
         if let Some(job) = prover_job {
             job.updated_at = Utc::now();
             job.updated_by = prover_name.to_string();
+            job.lease_expires_at =
+                Some(Utc::now() + chrono::Duration::from_std(lease_duration).unwrap());
+            Ok(true)
+        } else {
+            Ok(false)
         }
-
-        Ok(())
     }
 
     async fn store_proof(
@@ -310,11 +372,13 @@ impl DatabaseInterface for MockDatabase {
         let prover_job_queue = &mut self.prover_job_queue.write().await.1;
 
         for job in prover_job_queue.iter_mut() {
-            if job.updated_by == prover_name
+            if job.assigned_prover.as_deref() == Some(prover_name)
                 && job.job_status == ProverJobStatus::InProgress.to_number()
             {
                 job.job_status = ProverJobStatus::Idle.to_number();
                 job.updated_at = Utc::now();
+                job.assigned_prover = None;
+                job.lease_expires_at = None;
             }
         }
 
@@ -373,11 +437,26 @@ impl DatabaseInterface for MockDatabase {
         Ok(witness)
     }
 
+    async fn load_witness_hash(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let block_witness = self.block_witness.read().await;
+        let witness_hash = block_witness
+            .iter()
+            .find(|witness| witness.block == *block_number as i64)
+            .and_then(|w| w.witness_hash.clone());
+
+        Ok(witness_hash)
+    }
+
     async fn store_witness(
         &self,
         _: &mut StorageProcessor<'_>,
         block: BlockNumber,
         witness: serde_json::Value,
+        witness_hash: Vec<u8>,
     ) -> anyhow::Result<()> {
         let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
         let mut block_witness = self.block_witness.write().await;
@@ -389,9 +468,23 @@ impl DatabaseInterface for MockDatabase {
             block_witness.push(StorageBlockWitness {
                 block: *block as i64,
                 witness: witness_str,
+                witness_hash: Some(witness_hash),
             });
         }
 
         Ok(())
     }
+
+    async fn remove_witness(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        self.block_witness
+            .write()
+            .await
+            .retain(|witness| witness.block != *block as i64);
+
+        Ok(())
+    }
 }
@@ -1,2 +1,3 @@
 mod mock;
 mod prover_server;
+mod witness_export;
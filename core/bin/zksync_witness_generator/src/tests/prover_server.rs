@@ -7,7 +7,7 @@ use num::BigUint;
 use zksync_config::{
     configs::{
         api::ProverApiConfig,
-        prover::{Core, Prover, WitnessGenerator},
+        prover::{Core, ExitProofGenerator, Prover, WitnessGenerator},
     },
     ProverConfig,
 };
@@ -17,7 +17,7 @@ use zksync_prover_utils::api::ProverInputRequest;
 use zksync_types::{block::Block, AccountId, BlockNumber, TokenId, H256};
 // Local deps
 use super::mock::MockDatabase;
-use crate::{run_prover_server, DatabaseInterface};
+use crate::{run_prover_server, witness_hash, DatabaseInterface};
 
 const CORRECT_PROVER_SECRET_AUTH: &str = "42";
 const INCORRECT_PROVER_SECRET_AUTH: &str = "123";
@@ -32,6 +32,7 @@ impl Default for MockProverOptions {
             port: SERVER_BIND_PORT,
             url: SERVER_BIND_TO.to_string(),
             secret_auth: CORRECT_PROVER_SECRET_AUTH.to_string(),
+            witness_export_port: SERVER_BIND_PORT + 1,
         };
         let prover = ProverConfig {
             prover: Prover {
@@ -39,15 +40,22 @@ impl Default for MockProverOptions {
                 cycle_wait: 500,
                 request_timeout: 10,
                 die_after_proof: false,
+                capability_tags: Vec::new(),
             },
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                job_aging_interval: 0,
+                aged_priority_op_threshold: 0,
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
                 witness_generators: 1,
             },
+            exit_proof_generator: ExitProofGenerator {
+                job_check_interval: 1000,
+                job_timeout: 3600,
+            },
         };
 
         MockProverOptions(api, prover)
@@ -161,6 +169,26 @@ async fn test_api_client_simple_simulation(prover_name: &str, database: MockData
     assert!(witness.is_some());
 }
 
+/// Checks that the witness hash survives the storage round trip and detects the witness changes.
+#[test]
+fn test_witness_hash() {
+    let witness = serde_json::json!({
+        "public_data_commitment": "0x1234",
+        "operations": [{ "chunk": 1 }, { "chunk": 2 }],
+        "validator_account_id": 0,
+    });
+    let expected_hash = witness_hash(&witness);
+
+    // Witness is stored as a string in the database.
+    let witness_str = serde_json::to_string(&witness).unwrap();
+    let restored_witness: serde_json::Value = serde_json::from_str(&witness_str).unwrap();
+    assert_eq!(witness_hash(&restored_witness), expected_hash);
+
+    let mut corrupted_witness = restored_witness;
+    corrupted_witness["validator_account_id"] = serde_json::json!(1);
+    assert_ne!(witness_hash(&corrupted_witness), expected_hash);
+}
+
 pub async fn get_test_block() -> Block {
     let (circuit_tree, accounts) = MockDatabase::get_default_tree_and_accounts();
     let validator_account_id = AccountId(0);
@@ -0,0 +1,36 @@
+// Workspace deps
+use zksync_crypto::params::account_tree_depth;
+use zksync_types::{AccountId, BlockNumber};
+// Local deps
+use super::{mock::MockDatabase, prover_server::get_test_block};
+use crate::witness_export::{BlockWitnessExport, WITNESS_EXPORT_VERSION};
+
+/// Checks that the export contains the touched accounts with the valid audit paths
+/// and survives the serialization round trip.
+#[tokio::test]
+async fn test_block_witness_export() {
+    let (_, accounts) = MockDatabase::get_default_tree_and_accounts();
+    let block = get_test_block().await;
+    let new_root_hash = block.new_root_hash;
+
+    let export = BlockWitnessExport::new(block, &accounts).unwrap();
+    assert_eq!(export.version, WITNESS_EXPORT_VERSION);
+    assert_eq!(export.block_number, BlockNumber(1));
+    assert_eq!(export.parameters.account_tree_depth, account_tree_depth());
+    assert_eq!(export.parameters.block_chunks_size, 10);
+    assert_eq!(export.accounts.len(), 1);
+    assert_eq!(export.accounts[0].account_id, AccountId(0));
+    assert_eq!(export.accounts[0].audit_path.len(), account_tree_depth());
+    export.verify_pre_state().unwrap();
+
+    let serialized = serde_json::to_string(&export).unwrap();
+    let mut restored: BlockWitnessExport = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(restored.previous_root_hash, export.previous_root_hash);
+    assert_eq!(restored.witness_hash, export.witness_hash);
+    assert_eq!(restored.block.new_root_hash, new_root_hash);
+    restored.verify_pre_state().unwrap();
+
+    // Pre-state that does not match the root hash must be detected.
+    restored.accounts[0].account_id = AccountId(1);
+    assert!(restored.verify_pre_state().is_err());
+}
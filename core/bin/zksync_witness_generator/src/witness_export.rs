@@ -0,0 +1,242 @@
+//! Export of the data required to regenerate the witness of a block without the full node state.
+//!
+//! The export is a JSON document (`BlockWitnessExport`) containing:
+//!
+//! - `version`: version of the format, bumped on every incompatible change;
+//! - `block_number` and `previous_root_hash`: the block and the root hash of the account tree
+//!   before the block was applied;
+//! - `parameters`: depths of the account and balance trees and the size of the block in chunks;
+//! - `accounts`: pre-state of every account touched by the block (including the fee account),
+//!   ordered by the account id, along with the audit path of the account in the account tree.
+//!   Audit path lists the sibling hashes starting from the leaf level, the sibling is the left one
+//!   if the corresponding bit of the account id is set. `account` is `null` for the accounts
+//!   created in this block;
+//! - `block`: the block itself, as stored by the server;
+//! - `witness_hash`: keccak256 hash of the JSON serialized witness (`ProverData`)
+//!   produced by the witness generator, so the regenerated witness can be compared with ours.
+//!
+//! Field elements are serialized as `0x`-prefixed big-endian hex strings.
+//! The audit paths are enough to rebuild the part of the account tree affected by the block,
+//! see `BlockWitnessExport::verify_pre_state` for the reference hashing procedure.
+//!
+//! The exports are served at `/witness_export/{block_number}` by the read-only server listening
+//! on `ProverApiConfig::witness_export_port`, separately from the prover API, so the auditors
+//! don't need the prover token. Every export rebuilds the whole account tree, so the server
+//! builds one export at a time and rejects the requests made meanwhile.
+
+// Built-in
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+// External
+use actix_web::{dev::Server, web, App, HttpResponse, HttpServer};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+// Workspace deps
+use zksync_circuit::serialization::ProverData;
+use zksync_circuit::witness::utils::build_block_witness;
+use zksync_crypto::circuit::{account::CircuitAccount, CircuitAccountTree};
+use zksync_crypto::merkle_tree::{hasher::Hasher, RescueHasher};
+use zksync_crypto::params::{account_tree_depth, balance_tree_depth};
+use zksync_crypto::primitives::GetBits;
+use zksync_crypto::serialization::{FrSerde, VecFrSerde};
+use zksync_crypto::{Engine, Fr};
+use zksync_types::{block::Block, Account, AccountId, AccountMap, BlockNumber, H256};
+// Local deps
+use crate::database_interface::DatabaseInterface;
+use crate::witness_hash;
+
+/// Version of the export format.
+pub const WITNESS_EXPORT_VERSION: u32 = 1;
+
+/// Parameters of the circuit the witness is generated for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitParameters {
+    pub account_tree_depth: usize,
+    pub balance_tree_depth: usize,
+    pub block_chunks_size: usize,
+}
+
+/// State of the account before the block was applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountPreState {
+    pub account_id: AccountId,
+    /// `None` if the account does not exist before the block.
+    pub account: Option<Account>,
+    /// Sibling hashes from the leaf level up to the root.
+    #[serde(with = "VecFrSerde")]
+    pub audit_path: Vec<Fr>,
+}
+
+/// Everything needed to regenerate the witness of the block, see the module docs for the format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockWitnessExport {
+    pub version: u32,
+    pub block_number: BlockNumber,
+    #[serde(with = "FrSerde")]
+    pub previous_root_hash: Fr,
+    pub parameters: CircuitParameters,
+    pub accounts: Vec<AccountPreState>,
+    pub block: Block,
+    pub witness_hash: H256,
+}
+
+impl BlockWitnessExport {
+    /// Builds the export from the block and the state of the accounts before the block.
+    pub fn new(block: Block, accounts: &AccountMap) -> anyhow::Result<Self> {
+        let mut circuit_account_tree = CircuitAccountTree::new(account_tree_depth());
+        for (id, account) in accounts {
+            circuit_account_tree.insert(**id, account.clone().into());
+        }
+        let previous_root_hash = circuit_account_tree.root_hash();
+
+        let mut touched_accounts = block
+            .block_transactions
+            .iter()
+            .flat_map(|op| op.get_updated_account_ids())
+            .chain(std::iter::once(block.fee_account))
+            .collect::<Vec<_>>();
+        touched_accounts.sort_unstable();
+        touched_accounts.dedup();
+        let accounts = touched_accounts
+            .into_iter()
+            .map(|account_id| AccountPreState {
+                account_id,
+                account: accounts.get(&account_id).cloned(),
+                audit_path: circuit_account_tree
+                    .merkle_path(*account_id)
+                    .into_iter()
+                    .map(|(hash, _)| hash)
+                    .collect(),
+            })
+            .collect();
+
+        let witness: ProverData = build_block_witness(&mut circuit_account_tree, &block)?.into();
+        anyhow::ensure!(
+            circuit_account_tree.root_hash() == block.new_root_hash,
+            "Root hash after applying block {} does not match the stored one",
+            block.block_number
+        );
+        let witness = serde_json::to_value(witness)?;
+
+        Ok(Self {
+            version: WITNESS_EXPORT_VERSION,
+            block_number: block.block_number,
+            previous_root_hash,
+            parameters: CircuitParameters {
+                account_tree_depth: account_tree_depth(),
+                balance_tree_depth: balance_tree_depth(),
+                block_chunks_size: block.block_chunks_size,
+            },
+            accounts,
+            witness_hash: H256::from_slice(&witness_hash(&witness)),
+            block,
+        })
+    }
+
+    /// Checks that the audit paths of the exported accounts lead to the previous root hash.
+    pub fn verify_pre_state(&self) -> anyhow::Result<()> {
+        let hasher = RescueHasher::<Engine>::default();
+        for pre_state in &self.accounts {
+            let leaf: CircuitAccount<Engine> = pre_state
+                .account
+                .clone()
+                .map(Into::into)
+                .unwrap_or_default();
+            let mut hash = hasher.hash_bits(leaf.get_bits_le());
+            for (level, sibling) in pre_state.audit_path.iter().enumerate() {
+                hash = if (*pre_state.account_id >> level) & 1 == 1 {
+                    hasher.compress(sibling, &hash, level)
+                } else {
+                    hasher.compress(&hash, sibling, level)
+                };
+            }
+            anyhow::ensure!(
+                hash == self.previous_root_hash,
+                "Audit path of the account {} does not match the previous root hash",
+                pre_state.account_id
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Loads the block and the state before it and builds the export, returns `None`
+/// if the block does not exist.
+///
+/// The account tree is always restored from scratch, so the export does not rely
+/// on the tree cache of the witness generator.
+pub async fn export_block_witness<DB: DatabaseInterface>(
+    database: &DB,
+    block_number: BlockNumber,
+) -> anyhow::Result<Option<BlockWitnessExport>> {
+    let start = Instant::now();
+    let mut storage = database.acquire_connection().await?;
+    let block = match database.load_block(&mut storage, block_number).await? {
+        Some(block) => block,
+        None => return Ok(None),
+    };
+    let (_, accounts) = database
+        .load_committed_state(&mut storage, Some(block_number - 1))
+        .await?;
+    drop(storage);
+
+    // Building the tree takes seconds, so it's not done on the async runtime.
+    let export =
+        tokio::task::spawn_blocking(move || BlockWitnessExport::new(block, &accounts)).await??;
+    metrics::histogram!("witness_generator", start.elapsed(), "stage" => "export_block_witness");
+    Ok(Some(export))
+}
+
+#[derive(Debug, Clone)]
+struct ExportServerState<DB: DatabaseInterface> {
+    database: DB,
+    /// Holds a single permit, taken by the export in progress.
+    export_permit: Arc<Semaphore>,
+}
+
+async fn export_witness<DB: DatabaseInterface>(
+    data: web::Data<ExportServerState<DB>>,
+    block_number: web::Path<u32>,
+) -> actix_web::Result<HttpResponse> {
+    let block_number = BlockNumber(block_number.into_inner());
+    let _permit = data.export_permit.try_acquire().map_err(|_| {
+        actix_web::error::ErrorServiceUnavailable("another witness is being exported, retry later")
+    })?;
+    let export = export_block_witness(&data.database, block_number)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to export witness for block {}: {}", block_number, e);
+            actix_web::error::ErrorInternalServerError("failed to export witness")
+        })?;
+
+    match export {
+        Some(export) => Ok(HttpResponse::Ok().json(export)),
+        None => Err(actix_web::error::ErrorNotFound("block not found")),
+    }
+}
+
+/// Binds the read-only server of the witness exports, see the module docs.
+pub fn witness_export_server<DB: DatabaseInterface>(
+    database: DB,
+    bind_addr: SocketAddr,
+) -> std::io::Result<Server> {
+    let export_permit = Arc::new(Semaphore::new(1));
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(ExportServerState {
+                database: database.clone(),
+                export_permit: export_permit.clone(),
+            }))
+            .route(
+                "/witness_export/{block_number}",
+                web::get().to(export_witness::<DB>),
+            )
+    })
+    .bind(bind_addr)?
+    .run();
+    Ok(server)
+}
@@ -7,6 +7,7 @@ use tokio::time::sleep;
 use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
 // Workspace deps
 use crate::database_interface::DatabaseInterface;
+use crate::witness_hash;
 use zksync_circuit::serialization::ProverData;
 use zksync_circuit::witness::utils::build_block_witness;
 use zksync_crypto::circuit::CircuitAccountTree;
@@ -71,6 +72,52 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             .expect("failed to start provers server");
     }
 
+    /// Starts the thread generating the witness of the `start_block` again, e.g. after the stored
+    /// one didn't match its hash and was removed. The generation is retried until the witness
+    /// is stored.
+    pub fn start_regeneration(self) {
+        thread::Builder::new()
+            .name("witness_regeneration".to_string())
+            .spawn(move || {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Unable to build runtime for a witness regeneration");
+
+                runtime.block_on(async move {
+                    self.regenerate().await;
+                });
+            })
+            .expect("failed to start witness regeneration");
+    }
+
+    async fn regenerate(self) {
+        let block_number = self.start_block;
+        loop {
+            match self.should_work_on_block(block_number).await {
+                Ok(BlockInfo::NoWitness(block)) => {
+                    match self.prepare_witness_and_save_it(block).await {
+                        Ok(()) => {
+                            vlog::info!("Witness for block {} is generated again", block_number);
+                            return;
+                        }
+                        Err(err) => vlog::warn!(
+                            "Failed to generate the witness for block {} again: {}",
+                            block_number,
+                            err
+                        ),
+                    }
+                }
+                // The block is reverted or its witness is already stored.
+                Ok(_) => return,
+                Err(err) => {
+                    vlog::warn!("witness for block {} check failed: {}", block_number, err)
+                }
+            }
+            sleep(self.rounds_interval).await;
+        }
+    }
+
     /// Returns status of witness for block with index block_number
     async fn should_work_on_block(
         &self,
@@ -229,12 +276,12 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         metrics::histogram!("witness_generator", start.elapsed(), "stage" => "build_witness");
 
         let start = Instant::now();
+        let witness = serde_json::to_value(witness).expect("Witness serialize to json");
+        // The hash is stored along with the witness, so the prover server is able to detect
+        // the corrupted witness before creating a prover job for it.
+        let witness_hash = witness_hash(&witness);
         self.database
-            .store_witness(
-                &mut storage,
-                block.block_number,
-                serde_json::to_value(witness).expect("Witness serialize to json"),
-            )
+            .store_witness(&mut storage, block.block_number, witness, witness_hash)
             .await?;
         metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_witness");
 
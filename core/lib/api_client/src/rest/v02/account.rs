@@ -1,10 +1,14 @@
 use crate::rest::client::{Client, Result};
 
 use zksync_api_types::v02::{
-    pagination::{ApiEither, PaginationQuery},
+    account::{
+        AccountStateQuery, AccountsBatchRequest, DepositingBalancesVersion,
+        IncomingAccountStateChangesQuery,
+    },
+    pagination::{ApiEither, Cursor, PaginationQuery},
     Response,
 };
-use zksync_types::{tx::TxHash, SerialId};
+use zksync_types::{tx::TxHash, Address, SerialId};
 
 impl Client {
     pub async fn account_info(
@@ -20,15 +24,27 @@ impl Client {
         .await
     }
 
-    pub async fn account_full_info(&self, account_id_or_address: &str) -> Result<Response> {
+    pub async fn account_full_info(
+        &self,
+        account_id_or_address: &str,
+        depositing_version: DepositingBalancesVersion,
+    ) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
             &format!("accounts/{}", account_id_or_address),
         )
+        .query(&AccountStateQuery { depositing_version })
         .send()
         .await
     }
 
+    pub async fn accounts_batch_info(&self, addresses: Vec<Address>) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "accounts/batch")
+            .body(&AccountsBatchRequest { addresses })
+            .send()
+            .await
+    }
+
     pub async fn account_txs(
         &self,
         pagination_query: &PaginationQuery<ApiEither<TxHash>>,
@@ -43,6 +59,91 @@ impl Client {
         .await
     }
 
+    pub async fn account_txs_by_cursor(
+        &self,
+        pagination_query: &PaginationQuery<Option<Cursor>>,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/transactions/history", account_id_or_address),
+        )
+        .query(pagination_query)
+        .send()
+        .await
+    }
+
+    pub async fn account_state_changes(
+        &self,
+        query: &IncomingAccountStateChangesQuery,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/events", account_id_or_address),
+        )
+        .query(query)
+        .send()
+        .await
+    }
+
+    pub async fn account_next_nonce(&self, account_id_or_address: &str) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/nonce/next", account_id_or_address),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn account_pubkey_auth(
+        &self,
+        account_id_or_address: &str,
+        nonce: u32,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/pubkey_auth/{}", account_id_or_address, nonce),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn request_account_exit_proof(
+        &self,
+        account_id_or_address: &str,
+        token: &str,
+    ) -> Result<Response> {
+        self.post_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/exit_proof/{}", account_id_or_address, token),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn account_exit_proof(
+        &self,
+        account_id_or_address: &str,
+        token: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/exit_proof/{}", account_id_or_address, token),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn account_full_exits(&self, account_id_or_address: &str) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/full_exits", account_id_or_address),
+        )
+        .send()
+        .await
+    }
+
     pub async fn account_pending_txs(
         &self,
         pagination_query: &PaginationQuery<ApiEither<SerialId>>,
@@ -1,5 +1,6 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::v02::Response;
+use zksync_types::BlockNumber;
 
 impl Client {
     pub async fn config(&self) -> Result<Response> {
@@ -7,4 +8,19 @@ impl Client {
             .send()
             .await
     }
+
+    pub async fn protocol_parameters_history(&self) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "config/protocolParameters")
+            .send()
+            .await
+    }
+
+    pub async fn block_protocol_parameters(&self, block_number: BlockNumber) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("config/protocolParameters/{}", block_number),
+        )
+        .send()
+        .await
+    }
 }
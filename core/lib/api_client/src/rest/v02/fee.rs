@@ -1,7 +1,9 @@
 // Local uses
 use crate::rest::client::{Client, Result};
 use zksync_api_types::v02::{
-    fee::{ApiTxFeeTypes, BatchFeeRequest, TxFeeRequest, TxInBatchFeeRequest},
+    fee::{
+        ApiTxFeeTypes, BatchFeeRequest, IncomingFeeHistoryQuery, TxFeeRequest, TxInBatchFeeRequest,
+    },
     Response,
 };
 use zksync_types::{Address, TokenLike};
@@ -36,4 +38,17 @@ impl Client {
             .send()
             .await
     }
+
+    /// Requires the admin authorization token.
+    pub async fn fee_history(
+        &self,
+        auth_token: &str,
+        query: &IncomingFeeHistoryQuery,
+    ) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "fee/history")
+            .bearer_auth(auth_token)
+            .query(query)
+            .send()
+            .await
+    }
 }
@@ -1,9 +1,13 @@
 pub mod account;
 pub mod block;
 pub mod config;
+pub mod error;
+pub mod event;
 pub mod fee;
+pub mod search;
 pub mod status;
 pub mod token;
 pub mod transaction;
+pub mod withdrawal;
 
 const API_V02_SCOPE: &str = "/api/v0.2/";
@@ -1,9 +1,15 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::{
-    v02::{transaction::IncomingTxBatch, Response},
+    v02::{
+        transaction::{IncomingTxBatch, SubmissionMode, SubmitTxQuery},
+        Response,
+    },
     TxWithSignature,
 };
-use zksync_types::tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash, ZkSyncTx};
+use zksync_types::{
+    tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash, ZkSyncTx},
+    BlockNumber, H256,
+};
 
 impl Client {
     pub async fn submit_tx(
@@ -17,6 +23,41 @@ impl Client {
             .await
     }
 
+    /// Submits the transaction without waiting for its verification,
+    /// the eventual rejection is reported by the receipt.
+    pub async fn submit_tx_async(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions")
+            .query(&SubmitTxQuery {
+                mode: SubmissionMode::Async,
+                not_before_block: None,
+            })
+            .body(&TxWithSignature { tx, signature })
+            .send()
+            .await
+    }
+
+    /// Submits the transaction that isn't executed until the block `not_before_block`
+    /// is committed.
+    pub async fn submit_scheduled_tx(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        not_before_block: BlockNumber,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions")
+            .query(&SubmitTxQuery {
+                mode: SubmissionMode::Sync,
+                not_before_block: Some(*not_before_block),
+            })
+            .body(&TxWithSignature { tx, signature })
+            .send()
+            .await
+    }
+
     pub async fn submit_batch(
         &self,
         txs: Vec<TxWithSignature>,
@@ -46,6 +87,24 @@ impl Client {
         .await
     }
 
+    pub async fn tx_submission_status(&self, tx_hash: TxHash) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/submission", tx_hash.to_string()),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn priority_ops_by_eth_hash(&self, eth_tx_hash: H256) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/eth/{:?}", eth_tx_hash),
+        )
+        .send()
+        .await
+    }
+
     pub async fn get_batch(&self, batch_hash: TxHash) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
@@ -0,0 +1,14 @@
+use crate::rest::client::{Client, Result};
+use zksync_api_types::v02::Response;
+use zksync_types::tx::TxHash;
+
+impl Client {
+    pub async fn withdrawal(&self, tx_hash: TxHash) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("withdrawals/{}", tx_hash.to_string()),
+        )
+        .send()
+        .await
+    }
+}
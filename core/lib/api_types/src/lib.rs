@@ -2,7 +2,7 @@ pub use either::Either;
 use serde::{Deserialize, Serialize};
 use zksync_types::{
     tx::{TxEthSignatureVariant, TxHash},
-    ZkSyncTx, H256,
+    BlockNumber, SignedZkSyncTx, ZkSyncTx, H256,
 };
 
 pub mod v02;
@@ -35,3 +35,11 @@ pub struct CoreStatus {
     pub replica_database_available: bool,
     pub web3_available: bool,
 }
+
+/// Checked transaction forwarded to the mempool of the core server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewTxRequest {
+    pub tx: SignedZkSyncTx,
+    /// If set, the transaction isn't executed until this block is committed.
+    pub not_before_block: Option<BlockNumber>,
+}
@@ -3,8 +3,10 @@ use std::collections::{BTreeMap, HashMap};
 use num::{BigUint, ToPrimitive};
 use serde::{Deserialize, Serialize};
 
+use zksync_crypto::proof::EncodedSingleProof;
 use zksync_types::{
-    AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, TokenId, ZkSyncPriorityOp,
+    block::Block, AccountId, AccountUpdate, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash,
+    SerialId, TokenId, ZkSyncPriorityOp, H256,
 };
 use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
 
@@ -33,6 +35,22 @@ pub struct Account {
     pub account_type: Option<EthAccountType>,
 }
 
+/// Request of the bulk account info endpoint.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsBatchRequest {
+    pub addresses: Vec<Address>,
+}
+
+/// State of the account from the bulk request, both states are `None` for unknown addresses.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBatchState {
+    pub address: Address,
+    pub committed: Option<Account>,
+    pub finalized: Option<Account>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum AccountAddressOrId {
@@ -100,10 +118,90 @@ pub struct DepositingFunds {
     pub expected_accept_block: u64,
 }
 
+/// Funds being deposited in the token, along with the symbol of the token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositingTokenFunds {
+    pub symbol: String,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    pub expected_accept_block: u64,
+}
+
+/// Deposit waiting for the confirmations on L1.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositProgress {
+    pub eth_tx_hash: H256,
+    pub token_id: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    pub received_on_block: u64,
+    pub expected_accept_block: u64,
+    /// `None` if the latest Ethereum block is unknown.
+    pub remaining_confirmations: Option<u64>,
+    /// Estimated time until the deposit is accepted, `None` if the block time is unknown.
+    pub eta_seconds: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DepositingAccountBalances {
+    /// Funds keyed by the token symbol, the tokens sharing the symbol are summed up.
     pub balances: HashMap<String, DepositingFunds>,
+    /// Funds keyed by the token id, only included in the `v2` version of the balances.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balances_by_token_id: Option<BTreeMap<TokenId, DepositingTokenFunds>>,
+    /// Every deposit of the account, in the order of the priority operations.
+    #[serde(default)]
+    pub deposits: Vec<DepositProgress>,
+}
+
+impl DepositingAccountBalances {
+    /// Aggregates the funds by the token symbol, keeping the funds by the token id
+    /// if they are requested.
+    pub fn new(
+        by_token_id: BTreeMap<TokenId, DepositingTokenFunds>,
+        deposits: Vec<DepositProgress>,
+        include_token_ids: bool,
+    ) -> Self {
+        let mut balances: HashMap<String, DepositingFunds> = HashMap::new();
+        for funds in by_token_id.values() {
+            let balance = balances.entry(funds.symbol.clone()).or_default();
+            balance.amount += &funds.amount;
+            balance.expected_accept_block = balance
+                .expected_accept_block
+                .max(funds.expected_accept_block);
+        }
+        Self {
+            balances,
+            balances_by_token_id: Some(by_token_id).filter(|_| include_token_ids),
+            deposits,
+        }
+    }
+}
+
+/// Version of the depositing balances in the account state.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DepositingBalancesVersion {
+    /// Balances keyed by the token symbol.
+    V1,
+    /// Balances keyed both by the token symbol and by the token id.
+    V2,
+}
+
+impl Default for DepositingBalancesVersion {
+    fn default() -> Self {
+        Self::V1
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateQuery {
+    #[serde(default)]
+    pub depositing_version: DepositingBalancesVersion,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -115,3 +213,255 @@ pub struct IncomingAccountTxsQuery {
     pub token: Option<String>,
     pub second_account: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingAccountTxsCursorQuery {
+    /// Opaque cursor returned with the previous page, absent for the first page.
+    pub from: Option<String>,
+    pub limit: u32,
+    pub direction: PaginationDirection,
+    pub token: Option<String>,
+    pub second_account: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingAccountStateChangesQuery {
+    /// First block of the range, the genesis block by default.
+    pub from_block: Option<u32>,
+    /// Last block of the range, the last committed block by default.
+    pub to_block: Option<u32>,
+}
+
+/// Atomic change of the account state, the API counterpart of `AccountUpdate`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AccountStateChangeKind {
+    Create {
+        address: Address,
+        nonce: Nonce,
+    },
+    Delete {
+        address: Address,
+        nonce: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    UpdateBalance {
+        token_id: TokenId,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        old_balance: BigUint,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        new_balance: BigUint,
+        old_nonce: Nonce,
+        new_nonce: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    ChangePubKeyHash {
+        old_pub_key_hash: PubKeyHash,
+        new_pub_key_hash: PubKeyHash,
+        old_nonce: Nonce,
+        new_nonce: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    MintNFT {
+        token_id: TokenId,
+        nonce: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    RemoveNFT {
+        token_id: TokenId,
+        nonce: Nonce,
+    },
+}
+
+impl From<AccountUpdate> for AccountStateChangeKind {
+    fn from(update: AccountUpdate) -> Self {
+        match update {
+            AccountUpdate::Create { address, nonce } => Self::Create { address, nonce },
+            AccountUpdate::Delete { address, nonce } => Self::Delete { address, nonce },
+            AccountUpdate::UpdateBalance {
+                old_nonce,
+                new_nonce,
+                balance_update: (token_id, old_balance, new_balance),
+            } => Self::UpdateBalance {
+                token_id,
+                old_balance,
+                new_balance,
+                old_nonce,
+                new_nonce,
+            },
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash,
+                new_pub_key_hash,
+                old_nonce,
+                new_nonce,
+            } => Self::ChangePubKeyHash {
+                old_pub_key_hash,
+                new_pub_key_hash,
+                old_nonce,
+                new_nonce,
+            },
+            AccountUpdate::MintNFT { token, nonce } => Self::MintNFT {
+                token_id: token.id,
+                nonce,
+            },
+            AccountUpdate::RemoveNFT { token, nonce } => Self::RemoveNFT {
+                token_id: token.id,
+                nonce,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateChange {
+    pub block_number: BlockNumber,
+    #[serde(flatten)]
+    pub change: AccountStateChangeKind,
+}
+
+/// State changes of the account in the range of blocks, in the order of applying.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateChanges {
+    pub from_block: BlockNumber,
+    /// Last block of the range. It may be less than the requested one, since
+    /// the range is limited, the next range should be requested from the next block.
+    pub to_block: BlockNumber,
+    pub changes: Vec<AccountStateChange>,
+}
+
+/// Nonce to be used by the next transaction of the account.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountNextNonce {
+    /// Nonce of the account in the last committed state.
+    pub committed_nonce: Nonce,
+    /// Nonce following the transactions of the account queued in the mempool.
+    pub next_nonce: Nonce,
+}
+
+/// Status of the onchain authorization of the `ChangePubKey` transaction
+/// made by the `setAuthPubkeyHash` call of the contract.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PubKeyAuthStatus {
+    pub address: Address,
+    pub nonce: Nonce,
+    /// Fact stored by the contract, i.e. the hash of the authorized public key hash,
+    /// `None` if there's no authorization.
+    pub fact: Option<H256>,
+    /// Hash of the Ethereum transaction that has set the fact, `None` if the transaction
+    /// is older than the recent blocks searched by the server.
+    pub eth_tx_hash: Option<H256>,
+    pub eth_block: Option<u64>,
+    /// Number of the Ethereum blocks confirming the authorization, including its own block.
+    pub confirmations: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ExitProofStatus {
+    Queued,
+    InProgress,
+    Ready,
+    Failed,
+}
+
+/// Block info in the format stored by the contract, required to prove the exit against it.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredBlockInfo {
+    pub block_number: BlockNumber,
+    pub priority_operations: u64,
+    pub pending_onchain_operations_hash: H256,
+    pub timestamp: u64,
+    pub state_hash: H256,
+    pub commitment: H256,
+}
+
+impl StoredBlockInfo {
+    pub fn from_block(block: &Block) -> Self {
+        Self {
+            block_number: block.block_number,
+            priority_operations: block.number_of_processed_prior_ops(),
+            pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
+            timestamp: block.timestamp,
+            state_hash: block.get_eth_encoded_root(),
+            commitment: block.block_commitment,
+        }
+    }
+}
+
+/// Inputs of the `performExodus` call of the contract.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitProofData {
+    pub stored_block_info: StoredBlockInfo,
+    pub owner: Address,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub token_address: Address,
+    pub amount: BigUintSerdeWrapper,
+    pub nft_creator_id: AccountId,
+    pub nft_creator_address: Address,
+    pub nft_serial_id: u32,
+    pub nft_content_hash: H256,
+    pub proof: EncodedSingleProof,
+}
+
+/// Exit proof of the account balance against the state of the last finalized block.
+/// The proof is generated asynchronously once requested, so its state is polled until it's ready.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitProofInfo {
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    /// Block the proof is generated against, present once the status is `ready`.
+    pub block_number: Option<BlockNumber>,
+    pub status: ExitProofStatus,
+    /// Reason of the failure if the status is `failed`.
+    pub error: Option<String>,
+    /// Present once the status is `ready`.
+    pub data: Option<ExitProofData>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum FullExitStatus {
+    /// The request doesn't have enough confirmations on L1 yet.
+    AwaitingConfirmations,
+    /// The request is confirmed and waits to be included into a block.
+    Queued,
+    Committed,
+    Finalized,
+}
+
+/// Full exit requested by the owner of the account on L1.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FullExitInfo {
+    pub serial_id: SerialId,
+    pub eth_tx_hash: H256,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub received_on_block: u64,
+    pub status: FullExitStatus,
+    /// Number of the Ethereum blocks left until the request is confirmed,
+    /// `None` once it's confirmed or if the latest Ethereum block is unknown.
+    pub remaining_confirmations: Option<u64>,
+    /// Block the full exit is executed in.
+    pub block_number: Option<BlockNumber>,
+    /// Withdrawn amount, `None` until the full exit is executed or if it has failed.
+    pub withdraw_amount: Option<BigUintSerdeWrapper>,
+}
+
+/// Full exits of the account, the newest first.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountFullExits {
+    pub address: Address,
+    pub full_exits: Vec<FullExitInfo>,
+}
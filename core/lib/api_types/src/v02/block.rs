@@ -10,6 +10,20 @@ pub enum BlockStatus {
     Finalized,
 }
 
+/// Maximum number of blocks in the range requested via `/blocks/range`.
+pub const MAX_BLOCK_RANGE: u32 = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockRangeQuery {
+    /// First block of the range, inclusive.
+    pub from: u32,
+    /// Last block of the range, inclusive.
+    pub to: u32,
+    /// Only the blocks with the status are returned if set.
+    pub status: Option<BlockStatus>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockInfo {
@@ -23,3 +37,17 @@ pub struct BlockInfo {
     pub finalized_at: Option<DateTime<Utc>>,
     pub status: BlockStatus,
 }
+
+/// Event of the `/blocks/stream` endpoint, emitted once the block is committed or finalized.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockStreamEvent {
+    pub block_number: BlockNumber,
+    /// Root hash of the state after the block, serves as the block hash.
+    #[serde(with = "FrSerde")]
+    pub new_state_root: Fr,
+    pub tx_count: u32,
+    /// Time of the block commitment or finalization, depending on the status.
+    pub timestamp: DateTime<Utc>,
+    pub status: BlockStatus,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_types::{tx::TxHash, H256};
+use zksync_utils::ZeroPrefixHexSerde;
+
+use crate::{v02::transaction::IncomingTxBatch, TxWithSignature};
+
+/// Transaction or batch encrypted to the public key of the operator.
+/// The envelope is only decrypted and its transactions are only submitted to the mempool
+/// once the visibility delay has passed, so nobody can react to them before that.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingEncryptedTx {
+    /// ECIES ciphertext of the JSON-serialized `EncryptedTxPayload`.
+    #[serde(with = "ZeroPrefixHexSerde")]
+    pub envelope: Vec<u8>,
+}
+
+/// Plaintext of the envelope, has the same format as the body of the regular submission request.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum EncryptedTxPayload {
+    Tx(TxWithSignature),
+    Batch(IncomingTxBatch),
+}
+
+/// Public key used to encrypt the envelopes.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionKey {
+    /// Uncompressed secp256k1 public key without the `0x04` prefix.
+    #[serde(with = "ZeroPrefixHexSerde")]
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EncryptedTxState {
+    /// Waiting for the visibility delay to pass.
+    Pending,
+    /// Transactions were submitted to the mempool.
+    Revealed,
+    /// Envelope couldn't be decrypted or its transactions were rejected by the mempool.
+    Rejected,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedTxStatus {
+    /// Keccak256 hash of the envelope, identifies the submission.
+    pub envelope_hash: H256,
+    pub state: EncryptedTxState,
+    pub created_at: DateTime<Utc>,
+    pub reveal_at: DateTime<Utc>,
+    pub revealed_at: Option<DateTime<Utc>>,
+    /// Hashes of the submitted transactions, empty until the envelope is revealed.
+    pub tx_hashes: Vec<TxHash>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use zksync_types::{Address, BlockNumber, TokenId};
+
+use crate::v02::{
+    pagination::{Cursor, PaginationDirection},
+    transaction::Transaction,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingEventsQuery {
+    /// Opaque cursor returned with the previous page, absent for the first page.
+    pub from: Option<String>,
+    pub limit: u32,
+    pub direction: PaginationDirection,
+    pub address: Option<Address>,
+    pub token: Option<String>,
+    pub op_type: Option<String>,
+    pub from_block: Option<u32>,
+    pub to_block: Option<u32>,
+}
+
+/// Filters of the events, similar to the `eth_getLogs` ones.
+/// Only the events matching all the specified filters are returned.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EventsFilter {
+    /// Address of any of the accounts affected by the operation.
+    pub address: Option<Address>,
+    /// Any of the tokens used by the operation, including the fee token.
+    pub token: Option<TokenId>,
+    /// Type of the operation, e.g. `Transfer` or `Deposit`.
+    pub op_type: Option<String>,
+    /// Inclusive range of the blocks.
+    pub from_block: Option<BlockNumber>,
+    pub to_block: Option<BlockNumber>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventsRequest {
+    pub filter: EventsFilter,
+    /// Events are loaded starting right after the cursor, from the beginning
+    /// of the history in the requested direction if `None`.
+    pub cursor: Option<Cursor>,
+}
+
+/// Event of the executed L2 transaction or priority operation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2Event {
+    /// Position of the event, the events are ordered by it.
+    pub cursor: Cursor,
+    pub op_type: String,
+    pub tx: Transaction,
+}
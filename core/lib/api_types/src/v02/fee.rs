@@ -1,8 +1,15 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use num::BigUint;
 use serde::{Deserialize, Serialize};
-use zksync_types::{tokens::ChangePubKeyFeeTypeArg, Address, BatchFee, Fee, TokenLike, TxFeeTypes};
+use zksync_types::{
+    tokens::ChangePubKeyFeeTypeArg, Address, BatchFee, Fee, TokenId, TokenLike, TxFeeTypes,
+};
 use zksync_utils::BigUintSerdeAsRadix10Str;
 
+/// Maximum number of the fee quotes returned by the single history request.
+pub const MAX_FEE_QUOTES: u32 = 100;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiFee {
@@ -84,6 +91,10 @@ pub struct TxFeeRequest {
 pub struct TxInBatchFeeRequest {
     pub tx_type: ApiTxFeeTypes,
     pub address: Address,
+    /// Token of the transaction, if provided, it has to be known to the server.
+    /// The fee itself is paid in the token of the batch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<TokenLike>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -92,3 +103,42 @@ pub struct BatchFeeRequest {
     pub transactions: Vec<TxInBatchFeeRequest>,
     pub token_like: TokenLike,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingFeeHistoryQuery {
+    /// Fee token: id, address or symbol.
+    pub token: Option<String>,
+    /// Recipient of the transaction, the quotes for the batches are matched by any of them.
+    pub address: Option<Address>,
+    /// Type of the transaction, the quotes for the batches are matched by any of them.
+    pub tx_type: Option<String>,
+    /// Start of the time range, one day before its end by default.
+    pub from: Option<DateTime<Utc>>,
+    /// End of the time range, the current time by default.
+    pub to: Option<DateTime<Utc>>,
+    /// Maximum number of the quotes, `MAX_FEE_QUOTES` by default.
+    pub limit: Option<u32>,
+}
+
+/// Fee quote served by the server along with the market data it's calculated from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiFeeQuote {
+    /// Types of the transactions, a single one unless the quote is for a batch.
+    pub tx_types: Vec<String>,
+    pub addresses: Vec<Address>,
+    pub token_id: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas_fee: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub zkp_fee: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub total_fee: BigUint,
+    pub subsidized: bool,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas_price_wei: BigUint,
+    /// Price of the whole fee token in USD.
+    pub token_usd_price: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+
+use zksync_types::{tx::TxHash, AccountId, Address, TokenId};
+use zksync_utils::{BigUintSerdeWrapper, ZeroPrefixHexSerde};
+
+/// Pending transaction exposed by the public mempool feed.
+///
+/// Depending on the server configuration, amounts, fees and addresses may be redacted,
+/// in which case the corresponding fields are `null`. Redacted addresses also hide the
+/// account id and the hash of the transaction, since both of them identify the sender.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTx {
+    #[serde(serialize_with = "serialize_tx_hash")]
+    pub tx_hash: Option<TxHash>,
+    pub tx_type: String,
+    pub account_id: Option<AccountId>,
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub token: TokenId,
+    pub amount: Option<BigUintSerdeWrapper>,
+    pub fee: Option<BigUintSerdeWrapper>,
+    pub created_at: DateTime<Utc>,
+}
+
+fn serialize_tx_hash<S: Serializer>(
+    tx_hash: &Option<TxHash>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match tx_hash {
+        Some(tx_hash) => ZeroPrefixHexSerde::serialize(tx_hash, serializer),
+        None => serializer.serialize_none(),
+    }
+}
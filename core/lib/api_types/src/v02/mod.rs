@@ -6,11 +6,18 @@ use zksync_types::network::Network;
 
 pub mod account;
 pub mod block;
+pub mod encrypted_tx;
+pub mod event;
 pub mod fee;
+pub mod mempool;
 pub mod pagination;
+pub mod recovery;
+pub mod search;
 pub mod status;
 pub mod token;
 pub mod transaction;
+pub mod withdrawal;
+pub mod ws;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
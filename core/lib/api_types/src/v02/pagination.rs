@@ -1,6 +1,6 @@
 use either::Either;
-use serde::{Deserialize, Serialize, Serializer};
-use std::str::FromStr;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
 use thiserror::Error;
 use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, SerialId, TokenId};
 
@@ -42,9 +42,7 @@ impl<T: FromStr + Serialize> FromStr for ApiEither<T> {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "latest" => Ok(ApiEither {
-                inner: Either::Right(Latest),
-            }),
+            "latest" => Ok(ApiEither::latest()),
             _ => {
                 if let Ok(value) = T::from_str(s) {
                     Ok(ApiEither::from(value))
@@ -56,6 +54,14 @@ impl<T: FromStr + Serialize> FromStr for ApiEither<T> {
     }
 }
 
+impl<T: Serialize> ApiEither<T> {
+    pub fn latest() -> Self {
+        Self {
+            inner: Either::Right(Latest),
+        }
+    }
+}
+
 impl<T: Serialize> From<T> for ApiEither<T> {
     fn from(value: T) -> ApiEither<T> {
         ApiEither {
@@ -139,3 +145,105 @@ pub struct AccountTxsRequest {
     pub token: Option<TokenId>,
     pub second_address: Option<Address>,
 }
+
+/// Version of the cursor encoding, allows to change the format without breaking the clients.
+const CURSOR_VERSION: u8 = 1;
+
+/// Opaque cursor of the cursor-based pagination.
+///
+/// Points to the position of the item in the sequence of the executed transactions
+/// and priority operations, so the page is loaded with a single index scan regardless
+/// of the position in the history. Clients must not rely on the cursor format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor(pub i64);
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut bytes = vec![CURSOR_VERSION];
+        bytes.extend_from_slice(&self.0.to_be_bytes());
+        write!(f, "{}", hex::encode(bytes))
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = UnknownFromParameter;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|_| UnknownFromParameter(s.to_string()))?;
+        match bytes.split_first() {
+            Some((&CURSOR_VERSION, position)) if position.len() == 8 => {
+                let mut be_bytes = [0u8; 8];
+                be_bytes.copy_from_slice(position);
+                Ok(Cursor(i64::from_be_bytes(be_bytes)))
+            }
+            _ => Err(UnknownFromParameter(s.to_string())),
+        }
+    }
+}
+
+impl Serialize for Cursor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPaginationDetails {
+    /// `None` for the first page.
+    pub from: Option<Cursor>,
+    pub limit: u32,
+    pub direction: PaginationDirection,
+    /// Cursor of the next page, `None` if there are no more items.
+    pub next: Option<Cursor>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPaginated<T: Sized + Serialize> {
+    pub list: Vec<T>,
+    pub pagination: CursorPaginationDetails,
+}
+
+impl<T: Sized + Serialize> CursorPaginated<T> {
+    pub fn new(
+        list: Vec<T>,
+        from: Option<Cursor>,
+        limit: u32,
+        direction: PaginationDirection,
+        next: Option<Cursor>,
+    ) -> Self {
+        Self {
+            list,
+            pagination: CursorPaginationDetails {
+                from,
+                limit,
+                direction,
+                next,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountTxsCursorRequest {
+    pub address: Address,
+    /// Items are loaded starting right after the cursor, from the beginning
+    /// of the history in the requested direction if `None`.
+    pub cursor: Option<Cursor>,
+    pub token: Option<TokenId>,
+    pub second_address: Option<Address>,
+}
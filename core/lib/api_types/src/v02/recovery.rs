@@ -0,0 +1,95 @@
+use chrono::serde::ts_milliseconds;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_types::{tx::TxEthSignature, AccountId, Address, PubKeyHash};
+
+/// Request of the account owner to set the guardians of the account.
+/// Signed by the Ethereum key of the account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetGuardians {
+    pub account_id: AccountId,
+    pub guardians: Vec<Address>,
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+    pub signature: TxEthSignature,
+}
+
+impl SetGuardians {
+    pub fn get_ethereum_sign_message(&self) -> String {
+        format!(
+            "By signing this message, you are allowing the following addresses to recover your zkSync account {}.\n\
+            Recovery changes the public key of the account after the timelock, unless you cancel it.\n\
+            Guardians: {}\n\
+            Timestamp: {}",
+            self.account_id,
+            self.guardians
+                .iter()
+                .map(|guardian| format!("{:?}", guardian))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.timestamp.timestamp_millis()
+        )
+    }
+}
+
+/// Request of the guardian to recover the account, signed by the Ethereum key of the guardian.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InitiateRecovery {
+    pub account_id: AccountId,
+    pub guardian: Address,
+    pub new_pub_key_hash: PubKeyHash,
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+    pub signature: TxEthSignature,
+}
+
+impl InitiateRecovery {
+    pub fn get_ethereum_sign_message(&self) -> String {
+        format!(
+            "Recover zkSync account {}.\n\
+            New public key hash: {}\n\
+            Timestamp: {}",
+            self.account_id,
+            self.new_pub_key_hash.as_hex(),
+            self.timestamp.timestamp_millis()
+        )
+    }
+}
+
+/// Request of the account owner to cancel the recovery in progress.
+/// Signed by the Ethereum key of the account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelRecovery {
+    pub account_id: AccountId,
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+    pub signature: TxEthSignature,
+}
+
+impl CancelRecovery {
+    pub fn get_ethereum_sign_message(&self) -> String {
+        format!(
+            "Cancel the recovery of zkSync account {}.\n\
+            Timestamp: {}",
+            self.account_id,
+            self.timestamp.timestamp_millis()
+        )
+    }
+}
+
+/// Recovery of the account initiated by the guardian. `ChangePubKey` to the new public key hash
+/// is only accepted if it is not valid before `executable_at`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRecovery {
+    pub id: i64,
+    pub account_id: AccountId,
+    pub new_pub_key_hash: PubKeyHash,
+    pub initiated_by: Address,
+    pub created_at: DateTime<Utc>,
+    pub executable_at: DateTime<Utc>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub query: String,
+}
+
+/// Kind of the entity the search query was resolved to.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchEntityType {
+    Account,
+    Transaction,
+    /// The query is the Ethereum hash of the priority operation,
+    /// the link points to the corresponding zkSync transaction.
+    PriorityOperation,
+    Block,
+    Token,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub entity_type: SearchEntityType,
+    /// Path of the API resource of the entity, e.g. `/api/v0.2/blocks/1`.
+    pub link: String,
+}
@@ -1,4 +1,5 @@
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use zksync_types::{AccountId, Address, Token, TokenId, H256};
 
@@ -22,6 +23,43 @@ pub struct TokenPrice {
     pub price: BigDecimal,
 }
 
+/// Maximum number of the intervals in the requested token price history.
+pub const MAX_PRICE_HISTORY_POINTS: u64 = 1000;
+/// Resolution of the token price history if it's not provided, one hour.
+pub const DEFAULT_PRICE_HISTORY_RESOLUTION_SECS: u64 = 3600;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingTokenPriceHistoryQuery {
+    /// Start of the time range, one day before its end by default.
+    pub from: Option<DateTime<Utc>>,
+    /// End of the time range, the current time by default.
+    pub to: Option<DateTime<Utc>>,
+    /// Length of the interval in seconds, every interval is represented by its last price.
+    pub resolution: Option<u64>,
+}
+
+/// Price of the token used by the server for the fee calculation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPricePoint {
+    /// Start of the interval.
+    pub timestamp: DateTime<Utc>,
+    pub usd_price: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPriceHistory {
+    pub token_id: TokenId,
+    pub token_symbol: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub resolution: u64,
+    /// Prices in the intervals with at least one price fetched, in the chronological order.
+    pub prices: Vec<TokenPricePoint>,
+}
+
 impl ApiToken {
     pub fn from_token_and_eligibility(token: Token, eligibility: bool) -> Self {
         ApiToken {
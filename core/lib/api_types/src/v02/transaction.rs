@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use zksync_types::{
     tx::{
         ChangePubKey, Close, EthBatchSignatures, ForcedExit, MintNFT, Swap, Transfer,
-        TxEthSignature, TxHash, Withdraw, WithdrawNFT,
+        TxEthSignature, TxFailureCode, TxHash, Withdraw, WithdrawNFT,
     },
     AccountId, Address, BlockNumber, EthBlockId, PubKeyHash, SerialId, TokenId, ZkSyncOp,
     ZkSyncPriorityOp, H256,
@@ -20,10 +20,95 @@ pub struct IncomingTxBatch {
     pub signature: Option<EthBatchSignatures>,
 }
 
+/// Mode of the transaction submission.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum SubmissionMode {
+    /// The response is sent once the transaction is added to the mempool.
+    Sync,
+    /// The hash is returned right away, the transaction is verified in the background
+    /// and the rejection is reported by the receipt.
+    Async,
+}
+
+impl Default for SubmissionMode {
+    fn default() -> Self {
+        Self::Sync
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTxQuery {
+    #[serde(default)]
+    pub mode: SubmissionMode,
+    /// The transaction isn't executed until the block with this number is committed.
+    /// The scheduled transaction is replaced by any transaction with the same nonce.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before_block: Option<u32>,
+}
+
+/// Stage of the transaction submission that is in progress.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum SubmissionStage {
+    /// The signatures and the fee of the transaction are being checked.
+    Verifying,
+    /// The transaction is sent to the mempool, which hasn't acknowledged it yet.
+    SentToMempool,
+    /// The transaction is stored by the mempool, the response isn't sent yet.
+    Accepted,
+}
+
+impl SubmissionStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Verifying => "verifying",
+            Self::SentToMempool => "sentToMempool",
+            Self::Accepted => "accepted",
+        }
+    }
+
+    pub fn parse(stage: &str) -> Option<Self> {
+        match stage {
+            "verifying" => Some(Self::Verifying),
+            "sentToMempool" => Some(Self::SentToMempool),
+            "accepted" => Some(Self::Accepted),
+            _ => None,
+        }
+    }
+}
+
+/// Submission that was in progress when the API server was shut down.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedSubmission {
+    /// The last known stage of the submission.
+    pub stage: SubmissionStage,
+    pub interrupted_at: DateTime<Utc>,
+}
+
+/// Response of the `/transactions/{txHash}/submission` endpoint.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionStatus {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    /// Set if the submission was interrupted by the restart of the API server.
+    pub interrupted: Option<InterruptedSubmission>,
+    /// Receipt of the transaction, `None` if the transaction is unknown to the server.
+    pub receipt: Option<Receipt>,
+    /// Whether the transaction has to be submitted again,
+    /// i.e. it's neither stored in the mempool nor executed.
+    pub should_resubmit: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
 pub enum TxInBlockStatus {
     Queued,
+    /// The transaction is executed in the pending block, which isn't sealed yet.
+    Pending,
     Committed,
     Finalized,
     Rejected,
@@ -45,6 +130,63 @@ pub struct TxData {
     pub eth_signature: Option<String>,
 }
 
+/// Transaction pruned from the database, stored in the cold storage as `SignedArchivedTx`.
+/// Contains both the receipt and the data, so a single request serves both of the endpoints.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedTx {
+    pub receipt: Receipt,
+    pub data: TxData,
+}
+
+impl ArchivedTx {
+    /// Key of the blob in the bucket relative to its base URL.
+    pub fn key(tx_hash: TxHash) -> String {
+        format!("txs/{}.json", hex::encode(tx_hash.as_ref()))
+    }
+
+    /// Serializes the transaction and authenticates it with the key shared by the core
+    /// and the API, so the API only serves the blobs exported by the core.
+    pub fn sign(&self, tx_hash: TxHash, key: &H256) -> serde_json::Result<SignedArchivedTx> {
+        let payload = serde_json::to_string(self)?;
+        let mac = SignedArchivedTx::mac(tx_hash, &payload, key);
+        Ok(SignedArchivedTx { payload, mac })
+    }
+}
+
+/// Blob in the cold storage: the serialized `ArchivedTx` and its authentication code.
+/// The code is bound to the transaction hash, so the blob can't be served for another key.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedArchivedTx {
+    pub payload: String,
+    pub mac: H256,
+}
+
+impl SignedArchivedTx {
+    fn mac(tx_hash: TxHash, payload: &str, key: &H256) -> H256 {
+        // Keccak is not prone to the length extension, so the keyed hash is a valid MAC.
+        let mut bytes = Vec::with_capacity(64 + payload.len());
+        bytes.extend_from_slice(key.as_bytes());
+        bytes.extend_from_slice(tx_hash.as_ref());
+        bytes.extend_from_slice(payload.as_bytes());
+        H256(tiny_keccak::keccak256(&bytes))
+    }
+
+    /// Returns the archived transaction if the blob was signed with the key for the given hash.
+    pub fn verify(self, tx_hash: TxHash, key: &H256) -> Option<ArchivedTx> {
+        let expected = Self::mac(tx_hash, &self.payload, key);
+        // Constant-time comparison, the codes are compared for the requests of the users.
+        let diff = expected
+            .as_bytes()
+            .iter()
+            .zip(self.mac.as_bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b));
+        if diff != 0 {
+            return None;
+        }
+        serde_json::from_str(&self.payload).ok()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct L1Receipt {
@@ -52,6 +194,20 @@ pub struct L1Receipt {
     pub eth_block: EthBlockId,
     pub rollup_block: Option<BlockNumber>,
     pub id: SerialId,
+    /// Position of the operation in the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_index: Option<u32>,
+}
+
+/// Priority operation created by the L1 transaction.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct L1TxPriorityOp {
+    /// Hash of the priority operation in zkSync.
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    #[serde(flatten)]
+    pub receipt: L1Receipt,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -62,6 +218,12 @@ pub struct L2Receipt {
     pub rollup_block: Option<BlockNumber>,
     pub status: TxInBlockStatus,
     pub fail_reason: Option<String>,
+    /// Machine-readable reason of the failure, set for the transactions rejected in the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_code: Option<TxFailureCode>,
+    /// Position of the transaction in the block, not set for the rejected transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_index: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -243,6 +405,16 @@ pub struct ApiTxBatch {
     pub batch_status: BatchStatus,
 }
 
+/// Batch of transactions together with the receipts of its transactions.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchInfo {
+    #[serde(flatten)]
+    pub batch: ApiTxBatch,
+    /// Receipts in the order of `transaction_hashes`.
+    pub receipts: Vec<L2Receipt>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchStatus {
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use zksync_types::{tx::TxHash, BlockNumber, H256};
+use zksync_utils::ZeroPrefixHexSerde;
+
+/// Stage of the withdrawal lifecycle, from the mempool to the funds on L1.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WithdrawalStage {
+    /// Withdrawal is waiting in the mempool or in the priority queue.
+    Queued,
+    /// Withdrawal is included in the committed block.
+    Included,
+    /// Block with the withdrawal is verified, but the funds are not released yet.
+    Verified,
+    /// `executeBlocks` transaction releasing the funds is confirmed on L1.
+    Released,
+    /// Withdrawal failed and will never be released.
+    Rejected,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalInfo {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    pub stage: WithdrawalStage,
+    pub block_number: Option<BlockNumber>,
+    /// Whether the funds are left on the contract as a pending balance,
+    /// which has to be withdrawn by a separate `withdrawPendingBalance` call.
+    pub deferred: bool,
+    /// Hash of the L1 transaction that executed the block with the withdrawal.
+    pub l1_tx_hash: Option<H256>,
+    pub fail_reason: Option<String>,
+}
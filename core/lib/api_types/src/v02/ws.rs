@@ -0,0 +1,97 @@
+//! Messages of the `/ws/v02` WebSocket endpoint.
+//!
+//! Client sends JSON-serialized [`WsRequest`] messages in order to manage its subscriptions,
+//! and the server responds with [`WsMessage`] ones. Once subscribed, the client receives an
+//! event every time the status of a matching transaction changes
+//! (`queued` -> `committed` -> `finalized`, or `rejected`).
+//!
+//! If the public mempool feed is enabled on the server, the client may also subscribe
+//! for the pending transactions received by the server.
+//!
+//! The server may require the client to prove the control of the address before subscribing
+//! to the private topics of the account (see [`Subscription::private_address`]). To do so,
+//! the client requests the challenge and sends it back signed by the account's Ethereum key.
+//! The authenticated addresses are remembered until the connection is closed.
+
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    event::transaction::TransactionEvent,
+    tx::{PackedEthSignature, TxHash},
+    Address,
+};
+
+use super::{mempool::PendingTx, transaction::Receipt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Subscription {
+    /// Status updates of the single transaction (either L2 or L1 one).
+    #[serde(rename_all = "camelCase")]
+    TxStatus { tx_hash: TxHash },
+    /// Status updates of all the transactions initiated by the account.
+    AccountTxs { address: Address },
+    /// Transactions from the public mempool feed, sent once they are old enough to be exposed.
+    PendingTxs,
+}
+
+impl Subscription {
+    /// Address of the account whose activity is exposed by the topic,
+    /// `None` if the topic is public.
+    pub fn private_address(&self) -> Option<Address> {
+        match self {
+            Self::AccountTxs { address } => Some(*address),
+            Self::TxStatus { .. } | Self::PendingTxs => None,
+        }
+    }
+}
+
+/// Proof of the control of the address: the challenge of the session signed
+/// by the Ethereum key of the account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsAuthentication {
+    pub address: Address,
+    pub signature: PackedEthSignature,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "camelCase")]
+pub enum WsRequest {
+    Subscribe(Subscription),
+    Unsubscribe(Subscription),
+    /// Requests the challenge to be signed for the authentication.
+    Challenge,
+    Authenticate(WsAuthentication),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WsMessage {
+    /// Confirms the subscription. For the `txStatus` topic also contains the current receipt
+    /// of the transaction, so the client doesn't have to request it separately.
+    Subscribed {
+        subscription: Subscription,
+        receipt: Option<Receipt>,
+    },
+    Unsubscribed {
+        subscription: Subscription,
+    },
+    Event {
+        subscription: Subscription,
+        event: TransactionEvent,
+    },
+    PendingTx {
+        tx: PendingTx,
+    },
+    /// Message to be signed by the client in order to authenticate.
+    Challenge {
+        message: String,
+    },
+    /// Private topics of the address are available for the connection.
+    Authenticated {
+        address: Address,
+    },
+    Error {
+        message: String,
+    },
+}
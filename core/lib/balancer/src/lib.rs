@@ -1,9 +1,14 @@
-use std::time::Instant;
+use std::{
+    future::Future,
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
 
 use futures::{
     channel::mpsc::{self, Receiver, Sender},
-    SinkExt, StreamExt,
+    future, SinkExt, StreamExt,
 };
+use tokio::{task::JoinHandle, time};
 
 pub struct Balancer<R> {
     channels: Vec<Sender<R>>,
@@ -54,12 +59,254 @@ impl<R> Balancer<R> {
     }
 }
 
+/// Requests routed by the adaptive balancer. Requests with the same key are processed
+/// by the same item in the order of their arrival.
+pub trait ShardedRequest {
+    fn shard_key(&self) -> u64;
+}
+
+/// Bounds and thresholds of the adaptive scaling of the balanced items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalingConfig {
+    /// Number of items that are always running.
+    pub min_items: usize,
+    /// Maximum number of the concurrently running items.
+    pub max_items: usize,
+    /// Capacity of the request channel of each item.
+    pub channel_capacity: usize,
+    /// A new item is spawned once the saturation exceeds this value.
+    pub scale_up_saturation: f64,
+    /// An item is retired once the saturation stays below this value
+    /// for `scale_down_intervals` intervals in a row.
+    pub scale_down_saturation: f64,
+    /// Number of the consecutive intervals of the low saturation before an item is retired.
+    pub scale_down_intervals: u32,
+    /// Time between two scaling decisions.
+    pub interval: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scaling {
+    Up,
+    Down,
+    Keep,
+}
+
+impl ScalingConfig {
+    /// `low_intervals` is the number of the preceding intervals of the low saturation,
+    /// the current one included.
+    fn decision(&self, items: usize, saturation: f64, low_intervals: u32) -> Scaling {
+        if saturation > self.scale_up_saturation && items < self.max_items {
+            Scaling::Up
+        } else if saturation < self.scale_down_saturation
+            && low_intervals >= self.scale_down_intervals
+            && items > self.min_items
+        {
+            Scaling::Down
+        } else {
+            Scaling::Keep
+        }
+    }
+}
+
+/// Saturation of the item channels observed during the current scaling interval.
+#[derive(Debug)]
+struct SaturationWindow {
+    started_at: Instant,
+    /// Time the balancer spent waiting for the room in the full channels.
+    blocked: Duration,
+}
+
+impl Default for SaturationWindow {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            blocked: Duration::default(),
+        }
+    }
+}
+
+impl SaturationWindow {
+    fn record(&mut self, blocked: Duration) {
+        self.blocked += blocked;
+    }
+
+    /// Share of the interval the balancer was blocked by the full channels, from 0 to 1.
+    fn saturation(&self) -> f64 {
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+        if elapsed == 0.0 {
+            0.0
+        } else {
+            (self.blocked.as_secs_f64() / elapsed).min(1.0)
+        }
+    }
+}
+
+/// Running balanced item.
+struct Item<R> {
+    sender: Sender<R>,
+    handle: JoinHandle<()>,
+}
+
+/// Balancer that adjusts the number of the running items to the load.
+///
+/// Sending a request blocks only when the channel of the item is full, so the share of the time
+/// the balancer is blocked measures the saturation of the items. Items are spawned and retired
+/// within the configured bounds. Requests are routed by their shard key, so the requests with
+/// the same key are never processed concurrently. Once the number of items changes, the keys are
+/// routed to the new items only after the previous ones processed all the requests sent to them.
+pub struct AdaptiveBalancer<R, T, S> {
+    builder: T,
+    config: ScalingConfig,
+    items: Vec<Item<R>>,
+    /// Items of the previous generation that are still processing their requests.
+    draining: Vec<JoinHandle<()>>,
+    requests: Receiver<R>,
+    window: SaturationWindow,
+    /// Number of the consecutive intervals of the low saturation.
+    low_intervals: u32,
+    _item: PhantomData<fn() -> S>,
+}
+
+impl<R, T, S> AdaptiveBalancer<R, T, S>
+where
+    R: ShardedRequest,
+    T: BuildBalancedItem<R, S>,
+    S: Future<Output = ()> + Send + 'static,
+{
+    /// Creates the balancer and spawns the minimal number of items.
+    /// Must be called within the Tokio runtime.
+    pub fn new(builder: T, requests: Receiver<R>, config: ScalingConfig) -> Self {
+        assert!(
+            config.min_items > 0 && config.min_items <= config.max_items,
+            "Incorrect bounds of the balanced items: [{}, {}]",
+            config.min_items,
+            config.max_items
+        );
+
+        let mut balancer = Self {
+            builder,
+            config,
+            items: Vec::new(),
+            draining: Vec::new(),
+            requests,
+            window: SaturationWindow::default(),
+            low_intervals: 0,
+            _item: PhantomData,
+        };
+        balancer.spawn_items(balancer.config.min_items);
+        balancer
+    }
+
+    fn spawn_item(&self) -> Item<R> {
+        let (sender, receiver) = mpsc::channel(self.config.channel_capacity);
+        let handle = tokio::spawn(self.builder.build_with_receiver(receiver));
+        Item { sender, handle }
+    }
+
+    /// Replaces the running items with the new generation of `count` items.
+    fn spawn_items(&mut self, count: usize) {
+        // Dropping the sender closes the channel, the item finishes once the channel is drained.
+        let previous = std::mem::take(&mut self.items);
+        self.draining
+            .extend(previous.into_iter().map(|item| item.handle));
+        self.items = (0..count).map(|_| self.spawn_item()).collect();
+    }
+
+    fn rescale(&mut self) {
+        let saturation = self.window.saturation();
+        self.window = SaturationWindow::default();
+        if saturation < self.config.scale_down_saturation {
+            self.low_intervals += 1;
+        } else {
+            self.low_intervals = 0;
+        }
+
+        let items = self.items.len();
+        match self.config.decision(items, saturation, self.low_intervals) {
+            Scaling::Up => self.spawn_items(items + 1),
+            Scaling::Down => {
+                self.low_intervals = 0;
+                self.spawn_items(items - 1);
+            }
+            Scaling::Keep => {}
+        }
+        metrics::gauge!("balancer.saturation", saturation);
+        metrics::gauge!("balancer.items", self.items.len() as f64);
+    }
+
+    /// Waits until the items of the previous generation process all their requests, so the
+    /// requests routed to the other item after the rescaling don't overtake them.
+    async fn wait_for_draining(&mut self) {
+        for handle in self.draining.drain(..) {
+            if let Err(err) = handle.await {
+                vlog::error!("Balanced item terminated abnormally: {}", err);
+            }
+        }
+    }
+
+    async fn dispatch(&mut self, mut request: R) {
+        self.wait_for_draining().await;
+
+        let index = (request.shard_key() % self.items.len() as u64) as usize;
+        let start = Instant::now();
+        loop {
+            let sender = &mut self.items[index].sender;
+            if future::poll_fn(|cx| sender.poll_ready(cx)).await.is_ok() {
+                match sender.try_send(request) {
+                    Ok(()) => break,
+                    Err(err) if err.is_full() => {
+                        request = err.into_inner();
+                        continue;
+                    }
+                    Err(err) => request = err.into_inner(),
+                }
+            }
+            // The receiver is dropped only if the item has terminated abnormally, e.g. panicked.
+            // The item is replaced, so the request is not lost.
+            vlog::error!("Balanced item #{} has terminated, respawning it", index);
+            metrics::increment_counter!("balancer.respawned_items");
+            self.items[index] = self.spawn_item();
+        }
+        let blocked = start.elapsed();
+        self.window.record(blocked);
+        metrics::histogram!("balancer.dispatch", blocked);
+    }
+
+    pub async fn run(mut self) {
+        let mut timer = time::interval(self.config.interval);
+        // The first tick completes immediately.
+        timer.tick().await;
+        loop {
+            // Requests are dispatched outside of `select!`, so that the dispatching
+            // that waits for the room in the channel is never cancelled.
+            let request = tokio::select! {
+                request = self.requests.next() => request,
+                _ = timer.tick() => {
+                    self.rescale();
+                    continue;
+                }
+            };
+            match request {
+                Some(request) => self.dispatch(request).await,
+                None => break,
+            }
+        }
+        // Let the items process the remaining requests.
+        self.spawn_items(0);
+        self.wait_for_draining().await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Balancer, BuildBalancedItem};
+    use crate::{
+        AdaptiveBalancer, Balancer, BuildBalancedItem, Scaling, ScalingConfig, ShardedRequest,
+    };
     use futures::channel::mpsc;
-    use futures::channel::mpsc::Receiver;
-    use futures::{SinkExt, StreamExt};
+    use futures::channel::mpsc::{Receiver, UnboundedSender};
+    use futures::{future::BoxFuture, FutureExt, SinkExt, StreamExt};
+    use std::time::Duration;
 
     struct SomeBalancedItemBuilder;
     struct SomeBalancedItem {
@@ -88,4 +335,109 @@ mod tests {
             }
         }
     }
+
+    impl ShardedRequest for i32 {
+        fn shard_key(&self) -> u64 {
+            (self % 3) as u64
+        }
+    }
+
+    struct ForwardingItemBuilder {
+        output: UnboundedSender<i32>,
+    }
+
+    impl BuildBalancedItem<i32, BoxFuture<'static, ()>> for ForwardingItemBuilder {
+        fn build_with_receiver(&self, mut receiver: Receiver<i32>) -> BoxFuture<'static, ()> {
+            let output = self.output.clone();
+            async move {
+                while let Some(request) = receiver.next().await {
+                    // Emulates an item that crashes on a malformed request.
+                    assert!(request >= 0, "Malformed request");
+                    output.unbounded_send(request).unwrap();
+                }
+            }
+            .boxed()
+        }
+    }
+
+    fn scaling_config() -> ScalingConfig {
+        ScalingConfig {
+            min_items: 1,
+            max_items: 4,
+            channel_capacity: 2,
+            scale_up_saturation: 0.2,
+            scale_down_saturation: 0.01,
+            scale_down_intervals: 2,
+            interval: Duration::from_millis(10),
+        }
+    }
+
+    #[test]
+    fn scaling_decision() {
+        let config = scaling_config();
+
+        assert_eq!(config.decision(1, 0.5, 0), Scaling::Up);
+        assert_eq!(config.decision(4, 0.5, 0), Scaling::Keep);
+        assert_eq!(config.decision(2, 0.1, 0), Scaling::Keep);
+        // Items are retired only after several intervals of the low saturation.
+        assert_eq!(config.decision(2, 0.0, 1), Scaling::Keep);
+        assert_eq!(config.decision(2, 0.0, 2), Scaling::Down);
+        assert_eq!(config.decision(1, 0.0, 2), Scaling::Keep);
+    }
+
+    #[tokio::test]
+    async fn adaptive_balancer_delivers_all_requests() {
+        let (mut request_sender, request_receiver) = mpsc::channel(2);
+        let (output, mut results) = mpsc::unbounded();
+
+        let balancer = AdaptiveBalancer::new(
+            ForwardingItemBuilder { output },
+            request_receiver,
+            scaling_config(),
+        );
+        tokio::spawn(balancer.run());
+
+        for i in 0..50 {
+            request_sender.send(i).await.unwrap();
+            if i % 10 == 0 {
+                // Let the balancer rescale in between.
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        }
+        drop(request_sender);
+
+        let received: Vec<i32> = results.take(50).collect().await;
+        // Requests with the same key are processed in the order of their arrival,
+        // even though the number of items changes in between.
+        for key in 0..3 {
+            let expected: Vec<_> = (0..50).filter(|i| i % 3 == key).collect();
+            let actual: Vec<_> = received.iter().copied().filter(|i| i % 3 == key).collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn adaptive_balancer_respawns_terminated_items() {
+        let (mut request_sender, request_receiver) = mpsc::channel(2);
+        let (output, mut results) = mpsc::unbounded();
+
+        let config = ScalingConfig {
+            interval: Duration::from_secs(60),
+            ..scaling_config()
+        };
+        let balancer =
+            AdaptiveBalancer::new(ForwardingItemBuilder { output }, request_receiver, config);
+        tokio::spawn(balancer.run());
+
+        request_sender.send(-3).await.unwrap();
+        // Let the item crash.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        for i in 0..10 {
+            request_sender.send(i).await.unwrap();
+        }
+        drop(request_sender);
+
+        let received: Vec<i32> = results.take(10).collect().await;
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+    }
 }
@@ -2,11 +2,11 @@ use num::{rational::Ratio, BigUint};
 /// External uses
 use serde::Deserialize;
 /// Built-in uses
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
 use zksync_utils::scaled_u64_to_ratio;
 // Workspace uses
-use zksync_types::AccountId;
+use zksync_types::{AccountId, H256};
 // Local uses
 use crate::envy_load;
 
@@ -30,6 +30,26 @@ pub struct ApiConfig {
     /// Configuration options for the Prometheus exporter.
     pub prometheus: PrometheusConfig,
     pub token_config: TokenConfig,
+    /// Configuration options for the public mempool feed.
+    pub mempool_feed: MempoolFeedConfig,
+    /// Configuration options for the cold storage of the pruned data.
+    pub archive: ArchiveConfig,
+    /// Configuration options for the API keys and their request quotas.
+    pub keys: ApiKeysConfig,
+    /// Configuration options for the guardian-based account recovery.
+    pub recovery: RecoveryConfig,
+    /// Configuration options for the per-IP rate limiting of the REST API.
+    pub rate_limit: RateLimitConfig,
+    /// Configuration options for the encrypted transactions submitted with the visibility delay.
+    pub encrypted_txs: EncryptedTxsConfig,
+    /// Configuration options for the sandbox API server serving the synthetic data.
+    pub sandbox: SandboxApiConfig,
+    /// Configuration options for the GraphQL endpoint of the REST API server.
+    pub graphql: GraphQlConfig,
+    /// Configuration options for the timeouts of the REST API requests.
+    pub request_timeout: RequestTimeoutConfig,
+    /// Configuration options for the circuit breakers of the storage and the mempool.
+    pub circuit_breaker: CircuitBreakerConfig,
 }
 
 impl ApiConfig {
@@ -44,6 +64,16 @@ impl ApiConfig {
             prover: envy_load!("prover", "API_PROVER_"),
             prometheus: envy_load!("prometheus", "API_PROMETHEUS_"),
             token_config: envy_load!("token", "API_TOKEN_"),
+            mempool_feed: envy_load!("mempool_feed", "API_MEMPOOL_FEED_"),
+            archive: envy_load!("archive", "API_ARCHIVE_"),
+            keys: envy_load!("keys", "API_KEYS_"),
+            recovery: envy_load!("recovery", "API_RECOVERY_"),
+            rate_limit: RateLimitConfig::from_env(),
+            encrypted_txs: envy_load!("encrypted_txs", "API_ENCRYPTED_TXS_"),
+            sandbox: envy_load!("sandbox", "API_SANDBOX_"),
+            graphql: envy_load!("graphql", "API_GRAPHQL_"),
+            request_timeout: envy_load!("request_timeout", "API_REQUEST_TIMEOUT_"),
+            circuit_breaker: envy_load!("circuit_breaker", "API_CIRCUIT_BREAKER_"),
         }
     }
 }
@@ -56,6 +86,11 @@ impl CommonApiConfig {
     pub fn from_env() -> Self {
         envy_load!("common", "API_COMMON_")
     }
+
+    /// Converts `self.internal_request_timeout_ms` into `Duration`.
+    pub fn internal_request_timeout(&self) -> Duration {
+        Duration::from_millis(self.internal_request_timeout_ms)
+    }
 }
 
 impl AdminApiConfig {
@@ -113,6 +148,8 @@ pub struct CommonApiConfig {
 
     pub max_number_of_transactions_per_batch: u64,
     pub max_number_of_authors_per_batch: u64,
+    /// Maximum number of addresses in the single bulk account info request.
+    pub max_accounts_per_batch_request: u64,
 
     /// The IPs which have their CPK (CREATE2) subsidized
     pub subsidized_ips: Vec<String>,
@@ -122,12 +159,28 @@ pub struct CommonApiConfig {
 
     /// The name of current subsidy. It is needed to conveniently fetch historical data regarding subsidies for different partners
     pub subsidy_name: String,
+
+    /// Deadline of the requests sent by the API to the signature checker and the mempool,
+    /// in milliseconds. Requests are dropped by them once nobody waits for the response.
+    pub internal_request_timeout_ms: u64,
+    /// Maximum number of the transactions submitted in the async mode that are processed
+    /// concurrently. Once the limit is reached, the submissions are processed synchronously.
+    pub max_async_submissions: usize,
+    /// Whether the fee quotes served by the API are stored to the audit log.
+    /// The quotes are written in the background, and dropped if the storage falls behind.
+    pub store_fee_quotes: bool,
+    /// Part of the required fee the provided fee may fall short of, in percent.
+    /// Covers the fee changes between the quote and the submission of the transaction.
+    pub fee_tolerance_percent: u32,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct TokenConfig {
     /// The interval of updating tokens from database
     pub invalidate_token_cache_period_sec: u64,
+    /// The period the unknown tokens are remembered for, so the repeated requests
+    /// don't reach the database. `0` disables the caching of the unknown tokens.
+    pub negative_cache_period_sec: u64,
 }
 
 impl TokenConfig {
@@ -138,6 +191,360 @@ impl TokenConfig {
     pub fn invalidate_token_cache_period(&self) -> Duration {
         Duration::from_secs(self.invalidate_token_cache_period_sec)
     }
+
+    pub fn negative_cache_period(&self) -> Duration {
+        Duration::from_secs(self.negative_cache_period_sec)
+    }
+}
+
+/// Public feed of the pending transactions. Since the mempool content may be sensitive,
+/// transactions are exposed only after some delay and can be partially redacted.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct MempoolFeedConfig {
+    /// Whether the feed is available via REST and WebSocket API.
+    pub enabled: bool,
+    /// Minimum age of the transaction before it's exposed by the feed, in seconds.
+    pub min_age_secs: u64,
+    /// The maximum amount of transactions returned by a single request,
+    /// also the size of the pages broadcasted via WebSocket.
+    pub max_txs: u32,
+    /// Hide the amounts of the transactions.
+    pub redact_amounts: bool,
+    /// Hide the fees of the transactions.
+    pub redact_fees: bool,
+    /// Hide the sender and recipient addresses of the transactions.
+    pub redact_addresses: bool,
+}
+
+impl MempoolFeedConfig {
+    pub fn from_env() -> Self {
+        envy_load!("mempool_feed", "API_MEMPOOL_FEED_")
+    }
+
+    /// Converts `self.min_age_secs` into `Duration`.
+    pub fn min_age(&self) -> Duration {
+        Duration::from_secs(self.min_age_secs)
+    }
+}
+
+/// Cold storage (S3-compatible bucket) with the records pruned from the database.
+/// Records are stored as JSON blobs indexed by the transaction hash, and the API falls back
+/// to the archive when the record is not found in the database.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ArchiveConfig {
+    /// Whether the API should query the archive.
+    pub enabled: bool,
+    /// Whether the rejected transactions cleaner should upload the transactions to the archive
+    /// before removing them. The bucket must accept `PUT` requests from the core.
+    pub export_enabled: bool,
+    /// Base URL of the bucket, e.g. `https://<bucket>.s3.<region>.amazonaws.com/<prefix>`.
+    pub url: String,
+    /// Timeout of the single request to the archive, in milliseconds.
+    pub request_timeout_ms: u64,
+    /// For how long the transactions missing in the archive are not requested again, in seconds.
+    pub negative_cache_period_sec: u64,
+    /// Key authenticating the exported blobs, shared by the core and the API.
+    /// The blobs not signed with it are ignored by the API.
+    pub signing_key: H256,
+}
+
+impl ArchiveConfig {
+    pub fn from_env() -> Self {
+        envy_load!("archive", "API_ARCHIVE_")
+    }
+
+    /// Converts `self.request_timeout_ms` into `Duration`.
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+
+    /// Converts `self.negative_cache_period_sec` into `Duration`.
+    pub fn negative_cache_period(&self) -> Duration {
+        Duration::from_secs(self.negative_cache_period_sec)
+    }
+}
+
+/// API keys issued to the partners. Requests with the `X-API-Key` header are checked against
+/// the keys stored in the database and are subject to the per-key quotas.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ApiKeysConfig {
+    /// Whether the REST API checks the API keys.
+    pub enabled: bool,
+    /// Reject the requests without the API key.
+    pub require_key: bool,
+    /// How long the key loaded from the database is cached, in seconds.
+    /// Revoked keys remain valid until the cached entry expires.
+    pub cache_ttl_secs: u64,
+    /// How long the unknown key is cached, in seconds.
+    /// The key created by another API server is rejected until the cached entry expires.
+    pub negative_cache_ttl_secs: u64,
+}
+
+impl ApiKeysConfig {
+    pub fn from_env() -> Self {
+        envy_load!("keys", "API_KEYS_")
+    }
+
+    /// Converts `self.cache_ttl_secs` into `Duration`.
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.cache_ttl_secs)
+    }
+
+    /// Converts `self.negative_cache_ttl_secs` into `Duration`.
+    pub fn negative_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.negative_cache_ttl_secs)
+    }
+}
+
+/// Guardian-based account recovery. `ChangePubKey` to the key proposed by the guardian
+/// is only accepted once the timelock has passed, unless the recovery is cancelled by the owner.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RecoveryConfig {
+    /// Time the owner has to cancel the recovery, in seconds.
+    pub timelock_secs: u64,
+    /// Maximum number of the guardians per account.
+    pub max_guardians: usize,
+}
+
+impl RecoveryConfig {
+    pub fn from_env() -> Self {
+        envy_load!("recovery", "API_RECOVERY_")
+    }
+
+    /// Converts `self.timelock_secs` into `Duration`.
+    pub fn timelock(&self) -> Duration {
+        Duration::from_secs(self.timelock_secs)
+    }
+}
+
+/// Storage of the rate limiter buckets.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitBackend {
+    /// Buckets are kept in memory, so the limits are enforced per API server instance.
+    Memory,
+    /// Buckets are kept in Redis and shared between all the API server replicas.
+    Redis,
+}
+
+/// Per-IP rate limiting of the REST and JSON-RPC APIs. Every client IP has a token bucket per
+/// route group, requests submitting the transactions are limited separately from the read ones.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    /// Whether the requests are rate limited.
+    pub enabled: bool,
+    /// Storage of the buckets.
+    pub backend: RateLimitBackend,
+    /// URL of the Redis server, only used by the `redis` backend.
+    pub redis_url: String,
+    /// Sustained rate of the read requests per IP.
+    pub read_requests_per_second: u32,
+    /// Maximum number of the read requests per IP made at once.
+    pub read_burst: u32,
+    /// Sustained rate of the requests submitting transactions per IP.
+    pub submit_requests_per_second: u32,
+    /// Maximum number of the requests submitting transactions per IP made at once.
+    pub submit_burst: u32,
+    /// Addresses of the proxies allowed to pass the client IP in the `CF-Connecting-IP` header,
+    /// the header is ignored for the other peers. The JSON-RPC servers do not know the address
+    /// of the peer, so they only use the header if the proxies are set.
+    pub trusted_proxies: Vec<String>,
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> Self {
+        let config: Self = envy_load!("rate_limit", "API_RATE_LIMIT_");
+        config.validate();
+        config
+    }
+
+    /// Panics if the limits never let any request through.
+    fn validate(&self) {
+        assert!(
+            self.read_requests_per_second > 0
+                && self.read_burst > 0
+                && self.submit_requests_per_second > 0
+                && self.submit_burst > 0,
+            "Rate limits and bursts must be positive, disable the rate limiting instead"
+        );
+    }
+
+    /// Parses `self.trusted_proxies` into the IP addresses.
+    pub fn trusted_proxies(&self) -> Vec<IpAddr> {
+        self.trusted_proxies
+            .iter()
+            .filter(|proxy| !proxy.is_empty())
+            .map(|proxy| {
+                proxy
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Invalid trusted proxy address: {}", proxy))
+            })
+            .collect()
+    }
+}
+
+/// Commit-reveal submission of the transactions. The client encrypts the transaction to the operator
+/// key, the envelope is only decrypted and submitted to the mempool after the visibility delay,
+/// so the pending transaction can't be front-run by the observers of the mempool.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EncryptedTxsConfig {
+    /// Whether the API accepts the encrypted transactions.
+    pub enabled: bool,
+    /// Private secp256k1 key used to decrypt the envelopes.
+    pub secret_key: H256,
+    /// Delay between the submission of the envelope and its reveal, in milliseconds.
+    pub reveal_delay_ms: u64,
+    /// How often the API server checks for the envelopes to reveal, in milliseconds.
+    pub reveal_poll_interval_ms: u64,
+    /// How long the revealed envelopes are kept to serve their status, in hours.
+    pub retention_hours: u64,
+    /// Maximum number of the envelopes submitted from one IP which are not revealed yet.
+    pub max_pending_per_client: u64,
+}
+
+impl EncryptedTxsConfig {
+    pub fn from_env() -> Self {
+        envy_load!("encrypted_txs", "API_ENCRYPTED_TXS_")
+    }
+
+    /// Converts `self.reveal_delay_ms` into `Duration`.
+    pub fn reveal_delay(&self) -> Duration {
+        Duration::from_millis(self.reveal_delay_ms)
+    }
+
+    /// Converts `self.reveal_poll_interval_ms` into `Duration`.
+    pub fn reveal_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.reveal_poll_interval_ms)
+    }
+
+    /// Converts `self.retention_hours` into `Duration`.
+    pub fn retention(&self) -> Duration {
+        Duration::from_secs(self.retention_hours * 60 * 60)
+    }
+}
+
+/// Sandbox mode of the REST API: the server serves the deterministic synthetic accounts and blocks
+/// from memory and simulates the processing of the submitted transactions, so the SDKs can be
+/// tested without the rest of the node.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct SandboxApiConfig {
+    /// Seed of the synthetic data, the same seed always produces the same accounts and blocks.
+    pub seed: u64,
+    /// Number of the synthetic accounts.
+    pub accounts_count: u32,
+    /// Number of the blocks existing at the start.
+    pub blocks_count: u32,
+    /// Delay between the submission of the transaction and its commitment, in milliseconds.
+    pub commit_delay_ms: u64,
+    /// Delay between the commitment of the transaction and its finalization, in milliseconds.
+    pub finalize_delay_ms: u64,
+    /// Maximum number of the sessions kept in memory, the least recently used one is dropped
+    /// to make room for the new one.
+    pub max_sessions: usize,
+    /// Maximum number of the transactions submitted within a single session.
+    pub max_txs_per_session: usize,
+}
+
+impl SandboxApiConfig {
+    pub fn from_env() -> Self {
+        envy_load!("sandbox", "API_SANDBOX_")
+    }
+
+    /// Converts `self.commit_delay_ms` into `Duration`.
+    pub fn commit_delay(&self) -> Duration {
+        Duration::from_millis(self.commit_delay_ms)
+    }
+
+    /// Converts `self.finalize_delay_ms` into `Duration`.
+    pub fn finalize_delay(&self) -> Duration {
+        Duration::from_millis(self.finalize_delay_ms)
+    }
+}
+
+/// GraphQL facade over the storage, so the analytics consumers can fetch the accounts, transactions,
+/// blocks and tokens with their relations in a single query.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct GraphQlConfig {
+    /// Whether the REST API server serves the `/graphql` endpoint.
+    pub enabled: bool,
+    /// Maximum nesting depth of the query.
+    pub max_depth: usize,
+    /// Maximum complexity of the query, i.e. the number of the resolved fields.
+    pub max_complexity: usize,
+    /// Maximum number of the items returned by the list fields.
+    pub max_page_size: u32,
+}
+
+impl GraphQlConfig {
+    pub fn from_env() -> Self {
+        envy_load!("graphql", "API_GRAPHQL_")
+    }
+}
+
+/// Timeouts of the REST API requests, so the slow queries don't hold the connections indefinitely.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RequestTimeoutConfig {
+    /// Timeout of the requests to the routes without the specific timeout.
+    pub default_ms: u64,
+    /// Timeouts of the specific routes in the `<path prefix>=<timeout in ms>` format,
+    /// the timeout of the longest matching prefix is applied.
+    pub route_timeouts: Vec<String>,
+}
+
+impl RequestTimeoutConfig {
+    pub fn from_env() -> Self {
+        envy_load!("request_timeout", "API_REQUEST_TIMEOUT_")
+    }
+
+    /// Converts `self.default_ms` into `Duration`.
+    pub fn default_timeout(&self) -> Duration {
+        Duration::from_millis(self.default_ms)
+    }
+
+    /// Parses `self.route_timeouts` into the pairs of the path prefix and the timeout.
+    pub fn route_timeouts(&self) -> Vec<(String, Duration)> {
+        self.route_timeouts
+            .iter()
+            .map(|entry| {
+                let (prefix, timeout_ms) = entry
+                    .rsplit_once('=')
+                    .and_then(|(prefix, timeout_ms)| Some((prefix, timeout_ms.parse().ok()?)))
+                    .unwrap_or_else(|| panic!("Invalid route timeout: {}", entry));
+                (prefix.to_owned(), Duration::from_millis(timeout_ms))
+            })
+            .collect()
+    }
+}
+
+/// Circuit breaker failing the requests fast while the downstream service (the storage or the mempool)
+/// keeps failing, instead of piling up the requests waiting for it.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct CircuitBreakerConfig {
+    pub enabled: bool,
+    /// Length of the window the error rate is computed over.
+    pub window_secs: u64,
+    /// Minimum number of the requests in the window required to open the breaker.
+    pub min_requests: u32,
+    /// Share of the failed requests (from 0 to 1) at which the breaker opens.
+    pub error_rate_threshold: f64,
+    /// Time the breaker stays open before a trial request is let through.
+    pub open_secs: u64,
+}
+
+impl CircuitBreakerConfig {
+    pub fn from_env() -> Self {
+        envy_load!("circuit_breaker", "API_CIRCUIT_BREAKER_")
+    }
+
+    /// Converts `self.window_secs` into `Duration`.
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+
+    /// Converts `self.open_secs` into `Duration`.
+    pub fn open_duration(&self) -> Duration {
+        Duration::from_secs(self.open_secs)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -164,12 +571,28 @@ pub struct ProverApiConfig {
     pub url: String,
     /// Secret used to generate access token (JWT).
     pub secret_auth: String,
+    /// Port of the read-only server of the block witness exports, it doesn't require the token.
+    pub witness_export_port: u16,
 }
 
 impl ProverApiConfig {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn witness_export_bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.witness_export_port)
+    }
+}
+
+/// Transport of the private API between the API servers and the core.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivateApiTransport {
+    /// JSON over HTTP, always served by the core for compatibility.
+    Http,
+    /// gRPC, served by the core in addition to HTTP.
+    Grpc,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -178,12 +601,50 @@ pub struct PrivateApiConfig {
     pub port: u16,
     /// URL to access API server.
     pub url: String,
+    /// Transport used by the clients of the private API.
+    pub transport: PrivateApiTransport,
+    /// Port to which the gRPC server is listening.
+    pub grpc_port: u16,
+    /// URL to access the gRPC server.
+    pub grpc_url: String,
+    /// Timeout of the single request of the client of the private API, in milliseconds.
+    pub client_request_timeout_ms: u64,
+    /// Deadline of the call of the client including the retries, in milliseconds.
+    pub client_deadline_ms: u64,
+    /// Maximum number of the retries of the failed request.
+    pub client_max_retries: u32,
+    /// Base delay before the retry, doubled after every attempt, in milliseconds.
+    pub client_retry_backoff_ms: u64,
+    /// Maximum number of the idle keep-alive connections to the private API.
+    pub client_pool_max_idle: usize,
+    /// Time after which the idle keep-alive connection is closed, in seconds.
+    pub client_pool_idle_timeout_secs: u64,
 }
 
 impl PrivateApiConfig {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn grpc_bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.grpc_port)
+    }
+
+    pub fn client_request_timeout(&self) -> Duration {
+        Duration::from_millis(self.client_request_timeout_ms)
+    }
+
+    pub fn client_deadline(&self) -> Duration {
+        Duration::from_millis(self.client_deadline_ms)
+    }
+
+    pub fn client_retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.client_retry_backoff_ms)
+    }
+
+    pub fn client_pool_idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.client_pool_idle_timeout_secs)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -192,6 +653,16 @@ pub struct RestApiConfig {
     pub port: u16,
     /// URL to access API server.
     pub url: String,
+    /// Whether the private topics of the WebSocket API (e.g. transactions of the account)
+    /// are available only to the clients that proved the control of the address.
+    pub ws_private_topics_auth: bool,
+    /// Minimum size of the response body in bytes to be compressed.
+    pub compression_min_size: u64,
+    /// Path prefixes of the routes which responses are never compressed.
+    pub compression_excluded_routes: Vec<String>,
+    /// Directory with the files of the `swagger-ui-dist` package, the Swagger UI
+    /// is not served if they are missing.
+    pub swagger_ui_dir: String,
 }
 
 impl RestApiConfig {
@@ -259,10 +730,15 @@ mod tests {
                 enforce_pubkey_change_fee: true,
                 max_number_of_transactions_per_batch: 200,
                 max_number_of_authors_per_batch: 10,
+                max_accounts_per_batch_request: 100,
                 fee_free_accounts: vec![AccountId(4078), AccountId(387)],
                 subsidized_ips: vec!["127.0.0.1".to_owned()],
                 max_subsidy_usd_scaled: 20000,
                 subsidy_name: String::from("PartnerName"),
+                internal_request_timeout_ms: 30000,
+                max_async_submissions: 1000,
+                store_fee_quotes: true,
+                fee_tolerance_percent: 5,
             },
             admin: AdminApiConfig {
                 port: 8080,
@@ -272,6 +748,10 @@ mod tests {
             rest: RestApiConfig {
                 port: 3001,
                 url: "http://127.0.0.1:3001".into(),
+                ws_private_topics_auth: true,
+                compression_min_size: 1024,
+                compression_excluded_routes: vec!["/api/v0.2/networkStatus".to_owned()],
+                swagger_ui_dir: "etc/swagger-ui".into(),
             },
             json_rpc: JsonRpcConfig {
                 http_port: 3030,
@@ -288,15 +768,96 @@ mod tests {
             private: PrivateApiConfig {
                 port: 8090,
                 url: "http://127.0.0.1:8090".into(),
+                transport: PrivateApiTransport::Http,
+                grpc_port: 8091,
+                grpc_url: "http://127.0.0.1:8091".into(),
+                client_request_timeout_ms: 2000,
+                client_deadline_ms: 5000,
+                client_max_retries: 3,
+                client_retry_backoff_ms: 100,
+                client_pool_max_idle: 8,
+                client_pool_idle_timeout_secs: 90,
             },
             prover: ProverApiConfig {
                 port: 8088,
                 url: "http://127.0.0.1:8088".into(),
                 secret_auth: "sample".into(),
+                witness_export_port: 8089,
             },
             prometheus: PrometheusConfig { port: 3312 },
             token_config: TokenConfig {
                 invalidate_token_cache_period_sec: 10,
+                negative_cache_period_sec: 5,
+            },
+            mempool_feed: MempoolFeedConfig {
+                enabled: true,
+                min_age_secs: 30,
+                max_txs: 100,
+                redact_amounts: false,
+                redact_fees: false,
+                redact_addresses: true,
+            },
+            archive: ArchiveConfig {
+                enabled: false,
+                export_enabled: false,
+                url: "http://127.0.0.1:9000/zksync-archive".into(),
+                request_timeout_ms: 5000,
+                negative_cache_period_sec: 60,
+                signing_key: H256::repeat_byte(0x22),
+            },
+            keys: ApiKeysConfig {
+                enabled: true,
+                require_key: false,
+                cache_ttl_secs: 60,
+                negative_cache_ttl_secs: 10,
+            },
+            recovery: RecoveryConfig {
+                timelock_secs: 259200,
+                max_guardians: 5,
+            },
+            rate_limit: RateLimitConfig {
+                enabled: true,
+                backend: RateLimitBackend::Memory,
+                redis_url: "redis://127.0.0.1:6379".into(),
+                read_requests_per_second: 50,
+                read_burst: 100,
+                submit_requests_per_second: 5,
+                submit_burst: 20,
+                trusted_proxies: vec!["10.0.0.1".into(), "10.0.0.2".into()],
+            },
+            encrypted_txs: EncryptedTxsConfig {
+                enabled: true,
+                secret_key: H256::repeat_byte(0x11),
+                reveal_delay_ms: 1000,
+                reveal_poll_interval_ms: 200,
+                retention_hours: 168,
+                max_pending_per_client: 10,
+            },
+            sandbox: SandboxApiConfig {
+                seed: 42,
+                accounts_count: 10,
+                blocks_count: 100,
+                commit_delay_ms: 2000,
+                finalize_delay_ms: 10000,
+                max_sessions: 1000,
+                max_txs_per_session: 1000,
+            },
+            graphql: GraphQlConfig {
+                enabled: true,
+                max_depth: 8,
+                max_complexity: 1000,
+                max_page_size: 100,
+            },
+            request_timeout: RequestTimeoutConfig {
+                default_ms: 30000,
+                route_timeouts: vec!["/api/v0.2/transactions=10000".to_owned()],
+            },
+            circuit_breaker: CircuitBreakerConfig {
+                enabled: true,
+                window_secs: 30,
+                min_requests: 20,
+                error_rate_threshold: 0.5,
+                open_secs: 10,
             },
         }
     }
@@ -313,12 +874,22 @@ API_COMMON_MAX_SUBSIDY_USD_SCALED=20000
 API_COMMON_SUBSIDY_NAME=PartnerName
 API_COMMON_MAX_NUMBER_OF_TRANSACTIONS_PER_BATCH=200
 API_COMMON_MAX_NUMBER_OF_AUTHORS_PER_BATCH=10
+API_COMMON_MAX_ACCOUNTS_PER_BATCH_REQUEST=100
+API_COMMON_INTERNAL_REQUEST_TIMEOUT_MS=30000
+API_COMMON_MAX_ASYNC_SUBMISSIONS=1000
+API_COMMON_STORE_FEE_QUOTES=true
+API_COMMON_FEE_TOLERANCE_PERCENT=5
 API_TOKEN_INVALIDATE_TOKEN_CACHE_PERIOD_SEC="10"
+API_TOKEN_NEGATIVE_CACHE_PERIOD_SEC="5"
 API_ADMIN_PORT="8080"
 API_ADMIN_URL="http://127.0.0.1:8080"
 API_ADMIN_SECRET_AUTH="sample"
 API_REST_PORT="3001"
 API_REST_URL="http://127.0.0.1:3001"
+API_REST_WS_PRIVATE_TOPICS_AUTH="true"
+API_REST_COMPRESSION_MIN_SIZE="1024"
+API_REST_COMPRESSION_EXCLUDED_ROUTES="/api/v0.2/networkStatus"
+API_REST_SWAGGER_UI_DIR="etc/swagger-ui"
 API_JSON_RPC_HTTP_PORT="3030"
 API_JSON_RPC_HTTP_URL="http://127.0.0.1:3030"
 API_JSON_RPC_WS_PORT="3031"
@@ -329,10 +900,70 @@ API_WEB3_CHAIN_ID="240"
 API_WEB3_MAX_BLOCK_RANGE="10"
 API_PRIVATE_PORT="8090"
 API_PRIVATE_URL="http://127.0.0.1:8090"
+API_PRIVATE_TRANSPORT="http"
+API_PRIVATE_GRPC_PORT="8091"
+API_PRIVATE_GRPC_URL="http://127.0.0.1:8091"
+API_PRIVATE_CLIENT_REQUEST_TIMEOUT_MS="2000"
+API_PRIVATE_CLIENT_DEADLINE_MS="5000"
+API_PRIVATE_CLIENT_MAX_RETRIES="3"
+API_PRIVATE_CLIENT_RETRY_BACKOFF_MS="100"
+API_PRIVATE_CLIENT_POOL_MAX_IDLE="8"
+API_PRIVATE_CLIENT_POOL_IDLE_TIMEOUT_SECS="90"
 API_PROVER_PORT="8088"
 API_PROVER_URL="http://127.0.0.1:8088"
 API_PROVER_SECRET_AUTH="sample"
+API_PROVER_WITNESS_EXPORT_PORT="8089"
 API_PROMETHEUS_PORT="3312"
+API_MEMPOOL_FEED_ENABLED="true"
+API_MEMPOOL_FEED_MIN_AGE_SECS="30"
+API_MEMPOOL_FEED_MAX_TXS="100"
+API_MEMPOOL_FEED_REDACT_AMOUNTS="false"
+API_MEMPOOL_FEED_REDACT_FEES="false"
+API_MEMPOOL_FEED_REDACT_ADDRESSES="true"
+API_ARCHIVE_ENABLED="false"
+API_ARCHIVE_EXPORT_ENABLED="false"
+API_ARCHIVE_URL="http://127.0.0.1:9000/zksync-archive"
+API_ARCHIVE_REQUEST_TIMEOUT_MS="5000"
+API_ARCHIVE_NEGATIVE_CACHE_PERIOD_SEC="60"
+API_ARCHIVE_SIGNING_KEY="0x2222222222222222222222222222222222222222222222222222222222222222"
+API_KEYS_ENABLED="true"
+API_KEYS_REQUIRE_KEY="false"
+API_KEYS_CACHE_TTL_SECS="60"
+API_KEYS_NEGATIVE_CACHE_TTL_SECS="10"
+API_RECOVERY_TIMELOCK_SECS="259200"
+API_RECOVERY_MAX_GUARDIANS="5"
+API_RATE_LIMIT_ENABLED="true"
+API_RATE_LIMIT_BACKEND="memory"
+API_RATE_LIMIT_REDIS_URL="redis://127.0.0.1:6379"
+API_RATE_LIMIT_READ_REQUESTS_PER_SECOND="50"
+API_RATE_LIMIT_READ_BURST="100"
+API_RATE_LIMIT_SUBMIT_REQUESTS_PER_SECOND="5"
+API_RATE_LIMIT_SUBMIT_BURST="20"
+API_RATE_LIMIT_TRUSTED_PROXIES="10.0.0.1,10.0.0.2"
+API_ENCRYPTED_TXS_ENABLED="true"
+API_ENCRYPTED_TXS_SECRET_KEY="0x1111111111111111111111111111111111111111111111111111111111111111"
+API_ENCRYPTED_TXS_REVEAL_DELAY_MS="1000"
+API_ENCRYPTED_TXS_REVEAL_POLL_INTERVAL_MS="200"
+API_ENCRYPTED_TXS_RETENTION_HOURS="168"
+API_ENCRYPTED_TXS_MAX_PENDING_PER_CLIENT="10"
+API_SANDBOX_SEED="42"
+API_SANDBOX_ACCOUNTS_COUNT="10"
+API_SANDBOX_BLOCKS_COUNT="100"
+API_SANDBOX_COMMIT_DELAY_MS="2000"
+API_SANDBOX_FINALIZE_DELAY_MS="10000"
+API_SANDBOX_MAX_SESSIONS="1000"
+API_SANDBOX_MAX_TXS_PER_SESSION="1000"
+API_GRAPHQL_ENABLED="true"
+API_GRAPHQL_MAX_DEPTH="8"
+API_GRAPHQL_MAX_COMPLEXITY="1000"
+API_GRAPHQL_MAX_PAGE_SIZE="100"
+API_REQUEST_TIMEOUT_DEFAULT_MS="30000"
+API_REQUEST_TIMEOUT_ROUTE_TIMEOUTS="/api/v0.2/transactions=10000"
+API_CIRCUIT_BREAKER_ENABLED="true"
+API_CIRCUIT_BREAKER_WINDOW_SECS="30"
+API_CIRCUIT_BREAKER_MIN_REQUESTS="20"
+API_CIRCUIT_BREAKER_ERROR_RATE_THRESHOLD="0.5"
+API_CIRCUIT_BREAKER_OPEN_SECS="10"
         "#;
         set_env(config);
 
@@ -354,6 +985,10 @@ API_PROMETHEUS_PORT="3312"
             config.prover.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.prover.port)
         );
+        assert_eq!(
+            config.prover.witness_export_bind_addr(),
+            SocketAddr::new(bind_broadcast_addr, config.prover.witness_export_port)
+        );
         assert_eq!(
             config.rest.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.rest.port)
@@ -370,5 +1005,63 @@ API_PROMETHEUS_PORT="3312"
             config.web3.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.web3.port)
         );
+        assert_eq!(
+            config.mempool_feed.min_age(),
+            Duration::from_secs(config.mempool_feed.min_age_secs)
+        );
+        assert_eq!(
+            config.archive.request_timeout(),
+            Duration::from_millis(config.archive.request_timeout_ms)
+        );
+        assert_eq!(
+            config.archive.negative_cache_period(),
+            Duration::from_secs(config.archive.negative_cache_period_sec)
+        );
+        assert_eq!(
+            config.request_timeout.route_timeouts(),
+            vec![(
+                "/api/v0.2/transactions".to_owned(),
+                Duration::from_millis(10000)
+            )]
+        );
+        assert_eq!(
+            config.keys.cache_ttl(),
+            Duration::from_secs(config.keys.cache_ttl_secs)
+        );
+        assert_eq!(
+            config.keys.negative_cache_ttl(),
+            Duration::from_secs(config.keys.negative_cache_ttl_secs)
+        );
+        assert_eq!(
+            config.recovery.timelock(),
+            Duration::from_secs(config.recovery.timelock_secs)
+        );
+        assert_eq!(
+            config.encrypted_txs.reveal_delay(),
+            Duration::from_millis(config.encrypted_txs.reveal_delay_ms)
+        );
+        assert_eq!(
+            config.sandbox.commit_delay(),
+            Duration::from_millis(config.sandbox.commit_delay_ms)
+        );
+        assert_eq!(
+            config.sandbox.finalize_delay(),
+            Duration::from_millis(config.sandbox.finalize_delay_ms)
+        );
+        assert_eq!(
+            config.rate_limit.trusted_proxies(),
+            vec![
+                "10.0.0.1".parse::<IpAddr>().unwrap(),
+                "10.0.0.2".parse::<IpAddr>().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Rate limits and bursts must be positive")]
+    fn zero_rate_limit() {
+        let mut config = expected_config().rate_limit;
+        config.submit_requests_per_second = 0;
+        config.validate();
     }
 }
@@ -0,0 +1,65 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the auditor cross-checking the stored balances with the account updates history.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct BalanceAuditorConfig {
+    /// Whether the balances are audited.
+    pub enabled: bool,
+    /// Interval between the audits, in seconds.
+    pub audit_interval: u64,
+    /// Number of the randomly chosen accounts checked by every audit.
+    pub sample_size: u32,
+    /// Mattermost webhook the discrepancies are reported to, the alerts are disabled if the URL is invalid.
+    pub webhook_url: String,
+}
+
+impl BalanceAuditorConfig {
+    pub fn from_env() -> Self {
+        envy_load!("balance_auditor", "BALANCE_AUDITOR_")
+    }
+
+    /// Converts `self.audit_interval` into `Duration`.
+    pub fn audit_interval(&self) -> Duration {
+        Duration::from_secs(self.audit_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> BalanceAuditorConfig {
+        BalanceAuditorConfig {
+            enabled: true,
+            audit_interval: 300,
+            sample_size: 100,
+            webhook_url: "http://127.0.0.1".into(),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+BALANCE_AUDITOR_ENABLED="true"
+BALANCE_AUDITOR_AUDIT_INTERVAL="300"
+BALANCE_AUDITOR_SAMPLE_SIZE="100"
+BALANCE_AUDITOR_WEBHOOK_URL="http://127.0.0.1"
+        "#;
+        set_env(config);
+
+        let actual = BalanceAuditorConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+
+    #[test]
+    fn methods() {
+        let config = expected_config();
+        assert_eq!(config.audit_interval(), Duration::from_secs(300));
+    }
+}
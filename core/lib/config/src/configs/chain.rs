@@ -16,6 +16,8 @@ pub struct ChainConfig {
     pub eth: Eth,
     /// State keeper / block generating configuration.
     pub state_keeper: StateKeeper,
+    /// Mempool transaction handlers configuration.
+    pub mempool: Mempool,
 }
 
 impl ChainConfig {
@@ -24,6 +26,7 @@ impl ChainConfig {
             circuit: envy_load!("circuit", "CHAIN_CIRCUIT_"),
             eth: envy_load!("eth", "CHAIN_ETH_"),
             state_keeper: envy_load!("state_keeper", "CHAIN_STATE_KEEPER_"),
+            mempool: envy_load!("mempool", "CHAIN_MEMPOOL_"),
         }
     }
     pub fn max_blocks_to_aggregate(&self) -> u32 {
@@ -94,6 +97,36 @@ pub struct StateKeeper {
     pub block_prove_deadline: u64,
     pub block_execute_deadline: u64,
     pub max_aggregated_tx_gas: usize,
+    /// Time (in seconds) between two sweeps evicting stale transactions from the mempool.
+    pub mempool_revalidation_interval: u64,
+    /// Number of the sealed blocks without a witness after which the blocks are sealed
+    /// after `throttled_miniblock_iterations` iterations.
+    pub witness_backlog_soft_limit: u64,
+    /// Number of the sealed blocks without a witness after which the blocks are also
+    /// limited to the smallest size from `block_chunk_sizes`.
+    pub witness_backlog_hard_limit: u64,
+    /// Maximum amount of miniblock iterations before sealing the block while the block production is throttled.
+    pub throttled_miniblock_iterations: u64,
+    /// Time (in seconds) between two checks of the witness backlog.
+    pub witness_backlog_check_interval: u64,
+    /// Share (percents) of the block chunks, once used the block is sealed. Disabled if zero.
+    pub seal_chunks_utilization_percent: u32,
+    /// Time (in seconds) since the first operation of the block, after which the block is sealed. Disabled if zero.
+    pub seal_first_tx_age: u64,
+    /// Time (in seconds) since the first priority operation of the block, after which the block is sealed.
+    /// Disabled if zero.
+    pub seal_priority_op_age: u64,
+    /// Estimated gas cost of the block commit, once reached the block is sealed. Disabled if zero.
+    pub seal_commit_gas_limit: u64,
+    /// Number of the threads the operations of the independent transactions are created on.
+    /// The transactions are executed sequentially if zero or one.
+    pub parallel_execution_threads: usize,
+    /// Number of blocks between two snapshots of the account tree stored on disk. Disabled if zero.
+    pub tree_snapshot_interval: u32,
+    /// Directory the account tree snapshots are stored in. Relative to `$ZKSYNC_HOME` unless absolute.
+    pub tree_snapshot_dir: String,
+    /// Number of the latest account tree snapshots kept on disk.
+    pub tree_snapshots_to_keep: usize,
 }
 
 impl StateKeeper {
@@ -113,6 +146,79 @@ impl StateKeeper {
     pub fn block_execute_deadline(&self) -> Duration {
         Duration::from_secs(self.block_execute_deadline)
     }
+
+    /// Converts `self.mempool_revalidation_interval` into `Duration`.
+    pub fn mempool_revalidation_interval(&self) -> Duration {
+        Duration::from_secs(self.mempool_revalidation_interval)
+    }
+
+    /// Converts `self.witness_backlog_check_interval` into `Duration`.
+    pub fn witness_backlog_check_interval(&self) -> Duration {
+        Duration::from_secs(self.witness_backlog_check_interval)
+    }
+
+    /// Converts `self.seal_first_tx_age` into `Duration`.
+    pub fn seal_first_tx_age(&self) -> Duration {
+        Duration::from_secs(self.seal_first_tx_age)
+    }
+
+    /// Converts `self.seal_priority_op_age` into `Duration`.
+    pub fn seal_priority_op_age(&self) -> Duration {
+        Duration::from_secs(self.seal_priority_op_age)
+    }
+}
+
+/// Transaction requests are distributed between several mempool handlers,
+/// the number of which is adjusted to the load within the configured bounds.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Mempool {
+    /// Number of the transaction handlers that are always running.
+    pub min_tx_handlers: usize,
+    /// Maximum number of the concurrently running transaction handlers.
+    pub max_tx_handlers: usize,
+    /// Capacity of the request channel of each transaction handler.
+    pub tx_handler_channel_capacity: usize,
+    /// Share (in percents) of the scaling interval the requests waited for the room in the full
+    /// handler channels, above which a new handler is spawned.
+    pub scale_up_saturation: u8,
+    /// Share (in percents) of the scaling interval the requests waited for the room in the full
+    /// handler channels, below which one of the handlers is retired.
+    pub scale_down_saturation: u8,
+    /// Number of the consecutive scaling intervals of the low saturation
+    /// before a handler is retired.
+    pub scale_down_intervals: u32,
+    /// Time (in milliseconds) between two scaling decisions.
+    pub scaling_interval: u64,
+    /// Share (in percents) of the block chunks that deposits can take.
+    pub deposit_chunks_quota: u8,
+    /// Share (in percents) of the block chunks that full exits can take.
+    pub full_exit_chunks_quota: u8,
+    /// Whether the proposed blocks are simulated against the committed state before being
+    /// sent to the state keeper.
+    pub simulate_proposed_blocks: bool,
+    /// Number of the proposals a transaction with insufficient balance is swapped out of,
+    /// before it's passed to the state keeper anyway.
+    pub simulation_max_deferrals: usize,
+    /// Number of the Ethereum blocks before the deadline of a priority operation, since which
+    /// it's included regardless of the quotas and the proposed block has no L2 transactions.
+    pub priority_op_deadline_margin: u64,
+}
+
+impl Mempool {
+    /// Converts `self.scale_up_saturation` into a fraction.
+    pub fn scale_up_saturation(&self) -> f64 {
+        f64::from(self.scale_up_saturation) / 100.0
+    }
+
+    /// Converts `self.scale_down_saturation` into a fraction.
+    pub fn scale_down_saturation(&self) -> f64 {
+        f64::from(self.scale_down_saturation) / 100.0
+    }
+
+    /// Converts `self.scaling_interval` into `Duration`.
+    pub fn scaling_interval(&self) -> Duration {
+        Duration::from_millis(self.scaling_interval)
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +253,33 @@ mod tests {
                 block_prove_deadline: 3_000,
                 block_execute_deadline: 4_000,
                 max_aggregated_tx_gas: 4_000_000,
+                mempool_revalidation_interval: 60,
+                witness_backlog_soft_limit: 50,
+                witness_backlog_hard_limit: 100,
+                throttled_miniblock_iterations: 40,
+                witness_backlog_check_interval: 10,
+                seal_chunks_utilization_percent: 95,
+                seal_first_tx_age: 10,
+                seal_priority_op_age: 5,
+                seal_commit_gas_limit: 0,
+                parallel_execution_threads: 4,
+                tree_snapshot_interval: 100,
+                tree_snapshot_dir: "db/tree_snapshots".into(),
+                tree_snapshots_to_keep: 2,
+            },
+            mempool: Mempool {
+                min_tx_handlers: 1,
+                max_tx_handlers: 8,
+                tx_handler_channel_capacity: 1000,
+                scale_up_saturation: 20,
+                scale_down_saturation: 1,
+                scale_down_intervals: 6,
+                scaling_interval: 10_000,
+                deposit_chunks_quota: 50,
+                full_exit_chunks_quota: 50,
+                simulate_proposed_blocks: true,
+                simulation_max_deferrals: 10,
+                priority_op_deadline_margin: 5760,
             },
         }
     }
@@ -175,6 +308,31 @@ CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE="300"
 CHAIN_STATE_KEEPER_BLOCK_PROVE_DEADLINE="3000"
 CHAIN_STATE_KEEPER_BLOCK_EXECUTE_DEADLINE="4000"
 CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
+CHAIN_STATE_KEEPER_MEMPOOL_REVALIDATION_INTERVAL="60"
+CHAIN_STATE_KEEPER_WITNESS_BACKLOG_SOFT_LIMIT="50"
+CHAIN_STATE_KEEPER_WITNESS_BACKLOG_HARD_LIMIT="100"
+CHAIN_STATE_KEEPER_THROTTLED_MINIBLOCK_ITERATIONS="40"
+CHAIN_STATE_KEEPER_WITNESS_BACKLOG_CHECK_INTERVAL="10"
+CHAIN_STATE_KEEPER_SEAL_CHUNKS_UTILIZATION_PERCENT="95"
+CHAIN_STATE_KEEPER_SEAL_FIRST_TX_AGE="10"
+CHAIN_STATE_KEEPER_SEAL_PRIORITY_OP_AGE="5"
+CHAIN_STATE_KEEPER_SEAL_COMMIT_GAS_LIMIT="0"
+CHAIN_STATE_KEEPER_PARALLEL_EXECUTION_THREADS="4"
+CHAIN_STATE_KEEPER_TREE_SNAPSHOT_INTERVAL="100"
+CHAIN_STATE_KEEPER_TREE_SNAPSHOT_DIR="db/tree_snapshots"
+CHAIN_STATE_KEEPER_TREE_SNAPSHOTS_TO_KEEP="2"
+CHAIN_MEMPOOL_MIN_TX_HANDLERS="1"
+CHAIN_MEMPOOL_MAX_TX_HANDLERS="8"
+CHAIN_MEMPOOL_TX_HANDLER_CHANNEL_CAPACITY="1000"
+CHAIN_MEMPOOL_SCALE_UP_SATURATION="20"
+CHAIN_MEMPOOL_SCALE_DOWN_SATURATION="1"
+CHAIN_MEMPOOL_SCALE_DOWN_INTERVALS="6"
+CHAIN_MEMPOOL_SCALING_INTERVAL="10000"
+CHAIN_MEMPOOL_DEPOSIT_CHUNKS_QUOTA="50"
+CHAIN_MEMPOOL_FULL_EXIT_CHUNKS_QUOTA="50"
+CHAIN_MEMPOOL_SIMULATE_PROPOSED_BLOCKS="true"
+CHAIN_MEMPOOL_SIMULATION_MAX_DEFERRALS="10"
+CHAIN_MEMPOOL_PRIORITY_OP_DEADLINE_MARGIN="5760"
         "#;
         set_env(config);
 
@@ -191,5 +349,17 @@ CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
             config.state_keeper.miniblock_iteration_interval(),
             Duration::from_millis(config.state_keeper.miniblock_iteration_interval)
         );
+        assert_eq!(
+            config.state_keeper.seal_first_tx_age(),
+            Duration::from_secs(config.state_keeper.seal_first_tx_age)
+        );
+        assert_eq!(
+            config.state_keeper.seal_priority_op_age(),
+            Duration::from_secs(config.state_keeper.seal_priority_op_age)
+        );
+        assert_eq!(
+            config.mempool.scaling_interval(),
+            Duration::from_millis(config.mempool.scaling_interval)
+        );
     }
 }
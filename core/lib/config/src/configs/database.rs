@@ -12,12 +12,21 @@ use crate::envy_load;
 pub struct DBConfig {
     /// Amount of open connections to the database held by server in the pool.
     pub pool_size: usize,
+    /// Amount of open connections in the pool serving the API reads, separate from the core pool.
+    pub api_pool_size: usize,
+    /// Maximum number of the API requests waiting for a connection,
+    /// the requests beyond this number are rejected right away.
+    pub api_pool_max_waiting: usize,
     /// Database URL.
     pub url: String,
     /// Rejected transactions will be stored in the database for this amount of hours.
     pub rejected_transactions_max_age: u64,
     /// Sleep time (in hours) of the actor responsible for deleting failed transactions from the database.
     pub rejected_transactions_cleaner_interval: u64,
+    /// Fee quotes served by the API will be stored in the database for this amount of hours.
+    pub fee_quotes_max_age: u64,
+    /// Prices fetched by the fee ticker will be kept in the price history for this amount of hours.
+    pub ticker_price_history_max_age: u64,
 }
 
 impl DBConfig {
@@ -34,6 +43,14 @@ impl DBConfig {
     pub fn rejected_transactions_cleaner_interval(&self) -> time::Duration {
         time::Duration::from_secs(self.rejected_transactions_cleaner_interval)
     }
+
+    pub fn fee_quotes_max_age(&self) -> time::Duration {
+        time::Duration::from_secs(self.fee_quotes_max_age * Self::SECS_PER_HOUR)
+    }
+
+    pub fn ticker_price_history_max_age(&self) -> time::Duration {
+        time::Duration::from_secs(self.ticker_price_history_max_age * Self::SECS_PER_HOUR)
+    }
 }
 
 #[cfg(test)]
@@ -44,9 +61,13 @@ mod tests {
     fn expected_config() -> DBConfig {
         DBConfig {
             pool_size: 10,
+            api_pool_size: 20,
+            api_pool_max_waiting: 100,
             url: "postgres://postgres@localhost/plasma".into(),
             rejected_transactions_max_age: 336,
             rejected_transactions_cleaner_interval: 24,
+            fee_quotes_max_age: 720,
+            ticker_price_history_max_age: 2160,
         }
     }
 
@@ -54,9 +75,13 @@ mod tests {
     fn from_env() {
         let config = r#"
 DATABASE_POOL_SIZE="10"
+DATABASE_API_POOL_SIZE="20"
+DATABASE_API_POOL_MAX_WAITING="100"
 DATABASE_URL="postgres://postgres@localhost/plasma"
 DATABASE_REJECTED_TRANSACTIONS_MAX_AGE="336"
 DATABASE_REJECTED_TRANSACTIONS_CLEANER_INTERVAL="24"
+DATABASE_FEE_QUOTES_MAX_AGE="720"
+DATABASE_TICKER_PRICE_HISTORY_MAX_AGE="2160"
         "#;
         set_env(config);
 
@@ -0,0 +1,82 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the monitor tracking the sizes of the deposits received by the Ethereum watcher.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct DepositMonitorConfig {
+    /// Whether the deposits are monitored.
+    pub enabled: bool,
+    /// Number of the latest deposits of each token the distribution is computed over.
+    pub window_size: usize,
+    /// Number of the deposits of the token required before its anomalies are reported.
+    pub min_samples: usize,
+    /// Deposit is reported as a whale one if it's larger than the 99th percentile of the window
+    /// multiplied by this value.
+    pub whale_multiplier: u64,
+    /// Deposit is considered dust if it's smaller than the median of the window divided by this value.
+    pub dust_divisor: u64,
+    /// Number of the dust deposits of the token within `dust_flood_interval` reported as a flood.
+    pub dust_flood_count: usize,
+    /// Time interval of the dust flood detection, in seconds.
+    pub dust_flood_interval: u64,
+    /// Mattermost webhook the anomalies are reported to, the alerts are disabled if the URL is invalid.
+    pub webhook_url: String,
+}
+
+impl DepositMonitorConfig {
+    pub fn from_env() -> Self {
+        envy_load!("deposit_monitor", "DEPOSIT_MONITOR_")
+    }
+
+    /// Converts `self.dust_flood_interval` into `Duration`.
+    pub fn dust_flood_interval(&self) -> Duration {
+        Duration::from_secs(self.dust_flood_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> DepositMonitorConfig {
+        DepositMonitorConfig {
+            enabled: true,
+            window_size: 1000,
+            min_samples: 50,
+            whale_multiplier: 10,
+            dust_divisor: 1000,
+            dust_flood_count: 100,
+            dust_flood_interval: 600,
+            webhook_url: "http://127.0.0.1".into(),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+DEPOSIT_MONITOR_ENABLED="true"
+DEPOSIT_MONITOR_WINDOW_SIZE="1000"
+DEPOSIT_MONITOR_MIN_SAMPLES="50"
+DEPOSIT_MONITOR_WHALE_MULTIPLIER="10"
+DEPOSIT_MONITOR_DUST_DIVISOR="1000"
+DEPOSIT_MONITOR_DUST_FLOOD_COUNT="100"
+DEPOSIT_MONITOR_DUST_FLOOD_INTERVAL="600"
+DEPOSIT_MONITOR_WEBHOOK_URL="http://127.0.0.1"
+        "#;
+        set_env(config);
+
+        let actual = DepositMonitorConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+
+    #[test]
+    fn methods() {
+        let config = expected_config();
+        assert_eq!(config.dust_flood_interval(), Duration::from_secs(600));
+    }
+}
@@ -14,6 +14,16 @@ pub struct ETHSenderConfig {
     pub sender: Sender,
     /// Options related to the `gas_adjuster` submodule.
     pub gas_price_limit: GasLimit,
+    /// Restrictions checked before signing any transaction with the operator key.
+    pub signing_policy: SigningPolicy,
+    /// Options related to the EIP-1559 fee market transactions.
+    pub fee_market: FeeMarket,
+    /// Additional operator keys the transactions are distributed among.
+    pub operator_keys: OperatorKeys,
+    /// Alerts on the operations not confirmed for too long.
+    pub stuck_tx_alert: StuckTxAlert,
+    /// Simulation of the transactions before they're sent.
+    pub tx_simulation: TxSimulation,
 }
 
 impl ETHSenderConfig {
@@ -24,6 +34,14 @@ impl ETHSenderConfig {
                 "eth_sender.gas_price_limit",
                 "ETH_SENDER_GAS_PRICE_LIMIT_"
             ),
+            signing_policy: envy_load!(
+                "eth_sender.signing_policy",
+                "ETH_SENDER_SIGNING_POLICY_"
+            ),
+            fee_market: envy_load!("eth_sender.fee_market", "ETH_SENDER_FEE_MARKET_"),
+            operator_keys: envy_load!("eth_sender.operator_keys", "ETH_SENDER_OPERATOR_KEYS_"),
+            stuck_tx_alert: envy_load!("eth_sender.stuck_tx_alert", "ETH_SENDER_STUCK_TX_ALERT_"),
+            tx_simulation: envy_load!("eth_sender.tx_simulation", "ETH_SENDER_TX_SIMULATION_"),
         }
     }
 }
@@ -44,6 +62,13 @@ pub struct Sender {
     pub max_txs_in_flight: u64,
     /// Whether sender should interact with L1 or not.
     pub is_enabled: bool,
+    /// Whether withdrawals should be completed by the `executeBlocks` transaction itself
+    /// instead of being left pending on the contract.
+    pub complete_withdrawals_on_execute: bool,
+    /// The maximum amount of withdrawals completed by a single `executeBlocks` transaction.
+    /// Withdrawals of the bigger operations are left pending, so the gas limit of the
+    /// transaction stays predictable.
+    pub max_withdrawals_per_execute_tx: u64,
 }
 
 impl Sender {
@@ -53,9 +78,35 @@ impl Sender {
     }
 }
 
+/// Heuristic the gas price of the legacy transactions is chosen with.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GasPriceStrategy {
+    /// Gas price suggested by the Ethereum node, limited by the scaled average of the recent prices.
+    NodeOracle,
+    /// Base fee of the next block plus the percentile of the recent priority fees,
+    /// configured in the `fee_market` section.
+    FeeHistory,
+    /// Gas price suggested by the Ethereum node, limited by the `default` limit.
+    FixedCap,
+}
+
+impl GasPriceStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NodeOracle => "node_oracle",
+            Self::FeeHistory => "fee_history",
+            Self::FixedCap => "fixed_cap",
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct GasLimit {
+    /// Heuristic the gas price is chosen with.
+    pub strategy: GasPriceStrategy,
     /// Gas price limit to be used by GasAdjuster until the statistics data is gathered.
+    /// It's the permanent limit of the `fixed_cap` strategy.
     pub default: u64,
     /// Interval between updates of the gas price limit (used by GasAdjuster) in seconds.
     pub update_interval: u64,
@@ -77,6 +128,97 @@ impl GasLimit {
     }
 }
 
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct SigningPolicy {
+    /// Contracts the operator is allowed to send transactions to, in addition to the zkSync contract.
+    pub allowed_contracts: Vec<Address>,
+    /// Maximum gas price of the signed transaction in wei.
+    pub max_gas_price: u64,
+    /// Maximum value of the signed transaction in wei.
+    pub max_value: u64,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FeeMarket {
+    /// Whether the EIP-1559 transactions are sent instead of the legacy ones.
+    pub eip1559_enabled: bool,
+    /// Number of the recent blocks the fees are estimated over.
+    pub fee_history_blocks: u64,
+    /// Percentile of the priority fees paid in the recent blocks used as the priority fee.
+    pub priority_fee_percentile: f64,
+    /// Multiplier of the latest base fee in the max fee per gas, so the transaction
+    /// stays includable while the base fee grows.
+    pub base_fee_multiplier: f64,
+    /// Minimum priority fee per gas in wei.
+    pub min_priority_fee: u64,
+    /// Maximum fee per gas in wei, the fees are never raised above it.
+    pub max_fee_per_gas_cap: u64,
+    /// Percentage both fees are raised by when the stuck transaction is replaced.
+    /// Nodes don't accept the replacement raising the fees by less than 10%.
+    pub escalation_percent: u64,
+}
+
+/// Way the operations are assigned to the operator keys.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAssignment {
+    /// Keys are used in turns, the key is kept while the operations the next one
+    /// has to go after are in flight.
+    RoundRobin,
+    /// Every type of the operations is signed with the dedicated key.
+    RoleBased,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct OperatorKeys {
+    /// Way the operations are assigned to the keys.
+    pub assignment: KeyAssignment,
+    /// Private keys of the additional operator accounts, their addresses are derived from them.
+    pub private_keys: Vec<H256>,
+    /// Index of the key signing the commit operations with the role-based assignment.
+    /// The main operator key has index 0, the additional keys follow it.
+    pub commit_key: usize,
+    /// Index of the key signing the prove operations with the role-based assignment.
+    pub prove_key: usize,
+    /// Index of the key signing the execute operations with the role-based assignment.
+    pub execute_key: usize,
+    /// Balance of the operator account in wei below which it's reported as low,
+    /// the round-robin assignment skips such keys.
+    pub min_balance: u64,
+    /// Balance of the operator account in wei below which the new blocks are not committed
+    /// with it, so the funds left are spent on proving and executing the committed ones.
+    pub critical_balance: u64,
+    /// Interval between the checks of the operator accounts balances in seconds.
+    pub balance_check_interval: u64,
+    /// Mattermost webhook the low balances are reported to, the alerts are disabled if the URL is invalid.
+    pub webhook_url: String,
+}
+
+impl OperatorKeys {
+    /// Converts `self.balance_check_interval` into `Duration`.
+    pub fn balance_check_interval(&self) -> Duration {
+        Duration::from_secs(self.balance_check_interval)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct StuckTxAlert {
+    /// Amount of blocks since the first transaction of the operation was sent after which
+    /// the unconfirmed operation is reported, regardless of its replacements.
+    pub hard_deadline_blocks: u64,
+    /// Mattermost webhook the stuck operations are reported to, the alerts are disabled if the URL is invalid.
+    pub webhook_url: String,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct TxSimulation {
+    /// Whether the transactions are simulated with `eth_call` before they're sent.
+    pub enabled: bool,
+    /// Amount of blocks the operations of the same type are not sent for
+    /// after the simulated transaction reverts.
+    pub pause_blocks: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,17 +232,55 @@ mod tests {
                 tx_poll_period: 3,
                 max_txs_in_flight: 3,
                 is_enabled: true,
+                complete_withdrawals_on_execute: true,
+                max_withdrawals_per_execute_tx: 100,
                 operator_private_key: hash(
                     "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
                 ),
                 operator_commit_eth_addr: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
             },
             gas_price_limit: GasLimit {
+                strategy: GasPriceStrategy::FeeHistory,
                 default: 400000000000,
                 update_interval: 150,
                 sample_interval: 15,
                 scale_factor: 1.0f64,
             },
+            signing_policy: SigningPolicy {
+                allowed_contracts: vec![addr("70a0F165d6f8054d0d0CF8dFd4DD2005f0AF6B55")],
+                max_gas_price: 2000000000000,
+                max_value: 0,
+            },
+            fee_market: FeeMarket {
+                eip1559_enabled: true,
+                fee_history_blocks: 10,
+                priority_fee_percentile: 50.0,
+                base_fee_multiplier: 2.0,
+                min_priority_fee: 1000000000,
+                max_fee_per_gas_cap: 1000000000000,
+                escalation_percent: 15,
+            },
+            operator_keys: OperatorKeys {
+                assignment: KeyAssignment::RoleBased,
+                private_keys: vec![hash(
+                    "0559b9f000b4e4bbb7fe02e1374cef9623c2ab7c3791204b490e1f229191d104",
+                )],
+                commit_key: 0,
+                prove_key: 1,
+                execute_key: 1,
+                min_balance: 1000000000000000000,
+                critical_balance: 100000000000000000,
+                balance_check_interval: 60,
+                webhook_url: "http://127.0.0.1".into(),
+            },
+            stuck_tx_alert: StuckTxAlert {
+                hard_deadline_blocks: 300,
+                webhook_url: "http://127.0.0.1".into(),
+            },
+            tx_simulation: TxSimulation {
+                enabled: true,
+                pause_blocks: 10,
+            },
         }
     }
 
@@ -112,12 +292,38 @@ ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK="30"
 ETH_SENDER_SENDER_TX_POLL_PERIOD="3"
 ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
 ETH_SENDER_SENDER_IS_ENABLED="true"
+ETH_SENDER_SENDER_COMPLETE_WITHDRAWALS_ON_EXECUTE="true"
+ETH_SENDER_SENDER_MAX_WITHDRAWALS_PER_EXECUTE_TX="100"
 ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
 ETH_SENDER_SENDER_OPERATOR_COMMIT_ETH_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+ETH_SENDER_GAS_PRICE_LIMIT_STRATEGY="fee_history"
 ETH_SENDER_GAS_PRICE_LIMIT_DEFAULT="400000000000"
 ETH_SENDER_GAS_PRICE_LIMIT_UPDATE_INTERVAL="150"
 ETH_SENDER_GAS_PRICE_LIMIT_SAMPLE_INTERVAL="15"
 ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
+ETH_SENDER_SIGNING_POLICY_ALLOWED_CONTRACTS="0x70a0F165d6f8054d0d0CF8dFd4DD2005f0AF6B55"
+ETH_SENDER_SIGNING_POLICY_MAX_GAS_PRICE="2000000000000"
+ETH_SENDER_SIGNING_POLICY_MAX_VALUE="0"
+ETH_SENDER_FEE_MARKET_EIP1559_ENABLED="true"
+ETH_SENDER_FEE_MARKET_FEE_HISTORY_BLOCKS="10"
+ETH_SENDER_FEE_MARKET_PRIORITY_FEE_PERCENTILE="50"
+ETH_SENDER_FEE_MARKET_BASE_FEE_MULTIPLIER="2"
+ETH_SENDER_FEE_MARKET_MIN_PRIORITY_FEE="1000000000"
+ETH_SENDER_FEE_MARKET_MAX_FEE_PER_GAS_CAP="1000000000000"
+ETH_SENDER_FEE_MARKET_ESCALATION_PERCENT="15"
+ETH_SENDER_OPERATOR_KEYS_ASSIGNMENT="role_based"
+ETH_SENDER_OPERATOR_KEYS_PRIVATE_KEYS="0x0559b9f000b4e4bbb7fe02e1374cef9623c2ab7c3791204b490e1f229191d104"
+ETH_SENDER_OPERATOR_KEYS_COMMIT_KEY="0"
+ETH_SENDER_OPERATOR_KEYS_PROVE_KEY="1"
+ETH_SENDER_OPERATOR_KEYS_EXECUTE_KEY="1"
+ETH_SENDER_OPERATOR_KEYS_MIN_BALANCE="1000000000000000000"
+ETH_SENDER_OPERATOR_KEYS_CRITICAL_BALANCE="100000000000000000"
+ETH_SENDER_OPERATOR_KEYS_BALANCE_CHECK_INTERVAL="60"
+ETH_SENDER_OPERATOR_KEYS_WEBHOOK_URL="http://127.0.0.1"
+ETH_SENDER_STUCK_TX_ALERT_HARD_DEADLINE_BLOCKS="300"
+ETH_SENDER_STUCK_TX_ALERT_WEBHOOK_URL="http://127.0.0.1"
+ETH_SENDER_TX_SIMULATION_ENABLED="true"
+ETH_SENDER_TX_SIMULATION_PAUSE_BLOCKS="10"
         "#;
         set_env(config);
 
@@ -143,5 +349,9 @@ ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
             config.gas_price_limit.sample_interval(),
             Duration::from_secs(config.gas_price_limit.sample_interval)
         );
+        assert_eq!(
+            config.operator_keys.balance_check_interval(),
+            Duration::from_secs(config.operator_keys.balance_check_interval)
+        );
     }
 }
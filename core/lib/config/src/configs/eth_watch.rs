@@ -1,7 +1,11 @@
 // Built-in uses
+use std::collections::HashMap;
 use std::time::Duration;
 // External uses
+use num::BigUint;
 use serde::Deserialize;
+// Workspace uses
+use zksync_types::TokenId;
 // Local uses
 use crate::envy_load;
 
@@ -11,9 +15,26 @@ pub struct ETHWatchConfig {
     /// Amount of confirmations for the priority operation to be processed.
     /// In production this should be a non-zero value because of block reverts.
     pub confirmations_for_eth_event: u64,
+    /// Amount of confirmations for the full exit operations.
+    pub confirmations_for_full_exit: u64,
+    /// Amount of confirmations for the deposits above the threshold of their token.
+    pub confirmations_for_large_deposit: u64,
+    /// Thresholds of the large deposits in the `<token_id>=<amount>` format,
+    /// the amount is in the smallest units of the token.
+    pub large_deposit_thresholds: Vec<String>,
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// Whether to subscribe to the contract logs and the new blocks via WebSocket, so the new
+    /// events are processed and confirmed as soon as the blocks are mined. The node is polled
+    /// as usual while the subscription is down.
+    pub log_subscription_enabled: bool,
+    /// WebSocket URL of the Ethereum node used for the log subscription.
+    pub web3_ws_url: String,
+    /// How often we want to poll the Ethereum node while the log subscription is active.
+    /// The node is polled on every new block then, so it only covers the missed notifications.
+    /// Value in milliseconds.
+    pub subscribed_poll_interval: u64,
 }
 
 impl ETHWatchConfig {
@@ -25,6 +46,27 @@ impl ETHWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Parses `self.large_deposit_thresholds` into the thresholds by the token id.
+    pub fn large_deposit_thresholds(&self) -> HashMap<TokenId, BigUint> {
+        self.large_deposit_thresholds
+            .iter()
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .split_once('=')
+                    .and_then(|(token_id, amount)| {
+                        Some((TokenId(token_id.parse().ok()?), amount.parse().ok()?))
+                    })
+                    .unwrap_or_else(|| panic!("Invalid large deposit threshold: {}", entry))
+            })
+            .collect()
+    }
+
+    /// Converts `self.subscribed_poll_interval` into `Duration`.
+    pub fn subscribed_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.subscribed_poll_interval)
+    }
 }
 
 #[cfg(test)]
@@ -35,7 +77,13 @@ mod tests {
     fn expected_config() -> ETHWatchConfig {
         ETHWatchConfig {
             confirmations_for_eth_event: 0,
+            confirmations_for_full_exit: 1,
+            confirmations_for_large_deposit: 10,
+            large_deposit_thresholds: vec!["0=1000".to_owned(), "1=500".to_owned()],
             eth_node_poll_interval: 300,
+            log_subscription_enabled: true,
+            web3_ws_url: "ws://127.0.0.1:8546".into(),
+            subscribed_poll_interval: 15000,
         }
     }
 
@@ -43,7 +91,13 @@ mod tests {
     fn from_env() {
         let config = r#"
 ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
+ETH_WATCH_CONFIRMATIONS_FOR_FULL_EXIT="1"
+ETH_WATCH_CONFIRMATIONS_FOR_LARGE_DEPOSIT="10"
+ETH_WATCH_LARGE_DEPOSIT_THRESHOLDS="0=1000,1=500"
 ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
+ETH_WATCH_LOG_SUBSCRIPTION_ENABLED="true"
+ETH_WATCH_WEB3_WS_URL="ws://127.0.0.1:8546"
+ETH_WATCH_SUBSCRIBED_POLL_INTERVAL="15000"
         "#;
         set_env(config);
 
@@ -60,5 +114,18 @@ ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
             config.poll_interval(),
             Duration::from_millis(config.eth_node_poll_interval)
         );
+        assert_eq!(
+            config.large_deposit_thresholds(),
+            vec![
+                (TokenId(0), BigUint::from(1000u32)),
+                (TokenId(1), BigUint::from(500u32))
+            ]
+            .into_iter()
+            .collect()
+        );
+        assert_eq!(
+            config.subscribed_poll_interval(),
+            Duration::from_millis(config.subscribed_poll_interval)
+        );
     }
 }
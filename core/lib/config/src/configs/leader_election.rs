@@ -0,0 +1,62 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the leader election between the hot-standby core servers.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct LeaderElectionConfig {
+    /// If disabled, the server produces blocks and sends them to L1 unconditionally.
+    pub enabled: bool,
+    /// Name of the server instance stored together with its leadership term.
+    pub instance_name: String,
+    /// How often the standby instance tries to become the leader and the leader checks
+    /// that it still holds the leadership. Value in milliseconds.
+    pub poll_interval: u64,
+}
+
+impl LeaderElectionConfig {
+    pub fn from_env() -> Self {
+        envy_load!("leader_election", "LEADER_ELECTION_")
+    }
+
+    /// Converts `self.poll_interval` into `Duration`.
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> LeaderElectionConfig {
+        LeaderElectionConfig {
+            enabled: true,
+            instance_name: "zksync-server-eu".into(),
+            poll_interval: 1000,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+LEADER_ELECTION_ENABLED="true"
+LEADER_ELECTION_INSTANCE_NAME="zksync-server-eu"
+LEADER_ELECTION_POLL_INTERVAL="1000"
+        "#;
+        set_env(config);
+
+        let actual = LeaderElectionConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+
+    #[test]
+    fn methods() {
+        let config = expected_config();
+        assert_eq!(config.poll_interval(), Duration::from_millis(1000));
+    }
+}
@@ -1,17 +1,20 @@
 // Public re-exports
 pub use self::{
-    api::ApiConfig, chain::ChainConfig, contracts::ContractsConfig, database::DBConfig,
+    api::ApiConfig, balance_auditor::BalanceAuditorConfig, chain::ChainConfig,
+    contracts::ContractsConfig, database::DBConfig, deposit_monitor::DepositMonitorConfig,
     dev_liquidity_token_watcher::DevLiquidityTokenWatcherConfig, eth_client::ETHClientConfig,
     eth_sender::ETHSenderConfig, eth_watch::ETHWatchConfig, event_listener::EventListenerConfig,
     forced_exit_requests::ForcedExitRequestsConfig, gateway_watcher::GatewayWatcherConfig,
-    misc::MiscConfig, prover::ProverConfig, ticker::TickerConfig,
-    token_handler::TokenHandlerConfig,
+    leader_election::LeaderElectionConfig, misc::MiscConfig, prover::ProverConfig,
+    ticker::TickerConfig, token_handler::TokenHandlerConfig,
 };
 
 pub mod api;
+pub mod balance_auditor;
 pub mod chain;
 pub mod contracts;
 pub mod database;
+pub mod deposit_monitor;
 pub mod dev_liquidity_token_watcher;
 pub mod eth_client;
 pub mod eth_sender;
@@ -19,6 +22,7 @@ pub mod eth_watch;
 pub mod event_listener;
 pub mod forced_exit_requests;
 pub mod gateway_watcher;
+pub mod leader_election;
 pub mod misc;
 pub mod prover;
 pub mod ticker;
@@ -11,6 +11,7 @@ pub struct ProverConfig {
     pub prover: Prover,
     pub core: Core,
     pub witness_generator: WitnessGenerator,
+    pub exit_proof_generator: ExitProofGenerator,
 }
 
 impl ProverConfig {
@@ -19,6 +20,10 @@ impl ProverConfig {
             prover: envy_load!("prover.prover", "PROVER_PROVER_"),
             core: envy_load!("prover.core", "PROVER_CORE_"),
             witness_generator: envy_load!("prover.witness_generator", "PROVER_WITNESS_GENERATOR_"),
+            exit_proof_generator: envy_load!(
+                "prover.exit_proof_generator",
+                "PROVER_EXIT_PROOF_GENERATOR_"
+            ),
         }
     }
 }
@@ -34,6 +39,12 @@ pub struct Prover {
     pub request_timeout: u64,
     /// Flag for dying after proving cycle
     pub die_after_proof: bool,
+    /// Tags of the jobs the prover is capable of, the prover without the tags gets any job.
+    /// The tags are `single_proof` and `aggregated_proof` to limit the types of the jobs,
+    /// `block_size=<chunks>` to limit the block sizes of the single proofs,
+    /// and `max_aggregated_blocks=<number>` to limit the number of blocks in the aggregated proofs.
+    /// Other tags, e.g. `gpu`, are ignored.
+    pub capability_tags: Vec<String>,
 }
 
 impl Prover {
@@ -56,10 +67,17 @@ impl Prover {
 /// Core settings related to the prover applications interacting with it.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Core {
-    /// Timeout to consider prover gone in ms.
+    /// Timeout to consider prover gone in ms, i.e. the duration of the job lease.
+    /// The job is returned to the queue unless the prover sends a heartbeat within it.
     pub gone_timeout: u64,
     /// Amount of provers in the cluser if there is no pending jobs.
     pub idle_provers: u32,
+    /// Waiting time in seconds for which an idle job gains one priority level,
+    /// 0 disables the aging.
+    pub job_aging_interval: u64,
+    /// Age in seconds of the oldest priority operation of the job blocks after which the job
+    /// is given out before any other job, 0 disables it.
+    pub aged_priority_op_threshold: u64,
 }
 
 impl Core {
@@ -67,6 +85,16 @@ impl Core {
     pub fn gone_timeout(&self) -> Duration {
         Duration::from_millis(self.gone_timeout)
     }
+
+    /// Converts `self.job_aging_interval` into `Duration`.
+    pub fn job_aging_interval(&self) -> Duration {
+        Duration::from_secs(self.job_aging_interval)
+    }
+
+    /// Converts `self.aged_priority_op_threshold` into `Duration`.
+    pub fn aged_priority_op_threshold(&self) -> Duration {
+        Duration::from_secs(self.aged_priority_op_threshold)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -84,6 +112,27 @@ impl WitnessGenerator {
     }
 }
 
+/// Settings of the generator of the exit proofs requested via API.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ExitProofGenerator {
+    /// Interval to check whether there are requested exit proofs in ms.
+    pub job_check_interval: u64,
+    /// Timeout to consider the exit proof generation abandoned in seconds.
+    pub job_timeout: u64,
+}
+
+impl ExitProofGenerator {
+    /// Converts `self.job_check_interval` into `Duration`.
+    pub fn job_check_interval(&self) -> Duration {
+        Duration::from_millis(self.job_check_interval)
+    }
+
+    /// Converts `self.job_timeout` into `Duration`.
+    pub fn job_timeout(&self) -> Duration {
+        Duration::from_secs(self.job_timeout)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,15 +145,22 @@ mod tests {
                 cycle_wait: 500,
                 request_timeout: 10,
                 die_after_proof: false,
+                capability_tags: vec!["single_proof".to_owned(), "block_size=26".to_owned()],
             },
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                job_aging_interval: 300,
+                aged_priority_op_threshold: 1800,
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
                 witness_generators: 2,
             },
+            exit_proof_generator: ExitProofGenerator {
+                job_check_interval: 1000,
+                job_timeout: 3600,
+            },
         }
     }
 
@@ -115,10 +171,15 @@ PROVER_PROVER_HEARTBEAT_INTERVAL="1000"
 PROVER_PROVER_CYCLE_WAIT="500"
 PROVER_PROVER_REQUEST_TIMEOUT="10"
 PROVER_PROVER_DIE_AFTER_PROOF=false
+PROVER_PROVER_CAPABILITY_TAGS="single_proof,block_size=26"
 PROVER_CORE_GONE_TIMEOUT="60000"
 PROVER_CORE_IDLE_PROVERS="1"
+PROVER_CORE_JOB_AGING_INTERVAL="300"
+PROVER_CORE_AGED_PRIORITY_OP_THRESHOLD="1800"
 PROVER_WITNESS_GENERATOR_PREPARE_DATA_INTERVAL="500"
 PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
+PROVER_EXIT_PROOF_GENERATOR_JOB_CHECK_INTERVAL="1000"
+PROVER_EXIT_PROOF_GENERATOR_JOB_TIMEOUT="3600"
         "#;
         set_env(config);
 
@@ -148,10 +209,27 @@ PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
             config.core.gone_timeout(),
             Duration::from_millis(config.core.gone_timeout)
         );
+        assert_eq!(
+            config.core.job_aging_interval(),
+            Duration::from_secs(config.core.job_aging_interval)
+        );
+        assert_eq!(
+            config.core.aged_priority_op_threshold(),
+            Duration::from_secs(config.core.aged_priority_op_threshold)
+        );
 
         assert_eq!(
             config.witness_generator.prepare_data_interval(),
             Duration::from_millis(config.witness_generator.prepare_data_interval)
         );
+
+        assert_eq!(
+            config.exit_proof_generator.job_check_interval(),
+            Duration::from_millis(config.exit_proof_generator.job_check_interval)
+        );
+        assert_eq!(
+            config.exit_proof_generator.job_timeout(),
+            Duration::from_secs(config.exit_proof_generator.job_timeout)
+        );
     }
 }
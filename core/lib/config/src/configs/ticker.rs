@@ -1,5 +1,8 @@
-use num::{rational::Ratio, BigUint};
+// Built-in uses
+use std::fs;
+use std::time::Duration;
 // External uses
+use num::{rational::Ratio, BigUint};
 use serde::Deserialize;
 // Workspace uses
 use zksync_types::Address;
@@ -11,17 +14,112 @@ use crate::envy_load;
 pub enum TokenPriceSource {
     CoinGecko,
     CoinMarketCap,
+    /// Time-weighted average price from the Uniswap V3 subgraph.
+    Uniswap,
+    /// Chainlink price feeds read from the Ethereum node.
+    Chainlink,
+}
+
+/// Chainlink price feed of the token.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainlinkFeed {
+    /// Address of the token on L1, zero for ETH.
+    pub token: Address,
+    /// Address of the aggregator contract reporting the USD price of the token.
+    pub feed: Address,
+    /// Maximum time between the updates of the feed, the older answers are stale.
+    pub heartbeat: Duration,
+    /// Number of decimals of the answer of the feed.
+    pub decimals: u8,
+}
+
+/// Sanity bounds of the token price, the prices outside of them are never accepted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenPriceBounds {
+    /// Address of the token on L1, zero for ETH.
+    pub token: Address,
+    /// Minimum USD price of the token.
+    pub min_usd_price: Ratio<BigUint>,
+    /// Maximum USD price of the token.
+    pub max_usd_price: Ratio<BigUint>,
+    /// Maximum change of the token price between the updates, in percent.
+    pub max_change_percent: u32,
+}
+
+/// Type of the transactions the fee subsidy rule applies to.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum SubsidizedTxType {
+    /// Transfers, including the ones to the new accounts.
+    Transfer,
+    /// Withdrawals, including the fast ones.
+    Withdraw,
+    /// NFT withdrawals, including the fast ones.
+    WithdrawNFT,
+    ChangePubKey,
+    MintNFT,
+    Swap,
+}
+
+/// Rule of the fee subsidy, e.g. for a promotion. The rules are loaded from the JSON file.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct SubsidyRule {
+    /// Name of the rule, the subsidized amounts are accounted under it.
+    pub name: String,
+    /// Discount of the fee in percent, 100 makes the transactions free.
+    pub discount_percent: u32,
+    /// Maximum total USD amount subsidized by the rule, scaled by SUBSIDY_USD_AMOUNTS_SCALE.
+    pub max_subsidy_usd_scaled: u64,
+    /// Types of the transactions the rule applies to, any if empty.
+    #[serde(default)]
+    pub tx_types: Vec<SubsidizedTxType>,
+    /// Tokens the fee is paid in, any if empty.
+    #[serde(default)]
+    pub tokens: Vec<Address>,
+    /// Addresses the fee is requested for, any if empty. These are the recipients
+    /// of the transfers and withdrawals and the accounts changing the public key.
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+    /// Unix timestamp the rule is active since.
+    #[serde(default)]
+    pub active_from: Option<u64>,
+    /// Unix timestamp the rule is active until.
+    #[serde(default)]
+    pub active_until: Option<u64>,
+}
+
+impl SubsidyRule {
+    pub fn max_subsidy_usd(&self) -> Ratio<BigUint> {
+        scaled_u64_to_ratio(self.max_subsidy_usd_scaled)
+    }
+
+    /// Whether the rule is active at the unix timestamp.
+    pub fn is_active(&self, timestamp: u64) -> bool {
+        self.active_from.map_or(true, |from| from <= timestamp)
+            && self.active_until.map_or(true, |until| timestamp < until)
+    }
 }
 
 /// Configuration for the fee ticker.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct TickerConfig {
-    /// Indicator of the API to be used for getting token prices.
-    pub token_price_source: TokenPriceSource,
+    /// APIs the token prices are requested from, the median of their prices is used.
+    pub token_price_sources: Vec<TokenPriceSource>,
     /// URL of CoinMarketCap API. Can be set to the mock server for local development.
     pub coinmarketcap_base_url: String,
     /// URL of CoinGecko API. Can be set to the mock server for local development.
     pub coingecko_base_url: String,
+    /// URL of the Uniswap V3 subgraph the time-weighted average prices are calculated from.
+    pub uniswap_v3_url: String,
+    /// Maximum age of the price reported by the API, the older prices are ignored.
+    pub max_price_age_seconds: u64,
+    /// Tokens priced by the Chainlink feeds.
+    pub chainlink_tokens: Vec<Address>,
+    /// Addresses of the Chainlink feeds, in the same order as the tokens.
+    pub chainlink_feeds: Vec<Address>,
+    /// Heartbeats of the Chainlink feeds in seconds, in the same order as the tokens.
+    pub chainlink_heartbeats: Vec<u64>,
+    /// Decimals of the Chainlink feeds answers, in the same order as the tokens.
+    pub chainlink_decimals: Vec<u8>,
     /// Coefficient for scaling all fees in percent.
     pub scale_fee_percent: u32,
     /// Coefficient for the fee price for fast withdrawal requests.
@@ -40,6 +138,55 @@ pub struct TickerConfig {
     pub number_of_ticker_actors: u8,
     /// Subsidized price for ChangePubKey in cents scaled by SUBSIDY_USD_AMOUNTS_SCALE
     pub subsidy_cpk_price_usd_scaled: u64,
+    /// Name of the file with the fee subsidy rules in `etc/subsidy-rules`, no rules if empty.
+    pub subsidy_rules_name: String,
+    /// Mempool size the fees start growing from, the congestion scaling is disabled if zero.
+    pub congestion_mempool_threshold: u32,
+    /// Maximum multiplier of the fees applied in the congestion, in percent.
+    pub congestion_max_multiplier_percent: u32,
+    /// Part of the excess multiplier dropped per update once the mempool drains, in percent.
+    pub congestion_decay_percent: u32,
+    /// Interval of the congestion multiplier updates in seconds.
+    pub congestion_update_interval_seconds: u64,
+    /// Batches with more transactions have the per-transaction overhead discounted,
+    /// the discount is disabled if zero.
+    pub batch_discount_min_txs: u32,
+    /// Part of the per-transaction overhead discounted in the large batches, in percent.
+    pub batch_overhead_discount_percent: u32,
+    /// Weight of the new gas price in its moving average the fees are calculated with,
+    /// in percent. 100 disables the smoothing.
+    pub gas_price_smoothing_percent: u32,
+    /// Maximum drop of the new gas price below the average, in percent.
+    /// The new gas prices are not clamped if zero, the rises are never clamped.
+    pub gas_price_max_deviation_percent: u32,
+    /// Interval of the gas price average updates in seconds.
+    pub gas_price_update_interval_seconds: u64,
+    /// Time the token prices are cached by the API for in seconds, the cache is disabled if zero.
+    pub price_cache_ttl_seconds: u64,
+    /// Interval of the refresh of the cached prices of the active tokens in seconds.
+    pub price_cache_refresh_interval_seconds: u64,
+    /// Tokens requested within this time (in seconds) are considered active,
+    /// their cached prices are kept warm.
+    pub price_cache_active_token_seconds: u64,
+    /// Maximum change of the token price between the updates, in percent.
+    /// Prices moving further are considered anomalies and the last good price is kept.
+    pub max_price_change_percent: u32,
+    /// Number of the consecutive anomalies opening the circuit breaker of the token price.
+    pub price_anomalies_to_open_breaker: u32,
+    /// Number of the consecutive consistent prices closing the circuit breaker,
+    /// the latest of them becomes the new price of the token.
+    pub stable_prices_to_close_breaker: u32,
+    /// Tokens with the individual price sanity bounds.
+    pub price_bounds_tokens: Vec<Address>,
+    /// Minimum USD prices of the tokens scaled by SUBSIDY_USD_AMOUNTS_SCALE,
+    /// in the same order as the tokens.
+    pub price_bounds_min_usd_scaled: Vec<u64>,
+    /// Maximum USD prices of the tokens scaled by SUBSIDY_USD_AMOUNTS_SCALE,
+    /// in the same order as the tokens.
+    pub price_bounds_max_usd_scaled: Vec<u64>,
+    /// Maximum changes of the token prices between the updates in percent,
+    /// overriding `max_price_change_percent`, in the same order as the tokens.
+    pub price_bounds_max_change_percent: Vec<u32>,
 }
 
 impl TickerConfig {
@@ -51,13 +198,96 @@ impl TickerConfig {
         envy_load!("fee_ticker", "FEE_TICKER_")
     }
 
-    /// Returns the token price source type and the corresponding API URL.
-    pub fn price_source(&self) -> (TokenPriceSource, String) {
-        let url = match self.token_price_source {
-            TokenPriceSource::CoinGecko => self.coingecko_base_url.clone(),
-            TokenPriceSource::CoinMarketCap => self.coinmarketcap_base_url.clone(),
-        };
-        (self.token_price_source, url)
+    /// Loads the configured fee subsidy rules.
+    pub fn subsidy_rules(&self) -> Vec<SubsidyRule> {
+        if self.subsidy_rules_name.is_empty() {
+            return Vec::new();
+        }
+        let path = format!("./etc/subsidy-rules/{}.json", self.subsidy_rules_name);
+
+        serde_json::from_str(&fs::read_to_string(path).expect("File for subsidy rules not found"))
+            .expect("Invalid subsidy rules format")
+    }
+
+    /// Returns the token price source types and the corresponding API URLs.
+    pub fn price_sources(&self) -> Vec<(TokenPriceSource, String)> {
+        self.token_price_sources
+            .iter()
+            .map(|&source| {
+                let url = match source {
+                    TokenPriceSource::CoinGecko => self.coingecko_base_url.clone(),
+                    TokenPriceSource::CoinMarketCap => self.coinmarketcap_base_url.clone(),
+                    TokenPriceSource::Uniswap => self.uniswap_v3_url.clone(),
+                    // The feeds are read from the Ethereum node, not from an HTTP API.
+                    TokenPriceSource::Chainlink => String::new(),
+                };
+                (source, url)
+            })
+            .collect()
+    }
+
+    pub fn max_price_age(&self) -> Duration {
+        Duration::from_secs(self.max_price_age_seconds)
+    }
+
+    pub fn congestion_update_interval(&self) -> Duration {
+        Duration::from_secs(self.congestion_update_interval_seconds)
+    }
+
+    pub fn gas_price_update_interval(&self) -> Duration {
+        Duration::from_secs(self.gas_price_update_interval_seconds)
+    }
+
+    pub fn price_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.price_cache_ttl_seconds)
+    }
+
+    pub fn price_cache_refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.price_cache_refresh_interval_seconds)
+    }
+
+    pub fn price_cache_active_token_period(&self) -> Duration {
+        Duration::from_secs(self.price_cache_active_token_seconds)
+    }
+
+    /// Returns the configured Chainlink feeds of the tokens.
+    pub fn chainlink_feeds(&self) -> Vec<ChainlinkFeed> {
+        let tokens_count = self.chainlink_tokens.len();
+        assert!(
+            self.chainlink_feeds.len() == tokens_count
+                && self.chainlink_heartbeats.len() == tokens_count
+                && self.chainlink_decimals.len() == tokens_count,
+            "Every Chainlink token must have a feed, a heartbeat and decimals"
+        );
+
+        (0..tokens_count)
+            .map(|idx| ChainlinkFeed {
+                token: self.chainlink_tokens[idx],
+                feed: self.chainlink_feeds[idx],
+                heartbeat: Duration::from_secs(self.chainlink_heartbeats[idx]),
+                decimals: self.chainlink_decimals[idx],
+            })
+            .collect()
+    }
+
+    /// Returns the configured price sanity bounds of the tokens.
+    pub fn price_bounds(&self) -> Vec<TokenPriceBounds> {
+        let tokens_count = self.price_bounds_tokens.len();
+        assert!(
+            self.price_bounds_min_usd_scaled.len() == tokens_count
+                && self.price_bounds_max_usd_scaled.len() == tokens_count
+                && self.price_bounds_max_change_percent.len() == tokens_count,
+            "Every token with the price bounds must have the minimum and maximum prices and the maximum change"
+        );
+
+        (0..tokens_count)
+            .map(|idx| TokenPriceBounds {
+                token: self.price_bounds_tokens[idx],
+                min_usd_price: scaled_u64_to_ratio(self.price_bounds_min_usd_scaled[idx]),
+                max_usd_price: scaled_u64_to_ratio(self.price_bounds_max_usd_scaled[idx]),
+                max_change_percent: self.price_bounds_max_change_percent[idx],
+            })
+            .collect()
     }
 }
 
@@ -68,9 +298,15 @@ mod tests {
 
     fn expected_config() -> TickerConfig {
         TickerConfig {
-            token_price_source: TokenPriceSource::CoinGecko,
+            token_price_sources: vec![TokenPriceSource::CoinGecko, TokenPriceSource::Uniswap],
             coinmarketcap_base_url: "http://127.0.0.1:9876".into(),
             coingecko_base_url: "http://127.0.0.1:9876".into(),
+            uniswap_v3_url: "http://127.0.0.1:9975/graphql".into(),
+            max_price_age_seconds: 3600,
+            chainlink_tokens: vec![addr("0000000000000000000000000000000000000000")],
+            chainlink_feeds: vec![addr("5f4ec3df9cbd43714fe2740f5e3616155c5b8419")],
+            chainlink_heartbeats: vec![3600],
+            chainlink_decimals: vec![8],
             scale_fee_percent: 100,
             fast_processing_coeff: 10.0f64,
             uniswap_url: "http://127.0.0.1:9975/graphql".to_string(),
@@ -80,15 +316,41 @@ mod tests {
             token_market_update_time: 120,
             number_of_ticker_actors: 4,
             subsidy_cpk_price_usd_scaled: 100,
+            subsidy_rules_name: "localhost".into(),
+            congestion_mempool_threshold: 5000,
+            congestion_max_multiplier_percent: 300,
+            congestion_decay_percent: 20,
+            congestion_update_interval_seconds: 10,
+            batch_discount_min_txs: 10,
+            batch_overhead_discount_percent: 50,
+            gas_price_smoothing_percent: 20,
+            gas_price_max_deviation_percent: 25,
+            gas_price_update_interval_seconds: 30,
+            price_cache_ttl_seconds: 10,
+            price_cache_refresh_interval_seconds: 5,
+            price_cache_active_token_seconds: 600,
+            max_price_change_percent: 50,
+            price_anomalies_to_open_breaker: 3,
+            stable_prices_to_close_breaker: 6,
+            price_bounds_tokens: vec![addr("0000000000000000000000000000000000000000")],
+            price_bounds_min_usd_scaled: vec![100_000_000],
+            price_bounds_max_usd_scaled: vec![100_000_000_000],
+            price_bounds_max_change_percent: vec![30],
         }
     }
 
     #[test]
     fn from_env() {
         let config = r#"
-FEE_TICKER_TOKEN_PRICE_SOURCE="CoinGecko"
+FEE_TICKER_TOKEN_PRICE_SOURCES="CoinGecko,Uniswap"
 FEE_TICKER_COINMARKETCAP_BASE_URL="http://127.0.0.1:9876"
 FEE_TICKER_COINGECKO_BASE_URL="http://127.0.0.1:9876"
+FEE_TICKER_UNISWAP_V3_URL=http://127.0.0.1:9975/graphql
+FEE_TICKER_MAX_PRICE_AGE_SECONDS=3600
+FEE_TICKER_CHAINLINK_TOKENS="0x0000000000000000000000000000000000000000"
+FEE_TICKER_CHAINLINK_FEEDS="0x5f4ec3df9cbd43714fe2740f5e3616155c5b8419"
+FEE_TICKER_CHAINLINK_HEARTBEATS="3600"
+FEE_TICKER_CHAINLINK_DECIMALS="8"
 FEE_TICKER_FAST_PROCESSING_COEFF="10"
 FEE_TICKER_UNISWAP_URL=http://127.0.0.1:9975/graphql
 FEE_TICKER_AVAILABLE_LIQUIDITY_SECONDS=1000
@@ -99,6 +361,26 @@ FEE_TICKER_NUMBER_OF_TICKER_ACTORS="4"
 FEE_TICKER_SUBSIDIZED_TOKENS_LIMITS=156
 FEE_TICKER_SCALE_FEE_PERCENT=100
 FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
+FEE_TICKER_SUBSIDY_RULES_NAME=localhost
+FEE_TICKER_CONGESTION_MEMPOOL_THRESHOLD=5000
+FEE_TICKER_CONGESTION_MAX_MULTIPLIER_PERCENT=300
+FEE_TICKER_CONGESTION_DECAY_PERCENT=20
+FEE_TICKER_CONGESTION_UPDATE_INTERVAL_SECONDS=10
+FEE_TICKER_BATCH_DISCOUNT_MIN_TXS=10
+FEE_TICKER_BATCH_OVERHEAD_DISCOUNT_PERCENT=50
+FEE_TICKER_GAS_PRICE_SMOOTHING_PERCENT=20
+FEE_TICKER_GAS_PRICE_MAX_DEVIATION_PERCENT=25
+FEE_TICKER_GAS_PRICE_UPDATE_INTERVAL_SECONDS=30
+FEE_TICKER_PRICE_CACHE_TTL_SECONDS=10
+FEE_TICKER_PRICE_CACHE_REFRESH_INTERVAL_SECONDS=5
+FEE_TICKER_PRICE_CACHE_ACTIVE_TOKEN_SECONDS=600
+FEE_TICKER_MAX_PRICE_CHANGE_PERCENT=50
+FEE_TICKER_PRICE_ANOMALIES_TO_OPEN_BREAKER=3
+FEE_TICKER_STABLE_PRICES_TO_CLOSE_BREAKER=6
+FEE_TICKER_PRICE_BOUNDS_TOKENS="0x0000000000000000000000000000000000000000"
+FEE_TICKER_PRICE_BOUNDS_MIN_USD_SCALED="100000000"
+FEE_TICKER_PRICE_BOUNDS_MAX_USD_SCALED="100000000000"
+FEE_TICKER_PRICE_BOUNDS_MAX_CHANGE_PERCENT="30"
         "#;
         set_env(config);
 
@@ -111,22 +393,73 @@ FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
     fn methods() {
         const COINGECKO_URL: &str = "http://coingecko";
         const COINMARKETCAP_URL: &str = "http://coinmarketcap";
+        const UNISWAP_URL: &str = "http://uniswap";
 
         let mut config = expected_config();
 
         config.coingecko_base_url = COINGECKO_URL.into();
         config.coinmarketcap_base_url = COINMARKETCAP_URL.into();
+        config.uniswap_v3_url = UNISWAP_URL.into();
 
-        config.token_price_source = TokenPriceSource::CoinGecko;
+        config.token_price_sources = vec![
+            TokenPriceSource::CoinMarketCap,
+            TokenPriceSource::CoinGecko,
+            TokenPriceSource::Uniswap,
+        ];
+        assert_eq!(
+            config.price_sources(),
+            vec![
+                (TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL.into()),
+                (TokenPriceSource::CoinGecko, COINGECKO_URL.into()),
+                (TokenPriceSource::Uniswap, UNISWAP_URL.into()),
+            ]
+        );
+        assert_eq!(config.max_price_age(), Duration::from_secs(3600));
+        assert_eq!(config.congestion_update_interval(), Duration::from_secs(10));
+        assert_eq!(config.gas_price_update_interval(), Duration::from_secs(30));
+        assert_eq!(config.price_cache_ttl(), Duration::from_secs(10));
+        assert_eq!(
+            config.chainlink_feeds(),
+            vec![ChainlinkFeed {
+                token: addr("0000000000000000000000000000000000000000"),
+                feed: addr("5f4ec3df9cbd43714fe2740f5e3616155c5b8419"),
+                heartbeat: Duration::from_secs(3600),
+                decimals: 8,
+            }]
+        );
         assert_eq!(
-            config.price_source(),
-            (TokenPriceSource::CoinGecko, COINGECKO_URL.into())
+            config.price_bounds(),
+            vec![TokenPriceBounds {
+                token: addr("0000000000000000000000000000000000000000"),
+                min_usd_price: Ratio::from_integer(BigUint::from(100u32)),
+                max_usd_price: Ratio::from_integer(BigUint::from(100_000u32)),
+                max_change_percent: 30,
+            }]
         );
+    }
 
-        config.token_price_source = TokenPriceSource::CoinMarketCap;
+    #[test]
+    fn subsidy_rule() {
+        let rule: SubsidyRule = serde_json::from_str(
+            r#"{
+                "name": "FreeTransfers",
+                "discount_percent": 100,
+                "max_subsidy_usd_scaled": 1000000000,
+                "tx_types": ["Transfer"],
+                "active_from": 1661000000,
+                "active_until": 1662000000
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(rule.tx_types, vec![SubsidizedTxType::Transfer]);
+        assert!(rule.tokens.is_empty() && rule.addresses.is_empty());
         assert_eq!(
-            config.price_source(),
-            (TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL.into())
+            rule.max_subsidy_usd(),
+            Ratio::from_integer(BigUint::from(1000u32))
         );
+
+        assert!(!rule.is_active(1660999999));
+        assert!(rule.is_active(1661000000));
+        assert!(!rule.is_active(1662000000));
     }
 }
@@ -1,8 +1,8 @@
 pub use crate::configs::{
-    ApiConfig, ChainConfig, ContractsConfig, DBConfig, DevLiquidityTokenWatcherConfig,
-    ETHClientConfig, ETHSenderConfig, ETHWatchConfig, EventListenerConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ProverConfig, TickerConfig,
-    TokenHandlerConfig,
+    ApiConfig, BalanceAuditorConfig, ChainConfig, ContractsConfig, DBConfig, DepositMonitorConfig,
+    DevLiquidityTokenWatcherConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
+    EventListenerConfig, ForcedExitRequestsConfig, GatewayWatcherConfig, LeaderElectionConfig,
+    MiscConfig, ProverConfig, TickerConfig, TokenHandlerConfig,
 };
 
 pub mod configs;
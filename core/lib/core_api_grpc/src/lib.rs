@@ -0,0 +1,154 @@
+//! gRPC definitions of the private API of the core server.
+//!
+//! The service mirrors the JSON-over-HTTP private API, which is still served by the core
+//! for compatibility, see `PrivateApiTransport` in the config. The operations and transactions
+//! are passed as JSON-serialized bytes, the same way they're passed over HTTP, so both
+//! transports share the encoding defined in `zksync_types`.
+
+use zksync_types::{tx::error::TxAddError, Address, BlockNumber, PriorityOp, SignedZkSyncTx, H256};
+
+pub mod proto {
+    tonic::include_proto!("zksync.core_api");
+}
+
+/// Decoding error of the message, either a malformed field or a malformed JSON payload.
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error("Invalid length of the `{0}` field")]
+    InvalidLength(&'static str),
+    #[error("Invalid JSON payload: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl From<zksync_api_types::CoreStatus> for proto::CoreStatus {
+    fn from(status: zksync_api_types::CoreStatus) -> Self {
+        Self {
+            main_database_available: status.main_database_available,
+            replica_database_available: status.replica_database_available,
+            web3_available: status.web3_available,
+        }
+    }
+}
+
+impl From<proto::CoreStatus> for zksync_api_types::CoreStatus {
+    fn from(status: proto::CoreStatus) -> Self {
+        Self {
+            main_database_available: status.main_database_available,
+            replica_database_available: status.replica_database_available,
+            web3_available: status.web3_available,
+        }
+    }
+}
+
+impl proto::GetUnconfirmedDepositsRequest {
+    pub fn new(address: Address) -> Self {
+        Self {
+            address: address.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn address(&self) -> Result<Address, DecodeError> {
+        if self.address.len() != Address::len_bytes() {
+            return Err(DecodeError::InvalidLength("address"));
+        }
+        Ok(Address::from_slice(&self.address))
+    }
+}
+
+impl proto::GetUnconfirmedOpRequest {
+    pub fn new(tx_hash: H256) -> Self {
+        Self {
+            tx_hash: tx_hash.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn tx_hash(&self) -> Result<H256, DecodeError> {
+        if self.tx_hash.len() != H256::len_bytes() {
+            return Err(DecodeError::InvalidLength("tx_hash"));
+        }
+        Ok(H256::from_slice(&self.tx_hash))
+    }
+}
+
+impl proto::PriorityOps {
+    pub fn new<'a>(ops: impl IntoIterator<Item = &'a PriorityOp>) -> Result<Self, DecodeError> {
+        let ops = ops
+            .into_iter()
+            .map(serde_json::to_vec)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { ops })
+    }
+
+    pub fn decode_ops(&self) -> Result<Vec<PriorityOp>, DecodeError> {
+        self.ops
+            .iter()
+            .map(|op| Ok(serde_json::from_slice(op)?))
+            .collect()
+    }
+}
+
+impl proto::SubmitTxRequest {
+    pub fn new(
+        tx: &SignedZkSyncTx,
+        not_before_block: Option<BlockNumber>,
+    ) -> Result<Self, DecodeError> {
+        Ok(Self {
+            tx: serde_json::to_vec(tx)?,
+            not_before_block: not_before_block.map(|block| *block).unwrap_or_default(),
+        })
+    }
+
+    pub fn decode_tx(&self) -> Result<(SignedZkSyncTx, Option<BlockNumber>), DecodeError> {
+        let tx = serde_json::from_slice(&self.tx)?;
+        // The blocks are numbered from 1, so 0 is never a valid block to wait for.
+        let not_before_block = Some(self.not_before_block)
+            .filter(|&block| block != 0)
+            .map(BlockNumber);
+        Ok((tx, not_before_block))
+    }
+}
+
+impl proto::SubmitTxResponse {
+    pub fn new(result: Result<(), TxAddError>) -> Result<Self, DecodeError> {
+        let error = match result {
+            Ok(()) => Vec::new(),
+            Err(err) => serde_json::to_vec(&err)?,
+        };
+        Ok(Self { error })
+    }
+
+    pub fn decode_result(&self) -> Result<Result<(), TxAddError>, DecodeError> {
+        if self.error.is_empty() {
+            return Ok(Ok(()));
+        }
+        Ok(Err(serde_json::from_slice(&self.error)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submit_tx_response_roundtrip() {
+        for result in [Ok(()), Err(TxAddError::NonceMismatch)] {
+            let response = proto::SubmitTxResponse::new(result).unwrap();
+            let decoded = response.decode_result().unwrap();
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", result));
+        }
+    }
+
+    #[test]
+    fn invalid_address_length() {
+        let request = proto::GetUnconfirmedDepositsRequest::new(Address::repeat_byte(1));
+        assert_eq!(request.address().unwrap(), Address::repeat_byte(1));
+
+        let request = proto::GetUnconfirmedDepositsRequest {
+            address: vec![1; 32],
+        };
+        assert!(matches!(
+            request.address(),
+            Err(DecodeError::InvalidLength("address"))
+        ));
+    }
+}
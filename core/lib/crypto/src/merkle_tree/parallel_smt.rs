@@ -432,17 +432,22 @@ where
     /// Returns the Merkle root hash of the tree. This operation can cost up to O(N*logN):
     /// the root hash is calculated in this method, and it will build the whole hash tree
     /// if this method was not called. The intermediate calculation results are caches though,
-    /// thus follow-up invocations will cost less.
+    /// thus follow-up invocations will cost less: every insert only invalidates the cached hashes
+    /// on the path from the leaf to the root, so only the invalidated ("dirty") paths are rehashed.
     pub fn root_hash(&self) -> Hash {
+        let root_index = self.nodes[Self::ROOT_ITEM_IDX].index;
+        if let Some(root_hash) = self.cache.read().expect("Read lock").get(&root_index) {
+            // Nothing was inserted since the last calculation.
+            return root_hash.clone();
+        }
+
         let (root_hash, intermediate_hashes) = self.get_hash(Self::ROOT_ITEM_IDX);
 
-        // Store all the intermediate hashes in the cache.
-        for (item_idx, hash) in intermediate_hashes {
-            self.cache
-                .write()
-                .expect("write lock")
-                .insert(item_idx, hash);
-        }
+        // Store all the intermediate hashes in the cache at once.
+        self.cache
+            .write()
+            .expect("write lock")
+            .extend(intermediate_hashes);
         root_hash
     }
 
@@ -580,13 +585,19 @@ where
         }
     }
 
-    /// Finds the hash of the node's child, using one of the following strategy:
+    /// Finds the hash of the node's child without calculating it, using one of the following
+    /// strategy:
     /// - If the hash exists in cache, the cached value is returned;
     /// - If the element with the child's index absents in the tree, the precomputed hash
     ///   for the corresponding layer is returned.
-    /// - Otherwise, the hash for the child is actually calculated using `calculate_child_hash`
-    ///   method.
-    fn get_child_hash(&self, parent: &Node, dir: NodeDirection) -> (Hash, Vec<(NodeIndex, Hash)>) {
+    /// - Otherwise, the child is "dirty" and `None` is returned, so the hash for the child
+    ///   should be actually calculated using `calculate_child_hash` method.
+    fn clean_child_hash(
+        &self,
+        cache_lock: &RwLockReadGuard<FnvHashMap<NodeIndex, Hash>>,
+        parent: &Node,
+        dir: NodeDirection,
+    ) -> Option<Hash> {
         let child_ref = match dir {
             NodeDirection::Left => parent.left,
             NodeDirection::Right => parent.right,
@@ -594,27 +605,30 @@ where
 
         let child_index = dir.child_index(parent.index);
 
-        // Check if the child data exists in the cache.
-        if let Some(cached) = self.cache.read().expect("Read lock").get(&child_index) {
+        if let Some(cached) = cache_lock.get(&child_index) {
             // Cache hit, no calculations required.
-            let updates = vec![];
-
-            (cached.clone(), updates)
+            Some(cached.clone())
+        } else if child_ref.is_none() {
+            Some(self.prehashed[parent.depth + 1].clone())
         } else {
-            match child_ref {
-                Some(child_ref) => {
-                    // Child exists in the tree, we must calculate the underlying hashes.
-                    self.calculate_child_hash(child_ref, parent)
-                }
-                None => {
-                    let default_hash_for_layer = self.prehashed[parent.depth + 1].clone();
-                    let updates = vec![];
-                    (default_hash_for_layer, updates)
-                }
-            }
+            None
         }
     }
 
+    /// Calculates the hash of the "dirty" node's child, i.e. one existing in the tree
+    /// but absent in the cache.
+    fn calculate_dirty_child_hash(
+        &self,
+        parent: &Node,
+        dir: NodeDirection,
+    ) -> (Hash, Vec<(NodeIndex, Hash)>) {
+        let child_ref = match dir {
+            NodeDirection::Left => parent.left,
+            NodeDirection::Right => parent.right,
+        };
+        self.calculate_child_hash(child_ref.expect("Dirty child must exist"), parent)
+    }
+
     /// Calculates the hash of the node's child given the parent node and the child direction.
     fn calculate_child_hash(
         &self,
@@ -657,7 +671,7 @@ where
     /// Returns the calculates hash and the list of updated underlying
     /// hashes together with their positions.
     fn get_hash(&self, node_ref: NodeRef) -> (Hash, Vec<(NodeIndex, Hash)>) {
-        let node = &self.nodes[node_ref].clone();
+        let node = &self.nodes[node_ref];
 
         // Calculate the hash of this node, and collect the underlying updates.
         // The updates list won't contain the current node, we will add it below.
@@ -675,12 +689,32 @@ where
                 (item_hash, updates)
             } else {
                 // Not a leaf node: recursively calculate the hashes up to this node.
+                // Only the "dirty" children have to be recalculated, the clean ones are taken
+                // as is.
+                let (lhs_clean, rhs_clean) = {
+                    let cache_lock = self.cache.read().expect("Read lock");
+                    (
+                        self.clean_child_hash(&cache_lock, node, NodeDirection::Left),
+                        self.clean_child_hash(&cache_lock, node, NodeDirection::Right),
+                    )
+                };
 
-                // Use `rayon` to calculate hashes in parallel.
-                let (left_hashes, right_hashes) = rayon::join(
-                    || self.get_child_hash(node, NodeDirection::Left),
-                    || self.get_child_hash(node, NodeDirection::Right),
-                );
+                let (left_hashes, right_hashes) = match (lhs_clean, rhs_clean) {
+                    (Some(lhs_hash), Some(rhs_hash)) => ((lhs_hash, vec![]), (rhs_hash, vec![])),
+                    (Some(lhs_hash), None) => (
+                        (lhs_hash, vec![]),
+                        self.calculate_dirty_child_hash(node, NodeDirection::Right),
+                    ),
+                    (None, Some(rhs_hash)) => (
+                        self.calculate_dirty_child_hash(node, NodeDirection::Left),
+                        (rhs_hash, vec![]),
+                    ),
+                    // Use `rayon` to calculate hashes in parallel if both subtrees are dirty.
+                    (None, None) => rayon::join(
+                        || self.calculate_dirty_child_hash(node, NodeDirection::Left),
+                        || self.calculate_dirty_child_hash(node, NodeDirection::Right),
+                    ),
+                };
 
                 let (lhs_hash, lhs_updates) = left_hashes;
                 let (rhs_hash, rhs_updates) = right_hashes;
@@ -862,6 +896,38 @@ mod tests {
         assert_eq!(tree.root_hash(), 697_516_875);
     }
 
+    /// Checks that the root hash recalculated after the updates of the cached tree
+    /// is the same as the root hash of the tree built from scratch.
+    #[test]
+    fn incremental_root_hash() {
+        let mut tree = TestSMT::new(8);
+        for idx in 0..100 {
+            tree.insert(idx, TestLeaf(idx as u64));
+        }
+        tree.root_hash();
+
+        let mut values: Vec<_> = (0..100).collect();
+        for round in 1..10u64 {
+            // Update a few of the existing elements, add a new one, and remove one.
+            for idx in (0..100).step_by(7 * round as usize) {
+                values[idx] = idx as u64 * round;
+                tree.insert(idx as u32, TestLeaf(values[idx]));
+            }
+            values.push(round);
+            tree.insert(values.len() as u32 - 1, TestLeaf(round));
+            values[round as usize] = 0;
+            tree.remove(round as u32);
+
+            let mut fresh_tree = TestSMT::new(8);
+            for (idx, &value) in values.iter().enumerate() {
+                fresh_tree.insert(idx as u32, TestLeaf(value));
+            }
+            assert_eq!(tree.root_hash(), fresh_tree.root_hash());
+            // The root hash is served from the cache until the next update.
+            assert_eq!(tree.root_hash(), fresh_tree.root_hash());
+        }
+    }
+
     /// Checks the correctness of the built Merkle proofs
     #[test]
     fn merkle_path_test() {
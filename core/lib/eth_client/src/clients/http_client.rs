@@ -13,7 +13,7 @@ use web3::{
     },
     transports::Http,
     types::{
-        Address, BlockId, BlockNumber, Bytes, Filter, Log, Transaction, TransactionId,
+        Address, BlockId, BlockNumber, Bytes, FeeHistory, Filter, Log, Transaction, TransactionId,
         TransactionReceipt, H160, H256, U256, U64,
     },
     Web3,
@@ -22,12 +22,15 @@ use web3::{
 // Workspace uses
 use zksync_eth_signer::{raw_ethereum_tx::RawTransaction, EthereumSigner};
 
-use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, RevertReason, SignedCallResult};
+use crate::revert_reason::decode_revert_reason;
 /// Gas limit value to be used in transaction if for some reason
 /// gas limit was not set for it.
 ///
 /// This is an emergency value, which will not be used normally.
 const FALLBACK_GAS_LIMIT: u64 = 3_000_000;
+/// Type of the fee market transactions introduced by EIP-1559.
+const EIP1559_TX_TYPE: u64 = 2;
 
 struct ETHDirectClientInner<S: EthereumSigner> {
     eth_signer: S,
@@ -153,6 +156,26 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(network_gas_price)
     }
 
+    /// Loads the base fees and the priority fee percentiles of the recent blocks.
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        newest_block: BlockNumber,
+        reward_percentiles: Vec<f64>,
+    ) -> Result<FeeHistory, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let history = self
+            .inner
+            .web3
+            .eth()
+            .fee_history(block_count.into(), newest_block, Some(reward_percentiles))
+            .await?;
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.fee_history", start.elapsed());
+        Ok(history)
+    }
+
     pub async fn sign_prepared_tx(
         &self,
         data: Vec<u8>,
@@ -171,11 +194,24 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         #[cfg(feature = "with-metrics")]
         let start = Instant::now();
 
-        // fetch current gas_price
-        let gas_price = match options.gas_price {
-            Some(gas_price) => gas_price,
-            None => self.get_gas_price().await?,
-        };
+        // The fee market (EIP-1559) transaction is sent once its fees are provided,
+        // the legacy one otherwise. The max fee per gas takes the place of the gas price then.
+        let (transaction_type, gas_price, max_priority_fee_per_gas) =
+            match (options.max_fee_per_gas, options.max_priority_fee_per_gas) {
+                (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => (
+                    Some(U64::from(EIP1559_TX_TYPE)),
+                    max_fee_per_gas,
+                    Some(max_priority_fee_per_gas),
+                ),
+                _ => {
+                    // fetch current gas_price
+                    let gas_price = match options.gas_price {
+                        Some(gas_price) => gas_price,
+                        None => self.get_gas_price().await?,
+                    };
+                    (None, gas_price, None)
+                }
+            };
 
         let nonce = match options.nonce {
             Some(nonce) => nonce,
@@ -200,16 +236,16 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         // form and sign tx
         let tx = RawTransaction {
             chain_id: self.inner.chain_id,
-            transaction_type: None,
+            transaction_type,
             access_list: None,
-            max_fee_per_gas: Default::default(),
+            max_fee_per_gas: max_priority_fee_per_gas.map(|_| gas_price),
             nonce,
             to: Some(contract_addr),
             value: options.value.unwrap_or_default(),
             gas_price,
             gas,
             data,
-            max_priority_fee_per_gas: Default::default(),
+            max_priority_fee_per_gas,
         };
 
         let signed_tx = self.inner.eth_signer.sign_transaction(tx).await?;
@@ -228,6 +264,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(SignedCallResult {
             raw_tx: signed_tx,
             gas_price,
+            max_priority_fee_per_gas,
             nonce,
             hash,
         })
@@ -333,6 +370,53 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         }
     }
 
+    pub async fn simulate_tx(&self, data: Vec<u8>) -> Result<Option<RevertReason>, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let call_request = web3::types::CallRequest {
+            from: Some(self.inner.sender_account),
+            to: Some(self.inner.contract_addr),
+            gas: None,
+            gas_price: None,
+            value: None,
+            data: Some(data.into()),
+            transaction_type: None,
+            access_list: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+
+        let result = self
+            .inner
+            .web3
+            .eth()
+            .call(call_request, Some(BlockId::Number(BlockNumber::Pending)))
+            .await;
+        let revert_reason = match result {
+            Ok(_) => None,
+            // Nodes report the reverted call as an error, with the revert data if they support it.
+            Err(web3::Error::Rpc(err)) if err.message.contains("revert") => {
+                let data = err
+                    .data
+                    .as_ref()
+                    .and_then(|data| data.as_str())
+                    .and_then(|data| hex::decode(data.trim_start_matches("0x")).ok())
+                    .unwrap_or_default();
+                let reason = if data.is_empty() {
+                    err.message
+                } else {
+                    decode_revert_reason(&data, &self.inner.contract)
+                };
+                Some(RevertReason { data, reason })
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.simulate_tx", start.elapsed());
+        Ok(revert_reason)
+    }
+
     pub async fn eth_balance(&self, address: Address) -> Result<U256, anyhow::Error> {
         #[cfg(feature = "with-metrics")]
         let start = Instant::now();
@@ -342,6 +426,15 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(balance)
     }
 
+    pub async fn get_code(&self, address: Address) -> Result<Bytes, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let code = self.inner.web3.eth().code(address, None).await?;
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.get_code", start.elapsed());
+        Ok(code)
+    }
+
     pub async fn sender_eth_balance(&self) -> Result<U256, anyhow::Error> {
         self.eth_balance(self.inner.sender_account).await
     }
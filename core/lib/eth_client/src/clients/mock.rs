@@ -7,12 +7,12 @@ use tokio::sync::RwLock;
 use web3::contract::tokens::{Detokenize, Tokenize};
 use web3::contract::Options;
 use web3::transports::Http;
-use web3::types::{BlockId, Filter, Log, Transaction, U64};
+use web3::types::{Block, BlockId, BlockNumber, Bytes, FeeHistory, Filter, Log, Transaction, U64};
 
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
 use crate::{
-    ethereum_gateway::{ExecutedTxStatus, FailureInfo},
+    ethereum_gateway::{ExecutedTxStatus, FailureInfo, RevertReason},
     SignedCallResult,
 };
 
@@ -20,6 +20,9 @@ use crate::{
 struct MockEthereumInner {
     block_number: u64,
     gas_price: U256,
+    nonce: u64,
+    balance: U256,
+    revert_reason: Option<RevertReason>,
     tx_statuses: Arc<RwLock<HashMap<H256, ExecutedTxStatus>>>,
     sent_txs: Arc<RwLock<HashSet<Vec<u8>>>>,
 }
@@ -35,6 +38,9 @@ impl Default for MockEthereumInner {
         Self {
             block_number: 1,
             gas_price: 100.into(),
+            nonce: 0,
+            balance: U256::zero(),
+            revert_reason: None,
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
         }
@@ -124,6 +130,37 @@ impl MockEthereum {
         Ok(self.inner.gas_price)
     }
 
+    /// Sets the nonce of the mock account, it's not changed by the sent transactions.
+    pub fn set_nonce(&mut self, nonce: u64) {
+        Arc::get_mut(&mut self.inner).unwrap().nonce = nonce;
+    }
+
+    /// Sets the balance of the mock account, it's not changed by the sent transactions.
+    pub fn set_balance(&mut self, balance: U256) {
+        Arc::get_mut(&mut self.inner).unwrap().balance = balance;
+    }
+
+    /// Fee history with the current gas price as the base fee of every block
+    /// and the tenth of it as every priority fee percentile.
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        newest_block: BlockNumber,
+        reward_percentiles: Vec<f64>,
+    ) -> anyhow::Result<FeeHistory> {
+        let base_fee = self.inner.gas_price;
+        let priority_fee = base_fee / 10;
+        Ok(FeeHistory {
+            oldest_block: newest_block,
+            base_fee_per_gas: vec![base_fee; block_count as usize + 1],
+            gas_used_ratio: vec![0.5; block_count as usize],
+            reward: Some(vec![
+                vec![priority_fee; reward_percentiles.len()];
+                block_count as usize
+            ]),
+        })
+    }
+
     pub async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, anyhow::Error> {
         // Cut hash of transaction
         let mut hash: [u8; 32] = Default::default();
@@ -137,7 +174,13 @@ impl MockEthereum {
         raw_tx: Vec<u8>,
         options: Options,
     ) -> anyhow::Result<SignedCallResult> {
-        let gas_price = options.gas_price.unwrap_or(self.inner.gas_price);
+        let max_priority_fee_per_gas = options
+            .max_fee_per_gas
+            .and(options.max_priority_fee_per_gas);
+        let gas_price = options
+            .max_fee_per_gas
+            .or(options.gas_price)
+            .unwrap_or(self.inner.gas_price);
         let nonce = options.nonce.expect("Nonce must be set for every tx");
 
         // Nonce and gas_price are appended to distinguish the same transactions
@@ -152,6 +195,7 @@ impl MockEthereum {
         Ok(SignedCallResult {
             raw_tx: new_raw_tx,
             gas_price,
+            max_priority_fee_per_gas,
             nonce,
             hash,
         })
@@ -164,16 +208,26 @@ impl MockEthereum {
         Ok(None)
     }
 
+    /// Sets the reason the simulated transactions revert for, `None` if they succeed.
+    pub fn set_revert_reason(&mut self, revert_reason: Option<RevertReason>) {
+        Arc::get_mut(&mut self.inner).unwrap().revert_reason = revert_reason;
+    }
+
+    pub async fn simulate_tx(&self, _data: Vec<u8>) -> Result<Option<RevertReason>, Error> {
+        Ok(self.inner.revert_reason.clone())
+    }
+
+    /// Mock account has no mempool, so the pending nonce is the same as the latest one.
     pub async fn pending_nonce(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.inner.nonce.into())
     }
 
     pub async fn current_nonce(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.inner.nonce.into())
     }
 
     pub async fn sender_eth_balance(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.inner.balance)
     }
 
     pub async fn sign_prepared_tx_for_addr(
@@ -193,6 +247,11 @@ impl MockEthereum {
         unreachable!()
     }
 
+    /// There are no contracts deployed in the mock, so all the accounts are externally owned.
+    pub async fn get_code(&self, _address: Address) -> Result<Bytes, Error> {
+        Ok(Bytes::default())
+    }
+
     pub async fn contract_balance(
         &self,
         _token_address: Address,
@@ -269,4 +328,9 @@ impl MockEthereum {
     pub async fn get_tx(&self, _hash: H256) -> Result<Option<Transaction>, anyhow::Error> {
         unreachable!()
     }
+
+    /// The mock doesn't keep the blocks.
+    pub async fn block(&self, _id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        Ok(None)
+    }
 }
@@ -5,12 +5,14 @@ use web3::{
     contract::tokens::{Detokenize, Tokenize},
     contract::Options,
     transports::Http,
-    types::{Address, BlockId, Filter, Log, Transaction, U64},
+    types::{
+        Address, Block, BlockId, BlockNumber, Bytes, FeeHistory, Filter, Log, Transaction, U64,
+    },
 };
 use zksync_eth_signer::PrivateKeySigner;
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
-use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, RevertReason, SignedCallResult};
 use crate::ETHDirectClient;
 
 #[derive(Debug, Default)]
@@ -24,11 +26,21 @@ pub struct MultiplexerEthereumClient {
     inner: Arc<MultiplexerEthereumClientInner>,
 }
 
+/// Calls the clients one by one, starting from the preferred one. Once the preferred client fails
+/// and another one succeeds, the latter becomes preferred, so the following calls don't wait
+/// for the failing client.
 macro_rules! multiple_call {
     ($self:expr, $func:ident($($attr:expr),*)) => {
-        for (name, client) in $self.clients() {
+        for (idx, (name, client)) in $self.clients().enumerate() {
             match client.$func($($attr.clone()),*).await {
-                Ok(res) => return Ok(res),
+                Ok(res) => {
+                    if idx > 0 && $self.prioritize_client(name) {
+                        vlog::warn!("Ethereum Gateway failed over to `{}`", name);
+                        #[cfg(feature = "with-metrics")]
+                        metrics::increment_counter!("eth_client.multiplexed.failover");
+                    }
+                    return Ok(res);
+                }
                 Err(err) => vlog::error!("Error in interface: {}, {} ", name, err),
             }
         }
@@ -55,12 +67,30 @@ impl MultiplexerEthereumClient {
 
     pub fn prioritize_client(&self, name: &str) -> bool {
         if let Some(idx) = self.inner.clients.iter().position(|(key, _)| key == name) {
-            self.inner.preferred.swap(idx, Ordering::Acquire) != idx
+            let changed = self.inner.preferred.swap(idx, Ordering::Acquire) != idx;
+            if changed {
+                self.report_active_client();
+            }
+            changed
         } else {
             false
         }
     }
 
+    /// Reports the currently preferred client to the metrics: its gauge is `1`, the gauges
+    /// of the rest of the clients are `0`.
+    #[cfg(feature = "with-metrics")]
+    pub fn report_active_client(&self) {
+        let preferred = self.inner.preferred.load(Ordering::Relaxed);
+        for (idx, (name, _)) in self.inner.clients.iter().enumerate() {
+            let active = if idx == preferred { 1.0 } else { 0.0 };
+            metrics::gauge!("eth_client.multiplexed.active", active, "address" => name.clone());
+        }
+    }
+
+    #[cfg(not(feature = "with-metrics"))]
+    pub fn report_active_client(&self) {}
+
     pub fn clients(&self) -> impl Iterator<Item = (&str, &ETHDirectClient<PrivateKeySigner>)> {
         let preferred = self.inner.preferred.load(Ordering::Relaxed);
         self.inner
@@ -106,6 +136,18 @@ impl MultiplexerEthereumClient {
         multiple_call!(self, get_gas_price());
     }
 
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        newest_block: BlockNumber,
+        reward_percentiles: Vec<f64>,
+    ) -> Result<FeeHistory, anyhow::Error> {
+        multiple_call!(
+            self,
+            fee_history(block_count, newest_block, reward_percentiles)
+        );
+    }
+
     pub async fn sender_eth_balance(&self) -> Result<U256, anyhow::Error> {
         multiple_call!(self, sender_eth_balance());
     }
@@ -148,10 +190,18 @@ impl MultiplexerEthereumClient {
         multiple_call!(self, failure_reason(tx_hash));
     }
 
+    pub async fn simulate_tx(&self, data: Vec<u8>) -> Result<Option<RevertReason>, anyhow::Error> {
+        multiple_call!(self, simulate_tx(data));
+    }
+
     pub async fn eth_balance(&self, address: Address) -> Result<U256, anyhow::Error> {
         multiple_call!(self, eth_balance(address));
     }
 
+    pub async fn get_code(&self, address: Address) -> Result<Bytes, anyhow::Error> {
+        multiple_call!(self, get_code(address));
+    }
+
     pub async fn allowance(
         &self,
         token_address: Address,
@@ -226,4 +276,8 @@ impl MultiplexerEthereumClient {
     pub async fn get_tx(&self, hash: H256) -> Result<Option<Transaction>, anyhow::Error> {
         multiple_call!(self, get_tx(hash));
     }
+
+    pub async fn block(&self, id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        multiple_call!(self, block(id));
+    }
 }
@@ -1,7 +1,9 @@
 use web3::contract::tokens::{Detokenize, Tokenize};
 use web3::contract::{Contract, Options};
 use web3::transports::Http;
-use web3::types::{Address, BlockId, Filter, Log, Transaction, U64};
+use web3::types::{
+    Address, Block, BlockId, BlockNumber, Bytes, FeeHistory, Filter, Log, Transaction, U64,
+};
 
 use std::fmt::Debug;
 use zksync_config::{ETHClientConfig, ETHSenderConfig};
@@ -16,7 +18,10 @@ use crate::ETHDirectClient;
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignedCallResult {
     pub raw_tx: Vec<u8>,
+    /// Gas price of the legacy transaction or the max fee per gas of the EIP-1559 one.
     pub gas_price: U256,
+    /// Max priority fee per gas, `None` for the legacy transaction.
+    pub max_priority_fee_per_gas: Option<U256>,
     pub nonce: U256,
     pub hash: H256,
 }
@@ -41,6 +46,15 @@ pub struct FailureInfo {
     pub gas_limit: U256,
 }
 
+/// Reason the simulated transaction would revert for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevertReason {
+    /// Revert data returned by the node, empty if the node doesn't provide it.
+    pub data: Vec<u8>,
+    /// Decoded revert reason, or the error message of the node if there is no revert data.
+    pub reason: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum EthereumGateway {
     Direct(ETHDirectClient<PrivateKeySigner>),
@@ -53,6 +67,22 @@ impl EthereumGateway {
         eth_client_config: &ETHClientConfig,
         eth_sender_config: &ETHSenderConfig,
         main_contract: Address,
+    ) -> Self {
+        Self::with_operator_key(
+            eth_client_config,
+            eth_sender_config.sender.operator_commit_eth_addr,
+            eth_sender_config.sender.operator_private_key,
+            main_contract,
+        )
+    }
+
+    /// Creates the gateway signing the transactions with the given operator key,
+    /// e.g. with one of the additional operator keys of `eth_sender`.
+    pub fn with_operator_key(
+        eth_client_config: &ETHClientConfig,
+        operator_eth_addr: Address,
+        operator_private_key: H256,
+        main_contract: Address,
     ) -> Self {
         if eth_client_config.web3_url.len() == 1 {
             let transport = web3::transports::Http::new(&eth_client_config.web3_url()).unwrap();
@@ -60,8 +90,8 @@ impl EthereumGateway {
             EthereumGateway::Direct(ETHDirectClient::new(
                 transport,
                 zksync_contract(),
-                eth_sender_config.sender.operator_commit_eth_addr,
-                PrivateKeySigner::new(eth_sender_config.sender.operator_private_key),
+                operator_eth_addr,
+                PrivateKeySigner::new(operator_private_key),
                 main_contract,
                 eth_client_config.chain_id,
                 eth_client_config.gas_price_factor,
@@ -77,14 +107,15 @@ impl EthereumGateway {
                     ETHDirectClient::new(
                         transport,
                         contract.clone(),
-                        eth_sender_config.sender.operator_commit_eth_addr,
-                        PrivateKeySigner::new(eth_sender_config.sender.operator_private_key),
+                        operator_eth_addr,
+                        PrivateKeySigner::new(operator_private_key),
                         main_contract,
                         eth_client_config.chain_id,
                         eth_client_config.gas_price_factor,
                     ),
                 );
             }
+            client.report_active_client();
             EthereumGateway::Multiplexed(client)
         }
     }
@@ -126,6 +157,18 @@ impl EthereumGateway {
     pub async fn get_gas_price(&self) -> Result<U256, anyhow::Error> {
         delegate_call!(self.get_gas_price())
     }
+
+    /// Returns the base fees of the `block_count` blocks up to `newest_block` and the
+    /// requested percentiles of the priority fees paid in them.
+    pub async fn fee_history(
+        &self,
+        block_count: u64,
+        newest_block: BlockNumber,
+        reward_percentiles: Vec<f64>,
+    ) -> Result<FeeHistory, anyhow::Error> {
+        delegate_call!(self.fee_history(block_count, newest_block, reward_percentiles))
+    }
+
     /// Returns the account balance.
     pub async fn sender_eth_balance(&self) -> Result<U256, anyhow::Error> {
         delegate_call!(self.sender_eth_balance())
@@ -173,11 +216,22 @@ impl EthereumGateway {
         delegate_call!(self.failure_reason(tx_hash))
     }
 
+    /// Simulates the transaction calling the main contract with `data` from the operator account
+    /// against the pending state. Returns the revert reason if the transaction would revert.
+    pub async fn simulate_tx(&self, data: Vec<u8>) -> Result<Option<RevertReason>, anyhow::Error> {
+        delegate_call!(self.simulate_tx(data))
+    }
+
     /// Auxiliary function that returns the balance of the account on Ethereum.
     pub async fn eth_balance(&self, address: Address) -> Result<U256, anyhow::Error> {
         delegate_call!(self.eth_balance(address))
     }
 
+    /// Returns the code deployed at the address, which is empty for the externally owned accounts.
+    pub async fn get_code(&self, address: Address) -> Result<Bytes, anyhow::Error> {
+        delegate_call!(self.get_code(address))
+    }
+
     pub async fn allowance(
         &self,
         token_address: Address,
@@ -265,6 +319,10 @@ impl EthereumGateway {
         delegate_call!(self.get_tx(hash))
     }
 
+    pub async fn block(&self, id: BlockId) -> anyhow::Result<Option<Block<H256>>> {
+        delegate_call!(self.block(id))
+    }
+
     pub fn is_multiplexed(&self) -> bool {
         matches!(self, EthereumGateway::Multiplexed(_))
     }
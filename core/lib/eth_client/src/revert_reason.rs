@@ -0,0 +1,48 @@
+//! Decoding of the revert data returned by the reverted contract calls.
+
+use ethabi::{ParamType, Token};
+
+/// Selector of the `Error(string)` error emitted by `require` and `revert` with a message.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the `Panic(uint256)` error emitted by the failed assertions and arithmetic checks.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decodes the revert data into the human-readable reason. Besides the built-in errors,
+/// the custom errors declared in the `contract` ABI are recognized.
+pub fn decode_revert_reason(data: &[u8], contract: &ethabi::Contract) -> String {
+    if data.len() < 4 {
+        return "unknown".to_string();
+    }
+    let (selector, payload) = data.split_at(4);
+
+    if selector == ERROR_SELECTOR {
+        if let Ok(tokens) = ethabi::decode(&[ParamType::String], payload) {
+            if let Some(Token::String(message)) = tokens.into_iter().next() {
+                return message;
+            }
+        }
+    } else if selector == PANIC_SELECTOR {
+        if let Ok(tokens) = ethabi::decode(&[ParamType::Uint(256)], payload) {
+            if let Some(Token::Uint(code)) = tokens.into_iter().next() {
+                return format!("Panic({:#x})", code);
+            }
+        }
+    } else {
+        for error in contract.errors.values().flatten() {
+            let param_types: Vec<_> = error
+                .inputs
+                .iter()
+                .map(|param| param.kind.clone())
+                .collect();
+            if ethabi::short_signature(&error.name, &param_types) != selector {
+                continue;
+            }
+            if let Ok(tokens) = ethabi::decode(&param_types, payload) {
+                let args: Vec<_> = tokens.iter().map(ToString::to_string).collect();
+                return format!("{}({})", error.name, args.join(", "));
+            }
+        }
+    }
+
+    format!("unknown error 0x{}", hex::encode(data))
+}
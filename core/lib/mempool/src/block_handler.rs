@@ -7,7 +7,11 @@ use zksync_types::{
     PriorityOp,
 };
 
-use crate::state::MempoolState;
+use crate::{
+    mempool_transactions_queue::{MempoolTransactionsQueue, PriorityOpQuotas},
+    simulation::{BlockSimulator, StateView},
+    state::MempoolState,
+};
 
 #[derive(Clone, Debug, Default)]
 pub struct ProposedBlock {
@@ -47,9 +51,73 @@ pub(crate) struct MempoolBlocksHandler {
     pub mempool_state: MempoolState,
     pub requests: mpsc::Receiver<MempoolBlocksRequest>,
     pub max_block_size_chunks: usize,
+    pub priority_op_quotas: PriorityOpQuotas,
+    /// Number of the Ethereum blocks before the deadline of a priority operation,
+    /// since which it's included regardless of the quotas.
+    pub priority_op_deadline_margin: u64,
+    /// Simulator of the proposed blocks, `None` if the simulation is disabled.
+    pub simulator: Option<BlockSimulator>,
 }
 
 impl MempoolBlocksHandler {
+    /// Swaps out the transactions that fail the simulation for the next transactions from the queue,
+    /// until the block is filled with the ones that pass it.
+    async fn simulate_block(
+        &mut self,
+        tx_queue: &mut MempoolTransactionsQueue,
+        priority_ops: &[PriorityOp],
+        mut txs: Vec<SignedTxVariant>,
+        mut chunks_left: usize,
+        block_timestamp: u64,
+    ) -> Result<(Vec<SignedTxVariant>, usize), TxAddError> {
+        let simulator = match &mut self.simulator {
+            Some(simulator) => simulator,
+            None => return Ok((txs, chunks_left)),
+        };
+        let mut storage = self.mempool_state.access_storage().await?;
+
+        let mut view = StateView::default();
+        view.load(&mut storage, priority_ops, &txs)
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        view.apply_priority_ops(priority_ops);
+
+        let mut accepted = Vec::with_capacity(txs.len());
+        let mut rejected = Vec::new();
+        while !txs.is_empty() {
+            let (passed, failed) = simulator.simulate(&mut view, txs, block_timestamp);
+            accepted.extend(passed);
+            if failed.is_empty() {
+                break;
+            }
+            for failed in &failed {
+                chunks_left += self.mempool_state.required_chunks(&failed.tx).await?;
+            }
+            rejected.extend(failed);
+
+            let (left, replacements) = tx_queue
+                .select_l2_transactions(chunks_left, block_timestamp, &self.mempool_state)
+                .await?;
+            chunks_left = left;
+            txs = replacements;
+            view.load(&mut storage, &[], &txs)
+                .await
+                .map_err(|_| TxAddError::DbError)?;
+        }
+
+        simulator
+            .finish_proposal(&mut storage, &rejected)
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        if !rejected.is_empty() {
+            vlog::debug!(
+                "Swapped out {} transactions failing the simulation of the next miniblock",
+                rejected.len()
+            );
+        }
+        Ok((accepted, chunks_left))
+    }
+
     async fn propose_new_block(
         &mut self,
         current_unprocessed_priority_op: u64,
@@ -68,10 +136,21 @@ impl MempoolBlocksHandler {
             .select_transactions(
                 self.max_block_size_chunks,
                 current_unprocessed_priority_op,
+                &self.priority_op_quotas,
+                self.priority_op_deadline_margin,
                 block_timestamp,
                 &self.mempool_state,
             )
             .await?;
+        let (txs, chunks_left) = self
+            .simulate_block(
+                &mut tx_queue,
+                &priority_ops,
+                txs,
+                chunks_left,
+                block_timestamp,
+            )
+            .await?;
 
         if !priority_ops.is_empty() || !txs.is_empty() {
             vlog::debug!(
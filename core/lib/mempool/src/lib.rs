@@ -10,47 +10,88 @@
 //! Communication channel with other actors:
 //! Mempool does not push information to other actors, only accepts requests. (see `MempoolRequest`)
 
+// Built-in uses
+use std::time::Duration;
+
 // External uses
-use futures::channel::mpsc;
+use futures::{channel::mpsc, future::BoxFuture, FutureExt};
 
 use tokio::task::JoinHandle;
 
 // Workspace uses
+use zksync_balancer::{AdaptiveBalancer, BuildBalancedItem, ScalingConfig};
+use zksync_config::configs::chain::Mempool as MempoolConfig;
 use zksync_storage::ConnectionPool;
 
 // Local uses
 use crate::block_handler::MempoolBlocksHandler;
 pub use crate::block_handler::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
-use crate::mempool_transactions_queue::MempoolTransactionsQueue;
+use crate::mempool_transactions_queue::{MempoolTransactionsQueue, PriorityOpQuotas};
+use crate::revalidator::MempoolRevalidator;
+use crate::simulation::BlockSimulator;
 use crate::state::MempoolState;
 pub use crate::transactions_handler::MempoolTransactionRequest;
 use crate::transactions_handler::MempoolTransactionsHandler;
 
 mod block_handler;
 mod mempool_transactions_queue;
+mod revalidator;
+mod simulation;
 mod state;
 mod transactions_handler;
 
+struct MempoolTransactionsHandlerBuilder {
+    db_pool: ConnectionPool,
+    max_block_size_chunks: usize,
+}
+
+impl BuildBalancedItem<MempoolTransactionRequest, BoxFuture<'static, ()>>
+    for MempoolTransactionsHandlerBuilder
+{
+    fn build_with_receiver(
+        &self,
+        receiver: mpsc::Receiver<MempoolTransactionRequest>,
+    ) -> BoxFuture<'static, ()> {
+        let handler = MempoolTransactionsHandler {
+            db_pool: self.db_pool.clone(),
+            mempool_state: MempoolState::new(self.db_pool.clone()),
+            requests: receiver,
+            max_block_size_chunks: self.max_block_size_chunks,
+        };
+        handler.run().boxed()
+    }
+}
+
 // Due channel based nature, for better performance,
-// you need to run independent mempool_tx_handler for each actor, e.g. for each API actor
+// you need to run independent mempool_tx_handler for each actor, e.g. for each API actor.
+// Requests of the actor are distributed between several handlers, the number of which
+// is adjusted to the load.
 #[must_use]
 pub fn run_mempool_tx_handler(
     db_pool: ConnectionPool,
     tx_requests: mpsc::Receiver<MempoolTransactionRequest>,
     block_chunk_sizes: Vec<usize>,
+    config: &MempoolConfig,
 ) -> JoinHandle<()> {
-    let mempool_state = MempoolState::new(db_pool.clone());
     let max_block_size_chunks = *block_chunk_sizes
         .iter()
         .max()
         .expect("failed to find max block chunks size");
-    let handler = MempoolTransactionsHandler {
+    let builder = MempoolTransactionsHandlerBuilder {
         db_pool,
-        mempool_state,
-        requests: tx_requests,
         max_block_size_chunks,
     };
-    tokio::spawn(handler.run())
+    let scaling = ScalingConfig {
+        min_items: config.min_tx_handlers,
+        max_items: config.max_tx_handlers,
+        channel_capacity: config.tx_handler_channel_capacity,
+        scale_up_saturation: config.scale_up_saturation(),
+        scale_down_saturation: config.scale_down_saturation(),
+        scale_down_intervals: config.scale_down_intervals,
+        interval: config.scaling_interval(),
+    };
+    let balancer = AdaptiveBalancer::new(builder, tx_requests, scaling);
+    tokio::spawn(balancer.run())
 }
 
 #[must_use]
@@ -58,6 +99,7 @@ pub fn run_mempool_block_handler(
     db_pool: ConnectionPool,
     block_requests: mpsc::Receiver<MempoolBlocksRequest>,
     block_chunk_sizes: Vec<usize>,
+    config: &MempoolConfig,
 ) -> JoinHandle<()> {
     let mempool_state = MempoolState::new(db_pool);
     let max_block_size_chunks = *block_chunk_sizes
@@ -69,7 +111,27 @@ pub fn run_mempool_block_handler(
         mempool_state,
         requests: block_requests,
         max_block_size_chunks,
+        priority_op_quotas: PriorityOpQuotas::new(max_block_size_chunks, config),
+        priority_op_deadline_margin: config.priority_op_deadline_margin,
+        simulator: config
+            .simulate_proposed_blocks
+            .then(|| BlockSimulator::new(config.simulation_max_deferrals)),
     };
 
     tokio::spawn(blocks_handler.run())
 }
+
+/// Runs the actor that periodically evicts the transactions which can't be executed anymore
+/// (e.g. because their nonces are lower than the committed ones) from the mempool.
+#[must_use]
+pub fn run_mempool_revalidator(
+    db_pool: ConnectionPool,
+    sweep_interval: Duration,
+) -> JoinHandle<()> {
+    let revalidator = MempoolRevalidator {
+        db_pool,
+        sweep_interval,
+    };
+
+    tokio::spawn(revalidator.run())
+}
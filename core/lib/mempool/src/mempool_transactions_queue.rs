@@ -1,9 +1,55 @@
 use crate::MempoolState;
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, VecDeque};
+use zksync_config::configs::chain::Mempool as MempoolConfig;
 use zksync_types::mempool::SignedTxVariant;
 use zksync_types::tx::error::TxAddError;
-use zksync_types::PriorityOp;
+use zksync_types::{PriorityOp, SerialId, ZkSyncPriorityOp};
+
+/// Maximum number of chunks the priority operations of each kind can take in a block,
+/// so the flood of one kind of operations doesn't take the whole block.
+///
+/// Priority operations must be executed in the order of their serial ids, so the lanes
+/// can't overtake each other: once the next operation exceeds the quota of its kind,
+/// the selection of the priority operations for the block stops and the rest of the block
+/// is left for the L2 transactions. The operations approaching their deadline are selected
+/// regardless of the quotas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PriorityOpQuotas {
+    pub deposit_chunks: usize,
+    pub full_exit_chunks: usize,
+}
+
+impl PriorityOpQuotas {
+    pub fn new(max_block_size_chunks: usize, config: &MempoolConfig) -> Self {
+        let share = |percents: u8| max_block_size_chunks * usize::from(percents.min(100)) / 100;
+        Self {
+            deposit_chunks: share(config.deposit_chunks_quota),
+            full_exit_chunks: share(config.full_exit_chunks_quota),
+        }
+    }
+}
+
+/// Chunks used by the priority operations of each kind in the proposed block.
+#[derive(Debug, Default)]
+struct PriorityOpChunks {
+    deposit: usize,
+    full_exit: usize,
+}
+
+impl PriorityOpChunks {
+    fn total(&self) -> usize {
+        self.deposit + self.full_exit
+    }
+
+    /// Returns the counter of the operation kind and its quota.
+    fn lane(&mut self, op: &ZkSyncPriorityOp, quotas: &PriorityOpQuotas) -> (&mut usize, usize) {
+        match op {
+            ZkSyncPriorityOp::Deposit(_) => (&mut self.deposit, quotas.deposit_chunks),
+            ZkSyncPriorityOp::FullExit(_) => (&mut self.full_exit, quotas.full_exit_chunks),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 struct MempoolPendingTransaction {
@@ -43,17 +89,22 @@ pub(crate) struct MempoolTransactionsQueue {
     pending_l2_transactions: BinaryHeap<MempoolPendingTransaction>,
 
     l1_transactions: VecDeque<PriorityOp>,
+    /// Last Ethereum block processed by the Ethereum watcher, the deadlines of the priority
+    /// operations are compared with it. `None` if the watcher has not saved its progress yet.
+    last_ethereum_block: Option<u64>,
 }
 
 impl MempoolTransactionsQueue {
     pub(crate) fn new(
         l1_transactions: VecDeque<PriorityOp>,
         l2_transactions: VecDeque<SignedTxVariant>,
+        last_ethereum_block: Option<u64>,
     ) -> Self {
         let mut res = Self {
             ready_l2_transactions: Default::default(),
             pending_l2_transactions: Default::default(),
             l1_transactions,
+            last_ethereum_block,
         };
         // Due to complexity of json structure in database for transactions it's easier and safer
         // to add even not ready txs to mempool and prepare them before when it's needed.
@@ -125,16 +176,57 @@ impl MempoolTransactionsQueue {
             .append(&mut ready_pending_l2_operations);
     }
 
-    /// Collect txs depending on desired chunks and execution time
+    /// Returns the serial id of the last unprocessed priority operation whose deadline is within
+    /// `deadline_margin` Ethereum blocks, if any. All the operations up to it must be included
+    /// as soon as possible, since the operations are executed in the order of their serial ids.
+    fn urgent_priority_ops_until(
+        &self,
+        current_unprocessed_l1_tx: u64,
+        deadline_margin: u64,
+    ) -> Option<SerialId> {
+        let last_ethereum_block = self.last_ethereum_block?;
+        if deadline_margin == 0 {
+            return None;
+        }
+        self.l1_transactions
+            .iter()
+            .filter(|op| op.serial_id >= current_unprocessed_l1_tx)
+            .filter(|op| op.deadline_block <= last_ethereum_block + deadline_margin)
+            .map(|op| op.serial_id)
+            .max()
+    }
+
+    /// Collect txs depending on desired chunks and execution time.
+    ///
+    /// Once a priority operation approaches its deadline, the block is left for the priority
+    /// operations only, so they are not crowded out by the L2 transactions until it expires.
     pub(crate) async fn select_transactions(
         &mut self,
         chunks: usize,
         current_unprocessed_priority_op: u64,
+        priority_op_quotas: &PriorityOpQuotas,
+        deadline_margin: u64,
         block_timestamp: u64,
         mempool_state: &MempoolState,
     ) -> Result<(Vec<SignedTxVariant>, Vec<PriorityOp>, usize), TxAddError> {
-        let (chunks_left, priority_ops) =
-            self.select_l1_transactions(chunks, current_unprocessed_priority_op);
+        let urgent_until =
+            self.urgent_priority_ops_until(current_unprocessed_priority_op, deadline_margin);
+        let (chunks_left, priority_ops) = self.select_l1_transactions(
+            chunks,
+            current_unprocessed_priority_op,
+            priority_op_quotas,
+            urgent_until,
+        );
+
+        if let Some(urgent_until) = urgent_until {
+            vlog::warn!(
+                "Priority operations up to #{} approach their deadline, proposing {} of them without L2 txs",
+                urgent_until,
+                priority_ops.len()
+            );
+            metrics::increment_counter!("mempool.priority_op_deadline_forced");
+            return Ok((Vec::new(), priority_ops, chunks_left));
+        }
 
         let (chunks_left, executed_txs) = self
             .select_l2_transactions(chunks_left, block_timestamp, mempool_state)
@@ -143,15 +235,18 @@ impl MempoolTransactionsQueue {
         Ok((executed_txs, priority_ops, chunks_left))
     }
 
-    /// Returns: chunks left from max amount of chunks, ops selected
+    /// Returns: chunks left from max amount of chunks, ops selected.
+    /// The operations up to `urgent_until` are selected regardless of the quotas.
     fn select_l1_transactions(
         &mut self,
         max_block_size_chunks: usize,
         current_unprocessed_l1_tx: u64,
+        quotas: &PriorityOpQuotas,
+        urgent_until: Option<SerialId>,
     ) -> (usize, Vec<PriorityOp>) {
         let mut result = vec![];
 
-        let mut used_chunks = 0;
+        let mut used_chunks = PriorityOpChunks::default();
         let mut current_l1_tx = current_unprocessed_l1_tx;
         while let Some(tx) = self.pop_front_l1_transactions() {
             // Since the transaction addition is asynchronous process and we are checking node many times,
@@ -162,20 +257,47 @@ impl MempoolTransactionsQueue {
                 continue;
             }
             assert_eq!(current_l1_tx, tx.serial_id, "Wrong order for priority ops");
-            if used_chunks + tx.data.chunks() <= max_block_size_chunks {
-                used_chunks += tx.data.chunks();
+            let chunks = tx.data.chunks();
+            let fits_block = used_chunks.total() + chunks <= max_block_size_chunks;
+            // The first operation is always taken if it fits the block,
+            // so the queue makes progress even with the quota smaller than one operation.
+            let is_first = result.is_empty();
+            let is_urgent = urgent_until.map_or(false, |until| tx.serial_id <= until);
+            let (lane_chunks, quota) = used_chunks.lane(&tx.data, quotas);
+            if fits_block && (is_first || is_urgent || *lane_chunks + chunks <= quota) {
+                *lane_chunks += chunks;
                 result.push(tx);
                 current_l1_tx += 1;
             } else {
+                if fits_block {
+                    metrics::increment_counter!(
+                        "mempool.priority_op_quota_exceeded",
+                        "kind" => tx.data.variance_name()
+                    );
+                }
                 // We don't push back transactions because the transaction queue is used only once
                 break;
             }
         }
-        (max_block_size_chunks - used_chunks, result)
+        if used_chunks.deposit > 0 {
+            metrics::histogram!(
+                "mempool.priority_op_chunks",
+                used_chunks.deposit as f64,
+                "kind" => "Deposit"
+            );
+        }
+        if used_chunks.full_exit > 0 {
+            metrics::histogram!(
+                "mempool.priority_op_chunks",
+                used_chunks.full_exit as f64,
+                "kind" => "FullExit"
+            );
+        }
+        (max_block_size_chunks - used_chunks.total(), result)
     }
 
     /// Collect txs depending on the remaining chunks size
-    async fn select_l2_transactions(
+    pub(crate) async fn select_l2_transactions(
         &mut self,
         mut chunks_left: usize,
         block_timestamp: u64,
@@ -205,7 +327,8 @@ mod tests {
 
     use zksync_types::tx::{TimeRange, Transfer, Withdraw};
     use zksync_types::{
-        AccountId, Address, Deposit, Nonce, SignedZkSyncTx, TokenId, ZkSyncPriorityOp, ZkSyncTx,
+        AccountId, Address, Deposit, FullExit, Nonce, SignedZkSyncTx, TokenId, ZkSyncPriorityOp,
+        ZkSyncTx,
     };
 
     use super::*;
@@ -256,6 +379,7 @@ mod tests {
             ready_l2_transactions: VecDeque::new(),
             pending_l2_transactions: BinaryHeap::new(),
             l1_transactions: Default::default(),
+            last_ethereum_block: None,
         };
 
         transactions_queue.add_l1_transactions(vec![
@@ -354,12 +478,203 @@ mod tests {
         assert_eq!(op.serial_id, 6);
     }
 
+    fn get_priority_op(serial_id: u64, data: ZkSyncPriorityOp) -> PriorityOp {
+        PriorityOp {
+            serial_id,
+            data,
+            deadline_block: 0,
+            eth_hash: Default::default(),
+            eth_block: 0,
+            eth_block_index: None,
+        }
+    }
+
+    fn get_deposit(serial_id: u64) -> PriorityOp {
+        get_priority_op(
+            serial_id,
+            ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: Default::default(),
+                amount: Default::default(),
+                to: Default::default(),
+            }),
+        )
+    }
+
+    fn get_full_exit(serial_id: u64) -> PriorityOp {
+        get_priority_op(
+            serial_id,
+            ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: AccountId(1),
+                eth_address: Default::default(),
+                token: TokenId(0),
+                is_legacy: false,
+            }),
+        )
+    }
+
+    #[test]
+    fn test_priority_op_quotas() {
+        let deposit_chunks = get_deposit(0).data.chunks();
+        let full_exit_chunks = get_full_exit(0).data.chunks();
+        let max_block_size_chunks = 10 * (deposit_chunks + full_exit_chunks);
+        let quotas = PriorityOpQuotas {
+            deposit_chunks: 3 * deposit_chunks,
+            full_exit_chunks: max_block_size_chunks,
+        };
+        let queue = |ops: Vec<PriorityOp>| MempoolTransactionsQueue {
+            ready_l2_transactions: VecDeque::new(),
+            pending_l2_transactions: BinaryHeap::new(),
+            l1_transactions: ops.into(),
+            last_ethereum_block: None,
+        };
+
+        // Deposits are selected until their quota is reached, even if the full exits follow them.
+        let mut transactions_queue = queue(vec![
+            get_full_exit(0),
+            get_deposit(1),
+            get_deposit(2),
+            get_deposit(3),
+            get_deposit(4),
+            get_full_exit(5),
+        ]);
+        let (chunks_left, ops) =
+            transactions_queue.select_l1_transactions(max_block_size_chunks, 0, &quotas, None);
+        let serial_ids: Vec<_> = ops.iter().map(|op| op.serial_id).collect();
+        assert_eq!(serial_ids, vec![0, 1, 2, 3]);
+        assert_eq!(
+            chunks_left,
+            max_block_size_chunks - full_exit_chunks - 3 * deposit_chunks
+        );
+
+        // The first operation is taken even if it doesn't fit the quota.
+        let quotas = PriorityOpQuotas {
+            deposit_chunks: 0,
+            full_exit_chunks: 0,
+        };
+        let mut transactions_queue = queue(vec![get_deposit(7), get_deposit(8)]);
+        let (_, ops) =
+            transactions_queue.select_l1_transactions(max_block_size_chunks, 7, &quotas, None);
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].serial_id, 7);
+    }
+
+    #[test]
+    fn test_priority_op_quotas_from_config() {
+        let deposit_chunks = get_deposit(0).data.chunks();
+        let max_block_size_chunks = 4 * deposit_chunks;
+        let config = MempoolConfig {
+            min_tx_handlers: 1,
+            max_tx_handlers: 1,
+            tx_handler_channel_capacity: 1,
+            scale_up_saturation: 20,
+            scale_down_saturation: 1,
+            scale_down_intervals: 6,
+            scaling_interval: 10_000,
+            deposit_chunks_quota: 50,
+            full_exit_chunks_quota: 50,
+            simulate_proposed_blocks: false,
+            simulation_max_deferrals: 10,
+            priority_op_deadline_margin: 5760,
+        };
+        let quotas = PriorityOpQuotas::new(max_block_size_chunks, &config);
+        assert_eq!(quotas.deposit_chunks, 2 * deposit_chunks);
+
+        // The deposit exceeding the quota is not proposed even though it fits the block,
+        // and the deadlines are not known yet.
+        let mut transactions_queue = MempoolTransactionsQueue::new(
+            vec![
+                get_deposit(0),
+                get_deposit(1),
+                get_deposit(2),
+                get_deposit(3),
+            ]
+            .into(),
+            VecDeque::new(),
+            None,
+        );
+        let urgent_until =
+            transactions_queue.urgent_priority_ops_until(0, config.priority_op_deadline_margin);
+        assert_eq!(urgent_until, None);
+        let (chunks_left, ops) = transactions_queue.select_l1_transactions(
+            max_block_size_chunks,
+            0,
+            &quotas,
+            urgent_until,
+        );
+        let serial_ids: Vec<_> = ops.iter().map(|op| op.serial_id).collect();
+        assert_eq!(serial_ids, vec![0, 1]);
+        assert_eq!(chunks_left, 2 * deposit_chunks);
+
+        // It's proposed in the next block.
+        let mut transactions_queue = MempoolTransactionsQueue::new(
+            vec![get_deposit(2), get_deposit(3)].into(),
+            VecDeque::new(),
+            None,
+        );
+        let (_, ops) =
+            transactions_queue.select_l1_transactions(max_block_size_chunks, 2, &quotas, None);
+        let serial_ids: Vec<_> = ops.iter().map(|op| op.serial_id).collect();
+        assert_eq!(serial_ids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_priority_op_deadlines() {
+        let deposit_chunks = get_deposit(0).data.chunks();
+        let max_block_size_chunks = 10 * deposit_chunks;
+        let quotas = PriorityOpQuotas {
+            deposit_chunks,
+            full_exit_chunks: max_block_size_chunks,
+        };
+        let deposit_with_deadline = |serial_id, deadline_block| PriorityOp {
+            deadline_block,
+            ..get_deposit(serial_id)
+        };
+        let mut transactions_queue = MempoolTransactionsQueue::new(
+            vec![
+                deposit_with_deadline(0, 1000),
+                deposit_with_deadline(1, 1000),
+                deposit_with_deadline(2, 105),
+                deposit_with_deadline(3, 1000),
+            ]
+            .into(),
+            VecDeque::new(),
+            Some(100),
+        );
+
+        // Deadlines are not enforced without the margin, and the processed operations are ignored.
+        assert_eq!(transactions_queue.urgent_priority_ops_until(0, 0), None);
+        assert_eq!(transactions_queue.urgent_priority_ops_until(0, 4), None);
+        assert_eq!(transactions_queue.urgent_priority_ops_until(3, 10), None);
+        let urgent_until = transactions_queue.urgent_priority_ops_until(0, 10);
+        assert_eq!(urgent_until, Some(2));
+
+        // All the operations up to the urgent one are selected regardless of the quota.
+        let (_, ops) = transactions_queue.select_l1_transactions(
+            max_block_size_chunks,
+            0,
+            &quotas,
+            urgent_until,
+        );
+        let serial_ids: Vec<_> = ops.iter().map(|op| op.serial_id).collect();
+        assert_eq!(serial_ids, vec![0, 1, 2]);
+
+        // Deadlines are not known until the Ethereum watcher saves its progress.
+        let transactions_queue = MempoolTransactionsQueue::new(
+            vec![deposit_with_deadline(0, 0)].into(),
+            VecDeque::new(),
+            None,
+        );
+        assert_eq!(transactions_queue.urgent_priority_ops_until(0, 10), None);
+    }
+
     #[test]
     fn test_mempool_transactions_queue() {
         let mut transactions_queue = MempoolTransactionsQueue {
             ready_l2_transactions: VecDeque::new(),
             pending_l2_transactions: BinaryHeap::new(),
             l1_transactions: Default::default(),
+            last_ethereum_block: None,
         };
 
         let withdraw0 = get_withdraw();
@@ -0,0 +1,205 @@
+//! Revalidator periodically sweeps the transactions queued in the mempool and evicts
+//! the ones that can no longer be executed.
+//!
+//! Once a block is committed, the nonces of the affected accounts are updated, and some
+//! of the queued transactions may end up with the nonce lower than the committed one.
+//! Such transactions would otherwise be loaded on every block proposal only to fail in
+//! the state keeper. Batches are evicted as a whole if any of their transactions is stale.
+//!
+//! The reason of the eviction is stored in the database, so the API is able to report it.
+
+// Built-in uses
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+// External uses
+use tokio::time;
+
+// Workspace uses
+use zksync_storage::{ConnectionPool, QueryResult, StorageProcessor};
+use zksync_types::{
+    mempool::SignedTxVariant, tx::error::TxAddError, AccountId, Nonce, SignedZkSyncTx,
+};
+
+/// Transaction (or batch) that has to be removed from the mempool.
+#[derive(Debug, Clone)]
+pub(crate) struct StaleTx {
+    pub tx: SignedTxVariant,
+    pub reason: String,
+}
+
+pub(crate) struct MempoolRevalidator {
+    pub db_pool: ConnectionPool,
+    pub sweep_interval: Duration,
+}
+
+impl MempoolRevalidator {
+    /// Returns the reason why the transaction can't be executed anymore, if any.
+    fn check_tx(
+        tx: &SignedZkSyncTx,
+        committed_nonces: &HashMap<AccountId, Nonce>,
+    ) -> Option<String> {
+        let account_id = tx.account_id().ok()?;
+        let committed_nonce = committed_nonces.get(&account_id)?;
+        if tx.nonce() < *committed_nonce {
+            Some(TxAddError::NonceMismatch.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Selects the elements of the queue that can no longer be executed.
+    ///
+    /// The result depends only on the queue contents and the provided nonces, and preserves
+    /// the order of the queue, so every sweep over the same data evicts the same transactions.
+    pub(crate) fn find_stale_txs(
+        txs: impl IntoIterator<Item = SignedTxVariant>,
+        committed_nonces: &HashMap<AccountId, Nonce>,
+    ) -> Vec<StaleTx> {
+        txs.into_iter()
+            .filter_map(|tx| {
+                let reason = tx
+                    .get_transactions()
+                    .iter()
+                    .find_map(|tx| Self::check_tx(tx, committed_nonces))?;
+                Some(StaleTx { tx, reason })
+            })
+            .collect()
+    }
+
+    async fn load_committed_nonces(
+        storage: &mut StorageProcessor<'_>,
+        txs: &[SignedTxVariant],
+    ) -> QueryResult<HashMap<AccountId, Nonce>> {
+        let accounts: BTreeSet<AccountId> = txs
+            .iter()
+            .flat_map(SignedTxVariant::get_transactions)
+            .filter_map(|tx| tx.account_id().ok())
+            .collect();
+
+        let mut nonces = HashMap::with_capacity(accounts.len());
+        for account_id in accounts {
+            if let Some(nonce) = storage
+                .chain()
+                .account_schema()
+                .estimate_nonce(account_id)
+                .await?
+            {
+                nonces.insert(account_id, nonce);
+            }
+        }
+        Ok(nonces)
+    }
+
+    async fn sweep(&self) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut storage = self.db_pool.access_storage().await?;
+
+        // Already executed transactions are not stale, they should be just removed.
+        storage.chain().mempool_schema().collect_garbage().await?;
+
+        let txs: Vec<_> = storage
+            .chain()
+            .mempool_schema()
+            .load_txs(&[])
+            .await?
+            .into_iter()
+            .collect();
+        let committed_nonces = Self::load_committed_nonces(&mut storage, &txs).await?;
+
+        let stale_txs = Self::find_stale_txs(txs, &committed_nonces);
+        for stale in &stale_txs {
+            let batch_id = match &stale.tx {
+                SignedTxVariant::Tx(_) => None,
+                SignedTxVariant::Batch(batch) => Some(batch.batch_id),
+            };
+            storage
+                .chain()
+                .mempool_schema()
+                .evict_txs(&stale.tx.hashes(), batch_id, &stale.reason)
+                .await?;
+        }
+
+        if !stale_txs.is_empty() {
+            vlog::info!(
+                "Evicted {} stale transactions from the mempool",
+                stale_txs.len()
+            );
+        }
+        metrics::counter!("mempool.evicted_txs", stale_txs.len() as u64);
+        metrics::histogram!("mempool.revalidation_sweep", start.elapsed());
+        Ok(())
+    }
+
+    pub async fn run(self) {
+        vlog::info!("Mempool revalidator is running");
+        let mut timer = time::interval(self.sweep_interval);
+        loop {
+            timer.tick().await;
+            if let Err(err) = self.sweep().await {
+                vlog::error!("Mempool revalidation sweep failed: {}", err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use zksync_types::tx::Transfer;
+    use zksync_types::{Address, TokenId, ZkSyncTx};
+
+    use super::*;
+
+    fn get_transfer(account_id: u32, nonce: u32) -> SignedZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(account_id),
+            Address::random(),
+            Address::random(),
+            TokenId(0),
+            500u32.into(),
+            20u32.into(),
+            Nonce(nonce),
+            Default::default(),
+            None,
+        );
+
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn find_stale_txs() {
+        let committed_nonces: HashMap<_, _> =
+            vec![(AccountId(1), Nonce(5)), (AccountId(2), Nonce(1))]
+                .into_iter()
+                .collect();
+
+        let stale_tx = SignedTxVariant::Tx(get_transfer(1, 4));
+        let valid_tx = SignedTxVariant::Tx(get_transfer(1, 5));
+        let unknown_account_tx = SignedTxVariant::Tx(get_transfer(3, 0));
+        // One stale transaction invalidates the whole batch.
+        let stale_batch =
+            SignedTxVariant::batch(vec![get_transfer(2, 1), get_transfer(2, 0)], 1, Vec::new());
+        let valid_batch =
+            SignedTxVariant::batch(vec![get_transfer(2, 1), get_transfer(2, 2)], 2, Vec::new());
+
+        let queue = vec![
+            stale_tx.clone(),
+            valid_tx,
+            unknown_account_tx,
+            stale_batch.clone(),
+            valid_batch,
+        ];
+        let stale_txs = MempoolRevalidator::find_stale_txs(queue, &committed_nonces);
+
+        assert_eq!(stale_txs.len(), 2);
+        assert_eq!(stale_txs[0].tx.hashes(), stale_tx.hashes());
+        assert_eq!(stale_txs[1].tx.hashes(), stale_batch.hashes());
+        assert_eq!(stale_txs[1].reason, TxAddError::NonceMismatch.to_string());
+    }
+}
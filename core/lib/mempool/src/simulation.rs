@@ -0,0 +1,576 @@
+//! Pre-simulation of the proposed blocks.
+//!
+//! Sometimes the queue yields transactions that the state keeper rejects right away, e.g. the
+//! batches that expired while waiting in the mempool or the transfers from the accounts without
+//! enough funds. Every such transaction takes the place of the one that could be executed, so
+//! the block is sealed with fewer operations than it could have.
+//!
+//! The simulator replays the proposed block over a lightweight view of the committed state,
+//! which contains only the nonces and the balances of the involved accounts. The transactions
+//! that fail are swapped out, and the freed chunks are filled with the next transactions from
+//! the queue.
+//!
+//! The committed state may lag behind the state of the state keeper (the pending block isn't
+//! there yet), so the view is treated as an approximation:
+//! - the accounts with the nonce gaps are not checked at all;
+//! - the expired transactions and the transactions with stale nonces fail in any case,
+//!   so they are evicted from the mempool;
+//! - the transactions with insufficient balance may be funded by the pending block, so they
+//!   are only deferred, and passed to the state keeper after `max_deferrals` proposals;
+//! - the transactions following the deferred one from the same account are deferred as well,
+//!   since the nonce of the account isn't increased without it.
+
+// Built-in uses
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+// External uses
+use num::BigUint;
+
+// Workspace uses
+use zksync_storage::{QueryResult, StorageProcessor};
+use zksync_types::{
+    mempool::SignedTxVariant,
+    tx::{error::TxAddError, TxHash},
+    Account, AccountId, Address, PriorityOp, SignedZkSyncTx, TokenId, ZkSyncPriorityOp, ZkSyncTx,
+};
+
+/// Reason of the simulated transaction failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SimulationFailure {
+    Expired,
+    NonceMismatch,
+    NotEnoughBalance,
+    /// The preceding transaction of the account is deferred.
+    AccountDeferred,
+}
+
+impl SimulationFailure {
+    /// Whether the transaction fails regardless of the state not visible to the simulator.
+    pub fn is_definite(self) -> bool {
+        !matches!(self, Self::NotEnoughBalance | Self::AccountDeferred)
+    }
+
+    fn reason(self) -> String {
+        match self {
+            Self::Expired => "Transaction is expired".to_string(),
+            Self::NonceMismatch => TxAddError::NonceMismatch.to_string(),
+            Self::NotEnoughBalance => "Not enough balance".to_string(),
+            Self::AccountDeferred => "Preceding transaction of the account is deferred".to_string(),
+        }
+    }
+}
+
+/// Account charged by the transaction, and the changes of the balances it causes.
+#[derive(Debug)]
+struct BalanceChanges {
+    account_id: AccountId,
+    debits: Vec<(TokenId, BigUint)>,
+    credit: Option<(Address, TokenId, BigUint)>,
+}
+
+impl BalanceChanges {
+    /// Returns `None` for the transactions the simulator doesn't check.
+    fn new(tx: &ZkSyncTx) -> Option<Self> {
+        let (account_id, debits, credit) = match tx {
+            ZkSyncTx::Transfer(tx) => (
+                tx.account_id,
+                vec![(tx.token, &tx.amount + &tx.fee)],
+                Some((tx.to, tx.token, tx.amount.clone())),
+            ),
+            ZkSyncTx::Withdraw(tx) => (tx.account_id, vec![(tx.token, &tx.amount + &tx.fee)], None),
+            ZkSyncTx::ForcedExit(tx) => (
+                tx.initiator_account_id,
+                vec![(tx.token, tx.fee.clone())],
+                None,
+            ),
+            ZkSyncTx::ChangePubKey(tx) => {
+                (tx.account_id, vec![(tx.fee_token, tx.fee.clone())], None)
+            }
+            ZkSyncTx::MintNFT(tx) => (tx.creator_id, vec![(tx.fee_token, tx.fee.clone())], None),
+            ZkSyncTx::WithdrawNFT(tx) => (
+                tx.account_id,
+                vec![
+                    (tx.fee_token, tx.fee.clone()),
+                    (tx.token, BigUint::from(1u32)),
+                ],
+                None,
+            ),
+            // Swaps involve several accounts and orders, and `Close` is disabled.
+            ZkSyncTx::Swap(_) | ZkSyncTx::Close(_) => return None,
+        };
+        Some(Self {
+            account_id,
+            debits,
+            credit,
+        })
+    }
+}
+
+/// Nonces and balances of the accounts touched by the proposed block.
+///
+/// The accounts missing from the view (or stored as `None`) can't be predicted,
+/// so their transactions are always accepted.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StateView {
+    accounts: HashMap<AccountId, Option<Account>>,
+    account_ids: HashMap<Address, AccountId>,
+}
+
+impl StateView {
+    /// Loads the committed state of the accounts involved in the elements
+    /// that are not in the view yet.
+    pub async fn load(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        priority_ops: &[PriorityOp],
+        txs: &[SignedTxVariant],
+    ) -> QueryResult<()> {
+        let mut account_ids = HashSet::new();
+        let mut addresses: HashSet<_> = priority_ops
+            .iter()
+            .filter_map(|op| match &op.data {
+                ZkSyncPriorityOp::Deposit(deposit) => Some(deposit.to),
+                ZkSyncPriorityOp::FullExit(_) => None,
+            })
+            .collect();
+        for tx in txs.iter().flat_map(SignedTxVariant::get_transactions) {
+            if let Some(changes) = BalanceChanges::new(&tx.tx) {
+                account_ids.insert(changes.account_id);
+                if let Some((address, _, _)) = changes.credit {
+                    addresses.insert(address);
+                }
+            }
+        }
+
+        addresses.retain(|address| !self.account_ids.contains_key(address));
+        let addresses: Vec<_> = addresses.into_iter().collect();
+        let known_ids = storage
+            .chain()
+            .account_schema()
+            .account_ids_by_addresses(&addresses)
+            .await?;
+        account_ids.extend(known_ids.values().copied());
+        self.account_ids.extend(known_ids);
+
+        for account_id in account_ids {
+            if self.accounts.contains_key(&account_id) {
+                continue;
+            }
+            let (_, committed) = storage
+                .chain()
+                .account_schema()
+                .last_committed_state_for_account(account_id)
+                .await?;
+            self.accounts.insert(account_id, committed);
+        }
+        Ok(())
+    }
+
+    fn account_mut(&mut self, account_id: AccountId) -> Option<&mut Account> {
+        self.accounts.get_mut(&account_id).and_then(Option::as_mut)
+    }
+
+    fn forget(&mut self, account_id: AccountId) {
+        self.accounts.insert(account_id, None);
+    }
+
+    fn credit(&mut self, address: Address, token: TokenId, amount: &BigUint) {
+        let account_id = match self.account_ids.get(&address) {
+            Some(account_id) => *account_id,
+            None => return,
+        };
+        if let Some(account) = self.account_mut(account_id) {
+            account.add_balance(token, amount);
+        }
+    }
+
+    /// Applies the priority operations included in the block.
+    pub fn apply_priority_ops(&mut self, priority_ops: &[PriorityOp]) {
+        for op in priority_ops {
+            match &op.data {
+                ZkSyncPriorityOp::Deposit(deposit) => {
+                    self.credit(deposit.to, deposit.token, &deposit.amount)
+                }
+                // Full exit withdraws the whole balance only if the owner matches,
+                // the outcome isn't worth predicting.
+                ZkSyncPriorityOp::FullExit(full_exit) => self.forget(full_exit.account_id),
+            }
+        }
+    }
+
+    fn apply_tx(
+        &mut self,
+        tx: &SignedZkSyncTx,
+        block_timestamp: u64,
+    ) -> Result<(), SimulationFailure> {
+        if tx.tx.time_range().valid_until < block_timestamp {
+            return Err(SimulationFailure::Expired);
+        }
+
+        let changes = match BalanceChanges::new(&tx.tx) {
+            Some(changes) => changes,
+            None => return Ok(()),
+        };
+        let account = match self.account_mut(changes.account_id) {
+            Some(account) => account,
+            None => return Ok(()),
+        };
+        if tx.nonce() < account.nonce {
+            return Err(SimulationFailure::NonceMismatch);
+        }
+        if tx.nonce() > account.nonce {
+            // The preceding transactions are either in the pending block or still in the
+            // queue, the state of the account is unknown.
+            self.forget(changes.account_id);
+            return Ok(());
+        }
+
+        let mut required: HashMap<TokenId, BigUint> = HashMap::new();
+        for (token, amount) in changes.debits {
+            *required.entry(token).or_default() += amount;
+        }
+        if required
+            .iter()
+            .any(|(token, amount)| account.get_balance(*token) < *amount)
+        {
+            return Err(SimulationFailure::NotEnoughBalance);
+        }
+        for (token, amount) in &required {
+            account.sub_balance(*token, amount);
+        }
+        *account.nonce += 1;
+
+        if let Some((address, token, amount)) = changes.credit {
+            self.credit(address, token, &amount);
+        }
+        Ok(())
+    }
+
+    /// Applies all the transactions of the element atomically: if one of them fails,
+    /// the view is left intact.
+    pub fn apply(
+        &mut self,
+        element: &SignedTxVariant,
+        block_timestamp: u64,
+    ) -> Result<(), SimulationFailure> {
+        let mut view = self.clone();
+        for tx in element.get_transactions() {
+            view.apply_tx(&tx, block_timestamp)?;
+        }
+        *self = view;
+        Ok(())
+    }
+}
+
+/// Element of the proposed block that has to be swapped out.
+#[derive(Debug, Clone)]
+pub(crate) struct RejectedTx {
+    pub tx: SignedTxVariant,
+    pub failure: SimulationFailure,
+}
+
+#[derive(Debug)]
+pub(crate) struct BlockSimulator {
+    max_deferrals: usize,
+    /// Number of the proposals the elements were deferred from, by the hash of their first transaction.
+    deferrals: HashMap<TxHash, usize>,
+}
+
+impl BlockSimulator {
+    pub fn new(max_deferrals: usize) -> Self {
+        Self {
+            max_deferrals,
+            deferrals: HashMap::new(),
+        }
+    }
+
+    fn element_key(element: &SignedTxVariant) -> TxHash {
+        element.hashes()[0]
+    }
+
+    fn element_accounts(element: &SignedTxVariant) -> Vec<AccountId> {
+        element
+            .get_transactions()
+            .iter()
+            .filter_map(|tx| tx.tx.account_id().ok())
+            .collect()
+    }
+
+    /// Simulates the elements in order, returns the accepted and the rejected ones.
+    pub fn simulate(
+        &mut self,
+        view: &mut StateView,
+        txs: Vec<SignedTxVariant>,
+        block_timestamp: u64,
+    ) -> (Vec<SignedTxVariant>, Vec<RejectedTx>) {
+        let start = Instant::now();
+        let mut accepted = Vec::with_capacity(txs.len());
+        let mut rejected = Vec::new();
+        let mut deferred_accounts = HashSet::new();
+        for tx in txs {
+            let accounts = Self::element_accounts(&tx);
+            let failure = if accounts.iter().any(|id| deferred_accounts.contains(id)) {
+                // Doesn't count towards the limit, the element follows the deferred one anyway.
+                Some(SimulationFailure::AccountDeferred)
+            } else {
+                match view.apply(&tx, block_timestamp) {
+                    Ok(()) => None,
+                    Err(failure) if failure.is_definite() => Some(failure),
+                    Err(failure) => {
+                        let deferrals = self.deferrals.entry(Self::element_key(&tx)).or_default();
+                        if *deferrals < self.max_deferrals {
+                            *deferrals += 1;
+                            Some(failure)
+                        } else {
+                            // The view may be outdated, let the state keeper decide.
+                            None
+                        }
+                    }
+                }
+            };
+            match failure {
+                Some(failure) => {
+                    if !failure.is_definite() {
+                        deferred_accounts.extend(accounts);
+                    }
+                    rejected.push(RejectedTx { tx, failure })
+                }
+                None => accepted.push(tx),
+            }
+        }
+        metrics::histogram!("mempool.simulation", start.elapsed());
+        (accepted, rejected)
+    }
+
+    /// Evicts the definitely failing elements from the mempool and forgets the deferral
+    /// counters of the elements that weren't deferred from the last proposal.
+    pub async fn finish_proposal(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        rejected: &[RejectedTx],
+    ) -> QueryResult<()> {
+        let deferred: HashSet<_> = rejected
+            .iter()
+            .filter(|rejected| !rejected.failure.is_definite())
+            .map(|rejected| Self::element_key(&rejected.tx))
+            .collect();
+        self.deferrals.retain(|key, _| deferred.contains(key));
+
+        for rejected in rejected {
+            if !rejected.failure.is_definite() {
+                metrics::increment_counter!("mempool.simulation_rejected_txs", "action" => "deferred");
+                continue;
+            }
+            metrics::increment_counter!("mempool.simulation_rejected_txs", "action" => "evicted");
+            let batch_id = match &rejected.tx {
+                SignedTxVariant::Tx(_) => None,
+                SignedTxVariant::Batch(batch) => Some(batch.batch_id),
+            };
+            storage
+                .chain()
+                .mempool_schema()
+                .evict_txs(&rejected.tx.hashes(), batch_id, &rejected.failure.reason())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use zksync_types::tx::{TimeRange, Transfer};
+    use zksync_types::{Deposit, Nonce};
+
+    use super::*;
+
+    fn get_transfer(account_id: u32, to: Address, amount: u32, nonce: u32) -> SignedTxVariant {
+        get_transfer_with_time_range(account_id, to, amount, nonce, Default::default()).into()
+    }
+
+    fn get_transfer_with_time_range(
+        account_id: u32,
+        to: Address,
+        amount: u32,
+        nonce: u32,
+        time_range: TimeRange,
+    ) -> SignedZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(account_id),
+            Address::random(),
+            to,
+            TokenId(0),
+            amount.into(),
+            10u32.into(),
+            Nonce(nonce),
+            time_range,
+            None,
+        );
+
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Creates the view with the accounts `1` and `2` holding 100 units of token `0`.
+    fn get_view() -> (StateView, Address, Address) {
+        let mut view = StateView::default();
+        let (first, second) = (Address::random(), Address::random());
+        for (id, address) in vec![(1, first), (2, second)] {
+            let mut account = Account::default_with_address(&address);
+            account.set_balance(TokenId(0), 100u32.into());
+            view.accounts.insert(AccountId(id), Some(account));
+            view.account_ids.insert(address, AccountId(id));
+        }
+        (view, first, second)
+    }
+
+    #[test]
+    fn simulate_transfers() {
+        let (mut view, first, second) = get_view();
+        let mut simulator = BlockSimulator::new(1);
+
+        let valid = get_transfer(1, second, 50, 0);
+        let stale_nonce = get_transfer(1, second, 10, 0);
+        // Funded by the previous transfer.
+        let funded = get_transfer(2, first, 140, 0);
+        let unknown_account = get_transfer(3, first, 1000, 0);
+        // The state of the account is unknown after the nonce gap.
+        let nonce_gap = get_transfer(1, second, 10, 5);
+        let after_nonce_gap = get_transfer(1, second, 1000, 6);
+
+        let queue = vec![
+            valid.clone(),
+            stale_nonce.clone(),
+            funded.clone(),
+            unknown_account.clone(),
+            nonce_gap.clone(),
+            after_nonce_gap.clone(),
+        ];
+        let (accepted, rejected) = simulator.simulate(&mut view, queue, 0);
+
+        let accepted: Vec<_> = accepted.iter().map(SignedTxVariant::hashes).collect();
+        assert_eq!(
+            accepted,
+            vec![
+                valid.hashes(),
+                funded.hashes(),
+                unknown_account.hashes(),
+                nonce_gap.hashes(),
+                after_nonce_gap.hashes(),
+            ]
+        );
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].tx.hashes(), stale_nonce.hashes());
+        assert_eq!(rejected[0].failure, SimulationFailure::NonceMismatch);
+    }
+
+    #[test]
+    fn deferred_account() {
+        let (mut view, first, second) = get_view();
+        let mut simulator = BlockSimulator::new(1);
+
+        let not_enough_balance = get_transfer(1, second, 100, 0);
+        // Would be accepted after the nonce gap, but the preceding transaction isn't executed.
+        let next = get_transfer(1, second, 10, 1);
+        let other_account = get_transfer(2, first, 10, 0);
+
+        let queue = vec![
+            not_enough_balance.clone(),
+            next.clone(),
+            other_account.clone(),
+        ];
+        let (accepted, rejected) = simulator.simulate(&mut view, queue, 0);
+
+        let accepted: Vec<_> = accepted.iter().map(SignedTxVariant::hashes).collect();
+        assert_eq!(accepted, vec![other_account.hashes()]);
+        let rejected: Vec<_> = rejected
+            .iter()
+            .map(|rejected| (rejected.tx.hashes(), rejected.failure))
+            .collect();
+        assert_eq!(
+            rejected,
+            vec![
+                (
+                    not_enough_balance.hashes(),
+                    SimulationFailure::NotEnoughBalance
+                ),
+                (next.hashes(), SimulationFailure::AccountDeferred),
+            ]
+        );
+
+        // Once the deferral limit is reached, the whole sequence is passed to the state keeper.
+        let (mut view, _, _) = get_view();
+        let (accepted, rejected) = simulator.simulate(&mut view, vec![not_enough_balance, next], 0);
+        assert_eq!(accepted.len(), 2);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn deferrals_limit() {
+        let (_, _, second) = get_view();
+        let mut simulator = BlockSimulator::new(2);
+        let tx = get_transfer(1, second, 100, 0);
+
+        for _ in 0..2 {
+            let (mut view, _, _) = get_view();
+            let (accepted, rejected) = simulator.simulate(&mut view, vec![tx.clone()], 0);
+            assert!(accepted.is_empty());
+            assert_eq!(rejected[0].failure, SimulationFailure::NotEnoughBalance);
+        }
+        // The view may be outdated, so the transaction is eventually passed to the state keeper.
+        let (mut view, _, _) = get_view();
+        let (accepted, rejected) = simulator.simulate(&mut view, vec![tx], 0);
+        assert_eq!(accepted.len(), 1);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn expired_batch() {
+        let (mut view, _, second) = get_view();
+        let batch = SignedTxVariant::batch(
+            vec![
+                get_transfer_with_time_range(1, second, 10, 0, Default::default()),
+                get_transfer_with_time_range(1, second, 10, 1, TimeRange::new(0, 5)),
+            ],
+            1,
+            Vec::new(),
+        );
+
+        assert_eq!(view.apply(&batch, 10), Err(SimulationFailure::Expired));
+        // The batch is applied atomically.
+        let account = view.account_mut(AccountId(1)).unwrap();
+        assert_eq!(account.nonce, Nonce(0));
+        assert_eq!(account.get_balance(TokenId(0)), 100u32.into());
+
+        assert_eq!(view.apply(&batch, 5), Ok(()));
+        let account = view.account_mut(AccountId(1)).unwrap();
+        assert_eq!(account.nonce, Nonce(2));
+        assert_eq!(account.get_balance(TokenId(0)), 60u32.into());
+    }
+
+    #[test]
+    fn deposits() {
+        let (mut view, first, second) = get_view();
+        let deposit = PriorityOp {
+            serial_id: 0,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: TokenId(0),
+                amount: 50u32.into(),
+                to: first,
+            }),
+            deadline_block: 0,
+            eth_hash: Default::default(),
+            eth_block: 0,
+            eth_block_index: None,
+        };
+
+        view.apply_priority_ops(&[deposit]);
+        assert_eq!(view.apply(&get_transfer(1, second, 140, 0), 0), Ok(()));
+    }
+}
@@ -114,6 +114,13 @@ impl MempoolState {
         Self { db_pool }
     }
 
+    pub async fn access_storage(&self) -> Result<StorageProcessor<'_>, TxAddError> {
+        self.db_pool
+            .access_storage()
+            .await
+            .map_err(|_| TxAddError::DbError)
+    }
+
     pub async fn get_transaction_queue(
         &self,
         executed_txs: &[TxHash],
@@ -135,16 +142,32 @@ impl MempoolState {
             .await
             .map_err(|_| TxAddError::DbError)?;
 
+        // The scheduled transactions stay in the database until their block is committed.
+        let last_committed_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+
         // Load transactions that were not yet processed and are awaiting in the
         // mempool.
         let mempool_txs = transaction
             .chain()
             .mempool_schema()
-            .load_txs(executed_txs)
+            .load_ready_txs(executed_txs, last_committed_block)
             .await
             .map_err(|_| TxAddError::DbError)?;
 
-        let transactions_queue = MempoolTransactionsQueue::new(priority_ops, mempool_txs);
+        let last_ethereum_block = transaction
+            .eth_watch_schema()
+            .load_state()
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .map(|state| state.last_ethereum_block());
+
+        let transactions_queue =
+            MempoolTransactionsQueue::new(priority_ops, mempool_txs, last_ethereum_block);
 
         Ok(transactions_queue)
     }
@@ -1,12 +1,14 @@
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
 
+use zksync_balancer::ShardedRequest;
 use zksync_storage::ConnectionPool;
 use zksync_types::{
     mempool::SignedTxsBatch,
     tx::{error::TxAddError, TxEthSignature},
-    PriorityOp, SignedZkSyncTx,
+    BlockNumber, PriorityOp, SerialId, SignedZkSyncTx,
 };
+use zksync_utils::deadline::DeadlineSender;
 
 use crate::state::MempoolState;
 
@@ -14,8 +16,14 @@ use crate::state::MempoolState;
 pub enum MempoolTransactionRequest {
     /// Add new transaction to mempool, transaction should be previously checked
     /// for correctness (including its Ethereum and ZKSync signatures).
-    /// oneshot is used to receive tx add result.
-    NewTx(Box<SignedZkSyncTx>, oneshot::Sender<Result<(), TxAddError>>),
+    /// oneshot is used to receive tx add result, the transaction is not added
+    /// if the request is abandoned before it's handled.
+    /// If the block number is set, the transaction isn't executed until this block is committed.
+    NewTx(
+        Box<SignedZkSyncTx>,
+        Option<BlockNumber>,
+        DeadlineSender<Result<(), TxAddError>>,
+    ),
 
     /// Add new priority ops, confirmed or not
     NewPriorityOps(
@@ -23,6 +31,10 @@ pub enum MempoolTransactionRequest {
         bool,
         oneshot::Sender<Result<(), TxAddError>>,
     ),
+    /// Remove the unconfirmed priority ops received in the Ethereum blocks starting from
+    /// the given one, as these blocks were reorged out of the chain.
+    /// Responds with the serial ids of the removed operations.
+    RevertUnconfirmedPriorityOps(u64, oneshot::Sender<Result<Vec<SerialId>, TxAddError>>),
     /// Add a new batch of transactions to the mempool. All transactions in batch must
     /// be either executed successfully, or otherwise fail all together.
     /// Invariants for each individual transaction in the batch are the same as in
@@ -30,8 +42,24 @@ pub enum MempoolTransactionRequest {
     NewTxsBatch(
         Vec<SignedZkSyncTx>,
         Vec<TxEthSignature>,
-        oneshot::Sender<Result<(), TxAddError>>,
+        DeadlineSender<Result<(), TxAddError>>,
     ),
+    /// Checks that the handler keeps processing the requests, the response is sent immediately.
+    /// Used by the readiness probes of the API servers.
+    Ping(oneshot::Sender<()>),
+}
+
+impl ShardedRequest for MempoolTransactionRequest {
+    /// Transactions of the same account are handled by the same handler, so their nonces
+    /// are checked in the order the transactions were submitted.
+    fn shard_key(&self) -> u64 {
+        let account = match self {
+            Self::NewTx(tx, ..) => Some(tx.tx.account()),
+            Self::NewTxsBatch(txs, ..) => txs.first().map(|tx| tx.tx.account()),
+            _ => None,
+        };
+        account.map_or(0, |address| address.to_low_u64_be())
+    }
 }
 
 pub(crate) struct MempoolTransactionsHandler {
@@ -42,7 +70,11 @@ pub(crate) struct MempoolTransactionsHandler {
 }
 
 impl MempoolTransactionsHandler {
-    async fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
+    async fn add_tx(
+        &mut self,
+        tx: SignedZkSyncTx,
+        not_before_block: Option<BlockNumber>,
+    ) -> Result<(), TxAddError> {
         // Correctness should be checked by `signature_checker`, thus
         // `tx.check_correctness()` is not invoked here.
         let mut storage = self.db_pool.access_storage().await.map_err(|err| {
@@ -50,11 +82,12 @@ impl MempoolTransactionsHandler {
             TxAddError::DbError
         })?;
 
+        // Close operation does not exist so we will never met this error
+        let account_id = tx.account_id().map_err(|_| TxAddError::Other)?;
         let nonce = storage
             .chain()
             .account_schema()
-            // Close operation does not exist so we will never met this error
-            .estimate_nonce(tx.account_id().map_err(|_| TxAddError::Other)?)
+            .estimate_nonce(account_id)
             .await
             .map_err(|_| TxAddError::DbError)?
             .unwrap_or_default();
@@ -63,15 +96,46 @@ impl MempoolTransactionsHandler {
             return Err(TxAddError::NonceMismatch);
         }
 
-        storage
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        // The scheduled transaction can't take the nonce of the pending one, while any
+        // transaction replaces the scheduled ones with its nonce, so they don't block
+        // the account until their block is committed.
+        if not_before_block.is_some()
+            && transaction
+                .chain()
+                .mempool_schema()
+                .has_unscheduled_tx(account_id, tx.nonce())
+                .await
+                .map_err(|_| TxAddError::DbError)?
+        {
+            return Err(TxAddError::NonceMismatch);
+        }
+        transaction
+            .chain()
+            .mempool_schema()
+            .evict_scheduled_txs(
+                account_id,
+                tx.nonce(),
+                "Replaced by the transaction with the same nonce",
+            )
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        transaction
             .chain()
             .mempool_schema()
-            .insert_tx(&tx)
+            .insert_scheduled_tx(&tx, not_before_block)
             .await
             .map_err(|err| {
                 vlog::error!("Mempool storage access error: {}", err);
                 TxAddError::DbError
             })?;
+        transaction
+            .commit()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
 
         let labels = vec![
             ("stage", "mempool".to_string()),
@@ -133,6 +197,33 @@ impl MempoolTransactionsHandler {
         Ok(())
     }
 
+    async fn revert_unconfirmed_priority_ops(
+        &mut self,
+        from_eth_block: u64,
+    ) -> Result<Vec<SerialId>, TxAddError> {
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+        let serial_ids = storage
+            .chain()
+            .mempool_schema()
+            .remove_unconfirmed_priority_ops(from_eth_block)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+
+        if !serial_ids.is_empty() {
+            vlog::warn!(
+                "Unconfirmed priority operations {:?} are removed from the mempool",
+                serial_ids
+            );
+        }
+        Ok(serial_ids)
+    }
+
     async fn add_batch(
         &mut self,
         txs: Vec<SignedZkSyncTx>,
@@ -197,11 +288,19 @@ impl MempoolTransactionsHandler {
         vlog::info!("Transaction mempool handler is running");
         while let Some(request) = self.requests.next().await {
             match request {
-                MempoolTransactionRequest::NewTx(tx, resp) => {
-                    let tx_add_result = self.add_tx(*tx).await;
+                MempoolTransactionRequest::NewTx(tx, not_before_block, resp) => {
+                    if resp.is_abandoned() {
+                        metrics::increment_counter!("mempool.abandoned_requests", "type" => "tx");
+                        continue;
+                    }
+                    let tx_add_result = self.add_tx(*tx, not_before_block).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
                 MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, resp) => {
+                    if resp.is_abandoned() {
+                        metrics::increment_counter!("mempool.abandoned_requests", "type" => "batch");
+                        continue;
+                    }
                     let tx_add_result = self.add_batch(txs, eth_signatures).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
@@ -209,6 +308,13 @@ impl MempoolTransactionsHandler {
                     let tx_add_result = self.add_priority_ops(ops, confirmed).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
+                MempoolTransactionRequest::RevertUnconfirmedPriorityOps(from_eth_block, resp) => {
+                    let result = self.revert_unconfirmed_priority_ops(from_eth_block).await;
+                    resp.send(result).unwrap_or_default();
+                }
+                MempoolTransactionRequest::Ping(resp) => {
+                    resp.send(()).unwrap_or_default();
+                }
             }
         }
     }
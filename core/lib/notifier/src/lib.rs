@@ -26,4 +26,36 @@ impl Notifier {
 
         Ok(())
     }
+
+    pub async fn send_deposit_anomaly_notify(&self, anomaly: &str) -> anyhow::Result<()> {
+        self.matter_most_notifier
+            .send_notify("deposit_monitor_bot", anomaly)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn send_balance_discrepancy_notify(&self, discrepancy: &str) -> anyhow::Result<()> {
+        self.matter_most_notifier
+            .send_notify("balance_auditor_bot", discrepancy)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn send_stuck_tx_notify(&self, message: &str) -> anyhow::Result<()> {
+        self.matter_most_notifier
+            .send_notify("eth_sender_bot", message)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn send_operator_balance_notify(&self, message: &str) -> anyhow::Result<()> {
+        self.matter_most_notifier
+            .send_notify("eth_sender_bot", message)
+            .await?;
+
+        Ok(())
+    }
 }
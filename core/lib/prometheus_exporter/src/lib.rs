@@ -1,8 +1,9 @@
 //! This module handles metric export to the Prometheus server
 
-use metrics_exporter_prometheus::PrometheusBuilder;
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use num::rational::Ratio;
 use num::{BigUint, ToPrimitive};
+use once_cell::sync::OnceCell;
 use std::collections::HashMap;
 use std::ops::Add;
 use std::time::{Duration, Instant};
@@ -16,6 +17,24 @@ use zksync_types::{ExecutedOperations, TokenId};
 
 const QUERY_INTERVAL: Duration = Duration::from_secs(30);
 
+/// Handle to the installed recorder, used to render the metrics outside of the exporter
+/// (e.g. by the `/metrics` endpoint of the REST API server).
+static PROMETHEUS_HANDLE: OnceCell<PrometheusHandle> = OnceCell::new();
+
+const POOL_UTILIZATION_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Periodically reports the utilization of the connection pools of the process.
+pub fn run_pool_utilization_reporter(pools: Vec<ConnectionPool>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            for pool in &pools {
+                pool.report_utilization();
+            }
+            sleep(POOL_UTILIZATION_INTERVAL).await;
+        }
+    })
+}
+
 pub fn run_operation_counter(connection_pool: ConnectionPool) -> JoinHandle<()> {
     tokio::spawn(async move {
         loop {
@@ -117,6 +136,8 @@ pub fn run_prometheus_exporter(port: u16) -> JoinHandle<()> {
         .listen_address(addr)
         .build_with_exporter()
         .expect("failed to install Prometheus recorder");
+    // The second recorder can't be installed anyway, the check below will panic.
+    let _ = PROMETHEUS_HANDLE.set(recorder.handle());
     metrics::set_boxed_recorder(Box::new(recorder)).expect("failed to set metrics recorder");
 
     tokio::spawn(async move {
@@ -129,6 +150,12 @@ pub fn run_prometheus_exporter(port: u16) -> JoinHandle<()> {
     })
 }
 
+/// Renders the current state of the metrics in the Prometheus text format.
+/// Returns `None` if the exporter isn't running in this process.
+pub fn render_metrics() -> Option<String> {
+    PROMETHEUS_HANDLE.get().map(PrometheusHandle::render)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{get_volumes, token_amount_to_usd, BigUint, ToPrimitive, TokenId};
@@ -182,6 +209,7 @@ mod tests {
             success,
             op: Some(ZkSyncOp::Transfer(Box::new(transfer_op))),
             fail_reason: None,
+            fail_code: None,
             block_index: None,
             created_at: Utc::now(),
             batch_id: None,
@@ -243,6 +271,7 @@ mod tests {
             success,
             op: Some(ZkSyncOp::Swap(Box::new(swap_op))),
             fail_reason: None,
+            fail_code: None,
             block_index: None,
             created_at: Utc::now(),
             batch_id: None,
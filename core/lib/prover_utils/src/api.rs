@@ -13,6 +13,10 @@ pub struct ProverInputRequest {
 pub struct ProverInputRequestAuxData {
     pub prefer_aggregated_proof: Option<bool>,
     pub preferred_block_size: Option<usize>,
+    /// Tags of the jobs the prover is capable of, see `ProverCapabilities` for the format.
+    /// The prover without the tags gets any job.
+    #[serde(default)]
+    pub capability_tags: Vec<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1,5 +1,6 @@
 use crate::handler::error::*;
 use thiserror::Error;
+use zksync_types::tx::TxFailureCode;
 
 #[derive(Clone, Debug, Error, PartialEq)]
 pub enum OpError {
@@ -23,6 +24,23 @@ pub enum OpError {
     TimestampError,
 }
 
+impl OpError {
+    /// Returns the machine-readable reason of the failure, stored along with the rejected transaction.
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::TransferOpError(err) => err.failure_code(),
+            Self::WithdrawOpError(err) => err.failure_code(),
+            Self::WithdrawNFTOpError(err) => err.failure_code(),
+            Self::CloseOpError(err) => err.failure_code(),
+            Self::ChangePubKeyOpError(err) => err.failure_code(),
+            Self::ForcedExitOpError(err) => err.failure_code(),
+            Self::SwapOpError(err) => err.failure_code(),
+            Self::MintNFTOpError(err) => err.failure_code(),
+            Self::TimestampError => TxFailureCode::InvalidTimestamp,
+        }
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 #[error(
     "Batch execution failed, since tx #{failed_tx_index} of batch failed with a reason: {reason}"
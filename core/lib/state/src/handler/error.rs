@@ -1,4 +1,5 @@
 use thiserror::Error;
+use zksync_types::tx::TxFailureCode;
 
 #[derive(Clone, Debug, Error, PartialEq)]
 pub enum ChangePubKeyOpError {
@@ -202,3 +203,148 @@ pub enum SwapOpError {
     #[error("Self-swap is not allowed")]
     SelfSwap,
 }
+
+impl ChangePubKeyOpError {
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::InvalidFeeTokenId => TxFailureCode::InvalidToken,
+            Self::AccountNotFound => TxFailureCode::AccountNotFound,
+            Self::InvalidAccountAddress | Self::InvalidAccountId | Self::AccountIdTooBig => {
+                TxFailureCode::InvalidAccount
+            }
+            Self::InvalidAuthData => TxFailureCode::InvalidPubKeyAuth,
+            Self::InvalidZksyncSignature => TxFailureCode::InvalidSignature,
+            Self::NonceMismatch => TxFailureCode::NonceMismatch,
+            Self::InsufficientBalance => TxFailureCode::InsufficientBalance,
+        }
+    }
+}
+
+impl CloseOpError {
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::InvalidAccountId => TxFailureCode::InvalidAccount,
+            Self::NonceMismatch => TxFailureCode::NonceMismatch,
+            Self::CloseOperationsDisabled | Self::AccountNotEmpty(_) => {
+                TxFailureCode::InvalidOperation
+            }
+        }
+    }
+}
+
+impl ForcedExitOpError {
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::InitiatorAccountNotFound | Self::TargetAccountNotFound => {
+                TxFailureCode::AccountNotFound
+            }
+            Self::IncorrectInitiatorAccount | Self::InvalidTargetAccount => {
+                TxFailureCode::InvalidAccount
+            }
+            Self::InvalidSignature => TxFailureCode::InvalidSignature,
+            Self::InvalidFeeTokenId | Self::InvalidTokenId => TxFailureCode::InvalidToken,
+            Self::NonceMismatch => TxFailureCode::NonceMismatch,
+            Self::InitiatorInsufficientBalance => TxFailureCode::InsufficientBalance,
+            Self::TargetAccountNotLocked | Self::TargetAccountBalanceMismatch => {
+                TxFailureCode::InvalidOperation
+            }
+        }
+    }
+}
+
+impl TransferOpError {
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::InvalidFeeTokenId | Self::InvalidTokenId => TxFailureCode::InvalidToken,
+            Self::FromAccountNotFound => TxFailureCode::AccountNotFound,
+            Self::FromAccountLocked => TxFailureCode::AccountLocked,
+            Self::TargetAccountZero
+            | Self::TransferAccountIncorrect
+            | Self::SourceAccountIncorrect
+            | Self::TargetAccountIncorrect => TxFailureCode::InvalidAccount,
+            Self::InvalidSignature => TxFailureCode::InvalidSignature,
+            Self::NonceMismatch => TxFailureCode::NonceMismatch,
+            Self::InsufficientBalance => TxFailureCode::InsufficientBalance,
+            Self::CannotTransferToSelf => TxFailureCode::InvalidOperation,
+        }
+    }
+}
+
+impl MintNFTOpError {
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::InvalidTokenId => TxFailureCode::InvalidToken,
+            Self::CreatorAccountIsLocked | Self::CreatorAccountLocked => {
+                TxFailureCode::AccountLocked
+            }
+            Self::CreatorAccountNotFound | Self::RecipientAccountNotFound => {
+                TxFailureCode::AccountNotFound
+            }
+            Self::InvalidSignature => TxFailureCode::InvalidSignature,
+            Self::RecipientAccountIncorrect => TxFailureCode::InvalidAccount,
+            Self::NonceMismatch => TxFailureCode::NonceMismatch,
+            Self::InsufficientBalance => TxFailureCode::InsufficientBalance,
+            Self::TokenIsAlreadyInAccount => TxFailureCode::InvalidOperation,
+        }
+    }
+}
+
+impl WithdrawNFTOpError {
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::InvalidFeeTokenId | Self::InvalidTokenId | Self::NFTNotFound => {
+                TxFailureCode::InvalidToken
+            }
+            Self::FromAccountNotFound => TxFailureCode::AccountNotFound,
+            Self::FromAccountLocked => TxFailureCode::AccountLocked,
+            Self::InvalidSignature => TxFailureCode::InvalidSignature,
+            Self::FromAccountIncorrect | Self::CreatorAccountIncorrect => {
+                TxFailureCode::InvalidAccount
+            }
+            Self::NonceMismatch => TxFailureCode::NonceMismatch,
+            Self::InsufficientBalance | Self::InsufficientNFTBalance => {
+                TxFailureCode::InsufficientBalance
+            }
+        }
+    }
+}
+
+impl WithdrawOpError {
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::InvalidFeeTokenId | Self::InvalidTokenId => TxFailureCode::InvalidToken,
+            Self::FromAccountNotFound => TxFailureCode::AccountNotFound,
+            Self::FromAccountLocked => TxFailureCode::AccountLocked,
+            Self::InvalidSignature => TxFailureCode::InvalidSignature,
+            Self::FromAccountIncorrect => TxFailureCode::InvalidAccount,
+            Self::NonceMismatch => TxFailureCode::NonceMismatch,
+            Self::InsufficientBalance => TxFailureCode::InsufficientBalance,
+        }
+    }
+}
+
+impl SwapOpError {
+    pub fn failure_code(&self) -> TxFailureCode {
+        match self {
+            Self::InvalidTokenId => TxFailureCode::InvalidToken,
+            Self::AccountZero
+            | Self::AccountIncorrect
+            | Self::SubmitterAccountIncorrect
+            | Self::SourceAccountIncorrect => TxFailureCode::InvalidAccount,
+            Self::SubmitterAccountNotFound
+            | Self::AccountNotFound
+            | Self::RecipientAccountNotFound => TxFailureCode::AccountNotFound,
+            Self::AccountLocked => TxFailureCode::AccountLocked,
+            Self::SwapInvalidSignature | Self::OrderInvalidSignature => {
+                TxFailureCode::InvalidSignature
+            }
+            Self::NonceMismatch => TxFailureCode::NonceMismatch,
+            Self::InsufficientBalance => TxFailureCode::InsufficientBalance,
+            Self::BuySellNotMatched
+            | Self::SwapSameToken
+            | Self::AmountsNotMatched
+            | Self::AmountsNotCompatible
+            | Self::SelfSwap => TxFailureCode::InvalidOperation,
+        }
+    }
+}
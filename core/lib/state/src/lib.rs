@@ -1,4 +1,6 @@
 pub mod handler;
+pub mod parallel;
+pub mod shared;
 pub mod state;
 
 pub mod error;
@@ -0,0 +1,173 @@
+//! Parallel execution of the independent transactions.
+//!
+//! Most of the time of the transaction execution is spent on creating the operation, i.e. on the checks
+//! of the signatures, while the operation itself is applied quickly. The operations of the transactions
+//! touching disjoint sets of accounts don't depend on each other, so the sequence of transactions is split
+//! into waves of such transactions, and the operations of a wave are created against the same state in
+//! parallel. The operations are then applied one by one in the original order, so the resulting state
+//! and updates are the same as after the sequential execution.
+//!
+//! The transactions creating accounts or tokens, and the transactions whose accounts can't be resolved,
+//! always form a wave on their own, since the operations of the following transactions depend on them.
+
+// Built-in deps
+use std::{borrow::Borrow, collections::HashSet};
+// External uses
+use rayon::prelude::*;
+// Workspace uses
+use zksync_types::{
+    operations::{
+        ChangePubKeyOp, ForcedExitOp, MintNFTOp, SwapOp, WithdrawNFTOp, WithdrawOp, ZkSyncOp,
+    },
+    AccountId, Address, ChangePubKey, ForcedExit, MintNFT, Swap, Transfer, Withdraw, WithdrawNFT,
+    ZkSyncTx,
+};
+// Local uses
+use crate::{
+    error::OpError,
+    handler::{error::CloseOpError, TxHandler},
+    state::{OpSuccess, TransferOutcome, ZkSyncState},
+    tx_ext::TxCheck,
+};
+
+/// Operation created from the transaction, not yet applied to the state.
+#[derive(Debug, Clone)]
+pub enum PreparedOp {
+    Transfer(TransferOutcome),
+    Withdraw(WithdrawOp),
+    ChangePubKey(ChangePubKeyOp),
+    ForcedExit(ForcedExitOp),
+    Swap(SwapOp),
+    MintNFT(MintNFTOp),
+    WithdrawNFT(WithdrawNFTOp),
+}
+
+impl PreparedOp {
+    pub fn to_zksync_op(&self) -> ZkSyncOp {
+        match self.clone() {
+            Self::Transfer(op) => op.into_franklin_op(),
+            Self::Withdraw(op) => op.into(),
+            Self::ChangePubKey(op) => op.into(),
+            Self::ForcedExit(op) => op.into(),
+            Self::Swap(op) => op.into(),
+            Self::MintNFT(op) => op.into(),
+            Self::WithdrawNFT(op) => op.into(),
+        }
+    }
+}
+
+impl ZkSyncState {
+    /// Returns the accounts the operation of the transaction is created from,
+    /// or `None` if the transaction must be executed on its own.
+    fn tx_dependencies(&self, tx: &ZkSyncTx) -> Option<Vec<AccountId>> {
+        let resolve = |address: &Address| self.get_account_by_address(address).map(|(id, _)| id);
+        let accounts = match tx {
+            ZkSyncTx::Transfer(tx) => vec![tx.account_id, resolve(&tx.from)?, resolve(&tx.to)?],
+            ZkSyncTx::Withdraw(tx) => vec![tx.account_id, resolve(&tx.from)?],
+            ZkSyncTx::WithdrawNFT(tx) => vec![tx.account_id, resolve(&tx.from)?],
+            ZkSyncTx::ChangePubKey(tx) => vec![tx.account_id, resolve(&tx.account)?],
+            ZkSyncTx::ForcedExit(tx) => vec![tx.initiator_account_id, resolve(&tx.target)?],
+            ZkSyncTx::Swap(_) | ZkSyncTx::MintNFT(_) | ZkSyncTx::Close(_) => return None,
+        };
+        Some(accounts)
+    }
+
+    /// Returns the number of the leading transactions forming the wave of independent transactions.
+    /// The wave always contains at least one transaction unless `txs` is empty.
+    /// Only the transactions of the wave and the one following it are inspected.
+    pub fn independent_wave_len<'a>(&self, txs: impl IntoIterator<Item = &'a ZkSyncTx>) -> usize {
+        let mut touched = HashSet::new();
+        let mut wave_len = 0;
+        for (len, tx) in txs.into_iter().enumerate() {
+            let accounts = match self.tx_dependencies(tx) {
+                Some(accounts) => accounts,
+                None => return len.max(1),
+            };
+            if accounts.iter().any(|account| touched.contains(account)) {
+                return len;
+            }
+            touched.extend(accounts);
+            wave_len = len + 1;
+        }
+        wave_len
+    }
+
+    /// Creates the operation of the transaction without applying it.
+    pub fn prepare_tx(&self, tx: ZkSyncTx) -> Result<PreparedOp, OpError> {
+        Ok(match tx {
+            ZkSyncTx::Transfer(tx) => PreparedOp::Transfer(self.create_op(*tx)?),
+            ZkSyncTx::Withdraw(tx) => PreparedOp::Withdraw(self.create_op(*tx)?),
+            ZkSyncTx::ChangePubKey(tx) => PreparedOp::ChangePubKey(self.create_op(*tx)?),
+            ZkSyncTx::ForcedExit(tx) => PreparedOp::ForcedExit(self.create_op(*tx)?),
+            ZkSyncTx::Swap(tx) => PreparedOp::Swap(self.create_op(*tx)?),
+            ZkSyncTx::MintNFT(tx) => PreparedOp::MintNFT(self.create_op(*tx)?),
+            ZkSyncTx::WithdrawNFT(tx) => PreparedOp::WithdrawNFT(self.create_op(*tx)?),
+            ZkSyncTx::Close(_) => {
+                return Err(OpError::CloseOpError(CloseOpError::CloseOperationsDisabled))
+            }
+        })
+    }
+
+    /// Creates the operations of the wave of independent transactions in parallel,
+    /// on the current `rayon` thread pool.
+    pub fn prepare_txs_wave<T>(&self, txs: &[T]) -> Vec<Result<PreparedOp, OpError>>
+    where
+        T: Borrow<ZkSyncTx> + Sync,
+    {
+        debug_assert_eq!(
+            self.independent_wave_len(txs.iter().map(Borrow::borrow)),
+            txs.len()
+        );
+        txs.par_iter()
+            .map(|tx| self.prepare_tx(tx.borrow().clone()))
+            .collect()
+    }
+
+    /// Applies the operation prepared from the transaction.
+    /// Equivalent to [`ZkSyncState::execute_tx`] if the operation is created against the current state
+    /// of the accounts of the transaction.
+    pub fn execute_prepared_tx(
+        &mut self,
+        tx: &ZkSyncTx,
+        prepared: Result<PreparedOp, OpError>,
+        block_timestamp: u64,
+    ) -> Result<OpSuccess, OpError> {
+        tx.check_timestamp(block_timestamp)?;
+
+        let (fee, updates, executed_op) = match prepared? {
+            PreparedOp::Transfer(op) => {
+                let (fee, updates) = <Self as TxHandler<Transfer>>::apply_op(self, &op)?;
+                (fee, updates, op.into_franklin_op())
+            }
+            PreparedOp::Withdraw(op) => {
+                let (fee, updates) = <Self as TxHandler<Withdraw>>::apply_op(self, &op)?;
+                (fee, updates, op.into())
+            }
+            PreparedOp::ChangePubKey(op) => {
+                let (fee, updates) = <Self as TxHandler<ChangePubKey>>::apply_op(self, &op)?;
+                (fee, updates, op.into())
+            }
+            PreparedOp::ForcedExit(op) => {
+                let (fee, updates) = <Self as TxHandler<ForcedExit>>::apply_op(self, &op)?;
+                (fee, updates, op.into())
+            }
+            PreparedOp::Swap(op) => {
+                let (fee, updates) = <Self as TxHandler<Swap>>::apply_op(self, &op)?;
+                (fee, updates, op.into())
+            }
+            PreparedOp::MintNFT(op) => {
+                let (fee, updates) = <Self as TxHandler<MintNFT>>::apply_op(self, &op)?;
+                (fee, updates, op.into())
+            }
+            PreparedOp::WithdrawNFT(op) => {
+                let (fee, updates) = <Self as TxHandler<WithdrawNFT>>::apply_op(self, &op)?;
+                (fee, updates, op.into())
+            }
+        };
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op,
+        })
+    }
+}
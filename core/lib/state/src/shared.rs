@@ -0,0 +1,161 @@
+//! Read-only view of the committed state shared between the state keeper and the API.
+//!
+//! When the API and the core server run in the same process, the API can take the committed
+//! state of the accounts from the memory of the state keeper instead of loading it from the database.
+//! The state keeper builds a new immutable snapshot every time it stores the pending block, and
+//! the committer publishes it once the block is persisted, so the API never serves the state which
+//! would be lost on restart. Readers take the current snapshot by cloning an `Arc`, so neither side
+//! blocks the other. The snapshot is reset when the blocks are reverted.
+//!
+//! The snapshot contains only the accounts updated since the start of the server: the state
+//! keeper doesn't know in which block the rest of the accounts were updated last, so they still have
+//! to be loaded from the database.
+
+// Built-in uses
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+// Workspace uses
+use zksync_types::{Account, AccountId, Address, BlockNumber};
+
+/// Once the amount of layers exceeds this value, they are merged into one.
+const MAX_SNAPSHOT_LAYERS: usize = 64;
+
+/// Committed state of the account together with the last block that updated it.
+#[derive(Debug, Clone)]
+pub struct CommittedAccount {
+    pub account: Account,
+    pub last_update_block: BlockNumber,
+}
+
+/// Accounts updated by the single publication.
+/// The `None` values mark the removed accounts, so they shadow the older layers.
+#[derive(Debug, Default)]
+struct SnapshotLayer {
+    accounts: HashMap<AccountId, Option<Arc<CommittedAccount>>>,
+    account_ids: HashMap<Address, Option<AccountId>>,
+}
+
+/// Immutable snapshot of the accounts updated since the start of the server.
+///
+/// Every publication adds a new layer on top of the previous ones instead of copying
+/// the whole state, the layers are shared between the consequent snapshots.
+#[derive(Debug, Clone, Default)]
+pub struct CommittedStateSnapshot {
+    block_number: BlockNumber,
+    layers: Vec<Arc<SnapshotLayer>>,
+}
+
+impl CommittedStateSnapshot {
+    /// Number of the latest block (possibly the pending one) included into the snapshot.
+    pub fn block_number(&self) -> BlockNumber {
+        self.block_number
+    }
+
+    /// Returns the committed state of the account, `Some(None)` if the account was removed,
+    /// or `None` if the account wasn't updated since the start of the server.
+    pub fn account(&self, account_id: AccountId) -> Option<Option<Arc<CommittedAccount>>> {
+        self.layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.accounts.get(&account_id))
+            .cloned()
+    }
+
+    /// Returns the ID of the account with the given address, `Some(None)` if the account
+    /// was removed, or `None` if the address is not known to the snapshot.
+    pub fn account_id_by_address(&self, address: &Address) -> Option<Option<AccountId>> {
+        self.layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.account_ids.get(address))
+            .copied()
+    }
+
+    /// Creates the next snapshot with the given accounts updated in the block `block_number`.
+    /// The `None` value means that the account doesn't exist anymore.
+    pub fn with_updates(
+        &self,
+        block_number: BlockNumber,
+        updates: impl IntoIterator<Item = (AccountId, Option<Account>)>,
+    ) -> Self {
+        let mut layer = SnapshotLayer::default();
+        for (account_id, account) in updates {
+            match account {
+                Some(account) => {
+                    layer.account_ids.insert(account.address, Some(account_id));
+                    layer.accounts.insert(
+                        account_id,
+                        Some(Arc::new(CommittedAccount {
+                            account,
+                            last_update_block: block_number,
+                        })),
+                    );
+                }
+                None => {
+                    if let Some(Some(removed)) = self.account(account_id) {
+                        layer.account_ids.insert(removed.account.address, None);
+                    }
+                    layer.accounts.insert(account_id, None);
+                }
+            }
+        }
+
+        let mut layers = self.layers.clone();
+        layers.push(Arc::new(layer));
+        if layers.len() > MAX_SNAPSHOT_LAYERS {
+            layers = vec![Arc::new(Self::merge_layers(&layers))];
+        }
+
+        Self {
+            block_number,
+            layers,
+        }
+    }
+
+    fn merge_layers(layers: &[Arc<SnapshotLayer>]) -> SnapshotLayer {
+        let mut merged = SnapshotLayer::default();
+        for layer in layers {
+            merged.accounts.extend(
+                layer
+                    .accounts
+                    .iter()
+                    .map(|(id, account)| (*id, account.clone())),
+            );
+            merged.account_ids.extend(
+                layer
+                    .account_ids
+                    .iter()
+                    .map(|(address, id)| (*address, *id)),
+            );
+        }
+        // The removed accounts are kept: otherwise the readers would take them from the database.
+        merged
+    }
+}
+
+/// Handle to the latest committed state snapshot, published by the state keeper
+/// and consulted by the API.
+#[derive(Debug, Clone, Default)]
+pub struct SharedCommittedState(Arc<RwLock<Arc<CommittedStateSnapshot>>>);
+
+impl SharedCommittedState {
+    /// Returns the latest published snapshot.
+    pub fn snapshot(&self) -> Arc<CommittedStateSnapshot> {
+        self.0
+            .read()
+            .expect("committed state lock is poisoned")
+            .clone()
+    }
+
+    /// Replaces the current snapshot with the next one.
+    pub fn publish(&self, snapshot: CommittedStateSnapshot) {
+        *self.0.write().expect("committed state lock is poisoned") = Arc::new(snapshot);
+    }
+
+    /// Replaces the current snapshot with the empty one, so the readers fall back to the database.
+    pub fn reset(&self) {
+        self.publish(CommittedStateSnapshot::default());
+    }
+}
@@ -41,7 +41,7 @@ pub struct CollectedFee {
 }
 
 /// Helper enum to unify Transfer / TransferToNew operations.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum TransferOutcome {
     Transfer(TransferOp),
     TransferToNew(TransferToNewOp),
@@ -1,5 +1,7 @@
 mod collect_fee;
 mod operations;
+mod parallel;
+mod shared;
 mod timestamp;
 
 use crate::state::ZkSyncState;
@@ -221,3 +223,39 @@ fn test_tree_state() {
     // After we restored previously observed balance, root should be identical.
     assert_eq!(balance_root, restored_balance_root);
 }
+
+#[test]
+fn test_failure_codes() {
+    use crate::{
+        error::OpError,
+        handler::error::{ChangePubKeyOpError, SwapOpError, TransferOpError},
+    };
+    use zksync_types::tx::TxFailureCode;
+
+    let cases = vec![
+        (
+            OpError::from(TransferOpError::InsufficientBalance),
+            TxFailureCode::InsufficientBalance,
+        ),
+        (
+            OpError::from(TransferOpError::FromAccountLocked),
+            TxFailureCode::AccountLocked,
+        ),
+        (
+            OpError::from(ChangePubKeyOpError::InvalidAuthData),
+            TxFailureCode::InvalidPubKeyAuth,
+        ),
+        (
+            OpError::from(SwapOpError::OrderInvalidSignature),
+            TxFailureCode::InvalidSignature,
+        ),
+        (
+            OpError::from(SwapOpError::SelfSwap),
+            TxFailureCode::InvalidOperation,
+        ),
+        (OpError::TimestampError, TxFailureCode::InvalidTimestamp),
+    ];
+    for (error, code) in cases {
+        assert_eq!(error.failure_code(), code, "{}", error);
+    }
+}
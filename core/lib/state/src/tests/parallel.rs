@@ -0,0 +1,84 @@
+use crate::tests::{AccountState::*, PlasmaTestBuilder};
+use num::BigUint;
+use web3::types::Address;
+use zksync_crypto::PrivateKey;
+use zksync_types::{tx::Transfer, Account, AccountId, TokenId, ZkSyncTx};
+
+fn transfer(
+    (from_id, from, from_sk): &(AccountId, Account, PrivateKey),
+    to: Address,
+    amount: u32,
+    nonce_offset: u32,
+) -> ZkSyncTx {
+    Transfer::new_signed(
+        *from_id,
+        from.address,
+        to,
+        TokenId(0),
+        BigUint::from(amount),
+        BigUint::from(1u32),
+        from.nonce + nonce_offset,
+        Default::default(),
+        from_sk,
+    )
+    .unwrap()
+    .into()
+}
+
+/// Check that the transactions are split into the waves of the independent transactions,
+/// and that their parallel execution gives the same result as the sequential one.
+#[test]
+fn parallel_execution() {
+    let mut tb = PlasmaTestBuilder::new();
+    let accounts: Vec<_> = (0..4).map(|_| tb.add_account(Unlocked)).collect();
+    for (account_id, _, _) in &accounts {
+        tb.set_balance(*account_id, TokenId(0), 100u32);
+    }
+    let address = |index: usize| accounts[index].1.address;
+
+    let txs = vec![
+        transfer(&accounts[0], address(1), 10, 0),
+        // Fails because of the insufficient balance, the failure doesn't affect the wave.
+        transfer(&accounts[2], address(3), 1000, 0),
+        // Depends on the first transfer.
+        transfer(&accounts[1], address(2), 10, 0),
+        // Creates the account, so it's executed on its own.
+        transfer(&accounts[3], Address::repeat_byte(0x11), 10, 0),
+        transfer(&accounts[0], address(3), 10, 1),
+    ];
+    assert_eq!(tb.state.independent_wave_len(&txs), 2);
+    assert_eq!(tb.state.independent_wave_len(&txs[2..]), 1);
+    assert_eq!(tb.state.independent_wave_len(&txs[3..]), 1);
+    assert_eq!(tb.state.independent_wave_len(&[] as &[ZkSyncTx]), 0);
+
+    let mut sequential_state = tb.state.clone();
+    let sequential_results: Vec<_> = txs
+        .iter()
+        .map(|tx| sequential_state.execute_tx(tx.clone(), tb.block_timestamp))
+        .collect();
+
+    let mut parallel_results = Vec::new();
+    let mut executed = 0;
+    while executed < txs.len() {
+        let wave_len = tb.state.independent_wave_len(&txs[executed..]);
+        let wave = &txs[executed..executed + wave_len];
+        for (tx, prepared) in wave.iter().zip(tb.state.prepare_txs_wave(wave)) {
+            let result = tb
+                .state
+                .execute_prepared_tx(tx, prepared, tb.block_timestamp);
+            parallel_results.push(result);
+        }
+        executed += wave_len;
+    }
+
+    assert_eq!(parallel_results.len(), sequential_results.len());
+    for (parallel, sequential) in parallel_results.iter().zip(&sequential_results) {
+        match (parallel, sequential) {
+            (Ok(parallel), Ok(sequential)) => assert_eq!(parallel.updates, sequential.updates),
+            (Err(parallel), Err(sequential)) => assert_eq!(parallel, sequential),
+            _ => panic!("Parallel and sequential execution results differ"),
+        }
+    }
+    assert!(sequential_results[1].is_err());
+    assert_eq!(tb.state.root_hash(), sequential_state.root_hash());
+}
@@ -0,0 +1,96 @@
+use crate::shared::{CommittedStateSnapshot, SharedCommittedState};
+use web3::types::H160;
+use zksync_types::{Account, AccountId, BlockNumber, Nonce};
+
+/// Checks that the newer layers of the snapshot shadow the older ones
+/// and the published snapshots are not affected by the later updates.
+#[test]
+fn snapshot_layers() {
+    let shared = SharedCommittedState::default();
+    let first = Account::default_with_address(&H160::from_low_u64_be(1));
+    let second = Account::default_with_address(&H160::from_low_u64_be(2));
+
+    let snapshot = shared.snapshot().with_updates(
+        BlockNumber(1),
+        vec![
+            (AccountId(0), Some(first.clone())),
+            (AccountId(1), Some(second.clone())),
+        ],
+    );
+    shared.publish(snapshot);
+    let old_snapshot = shared.snapshot();
+
+    let mut updated = first.clone();
+    updated.nonce = Nonce(5);
+    let snapshot = shared.snapshot().with_updates(
+        BlockNumber(2),
+        vec![(AccountId(0), Some(updated)), (AccountId(1), None)],
+    );
+    shared.publish(snapshot);
+    let snapshot = shared.snapshot();
+
+    assert_eq!(snapshot.block_number(), BlockNumber(2));
+    let account = snapshot.account(AccountId(0)).unwrap().unwrap();
+    assert_eq!(account.account.nonce, Nonce(5));
+    assert_eq!(account.last_update_block, BlockNumber(2));
+    assert!(matches!(snapshot.account(AccountId(1)), Some(None)));
+    assert_eq!(snapshot.account_id_by_address(&second.address), Some(None));
+    assert!(snapshot.account(AccountId(2)).is_none());
+
+    assert_eq!(old_snapshot.block_number(), BlockNumber(1));
+    assert_eq!(
+        old_snapshot
+            .account(AccountId(0))
+            .unwrap()
+            .unwrap()
+            .account
+            .nonce,
+        Nonce(0)
+    );
+    assert_eq!(
+        old_snapshot.account_id_by_address(&second.address),
+        Some(Some(AccountId(1)))
+    );
+
+    shared.reset();
+    let snapshot = shared.snapshot();
+    assert_eq!(snapshot.block_number(), BlockNumber(0));
+    assert!(snapshot.account(AccountId(0)).is_none());
+    assert!(snapshot.account_id_by_address(&second.address).is_none());
+}
+
+/// Checks that the state, including the removed accounts, is preserved once the layers are merged.
+#[test]
+fn snapshot_layers_merge() {
+    let mut snapshot = CommittedStateSnapshot::default();
+    let account = Account::default_with_address(&H160::from_low_u64_be(1));
+    snapshot = snapshot.with_updates(BlockNumber(1), vec![(AccountId(0), Some(account))]);
+    for block in 2..200 {
+        let account = Account::default_with_address(&H160::from_low_u64_be(block));
+        snapshot = snapshot.with_updates(
+            BlockNumber(block as u32),
+            vec![(AccountId(block as u32), Some(account))],
+        );
+    }
+    snapshot = snapshot.with_updates(BlockNumber(200), vec![(AccountId(100), None)]);
+
+    // Merge the layers once again, the removed account must not disappear from the snapshot.
+    for block in 201..300 {
+        snapshot = snapshot.with_updates(
+            BlockNumber(block),
+            Vec::<(AccountId, Option<Account>)>::new(),
+        );
+    }
+
+    let account = snapshot.account(AccountId(0)).unwrap().unwrap();
+    assert_eq!(account.last_update_block, BlockNumber(1));
+    assert_eq!(
+        snapshot.account_id_by_address(&H160::from_low_u64_be(150)),
+        Some(Some(AccountId(150)))
+    );
+    assert!(matches!(snapshot.account(AccountId(100)), Some(None)));
+    assert_eq!(
+        snapshot.account_id_by_address(&H160::from_low_u64_be(100)),
+        Some(None)
+    );
+}
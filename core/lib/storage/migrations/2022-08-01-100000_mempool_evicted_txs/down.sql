@@ -0,0 +1 @@
+DROP TABLE IF EXISTS mempool_evicted_txs;
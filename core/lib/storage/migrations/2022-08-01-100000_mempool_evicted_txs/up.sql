@@ -0,0 +1,8 @@
+CREATE TABLE mempool_evicted_txs (
+    tx_hash TEXT PRIMARY KEY,
+    batch_id BIGINT NOT NULL DEFAULT 0,
+    reason TEXT NOT NULL,
+    evicted_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS ix_mempool_evicted_txs_evicted_at ON public.mempool_evicted_txs USING btree (evicted_at);
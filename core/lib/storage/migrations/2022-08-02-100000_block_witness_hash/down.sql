@@ -0,0 +1 @@
+ALTER TABLE block_witness DROP COLUMN witness_hash;
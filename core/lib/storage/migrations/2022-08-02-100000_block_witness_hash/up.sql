@@ -0,0 +1 @@
+ALTER TABLE block_witness ADD COLUMN witness_hash BYTEA;
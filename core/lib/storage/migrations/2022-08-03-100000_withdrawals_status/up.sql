@@ -0,0 +1,10 @@
+CREATE TABLE withdrawals_status (
+    tx_hash BYTEA PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    aggregated_op_id BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    eth_tx_hash BYTEA,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS ix_withdrawals_status_aggregated_op_id ON public.withdrawals_status USING btree (aggregated_op_id);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS ix_tx_filters_address_token_sequence_number;
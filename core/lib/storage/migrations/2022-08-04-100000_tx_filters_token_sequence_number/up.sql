@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS ix_tx_filters_address_token_sequence_number ON public.tx_filters USING btree (address, token, sequence_number) include(is_priority);
@@ -0,0 +1,10 @@
+CREATE TABLE api_keys (
+    id BIGSERIAL PRIMARY KEY,
+    -- Keys themselves are not stored, only their keccak256 hashes.
+    key_hash BYTEA NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    requests_per_second INTEGER NOT NULL,
+    requests_per_day BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
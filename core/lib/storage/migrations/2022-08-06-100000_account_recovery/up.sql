@@ -0,0 +1,18 @@
+CREATE TABLE account_guardians (
+    account_id BIGINT NOT NULL,
+    guardian BYTEA NOT NULL,
+    PRIMARY KEY (account_id, guardian)
+);
+
+CREATE TABLE account_recoveries (
+    id BIGSERIAL PRIMARY KEY,
+    account_id BIGINT NOT NULL,
+    new_pub_key_hash BYTEA NOT NULL,
+    -- Address of the guardian that initiated the recovery.
+    initiated_by BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- `ChangePubKey` to the new key is not accepted if it may be executed before this moment.
+    executable_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    cancelled_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX account_recoveries_account_id_idx ON account_recoveries (account_id);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS operator_key_audit_log;
+DROP FUNCTION IF EXISTS operator_key_audit_log_append_only;
@@ -0,0 +1,29 @@
+-- Every use of the operator Ethereum key by `eth_sender`, including the rejected ones.
+CREATE TABLE operator_key_audit_log
+(
+    id               BIGSERIAL PRIMARY KEY,
+    eth_op_id        BIGINT    NOT NULL,
+    op_type          TEXT      NOT NULL,
+    contract_address BYTEA     NOT NULL,
+    payload_hash     BYTEA     NOT NULL,
+    nonce            NUMERIC   NOT NULL,
+    gas_limit        NUMERIC   NOT NULL,
+    gas_price        NUMERIC   NOT NULL,
+    value            NUMERIC   NOT NULL,
+    tx_hash          BYTEA,
+    outcome          TEXT      NOT NULL,
+    reason           TEXT,
+    created_at       TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX operator_key_audit_log_eth_op_id_idx ON operator_key_audit_log (eth_op_id);
+
+-- The log is append-only.
+CREATE FUNCTION operator_key_audit_log_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'operator_key_audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER operator_key_audit_log_append_only
+    BEFORE UPDATE OR DELETE ON operator_key_audit_log
+    FOR EACH ROW EXECUTE PROCEDURE operator_key_audit_log_append_only();
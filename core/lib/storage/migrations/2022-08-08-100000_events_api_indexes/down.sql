@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS ix_executed_priority_operations_type_sequence_number;
+DROP INDEX IF EXISTS ix_executed_transactions_type_sequence_number;
+DROP INDEX IF EXISTS ix_tx_filters_token_sequence_number;
@@ -0,0 +1,5 @@
+-- Indexes for the events API: the events are filtered by the token and the type of the operation
+-- and ordered by the sequence number.
+CREATE INDEX IF NOT EXISTS ix_tx_filters_token_sequence_number ON public.tx_filters USING btree (token, sequence_number) include(is_priority);
+CREATE INDEX IF NOT EXISTS ix_executed_transactions_type_sequence_number ON public.executed_transactions USING btree ((tx->>'type'), sequence_number);
+CREATE INDEX IF NOT EXISTS ix_executed_priority_operations_type_sequence_number ON public.executed_priority_operations USING btree ((operation->>'type'), sequence_number);
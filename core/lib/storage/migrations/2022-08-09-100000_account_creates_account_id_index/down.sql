@@ -0,0 +1 @@
+DROP INDEX IF EXISTS account_creates_account_id_idx;
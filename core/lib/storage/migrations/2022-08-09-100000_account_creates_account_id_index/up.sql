@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS account_creates_account_id_idx ON account_creates (account_id);
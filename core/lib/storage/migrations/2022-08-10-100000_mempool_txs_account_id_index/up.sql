@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS mempool_txs_account_id_idx ON mempool_txs (
+    (COALESCE(tx->>'accountId', tx->>'initiatorAccountId', tx->>'creatorId', tx->>'submitterId')::bigint)
+);
@@ -0,0 +1,8 @@
+-- Prices of the tokens fetched by the fee ticker, `ticker_price` keeps only the latest one.
+CREATE TABLE ticker_price_history (
+    id BIGSERIAL PRIMARY KEY,
+    token_id INTEGER NOT NULL REFERENCES tokens(id) ON UPDATE CASCADE,
+    usd_price NUMERIC NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL
+);
+CREATE INDEX IF NOT EXISTS ticker_price_history_token_id_created_at_idx ON ticker_price_history (token_id, created_at);
@@ -0,0 +1,14 @@
+CREATE TABLE encrypted_txs (
+    -- Keccak256 hash of the encrypted envelope.
+    envelope_hash BYTEA PRIMARY KEY,
+    envelope BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- The envelope is not decrypted before this moment.
+    reveal_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revealed_at TIMESTAMP WITH TIME ZONE,
+    -- Hashes of the transactions submitted to the mempool once the envelope is revealed.
+    tx_hashes BYTEA[] NOT NULL DEFAULT '{}',
+    -- Reason the envelope couldn't be decrypted or its transactions were rejected.
+    error TEXT
+);
+CREATE INDEX encrypted_txs_reveal_at_idx ON encrypted_txs (reveal_at) WHERE revealed_at IS NULL;
@@ -0,0 +1,8 @@
+-- Overrides of the token properties set by the operator via the admin API.
+CREATE TABLE token_admin_settings (
+    token_id INTEGER PRIMARY KEY REFERENCES tokens (id) ON DELETE CASCADE,
+    -- `NULL` means the eligibility is decided by the market volume of the token.
+    fee_eligible BOOLEAN,
+    disabled BOOLEAN NOT NULL DEFAULT false,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
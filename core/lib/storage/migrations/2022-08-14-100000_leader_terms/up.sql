@@ -0,0 +1,9 @@
+-- Leadership term of the core server instances, used as the fencing token.
+-- The table always contains a single row.
+CREATE TABLE leader_terms (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    term BIGINT NOT NULL,
+    leader TEXT NOT NULL,
+    elected_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+INSERT INTO leader_terms ( term, leader, elected_at ) VALUES ( 0, '', now() );
@@ -0,0 +1,7 @@
+-- Protocol parameters in force starting from the `from_block` until the next update.
+CREATE TABLE protocol_parameters (
+    from_block BIGINT PRIMARY KEY,
+    block_chunk_sizes BIGINT[] NOT NULL,
+    max_withdrawals_per_execute_tx BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
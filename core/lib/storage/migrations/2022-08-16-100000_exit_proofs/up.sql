@@ -0,0 +1,13 @@
+-- Exit proofs requested via API, generated asynchronously by the exit proof generator.
+-- The job is queued until `started_at` is set and is finished once either `proof` or `error` is set.
+CREATE TABLE exit_proofs (
+    account_id BIGINT NOT NULL,
+    token_id INTEGER NOT NULL,
+    block_number BIGINT NOT NULL,
+    proof JSONB,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    started_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (account_id, token_id, block_number)
+);
+CREATE INDEX exit_proofs_created_at_idx ON exit_proofs (created_at);
@@ -0,0 +1,2 @@
+-- The transaction is not executed until the block with the given number is committed.
+ALTER TABLE mempool_txs ADD COLUMN not_before_block BIGINT;
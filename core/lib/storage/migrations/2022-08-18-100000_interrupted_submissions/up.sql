@@ -0,0 +1,7 @@
+-- Submissions that were in progress when the API server was shut down, the clients
+-- use them to decide whether the transaction has to be resubmitted.
+CREATE TABLE interrupted_submissions (
+    tx_hash TEXT PRIMARY KEY,
+    stage TEXT NOT NULL,
+    interrupted_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,8 @@
+-- State of the Ethereum watcher, so it continues from the last processed Ethereum block on restart.
+-- The table contains at most a single row.
+CREATE TABLE eth_watch_state (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    last_ethereum_block BIGINT NOT NULL,
+    next_priority_op_id BIGINT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE eth_operations DROP sender_address;
+DROP TABLE eth_operator_nonces;
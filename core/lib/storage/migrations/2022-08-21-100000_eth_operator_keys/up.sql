@@ -0,0 +1,9 @@
+-- Address of the additional operator key the transactions of the operation are signed with,
+-- `NULL` for the main operator key.
+ALTER TABLE eth_operations ADD sender_address bytea;
+
+-- Nonces of the additional operator keys, the one of the main key is stored in `eth_parameters`.
+CREATE TABLE eth_operator_nonces (
+    address bytea PRIMARY KEY,
+    nonce BIGINT NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE eth_operations DROP first_deadline_block;
@@ -0,0 +1,6 @@
+-- Deadline block of the first transaction of the operation, the hard deadline
+-- of its confirmation is counted from the block the operation was first sent at.
+-- The operations sent before the column was added count it from their latest deadline.
+ALTER TABLE eth_operations ADD first_deadline_block BIGINT;
+UPDATE eth_operations SET first_deadline_block = last_deadline_block;
+ALTER TABLE eth_operations ALTER COLUMN first_deadline_block SET NOT NULL;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS subsidies_subsidy_type_idx;
+ALTER TABLE subsidies DROP created_at;
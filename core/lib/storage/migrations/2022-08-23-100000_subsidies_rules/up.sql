@@ -0,0 +1,4 @@
+-- The subsidies are given by the fee subsidy rules as well. The budget of every rule
+-- is checked by its type, and the subsidized amounts are accounted by the time.
+ALTER TABLE subsidies ADD created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now();
+CREATE INDEX IF NOT EXISTS subsidies_subsidy_type_idx ON subsidies (subsidy_type);
@@ -0,0 +1 @@
+ALTER TABLE ticker_market_volume DROP COLUMN fee_allowed;
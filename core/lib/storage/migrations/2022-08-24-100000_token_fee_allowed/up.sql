@@ -0,0 +1,2 @@
+-- Result of the last liquidity check of the token, `NULL` if the token is not checked yet.
+ALTER TABLE ticker_market_volume ADD COLUMN fee_allowed BOOLEAN;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS fee_quotes_created_at_index;
+DROP TABLE IF EXISTS fee_quotes;
@@ -0,0 +1,17 @@
+-- Audit log of the fee quotes served by the API along with the inputs they are calculated from.
+CREATE TABLE fee_quotes (
+    id BIGSERIAL PRIMARY KEY,
+    -- Types of the transactions, a single one unless the quote is for a batch.
+    tx_types TEXT[] NOT NULL,
+    addresses BYTEA[] NOT NULL,
+    token_id INTEGER NOT NULL,
+    zkp_fee NUMERIC NOT NULL,
+    gas_fee NUMERIC NOT NULL,
+    total_fee NUMERIC NOT NULL,
+    subsidized BOOLEAN NOT NULL,
+    gas_price_wei NUMERIC NOT NULL,
+    token_usd_price NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX fee_quotes_created_at_index ON fee_quotes (created_at);
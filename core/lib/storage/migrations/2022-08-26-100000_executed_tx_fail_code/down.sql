@@ -0,0 +1 @@
+ALTER TABLE executed_transactions DROP COLUMN IF EXISTS fail_code;
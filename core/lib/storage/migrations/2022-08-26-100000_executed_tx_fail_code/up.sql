@@ -0,0 +1 @@
+ALTER TABLE executed_transactions ADD COLUMN fail_code TEXT;
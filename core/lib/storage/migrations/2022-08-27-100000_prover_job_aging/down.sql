@@ -0,0 +1 @@
+ALTER TABLE prover_job_queue DROP COLUMN oldest_priority_op_at;
@@ -0,0 +1 @@
+ALTER TABLE prover_job_queue ADD COLUMN oldest_priority_op_at TIMESTAMP WITH TIME ZONE;
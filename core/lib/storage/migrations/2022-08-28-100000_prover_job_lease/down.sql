@@ -0,0 +1,3 @@
+ALTER TABLE prover_job_queue DROP COLUMN reassignments;
+ALTER TABLE prover_job_queue DROP COLUMN lease_expires_at;
+ALTER TABLE prover_job_queue DROP COLUMN assigned_prover;
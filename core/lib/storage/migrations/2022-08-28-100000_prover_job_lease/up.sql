@@ -0,0 +1,9 @@
+ALTER TABLE prover_job_queue ADD COLUMN assigned_prover TEXT;
+ALTER TABLE prover_job_queue ADD COLUMN lease_expires_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE prover_job_queue ADD COLUMN reassignments INTEGER NOT NULL DEFAULT 0;
+
+-- The jobs in progress are leased to the prover which sent the last heartbeat,
+-- with the timeout the stale jobs were detected with before.
+UPDATE prover_job_queue
+SET (assigned_prover, lease_expires_at) = (updated_by, updated_at + INTERVAL '120 seconds')
+WHERE job_status = 1;
@@ -0,0 +1 @@
+ALTER TABLE api_keys DROP CONSTRAINT api_keys_quotas_non_negative;
@@ -0,0 +1,3 @@
+ALTER TABLE api_keys
+    ADD CONSTRAINT api_keys_quotas_non_negative
+    CHECK (requests_per_second >= 0 AND requests_per_day >= 0);
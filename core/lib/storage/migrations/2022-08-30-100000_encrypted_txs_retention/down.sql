@@ -0,0 +1 @@
+DROP INDEX IF EXISTS encrypted_txs_revealed_at_idx;
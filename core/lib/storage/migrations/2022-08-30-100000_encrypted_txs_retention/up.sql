@@ -0,0 +1,2 @@
+-- Revealed envelopes are removed once the retention period has passed.
+CREATE INDEX encrypted_txs_revealed_at_idx ON encrypted_txs (revealed_at) WHERE revealed_at IS NOT NULL;
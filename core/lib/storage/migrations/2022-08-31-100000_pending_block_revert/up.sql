@@ -0,0 +1,11 @@
+-- Revert of the blocks in progress, so it's finished after a crash. The Ethereum sender doesn't
+-- send the new operations while there is one. The table always contains a single row.
+CREATE TABLE pending_block_revert (
+    id BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    -- The blocks after this one are being reverted, NULL if there is no revert in progress.
+    last_correct_block BIGINT,
+    -- Transaction reverting the blocks on the contract, once it's sent.
+    revert_tx_hash BYTEA,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+INSERT INTO pending_block_revert ( updated_at ) VALUES ( now() );
@@ -0,0 +1,6 @@
+ALTER TABLE pending_block_revert
+    DROP COLUMN revert_tx_nonce,
+    DROP COLUMN revert_tx_hashes,
+    DROP COLUMN revert_tx_gas_price,
+    DROP COLUMN revert_tx_priority_fee;
+ALTER TABLE pending_block_revert ADD COLUMN revert_tx_hash BYTEA;
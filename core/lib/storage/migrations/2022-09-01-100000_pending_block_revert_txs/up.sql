@@ -0,0 +1,11 @@
+-- The revert transaction is recorded once it's sent, and it's replaced with the one paying
+-- the escalated fees while it's stuck, so any of the sent transactions may be mined.
+ALTER TABLE pending_block_revert DROP COLUMN revert_tx_hash;
+ALTER TABLE pending_block_revert
+    -- Nonce of the revert transaction and its replacements.
+    ADD COLUMN revert_tx_nonce BIGINT,
+    -- Hashes of the sent revert transactions, the last one is sent last.
+    ADD COLUMN revert_tx_hashes BYTEA[] NOT NULL DEFAULT '{}',
+    -- Fees of the last sent revert transaction, the priority fee is NULL for the legacy one.
+    ADD COLUMN revert_tx_gas_price NUMERIC,
+    ADD COLUMN revert_tx_priority_fee NUMERIC;
@@ -0,0 +1,4 @@
+DELETE FROM exit_proofs WHERE block_number IS NULL;
+ALTER TABLE exit_proofs DROP CONSTRAINT exit_proofs_pkey;
+ALTER TABLE exit_proofs ALTER COLUMN block_number SET NOT NULL;
+ALTER TABLE exit_proofs ADD PRIMARY KEY (account_id, token_id, block_number);
@@ -0,0 +1,10 @@
+-- Exit proofs are keyed by the account and the token, the proof is generated against the last
+-- finalized block at the time the job is taken, and requested again once it becomes outdated.
+-- Only the latest proof of every balance is kept, the queued jobs are proven against the current block.
+DELETE FROM exit_proofs AS older USING exit_proofs AS newer
+WHERE older.account_id = newer.account_id AND older.token_id = newer.token_id
+    AND older.block_number < newer.block_number;
+ALTER TABLE exit_proofs DROP CONSTRAINT exit_proofs_pkey;
+ALTER TABLE exit_proofs ALTER COLUMN block_number DROP NOT NULL;
+UPDATE exit_proofs SET block_number = NULL WHERE proof IS NULL;
+ALTER TABLE exit_proofs ADD PRIMARY KEY (account_id, token_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS encrypted_txs_submitted_by_idx;
+ALTER TABLE encrypted_txs DROP COLUMN submitted_by;
+ALTER TABLE encrypted_txs DROP COLUMN claimed_at;
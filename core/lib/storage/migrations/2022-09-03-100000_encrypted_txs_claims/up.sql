@@ -0,0 +1,7 @@
+-- Envelopes are claimed by the revealer before their transactions are submitted, the claim
+-- expires if the revealer is stopped before storing the results.
+ALTER TABLE encrypted_txs ADD COLUMN claimed_at TIMESTAMP WITH TIME ZONE;
+-- IP of the client that submitted the envelope, the number of the pending envelopes per client
+-- is limited.
+ALTER TABLE encrypted_txs ADD COLUMN submitted_by TEXT;
+CREATE INDEX encrypted_txs_submitted_by_idx ON encrypted_txs (submitted_by) WHERE revealed_at IS NULL;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS mempool_txs_account_id_nonce_idx;
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS nonce;
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS account_id;
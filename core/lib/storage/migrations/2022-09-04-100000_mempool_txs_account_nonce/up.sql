@@ -0,0 +1,7 @@
+-- The account and the nonce of the transaction, so the transactions replacing each other
+-- are found by the index rather than by scanning the serialized transactions.
+ALTER TABLE mempool_txs ADD COLUMN account_id BIGINT GENERATED ALWAYS AS (
+    COALESCE(tx->>'accountId', tx->>'initiatorAccountId', tx->>'creatorId', tx->>'submitterId')::bigint
+) STORED;
+ALTER TABLE mempool_txs ADD COLUMN nonce BIGINT GENERATED ALWAYS AS ((tx->>'nonce')::bigint) STORED;
+CREATE INDEX mempool_txs_account_id_nonce_idx ON mempool_txs (account_id, nonce);
@@ -0,0 +1,108 @@
+// Built-in deps
+use std::{convert::TryFrom, time::Instant};
+// External imports
+use anyhow::format_err;
+use chrono::Utc;
+// Local imports
+use self::records::StoredApiKey;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// API keys schema contains the keys issued to the API clients along with their request quotas.
+#[derive(Debug)]
+pub struct ApiKeysSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ApiKeysSchema<'a, 'c> {
+    /// Stores the new API key, only the hash of the key is persisted.
+    pub async fn store_api_key(
+        &mut self,
+        key_hash: &[u8],
+        name: &str,
+        requests_per_second: u32,
+        requests_per_day: u64,
+    ) -> QueryResult<StoredApiKey> {
+        let start = Instant::now();
+        let requests_per_second = i32::try_from(requests_per_second)
+            .map_err(|_| format_err!("Requests per second quota is too big"))?;
+        let requests_per_day = i64::try_from(requests_per_day)
+            .map_err(|_| format_err!("Requests per day quota is too big"))?;
+        let api_key = sqlx::query_as!(
+            StoredApiKey,
+            r#"
+            INSERT INTO api_keys ( key_hash, name, requests_per_second, requests_per_day, created_at )
+            VALUES ( $1, $2, $3, $4, $5 )
+            RETURNING *
+            "#,
+            key_hash,
+            name,
+            requests_per_second,
+            requests_per_day,
+            Utc::now()
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_keys.store_api_key", start.elapsed());
+        Ok(api_key)
+    }
+
+    /// Loads the API key by its hash, revoked keys are not returned.
+    pub async fn get_active_api_key(
+        &mut self,
+        key_hash: &[u8],
+    ) -> QueryResult<Option<StoredApiKey>> {
+        let start = Instant::now();
+        let api_key = sqlx::query_as!(
+            StoredApiKey,
+            r#"
+            SELECT * FROM api_keys
+            WHERE key_hash = $1 AND revoked_at IS NULL
+            "#,
+            key_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_keys.get_active_api_key", start.elapsed());
+        Ok(api_key)
+    }
+
+    /// Loads all the issued API keys, including the revoked ones.
+    pub async fn load_api_keys(&mut self) -> QueryResult<Vec<StoredApiKey>> {
+        let start = Instant::now();
+        let api_keys = sqlx::query_as!(
+            StoredApiKey,
+            r#"
+            SELECT * FROM api_keys
+            ORDER BY id
+            "#
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_keys.load_api_keys", start.elapsed());
+        Ok(api_keys)
+    }
+
+    /// Revokes the API key. Returns the hash of the revoked key,
+    /// `None` if there is no such key or it's already revoked.
+    pub async fn revoke_api_key(&mut self, id: i64) -> QueryResult<Option<Vec<u8>>> {
+        let start = Instant::now();
+        let key_hash = sqlx::query!(
+            r#"
+            UPDATE api_keys SET revoked_at = $2
+            WHERE id = $1 AND revoked_at IS NULL
+            RETURNING key_hash
+            "#,
+            id,
+            Utc::now()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.key_hash);
+
+        metrics::histogram!("sql.api_keys.revoke_api_key", start.elapsed());
+        Ok(key_hash)
+    }
+}
@@ -0,0 +1,13 @@
+// External imports
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone)]
+pub struct StoredApiKey {
+    pub id: i64,
+    pub key_hash: Vec<u8>,
+    pub name: String,
+    pub requests_per_second: i32,
+    pub requests_per_day: i64,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
@@ -1,4 +1,5 @@
 // Built-in deps
+use std::collections::HashMap;
 use std::time::Instant;
 // External imports
 use num::{BigUint, Zero};
@@ -6,7 +7,8 @@ use sqlx::{types::BigDecimal, Acquire};
 // Workspace imports
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID};
 use zksync_types::{
-    Account, AccountId, AccountUpdates, Address, BlockNumber, Nonce, PubKeyHash, TokenId,
+    Account, AccountId, AccountUpdate, AccountUpdates, Address, BlockNumber, Nonce, PubKeyHash,
+    TokenId,
 };
 // Local imports
 use self::records::*;
@@ -19,7 +21,7 @@ pub mod restore_account;
 mod stored_state;
 
 pub(crate) use self::restore_account::restore_account;
-pub use self::stored_state::StoredAccountState;
+pub use self::stored_state::{StoredAccountState, StoredAccountStates};
 use crate::tokens::records::StorageNFT;
 use num::bigint::ToBigInt;
 
@@ -327,6 +329,229 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(((last_block, account), account_state))
     }
 
+    /// Loads both finalized and committed states for the set of accounts.
+    /// Unlike `last_committed_state_for_account`, the number of queries does not depend
+    /// on the number of accounts. Unknown accounts are not included into the result.
+    pub async fn account_states_by_ids(
+        &mut self,
+        account_ids: &[AccountId],
+    ) -> QueryResult<HashMap<AccountId, StoredAccountStates>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let ids: Vec<i64> = account_ids.iter().map(|id| i64::from(**id)).collect();
+        let creator_ids: Vec<i32> = account_ids.iter().map(|id| **id as i32).collect();
+
+        // Finalized states, see `account_and_last_block`.
+        let stored_accounts = sqlx::query_as!(
+            StorageAccount,
+            "
+                SELECT * FROM accounts
+                WHERE id = ANY($1)
+            ",
+            &ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let stored_balances = sqlx::query_as!(
+            StorageBalance,
+            "
+                SELECT * FROM balances
+                WHERE account_id = ANY($1)
+            ",
+            &ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let mut balances: HashMap<i64, Vec<StorageBalance>> = HashMap::new();
+        for balance in stored_balances {
+            balances
+                .entry(balance.account_id)
+                .or_default()
+                .push(balance);
+        }
+        let mut finalized: HashMap<AccountId, (i64, Account)> = stored_accounts
+            .iter()
+            .map(|stored_account| {
+                let balances = balances.remove(&stored_account.id).unwrap_or_default();
+                let (account_id, account) = restore_account(stored_account, balances);
+                (account_id, (stored_account.last_block, account))
+            })
+            .collect();
+        let nfts = sqlx::query_as!(
+            StorageNFT,
+            "
+                SELECT nft.*, tokens.symbol FROM nft
+                INNER JOIN tokens
+                ON tokens.id = nft.token_id
+                WHERE creator_account_id = ANY($1)
+            ",
+            &creator_ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        for nft in nfts {
+            if let Some((_, account)) = finalized.get_mut(&AccountId(nft.creator_account_id as u32))
+            {
+                account
+                    .minted_nfts
+                    .insert(TokenId(nft.token_id as u32), nft.into());
+            }
+        }
+
+        let last_verified_block = BlockSchema(&mut transaction)
+            .get_last_verified_confirmed_block()
+            .await?
+            .0 as i64;
+
+        let account_balance_diff = sqlx::query_as!(
+            StorageAccountUpdate,
+            "
+                SELECT * FROM account_balance_updates
+                WHERE account_id = ANY($1) AND block_number > $2
+            ",
+            &ids,
+            last_verified_block
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let account_creation_diff = sqlx::query_as!(
+            StorageAccountCreation,
+            "
+                SELECT * FROM account_creates
+                WHERE account_id = ANY($1) AND block_number > $2
+            ",
+            &ids,
+            last_verified_block
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let account_pubkey_diff = sqlx::query_as!(
+            StorageAccountPubkeyUpdate,
+            "
+                SELECT * FROM account_pubkey_updates
+                WHERE account_id = ANY($1) AND block_number > $2
+            ",
+            &ids,
+            last_verified_block
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let mint_nft_updates = sqlx::query_as!(
+            StorageMintNFTUpdate,
+            "
+                SELECT * FROM mint_nft_updates
+                WHERE creator_account_id = ANY($1) AND block_number > $2
+            ",
+            &creator_ids,
+            last_verified_block
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut account_diff = Vec::new();
+        account_diff.extend(
+            account_balance_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_creation_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_pubkey_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(mint_nft_updates.into_iter().map(StorageAccountDiff::from));
+        account_diff.sort_by(StorageAccountDiff::cmp_order);
+
+        // Apply the diffs on top of the finalized states, tracking the last updated block.
+        let mut committed: HashMap<AccountId, (i64, Option<Account>)> = finalized
+            .iter()
+            .map(|(id, (last_block, account))| (*id, (*last_block, Some(account.clone()))))
+            .collect();
+        for diff in account_diff {
+            let block_number = diff.block_number();
+            let (account_id, update): (AccountId, AccountUpdate) = diff.into();
+            let (last_block, account) = committed.entry(account_id).or_insert((0, None));
+            *last_block = block_number;
+            *account = Account::apply_update(account.take(), update);
+        }
+
+        let account_types = AccountSchema(&mut transaction)
+            .account_types_by_ids(&ids)
+            .await?;
+        transaction.commit().await?;
+
+        let states = committed
+            .into_iter()
+            .map(|(account_id, (committed_block, committed))| {
+                let states = StoredAccountStates {
+                    finalized: finalized
+                        .remove(&account_id)
+                        .map(|(block, account)| (BlockNumber(block as u32), account)),
+                    committed: committed
+                        .map(|account| (BlockNumber(committed_block as u32), account)),
+                    account_type: account_types.get(&account_id).copied(),
+                };
+                (account_id, states)
+            })
+            .collect();
+
+        metrics::histogram!("sql.chain.account.account_states_by_ids", start.elapsed());
+        Ok(states)
+    }
+
+    /// Batched version of `account_type_by_id`, accounts without the type are omitted.
+    async fn account_types_by_ids(
+        &mut self,
+        ids: &[i64],
+    ) -> QueryResult<HashMap<AccountId, EthAccountType>> {
+        let db_account_types = sqlx::query_as!(
+            StorageAccountType,
+            r#"
+            SELECT account_id, account_type as "account_type!: DbAccountType"
+            FROM eth_account_types WHERE account_id = ANY($1)
+            "#,
+            ids
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let mut pub_key_hashes: HashMap<i64, PubKeyHash> = sqlx::query!(
+            "
+            SELECT account_id, pub_key_hash
+            FROM no_2fa_pub_key_hash WHERE account_id = ANY($1)
+            ",
+            ids
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| {
+            let pub_key_hash = PubKeyHash::from_hex(&record.pub_key_hash).unwrap();
+            (record.account_id, pub_key_hash)
+        })
+        .collect();
+
+        Ok(db_account_types
+            .into_iter()
+            .map(|record| {
+                let pub_key_hash = match record.account_type {
+                    DbAccountType::No2FA => pub_key_hashes.remove(&record.account_id),
+                    _ => None,
+                };
+                (
+                    AccountId(record.account_id as u32),
+                    EthAccountType::from_db(record.account_type, pub_key_hash),
+                )
+            })
+            .collect())
+    }
+
     /// Loads the last verified state for the account (i.e. the one obtained in the last block
     /// which was both committed and verified).
     pub async fn last_verified_state_for_account(
@@ -430,6 +655,43 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(account_id)
     }
 
+    /// Batched version of `account_id_by_address`, addresses without an account are omitted.
+    pub async fn account_ids_by_addresses(
+        &mut self,
+        addresses: &[Address],
+    ) -> QueryResult<HashMap<Address, AccountId>> {
+        let start = Instant::now();
+        let addresses: Vec<Vec<u8>> = addresses
+            .iter()
+            .map(|address| address.as_bytes().to_vec())
+            .collect();
+        let account_ids = sqlx::query!(
+            r#"
+                SELECT DISTINCT ON (address) address, account_id FROM account_creates
+                WHERE address = ANY($1) AND is_create = $2
+                ORDER BY address, block_number DESC
+            "#,
+            &addresses,
+            true
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| {
+            (
+                Address::from_slice(&record.address),
+                AccountId(record.account_id as u32),
+            )
+        })
+        .collect();
+
+        metrics::histogram!(
+            "sql.chain.account.account_ids_by_addresses",
+            start.elapsed()
+        );
+        Ok(account_ids)
+    }
+
     pub async fn account_address_by_id(
         &mut self,
         account_id: AccountId,
@@ -593,4 +855,88 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         metrics::histogram!("sql.chain.account.get_nft_owner", start.elapsed());
         Ok(owner_id)
     }
+
+    /// Returns up to `count` randomly chosen IDs of the existing accounts.
+    pub async fn sample_account_ids(&mut self, count: u32) -> QueryResult<Vec<AccountId>> {
+        let start = Instant::now();
+        let account_ids = sqlx::query!(
+            "SELECT id FROM accounts ORDER BY random() LIMIT $1",
+            i64::from(count)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| AccountId(record.id as u32))
+        .collect();
+
+        metrics::histogram!("sql.chain.account.sample_account_ids", start.elapsed());
+        Ok(account_ids)
+    }
+
+    /// Loads the balances of the account from the `balances` table, i.e. the finalized ones.
+    pub async fn stored_account_balances(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<HashMap<TokenId, BigUint>> {
+        let start = Instant::now();
+        let balances = sqlx::query_as!(
+            StorageBalance,
+            "SELECT * FROM balances WHERE account_id = $1",
+            i64::from(*account_id)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|balance| {
+            (
+                TokenId(balance.coin_id as u32),
+                balance.balance.to_bigint().unwrap().to_biguint().unwrap(),
+            )
+        })
+        .collect();
+
+        metrics::histogram!("sql.chain.account.stored_account_balances", start.elapsed());
+        Ok(balances)
+    }
+
+    /// Loads the balance changes of the account up to the block (inclusive) in the order of applying.
+    pub async fn account_balance_history(
+        &mut self,
+        account_id: AccountId,
+        last_block: BlockNumber,
+    ) -> QueryResult<Vec<BalanceHistoryEntry>> {
+        let start = Instant::now();
+        let history = sqlx::query!(
+            r#"
+                SELECT block_number, coin_id, old_balance, new_balance FROM account_balance_updates
+                WHERE account_id = $1 AND block_number <= $2
+                ORDER BY block_number, update_order_id
+            "#,
+            i64::from(*account_id),
+            i64::from(*last_block)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| BalanceHistoryEntry {
+            block_number: BlockNumber(record.block_number as u32),
+            token: TokenId(record.coin_id as u32),
+            old_balance: record
+                .old_balance
+                .to_bigint()
+                .unwrap()
+                .to_biguint()
+                .unwrap(),
+            new_balance: record
+                .new_balance
+                .to_bigint()
+                .unwrap()
+                .to_biguint()
+                .unwrap(),
+        })
+        .collect();
+
+        metrics::histogram!("sql.chain.account.account_balance_history", start.elapsed());
+        Ok(history)
+    }
 }
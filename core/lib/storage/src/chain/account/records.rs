@@ -1,8 +1,9 @@
 // Workspace imports
 use zksync_api_types::v02::account::EthAccountType as ApiEthAccountType;
 // External imports
+use num::BigUint;
 use sqlx::{types::BigDecimal, FromRow};
-use zksync_types::{AccountId, Address, PubKeyHash, TokenId, H256, NFT};
+use zksync_types::{AccountId, Address, BlockNumber, PubKeyHash, TokenId, H256, NFT};
 
 #[derive(Debug, FromRow)]
 pub(crate) struct StorageAccount {
@@ -85,6 +86,15 @@ pub(crate) struct StorageBalance {
     pub balance: BigDecimal,
 }
 
+/// Change of the account balance from the updates history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceHistoryEntry {
+    pub block_number: BlockNumber,
+    pub token: TokenId,
+    pub old_balance: BigUint,
+    pub new_balance: BigUint,
+}
+
 #[derive(Debug, Clone, Copy, sqlx::Type)]
 #[sqlx(type_name = "eth_account_type")]
 pub(crate) enum DbAccountType {
@@ -94,7 +104,6 @@ pub(crate) enum DbAccountType {
 }
 
 pub(crate) struct StorageAccountType {
-    #[allow(dead_code)]
     pub account_id: i64,
     pub account_type: DbAccountType,
 }
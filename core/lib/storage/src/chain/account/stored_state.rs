@@ -1,7 +1,17 @@
-use zksync_types::{Account, AccountId};
+use super::records::EthAccountType;
+use zksync_types::{Account, AccountId, BlockNumber};
 
 #[derive(Debug, PartialEq)]
 pub struct StoredAccountState {
     pub committed: Option<(AccountId, Account)>,
     pub verified: Option<(AccountId, Account)>,
 }
+
+/// Finalized and committed states of the account, each one along with the number
+/// of the last block that updated it.
+#[derive(Debug, Clone)]
+pub struct StoredAccountStates {
+    pub finalized: Option<(BlockNumber, Account)>,
+    pub committed: Option<(BlockNumber, Account)>,
+    pub account_type: Option<EthAccountType>,
+}
@@ -12,7 +12,7 @@ use zksync_api_types::v02::transaction::{
 use zksync_types::{
     aggregated_operations::AggregatedOperation,
     block::{ExecutedPriorityOp, ExecutedTx},
-    tx::TxHash,
+    tx::{TxFailureCode, TxHash},
     BlockNumber, PriorityOp, SignedZkSyncTx, ZkSyncOp, ZkSyncTx, H256,
 };
 // Local imports
@@ -46,6 +46,7 @@ impl StoredExecutedTransaction {
             success: self.success,
             op: franklin_op,
             fail_reason: self.fail_reason,
+            fail_code: self.fail_code.as_deref().and_then(TxFailureCode::parse),
             block_index: self
                 .block_index
                 .map(|val| u32::try_from(val).expect("Invalid block index")),
@@ -210,6 +211,7 @@ impl NewExecutedTransaction {
             created_at: exec_tx.created_at,
             eth_sign_data,
             batch_id: exec_tx.batch_id,
+            fail_code: exec_tx.fail_code.map(|code| code.as_str().to_string()),
             affected_accounts,
             used_tokens,
         })
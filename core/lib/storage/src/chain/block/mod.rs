@@ -1,6 +1,9 @@
 // Built-in deps
+use std::str::FromStr;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 // External imports
+use anyhow::format_err;
+use sqlx::types::BigDecimal;
 // Workspace imports
 use zksync_api_types::{
     v02::{
@@ -18,8 +21,8 @@ use zksync_types::{
 };
 // Local imports
 use self::records::{
-    BlockTransactionItem, StorageBlock, StorageBlockDetails, StorageBlockMetadata,
-    StoragePendingBlock, StorageRootHash, TransactionItem,
+    BlockTransactionItem, PendingBlockRevert, PendingRevertTx, StorageBlock, StorageBlockDetails,
+    StorageBlockMetadata, StoragePendingBlock, StorageRootHash, TransactionItem,
 };
 use crate::{
     chain::operations::{
@@ -723,6 +726,19 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(block_number <= last_finalized_block)
     }
 
+    /// Returns whether the block is the pending one, i.e. its transactions are executed
+    /// but the block isn't sealed yet.
+    pub async fn is_block_pending(&mut self, block_number: BlockNumber) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = self
+            .load_storage_pending_block()
+            .await?
+            .map_or(false, |block| block.number == i64::from(*block_number));
+
+        metrics::histogram!("sql.chain.block.is_block_pending", start.elapsed());
+        Ok(result)
+    }
+
     pub async fn pending_block_chunks_left(&mut self) -> QueryResult<Option<usize>> {
         let start = Instant::now();
         let maybe_block_chunks = sqlx::query!(
@@ -1476,6 +1492,226 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Reverts the blocks with number greater than `last_block` in a single database transaction.
+    ///
+    /// The executed transactions are returned to the mempool and the reverted blocks are stored
+    /// to be executed again by the state keeper, while the state, the tree cache, the aggregated
+    /// operations together with the Ethereum transactions, and the proofs of the reverted blocks
+    /// are removed. The reverted blocks must not be verified. The revert in progress is finished.
+    pub async fn revert_blocks(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        transaction
+            .chain()
+            .mempool_schema()
+            .return_executed_txs_to_mempool(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .clear_current_nonce_table(last_block)
+            .await?;
+        transaction
+            .chain()
+            .block_schema()
+            .remove_blocks(last_block)
+            .await?;
+        transaction
+            .chain()
+            .block_schema()
+            .remove_pending_block()
+            .await?;
+        transaction
+            .chain()
+            .tree_cache_schema_bincode()
+            .remove_new_account_tree_cache(last_block)
+            .await?;
+
+        transaction
+            .chain()
+            .state_schema()
+            .remove_account_balance_updates(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .remove_account_creates(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .remove_account_pubkey_updates(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .remove_mint_nft_updates(last_block)
+            .await?;
+
+        transaction
+            .chain()
+            .operations_schema()
+            .remove_eth_unprocessed_aggregated_ops()
+            .await?;
+        transaction
+            .chain()
+            .operations_schema()
+            .remove_aggregate_operations_and_bindings(last_block)
+            .await?;
+
+        transaction
+            .prover_schema()
+            .remove_witnesses(last_block)
+            .await?;
+        transaction
+            .prover_schema()
+            .remove_proofs(last_block)
+            .await?;
+        transaction
+            .prover_schema()
+            .remove_aggregated_proofs(last_block)
+            .await?;
+        transaction
+            .prover_schema()
+            .remove_prover_jobs(last_block)
+            .await?;
+
+        transaction
+            .ethereum_schema()
+            .update_eth_parameters(last_block)
+            .await?;
+        transaction
+            .chain()
+            .block_schema()
+            .clear_pending_revert()
+            .await?;
+
+        transaction.commit().await?;
+        metrics::histogram!("sql.chain.block.revert_blocks", start.elapsed());
+        Ok(())
+    }
+
+    /// Records the start of the revert of the blocks after `last_correct_block`. The Ethereum
+    /// sender doesn't send the new operations from then on, and the operations being sent
+    /// meanwhile are waited for, see `lock_pending_revert`. The recorded revert transaction
+    /// is kept if the same revert is resumed.
+    pub async fn start_revert(&mut self, last_correct_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE pending_block_revert
+            SET revert_tx_nonce = CASE WHEN last_correct_block = $1 THEN revert_tx_nonce END,
+                revert_tx_hashes = CASE WHEN last_correct_block = $1
+                    THEN revert_tx_hashes ELSE '{}' END,
+                revert_tx_gas_price = CASE WHEN last_correct_block = $1
+                    THEN revert_tx_gas_price END,
+                revert_tx_priority_fee = CASE WHEN last_correct_block = $1
+                    THEN revert_tx_priority_fee END,
+                last_correct_block = $1, updated_at = now()",
+            i64::from(*last_correct_block)
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.start_revert", start.elapsed());
+        Ok(())
+    }
+
+    /// Records the sent transaction reverting the blocks on the contract, or its replacement
+    /// with the same nonce.
+    pub async fn add_revert_tx(
+        &mut self,
+        nonce: i64,
+        tx_hash: H256,
+        gas_price: U256,
+        priority_fee: Option<U256>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let gas_price = BigDecimal::from_str(&gas_price.to_string())?;
+        let priority_fee = priority_fee
+            .map(|fee| BigDecimal::from_str(&fee.to_string()))
+            .transpose()?;
+        sqlx::query!(
+            "UPDATE pending_block_revert
+            SET revert_tx_nonce = $1, revert_tx_hashes = array_append(revert_tx_hashes, $2),
+                revert_tx_gas_price = $3, revert_tx_priority_fee = $4, updated_at = now()",
+            nonce,
+            tx_hash.as_bytes(),
+            gas_price,
+            priority_fee
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.add_revert_tx", start.elapsed());
+        Ok(())
+    }
+
+    /// Clears the revert in progress, e.g. if it's rejected before the contract is touched.
+    pub async fn clear_pending_revert(&mut self) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE pending_block_revert
+            SET last_correct_block = NULL, revert_tx_nonce = NULL, revert_tx_hashes = '{}',
+                revert_tx_gas_price = NULL, revert_tx_priority_fee = NULL, updated_at = now()"
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.clear_pending_revert", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the revert in progress, if any.
+    pub async fn load_pending_revert(&mut self) -> QueryResult<Option<PendingBlockRevert>> {
+        let start = Instant::now();
+        let record = sqlx::query!(
+            "SELECT last_correct_block, revert_tx_nonce, revert_tx_hashes,
+                revert_tx_gas_price, revert_tx_priority_fee
+            FROM pending_block_revert"
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.load_pending_revert", start.elapsed());
+        let parse_fee = |fee: BigDecimal| {
+            U256::from_dec_str(&fee.to_string())
+                .map_err(|err| format_err!("Invalid revert transaction fee {}: {:?}", fee, err))
+        };
+        let revert_tx = match (record.revert_tx_nonce, record.revert_tx_gas_price) {
+            (Some(nonce), Some(gas_price)) => Some(PendingRevertTx {
+                nonce,
+                tx_hashes: record
+                    .revert_tx_hashes
+                    .iter()
+                    .map(|tx_hash| H256::from_slice(tx_hash))
+                    .collect(),
+                gas_price: parse_fee(gas_price)?,
+                priority_fee: record.revert_tx_priority_fee.map(parse_fee).transpose()?,
+            }),
+            _ => None,
+        };
+        Ok(record
+            .last_correct_block
+            .map(|last_correct_block| PendingBlockRevert {
+                last_correct_block: BlockNumber(last_correct_block as u32),
+                revert_tx,
+            }))
+    }
+
+    /// Returns the last correct block of the revert in progress, if any, and locks it until
+    /// the end of the transaction, so the revert can't be started meanwhile.
+    pub async fn lock_pending_revert(&mut self) -> QueryResult<Option<BlockNumber>> {
+        let start = Instant::now();
+        let last_correct_block =
+            sqlx::query_scalar!("SELECT last_correct_block FROM pending_block_revert FOR SHARE")
+                .fetch_one(self.0.conn())
+                .await?;
+
+        metrics::histogram!("sql.chain.block.lock_pending_revert", start.elapsed());
+        Ok(last_correct_block.map(|block| BlockNumber(block as u32)))
+    }
+
     pub async fn store_factories_for_block_withdraw_nfts(
         &mut self,
         from_block: BlockNumber,
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::value::Value;
 use sqlx::FromRow;
 // Workspace imports
-use zksync_types::{event::block::BlockDetails, BlockNumber};
+use zksync_types::{event::block::BlockDetails, BlockNumber, H256, U256};
 use zksync_utils::{BytesToHexSerde, OptionBytesToHexSerde, SyncBlockPrefix, ZeroxPrefix};
 // Local imports
 
@@ -128,6 +128,26 @@ impl From<StorageBlockDetails> for BlockDetails {
     }
 }
 
+/// Revert of the blocks in progress.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingBlockRevert {
+    /// The blocks after this one are being reverted.
+    pub last_correct_block: BlockNumber,
+    /// Transaction reverting the blocks on the contract, once it's sent.
+    pub revert_tx: Option<PendingRevertTx>,
+}
+
+/// Transaction reverting the blocks on the contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingRevertTx {
+    pub nonce: i64,
+    /// Hashes of the sent transaction and its replacements, any of them may be mined.
+    pub tx_hashes: Vec<H256>,
+    /// Fees of the last sent transaction, the priority fee is `None` for the legacy one.
+    pub gas_price: U256,
+    pub priority_fee: Option<U256>,
+}
+
 #[derive(Debug, FromRow)]
 pub(crate) struct StorageBlockMetadata {
     #[allow(dead_code)]
@@ -1,18 +1,20 @@
 // Built-in deps
 use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
 // External imports
+use chrono::{DateTime, Duration, Utc};
 use itertools::Itertools;
 // Workspace imports
 use zksync_api_types::v02::pagination::PaginationDirection;
 use zksync_api_types::v02::transaction::{
-    ApiTxBatch, BatchStatus, TxHashSerializeWrapper, TxInBlockStatus,
+    ApiTxBatch, BatchStatus, InterruptedSubmission, SubmissionStage, TxHashSerializeWrapper,
+    TxInBlockStatus,
 };
 use zksync_types::{
     block::IncompleteBlock,
     mempool::SignedTxVariant,
     tx::{TxEthSignature, TxHash},
-    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx,
-    PriorityOp, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, H256,
+    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx, Nonce,
+    PriorityOp, PubKeyHash, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, H256,
 };
 // Local imports
 use self::records::{MempoolPriorityOp, MempoolTx, QueuedBatchTx, RevertedBlock};
@@ -41,18 +43,39 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
     pub async fn load_txs(
         &mut self,
         executed_txs: &[TxHash],
+    ) -> QueryResult<VecDeque<SignedTxVariant>> {
+        self.load_scheduled_txs(executed_txs, None).await
+    }
+
+    /// Same as `load_txs`, but skips the transactions scheduled to be executed
+    /// after the block `last_committed_block`.
+    pub async fn load_ready_txs(
+        &mut self,
+        executed_txs: &[TxHash],
+        last_committed_block: BlockNumber,
+    ) -> QueryResult<VecDeque<SignedTxVariant>> {
+        self.load_scheduled_txs(executed_txs, Some(last_committed_block))
+            .await
+    }
+
+    async fn load_scheduled_txs(
+        &mut self,
+        executed_txs: &[TxHash],
+        last_committed_block: Option<BlockNumber>,
     ) -> QueryResult<VecDeque<SignedTxVariant>> {
         let start = Instant::now();
         // Load the transactions from mempool along with corresponding batch IDs.
         let excluded_txs: Vec<String> = executed_txs.iter().map(|tx| tx.to_string()).collect();
+        let last_committed_block = last_committed_block.map(|block| *block as i64);
         let txs: Vec<MempoolTx> = sqlx::query_as!(
             MempoolTx,
             "SELECT * FROM mempool_txs WHERE reverted = false AND tx_hash NOT IN (
                 SELECT u.hashes FROM UNNEST ($1::text[]) as u(hashes)
             )
-
+            AND ($2::bigint IS NULL OR not_before_block IS NULL OR not_before_block <= $2)
             ORDER BY id",
-            &excluded_txs
+            &excluded_txs,
+            last_committed_block
         )
         .fetch_all(self.0.conn())
         .await?;
@@ -242,6 +265,15 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
     /// Adds a new transaction to the mempool schema.
     pub async fn insert_tx(&mut self, tx_data: &SignedZkSyncTx) -> QueryResult<()> {
+        self.insert_scheduled_tx(tx_data, None).await
+    }
+
+    /// Stores the transaction that isn't executed until the block `not_before_block` is committed.
+    pub async fn insert_scheduled_tx(
+        &mut self,
+        tx_data: &SignedZkSyncTx,
+        not_before_block: Option<BlockNumber>,
+    ) -> QueryResult<()> {
         let start = Instant::now();
         let tx_hash = hex::encode(tx_data.tx.hash().as_ref());
         let tx = serde_json::to_value(&tx_data.tx)?;
@@ -252,14 +284,17 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .as_ref()
             .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
 
+        let not_before_block = not_before_block.map(|block| *block as i64);
+
         sqlx::query!(
-            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-            VALUES ($1, $2, $3, $4, $5)",
+            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, not_before_block)
+            VALUES ($1, $2, $3, $4, $5, $6)",
             tx_hash,
             tx,
             tx_data.created_at,
             eth_sign_data,
             batch_id,
+            not_before_block,
         )
         .execute(self.0.conn())
         .await?;
@@ -268,6 +303,107 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Returns whether the mempool has a transaction of the account with the nonce
+    /// which isn't scheduled, i.e. is executed as soon as possible.
+    pub async fn has_unscheduled_tx(
+        &mut self,
+        account_id: AccountId,
+        nonce: Nonce,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+
+        let exists = sqlx::query!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM mempool_txs
+                WHERE account_id = $1 AND nonce = $2
+                    AND not_before_block IS NULL
+            ) as "exists!"
+            "#,
+            i64::from(*account_id),
+            i64::from(*nonce)
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .exists;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "has_unscheduled_tx");
+        Ok(exists)
+    }
+
+    /// Evicts the scheduled transactions of the account with the nonce, so they are replaced
+    /// by the new transaction. The scheduled transactions are never a part of the batch.
+    pub async fn evict_scheduled_txs(
+        &mut self,
+        account_id: AccountId,
+        nonce: Nonce,
+        reason: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+
+        sqlx::query!(
+            r#"
+            WITH evicted AS (
+                DELETE FROM mempool_txs
+                WHERE account_id = $1 AND nonce = $2
+                    AND not_before_block IS NOT NULL
+                    AND batch_id = 0
+                RETURNING tx_hash
+            )
+            INSERT INTO mempool_evicted_txs (tx_hash, batch_id, reason, evicted_at)
+            SELECT tx_hash, 0, $3, now() FROM evicted
+            ON CONFLICT (tx_hash) DO UPDATE SET reason = $3, evicted_at = now()
+            "#,
+            i64::from(*account_id),
+            i64::from(*nonce),
+            reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.mempool.evict_scheduled_txs", start.elapsed());
+        Ok(())
+    }
+
+    /// Evicts the queued `ChangePubKey` transactions of the account to the given public key hash,
+    /// along with the batches they belong to. Returns the number of the evicted transactions.
+    pub async fn evict_change_pubkey_txs(
+        &mut self,
+        account_id: AccountId,
+        new_pk_hash: &PubKeyHash,
+        reason: &str,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+
+        let evicted = sqlx::query!(
+            r#"
+            WITH matched AS (
+                SELECT tx_hash, batch_id FROM mempool_txs
+                WHERE account_id = $1
+                    AND tx->>'type' = 'ChangePubKey'
+                    AND tx->>'newPkHash' = $2
+            ), evicted AS (
+                DELETE FROM mempool_txs
+                WHERE tx_hash IN (SELECT tx_hash FROM matched)
+                    OR batch_id IN (SELECT batch_id FROM matched WHERE batch_id <> 0)
+                RETURNING tx_hash, batch_id
+            )
+            INSERT INTO mempool_evicted_txs (tx_hash, batch_id, reason, evicted_at)
+            SELECT tx_hash, batch_id, $3, now() FROM evicted
+            ON CONFLICT (tx_hash) DO UPDATE SET reason = $3, evicted_at = now()
+            "#,
+            i64::from(*account_id),
+            new_pk_hash.as_hex(),
+            reason
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.chain.mempool.evict_change_pubkey_txs", start.elapsed());
+        Ok(evicted)
+    }
+
     pub async fn remove_tx(&mut self, tx: &[u8]) -> QueryResult<()> {
         let start = Instant::now();
         let tx_hash = hex::encode(tx);
@@ -321,6 +457,31 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(contains)
     }
 
+    /// Returns the distinct nonces of the account's transactions waiting in the mempool,
+    /// in the ascending order.
+    /// Nonces of the accounts which sign the orders of the `Swap` are not taken into account.
+    pub async fn get_queued_nonces(&mut self, account_id: AccountId) -> QueryResult<Vec<Nonce>> {
+        let start = Instant::now();
+
+        let nonces = sqlx::query!(
+            r#"
+            SELECT DISTINCT nonce as "nonce!"
+            FROM mempool_txs
+            WHERE account_id = $1
+            ORDER BY 1
+            "#,
+            i64::from(*account_id)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| Nonce(row.nonce as u32))
+        .collect();
+
+        metrics::histogram!("sql.chain.mempool.get_queued_nonces", start.elapsed());
+        Ok(nonces)
+    }
+
     /// Returns zkSync transaction with the given hash.
     pub async fn get_tx(&mut self, tx_hash: &[u8]) -> QueryResult<Option<SignedZkSyncTx>> {
         let start = Instant::now();
@@ -403,6 +564,182 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Removes transactions that can no longer be executed from the mempool and
+    /// stores the reason of the eviction, so it can be reported to the user.
+    ///
+    /// Transactions of the same batch should be evicted together, since batch
+    /// can't be executed partially.
+    pub async fn evict_txs(
+        &mut self,
+        tx_hashes: &[TxHash],
+        batch_id: Option<i64>,
+        reason: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(hex::encode).collect();
+        // Special case: batch_id == 0 <==> transaction is not a part of some batch.
+        let batch_id = batch_id.unwrap_or(0);
+
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            "INSERT INTO mempool_evicted_txs (tx_hash, batch_id, reason, evicted_at)
+            SELECT u.tx_hash, $2, $3, now()
+                FROM UNNEST ($1::text[]) AS u(tx_hash)
+            ON CONFLICT (tx_hash) DO UPDATE SET reason = $3, evicted_at = now()",
+            &tx_hashes,
+            batch_id,
+            reason
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM mempool_txs
+            WHERE tx_hash = ANY($1)",
+            &tx_hashes
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.mempool.evict_txs", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the reason why the transaction was evicted from the mempool, if it was.
+    pub async fn get_eviction_reason(&mut self, tx_hash: TxHash) -> QueryResult<Option<String>> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        let reason = sqlx::query!(
+            "SELECT reason FROM mempool_evicted_txs
+            WHERE tx_hash = $1",
+            &tx_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|record| record.reason);
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_eviction_reason");
+        Ok(reason)
+    }
+
+    /// Records the reason why the transaction was rejected before getting into the mempool,
+    /// so it's reported the same way as the evicted ones.
+    ///
+    /// Unlike `evict_txs`, the mempool is left intact: the rejected transaction may be
+    /// a duplicate of the queued one. The existing record is not overwritten.
+    pub async fn store_rejected_tx(&mut self, tx_hash: TxHash, reason: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        sqlx::query!(
+            "INSERT INTO mempool_evicted_txs (tx_hash, batch_id, reason, evicted_at)
+            VALUES ($1, 0, $2, now())
+            ON CONFLICT (tx_hash) DO NOTHING",
+            &tx_hash,
+            reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.mempool.store_rejected_tx", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the eviction records that are older than `max_age`.
+    pub async fn remove_evicted_txs(&mut self, max_age: Duration) -> QueryResult<()> {
+        let start = Instant::now();
+        let offset = Utc::now() - max_age;
+
+        sqlx::query!(
+            "DELETE FROM mempool_evicted_txs WHERE evicted_at < $1",
+            offset
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.mempool.remove_evicted_txs", start.elapsed());
+        Ok(())
+    }
+
+    /// Stores the submissions that were in progress when the API server was shut down,
+    /// along with their last known stages.
+    pub async fn store_interrupted_submissions(
+        &mut self,
+        submissions: &[(TxHash, SubmissionStage)],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let (tx_hashes, stages): (Vec<_>, Vec<_>) = submissions
+            .iter()
+            .map(|(tx_hash, stage)| (hex::encode(tx_hash.as_ref()), stage.as_str().to_string()))
+            .unzip();
+
+        sqlx::query!(
+            "INSERT INTO interrupted_submissions (tx_hash, stage, interrupted_at)
+            SELECT u.tx_hash, u.stage, now()
+            FROM UNNEST ($1::text[], $2::text[]) AS u(tx_hash, stage)
+            ON CONFLICT (tx_hash) DO UPDATE
+            SET stage = EXCLUDED.stage, interrupted_at = EXCLUDED.interrupted_at",
+            &tx_hashes,
+            &stages
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.mempool.store_interrupted_submissions",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Returns the interrupted submission of the transaction, if any.
+    pub async fn get_interrupted_submission(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<InterruptedSubmission>> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        let submission = sqlx::query!(
+            "SELECT stage, interrupted_at FROM interrupted_submissions WHERE tx_hash = $1",
+            &tx_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .and_then(|row| {
+            SubmissionStage::parse(&row.stage).map(|stage| InterruptedSubmission {
+                stage,
+                interrupted_at: row.interrupted_at,
+            })
+        });
+
+        metrics::histogram!(
+            "sql.chain.mempool.get_interrupted_submission",
+            start.elapsed()
+        );
+        Ok(submission)
+    }
+
+    /// Removes the interrupted submissions that are older than `max_age`.
+    pub async fn remove_interrupted_submissions(&mut self, max_age: Duration) -> QueryResult<()> {
+        let start = Instant::now();
+        let offset = Utc::now() - max_age;
+
+        sqlx::query!(
+            "DELETE FROM interrupted_submissions WHERE interrupted_at < $1",
+            offset
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.mempool.remove_interrupted_submissions",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
     pub async fn insert_priority_ops(
         &mut self,
         ops: &[PriorityOp],
@@ -477,6 +814,20 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(ops.into_iter().map(|op| op.into()).collect())
     }
 
+    /// Loads the priority operations that don't have enough confirmations yet.
+    pub async fn get_unconfirmed_priority_ops(&mut self) -> QueryResult<Vec<PriorityOp>> {
+        let start = Instant::now();
+        let ops = sqlx::query_as!(
+            MempoolPriorityOp,
+            "SELECT serial_id,data,deadline_block,eth_hash,tx_hash,eth_block,eth_block_index,created_at FROM mempool_priority_operations WHERE confirmed = false ORDER BY serial_id"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_unconfirmed_priority_ops");
+        Ok(ops.into_iter().map(|op| op.into()).collect())
+    }
+
     pub async fn remove_priority_op_from_mempool(&mut self, id: i64) -> QueryResult<()> {
         sqlx::query!(
             "DELETE FROM mempool_priority_operations WHERE serial_id=$1",
@@ -548,6 +899,33 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .map(|op| op.into());
         Ok(op)
     }
+    /// Loads the pending priority operations created by the L1 transaction.
+    pub async fn get_pending_operations_by_eth_hash(
+        &mut self,
+        eth_hash: H256,
+    ) -> QueryResult<Vec<PriorityOp>> {
+        let start = Instant::now();
+        let ops = sqlx::query_as!(
+            MempoolPriorityOp,
+            r#"
+                SELECT serial_id,data,deadline_block,eth_hash,
+                       tx_hash,eth_block,eth_block_index,created_at
+                FROM mempool_priority_operations
+                WHERE eth_hash = $1
+                ORDER BY serial_id
+            "#,
+            eth_hash.as_bytes()
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|op| op.into())
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_pending_operations_by_eth_hash");
+        Ok(ops)
+    }
+
     pub async fn get_pending_deposits(&mut self, address: Address) -> QueryResult<Vec<PriorityOp>> {
         let ops = sqlx::query_as!(
             MempoolPriorityOp,
@@ -575,6 +953,49 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Removes the unconfirmed priority operations received in the Ethereum blocks starting
+    /// from the given one, e.g. once these blocks are reorged out of the chain.
+    /// Returns the serial ids of the removed operations.
+    pub async fn remove_unconfirmed_priority_ops(
+        &mut self,
+        from_eth_block: u64,
+    ) -> QueryResult<Vec<SerialId>> {
+        let start = Instant::now();
+        let serial_ids = sqlx::query!(
+            "DELETE FROM mempool_priority_operations
+            WHERE confirmed = false AND eth_block >= $1
+            RETURNING serial_id",
+            from_eth_block as i64
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.serial_id as SerialId)
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "remove_unconfirmed_priority_ops");
+        Ok(serial_ids)
+    }
+
+    /// Loads the serial ids of the priority operations that are already executed
+    /// but are still stored in the mempool.
+    pub async fn get_executed_priority_ops_in_mempool(&mut self) -> QueryResult<Vec<SerialId>> {
+        let start = Instant::now();
+        let serial_ids = sqlx::query!(
+            "SELECT serial_id FROM mempool_priority_operations
+            WHERE serial_id IN (SELECT priority_op_serialid FROM executed_priority_operations)
+            ORDER BY serial_id"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.serial_id as SerialId)
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_executed_priority_ops_in_mempool");
+        Ok(serial_ids)
+    }
+
     /// Returns mempool size.
     pub async fn get_mempool_size(&mut self) -> QueryResult<u32> {
         let start = Instant::now();
@@ -588,6 +1009,72 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(size.unwrap_or(0) as u32)
     }
 
+    /// Loads the latest transactions from the mempool that were received within the given
+    /// time range (the lower bound is exclusive). Transactions are ordered from newest to oldest.
+    pub async fn load_pending_txs(
+        &mut self,
+        created_after: Option<DateTime<Utc>>,
+        created_before: DateTime<Utc>,
+        limit: u32,
+    ) -> QueryResult<Vec<SignedZkSyncTx>> {
+        let start = Instant::now();
+        let txs = sqlx::query_as!(
+            MempoolTx,
+            "SELECT * FROM mempool_txs
+            WHERE reverted = false AND ($1::timestamptz IS NULL OR created_at > $1) AND created_at <= $2
+            ORDER BY id DESC
+            LIMIT $3",
+            created_after,
+            created_before,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(SignedZkSyncTx::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        metrics::histogram!("sql.chain.mempool.load_pending_txs", start.elapsed());
+        Ok(txs)
+    }
+
+    /// Loads the page of the transactions from the mempool that were received within the
+    /// given time range (the lower bound is exclusive). Transactions are ordered from oldest
+    /// to newest and returned along with their ids: the page starts right after the transaction
+    /// with `after_id`, so the id of the last returned transaction is the start of the next page.
+    pub async fn load_pending_txs_page(
+        &mut self,
+        created_after: DateTime<Utc>,
+        created_before: DateTime<Utc>,
+        after_id: Option<i64>,
+        limit: u32,
+    ) -> QueryResult<Vec<(i64, SignedZkSyncTx)>> {
+        let start = Instant::now();
+        let txs = sqlx::query_as!(
+            MempoolTx,
+            "SELECT * FROM mempool_txs
+            WHERE reverted = false AND created_at > $1 AND created_at <= $2
+                AND ($3::bigint IS NULL OR id > $3)
+            ORDER BY id ASC
+            LIMIT $4",
+            created_after,
+            created_before,
+            after_id,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|tx| {
+            let id = tx.id;
+            SignedZkSyncTx::try_from(tx).map(|tx| (id, tx))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+        metrics::histogram!("sql.chain.mempool.load_pending_txs_page", start.elapsed());
+        Ok(txs)
+    }
+
     /// Get info about batch in mempool.
     pub async fn get_queued_batch_info(
         &mut self,
@@ -665,6 +1152,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 mempool_reverted_txs_meta.to_account,
                 mempool_reverted_txs_meta.success,
                 mempool_reverted_txs_meta.fail_reason,
+                Null::text as "fail_code?",
                 mempool_reverted_txs_meta.primary_account_address,
                 mempool_txs.created_at,
                 mempool_txs.eth_sign_data,
@@ -721,15 +1209,27 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 }
             });
 
+            // The block is restored with the sizes it was created with, the current ones
+            // are used only if the protocol parameters weren't recorded at that moment.
+            let block_number = BlockNumber(block.number as u32);
+            let recorded_block_sizes = transaction
+                .protocol_parameters_schema()
+                .load_parameters_for_block(block_number)
+                .await?
+                .map(|parameters| parameters.block_chunk_sizes);
+            let block_sizes = recorded_block_sizes
+                .as_deref()
+                .unwrap_or(available_block_sizes);
+
             incomplete_blocks.push_back(IncompleteBlock::new_from_available_block_sizes(
-                BlockNumber(block.number as u32),
+                block_number,
                 fee_account_id,
                 executed_operations,
                 (
                     block.unprocessed_priority_op_before as u64,
                     block.unprocessed_priority_op_after as u64,
                 ),
-                available_block_sizes,
+                block_sizes,
                 Default::default(),
                 Default::default(),
                 block.timestamp as u64,
@@ -865,6 +1365,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 op,
                 block_index,
                 fail_reason,
+                ..
             } = *reverted_tx;
 
             let block_index = block_index.map(|b| b as i32);
@@ -33,6 +33,12 @@ pub(crate) struct MempoolTx {
     pub next_priority_op_serial_id: Option<i64>,
     #[allow(dead_code)]
     pub reverted: bool,
+    #[allow(dead_code)]
+    pub not_before_block: Option<i64>,
+    #[allow(dead_code)]
+    pub account_id: Option<i64>,
+    #[allow(dead_code)]
+    pub nonce: Option<i64>,
 }
 
 impl TryFrom<MempoolTx> for SignedZkSyncTx {
@@ -6,6 +6,7 @@ pub mod operations_ext;
 pub mod state;
 pub mod stats;
 pub mod tree_cache;
+pub mod withdrawals;
 
 use super::StorageProcessor;
 
@@ -50,4 +51,8 @@ impl<'a, 'c> ChainIntermediator<'a, 'c> {
     pub fn tree_cache_schema_bincode(self) -> tree_cache::TreeCacheSchemaBincode<'a, 'c> {
         tree_cache::TreeCacheSchemaBincode(self.0)
     }
+
+    pub fn withdrawals_schema(self) -> withdrawals::WithdrawalsSchema<'a, 'c> {
+        withdrawals::WithdrawalsSchema(self.0)
+    }
 }
@@ -1,7 +1,7 @@
 // Built-in deps
 use std::time::Instant;
 // External imports
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 // Workspace imports
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
@@ -138,6 +138,27 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(op)
     }
 
+    /// Retrieves all the executed priority operations created by the L1 transaction.
+    pub async fn get_executed_priority_operations_by_eth_hash(
+        &mut self,
+        eth_hash: &[u8],
+    ) -> QueryResult<Vec<StoredExecutedPriorityOperation>> {
+        let start = Instant::now();
+        let ops = sqlx::query_as!(
+            StoredExecutedPriorityOperation,
+            "SELECT * FROM executed_priority_operations WHERE eth_hash = $1 ORDER BY priority_op_serialid",
+            eth_hash
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.get_executed_priority_operations_by_eth_hash",
+            start.elapsed()
+        );
+        Ok(ops)
+    }
+
     pub async fn confirm_aggregated_operations(
         &mut self,
         first_block: BlockNumber,
@@ -182,11 +203,11 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             // Possible scenario: user had no enough funds for transfer, then deposited some and
             // sent the same transfer again.
             sqlx::query!(
-                "INSERT INTO executed_transactions (block_number, block_index, tx, operation, tx_hash, from_account, to_account, success, fail_reason, primary_account_address, nonce, created_at, eth_sign_data, batch_id)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                "INSERT INTO executed_transactions (block_number, block_index, tx, operation, tx_hash, from_account, to_account, success, fail_reason, primary_account_address, nonce, created_at, eth_sign_data, batch_id, fail_code)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
                 ON CONFLICT (tx_hash)
                 DO UPDATE
-                SET block_number = $1, block_index = $2, tx = $3, operation = $4, tx_hash = $5, from_account = $6, to_account = $7, success = $8, fail_reason = $9, primary_account_address = $10, nonce = $11, created_at = $12, eth_sign_data = $13, batch_id = $14
+                SET block_number = $1, block_index = $2, tx = $3, operation = $4, tx_hash = $5, from_account = $6, to_account = $7, success = $8, fail_reason = $9, primary_account_address = $10, nonce = $11, created_at = $12, eth_sign_data = $13, batch_id = $14, fail_code = $15
                 RETURNING sequence_number
                 ",
                 operation.block_number,
@@ -203,6 +224,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
                 operation.created_at,
                 operation.eth_sign_data,
                 operation.batch_id,
+                operation.fail_code,
             )
             .fetch_optional(transaction.conn())
             .await?.map(|a| a.sequence_number).flatten()
@@ -210,8 +232,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             // If transaction failed, we do nothing on conflict.
             sqlx::query!(
                 "
-                INSERT INTO executed_transactions (block_number, block_index, tx, operation, tx_hash, from_account, to_account, success, fail_reason, primary_account_address, nonce, created_at, eth_sign_data, batch_id)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                INSERT INTO executed_transactions (block_number, block_index, tx, operation, tx_hash, from_account, to_account, success, fail_reason, primary_account_address, nonce, created_at, eth_sign_data, batch_id, fail_code)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
                 ON CONFLICT (tx_hash)
                 DO NOTHING
                 RETURNING sequence_number
@@ -230,6 +252,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
                 operation.created_at,
                 operation.eth_sign_data,
                 operation.batch_id,
+                operation.fail_code,
             )
             .fetch_optional(transaction.conn())
             .await?.map(|a| a.sequence_number).flatten()
@@ -271,23 +294,75 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
 
     /// Removes all rejected transactions with an age greater than `max_age` from the database.
     pub async fn remove_rejected_transactions(&mut self, max_age: Duration) -> QueryResult<()> {
-        let start = Instant::now();
-
         let mut transaction = self.0.start_transaction().await?;
+        let tx_hashes: Vec<_> = OperationsSchema(&mut transaction)
+            .load_rejected_transactions(max_age, None, 1000)
+            .await?
+            .into_iter()
+            .map(|(_, tx_hash)| tx_hash)
+            .collect();
+        OperationsSchema(&mut transaction)
+            .remove_rejected_transactions_by_hash(&tx_hashes)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Loads at most `limit` rejected transactions with an age greater than `max_age`, ordered
+    /// by their creation time and hash. Returns the creation time and the hash of each one.
+    ///
+    /// The page starts right after the `after` transaction, if it's provided, so the creation
+    /// time and the hash of the last returned transaction is the start of the next page.
+    pub async fn load_rejected_transactions(
+        &mut self,
+        max_age: Duration,
+        after: Option<(DateTime<Utc>, TxHash)>,
+        limit: u32,
+    ) -> QueryResult<Vec<(DateTime<Utc>, TxHash)>> {
+        let start = Instant::now();
         let offset = Utc::now() - max_age;
-        let tx_hashes: Vec<Vec<u8>> = sqlx::query!(
+        let (after_created_at, after_tx_hash) = match after {
+            Some((created_at, tx_hash)) => (Some(created_at), Some(tx_hash.as_ref().to_vec())),
+            None => (None, None),
+        };
+        let tx_hashes = sqlx::query!(
             r#"
-            SELECT tx_hash FROM executed_transactions 
-            WHERE success = false AND created_at < $1 LIMIT 1000
+            SELECT tx_hash, created_at FROM executed_transactions
+            WHERE success = false AND created_at < $1
+                AND ($2::timestamptz IS NULL OR (created_at, tx_hash) > ($2, $3::bytea))
+            ORDER BY created_at, tx_hash
+            LIMIT $4
             "#,
-            offset
+            offset,
+            after_created_at,
+            after_tx_hash,
+            i64::from(limit)
         )
-        .fetch_all(transaction.conn())
+        .fetch_all(self.0.conn())
         .await?
         .into_iter()
-        .map(|value| value.tx_hash)
+        .filter_map(|value| Some((value.created_at, TxHash::from_slice(&value.tx_hash)?)))
         .collect();
 
+        metrics::histogram!(
+            "sql.chain.operations.load_rejected_transactions",
+            start.elapsed()
+        );
+        Ok(tx_hashes)
+    }
+
+    /// Removes the rejected transactions returned by `load_rejected_transactions`.
+    pub async fn remove_rejected_transactions_by_hash(
+        &mut self,
+        tx_hashes: &[TxHash],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+
+        let mut transaction = self.0.start_transaction().await?;
+        let tx_hashes: Vec<Vec<u8>> = tx_hashes
+            .iter()
+            .map(|tx_hash| tx_hash.as_ref().to_vec())
+            .collect();
         sqlx::query!(
             "DELETE FROM executed_transactions WHERE tx_hash = ANY ($1)",
             &tx_hashes
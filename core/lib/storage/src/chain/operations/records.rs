@@ -62,6 +62,7 @@ pub(crate) struct StoredExecutedTransaction {
     pub created_at: DateTime<Utc>,
     pub eth_sign_data: Option<serde_json::Value>,
     pub batch_id: Option<i64>,
+    pub fail_code: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -99,6 +100,7 @@ pub(crate) struct NewExecutedTransaction {
     pub created_at: DateTime<Utc>,
     pub eth_sign_data: Option<serde_json::Value>,
     pub batch_id: Option<i64>,
+    pub fail_code: Option<String>,
     pub affected_accounts: Vec<Vec<u8>>,
     pub used_tokens: Vec<i32>,
 }
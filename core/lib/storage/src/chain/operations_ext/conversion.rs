@@ -6,7 +6,7 @@ use zksync_api_types::v02::transaction::{
     TransactionData, TxData, TxInBlockStatus, WithdrawData, WithdrawNFTData,
 };
 use zksync_types::{
-    tx::{EthSignData, TxHash},
+    tx::{EthSignData, TxFailureCode, TxHash},
     BlockNumber, EthBlockId, ZkSyncOp, ZkSyncTx, H256,
 };
 // Local imports
@@ -15,18 +15,15 @@ use super::records::{StorageTxData, StorageTxReceipt};
 impl StorageTxReceipt {
     pub(super) fn receipt_from_storage_receipt(
         receipt: StorageTxReceipt,
-        is_block_finalized: Option<bool>,
+        block_status: Option<TxInBlockStatus>,
     ) -> Receipt {
         if receipt.block_number.is_some() {
             let status = if receipt.success.unwrap() {
-                if is_block_finalized.unwrap() {
-                    TxInBlockStatus::Finalized
-                } else {
-                    TxInBlockStatus::Committed
-                }
+                block_status.unwrap()
             } else {
                 TxInBlockStatus::Rejected
             };
+            let block_index = receipt.block_index.map(|index| index as u32);
             if receipt.eth_block.is_some() {
                 Receipt::L1(L1Receipt {
                     status,
@@ -35,6 +32,7 @@ impl StorageTxReceipt {
                         .block_number
                         .map(|number| BlockNumber(number as u32)),
                     id: receipt.priority_op_serialid.unwrap() as u64,
+                    block_index,
                 })
             } else {
                 Receipt::L2(L2Receipt {
@@ -44,6 +42,8 @@ impl StorageTxReceipt {
                         .block_number
                         .map(|number| BlockNumber(number as u32)),
                     fail_reason: receipt.fail_reason,
+                    fail_code: receipt.fail_code.as_deref().and_then(TxFailureCode::parse),
+                    block_index,
                 })
             }
         } else {
@@ -52,6 +52,8 @@ impl StorageTxReceipt {
                 tx_hash: TxHash::from_slice(&receipt.tx_hash).unwrap(),
                 rollup_block: None,
                 fail_reason: None,
+                fail_code: None,
+                block_index: None,
             })
         }
     }
@@ -86,7 +88,7 @@ impl StorageTxData {
 
     pub(super) fn data_from_storage_data(
         data: StorageTxData,
-        is_block_finalized: Option<bool>,
+        block_status: Option<TxInBlockStatus>,
         complete_withdrawals_tx_hash: Option<H256>,
     ) -> TxData {
         let tx_hash = TxHash::from_slice(&data.tx_hash).unwrap();
@@ -94,11 +96,7 @@ impl StorageTxData {
         let tx = if data.block_number.is_some() {
             let block_number = data.block_number.map(|number| BlockNumber(number as u32));
             let status = if data.success.unwrap() {
-                if is_block_finalized.unwrap() {
-                    TxInBlockStatus::Finalized
-                } else {
-                    TxInBlockStatus::Committed
-                }
+                block_status.unwrap()
             } else {
                 TxInBlockStatus::Rejected
             };
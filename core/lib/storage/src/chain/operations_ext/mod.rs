@@ -7,7 +7,12 @@ use chrono::{DateTime, Utc};
 // Workspace imports
 use zksync_api_types::{
     v02::{
-        pagination::{AccountTxsRequest, PaginationDirection, PaginationQuery},
+        account::{FullExitInfo, FullExitStatus},
+        event::{EventsRequest, L2Event},
+        pagination::{
+            AccountTxsCursorRequest, AccountTxsRequest, Cursor, CursorPaginated,
+            PaginationDirection, PaginationQuery,
+        },
         transaction::{
             ApiTxBatch, BatchStatus, Receipt, Transaction, TxData, TxHashSerializeWrapper,
             TxInBlockStatus,
@@ -17,8 +22,10 @@ use zksync_api_types::{
 };
 use zksync_crypto::params;
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, tx::TxHash, Address, BlockNumber, TokenId,
-    ZkSyncOp, ZkSyncTx, H256,
+    aggregated_operations::AggregatedActionType,
+    tx::{TxFailureCode, TxHash},
+    Address, BlockNumber, FullExitOp, SerialId, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx,
+    H256,
 };
 
 // Local imports
@@ -79,6 +86,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 success: tx.success,
                 verified,
                 fail_reason: tx.fail_reason,
+                fail_code: tx.fail_code.as_deref().and_then(TxFailureCode::parse),
                 prover_run: None,
             }))
         } else {
@@ -100,8 +108,10 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     SELECT
                         tx_hash,
                         block_number,
+                        block_index,
                         success,
                         fail_reason,
+                        fail_code,
                         Null::bigint as eth_block,
                         Null::bigint as priority_op_serialid
                     FROM executed_transactions
@@ -110,8 +120,10 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     SELECT
                         tx_hash,
                         block_number,
+                        block_index,
                         true as success,
                         Null as fail_reason,
+                        Null as fail_code,
                         eth_block,
                         priority_op_serialid
                     FROM executed_priority_operations
@@ -120,8 +132,10 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     SELECT
                         decode(tx_hash, 'hex'),
                         Null::bigint as block_number,
+                        Null::integer as block_index,
                         Null::boolean as success,
                         Null as fail_reason,
+                        Null as fail_code,
                         Null::bigint as eth_block,
                         Null::bigint as priority_op_serialid
                     FROM mempool_txs
@@ -137,8 +151,10 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 SELECT
                     tx_hash as "tx_hash!",
                     block_number as "block_number?",
+                    block_index as "block_index?",
                     success as "success?",
                     fail_reason as "fail_reason?",
+                    fail_code as "fail_code?",
                     eth_block as "eth_block?",
                     priority_op_serialid as "priority_op_serialid?"
                 FROM everything
@@ -150,12 +166,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         .await?;
 
         let result = if let Some(receipt) = receipt {
-            let is_block_finalized =
-                is_block_finalized(&mut transaction, receipt.block_number).await?;
+            let block_status = block_tx_status(&mut transaction, receipt.block_number).await?;
 
             Some(StorageTxReceipt::receipt_from_storage_receipt(
                 receipt,
-                is_block_finalized,
+                block_status,
             ))
         } else {
             None
@@ -380,6 +395,117 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         result
     }
 
+    /// Loads the full exits requested by the address, both pending and executed ones,
+    /// starting from the newest one.
+    pub async fn get_account_full_exits(
+        &mut self,
+        address: Address,
+        limit: u32,
+    ) -> QueryResult<Vec<FullExitInfo>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let pending_ops = sqlx::query!(
+            r#"
+                SELECT serial_id, data, eth_hash, eth_block, confirmed
+                FROM mempool_priority_operations
+                WHERE type = 'FullExit' AND l1_address = $1 AND reverted = false
+                    AND NOT EXISTS (
+                        SELECT 1 FROM executed_priority_operations
+                        WHERE priority_op_serialid = mempool_priority_operations.serial_id
+                    )
+                ORDER BY serial_id DESC
+                LIMIT $2
+            "#,
+            address.as_bytes(),
+            i64::from(limit)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let executed_ops = sqlx::query!(
+            r#"
+                SELECT priority_op_serialid, operation, eth_hash, eth_block, block_number
+                FROM executed_priority_operations
+                WHERE from_account = $1 AND operation->>'type' = 'FullExit'
+                ORDER BY priority_op_serialid DESC
+                LIMIT $2
+            "#,
+            address.as_bytes(),
+            i64::from(limit)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let last_finalized = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+
+        let mut full_exits = Vec::with_capacity(pending_ops.len() + executed_ops.len());
+        for op in pending_ops {
+            let priority_op = match serde_json::from_value(op.data)? {
+                ZkSyncPriorityOp::FullExit(priority_op) => priority_op,
+                _ => continue,
+            };
+            let status = if op.confirmed {
+                FullExitStatus::Queued
+            } else {
+                FullExitStatus::AwaitingConfirmations
+            };
+            full_exits.push(FullExitInfo {
+                serial_id: op.serial_id as SerialId,
+                eth_tx_hash: H256::from_slice(&op.eth_hash),
+                account_id: priority_op.account_id,
+                token_id: priority_op.token,
+                received_on_block: op.eth_block as u64,
+                status,
+                remaining_confirmations: None,
+                block_number: None,
+                withdraw_amount: None,
+            });
+        }
+        for op in executed_ops {
+            let full_exit = match serde_json::from_value(op.operation)? {
+                ZkSyncOp::FullExit(full_exit) => full_exit,
+                _ => continue,
+            };
+            let FullExitOp {
+                priority_op,
+                withdraw_amount,
+                ..
+            } = *full_exit;
+            let block_number = BlockNumber(op.block_number as u32);
+            let status = if block_number <= last_finalized {
+                FullExitStatus::Finalized
+            } else {
+                FullExitStatus::Committed
+            };
+            full_exits.push(FullExitInfo {
+                serial_id: op.priority_op_serialid as SerialId,
+                eth_tx_hash: H256::from_slice(&op.eth_hash),
+                account_id: priority_op.account_id,
+                token_id: priority_op.token,
+                received_on_block: op.eth_block as u64,
+                status,
+                remaining_confirmations: None,
+                block_number: Some(block_number),
+                withdraw_amount,
+            });
+        }
+        transaction.commit().await?;
+
+        full_exits.sort_by(|a, b| b.serial_id.cmp(&a.serial_id));
+        full_exits.truncate(limit as usize);
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_full_exits",
+            start.elapsed()
+        );
+        Ok(full_exits)
+    }
+
     pub async fn get_tx_by_hash(&mut self, hash: &[u8]) -> QueryResult<Option<TxByHashResponse>> {
         let start = Instant::now();
 
@@ -1112,6 +1238,264 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(txs)
     }
 
+    /// Loads the page of the account transactions starting right after the cursor.
+    /// Unlike `get_account_transactions`, the position is not resolved from the transaction
+    /// hash and the total count is not calculated, so the cost of the query doesn't depend
+    /// on the size of the account history.
+    pub async fn get_account_transactions_by_cursor(
+        &mut self,
+        query: &PaginationQuery<AccountTxsCursorRequest>,
+    ) -> QueryResult<CursorPaginated<Transaction>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        // The cursor itself is excluded from the page.
+        let id_from = match (query.from.cursor, query.direction) {
+            (Some(cursor), PaginationDirection::Newer) => cursor.0.saturating_add(1),
+            (Some(cursor), PaginationDirection::Older) => cursor.0.saturating_sub(1),
+            (None, PaginationDirection::Newer) => 0,
+            (None, PaginationDirection::Older) => i64::MAX,
+        };
+        // One extra item is loaded to find out whether there is a next page.
+        let limit = i64::from(query.limit) + 1;
+
+        let mut raw_txs = if let Some(address) = query.from.second_address {
+            // It's impossible to have priority operations for two accounts
+            transaction
+                .chain()
+                .operations_ext_schema()
+                .get_executed_transactions_for_two_accounts(
+                    query.from.address,
+                    address,
+                    query.from.token,
+                    limit,
+                    id_from,
+                    query.direction,
+                )
+                .await?
+        } else {
+            let (priority_records, executed_records): (Vec<_>, Vec<_>) = transaction
+                .chain()
+                .operations_ext_schema()
+                .get_tx_seq_numbers_for_account(
+                    query.from.address,
+                    query.from.token,
+                    limit,
+                    id_from,
+                    query.direction,
+                )
+                .await?
+                .into_iter()
+                .partition(|record| record.is_priority);
+
+            let mut txs = transaction
+                .chain()
+                .operations_ext_schema()
+                .get_executed_txs_for_account(
+                    executed_records
+                        .into_iter()
+                        .map(|record| record.sequence_number)
+                        .collect(),
+                )
+                .await?;
+            txs.append(
+                &mut transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_priority_operations_for_account(
+                        priority_records
+                            .into_iter()
+                            .map(|record| record.sequence_number)
+                            .collect(),
+                    )
+                    .await?,
+            );
+            txs
+        };
+        raw_txs.sort_by(|tx1, tx2| match query.direction {
+            PaginationDirection::Newer => tx1.sequence_number.cmp(&tx2.sequence_number),
+            PaginationDirection::Older => tx2.sequence_number.cmp(&tx1.sequence_number),
+        });
+
+        let next = if raw_txs.len() > query.limit as usize {
+            raw_txs.truncate(query.limit as usize);
+            raw_txs.last().and_then(|tx| tx.sequence_number).map(Cursor)
+        } else {
+            None
+        };
+
+        let last_finalized = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let txs = raw_txs
+            .into_iter()
+            .map(|tx| {
+                let is_finalized = tx.block_number as u32 <= *last_finalized;
+                TransactionItem::transaction_from_item(tx, is_finalized)
+            })
+            .collect();
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_transactions_by_cursor",
+            start.elapsed()
+        );
+        Ok(CursorPaginated::new(
+            txs,
+            query.from.cursor,
+            query.limit,
+            query.direction,
+            next,
+        ))
+    }
+
+    /// Loads the page of the events (executed transactions and priority operations)
+    /// matching the filter, starting right after the cursor.
+    /// Address and token filters are resolved with the `tx_filters` index, so the events
+    /// affecting the account are found regardless of the role of the account in the operation.
+    pub async fn get_events(
+        &mut self,
+        query: &PaginationQuery<EventsRequest>,
+    ) -> QueryResult<CursorPaginated<L2Event>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let filter = &query.from.filter;
+        // The cursor itself is excluded from the page.
+        let (id_from, cursor_condition, order) = match (query.from.cursor, query.direction) {
+            (Some(cursor), PaginationDirection::Newer) => {
+                (cursor.0.saturating_add(1), "sequence_number >= $1", "ASC")
+            }
+            (Some(cursor), PaginationDirection::Older) => {
+                (cursor.0.saturating_sub(1), "sequence_number <= $1", "DESC")
+            }
+            (None, PaginationDirection::Newer) => (0, "sequence_number >= $1", "ASC"),
+            (None, PaginationDirection::Older) => (i64::MAX, "sequence_number <= $1", "DESC"),
+        };
+        // One extra item is loaded to find out whether there is a next page.
+        let limit = i64::from(query.limit) + 1;
+
+        let conditions = |is_priority: bool, op_column: &str| {
+            let mut conditions = vec![cursor_condition.to_owned()];
+            if filter.address.is_some() || filter.token.is_some() {
+                let mut tx_filters = vec![format!("is_priority = {}", is_priority)];
+                if filter.address.is_some() {
+                    tx_filters.push("address = $3".to_owned());
+                }
+                if filter.token.is_some() {
+                    tx_filters.push("token = $4".to_owned());
+                }
+                conditions.push(format!(
+                    "sequence_number IN (SELECT sequence_number FROM tx_filters WHERE {})",
+                    tx_filters.join(" AND ")
+                ));
+            }
+            if filter.op_type.is_some() {
+                conditions.push(format!("{}->>'type' = $5", op_column));
+            }
+            if filter.from_block.is_some() {
+                conditions.push("block_number >= $6".to_owned());
+            }
+            if filter.to_block.is_some() {
+                conditions.push("block_number <= $7".to_owned());
+            }
+            conditions.join(" AND ")
+        };
+
+        let query_str = format!(
+            r#"
+                SELECT * FROM (
+                    SELECT
+                        sequence_number,
+                        tx_hash,
+                        tx as op,
+                        block_number,
+                        created_at,
+                        success,
+                        fail_reason,
+                        Null::bytea as eth_hash,
+                        Null::bigint as priority_op_serialid,
+                        block_index,
+                        batch_id
+                    FROM executed_transactions
+                    WHERE {}
+                    UNION ALL
+                    SELECT
+                        sequence_number,
+                        tx_hash,
+                        operation as op,
+                        block_number,
+                        created_at,
+                        true as success,
+                        Null as fail_reason,
+                        eth_hash,
+                        priority_op_serialid,
+                        block_index,
+                        Null::bigint as batch_id
+                    FROM executed_priority_operations
+                    WHERE {}
+                ) events
+                ORDER BY sequence_number {}
+                LIMIT $2
+            "#,
+            conditions(false, "tx"),
+            conditions(true, "operation"),
+            order
+        );
+
+        let mut items: Vec<TransactionItem> = sqlx::query_as(&query_str)
+            .bind(id_from)
+            .bind(limit)
+            .bind(filter.address.unwrap_or_default().as_bytes())
+            .bind(filter.token.unwrap_or_default().0 as i32)
+            .bind(filter.op_type.clone().unwrap_or_default())
+            .bind(i64::from(*filter.from_block.unwrap_or_default()))
+            .bind(i64::from(*filter.to_block.unwrap_or_default()))
+            .fetch_all(transaction.conn())
+            .await?;
+
+        let next = if items.len() > query.limit as usize {
+            items.truncate(query.limit as usize);
+            items
+                .last()
+                .and_then(|item| item.sequence_number)
+                .map(Cursor)
+        } else {
+            None
+        };
+
+        let last_finalized = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let events = items
+            .into_iter()
+            .map(|item| {
+                let cursor = Cursor(item.sequence_number.unwrap_or_default());
+                let op_type = item.op["type"].as_str().unwrap_or_default().to_owned();
+                let is_finalized = item.block_number as u32 <= *last_finalized;
+                L2Event {
+                    cursor,
+                    op_type,
+                    tx: TransactionItem::transaction_from_item(item, is_finalized),
+                }
+            })
+            .collect();
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.operations_ext.get_events", start.elapsed());
+        Ok(CursorPaginated::new(
+            events,
+            query.from.cursor,
+            query.limit,
+            query.direction,
+            next,
+        ))
+    }
+
     async fn get_executed_transactions_for_two_accounts(
         &mut self,
         address: Address,
@@ -1506,8 +1890,18 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 .map(|tx| TxHashSerializeWrapper(TxHash::from_slice(&tx.tx_hash).unwrap()))
                 .collect();
             let block_number = BlockNumber(batch_data[0].block_number as u32);
+            let is_block_pending = transaction
+                .chain()
+                .block_schema()
+                .is_block_pending(block_number)
+                .await?;
             let batch_status = if batch_data[0].success {
-                if let Some(op) = transaction
+                if is_block_pending {
+                    BatchStatus {
+                        updated_at: created_at,
+                        last_state: TxInBlockStatus::Pending,
+                    }
+                } else if let Some(op) = transaction
                     .chain()
                     .operations_schema()
                     .get_stored_aggregated_operation(
@@ -2002,23 +2396,26 @@ async fn complete_withdrawals_tx_hash(
     Ok(result)
 }
 
-async fn is_block_finalized(
+/// Returns the status of the successful operations included into the block.
+async fn block_tx_status(
     transaction: &mut StorageProcessor<'_>,
     block_number: Option<i64>,
-) -> QueryResult<Option<bool>> {
+) -> QueryResult<Option<TxInBlockStatus>> {
     // We always use Option<i64> for block number in cases with this module.
     // So it's much cleaner to keep this check here
-    if let Some(block_number) = block_number {
-        Ok(Some(
-            transaction
-                .chain()
-                .block_schema()
-                .is_block_finalized(BlockNumber(block_number as u32))
-                .await?,
-        ))
+    let block_number = match block_number {
+        Some(block_number) => BlockNumber(block_number as u32),
+        None => return Ok(None),
+    };
+    let mut block_schema = transaction.chain().block_schema();
+    let status = if block_schema.is_block_finalized(block_number).await? {
+        TxInBlockStatus::Finalized
+    } else if block_schema.is_block_pending(block_number).await? {
+        TxInBlockStatus::Pending
     } else {
-        Ok(None)
-    }
+        TxInBlockStatus::Committed
+    };
+    Ok(Some(status))
 }
 
 async fn tx_data_from_storage(
@@ -2027,11 +2424,11 @@ async fn tx_data_from_storage(
 ) -> QueryResult<TxData> {
     let complete_withdrawals_tx_hash = complete_withdrawals_tx_hash(transaction, &data).await?;
 
-    let is_block_finalized = is_block_finalized(transaction, data.block_number).await?;
+    let block_status = block_tx_status(transaction, data.block_number).await?;
 
     Ok(StorageTxData::data_from_storage_data(
         data,
-        is_block_finalized,
+        block_status,
         complete_withdrawals_tx_hash,
     ))
 }
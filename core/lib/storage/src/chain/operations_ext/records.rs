@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::value::Value;
 use sqlx::FromRow;
 // Workspace imports
+use zksync_types::tx::TxFailureCode;
 // Local imports
 use crate::prover::records::ProverRun;
 
@@ -45,6 +46,7 @@ pub struct TxReceiptResponse {
     pub success: bool,
     pub verified: bool,
     pub fail_reason: Option<String>,
+    pub fail_code: Option<TxFailureCode>,
     pub prover_run: Option<ProverRun>,
 }
 
@@ -155,8 +157,10 @@ pub struct InBlockBatchTx {
 pub struct StorageTxReceipt {
     pub tx_hash: Vec<u8>,
     pub block_number: Option<i64>,
+    pub block_index: Option<i32>,
     pub success: Option<bool>,
     pub fail_reason: Option<String>,
+    pub fail_code: Option<String>,
     pub eth_block: Option<i64>,
     pub priority_op_serialid: Option<i64>,
 }
@@ -676,6 +676,84 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         }
     }
 
+    /// Loads the updates of the account committed in the blocks range (both ends are inclusive),
+    /// in the order they were applied to the state.
+    pub async fn load_account_state_changes(
+        &mut self,
+        account_id: AccountId,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<Vec<(BlockNumber, AccountUpdate)>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let account_id = i64::from(*account_id);
+        let from_block = i64::from(*from_block);
+        let to_block = i64::from(*to_block);
+
+        let balance_updates = sqlx::query_as!(
+            StorageAccountUpdate,
+            "SELECT * FROM account_balance_updates
+            WHERE account_id = $1 AND block_number >= $2 AND block_number <= $3",
+            account_id,
+            from_block,
+            to_block,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let creates = sqlx::query_as!(
+            StorageAccountCreation,
+            "SELECT * FROM account_creates
+            WHERE account_id = $1 AND block_number >= $2 AND block_number <= $3",
+            account_id,
+            from_block,
+            to_block,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let pubkey_updates = sqlx::query_as!(
+            StorageAccountPubkeyUpdate,
+            "SELECT * FROM account_pubkey_updates
+            WHERE account_id = $1 AND block_number >= $2 AND block_number <= $3",
+            account_id,
+            from_block,
+            to_block,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let mint_nft_updates = sqlx::query_as!(
+            StorageMintNFTUpdate,
+            "SELECT * FROM mint_nft_updates
+            WHERE creator_account_id = $1 AND block_number >= $2 AND block_number <= $3",
+            account_id as i32,
+            from_block,
+            to_block,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        let mut account_diff: Vec<StorageAccountDiff> = Vec::new();
+        account_diff.extend(balance_updates.into_iter().map(StorageAccountDiff::from));
+        account_diff.extend(creates.into_iter().map(StorageAccountDiff::from));
+        account_diff.extend(pubkey_updates.into_iter().map(StorageAccountDiff::from));
+        account_diff.extend(mint_nft_updates.into_iter().map(StorageAccountDiff::from));
+        account_diff.sort_by(StorageAccountDiff::cmp_order);
+        let changes = account_diff
+            .into_iter()
+            .map(|diff| {
+                let block_number = BlockNumber(diff.block_number() as u32);
+                let (_, update): (AccountId, AccountUpdate) = diff.into();
+                (block_number, update)
+            })
+            .collect();
+
+        metrics::histogram!(
+            "sql.chain.state.load_account_state_changes",
+            start.elapsed()
+        );
+        Ok(changes)
+    }
+
     /// Loads the state of accounts updated in a specific block.
     pub async fn load_state_diff_for_block(
         &mut self,
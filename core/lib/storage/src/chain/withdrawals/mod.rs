@@ -0,0 +1,140 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{
+    aggregated_operations::BlocksExecuteOperation, ethereum::WithdrawalStatus, tx::TxHash, H256,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub use self::records::StoredWithdrawalStatus;
+
+pub mod records;
+
+/// Withdrawals schema tracks the status of every withdrawal processed by the contract
+/// during the blocks execution.
+#[derive(Debug)]
+pub struct WithdrawalsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> WithdrawalsSchema<'a, 'c> {
+    /// Stores the withdrawals of the execute operation. Depending on the operation, the
+    /// withdrawals are either scheduled to be completed by the execute transaction or deferred.
+    pub async fn schedule_withdrawals(
+        &mut self,
+        aggregated_op_id: i64,
+        operation: &BlocksExecuteOperation,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let status = if operation.complete_withdrawals {
+            WithdrawalStatus::Scheduled
+        } else {
+            WithdrawalStatus::Deferred
+        };
+
+        let mut tx_hashes = Vec::new();
+        let mut block_numbers = Vec::new();
+        for block in &operation.blocks {
+            for tx_hash in block.processable_ops_hashes() {
+                tx_hashes.push(tx_hash.as_ref().to_vec());
+                block_numbers.push(i64::from(*block.block_number));
+            }
+        }
+
+        sqlx::query!(
+            "
+            INSERT INTO withdrawals_status (tx_hash, block_number, aggregated_op_id, status, updated_at)
+            SELECT u.tx_hash, u.block_number, $3, $4, now()
+                FROM UNNEST ($1::bytea[], $2::bigint[])
+                AS u(tx_hash, block_number)
+            ON CONFLICT (tx_hash)
+            DO UPDATE SET aggregated_op_id = $3, status = $4, updated_at = now()
+            ",
+            &tx_hashes,
+            &block_numbers,
+            aggregated_op_id,
+            status.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.withdrawals.schedule_withdrawals",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Returns `true` if the withdrawals of the execute operation are completed
+    /// by the execute transaction.
+    pub async fn completes_withdrawals(&mut self, aggregated_op_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let completes = sqlx::query!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM withdrawals_status WHERE aggregated_op_id = $1 AND status <> $2
+            ) as "exists!"
+            "#,
+            aggregated_op_id,
+            WithdrawalStatus::Deferred.as_str(),
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .exists;
+
+        metrics::histogram!(
+            "sql.chain.withdrawals.completes_withdrawals",
+            start.elapsed()
+        );
+        Ok(completes)
+    }
+
+    /// Marks the scheduled withdrawals of the execute operation as completed
+    /// once the execute transaction is confirmed.
+    pub async fn confirm_withdrawals(
+        &mut self,
+        aggregated_op_id: i64,
+        eth_tx_hash: H256,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "
+            UPDATE withdrawals_status
+            SET status = (CASE WHEN status = $3 THEN $4 ELSE status END),
+                eth_tx_hash = $2,
+                updated_at = now()
+            WHERE aggregated_op_id = $1
+            ",
+            aggregated_op_id,
+            eth_tx_hash.as_bytes(),
+            WithdrawalStatus::Scheduled.as_str(),
+            WithdrawalStatus::Completed.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.withdrawals.confirm_withdrawals", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the status of the withdrawal with the given hash.
+    pub async fn get_withdrawal_status(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<StoredWithdrawalStatus>> {
+        let start = Instant::now();
+        let status = sqlx::query_as!(
+            StoredWithdrawalStatus,
+            "SELECT * FROM withdrawals_status WHERE tx_hash = $1",
+            tx_hash.as_ref(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.withdrawals.get_withdrawal_status",
+            start.elapsed()
+        );
+        Ok(status)
+    }
+}
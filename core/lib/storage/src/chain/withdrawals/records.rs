@@ -0,0 +1,24 @@
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::ethereum::WithdrawalStatus;
+// Local imports
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredWithdrawalStatus {
+    pub tx_hash: Vec<u8>,
+    pub block_number: i64,
+    pub aggregated_op_id: i64,
+    pub status: String,
+    pub eth_tx_hash: Option<Vec<u8>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StoredWithdrawalStatus {
+    pub fn status(&self) -> WithdrawalStatus {
+        self.status
+            .parse()
+            .expect("Withdrawal status should be correctly stored")
+    }
+}
@@ -55,6 +55,11 @@ impl Manager for DbPool {
 #[derive(Clone)]
 pub struct ConnectionPool {
     pool: Pool,
+    /// Name of the pool used as the label of the metrics.
+    name: &'static str,
+    /// Maximum number of the tasks waiting for a connection, the rest are rejected right away.
+    /// `None` if the tasks wait for the connection until the pool timeout.
+    max_waiting: Option<usize>,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -73,7 +78,11 @@ impl ConnectionPool {
 
         let pool = DbPool::create(database_url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            name: "main",
+            max_waiting: None,
+        }
     }
 
     /// Establishes a pool of the connections to the replica of database and
@@ -86,8 +95,34 @@ impl ConnectionPool {
 
         let pool = DbPool::create(database_url, max_size as usize);
 
-        Self { pool }
+        Self {
+            pool,
+            name: "replica",
+            max_waiting: None,
+        }
+    }
+
+    /// Establishes a pool of the connections to the replica of database dedicated to the API reads,
+    /// so the bursts of the API traffic don't starve the core components of the connections.
+    /// The requests are rejected once too many of them are waiting for a connection.
+    /// pool_max_size - number of connections in pool,
+    /// if not set env variable "DATABASE_API_POOL_SIZE" is going to be used.
+    /// max_waiting - maximum number of the waiting requests,
+    /// if not set env variable "DATABASE_API_POOL_MAX_WAITING" is going to be used.
+    pub fn new_api_pool(pool_max_size: Option<u32>, max_waiting: Option<usize>) -> Self {
+        let database_url = get_database_replica_url();
+        let max_size = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_API_POOL_SIZE"));
+        let max_waiting = max_waiting.unwrap_or_else(|| parse_env("DATABASE_API_POOL_MAX_WAITING"));
+
+        let pool = DbPool::create(database_url, max_size as usize);
+
+        Self {
+            pool,
+            name: "api",
+            max_waiting: Some(max_waiting),
+        }
     }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -97,13 +132,48 @@ impl ConnectionPool {
     /// This method is intended to be used in crucial contexts, where the
     /// database access is must-have (e.g. block committer).
     pub async fn access_storage(&self) -> Result<StorageProcessor<'_>, SqlxError> {
+        if let Some(max_waiting) = self.max_waiting {
+            if self.is_exhausted() && self.waiting() >= max_waiting {
+                metrics::increment_counter!("sql.pool.rejected", "pool" => self.name);
+                return Err(SqlxError::PoolTimedOut);
+            }
+        }
+
         let start = Instant::now();
         let connection = self.get_pooled_connection().await;
-        metrics::histogram!("sql.connection_acquire", start.elapsed());
+        metrics::histogram!("sql.connection_acquire", start.elapsed(), "pool" => self.name);
 
         Ok(StorageProcessor::from_pool(connection))
     }
 
+    /// Reports the utilization of the pool to the metrics, the `pool` label distinguishes
+    /// the pools of the same process.
+    pub fn report_utilization(&self) {
+        let pool_name = self.name;
+        let status = self.pool.status();
+        // `available` is negative when there are tasks waiting for the connection.
+        let in_use = status.size as isize - status.available.max(0);
+        let waiting = self.waiting();
+        metrics::gauge!("sql.pool.max_size", status.max_size as f64, "pool" => pool_name);
+        metrics::gauge!("sql.pool.size", status.size as f64, "pool" => pool_name);
+        metrics::gauge!("sql.pool.in_use", in_use as f64, "pool" => pool_name);
+        metrics::gauge!("sql.pool.waiting", waiting as f64, "pool" => pool_name);
+        // Share of the connections in use, the pool is saturated once it reaches 1.
+        let saturation = in_use as f64 / status.max_size.max(1) as f64;
+        metrics::gauge!("sql.pool.saturation", saturation, "pool" => pool_name);
+    }
+
+    /// Number of the tasks waiting for a connection.
+    fn waiting(&self) -> usize {
+        (-self.pool.status().available).max(0) as usize
+    }
+
+    /// Checks whether all the connections are in use and no new connection can be opened.
+    fn is_exhausted(&self) -> bool {
+        let status = self.pool.status();
+        status.size >= status.max_size && status.available <= 0
+    }
+
     async fn get_pooled_connection(&self) -> PooledConnection {
         let mut retry_count = 0;
 
@@ -0,0 +1,177 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{tx::TxHash, H256};
+// Local imports
+use self::records::StoredEncryptedTx;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Encrypted transactions schema stores the envelopes submitted with the visibility delay
+/// until they are revealed, along with the results of their submission.
+#[derive(Debug)]
+pub struct EncryptedTxsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> EncryptedTxsSchema<'a, 'c> {
+    /// Stores the envelope submitted by the client to be revealed at the given moment.
+    /// The repeated submission of the same envelope doesn't change the stored one.
+    pub async fn store_envelope(
+        &mut self,
+        envelope_hash: H256,
+        envelope: &[u8],
+        reveal_at: DateTime<Utc>,
+        submitted_by: Option<&str>,
+    ) -> QueryResult<StoredEncryptedTx> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO encrypted_txs (
+                envelope_hash, envelope, created_at, reveal_at, submitted_by
+            )
+            VALUES ( $1, $2, $3, $4, $5 )
+            ON CONFLICT (envelope_hash) DO NOTHING
+            "#,
+            envelope_hash.as_bytes(),
+            envelope,
+            Utc::now(),
+            reveal_at,
+            submitted_by
+        )
+        .execute(transaction.conn())
+        .await?;
+        let stored = EncryptedTxsSchema(&mut transaction)
+            .get_envelope(envelope_hash)
+            .await?
+            .expect("Envelope was stored above");
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.encrypted_txs.store_envelope", start.elapsed());
+        Ok(stored)
+    }
+
+    /// Loads the envelope by its hash.
+    pub async fn get_envelope(
+        &mut self,
+        envelope_hash: H256,
+    ) -> QueryResult<Option<StoredEncryptedTx>> {
+        let start = Instant::now();
+        let stored = sqlx::query_as!(
+            StoredEncryptedTx,
+            "SELECT * FROM encrypted_txs WHERE envelope_hash = $1",
+            envelope_hash.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.encrypted_txs.get_envelope", start.elapsed());
+        Ok(stored)
+    }
+
+    /// Returns the number of the envelopes submitted by the client which are not revealed yet.
+    pub async fn count_pending_envelopes(&mut self, submitted_by: &str) -> QueryResult<u64> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!" FROM encrypted_txs
+            WHERE submitted_by = $1 AND revealed_at IS NULL
+            "#,
+            submitted_by
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!("sql.encrypted_txs.count_pending_envelopes", start.elapsed());
+        Ok(count as u64)
+    }
+
+    /// Claims up to `limit` envelopes which may be revealed and returns them.
+    ///
+    /// The claimed envelopes are skipped by the other API server replicas until the claim expires
+    /// after `claim_timeout`. If the results are not stored by then (e.g. the server is stopped
+    /// in the middle), the envelopes are revealed again instead of being lost.
+    pub async fn claim_envelopes_to_reveal(
+        &mut self,
+        limit: u32,
+        claim_timeout: chrono::Duration,
+    ) -> QueryResult<Vec<StoredEncryptedTx>> {
+        let start = Instant::now();
+        let now = Utc::now();
+        let envelopes = sqlx::query_as!(
+            StoredEncryptedTx,
+            r#"
+            UPDATE encrypted_txs SET claimed_at = $1
+            WHERE envelope_hash IN (
+                SELECT envelope_hash FROM encrypted_txs
+                WHERE revealed_at IS NULL AND reveal_at <= $1
+                    AND (claimed_at IS NULL OR claimed_at < $2)
+                ORDER BY reveal_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+            now,
+            now - claim_timeout,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.encrypted_txs.claim_envelopes_to_reveal",
+            start.elapsed()
+        );
+        Ok(envelopes)
+    }
+
+    /// Marks the envelope as revealed and stores the result of its submission.
+    /// The envelope itself is not needed anymore, so it's dropped.
+    pub async fn store_reveal_result(
+        &mut self,
+        envelope_hash: H256,
+        tx_hashes: &[TxHash],
+        error: Option<String>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = tx_hashes
+            .iter()
+            .map(|hash| hash.as_ref().to_vec())
+            .collect();
+        sqlx::query!(
+            r#"
+            UPDATE encrypted_txs
+            SET revealed_at = $2, tx_hashes = $3, error = $4, envelope = '', claimed_at = NULL
+            WHERE envelope_hash = $1
+            "#,
+            envelope_hash.as_bytes(),
+            Utc::now(),
+            &tx_hashes,
+            error
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.encrypted_txs.store_reveal_result", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the envelopes revealed before the given moment, returns the number of removed ones.
+    pub async fn remove_revealed_envelopes(&mut self, before: DateTime<Utc>) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!("DELETE FROM encrypted_txs WHERE revealed_at < $1", before)
+            .execute(self.0.conn())
+            .await?
+            .rows_affected();
+
+        metrics::histogram!(
+            "sql.encrypted_txs.remove_revealed_envelopes",
+            start.elapsed()
+        );
+        Ok(removed)
+    }
+}
@@ -0,0 +1,43 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_api_types::v02::encrypted_tx::{EncryptedTxState, EncryptedTxStatus};
+use zksync_types::{tx::TxHash, H256};
+
+#[derive(Debug, Clone)]
+pub struct StoredEncryptedTx {
+    pub envelope_hash: Vec<u8>,
+    pub envelope: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub reveal_at: DateTime<Utc>,
+    pub revealed_at: Option<DateTime<Utc>>,
+    pub tx_hashes: Vec<Vec<u8>>,
+    pub error: Option<String>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub submitted_by: Option<String>,
+}
+
+impl From<StoredEncryptedTx> for EncryptedTxStatus {
+    fn from(val: StoredEncryptedTx) -> Self {
+        let state = match (val.revealed_at, &val.error) {
+            (None, _) => EncryptedTxState::Pending,
+            (Some(_), None) => EncryptedTxState::Revealed,
+            (Some(_), Some(_)) => EncryptedTxState::Rejected,
+        };
+        Self {
+            envelope_hash: H256::from_slice(&val.envelope_hash),
+            state,
+            created_at: val.created_at,
+            reveal_at: val.reveal_at,
+            revealed_at: val.revealed_at,
+            tx_hashes: val
+                .tx_hashes
+                .iter()
+                .map(|hash| {
+                    TxHash::from_slice(hash).expect("Incorrect tx hash stored in the database")
+                })
+                .collect(),
+            error: val.error,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::SerialId;
+// Local imports
+use self::records::StoredEthWatchState;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Ethereum watcher schema stores the progress of the Ethereum watcher, so after the restart
+/// it only rescans the Ethereum blocks processed since the last update.
+///
+/// The unconfirmed priority operations are stored in the mempool schema.
+#[derive(Debug)]
+pub struct EthWatchSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> EthWatchSchema<'a, 'c> {
+    /// Loads the state of the Ethereum watcher, `None` if it was never saved.
+    pub async fn load_state(&mut self) -> QueryResult<Option<StoredEthWatchState>> {
+        let start = Instant::now();
+        let state = sqlx::query_as!(
+            StoredEthWatchState,
+            "SELECT last_ethereum_block, next_priority_op_id, updated_at FROM eth_watch_state"
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.eth_watch.load_state", start.elapsed());
+        Ok(state)
+    }
+
+    /// Saves the last Ethereum block processed by the watcher and the serial id
+    /// of the next priority operation it expects.
+    pub async fn save_state(
+        &mut self,
+        last_ethereum_block: u64,
+        next_priority_op_id: SerialId,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO eth_watch_state ( last_ethereum_block, next_priority_op_id, updated_at )
+            VALUES ( $1, $2, now() )
+            ON CONFLICT (id) DO UPDATE
+            SET last_ethereum_block = $1, next_priority_op_id = $2, updated_at = now()
+            "#,
+            last_ethereum_block as i64,
+            next_priority_op_id as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.eth_watch.save_state", start.elapsed());
+        Ok(())
+    }
+}
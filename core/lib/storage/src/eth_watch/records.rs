@@ -0,0 +1,22 @@
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::SerialId;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredEthWatchState {
+    pub last_ethereum_block: i64,
+    pub next_priority_op_id: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StoredEthWatchState {
+    pub fn last_ethereum_block(&self) -> u64 {
+        self.last_ethereum_block as u64
+    }
+
+    pub fn next_priority_op_id(&self) -> SerialId {
+        self.next_priority_op_id as SerialId
+    }
+}
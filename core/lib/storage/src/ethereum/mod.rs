@@ -7,14 +7,16 @@ use sqlx::types::BigDecimal;
 // Workspace imports
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
-    ethereum::{ETHOperation, InsertedOperationResponse},
+    ethereum::{ETHOperation, InsertedOperationResponse, OperatorKeyUsage},
     event::{
         account::AccountStateChangeStatus, block::BlockStatus, transaction::TransactionStatus,
     },
-    BlockNumber, H256, U256,
+    Address, BlockNumber, H256, U256,
 };
 // Local imports
-use self::records::{ETHOperationData, ETHParams, ETHStats, ETHTxHash, StorageETHOperation};
+use self::records::{
+    ETHOperationData, ETHParams, ETHStats, ETHTxHash, StorageETHOperation, StoredOperatorKeyUsage,
+};
 use crate::{chain::operations::records::StoredAggregatedOperation, QueryResult, StorageProcessor};
 use chrono::{DateTime, Utc};
 
@@ -93,11 +95,17 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 .expect("Stored operation type must have a valid value");
             let last_used_gas_price =
                 U256::from_str(&eth_op.last_used_gas_price.to_string()).unwrap();
+            let last_used_priority_fee = eth_op
+                .last_used_priority_fee
+                .map(|fee| U256::from_str(&fee.to_string()).unwrap());
             let used_tx_hashes = eth_tx_hashes
                 .iter()
                 .map(|entry| H256::from_slice(&entry.tx_hash))
                 .collect();
             let final_hash = eth_op.final_hash.map(|hash| H256::from_slice(&hash));
+            let sender = eth_op
+                .sender_address
+                .map(|address| Address::from_slice(&address));
 
             let eth_op = ETHOperation {
                 id: eth_op.id,
@@ -105,7 +113,10 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 op,
                 nonce: eth_op.nonce.into(),
                 last_deadline_block: eth_op.last_deadline_block as u64,
+                first_deadline_block: eth_op.first_deadline_block as u64,
                 last_used_gas_price,
+                last_used_priority_fee,
+                sender,
                 used_tx_hashes,
                 encoded_tx_data: eth_op.raw_tx,
                 confirmed: eth_op.confirmed,
@@ -215,12 +226,18 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
     /// Stores the sent (but not confirmed yet) Ethereum transaction in the database.
     /// Returns the `ETHOperation` object containing the assigned nonce and operation ID.
+    ///
+    /// The nonce is assigned from the ones of the `sender` key, which is the address of
+    /// the additional operator key or `None` for the main one.
+    #[allow(clippy::too_many_arguments)]
     pub async fn save_new_eth_tx(
         &mut self,
         op_type: AggregatedActionType,
         operation: Option<(i64, AggregatedOperation)>,
         last_deadline_block: i64,
         last_used_gas_price: BigUint,
+        last_used_priority_fee: Option<BigUint>,
+        sender: Option<Address>,
         raw_tx: Vec<u8>,
     ) -> QueryResult<InsertedOperationResponse> {
         let start = Instant::now();
@@ -228,19 +245,28 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         // It's important to assign nonce within the same db transaction
         // as saving the operation to avoid the state divergence.
-        let nonce = EthereumSchema(&mut transaction).get_next_nonce().await?;
+        let nonce = match sender {
+            Some(address) => {
+                EthereumSchema(&mut transaction)
+                    .get_next_operator_nonce(address)
+                    .await?
+            }
+            None => EthereumSchema(&mut transaction).get_next_nonce().await?,
+        };
 
         // Create and insert the operation.
 
         // Obtain the operation ID for the follow-up queried.
         let last_used_gas_price = BigDecimal::from(BigInt::from(last_used_gas_price));
+        let last_used_priority_fee =
+            last_used_priority_fee.map(|fee| BigDecimal::from(BigInt::from(fee)));
         let eth_op_id = sqlx::query!(
             "
-                INSERT INTO eth_operations (op_type, nonce, last_deadline_block, last_used_gas_price, raw_tx)
-                VALUES ($1, $2, $3, $4, $5)
+                INSERT INTO eth_operations (op_type, nonce, last_deadline_block, first_deadline_block, last_used_gas_price, last_used_priority_fee, sender_address, raw_tx)
+                VALUES ($1, $2, $3, $3, $4, $5, $6, $7)
                 RETURNING id
             ",
-            op_type.to_string(), nonce, last_deadline_block, last_used_gas_price, raw_tx,
+            op_type.to_string(), nonce, last_deadline_block, last_used_gas_price, last_used_priority_fee, sender.map(|address| address.as_bytes().to_vec()), raw_tx,
         )
         .fetch_one(transaction.conn())
         .await?
@@ -323,21 +349,25 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
     }
 
     /// Updates the Ethereum operation by adding a new tx data.
-    /// The new deadline block / gas value are placed instead of old values to the main entry.
+    /// The new deadline block / gas values are placed instead of old values to the main entry.
+    /// The priority fee is `None` for the legacy transactions.
     pub async fn update_eth_tx(
         &mut self,
         eth_op_id: i64,
         new_deadline_block: i64,
         new_gas_value: BigUint,
+        new_priority_fee: Option<BigUint>,
     ) -> QueryResult<()> {
         let start = Instant::now();
         // Update the stored tx.
         let new_gas_price = BigDecimal::from(BigInt::from(new_gas_value));
+        let new_priority_fee = new_priority_fee.map(|fee| BigDecimal::from(BigInt::from(fee)));
         sqlx::query!(
             "UPDATE eth_operations 
-            SET last_used_gas_price = $1, last_deadline_block = $2
-            WHERE id = $3",
+            SET last_used_gas_price = $1, last_used_priority_fee = $2, last_deadline_block = $3
+            WHERE id = $4",
             new_gas_price,
+            new_priority_fee,
             new_deadline_block,
             eth_op_id
         )
@@ -612,6 +642,129 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(old_nonce_value)
     }
 
+    /// Returns the nonce obtained by `get_next_nonce` if nothing was sent with it, so it doesn't
+    /// leave a gap. Returns `false` if the next nonce was obtained meanwhile, so the nonce can't
+    /// be returned.
+    pub async fn release_nonce(&mut self, nonce: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "UPDATE eth_parameters
+            SET nonce = $1
+            WHERE id = true AND nonce = $1 + 1",
+            nonce
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.release_nonce", start.elapsed());
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Obtains the next nonce of the additional operator key and updates the corresponding
+    /// entry in the database for the next invocation.
+    ///
+    /// The nonce of the key has to be initialized with `initialize_operator_nonce` first.
+    pub async fn get_next_operator_nonce(&mut self, address: Address) -> QueryResult<i64> {
+        let start = Instant::now();
+        let nonce = sqlx::query!(
+            r#"UPDATE eth_operator_nonces
+            SET nonce = nonce + 1
+            WHERE address = $1
+            RETURNING nonce - 1 AS "nonce!""#,
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .ok_or_else(|| format_err!("Nonce of the operator key {:?} is not initialized", address))?
+        .nonce;
+
+        metrics::histogram!("sql.ethereum.get_next_operator_nonce", start.elapsed());
+        Ok(nonce)
+    }
+
+    /// Initializes the nonce of the additional operator key, unless it's already stored.
+    /// The nonce is tracked by the database afterwards, like the one of the main key.
+    pub async fn initialize_operator_nonce(
+        &mut self,
+        address: Address,
+        nonce: i64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO eth_operator_nonces (address, nonce)
+            VALUES ($1, $2)
+            ON CONFLICT (address) DO NOTHING",
+            address.as_bytes(),
+            nonce
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.initialize_operator_nonce", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the nonce to be used by the next transaction of the additional operator key,
+    /// `None` if it's not initialized.
+    pub async fn load_operator_nonce(&mut self, address: Address) -> QueryResult<Option<i64>> {
+        let start = Instant::now();
+        let nonce = sqlx::query!(
+            "SELECT nonce FROM eth_operator_nonces WHERE address = $1",
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.nonce);
+
+        metrics::histogram!("sql.ethereum.load_operator_nonce", start.elapsed());
+        Ok(nonce)
+    }
+
+    /// Overrides the nonce to be used by the next transaction of the additional operator key.
+    /// Same as `set_nonce`, must only be used to resync the nonce with the chain.
+    pub async fn set_operator_nonce(&mut self, address: Address, nonce: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE eth_operator_nonces
+            SET nonce = $2
+            WHERE address = $1",
+            address.as_bytes(),
+            nonce
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.set_operator_nonce", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the nonce to be used by the next Ethereum transaction of the operator.
+    pub async fn load_nonce(&mut self) -> QueryResult<i64> {
+        let start = Instant::now();
+        let params = self.load_eth_params().await?;
+
+        metrics::histogram!("sql.ethereum.load_nonce", start.elapsed());
+        Ok(params.nonce)
+    }
+
+    /// Overrides the nonce to be used by the next Ethereum transaction of the operator.
+    /// Must only be used to resync the nonce with the chain while no transactions are sent,
+    /// i.e. by `eth_sender` on startup or while it's stopped.
+    pub async fn set_nonce(&mut self, nonce: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE eth_parameters
+            SET nonce = $1
+            WHERE id = true",
+            nonce
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.set_nonce", start.elapsed());
+        Ok(())
+    }
+
     /// Method that internally initializes the `eth_parameters` table.
     /// Since in db tests the database is empty, we must provide a possibility
     /// to initialize required db fields.
@@ -721,4 +874,53 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         metrics::histogram!("sql.ethereum.update_eth_parameters", start.elapsed());
         Ok(())
     }
+
+    /// Appends the entry to the operator key audit log.
+    pub async fn record_operator_key_usage(&mut self, usage: &OperatorKeyUsage) -> QueryResult<()> {
+        let start = Instant::now();
+        let to_decimal = |value: U256| BigDecimal::from_str(&value.to_string()).unwrap();
+        sqlx::query!(
+            "
+                INSERT INTO operator_key_audit_log (
+                    eth_op_id, op_type, contract_address, payload_hash, nonce,
+                    gas_limit, gas_price, value, tx_hash, outcome, reason
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ",
+            usage.eth_op_id,
+            usage.op_type.to_string(),
+            usage.contract_addr.as_bytes(),
+            usage.payload_hash.as_bytes(),
+            to_decimal(usage.nonce),
+            to_decimal(usage.gas_limit),
+            to_decimal(usage.gas_price),
+            to_decimal(usage.value),
+            usage.tx_hash.as_ref().map(|hash| hash.as_bytes()),
+            usage.outcome.as_str(),
+            usage.reason,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.record_operator_key_usage", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the audit log entries of the Ethereum operation, oldest first.
+    pub async fn load_operator_key_usages(
+        &mut self,
+        eth_op_id: i64,
+    ) -> QueryResult<Vec<StoredOperatorKeyUsage>> {
+        let start = Instant::now();
+        let usages = sqlx::query_as!(
+            StoredOperatorKeyUsage,
+            "SELECT * FROM operator_key_audit_log WHERE eth_op_id = $1 ORDER BY id",
+            eth_op_id
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_operator_key_usages", start.elapsed());
+        Ok(usages)
+    }
 }
@@ -15,6 +15,9 @@ pub struct StorageETHOperation {
     pub last_deadline_block: i64,
     pub last_used_gas_price: BigDecimal,
     pub created_at: Option<DateTime<Utc>>,
+    pub last_used_priority_fee: Option<BigDecimal>,
+    pub sender_address: Option<Vec<u8>>,
+    pub first_deadline_block: i64,
 }
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
@@ -30,6 +33,9 @@ pub struct ETHOperationData {
     pub agg_op_id: Option<i64>,
     pub arguments: Option<serde_json::Value>,
     pub created_at: Option<DateTime<Utc>>,
+    pub last_used_priority_fee: Option<BigDecimal>,
+    pub sender_address: Option<Vec<u8>>,
+    pub first_deadline_block: i64,
 }
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
@@ -67,3 +73,21 @@ impl From<ETHParams> for ETHStats {
         }
     }
 }
+
+/// Stored entry of the operator key audit log.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredOperatorKeyUsage {
+    pub id: i64,
+    pub eth_op_id: i64,
+    pub op_type: String,
+    pub contract_address: Vec<u8>,
+    pub payload_hash: Vec<u8>,
+    pub nonce: BigDecimal,
+    pub gas_limit: BigDecimal,
+    pub gas_price: BigDecimal,
+    pub value: BigDecimal,
+    pub tx_hash: Option<Vec<u8>>,
+    pub outcome: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
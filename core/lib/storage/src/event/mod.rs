@@ -13,7 +13,7 @@ use zksync_types::{
         transaction::{TransactionEvent, TransactionStatus},
         EventId,
     },
-    BlockNumber,
+    BlockNumber, ZkSyncTx,
 };
 // Local uses
 use crate::{QueryResult, StorageProcessor};
@@ -287,4 +287,42 @@ impl<'a, 'c> EventSchema<'a, 'c> {
         metrics::histogram!("sql.event.store_queued_transaction_event", start.elapsed());
         Ok(())
     }
+
+    /// Store the `Rejected` event of the transaction that didn't get into the mempool.
+    /// The event is attributed to the last committed block.
+    pub async fn store_rejected_transaction_event(
+        &mut self,
+        tx: &ZkSyncTx,
+        fail_reason: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let block_number = transaction
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+
+        if let Some(transaction_event) =
+            TransactionEvent::rejected(tx, block_number, fail_reason.to_string())
+        {
+            let event = serde_json::to_value(transaction_event)
+                .expect("couldn't serialize transaction event");
+            transaction
+                .event_schema()
+                .store_event_data(
+                    block_number,
+                    EventType::Transaction,
+                    slice::from_ref(&event),
+                )
+                .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.event.store_rejected_transaction_event",
+            start.elapsed()
+        );
+        Ok(())
+    }
 }
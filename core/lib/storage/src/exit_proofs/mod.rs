@@ -0,0 +1,164 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_api_types::v02::account::ExitProofData;
+use zksync_types::{AccountId, BlockNumber, TokenId};
+// Local imports
+use self::records::StoredExitProof;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Exit proofs schema is the queue of the exit proofs requested via API.
+///
+/// There is at most one proof per account and token. The proofs are generated asynchronously
+/// by the exit proof generator against the last finalized block at the time, the finished ones
+/// are kept, so the repeated requests are served from the database until a newer block
+/// is finalized.
+#[derive(Debug)]
+pub struct ExitProofsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ExitProofsSchema<'a, 'c> {
+    /// Queues the generation of the exit proof unless it's already queued or in progress.
+    /// The finished proof is only queued again if it's failed or generated against the block
+    /// older than `last_finalized_block`. Returns the stored state of the proof.
+    pub async fn request_exit_proof(
+        &mut self,
+        account_id: AccountId,
+        token_id: TokenId,
+        last_finalized_block: BlockNumber,
+    ) -> QueryResult<StoredExitProof> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO exit_proofs ( account_id, token_id, created_at )
+            VALUES ( $1, $2, $3 )
+            ON CONFLICT (account_id, token_id) DO UPDATE
+            SET block_number = NULL, proof = NULL, error = NULL, started_at = NULL, created_at = $3
+            WHERE exit_proofs.error IS NOT NULL OR exit_proofs.block_number < $4
+            "#,
+            i64::from(*account_id),
+            *token_id as i32,
+            Utc::now(),
+            i64::from(*last_finalized_block)
+        )
+        .execute(transaction.conn())
+        .await?;
+        let exit_proof = sqlx::query_as!(
+            StoredExitProof,
+            r#"
+            SELECT * FROM exit_proofs
+            WHERE account_id = $1 AND token_id = $2
+            "#,
+            i64::from(*account_id),
+            *token_id as i32
+        )
+        .fetch_one(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.exit_proofs.request_exit_proof", start.elapsed());
+        Ok(exit_proof)
+    }
+
+    /// Returns the stored state of the exit proof, if it's ever been requested.
+    pub async fn load_exit_proof(
+        &mut self,
+        account_id: AccountId,
+        token_id: TokenId,
+    ) -> QueryResult<Option<StoredExitProof>> {
+        let start = Instant::now();
+        let exit_proof = sqlx::query_as!(
+            StoredExitProof,
+            r#"
+            SELECT * FROM exit_proofs
+            WHERE account_id = $1 AND token_id = $2
+            "#,
+            i64::from(*account_id),
+            *token_id as i32
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.exit_proofs.load_exit_proof", start.elapsed());
+        Ok(exit_proof)
+    }
+
+    /// Marks the oldest queued exit proof as started and returns it.
+    /// Proofs started before `abandoned_before` and not finished yet are considered queued.
+    pub async fn start_exit_proof_job(
+        &mut self,
+        abandoned_before: DateTime<Utc>,
+    ) -> QueryResult<Option<StoredExitProof>> {
+        let start = Instant::now();
+        let exit_proof = sqlx::query_as!(
+            StoredExitProof,
+            r#"
+            UPDATE exit_proofs SET started_at = $2
+            WHERE (account_id, token_id) = (
+                SELECT account_id, token_id FROM exit_proofs
+                WHERE proof IS NULL AND error IS NULL
+                    AND (started_at IS NULL OR started_at < $1)
+                ORDER BY created_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+            abandoned_before,
+            Utc::now()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.exit_proofs.start_exit_proof_job", start.elapsed());
+        Ok(exit_proof)
+    }
+
+    /// Stores the generated exit proof along with the block it's generated against.
+    pub async fn store_exit_proof(&mut self, data: &ExitProofData) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE exit_proofs SET block_number = $3, proof = $4
+            WHERE account_id = $1 AND token_id = $2
+            "#,
+            i64::from(*data.account_id),
+            *data.token_id as i32,
+            i64::from(*data.stored_block_info.block_number),
+            serde_json::to_value(data)?
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.exit_proofs.store_exit_proof", start.elapsed());
+        Ok(())
+    }
+
+    /// Marks the exit proof as failed, it's queued again on the next request.
+    pub async fn store_exit_proof_error(
+        &mut self,
+        account_id: AccountId,
+        token_id: TokenId,
+        error: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE exit_proofs SET error = $3
+            WHERE account_id = $1 AND token_id = $2
+            "#,
+            i64::from(*account_id),
+            *token_id as i32,
+            error
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.exit_proofs.store_exit_proof_error", start.elapsed());
+        Ok(())
+    }
+}
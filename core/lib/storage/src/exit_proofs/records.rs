@@ -0,0 +1,45 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+use zksync_api_types::v02::account::{ExitProofInfo, ExitProofStatus};
+use zksync_types::{AccountId, BlockNumber, TokenId};
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredExitProof {
+    pub account_id: i64,
+    pub token_id: i32,
+    pub block_number: Option<i64>,
+    pub proof: Option<Value>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+impl StoredExitProof {
+    pub fn status(&self) -> ExitProofStatus {
+        if self.proof.is_some() {
+            ExitProofStatus::Ready
+        } else if self.error.is_some() {
+            ExitProofStatus::Failed
+        } else if self.started_at.is_some() {
+            ExitProofStatus::InProgress
+        } else {
+            ExitProofStatus::Queued
+        }
+    }
+
+    pub fn into_info(self) -> anyhow::Result<ExitProofInfo> {
+        let status = self.status();
+        let data = self.proof.map(serde_json::from_value).transpose()?;
+        Ok(ExitProofInfo {
+            account_id: AccountId(self.account_id as u32),
+            token_id: TokenId(self.token_id as u32),
+            block_number: self.block_number.map(|block| BlockNumber(block as u32)),
+            status,
+            error: self.error,
+            data,
+        })
+    }
+}
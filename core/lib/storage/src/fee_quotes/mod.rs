@@ -0,0 +1,116 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_utils::{biguint_to_big_decimal, ratio_to_big_decimal};
+// Local imports
+use self::records::{FeeQuote, FeeQuotesFilter, StoredFeeQuote};
+use crate::tokens::STORED_USD_PRICE_PRECISION;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Fee quotes schema is the audit log of the fee quotes served by the API, so the disputes
+/// about the quoted fees can be resolved and the pricing drift analyzed.
+#[derive(Debug)]
+pub struct FeeQuotesSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> FeeQuotesSchema<'a, 'c> {
+    /// Stores the served fee quote.
+    ///
+    /// Note, that the token price precision cannot be greater than `STORED_USD_PRICE_PRECISION`,
+    /// so the number might get rounded.
+    pub async fn store_fee_quote(&mut self, quote: &FeeQuote) -> QueryResult<()> {
+        let start = Instant::now();
+        let addresses: Vec<_> = quote
+            .addresses
+            .iter()
+            .map(|address| address.as_bytes().to_vec())
+            .collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO fee_quotes (
+                tx_types, addresses, token_id, zkp_fee, gas_fee, total_fee,
+                subsidized, gas_price_wei, token_usd_price, created_at
+            )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10 )
+            "#,
+            &quote.tx_types,
+            &addresses,
+            *quote.token_id as i32,
+            biguint_to_big_decimal(quote.zkp_fee.clone()),
+            biguint_to_big_decimal(quote.gas_fee.clone()),
+            biguint_to_big_decimal(quote.total_fee.clone()),
+            quote.subsidized,
+            biguint_to_big_decimal(quote.gas_price_wei.clone()),
+            ratio_to_big_decimal(&quote.token_usd_price, STORED_USD_PRICE_PRECISION),
+            quote.created_at
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_quotes.store_fee_quote", start.elapsed());
+        Ok(())
+    }
+
+    /// Stores the batch of the served fee quotes within a single transaction.
+    pub async fn store_fee_quotes(&mut self, quotes: &[FeeQuote]) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        for quote in quotes {
+            transaction
+                .fee_quotes_schema()
+                .store_fee_quote(quote)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.fee_quotes.store_fee_quotes", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the fee quotes matching the filter, the most recent first.
+    pub async fn load_fee_quotes(
+        &mut self,
+        filter: &FeeQuotesFilter,
+    ) -> QueryResult<Vec<StoredFeeQuote>> {
+        let start = Instant::now();
+        let quotes = sqlx::query_as!(
+            StoredFeeQuote,
+            r#"
+            SELECT * FROM fee_quotes
+            WHERE created_at >= $1 AND created_at <= $2
+                AND ($3::integer IS NULL OR token_id = $3)
+                AND ($4::bytea IS NULL OR $4 = ANY(addresses))
+                AND ($5::text IS NULL OR $5 = ANY(tx_types))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $6
+            "#,
+            filter.from,
+            filter.to,
+            filter.token_id.map(|token_id| *token_id as i32),
+            filter.address.map(|address| address.as_bytes().to_vec()),
+            filter.tx_type,
+            i64::from(filter.limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_quotes.load_fee_quotes", start.elapsed());
+        Ok(quotes)
+    }
+
+    /// Removes the fee quotes served before the given time.
+    /// Returns the number of the removed quotes.
+    pub async fn remove_old_fee_quotes(&mut self, before: DateTime<Utc>) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!("DELETE FROM fee_quotes WHERE created_at < $1", before)
+            .execute(self.0.conn())
+            .await?
+            .rows_affected();
+
+        metrics::histogram!("sql.fee_quotes.remove_old_fee_quotes", start.elapsed());
+        Ok(removed)
+    }
+}
@@ -0,0 +1,53 @@
+// External imports
+use chrono::{DateTime, Utc};
+use num::{rational::Ratio, BigUint};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{Address, TokenId};
+
+/// Fee quote served by the API along with the inputs it's calculated from.
+#[derive(Debug, Clone)]
+pub struct FeeQuote {
+    /// Types of the transactions, a single one unless the quote is for a batch.
+    pub tx_types: Vec<String>,
+    /// Recipients of the transactions, in the order of `tx_types`.
+    pub addresses: Vec<Address>,
+    pub token_id: TokenId,
+    pub zkp_fee: BigUint,
+    pub gas_fee: BigUint,
+    pub total_fee: BigUint,
+    pub subsidized: bool,
+    pub gas_price_wei: BigUint,
+    /// Price of the whole fee token, not the smallest unit of it.
+    pub token_usd_price: Ratio<BigUint>,
+    /// Time the quote is served at, it may be stored later.
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredFeeQuote {
+    pub id: i64,
+    pub tx_types: Vec<String>,
+    pub addresses: Vec<Vec<u8>>,
+    pub token_id: i32,
+    pub zkp_fee: BigDecimal,
+    pub gas_fee: BigDecimal,
+    pub total_fee: BigDecimal,
+    pub subsidized: bool,
+    pub gas_price_wei: BigDecimal,
+    pub token_usd_price: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters of the loaded fee quotes, the time range is inclusive.
+#[derive(Debug, Clone)]
+pub struct FeeQuotesFilter {
+    pub token_id: Option<TokenId>,
+    /// Quotes for the batches are matched by any of their addresses.
+    pub address: Option<Address>,
+    /// Quotes for the batches are matched by any of their transaction types.
+    pub tx_type: Option<String>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub limit: u32,
+}
@@ -0,0 +1,43 @@
+// Built-in uses
+// External uses
+use sqlx::{Connection, PgConnection};
+// Workspace uses
+// Local uses
+use crate::{get_database_url, QueryResult};
+
+/// Key of the advisory lock held by the leader.
+const LEADER_LOCK_KEY: i64 = 0x7a6b_7379_6e63;
+
+/// A dedicated connection to the database holding the session-level advisory lock of the leader.
+///
+/// The lock is released by the database as soon as the connection is closed, so another
+/// instance can become the leader once the current one is stopped or loses the connection.
+pub struct LeaderLock {
+    conn: PgConnection,
+}
+
+impl LeaderLock {
+    /// Creates new connection to the database to hold the leader lock.
+    pub async fn connect() -> QueryResult<Self> {
+        let database_url = get_database_url();
+        let conn = PgConnection::connect(&database_url).await?;
+        Ok(Self { conn })
+    }
+
+    /// Tries to acquire the leader lock without waiting.
+    /// Returns `true` if the lock is acquired (or is already held by this connection).
+    pub async fn try_acquire(&mut self) -> QueryResult<bool> {
+        let locked = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock($1) AS "locked!""#,
+            LEADER_LOCK_KEY
+        )
+        .fetch_one(&mut self.conn)
+        .await?;
+        Ok(locked)
+    }
+
+    /// Checks that the connection holding the lock is still alive.
+    pub async fn check(&mut self) -> QueryResult<()> {
+        Ok(self.conn.ping().await?)
+    }
+}
@@ -0,0 +1,46 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod lock;
+
+/// Leader election schema maintains the leadership term of the core server instances.
+///
+/// The term is incremented every time a new leader is elected and serves as the fencing token:
+/// the actions which must be performed only by the leader lock the term for the duration
+/// of the transaction, so the next leader can't be elected until they are finished,
+/// and the previous leader can't perform them once the next one is elected.
+#[derive(Debug)]
+pub struct LeaderElectionSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> LeaderElectionSchema<'a, 'c> {
+    /// Starts the next leadership term and returns its number.
+    /// Must be called only by the instance holding the leader lock.
+    pub async fn start_term(&mut self, leader: &str) -> QueryResult<i64> {
+        let start = Instant::now();
+        let term = sqlx::query_scalar!(
+            "UPDATE leader_terms SET term = term + 1, leader = $1, elected_at = now() RETURNING term",
+            leader
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.leader_election.start_term", start.elapsed());
+        Ok(term)
+    }
+
+    /// Returns the current leadership term and locks it until the end of the transaction,
+    /// so the next term can't be started meanwhile.
+    pub async fn lock_current_term(&mut self) -> QueryResult<i64> {
+        let start = Instant::now();
+        let term = sqlx::query_scalar!("SELECT term FROM leader_terms FOR SHARE")
+            .fetch_one(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.leader_election.lock_current_term", start.elapsed());
+        Ok(term)
+    }
+}
@@ -32,10 +32,17 @@
 //!
 //! There are the following sets of schemas:
 //!
+//! - api_keys, for the keys issued to the API clients.
 //! - config, for the server config.
 //! - data_restore, for the data_restore crate.
+//! - encrypted_txs, for the transactions submitted with the visibility delay.
 //! - ethereum, for the data associated with the Ethereum blockchain.
+//! - exit_proofs, for the exit proofs requested via API.
+//! - fee_quotes, for the audit log of the fee quotes served by the API.
+//! - leader_election, for the leadership term of the core server instances.
 //! - prover, for the data on prover jobs, proofs, etc.
+//! - protocol_parameters, for the history of the protocol parameters changes.
+//! - recovery, for the account guardians and the recoveries initiated by them.
 //! - tokens, for storing and loading known tokens.
 //! - chain - the biggest one, which includes several schemas for the ZKSync sidechain itself.
 //!
@@ -87,17 +94,25 @@ use crate::connection::{holder::ConnectionHolder, PooledConnection};
 #[cfg(test)]
 mod tests;
 
+pub mod api_keys;
 pub mod chain;
 pub mod config;
 pub mod connection;
 pub mod data_restore;
 pub mod diff;
+pub mod encrypted_txs;
+pub mod eth_watch;
 pub mod ethereum;
 pub mod event;
+pub mod exit_proofs;
+pub mod fee_quotes;
 pub mod forced_exit_requests;
+pub mod leader_election;
 pub mod listener;
 pub mod misc;
+pub mod protocol_parameters;
 pub mod prover;
+pub mod recovery;
 pub mod test_data;
 pub mod tokens;
 pub mod utils;
@@ -206,6 +221,11 @@ impl<'a> StorageProcessor<'a> {
         chain::ChainIntermediator(self)
     }
 
+    /// Gains access to the `ApiKeys` schema.
+    pub fn api_keys_schema(&mut self) -> api_keys::ApiKeysSchema<'_, 'a> {
+        api_keys::ApiKeysSchema(self)
+    }
+
     /// Gains access to the `Config` schema.
     pub fn config_schema(&mut self) -> config::ConfigSchema<'_, 'a> {
         config::ConfigSchema(self)
@@ -216,16 +236,53 @@ impl<'a> StorageProcessor<'a> {
         data_restore::DataRestoreSchema(self)
     }
 
+    /// Gains access to the `EncryptedTxs` schema.
+    pub fn encrypted_txs_schema(&mut self) -> encrypted_txs::EncryptedTxsSchema<'_, 'a> {
+        encrypted_txs::EncryptedTxsSchema(self)
+    }
+
+    /// Gains access to the `EthWatch` schema.
+    pub fn eth_watch_schema(&mut self) -> eth_watch::EthWatchSchema<'_, 'a> {
+        eth_watch::EthWatchSchema(self)
+    }
+
     /// Gains access to the `Ethereum` schema.
     pub fn ethereum_schema(&mut self) -> ethereum::EthereumSchema<'_, 'a> {
         ethereum::EthereumSchema(self)
     }
 
+    /// Gains access to the `ExitProofs` schema.
+    pub fn exit_proofs_schema(&mut self) -> exit_proofs::ExitProofsSchema<'_, 'a> {
+        exit_proofs::ExitProofsSchema(self)
+    }
+
+    /// Gains access to the `FeeQuotes` schema.
+    pub fn fee_quotes_schema(&mut self) -> fee_quotes::FeeQuotesSchema<'_, 'a> {
+        fee_quotes::FeeQuotesSchema(self)
+    }
+
+    /// Gains access to the `LeaderElection` schema.
+    pub fn leader_election_schema(&mut self) -> leader_election::LeaderElectionSchema<'_, 'a> {
+        leader_election::LeaderElectionSchema(self)
+    }
+
+    /// Gains access to the `ProtocolParameters` schema.
+    pub fn protocol_parameters_schema(
+        &mut self,
+    ) -> protocol_parameters::ProtocolParametersSchema<'_, 'a> {
+        protocol_parameters::ProtocolParametersSchema(self)
+    }
+
     /// Gains access to the `Prover` schema.
     pub fn prover_schema(&mut self) -> prover::ProverSchema<'_, 'a> {
         prover::ProverSchema(self)
     }
 
+    /// Gains access to the `Recovery` schema.
+    pub fn recovery_schema(&mut self) -> recovery::RecoverySchema<'_, 'a> {
+        recovery::RecoverySchema(self)
+    }
+
     /// Gains access to the `Tokens` schema.
     pub fn tokens_schema(&mut self) -> tokens::TokensSchema<'_, 'a> {
         tokens::TokensSchema(self)
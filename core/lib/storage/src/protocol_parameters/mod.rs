@@ -0,0 +1,128 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{
+    protocol::{ProtocolParameters, ProtocolParametersUpdate},
+    BlockNumber,
+};
+// Local imports
+use self::records::StorageProtocolParameters;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Protocol parameters schema stores the history of the protocol parameters changes,
+/// so the stored blocks can be validated and restored with the parameters they were created with.
+///
+/// The server records the parameters from its config on start, and the `protocol_parameters`
+/// tool allows recording the parameters for the blocks created before the history was kept.
+#[derive(Debug)]
+pub struct ProtocolParametersSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ProtocolParametersSchema<'a, 'c> {
+    /// Stores the parameters in force starting from the `update.from_block`.
+    /// Parameters stored for the same block earlier are overwritten.
+    pub async fn store_parameters(&mut self, update: &ProtocolParametersUpdate) -> QueryResult<()> {
+        let start = Instant::now();
+        let block_chunk_sizes: Vec<i64> = update
+            .parameters
+            .block_chunk_sizes
+            .iter()
+            .map(|&size| size as i64)
+            .collect();
+
+        sqlx::query!(
+            "INSERT INTO protocol_parameters (from_block, block_chunk_sizes, max_withdrawals_per_execute_tx, created_at)
+            VALUES ($1, $2, $3, now())
+            ON CONFLICT (from_block) DO UPDATE
+            SET block_chunk_sizes = $2, max_withdrawals_per_execute_tx = $3, created_at = now()",
+            i64::from(*update.from_block),
+            &block_chunk_sizes,
+            update.parameters.max_withdrawals_per_execute_tx as i64,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.protocol_parameters.store_parameters", start.elapsed());
+        Ok(())
+    }
+
+    /// Records the `parameters` starting from the `from_block`, unless they are already in force
+    /// at this block. Returns `true` if the parameters were stored.
+    pub async fn record_parameters(
+        &mut self,
+        from_block: BlockNumber,
+        parameters: &ProtocolParameters,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let in_force = transaction
+            .protocol_parameters_schema()
+            .load_parameters_for_block(from_block)
+            .await?;
+        let changed = in_force.as_ref() != Some(parameters);
+        if changed {
+            transaction
+                .protocol_parameters_schema()
+                .store_parameters(&ProtocolParametersUpdate {
+                    from_block,
+                    parameters: parameters.clone(),
+                })
+                .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.protocol_parameters.record_parameters", start.elapsed());
+        Ok(changed)
+    }
+
+    /// Loads all the stored changes of the parameters ordered by the block number.
+    pub async fn load_parameters_history(&mut self) -> QueryResult<Vec<ProtocolParametersUpdate>> {
+        let start = Instant::now();
+        let history = sqlx::query_as!(
+            StorageProtocolParameters,
+            "SELECT from_block, block_chunk_sizes, max_withdrawals_per_execute_tx
+            FROM protocol_parameters
+            ORDER BY from_block"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(ProtocolParametersUpdate::from)
+        .collect();
+
+        metrics::histogram!(
+            "sql.protocol_parameters.load_parameters_history",
+            start.elapsed()
+        );
+        Ok(history)
+    }
+
+    /// Loads the parameters in force at the given block.
+    /// Returns `None` if there are no parameters recorded for this block or any block before it.
+    pub async fn load_parameters_for_block(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<ProtocolParameters>> {
+        let start = Instant::now();
+        let parameters = sqlx::query_as!(
+            StorageProtocolParameters,
+            "SELECT from_block, block_chunk_sizes, max_withdrawals_per_execute_tx
+            FROM protocol_parameters
+            WHERE from_block <= $1
+            ORDER BY from_block DESC
+            LIMIT 1",
+            i64::from(*block_number)
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|stored| ProtocolParametersUpdate::from(stored).parameters);
+
+        metrics::histogram!(
+            "sql.protocol_parameters.load_parameters_for_block",
+            start.elapsed()
+        );
+        Ok(parameters)
+    }
+}
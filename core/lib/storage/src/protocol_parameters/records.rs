@@ -0,0 +1,31 @@
+// External imports
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::{
+    protocol::{ProtocolParameters, ProtocolParametersUpdate},
+    BlockNumber,
+};
+// Local imports
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageProtocolParameters {
+    pub from_block: i64,
+    pub block_chunk_sizes: Vec<i64>,
+    pub max_withdrawals_per_execute_tx: i64,
+}
+
+impl From<StorageProtocolParameters> for ProtocolParametersUpdate {
+    fn from(stored: StorageProtocolParameters) -> Self {
+        Self {
+            from_block: BlockNumber(stored.from_block as u32),
+            parameters: ProtocolParameters {
+                block_chunk_sizes: stored
+                    .block_chunk_sizes
+                    .into_iter()
+                    .map(|size| size as usize)
+                    .collect(),
+                max_withdrawals_per_execute_tx: stored.max_withdrawals_per_execute_tx as u64,
+            },
+        }
+    }
+}
@@ -1,5 +1,5 @@
 // Built-in deps
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External imports
 use anyhow::format_err;
 // Workspace imports
@@ -12,7 +12,10 @@ use crate::{QueryResult, StorageProcessor};
 use chrono::{TimeZone, Utc};
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::prover::{ProverJob, ProverJobStatus, ProverJobType};
+use zksync_types::prover::{
+    IdleProverJob, ProverCapabilities, ProverJob, ProverJobAging, ProverJobAssignment,
+    ProverJobStatus, ProverJobType,
+};
 
 pub mod records;
 
@@ -38,6 +41,9 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(pending_jobs_count)
     }
 
+    /// Adds the job unless the job of the same type for the same blocks exists.
+    /// The creation time of the oldest priority operation of the blocks is stored with the job,
+    /// so the jobs of such blocks can be given out first.
     pub async fn add_prover_job_to_job_queue(
         &mut self,
         first_block: BlockNumber,
@@ -50,10 +56,11 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         sqlx::query!(
         "
           WITH job_values as (
-            SELECT $1::int4, $2::int4, $3::text, 'server_add_job', $4::int8, $5::int8, $6::jsonb
+            SELECT $1::int4, $2::int4, $3::text, 'server_add_job', $4::int8, $5::int8, $6::jsonb,
+              (SELECT MIN(created_at) FROM executed_priority_operations WHERE block_number BETWEEN $4 AND $5)
             WHERE NOT EXISTS (SELECT * FROM prover_job_queue WHERE first_block = $4 and last_block = $5 and job_type = $3 LIMIT 1)
           )
-          INSERT INTO prover_job_queue (job_status, job_priority, job_type, updated_by, first_block, last_block, job_data)
+          INSERT INTO prover_job_queue (job_status, job_priority, job_type, updated_by, first_block, last_block, job_data, oldest_priority_op_at)
           SELECT * from job_values
         ",
             ProverJobStatus::Idle.to_number(),
@@ -68,11 +75,14 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Returns the jobs with the expired leases to the queue, so other provers can take them.
     pub async fn mark_stale_jobs_as_idle(&mut self) -> QueryResult<()> {
         let start = Instant::now();
         let result = sqlx::query!(
-            "UPDATE prover_job_queue SET (job_status, updated_at, updated_by) = ($1, now(), 'server_clean_idle')
-            WHERE job_status = $2 AND (now() - INTERVAL '120 seconds') >= updated_at RETURNING id",
+            "UPDATE prover_job_queue
+            SET (job_status, updated_at, updated_by, assigned_prover, lease_expires_at, reassignments)
+                = ($1, now(), 'server_clean_idle', NULL, NULL, reassignments + 1)
+            WHERE job_status = $2 AND lease_expires_at <= now() RETURNING id",
             ProverJobStatus::Idle.to_number(),
             ProverJobStatus::InProgress.to_number(),
         )
@@ -83,8 +93,27 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
-    pub async fn get_idle_prover_job_from_job_queue(&mut self) -> QueryResult<Option<ProverJob>> {
+    /// Gives out the idle job with the highest priority, i.e. the lowest `job_priority`.
+    /// The jobs of the blocks with the aged priority operations go first, and the rest of the jobs
+    /// gain one priority level per `aging.interval` of waiting.
+    ///
+    /// Only the jobs matching the `capabilities` of the prover are considered, the block size
+    /// of the single proof job is the size of its block.
+    ///
+    /// The job is leased to the prover for `lease_duration`, the heartbeats renew the lease.
+    pub async fn get_idle_prover_job_from_job_queue(
+        &mut self,
+        prover_name: &str,
+        capabilities: &ProverCapabilities,
+        lease_duration: Duration,
+        aging: &ProverJobAging,
+    ) -> QueryResult<Option<ProverJob>> {
         let start = Instant::now();
+        let block_sizes: Vec<i64> = capabilities
+            .block_sizes
+            .iter()
+            .map(|&size| size as i64)
+            .collect();
         // Select the block to prove.
         let mut transaction = self.0.start_transaction().await?;
         sqlx::query!("LOCK TABLE prover_job_queue IN EXCLUSIVE MODE")
@@ -95,11 +124,33 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             StorageProverJobQueue,
             r#"
                 SELECT * FROM prover_job_queue
-                WHERE job_status = $1
-                ORDER BY (job_priority, id, first_block)
+                WHERE job_status = $1 AND (
+                    (job_type = $4 AND $5 AND (
+                        cardinality($6::int8[]) = 0
+                        OR (SELECT block_size FROM blocks WHERE number = first_block) = ANY($6::int8[])
+                    ))
+                    OR (job_type = $7 AND $8 AND ($9::int8 = 0 OR last_block - first_block + 1 <= $9::int8))
+                )
+                ORDER BY
+                    COALESCE($3::float8 > 0 AND oldest_priority_op_at <= now() - $3::float8 * INTERVAL '1 second', false) DESC,
+                    job_priority - (
+                        CASE WHEN $2::float8 > 0
+                        THEN FLOOR(EXTRACT(EPOCH FROM now() - created_at)::float8 / $2::float8)
+                        ELSE 0 END
+                    ),
+                    id,
+                    first_block
                 LIMIT 1
             "#,
-            ProverJobStatus::Idle.to_number()
+            ProverJobStatus::Idle.to_number(),
+            aging.interval.as_secs_f64(),
+            aging.priority_op_threshold.as_secs_f64(),
+            ProverJobType::SingleProof.to_string(),
+            capabilities.single_proofs,
+            &block_sizes,
+            ProverJobType::AggregatedProof.to_string(),
+            capabilities.aggregated_proofs,
+            capabilities.max_aggregated_blocks.unwrap_or(0) as i64,
         )
         .fetch_optional(transaction.conn())
         .await?;
@@ -108,15 +159,31 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             sqlx::query!(
                 r#"
                 UPDATE prover_job_queue
-                SET (job_status, updated_at, updated_by) = ($1, now(), 'server_give_job')
+                SET (job_status, updated_at, updated_by, assigned_prover, lease_expires_at)
+                    = ($1, now(), 'server_give_job', $3, now() + $4::float8 * INTERVAL '1 second')
                 WHERE id = $2;
             "#,
                 ProverJobStatus::InProgress.to_number(),
                 job.id,
+                prover_name,
+                lease_duration.as_secs_f64(),
             )
             .execute(transaction.conn())
             .await?;
 
+            let now = Utc::now();
+            let queue_wait = (now - job.created_at).to_std().unwrap_or_default();
+            let job_type = job.job_type.clone();
+            metrics::histogram!("prover.job_queue_wait", queue_wait, "job_type" => job_type.clone());
+            let priority_op_threshold = aging.priority_op_threshold;
+            let priority_op_aged = job.oldest_priority_op_at.map_or(false, |created_at| {
+                priority_op_threshold.as_secs() > 0
+                    && (now - created_at).to_std().unwrap_or_default() >= priority_op_threshold
+            });
+            if priority_op_aged {
+                metrics::increment_counter!("prover.aged_priority_op_jobs", "job_type" => job_type);
+            }
+
             Some(ProverJob::new(
                 job.id,
                 BlockNumber(job.first_block as u32),
@@ -131,34 +198,79 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(prover_job)
     }
 
-    /// Updates the state of ongoing prover job.
+    /// Loads the idle jobs with the sizes of the blocks of the single proof jobs,
+    /// so the jobs can be matched against the capabilities of the provers.
+    pub async fn load_idle_jobs(&mut self) -> QueryResult<Vec<IdleProverJob>> {
+        let start = Instant::now();
+        let records = sqlx::query!(
+            r#"
+                SELECT prover_job_queue.id, job_type, first_block, last_block,
+                    blocks.block_size AS "block_size?"
+                FROM prover_job_queue
+                LEFT JOIN blocks ON job_type = $2 AND blocks.number = first_block
+                WHERE job_status = $1
+                ORDER BY prover_job_queue.id
+            "#,
+            ProverJobStatus::Idle.to_number(),
+            ProverJobType::SingleProof.to_string(),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let single_proof = ProverJobType::SingleProof.to_string();
+        let jobs = records
+            .into_iter()
+            .map(|record| IdleProverJob {
+                job_id: record.id,
+                job_type: if record.job_type == single_proof {
+                    ProverJobType::SingleProof
+                } else {
+                    ProverJobType::AggregatedProof
+                },
+                first_block: BlockNumber(record.first_block as u32),
+                last_block: BlockNumber(record.last_block as u32),
+                block_size: record.block_size.map(|size| size as usize),
+            })
+            .collect();
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_idle_jobs");
+        Ok(jobs)
+    }
+
+    /// Renews the lease of the job in progress for `lease_duration`.
+    /// Returns `false` if the job isn't leased to the prover, e.g. the lease has expired
+    /// and the job was given out to another prover.
     pub async fn record_prover_is_working(
         &mut self,
         job_id: i32,
         prover_name: &str,
-    ) -> QueryResult<()> {
+        lease_duration: Duration,
+    ) -> QueryResult<bool> {
         let start = Instant::now();
-        sqlx::query!(
+        let renewed = sqlx::query!(
             "UPDATE prover_job_queue
-            SET (updated_at, updated_by) = (now(), $1)
-            WHERE id = $2",
-            prover_name.to_string(),
+            SET (updated_at, updated_by, lease_expires_at) = (now(), $1, now() + $3::float8 * INTERVAL '1 second')
+            WHERE id = $2 AND job_status = $4 AND assigned_prover = $1
+            RETURNING id",
+            prover_name,
             job_id,
+            lease_duration.as_secs_f64(),
+            ProverJobStatus::InProgress.to_number(),
         )
-        .execute(self.0.conn())
-        .await?;
+        .fetch_optional(self.0.conn())
+        .await?
+        .is_some();
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_is_working");
-        Ok(())
+        Ok(renewed)
     }
 
-    /// Marks the prover as stopped.
+    /// Marks the prover as stopped, returning the jobs leased to it to the queue.
     pub async fn record_prover_stop(&mut self, prover_name: &str) -> QueryResult<()> {
         let start = Instant::now();
         sqlx::query!(
             "UPDATE prover_job_queue
-            SET (updated_at, job_status) = (now(), $1)
-            WHERE updated_by = $2 and job_status = $3",
+            SET (updated_at, job_status, assigned_prover, lease_expires_at) = (now(), $1, NULL, NULL)
+            WHERE assigned_prover = $2 and job_status = $3",
             ProverJobStatus::Idle.to_number(),
             prover_name,
             ProverJobStatus::InProgress.to_number()
@@ -170,6 +282,42 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Loads the assignments of the jobs which are not done yet, ordered by the job id.
+    pub async fn load_prover_job_assignments(&mut self) -> QueryResult<Vec<ProverJobAssignment>> {
+        let start = Instant::now();
+        let jobs = sqlx::query!(
+            "SELECT id, job_type, first_block, last_block, job_status, assigned_prover,
+                lease_expires_at, reassignments, created_at, updated_at
+            FROM prover_job_queue
+            WHERE job_status != $1
+            ORDER BY id",
+            ProverJobStatus::Done.to_number(),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let assignments = jobs
+            .into_iter()
+            .map(|job| {
+                Ok(ProverJobAssignment {
+                    job_id: job.id,
+                    job_type: job.job_type,
+                    first_block: BlockNumber(job.first_block as u32),
+                    last_block: BlockNumber(job.last_block as u32),
+                    status: ProverJobStatus::from_number(job.job_status)?,
+                    assigned_prover: job.assigned_prover,
+                    lease_expires_at: job.lease_expires_at,
+                    reassignments: job.reassignments,
+                    created_at: job.created_at,
+                    updated_at: job.updated_at,
+                })
+            })
+            .collect::<QueryResult<_>>()?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_prover_job_assignments");
+        Ok(assignments)
+    }
+
     /// Stores the proof for a block.
     pub async fn store_proof(
         &mut self,
@@ -324,21 +472,24 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(proof)
     }
 
-    /// Stores witness for a block
+    /// Stores witness for a block along with its hash, so the witness can be
+    /// checked for integrity before creating a prover job for it.
     pub async fn store_witness(
         &mut self,
         block: BlockNumber,
         witness: serde_json::Value,
+        witness_hash: Vec<u8>,
     ) -> QueryResult<()> {
         let start = Instant::now();
         let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
         sqlx::query!(
-            "INSERT INTO block_witness (block, witness)
-            VALUES ($1, $2)
+            "INSERT INTO block_witness (block, witness, witness_hash)
+            VALUES ($1, $2, $3)
             ON CONFLICT (block)
             DO NOTHING",
             i64::from(*block),
-            witness_str
+            witness_str,
+            witness_hash
         )
         .execute(self.0.conn())
         .await?;
@@ -366,6 +517,43 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness")))
     }
 
+    /// Gets the hash of the witness for a block, computed at the moment of the witness creation.
+    pub async fn get_witness_hash(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<Vec<u8>>> {
+        let start = Instant::now();
+        let witness_hash = sqlx::query!(
+            "SELECT witness_hash FROM block_witness WHERE block = $1",
+            i64::from(*block_number),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .and_then(|record| record.witness_hash);
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "get_witness_hash");
+        Ok(witness_hash)
+    }
+
+    /// Returns the number of the saved blocks following the last block with a witness,
+    /// i.e. the backlog of the witness generator.
+    pub async fn witness_backlog(&mut self) -> QueryResult<u32> {
+        let start = Instant::now();
+        let backlog = sqlx::query!(
+            r#"
+            SELECT
+                (SELECT COALESCE(MAX(number), 0) FROM blocks)
+                - (SELECT COALESCE(MAX(block), 0) FROM block_witness) as "backlog!"
+            "#
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .backlog;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "witness_backlog");
+        Ok(backlog.max(0) as u32)
+    }
+
     pub async fn get_last_block_prover_job_queue(
         &mut self,
         action_type: ProverJobType,
@@ -409,6 +597,20 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     }
 
     // Removes witnesses for blocks with number greater than `last_block`
+    /// Removes the witness of the block, e.g. the corrupted one, so it's generated again.
+    pub async fn remove_witness(&mut self, block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM block_witness WHERE block = $1",
+            i64::from(*block)
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "remove_witness");
+        Ok(())
+    }
+
     pub async fn remove_witnesses(&mut self, last_block: BlockNumber) -> QueryResult<()> {
         let start = Instant::now();
         sqlx::query!(
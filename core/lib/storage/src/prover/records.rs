@@ -54,6 +54,9 @@ pub struct IntegerNumber {
 pub struct StorageBlockWitness {
     pub block: i64,
     pub witness: String,
+    /// Hash of the witness computed by the witness generator at the moment of its creation.
+    /// Is `None` for witnesses created before the hash was introduced.
+    pub witness_hash: Option<Vec<u8>>,
 }
 
 #[derive(Debug, FromRow)]
@@ -68,4 +71,8 @@ pub struct StorageProverJobQueue {
     pub first_block: i64,
     pub last_block: i64,
     pub job_data: serde_json::Value,
+    pub oldest_priority_op_at: Option<DateTime<Utc>>,
+    pub assigned_prover: Option<String>,
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    pub reassignments: i32,
 }
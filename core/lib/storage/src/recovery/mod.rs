@@ -0,0 +1,173 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{AccountId, Address, PubKeyHash};
+// Local imports
+use self::records::StoredAccountRecovery;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Recovery schema contains the guardians of the accounts and the recoveries initiated by them.
+#[derive(Debug)]
+pub struct RecoverySchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> RecoverySchema<'a, 'c> {
+    /// Replaces the guardians of the account with the given ones.
+    pub async fn set_guardians(
+        &mut self,
+        account_id: AccountId,
+        guardians: &[Address],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let guardians: Vec<_> = guardians
+            .iter()
+            .map(|guardian| guardian.as_bytes().to_vec())
+            .collect();
+
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            "DELETE FROM account_guardians WHERE account_id = $1",
+            i64::from(*account_id)
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "INSERT INTO account_guardians (account_id, guardian)
+            SELECT $1, u.guardian
+                FROM UNNEST ($2::bytea[]) AS u(guardian)
+            ON CONFLICT DO NOTHING",
+            i64::from(*account_id),
+            &guardians
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.recovery.set_guardians", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the guardians of the account.
+    pub async fn load_guardians(&mut self, account_id: AccountId) -> QueryResult<Vec<Address>> {
+        let start = Instant::now();
+        let guardians = sqlx::query!(
+            "SELECT guardian FROM account_guardians WHERE account_id = $1 ORDER BY guardian",
+            i64::from(*account_id)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| Address::from_slice(&record.guardian))
+        .collect();
+
+        metrics::histogram!("sql.recovery.load_guardians", start.elapsed());
+        Ok(guardians)
+    }
+
+    /// Stores the recovery initiated by the guardian.
+    pub async fn store_recovery(
+        &mut self,
+        account_id: AccountId,
+        new_pub_key_hash: &PubKeyHash,
+        initiated_by: Address,
+        executable_at: DateTime<Utc>,
+    ) -> QueryResult<StoredAccountRecovery> {
+        let start = Instant::now();
+        let recovery = sqlx::query_as!(
+            StoredAccountRecovery,
+            r#"
+            INSERT INTO account_recoveries ( account_id, new_pub_key_hash, initiated_by, created_at, executable_at )
+            VALUES ( $1, $2, $3, $4, $5 )
+            RETURNING *
+            "#,
+            i64::from(*account_id),
+            &new_pub_key_hash.data[..],
+            initiated_by.as_bytes(),
+            Utc::now(),
+            executable_at
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.recovery.store_recovery", start.elapsed());
+        Ok(recovery)
+    }
+
+    /// Loads the latest recovery of the account which was not cancelled.
+    pub async fn get_active_recovery(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<Option<StoredAccountRecovery>> {
+        let start = Instant::now();
+        let recovery = sqlx::query_as!(
+            StoredAccountRecovery,
+            r#"
+            SELECT * FROM account_recoveries
+            WHERE account_id = $1 AND cancelled_at IS NULL
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            i64::from(*account_id)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.recovery.get_active_recovery", start.elapsed());
+        Ok(recovery)
+    }
+
+    /// Loads the latest recovery of the account to the given public key hash,
+    /// including the cancelled one.
+    pub async fn get_recovery_by_pub_key_hash(
+        &mut self,
+        account_id: AccountId,
+        new_pub_key_hash: &PubKeyHash,
+    ) -> QueryResult<Option<StoredAccountRecovery>> {
+        let start = Instant::now();
+        let recovery = sqlx::query_as!(
+            StoredAccountRecovery,
+            r#"
+            SELECT * FROM account_recoveries
+            WHERE account_id = $1 AND new_pub_key_hash = $2
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            i64::from(*account_id),
+            &new_pub_key_hash.data[..]
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.recovery.get_recovery_by_pub_key_hash", start.elapsed());
+        Ok(recovery)
+    }
+
+    /// Cancels the recoveries of the account in progress.
+    /// Returns the latest cancelled recovery, if any.
+    pub async fn cancel_recovery(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<Option<StoredAccountRecovery>> {
+        let start = Instant::now();
+        let cancelled = sqlx::query_as!(
+            StoredAccountRecovery,
+            r#"
+            UPDATE account_recoveries SET cancelled_at = $2
+            WHERE account_id = $1 AND cancelled_at IS NULL
+            RETURNING *
+            "#,
+            i64::from(*account_id),
+            Utc::now()
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .max_by_key(|recovery| recovery.id);
+
+        metrics::histogram!("sql.recovery.cancel_recovery", start.elapsed());
+        Ok(cancelled)
+    }
+}
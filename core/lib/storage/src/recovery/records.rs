@@ -0,0 +1,31 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_api_types::v02::recovery::AccountRecovery;
+use zksync_types::{AccountId, Address, PubKeyHash};
+
+#[derive(Debug, Clone)]
+pub struct StoredAccountRecovery {
+    pub id: i64,
+    pub account_id: i64,
+    pub new_pub_key_hash: Vec<u8>,
+    pub initiated_by: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub executable_at: DateTime<Utc>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
+
+impl From<StoredAccountRecovery> for AccountRecovery {
+    fn from(val: StoredAccountRecovery) -> Self {
+        Self {
+            id: val.id,
+            account_id: AccountId(val.account_id as u32),
+            new_pub_key_hash: PubKeyHash::from_bytes(&val.new_pub_key_hash)
+                .expect("Incorrect public key hash stored in the database"),
+            initiated_by: Address::from_slice(&val.initiated_by),
+            created_at: val.created_at,
+            executable_at: val.executable_at,
+            cancelled_at: val.cancelled_at,
+        }
+    }
+}
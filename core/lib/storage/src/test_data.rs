@@ -267,6 +267,7 @@ pub fn gen_unique_aggregated_operation_with_txs(
         AggregatedActionType::ExecuteBlocks => {
             AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation {
                 blocks: vec![block],
+                complete_withdrawals: false,
             })
         }
     }
@@ -0,0 +1,66 @@
+// Local imports
+use crate::tests::db_test;
+use crate::{api_keys::ApiKeysSchema, QueryResult, StorageProcessor};
+
+/// Checks that the API keys are stored, loaded and revoked correctly.
+#[db_test]
+async fn api_keys(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let key_hash = vec![0xaa; 32];
+    let another_key_hash = vec![0xbb; 32];
+
+    assert!(ApiKeysSchema(&mut storage)
+        .get_active_api_key(&key_hash)
+        .await?
+        .is_none());
+
+    let stored = ApiKeysSchema(&mut storage)
+        .store_api_key(&key_hash, "partner", 10, 100_000)
+        .await?;
+    assert_eq!(stored.key_hash, key_hash);
+    assert_eq!(stored.name, "partner");
+    assert_eq!(stored.requests_per_second, 10);
+    assert_eq!(stored.requests_per_day, 100_000);
+    assert!(stored.revoked_at.is_none());
+    ApiKeysSchema(&mut storage)
+        .store_api_key(&another_key_hash, "another_partner", 1, 1000)
+        .await?;
+
+    let loaded = ApiKeysSchema(&mut storage)
+        .get_active_api_key(&key_hash)
+        .await?
+        .expect("API key must be stored");
+    assert_eq!(loaded.id, stored.id);
+
+    // Only the active keys can be revoked.
+    assert_eq!(
+        ApiKeysSchema(&mut storage)
+            .revoke_api_key(stored.id)
+            .await?,
+        Some(key_hash.clone())
+    );
+    assert!(ApiKeysSchema(&mut storage)
+        .revoke_api_key(stored.id)
+        .await?
+        .is_none());
+    assert!(ApiKeysSchema(&mut storage)
+        .get_active_api_key(&key_hash)
+        .await?
+        .is_none());
+
+    let keys = ApiKeysSchema(&mut storage).load_api_keys().await?;
+    assert_eq!(keys.len(), 2);
+    assert!(keys[0].revoked_at.is_some());
+    assert!(keys[1].revoked_at.is_none());
+
+    // Quotas that don't fit into the stored integers are rejected.
+    assert!(ApiKeysSchema(&mut storage)
+        .store_api_key(&[0xcc; 32], "too_big", u32::MAX, 1000)
+        .await
+        .is_err());
+    assert!(ApiKeysSchema(&mut storage)
+        .store_api_key(&[0xcc; 32], "too_big", 1, u64::MAX)
+        .await
+        .is_err());
+
+    Ok(())
+}
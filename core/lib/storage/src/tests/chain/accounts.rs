@@ -217,6 +217,98 @@ async fn stored_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the batched getters return the same states as the single-account ones.
+#[db_test]
+async fn account_states_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let _lock = ACCOUNT_MUTEX.lock().await;
+    let mut rng = create_rng();
+
+    let block_size = 100;
+    let (accounts_block, updates_block) = apply_random_updates(AccountMap::default(), &mut rng);
+    BlockSchema(&mut storage)
+        .save_full_block(gen_sample_block(
+            BlockNumber(1),
+            block_size,
+            Default::default(),
+        ))
+        .await?;
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(1), &updates_block, 0)
+        .await?;
+
+    let unknown_address = Address::random();
+    let unknown_id = AccountId(u32::MAX >> 8);
+    let mut addresses: Vec<_> = accounts_block
+        .values()
+        .map(|account| account.address)
+        .collect();
+    addresses.push(unknown_address);
+    let account_ids = AccountSchema(&mut storage)
+        .account_ids_by_addresses(&addresses)
+        .await?;
+    assert_eq!(account_ids.len(), accounts_block.len());
+    assert!(!account_ids.contains_key(&unknown_address));
+    for (account_id, account) in accounts_block.iter() {
+        assert_eq!(account_ids[&account.address], *account_id);
+    }
+
+    let (first_id, _) = accounts_block.iter().next().unwrap();
+    AccountSchema(&mut storage)
+        .set_account_type(*first_id, EthAccountType::CREATE2)
+        .await?;
+
+    let mut ids: Vec<_> = accounts_block.keys().copied().collect();
+    ids.push(unknown_id);
+    let states = AccountSchema(&mut storage)
+        .account_states_by_ids(&ids)
+        .await?;
+    assert_eq!(states.len(), accounts_block.len());
+    assert!(!states.contains_key(&unknown_id));
+    for account_id in accounts_block.keys() {
+        let state = &states[account_id];
+        let (_, committed) = AccountSchema(&mut storage)
+            .last_committed_state_for_account(*account_id)
+            .await?;
+        assert!(state.finalized.is_none());
+        assert_eq!(
+            state.committed.clone(),
+            committed.map(|account| (BlockNumber(1), account))
+        );
+        assert_eq!(
+            matches!(state.account_type, Some(EthAccountType::CREATE2)),
+            account_id == first_id
+        );
+    }
+
+    // Once the block is verified, finalized states become available.
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            BlockNumber(1),
+            AggregatedActionType::ExecuteBlocks,
+            block_size,
+        ))
+        .await?;
+    StateSchema(&mut storage)
+        .apply_state_update(BlockNumber(1))
+        .await?;
+
+    let states = AccountSchema(&mut storage)
+        .account_states_by_ids(&ids)
+        .await?;
+    assert_eq!(states.len(), accounts_block.len());
+    for account_id in accounts_block.keys() {
+        let state = &states[account_id];
+        let finalized = AccountSchema(&mut storage)
+            .last_verified_state_for_account(*account_id)
+            .await?
+            .map(|account| (BlockNumber(1), account));
+        assert_eq!(state.finalized, finalized);
+        assert_eq!(state.committed, finalized);
+    }
+
+    Ok(())
+}
+
 #[db_test]
 async fn test_get_balance(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     let _lock = ACCOUNT_MUTEX.lock().await;
@@ -534,3 +626,85 @@ async fn test_get_nft_owner(mut storage: StorageProcessor<'_>) -> QueryResult<()
 
     Ok(())
 }
+
+/// Checks that the balance history and the stored balances of the account are loaded.
+#[db_test]
+async fn account_balance_history(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let _lock = ACCOUNT_MUTEX.lock().await;
+    let account_id = AccountId(1);
+    let updates1 = vec![
+        (
+            account_id,
+            AccountUpdate::Create {
+                address: Address::random(),
+                nonce: Nonce(0),
+            },
+        ),
+        (
+            account_id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(1),
+                balance_update: (TokenId(0), BigUint::zero(), BigUint::from(100u32)),
+            },
+        ),
+    ];
+    let updates2 = vec![(
+        account_id,
+        AccountUpdate::UpdateBalance {
+            old_nonce: Nonce(1),
+            new_nonce: Nonce(2),
+            balance_update: (TokenId(0), BigUint::from(100u32), BigUint::from(40u32)),
+        },
+    )];
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(BlockNumber(1), &updates1, 0)
+        .await?;
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(BlockNumber(2), &updates2, 0)
+        .await?;
+    storage
+        .chain()
+        .state_schema()
+        .apply_state_update(BlockNumber(1))
+        .await?;
+
+    let sampled = storage
+        .chain()
+        .account_schema()
+        .sample_account_ids(10)
+        .await?;
+    assert!(sampled.contains(&account_id));
+
+    let stored = storage
+        .chain()
+        .account_schema()
+        .stored_account_balances(account_id)
+        .await?;
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[&TokenId(0)], BigUint::from(100u32));
+
+    let history = storage
+        .chain()
+        .account_schema()
+        .account_balance_history(account_id, BlockNumber(1))
+        .await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].block_number, BlockNumber(1));
+    assert_eq!(history[0].new_balance, BigUint::from(100u32));
+
+    let history = storage
+        .chain()
+        .account_schema()
+        .account_balance_history(account_id, BlockNumber(2))
+        .await?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1].old_balance, BigUint::from(100u32));
+    assert_eq!(history[1].new_balance, BigUint::from(40u32));
+
+    Ok(())
+}
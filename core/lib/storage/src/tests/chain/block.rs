@@ -17,7 +17,10 @@ use super::operations_ext::{
 };
 use crate::{
     chain::{
-        block::{records::StorageBlockDetails, BlockSchema},
+        block::{
+            records::{PendingBlockRevert, PendingRevertTx, StorageBlockDetails},
+            BlockSchema,
+        },
         operations::OperationsSchema,
         state::StateSchema,
     },
@@ -275,6 +278,8 @@ async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) ->
                 Some((id, op)),
                 100,
                 100u32.into(),
+                None,
+                None,
                 Default::default(),
             )
             .await?;
@@ -318,6 +323,8 @@ async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) ->
                         Some((id, op)),
                         100,
                         100u32.into(),
+                        None,
+                        None,
                         Default::default(),
                     )
                     .await?;
@@ -483,6 +490,8 @@ async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
                 Some((id, op)),
                 100,
                 100u32.into(),
+                None,
+                None,
                 Default::default(),
             )
             .await?;
@@ -516,6 +525,8 @@ async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
                     Some((id, op)),
                     100,
                     100u32.into(),
+                    None,
+                    None,
                     Default::default(),
                 )
                 .await?;
@@ -618,6 +629,8 @@ async fn unconfirmed_transaction(mut storage: StorageProcessor<'_>) -> QueryResu
                 Some((id, op)),
                 100,
                 100u32.into(),
+                None,
+                None,
                 Default::default(),
             )
             .await?;
@@ -655,6 +668,8 @@ async fn unconfirmed_transaction(mut storage: StorageProcessor<'_>) -> QueryResu
                     Some((id, op)),
                     100,
                     100u32.into(),
+                    None,
+                    None,
                     Default::default(),
                 )
                 .await?;
@@ -729,6 +744,7 @@ async fn pending_block_workflow(mut storage: StorageProcessor<'_>) -> QueryResul
             success: true,
             op: Some(change_pubkey_op),
             fail_reason: None,
+            fail_code: None,
             block_index: None,
             created_at: chrono::Utc::now(),
             batch_id: None,
@@ -764,6 +780,7 @@ async fn pending_block_workflow(mut storage: StorageProcessor<'_>) -> QueryResul
             success: true,
             op: Some(transfer_to_new_op),
             fail_reason: None,
+            fail_code: None,
             block_index: None,
             created_at: chrono::Utc::now(),
             batch_id: None,
@@ -1115,6 +1132,88 @@ async fn test_remove_blocks(mut storage: StorageProcessor<'_>) -> QueryResult<()
     Ok(())
 }
 
+/// Checks that the blocks are reverted together with their aggregated operations,
+/// and the revert in progress is finished.
+#[db_test]
+async fn test_revert_blocks(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for block_number in 1..=5 {
+        BlockSchema(&mut storage)
+            .save_full_block(gen_sample_block(
+                BlockNumber(block_number),
+                BLOCK_SIZE_CHUNKS,
+                Default::default(),
+            ))
+            .await?;
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                BlockNumber(block_number),
+                AggregatedActionType::CommitBlocks,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+    }
+    BlockSchema(&mut storage)
+        .save_pending_block(gen_sample_pending_block(BlockNumber(6), Vec::new()))
+        .await?;
+
+    assert_eq!(BlockSchema(&mut storage).load_pending_revert().await?, None);
+    BlockSchema(&mut storage)
+        .start_revert(BlockNumber(2))
+        .await?;
+    assert_eq!(
+        BlockSchema(&mut storage).load_pending_revert().await?,
+        Some(PendingBlockRevert {
+            last_correct_block: BlockNumber(2),
+            revert_tx: None,
+        })
+    );
+    BlockSchema(&mut storage)
+        .add_revert_tx(7, dummy_ethereum_tx_hash(1), 100.into(), None)
+        .await?;
+    // The replacement is added to the sent transactions.
+    BlockSchema(&mut storage)
+        .add_revert_tx(7, dummy_ethereum_tx_hash(2), 115.into(), Some(3.into()))
+        .await?;
+    // The recorded transactions are kept while the same revert is resumed.
+    BlockSchema(&mut storage)
+        .start_revert(BlockNumber(2))
+        .await?;
+    assert_eq!(
+        BlockSchema(&mut storage).load_pending_revert().await?,
+        Some(PendingBlockRevert {
+            last_correct_block: BlockNumber(2),
+            revert_tx: Some(PendingRevertTx {
+                nonce: 7,
+                tx_hashes: vec![dummy_ethereum_tx_hash(1), dummy_ethereum_tx_hash(2)],
+                gas_price: 115.into(),
+                priority_fee: Some(3.into()),
+            }),
+        })
+    );
+    assert_eq!(
+        BlockSchema(&mut storage).lock_pending_revert().await?,
+        Some(BlockNumber(2))
+    );
+
+    BlockSchema(&mut storage)
+        .revert_blocks(BlockNumber(2))
+        .await?;
+
+    assert_eq!(
+        BlockSchema(&mut storage).get_last_saved_block().await?,
+        BlockNumber(2)
+    );
+    assert_eq!(
+        BlockSchema(&mut storage).get_last_committed_block().await?,
+        BlockNumber(2)
+    );
+    assert!(!BlockSchema(&mut storage).pending_block_exists().await?);
+    assert_eq!(BlockSchema(&mut storage).load_pending_revert().await?, None);
+    assert_eq!(BlockSchema(&mut storage).lock_pending_revert().await?, None);
+
+    Ok(())
+}
+
 /// Check that blocks are removed correctly.
 #[db_test]
 async fn test_remove_pending_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -1,6 +1,9 @@
+// Built-in imports
+use std::collections::VecDeque;
 // External imports
 use chrono::Utc;
 // Workspace imports
+use zksync_api_types::v02::transaction::{InterruptedSubmission, SubmissionStage};
 use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 use zksync_types::{
     block::{Block, ExecutedOperations},
@@ -8,7 +11,7 @@ use zksync_types::{
     priority_ops::FullExit,
     tx::{ChangePubKey, Transfer, TxHash, Withdraw},
     AccountId, Address, BlockNumber, ExecutedPriorityOp, ExecutedTx, FullExitOp, Nonce, PriorityOp,
-    SignedZkSyncTx, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
+    PubKeyHash, SignedZkSyncTx, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
 // Local imports
 use crate::test_data::gen_eth_sign_data;
@@ -165,6 +168,123 @@ async fn store_load(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the scheduled transactions are loaded once the target block is committed.
+#[db_test]
+async fn load_scheduled_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    MempoolSchema(&mut storage).insert_tx(&txs[0]).await?;
+    MempoolSchema(&mut storage)
+        .insert_scheduled_tx(&txs[1], Some(BlockNumber(5)))
+        .await?;
+    MempoolSchema(&mut storage)
+        .insert_scheduled_tx(&txs[2], Some(BlockNumber(10)))
+        .await?;
+
+    let ready_hashes = |txs: VecDeque<SignedTxVariant>| -> Vec<TxHash> {
+        txs.into_iter().map(|tx| unwrap_tx(tx).hash()).collect()
+    };
+    let ready_txs = MempoolSchema(&mut storage)
+        .load_ready_txs(&[], BlockNumber(4))
+        .await?;
+    assert_eq!(ready_hashes(ready_txs), vec![txs[0].hash()]);
+    let ready_txs = MempoolSchema(&mut storage)
+        .load_ready_txs(&[], BlockNumber(5))
+        .await?;
+    assert_eq!(ready_hashes(ready_txs), vec![txs[0].hash(), txs[1].hash()]);
+
+    // The schedule is ignored when all the transactions are loaded.
+    let all_txs = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(all_txs.len(), txs.len());
+
+    Ok(())
+}
+
+/// Checks that only the scheduled transactions are replaced by the ones with the same nonce.
+#[db_test]
+async fn evict_scheduled_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Every transaction is sent by its own account with the same nonce.
+    let txs = gen_transfers(2);
+    let nonce = txs[0].nonce();
+    MempoolSchema(&mut storage)
+        .insert_scheduled_tx(&txs[0], Some(BlockNumber(5)))
+        .await?;
+    MempoolSchema(&mut storage).insert_tx(&txs[1]).await?;
+
+    assert!(
+        !MempoolSchema(&mut storage)
+            .has_unscheduled_tx(AccountId(0), nonce)
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .has_unscheduled_tx(AccountId(1), nonce)
+            .await?
+    );
+
+    for account_id in 0..2 {
+        MempoolSchema(&mut storage)
+            .evict_scheduled_txs(AccountId(account_id), nonce, "Replaced")
+            .await?;
+    }
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(txs[0].hash())
+            .await?
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_eviction_reason(txs[0].hash())
+            .await?,
+        Some("Replaced".to_string())
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .contains_tx(txs[1].hash())
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that only the `ChangePubKey` transactions of the account to the given key are evicted.
+#[db_test]
+async fn evict_change_pubkey_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    let change_pubkey = match &txs[3].tx {
+        ZkSyncTx::ChangePubKey(tx) => tx.clone(),
+        _ => panic!("ChangePubKey is expected"),
+    };
+
+    let other_pk_hash = PubKeyHash::from_bytes(&[0xaa; 20]).unwrap();
+    let evicted = MempoolSchema(&mut storage)
+        .evict_change_pubkey_txs(change_pubkey.account_id, &other_pk_hash, "Cancelled")
+        .await?;
+    assert_eq!(evicted, 0);
+
+    let evicted = MempoolSchema(&mut storage)
+        .evict_change_pubkey_txs(
+            change_pubkey.account_id,
+            &change_pubkey.new_pk_hash,
+            "Cancelled",
+        )
+        .await?;
+    assert_eq!(evicted, 1);
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_eviction_reason(txs[3].hash())
+            .await?,
+        Some("Cancelled".to_string())
+    );
+    for tx in &txs[..3] {
+        assert!(MempoolSchema(&mut storage).contains_tx(tx.hash()).await?);
+    }
+
+    Ok(())
+}
+
 /// Checks the save&load routine for mempool schema.
 #[db_test]
 async fn store_load_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -286,6 +406,7 @@ async fn collect_garbage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         created_at: chrono::Utc::now(),
         eth_sign_data: None,
         batch_id: None,
+        fail_code: None,
         affected_accounts: Vec::new(),
         used_tokens: Vec::new(),
     };
@@ -309,6 +430,188 @@ async fn collect_garbage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that evicted txs are removed from the mempool and the eviction reason is stored.
+#[db_test]
+async fn evict_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(4);
+    MempoolSchema(&mut storage).insert_tx(&txs[0]).await?;
+    let batch_id = MempoolSchema(&mut storage)
+        .insert_batch(&txs[1..3], vec![])
+        .await?;
+    MempoolSchema(&mut storage).insert_tx(&txs[3]).await?;
+
+    // Evict the single transaction and the whole batch.
+    let reason = "Tx nonce is too low.";
+    MempoolSchema(&mut storage)
+        .evict_txs(&[txs[0].hash()], None, reason)
+        .await?;
+    MempoolSchema(&mut storage)
+        .evict_txs(&[txs[1].hash(), txs[2].hash()], Some(batch_id), reason)
+        .await?;
+
+    // Only the last transaction should be retained.
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), 1);
+    assert_eq!(unwrap_tx(txs_from_db[0].clone()).hash(), txs[3].hash());
+
+    for tx in &txs[..3] {
+        let eviction_reason = MempoolSchema(&mut storage)
+            .get_eviction_reason(tx.hash())
+            .await?;
+        assert_eq!(eviction_reason.as_deref(), Some(reason));
+    }
+    assert!(MempoolSchema(&mut storage)
+        .get_eviction_reason(txs[3].hash())
+        .await?
+        .is_none());
+
+    // Records older than the max age are removed.
+    MempoolSchema(&mut storage)
+        .remove_evicted_txs(chrono::Duration::zero())
+        .await?;
+    assert!(MempoolSchema(&mut storage)
+        .get_eviction_reason(txs[0].hash())
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks that the rejection of the transaction doesn't affect the mempool.
+#[db_test]
+async fn store_rejected_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(2);
+    MempoolSchema(&mut storage).insert_tx(&txs[0]).await?;
+
+    // The duplicate of the queued transaction is rejected.
+    let reason = "Transaction is already in the mempool";
+    MempoolSchema(&mut storage)
+        .store_rejected_tx(txs[0].hash(), reason)
+        .await?;
+    MempoolSchema(&mut storage)
+        .store_rejected_tx(txs[1].hash(), reason)
+        .await?;
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), 1);
+    for tx in &txs {
+        let rejection_reason = MempoolSchema(&mut storage)
+            .get_eviction_reason(tx.hash())
+            .await?;
+        assert_eq!(rejection_reason.as_deref(), Some(reason));
+    }
+
+    // The first recorded reason is kept.
+    MempoolSchema(&mut storage)
+        .store_rejected_tx(txs[1].hash(), "Another reason")
+        .await?;
+    let rejection_reason = MempoolSchema(&mut storage)
+        .get_eviction_reason(txs[1].hash())
+        .await?;
+    assert_eq!(rejection_reason.as_deref(), Some(reason));
+
+    Ok(())
+}
+
+/// Checks the storing and the cleanup of the submissions interrupted by the shutdown.
+#[db_test]
+async fn interrupted_submissions(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    MempoolSchema(&mut storage)
+        .store_interrupted_submissions(&[
+            (txs[0].hash(), SubmissionStage::Verifying),
+            (txs[1].hash(), SubmissionStage::SentToMempool),
+        ])
+        .await?;
+    // The stage is updated by the next snapshot.
+    MempoolSchema(&mut storage)
+        .store_interrupted_submissions(&[(txs[1].hash(), SubmissionStage::Accepted)])
+        .await?;
+
+    let stage = |submission: Option<InterruptedSubmission>| submission.map(|s| s.stage);
+    let submission = MempoolSchema(&mut storage)
+        .get_interrupted_submission(txs[0].hash())
+        .await?;
+    assert_eq!(stage(submission), Some(SubmissionStage::Verifying));
+    let submission = MempoolSchema(&mut storage)
+        .get_interrupted_submission(txs[1].hash())
+        .await?;
+    assert_eq!(stage(submission), Some(SubmissionStage::Accepted));
+    let submission = MempoolSchema(&mut storage)
+        .get_interrupted_submission(txs[2].hash())
+        .await?;
+    assert_eq!(submission, None);
+
+    MempoolSchema(&mut storage)
+        .remove_interrupted_submissions(chrono::Duration::seconds(-1))
+        .await?;
+    let submission = MempoolSchema(&mut storage)
+        .get_interrupted_submission(txs[0].hash())
+        .await?;
+    assert_eq!(submission, None);
+
+    Ok(())
+}
+
+/// Checks that pending transactions are loaded according to their age.
+#[db_test]
+async fn load_pending_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let now = Utc::now();
+    let mut txs = gen_transfers(3);
+    for (tx, age) in txs.iter_mut().zip(&[30, 20, 10]) {
+        tx.created_at = now - chrono::Duration::seconds(*age);
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    // Transactions younger than 15 seconds are not loaded, the newest ones go first.
+    let pending_txs = MempoolSchema(&mut storage)
+        .load_pending_txs(None, now - chrono::Duration::seconds(15), 10)
+        .await?;
+    let hashes: Vec<_> = pending_txs.iter().map(|tx| tx.hash()).collect();
+    assert_eq!(hashes, vec![txs[1].hash(), txs[0].hash()]);
+
+    // The lower bound is exclusive.
+    let pending_txs = MempoolSchema(&mut storage)
+        .load_pending_txs(Some(txs[0].created_at), now, 10)
+        .await?;
+    let hashes: Vec<_> = pending_txs.iter().map(|tx| tx.hash()).collect();
+    assert_eq!(hashes, vec![txs[2].hash(), txs[1].hash()]);
+
+    let pending_txs = MempoolSchema(&mut storage)
+        .load_pending_txs(None, now, 1)
+        .await?;
+    assert_eq!(pending_txs.len(), 1);
+    assert_eq!(pending_txs[0].hash(), txs[2].hash());
+
+    Ok(())
+}
+
+/// Checks that pending transactions are paged from oldest to newest without gaps.
+#[db_test]
+async fn load_pending_txs_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let now = Utc::now();
+    let mut txs = gen_transfers(5);
+    for (tx, age) in txs.iter_mut().zip(&[50, 40, 30, 20, 10]) {
+        tx.created_at = now - chrono::Duration::seconds(*age);
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    // The oldest transaction is out of the range, the rest is split into two pages.
+    let mut hashes = Vec::new();
+    let mut last_id = None;
+    for expected_len in &[3, 1] {
+        let page = MempoolSchema(&mut storage)
+            .load_pending_txs_page(txs[0].created_at, now, last_id, 3)
+            .await?;
+        assert_eq!(page.len(), *expected_len);
+        last_id = page.last().map(|(id, _)| *id);
+        hashes.extend(page.iter().map(|(_, tx)| tx.hash()));
+    }
+    let expected: Vec<_> = txs[1..].iter().map(|tx| tx.hash()).collect();
+    assert_eq!(hashes, expected);
+
+    Ok(())
+}
+
 /// Checks that memory pool contains previously inserted transaction.
 #[db_test]
 async fn contains_and_get_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -431,6 +734,7 @@ async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>)
             success: true,
             op: None,
             fail_reason: None,
+            fail_code: None,
             block_index: Some(0),
             created_at: Utc::now(),
             batch_id: None,
@@ -512,3 +816,80 @@ async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>)
     assert_eq!(block_tx.variance_name(), "FullExit");
     Ok(())
 }
+
+/// Checks that the nonces of the queued transactions are found for the account.
+#[db_test]
+async fn get_queued_nonces(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut txs = zksync_txs();
+    let transfer = Transfer::new(
+        AccountId(42),
+        Address::random(),
+        Address::random(),
+        TokenId(0),
+        100u32.into(),
+        10u32.into(),
+        Nonce(15),
+        Default::default(),
+        None,
+    );
+    txs.push(SignedZkSyncTx {
+        tx: ZkSyncTx::Transfer(Box::new(transfer)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    });
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    let mut mempool = MempoolSchema(&mut storage);
+    assert_eq!(
+        mempool.get_queued_nonces(AccountId(42)).await?,
+        vec![Nonce(10), Nonce(15)]
+    );
+    assert_eq!(
+        mempool.get_queued_nonces(AccountId(33)).await?,
+        vec![Nonce(12)]
+    );
+    assert_eq!(
+        mempool.get_queued_nonces(AccountId(123)).await?,
+        vec![Nonce(13)]
+    );
+    assert!(mempool.get_queued_nonces(AccountId(7)).await?.is_empty());
+
+    Ok(())
+}
+
+/// Checks that only the unconfirmed priority operations of the reorged blocks are removed.
+#[db_test]
+async fn remove_unconfirmed_priority_ops(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let priority_op = |serial_id, eth_block| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::FullExit(FullExit {
+            account_id: AccountId(1),
+            eth_address: Address::random(),
+            token: TokenId(0),
+            is_legacy: false,
+        }),
+        deadline_block: 100,
+        eth_hash: H256::from_low_u64_be(serial_id),
+        eth_block,
+        eth_block_index: Some(1),
+    };
+
+    let mut mempool = MempoolSchema(&mut storage);
+    mempool
+        .insert_priority_ops(&[priority_op(0, 10)], true)
+        .await?;
+    mempool
+        .insert_priority_ops(&[priority_op(1, 11), priority_op(2, 12)], false)
+        .await?;
+
+    let mut removed = mempool.remove_unconfirmed_priority_ops(10).await?;
+    removed.sort_unstable();
+    assert_eq!(removed, vec![1, 2]);
+    // The confirmed operation is kept.
+    assert_eq!(mempool.get_confirmed_priority_ops().await?.len(), 1);
+    assert!(mempool.remove_unconfirmed_priority_ops(0).await?.is_empty());
+
+    Ok(())
+}
@@ -5,5 +5,6 @@ mod operations;
 mod operations_ext;
 mod state;
 mod tree_cache;
+mod withdrawals;
 
 pub use block::apply_random_updates;
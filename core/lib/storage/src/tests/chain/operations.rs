@@ -2,8 +2,9 @@
 use chrono::{Duration, Utc};
 // Workspace imports
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, Address, BlockNumber, Deposit, SequentialTxId,
-    ZkSyncPriorityOp, H256,
+    aggregated_operations::AggregatedActionType,
+    tx::{TxFailureCode, TxHash},
+    Address, BlockNumber, Deposit, SequentialTxId, ZkSyncPriorityOp, H256,
 };
 // Local imports
 use crate::chain::mempool::MempoolSchema;
@@ -14,6 +15,7 @@ use crate::{
             records::{NewExecutedPriorityOperation, NewExecutedTransaction},
             OperationsSchema,
         },
+        operations_ext::OperationsExtSchema,
     },
     test_data::gen_unique_aggregated_operation,
     tests::db_test,
@@ -64,6 +66,7 @@ async fn executed_operations(mut storage: StorageProcessor<'_>) -> QueryResult<(
         created_at: chrono::Utc::now(),
         eth_sign_data: None,
         batch_id: Some(10),
+        fail_code: None,
         affected_accounts: Vec::new(),
         used_tokens: Vec::new(),
     };
@@ -159,6 +162,7 @@ async fn duplicated_operations(mut storage: StorageProcessor<'_>) -> QueryResult
         created_at: chrono::Utc::now(),
         eth_sign_data: None,
         batch_id: None,
+        fail_code: None,
         affected_accounts: Vec::new(),
         used_tokens: Vec::new(),
     };
@@ -227,13 +231,14 @@ async fn transaction_resent(mut storage: StorageProcessor<'_>) -> QueryResult<()
         from_account: Default::default(),
         to_account: None,
         success: false, // <- Note that success is false. We'll replace this tx with succeeded one.
-        fail_reason: None,
+        fail_reason: Some("Nonce mismatch".to_string()),
         block_index: None,
         primary_account_address: Default::default(),
         nonce: Default::default(),
         created_at: chrono::Utc::now(),
         eth_sign_data: None,
         batch_id: None,
+        fail_code: Some(TxFailureCode::NonceMismatch.as_str().to_string()),
         affected_accounts: Vec::new(),
         used_tokens: Vec::new(),
     };
@@ -243,14 +248,22 @@ async fn transaction_resent(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .store_executed_tx(executed_tx.clone())
         .await?;
 
-    // Check that we can still load it.
-    assert!(OperationsSchema(&mut storage)
+    // Check that we can still load it along with the failure code.
+    let loaded_tx = OperationsSchema(&mut storage)
         .get_executed_operation(executed_tx.tx_hash.as_ref())
         .await?
-        .is_some());
+        .unwrap();
+    assert_eq!(loaded_tx.fail_code, executed_tx.fail_code);
+    let receipt = OperationsExtSchema(&mut storage)
+        .tx_receipt(executed_tx.tx_hash.as_ref())
+        .await?
+        .unwrap();
+    assert_eq!(receipt.fail_code, Some(TxFailureCode::NonceMismatch));
 
     // Replace failed tx with a successfull one.
     executed_tx.success = true;
+    executed_tx.fail_reason = None;
+    executed_tx.fail_code = None;
 
     OperationsSchema(&mut storage)
         .store_executed_tx(executed_tx.clone())
@@ -263,6 +276,7 @@ async fn transaction_resent(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .unwrap();
     assert_eq!(loaded_tx.tx_hash, executed_tx.tx_hash);
     assert!(loaded_tx.success);
+    assert_eq!(loaded_tx.fail_code, None);
 
     // Get the block transactions and check if there is exactly 1 tx (failed tx not copied but replaced).
     let block_txs = BlockSchema(&mut storage)
@@ -314,6 +328,7 @@ async fn remove_rejected_transactions(mut storage: StorageProcessor<'_>) -> Quer
         created_at: timestamp_1,
         eth_sign_data: None,
         batch_id: None,
+        fail_code: None,
         affected_accounts: vec![Address::zero().as_bytes().to_vec()],
         used_tokens: vec![0],
     };
@@ -427,6 +442,53 @@ async fn remove_rejected_transactions(mut storage: StorageProcessor<'_>) -> Quer
     Ok(())
 }
 
+/// Checks that the rejected transactions are loaded in the stable order page by page.
+#[db_test]
+async fn load_rejected_transactions_pages(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let created_at = Utc::now() - Duration::weeks(1);
+    let mut expected = Vec::new();
+    // Two transactions share the creation time, so they are ordered by the hash.
+    for (byte, age) in vec![(3u8, 0), (1, 1), (2, 1)] {
+        let tx_hash = TxHash::from_slice(&[byte; 32]).unwrap();
+        let tx = NewExecutedTransaction {
+            block_number: 1,
+            tx_hash: tx_hash.as_ref().to_vec(),
+            tx: Default::default(),
+            operation: Default::default(),
+            from_account: Default::default(),
+            to_account: None,
+            success: false,
+            fail_reason: None,
+            block_index: None,
+            primary_account_address: Default::default(),
+            nonce: Default::default(),
+            created_at: created_at - Duration::days(age),
+            eth_sign_data: None,
+            batch_id: None,
+            fail_code: None,
+            affected_accounts: vec![Address::zero().as_bytes().to_vec()],
+            used_tokens: vec![0],
+        };
+        expected.push((tx.created_at, tx_hash));
+        OperationsSchema(&mut storage).store_executed_tx(tx).await?;
+    }
+    expected.sort();
+    let expected: Vec<_> = expected.into_iter().map(|(_, tx_hash)| tx_hash).collect();
+    let hashes =
+        |page: &[(_, TxHash)]| page.iter().map(|(_, tx_hash)| *tx_hash).collect::<Vec<_>>();
+
+    let first_page = OperationsSchema(&mut storage)
+        .load_rejected_transactions(Duration::days(1), None, 2)
+        .await?;
+    assert_eq!(hashes(&first_page), expected[..2]);
+    let second_page = OperationsSchema(&mut storage)
+        .load_rejected_transactions(Duration::days(1), first_page.last().copied(), 2)
+        .await?;
+    assert_eq!(hashes(&second_page), expected[2..]);
+
+    Ok(())
+}
+
 /// Checks that getting executed priority operation by `eth_hash` is working correctly.
 #[db_test]
 async fn priority_ops_hashes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
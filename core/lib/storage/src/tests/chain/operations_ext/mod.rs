@@ -3,15 +3,20 @@ use std::collections::HashMap;
 // External imports
 // Workspace imports
 use zksync_api_types::v02::{
-    pagination::{AccountTxsRequest, ApiEither, PaginationDirection, PaginationQuery},
+    account::FullExitStatus,
+    event::{EventsFilter, EventsRequest},
+    pagination::{
+        AccountTxsCursorRequest, AccountTxsRequest, ApiEither, PaginationDirection, PaginationQuery,
+    },
     transaction::{Receipt, TxInBlockStatus},
 };
 use zksync_crypto::{franklin_crypto::bellman::pairing::ff::Field, Fr};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
-    block::Block,
+    block::{Block, PendingBlock},
     tx::TxHash,
-    AccountId, AccountUpdate, BlockNumber, ExecutedOperations, Nonce, ZkSyncOp, H256,
+    AccountId, AccountUpdate, Address, BlockNumber, ExecutedOperations, FullExit, Nonce,
+    PriorityOp, TokenId, ZkSyncOp, ZkSyncPriorityOp, H256,
 };
 // Local imports
 use self::setup::TransactionsHistoryTestSetup;
@@ -110,7 +115,15 @@ async fn confirm_eth_op(
     let eth_tx_hash = dummy_ethereum_tx_hash(op.0);
     let response = storage
         .ethereum_schema()
-        .save_new_eth_tx(op_type, Some(op), 100, 100u32.into(), Default::default())
+        .save_new_eth_tx(
+            op_type,
+            Some(op),
+            100,
+            100u32.into(),
+            None,
+            None,
+            Default::default(),
+        )
         .await?;
     storage
         .ethereum_schema()
@@ -598,6 +611,207 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
     Ok(())
 }
 
+/// Checks that the whole account history can be traversed with the cursor-based pagination
+/// in both directions and the result matches the one of `get_account_transactions`.
+#[db_test]
+async fn get_account_transactions_by_cursor(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    let from = setup.from_zksync_account.address;
+    setup.add_block(1);
+    setup.add_block(2);
+    commit_schema_data(&mut storage, &setup).await?;
+    commit_block(&mut storage, BlockNumber(1)).await?;
+    commit_block(&mut storage, BlockNumber(2)).await?;
+
+    let expected: Vec<TxHash> = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_transactions(&PaginationQuery {
+            from: AccountTxsRequest {
+                address: from,
+                tx_hash: ApiEither::from(setup.get_tx_hash(0, 0)),
+                token: None,
+                second_address: None,
+            },
+            limit: 100,
+            direction: PaginationDirection::Newer,
+        })
+        .await?
+        .unwrap()
+        .into_iter()
+        .map(|tx| tx.tx_hash)
+        .collect();
+
+    for &direction in &[PaginationDirection::Newer, PaginationDirection::Older] {
+        let mut cursor = None;
+        let mut actual = Vec::new();
+        loop {
+            let page = storage
+                .chain()
+                .operations_ext_schema()
+                .get_account_transactions_by_cursor(&PaginationQuery {
+                    from: AccountTxsCursorRequest {
+                        address: from,
+                        cursor,
+                        token: None,
+                        second_address: None,
+                    },
+                    limit: 7,
+                    direction,
+                })
+                .await?;
+            assert_eq!(page.pagination.from, cursor);
+            assert!(page.list.len() <= 7);
+            actual.extend(page.list.into_iter().map(|tx| tx.tx_hash));
+
+            cursor = page.pagination.next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        if direction == PaginationDirection::Older {
+            actual.reverse();
+        }
+        assert_eq!(actual, expected, "{:?} traversal failed", direction);
+    }
+
+    Ok(())
+}
+
+/// Checks that the events are filtered and paginated correctly.
+#[db_test]
+async fn get_events(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    let from = setup.from_zksync_account.address;
+    setup.add_block(1);
+    setup.add_block(2);
+    commit_schema_data(&mut storage, &setup).await?;
+    commit_block(&mut storage, BlockNumber(1)).await?;
+    commit_block(&mut storage, BlockNumber(2)).await?;
+
+    let all_events = |filter: EventsFilter| PaginationQuery {
+        from: EventsRequest {
+            filter,
+            cursor: None,
+        },
+        limit: 100,
+        direction: PaginationDirection::Newer,
+    };
+
+    // Without filters, all the operations are returned in the execution order.
+    let events = storage
+        .chain()
+        .operations_ext_schema()
+        .get_events(&all_events(EventsFilter::default()))
+        .await?;
+    assert!(events.pagination.next.is_none());
+    let expected: Vec<TxHash> = setup
+        .blocks
+        .iter()
+        .flat_map(|block| block.block_transactions.iter())
+        .map(|op| match op {
+            ExecutedOperations::Tx(tx) => tx.signed_tx.tx.hash(),
+            ExecutedOperations::PriorityOp(op) => op.priority_op.tx_hash(),
+        })
+        .collect();
+    let actual: Vec<TxHash> = events.list.iter().map(|event| event.tx.tx_hash).collect();
+    assert_eq!(actual, expected);
+
+    // Address filter returns the same operations as the account history.
+    let expected: Vec<TxHash> = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_transactions_by_cursor(&PaginationQuery {
+            from: AccountTxsCursorRequest {
+                address: from,
+                cursor: None,
+                token: None,
+                second_address: None,
+            },
+            limit: 100,
+            direction: PaginationDirection::Newer,
+        })
+        .await?
+        .list
+        .into_iter()
+        .map(|tx| tx.tx_hash)
+        .collect();
+    let events = storage
+        .chain()
+        .operations_ext_schema()
+        .get_events(&all_events(EventsFilter {
+            address: Some(from),
+            ..Default::default()
+        }))
+        .await?;
+    let actual: Vec<TxHash> = events.list.iter().map(|event| event.tx.tx_hash).collect();
+    assert_eq!(actual, expected);
+
+    let events = storage
+        .chain()
+        .operations_ext_schema()
+        .get_events(&all_events(EventsFilter {
+            op_type: Some("Deposit".into()),
+            ..Default::default()
+        }))
+        .await?;
+    assert!(!events.list.is_empty());
+    assert!(events.list.iter().all(|event| event.op_type == "Deposit"));
+
+    let events = storage
+        .chain()
+        .operations_ext_schema()
+        .get_events(&all_events(EventsFilter {
+            from_block: Some(BlockNumber(2)),
+            to_block: Some(BlockNumber(2)),
+            ..Default::default()
+        }))
+        .await?;
+    assert_eq!(events.list.len(), setup.blocks[1].block_transactions.len());
+    assert!(events
+        .list
+        .iter()
+        .all(|event| event.tx.block_number == Some(BlockNumber(2))));
+
+    // The traversal in the opposite direction with the small pages yields the same events.
+    let mut cursor = None;
+    let mut actual = Vec::new();
+    loop {
+        let page = storage
+            .chain()
+            .operations_ext_schema()
+            .get_events(&PaginationQuery {
+                from: EventsRequest {
+                    filter: EventsFilter::default(),
+                    cursor,
+                },
+                limit: 5,
+                direction: PaginationDirection::Older,
+            })
+            .await?;
+        assert!(page.list.len() <= 5);
+        actual.extend(page.list.into_iter().map(|event| event.tx.tx_hash));
+        cursor = page.pagination.next;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    actual.reverse();
+    let expected: Vec<TxHash> = storage
+        .chain()
+        .operations_ext_schema()
+        .get_events(&all_events(EventsFilter::default()))
+        .await?
+        .list
+        .into_iter()
+        .map(|event| event.tx.tx_hash)
+        .collect();
+    assert_eq!(actual, expected);
+
+    Ok(())
+}
+
 /// Test `get_tx_created_at_and_block_number` method
 #[db_test]
 async fn get_tx_sequnecner_id(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -940,6 +1154,52 @@ async fn tx_receipt(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         }
     }
 
+    // Test receipts for the operations from the pending block.
+    setup.add_block(3);
+    let block = setup.blocks[2].clone();
+    storage
+        .chain()
+        .block_schema()
+        .save_pending_block(PendingBlock {
+            number: block.block_number,
+            chunks_left: 0,
+            unprocessed_priority_op_before: 0,
+            pending_block_iteration: 1,
+            success_operations: block.block_transactions,
+            failed_txs: Vec::new(),
+            timestamp: 0,
+        })
+        .await?;
+    let l2_receipt = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_receipt_api_v02(setup.get_tx_hash(2, 3).as_ref())
+        .await?;
+    match l2_receipt.unwrap() {
+        Receipt::L2(receipt) => {
+            assert_eq!(receipt.status, TxInBlockStatus::Pending);
+            assert_eq!(receipt.rollup_block, Some(BlockNumber(3)));
+            assert_eq!(receipt.block_index, Some(3));
+        }
+        Receipt::L1(_) => {
+            panic!("Should be L2 receipt");
+        }
+    }
+    let l1_receipt = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_receipt_api_v02(setup.get_tx_hash(2, 0).as_ref())
+        .await?;
+    match l1_receipt.unwrap() {
+        Receipt::L1(receipt) => {
+            assert_eq!(receipt.status, TxInBlockStatus::Pending);
+            assert_eq!(receipt.block_index, Some(0));
+        }
+        Receipt::L2(_) => {
+            panic!("Should be L1 receipt");
+        }
+    }
+
     Ok(())
 }
 
@@ -1256,3 +1516,81 @@ async fn test_getting_swap_for_acc(mut storage: StorageProcessor<'_>) -> QueryRe
 
     Ok(())
 }
+
+/// Checks that both the pending and the executed full exits of the account are loaded.
+#[db_test]
+async fn account_full_exits(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    commit_schema_data(&mut storage, &setup).await?;
+    commit_block(&mut storage, BlockNumber(1)).await?;
+
+    let address = setup.from_zksync_account.address;
+    let pending_full_exit = |serial_id| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::FullExit(FullExit {
+            account_id: AccountId(0xbabe),
+            eth_address: address,
+            token: TokenId(0),
+            is_legacy: false,
+        }),
+        deadline_block: 100,
+        eth_hash: H256::from_low_u64_be(serial_id),
+        eth_block: 20,
+        eth_block_index: Some(1),
+    };
+    let mut mempool = storage.chain().mempool_schema();
+    mempool
+        .insert_priority_ops(&[pending_full_exit(10)], true)
+        .await?;
+    mempool
+        .insert_priority_ops(&[pending_full_exit(11)], false)
+        .await?;
+
+    let full_exits = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_full_exits(address, 10)
+        .await?;
+    let statuses: Vec<_> = full_exits
+        .iter()
+        .map(|full_exit| (full_exit.serial_id, full_exit.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (11, FullExitStatus::AwaitingConfirmations),
+            (10, FullExitStatus::Queued),
+            (3, FullExitStatus::Committed),
+        ]
+    );
+    assert_eq!(full_exits[2].block_number, Some(BlockNumber(1)));
+    assert_eq!(full_exits[2].withdraw_amount, Some(setup.amount.into()));
+
+    // The limit keeps the newest full exits.
+    let full_exits = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_full_exits(address, 1)
+        .await?;
+    assert_eq!(full_exits.len(), 1);
+    assert_eq!(full_exits[0].serial_id, 11);
+
+    verify_block(&mut storage, BlockNumber(1)).await?;
+    let full_exits = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_full_exits(address, 10)
+        .await?;
+    assert_eq!(full_exits[2].status, FullExitStatus::Finalized);
+
+    // Other addresses don't have full exits.
+    assert!(storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_full_exits(Address::random(), 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -281,6 +281,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(transfer_to_new_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -313,6 +314,7 @@ impl TransactionsHistoryTestSetup {
             success: block_index.is_some(),
             op: Some(transfer_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -344,6 +346,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(withdraw_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -375,6 +378,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(mint_nft_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -409,6 +413,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(withdraw_nft_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -465,6 +470,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(swap_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -484,6 +490,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(close_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -510,6 +517,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(change_pubkey_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -578,6 +586,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(swap_op),
             fail_reason: None,
+            fail_code: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
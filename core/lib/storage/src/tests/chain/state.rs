@@ -220,6 +220,52 @@ async fn state_diff(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the updates of the single account are loaded in the order of applying.
+#[db_test]
+async fn account_state_changes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+
+    let mut accounts_map = AccountMap::default();
+    let mut committed_updates = Vec::new();
+    for block_number in 1..=3 {
+        let block_number = BlockNumber(block_number);
+        let (new_accounts_map, updates) = apply_random_updates(accounts_map, &mut rng);
+        accounts_map = new_accounts_map;
+        StateSchema(&mut storage)
+            .commit_state_update(block_number, &updates, 0)
+            .await?;
+        committed_updates.extend(
+            updates
+                .into_iter()
+                .map(|(account_id, update)| (block_number, account_id, update)),
+        );
+    }
+
+    let expected_changes = |account_id: AccountId, from_block: u32, to_block: u32| {
+        committed_updates
+            .iter()
+            .filter(|(block_number, id, _)| {
+                *id == account_id && **block_number >= from_block && **block_number <= to_block
+            })
+            .map(|(block_number, _, update)| (*block_number, update.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    let account_id = committed_updates[0].1;
+    let changes = StateSchema(&mut storage)
+        .load_account_state_changes(account_id, BlockNumber(1), BlockNumber(3))
+        .await?;
+    assert!(!changes.is_empty());
+    assert_eq!(changes, expected_changes(account_id, 1, 3));
+
+    let changes = StateSchema(&mut storage)
+        .load_account_state_changes(account_id, BlockNumber(2), BlockNumber(2))
+        .await?;
+    assert_eq!(changes, expected_changes(account_id, 2, 2));
+
+    Ok(())
+}
+
 /// Checks if account updates are removed correctly.
 #[db_test]
 async fn test_remove_account_updates(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
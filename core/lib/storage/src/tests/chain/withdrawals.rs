@@ -0,0 +1,86 @@
+// External imports
+// Workspace imports
+use zksync_types::{
+    aggregated_operations::BlocksExecuteOperation, ethereum::WithdrawalStatus, H256,
+};
+// Local imports
+use super::operations_ext::setup::TransactionsHistoryTestSetup;
+use crate::{chain::withdrawals::WithdrawalsSchema, tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that withdrawals completed by the execute transaction are tracked
+/// from the scheduling to the confirmation.
+#[db_test]
+async fn test_withdrawals_status(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    setup.add_block(2);
+
+    let withdraw_hash = setup.get_tx_hash(0, 5);
+    let full_exit_hash = setup.get_tx_hash(0, 9);
+    let deferred_hash = setup.get_tx_hash(1, 5);
+    // Transfers are not processed during the execution.
+    let transfer_hash = setup.get_tx_hash(0, 2);
+
+    let operation = BlocksExecuteOperation {
+        blocks: vec![setup.blocks[0].clone()],
+        complete_withdrawals: true,
+    };
+    WithdrawalsSchema(&mut storage)
+        .schedule_withdrawals(1, &operation)
+        .await?;
+    let operation = BlocksExecuteOperation {
+        blocks: vec![setup.blocks[1].clone()],
+        complete_withdrawals: false,
+    };
+    WithdrawalsSchema(&mut storage)
+        .schedule_withdrawals(2, &operation)
+        .await?;
+
+    assert!(
+        WithdrawalsSchema(&mut storage)
+            .completes_withdrawals(1)
+            .await?
+    );
+    assert!(
+        !WithdrawalsSchema(&mut storage)
+            .completes_withdrawals(2)
+            .await?
+    );
+    assert!(WithdrawalsSchema(&mut storage)
+        .get_withdrawal_status(transfer_hash)
+        .await?
+        .is_none());
+
+    let status = WithdrawalsSchema(&mut storage)
+        .get_withdrawal_status(withdraw_hash)
+        .await?
+        .expect("withdrawal should be stored");
+    assert_eq!(status.status(), WithdrawalStatus::Scheduled);
+    assert_eq!(status.block_number, 1);
+    assert_eq!(status.eth_tx_hash, None);
+
+    // Confirm both operations, only the scheduled withdrawals should become completed.
+    let eth_tx_hash = H256::random();
+    WithdrawalsSchema(&mut storage)
+        .confirm_withdrawals(1, eth_tx_hash)
+        .await?;
+    WithdrawalsSchema(&mut storage)
+        .confirm_withdrawals(2, H256::random())
+        .await?;
+
+    for &tx_hash in &[withdraw_hash, full_exit_hash] {
+        let status = WithdrawalsSchema(&mut storage)
+            .get_withdrawal_status(tx_hash)
+            .await?
+            .expect("withdrawal should be stored");
+        assert_eq!(status.status(), WithdrawalStatus::Completed);
+        assert_eq!(status.eth_tx_hash, Some(eth_tx_hash.as_bytes().to_vec()));
+    }
+    let status = WithdrawalsSchema(&mut storage)
+        .get_withdrawal_status(deferred_hash)
+        .await?
+        .expect("withdrawal should be stored");
+    assert_eq!(status.status(), WithdrawalStatus::Deferred);
+
+    Ok(())
+}
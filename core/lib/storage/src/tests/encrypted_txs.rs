@@ -0,0 +1,168 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_api_types::v02::encrypted_tx::{EncryptedTxState, EncryptedTxStatus};
+use zksync_types::{tx::TxHash, H256};
+// Local imports
+use crate::tests::db_test;
+use crate::{encrypted_txs::EncryptedTxsSchema, QueryResult, StorageProcessor};
+
+/// Checks that the envelopes are only claimed once their reveal time has come, and are claimed
+/// again if the claim expires before their reveal result is stored.
+#[db_test]
+async fn claim_envelopes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let ready_hash = H256::repeat_byte(1);
+    let delayed_hash = H256::repeat_byte(2);
+    let claim_timeout = Duration::minutes(1);
+
+    let stored = EncryptedTxsSchema(&mut storage)
+        .store_envelope(
+            ready_hash,
+            &[1, 2, 3],
+            Utc::now() - Duration::seconds(1),
+            None,
+        )
+        .await?;
+    assert_eq!(stored.envelope, vec![1, 2, 3]);
+    // The repeated submission doesn't overwrite the envelope.
+    let stored = EncryptedTxsSchema(&mut storage)
+        .store_envelope(
+            ready_hash,
+            &[4, 5, 6],
+            Utc::now() + Duration::hours(1),
+            None,
+        )
+        .await?;
+    assert_eq!(stored.envelope, vec![1, 2, 3]);
+    EncryptedTxsSchema(&mut storage)
+        .store_envelope(delayed_hash, &[7], Utc::now() + Duration::hours(1), None)
+        .await?;
+
+    let claimed = EncryptedTxsSchema(&mut storage)
+        .claim_envelopes_to_reveal(10, claim_timeout)
+        .await?;
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(H256::from_slice(&claimed[0].envelope_hash), ready_hash);
+    assert!(claimed[0].revealed_at.is_none());
+    assert!(claimed[0].claimed_at.is_some());
+    // The claimed envelope is skipped until the claim expires.
+    assert!(EncryptedTxsSchema(&mut storage)
+        .claim_envelopes_to_reveal(10, claim_timeout)
+        .await?
+        .is_empty());
+    assert_eq!(
+        EncryptedTxsSchema(&mut storage)
+            .claim_envelopes_to_reveal(10, Duration::zero())
+            .await?
+            .len(),
+        1
+    );
+    EncryptedTxsSchema(&mut storage)
+        .store_reveal_result(ready_hash, &[], None)
+        .await?;
+    assert!(EncryptedTxsSchema(&mut storage)
+        .claim_envelopes_to_reveal(10, Duration::zero())
+        .await?
+        .is_empty());
+
+    let status: EncryptedTxStatus = EncryptedTxsSchema(&mut storage)
+        .get_envelope(delayed_hash)
+        .await?
+        .unwrap()
+        .into();
+    assert_eq!(status.state, EncryptedTxState::Pending);
+
+    Ok(())
+}
+
+/// Checks that the state of the revealed envelope depends on the submission result,
+/// and the revealed envelopes are removed once the retention period has passed.
+#[db_test]
+async fn reveal_result(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let revealed_hash = H256::repeat_byte(1);
+    let rejected_hash = H256::repeat_byte(2);
+    let pending_hash = H256::repeat_byte(3);
+    for hash in [revealed_hash, rejected_hash, pending_hash] {
+        EncryptedTxsSchema(&mut storage)
+            .store_envelope(hash, &[1], Utc::now() - Duration::seconds(1), None)
+            .await?;
+    }
+
+    let tx_hash = TxHash::from_slice(&[3; 32]).unwrap();
+    EncryptedTxsSchema(&mut storage)
+        .store_reveal_result(revealed_hash, &[tx_hash], None)
+        .await?;
+    EncryptedTxsSchema(&mut storage)
+        .store_reveal_result(rejected_hash, &[], Some("Cannot decrypt".to_string()))
+        .await?;
+
+    let revealed: EncryptedTxStatus = EncryptedTxsSchema(&mut storage)
+        .get_envelope(revealed_hash)
+        .await?
+        .unwrap()
+        .into();
+    assert_eq!(revealed.state, EncryptedTxState::Revealed);
+    assert_eq!(revealed.tx_hashes, vec![tx_hash]);
+    // The revealed envelope is not stored anymore.
+    assert!(EncryptedTxsSchema(&mut storage)
+        .get_envelope(revealed_hash)
+        .await?
+        .unwrap()
+        .envelope
+        .is_empty());
+
+    let rejected: EncryptedTxStatus = EncryptedTxsSchema(&mut storage)
+        .get_envelope(rejected_hash)
+        .await?
+        .unwrap()
+        .into();
+    assert_eq!(rejected.state, EncryptedTxState::Rejected);
+    assert_eq!(rejected.error.as_deref(), Some("Cannot decrypt"));
+
+    let removed = EncryptedTxsSchema(&mut storage)
+        .remove_revealed_envelopes(Utc::now() + Duration::seconds(1))
+        .await?;
+    assert_eq!(removed, 2);
+    assert!(EncryptedTxsSchema(&mut storage)
+        .get_envelope(revealed_hash)
+        .await?
+        .is_none());
+    assert!(EncryptedTxsSchema(&mut storage)
+        .get_envelope(pending_hash)
+        .await?
+        .is_some());
+
+    Ok(())
+}
+
+/// Checks that only the pending envelopes of the client are counted.
+#[db_test]
+async fn count_pending_envelopes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let client = "100.100.100.100";
+    let reveal_at = Utc::now() - Duration::seconds(1);
+    for byte in 1..=3 {
+        EncryptedTxsSchema(&mut storage)
+            .store_envelope(H256::repeat_byte(byte), &[byte], reveal_at, Some(client))
+            .await?;
+    }
+    EncryptedTxsSchema(&mut storage)
+        .store_envelope(
+            H256::repeat_byte(4),
+            &[4],
+            reveal_at,
+            Some("200.200.200.200"),
+        )
+        .await?;
+    EncryptedTxsSchema(&mut storage)
+        .store_reveal_result(H256::repeat_byte(1), &[], None)
+        .await?;
+
+    assert_eq!(
+        EncryptedTxsSchema(&mut storage)
+            .count_pending_envelopes(client)
+            .await?,
+        2
+    );
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+// Built-in uses
+// External uses
+// Workspace uses
+// Local uses
+use super::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the saved state of the Ethereum watcher is overwritten by the next one.
+#[db_test]
+async fn eth_watch_state(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(storage.eth_watch_schema().load_state().await?.is_none());
+
+    storage.eth_watch_schema().save_state(100, 5).await?;
+    storage.eth_watch_schema().save_state(110, 7).await?;
+
+    let state = storage
+        .eth_watch_schema()
+        .load_state()
+        .await?
+        .expect("State is not saved");
+    assert_eq!(state.last_ethereum_block(), 110);
+    assert_eq!(state.next_priority_op_id(), 7);
+
+    Ok(())
+}
@@ -4,8 +4,8 @@ use std::str::FromStr;
 // Workspace imports
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
-    ethereum::ETHOperation,
-    BlockNumber, H256, U256,
+    ethereum::{ETHOperation, OperatorKeyUsage, OperatorKeyUsageOutcome},
+    Address, BlockNumber, H256, U256,
 };
 // Local imports
 use crate::test_data::{gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS};
@@ -52,7 +52,10 @@ impl EthereumTxParams {
             op: self.op.clone(),
             nonce: nonce.into(),
             last_deadline_block: self.deadline_block,
+            first_deadline_block: self.deadline_block,
             last_used_gas_price,
+            last_used_priority_fee: None,
+            sender: None,
             used_tx_hashes,
             encoded_tx_data: self.raw_tx.clone(),
             confirmed: false,
@@ -89,6 +92,10 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
 
         assert_eq!(actual_next_nonce, expected_next_nonce);
     }
+    // Only the last obtained nonce can be released.
+    assert!(!EthereumSchema(&mut storage).release_nonce(3).await?);
+    assert!(EthereumSchema(&mut storage).release_nonce(4).await?);
+    assert_eq!(EthereumSchema(&mut storage).get_next_nonce().await?, 4);
 
     let unconfirmed_operations = EthereumSchema(&mut storage)
         .load_unconfirmed_operations()
@@ -116,6 +123,8 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
             params.op.clone(),
             params.deadline_block as i64,
             params.gas_price.clone(),
+            None,
+            None,
             params.raw_tx.clone(),
         )
         .await?;
@@ -156,6 +165,8 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
             params_2.op.clone(),
             params_2.deadline_block as i64,
             params_2.gas_price.clone(),
+            None,
+            None,
             params_2.raw_tx.clone(),
         )
         .await?;
@@ -202,6 +213,147 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
 ///
 /// If there is an `ETHOperation` and it's not confirmed, it must be returned by `load_unconfirmed_operations`
 /// and **not** returned by `load_unprocessed_operations`.
+/// Checks that the priority fee of the EIP-1559 transactions is stored and updated.
+#[db_test]
+async fn ethereum_priority_fee(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let block_number = BlockNumber(1);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+
+    let params = EthereumTxParams::new("CommitBlocks".into(), op);
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            Some(100u32.into()),
+            None,
+            params.raw_tx.clone(),
+        )
+        .await?;
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    let eth_op = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?[0]
+        .clone();
+    assert_eq!(eth_op.last_used_priority_fee, Some(100.into()));
+
+    // The replacement raises both fees.
+    EthereumSchema(&mut storage)
+        .update_eth_tx(response.id, 200, 2000u32.into(), Some(200u32.into()))
+        .await?;
+    let eth_op = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?[0]
+        .clone();
+    assert_eq!(eth_op.last_used_gas_price, 2000.into());
+    assert_eq!(eth_op.last_used_priority_fee, Some(200.into()));
+    assert_eq!(eth_op.last_deadline_block, 200);
+    // The deadline of the first transaction is kept.
+    assert_eq!(eth_op.first_deadline_block, params.deadline_block);
+
+    // The legacy transaction has no priority fee.
+    EthereumSchema(&mut storage)
+        .update_eth_tx(response.id, 300, 3000u32.into(), None)
+        .await?;
+    let eth_op = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?[0]
+        .clone();
+    assert_eq!(eth_op.last_used_priority_fee, None);
+
+    Ok(())
+}
+
+/// Checks that the nonces of the additional operator keys are tracked independently
+/// of the main key and of each other.
+#[db_test]
+async fn ethereum_operator_nonces(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+    let first_key = Address::from_low_u64_be(1);
+    let second_key = Address::from_low_u64_be(2);
+
+    // The nonce of the key has to be initialized first.
+    assert!(EthereumSchema(&mut storage)
+        .get_next_operator_nonce(first_key)
+        .await
+        .is_err());
+
+    EthereumSchema(&mut storage)
+        .initialize_operator_nonce(first_key, 10)
+        .await?;
+    EthereumSchema(&mut storage)
+        .initialize_operator_nonce(second_key, 0)
+        .await?;
+    // The stored nonce is not overwritten on restart.
+    EthereumSchema(&mut storage)
+        .initialize_operator_nonce(first_key, 0)
+        .await?;
+
+    let block_number = BlockNumber(1);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+    let params = EthereumTxParams::new("CommitBlocks".into(), op);
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            None,
+            Some(first_key),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    assert_eq!(response.nonce, 10.into());
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    let eth_op = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?[0]
+        .clone();
+    assert_eq!(eth_op.sender, Some(first_key));
+
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .get_next_operator_nonce(first_key)
+            .await?,
+        11
+    );
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .get_next_operator_nonce(second_key)
+            .await?,
+        0
+    );
+    // The nonce of the main key is not affected.
+    assert_eq!(EthereumSchema(&mut storage).get_next_nonce().await?, 0);
+
+    Ok(())
+}
+
 #[db_test]
 async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     EthereumSchema(&mut storage).initialize_eth_data().await?;
@@ -270,6 +422,8 @@ async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<
             params.op.clone(),
             params.deadline_block as i64,
             params.gas_price.clone(),
+            None,
+            None,
             params.raw_tx.clone(),
         )
         .await?;
@@ -328,6 +482,8 @@ async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<
             verify_params.op,
             verify_params.deadline_block as i64,
             verify_params.gas_price.clone(),
+            None,
+            None,
             verify_params.raw_tx.clone(),
         )
         .await?;
@@ -399,3 +555,101 @@ async fn test_update_eth_parameters(mut storage: StorageProcessor<'_>) -> QueryR
 
     Ok(())
 }
+
+/// Checks that the resynced nonce is used by the next transaction.
+#[db_test]
+async fn ethereum_nonce_resync(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+    assert_eq!(storage.ethereum_schema().load_nonce().await?, 0);
+
+    storage.ethereum_schema().set_nonce(42).await?;
+    assert_eq!(storage.ethereum_schema().load_nonce().await?, 42);
+    assert_eq!(storage.ethereum_schema().get_next_nonce().await?, 42);
+    assert_eq!(storage.ethereum_schema().load_nonce().await?, 43);
+
+    // The same applies to the nonces of the additional operator keys.
+    let key = Address::from_low_u64_be(1);
+    assert_eq!(
+        storage.ethereum_schema().load_operator_nonce(key).await?,
+        None
+    );
+    storage
+        .ethereum_schema()
+        .initialize_operator_nonce(key, 10)
+        .await?;
+    storage.ethereum_schema().set_operator_nonce(key, 7).await?;
+    assert_eq!(
+        storage.ethereum_schema().load_operator_nonce(key).await?,
+        Some(7)
+    );
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .get_next_operator_nonce(key)
+            .await?,
+        7
+    );
+
+    Ok(())
+}
+
+/// Checks that the operator key audit log can be written and read, but not modified.
+#[db_test]
+async fn operator_key_audit_log(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let rejected = OperatorKeyUsage {
+        eth_op_id: 1,
+        op_type: AggregatedActionType::CommitBlocks,
+        contract_addr: Address::repeat_byte(0x11),
+        payload_hash: H256::repeat_byte(0x22),
+        nonce: U256::from(3),
+        gas_limit: U256::from(5_000_000),
+        gas_price: U256::from_dec_str("1000000000000").unwrap(),
+        value: U256::zero(),
+        tx_hash: None,
+        outcome: OperatorKeyUsageOutcome::Rejected,
+        reason: Some("gas price is too high".into()),
+    };
+    let signed = OperatorKeyUsage {
+        gas_price: U256::from(1_000_000_000),
+        tx_hash: Some(H256::repeat_byte(0x33)),
+        outcome: OperatorKeyUsageOutcome::Signed,
+        reason: None,
+        ..rejected.clone()
+    };
+    storage
+        .ethereum_schema()
+        .record_operator_key_usage(&rejected)
+        .await?;
+    storage
+        .ethereum_schema()
+        .record_operator_key_usage(&signed)
+        .await?;
+
+    let usages = storage
+        .ethereum_schema()
+        .load_operator_key_usages(1)
+        .await?;
+    assert_eq!(usages.len(), 2);
+    assert_eq!(usages[0].outcome, "rejected");
+    assert_eq!(usages[0].op_type, "CommitBlocks");
+    assert_eq!(usages[0].gas_price.to_string(), "1000000000000");
+    assert_eq!(usages[0].reason.as_deref(), Some("gas price is too high"));
+    assert_eq!(usages[1].outcome, "signed");
+    assert_eq!(
+        usages[1].tx_hash.as_deref(),
+        Some(H256::repeat_byte(0x33).as_bytes())
+    );
+    assert!(storage
+        .ethereum_schema()
+        .load_operator_key_usages(2)
+        .await?
+        .is_empty());
+
+    // Entries can't be changed once written.
+    let update = sqlx::query("UPDATE operator_key_audit_log SET outcome = 'signed'")
+        .execute(storage.conn())
+        .await;
+    assert!(update.is_err());
+
+    Ok(())
+}
@@ -72,6 +72,8 @@ async fn store_operation(
             Some((id, op)),
             100,
             100u32.into(),
+            None,
+            None,
             Default::default(),
         )
         .await?;
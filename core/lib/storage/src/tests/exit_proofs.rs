@@ -0,0 +1,136 @@
+// Built-in uses
+// External uses
+use chrono::{Duration, Utc};
+// Workspace uses
+use zksync_api_types::v02::account::{ExitProofData, ExitProofStatus, StoredBlockInfo};
+use zksync_crypto::proof::EncodedSingleProof;
+use zksync_types::{AccountId, Address, BlockNumber, TokenId};
+// Local uses
+use super::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+fn exit_proof_data(account_id: AccountId, token_id: TokenId, block: BlockNumber) -> ExitProofData {
+    ExitProofData {
+        stored_block_info: StoredBlockInfo {
+            block_number: block,
+            priority_operations: 0,
+            pending_onchain_operations_hash: Default::default(),
+            timestamp: 0,
+            state_hash: Default::default(),
+            commitment: Default::default(),
+        },
+        owner: Address::random(),
+        account_id,
+        token_id,
+        token_address: Address::zero(),
+        amount: 100u32.into(),
+        nft_creator_id: AccountId(0),
+        nft_creator_address: Address::zero(),
+        nft_serial_id: 0,
+        nft_content_hash: Default::default(),
+        proof: EncodedSingleProof::default(),
+    }
+}
+
+/// Checks the lifecycle of the requested exit proof.
+#[db_test]
+async fn exit_proof_jobs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let (account_id, token_id, block) = (AccountId(1), TokenId(0), BlockNumber(5));
+    let requested = storage
+        .exit_proofs_schema()
+        .request_exit_proof(account_id, token_id, block)
+        .await?;
+    assert_eq!(requested.status(), ExitProofStatus::Queued);
+    assert_eq!(requested.block_number, None);
+    // The repeated request doesn't queue the proof again.
+    let repeated = storage
+        .exit_proofs_schema()
+        .request_exit_proof(account_id, token_id, block)
+        .await?;
+    assert_eq!(repeated.created_at, requested.created_at);
+    let other = storage
+        .exit_proofs_schema()
+        .request_exit_proof(account_id, TokenId(1), block)
+        .await?;
+
+    let abandoned_before = Utc::now() - Duration::hours(1);
+    let job = storage
+        .exit_proofs_schema()
+        .start_exit_proof_job(abandoned_before)
+        .await?
+        .expect("Queued job is not started");
+    assert_eq!(job.token_id, 0);
+    assert_eq!(job.status(), ExitProofStatus::InProgress);
+    let job = storage
+        .exit_proofs_schema()
+        .start_exit_proof_job(abandoned_before)
+        .await?
+        .expect("Queued job is not started");
+    assert_eq!(job.token_id, other.token_id);
+    assert!(storage
+        .exit_proofs_schema()
+        .start_exit_proof_job(abandoned_before)
+        .await?
+        .is_none());
+
+    // The unfinished jobs are restarted once they're considered abandoned.
+    let job = storage
+        .exit_proofs_schema()
+        .start_exit_proof_job(Utc::now() + Duration::hours(1))
+        .await?
+        .expect("Abandoned job is not restarted");
+    assert_eq!(job.token_id, 0);
+
+    let data = exit_proof_data(account_id, token_id, block);
+    storage.exit_proofs_schema().store_exit_proof(&data).await?;
+    storage
+        .exit_proofs_schema()
+        .store_exit_proof_error(account_id, TokenId(1), "Account not found")
+        .await?;
+
+    let ready = storage
+        .exit_proofs_schema()
+        .request_exit_proof(account_id, token_id, block)
+        .await?
+        .into_info()?;
+    assert_eq!(ready.status, ExitProofStatus::Ready);
+    assert_eq!(ready.block_number, Some(block));
+    assert_eq!(ready.data, Some(data));
+    let failed = storage
+        .exit_proofs_schema()
+        .load_exit_proof(account_id, TokenId(1))
+        .await?
+        .expect("Requested proof is not stored")
+        .into_info()?;
+    assert_eq!(failed.status, ExitProofStatus::Failed);
+    assert_eq!(failed.error.as_deref(), Some("Account not found"));
+    // Finished jobs are never restarted.
+    assert!(storage
+        .exit_proofs_schema()
+        .start_exit_proof_job(Utc::now() + Duration::hours(1))
+        .await?
+        .is_none());
+
+    // The failed proofs and the ones outdated by the newer finalized block are queued again.
+    let retried = storage
+        .exit_proofs_schema()
+        .request_exit_proof(account_id, TokenId(1), block)
+        .await?;
+    assert_eq!(retried.status(), ExitProofStatus::Queued);
+    assert_eq!(retried.error, None);
+    let outdated = storage
+        .exit_proofs_schema()
+        .request_exit_proof(account_id, token_id, block + 1)
+        .await?
+        .into_info()?;
+    assert_eq!(outdated.status, ExitProofStatus::Queued);
+    assert_eq!(outdated.block_number, None);
+    assert_eq!(outdated.data, None);
+    assert!(storage
+        .exit_proofs_schema()
+        .load_exit_proof(AccountId(2), token_id)
+        .await?
+        .is_none());
+
+    Ok(())
+}
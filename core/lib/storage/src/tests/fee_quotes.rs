@@ -0,0 +1,127 @@
+// External imports
+use chrono::{Duration, Utc};
+use num::{rational::Ratio, BigUint};
+// Workspace imports
+use zksync_types::{Address, TokenId};
+// Local imports
+use crate::fee_quotes::{
+    records::{FeeQuote, FeeQuotesFilter},
+    FeeQuotesSchema,
+};
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+fn fee_quote(tx_types: &[&str], addresses: Vec<Address>, token_id: TokenId) -> FeeQuote {
+    FeeQuote {
+        tx_types: tx_types.iter().map(|tx_type| tx_type.to_string()).collect(),
+        addresses,
+        token_id,
+        zkp_fee: BigUint::from(100u32),
+        gas_fee: BigUint::from(900u32),
+        total_fee: BigUint::from(1000u32),
+        subsidized: false,
+        gas_price_wei: BigUint::from(1_000_000_000u64),
+        token_usd_price: Ratio::new(BigUint::from(3u32), BigUint::from(2u32)),
+        created_at: Utc::now(),
+    }
+}
+
+/// Checks that the fee quotes are stored, filtered and pruned correctly.
+#[db_test]
+async fn fee_quotes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let another_address = Address::repeat_byte(2);
+    FeeQuotesSchema(&mut storage)
+        .store_fee_quote(&fee_quote(&["Transfer"], vec![address], TokenId(0)))
+        .await?;
+    FeeQuotesSchema(&mut storage)
+        .store_fee_quotes(&[fee_quote(
+            &["Withdraw", "TransferToNew"],
+            vec![another_address, address],
+            TokenId(1),
+        )])
+        .await?;
+
+    let filter = FeeQuotesFilter {
+        token_id: None,
+        address: None,
+        tx_type: None,
+        from: Utc::now() - Duration::hours(1),
+        to: Utc::now() + Duration::hours(1),
+        limit: 10,
+    };
+    let quotes = FeeQuotesSchema(&mut storage)
+        .load_fee_quotes(&filter)
+        .await?;
+    assert_eq!(quotes.len(), 2);
+    // The most recent quote goes first.
+    assert_eq!(quotes[0].token_id, 1);
+    assert_eq!(quotes[1].tx_types, vec!["Transfer".to_string()]);
+    assert_eq!(quotes[1].addresses, vec![address.as_bytes().to_vec()]);
+    assert_eq!(quotes[1].total_fee.to_string(), "1000");
+    assert_eq!(quotes[1].token_usd_price.to_string(), "1.500000");
+
+    // The batches are matched by any of their addresses and transaction types.
+    let by_address = FeeQuotesFilter {
+        address: Some(address),
+        ..filter.clone()
+    };
+    assert_eq!(
+        FeeQuotesSchema(&mut storage)
+            .load_fee_quotes(&by_address)
+            .await?
+            .len(),
+        2
+    );
+    let by_tx_type = FeeQuotesFilter {
+        tx_type: Some("TransferToNew".to_string()),
+        ..filter.clone()
+    };
+    assert_eq!(
+        FeeQuotesSchema(&mut storage)
+            .load_fee_quotes(&by_tx_type)
+            .await?
+            .len(),
+        1
+    );
+    let by_token = FeeQuotesFilter {
+        token_id: Some(TokenId(0)),
+        address: Some(another_address),
+        ..filter.clone()
+    };
+    assert!(FeeQuotesSchema(&mut storage)
+        .load_fee_quotes(&by_token)
+        .await?
+        .is_empty());
+    let limited = FeeQuotesFilter {
+        limit: 1,
+        ..filter.clone()
+    };
+    assert_eq!(
+        FeeQuotesSchema(&mut storage)
+            .load_fee_quotes(&limited)
+            .await?
+            .len(),
+        1
+    );
+
+    // Pruning.
+    assert_eq!(
+        FeeQuotesSchema(&mut storage)
+            .remove_old_fee_quotes(Utc::now() - Duration::hours(1))
+            .await?,
+        0
+    );
+    assert_eq!(
+        FeeQuotesSchema(&mut storage)
+            .remove_old_fee_quotes(Utc::now() + Duration::seconds(1))
+            .await?,
+        2
+    );
+    assert!(FeeQuotesSchema(&mut storage)
+        .load_fee_quotes(&filter)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+// Built-in uses
+// External uses
+// Workspace uses
+// Local uses
+use super::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that every elected leader starts the next term.
+#[db_test]
+async fn leader_terms(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let initial_term = storage.leader_election_schema().lock_current_term().await?;
+
+    let first_term = storage.leader_election_schema().start_term("first").await?;
+    assert_eq!(first_term, initial_term + 1);
+    let second_term = storage
+        .leader_election_schema()
+        .start_term("second")
+        .await?;
+    assert_eq!(second_term, first_term + 1);
+
+    assert_eq!(
+        storage.leader_election_schema().lock_current_term().await?,
+        second_term
+    );
+
+    Ok(())
+}
@@ -23,14 +23,22 @@ use tokio::sync::Mutex;
 // Workspace imports
 use zksync_crypto::rand::{SeedableRng, XorShiftRng};
 
+mod api_keys;
 pub(crate) mod chain;
 mod config;
 mod data_restore;
+mod encrypted_txs;
+mod eth_watch;
 mod ethereum;
 mod event;
+mod exit_proofs;
+mod fee_quotes;
 mod forced_exit_requests;
+mod leader_election;
 mod misc;
+mod protocol_parameters;
 mod prover;
+mod recovery;
 mod tokens;
 
 pub use db_test_macro::test as db_test;
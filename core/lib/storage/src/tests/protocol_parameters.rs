@@ -0,0 +1,130 @@
+// Built-in uses
+// External uses
+// Workspace uses
+use zksync_types::{
+    protocol::{ProtocolParameters, ProtocolParametersUpdate},
+    BlockNumber,
+};
+// Local uses
+use super::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+fn parameters(block_chunk_sizes: Vec<usize>, max_withdrawals: u64) -> ProtocolParameters {
+    ProtocolParameters {
+        block_chunk_sizes,
+        max_withdrawals_per_execute_tx: max_withdrawals,
+    }
+}
+
+/// Checks that the parameters in force at the block are loaded.
+#[db_test]
+async fn parameters_for_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(storage
+        .protocol_parameters_schema()
+        .load_parameters_for_block(BlockNumber(1))
+        .await?
+        .is_none());
+
+    let first = ProtocolParametersUpdate {
+        from_block: BlockNumber(1),
+        parameters: parameters(vec![10, 32, 72], 100),
+    };
+    let second = ProtocolParametersUpdate {
+        from_block: BlockNumber(10),
+        parameters: parameters(vec![26, 78, 182], 50),
+    };
+    // Updates may be stored in any order.
+    storage
+        .protocol_parameters_schema()
+        .store_parameters(&second)
+        .await?;
+    storage
+        .protocol_parameters_schema()
+        .store_parameters(&first)
+        .await?;
+
+    for (block, expected) in [(1, &first), (9, &first), (10, &second), (100, &second)] {
+        let loaded = storage
+            .protocol_parameters_schema()
+            .load_parameters_for_block(BlockNumber(block))
+            .await?;
+        assert_eq!(
+            loaded.as_ref(),
+            Some(&expected.parameters),
+            "block {}",
+            block
+        );
+    }
+
+    let history = storage
+        .protocol_parameters_schema()
+        .load_parameters_history()
+        .await?;
+    assert_eq!(history, vec![first, second]);
+
+    // Storing the parameters for the same block overwrites them.
+    let overwritten = ProtocolParametersUpdate {
+        from_block: BlockNumber(10),
+        parameters: parameters(vec![26, 78], 50),
+    };
+    storage
+        .protocol_parameters_schema()
+        .store_parameters(&overwritten)
+        .await?;
+    assert_eq!(
+        storage
+            .protocol_parameters_schema()
+            .load_parameters_for_block(BlockNumber(10))
+            .await?,
+        Some(overwritten.parameters)
+    );
+
+    Ok(())
+}
+
+/// Checks that the parameters are recorded only if they differ from the ones in force.
+#[db_test]
+async fn record_parameters(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let initial = parameters(vec![10, 32, 72], 100);
+    assert!(
+        storage
+            .protocol_parameters_schema()
+            .record_parameters(BlockNumber(1), &initial)
+            .await?
+    );
+    // The same parameters are not recorded again.
+    assert!(
+        !storage
+            .protocol_parameters_schema()
+            .record_parameters(BlockNumber(5), &initial)
+            .await?
+    );
+
+    let changed = parameters(vec![10, 32], 100);
+    assert!(
+        storage
+            .protocol_parameters_schema()
+            .record_parameters(BlockNumber(5), &changed)
+            .await?
+    );
+
+    let history = storage
+        .protocol_parameters_schema()
+        .load_parameters_history()
+        .await?;
+    assert_eq!(
+        history,
+        vec![
+            ProtocolParametersUpdate {
+                from_block: BlockNumber(1),
+                parameters: initial,
+            },
+            ProtocolParametersUpdate {
+                from_block: BlockNumber(5),
+                parameters: changed,
+            },
+        ]
+    );
+
+    Ok(())
+}
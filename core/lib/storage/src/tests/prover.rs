@@ -1,10 +1,12 @@
+// Built-in imports
+use std::time::Duration;
 // External imports
 use anyhow::format_err;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 // Workspace imports
 use zksync_types::{
-    prover::{ProverJob, ProverJobType},
+    prover::{ProverCapabilities, ProverJob, ProverJobAging, ProverJobStatus, ProverJobType},
     BlockNumber,
 };
 // Local imports
@@ -14,14 +16,44 @@ use crate::{prover::ProverSchema, QueryResult, StorageProcessor};
 
 static MUTEX: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
+const PROVER_NAME: &str = "test_prover";
+const LEASE_DURATION: Duration = Duration::from_secs(60);
+
 async fn get_idle_job_from_queue(storage: &mut StorageProcessor<'_>) -> QueryResult<ProverJob> {
+    get_idle_job_with_aging(storage, &ProverJobAging::default()).await
+}
+
+async fn get_idle_job_with_aging(
+    storage: &mut StorageProcessor<'_>,
+    aging: &ProverJobAging,
+) -> QueryResult<ProverJob> {
     let job = ProverSchema(storage)
-        .get_idle_prover_job_from_job_queue()
+        .get_idle_prover_job_from_job_queue(
+            PROVER_NAME,
+            &ProverCapabilities::default(),
+            LEASE_DURATION,
+            aging,
+        )
         .await?;
 
     job.ok_or_else(|| format_err!("expect idle job from job queue"))
 }
 
+async fn get_capable_job(
+    storage: &mut StorageProcessor<'_>,
+    capability_tags: &[&str],
+) -> QueryResult<Option<ProverJob>> {
+    let capabilities = ProverCapabilities::from_tags(capability_tags)?;
+    ProverSchema(storage)
+        .get_idle_prover_job_from_job_queue(
+            PROVER_NAME,
+            &capabilities,
+            LEASE_DURATION,
+            &ProverJobAging::default(),
+        )
+        .await
+}
+
 /// Checks that the `prover_job_queue` correctly processes requests to it.
 /// `prover_job_queue` table is locked when accessed, so it cannot be accessed simultaneously.
 #[db_test]
@@ -183,13 +215,13 @@ async fn pending_jobs_count(storage: &mut StorageProcessor<'_>) -> QueryResult<(
 
     // Record prover is working and stopped it.
     ProverSchema(storage)
-        .record_prover_is_working(first_job.job_id, "test_prover")
+        .record_prover_is_working(first_job.job_id, PROVER_NAME, LEASE_DURATION)
         .await?;
     ProverSchema(storage)
-        .record_prover_is_working(second_job.job_id, "test_prover")
+        .record_prover_is_working(second_job.job_id, PROVER_NAME, LEASE_DURATION)
         .await?;
     ProverSchema(storage)
-        .record_prover_is_working(third_job.job_id, "test_prover")
+        .record_prover_is_working(third_job.job_id, PROVER_NAME, LEASE_DURATION)
         .await?;
 
     // Store one proof and then turn off the prover.
@@ -204,7 +236,7 @@ async fn pending_jobs_count(storage: &mut StorageProcessor<'_>) -> QueryResult<(
     assert_eq!(jobs_count, 2);
 
     ProverSchema(storage)
-        .record_prover_stop("test_prover")
+        .record_prover_stop(PROVER_NAME)
         .await?;
 
     let jobs_count = ProverSchema(storage).pending_jobs_count().await?;
@@ -213,6 +245,191 @@ async fn pending_jobs_count(storage: &mut StorageProcessor<'_>) -> QueryResult<(
     Ok(())
 }
 
+/// Checks that the waiting jobs and the jobs of the blocks with aged priority operations
+/// are given out before the jobs with the higher `job_priority`.
+#[db_test]
+async fn test_prover_job_aging(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    for (block, job_priority, job_type) in [
+        (1, 1, ProverJobType::SingleProof),
+        (2, 0, ProverJobType::AggregatedProof),
+        (3, 1, ProverJobType::SingleProof),
+    ] {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(block),
+                BlockNumber(block),
+                Default::default(),
+                job_priority,
+                job_type,
+            )
+            .await?;
+    }
+    // The job of the first block waits for an hour,
+    // and the third block has a priority operation executed an hour ago.
+    sqlx::query(
+        "UPDATE prover_job_queue SET created_at = now() - INTERVAL '1 hour' WHERE first_block = 1",
+    )
+    .execute(storage.conn())
+    .await?;
+    sqlx::query(
+        "UPDATE prover_job_queue SET oldest_priority_op_at = now() - INTERVAL '1 hour'
+        WHERE first_block = 3",
+    )
+    .execute(storage.conn())
+    .await?;
+
+    let aging = ProverJobAging {
+        interval: Duration::from_secs(600),
+        priority_op_threshold: Duration::from_secs(1800),
+    };
+    // The aged priority operation goes first, then the aged job.
+    let job = get_idle_job_with_aging(&mut storage, &aging).await?;
+    assert_eq!(job.first_block, BlockNumber(3));
+    let job = get_idle_job_with_aging(&mut storage, &aging).await?;
+    assert_eq!(job.first_block, BlockNumber(1));
+    let job = get_idle_job_with_aging(&mut storage, &aging).await?;
+    assert_eq!(job.first_block, BlockNumber(2));
+
+    Ok(())
+}
+
+/// Checks that the job is leased to the prover it's given out to, and is returned to the queue
+/// once the lease expires.
+#[db_test]
+async fn test_prover_job_lease(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            Default::default(),
+            1,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    let job = get_idle_job_from_queue(&mut storage).await?;
+
+    // Only the prover holding the lease can renew it.
+    assert!(
+        ProverSchema(&mut storage)
+            .record_prover_is_working(job.job_id, PROVER_NAME, LEASE_DURATION)
+            .await?
+    );
+    assert!(
+        !ProverSchema(&mut storage)
+            .record_prover_is_working(job.job_id, "another_prover", LEASE_DURATION)
+            .await?
+    );
+
+    // The job with the lease in force isn't returned to the queue.
+    ProverSchema(&mut storage).mark_stale_jobs_as_idle().await?;
+    let assignments = ProverSchema(&mut storage)
+        .load_prover_job_assignments()
+        .await?;
+    assert_eq!(assignments.len(), 1);
+    assert_eq!(assignments[0].assigned_prover.as_deref(), Some(PROVER_NAME));
+    assert!(assignments[0].lease_expires_at.is_some());
+
+    sqlx::query("UPDATE prover_job_queue SET lease_expires_at = now() - INTERVAL '1 second'")
+        .execute(storage.conn())
+        .await?;
+    ProverSchema(&mut storage).mark_stale_jobs_as_idle().await?;
+    let assignments = ProverSchema(&mut storage)
+        .load_prover_job_assignments()
+        .await?;
+    assert!(matches!(assignments[0].status, ProverJobStatus::Idle));
+    assert_eq!(assignments[0].assigned_prover, None);
+    assert_eq!(assignments[0].reassignments, 1);
+
+    // The expired lease can't be renewed, the job is given out again.
+    assert!(
+        !ProverSchema(&mut storage)
+            .record_prover_is_working(job.job_id, PROVER_NAME, LEASE_DURATION)
+            .await?
+    );
+    let reassigned_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(reassigned_job.job_id, job.job_id);
+
+    Ok(())
+}
+
+/// Checks that the jobs are given out only to the provers capable of them.
+#[db_test]
+async fn test_prover_capabilities(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    for (block, block_size) in [(1, 10), (2, 20)] {
+        storage
+            .chain()
+            .block_schema()
+            .save_full_block(gen_sample_block(
+                BlockNumber(block),
+                block_size,
+                Default::default(),
+            ))
+            .await?;
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(block),
+                BlockNumber(block),
+                Default::default(),
+                1,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(2),
+            Default::default(),
+            0,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+
+    let idle_jobs = ProverSchema(&mut storage).load_idle_jobs().await?;
+    let idle_jobs: Vec<_> = idle_jobs
+        .iter()
+        .map(|job| (*job.first_block, *job.last_block, job.block_size))
+        .collect();
+    assert_eq!(
+        idle_jobs,
+        vec![(1, 1, Some(10)), (2, 2, Some(20)), (1, 2, None)]
+    );
+
+    let job = get_capable_job(&mut storage, &["single_proof", "block_size=20"])
+        .await?
+        .unwrap();
+    assert_eq!(job.first_block, BlockNumber(2));
+    let job = get_capable_job(
+        &mut storage,
+        &["aggregated_proof", "max_aggregated_blocks=1"],
+    )
+    .await?;
+    assert!(job.is_none());
+    let job = get_capable_job(&mut storage, &["max_aggregated_blocks=2"])
+        .await?
+        .unwrap();
+    assert_eq!(
+        (job.first_block, job.last_block),
+        (BlockNumber(1), BlockNumber(2))
+    );
+    assert!(get_capable_job(&mut storage, &["block_size=20"])
+        .await?
+        .is_none());
+    let job = get_capable_job(&mut storage, &[]).await?.unwrap();
+    assert_eq!(job.first_block, BlockNumber(1));
+
+    Ok(())
+}
+
 /// Checks that the witness can be stored and loaded.
 #[db_test]
 async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -241,7 +458,7 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
     let witness = serde_json::to_value(expected.clone()).unwrap();
     storage
         .prover_schema()
-        .store_witness(BLOCK_NUMBER, witness)
+        .store_witness(BLOCK_NUMBER, witness, vec![0xaa; 32])
         .await?;
 
     // Now load it.
@@ -251,13 +468,18 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .await?
         .map(|value| serde_json::from_value(value).unwrap());
     assert_eq!(loaded.as_ref(), Some(&expected));
+    let witness_hash = storage
+        .prover_schema()
+        .get_witness_hash(BLOCK_NUMBER)
+        .await?;
+    assert_eq!(witness_hash, Some(vec![0xaa; 32]));
 
     // Do nothing on conflict.
     let not_expected = String::from("__test");
     let witness = serde_json::to_value(expected.clone()).unwrap();
     storage
         .prover_schema()
-        .store_witness(BLOCK_NUMBER, witness)
+        .store_witness(BLOCK_NUMBER, witness, vec![0xbb; 32])
         .await?;
 
     let loaded = storage
@@ -267,6 +489,59 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .map(|value| serde_json::from_value(value).unwrap());
     assert_ne!(loaded, Some(not_expected));
     assert_eq!(loaded, Some(expected));
+    let witness_hash = storage
+        .prover_schema()
+        .get_witness_hash(BLOCK_NUMBER)
+        .await?;
+    assert_eq!(witness_hash, Some(vec![0xaa; 32]));
+
+    // The removed witness can be stored again.
+    storage.prover_schema().remove_witness(BLOCK_NUMBER).await?;
+    assert!(storage
+        .prover_schema()
+        .get_witness(BLOCK_NUMBER)
+        .await?
+        .is_none());
+    let witness = serde_json::to_value("test").unwrap();
+    storage
+        .prover_schema()
+        .store_witness(BLOCK_NUMBER, witness, vec![0xbb; 32])
+        .await?;
+    let witness_hash = storage
+        .prover_schema()
+        .get_witness_hash(BLOCK_NUMBER)
+        .await?;
+    assert_eq!(witness_hash, Some(vec![0xbb; 32]));
+
+    Ok(())
+}
+
+/// Checks that the witness backlog is the number of blocks after the last block with a witness.
+#[db_test]
+async fn test_witness_backlog(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(storage.prover_schema().witness_backlog().await?, 0);
+
+    for block_number in 1..=5 {
+        storage
+            .chain()
+            .block_schema()
+            .save_full_block(gen_sample_block(
+                BlockNumber(block_number),
+                100,
+                Default::default(),
+            ))
+            .await?;
+    }
+    assert_eq!(storage.prover_schema().witness_backlog().await?, 5);
+
+    for block_number in 1..=3 {
+        let witness = serde_json::to_value(String::from("test")).unwrap();
+        storage
+            .prover_schema()
+            .store_witness(BlockNumber(block_number), witness, Vec::new())
+            .await?;
+    }
+    assert_eq!(storage.prover_schema().witness_backlog().await?, 2);
 
     Ok(())
 }
@@ -288,7 +563,7 @@ async fn test_remove_witnesses(mut storage: StorageProcessor<'_>) -> QueryResult
         let witness = serde_json::to_value(String::from("test")).unwrap();
         storage
             .prover_schema()
-            .store_witness(BlockNumber(block_number), witness)
+            .store_witness(BlockNumber(block_number), witness, Vec::new())
             .await?;
     }
     // Remove witnesses for the 4th and 5th blocks.
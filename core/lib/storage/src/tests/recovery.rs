@@ -0,0 +1,106 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::{AccountId, Address, PubKeyHash};
+// Local imports
+use crate::tests::db_test;
+use crate::{recovery::RecoverySchema, QueryResult, StorageProcessor};
+
+/// Checks that the guardians are replaced rather than appended.
+#[db_test]
+async fn guardians(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let account_id = AccountId(1);
+    assert!(RecoverySchema(&mut storage)
+        .load_guardians(account_id)
+        .await?
+        .is_empty());
+
+    let guardians = vec![Address::repeat_byte(1), Address::repeat_byte(2)];
+    RecoverySchema(&mut storage)
+        .set_guardians(account_id, &guardians)
+        .await?;
+    assert_eq!(
+        RecoverySchema(&mut storage)
+            .load_guardians(account_id)
+            .await?,
+        guardians
+    );
+
+    let guardians = vec![Address::repeat_byte(3)];
+    RecoverySchema(&mut storage)
+        .set_guardians(account_id, &guardians)
+        .await?;
+    assert_eq!(
+        RecoverySchema(&mut storage)
+            .load_guardians(account_id)
+            .await?,
+        guardians
+    );
+    // Guardians of the other accounts are not affected.
+    assert!(RecoverySchema(&mut storage)
+        .load_guardians(AccountId(2))
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the recoveries are stored, loaded and cancelled correctly.
+#[db_test]
+async fn recoveries(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let account_id = AccountId(1);
+    let guardian = Address::repeat_byte(1);
+    let pub_key_hash = PubKeyHash::from_bytes(&[0xaa; 20]).unwrap();
+    let executable_at = Utc::now() + Duration::days(1);
+
+    assert!(RecoverySchema(&mut storage)
+        .get_active_recovery(account_id)
+        .await?
+        .is_none());
+    assert!(RecoverySchema(&mut storage)
+        .cancel_recovery(account_id)
+        .await?
+        .is_none());
+
+    let stored = RecoverySchema(&mut storage)
+        .store_recovery(account_id, &pub_key_hash, guardian, executable_at)
+        .await?;
+    assert_eq!(stored.account_id, 1);
+    assert_eq!(stored.new_pub_key_hash, pub_key_hash.data.to_vec());
+    assert_eq!(stored.initiated_by, guardian.as_bytes().to_vec());
+    assert!(stored.cancelled_at.is_none());
+
+    let active = RecoverySchema(&mut storage)
+        .get_active_recovery(account_id)
+        .await?
+        .expect("Recovery must be stored");
+    assert_eq!(active.id, stored.id);
+    let by_hash = RecoverySchema(&mut storage)
+        .get_recovery_by_pub_key_hash(account_id, &pub_key_hash)
+        .await?
+        .expect("Recovery must be stored");
+    assert_eq!(by_hash.id, stored.id);
+    assert!(RecoverySchema(&mut storage)
+        .get_recovery_by_pub_key_hash(AccountId(2), &pub_key_hash)
+        .await?
+        .is_none());
+
+    let cancelled = RecoverySchema(&mut storage)
+        .cancel_recovery(account_id)
+        .await?
+        .expect("Recovery must be cancelled");
+    assert_eq!(cancelled.id, stored.id);
+    assert!(cancelled.cancelled_at.is_some());
+    assert!(RecoverySchema(&mut storage)
+        .get_active_recovery(account_id)
+        .await?
+        .is_none());
+    // Cancelled recovery is still returned by the public key hash.
+    let cancelled = RecoverySchema(&mut storage)
+        .get_recovery_by_pub_key_hash(account_id, &pub_key_hash)
+        .await?
+        .expect("Recovery must be stored");
+    assert!(cancelled.cancelled_at.is_some());
+
+    Ok(())
+}
@@ -1,13 +1,14 @@
 // Built-in imports
 use std::str::FromStr;
 // External imports
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 use num::{rational::Ratio, BigUint};
 // Workspace imports
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
-    tokens::TokenMarketVolume, AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx,
-    Token, TokenId, TokenKind, TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
+    tokens::{TokenAdminSettings, TokenMarketVolume},
+    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx, Token, TokenId, TokenKind,
+    TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
 };
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
@@ -126,6 +127,23 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         .expect("token by symbol not found");
     assert_eq!(token_b, token_b_by_symbol_case_insensitive);
 
+    // Symbols are not unique.
+    let token_c = Token {
+        id: TokenId(3),
+        address: "0000000000000000000000000000000000000003".parse().unwrap(),
+        symbol: "abc".into(),
+        ..token_a.clone()
+    };
+    TokensSchema(&mut storage)
+        .store_or_update_token(token_c.clone())
+        .await
+        .expect("Store tokens query failed");
+    let tokens_by_symbol = TokensSchema(&mut storage)
+        .get_tokens_by_symbol("Abc")
+        .await
+        .expect("get tokens query failed");
+    assert_eq!(tokens_by_symbol, vec![token_a.clone(), token_c]);
+
     let db_nft_token = TokensSchema(&mut storage)
         .get_token(TokenLike::Id(nft.id))
         .await
@@ -179,6 +197,95 @@ async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Checks that the price history is aggregated into the intervals of the requested resolution.
+#[db_test]
+async fn test_ticker_price_history(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const TOKEN_ID: TokenId = TokenId(0);
+    // The start of the hour, so all the prices below are within the same hour.
+    let hour_start = Utc.timestamp(Utc::now().timestamp() / 3600 * 3600, 0);
+    let prices = vec![
+        (hour_start, 1u32),
+        (hour_start + chrono::Duration::minutes(10), 2u32),
+        (hour_start + chrono::Duration::minutes(50), 3u32),
+        (hour_start + chrono::Duration::minutes(70), 4u32),
+    ];
+    for (last_updated, usd_price) in &prices {
+        let price = TokenPrice {
+            usd_price: Ratio::from_integer(BigUint::from(*usd_price)),
+            last_updated: *last_updated,
+        };
+        storage
+            .tokens_schema()
+            .store_ticker_price_history(TOKEN_ID, &price)
+            .await?;
+    }
+
+    let from = hour_start;
+    let to = hour_start + chrono::Duration::hours(2);
+    // Every price is returned as is with the resolution of one minute.
+    let history = storage
+        .tokens_schema()
+        .load_ticker_price_history(TOKEN_ID, from, to, 60)
+        .await?;
+    let expected: Vec<_> = prices
+        .iter()
+        .map(|(last_updated, usd_price)| (last_updated.timestamp(), *usd_price))
+        .collect();
+    assert_eq!(
+        history
+            .iter()
+            .map(|price| (price.last_updated.timestamp(), price.usd_price.to_integer()))
+            .collect::<Vec<_>>(),
+        expected
+            .iter()
+            .map(|(timestamp, price)| (*timestamp, BigUint::from(*price)))
+            .collect::<Vec<_>>()
+    );
+
+    // The last price of the hour is returned with the resolution of one hour.
+    let history = storage
+        .tokens_schema()
+        .load_ticker_price_history(TOKEN_ID, from, to, 3600)
+        .await?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].last_updated.timestamp(), hour_start.timestamp());
+    assert_eq!(history[0].usd_price.to_integer(), BigUint::from(3u32));
+    assert_eq!(history[1].usd_price.to_integer(), BigUint::from(4u32));
+
+    // The range is applied before the aggregation.
+    let history = storage
+        .tokens_schema()
+        .load_ticker_price_history(
+            TOKEN_ID,
+            from,
+            hour_start + chrono::Duration::minutes(30),
+            3600,
+        )
+        .await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].usd_price.to_integer(), BigUint::from(2u32));
+
+    // Only the prices fetched before the given time are removed.
+    let removed = storage
+        .tokens_schema()
+        .remove_old_ticker_price_history(hour_start + chrono::Duration::minutes(50))
+        .await?;
+    assert_eq!(removed, 2);
+    let history = storage
+        .tokens_schema()
+        .load_ticker_price_history(TOKEN_ID, from, to, 60)
+        .await?;
+    assert_eq!(
+        history
+            .iter()
+            .map(|price| price.usd_price.to_integer())
+            .collect::<Vec<_>>(),
+        vec![BigUint::from(3u32), BigUint::from(4u32)]
+    );
+
+    Ok(())
+}
+
 /// Checks the store/load routine for `ticker_market_volume` table and load tokens by market volume.
 #[db_test]
 async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -187,6 +294,7 @@ async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()
     let market_volume = TokenMarketVolume {
         market_volume: Ratio::new(BigUint::from(2u32), BigUint::from(5u32)),
         last_updated: chrono::Utc::now(),
+        fee_allowed: Some(true),
     };
 
     storage
@@ -201,6 +309,7 @@ async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .expect("couldn't load market volume");
 
     assert_eq!(loaded.market_volume, market_volume.market_volume);
+    assert_eq!(loaded.fee_allowed, Some(true));
 
     assert_eq!(
         loaded.last_updated.timestamp(),
@@ -222,6 +331,66 @@ async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()
     Ok(())
 }
 
+/// Checks the token info update and the operator overrides of the token properties.
+#[db_test]
+async fn test_token_admin_settings(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token {
+        id: TokenId(1),
+        address: "0000000000000000000000000000000000000001".parse().unwrap(),
+        symbol: "ABC".into(),
+        decimals: 9,
+        kind: TokenKind::ERC20,
+        is_nft: false,
+    };
+    storage
+        .tokens_schema()
+        .store_or_update_token(token.clone())
+        .await?;
+
+    // Only the provided fields are updated.
+    let updated = storage
+        .tokens_schema()
+        .update_token_info(token.id, Some("XYZ"), None)
+        .await?
+        .expect("token not found");
+    assert_eq!(updated.symbol, "XYZ");
+    assert_eq!(updated.decimals, token.decimals);
+    assert!(storage
+        .tokens_schema()
+        .update_token_info(TokenId(2), Some("XYZ"), None)
+        .await?
+        .is_none());
+
+    assert!(storage
+        .tokens_schema()
+        .load_token_admin_settings()
+        .await?
+        .is_empty());
+
+    let settings = TokenAdminSettings {
+        fee_eligible: Some(false),
+        disabled: false,
+    };
+    storage
+        .tokens_schema()
+        .store_token_admin_settings(token.id, settings)
+        .await?;
+    let settings = TokenAdminSettings {
+        fee_eligible: None,
+        disabled: true,
+    };
+    storage
+        .tokens_schema()
+        .store_token_admin_settings(token.id, settings)
+        .await?;
+
+    let loaded = storage.tokens_schema().load_token_admin_settings().await?;
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[&token.id], settings);
+
+    Ok(())
+}
+
 /// Checks the store/load factories for nft
 #[db_test]
 async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -275,6 +444,7 @@ async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryRes
         success: true,
         op: Some(op),
         fail_reason: None,
+        fail_code: None,
         block_index: Some(0),
         created_at: Utc::now(),
         batch_id: None,
@@ -2,6 +2,7 @@
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 // External imports
+use chrono::{DateTime, Utc};
 use num::{rational::Ratio, BigUint};
 
 use thiserror::Error;
@@ -13,16 +14,23 @@ use zksync_api_types::v02::{
 use zksync_types::{AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT};
 use zksync_utils::ratio_to_big_decimal;
 // Local imports
-use self::records::{DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, TokenKind};
+use self::records::{
+    DBMarketVolume, DbTickerPrice, DbTickerPriceHistoryPoint, DbToken, DbTokenAdminSettings,
+    StorageApiNFT, StorageNFT, TokenKind,
+};
 
 use crate::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
-use zksync_types::tokens::TokenMarketVolume;
+use zksync_types::tokens::{TokenAdminSettings, TokenMarketVolume};
 
 pub mod records;
 
 /// Precision of the USD price per token
-pub(crate) const STORED_USD_PRICE_PRECISION: usize = 6;
+pub const STORED_USD_PRICE_PRECISION: usize = 6;
+
+/// Channel notified every time the tokens are changed via the admin API,
+/// the payload is the ID of the changed token.
+pub const TOKENS_UPDATED_CHANNEL: &str = "tokens_updated";
 
 /// Tokens schema handles the `tokens` table, providing methods to
 /// get and store new tokens.
@@ -457,6 +465,26 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(db_token.map(|t| t.into()))
     }
 
+    /// Returns all the tokens with the given symbol (case-insensitive), ordered by id.
+    /// Symbols are not unique, so there may be several of them.
+    pub async fn get_tokens_by_symbol(&mut self, symbol: &str) -> QueryResult<Vec<Token>> {
+        let start = Instant::now();
+        let db_tokens = sqlx::query_as!(
+            DbToken,
+            r#"
+            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            WHERE lower(symbol) = lower($1)
+            ORDER BY id
+            "#,
+            symbol
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.get_tokens_by_symbol", start.elapsed());
+        Ok(db_tokens.into_iter().map(|t| t.into()).collect())
+    }
+
     pub async fn get_token_market_volume(
         &mut self,
         token_id: TokenId,
@@ -465,7 +493,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let db_market_volume = sqlx::query_as!(
             DBMarketVolume,
             r#"
-            SELECT * FROM ticker_market_volume
+            SELECT token_id, market_volume, last_updated, fee_allowed FROM ticker_market_volume
             WHERE token_id = $1
             LIMIT 1
             "#,
@@ -488,15 +516,16 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             ratio_to_big_decimal(&market_volume.market_volume, STORED_USD_PRICE_PRECISION);
         sqlx::query!(
             r#"
-            INSERT INTO ticker_market_volume ( token_id, market_volume, last_updated )
-            VALUES ( $1, $2, $3 )
+            INSERT INTO ticker_market_volume ( token_id, market_volume, last_updated, fee_allowed )
+            VALUES ( $1, $2, $3, $4 )
             ON CONFLICT (token_id)
             DO
-              UPDATE SET market_volume = $2, last_updated = $3
+              UPDATE SET market_volume = $2, last_updated = $3, fee_allowed = $4
             "#,
             *token_id as i32,
             market_volume_rounded.clone(),
-            market_volume.last_updated
+            market_volume.last_updated,
+            market_volume.fee_allowed
         )
         .fetch_optional(self.0.conn())
         .await?;
@@ -505,6 +534,92 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Updates the symbol and/or the decimals of the token.
+    /// Returns the updated token or `None` if it doesn't exist.
+    pub async fn update_token_info(
+        &mut self,
+        token_id: TokenId,
+        symbol: Option<&str>,
+        decimals: Option<u8>,
+    ) -> QueryResult<Option<Token>> {
+        let start = Instant::now();
+        let db_token = sqlx::query_as!(
+            DbToken,
+            r#"
+            UPDATE tokens SET symbol = COALESCE($2, symbol), decimals = COALESCE($3, decimals)
+            WHERE id = $1
+            RETURNING id, address, decimals, kind as "kind: _", symbol
+            "#,
+            *token_id as i32,
+            symbol,
+            decimals.map(i16::from)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.update_token_info", start.elapsed());
+        Ok(db_token.map(|t| t.into()))
+    }
+
+    /// Loads the operator overrides of the token properties.
+    /// Tokens without overrides are not included.
+    pub async fn load_token_admin_settings(
+        &mut self,
+    ) -> QueryResult<HashMap<TokenId, TokenAdminSettings>> {
+        let start = Instant::now();
+        let settings = sqlx::query_as!(DbTokenAdminSettings, "SELECT * FROM token_admin_settings")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|settings| (TokenId(settings.token_id as u32), settings.into()))
+            .collect();
+
+        metrics::histogram!("sql.token.load_token_admin_settings", start.elapsed());
+        Ok(settings)
+    }
+
+    /// Replaces the operator overrides of the token properties.
+    pub async fn store_token_admin_settings(
+        &mut self,
+        token_id: TokenId,
+        settings: TokenAdminSettings,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_admin_settings ( token_id, fee_eligible, disabled, updated_at )
+            VALUES ( $1, $2, $3, now() )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET fee_eligible = $2, disabled = $3, updated_at = now()
+            "#,
+            *token_id as i32,
+            settings.fee_eligible,
+            settings.disabled
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.store_token_admin_settings", start.elapsed());
+        Ok(())
+    }
+
+    /// Notifies the listeners of the `TOKENS_UPDATED_CHANNEL` that the token is changed.
+    /// If called within a transaction, the notification is sent once it's committed.
+    pub async fn notify_token_updated(&mut self, token_id: TokenId) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "SELECT pg_notify($1, $2)",
+            TOKENS_UPDATED_CHANNEL,
+            token_id.to_string()
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.notify_token_updated", start.elapsed());
+        Ok(())
+    }
+
     /// Given token id, returns its price in USD and a timestamp of the last update.
     pub async fn get_historical_ticker_price(
         &mut self,
@@ -557,6 +672,86 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Appends the price fetched by the fee ticker to the price history of the token.
+    ///
+    /// Note, that the price precision cannot be greater than `STORED_USD_PRICE_PRECISION`,
+    /// so the number might get rounded.
+    pub async fn store_ticker_price_history(
+        &mut self,
+        token_id: TokenId,
+        price: &TokenPrice,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let usd_price_rounded = ratio_to_big_decimal(&price.usd_price, STORED_USD_PRICE_PRECISION);
+        sqlx::query!(
+            r#"
+            INSERT INTO ticker_price_history ( token_id, usd_price, created_at )
+            VALUES ( $1, $2, $3 )
+            "#,
+            *token_id as i32,
+            usd_price_rounded,
+            price.last_updated
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.store_ticker_price_history", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the price history of the token in the given time range, inclusive.
+    /// The range is split into the intervals of `resolution_secs` seconds, and for every
+    /// interval with at least one stored price the last price is returned, with
+    /// `last_updated` set to the start of the interval.
+    pub async fn load_ticker_price_history(
+        &mut self,
+        token_id: TokenId,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        resolution_secs: u64,
+    ) -> QueryResult<Vec<TokenPrice>> {
+        let start = Instant::now();
+        let points = sqlx::query_as!(
+            DbTickerPriceHistoryPoint,
+            r#"
+            SELECT DISTINCT ON (1)
+                to_timestamp(floor(date_part('epoch', created_at) / $4) * $4) as "timestamp!",
+                usd_price
+            FROM ticker_price_history
+            WHERE token_id = $1 AND created_at >= $2 AND created_at <= $3
+            ORDER BY 1, created_at DESC
+            "#,
+            *token_id as i32,
+            from,
+            to,
+            resolution_secs as f64
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.load_ticker_price_history", start.elapsed());
+        Ok(points.into_iter().map(TokenPrice::from).collect())
+    }
+
+    /// Removes the prices fetched before the given time from the price history.
+    /// Returns the number of the removed prices.
+    pub async fn remove_old_ticker_price_history(
+        &mut self,
+        before: DateTime<Utc>,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM ticker_price_history WHERE created_at < $1",
+            before
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.token.remove_old_ticker_price_history", start.elapsed());
+        Ok(removed)
+    }
+
     pub async fn store_nft_factory(
         &mut self,
         creator_id: AccountId,
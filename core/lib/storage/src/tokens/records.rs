@@ -9,7 +9,7 @@ use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
 use zksync_api_types::v02::token::ApiNFT;
 use zksync_types::{
-    tokens::{TokenMarketVolume, TokenPrice},
+    tokens::{TokenAdminSettings, TokenMarketVolume, TokenPrice},
     AccountId, Address, Token, TokenId, H256, NFT,
 };
 use zksync_utils::big_decimal_to_ratio;
@@ -82,6 +82,13 @@ pub struct DbTickerPrice {
     pub last_updated: DateTime<Utc>,
 }
 
+/// Last price of the token in the interval of the price history starting at `timestamp`.
+#[derive(Debug, Clone, FromRow)]
+pub struct DbTickerPriceHistoryPoint {
+    pub timestamp: DateTime<Utc>,
+    pub usd_price: BigDecimal,
+}
+
 #[derive(Debug, FromRow)]
 pub struct StorageNFT {
     // Unique token id in zksync
@@ -118,6 +125,15 @@ impl From<DbTickerPrice> for TokenPrice {
     }
 }
 
+impl From<DbTickerPriceHistoryPoint> for TokenPrice {
+    fn from(val: DbTickerPriceHistoryPoint) -> Self {
+        Self {
+            usd_price: big_decimal_to_ratio(&val.usd_price).expect("Price could not be negative"),
+            last_updated: val.timestamp,
+        }
+    }
+}
+
 impl From<StorageNFT> for NFT {
     fn from(val: StorageNFT) -> Self {
         Self {
@@ -157,6 +173,7 @@ pub struct DBMarketVolume {
     pub token_id: i32,
     pub market_volume: BigDecimal,
     pub last_updated: DateTime<Utc>,
+    pub fee_allowed: Option<bool>,
 }
 
 impl From<DBMarketVolume> for TokenMarketVolume {
@@ -165,6 +182,24 @@ impl From<DBMarketVolume> for TokenMarketVolume {
             market_volume: big_decimal_to_ratio(&val.market_volume)
                 .expect("Price could not be negative"),
             last_updated: val.last_updated,
+            fee_allowed: val.fee_allowed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbTokenAdminSettings {
+    pub token_id: i32,
+    pub fee_eligible: Option<bool>,
+    pub disabled: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DbTokenAdminSettings> for TokenAdminSettings {
+    fn from(val: DbTokenAdminSettings) -> Self {
+        Self {
+            fee_eligible: val.fee_eligible,
+            disabled: val.disabled,
         }
     }
 }
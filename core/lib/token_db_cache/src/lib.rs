@@ -1,17 +1,118 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use std::{collections::HashMap, sync::Arc};
 
+use thiserror::Error;
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 
-use zksync_storage::StorageProcessor;
-use zksync_types::tokens::TokenMarketVolume;
+use zksync_storage::{listener::StorageListener, tokens::TOKENS_UPDATED_CHANNEL, StorageProcessor};
+use zksync_types::tokens::{TokenAdminSettings, TokenMarketVolume};
 use zksync_types::{Token, TokenId, TokenLike, NFT};
 
+/// Delay before reconnecting the listener of the token updates.
+const LISTENER_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Maximum number of the unknown tokens remembered by the cache, so the requests
+/// for the random tokens can't exhaust the memory.
+const MAX_UNKNOWN_TOKENS: usize = 10_000;
+
+/// Generation of the token caches, incremented every time the tokens are changed via the admin API.
+/// The entries cached in the previous generations are considered stale by all the caches of the process.
+static CACHE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn cache_generation() -> u64 {
+    CACHE_GENERATION.load(Ordering::Acquire)
+}
+
+/// Invalidates the entries of all the token caches of the process.
+pub fn invalidate_token_caches() {
+    CACHE_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// Runs the task invalidating the token caches of the process every time
+/// the tokens are changed via the admin API (possibly by another process).
+pub fn run_token_cache_invalidation_listener() -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            if let Err(err) = listen_token_updates().await {
+                vlog::warn!("Failed to listen for the token updates: {}", err);
+            }
+            tokio::time::sleep(LISTENER_RECONNECT_DELAY).await;
+        }
+    })
+}
+
+async fn listen_token_updates() -> anyhow::Result<()> {
+    let mut listener = StorageListener::connect().await?;
+    listener.listen(TOKENS_UPDATED_CHANNEL).await?;
+    // Updates could happen while the listener was disconnected.
+    invalidate_token_caches();
+
+    loop {
+        match listener.try_recv().await? {
+            Some(notification) => {
+                vlog::info!("Token {} is updated", notification.payload());
+            }
+            None => {
+                // The notifications received while the connection was lost are not recoverable.
+                vlog::warn!("Listener of the token updates is disconnected, reconnecting");
+            }
+        }
+        invalidate_token_caches();
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TokenResolutionError {
+    #[error("Token {0} is not found")]
+    NotFound(TokenLike),
+    #[error(
+        "Symbol {symbol} is shared by the tokens {}, use the token id or address instead",
+        join_token_ids(.token_ids)
+    )]
+    AmbiguousSymbol {
+        symbol: String,
+        token_ids: Vec<TokenId>,
+    },
+    #[error("Failed to load the token: {0}")]
+    Storage(#[from] anyhow::Error),
+}
+
+fn join_token_ids(token_ids: &[TokenId]) -> String {
+    let token_ids: Vec<_> = token_ids.iter().map(ToString::to_string).collect();
+    token_ids.join(", ")
+}
+
+#[derive(Debug, Clone)]
+struct CachedEntry<T> {
+    value: T,
+    update_time: Instant,
+    generation: u64,
+}
+
+impl<T> CachedEntry<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            update_time: Instant::now(),
+            generation: cache_generation(),
+        }
+    }
+
+    fn is_fresh(&self, invalidate_after: Duration) -> bool {
+        self.update_time.elapsed() < invalidate_after && self.generation == cache_generation()
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TokenDBCache {
-    cache: Arc<RwLock<HashMap<TokenLike, (Token, Instant)>>>,
+    cache: Arc<RwLock<HashMap<TokenLike, CachedEntry<Token>>>>,
+    /// Tokens that are not found in the database.
+    unknown_tokens: Arc<RwLock<HashMap<TokenLike, CachedEntry<()>>>>,
     nft_tokens: Arc<RwLock<HashMap<TokenId, NFT>>>,
+    admin_settings: Arc<RwLock<Option<CachedEntry<HashMap<TokenId, TokenAdminSettings>>>>>,
     token_invalidate_cache: Duration,
+    negative_cache_period: Duration,
 }
 
 impl TokenDBCache {
@@ -22,6 +123,35 @@ impl TokenDBCache {
         }
     }
 
+    /// Makes the cache remember the unknown tokens for the given period.
+    /// The tokens added after that are visible right away, since adding the token
+    /// invalidates the token caches.
+    pub fn with_negative_cache(mut self, period: Duration) -> Self {
+        self.negative_cache_period = period;
+        self
+    }
+
+    async fn is_known_as_missing(&self, token_query: &TokenLike) -> bool {
+        match self.unknown_tokens.read().await.get(token_query) {
+            Some(entry) => entry.is_fresh(self.negative_cache_period),
+            None => false,
+        }
+    }
+
+    async fn remember_missing(&self, token_query: TokenLike) {
+        if self.negative_cache_period == Duration::default() {
+            return;
+        }
+        let mut unknown_tokens = self.unknown_tokens.write().await;
+        if unknown_tokens.len() >= MAX_UNKNOWN_TOKENS {
+            unknown_tokens.retain(|_, entry| entry.is_fresh(self.negative_cache_period));
+            if unknown_tokens.len() >= MAX_UNKNOWN_TOKENS {
+                unknown_tokens.clear();
+            }
+        }
+        unknown_tokens.insert(token_query, CachedEntry::new(()));
+    }
+
     /// Version of `get_token` that only attempts to find the token in the cache.
     /// This method should be used in places that don't require the DB connection itself,
     /// so taking a connection from the pool is avoided.
@@ -35,47 +165,112 @@ impl TokenDBCache {
     ) -> Option<Token> {
         let token_query = token_query.into();
         // Just return token from cache.
-        if let Some((token, update_time)) = self.cache.read().await.get(&token_query.to_lowercase())
-        {
-            if update_time.elapsed() < self.token_invalidate_cache {
-                return Some(token.clone());
+        if let Some(entry) = self.cache.read().await.get(&token_query.to_lowercase()) {
+            if entry.is_fresh(self.token_invalidate_cache) {
+                return Some(entry.value.clone());
             }
         }
         None
     }
 
     /// Performs case-insensitive token search.
+    /// Returns an error if the token is requested by the symbol shared by several tokens.
     pub async fn get_token(
         &self,
         storage: &mut StorageProcessor<'_>,
         token_query: impl Into<TokenLike>,
     ) -> anyhow::Result<Option<Token>> {
+        match self.resolve_token(storage, token_query).await {
+            Ok(token) => Ok(Some(token)),
+            Err(TokenResolutionError::NotFound(_)) => Ok(None),
+            Err(TokenResolutionError::Storage(err)) => Err(err),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Finds the token by its id, L1 address or symbol (case-insensitive).
+    /// Symbols are not unique, so the token can only be resolved by the symbol
+    /// if no other token shares it.
+    pub async fn resolve_token(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_query: impl Into<TokenLike>,
+    ) -> Result<Token, TokenResolutionError> {
         let token_query = token_query.into();
         // Just return token from cache.
-        if let Some((token, update_time)) = self.cache.read().await.get(&token_query.to_lowercase())
-        {
-            if update_time.elapsed() < self.token_invalidate_cache {
-                return Ok(Some(token.clone()));
-            }
+        if let Some(token) = self.try_get_token_from_cache(token_query.clone()).await {
+            return Ok(token);
+        }
+        let cache_key = token_query.to_lowercase();
+        if self.is_known_as_missing(&cache_key).await {
+            metrics::increment_counter!("token_db_cache.negative_hit");
+            return Err(TokenResolutionError::NotFound(token_query));
         }
         // Tries to fetch token from the underlying database.
-        let token = {
-            storage
-                .tokens_schema()
-                .get_token(token_query.clone())
-                .await?
+        let token = match &token_query {
+            TokenLike::Symbol(symbol) => {
+                let mut tokens = storage.tokens_schema().get_tokens_by_symbol(symbol).await?;
+                if tokens.len() > 1 {
+                    return Err(TokenResolutionError::AmbiguousSymbol {
+                        symbol: symbol.clone(),
+                        token_ids: tokens.iter().map(|token| token.id).collect(),
+                    });
+                }
+                tokens.pop()
+            }
+            _ => {
+                storage
+                    .tokens_schema()
+                    .get_token(token_query.clone())
+                    .await?
+            }
+        };
+        let token = match token {
+            Some(token) => token,
+            None => {
+                self.remember_missing(cache_key).await;
+                return Err(TokenResolutionError::NotFound(token_query));
+            }
         };
         // Stores received token into the local cache.
-        if let Some(token) = &token {
-            self.cache
-                .write()
-                .await
-                .insert(token_query.to_lowercase(), (token.clone(), Instant::now()));
-        }
+        self.cache
+            .write()
+            .await
+            .insert(cache_key, CachedEntry::new(token.clone()));
 
         Ok(token)
     }
 
+    /// Version of `get_token_admin_settings` that only attempts to find the settings in the cache.
+    pub async fn try_get_token_admin_settings_from_cache(
+        &self,
+        token_id: TokenId,
+    ) -> Option<TokenAdminSettings> {
+        match self.admin_settings.read().await.as_ref() {
+            Some(entry) if entry.is_fresh(self.token_invalidate_cache) => {
+                Some(entry.value.get(&token_id).copied().unwrap_or_default())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the operator overrides of the token properties.
+    /// The settings of all the tokens are cached at once, since there are only a few of them.
+    pub async fn get_token_admin_settings(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_id: TokenId,
+    ) -> anyhow::Result<TokenAdminSettings> {
+        if let Some(settings) = self.try_get_token_admin_settings_from_cache(token_id).await {
+            return Ok(settings);
+        }
+
+        let all_settings = storage.tokens_schema().load_token_admin_settings().await?;
+        let settings = all_settings.get(&token_id).copied().unwrap_or_default();
+        *self.admin_settings.write().await = Some(CachedEntry::new(all_settings));
+        Ok(settings)
+    }
+
     pub async fn token_symbol(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -112,14 +307,23 @@ impl TokenDBCache {
 
     pub async fn fill_token_cache(&mut self, storage: &mut StorageProcessor<'_>) {
         let tokens = Self::get_all_tokens(storage).await.unwrap();
+        let mut symbol_counts: HashMap<String, usize> = HashMap::new();
+        for token in &tokens {
+            *symbol_counts
+                .entry(token.symbol.to_lowercase())
+                .or_default() += 1;
+        }
         let mut cache = self.cache.write().await;
         for token in tokens {
-            let symbol = TokenLike::Symbol(token.symbol.clone());
+            // The shared symbols must not be resolved to any of the tokens.
+            if symbol_counts[&token.symbol.to_lowercase()] == 1 {
+                let symbol = TokenLike::Symbol(token.symbol.clone());
+                cache.insert(symbol.to_lowercase(), CachedEntry::new(token.clone()));
+            }
             let token_id = TokenLike::Id(token.id);
             let address = TokenLike::Address(token.address);
-            cache.insert(symbol.to_lowercase(), (token.clone(), Instant::now()));
-            cache.insert(token_id.to_lowercase(), (token.clone(), Instant::now()));
-            cache.insert(address.to_lowercase(), (token.clone(), Instant::now()));
+            cache.insert(token_id.to_lowercase(), CachedEntry::new(token.clone()));
+            cache.insert(address.to_lowercase(), CachedEntry::new(token.clone()));
         }
     }
 
@@ -169,6 +169,47 @@ fn smt_root_hash_cached(b: &mut Bencher<'_>, size: u32) {
     );
 }
 
+/// Measures the time of recalculating a SMT root hash after the block-like update of the cached
+/// tree: `updates` accounts scattered over the tree are changed, as well as the fee account
+/// which is changed by every block.
+///
+/// Only the paths of the updated accounts are rehashed, so this bench is expected to get much
+/// better results than `smt_root_hash` for the tree of the same size.
+fn smt_root_hash_after_updates(b: &mut Bencher<'_>, size: u32, updates: u32) {
+    let depth = zksync_crypto::params::account_tree_depth();
+
+    // Create a tree, fill it with some accounts and calculate the cache.
+    let mut tree = RealSMT::new(depth);
+    for (id, account) in (0..size).map(gen_account).enumerate() {
+        let id = id as u32;
+        tree.insert(id, account.clone());
+    }
+    tree.root_hash();
+
+    // The updated accounts are scattered over the tree by the stride coprime with its size.
+    let updated_ids: Vec<u32> = (1..=updates)
+        .map(|i| (i as u64 * 7919 % size as u64) as u32)
+        .collect();
+    let fee_account_id = 0;
+
+    let setup = || {
+        let mut tree = tree.clone();
+        for &id in &updated_ids {
+            tree.insert(id, gen_account(id + size));
+        }
+        tree.insert(fee_account_id, gen_account(size));
+        tree
+    };
+
+    b.iter_batched_ref(
+        setup,
+        |tree| {
+            let _hash = black_box(tree.root_hash());
+        },
+        BatchSize::SmallInput,
+    );
+}
+
 /// Measures the time to `drop` a tree with calculated cache.
 fn smt_drop(b: &mut Bencher<'_>, size: u32) {
     let depth = zksync_crypto::params::account_tree_depth();
@@ -217,6 +258,18 @@ pub fn bench_merkle_tree(c: &mut Criterion) {
         c.bench_function(&bench_name, |b| smt_root_hash_cached(b, *tree_size));
     }
 
+    for tree_size in &[1000, 10_000] {
+        for updates in &[1, 10, 100] {
+            let bench_name = format!(
+                "Parallel SMT root hash (after {} updates) / size {}",
+                updates, tree_size
+            );
+            c.bench_function(&bench_name, |b| {
+                smt_root_hash_after_updates(b, *tree_size, *updates)
+            });
+        }
+    }
+
     // Drop benchmarks.
     for tree_size in &[10, 100, 1000, 10_000] {
         let bench_name = format!("Parallel SMT drop / size {}", tree_size);
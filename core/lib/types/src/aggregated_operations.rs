@@ -101,6 +101,10 @@ impl BlocksProofOperation {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlocksExecuteOperation {
     pub blocks: Vec<Block>,
+    /// Whether withdrawals should be completed within the execute transaction. Otherwise the
+    /// withdrawn funds are stored as pending balances on the contract.
+    #[serde(default)]
+    pub complete_withdrawals: bool,
 }
 
 impl BlocksExecuteOperation {
@@ -119,8 +123,7 @@ impl BlocksExecuteOperation {
     }
 
     pub fn get_eth_tx_args(&self) -> Vec<Token> {
-        // Does not make automatic withdrawals in execute operation
-        let complete_withdrawals = Token::Bool(false);
+        let complete_withdrawals = Token::Bool(self.complete_withdrawals);
         vec![
             Token::Array(
                 self.blocks
@@ -132,8 +135,16 @@ impl BlocksExecuteOperation {
         ]
     }
 
+    /// Returns the number of withdrawals processed by the contract during the blocks execution.
+    pub fn withdrawals_count(&self) -> usize {
+        self.blocks
+            .iter()
+            .map(|block| block.processable_ops_pubdata().len())
+            .sum()
+    }
+
     pub fn block_range(&self) -> (BlockNumber, BlockNumber) {
-        let BlocksExecuteOperation { blocks } = self;
+        let BlocksExecuteOperation { blocks, .. } = self;
         (
             blocks.first().map(|b| b.block_number).unwrap_or_default(),
             blocks.last().map(|b| b.block_number).unwrap_or_default(),
@@ -141,7 +152,7 @@ impl BlocksExecuteOperation {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AggregatedActionType {
     CommitBlocks,
     CreateProofBlocks,
@@ -1,7 +1,10 @@
 //! zkSync network block definition.
 
 use super::{AccountId, BlockNumber, Fr, PriorityOp, ZkSyncOp};
-use crate::{tx::error::CloseOperationsDisabled, SignedZkSyncTx, TokenId};
+use crate::{
+    tx::{error::CloseOperationsDisabled, TxFailureCode, TxHash},
+    SignedZkSyncTx, TokenId,
+};
 use chrono::Utc;
 use chrono::{DateTime, TimeZone};
 use parity_crypto::digest::sha256;
@@ -48,6 +51,9 @@ pub struct ExecutedTx {
     pub success: bool,
     pub op: Option<ZkSyncOp>,
     pub fail_reason: Option<String>,
+    /// Machine-readable reason of the failure.
+    #[serde(default)]
+    pub fail_code: Option<TxFailureCode>,
     pub block_index: Option<u32>,
     pub created_at: DateTime<Utc>,
     pub batch_id: Option<i64>,
@@ -489,6 +495,23 @@ impl Block {
             .collect()
     }
 
+    /// Returns hashes of the operations processed by the contract on the block execution
+    /// (i.e. withdrawals), in the same order as `processable_ops_pubdata`.
+    pub fn processable_ops_hashes(&self) -> Vec<TxHash> {
+        self.block_transactions
+            .iter()
+            .filter(|tx| {
+                tx.get_executed_op()
+                    .map(ZkSyncOp::is_processable_onchain_operation)
+                    .unwrap_or(false)
+            })
+            .map(|tx| match tx {
+                ExecutedOperations::Tx(exec_tx) => exec_tx.signed_tx.hash(),
+                ExecutedOperations::PriorityOp(exec_op) => exec_op.priority_op.tx_hash(),
+            })
+            .collect()
+    }
+
     pub fn timestamp_utc(&self) -> DateTime<Utc> {
         Utc.timestamp(self.timestamp as i64, 0)
     }
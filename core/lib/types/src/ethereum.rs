@@ -1,10 +1,11 @@
 //! Common primitives for the Ethereum network interaction.
 // Built-in deps
 // External uses
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 // Local uses
 use crate::aggregated_operations::{AggregatedActionType, AggregatedOperation};
-use zksync_basic_types::{H256, U256};
+use zksync_basic_types::{Address, H256, U256};
 
 /// Numerical identifier of the Ethereum operation.
 pub type EthOpId = i64;
@@ -22,8 +23,18 @@ pub struct ETHOperation {
     pub nonce: U256,
     /// Deadline block of the last sent transaction.
     pub last_deadline_block: u64,
+    /// Deadline block of the first sent transaction, the hard deadline of the
+    /// operation confirmation is counted from it.
+    pub first_deadline_block: u64,
     /// Gas price used in the last sent transaction.
+    /// For the EIP-1559 transaction, it's the max fee per gas.
     pub last_used_gas_price: U256,
+    /// Max priority fee per gas used in the last sent transaction,
+    /// `None` if it was a legacy transaction.
+    pub last_used_priority_fee: Option<U256>,
+    /// Address of the additional operator key the transactions are signed with,
+    /// `None` for the main operator key.
+    pub sender: Option<Address>,
     /// Hashes of all the sent transactions.
     pub used_tx_hashes: Vec<H256>,
     /// Tx payload (not signed).
@@ -60,7 +71,10 @@ impl PartialEq for ETHOperation {
         // for the same operation in different states, so we compare them as well.
         (self.id == other.id)
             && (self.last_deadline_block == other.last_deadline_block)
+            && (self.first_deadline_block == other.first_deadline_block)
             && (self.last_used_gas_price == other.last_used_gas_price)
+            && (self.last_used_priority_fee == other.last_used_priority_fee)
+            && (self.sender == other.sender)
             && (self.used_tx_hashes == other.used_tx_hashes)
             && (self.confirmed == other.confirmed)
             && (self.final_hash == other.final_hash)
@@ -81,3 +95,104 @@ pub struct InsertedOperationResponse {
 #[derive(Debug, Error, PartialEq)]
 #[error("Unknown type of operation: {0}")]
 pub struct UnknownOperationType(pub String);
+
+/// Status of the withdrawal processed by the contract during the blocks execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WithdrawalStatus {
+    /// Withdrawal will be completed by the sent `executeBlocks` transaction.
+    Scheduled,
+    /// `executeBlocks` transaction completing the withdrawal is confirmed.
+    Completed,
+    /// Withdrawn funds are left on the contract as a pending balance, and have to be
+    /// withdrawn by a separate `withdrawPendingBalance` call.
+    Deferred,
+}
+
+impl WithdrawalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Scheduled => "scheduled",
+            Self::Completed => "completed",
+            Self::Deferred => "deferred",
+        }
+    }
+}
+
+impl std::str::FromStr for WithdrawalStatus {
+    type Err = UnknownWithdrawalStatus;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scheduled" => Ok(Self::Scheduled),
+            "completed" => Ok(Self::Completed),
+            "deferred" => Ok(Self::Deferred),
+            _ => Err(UnknownWithdrawalStatus(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Unknown withdrawal status: {0}")]
+pub struct UnknownWithdrawalStatus(pub String);
+
+/// Outcome of the attempt to sign the transaction with the operator key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperatorKeyUsageOutcome {
+    /// Transaction was signed.
+    Signed,
+    /// Transaction violates the signing policy, the key was not used.
+    Rejected,
+    /// Signer failed to sign the transaction.
+    Failed,
+}
+
+impl OperatorKeyUsageOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Signed => "signed",
+            Self::Rejected => "rejected",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for OperatorKeyUsageOutcome {
+    type Err = UnknownOperatorKeyUsageOutcome;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "signed" => Ok(Self::Signed),
+            "rejected" => Ok(Self::Rejected),
+            "failed" => Ok(Self::Failed),
+            _ => Err(UnknownOperatorKeyUsageOutcome(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Unknown operator key usage outcome: {0}")]
+pub struct UnknownOperatorKeyUsageOutcome(pub String);
+
+/// Entry of the operator key audit log, describes a single attempt to sign
+/// the Ethereum transaction with the operator key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorKeyUsage {
+    /// Ethereum operation the transaction was signed for.
+    pub eth_op_id: EthOpId,
+    pub op_type: AggregatedActionType,
+    /// Recipient of the transaction.
+    pub contract_addr: Address,
+    /// Keccak256 hash of the transaction calldata.
+    pub payload_hash: H256,
+    pub nonce: U256,
+    pub gas_limit: U256,
+    pub gas_price: U256,
+    pub value: U256,
+    /// Hash of the signed transaction, set only for the `Signed` outcome.
+    pub tx_hash: Option<H256>,
+    pub outcome: OperatorKeyUsageOutcome,
+    /// Reason of the rejection or failure.
+    pub reason: Option<String>,
+}
@@ -6,7 +6,9 @@ use serde::{Deserialize, Serialize};
 // Workspace uses
 // Local uses
 use super::account::AccountStateChangeStatus;
-use crate::{block::ExecutedOperations, AccountId, BlockNumber, TokenId};
+use crate::{
+    block::ExecutedOperations, tx::TxFailureCode, AccountId, BlockNumber, TokenId, ZkSyncTx,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -42,6 +44,8 @@ pub struct TransactionEvent {
     pub tx: serde_json::Value,
     pub status: TransactionStatus,
     pub fail_reason: Option<String>,
+    /// Machine-readable reason of the failure, set for the transactions rejected in the block.
+    pub fail_code: Option<TxFailureCode>,
     pub created_at: DateTime<Utc>,
     /// This field is lazily initialized and only used for filtering.
     /// Since the event is shared among server worker threads, it has
@@ -72,6 +76,7 @@ impl TransactionEvent {
                     TransactionStatus::Rejected
                 },
                 fail_reason: exec_tx.fail_reason.clone(),
+                fail_code: exec_tx.fail_code,
                 created_at: exec_tx.created_at,
                 tx_type: OnceCell::default(),
             },
@@ -83,12 +88,30 @@ impl TransactionEvent {
                 tx: serde_json::to_value(&exec_prior_op.op).unwrap(),
                 status,
                 fail_reason: None,
+                fail_code: None,
                 created_at: exec_prior_op.created_at,
                 tx_type: OnceCell::default(),
             },
         })
     }
 
+    /// Creates the event of the transaction rejected before getting into the mempool.
+    /// `block_number` is the last committed block at the moment of rejection.
+    pub fn rejected(tx: &ZkSyncTx, block_number: BlockNumber, fail_reason: String) -> Option<Self> {
+        Some(Self {
+            tx_hash: tx.hash().to_string(),
+            account_id: tx.account_id().ok()?, // Close events cannot be emitted.
+            token_id: tx.token_id(),
+            block_number,
+            tx: serde_json::to_value(tx).unwrap(),
+            status: TransactionStatus::Rejected,
+            fail_reason: Some(fail_reason),
+            fail_code: None,
+            created_at: Utc::now(),
+            tx_type: OnceCell::default(),
+        })
+    }
+
     pub fn tx_type(&self) -> TransactionType {
         *self
             .tx_type
@@ -268,6 +268,18 @@ impl GasCounter {
         Self::scale_up(approx_limit)
     }
 
+    /// Same as `execute_gas_limit_aggregated`, but also accounts the withdrawals completed
+    /// within the execute transaction.
+    pub fn execute_gas_limit_with_withdrawals(blocks: &[Block], withdrawals_count: usize) -> U256 {
+        let approx_limit = U256::from(Self::BASE_EXECUTE_BLOCKS_TX_COST)
+            + blocks
+                .iter()
+                .fold(U256::zero(), |acc, block| acc + block.verify_gas_limit)
+            + U256::from(withdrawals_count) * U256::from(Self::COMPLETE_WITHDRAWALS_ERC20_COST);
+
+        Self::scale_up(approx_limit)
+    }
+
     /// Increases the value by 30%.
     fn scale_up(value: U256) -> U256 {
         value * U256::from(130) / U256::from(100)
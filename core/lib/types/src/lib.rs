@@ -49,6 +49,7 @@ pub mod mempool;
 pub mod network;
 pub mod operations;
 pub mod priority_ops;
+pub mod protocol;
 pub mod prover;
 pub mod register_factory;
 pub mod tokens;
@@ -0,0 +1,28 @@
+//! Protocol parameters which may be changed during the lifetime of the network.
+//!
+//! The blocks are validated against the parameters in force at the moment they were created,
+//! so every change is stored together with the number of the first block it's applied to.
+
+// External uses
+use serde::{Deserialize, Serialize};
+// Workspace uses
+use zksync_basic_types::BlockNumber;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolParameters {
+    /// Sizes of the blocks (in chunks) that can be created.
+    pub block_chunk_sizes: Vec<usize>,
+    /// The maximum amount of withdrawals completed by a single `executeBlocks` transaction.
+    pub max_withdrawals_per_execute_tx: u64,
+}
+
+/// Protocol parameters in force starting from the specified block.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolParametersUpdate {
+    /// The first block created with these parameters.
+    pub from_block: BlockNumber,
+    #[serde(flatten)]
+    pub parameters: ProtocolParameters,
+}
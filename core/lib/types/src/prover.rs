@@ -1,7 +1,12 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zksync_basic_types::BlockNumber;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum ProverJobStatus {
     Idle = 0,
     InProgress = 1,
@@ -30,6 +35,16 @@ impl ProverJobStatus {
 pub const SINGLE_PROOF_JOB_PRIORITY: i32 = 1;
 pub const AGGREGATED_PROOF_JOB_PRIORITY: i32 = 0;
 
+/// Rules of raising the priority of the idle prover jobs, so a backlog doesn't delay the oldest
+/// blocks the longest. Every rule is disabled if zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProverJobAging {
+    /// The job gains one priority level per this interval of waiting in the queue.
+    pub interval: Duration,
+    /// The jobs of the blocks with the priority operations older than this are given out first.
+    pub priority_op_threshold: Duration,
+}
+
 #[derive(Debug, Clone)]
 pub struct ProverJob {
     pub job_id: i32,
@@ -54,6 +69,36 @@ impl ProverJob {
     }
 }
 
+/// Idle job in the queue with the properties matched against the prover capabilities.
+#[derive(Debug, Clone)]
+pub struct IdleProverJob {
+    pub job_id: i32,
+    pub job_type: ProverJobType,
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    /// Size of the block of the single proof job.
+    pub block_size: Option<usize>,
+}
+
+/// Assignment of the unfinished prover job to the prover.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProverJobAssignment {
+    pub job_id: i32,
+    pub job_type: String,
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    pub status: ProverJobStatus,
+    /// Prover holding the lease of the job in progress.
+    pub assigned_prover: Option<String>,
+    /// The job is returned to the queue unless the prover renews the lease before this time.
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    /// Number of times the job was returned to the queue after its lease expired.
+    pub reassignments: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub enum ProverJobType {
     SingleProof,
@@ -72,3 +117,185 @@ impl ToString for ProverJobType {
 #[derive(Debug, Error, PartialEq)]
 #[error("Incorrect ProverJobStatus number: {0}")]
 pub struct IncorrectProverJobStatus(pub i32);
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Incorrect prover capability tag: {0}")]
+pub struct InvalidProverCapabilityTag(pub String);
+
+/// Jobs the prover is capable of, parsed from the capability tags the prover sends with
+/// the job requests. The jobs are given out only to the capable provers.
+///
+/// The tags are:
+/// - `single_proof`, `aggregated_proof`: the types of the jobs, any type if there is neither;
+/// - `block_size=<chunks>`: the block size of the single proofs, any size if there is none;
+/// - `max_aggregated_blocks=<number>`: the max number of blocks in the aggregated proof.
+///
+/// The other tags, e.g. `gpu` or `cpu` labelling the fleets of the provers, are ignored, so the
+/// fleets are split by the jobs they're tagged with, e.g. only the GPU provers are tagged with
+/// `aggregated_proof`. The malformed values of the tags above are rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProverCapabilities {
+    pub single_proofs: bool,
+    pub aggregated_proofs: bool,
+    /// Empty if the blocks of any size are supported.
+    pub block_sizes: Vec<usize>,
+    pub max_aggregated_blocks: Option<usize>,
+}
+
+impl Default for ProverCapabilities {
+    /// The prover without the tags is capable of any job.
+    fn default() -> Self {
+        Self {
+            single_proofs: true,
+            aggregated_proofs: true,
+            block_sizes: Vec::new(),
+            max_aggregated_blocks: None,
+        }
+    }
+}
+
+impl ProverCapabilities {
+    /// Parses the tags, the unknown tags are ignored.
+    pub fn from_tags<T: AsRef<str>>(tags: &[T]) -> Result<Self, InvalidProverCapabilityTag> {
+        let mut capabilities = Self::default();
+        let (mut single_proofs, mut aggregated_proofs) = (false, false);
+        for tag in tags.iter().map(|tag| tag.as_ref().trim()) {
+            let invalid_tag = || InvalidProverCapabilityTag(tag.to_owned());
+            match tag.split_once('=') {
+                // Empty list of the tags in the config comes as an empty tag.
+                None if tag.is_empty() => {}
+                None if tag == "single_proof" => single_proofs = true,
+                None if tag == "aggregated_proof" => aggregated_proofs = true,
+                Some(("block_size", size)) => capabilities
+                    .block_sizes
+                    .push(size.parse().map_err(|_| invalid_tag())?),
+                Some(("max_aggregated_blocks", number)) => {
+                    capabilities.max_aggregated_blocks =
+                        Some(number.parse().map_err(|_| invalid_tag())?);
+                }
+                None if tag == "block_size" || tag == "max_aggregated_blocks" => {
+                    return Err(invalid_tag())
+                }
+                _ => {}
+            }
+        }
+
+        if single_proofs || aggregated_proofs {
+            capabilities.single_proofs = single_proofs;
+            capabilities.aggregated_proofs = aggregated_proofs;
+        }
+        Ok(capabilities)
+    }
+
+    /// Whether the job can be given out to the prover, matches the job queue filter
+    /// of the storage.
+    pub fn can_prove(&self, job: &IdleProverJob) -> bool {
+        match job.job_type {
+            ProverJobType::SingleProof => {
+                self.single_proofs
+                    && (self.block_sizes.is_empty()
+                        || job
+                            .block_size
+                            .map_or(false, |size| self.block_sizes.contains(&size)))
+            }
+            ProverJobType::AggregatedProof => {
+                let blocks = (*job.last_block - *job.first_block + 1) as usize;
+                self.aggregated_proofs
+                    && self
+                        .max_aggregated_blocks
+                        .map_or(true, |max| max == 0 || blocks <= max)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prover_capabilities_from_tags() {
+        let no_tags: [&str; 0] = [];
+        assert_eq!(
+            ProverCapabilities::from_tags(&no_tags),
+            Ok(ProverCapabilities::default())
+        );
+        assert_eq!(
+            ProverCapabilities::from_tags(&[""]),
+            Ok(ProverCapabilities::default())
+        );
+
+        let capabilities =
+            ProverCapabilities::from_tags(&["single_proof", "block_size=26", "block_size=78"])
+                .unwrap();
+        assert_eq!(
+            capabilities,
+            ProverCapabilities {
+                single_proofs: true,
+                aggregated_proofs: false,
+                block_sizes: vec![26, 78],
+                max_aggregated_blocks: None,
+            }
+        );
+
+        let capabilities = ProverCapabilities::from_tags(&["max_aggregated_blocks=5"]).unwrap();
+        assert!(capabilities.single_proofs && capabilities.aggregated_proofs);
+        assert_eq!(capabilities.max_aggregated_blocks, Some(5));
+
+        // The unknown tags label the fleets of the provers and don't limit the jobs.
+        let capabilities = ProverCapabilities::from_tags(&["gpu", "single_proof", "zone=eu"]);
+        assert_eq!(
+            capabilities.map(|capabilities| capabilities.aggregated_proofs),
+            Ok(false)
+        );
+        assert_eq!(
+            ProverCapabilities::from_tags(&["cpu"]),
+            Ok(ProverCapabilities::default())
+        );
+
+        for tag in [
+            "block_size=large",
+            "block_size",
+            "max_aggregated_blocks=many",
+        ] {
+            assert_eq!(
+                ProverCapabilities::from_tags(&[tag]),
+                Err(InvalidProverCapabilityTag(tag.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn prover_capabilities_match_jobs() {
+        let single_proof = IdleProverJob {
+            job_id: 1,
+            job_type: ProverJobType::SingleProof,
+            first_block: BlockNumber(1),
+            last_block: BlockNumber(1),
+            block_size: Some(26),
+        };
+        let aggregated_proof = IdleProverJob {
+            job_id: 2,
+            job_type: ProverJobType::AggregatedProof,
+            first_block: BlockNumber(1),
+            last_block: BlockNumber(5),
+            block_size: None,
+        };
+
+        let capabilities = ProverCapabilities::default();
+        assert!(capabilities.can_prove(&single_proof));
+        assert!(capabilities.can_prove(&aggregated_proof));
+
+        let capabilities =
+            ProverCapabilities::from_tags(&["single_proof", "block_size=78"]).unwrap();
+        assert!(!capabilities.can_prove(&single_proof));
+        assert!(!capabilities.can_prove(&aggregated_proof));
+        let capabilities = ProverCapabilities::from_tags(&["block_size=26"]).unwrap();
+        assert!(capabilities.can_prove(&single_proof));
+
+        let capabilities = ProverCapabilities::from_tags(&["max_aggregated_blocks=4"]).unwrap();
+        assert!(!capabilities.can_prove(&aggregated_proof));
+        let capabilities = ProverCapabilities::from_tags(&["max_aggregated_blocks=5"]).unwrap();
+        assert!(capabilities.can_prove(&aggregated_proof));
+    }
+}
@@ -0,0 +1,572 @@
+//! Tests validating the serialization formats against the golden files stored in `etc/test_config/golden`.
+//!
+//! Every case is built from the fixed inputs below, so the golden files are only used as the
+//! expected output and never as the input of the encoding. Covered formats are the public data of every operation, the bytes signed by the zkSync key,
+//! the messages signed by the Ethereum key and the JSON representation of the transactions
+//! and priority operations. Any change of them either forks the chain or breaks the SDKs.
+//!
+//! If the format was changed intentionally, regenerate the golden files by running the tests
+//! with the `ZKSYNC_UPDATE_GOLDEN=1` environment variable, review the diff and be sure to notify
+//! the team about the changes introduced!
+
+use num::BigUint;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    env,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+
+use crate::{
+    account::PubKeyHash,
+    operations::{
+        ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, MintNFTOp, NoopOp, SwapOp, TransferOp,
+        TransferToNewOp, WithdrawNFTOp, WithdrawOp,
+    },
+    priority_ops::{Deposit, FullExit},
+    tx::{
+        ChangePubKey, ForcedExit, MintNFT, Order, PackedEthSignature, Swap, TimeRange, Transfer,
+        TxSignature, Withdraw, WithdrawNFT,
+    },
+    AccountId, Address, Nonce, Token, TokenId, TokenKind, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx,
+    H256,
+};
+
+const UPDATE_GOLDEN_ENV: &str = "ZKSYNC_UPDATE_GOLDEN";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TxCase {
+    name: String,
+    tx: Value,
+    fee_token: FeeToken,
+    sign_bytes: String,
+    hash: String,
+    eth_sign_message: Option<String>,
+    /// Data signed by the Ethereum key, only present for the `ChangePubKey` transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    eth_signed_data: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct FeeToken {
+    symbol: String,
+    decimals: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct PubdataCase {
+    name: String,
+    pubdata: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct PriorityOpCase {
+    name: String,
+    op: Value,
+}
+
+fn golden_file_path(file: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../../etc/test_config/golden")
+        .join(file)
+}
+
+fn load_golden_file<T: DeserializeOwned>(file: &str) -> Vec<T> {
+    let path = golden_file_path(file);
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("Cannot read the golden file {}: {}", path.display(), err));
+    serde_json::from_str(&content)
+        .unwrap_or_else(|err| panic!("Cannot parse the golden file {}: {}", path.display(), err))
+}
+
+/// Compares the cases computed by the current code with the ones stored in the golden file,
+/// or overwrites the golden file if the `ZKSYNC_UPDATE_GOLDEN` variable is set.
+fn check_golden_file<T: Debug + PartialEq + Serialize>(file: &str, expected: &[T], actual: &[T]) {
+    if env::var(UPDATE_GOLDEN_ENV).is_ok() {
+        let mut content = serde_json::to_string_pretty(actual).unwrap();
+        content.push('\n');
+        fs::write(golden_file_path(file), content).expect("Cannot update the golden file");
+        return;
+    }
+
+    assert_eq!(
+        expected.len(),
+        actual.len(),
+        "Number of cases doesn't match the golden file {}, run the tests with {}=1 to update it if the change is intended",
+        file,
+        UPDATE_GOLDEN_ENV
+    );
+    for (expected, actual) in expected.iter().zip(actual) {
+        assert_eq!(
+            expected, actual,
+            "Serialization doesn't match the golden file {}, run the tests with {}=1 to update it if the change is intended",
+            file, UPDATE_GOLDEN_ENV
+        );
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn address(hex: &str) -> Address {
+    Address::from_str(hex).unwrap()
+}
+
+/// Signature of the transactions, it isn't a part of the signed bytes so the same key is used for all of them.
+fn signature(signature: &str) -> Option<TxSignature> {
+    let signature = json!({
+        "pubKey": "40771354dc314593e071eaf4d0f42ccb1fad6c7006c57464feeb7ab5872b7490",
+        "signature": signature,
+    });
+    Some(serde_json::from_value(signature).unwrap())
+}
+
+fn fee_token(symbol: &str, decimals: u8) -> FeeToken {
+    FeeToken {
+        symbol: symbol.to_string(),
+        decimals,
+    }
+}
+
+fn transaction_cases() -> Vec<(&'static str, ZkSyncTx, FeeToken)> {
+    let sender = address("ede35562d3555e61120a151b3c8e8e91d83a378a");
+    let recipient = address("19aa2ed8712072e918632259780e587698ef58df");
+    let time_range = TimeRange::new(0, u32::MAX as u64);
+
+    let swap_orders = (
+        Order {
+            account_id: AccountId(6),
+            recipient_address: address("823b6a996cea19e0c41e250b20e2e804ea72ccdf"),
+            nonce: Nonce(18),
+            token_buy: TokenId(2),
+            token_sell: TokenId(1),
+            price: (BigUint::from(1u8), BigUint::from(2u8)),
+            amount: BigUint::from(1000000u32),
+            time_range,
+            signature: signature("b76c83011ea9e14cf679d35b9a7084832a78bf3f975c5b5c3315f80993c227afb7a1cd7e7b8fc225a48d8c9be78335736115890df5bbacfc52ecf47b4e089500").unwrap(),
+        },
+        Order {
+            account_id: AccountId(44),
+            recipient_address: address("63adbb48d1bc2cf54562910ce54b7ca06b87f319"),
+            nonce: Nonce(101),
+            token_buy: TokenId(1),
+            token_sell: TokenId(2),
+            price: (BigUint::from(3u8), BigUint::from(1u8)),
+            amount: BigUint::from(2500000u32),
+            time_range,
+            signature: signature("b76c83011ea9e14cf679d35b9a7084832a78bf3f975c5b5c3315f80993c227afb7a1cd7e7b8fc225a48d8c9be78335736115890df5bbacfc52ecf47b4e089500").unwrap(),
+        },
+    );
+
+    vec![
+        (
+            "transfer",
+            Transfer::new(
+                AccountId(44),
+                sender,
+                recipient,
+                TokenId(0),
+                BigUint::from(1000000000000u64),
+                BigUint::from(1000000u32),
+                Nonce(12),
+                time_range,
+                signature("b3211c7e15d31d64619e0c7f65fce8c6e45637b5cfc8711478c5a151e6568d875ec7f48e040225fe3cc7f1e7294625cad6d98b4595d007d36ef62122de16ae01"),
+            )
+            .into(),
+            fee_token("ETH", 0),
+        ),
+        (
+            "withdraw",
+            Withdraw::new(
+                AccountId(44),
+                sender,
+                recipient,
+                TokenId(0),
+                BigUint::from(1000000000000u64),
+                BigUint::from(1000000u32),
+                Nonce(12),
+                time_range,
+                signature("11dc47fced9e6ffabe33112a4280c02d0c1ffa649ba3843eec256d427b90ed82e495c0cee2138d5a9e20328d31cb97b70d7e2ede0d8d967678803f4b5896f701"),
+            )
+            .into(),
+            fee_token("ETH", 0),
+        ),
+        (
+            "change_pubkey_onchain",
+            ChangePubKey::new(
+                AccountId(55),
+                sender,
+                PubKeyHash::from_hex("sync:18e8446d7748f2de52b28345bdbc76160e6b35eb").unwrap(),
+                TokenId(0),
+                BigUint::from(1000000000u32),
+                Nonce(13),
+                time_range,
+                signature("85782959384c1728192b0fe9466a4273b6d0e78e913eea894b780e0236fc4c9d673d3833e895bce992fc113a4d16bba47ef73fed9c4fca2af09ed06cd6885802"),
+                None,
+            )
+            .into(),
+            fee_token("ETH", 0),
+        ),
+        (
+            "forced_exit",
+            ForcedExit::new(
+                AccountId(44),
+                recipient,
+                TokenId(0),
+                BigUint::from(1000000u32),
+                Nonce(12),
+                time_range,
+                signature("b1b82f7ac37e2d4bd675e4a5cd5e48d9fad1739282db8a979c3e4d9e39d794915667ee2c125ba24f4fe81ad6d19491eef0be849a823ea6567517b7e207214705"),
+            )
+            .into(),
+            fee_token("ETH", 0),
+        ),
+        (
+            "mint_nft",
+            MintNFT::new(
+                AccountId(44),
+                sender,
+                H256::from_low_u64_be(0x123),
+                recipient,
+                BigUint::from(1000000u32),
+                TokenId(0),
+                Nonce(12),
+                signature("5cf4ef4680d58e23ede08cc2f8dd33123c339788721e307a813cdf82bc0bac1c10bc861c68d0b5328e4cb87b610e4dfdc13ddf8a444a4a2ac374ac3c73dbec05"),
+            )
+            .into(),
+            fee_token("ETH", 0),
+        ),
+        (
+            "withdraw_nft",
+            WithdrawNFT::new(
+                AccountId(44),
+                sender,
+                recipient,
+                TokenId(100000),
+                TokenId(0),
+                BigUint::from(1000000u32),
+                Nonce(12),
+                time_range,
+                signature("1236180fe01b42c0c3c084d152b0582e714fa19da85900777e811f484a5b3ea434af320f66c7c657a33024d7be22cea44b7406d0af88c097a9d7d6b5d7154d02"),
+            )
+            .into(),
+            fee_token("ETH", 0),
+        ),
+        (
+            "swap",
+            Swap::new(
+                AccountId(5),
+                sender,
+                Nonce(1),
+                swap_orders,
+                (BigUint::from(1000000u32), BigUint::from(2500000u32)),
+                BigUint::from(123u32),
+                TokenId(3),
+                signature("c13aabacf96448efb47763554753bfe2acc303a8297c8af59e718d685d422a901a43c42448f95cca632821df1ccb754950196e8444c0acef253c42c1578b5401"),
+            )
+            .into(),
+            fee_token("USDT", 1),
+        ),
+    ]
+}
+
+fn pubdata_cases() -> Vec<(&'static str, ZkSyncOp)> {
+    let alice = address("2a0a81e257a2f5d6ed4f07b81dbda09f107bd026");
+    let bob = address("21abaed8712072e918632259780e587698ef58da");
+    let amount = BigUint::from(42u32);
+
+    let transfer = Transfer::new(
+        AccountId(42),
+        alice,
+        bob,
+        TokenId(42),
+        amount.clone(),
+        amount.clone(),
+        Nonce(42),
+        Default::default(),
+        None,
+    );
+    let swap_order =
+        |account_id, nonce, token_buy, token_sell, amount: u32, price: (u8, u8)| Order {
+            account_id: AccountId(account_id),
+            recipient_address: Address::zero(),
+            nonce: Nonce(nonce),
+            token_buy: TokenId(token_buy),
+            token_sell: TokenId(token_sell),
+            price: (BigUint::from(price.0), BigUint::from(price.1)),
+            amount: BigUint::from(amount),
+            time_range: TimeRange::new(0, 1 << 31),
+            signature: Default::default(),
+        };
+    let eth_signature = PackedEthSignature::deserialize_packed(
+        &hex::decode("2a0a81e257a2f5d6ed4f07b81dbda09f107bd026dbda09f107bd026f5d6ed4f02a0a81e257a2f5d6ed4f07b81dbda09f107bd026dbda09f107bd026f5d6ed4f0d4").unwrap(),
+    )
+    .unwrap();
+
+    vec![
+        ("noop", NoopOp {}.into()),
+        (
+            "deposit",
+            DepositOp {
+                priority_op: Deposit {
+                    from: alice,
+                    to: bob,
+                    token: TokenId(42),
+                    amount: amount.clone(),
+                },
+                account_id: AccountId(42),
+            }
+            .into(),
+        ),
+        (
+            "transfer_to_new",
+            TransferToNewOp {
+                tx: transfer.clone(),
+                from: AccountId(1),
+                to: AccountId(2),
+            }
+            .into(),
+        ),
+        (
+            "withdraw",
+            WithdrawOp {
+                tx: Withdraw::new(
+                    AccountId(42),
+                    alice,
+                    bob,
+                    TokenId(42),
+                    amount.clone(),
+                    amount.clone(),
+                    Nonce(42),
+                    Default::default(),
+                    None,
+                ),
+                account_id: AccountId(42),
+            }
+            .into(),
+        ),
+        (
+            "transfer",
+            TransferOp {
+                tx: transfer,
+                from: AccountId(1),
+                to: AccountId(2),
+            }
+            .into(),
+        ),
+        (
+            "full_exit",
+            FullExitOp {
+                priority_op: FullExit {
+                    account_id: AccountId(42),
+                    eth_address: alice,
+                    token: TokenId(42),
+                    is_legacy: false,
+                },
+                withdraw_amount: None,
+                creator_account_id: None,
+                creator_address: None,
+                serial_id: None,
+                content_hash: None,
+            }
+            .into(),
+        ),
+        (
+            "change_pubkey",
+            ChangePubKeyOp {
+                tx: ChangePubKey::new(
+                    AccountId(42),
+                    alice,
+                    PubKeyHash::from_hex("sync:3cfb9a39096d9e02b24187355f628f9a6331511b").unwrap(),
+                    TokenId(42),
+                    amount.clone(),
+                    Nonce(42),
+                    Default::default(),
+                    None,
+                    Some(eth_signature),
+                ),
+                account_id: AccountId(42),
+            }
+            .into(),
+        ),
+        (
+            "forced_exit",
+            ForcedExitOp {
+                tx: ForcedExit::new(
+                    AccountId(42),
+                    alice,
+                    TokenId(42),
+                    amount.clone(),
+                    Nonce(42),
+                    Default::default(),
+                    None,
+                ),
+                target_account_id: AccountId(42),
+                withdraw_amount: None,
+            }
+            .into(),
+        ),
+        (
+            "swap",
+            SwapOp {
+                tx: Swap::new(
+                    AccountId(42),
+                    Address::zero(),
+                    Nonce(43),
+                    (
+                        swap_order(5, 123, 1, 7, 0, (1, 2)),
+                        swap_order(7, 100, 7, 1, 12345, (2, 1)),
+                    ),
+                    (BigUint::from(145u32), BigUint::from(217u32)),
+                    BigUint::from(44u32),
+                    TokenId(45),
+                    None,
+                ),
+                submitter: AccountId(42),
+                accounts: (AccountId(5), AccountId(7)),
+                recipients: (AccountId(6), AccountId(8)),
+            }
+            .into(),
+        ),
+        (
+            "mint_nft",
+            MintNFTOp {
+                tx: MintNFT::new(
+                    AccountId(10),
+                    Address::zero(),
+                    H256::zero(),
+                    Address::zero(),
+                    BigUint::from(10u32),
+                    TokenId(0),
+                    Nonce(0),
+                    None,
+                ),
+                creator_account_id: AccountId(10),
+                recipient_account_id: AccountId(11),
+            }
+            .into(),
+        ),
+        (
+            "withdraw_nft",
+            WithdrawNFTOp {
+                tx: WithdrawNFT::new(
+                    AccountId(42),
+                    alice,
+                    bob,
+                    TokenId(MIN_NFT_TOKEN_ID),
+                    TokenId(42),
+                    amount,
+                    Nonce(42),
+                    Default::default(),
+                    None,
+                ),
+                creator_id: AccountId(43),
+                creator_address: bob,
+                content_hash: H256::zero(),
+                serial_id: 0,
+            }
+            .into(),
+        ),
+    ]
+}
+
+fn priority_operation_cases() -> Vec<(&'static str, ZkSyncPriorityOp)> {
+    let alice = address("2a0a81e257a2f5d6ed4f07b81dbda09f107bd026");
+
+    vec![
+        (
+            "deposit",
+            ZkSyncPriorityOp::Deposit(Deposit {
+                from: alice,
+                to: address("21abaed8712072e918632259780e587698ef58da"),
+                token: TokenId(42),
+                amount: BigUint::from(42u32),
+            }),
+        ),
+        (
+            "full_exit",
+            ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: AccountId(42),
+                eth_address: alice,
+                token: TokenId(42),
+                is_legacy: false,
+            }),
+        ),
+    ]
+}
+
+#[test]
+fn transactions_golden_file() {
+    const FILE: &str = "transactions.json";
+
+    let actual = transaction_cases()
+        .into_iter()
+        .map(|(name, tx, fee_token)| {
+            let token = Token::new(
+                TokenId(0),
+                Address::zero(),
+                &fee_token.symbol,
+                fee_token.decimals,
+                TokenKind::ERC20,
+            );
+            assert!(
+                tx.signature().verify(&tx.get_bytes()).is_some(),
+                "Signature of {} doesn't match the signed bytes",
+                name
+            );
+
+            TxCase {
+                name: name.to_string(),
+                tx: serde_json::to_value(&tx).unwrap(),
+                fee_token,
+                sign_bytes: encode_hex(&tx.get_bytes()),
+                hash: tx.hash().to_string(),
+                eth_sign_message: tx.get_ethereum_sign_message(token),
+                eth_signed_data: match &tx {
+                    ZkSyncTx::ChangePubKey(tx) => {
+                        Some(encode_hex(&tx.get_eth_signed_data().unwrap()))
+                    }
+                    _ => None,
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    check_golden_file(FILE, &load_golden_file::<TxCase>(FILE), &actual);
+}
+
+#[test]
+fn pubdata_golden_file() {
+    const FILE: &str = "pubdata.json";
+
+    let actual = pubdata_cases()
+        .into_iter()
+        .map(|(name, op)| PubdataCase {
+            name: name.to_string(),
+            pubdata: encode_hex(&op.public_data()),
+        })
+        .collect::<Vec<_>>();
+
+    check_golden_file(FILE, &load_golden_file::<PubdataCase>(FILE), &actual);
+}
+
+#[test]
+fn priority_operations_golden_file() {
+    const FILE: &str = "priority_operations.json";
+
+    let actual = priority_operation_cases()
+        .into_iter()
+        .map(|(name, op)| PriorityOpCase {
+            name: name.to_string(),
+            op: serde_json::to_value(&op).unwrap(),
+        })
+        .collect::<Vec<_>>();
+
+    check_golden_file(FILE, &load_golden_file::<PriorityOpCase>(FILE), &actual);
+}
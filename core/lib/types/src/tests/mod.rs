@@ -1,3 +1,4 @@
 mod block;
+mod golden;
 mod hardcoded;
 pub mod utils;
@@ -1,7 +1,13 @@
 use chrono::{DateTime, Utc};
 use num::{rational::Ratio, BigUint};
-use serde::{Deserialize, Serialize};
-use std::{convert::TryFrom, fmt, fs::read_to_string, path::PathBuf, str::FromStr};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    convert::{Infallible, TryFrom},
+    fmt,
+    fs::read_to_string,
+    path::PathBuf,
+    str::FromStr,
+};
 use thiserror::Error;
 
 /// ID of the ETH token in zkSync network.
@@ -18,7 +24,10 @@ pub enum NewTokenEventParseError {
 
 // Order of the fields is important (from more specific types to less specific types)
 /// Set of values that can be interpreted as a token descriptor.
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+///
+/// The strings are deserialized the same way as they are parsed by `TokenLike::parse`,
+/// so e.g. `"1"` is the token ID both in the JSON body and in the URL path.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash)]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum TokenLike {
     /// ID of the token in the zkSync network.
@@ -63,6 +72,30 @@ impl fmt::Display for TokenLike {
     }
 }
 
+impl FromStr for TokenLike {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(value))
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenLike {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawTokenLike {
+            Id(TokenId),
+            String(String),
+        }
+
+        Ok(match RawTokenLike::deserialize(deserializer)? {
+            RawTokenLike::Id(id) => Self::Id(id),
+            RawTokenLike::String(value) => Self::parse(&value),
+        })
+    }
+}
+
 impl TokenLike {
     pub fn parse(value: &str) -> Self {
         // Try to interpret an address as the token ID.
@@ -239,6 +272,19 @@ pub struct TokenMarketVolume {
     #[serde(with = "UnsignedRatioSerializeAsDecimal")]
     pub market_volume: Ratio<BigUint>,
     pub last_updated: DateTime<Utc>,
+    /// Whether the token passed the last liquidity check, `None` if it's not checked yet.
+    #[serde(default)]
+    pub fee_allowed: Option<bool>,
+}
+
+/// Overrides of the token properties set by the operator.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenAdminSettings {
+    /// Forces the token to be (in)eligible for paying fees regardless of its market volume.
+    pub fee_eligible: Option<bool>,
+    /// Disabled tokens can only be withdrawn from zkSync.
+    pub disabled: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Hash, Eq)]
@@ -330,6 +376,30 @@ pub enum GetGenesisTokenListError {
 mod tests {
     use super::*;
 
+    #[test]
+    fn token_like_deserialize() {
+        let address: Address = "0x6b175474e89094c44da98b954eedeac495271d0f"
+            .parse()
+            .unwrap();
+        let cases = vec![
+            (r#"1"#, TokenLike::Id(TokenId(1))),
+            (r#""1""#, TokenLike::Id(TokenId(1))),
+            (
+                r#""0x6b175474e89094c44da98b954eedeac495271d0f""#,
+                TokenLike::Address(address),
+            ),
+            (
+                r#""6b175474e89094c44da98b954eedeac495271d0f""#,
+                TokenLike::Address(address),
+            ),
+            (r#""DAI""#, TokenLike::Symbol("DAI".to_owned())),
+        ];
+        for (json, expected) in cases {
+            let token_like: TokenLike = serde_json::from_str(json).unwrap();
+            assert_eq!(token_like, expected, "{}", json);
+        }
+    }
+
     #[test]
     fn tx_fee_type_deserialize_old_type() {
         let deserialized: TxFeeTypes =
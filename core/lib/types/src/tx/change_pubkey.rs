@@ -245,16 +245,12 @@ impl ChangePubKey {
         if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
             *cached_signer
         } else {
-            if let Some(res) = self
-                .signature
-                .verify_musig(&self.get_old_bytes())
-                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
-            {
+            if let Some(res) = self.signature.verify(&self.get_old_bytes()) {
                 return Some((res, TxVersion::Legacy));
             }
             self.signature
-                .verify_musig(&self.get_bytes())
-                .map(|pub_key| (PubKeyHash::from_pubkey(&pub_key), TxVersion::V1))
+                .verify(&self.get_bytes())
+                .map(|pub_key_hash| (pub_key_hash, TxVersion::V1))
         }
     }
 
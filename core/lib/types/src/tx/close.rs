@@ -33,9 +33,7 @@ impl Close {
     }
 
     pub fn verify_signature(&self) -> Option<PubKeyHash> {
-        self.signature
-            .verify_musig_rescue(&self.get_bytes())
-            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        self.signature.verify(&self.get_bytes())
     }
 
     pub fn check_correctness(&self) -> Result<(), TransactionError> {
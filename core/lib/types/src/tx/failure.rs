@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable reason of the transaction failure during the block execution.
+/// The human-readable description is stored next to it as the fail reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxFailureCode {
+    /// The account doesn't have enough funds to cover the amount and the fee.
+    InsufficientBalance,
+    /// The nonce of the transaction doesn't match the nonce of the account.
+    NonceMismatch,
+    /// The zkSync signature of the transaction is incorrect.
+    InvalidSignature,
+    /// The authorization of the new public key is incorrect.
+    InvalidPubKeyAuth,
+    /// One of the accounts of the transaction doesn't exist.
+    AccountNotFound,
+    /// The account has no public key set.
+    AccountLocked,
+    /// One of the account ids or addresses of the transaction is incorrect.
+    InvalidAccount,
+    /// The token or the fee token of the transaction is not supported.
+    InvalidToken,
+    /// The block timestamp is out of the validity range of the transaction.
+    InvalidTimestamp,
+    /// The batch requires too much gas to be included into a block.
+    BatchTooExpensive,
+    /// The transaction is rejected by the rest of the operation rules.
+    InvalidOperation,
+}
+
+impl TxFailureCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InsufficientBalance => "insufficientBalance",
+            Self::NonceMismatch => "nonceMismatch",
+            Self::InvalidSignature => "invalidSignature",
+            Self::InvalidPubKeyAuth => "invalidPubKeyAuth",
+            Self::AccountNotFound => "accountNotFound",
+            Self::AccountLocked => "accountLocked",
+            Self::InvalidAccount => "invalidAccount",
+            Self::InvalidToken => "invalidToken",
+            Self::InvalidTimestamp => "invalidTimestamp",
+            Self::BatchTooExpensive => "batchTooExpensive",
+            Self::InvalidOperation => "invalidOperation",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Self> {
+        match code {
+            "insufficientBalance" => Some(Self::InsufficientBalance),
+            "nonceMismatch" => Some(Self::NonceMismatch),
+            "invalidSignature" => Some(Self::InvalidSignature),
+            "invalidPubKeyAuth" => Some(Self::InvalidPubKeyAuth),
+            "accountNotFound" => Some(Self::AccountNotFound),
+            "accountLocked" => Some(Self::AccountLocked),
+            "invalidAccount" => Some(Self::InvalidAccount),
+            "invalidToken" => Some(Self::InvalidToken),
+            "invalidTimestamp" => Some(Self::InvalidTimestamp),
+            "batchTooExpensive" => Some(Self::BatchTooExpensive),
+            "invalidOperation" => Some(Self::InvalidOperation),
+            _ => None,
+        }
+    }
+}
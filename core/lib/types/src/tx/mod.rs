@@ -2,6 +2,7 @@
 
 mod change_pubkey;
 mod close;
+mod failure;
 mod forced_exit;
 mod mint_nft;
 mod primitives;
@@ -25,6 +26,7 @@ pub use self::{
         ChangePubKeyType,
     },
     error::TransactionError,
+    failure::TxFailureCode,
     forced_exit::ForcedExit,
     mint_nft::{calculate_token_address, calculate_token_data, calculate_token_hash, MintNFT},
     swap::{Order, Swap},
@@ -45,6 +47,7 @@ pub use self::primitives::{
     packed_public_key::PackedPublicKey,
     packed_signature::PackedSignature,
     signature::TxSignature,
+    signature_scheme::{SignatureScheme, SUPPORTED_SIGNATURE_SCHEMES},
     time_range::TimeRange,
     tx_hash::TxHash,
 };
@@ -14,18 +14,22 @@ use zksync_crypto::{
 };
 
 use crate::tx::{
-    primitives::{packed_public_key, packed_signature},
+    primitives::{packed_public_key, packed_signature, signature_scheme::SignatureScheme},
     PackedPublicKey, PackedSignature,
 };
+use crate::PubKeyHash;
 
 /// zkSync transaction signature.
 ///
-/// Represents a MuSig Rescue signature for the message.
+/// Represents a signature for the message in the scheme the signature is tagged with,
+/// which is a MuSig Rescue signature by default.
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TxSignature {
     pub pub_key: PackedPublicKey,
     pub signature: PackedSignature,
+    #[serde(default, skip_serializing_if = "SignatureScheme::is_default")]
+    pub scheme: SignatureScheme,
 }
 
 impl TxSignature {
@@ -54,9 +58,16 @@ impl TxSignature {
         Self {
             pub_key: PackedPublicKey(public_key_from_private(pk)),
             signature: PackedSignature(signature),
+            scheme: SignatureScheme::MusigRescue,
         }
     }
 
+    /// Restores the `PubKeyHash` of the signer given the initial message, using the verifier
+    /// of the signature scheme. Returns `None` if the signature is not valid for the message.
+    pub fn verify(&self, msg: &[u8]) -> Option<PubKeyHash> {
+        self.scheme.verifier().verify(self, msg)
+    }
+
     /// Restores a public key from the signature given the initial message.
     /// Returns `None` if an address cannot be recovered from the provided (signature, message) pair.
     ///
@@ -93,6 +104,7 @@ impl TxSignature {
         Ok(Self {
             pub_key: PackedPublicKey::deserialize_packed(&bytes[0..32])?,
             signature: PackedSignature::deserialize_packed(&bytes[32..])?,
+            scheme: SignatureScheme::MusigRescue,
         })
     }
 }
@@ -112,6 +124,7 @@ impl Default for TxSignature {
         Self {
             pub_key: PackedPublicKey::deserialize_packed(&[0; 32]).unwrap(),
             signature: PackedSignature::deserialize_packed(&[0; 64]).unwrap(),
+            scheme: SignatureScheme::default(),
         }
     }
 }
@@ -0,0 +1,64 @@
+//! Registry of the schemes of the zkSync transaction signatures.
+//!
+//! Every `TxSignature` is tagged with the scheme it was created with, and the signature
+//! is verified by the verifier registered for this scheme. The transactions don't call
+//! the scheme-specific methods directly, so a new scheme can be added at a protocol
+//! upgrade by registering its verifier and enabling it in `SUPPORTED_SIGNATURE_SCHEMES`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{tx::TxSignature, PubKeyHash};
+
+/// Schemes accepted in the transactions by the current version of the protocol.
+pub const SUPPORTED_SIGNATURE_SCHEMES: &[SignatureScheme] = &[SignatureScheme::MusigRescue];
+
+/// Scheme of the zkSync transaction signature.
+///
+/// The scheme is omitted in the serialized signature if it's the default one,
+/// so the signatures created before the schemes were introduced remain valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SignatureScheme {
+    /// MuSig Schnorr signature over the Rescue hash of the message.
+    MusigRescue,
+}
+
+impl Default for SignatureScheme {
+    fn default() -> Self {
+        Self::MusigRescue
+    }
+}
+
+impl SignatureScheme {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn is_supported(&self) -> bool {
+        SUPPORTED_SIGNATURE_SCHEMES.contains(self)
+    }
+
+    /// Returns the verifier registered for the scheme.
+    pub fn verifier(&self) -> &'static dyn SignatureVerifier {
+        match self {
+            Self::MusigRescue => &MusigRescueVerifier,
+        }
+    }
+}
+
+/// Verifies the signatures of a single scheme.
+pub trait SignatureVerifier: Sync {
+    /// Restores the hash of the signer's public key given the signed message.
+    /// Returns `None` if the signature is not valid for the message.
+    fn verify(&self, signature: &TxSignature, msg: &[u8]) -> Option<PubKeyHash>;
+}
+
+struct MusigRescueVerifier;
+
+impl SignatureVerifier for MusigRescueVerifier {
+    fn verify(&self, signature: &TxSignature, msg: &[u8]) -> Option<PubKeyHash> {
+        signature
+            .verify_musig_rescue(msg)
+            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+    }
+}
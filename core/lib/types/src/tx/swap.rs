@@ -91,9 +91,7 @@ impl Order {
     }
 
     pub fn verify_signature(&self) -> Option<PubKeyHash> {
-        self.signature
-            .verify_musig(&self.get_bytes())
-            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        self.signature.verify(&self.get_bytes())
     }
 
     pub fn get_ethereum_sign_message(
@@ -350,8 +348,8 @@ impl Swap {
             *cached_signer
         } else {
             self.signature
-                .verify_musig(&self.get_sign_bytes())
-                .map(|pub_key| (PubKeyHash::from_pubkey(&pub_key), TxVersion::V1))
+                .verify(&self.get_sign_bytes())
+                .map(|pub_key_hash| (pub_key_hash, TxVersion::V1))
         }
     }
 
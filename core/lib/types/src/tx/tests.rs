@@ -451,6 +451,37 @@ fn test_musig_rescue_signing_verification() {
     }
 }
 
+#[test]
+fn test_signature_scheme_serialization() {
+    let (pk, messages) = gen_pk_and_msg();
+    let signature = TxSignature::sign_musig(&pk, &messages[0]);
+    assert_eq!(signature.scheme, SignatureScheme::MusigRescue);
+    assert!(signature.scheme.is_supported());
+    assert_eq!(
+        signature.verify(&messages[0]),
+        Some(PubKeyHash::from_privkey(&pk))
+    );
+
+    // The default scheme is not serialized, so the signatures created
+    // before the schemes were introduced are deserialized as is.
+    let serialized = serde_json::to_value(&signature).unwrap();
+    assert!(serialized.get("scheme").is_none());
+    let deserialized: TxSignature = serde_json::from_value(serialized.clone()).unwrap();
+    assert_eq!(deserialized.scheme, SignatureScheme::MusigRescue);
+    assert_eq!(
+        deserialized.verify(&messages[0]),
+        Some(PubKeyHash::from_privkey(&pk))
+    );
+
+    let mut tagged = serialized;
+    tagged["scheme"] = serde_json::json!("musigRescue");
+    let deserialized: TxSignature = serde_json::from_value(tagged.clone()).unwrap();
+    assert_eq!(deserialized.scheme, SignatureScheme::MusigRescue);
+
+    tagged["scheme"] = serde_json::json!("unknownScheme");
+    assert!(serde_json::from_value::<TxSignature>(tagged).is_err());
+}
+
 #[test]
 fn test_ethereum_signature_verify_with_serialization() {
     let address: Address = "52312AD6f01657413b2eaE9287f6B9ADaD93D5FE".parse().unwrap();
@@ -174,17 +174,13 @@ impl Withdraw {
             *cached_signer
         } else {
             if self.token.0 < MIN_NFT_TOKEN_ID {
-                if let Some(res) = self
-                    .signature
-                    .verify_musig(&self.get_old_bytes())
-                    .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
-                {
+                if let Some(res) = self.signature.verify(&self.get_old_bytes()) {
                     return Some((res, TxVersion::Legacy));
                 }
             }
             self.signature
-                .verify_musig(&self.get_bytes())
-                .map(|pub_key| (PubKeyHash::from_pubkey(&pub_key), TxVersion::V1))
+                .verify(&self.get_bytes())
+                .map(|pub_key_hash| (pub_key_hash, TxVersion::V1))
         }
     }
 
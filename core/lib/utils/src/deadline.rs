@@ -0,0 +1,108 @@
+//! Deadlines of the requests passed between the actors.
+//!
+//! The request handler waits for the response only until the deadline, and the actor
+//! processing the request checks the deadline before starting the work, so the responses
+//! nobody is waiting for are not computed.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use futures::channel::oneshot;
+
+/// Point in time after which the result of the request is not needed anymore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// Deadline that never expires, used for the requests of the background tasks.
+    pub fn never() -> Self {
+        Self(None)
+    }
+
+    pub fn after(timeout: Duration) -> Self {
+        Self(Some(Instant::now() + timeout))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        matches!(self.0, Some(deadline) if deadline <= Instant::now())
+    }
+
+    /// Time left until the deadline, `None` if the deadline never expires.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.0
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Awaits the future until the deadline. Returns `None` if the deadline has expired first.
+    pub async fn wait<F: Future>(self, future: F) -> Option<F::Output> {
+        match self.remaining() {
+            Some(remaining) => tokio::time::timeout(remaining, future).await.ok(),
+            None => Some(future.await),
+        }
+    }
+}
+
+/// Sending half of the response channel which carries the deadline of the request.
+#[derive(Debug)]
+pub struct DeadlineSender<T> {
+    sender: oneshot::Sender<T>,
+    deadline: Deadline,
+}
+
+impl<T> DeadlineSender<T> {
+    pub fn new(sender: oneshot::Sender<T>, deadline: Deadline) -> Self {
+        Self { sender, deadline }
+    }
+
+    pub fn deadline(&self) -> Deadline {
+        self.deadline
+    }
+
+    /// Checks whether the response is still awaited: the request is abandoned
+    /// once its deadline has expired or the receiver has been dropped.
+    pub fn is_abandoned(&self) -> bool {
+        self.deadline.is_expired() || self.sender.is_canceled()
+    }
+
+    /// Sends the response, returning it back if the receiver has been dropped.
+    pub fn send(self, response: T) -> Result<(), T> {
+        self.sender.send(response)
+    }
+}
+
+/// Creates a oneshot channel for the response to the request with the given deadline.
+pub fn deadline_channel<T>(deadline: Deadline) -> (DeadlineSender<T>, oneshot::Receiver<T>) {
+    let (sender, receiver) = oneshot::channel();
+    (DeadlineSender::new(sender, deadline), receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn deadline_expiration() {
+        assert!(!Deadline::never().is_expired());
+        assert_eq!(Deadline::never().remaining(), None);
+        assert!(Deadline::after(Duration::from_secs(0)).is_expired());
+        assert!(!Deadline::after(Duration::from_secs(60)).is_expired());
+
+        let deadline = Deadline::after(Duration::from_millis(10));
+        assert_eq!(deadline.wait(futures::future::pending::<()>()).await, None);
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert_eq!(deadline.wait(async { 1 }).await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn abandoned_requests() {
+        let (sender, receiver) = deadline_channel::<u32>(Deadline::never());
+        assert!(!sender.is_abandoned());
+        drop(receiver);
+        assert!(sender.is_abandoned());
+
+        let (sender, receiver) = deadline_channel::<u32>(Deadline::after(Duration::from_secs(0)));
+        assert!(sender.is_abandoned());
+        sender.send(1).unwrap();
+        assert_eq!(receiver.await, Ok(1));
+    }
+}
@@ -1,6 +1,7 @@
 //! Various helpers used in the zkSync stack.
 
 mod convert;
+pub mod deadline;
 mod env_tools;
 mod format;
 mod macros;
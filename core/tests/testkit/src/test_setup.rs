@@ -1059,6 +1059,7 @@ impl TestSetup {
     pub async fn execute_blocks_onchain(&mut self, blocks: &[Block]) -> ETHExecResult {
         let block_execute_op = BlocksExecuteOperation {
             blocks: blocks.to_vec(),
+            complete_withdrawals: false,
         };
         self.commit_account
             .execute_block(&block_execute_op)
@@ -1114,6 +1115,7 @@ impl TestSetup {
 
         let block_execute_op = BlocksExecuteOperation {
             blocks: vec![new_block.clone()],
+            complete_withdrawals: false,
         };
         let withdrawals_result = self
             .commit_account
@@ -7,14 +7,17 @@ import { env } from 'process';
 
 const CONFIG_FILES = [
     'api.toml',
+    'balance_auditor.toml',
     'chain.toml',
     'contracts.toml',
     'database.toml',
+    'deposit_monitor.toml',
     'eth_client.toml',
     'eth_sender.toml',
     'eth_watch.toml',
     'event_listener.toml',
     'gateway_watcher.toml',
+    'leader_election.toml',
     'fee_ticker.toml',
     'misc.toml',
     'dev_liquidity_token_watcher.toml',
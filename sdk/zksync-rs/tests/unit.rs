@@ -72,7 +72,9 @@ fn priv_key_from_raw(raw: &[u8]) -> Option<PrivateKey> {
 }
 
 fn assert_tx_signature(signature: &TxSignature, expected_pub: &str, expected_sig: &str) {
-    let TxSignature { pub_key, signature } = signature;
+    let TxSignature {
+        pub_key, signature, ..
+    } = signature;
 
     let pub_point = pub_key.serialize_packed().unwrap();
     assert_eq!(hex::encode(pub_point), expected_pub);
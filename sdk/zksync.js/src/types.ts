@@ -304,6 +304,7 @@ export interface TransactionReceipt {
     executed: boolean;
     success?: boolean;
     failReason?: string;
+    failCode?: TxFailureCode;
     block?: BlockInfo;
 }
 
@@ -492,19 +493,35 @@ export interface SubmitBatchResponse {
 }
 
 export interface ApiL1TxReceipt {
-    status: 'queued' | 'committed' | 'finalized';
+    status: 'queued' | 'pending' | 'committed' | 'finalized';
     ethBlock: number;
     rollupBlock?: number;
     id: number;
+    blockIndex?: number;
 }
 
-export type L2TxStatus = 'queued' | 'committed' | 'finalized' | 'rejected';
+export type L2TxStatus = 'queued' | 'pending' | 'committed' | 'finalized' | 'rejected';
+
+export type TxFailureCode =
+    | 'insufficientBalance'
+    | 'nonceMismatch'
+    | 'invalidSignature'
+    | 'invalidPubKeyAuth'
+    | 'accountNotFound'
+    | 'accountLocked'
+    | 'invalidAccount'
+    | 'invalidToken'
+    | 'invalidTimestamp'
+    | 'batchTooExpensive'
+    | 'invalidOperation';
 
 export interface ApiL2TxReceipt {
     txHash: string;
     rollupBlock?: number;
     status: L2TxStatus;
     failReason?: string;
+    failCode?: TxFailureCode;
+    blockIndex?: number;
 }
 
 export type ApiTxReceipt = ApiL1TxReceipt | ApiL2TxReceipt;